@@ -335,3 +335,51 @@ test('complete repr', (t) => {
   const repr = (result as MontyComplete).repr()
   t.true(repr.includes('MontyComplete'))
 })
+
+// =============================================================================
+// Dataclass method call tests
+// =============================================================================
+
+function transactionDataclass(): Record<string, unknown> {
+  return {
+    __monty_type__: 'Dataclass',
+    name: 'Transaction',
+    typeId: 1n,
+    fieldNames: ['name'],
+    fields: { name: 't1' },
+    methods: ['__enter__', '__exit__'],
+    frozen: true,
+  }
+}
+
+test('start dataclass method call sets isMethodCall', (t) => {
+  const m = new Monty('with tx as name:\n    result = name\nresult', { inputs: ['tx'] })
+  const progress = m.start({ inputs: { tx: transactionDataclass() } })
+  t.true(progress instanceof MontySnapshot)
+  const enter = progress as MontySnapshot
+  t.true(enter.isMethodCall)
+  t.is(enter.functionName, '__enter__')
+  t.is(enter.args.length, 1)
+
+  const exit = enter.resume({ returnValue: 'entered' })
+  t.true(exit instanceof MontySnapshot)
+  t.true((exit as MontySnapshot).isMethodCall)
+  t.is((exit as MontySnapshot).functionName, '__exit__')
+
+  const result = (exit as MontySnapshot).resume({ returnValue: null })
+  t.true(result instanceof MontyComplete)
+  t.is((result as MontyComplete).output, 'entered')
+})
+
+test('start external function call is not a method call', (t) => {
+  const m = new Monty('func()', { externalFunctions: ['func'] })
+  const progress = m.start()
+  t.true(progress instanceof MontySnapshot)
+  t.false((progress as MontySnapshot).isMethodCall)
+})
+
+test('run rejects dataclass method calls', (t) => {
+  const m = new Monty('with tx:\n    pass', { inputs: ['tx'] })
+  const error = t.throws(() => m.run({ inputs: { tx: transactionDataclass() } }))
+  t.is(error?.message, "Dataclass method '__enter__' can't be called in run(), use start() and check isMethodCall")
+})
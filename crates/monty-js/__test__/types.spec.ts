@@ -51,6 +51,13 @@ test('float', (t) => {
   t.is(m.run({ inputs: { x: 0.0 } }), 0.0)
 })
 
+test('complex', (t) => {
+  const m = new Monty('x', { inputs: ['x'] })
+  const complex = { __monty_type__: 'Complex', real: 1, imag: -2.5 }
+  t.deepEqual(m.run({ inputs: { x: complex } }), complex)
+  t.deepEqual(new Monty('(1 + 2j) * 2j').run(), { __monty_type__: 'Complex', real: -4, imag: 2 })
+})
+
 // =============================================================================
 // String tests
 // =============================================================================
@@ -195,6 +202,44 @@ test('ellipsis output', (t) => {
   t.deepEqual(result, { __monty_type__: 'Ellipsis' })
 })
 
+// =============================================================================
+// Exception tests
+// =============================================================================
+
+test('exception output', (t) => {
+  const m = new Monty("ValueError('bad')")
+  t.deepEqual(m.run(), { __monty_type__: 'Exception', excType: 'ValueError', message: 'bad' })
+})
+
+test('exception group output', (t) => {
+  const m = new Monty("ExceptionGroup('many', [ValueError('a'), ExceptionGroup('inner', [KeyError('b')])])")
+  t.deepEqual(m.run(), {
+    __monty_type__: 'Exception',
+    excType: 'ExceptionGroup',
+    message: 'many',
+    exceptions: [
+      { __monty_type__: 'Exception', excType: 'ValueError', message: 'a' },
+      {
+        __monty_type__: 'Exception',
+        excType: 'ExceptionGroup',
+        message: 'inner',
+        exceptions: [{ __monty_type__: 'Exception', excType: 'KeyError', message: 'b' }],
+      },
+    ],
+  })
+})
+
+test('exception group input', (t) => {
+  const group = {
+    __monty_type__: 'Exception',
+    excType: 'ExceptionGroup',
+    message: 'many',
+    exceptions: [{ __monty_type__: 'Exception', excType: 'ValueError', message: 'a' }],
+  }
+  const m = new Monty('[x.message] + [str(e) for e in x.exceptions]', { inputs: ['x'] })
+  t.deepEqual(m.run({ inputs: { x: group } }), ['many', 'a'])
+})
+
 // =============================================================================
 // Nested collection tests
 // =============================================================================
@@ -14,6 +14,7 @@
 //! - `MontyObject::Float` ↔ `number` (including `NaN`, `Infinity`, `-Infinity`)
 //! - `MontyObject::String` ↔ `string`
 //! - `MontyObject::Bytes` ↔ `Buffer` (Node.js)
+//! - `MontyObject::ByteArray` → `Buffer` (converted back as `MontyObject::Bytes`)
 //! - `MontyObject::List` ↔ `Array`
 //! - `MontyObject::Dict` ↔ `Map` (preserves key types and insertion order)
 //! - `MontyObject::Set` ↔ `Set`
@@ -21,8 +22,10 @@
 //!
 //! ### Marked JS types (with `__monty_type__` property):
 //! - `MontyObject::Ellipsis` → `{ __monty_type__: 'Ellipsis' }`
+//! - `MontyObject::Complex` → `{ __monty_type__: 'Complex', real, imag }`
 //! - `MontyObject::Tuple` → `Array` with `__tuple__: true`
 //! - `MontyObject::Exception` → `{ __monty_type__: 'Exception', excType, message }`
+//! - `MontyObject::ExceptionGroup` ↔ `{ __monty_type__: 'Exception', excType, message, exceptions }`
 //! - `MontyObject::Type` → `{ __monty_type__: 'Type', value }`
 //! - `MontyObject::BuiltinFunction` → `{ __monty_type__: 'BuiltinFunction', value }`
 //! - `MontyObject::Dataclass` → `{ __monty_type__: 'Dataclass', name, fields, ... }`
@@ -76,15 +79,23 @@ pub fn monty_to_js<'e>(obj: &MontyObject, env: &'e Env) -> Result<JsMontyObject<
         MontyObject::Int(i) => create_js_int(*i, env)?,
         MontyObject::BigInt(bi) => create_js_bigint(bi, env)?,
         MontyObject::Float(f) => env.create_double(*f)?.into_unknown(env)?,
+        MontyObject::Complex { real, imag } => create_js_complex(*real, *imag, env)?,
         MontyObject::String(s) => env.create_string(s)?.into_unknown(env)?,
-        MontyObject::Bytes(bytes) => create_js_buffer(bytes, env)?,
+        MontyObject::Bytes(bytes) | MontyObject::ByteArray(bytes) => create_js_buffer(bytes, env)?,
         MontyObject::List(items) => create_js_array(items, env)?.into_unknown(env)?,
         MontyObject::Tuple(items) => create_js_tuple(items, env)?,
         // NamedTuple is converted to a tuple (loses named access in JS)
         MontyObject::NamedTuple { values, .. } => create_js_tuple(values, env)?,
         MontyObject::Dict(pairs) => create_js_map(pairs, env)?,
         MontyObject::Set(items) | MontyObject::FrozenSet(items) => create_js_set(items, env)?,
-        MontyObject::Exception { exc_type, arg } => create_js_exception(*exc_type, arg.as_deref(), env)?,
+        MontyObject::Exception { exc_type, arg } => {
+            create_js_exception(*exc_type, arg.as_deref(), env)?.into_unknown(env)?
+        }
+        MontyObject::ExceptionGroup {
+            exc_type,
+            message,
+            exceptions,
+        } => create_js_exception_group(*exc_type, message, exceptions, env)?,
         MontyObject::Type(t) => create_js_type_marker(&t.to_string(), env)?,
         MontyObject::BuiltinFunction(f) => create_js_builtin_function_marker(&f.to_string(), env)?,
         MontyObject::Dataclass {
@@ -94,6 +105,7 @@ pub fn monty_to_js<'e>(obj: &MontyObject, env: &'e Env) -> Result<JsMontyObject<
             attrs,
             methods,
             frozen,
+            ..
         } => create_js_dataclass(name, *type_id, field_names, attrs, methods, *frozen, env)?,
         MontyObject::Path(p) => env.create_string(p)?.into_unknown(env)?,
         MontyObject::Repr(s) | MontyObject::Cycle(_, s) => env.create_string(s)?.into_unknown(env)?,
@@ -246,12 +258,34 @@ fn create_js_ellipsis(env: &Env) -> Result<Unknown<'_>> {
     obj.into_unknown(env)
 }
 
+/// Creates a JS object representing a complex number: `{ __monty_type__: 'Complex', real, imag }`.
+fn create_js_complex(real: f64, imag: f64, env: &Env) -> Result<Unknown<'_>> {
+    let mut obj = Object::new(env)?;
+    obj.set_named_property("__monty_type__", "Complex")?;
+    obj.set_named_property("real", real)?;
+    obj.set_named_property("imag", imag)?;
+    obj.into_unknown(env)
+}
+
 /// Creates a JS object representing an exception.
-fn create_js_exception<'e>(exc_type: ExcType, arg: Option<&str>, env: &'e Env) -> Result<Unknown<'e>> {
+fn create_js_exception<'e>(exc_type: ExcType, arg: Option<&str>, env: &'e Env) -> Result<Object<'e>> {
     let mut obj = Object::new(env)?;
     obj.set_named_property("__monty_type__", "Exception")?;
     obj.set_named_property("excType", exc_type.to_string())?;
     obj.set_named_property("message", arg.unwrap_or(""))?;
+    Ok(obj)
+}
+
+/// Creates a JS object representing an exception group, with its sub-exceptions in an
+/// `exceptions` array: `{ __monty_type__: 'Exception', excType, message, exceptions }`.
+fn create_js_exception_group<'e>(
+    exc_type: ExcType,
+    message: &str,
+    exceptions: &[MontyObject],
+    env: &'e Env,
+) -> Result<Unknown<'e>> {
+    let mut obj = create_js_exception(exc_type, Some(message), env)?;
+    obj.set_named_property("exceptions", create_js_array(exceptions, env)?)?;
     obj.into_unknown(env)
 }
 
@@ -540,12 +574,29 @@ fn js_array_to_monty(arr: Object, env: Env) -> Result<MontyObject> {
 fn js_marked_object_to_monty(obj: &Object, monty_type: &str, env: Env) -> Result<MontyObject> {
     match monty_type {
         "Ellipsis" => Ok(MontyObject::Ellipsis),
+        "Complex" => {
+            let real: f64 = obj.get_named_property("real")?;
+            let imag: f64 = obj.get_named_property("imag")?;
+            Ok(MontyObject::Complex { real, imag })
+        }
         "Exception" => {
             let exc_type_str: String = obj.get_named_property("excType")?;
             let message: String = obj.get_named_property("message")?;
             let exc_type: ExcType = exc_type_str
                 .parse()
                 .map_err(|_| Error::from_reason(format!("Unknown exception type: {exc_type_str}")))?;
+            if let Some(exceptions) = obj.get_named_property::<Option<Array>>("exceptions")? {
+                let mut items = Vec::with_capacity(exceptions.len() as usize);
+                for i in 0..exceptions.len() {
+                    let item: Unknown = exceptions.get_element(i)?;
+                    items.push(js_to_monty(item, env)?);
+                }
+                return Ok(MontyObject::ExceptionGroup {
+                    exc_type,
+                    message,
+                    exceptions: items,
+                });
+            }
             let arg = if message.is_empty() { None } else { Some(message) };
             Ok(MontyObject::Exception { exc_type, arg })
         }
@@ -606,6 +657,7 @@ fn js_marked_object_to_monty(obj: &Object, monty_type: &str, env: Env) -> Result
             Ok(MontyObject::Dataclass {
                 name,
                 type_id,
+                instance_id: None,
                 field_names,
                 attrs,
                 methods,
@@ -245,6 +245,15 @@ impl Monty {
                         RunProgress::Complete(result) => {
                             return Ok(Either::A(monty_to_js(&result, env)?));
                         }
+                        RunProgress::FunctionCall {
+                            function_name,
+                            method_call: true,
+                            ..
+                        } => {
+                            return Err(Error::from_reason(format!(
+                                "Dataclass method '{function_name}' can't be called in run(), use start() and check isMethodCall",
+                            )));
+                        }
                         RunProgress::FunctionCall {
                             function_name,
                             args,
@@ -486,6 +495,8 @@ pub struct MontySnapshot {
     args: Vec<MontyObject>,
     /// The keyword arguments passed to the function (stored as MontyObject pairs for serialization).
     kwargs: Vec<(MontyObject, MontyObject)>,
+    /// Whether this is a method call on a dataclass, the instance is the first argument.
+    method_call: bool,
 }
 
 /// Options for resuming execution.
@@ -527,6 +538,14 @@ impl MontySnapshot {
         self.function_name.clone()
     }
 
+    /// Returns whether this is a method call on a dataclass.
+    ///
+    /// When true, `functionName` is the method name and the first argument is the instance.
+    #[napi(getter)]
+    pub fn is_method_call(&self) -> bool {
+        self.method_call
+    }
+
     /// Returns the positional arguments passed to the external function.
     #[napi(getter)]
     pub fn args<'env>(&self, env: &'env Env) -> Result<Vec<JsMontyObject<'env>>> {
@@ -623,6 +642,7 @@ impl MontySnapshot {
             function_name: &self.function_name,
             args: &self.args,
             kwargs: &self.kwargs,
+            method_call: self.method_call,
         };
 
         let bytes =
@@ -646,6 +666,7 @@ impl MontySnapshot {
             function_name: serialized.function_name,
             args: serialized.args,
             kwargs: serialized.kwargs,
+            method_call: serialized.method_call,
         })
     }
 
@@ -710,6 +731,7 @@ where
             function_name,
             args,
             kwargs,
+            method_call,
             state,
             ..
         } => {
@@ -720,6 +742,7 @@ where
                 function_name,
                 args,
                 kwargs,
+                method_call,
             })
         }
         RunProgress::ResolveFutures(_) => {
@@ -776,6 +799,7 @@ struct SerializedSnapshot<'a> {
     function_name: &'a str,
     args: &'a [MontyObject],
     kwargs: &'a [(MontyObject, MontyObject)],
+    method_call: bool,
 }
 
 /// Owned version of `SerializedSnapshot` for deserialization.
@@ -786,6 +810,9 @@ struct SerializedSnapshotOwned {
     function_name: String,
     args: Vec<MontyObject>,
     kwargs: Vec<(MontyObject, MontyObject)>,
+    /// Missing in snapshots dumped before method calls were exposed.
+    #[serde(default)]
+    method_call: bool,
 }
 
 // =============================================================================
@@ -391,6 +391,15 @@ export class MontySnapshot {
     return this._native.functionName
   }
 
+  /**
+   * Returns whether this is a method call on a dataclass.
+   *
+   * When true, `functionName` is the method name and the first argument is the instance.
+   */
+  get isMethodCall(): boolean {
+    return this._native.isMethodCall
+  }
+
   /** Returns the positional arguments passed to the external function. */
   get args(): JsMontyObject[] {
     return this._native.args
@@ -504,6 +513,18 @@ export async function runMontyAsync(montyRunner: Monty, options: RunMontyAsyncOp
   while (progress instanceof MontySnapshot) {
     const snapshot = progress
     const funcName = snapshot.functionName
+
+    if (snapshot.isMethodCall) {
+      // Dataclass methods have no JS implementation to dispatch to
+      progress = snapshot.resume({
+        exception: {
+          type: 'NotImplementedError',
+          message: `Dataclass method '${funcName}' can't be called from runMontyAsync()`,
+        },
+      })
+      continue
+    }
+
     const extFunction = externalFunctions[funcName]
 
     if (!extFunction) {
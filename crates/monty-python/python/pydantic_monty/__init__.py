@@ -112,6 +112,20 @@ async def run_monty_async(
                                 progress = await run_in_pool(partial(progress.resume, exception=exc))
                             else:
                                 progress = await run_in_pool(partial(progress.resume, return_value=result))
+                    # Handle method calls on dataclasses passed as inputs, e.g. `__enter__`
+                    elif progress.is_method_call:
+                        instance, *args = progress.args
+                        try:
+                            result = getattr(instance, progress.function_name)(*args, **progress.kwargs)
+                        except Exception as exc:
+                            progress = await run_in_pool(partial(progress.resume, exception=exc))
+                        else:
+                            if inspect.iscoroutine(result):
+                                call_id = progress.call_id
+                                tasks[call_id] = asyncio.create_task(_run_external_function(call_id, result))
+                                progress = await run_in_pool(partial(progress.resume, future=...))
+                            else:
+                                progress = await run_in_pool(partial(progress.resume, return_value=result))
                     # Handle external function calls
                     elif ext_function := external_functions.get(progress.function_name):
                         try:
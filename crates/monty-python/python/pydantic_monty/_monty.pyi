@@ -40,6 +40,7 @@ class Monty:
         type_check: bool = False,
         type_check_stubs: str | None = None,
         dataclass_registry: list[type] | None = None,
+        allowed_modules: list[str] | None = None,
     ) -> Self:
         """
         Create a new Monty interpreter by parsing the given code.
@@ -54,9 +55,12 @@ class Monty:
                 e.g. with input variable declarations or external function signatures
             dataclass_registry: Optional list of dataclass types to register for proper
                 isinstance() support on output, see `register_dataclass()` above.
+            allowed_modules: Optional list of builtin modules the code may import, listing a
+                package also allows its submodules. By default all builtin modules are allowed.
 
         Raises:
             MontySyntaxError: If the code cannot be parsed
+            MontyRuntimeError: If the code imports a module not in `allowed_modules`
             MontyTypingError: If type_check is True and type errors are found
         """
 
@@ -205,6 +209,13 @@ class MontySnapshot:
     def is_os_function(self) -> bool:
         """Whether this snapshot is for an OS function call (e.g., Path.stat)."""
 
+    @property
+    def is_method_call(self) -> bool:
+        """Whether this snapshot is for a method call on a dataclass passed as an input.
+
+        If `True`, `function_name` is the method name and `args[0]` is the dataclass instance.
+        """
+
     @property
     def function_name(self) -> str | OsFunction:
         """The name of the function being called (external function or OS function like 'Path.stat').
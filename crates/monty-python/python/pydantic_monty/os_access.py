@@ -29,6 +29,9 @@ OsFunction = Literal[
     'Path.absolute',
     'os.getenv',
     'os.environ',
+    'time.time',
+    'time.monotonic',
+    'time.sleep',
 ]
 
 
@@ -172,6 +175,12 @@ class AbstractOS(ABC):
                 return self.getenv(*args)
             case 'os.environ':
                 return self.get_environ()
+            case 'time.time':
+                return self.time()
+            case 'time.monotonic':
+                return self.monotonic()
+            case 'time.sleep':
+                return self.sleep(*args)
 
     @abstractmethod
     def path_exists(self, path: PurePosixPath) -> bool:
@@ -422,6 +431,41 @@ class AbstractOS(ABC):
         """
         raise NotImplementedError
 
+    def time(self) -> float:
+        """Get the current time, used by `time.time()`, `datetime.now()` and `date.today()`.
+
+        Override this to freeze or fake the clock seen by Monty code. Monty has no local timezone,
+        so naive datetimes from `datetime.now()` and `date.today()` are in UTC.
+
+        Returns:
+            Seconds since the Unix epoch, defaults to the real clock.
+        """
+        import time
+
+        return time.time()
+
+    def monotonic(self) -> float:
+        """Get the value of a monotonic clock, used by `time.monotonic()`.
+
+        Returns:
+            Seconds from an arbitrary starting point, defaults to the real monotonic clock.
+        """
+        import time
+
+        return time.monotonic()
+
+    def sleep(self, secs: float) -> None:
+        """Pause execution, used by `time.sleep()`.
+
+        Override this to skip or shorten sleeps in Monty code.
+
+        Args:
+            secs: The number of seconds to sleep, never negative.
+        """
+        import time
+
+        time.sleep(secs)
+
 
 class AbstractFile(Protocol):
     """Protocol defining the interface for files used with OSAccess.
@@ -5,13 +5,15 @@
 //! - `monty_to_py`: Convert Monty's `MontyObject` back to Python objects for output
 
 use ::monty::MontyObject;
-use monty::MontyException;
+use monty::{ExcType, MontyException};
 use num_bigint::BigInt;
 use pyo3::{
     exceptions::{PyBaseException, PyTypeError},
     prelude::*,
     sync::PyOnceLock,
-    types::{PyBool, PyBytes, PyDict, PyFloat, PyFrozenSet, PyInt, PyList, PySet, PyString, PyTuple},
+    types::{
+        PyBool, PyByteArray, PyBytes, PyComplex, PyDict, PyFloat, PyFrozenSet, PyInt, PyList, PySet, PyString, PyTuple,
+    },
 };
 
 use crate::{
@@ -24,9 +26,11 @@ use crate::{
 /// Handles all standard Python types that Monty supports as inputs.
 /// Unsupported types will raise a `TypeError`.
 ///
+/// Dataclass instances with methods are kept alive in `dc_registry`, see `dataclass_to_monty`.
+///
 /// # Important
 /// Checks `bool` before `int` since `bool` is a subclass of `int` in Python.
-pub fn py_to_monty(obj: &Bound<'_, PyAny>) -> PyResult<MontyObject> {
+pub fn py_to_monty(obj: &Bound<'_, PyAny>, dc_registry: &Bound<'_, PyDict>) -> PyResult<MontyObject> {
     if obj.is_none() {
         Ok(MontyObject::None)
     } else if let Ok(bool) = obj.cast::<PyBool>() {
@@ -43,12 +47,19 @@ pub fn py_to_monty(obj: &Bound<'_, PyAny>) -> PyResult<MontyObject> {
         }
     } else if let Ok(float) = obj.cast::<PyFloat>() {
         Ok(MontyObject::Float(float.extract()?))
+    } else if let Ok(complex) = obj.cast::<PyComplex>() {
+        Ok(MontyObject::Complex {
+            real: complex.real(),
+            imag: complex.imag(),
+        })
     } else if let Ok(string) = obj.cast::<PyString>() {
         Ok(MontyObject::String(string.extract()?))
     } else if let Ok(bytes) = obj.cast::<PyBytes>() {
         Ok(MontyObject::Bytes(bytes.extract()?))
+    } else if let Ok(bytearray) = obj.cast::<PyByteArray>() {
+        Ok(MontyObject::ByteArray(bytearray.to_vec()))
     } else if let Ok(list) = obj.cast::<PyList>() {
-        let items: PyResult<Vec<MontyObject>> = list.iter().map(|item| py_to_monty(&item)).collect();
+        let items: PyResult<Vec<MontyObject>> = list.iter().map(|item| py_to_monty(&item, dc_registry)).collect();
         Ok(MontyObject::List(items?))
     } else if let Ok(tuple) = obj.cast::<PyTuple>() {
         // Check for namedtuple BEFORE treating as regular tuple
@@ -71,7 +82,7 @@ pub fn py_to_monty(obj: &Bound<'_, PyAny>) -> PyResult<MontyObject> {
             // Extract field names as strings
             let field_names: PyResult<Vec<String>> = fields_tuple.iter().map(|f| f.extract::<String>()).collect();
             // Extract values
-            let values: PyResult<Vec<MontyObject>> = tuple.iter().map(|item| py_to_monty(&item)).collect();
+            let values: PyResult<Vec<MontyObject>> = tuple.iter().map(|item| py_to_monty(&item, dc_registry)).collect();
             return Ok(MontyObject::NamedTuple {
                 type_name,
                 field_names: field_names?,
@@ -79,28 +90,28 @@ pub fn py_to_monty(obj: &Bound<'_, PyAny>) -> PyResult<MontyObject> {
             });
         }
         // Regular tuple
-        let items: PyResult<Vec<MontyObject>> = tuple.iter().map(|item| py_to_monty(&item)).collect();
+        let items: PyResult<Vec<MontyObject>> = tuple.iter().map(|item| py_to_monty(&item, dc_registry)).collect();
         Ok(MontyObject::Tuple(items?))
     } else if let Ok(dict) = obj.cast::<PyDict>() {
         // in theory we could provide a way of passing the iterator direct to the internal MontyObject construct
         // it's probably not worth it right now
         Ok(MontyObject::dict(
             dict.iter()
-                .map(|(k, v)| Ok((py_to_monty(&k)?, py_to_monty(&v)?)))
+                .map(|(k, v)| Ok((py_to_monty(&k, dc_registry)?, py_to_monty(&v, dc_registry)?)))
                 .collect::<PyResult<Vec<(MontyObject, MontyObject)>>>()?,
         ))
     } else if let Ok(set) = obj.cast::<PySet>() {
-        let items: PyResult<Vec<MontyObject>> = set.iter().map(|item| py_to_monty(&item)).collect();
+        let items: PyResult<Vec<MontyObject>> = set.iter().map(|item| py_to_monty(&item, dc_registry)).collect();
         Ok(MontyObject::Set(items?))
     } else if let Ok(frozenset) = obj.cast::<PyFrozenSet>() {
-        let items: PyResult<Vec<MontyObject>> = frozenset.iter().map(|item| py_to_monty(&item)).collect();
+        let items: PyResult<Vec<MontyObject>> = frozenset.iter().map(|item| py_to_monty(&item, dc_registry)).collect();
         Ok(MontyObject::FrozenSet(items?))
     } else if obj.is(obj.py().Ellipsis()) {
         Ok(MontyObject::Ellipsis)
     } else if let Ok(exc) = obj.cast::<PyBaseException>() {
         Ok(exc_to_monty_object(exc))
     } else if is_dataclass(obj) {
-        dataclass_to_monty(obj)
+        dataclass_to_monty(obj, dc_registry)
     } else if obj.is_instance(get_pure_posix_path(obj.py())?)? {
         // Handle pathlib.PurePosixPath and thereby pathlib.PosixPath objects
         let path_str: String = obj.str()?.extract()?;
@@ -125,8 +136,10 @@ pub fn monty_to_py(py: Python<'_>, obj: &MontyObject, dc_registry: &Bound<'_, Py
         MontyObject::Int(i) => Ok(i.into_pyobject(py)?.clone().into_any().unbind()),
         MontyObject::BigInt(bi) => Ok(bi.into_pyobject(py)?.clone().into_any().unbind()),
         MontyObject::Float(f) => Ok(f.into_pyobject(py)?.clone().into_any().unbind()),
+        MontyObject::Complex { real, imag } => Ok(PyComplex::from_doubles(py, *real, *imag).into_any().unbind()),
         MontyObject::String(s) => Ok(PyString::new(py, s).into_any().unbind()),
         MontyObject::Bytes(b) => Ok(PyBytes::new(py, b).into_any().unbind()),
+        MontyObject::ByteArray(b) => Ok(PyByteArray::new(py, b).into_any().unbind()),
         MontyObject::List(items) => {
             let py_items: PyResult<Vec<Py<PyAny>>> =
                 items.iter().map(|item| monty_to_py(py, item, dc_registry)).collect();
@@ -195,6 +208,14 @@ pub fn monty_to_py(py: Python<'_>, obj: &MontyObject, dc_registry: &Bound<'_, Py
             let exc = exc_monty_to_py(py, MontyException::new(*exc_type, arg.clone()));
             Ok(exc.into_value(py).into_any())
         }
+        MontyObject::ExceptionGroup {
+            exc_type,
+            message,
+            exceptions,
+        } => {
+            let exc = exc_monty_to_py(py, object_to_monty_exception_group(*exc_type, message, exceptions));
+            Ok(exc.into_value(py).into_any())
+        }
         // Return Python's built-in type object
         MontyObject::Type(t) => import_builtins(py)?.getattr(py, t.to_string()),
         MontyObject::BuiltinFunction(f) => import_builtins(py)?.getattr(py, f.to_string()),
@@ -202,11 +223,21 @@ pub fn monty_to_py(py: Python<'_>, obj: &MontyObject, dc_registry: &Bound<'_, Py
         MontyObject::Dataclass {
             name,
             type_id,
+            instance_id,
             field_names,
             attrs,
             frozen,
             methods: _,
-        } => dataclass_to_py(py, name, *type_id, field_names, attrs, *frozen, dc_registry),
+        } => dataclass_to_py(
+            py,
+            name,
+            *type_id,
+            *instance_id,
+            field_names,
+            attrs,
+            *frozen,
+            dc_registry,
+        ),
         // Path - convert to Python pathlib.Path
         MontyObject::Path(p) => {
             let pure_posix_path = get_pure_posix_path(py)?;
@@ -219,6 +250,24 @@ pub fn monty_to_py(py: Python<'_>, obj: &MontyObject, dc_registry: &Bound<'_, Py
     }
 }
 
+/// Builds a `MontyException` from the fields of `MontyObject::ExceptionGroup`, including its
+/// sub-exceptions.
+fn object_to_monty_exception_group(exc_type: ExcType, message: &str, exceptions: &[MontyObject]) -> MontyException {
+    let exceptions = exceptions
+        .iter()
+        .filter_map(|exc| match exc {
+            MontyObject::Exception { exc_type, arg } => Some(MontyException::new(*exc_type, arg.clone())),
+            MontyObject::ExceptionGroup {
+                exc_type,
+                message,
+                exceptions,
+            } => Some(object_to_monty_exception_group(*exc_type, message, exceptions)),
+            _ => None,
+        })
+        .collect();
+    MontyException::new(exc_type, Some(message.to_owned())).with_exceptions(exceptions)
+}
+
 pub fn import_builtins(py: Python<'_>) -> PyResult<&Py<PyModule>> {
     static BUILTINS: PyOnceLock<Py<PyModule>> = PyOnceLock::new();
 
@@ -36,7 +36,10 @@ pub fn is_dataclass(value: &Bound<'_, PyAny>) -> bool {
 ///
 /// Extracts field names in definition order (for repr) and all field values as attrs.
 /// The `type_id` is set to `id(type(dc))` in Python, allowing registry lookups by type identity.
-pub fn dataclass_to_monty(value: &Bound<'_, PyAny>) -> PyResult<MontyObject> {
+///
+/// Instances with methods Monty can call are stored in `dc_registry` under `id(dc)`, which is
+/// set as the `instance_id`, so method calls and outputs resolve to this same object.
+pub fn dataclass_to_monty(value: &Bound<'_, PyAny>, dc_registry: &Bound<'_, PyDict>) -> PyResult<MontyObject> {
     let py = value.py();
 
     let dc_type = value.get_type();
@@ -65,38 +68,98 @@ pub fn dataclass_to_monty(value: &Bound<'_, PyAny>) -> PyResult<MontyObject> {
         if field_type.is(field_type_marker) {
             let field_name_str = field_name_obj.cast::<PyString>()?.to_str()?.to_string();
             let field_value = value.getattr(field_name_obj.cast::<PyString>()?)?;
-            let field_name_monty = py_to_monty(&field_name_obj)?;
-            let field_value_monty = py_to_monty(&field_value)?;
+            let field_name_monty = py_to_monty(&field_name_obj, dc_registry)?;
+            let field_value_monty = py_to_monty(&field_value, dc_registry)?;
 
             field_names.push(field_name_str);
             attrs.push((field_name_monty, field_value_monty));
         }
     }
 
+    let methods = dataclass_methods(&dc_type)?;
+
+    // Only instances Monty can call back into need to stay alive for the rest of the run
+    let instance_id = if methods.is_empty() {
+        None
+    } else {
+        let instance_id = value.as_ptr() as u64;
+        dc_registry.set_item(instance_id, value)?;
+        Some(instance_id)
+    };
+
     Ok(MontyObject::Dataclass {
         name,
         type_id,
+        instance_id,
         field_names,
         attrs: attrs.into(),
-        methods: vec![],
+        methods,
         frozen,
     })
 }
 
+/// Dunder methods exposed to Monty so dataclasses can be used as (async) context managers
+/// and async iterators.
+const PROTOCOL_METHODS: [&str; 6] = [
+    "__enter__",
+    "__exit__",
+    "__aenter__",
+    "__aexit__",
+    "__aiter__",
+    "__anext__",
+];
+
+/// Collects the names of methods Monty code may call on instances of a dataclass type.
+///
+/// Only the context manager and async iterator methods in `PROTOCOL_METHODS` are exposed,
+/// calling one of them in Monty pauses execution so the host can run it.
+fn dataclass_methods(dc_type: &Bound<'_, PyType>) -> PyResult<Vec<String>> {
+    let mut methods = Vec::new();
+    for method_name in PROTOCOL_METHODS {
+        if let Some(attr) = dc_type.getattr_opt(method_name)?
+            && attr.is_callable()
+        {
+            methods.push(method_name.to_string());
+        }
+    }
+    Ok(methods)
+}
+
 /// Converts a `MontyObject::Dataclass` to a Python object.
 ///
-/// If the `type_id` is found in the dc_registry, creates an instance of the original
+/// If the `instance_id` is found in the dc_registry, the original host instance is returned,
+/// with any fields Monty changed written back to it.
+/// Otherwise, if the `type_id` is found in the dc_registry, creates an instance of the original
 /// Python dataclass type (so `isinstance(result, OriginalClass)` works).
 /// Otherwise, falls back to creating a `PyUnknownDataclass`.
+#[expect(clippy::too_many_arguments)]
 pub fn dataclass_to_py(
     py: Python<'_>,
     name: &str,
     type_id: u64,
+    instance_id: Option<u64>,
     field_names: &[String],
     attrs: &DictPairs,
     frozen: bool,
     dc_registry: &Bound<'_, PyDict>,
 ) -> PyResult<Py<PyAny>> {
+    // Reuse the host instance so state set by its methods survives between calls
+    if let Some(instance_id) = instance_id
+        && let Some(instance) = dc_registry.get_item(instance_id)?
+        && instance.get_type().as_ptr() as u64 == type_id
+    {
+        if !frozen {
+            for (key, value) in attrs {
+                if let MontyObject::String(s) = key
+                    && field_names.contains(s)
+                    && py_to_monty(&instance.getattr(s.as_str())?, dc_registry).ok().as_ref() != Some(value)
+                {
+                    instance.setattr(s.as_str(), monty_to_py(py, value, dc_registry)?)?;
+                }
+            }
+        }
+        return Ok(instance.unbind());
+    }
     // Try to use the original type from the dc_registry (keyed by type_id)
     if let Some(original_type) = dc_registry.get_item(type_id)? {
         let original_type: Bound<'_, PyType> = original_type.cast_into()?;
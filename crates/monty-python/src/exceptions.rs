@@ -22,7 +22,7 @@ use pyo3::{
     types::{PyDict, PyList, PyString},
 };
 
-use crate::dataclass::get_frozen_instance_error;
+use crate::{convert::import_builtins, dataclass::get_frozen_instance_error};
 
 /// Base exception for all Monty interpreter errors.
 ///
@@ -363,15 +363,67 @@ impl PyFrame {
 /// Creates an appropriate Python exception type with the message.
 /// The traceback information is included in the exception message
 /// since PyO3 doesn't provide direct traceback manipulation.
+/// Chained exceptions are converted too and set as `__cause__` and `__context__`, and exception
+/// groups are converted with their sub-exceptions.
 pub fn exc_monty_to_py(py: Python<'_>, exc: MontyException) -> PyErr {
-    let exc_type = exc.exc_type();
-    let msg = exc.into_message().unwrap_or_default();
+    let cause = exc.cause().cloned();
+    let context = exc.context().cloned();
+    let suppress_context = exc.suppress_context();
+    let py_err = if exc.exc_type().is_group() {
+        let sub_exceptions = exc.exceptions().to_vec();
+        exc_group_to_py(
+            py,
+            exc.exc_type(),
+            exc.into_message().unwrap_or_default(),
+            sub_exceptions,
+        )
+    } else {
+        exc_type_to_py(py, exc.exc_type(), exc.into_message().unwrap_or_default())
+    };
+
+    let value = py_err.value(py);
+    if let Some(context) = context {
+        // setting the context of a freshly created exception can't fail
+        let _ = value.setattr("__context__", exc_monty_to_py(py, context).into_value(py));
+    }
+    if let Some(cause) = cause {
+        py_err.set_cause(py, Some(exc_monty_to_py(py, cause)));
+    }
+    if suppress_context {
+        let _ = value.setattr("__suppress_context__", true);
+    }
+    py_err
+}
+
+/// Creates a Python `ExceptionGroup` or `BaseExceptionGroup` containing the converted sub-exceptions.
+///
+/// Exception groups only exist from Python 3.11, so the class is looked up at runtime.
+fn exc_group_to_py(py: Python<'_>, exc_type: ExcType, msg: String, sub_exceptions: Vec<MontyException>) -> PyErr {
+    let sub_exceptions: Vec<Py<PyAny>> = sub_exceptions
+        .into_iter()
+        .map(|exc| exc_monty_to_py(py, exc).into_value(py).into_any())
+        .collect();
+    let group_name: &'static str = exc_type.into();
+    let group = import_builtins(py)
+        .and_then(|builtins| builtins.bind(py).getattr(group_name))
+        .and_then(|group_cls| group_cls.call1((msg, sub_exceptions)));
+    match group {
+        Ok(group) => PyErr::from_value(group),
+        Err(err) => err,
+    }
+}
 
+/// Creates a Python exception of the type matching `exc_type`.
+fn exc_type_to_py(py: Python<'_>, exc_type: ExcType, msg: String) -> PyErr {
     match exc_type {
         ExcType::Exception => exceptions::PyException::new_err(msg),
         ExcType::BaseException => exceptions::PyBaseException::new_err(msg),
         ExcType::SystemExit => exceptions::PySystemExit::new_err(msg),
         ExcType::KeyboardInterrupt => exceptions::PyKeyboardInterrupt::new_err(msg),
+        ExcType::GeneratorExit => exceptions::PyGeneratorExit::new_err(msg),
+        // groups need their sub-exceptions, so are created by `exc_group_to_py`
+        ExcType::BaseExceptionGroup => exceptions::PyBaseException::new_err(msg),
+        ExcType::ExceptionGroup => exceptions::PyException::new_err(msg),
         ExcType::ArithmeticError => exceptions::PyArithmeticError::new_err(msg),
         ExcType::OverflowError => exceptions::PyOverflowError::new_err(msg),
         ExcType::ZeroDivisionError => exceptions::PyZeroDivisionError::new_err(msg),
@@ -396,11 +448,15 @@ pub fn exc_monty_to_py(py: Python<'_>, exc: MontyException) -> PyErr {
         ExcType::NameError => exceptions::PyNameError::new_err(msg),
         ExcType::UnboundLocalError => exceptions::PyUnboundLocalError::new_err(msg),
         ExcType::StopIteration => exceptions::PyStopIteration::new_err(msg),
+        ExcType::StopAsyncIteration => exceptions::PyStopAsyncIteration::new_err(msg),
         ExcType::SyntaxError => exceptions::PySyntaxError::new_err(msg),
         ExcType::TimeoutError => exceptions::PyTimeoutError::new_err(msg),
         ExcType::TypeError => exceptions::PyTypeError::new_err(msg),
         ExcType::ValueError => exceptions::PyValueError::new_err(msg),
         ExcType::UnicodeDecodeError => exceptions::PyUnicodeDecodeError::new_err(msg),
+        // `json.JSONDecodeError` is built from the document and position, which aren't kept,
+        // so fall back to ValueError which it's a subclass of
+        ExcType::JSONDecodeError => exceptions::PyValueError::new_err(msg),
         ExcType::ImportError => exceptions::PyImportError::new_err(msg),
         ExcType::ModuleNotFoundError => exceptions::PyModuleNotFoundError::new_err(msg),
         ExcType::OSError => exceptions::PyOSError::new_err(msg),
@@ -408,6 +464,15 @@ pub fn exc_monty_to_py(py: Python<'_>, exc: MontyException) -> PyErr {
         ExcType::FileExistsError => exceptions::PyFileExistsError::new_err(msg),
         ExcType::IsADirectoryError => exceptions::PyIsADirectoryError::new_err(msg),
         ExcType::NotADirectoryError => exceptions::PyNotADirectoryError::new_err(msg),
+        ExcType::PatternError => {
+            if let Ok(exc_cls) = py.import("re").and_then(|re| re.getattr("error"))
+                && let Ok(exc_instance) = exc_cls.call1((PyString::new(py, &msg),))
+            {
+                return PyErr::from_value(exc_instance);
+            }
+            // if creating the right exception fails, fallback to Exception which it's a subclass of
+            exceptions::PyException::new_err(msg)
+        }
     }
 }
 
@@ -415,21 +480,73 @@ pub fn exc_monty_to_py(py: Python<'_>, exc: MontyException) -> PyErr {
 ///
 /// Used when resuming execution with an exception from Python.
 pub fn exc_py_to_monty(py: Python<'_>, py_err: &PyErr) -> MontyException {
-    let exc = py_err.value(py);
+    exc_bound_to_monty(py_err.value(py))
+}
+
+fn exc_bound_to_monty(exc: &Bound<'_, exceptions::PyBaseException>) -> MontyException {
+    if let Some(sub_exceptions) = py_group_exceptions(exc) {
+        let sub_exceptions = sub_exceptions.iter().map(exc_bound_to_monty).collect();
+        return MontyException::new(py_group_type(exc), py_group_message(exc)).with_exceptions(sub_exceptions);
+    }
     let exc_type = py_err_to_exc_type(exc);
     let arg = exc.str().ok().map(|s| s.to_string_lossy().into_owned());
 
     MontyException::new(exc_type, arg)
 }
 
-/// Converts a Python exception to Monty's `MontyObject::Exception`.
+/// Converts a Python exception to Monty's `MontyObject::Exception`, or `MontyObject::ExceptionGroup`
+/// for exception groups.
 pub fn exc_to_monty_object(exc: &Bound<'_, exceptions::PyBaseException>) -> ::monty::MontyObject {
+    if let Some(sub_exceptions) = py_group_exceptions(exc) {
+        return ::monty::MontyObject::ExceptionGroup {
+            exc_type: py_group_type(exc),
+            message: py_group_message(exc).unwrap_or_default(),
+            exceptions: sub_exceptions.iter().map(exc_to_monty_object).collect(),
+        };
+    }
     let exc_type = py_err_to_exc_type(exc);
     let arg = exc.str().ok().map(|s| s.to_string_lossy().into_owned());
 
     ::monty::MontyObject::Exception { exc_type, arg }
 }
 
+/// Returns the sub-exceptions of a Python `BaseExceptionGroup`, or `None` for other exceptions.
+///
+/// Exception groups only exist from Python 3.11, so the class is looked up at runtime.
+fn py_group_exceptions<'py>(
+    exc: &Bound<'py, exceptions::PyBaseException>,
+) -> Option<Vec<Bound<'py, exceptions::PyBaseException>>> {
+    let group_cls = import_builtins(exc.py())
+        .ok()?
+        .bind(exc.py())
+        .getattr("BaseExceptionGroup")
+        .ok()?;
+    if !exc.is_instance(&group_cls).ok()? {
+        return None;
+    }
+    let sub_exceptions = exc.getattr("exceptions").ok()?;
+    let sub_exceptions = sub_exceptions
+        .try_iter()
+        .ok()?
+        .filter_map(|item| item.ok()?.cast_into::<exceptions::PyBaseException>().ok())
+        .collect();
+    Some(sub_exceptions)
+}
+
+/// The Monty type of a Python exception group, which is an `ExceptionGroup` if it's an `Exception`.
+fn py_group_type(exc: &Bound<'_, exceptions::PyBaseException>) -> ExcType {
+    if exceptions::PyException::type_check(exc) {
+        ExcType::ExceptionGroup
+    } else {
+        ExcType::BaseExceptionGroup
+    }
+}
+
+/// The message of a Python exception group, since its `str()` includes the sub-exception count.
+fn py_group_message(exc: &Bound<'_, exceptions::PyBaseException>) -> Option<String> {
+    exc.getattr("message").ok()?.extract().ok()
+}
+
 /// Maps a Python exception type to Monty's `ExcType` enum.
 ///
 /// NOTE: order matters here as some exceptions are subclasses of others!
@@ -510,6 +627,8 @@ fn py_err_to_exc_type(exc: &Bound<'_, exceptions::PyBaseException>) -> ExcType {
             ExcType::TimeoutError
         } else if exceptions::PyMemoryError::type_check(exc) {
             ExcType::MemoryError
+        } else if exceptions::PyStopAsyncIteration::type_check(exc) {
+            ExcType::StopAsyncIteration
         } else {
             ExcType::Exception
         }
@@ -518,6 +637,8 @@ fn py_err_to_exc_type(exc: &Bound<'_, exceptions::PyBaseException>) -> ExcType {
         ExcType::SystemExit
     } else if exceptions::PyKeyboardInterrupt::type_check(exc) {
         ExcType::KeyboardInterrupt
+    } else if exceptions::PyGeneratorExit::type_check(exc) {
+        ExcType::GeneratorExit
     // Catch-all for BaseException
     } else {
         ExcType::BaseException
@@ -6,7 +6,7 @@
 
 use ::monty::{ExternalResult, MontyObject};
 use pyo3::{
-    exceptions::PyKeyError,
+    exceptions::{PyKeyError, PyTypeError},
     prelude::*,
     types::{PyDict, PyTuple},
 };
@@ -55,6 +55,32 @@ impl<'py> ExternalFunctionRegistry<'py> {
         }
     }
 
+    /// Calls a method on a dataclass passed in by the host.
+    ///
+    /// `args[0]` is the dataclass instance, it resolves to the original host object (kept in the
+    /// dataclass registry for the run) and the method is looked up on it, then called with the
+    /// remaining arguments.
+    pub fn call_method(
+        &self,
+        method_name: &str,
+        args: &[MontyObject],
+        kwargs: &[(MontyObject, MontyObject)],
+    ) -> ExternalResult {
+        let result = args
+            .split_first()
+            .ok_or_else(|| PyTypeError::new_err(format!("Method '{method_name}' called without an instance")))
+            .and_then(|(instance, rest)| {
+                let method = monty_to_py(self.py, instance, self.dc_registry)?
+                    .into_bound(self.py)
+                    .getattr(method_name)?;
+                self.call_callable(&method, rest, kwargs)
+            });
+        match result {
+            Ok(result) => ExternalResult::Return(result),
+            Err(err) => ExternalResult::Error(exc_py_to_monty(self.py, &err)),
+        }
+    }
+
     /// Inner implementation that returns `PyResult` for error handling.
     fn call_inner(
         &self,
@@ -67,7 +93,16 @@ impl<'py> ExternalFunctionRegistry<'py> {
             .functions
             .get_item(function_name)?
             .ok_or_else(|| PyKeyError::new_err(format!("External function '{function_name}' not found")))?;
+        self.call_callable(&callable, args, kwargs)
+    }
 
+    /// Calls a Python callable with Monty arguments, converting the result back to Monty format.
+    fn call_callable(
+        &self,
+        callable: &Bound<'py, PyAny>,
+        args: &[MontyObject],
+        kwargs: &[(MontyObject, MontyObject)],
+    ) -> PyResult<MontyObject> {
         // Convert positional arguments to Python objects
         let py_args: PyResult<Vec<Py<PyAny>>> = args
             .iter()
@@ -92,6 +127,6 @@ impl<'py> ExternalFunctionRegistry<'py> {
         };
 
         // Convert result back to Monty format
-        py_to_monty(&result)
+        py_to_monty(&result, self.dc_registry)
     }
 }
@@ -56,8 +56,9 @@ impl PyMonty {
     /// * `type_check` - Whether to perform type checking on the code
     /// * `type_check_stubs` - Prefix code to be executed before type checking
     /// * `dataclass_registry` - Registry of dataclass types for reconstructing original types on output.
+    /// * `allowed_modules` - Builtin modules the code may import, all of them when `None`
     #[new]
-    #[pyo3(signature = (code, *, script_name="main.py", inputs=None, external_functions=None, type_check=false, type_check_stubs=None, dataclass_registry=None))]
+    #[pyo3(signature = (code, *, script_name="main.py", inputs=None, external_functions=None, type_check=false, type_check_stubs=None, dataclass_registry=None, allowed_modules=None))]
     #[expect(clippy::too_many_arguments)]
     fn new(
         py: Python<'_>,
@@ -68,17 +69,27 @@ impl PyMonty {
         type_check: bool,
         type_check_stubs: Option<&str>,
         dataclass_registry: Option<Bound<'_, PyList>>,
+        allowed_modules: Option<&Bound<'_, PyList>>,
     ) -> PyResult<Self> {
         let input_names = list_str(inputs, "inputs")?;
         let external_function_names = list_str(external_functions, "external_functions")?;
+        let allowed_modules = allowed_modules
+            .map(|modules| list_str(Some(modules), "allowed_modules"))
+            .transpose()?;
 
         if type_check {
             py_type_check(py, &code, script_name, type_check_stubs)?;
         }
 
         // Create the snapshot (parses the code)
-        let runner = MontyRun::new(code, script_name, input_names.clone(), external_function_names.clone())
-            .map_err(|e| MontyError::new_err(py, e))?;
+        let runner = MontyRun::new_with_allowed_modules(
+            code,
+            script_name,
+            input_names.clone(),
+            external_function_names.clone(),
+            allowed_modules,
+        )
+        .map_err(|e| MontyError::new_err(py, e))?;
 
         Ok(Self {
             runner,
@@ -140,9 +151,6 @@ impl PyMonty {
         print_callback: Option<&Bound<'_, PyAny>>,
         os: Option<&Bound<'_, PyAny>>,
     ) -> PyResult<Py<PyAny>> {
-        // Extract input values in the order they were declared
-        let input_values = self.extract_input_values(inputs)?;
-
         if let Some(os_callback) = os
             && !os_callback.is_callable()
         {
@@ -157,16 +165,16 @@ impl PyMonty {
         if let Some(limits) = limits {
             let tracker = PySignalTracker::new(LimitedTracker::new(extract_limits(limits)?));
             if let Some(print_writer) = print_writer {
-                self.run_impl(py, input_values, tracker, external_functions, os, print_writer)
+                self.run_impl(py, inputs, tracker, external_functions, os, print_writer)
             } else {
-                self.run_impl(py, input_values, tracker, external_functions, os, StdPrint)
+                self.run_impl(py, inputs, tracker, external_functions, os, StdPrint)
             }
         } else {
             let tracker = PySignalTracker::new(NoLimitTracker);
             if let Some(print_writer) = print_writer {
-                self.run_impl(py, input_values, tracker, external_functions, os, print_writer)
+                self.run_impl(py, inputs, tracker, external_functions, os, print_writer)
             } else {
-                self.run_impl(py, input_values, tracker, external_functions, os, StdPrint)
+                self.run_impl(py, inputs, tracker, external_functions, os, StdPrint)
             }
         }
     }
@@ -179,8 +187,10 @@ impl PyMonty {
         limits: Option<&Bound<'py, PyDict>>,
         print_callback: Option<&Bound<'_, PyAny>>,
     ) -> PyResult<Bound<'py, PyAny>> {
+        // Dataclass instances are registered while converting, so each run gets its own registry
+        let dc_registry = self.dataclass_registry.bind(py).copy()?;
         // Extract input values in the order they were declared
-        let input_values = self.extract_input_values(inputs)?;
+        let input_values = self.extract_input_values(inputs, &dc_registry)?;
 
         // Helper macro to start execution with GIL released
         // CallbackStringPrint is Send so this works for both print_callback cases
@@ -215,7 +225,7 @@ impl PyMonty {
             py,
             self.script_name.clone(),
             print_callback.map(|c| c.clone().unbind()),
-            self.dataclass_registry.clone_ref(py),
+            dc_registry.unbind(),
         )
     }
 
@@ -307,7 +317,11 @@ impl PyMonty {
     /// Extracts input values from the dict in the order they were declared.
     ///
     /// Validates that all required inputs are provided and no extra inputs are given.
-    fn extract_input_values(&self, inputs: Option<&Bound<'_, PyDict>>) -> PyResult<Vec<::monty::MontyObject>> {
+    fn extract_input_values(
+        &self,
+        inputs: Option<&Bound<'_, PyDict>>,
+        dc_registry: &Bound<'_, PyDict>,
+    ) -> PyResult<Vec<::monty::MontyObject>> {
         if self.input_names.is_empty() {
             if inputs.is_some() {
                 return Err(PyTypeError::new_err(
@@ -331,7 +345,7 @@ impl PyMonty {
                 let value = inputs
                     .get_item(name)?
                     .ok_or_else(|| PyKeyError::new_err(format!("Missing required input: '{name}'")))?;
-                py_to_monty(&value)
+                py_to_monty(&value, dc_registry)
             })
             .collect::<PyResult<_>>()
     }
@@ -343,14 +357,20 @@ impl PyMonty {
     fn run_impl(
         &self,
         py: Python<'_>,
-        input_values: Vec<MontyObject>,
+        inputs: Option<&Bound<'_, PyDict>>,
         tracker: impl ResourceTracker + Send,
         external_functions: Option<&Bound<'_, PyDict>>,
         os: Option<&Bound<'_, PyAny>>,
         mut print_output: impl PrintWriter + Send,
     ) -> PyResult<Py<PyAny>> {
-        let dataclass_registry = self.dataclass_registry.bind(py);
-        if self.external_function_names.is_empty() && os.is_none() {
+        // Dataclass instances are registered while converting, so each run gets its own registry
+        let dataclass_registry = &self.dataclass_registry.bind(py).copy()?;
+        // Extract input values in the order they were declared
+        let input_values = self.extract_input_values(inputs, dataclass_registry)?;
+        let has_methods = input_values
+            .iter()
+            .any(|v| matches!(v, MontyObject::Dataclass { methods, .. } if !methods.is_empty()));
+        if self.external_function_names.is_empty() && os.is_none() && !has_methods {
             let runner = &self.runner;
             return match py.detach(|| runner.run(input_values, tracker, &mut print_output)) {
                 Ok(v) => monty_to_py(py, &v, dataclass_registry),
@@ -366,6 +386,24 @@ impl PyMonty {
         loop {
             match progress {
                 RunProgress::Complete(result) => return monty_to_py(py, &result, dataclass_registry),
+                RunProgress::FunctionCall {
+                    function_name,
+                    args,
+                    kwargs,
+                    method_call: true,
+                    state,
+                    ..
+                } => {
+                    let no_functions = PyDict::new(py);
+                    let functions = external_functions.unwrap_or(&no_functions);
+                    let registry = ExternalFunctionRegistry::new(py, functions, dataclass_registry);
+
+                    let return_value = registry.call_method(&function_name, &args, &kwargs);
+
+                    progress = py
+                        .detach(|| state.run(return_value, &mut print_output))
+                        .map_err(|e| MontyError::new_err(py, e))?;
+                }
                 RunProgress::FunctionCall {
                     function_name,
                     args,
@@ -416,7 +454,7 @@ impl PyMonty {
 
                         // call the os callback, if an exception is raised, return it to monty
                         match os_callback.call1((function.to_string(), py_args_tuple, py_kwargs)) {
-                            Ok(result) => py_to_monty(&result)?.into(),
+                            Ok(result) => py_to_monty(&result, dataclass_registry)?.into(),
                             Err(err) => exc_py_to_monty(py, &err).into(),
                         }
                     } else {
@@ -458,13 +496,15 @@ impl EitherProgress {
                     function_name,
                     args,
                     kwargs,
-                    state,
+                    method_call,
                     call_id,
+                    state,
                 } => Self::function_snapshot(
                     py,
                     function_name,
                     &args,
                     &kwargs,
+                    method_call,
                     call_id,
                     EitherSnapshot::NoLimit(state),
                     script_name,
@@ -502,13 +542,15 @@ impl EitherProgress {
                     function_name,
                     args,
                     kwargs,
-                    state,
+                    method_call,
                     call_id,
+                    state,
                 } => Self::function_snapshot(
                     py,
                     function_name,
                     &args,
                     &kwargs,
+                    method_call,
                     call_id,
                     EitherSnapshot::Limited(state),
                     script_name,
@@ -549,6 +591,7 @@ impl EitherProgress {
         function_name: String,
         args: &[MontyObject],
         kwargs: &[(MontyObject, MontyObject)],
+        is_method_call: bool,
         call_id: u32,
         snapshot: EitherSnapshot,
         script_name: String,
@@ -568,6 +611,7 @@ impl EitherProgress {
             print_callback: print_callback.map(|callback| callback.clone_ref(py)),
             script_name,
             is_os_function: false,
+            is_method_call,
             function_name,
             args: PyTuple::new(py, items?)?.unbind(),
             kwargs: dict.unbind(),
@@ -602,6 +646,7 @@ impl EitherProgress {
             print_callback: print_callback.map(|callback| callback.clone_ref(py)),
             script_name,
             is_os_function: true,
+            is_method_call: false,
             function_name: function.to_string(),
             args: PyTuple::new(py, items?)?.unbind(),
             kwargs: dict.unbind(),
@@ -656,6 +701,10 @@ pub struct PyMontySnapshot {
     #[pyo3(get)]
     pub is_os_function: bool,
 
+    /// Whether this call is a method call on a dataclass, the instance is the first argument
+    #[pyo3(get)]
+    pub is_method_call: bool,
+
     /// The name of the function being called.
     #[pyo3(get)]
     pub function_name: String,
@@ -674,13 +723,14 @@ pub struct PyMontySnapshot {
 fn extract_external_result(
     py: Python<'_>,
     dict: &Bound<'_, PyDict>,
+    dc_registry: &Bound<'_, PyDict>,
     error_msg: &'static str,
 ) -> PyResult<ExternalResult> {
     if dict.len() != 1 {
         Err(PyTypeError::new_err(error_msg))
     } else if let Some(rv) = dict.get_item(intern!(py, "return_value"))? {
         // Return value provided
-        Ok(py_to_monty(&rv)?.into())
+        Ok(py_to_monty(&rv, dc_registry)?.into())
     } else if let Some(exc) = dict.get_item(intern!(py, "exception"))? {
         // Exception provided
         let py_err = PyErr::from_value(exc.into_any());
@@ -714,7 +764,7 @@ impl PyMontySnapshot {
         let Some(kwargs) = kwargs else {
             return Err(PyTypeError::new_err(ARGS_ERROR));
         };
-        let external_result = extract_external_result(py, kwargs, ARGS_ERROR)?;
+        let external_result = extract_external_result(py, kwargs, self.dc_registry.bind(py), ARGS_ERROR)?;
 
         let snapshot = std::mem::replace(&mut self.snapshot, EitherSnapshot::Done);
 
@@ -772,6 +822,7 @@ impl PyMontySnapshot {
             snapshot: &'a EitherSnapshot,
             script_name: &'a str,
             is_os_function: bool,
+            is_method_call: bool,
             function_name: &'a str,
             args: Vec<MontyObject>,
             kwargs: Vec<(MontyObject, MontyObject)>,
@@ -784,12 +835,13 @@ impl PyMontySnapshot {
             ));
         }
 
+        let dc_registry = self.dc_registry.bind(py);
         // Convert Python args to MontyObject
         let args: Vec<MontyObject> = self
             .args
             .bind(py)
             .iter()
-            .map(|item| py_to_monty(&item))
+            .map(|item| py_to_monty(&item, dc_registry))
             .collect::<PyResult<_>>()?;
 
         // Convert Python kwargs to MontyObject pairs
@@ -797,13 +849,14 @@ impl PyMontySnapshot {
             .kwargs
             .bind(py)
             .iter()
-            .map(|(k, v)| Ok((py_to_monty(&k)?, py_to_monty(&v)?)))
+            .map(|(k, v)| Ok((py_to_monty(&k, dc_registry)?, py_to_monty(&v, dc_registry)?)))
             .collect::<PyResult<_>>()?;
 
         let serialized = SerializedSnapshot {
             snapshot: &self.snapshot,
             script_name: &self.script_name,
             is_os_function: self.is_os_function,
+            is_method_call: self.is_method_call,
             function_name: &self.function_name,
             args,
             kwargs,
@@ -841,6 +894,7 @@ impl PyMontySnapshot {
             snapshot: EitherSnapshot,
             script_name: String,
             is_os_function: bool,
+            is_method_call: bool,
             function_name: String,
             args: Vec<MontyObject>,
             kwargs: Vec<(MontyObject, MontyObject)>,
@@ -873,6 +927,7 @@ impl PyMontySnapshot {
             dc_registry: dc_registry.unbind(),
             script_name: serialized.script_name,
             is_os_function: serialized.is_os_function,
+            is_method_call: serialized.is_method_call,
             function_name: serialized.function_name,
             args: PyTuple::new(py, args)?.unbind(),
             kwargs: kwargs_dict.unbind(),
@@ -923,7 +978,7 @@ impl PyMontyFutureSnapshot {
             .map(|(key, value)| {
                 let call_id = key.extract::<u32>()?;
                 let dict = value.cast::<PyDict>()?;
-                let value = extract_external_result(py, dict, ARGS_ERROR)?;
+                let value = extract_external_result(py, dict, self.dc_registry.bind(py), ARGS_ERROR)?;
                 Ok((call_id, value))
             })
             .collect::<PyResult<Vec<_>>>()?;
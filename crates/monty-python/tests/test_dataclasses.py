@@ -1,3 +1,4 @@
+import asyncio
 from dataclasses import (
     FrozenInstanceError,
     asdict,
@@ -734,3 +735,163 @@ def test_repeat_dataclass_name():
     a, b = m.run(inputs={'a': Point(x=10, y=20), 'b': point_cls2(x=30, y=40)})
     assert isinstance(a, Point)
     assert isinstance(b, point_cls2)
+
+
+def test_dataclass_context_manager():
+    """Registered dataclasses with `__enter__`/`__exit__` can be used in `with` statements."""
+
+    log: list[str] = []
+
+    @dataclass
+    class Transaction:
+        name: str
+
+        def __enter__(self) -> str:
+            log.append(f'begin {self.name}')
+            return self.name
+
+        def __exit__(self, exc_type: type[BaseException] | None, exc: BaseException | None, tb: None) -> bool:
+            log.append(f'commit {self.name}' if exc is None else f'rollback {self.name}: {exc}')
+            return exc_type is KeyError
+
+    code = """
+with tx as name:
+    result = name.upper()
+with tx:
+    raise KeyError('missing')
+result
+"""
+    m = pydantic_monty.Monty(code, inputs=['tx'], dataclass_registry=[Transaction])
+    assert m.run(inputs={'tx': Transaction(name='t1')}) == snapshot('T1')
+    assert log == snapshot(['begin t1', 'commit t1', 'begin t1', "rollback t1: 'missing'"])
+
+
+async def test_dataclass_async_iterator():
+    """Registered dataclasses with `__aiter__`/`__anext__` can be used in `async for` loops."""
+
+    pending = ['a', 'b', 'c']
+
+    @dataclass
+    class Stream:
+        name: str
+
+        def __aiter__(self) -> 'Stream':
+            return self
+
+        async def __anext__(self) -> str:
+            await asyncio.sleep(0)
+            if not pending:
+                raise StopAsyncIteration
+            return f'{self.name}:{pending.pop(0)}'
+
+    code = """
+async def main():
+    return [item async for item in stream]
+
+await main()
+"""
+    m = pydantic_monty.Monty(code, inputs=['stream'], dataclass_registry=[Stream])
+    result = await pydantic_monty.run_monty_async(m, inputs={'stream': Stream(name='s')})
+    assert result == snapshot(['s:a', 's:b', 's:c'])
+
+
+async def test_dataclass_async_iterator_keeps_state():
+    """`__anext__` is called on the instance returned by `__aiter__`, so iteration state lives on the host object."""
+
+    @dataclass
+    class Countdown:
+        start: int
+
+        def __aiter__(self) -> 'Countdown':
+            self.remaining = self.start
+            return self
+
+        async def __anext__(self) -> int:
+            await asyncio.sleep(0)
+            if self.remaining == 0:
+                raise StopAsyncIteration
+            self.remaining -= 1
+            return self.remaining
+
+    code = """
+async def main():
+    return [n async for n in countdown]
+
+await main()
+"""
+    countdown = Countdown(start=3)
+    m = pydantic_monty.Monty(code, inputs=['countdown'], dataclass_registry=[Countdown])
+    result = await pydantic_monty.run_monty_async(m, inputs={'countdown': countdown})
+    assert result == snapshot([2, 1, 0])
+    assert countdown.remaining == 0
+
+
+def test_dataclass_context_manager_keeps_instance():
+    """`__enter__` and `__exit__` are called on the same host instance, so state set by one is seen by the other."""
+
+    @dataclass
+    class Connection:
+        name: str
+
+        def __enter__(self) -> str:
+            self._session = f'session-{self.name}'
+            return self._session
+
+        def __exit__(self, exc_type: type[BaseException] | None, exc: BaseException | None, tb: None) -> None:
+            self.closed = self._session
+
+    code = """
+with conn as session:
+    conn.name = 'changed'
+session
+"""
+    conn = Connection(name='db')
+    m = pydantic_monty.Monty(code, inputs=['conn'], dataclass_registry=[Connection])
+    assert m.run(inputs={'conn': conn}) == snapshot('session-db')
+    assert conn.closed == snapshot('session-db')
+    assert conn.name == snapshot('changed')
+
+
+def test_dataclass_public_methods_not_exposed():
+    """Only context manager and async iterator methods are forwarded to the host."""
+
+    @dataclass
+    class Account:
+        balance: int
+
+        def deposit(self, amount: int) -> int:
+            return self.balance + amount
+
+    m = pydantic_monty.Monty('acct.deposit(5)', inputs=['acct'], dataclass_registry=[Account])
+    with pytest.raises(pydantic_monty.MontyRuntimeError) as exc_info:
+        m.run(inputs={'acct': Account(balance=10)})
+    assert isinstance(exc_info.value.exception(), AttributeError)
+
+
+def test_dataclass_method_call_snapshot():
+    """Method calls on dataclasses pause execution with `is_method_call` set."""
+
+    @dataclass
+    class Account:
+        balance: int
+
+        def __enter__(self) -> int:
+            return self.balance
+
+        def __exit__(self, exc_type: type[BaseException] | None, exc: BaseException | None, tb: None) -> None:
+            pass
+
+    acct = Account(balance=10)
+    m = pydantic_monty.Monty('with acct as balance:\n    balance + 5', inputs=['acct'], dataclass_registry=[Account])
+    progress = m.start(inputs={'acct': acct})
+    assert isinstance(progress, pydantic_monty.MontySnapshot)
+    assert progress.is_method_call is True
+    assert progress.function_name == snapshot('__enter__')
+    assert progress.args == snapshot((Account(balance=10),))
+    assert progress.args[0] is acct
+    progress = progress.resume(return_value=10)
+    assert isinstance(progress, pydantic_monty.MontySnapshot)
+    assert progress.function_name == snapshot('__exit__')
+    assert progress.args[0] is acct
+    result = progress.resume(return_value=None)
+    assert isinstance(result, pydantic_monty.MontyComplete)
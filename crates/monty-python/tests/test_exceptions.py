@@ -1,3 +1,5 @@
+import sys
+
 import pytest
 from inline_snapshot import snapshot
 
@@ -102,6 +104,19 @@ def test_not_implemented_error():
     assert str(inner) == snapshot('not implemented')
 
 
+def test_import_not_in_allowed_modules():
+    with pytest.raises(pydantic_monty.MontyRuntimeError) as exc_info:
+        pydantic_monty.Monty('import sys, os', allowed_modules=['sys'])
+    inner = exc_info.value.exception()
+    assert isinstance(inner, ModuleNotFoundError)
+    assert str(inner) == snapshot("No module named 'os'")
+
+
+def test_import_in_allowed_modules():
+    m = pydantic_monty.Monty('import os.path\nos.path.join("a", "b")', allowed_modules=['os'])
+    assert m.run() == snapshot('a/b')
+
+
 # === MontySyntaxError tests ===
 
 
@@ -173,6 +188,82 @@ fail()
     assert str(inner) == snapshot('from function')
 
 
+def test_exception_cause():
+    code = """
+try:
+    1 / 0
+except ZeroDivisionError as e:
+    raise ValueError('wrapped') from e
+"""
+    m = pydantic_monty.Monty(code)
+    with pytest.raises(pydantic_monty.MontyRuntimeError) as exc_info:
+        m.run()
+    inner = exc_info.value.exception()
+    assert isinstance(inner, ValueError)
+    assert isinstance(inner.__cause__, ZeroDivisionError)
+    assert isinstance(inner.__context__, ZeroDivisionError)
+    assert inner.__suppress_context__ is True
+
+
+def test_exception_context():
+    code = """
+try:
+    {}['missing']
+except KeyError:
+    raise ValueError('while handling')
+"""
+    m = pydantic_monty.Monty(code)
+    with pytest.raises(pydantic_monty.MontyRuntimeError) as exc_info:
+        m.run()
+    inner = exc_info.value.exception()
+    assert isinstance(inner, ValueError)
+    assert inner.__cause__ is None
+    assert isinstance(inner.__context__, KeyError)
+    assert inner.__suppress_context__ is False
+
+
+requires_exception_groups = pytest.mark.skipif(sys.version_info < (3, 11), reason='ExceptionGroup requires 3.11')
+
+
+@requires_exception_groups
+def test_exception_group():
+    code = """
+try:
+    raise ExceptionGroup('errors', [ValueError('a'), TypeError('b')])
+except* ValueError:
+    pass
+"""
+    m = pydantic_monty.Monty(code)
+    with pytest.raises(pydantic_monty.MontyRuntimeError) as exc_info:
+        m.run()
+    inner = exc_info.value.exception()
+    assert isinstance(inner, ExceptionGroup)  # noqa: F821
+    assert inner.message == snapshot('errors')
+    assert repr(inner.exceptions) == snapshot("(TypeError('b'),)")
+
+
+@requires_exception_groups
+def test_exception_group_display():
+    code = """\
+try:
+    raise ExceptionGroup('errors', [ValueError('a'), TypeError('b')])
+except* ValueError:
+    pass
+"""
+    m = pydantic_monty.Monty(code)
+    with pytest.raises(pydantic_monty.MontyRuntimeError) as exc_info:
+        m.run()
+    assert exc_info.value.display() == snapshot("""\
+  + Exception Group Traceback (most recent call last):
+  |   File "main.py", line 2, in <module>
+  |     raise ExceptionGroup('errors', [ValueError('a'), TypeError('b')])
+  | ExceptionGroup: errors (1 sub-exception)
+  +-+---------------- 1 ----------------
+    | TypeError: b
+    +------------------------------------\
+""")
+
+
 # === Display and str methods ===
 
 
@@ -207,6 +298,32 @@ ValueError: test message\
 """)
 
 
+def test_display_chained_traceback():
+    code = """\
+try:
+    1 / 0
+except ZeroDivisionError as e:
+    raise ValueError('wrapped') from e
+"""
+    m = pydantic_monty.Monty(code)
+    with pytest.raises(pydantic_monty.MontyRuntimeError) as exc_info:
+        m.run()
+    assert exc_info.value.display() == snapshot("""\
+Traceback (most recent call last):
+  File "main.py", line 2, in <module>
+    1 / 0
+    ~~~~~
+ZeroDivisionError: division by zero
+
+The above exception was the direct cause of the following exception:
+
+Traceback (most recent call last):
+  File "main.py", line 4, in <module>
+    raise ValueError('wrapped') from e
+ValueError: wrapped\
+""")
+
+
 def test_str_returns_msg():
     m = pydantic_monty.Monty("raise ValueError('test message')")
     with pytest.raises(pydantic_monty.MontyRuntimeError) as exc_info:
@@ -1,3 +1,5 @@
+import sys
+
 import pytest
 from inline_snapshot import snapshot
 
@@ -42,6 +44,13 @@ def test_float():
     assert m.run(inputs={'x': 0.0}) == snapshot(0.0)
 
 
+def test_complex():
+    m = pydantic_monty.Monty('x', inputs=['x'])
+    assert m.run(inputs={'x': 1 + 2j}) == snapshot(1 + 2j)
+    assert m.run(inputs={'x': -1.5j}) == snapshot(-1.5j)
+    assert pydantic_monty.Monty('(1 + 2j) * 2j').run() == snapshot(-4 + 2j)
+
+
 def test_string():
     m = pydantic_monty.Monty('x', inputs=['x'])
     assert m.run(inputs={'x': 'hello'}) == snapshot('hello')
@@ -180,6 +189,15 @@ def test_exception_subclass_input():
     assert str(result) == snapshot('custom')
 
 
+@pytest.mark.skipif(sys.version_info < (3, 11), reason='ExceptionGroup requires 3.11')
+def test_exception_group_roundtrip():
+    m = pydantic_monty.Monty('x', inputs=['x'])
+    exc = ExceptionGroup('errors', [ValueError('a'), ExceptionGroup('inner', [KeyError('b')])])  # noqa: F821
+    result = m.run(inputs={'x': exc})
+    assert type(result) is ExceptionGroup  # noqa: F821
+    assert repr(result) == snapshot("ExceptionGroup('errors', [ValueError('a'), ExceptionGroup('inner', [KeyError('b')])])")
+
+
 # === Subtype coercion ===
 # Monty converts Python subclasses to their base types since it doesn't
 # have Python's class system.
@@ -0,0 +1,175 @@
+from typing import ClassVar, NoReturn, SupportsIndex, final, overload
+
+MINYEAR: int
+MAXYEAR: int
+
+@final
+class timedelta:
+    min: ClassVar[timedelta]
+    max: ClassVar[timedelta]
+    resolution: ClassVar[timedelta]
+    def __new__(
+        cls,
+        days: float = 0,
+        seconds: float = 0,
+        microseconds: float = 0,
+        milliseconds: float = 0,
+        minutes: float = 0,
+        hours: float = 0,
+        weeks: float = 0,
+    ) -> timedelta: ...
+    @property
+    def days(self) -> int: ...
+    @property
+    def seconds(self) -> int: ...
+    @property
+    def microseconds(self) -> int: ...
+    def total_seconds(self) -> float: ...
+    def __add__(self, value: timedelta, /) -> timedelta: ...
+    def __radd__(self, value: timedelta, /) -> timedelta: ...
+    def __sub__(self, value: timedelta, /) -> timedelta: ...
+    def __rsub__(self, value: timedelta, /) -> timedelta: ...
+    def __neg__(self) -> timedelta: ...
+    def __pos__(self) -> timedelta: ...
+    def __abs__(self) -> timedelta: ...
+    def __mul__(self, value: float, /) -> timedelta: ...
+    def __rmul__(self, value: float, /) -> timedelta: ...
+    @overload
+    def __floordiv__(self, value: timedelta, /) -> int: ...
+    @overload
+    def __floordiv__(self, value: int, /) -> timedelta: ...
+    @overload
+    def __truediv__(self, value: timedelta, /) -> float: ...
+    @overload
+    def __truediv__(self, value: float, /) -> timedelta: ...
+    def __mod__(self, value: timedelta, /) -> timedelta: ...
+    def __le__(self, value: timedelta, /) -> bool: ...
+    def __lt__(self, value: timedelta, /) -> bool: ...
+    def __ge__(self, value: timedelta, /) -> bool: ...
+    def __gt__(self, value: timedelta, /) -> bool: ...
+    def __eq__(self, value: object, /) -> bool: ...
+    def __bool__(self) -> bool: ...
+    def __hash__(self) -> int: ...
+
+@final
+class timezone:
+    utc: ClassVar[timezone]
+    min: ClassVar[timezone]
+    max: ClassVar[timezone]
+    def __new__(cls, offset: timedelta, name: str = ...) -> timezone: ...
+    def tzname(self, dt: datetime | None, /) -> str: ...
+    def utcoffset(self, dt: datetime | None, /) -> timedelta: ...
+    def dst(self, dt: datetime | None, /) -> None: ...
+    def __eq__(self, value: object, /) -> bool: ...
+    def __hash__(self) -> int: ...
+
+UTC: timezone
+
+class date:
+    min: ClassVar[date]
+    max: ClassVar[date]
+    resolution: ClassVar[timedelta]
+    def __new__(cls, year: SupportsIndex, month: SupportsIndex, day: SupportsIndex) -> date: ...
+    @classmethod
+    def fromtimestamp(cls, timestamp: float, /) -> date: ...
+    @classmethod
+    def today(cls) -> date: ...
+    @classmethod
+    def fromordinal(cls, n: int, /) -> date: ...
+    @classmethod
+    def fromisoformat(cls, date_string: str, /) -> date: ...
+    @property
+    def year(self) -> int: ...
+    @property
+    def month(self) -> int: ...
+    @property
+    def day(self) -> int: ...
+    def strftime(self, format: str) -> str: ...
+    def isoformat(self) -> str: ...
+    def replace(self, year: SupportsIndex = ..., month: SupportsIndex = ..., day: SupportsIndex = ...) -> date: ...
+    def toordinal(self) -> int: ...
+    def weekday(self) -> int: ...
+    def isoweekday(self) -> int: ...
+    def isocalendar(self) -> tuple[int, int, int]: ...
+    def __le__(self, value: date, /) -> bool: ...
+    def __lt__(self, value: date, /) -> bool: ...
+    def __ge__(self, value: date, /) -> bool: ...
+    def __gt__(self, value: date, /) -> bool: ...
+    def __eq__(self, value: object, /) -> bool: ...
+    def __add__(self, value: timedelta, /) -> date: ...
+    def __radd__(self, value: timedelta, /) -> date: ...
+    @overload
+    def __sub__(self, value: datetime, /) -> NoReturn: ...
+    @overload
+    def __sub__(self, value: date, /) -> timedelta: ...
+    @overload
+    def __sub__(self, value: timedelta, /) -> date: ...
+    def __hash__(self) -> int: ...
+
+@final
+class datetime(date):
+    min: ClassVar[datetime]
+    max: ClassVar[datetime]
+    def __new__(
+        cls,
+        year: SupportsIndex,
+        month: SupportsIndex,
+        day: SupportsIndex,
+        hour: SupportsIndex = 0,
+        minute: SupportsIndex = 0,
+        second: SupportsIndex = 0,
+        microsecond: SupportsIndex = 0,
+        tzinfo: timezone | None = None,
+    ) -> datetime: ...
+    @property
+    def hour(self) -> int: ...
+    @property
+    def minute(self) -> int: ...
+    @property
+    def second(self) -> int: ...
+    @property
+    def microsecond(self) -> int: ...
+    @property
+    def tzinfo(self) -> timezone | None: ...
+    @classmethod
+    def fromtimestamp(cls, timestamp: float, tz: timezone | None = None) -> datetime: ...
+    @classmethod
+    def today(cls) -> datetime: ...
+    @classmethod
+    def now(cls, tz: timezone | None = None) -> datetime: ...
+    @classmethod
+    def fromordinal(cls, n: int, /) -> datetime: ...
+    @classmethod
+    def fromisoformat(cls, date_string: str, /) -> datetime: ...
+    @classmethod
+    def strptime(cls, date_string: str, format: str, /) -> datetime: ...
+    def timestamp(self) -> float: ...
+    def date(self) -> date: ...
+    def replace(
+        self,
+        year: SupportsIndex = ...,
+        month: SupportsIndex = ...,
+        day: SupportsIndex = ...,
+        hour: SupportsIndex = ...,
+        minute: SupportsIndex = ...,
+        second: SupportsIndex = ...,
+        microsecond: SupportsIndex = ...,
+        tzinfo: timezone | None = ...,
+    ) -> datetime: ...
+    def astimezone(self, tz: timezone | None = None) -> datetime: ...
+    def isoformat(self, sep: str = 'T', timespec: str = 'auto') -> str: ...
+    def utcoffset(self) -> timedelta | None: ...
+    def tzname(self) -> str | None: ...
+    def dst(self) -> None: ...
+    def __le__(self, value: datetime, /) -> bool: ...  # type: ignore[override]
+    def __lt__(self, value: datetime, /) -> bool: ...  # type: ignore[override]
+    def __ge__(self, value: datetime, /) -> bool: ...  # type: ignore[override]
+    def __gt__(self, value: datetime, /) -> bool: ...  # type: ignore[override]
+    def __eq__(self, value: object, /) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __add__(self, value: timedelta, /) -> datetime: ...
+    def __radd__(self, value: timedelta, /) -> datetime: ...
+    @overload  # type: ignore[override]
+    def __sub__(self, value: datetime, /) -> timedelta: ...
+    @overload
+    def __sub__(self, value: timedelta, /) -> datetime: ...
@@ -0,0 +1,18 @@
+from collections.abc import Callable
+from typing import Any
+
+class JSONDecodeError(ValueError): ...
+
+def dumps(
+    obj: Any,
+    *,
+    skipkeys: bool = False,
+    ensure_ascii: bool = True,
+    check_circular: bool = True,
+    allow_nan: bool = True,
+    indent: None | int | str = None,
+    separators: tuple[str, str] | None = None,
+    default: Callable[[Any], Any] | None = None,
+    sort_keys: bool = False,
+) -> str: ...
+def loads(s: str | bytes | bytearray) -> Any: ...
@@ -0,0 +1,80 @@
+from collections.abc import Callable, Iterator
+from typing import Any, final, overload
+
+class error(Exception): ...
+
+PatternError = error
+
+NOFLAG: int
+IGNORECASE: int
+I: int
+MULTILINE: int
+M: int
+DOTALL: int
+S: int
+VERBOSE: int
+X: int
+ASCII: int
+A: int
+UNICODE: int
+U: int
+
+@final
+class Match:
+    @property
+    def pos(self) -> int: ...
+    @property
+    def endpos(self) -> int: ...
+    @property
+    def lastindex(self) -> int | None: ...
+    @property
+    def lastgroup(self) -> str | None: ...
+    @property
+    def string(self) -> str: ...
+    @property
+    def re(self) -> Pattern: ...
+    def expand(self, template: str) -> str: ...
+    @overload
+    def group(self, group: int | str = 0, /) -> str | Any: ...
+    @overload
+    def group(self, group1: int | str, group2: int | str, /, *groups: int | str) -> tuple[str | Any, ...]: ...
+    def groups(self, default: Any = None) -> tuple[str | Any, ...]: ...
+    def groupdict(self, default: Any = None) -> dict[str, str | Any]: ...
+    def start(self, group: int | str = 0, /) -> int: ...
+    def end(self, group: int | str = 0, /) -> int: ...
+    def span(self, group: int | str = 0, /) -> tuple[int, int]: ...
+    def __getitem__(self, key: int | str, /) -> str | Any: ...
+
+@final
+class Pattern:
+    @property
+    def flags(self) -> int: ...
+    @property
+    def groupindex(self) -> dict[str, int]: ...
+    @property
+    def groups(self) -> int: ...
+    @property
+    def pattern(self) -> str: ...
+    def search(self, string: str, pos: int = 0, endpos: int = ...) -> Match | None: ...
+    def match(self, string: str, pos: int = 0, endpos: int = ...) -> Match | None: ...
+    def fullmatch(self, string: str, pos: int = 0, endpos: int = ...) -> Match | None: ...
+    def split(self, string: str, maxsplit: int = 0) -> list[str | Any]: ...
+    def findall(self, string: str, pos: int = 0, endpos: int = ...) -> list[Any]: ...
+    def finditer(self, string: str, pos: int = 0, endpos: int = ...) -> Iterator[Match]: ...
+    def sub(self, repl: str | Callable[[Match], str], string: str, count: int = 0) -> str: ...
+    def subn(self, repl: str | Callable[[Match], str], string: str, count: int = 0) -> tuple[str, int]: ...
+
+def compile(pattern: str | Pattern, flags: int = 0) -> Pattern: ...
+def search(pattern: str | Pattern, string: str, flags: int = 0) -> Match | None: ...
+def match(pattern: str | Pattern, string: str, flags: int = 0) -> Match | None: ...
+def fullmatch(pattern: str | Pattern, string: str, flags: int = 0) -> Match | None: ...
+def split(pattern: str | Pattern, string: str, maxsplit: int = 0, flags: int = 0) -> list[str | Any]: ...
+def findall(pattern: str | Pattern, string: str, flags: int = 0) -> list[Any]: ...
+def finditer(pattern: str | Pattern, string: str, flags: int = 0) -> Iterator[Match]: ...
+def sub(
+    pattern: str | Pattern, repl: str | Callable[[Match], str], string: str, count: int = 0, flags: int = 0
+) -> str: ...
+def subn(
+    pattern: str | Pattern, repl: str | Callable[[Match], str], string: str, count: int = 0, flags: int = 0
+) -> tuple[str, int]: ...
+def escape(pattern: str) -> str: ...
@@ -0,0 +1,3 @@
+def time() -> float: ...
+def monotonic() -> float: ...
+def sleep(secs: float, /) -> None: ...
@@ -128,10 +128,15 @@ asyncio: 3.4-
 builtins: 3.0-
 collections: 3.0-
 dataclasses: 3.7-
+datetime: 3.0-
+json: 3.0-
+math: 3.0-
 os: 3.0-
 pathlib: 3.4-
 pathlib.types: 3.14-
+re: 3.0-
 sys: 3.0-
+time: 3.0-
 typing: 3.5-
 typing_extensions: 3.7-
 types: 3.0-
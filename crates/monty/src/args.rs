@@ -3,7 +3,7 @@ use std::vec::IntoIter;
 use crate::{
     MontyObject, ResourceTracker, defer_drop, defer_drop_mut,
     exception_private::{ExcType, RunError, RunResult, SimpleException},
-    expressions::{ExprLoc, Identifier},
+    expressions::{Expr, ExprLoc, Identifier},
     heap::{DropWithHeap, Heap, HeapGuard},
     intern::{Interns, StringId},
     parse::ParseError,
@@ -204,6 +204,29 @@ impl ArgValues {
         }
     }
 
+    /// Inserts `value` as the first positional argument.
+    ///
+    /// Used to pass `self` when calling methods of user-defined classes.
+    #[must_use]
+    pub fn prepend(self, value: Value) -> Self {
+        match self {
+            Self::Empty => Self::One(value),
+            Self::One(v) => Self::Two(value, v),
+            Self::Two(v1, v2) => Self::ArgsKargs {
+                args: vec![value, v1, v2],
+                kwargs: KwargsValues::Empty,
+            },
+            Self::Kwargs(kwargs) => Self::ArgsKargs {
+                args: vec![value],
+                kwargs,
+            },
+            Self::ArgsKargs { mut args, kwargs } => {
+                args.insert(0, value);
+                Self::ArgsKargs { args, kwargs }
+            }
+        }
+    }
+
     /// Variant of [`into_parts()`](Self::into_parts) that accepts no kwargs, returning an error if any are present.
     pub fn into_pos_only(self, method_name: &str, heap: &mut Heap<impl ResourceTracker>) -> RunResult<ArgPosIter> {
         match self {
@@ -264,7 +287,7 @@ impl ArgValues {
     /// Returns the number of positional arguments.
     ///
     /// For `Kwargs` returns 0, for `ArgsKargs` returns only the positional args count.
-    fn count(&self) -> usize {
+    pub fn count(&self) -> usize {
         match self {
             Self::Empty => 0,
             Self::One(_) => 1,
@@ -507,6 +530,110 @@ impl<T: ResourceTracker> DropWithHeap<T> for KwargsValuesIter {
     }
 }
 
+/// Arguments bound to the parameters of a function, `None` for optional parameters that
+/// weren't passed.
+pub(crate) struct BoundArgs<const N: usize>(pub [Option<Value>; N]);
+
+impl<const N: usize> BoundArgs<N> {
+    /// Returns the argument bound to parameter `index`.
+    pub fn get(&self, index: usize) -> Option<&Value> {
+        self.0[index].as_ref()
+    }
+}
+
+impl<T: ResourceTracker, const N: usize> DropWithHeap<T> for BoundArgs<N> {
+    fn drop_with_heap(self, heap: &mut Heap<T>) {
+        for value in self.0 {
+            value.drop_with_heap(heap);
+        }
+    }
+}
+
+/// Binds `args` to `params`, which can be passed by position or keyword, the first
+/// `required` of them being required.
+pub(crate) fn bind_args<const N: usize>(
+    name: &str,
+    params: [&str; N],
+    required: usize,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<BoundArgs<N>> {
+    let mut bound = BoundArgs(std::array::from_fn(|_| None));
+    bind_args_into(name, &params, required, args, &mut bound.0, heap, interns)?;
+    Ok(bound)
+}
+
+/// Like [`bind_args`], but for parameter lists only known at runtime.
+///
+/// Binds into `slots`, which must be empty and the same length as `params`. On error,
+/// any values already bound are dropped and the slots are left empty.
+pub(crate) fn bind_args_into(
+    name: &str,
+    params: &[&str],
+    required: usize,
+    args: ArgValues,
+    slots: &mut [Option<Value>],
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<()> {
+    let (positional, kwargs) = args.into_parts();
+    if positional.len() > params.len() {
+        let count = positional.len();
+        positional.drop_with_heap(heap);
+        kwargs.drop_with_heap(heap);
+        return Err(ExcType::type_error_too_many_positional(name, params.len(), count, 0));
+    }
+    for (slot, value) in slots.iter_mut().zip(positional) {
+        *slot = Some(value);
+    }
+
+    let mut kwargs = kwargs.into_iter();
+    while let Some((key, value)) = kwargs.next() {
+        let index = match key.as_either_str(heap) {
+            Some(key_name) => {
+                let key_name = key_name.as_str(interns);
+                match params.iter().position(|&param| param == key_name) {
+                    Some(index) if slots[index].is_none() => Ok(index),
+                    Some(index) => Err(ExcType::type_error_duplicate_arg(name, params[index])),
+                    None => Err(ExcType::type_error_unexpected_keyword(name, key_name)),
+                }
+            }
+            None => Err(ExcType::type_error("keywords must be strings")),
+        };
+        key.drop_with_heap(heap);
+        match index {
+            Ok(index) => slots[index] = Some(value),
+            Err(err) => {
+                value.drop_with_heap(heap);
+                kwargs.drop_with_heap(heap);
+                clear_slots(slots, heap);
+                return Err(err);
+            }
+        }
+    }
+
+    let missing: Vec<&str> = params[..required]
+        .iter()
+        .zip(slots.iter())
+        .filter(|(_, value)| value.is_none())
+        .map(|(param, _)| *param)
+        .collect();
+    if !missing.is_empty() {
+        let err = ExcType::type_error_missing_positional_with_names(name, &missing);
+        clear_slots(slots, heap);
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Drops and clears any values bound by [`bind_args_into`].
+fn clear_slots(slots: &mut [Option<Value>], heap: &mut Heap<impl ResourceTracker>) {
+    for slot in slots {
+        slot.take().drop_with_heap(heap);
+    }
+}
+
 /// A keyword argument in a function call expression.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Kwarg {
@@ -514,6 +641,25 @@ pub struct Kwarg {
     pub value: ExprLoc,
 }
 
+impl Kwarg {
+    /// Applies a transformation function to the keyword argument's value.
+    fn prepare(self, f: impl FnOnce(ExprLoc) -> Result<ExprLoc, ParseError>) -> Result<Self, ParseError> {
+        Ok(Self {
+            key: self.key,
+            value: f(self.value)?,
+        })
+    }
+}
+
+/// A keyword argument or `**mapping` unpacking in a call that uses unpacking.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum KwargExpr {
+    /// A named keyword argument: `key=value`.
+    Named(Kwarg),
+    /// A mapping unpacked into keyword arguments: `**mapping`.
+    Unpack(ExprLoc),
+}
+
 /// Expressions that make up a function call's arguments.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum ArgExprs {
@@ -523,32 +669,41 @@ pub enum ArgExprs {
     Args(Vec<ExprLoc>),
     Kwargs(Vec<Kwarg>),
     ArgsKargs {
-        args: Option<Vec<ExprLoc>>,
-        var_args: Option<ExprLoc>,
-        kwargs: Option<Vec<Kwarg>>,
-        var_kwargs: Option<ExprLoc>,
+        args: Vec<ExprLoc>,
+        kwargs: Vec<Kwarg>,
+    },
+    /// Arguments containing `*iterable` or `**mapping` unpacking.
+    ///
+    /// Positional arguments keep their source order, with `*iterable` represented as
+    /// `Expr::Starred`. Keyword arguments and `**mapping` unpackings also keep their
+    /// source order so evaluation order and duplicate-keyword errors match CPython.
+    Unpack {
+        args: Vec<ExprLoc>,
+        kwargs: Vec<KwargExpr>,
     },
 }
 
 impl ArgExprs {
-    /// Creates a new `ArgExprs` with optional `*args` and `**kwargs` unpacking expressions.
+    /// Creates a new `ArgExprs` from parsed positional and keyword arguments.
     ///
-    /// This is used when parsing function calls that may include `*expr` / `**expr`
-    /// syntax for unpacking iterables or mappings into arguments.
-    pub fn new_with_var_kwargs(
-        args: Vec<ExprLoc>,
-        var_args: Option<ExprLoc>,
-        kwargs: Vec<Kwarg>,
-        var_kwargs: Option<ExprLoc>,
-    ) -> Self {
-        // Full generality requires ArgsKargs when we have unpacking or mixed arg/kwarg usage
-        if var_args.is_some() || var_kwargs.is_some() || (!kwargs.is_empty() && !args.is_empty()) {
-            Self::ArgsKargs {
-                args: if args.is_empty() { None } else { Some(args) },
-                var_args,
-                kwargs: if kwargs.is_empty() { None } else { Some(kwargs) },
-                var_kwargs,
-            }
+    /// Positional arguments may contain `Expr::Starred` for `*expr` unpacking, and
+    /// keyword arguments may contain `**expr` unpacking; either forces `Unpack`.
+    pub fn new(args: Vec<ExprLoc>, kwargs: Vec<KwargExpr>) -> Self {
+        let has_unpacking = args.iter().any(|arg| matches!(arg.expr, Expr::Starred(_)))
+            || kwargs.iter().any(|kwarg| matches!(kwarg, KwargExpr::Unpack(_)));
+        if has_unpacking {
+            return Self::Unpack { args, kwargs };
+        }
+        let kwargs: Vec<Kwarg> = kwargs
+            .into_iter()
+            .map(|kwarg| match kwarg {
+                KwargExpr::Named(kwarg) => kwarg,
+                KwargExpr::Unpack(_) => unreachable!("unpacking handled above"),
+            })
+            .collect();
+        // Mixed positional and keyword arguments require ArgsKargs
+        if !kwargs.is_empty() && !args.is_empty() {
+            Self::ArgsKargs { args, kwargs }
         } else if !kwargs.is_empty() {
             Self::Kwargs(kwargs)
         } else if args.len() > 2 {
@@ -585,44 +740,26 @@ impl ArgExprs {
             Self::Kwargs(kwargs) => Self::Kwargs(
                 kwargs
                     .into_iter()
-                    .map(|kwarg| {
-                        Ok(Kwarg {
-                            key: kwarg.key,
-                            value: f(kwarg.value)?,
-                        })
-                    })
+                    .map(|kwarg| kwarg.prepare(&mut f))
                     .collect::<Result<Vec<_>, ParseError>>()?,
             ),
-            Self::ArgsKargs {
-                args,
-                var_args,
-                kwargs,
-                var_kwargs,
-            } => {
-                let args = args
-                    .map(|a| a.into_iter().map(&mut f).collect::<Result<Vec<_>, ParseError>>())
-                    .transpose()?;
-                let var_args = var_args.map(&mut f).transpose()?;
-                let kwargs = kwargs
-                    .map(|k| {
-                        k.into_iter()
-                            .map(|kwarg| {
-                                Ok(Kwarg {
-                                    key: kwarg.key,
-                                    value: f(kwarg.value)?,
-                                })
-                            })
-                            .collect::<Result<Vec<_>, ParseError>>()
+            Self::ArgsKargs { args, kwargs } => Self::ArgsKargs {
+                args: args.into_iter().map(&mut f).collect::<Result<Vec<_>, _>>()?,
+                kwargs: kwargs
+                    .into_iter()
+                    .map(|kwarg| kwarg.prepare(&mut f))
+                    .collect::<Result<Vec<_>, ParseError>>()?,
+            },
+            Self::Unpack { args, kwargs } => Self::Unpack {
+                args: args.into_iter().map(&mut f).collect::<Result<Vec<_>, _>>()?,
+                kwargs: kwargs
+                    .into_iter()
+                    .map(|kwarg| match kwarg {
+                        KwargExpr::Named(kwarg) => Ok(KwargExpr::Named(kwarg.prepare(&mut f)?)),
+                        KwargExpr::Unpack(mapping) => Ok(KwargExpr::Unpack(f(mapping)?)),
                     })
-                    .transpose()?;
-                let var_kwargs = var_kwargs.map(&mut f).transpose()?;
-                Self::ArgsKargs {
-                    args,
-                    var_args,
-                    kwargs,
-                    var_kwargs,
-                }
-            }
+                    .collect::<Result<Vec<_>, ParseError>>()?,
+            },
         };
         Ok(())
     }
@@ -158,6 +158,9 @@ pub(crate) struct GatherFuture {
     /// CallIds of external futures we're waiting on.
     /// Used to check if all external futures have resolved.
     pub pending_calls: Vec<CallId>,
+    /// Whether exceptions raised by items become their results (`return_exceptions=True`)
+    /// instead of failing the gather.
+    pub return_exceptions: bool,
 }
 
 impl GatherFuture {
@@ -165,7 +168,8 @@ impl GatherFuture {
     ///
     /// # Arguments
     /// * `items` - Coroutines or external futures to run concurrently
-    pub fn new(items: Vec<GatherItem>) -> Self {
+    /// * `return_exceptions` - Whether exceptions are returned as results rather than raised
+    pub fn new(items: Vec<GatherItem>, return_exceptions: bool) -> Self {
         let count = items.len();
         Self {
             items,
@@ -173,6 +177,7 @@ impl GatherFuture {
             results: (0..count).map(|_| None).collect(),
             waiter: None,
             pending_calls: Vec::new(),
+            return_exceptions,
         }
     }
 
@@ -15,7 +15,8 @@ use crate::{
 
 /// Implementation of the abs() builtin function.
 ///
-/// Returns the absolute value of a number. Works with integers, floats, and LongInts.
+/// Returns the absolute value of a number. Works with integers, floats, LongInts and
+/// complex numbers (whose absolute value is their magnitude, as a float).
 /// For `i64::MIN`, which overflows on negation, promotes to LongInt.
 pub fn builtin_abs(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
     let value = args.get_one_arg("abs", heap)?;
@@ -34,17 +35,16 @@ pub fn builtin_abs(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> Ru
         }
         Value::Float(f) => Ok(Value::Float(f.abs())),
         Value::Bool(b) => Ok(Value::Int(i64::from(*b))),
-        Value::Ref(id) => {
-            if let HeapData::LongInt(li) = heap.get(*id) {
-                Ok(li.abs().into_value(heap)?)
-            } else {
-                Err(SimpleException::new_msg(
-                    ExcType::TypeError,
-                    format!("bad operand type for abs(): '{}'", value.py_type(heap)),
-                )
-                .into())
-            }
-        }
+        Value::Ref(id) => match heap.get(*id) {
+            HeapData::LongInt(li) => Ok(li.abs().into_value(heap)?),
+            HeapData::Complex(c) => Ok(Value::Float(c.abs()?)),
+            HeapData::TimeDelta(td) => Ok(td.abs()?.into_value(heap)?),
+            _ => Err(SimpleException::new_msg(
+                ExcType::TypeError,
+                format!("bad operand type for abs(): '{}'", value.py_type(heap)),
+            )
+            .into()),
+        },
         _ => Err(SimpleException::new_msg(
             ExcType::TypeError,
             format!("bad operand type for abs(): '{}'", value.py_type(heap)),
@@ -0,0 +1,25 @@
+//! Implementation of the ascii() builtin function.
+
+use crate::{
+    args::ArgValues,
+    defer_drop,
+    exception_private::RunResult,
+    fstring::ascii_escape,
+    heap::{Heap, HeapData},
+    intern::Interns,
+    resource::{DepthGuard, ResourceTracker},
+    types::PyTrait,
+    value::Value,
+};
+
+/// Implementation of the ascii() builtin function.
+///
+/// Like `repr()`, but escapes non-ASCII characters with `\x`, `\u` or `\U` escapes.
+pub fn builtin_ascii(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let value = args.get_one_arg("ascii", heap)?;
+    defer_drop!(value, heap);
+    let mut guard = DepthGuard::default();
+    let escaped = ascii_escape(&value.py_repr(heap, &mut guard, interns));
+    let heap_id = heap.allocate(HeapData::Str(escaped.into()))?;
+    Ok(Value::Ref(heap_id))
+}
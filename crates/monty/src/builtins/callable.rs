@@ -0,0 +1,35 @@
+//! Implementation of the callable() builtin function.
+
+use crate::{
+    args::ArgValues,
+    exception_private::RunResult,
+    heap::{Heap, HeapData},
+    resource::ResourceTracker,
+    value::Value,
+};
+
+/// Implementation of the callable() builtin function.
+///
+/// Returns whether the argument can be called.
+pub fn builtin_callable(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    let value = args.get_one_arg("callable", heap)?;
+    let result = is_callable(&value, heap);
+    value.drop_with_heap(heap);
+    Ok(Value::Bool(result))
+}
+
+/// Returns whether `value` is a function, class or other object that can be called.
+pub(crate) fn is_callable(value: &Value, heap: &Heap<impl ResourceTracker>) -> bool {
+    match value {
+        Value::Builtin(_) | Value::ModuleFunction(_) | Value::DefFunction(_) | Value::ExtFunction(_) => true,
+        Value::Ref(id) => matches!(
+            heap.get(*id),
+            HeapData::Closure(_, _, _)
+                | HeapData::FunctionDefaults(_, _)
+                | HeapData::ClassObject(_)
+                | HeapData::BoundMethod(_)
+                | HeapData::NamedTupleFactory(_)
+        ),
+        _ => false,
+    }
+}
@@ -0,0 +1,89 @@
+//! Implementation of the compile() builtin function.
+
+use crate::{
+    args::{ArgValues, BoundArgs, bind_args},
+    defer_drop,
+    eval::EvalMode,
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{Heap, HeapData},
+    intern::{InternerBuilder, Interns},
+    parse::parse_eval,
+    resource::ResourceTracker,
+    types::{CodeObject, PyTrait},
+    value::Value,
+};
+
+/// Implementation of the compile() builtin function.
+///
+/// Takes the source, filename and mode (`'exec'`, `'eval'` or `'single'`), and returns a code
+/// object to pass to `eval()` or `exec()`. The source is parsed straight away so syntax errors
+/// are raised by `compile()`, but it's compiled to bytecode when it runs. The optional `flags`,
+/// `dont_inherit` and `optimize` arguments are accepted, but only `flags=0` and the default
+/// optimization level (`-1` or `0`) are supported; `dont_inherit` has no effect since there
+/// are no future statements to inherit.
+pub fn builtin_compile(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let params = ["source", "filename", "mode", "flags", "dont_inherit", "optimize"];
+    let BoundArgs([source, filename, mode, flags, dont_inherit, optimize]) =
+        bind_args("compile", params, 3, args, heap, interns)?;
+    let source = source.expect("source is required");
+    let filename = filename.expect("filename is required");
+    let mode = mode.expect("mode is required");
+    defer_drop!(source, heap);
+    defer_drop!(filename, heap);
+    defer_drop!(mode, heap);
+    defer_drop!(flags, heap);
+    defer_drop!(dont_inherit, heap);
+    defer_drop!(optimize, heap);
+
+    if let Some(flags) = flags
+        && flags.as_int(heap)? != 0
+    {
+        return Err(ExcType::not_implemented("compile() flags are not supported").into());
+    }
+    if let Some(optimize) = optimize {
+        match optimize.as_int(heap)? {
+            -1 | 0 => {}
+            1 | 2 => return Err(ExcType::not_implemented("compile() optimization levels are not supported").into()),
+            _ => {
+                return Err(SimpleException::new_msg(ExcType::ValueError, "compile(): invalid optimize value").into());
+            }
+        }
+    }
+
+    let Some(source) = source.as_either_str(heap) else {
+        return Err(ExcType::type_error(
+            "compile() arg 1 must be a string, bytes or AST object",
+        ));
+    };
+    let Some(filename) = filename.as_either_str(heap) else {
+        return Err(ExcType::type_error(format!(
+            "compile() argument 'filename' must be str, not {}",
+            filename.py_type(heap)
+        )));
+    };
+    let Some(mode) = mode.as_either_str(heap) else {
+        return Err(ExcType::type_error(format!(
+            "compile() argument 'mode' must be str, not {}",
+            mode.py_type(heap)
+        )));
+    };
+    let mode = match mode.as_str(interns) {
+        "eval" => EvalMode::Eval,
+        "exec" | "single" => EvalMode::Exec,
+        _ => {
+            return Err(SimpleException::new_msg(
+                ExcType::ValueError,
+                "compile() mode must be 'exec', 'eval' or 'single'",
+            )
+            .into());
+        }
+    };
+
+    let source = source.as_str(interns).to_owned();
+    let filename = filename.as_str(interns).to_owned();
+    parse_eval(&source, &filename, mode, InternerBuilder::new(&source))
+        .map_err(|e| e.into_python_exc(&filename, &source))?;
+
+    let code_id = heap.allocate(HeapData::CodeObject(CodeObject::new(source, filename, mode)))?;
+    Ok(Value::Ref(code_id))
+}
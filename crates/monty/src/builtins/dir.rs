@@ -0,0 +1,87 @@
+//! Implementation of the dir() builtin function.
+
+use crate::{
+    args::ArgValues,
+    defer_drop,
+    exception_private::RunResult,
+    heap::{Heap, HeapData, HeapId},
+    intern::Interns,
+    resource::ResourceTracker,
+    types::{Dict, List, str::allocate_string},
+    value::Value,
+};
+
+/// Implementation of the dir() builtin function.
+///
+/// Returns a sorted list of the attribute names of the argument: the attributes of
+/// user-defined instances (including those of their class and its bases), classes,
+/// modules and dataclasses, and the fields of namedtuples. Other values list no names.
+///
+/// `dir()` without an argument lists the caller's local names, so is handled by the VM.
+pub fn builtin_dir(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let value = args.get_one_arg("dir", heap)?;
+    defer_drop!(value, heap);
+
+    let mut names = Vec::new();
+    if let Value::Ref(id) = value {
+        match heap.get(*id) {
+            HeapData::Instance(inst) => {
+                push_dict_keys(inst.attrs(), &mut names, heap, interns);
+                push_class_attrs(inst.class_id(), &mut names, heap, interns);
+            }
+            HeapData::ClassObject(_) => push_class_attrs(*id, &mut names, heap, interns),
+            HeapData::Module(module) => push_dict_keys(module.attrs(), &mut names, heap, interns),
+            HeapData::Dataclass(dc) => {
+                push_dict_keys(dc.attrs(), &mut names, heap, interns);
+                names.extend(dc.methods().iter().cloned());
+            }
+            HeapData::NamedTuple(nt) => {
+                names.extend(nt.field_names().iter().map(|name| name.as_str(interns).to_owned()));
+                names.extend(["count".to_owned(), "index".to_owned()]);
+            }
+            _ => {}
+        }
+    }
+    names.sort();
+    names.dedup();
+    names_to_list(names, heap)
+}
+
+/// Allocates a list of the given attribute names.
+pub(crate) fn names_to_list(names: Vec<String>, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    let mut items = Vec::with_capacity(names.len());
+    for name in names {
+        match allocate_string(name, heap) {
+            Ok(item) => items.push(item),
+            Err(e) => {
+                for item in items {
+                    item.drop_with_heap(heap);
+                }
+                return Err(e);
+            }
+        }
+    }
+    let heap_id = heap.allocate(HeapData::List(List::new(items)))?;
+    Ok(Value::Ref(heap_id))
+}
+
+/// Adds the attribute names of a class and its bases.
+fn push_class_attrs(class_id: HeapId, names: &mut Vec<String>, heap: &Heap<impl ResourceTracker>, interns: &Interns) {
+    let mut current = Some(class_id);
+    while let Some(id) = current {
+        let HeapData::ClassObject(class) = heap.get(id) else {
+            return;
+        };
+        push_dict_keys(class.attrs(), names, heap, interns);
+        current = class.base();
+    }
+}
+
+/// Adds the string keys of an attribute dict.
+fn push_dict_keys(attrs: &Dict, names: &mut Vec<String>, heap: &Heap<impl ResourceTracker>, interns: &Interns) {
+    for (key, _) in attrs {
+        if let Some(name) = key.as_either_str(heap) {
+            names.push(name.as_str(interns).to_owned());
+        }
+    }
+}
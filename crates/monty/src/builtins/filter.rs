@@ -0,0 +1,30 @@
+//! Implementation of the filter() builtin function.
+
+use smallvec::smallvec;
+
+use crate::{
+    args::ArgValues,
+    exception_private::RunResult,
+    heap::Heap,
+    intern::Interns,
+    resource::ResourceTracker,
+    types::{MontyIter, iter::CallingIter},
+    value::Value,
+};
+
+/// Implementation of the filter() builtin function.
+///
+/// Returns an iterator over the items of the iterable for which the function returns
+/// a truthy value, or over the truthy items themselves if the function is `None`.
+/// The VM makes the calls as the iterator is advanced.
+pub fn builtin_filter(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let (func, iterable) = args.get_two_args("filter", heap)?;
+    let iter = match MontyIter::iterator_for(iterable, heap, interns) {
+        Ok(iter) => iter,
+        Err(e) => {
+            func.drop_with_heap(heap);
+            return Err(e);
+        }
+    };
+    MontyIter::new_calling(CallingIter::Filter, smallvec![func, iter], heap)
+}
@@ -0,0 +1,45 @@
+//! Implementation of the format() builtin function.
+
+use crate::{
+    args::ArgValues,
+    defer_drop,
+    exception_private::{ExcType, RunResult},
+    fstring::format_with_spec_str,
+    heap::Heap,
+    intern::Interns,
+    resource::{DepthGuard, ResourceTracker},
+    types::{PyTrait, str::allocate_string},
+    value::Value,
+};
+
+/// Implementation of the format() builtin function.
+///
+/// Formats a value with the format spec mini-language, as f-strings do. Instances of
+/// user-defined classes are formatted by the VM, which can call their `__format__` method.
+pub fn builtin_format(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let (value, spec) = args.get_one_two_args("format", heap)?;
+    defer_drop!(value, heap);
+    let spec = format_spec_arg(spec, heap, interns)?;
+    let mut guard = DepthGuard::default();
+    let formatted = format_with_spec_str(value, &spec, heap, &mut guard, interns)?;
+    allocate_string(formatted, heap)
+}
+
+/// Returns the format spec argument of format(), which defaults to an empty string.
+pub(crate) fn format_spec_arg(
+    spec: Option<Value>,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<String> {
+    let Some(spec) = spec else {
+        return Ok(String::new());
+    };
+    defer_drop!(spec, heap);
+    match spec.as_either_str(heap) {
+        Some(spec) => Ok(spec.as_str(interns).to_owned()),
+        None => Err(ExcType::type_error(format!(
+            "format() argument 2 must be str, not {}",
+            spec.py_type(heap)
+        ))),
+    }
+}
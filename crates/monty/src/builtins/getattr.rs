@@ -0,0 +1,36 @@
+//! Shared argument handling for the getattr(), setattr() and hasattr() builtin functions.
+//!
+//! `getattr()` itself is called by the VM, since looking up an attribute can need the host
+//! (e.g. for `Path` properties), see `VM::call_getattr`. So is `setattr()`, since a name that
+//! isn't interned yet must be, see `VM::call_setattr`.
+
+use crate::{
+    exception_private::{ExcType, RunResult},
+    heap::Heap,
+    intern::Interns,
+    resource::ResourceTracker,
+    types::PyTrait,
+    value::{EitherStr, Value},
+};
+
+/// Converts the attribute name argument of `getattr()`, `setattr()` or `hasattr()`.
+///
+/// Returns `EitherStr::Interned` if the name is interned. A name that isn't can't be an
+/// attribute of any value yet, since `setattr()` interns the names it sets, so is returned
+/// as `EitherStr::Heap`.
+pub(crate) fn attr_name_arg(
+    name: &Value,
+    heap: &Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<EitherStr> {
+    match name.as_either_str(heap) {
+        Some(EitherStr::Heap(name)) => Ok(interns
+            .find_str(&name)
+            .map_or(EitherStr::Heap(name), EitherStr::Interned)),
+        Some(name) => Ok(name),
+        None => Err(ExcType::type_error(format!(
+            "attribute name must be string, not '{}'",
+            name.py_type(heap)
+        ))),
+    }
+}
@@ -0,0 +1,39 @@
+//! Implementation of the hasattr() builtin function.
+
+use super::getattr::attr_name_arg;
+use crate::{
+    args::ArgValues,
+    defer_drop,
+    exception_private::{ExcType, RunError, RunResult},
+    heap::Heap,
+    intern::Interns,
+    resource::ResourceTracker,
+    types::AttrCallResult,
+    value::{EitherStr, Value},
+};
+
+/// Implementation of the hasattr() builtin function.
+///
+/// Returns whether looking up the attribute succeeds, i.e. doesn't raise `AttributeError`.
+pub fn builtin_hasattr(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let (obj, name) = args.get_two_args("hasattr", heap)?;
+    defer_drop!(obj, heap);
+    defer_drop!(name, heap);
+
+    let EitherStr::Interned(name_id) = attr_name_arg(name, heap, interns)? else {
+        return Ok(Value::Bool(false));
+    };
+    match obj.py_getattr(name_id, heap, interns) {
+        Ok(AttrCallResult::Value(value)) => {
+            value.drop_with_heap(heap);
+            Ok(Value::Bool(true))
+        }
+        // The attribute exists, but is computed by the host, so isn't looked up here
+        Ok(AttrCallResult::OsCall(_, args) | AttrCallResult::ExternalCall(_, args)) => {
+            args.drop_with_heap(heap);
+            Ok(Value::Bool(true))
+        }
+        Err(RunError::Exc(exc)) if exc.exc.exc_type() == ExcType::AttributeError => Ok(Value::Bool(false)),
+        Err(e) => Err(e),
+    }
+}
@@ -1,13 +1,13 @@
-//! Implementation of the isinstance() builtin function.
+//! Implementation of the isinstance() and issubclass() builtin functions.
 
 use super::Builtins;
 use crate::{
     args::ArgValues,
     defer_drop,
-    exception_private::{ExcType, RunResult},
-    heap::{Heap, HeapData},
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{Heap, HeapData, HeapId},
     resource::ResourceTracker,
-    types::{PyTrait, Type},
+    types::{PyTrait, Type, class::is_subclass},
     value::Value,
 };
 
@@ -20,14 +20,50 @@ pub fn builtin_isinstance(heap: &mut Heap<impl ResourceTracker>, args: ArgValues
     defer_drop!(classinfo, heap);
 
     let obj_type = obj.py_type(heap);
+    let obj_id = match obj {
+        Value::Ref(id) => Some(*id),
+        _ => None,
+    };
+    let obj_class = obj_id.and_then(|id| match heap.get(id) {
+        HeapData::Instance(instance) => Some(instance.class_id()),
+        _ => None,
+    });
 
-    match isinstance_check(obj_type, classinfo, heap) {
+    match isinstance_check(obj_type, obj_class, obj_id, classinfo, heap) {
         Ok(result) => Ok(Value::Bool(result)),
         Err(()) => Err(ExcType::isinstance_arg2_error()),
     }
 }
 
-/// Recursively checks if obj_type matches classinfo for isinstance().
+/// Implementation of the issubclass() builtin function.
+///
+/// Checks if a class is a subclass of a class or a tuple of classes.
+pub fn builtin_issubclass(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    let (cls, classinfo) = args.get_two_args("issubclass", heap)?;
+    defer_drop!(cls, heap);
+    defer_drop!(classinfo, heap);
+
+    // A class is checked like an instance of it, so its type and user-defined class
+    let (cls_type, cls_class) = match cls {
+        Value::Builtin(Builtins::Type(t)) => (*t, None),
+        Value::Builtin(Builtins::ExcType(exc_type)) => (Type::Exception(*exc_type), None),
+        Value::Ref(id) if matches!(heap.get(*id), HeapData::ClassObject(_)) => (Type::Object, Some(*id)),
+        _ => {
+            return Err(SimpleException::new_msg(ExcType::TypeError, "issubclass() arg 1 must be a class").into());
+        }
+    };
+
+    match isinstance_check(cls_type, cls_class, None, classinfo, heap) {
+        Ok(result) => Ok(Value::Bool(result)),
+        Err(()) => Err(SimpleException::new_msg(
+            ExcType::TypeError,
+            "issubclass() arg 2 must be a class, a tuple of classes, or a union",
+        )
+        .into()),
+    }
+}
+
+/// Recursively checks if obj_type matches classinfo for isinstance() and issubclass().
 ///
 /// Returns `Ok(true)` if the type matches, `Ok(false)` if it doesn't,
 /// or `Err(())` if classinfo is invalid (not a type or tuple of types).
@@ -37,7 +73,19 @@ pub fn builtin_isinstance(heap: &mut Heap<impl ResourceTracker>, args: ArgValues
 /// - Exception types: `isinstance(err, ValueError)`
 /// - Exception hierarchy: `isinstance(err, LookupError)` for KeyError/IndexError
 /// - Nested tuples: `isinstance(x, (int, (str, bytes)))`
-fn isinstance_check(obj_type: Type, classinfo: &Value, heap: &Heap<impl ResourceTracker>) -> Result<bool, ()> {
+/// - User-defined classes, including subclasses: `isinstance(dog, Animal)`
+/// - Dataclass and namedtuple types: `isinstance(point, Point)`
+///
+/// `obj_class` is the class of `obj` when it is an instance of a user-defined class. `obj_id` is
+/// `obj` itself when it's on the heap, for `isinstance()` to match dataclass and namedtuple
+/// instances against their type.
+fn isinstance_check(
+    obj_type: Type,
+    obj_class: Option<HeapId>,
+    obj_id: Option<HeapId>,
+    classinfo: &Value,
+    heap: &Heap<impl ResourceTracker>,
+) -> Result<bool, ()> {
     match classinfo {
         // Single type: isinstance(x, int)
         Value::Builtin(Builtins::Type(t)) => Ok(obj_type.is_instance_of(*t)),
@@ -48,19 +96,26 @@ fn isinstance_check(obj_type: Type, classinfo: &Value, heap: &Heap<impl Resource
             Ok(matches!(obj_type, Type::Exception(exc_type) if exc_type.is_subclass_of(*handler_type)))
         }
 
-        // Tuple of types (possibly nested): isinstance(x, (int, (str, bytes)))
-        Value::Ref(id) => {
-            if let HeapData::Tuple(tuple) = heap.get(*id) {
+        Value::Ref(id) => match heap.get(*id) {
+            // User-defined class: isinstance(x, MyClass)
+            HeapData::ClassObject(_) => Ok(obj_class.is_some_and(|class_id| is_subclass(class_id, *id, heap))),
+            // Dataclass type: isinstance(point, type(point))
+            HeapData::DataclassType(class) => Ok(obj_id
+                .is_some_and(|obj_id| matches!(heap.get(obj_id), HeapData::Dataclass(dc) if class.is_class_of(dc)))),
+            // Namedtuple type: isinstance(p, Point) where Point = namedtuple('Point', 'x y')
+            HeapData::NamedTupleFactory(factory) => Ok(obj_id
+                .is_some_and(|obj_id| matches!(heap.get(obj_id), HeapData::NamedTuple(nt) if factory.is_class_of(nt)))),
+            // Tuple of types (possibly nested): isinstance(x, (int, (str, bytes)))
+            HeapData::Tuple(tuple) => {
                 for v in tuple.as_slice() {
-                    if isinstance_check(obj_type, v, heap)? {
+                    if isinstance_check(obj_type, obj_class, obj_id, v, heap)? {
                         return Ok(true);
                     }
                 }
                 Ok(false)
-            } else {
-                Err(()) // Not a tuple - invalid
             }
-        }
+            _ => Err(()), // Not a class or tuple - invalid
+        },
         _ => Err(()), // Invalid classinfo
     }
 }
@@ -0,0 +1,40 @@
+//! Implementation of the map() builtin function.
+
+use crate::{
+    args::ArgValues,
+    exception_private::{ExcType, RunResult},
+    heap::{DropWithHeap, Heap},
+    intern::Interns,
+    resource::ResourceTracker,
+    types::{MontyIter, iter::CallingIter, tuple::TupleVec},
+    value::Value,
+};
+
+/// Implementation of the map() builtin function.
+///
+/// Returns an iterator that calls the function with an item from each iterable,
+/// stopping when the shortest iterable is exhausted. The VM makes the calls as the
+/// iterator is advanced.
+pub fn builtin_map(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let mut positional = args.into_pos_only("map", heap)?;
+    if positional.len() < 2 {
+        positional.drop_with_heap(heap);
+        return Err(ExcType::type_error("map() must have at least two arguments."));
+    }
+
+    let mut parts = TupleVec::with_capacity(positional.len());
+    parts.extend(positional.next());
+    while let Some(iterable) = positional.next() {
+        match MontyIter::iterator_for(iterable, heap, interns) {
+            Ok(iter) => parts.push(iter),
+            Err(e) => {
+                for part in parts {
+                    part.drop_with_heap(heap);
+                }
+                positional.drop_with_heap(heap);
+                return Err(e);
+            }
+        }
+    }
+    MontyIter::new_calling(CallingIter::Map, parts, heap)
+}
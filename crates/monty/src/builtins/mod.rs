@@ -6,15 +6,24 @@
 mod abs;
 mod all;
 mod any;
+mod ascii;
 mod bin;
+mod callable;
 mod chr;
+mod compile;
+mod dir;
 mod divmod;
 mod enumerate;
+mod filter;
+mod format;
+mod getattr;
+mod hasattr;
 mod hash;
 mod hex;
 mod id;
-mod isinstance;
+mod isinstance; // isinstance and issubclass share implementation
 mod len;
+mod map;
 mod min_max; // min and max share implementation
 mod next;
 mod oct;
@@ -26,16 +35,22 @@ mod reversed;
 mod round;
 mod sorted;
 mod sum;
+mod super_;
 mod type_;
+mod vars;
 mod zip;
 
+pub(crate) use callable::is_callable;
+pub(crate) use dir::names_to_list;
+pub(crate) use format::format_spec_arg;
+pub(crate) use getattr::attr_name_arg;
 use std::{fmt::Write, str::FromStr};
 
 use strum::{Display, EnumString, FromRepr, IntoStaticStr};
 
 use crate::{
     args::ArgValues,
-    exception_private::{ExcType, RunResult},
+    exception_private::{ExcType, RunError, RunResult},
     heap::Heap,
     intern::Interns,
     io::PrintWriter,
@@ -147,31 +162,31 @@ pub enum BuiltinsFunctions {
     All,
     // Anext,
     Any,
-    // Ascii,
+    Ascii,
     Bin,
     // bool - handled by Type enum
     // Breakpoint,
     // bytearray - handled by Type enum
     // bytes - handled by Type enum
-    // Callable,
+    Callable,
     Chr,
     // Classmethod,
-    // Compile,
+    Compile,
     // complex - handled by Type enum
     // Delattr,
     // dict - handled by Type enum
-    // Dir,
+    Dir,
     Divmod,
     Enumerate,
-    // Eval,
-    // Exec,
-    // Filter,
+    Eval,
+    Exec,
+    Filter,
     // float - handled by Type enum
-    // Format,
+    Format,
     // frozenset - handled by Type enum
-    // Getattr,
+    Getattr,
     // Globals,
-    // Hasattr,
+    Hasattr,
     Hash,
     // Help,
     Hex,
@@ -179,12 +194,12 @@ pub enum BuiltinsFunctions {
     // Input,
     // int - handled by Type enum
     Isinstance,
-    // Issubclass,
+    Issubclass,
     // Iter - handled by Type enum
     Len,
     // list - handled by Type enum
     // Locals,
-    // Map,
+    Map,
     Max,
     // memoryview - handled by Type enum
     Min,
@@ -201,16 +216,16 @@ pub enum BuiltinsFunctions {
     Reversed,
     Round,
     // set - handled by Type enum
-    // Setattr,
+    Setattr,
     // Slice,
     Sorted,
     // Staticmethod,
     // str - handled by Type enum
     Sum,
-    // Super,
+    Super,
     // tuple - handled by Type enum
     Type,
-    // Vars,
+    Vars,
     Zip,
     // __import__ - not planned
 }
@@ -231,15 +246,34 @@ impl BuiltinsFunctions {
             Self::Abs => abs::builtin_abs(heap, args),
             Self::All => all::builtin_all(heap, args, interns),
             Self::Any => any::builtin_any(heap, args, interns),
+            Self::Ascii => ascii::builtin_ascii(heap, args, interns),
             Self::Bin => bin::builtin_bin(heap, args),
+            Self::Callable => callable::builtin_callable(heap, args),
             Self::Chr => chr::builtin_chr(heap, args),
+            Self::Compile => compile::builtin_compile(heap, args, interns),
+            Self::Dir => dir::builtin_dir(heap, args, interns),
             Self::Divmod => divmod::builtin_divmod(heap, args),
             Self::Enumerate => enumerate::builtin_enumerate(heap, args, interns),
+            // Run by the VM, which compiles the code into the program's interns and calls it
+            Self::Eval | Self::Exec => {
+                args.drop_with_heap(heap);
+                Err(RunError::internal("eval() and exec() must be called by the VM"))
+            }
+            Self::Filter => filter::builtin_filter(heap, args, interns),
+            Self::Format => format::builtin_format(heap, args, interns),
+            // Run by the VM, since the attribute may be computed by the host
+            Self::Getattr => {
+                args.drop_with_heap(heap);
+                Err(RunError::internal("getattr() must be called by the VM"))
+            }
+            Self::Hasattr => hasattr::builtin_hasattr(heap, args, interns),
             Self::Hash => hash::builtin_hash(heap, args, interns),
             Self::Hex => hex::builtin_hex(heap, args),
             Self::Id => id::builtin_id(heap, args),
             Self::Isinstance => isinstance::builtin_isinstance(heap, args),
+            Self::Issubclass => isinstance::builtin_issubclass(heap, args),
             Self::Len => len::builtin_len(heap, args, interns),
+            Self::Map => map::builtin_map(heap, args, interns),
             Self::Max => min_max::builtin_max(heap, args, interns),
             Self::Min => min_max::builtin_min(heap, args, interns),
             Self::Next => next::builtin_next(heap, args, interns),
@@ -250,9 +284,16 @@ impl BuiltinsFunctions {
             Self::Repr => repr::builtin_repr(heap, args, interns),
             Self::Reversed => reversed::builtin_reversed(heap, args, interns),
             Self::Round => round::builtin_round(heap, args),
+            // Run by the VM, since a new attribute name must be interned
+            Self::Setattr => {
+                args.drop_with_heap(heap);
+                Err(RunError::internal("setattr() must be called by the VM"))
+            }
             Self::Sorted => sorted::builtin_sorted(heap, args, interns),
             Self::Sum => sum::builtin_sum(heap, args, interns),
+            Self::Super => super_::builtin_super(heap, args),
             Self::Type => type_::builtin_type(heap, args),
+            Self::Vars => vars::builtin_vars(heap, args, interns),
             Self::Zip => zip::builtin_zip(heap, args, interns),
         }
     }
@@ -64,7 +64,7 @@ pub fn builtin_sum(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, inter
             Err(e) => {
                 accumulator.drop_with_heap(heap);
                 iter.drop_with_heap(heap);
-                return Err(e.into());
+                return Err(e);
             }
         }
     }
@@ -0,0 +1,50 @@
+//! Implementation of the super() builtin function.
+
+use crate::{
+    args::ArgValues,
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{Heap, HeapData},
+    resource::ResourceTracker,
+    types::{PyTrait, SuperProxy},
+    value::Value,
+};
+
+/// Implementation of the super() builtin function.
+///
+/// Only the explicit two-argument form `super(cls, obj)` exists at runtime: the parser
+/// rewrites zero-argument `super()` inside methods to pass the enclosing class and the
+/// method's first parameter.
+pub fn builtin_super(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    if matches!(args, ArgValues::Empty) {
+        return Err(SimpleException::new_msg(ExcType::RuntimeError, "super(): no arguments").into());
+    }
+    let (cls, obj) = args.get_two_args("super", heap)?;
+
+    // mut needed for dec_ref_forget when ref-count-panic feature is enabled
+    #[cfg_attr(not(feature = "ref-count-panic"), expect(unused_mut))]
+    let mut cls = cls;
+    let class_id = match cls {
+        Value::Ref(id) if matches!(heap.get(id), HeapData::ClassObject(_)) => id,
+        _ => {
+            let type_name = cls.py_type(heap);
+            cls.drop_with_heap(heap);
+            obj.drop_with_heap(heap);
+            return Err(ExcType::type_error(format!(
+                "super() argument 1 must be a type, not {type_name}"
+            )));
+        }
+    };
+
+    // The proxy takes over both references
+    match heap.allocate(HeapData::SuperProxy(SuperProxy::new(class_id, obj))) {
+        Ok(proxy_id) => {
+            #[cfg(feature = "ref-count-panic")]
+            cls.dec_ref_forget();
+            Ok(Value::Ref(proxy_id))
+        }
+        Err(e) => {
+            cls.drop_with_heap(heap);
+            Err(e.into())
+        }
+    }
+}
@@ -2,15 +2,35 @@
 
 use super::Builtins;
 use crate::{
-    args::ArgValues, defer_drop, exception_private::RunResult, heap::Heap, resource::ResourceTracker, types::PyTrait,
+    args::ArgValues,
+    defer_drop,
+    exception_private::RunResult,
+    heap::{Heap, HeapData},
+    resource::ResourceTracker,
+    types::PyTrait,
     value::Value,
 };
 
 /// Implementation of the type() builtin function.
 ///
-/// Returns the type of an object.
+/// Returns the type of an object. For instances of user-defined classes this is the
+/// class object itself, and dataclass instances get their specific dataclass class.
 pub fn builtin_type(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
     let value = args.get_one_arg("type", heap)?;
     defer_drop!(value, heap);
+    if let Value::Ref(id) = value {
+        match heap.get(*id) {
+            HeapData::Instance(instance) => {
+                let class_id = instance.class_id();
+                heap.inc_ref(class_id);
+                return Ok(Value::Ref(class_id));
+            }
+            HeapData::Dataclass(dc) => {
+                let class = dc.class();
+                return Ok(Value::Ref(heap.allocate(HeapData::DataclassType(class))?));
+            }
+            _ => {}
+        }
+    }
     Ok(Value::Builtin(Builtins::Type(value.py_type(heap))))
 }
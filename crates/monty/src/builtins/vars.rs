@@ -0,0 +1,40 @@
+//! Implementation of the vars() builtin function.
+
+use crate::{
+    args::ArgValues,
+    defer_drop,
+    exception_private::{ExcType, RunResult},
+    heap::{Heap, HeapData},
+    intern::Interns,
+    resource::ResourceTracker,
+    types::Dict,
+    value::Value,
+};
+
+/// Implementation of the vars() builtin function.
+///
+/// Returns a dict of the attributes of a user-defined instance or class, module or
+/// dataclass. Unlike Python, the dict is a copy, so changing it doesn't change the object.
+///
+/// `vars()` without an argument returns the caller's local variables, so is handled by the VM.
+pub fn builtin_vars(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let value = args.get_one_arg("vars", heap)?;
+    defer_drop!(value, heap);
+
+    let pairs = match value {
+        Value::Ref(id) => heap.with_entry_mut(*id, |heap, data| match data {
+            HeapData::Instance(inst) => Some(inst.attrs().items(heap)),
+            HeapData::ClassObject(cls) => Some(cls.attrs().items(heap)),
+            HeapData::Module(module) => Some(module.attrs().items(heap)),
+            HeapData::Dataclass(dc) => Some(dc.attrs().items(heap)),
+            _ => None,
+        }),
+        _ => None,
+    };
+    let Some(pairs) = pairs else {
+        return Err(ExcType::type_error("vars() argument must have __dict__ attribute"));
+    };
+    let dict = Dict::from_pairs(pairs, heap, interns)?;
+    let heap_id = heap.allocate(HeapData::Dict(dict))?;
+    Ok(Value::Ref(heap_id))
+}
@@ -145,6 +145,7 @@ impl CodeBuilder {
     ///
     /// Used for MakeFunction: func_id (u16) + defaults_count (u8)
     /// Used for CallAttr: attr_name_id (u16) + arg_count (u8)
+    /// Used for BuildClass: name_id (u16) + has_base (u8)
     pub fn emit_u16_u8(&mut self, op: Opcode, operand1: u16, operand2: u8) {
         self.record_location();
         self.bytecode.push(op as u8);
@@ -160,6 +161,10 @@ impl CodeBuilder {
                 // pops obj + args, pushes result: 1 - (1 + arg_count) = -arg_count
                 self.adjust_stack(-i16::from(operand2));
             }
+            Opcode::BuildClass => {
+                // pops attrs dict + optional base, pushes class: 1 - (1 + has_base) = -has_base
+                self.adjust_stack(-i16::from(operand2));
+            }
             _ => {
                 if let Some(effect) = op.stack_effect() {
                     self.adjust_stack(effect);
@@ -187,6 +192,19 @@ impl CodeBuilder {
         }
     }
 
+    /// Emits an instruction with two u16 operands.
+    ///
+    /// Used for ExportLocal: slot (u16) + name_id (u16)
+    pub fn emit_u16_u16(&mut self, op: Opcode, operand1: u16, operand2: u16) {
+        self.record_location();
+        self.bytecode.push(op as u8);
+        self.bytecode.extend_from_slice(&operand1.to_le_bytes());
+        self.bytecode.extend_from_slice(&operand2.to_le_bytes());
+        if let Some(effect) = op.stack_effect() {
+            self.adjust_stack(effect);
+        }
+    }
+
     /// Emits `CallBuiltinFunction` instruction.
     ///
     /// Operands: builtin_id (u8) + arg_count (u8)
@@ -400,6 +418,15 @@ impl CodeBuilder {
         }
     }
 
+    /// Emits `DeleteLocal`, using wide variant for slots > 255.
+    pub fn emit_delete_local(&mut self, slot: u16) {
+        if let Ok(s) = u8::try_from(slot) {
+            self.emit_u8(Opcode::DeleteLocal, s);
+        } else {
+            self.emit_u16(Opcode::DeleteLocalW, slot);
+        }
+    }
+
     /// Adds a constant to the pool, returning its index.
     ///
     /// # Panics
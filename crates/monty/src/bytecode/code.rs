@@ -92,6 +92,18 @@ impl Code {
         }
     }
 
+    /// Estimates the memory used by this code object, for charging code compiled at runtime.
+    #[must_use]
+    pub fn estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.bytecode.len()
+            + self.constants.values.len() * std::mem::size_of::<Value>()
+            + self.location_table.len() * std::mem::size_of::<LocationEntry>()
+            + self.exception_table.len() * std::mem::size_of::<ExceptionEntry>()
+            + self.local_names.len() * std::mem::size_of::<StringId>()
+            + self.assigned_locals.len() * std::mem::size_of::<u16>()
+    }
+
     /// Returns the raw bytecode bytes.
     #[must_use]
     pub fn bytecode(&self) -> &[u8] {
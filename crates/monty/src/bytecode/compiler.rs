@@ -16,19 +16,23 @@ use super::{
     op::Opcode,
 };
 use crate::{
-    args::{ArgExprs, Kwarg},
+    args::{ArgExprs, KwargExpr},
     builtins::Builtins,
+    eval::EvalMode,
     exception_private::ExcType,
     exception_public::{MontyException, StackFrame},
     expressions::{
-        Callable, CmpOperator, Comprehension, Expr, ExprLoc, Identifier, Literal, NameScope, Node, Operator,
-        PreparedFunctionDef, PreparedNode, UnpackTarget,
+        Callable, ClassDef, ClassItem, CmpOperator, Comprehension, DeleteTarget, Expr, ExprLoc, Identifier, Literal,
+        NameScope, Node, Operator, PreparedFunctionDef, PreparedNode, UnpackTarget,
     },
     fstring::{ConversionFlag, FStringPart, FormatSpec, ParsedFormatSpec, encode_format_spec},
     function::Function,
-    intern::{Interns, StringId},
+    intern::{Interns, StaticStrings, StringId},
     modules::BuiltinModule,
-    parse::{CodeRange, ExceptHandler, Try},
+    namespace::NamespaceId,
+    parse::{CodeRange, ExceptHandler, ImportedModule, Match, Pattern, Try, WithItem},
+    signature::Signature,
+    types::Type,
     value::{EitherStr, Value},
 };
 
@@ -63,6 +67,12 @@ pub struct Compiler<'a> {
     /// finishes, so inner functions have lower indices.
     functions: Vec<Function>,
 
+    /// Function ID of the first function in `functions`.
+    ///
+    /// Zero for module compilation. Code compiled for `eval()`/`exec()` appends its
+    /// functions to those already in the interns, so its IDs start after them.
+    function_base: usize,
+
     /// Loop stack for break/continue handling.
     /// Each entry tracks the loop start offset and pending break jumps.
     loop_stack: Vec<LoopInfo>,
@@ -134,6 +144,20 @@ struct FinallyTarget {
     /// The loop depth when this finally was entered.
     /// Used to determine if break/continue targets a loop outside this finally.
     loop_depth_at_entry: usize,
+    /// The except handler depth when this finally was entered.
+    /// Handlers entered since then are cleaned up before jumping to the finally code.
+    except_handler_depth_at_entry: usize,
+}
+
+/// A jump taken when part of a pattern fails to match.
+///
+/// Failure can happen with intermediate values (e.g. the item being matched from a
+/// sequence) still on the stack, so each jump records the stack depth at its target.
+/// The cleanup code pops back down to the subject before trying the next case.
+struct PatternFail {
+    jump: JumpLabel,
+    /// Stack depth when the jump is taken.
+    depth: u16,
 }
 
 /// Result of module compilation: the module code and all compiled functions.
@@ -146,11 +170,12 @@ pub struct CompileResult {
 
 impl<'a> Compiler<'a> {
     /// Creates a new compiler with access to the string interner.
-    fn new(interns: &'a Interns, functions: Vec<Function>) -> Self {
+    fn new(interns: &'a Interns, functions: Vec<Function>, function_base: usize) -> Self {
         Self {
             code: CodeBuilder::new(),
             interns,
             functions,
+            function_base,
             loop_stack: Vec::new(),
             cell_base: 0,
             finally_targets: Vec::new(),
@@ -159,11 +184,17 @@ impl<'a> Compiler<'a> {
     }
 
     /// Creates a new compiler with a specific cell base offset.
-    fn new_with_cell_base(interns: &'a Interns, functions: Vec<Function>, cell_base: u16) -> Self {
+    fn new_with_cell_base(
+        interns: &'a Interns,
+        functions: Vec<Function>,
+        function_base: usize,
+        cell_base: u16,
+    ) -> Self {
         Self {
             code: CodeBuilder::new(),
             interns,
             functions,
+            function_base,
             loop_stack: Vec::new(),
             cell_base,
             finally_targets: Vec::new(),
@@ -181,7 +212,7 @@ impl<'a> Compiler<'a> {
         interns: &Interns,
         num_locals: u16,
     ) -> Result<CompileResult, CompileError> {
-        let mut compiler = Compiler::new(interns, Vec::new());
+        let mut compiler = Compiler::new(interns, Vec::new(), 0);
         compiler.compile_block(nodes)?;
 
         // Module returns None if no explicit return
@@ -194,32 +225,94 @@ impl<'a> Compiler<'a> {
         })
     }
 
+    /// Compiles code run by `eval()` or `exec()`, returning the functions it defines.
+    ///
+    /// The code is compiled as the body of `func_def`, a function whose parameters carry the
+    /// names the code can see, and is the last of the returned functions. `function_base` is
+    /// the number of functions compiled before, so the returned functions get the IDs
+    /// following them.
+    ///
+    /// For `exec()`, each of `exports` (a local slot and its name) is copied back into the
+    /// dict passed as the first parameter before returning. For `eval()`, the body must be a
+    /// single `return` of the expression.
+    pub fn compile_eval(
+        func_def: &PreparedFunctionDef,
+        mode: EvalMode,
+        exports: &[(NamespaceId, StringId)],
+        interns: &Interns,
+        function_base: usize,
+    ) -> Result<Vec<Function>, CompileError> {
+        let cell_base = u16::try_from(func_def.signature.param_count()).expect("function parameter count exceeds u16");
+        let mut compiler = Compiler::new_with_cell_base(interns, Vec::new(), function_base, cell_base);
+        compiler.register_param_names(&func_def.signature);
+        match (mode, func_def.body.as_slice()) {
+            (EvalMode::Eval, [Node::Return(expr)]) => compiler.compile_expr(expr)?,
+            (EvalMode::Eval, _) => unreachable!("eval() code is a single expression"),
+            (EvalMode::Exec, body) => {
+                compiler.compile_block(body)?;
+                for &(slot, name_id) in exports {
+                    let slot = u16::try_from(slot.index()).expect("local slot exceeds u16");
+                    let name = u16::try_from(name_id.index()).expect("name index exceeds u16");
+                    compiler.code.emit_u16_u16(Opcode::ExportLocal, slot, name);
+                }
+                compiler.code.emit(Opcode::LoadNone);
+            }
+        }
+        compiler.code.emit(Opcode::ReturnValue);
+
+        let namespace_size = u16::try_from(func_def.namespace_size).expect("function namespace size exceeds u16");
+        let code = compiler.code.build(namespace_size);
+        let mut functions = compiler.functions;
+        functions.push(Function::new(
+            func_def.name,
+            func_def.signature.clone(),
+            func_def.namespace_size,
+            Vec::new(),
+            func_def.cell_var_count,
+            func_def.cell_param_indices.clone(),
+            0,
+            false,
+            false,
+            code,
+        ));
+        Ok(functions)
+    }
+
     /// Compiles a function body to bytecode, returning the Code and any nested functions.
     ///
     /// Used internally when compiling function definitions. The function body is
     /// compiled to bytecode with an implicit `return None` at the end if there's
     /// no explicit return statement.
     ///
-    /// The `cell_base` parameter is the number of parameter slots, used to convert
-    /// cell variable namespace slots to cells array indices.
-    ///
     /// The `functions` parameter receives any previously compiled functions, and
     /// any nested functions found in the body will be added to it.
     fn compile_function_body(
-        body: &[PreparedNode],
+        func_def: &PreparedFunctionDef,
         interns: &Interns,
         functions: Vec<Function>,
-        num_locals: u16,
-        cell_base: u16,
+        function_base: usize,
     ) -> Result<(Code, Vec<Function>), CompileError> {
-        let mut compiler = Compiler::new_with_cell_base(interns, functions, cell_base);
-        compiler.compile_block(body)?;
+        // The number of parameter slots, used to convert cell variable namespace slots to cells array indices
+        let cell_base = u16::try_from(func_def.signature.param_count()).expect("function parameter count exceeds u16");
+        let mut compiler = Compiler::new_with_cell_base(interns, functions, function_base, cell_base);
+        compiler.register_param_names(&func_def.signature);
+        compiler.compile_block(&func_def.body)?;
 
         // Implicit return None if no explicit return
         compiler.code.emit(Opcode::LoadNone);
         compiler.code.emit(Opcode::ReturnValue);
 
-        Ok((compiler.code.build(num_locals), compiler.functions))
+        let namespace_size = u16::try_from(func_def.namespace_size).expect("function namespace size exceeds u16");
+        Ok((compiler.code.build(namespace_size), compiler.functions))
+    }
+
+    /// Registers the names of the parameters, so `eval()` and `exec()` called in the
+    /// function can see them even if the body never uses them.
+    fn register_param_names(&mut self, signature: &Signature) {
+        for (slot, name_id) in signature.param_names().enumerate() {
+            let slot = u16::try_from(slot).expect("local slot exceeds u16");
+            self.code.register_local_name(slot, name_id);
+        }
     }
 
     /// Compiles a block of statements.
@@ -327,13 +420,81 @@ impl<'a> Compiler<'a> {
                     u16::try_from(name_id.index()).expect("name index exceeds u16"),
                 );
             }
+            Node::SubscriptOpAssign {
+                object,
+                index,
+                op,
+                value,
+                target_position,
+            } => {
+                let Some(opcode) = operator_to_inplace_opcode(op) else {
+                    return Err(CompileError::new(
+                        "matrix multiplication augmented assignment (@=) is not yet supported",
+                        *target_position,
+                    ));
+                };
+                // Evaluate the container and index once: [obj, index] -> [obj, index, obj, index]
+                self.compile_expr(object)?;
+                self.code.emit(Opcode::Dup);
+                self.compile_expr(index)?;
+                self.code.emit(Opcode::Dup);
+                self.code.emit(Opcode::Rot3);
+                self.code.set_location(*target_position, None);
+                self.code.emit(Opcode::BinarySubscr);
+                self.compile_expr(value)?;
+                self.code.set_location(*target_position, None);
+                self.code.emit(opcode);
+                // [obj, index, result] -> [result, obj, index] for StoreSubscr
+                self.code.emit(Opcode::Rot3);
+                self.code.emit(Opcode::StoreSubscr);
+            }
+            Node::AttrOpAssign {
+                object,
+                attr,
+                op,
+                value,
+                target_position,
+            } => {
+                let Some(opcode) = operator_to_inplace_opcode(op) else {
+                    return Err(CompileError::new(
+                        "matrix multiplication augmented assignment (@=) is not yet supported",
+                        *target_position,
+                    ));
+                };
+                let name_id = attr.string_id().expect("AttrOpAssign requires interned attr name");
+                let name_idx = u16::try_from(name_id.index()).expect("name index exceeds u16");
+                // Evaluate the object once: [obj] -> [obj, obj.attr]
+                self.compile_expr(object)?;
+                self.code.emit(Opcode::Dup);
+                self.code.set_location(*target_position, None);
+                self.code.emit_u16(Opcode::LoadAttr, name_idx);
+                self.compile_expr(value)?;
+                self.code.set_location(*target_position, None);
+                self.code.emit(opcode);
+                // [obj, result] -> [result, obj] for StoreAttr
+                self.code.emit(Opcode::Rot2);
+                self.code.emit_u16(Opcode::StoreAttr, name_idx);
+            }
+            Node::Delete(targets) => {
+                for target in targets {
+                    self.compile_delete_target(target)?;
+                }
+            }
             Node::If { test, body, or_else } => self.compile_if(test, body, or_else)?,
             Node::For {
                 target,
                 iter,
                 body,
                 or_else,
+                is_async: false,
             } => self.compile_for(target, iter, body, or_else)?,
+            Node::For {
+                target,
+                iter,
+                body,
+                or_else,
+                is_async: true,
+            } => self.compile_async_for(target, iter, body, or_else)?,
             Node::While { test, body, or_else } => self.compile_while(test, body, or_else)?,
             Node::Assert { test, msg } => self.compile_assert(test, msg.as_ref())?,
             Node::Raise(expr) => {
@@ -344,9 +505,21 @@ impl<'a> Compiler<'a> {
                     self.code.emit(Opcode::Reraise);
                 }
             }
+            Node::RaiseFrom { exc, cause } => {
+                self.compile_expr(exc)?;
+                self.compile_expr(cause)?;
+                self.code.emit(Opcode::RaiseFrom);
+            }
             Node::FunctionDef(func_def) => self.compile_function_def(func_def)?,
+            Node::ClassDef(class_def) => self.compile_class_def(class_def)?,
             Node::Try(try_block) => self.compile_try(try_block)?,
-            Node::Import { module_name, binding } => self.compile_import(*module_name, binding),
+            Node::Match(match_stmt) => self.compile_match(match_stmt)?,
+            Node::With(with_stmt) => self.compile_with_items(&with_stmt.items, &with_stmt.body, with_stmt.is_async)?,
+            Node::Import(modules) => {
+                for module in modules {
+                    self.compile_import(module);
+                }
+            }
             Node::ImportFrom {
                 module_name,
                 names,
@@ -367,6 +540,7 @@ impl<'a> Compiler<'a> {
     /// 2. Creating a Function struct with the compiled Code
     /// 3. Adding the Function to the compiler's functions vector
     /// 4. Emitting bytecode to evaluate defaults and create the function at runtime
+    /// 5. Applying decorators (evaluated before the function is created) and storing the result
     fn compile_function_def(&mut self, func_def: &PreparedFunctionDef) -> Result<(), CompileError> {
         let func_pos = func_def.name.position;
 
@@ -384,16 +558,19 @@ impl<'a> Compiler<'a> {
             ));
         }
 
-        // 1. Compile the function body recursively
+        // 1. Evaluate decorators first, they're applied once the function exists
+        for decorator in &func_def.decorators {
+            self.compile_expr(decorator)?;
+        }
+
+        // 2. Compile the function body recursively
         // Take ownership of functions for the recursive compile, then restore
         let functions = std::mem::take(&mut self.functions);
-        let cell_base = u16::try_from(func_def.signature.param_count()).expect("function parameter count exceeds u16");
-        let namespace_size = u16::try_from(func_def.namespace_size).expect("function namespace size exceeds u16");
         let (body_code, mut functions) =
-            Self::compile_function_body(&func_def.body, self.interns, functions, namespace_size, cell_base)?;
+            Self::compile_function_body(func_def, self.interns, functions, self.function_base)?;
 
-        // 2. Create the compiled Function and add to the vector
-        let func_id = functions.len();
+        // 3. Create the compiled Function and add to the vector
+        let func_id = self.function_base + functions.len();
         let function = Function::new(
             func_def.name,
             func_def.signature.clone(),
@@ -403,6 +580,7 @@ impl<'a> Compiler<'a> {
             func_def.cell_param_indices.clone(),
             func_def.default_exprs.len(),
             func_def.is_async,
+            func_def.is_generator,
             body_code,
         );
         functions.push(function);
@@ -410,15 +588,16 @@ impl<'a> Compiler<'a> {
         // Restore functions to self
         self.functions = functions;
 
-        // 3. Compile and push default values (evaluated at definition time)
+        // 4. Compile and push default values (evaluated at definition time)
         for default_expr in &func_def.default_exprs {
             self.compile_expr(default_expr)?;
         }
         let defaults_count =
             u8::try_from(func_def.default_exprs.len()).expect("function default argument count exceeds u8");
-        let func_id_u16 = u16::try_from(func_id).expect("function count exceeds u16");
+        let func_id_u16 =
+            u16::try_from(func_id).map_err(|_| CompileError::new("too many functions defined", func_pos))?;
 
-        // 4. Emit MakeFunction or MakeClosure (if has free vars)
+        // 5. Emit MakeFunction or MakeClosure (if has free vars)
         if func_def.free_var_enclosing_slots.is_empty() {
             // MakeFunction: func_id (u16) + defaults_count (u8)
             self.code.emit_u16_u8(Opcode::MakeFunction, func_id_u16, defaults_count);
@@ -436,12 +615,67 @@ impl<'a> Compiler<'a> {
                 .emit_u16_u8_u8(Opcode::MakeClosure, func_id_u16, defaults_count, cell_count);
         }
 
-        // 5. Store the function object to its name slot
+        // 6. Apply decorators, innermost (last) first
+        self.compile_decorator_calls(&func_def.decorators);
+
+        // 7. Store the function object to its name slot
         self.compile_store(&func_def.name);
 
         Ok(())
     }
 
+    /// Calls the decorators (already on the stack below the function) on the function at TOS.
+    ///
+    /// Each call pops the function and the innermost remaining decorator, and pushes the result.
+    fn compile_decorator_calls(&mut self, decorators: &[ExprLoc]) {
+        for decorator in decorators.iter().rev() {
+            self.code.set_location(decorator.position, None);
+            self.code.emit_u8(Opcode::CallFunction, 1);
+        }
+    }
+
+    /// Compiles a class definition.
+    ///
+    /// Evaluates the optional base, then builds a dict of the class body items
+    /// (attribute values and method function objects) in source order, and finally
+    /// emits `BuildClass` and stores the class object to its name slot.
+    fn compile_class_def(&mut self, class_def: &ClassDef<PreparedFunctionDef>) -> Result<(), CompileError> {
+        if let Some(base) = &class_def.base {
+            self.compile_expr(base)?;
+        }
+        for item in &class_def.body {
+            match item {
+                ClassItem::Attr { name, value } => {
+                    let name_const = self.code.add_const(Value::InternString(*name));
+                    self.code.emit_u16(Opcode::LoadConst, name_const);
+                    self.compile_expr(value)?;
+                }
+                ClassItem::Method(func_def) => {
+                    let name_const = self.code.add_const(Value::InternString(func_def.name.name_id));
+                    self.code.emit_u16(Opcode::LoadConst, name_const);
+                    for decorator in &func_def.decorators {
+                        self.compile_expr(decorator)?;
+                    }
+                    // Methods are built like lambdas: the function object stays on the stack
+                    self.compile_lambda(func_def)?;
+                    self.compile_decorator_calls(&func_def.decorators);
+                }
+            }
+        }
+        self.code.set_location(class_def.name.position, None);
+        self.code.emit_u16(
+            Opcode::BuildDict,
+            u16::try_from(class_def.body.len()).expect("class body item count exceeds u16"),
+        );
+        self.code.emit_u16_u8(
+            Opcode::BuildClass,
+            u16::try_from(class_def.name.name_id.index()).expect("name index exceeds u16"),
+            u8::from(class_def.base.is_some()),
+        );
+        self.compile_store(&class_def.name);
+        Ok(())
+    }
+
     /// Compiles a lambda expression.
     ///
     /// This is similar to `compile_function_def` but:
@@ -467,13 +701,11 @@ impl<'a> Compiler<'a> {
 
         // 1. Compile the function body recursively
         let functions = std::mem::take(&mut self.functions);
-        let cell_base = u16::try_from(func_def.signature.param_count()).expect("function parameter count exceeds u16");
-        let namespace_size = u16::try_from(func_def.namespace_size).expect("function namespace size exceeds u16");
         let (body_code, mut functions) =
-            Self::compile_function_body(&func_def.body, self.interns, functions, namespace_size, cell_base)?;
+            Self::compile_function_body(func_def, self.interns, functions, self.function_base)?;
 
         // 2. Create the compiled Function and add to the vector
-        let func_id = functions.len();
+        let func_id = self.function_base + functions.len();
         let function = Function::new(
             func_def.name,
             func_def.signature.clone(),
@@ -483,6 +715,7 @@ impl<'a> Compiler<'a> {
             func_def.cell_param_indices.clone(),
             func_def.default_exprs.len(),
             func_def.is_async,
+            func_def.is_generator,
             body_code,
         );
         functions.push(function);
@@ -496,7 +729,8 @@ impl<'a> Compiler<'a> {
         }
         let defaults_count =
             u8::try_from(func_def.default_exprs.len()).expect("function default argument count exceeds u8");
-        let func_id_u16 = u16::try_from(func_id).expect("function count exceeds u16");
+        let func_id_u16 =
+            u16::try_from(func_id).map_err(|_| CompileError::new("too many functions defined", func_pos))?;
 
         // 4. Emit MakeFunction or MakeClosure (if has free vars)
         if func_def.free_var_enclosing_slots.is_empty() {
@@ -521,17 +755,24 @@ impl<'a> Compiler<'a> {
         Ok(())
     }
 
-    /// Compiles an import statement.
+    /// Compiles one module of an import statement.
     ///
     /// Emits `LoadModule` to create the module, then stores it to the binding name.
+    /// For `import os.path` the loaded and bound module is the top-level package `os`.
     /// If the module is unknown, emits `RaiseImportError` to defer the error to runtime.
     /// This allows imports inside `if TYPE_CHECKING:` blocks to compile successfully.
-    fn compile_import(&mut self, module_name: StringId, binding: &Identifier) {
-        let position = binding.position;
-        self.code.set_location(position, None);
+    fn compile_import(&mut self, module: &ImportedModule) {
+        let ImportedModule {
+            module_name,
+            bound_module,
+            binding,
+        } = module;
+        self.code.set_location(binding.position, None);
 
         // Look up the module by name
-        if let Some(builtin_module) = BuiltinModule::from_string_id(module_name) {
+        if BuiltinModule::from_string_id(*module_name).is_some()
+            && let Some(builtin_module) = BuiltinModule::from_string_id(*bound_module)
+        {
             // Known module - emit LoadModule
             self.code.emit_u8(Opcode::LoadModule, builtin_module as u8);
             // Store to the binding (respects Local/Global/Cell scope)
@@ -539,7 +780,7 @@ impl<'a> Compiler<'a> {
         } else {
             // Unknown module - defer error to runtime with RaiseImportError
             // This allows TYPE_CHECKING imports to compile without error
-            let name_const = self.code.add_const(Value::InternString(module_name));
+            let name_const = self.code.add_const(Value::InternString(*module_name));
             self.code.emit_u16(Opcode::RaiseImportError, name_const);
         }
     }
@@ -650,44 +891,72 @@ impl<'a> Compiler<'a> {
             }
 
             Expr::List(elements) => {
-                for elem in elements {
-                    self.compile_expr(elem)?;
-                }
-                self.code.emit_u16(
-                    Opcode::BuildList,
-                    u16::try_from(elements.len()).expect("elements count exceeds u16"),
-                );
+                self.compile_list_elements(elements)?;
             }
 
             Expr::Tuple(elements) => {
-                for elem in elements {
-                    self.compile_expr(elem)?;
+                if leading_unstarred(elements) < elements.len() {
+                    self.compile_list_elements(elements)?;
+                    self.code.emit(Opcode::ListToTuple);
+                } else {
+                    for elem in elements {
+                        self.compile_expr(elem)?;
+                    }
+                    self.code.emit_u16(
+                        Opcode::BuildTuple,
+                        u16::try_from(elements.len()).expect("elements count exceeds u16"),
+                    );
                 }
-                self.code.emit_u16(
-                    Opcode::BuildTuple,
-                    u16::try_from(elements.len()).expect("elements count exceeds u16"),
-                );
             }
 
             Expr::Dict(pairs) => {
-                for (key, value) in pairs {
-                    self.compile_expr(key)?;
+                // Leading `key: value` pairs are built directly, the rest are added one at a time
+                let leading = pairs.iter().take_while(|(key, _)| key.is_some()).count();
+                for (key, value) in &pairs[..leading] {
+                    self.compile_expr(key.as_ref().expect("leading pairs have keys"))?;
                     self.compile_expr(value)?;
                 }
                 self.code.emit_u16(
                     Opcode::BuildDict,
-                    u16::try_from(pairs.len()).expect("pairs count exceeds u16"),
+                    u16::try_from(leading).expect("pairs count exceeds u16"),
                 );
+                for (key, value) in &pairs[leading..] {
+                    if let Some(key) = key {
+                        self.compile_expr(key)?;
+                        self.compile_expr(value)?;
+                        self.code.emit_u8(Opcode::DictSetItem, 0);
+                    } else {
+                        self.compile_expr(value)?;
+                        self.code.emit(Opcode::DictUpdate);
+                    }
+                }
             }
 
             Expr::Set(elements) => {
-                for elem in elements {
+                let leading = leading_unstarred(elements);
+                for elem in &elements[..leading] {
                     self.compile_expr(elem)?;
                 }
                 self.code.emit_u16(
                     Opcode::BuildSet,
-                    u16::try_from(elements.len()).expect("elements count exceeds u16"),
+                    u16::try_from(leading).expect("elements count exceeds u16"),
                 );
+                for elem in &elements[leading..] {
+                    if let Expr::Starred(iterable) = &elem.expr {
+                        self.compile_expr(iterable)?;
+                        self.code.emit(Opcode::SetUpdate);
+                    } else {
+                        self.compile_expr(elem)?;
+                        self.code.emit_u8(Opcode::SetAdd, 0);
+                    }
+                }
+            }
+
+            Expr::Starred(_) => {
+                return Err(CompileError::new(
+                    "can't use starred expression here",
+                    expr_loc.position,
+                ));
             }
 
             Expr::Subscript { object, index } => {
@@ -760,6 +1029,33 @@ impl<'a> Compiler<'a> {
                 unreachable!("Expr::LambdaRaw should not exist after prepare phase")
             }
 
+            Expr::GeneratorExp { func_def, iter } => {
+                // Build the generator function, then call it with an iterator over the first iterable
+                self.compile_lambda(func_def)?;
+                self.compile_expr(iter)?;
+                self.code.emit(Opcode::GetIter);
+                self.code.set_location(expr_loc.position, None);
+                self.code.emit_u8(Opcode::CallFunction, 1);
+            }
+
+            Expr::GeneratorExpRaw { .. } => {
+                unreachable!("Expr::GeneratorExpRaw should not exist after prepare phase")
+            }
+
+            Expr::Yield(value) => {
+                if let Some(value) = value {
+                    self.compile_expr(value)?;
+                } else {
+                    self.code.emit(Opcode::LoadNone);
+                }
+                self.code.set_location(expr_loc.position, None);
+                self.code.emit(Opcode::YieldValue);
+            }
+
+            Expr::YieldFrom(value) => {
+                self.compile_yield_from(value, expr_loc.position)?;
+            }
+
             Expr::Await(value) => {
                 // Await expressions: compile the inner expression, then emit Await
                 // Await handles ExternalFuture, Coroutine, and GatherFuture
@@ -861,10 +1157,11 @@ impl<'a> Compiler<'a> {
                 self.code.emit_u16(Opcode::LoadGlobal, slot);
             }
             NameScope::Cell => {
+                // Register the name under its namespace slot, for NameError messages (unbound
+                // free variable) and so `eval()` can find the variable
+                self.code.register_local_name(slot, ident.name_id);
                 // Convert namespace slot to cells array index
                 let cell_index = slot.saturating_sub(self.cell_base);
-                // Register the name for NameError messages (unbound free variable)
-                self.code.register_local_name(cell_index, ident.name_id);
                 self.code.emit_u16(Opcode::LoadCell, cell_index);
             }
         }
@@ -892,6 +1189,7 @@ impl<'a> Compiler<'a> {
                 self.code.emit_u16(Opcode::StoreGlobal, slot);
             }
             NameScope::Cell => {
+                self.code.register_local_name(slot, target.name_id);
                 // Convert namespace slot to cells array index
                 let cell_index = slot.saturating_sub(self.cell_base);
                 self.code.emit_u16(Opcode::StoreCell, cell_index);
@@ -1172,65 +1470,51 @@ impl<'a> Compiler<'a> {
                 self.code.set_location(call_pos, None);
                 self.code.emit_call_function_kw(0, &kwname_ids);
             }
-            ArgExprs::ArgsKargs {
-                args,
-                var_args,
-                kwargs,
-                var_kwargs,
-            } => {
-                // Mixed positional and keyword arguments - may include *args or **kwargs unpacking
-                if var_args.is_some() || var_kwargs.is_some() {
-                    // Use CallFunctionEx for unpacking - no limit on this path since
-                    // args are built into a tuple dynamically at runtime
-                    self.compile_call_with_unpacking(
-                        callable,
-                        args.as_ref(),
-                        var_args.as_ref(),
-                        kwargs.as_ref(),
-                        var_kwargs.as_ref(),
+            ArgExprs::ArgsKargs { args, kwargs } => {
+                // Mixed positional and keyword arguments - use CallFunctionKw
+                // Check limits before compiling
+                if args.len() > MAX_CALL_ARGS {
+                    return Err(CompileError::new(
+                        format!("more than {MAX_CALL_ARGS} positional arguments in function call"),
                         call_pos,
-                    )?;
-                } else {
-                    // No unpacking - use CallFunctionKw for efficiency
-                    // Check limits before compiling
-                    let pos_count = args.as_ref().map_or(0, Vec::len);
-                    let kw_count = kwargs.as_ref().map_or(0, Vec::len);
-
-                    if pos_count > MAX_CALL_ARGS {
-                        return Err(CompileError::new(
-                            format!("more than {MAX_CALL_ARGS} positional arguments in function call"),
-                            call_pos,
-                        ));
-                    }
-                    if kw_count > MAX_CALL_ARGS {
-                        return Err(CompileError::new(
-                            format!("more than {MAX_CALL_ARGS} keyword arguments in function call"),
-                            call_pos,
-                        ));
-                    }
-
-                    // Compile positional args
-                    if let Some(args) = args {
-                        for arg in args {
-                            self.compile_expr(arg)?;
-                        }
-                    }
+                    ));
+                }
+                if kwargs.len() > MAX_CALL_ARGS {
+                    return Err(CompileError::new(
+                        format!("more than {MAX_CALL_ARGS} keyword arguments in function call"),
+                        call_pos,
+                    ));
+                }
 
-                    // Compile kwarg values and collect names
-                    let mut kwname_ids = Vec::new();
-                    if let Some(kwargs) = kwargs {
-                        for kwarg in kwargs {
-                            self.compile_expr(&kwarg.value)?;
-                            kwname_ids.push(u16::try_from(kwarg.key.name_id.index()).expect("name index exceeds u16"));
-                        }
-                    }
+                // Compile positional args
+                for arg in args {
+                    self.compile_expr(arg)?;
+                }
 
-                    self.code.set_location(call_pos, None);
-                    self.code.emit_call_function_kw(
-                        u8::try_from(pos_count).expect("positional arg count exceeds u8"),
-                        &kwname_ids,
-                    );
+                // Compile kwarg values and collect names
+                let mut kwname_ids = Vec::with_capacity(kwargs.len());
+                for kwarg in kwargs {
+                    self.compile_expr(&kwarg.value)?;
+                    kwname_ids.push(u16::try_from(kwarg.key.name_id.index()).expect("name index exceeds u16"));
                 }
+
+                self.code.set_location(call_pos, None);
+                self.code.emit_call_function_kw(
+                    u8::try_from(args.len()).expect("positional arg count exceeds u8"),
+                    &kwname_ids,
+                );
+            }
+            ArgExprs::Unpack { args, kwargs } => {
+                // Use CallFunctionExtended for unpacking - no limit on this path since
+                // args are built into a tuple dynamically at runtime.
+                // Get function name for error messages (0xFFFF for builtins)
+                let func_name_id = match callable {
+                    Callable::Name(ident) => u16::try_from(ident.name_id.index()).expect("name index exceeds u16"),
+                    Callable::Builtin(_) => 0xFFFF,
+                };
+                let has_kwargs = self.compile_unpacking_args(args, kwargs, func_name_id)?;
+                self.code.set_location(call_pos, None);
+                self.code.emit_u8(Opcode::CallFunctionExtended, u8::from(has_kwargs));
             }
         }
         Ok(())
@@ -1286,135 +1570,139 @@ impl<'a> Compiler<'a> {
                 self.code.set_location(call_pos, None);
                 self.code.emit_call_function_kw(0, &kwname_ids);
             }
-            ArgExprs::ArgsKargs {
-                args,
-                kwargs,
-                var_args,
-                var_kwargs,
-            } => {
-                // Mixed positional and keyword arguments - may include *args or **kwargs unpacking
-                if var_args.is_some() || var_kwargs.is_some() {
-                    // Use CallFunctionExtended for unpacking - no limit on this path since
-                    // args are built into a tuple dynamically at runtime.
-                    // Callable is already on stack, so we just need to build args and kwargs.
-                    self.compile_call_args_with_unpacking(
-                        args.as_ref(),
-                        var_args.as_ref(),
-                        kwargs.as_ref(),
-                        var_kwargs.as_ref(),
+            ArgExprs::ArgsKargs { args, kwargs } => {
+                // Mixed positional and keyword arguments - use CallFunctionKw
+                // Check limits separately (same as direct calls)
+                if args.len() > MAX_CALL_ARGS {
+                    return Err(CompileError::new(
+                        format!("more than {MAX_CALL_ARGS} positional arguments in function call"),
                         call_pos,
-                    )?;
-                } else {
-                    // No unpacking - use CallFunctionKw for efficiency
-                    let pos_args = args.as_deref().unwrap_or(&[]);
-                    let kw_args = kwargs.as_deref().unwrap_or(&[]);
-                    let pos_count = pos_args.len();
-                    let kw_count = kw_args.len();
-
-                    // Check limits separately (same as direct calls)
-                    if pos_count > MAX_CALL_ARGS {
-                        return Err(CompileError::new(
-                            format!("more than {MAX_CALL_ARGS} positional arguments in function call"),
-                            call_pos,
-                        ));
-                    }
-                    if kw_count > MAX_CALL_ARGS {
-                        return Err(CompileError::new(
-                            format!("more than {MAX_CALL_ARGS} keyword arguments in function call"),
-                            call_pos,
-                        ));
-                    }
-
-                    // Compile positional args
-                    for arg in pos_args {
-                        self.compile_expr(arg)?;
-                    }
+                    ));
+                }
+                if kwargs.len() > MAX_CALL_ARGS {
+                    return Err(CompileError::new(
+                        format!("more than {MAX_CALL_ARGS} keyword arguments in function call"),
+                        call_pos,
+                    ));
+                }
 
-                    // Compile keyword args
-                    let mut kwname_ids = Vec::with_capacity(kw_count);
-                    for kwarg in kw_args {
-                        self.compile_expr(&kwarg.value)?;
-                        kwname_ids.push(u16::try_from(kwarg.key.name_id.index()).expect("name index exceeds u16"));
-                    }
+                // Compile positional args
+                for arg in args {
+                    self.compile_expr(arg)?;
+                }
 
-                    self.code.set_location(call_pos, None);
-                    self.code.emit_call_function_kw(
-                        u8::try_from(pos_count).expect("positional arg count exceeds u8"),
-                        &kwname_ids,
-                    );
+                // Compile keyword args
+                let mut kwname_ids = Vec::with_capacity(kwargs.len());
+                for kwarg in kwargs {
+                    self.compile_expr(&kwarg.value)?;
+                    kwname_ids.push(u16::try_from(kwarg.key.name_id.index()).expect("name index exceeds u16"));
                 }
+
+                self.code.set_location(call_pos, None);
+                self.code.emit_call_function_kw(
+                    u8::try_from(args.len()).expect("positional arg count exceeds u8"),
+                    &kwname_ids,
+                );
+            }
+            ArgExprs::Unpack { args, kwargs } => {
+                // Use CallFunctionExtended for unpacking - no limit on this path since
+                // args are built into a tuple dynamically at runtime.
+                // Callable is already on stack and has no name, so use 0xFFFF like builtins.
+                let has_kwargs = self.compile_unpacking_args(args, kwargs, 0xFFFF)?;
+                self.code.set_location(call_pos, None);
+                self.code.emit_u8(Opcode::CallFunctionExtended, u8::from(has_kwargs));
             }
         }
         Ok(())
     }
 
-    /// Compiles arguments with `*args` and/or `**kwargs` unpacking when callable is already on stack.
+    /// Builds the args tuple and optional kwargs dict for a call with unpacking.
     ///
-    /// This is used for expression calls (e.g., `(lambda *a: a)(*xs)`) where the callable
-    /// is compiled as an expression and is already on the stack.
+    /// Positional arguments are collected into a list, with `ListExtend` for each
+    /// `*iterable`, then converted to a tuple. Keyword arguments start as a dict of the
+    /// leading named arguments; each `**mapping`, and each later run of named arguments,
+    /// is merged in with `DictMerge` so duplicate keywords raise CPython's `TypeError`.
+    /// `func_name_id` names the callee in those errors (0xFFFF when unknown).
     ///
     /// Stack layout: callable (on stack) -> callable, args_tuple, kwargs_dict?
-    fn compile_call_args_with_unpacking(
+    ///
+    /// Returns whether a kwargs dict was pushed, which becomes the call's flags operand.
+    fn compile_unpacking_args(
         &mut self,
-        args: Option<&Vec<ExprLoc>>,
-        var_args: Option<&ExprLoc>,
-        kwargs: Option<&Vec<Kwarg>>,
-        var_kwargs: Option<&ExprLoc>,
-        call_pos: CodeRange,
-    ) -> Result<(), CompileError> {
+        args: &[ExprLoc],
+        kwargs: &[KwargExpr],
+        func_name_id: u16,
+    ) -> Result<bool, CompileError> {
         // 1. Build args tuple
-        // Push regular positional args and build list
-        let pos_count = args.map_or(0, Vec::len);
-        if let Some(args) = args {
-            for arg in args {
-                self.compile_expr(arg)?;
+        self.compile_list_elements(args)?;
+        self.code.emit(Opcode::ListToTuple);
+
+        // 2. Build kwargs dict
+        if kwargs.is_empty() {
+            return Ok(false);
+        }
+        let leading = leading_named_kwargs(kwargs);
+        self.compile_named_kwargs(&kwargs[..leading])?;
+        let mut rest = &kwargs[leading..];
+        while let Some(first) = rest.first() {
+            if let KwargExpr::Unpack(mapping) = first {
+                self.compile_expr(mapping)?;
+                rest = &rest[1..];
+            } else {
+                let count = leading_named_kwargs(rest);
+                self.compile_named_kwargs(&rest[..count])?;
+                rest = &rest[count..];
             }
+            self.code.emit_u16(Opcode::DictMerge, func_name_id);
+        }
+        Ok(true)
+    }
+
+    /// Builds a dict from named keyword arguments.
+    ///
+    /// Stack: [] -> [dict]
+    fn compile_named_kwargs(&mut self, kwargs: &[KwargExpr]) -> Result<(), CompileError> {
+        for kwarg in kwargs {
+            let KwargExpr::Named(kwarg) = kwarg else {
+                unreachable!("compile_named_kwargs called with **mapping unpacking");
+            };
+            // Push key as interned string constant
+            let key_const = self.code.add_const(Value::InternString(kwarg.key.name_id));
+            self.code.emit_u16(Opcode::LoadConst, key_const);
+            // Push value
+            self.compile_expr(&kwarg.value)?;
         }
         self.code.emit_u16(
-            Opcode::BuildList,
-            u16::try_from(pos_count).expect("positional arg count exceeds u16"),
+            Opcode::BuildDict,
+            u16::try_from(kwargs.len()).expect("keyword count exceeds u16"),
         );
+        Ok(())
+    }
 
-        // Extend with *args if present
-        if let Some(var_args_expr) = var_args {
-            self.compile_expr(var_args_expr)?;
-            self.code.emit(Opcode::ListExtend);
+    /// Builds a list from elements that may include `*iterable` unpacking.
+    ///
+    /// Leading plain elements are built with `BuildList`, then each remaining element is
+    /// added with `ListAppend`, or with `ListExtend` for starred elements.
+    ///
+    /// Stack: [] -> [list]
+    fn compile_list_elements(&mut self, elements: &[ExprLoc]) -> Result<(), CompileError> {
+        let leading = leading_unstarred(elements);
+        for elem in &elements[..leading] {
+            self.compile_expr(elem)?;
         }
-
-        // Convert list to tuple
-        self.code.emit(Opcode::ListToTuple);
-
-        // 2. Build kwargs dict (if we have kwargs or var_kwargs)
-        let has_kwargs = kwargs.is_some() || var_kwargs.is_some();
-        if has_kwargs {
-            // Build dict from regular kwargs
-            let kw_count = kwargs.map_or(0, Vec::len);
-            if let Some(kwargs) = kwargs {
-                for kwarg in kwargs {
-                    // Push key as interned string constant
-                    let key_const = self.code.add_const(Value::InternString(kwarg.key.name_id));
-                    self.code.emit_u16(Opcode::LoadConst, key_const);
-                    // Push value
-                    self.compile_expr(&kwarg.value)?;
-                }
-            }
-            self.code.emit_u16(
-                Opcode::BuildDict,
-                u16::try_from(kw_count).expect("keyword count exceeds u16"),
-            );
-
-            // Merge **kwargs if present
-            // Use 0xFFFF for func_name_id (like builtins) since we don't have a name
-            if let Some(var_kwargs_expr) = var_kwargs {
-                self.compile_expr(var_kwargs_expr)?;
-                self.code.emit_u16(Opcode::DictMerge, 0xFFFF);
+        self.code.emit_u16(
+            Opcode::BuildList,
+            u16::try_from(leading).expect("elements count exceeds u16"),
+        );
+        for elem in &elements[leading..] {
+            if let Expr::Starred(iterable) = &elem.expr {
+                self.compile_expr(iterable)?;
+                self.code.emit(Opcode::ListExtend);
+            } else {
+                self.compile_expr(elem)?;
+                self.code.emit_u8(Opcode::ListAppend, 0);
             }
         }
-
-        // 3. Call the function
-        self.code.set_location(call_pos, None);
-        let flags = u8::from(has_kwargs);
-        self.code.emit_u8(Opcode::CallFunctionExtended, flags);
         Ok(())
     }
 
@@ -1449,90 +1737,11 @@ impl<'a> Compiler<'a> {
                 Ok(Some(u8::try_from(args.len()).expect("argument count exceeds u8")))
             }
             // Kwargs or unpacking - fall back to standard path
-            ArgExprs::Kwargs(_) | ArgExprs::ArgsKargs { .. } => Ok(None),
+            ArgExprs::Kwargs(_) | ArgExprs::ArgsKargs { .. } | ArgExprs::Unpack { .. } => Ok(None),
         }
     }
 
-    /// Compiles a function call with `*args` and/or `**kwargs` unpacking.
-    ///
-    /// This generates bytecode to build an args tuple and kwargs dict dynamically,
-    /// then calls the function using `CallFunctionEx`.
-    ///
-    /// Stack layout for call:
-    /// - callable (already on stack)
-    /// - args tuple
-    /// - kwargs dict (if present)
-    fn compile_call_with_unpacking(
-        &mut self,
-        callable: &Callable,
-        args: Option<&Vec<ExprLoc>>,
-        var_args: Option<&ExprLoc>,
-        kwargs: Option<&Vec<Kwarg>>,
-        var_kwargs: Option<&ExprLoc>,
-        call_pos: CodeRange,
-    ) -> Result<(), CompileError> {
-        // Get function name for error messages (0xFFFF for builtins)
-        let func_name_id = match callable {
-            Callable::Name(ident) => u16::try_from(ident.name_id.index()).expect("name index exceeds u16"),
-            Callable::Builtin(_) => 0xFFFF,
-        };
-
-        // 1. Build args tuple
-        // Push regular positional args and build list
-        let pos_count = args.map_or(0, Vec::len);
-        if let Some(args) = args {
-            for arg in args {
-                self.compile_expr(arg)?;
-            }
-        }
-        self.code.emit_u16(
-            Opcode::BuildList,
-            u16::try_from(pos_count).expect("positional arg count exceeds u16"),
-        );
-
-        // Extend with *args if present
-        if let Some(var_args_expr) = var_args {
-            self.compile_expr(var_args_expr)?;
-            self.code.emit(Opcode::ListExtend);
-        }
-
-        // Convert list to tuple
-        self.code.emit(Opcode::ListToTuple);
-
-        // 2. Build kwargs dict (if we have kwargs or var_kwargs)
-        let has_kwargs = kwargs.is_some() || var_kwargs.is_some();
-        if has_kwargs {
-            // Build dict from regular kwargs
-            let kw_count = kwargs.map_or(0, Vec::len);
-            if let Some(kwargs) = kwargs {
-                for kwarg in kwargs {
-                    // Push key as interned string constant
-                    let key_const = self.code.add_const(Value::InternString(kwarg.key.name_id));
-                    self.code.emit_u16(Opcode::LoadConst, key_const);
-                    // Push value
-                    self.compile_expr(&kwarg.value)?;
-                }
-            }
-            self.code.emit_u16(
-                Opcode::BuildDict,
-                u16::try_from(kw_count).expect("keyword count exceeds u16"),
-            );
-
-            // Merge **kwargs if present
-            if let Some(var_kwargs_expr) = var_kwargs {
-                self.compile_expr(var_kwargs_expr)?;
-                self.code.emit_u16(Opcode::DictMerge, func_name_id);
-            }
-        }
-
-        // 3. Call the function
-        self.code.set_location(call_pos, None);
-        let flags = u8::from(has_kwargs);
-        self.code.emit_u8(Opcode::CallFunctionExtended, flags);
-        Ok(())
-    }
-
-    /// Compiles an attribute call on an object.
+    /// Compiles an attribute call on an object.
     ///
     /// The object should already be on the stack. This compiles the arguments
     /// and emits a CallAttr opcode with the attribute name and arg count.
@@ -1614,35 +1823,15 @@ impl<'a> Compiler<'a> {
                     &kwname_ids,
                 );
             }
-            ArgExprs::ArgsKargs {
-                args,
-                kwargs,
-                var_args,
-                var_kwargs,
-            } => {
-                // Check if there's unpacking - use CallAttrExtended
-                if var_args.is_some() || var_kwargs.is_some() {
-                    return self.compile_method_call_with_unpacking(
-                        name_id,
-                        args.as_ref(),
-                        var_args.as_ref(),
-                        kwargs.as_ref(),
-                        var_kwargs.as_ref(),
-                        call_pos,
-                    );
-                }
-
-                // No unpacking - use CallAttrKw for efficiency
-                let pos_count = args.as_ref().map_or(0, Vec::len);
-                let kw_count = kwargs.as_ref().map_or(0, Vec::len);
-
-                if pos_count > MAX_CALL_ARGS {
+            ArgExprs::ArgsKargs { args, kwargs } => {
+                // Mixed positional and keyword arguments - use CallAttrKw
+                if args.len() > MAX_CALL_ARGS {
                     return Err(CompileError::new(
                         format!("more than {MAX_CALL_ARGS} positional arguments in method call"),
                         call_pos,
                     ));
                 }
-                if kw_count > MAX_CALL_ARGS {
+                if kwargs.len() > MAX_CALL_ARGS {
                     return Err(CompileError::new(
                         format!("more than {MAX_CALL_ARGS} keyword arguments in method call"),
                         call_pos,
@@ -1650,102 +1839,33 @@ impl<'a> Compiler<'a> {
                 }
 
                 // Compile positional args
-                if let Some(args) = args {
-                    for arg in args {
-                        self.compile_expr(arg)?;
-                    }
+                for arg in args {
+                    self.compile_expr(arg)?;
                 }
 
                 // Compile kwarg values and collect names
-                let mut kwname_ids = Vec::new();
-                if let Some(kwargs) = kwargs {
-                    for kwarg in kwargs {
-                        self.compile_expr(&kwarg.value)?;
-                        kwname_ids.push(u16::try_from(kwarg.key.name_id.index()).expect("name index exceeds u16"));
-                    }
+                let mut kwname_ids = Vec::with_capacity(kwargs.len());
+                for kwarg in kwargs {
+                    self.compile_expr(&kwarg.value)?;
+                    kwname_ids.push(u16::try_from(kwarg.key.name_id.index()).expect("name index exceeds u16"));
                 }
 
                 self.code.set_location(call_pos, None);
                 self.code.emit_call_attr_kw(
                     u16::try_from(name_id.index()).expect("name index exceeds u16"),
-                    u8::try_from(pos_count).expect("positional arg count exceeds u8"),
+                    u8::try_from(args.len()).expect("positional arg count exceeds u8"),
                     &kwname_ids,
                 );
             }
-        }
-        Ok(())
-    }
-
-    /// Compiles a method call with `*args` and/or `**kwargs` unpacking.
-    ///
-    /// The receiver object should already be on the stack. This builds the args tuple
-    /// and optional kwargs dict, then emits `CallAttrExtended`.
-    fn compile_method_call_with_unpacking(
-        &mut self,
-        name_id: StringId,
-        args: Option<&Vec<ExprLoc>>,
-        var_args: Option<&ExprLoc>,
-        kwargs: Option<&Vec<Kwarg>>,
-        var_kwargs: Option<&ExprLoc>,
-        call_pos: CodeRange,
-    ) -> Result<(), CompileError> {
-        // 1. Build args tuple
-        // Push regular positional args and build list
-        let pos_count = args.map_or(0, Vec::len);
-        if let Some(args) = args {
-            for arg in args {
-                self.compile_expr(arg)?;
-            }
-        }
-        self.code.emit_u16(
-            Opcode::BuildList,
-            u16::try_from(pos_count).expect("positional arg count exceeds u16"),
-        );
-
-        // Extend with *args if present
-        if let Some(var_args_expr) = var_args {
-            self.compile_expr(var_args_expr)?;
-            self.code.emit(Opcode::ListExtend);
-        }
-
-        // Convert list to tuple
-        self.code.emit(Opcode::ListToTuple);
-
-        // 2. Build kwargs dict (if we have kwargs or var_kwargs)
-        let has_kwargs = kwargs.is_some() || var_kwargs.is_some();
-        if has_kwargs {
-            // Build dict from regular kwargs
-            let kw_count = kwargs.map_or(0, Vec::len);
-            if let Some(kwargs) = kwargs {
-                for kwarg in kwargs {
-                    // Push key as interned string constant
-                    let key_const = self.code.add_const(Value::InternString(kwarg.key.name_id));
-                    self.code.emit_u16(Opcode::LoadConst, key_const);
-                    // Push value
-                    self.compile_expr(&kwarg.value)?;
-                }
-            }
-            self.code.emit_u16(
-                Opcode::BuildDict,
-                u16::try_from(kw_count).expect("keyword count exceeds u16"),
-            );
-
-            // Merge **kwargs if present
-            if let Some(var_kwargs_expr) = var_kwargs {
-                self.compile_expr(var_kwargs_expr)?;
-                // Use the method name for error messages
-                self.code.emit_u16(
-                    Opcode::DictMerge,
-                    u16::try_from(name_id.index()).expect("name index exceeds u16"),
-                );
+            ArgExprs::Unpack { args, kwargs } => {
+                // Unpacking - use CallAttrExtended, with the method name for error messages
+                let name_idx = u16::try_from(name_id.index()).expect("name index exceeds u16");
+                let has_kwargs = self.compile_unpacking_args(args, kwargs, name_idx)?;
+                self.code.set_location(call_pos, None);
+                self.code
+                    .emit_u16_u8(Opcode::CallAttrExtended, name_idx, u8::from(has_kwargs));
             }
         }
-
-        // 3. Call the method with CallAttrExtended
-        self.code.set_location(call_pos, None);
-        let name_idx = u16::try_from(name_id.index()).expect("name index exceeds u16");
-        let flags = u8::from(has_kwargs);
-        self.code.emit_u16_u8(Opcode::CallAttrExtended, name_idx, flags);
         Ok(())
     }
 
@@ -1803,6 +1923,105 @@ impl<'a> Compiler<'a> {
         Ok(())
     }
 
+    /// Compiles an `async for` loop.
+    ///
+    /// The bytecode structure:
+    /// ```text
+    ///   [evaluate iterable]
+    ///   GetAIter
+    /// loop_start:
+    ///   GetANext              ; protected by the EndAsyncFor handler
+    ///   Await
+    ///   [store target]
+    ///   [body]
+    ///   Jump -> loop_start
+    /// handler:
+    ///   EndAsyncFor           ; StopAsyncIteration ends the loop, other exceptions propagate
+    ///   [else block]
+    /// [break patches here]
+    /// ```
+    fn compile_async_for(
+        &mut self,
+        target: &UnpackTarget,
+        iter: &ExprLoc,
+        body: &[PreparedNode],
+        or_else: &[PreparedNode],
+    ) -> Result<(), CompileError> {
+        self.compile_expr(iter)?;
+        self.code.set_location(iter.position, None);
+        self.code.emit(Opcode::GetAIter);
+        let iter_depth = self.code.stack_depth();
+
+        let loop_start = self.code.current_offset();
+        self.loop_stack.push(LoopInfo {
+            start: loop_start,
+            break_jumps: Vec::new(),
+            has_iterator_on_stack: true,
+        });
+
+        self.code.emit(Opcode::GetANext);
+        self.code.emit(Opcode::Await);
+        let next_end = self.code.current_offset();
+        self.compile_unpack_target(target);
+        self.compile_block(body)?;
+        self.code.emit_jump_to(Opcode::Jump, loop_start);
+        self.compile_end_async_for(loop_start, next_end, iter_depth);
+
+        let loop_info = self.loop_stack.pop().expect("loop stack underflow");
+        if !or_else.is_empty() {
+            self.compile_block(or_else)?;
+        }
+        for break_jump in loop_info.break_jumps {
+            self.code.patch_jump(break_jump);
+        }
+        Ok(())
+    }
+
+    /// Emits the `EndAsyncFor` handler ending an `async for` loop, covering the
+    /// `GetANext`/`Await` pair between `next_start` and `next_end`.
+    ///
+    /// `iter_depth` is the stack depth with the async iterator on the stack.
+    fn compile_end_async_for(&mut self, next_start: usize, next_end: usize, iter_depth: u16) {
+        let handler_start = self.code.current_offset();
+        // VM pushes the exception onto the stack: [aiter, exc]
+        self.code.set_stack_depth(iter_depth + 1);
+        self.code.emit(Opcode::EndAsyncFor);
+        self.code.add_exception_entry(ExceptionEntry::new(
+            u32::try_from(next_start).expect("bytecode offset exceeds u32"),
+            u32::try_from(next_end).expect("bytecode offset exceeds u32"),
+            u32::try_from(handler_start).expect("bytecode offset exceeds u32"),
+            iter_depth,
+        ));
+    }
+
+    /// Compiles a `yield from` expression.
+    ///
+    /// The bytecode structure:
+    /// ```text
+    ///   [evaluate iterable]
+    ///   GetIter
+    ///   LoadNone              ; first value sent to the sub-iterator
+    /// loop_start:
+    ///   SendSub -> end        ; [subiter, sent] -> [subiter, yielded], or jump with [result]
+    ///   YieldValue            ; [subiter, yielded] -> [subiter, sent]
+    ///   Jump -> loop_start
+    /// end:
+    /// ```
+    fn compile_yield_from(&mut self, value: &ExprLoc, position: CodeRange) -> Result<(), CompileError> {
+        self.compile_expr(value)?;
+        self.code.emit(Opcode::GetIter);
+        self.code.emit(Opcode::LoadNone);
+        self.code.set_location(position, None);
+        let loop_start = self.code.current_offset();
+        let end_jump = self.code.emit_jump(Opcode::SendSub);
+        self.code.emit(Opcode::YieldValue);
+        self.code.emit_jump_to(Opcode::Jump, loop_start);
+        self.code.patch_jump(end_jump);
+        // On exhaustion the sub-iterator and sent value are replaced by the result
+        self.code.adjust_stack_depth(-1);
+        Ok(())
+    }
+
     /// Compiles a while loop.
     ///
     /// The bytecode structure:
@@ -1859,7 +2078,7 @@ impl<'a> Compiler<'a> {
     /// Compiles a break statement.
     ///
     /// Break exits the innermost loop and skips its else block. If inside a
-    /// try-finally, the finally block must run first.
+    /// try-finally or `with` block, the finally code must run first.
     ///
     /// The bytecode without finally:
     /// 1. Clean up exception state if inside except handler
@@ -1867,55 +2086,20 @@ impl<'a> Compiler<'a> {
     /// 3. Jump to after the else block
     ///
     /// With finally:
-    /// 1. Clean up exception state if inside except handler
-    /// 2. Pop the iterator if in a `for` loop
-    /// 3. Jump to "finally with break" path (patched when try compilation completes)
-    /// 4. That path runs finally, then jumps to after the else block
+    /// 1. Clean up exception state of except handlers inside the try-finally
+    /// 2. Jump to "finally with break" path (patched when try compilation completes)
+    /// 3. That path runs finally, then continues with the remaining cleanup (outer handlers,
+    ///    outer finally blocks, the iterator) before jumping to after the else block
+    ///
+    /// The iterator stays on the stack until the last finally has run, since a `with`
+    /// block keeps its context manager on top of it.
     fn compile_break(&mut self, position: CodeRange) -> Result<(), CompileError> {
         if self.loop_stack.is_empty() {
             return Err(CompileError::new("'break' outside loop", position));
         }
 
         let target_loop_depth = self.loop_stack.len() - 1;
-
-        // If inside except handlers, clean up ALL exception states
-        // Each nested except handler has pushed an exception onto the stack,
-        // so we need to clear/pop each one when breaking out
-        for _ in 0..self.except_handler_depth {
-            self.code.emit(Opcode::ClearException);
-            self.code.emit(Opcode::Pop); // Pop the exception value
-        }
-
-        // Pop the iterator only for `for` loops (has iterator on stack)
-        // `while` loops don't have an iterator to pop
-        if self.loop_stack[target_loop_depth].has_iterator_on_stack {
-            self.code.emit(Opcode::Pop);
-        }
-
-        // Check if we need to go through any finally blocks
-        // We need to run finally if break crosses the try boundary, i.e., if
-        // we're breaking from a loop that existed before the try started.
-        if let Some(finally_target) = self.finally_targets.last_mut()
-            && target_loop_depth < finally_target.loop_depth_at_entry
-        {
-            // Breaking from a loop that's outside (or at the start of) this try-finally,
-            // so finally must run before the break
-            let jump = self.code.emit_jump(Opcode::Jump);
-            finally_target.break_jumps.push(BreakContinueThruFinally {
-                jump,
-                target_loop_depth,
-            });
-            // Set stack depth for unreachable cleanup code (see comment below)
-            if self.except_handler_depth > 0 {
-                self.code
-                    .set_stack_depth(u16::try_from(self.except_handler_depth).unwrap_or(u16::MAX));
-            }
-            return Ok(());
-        }
-
-        // No finally to go through, jump directly to loop end
-        let jump = self.code.emit_jump(Opcode::Jump);
-        self.loop_stack[target_loop_depth].break_jumps.push(jump);
+        self.compile_loop_exit(target_loop_depth, true);
 
         // The code following this break is unreachable at runtime, but the compiler
         // will still emit cleanup code for each enclosing except handler (ClearException + Pop).
@@ -1932,45 +2116,14 @@ impl<'a> Compiler<'a> {
     ///
     /// Continue jumps back to the loop start (the ForIter instruction) which
     /// advances the iterator and either enters the next iteration or exits the loop.
-    /// If inside a try-finally, the finally block must run first.
+    /// If inside a try-finally or `with` block, the finally code must run first.
     fn compile_continue(&mut self, position: CodeRange) -> Result<(), CompileError> {
         if self.loop_stack.is_empty() {
             return Err(CompileError::new("'continue' not properly in loop", position));
         }
 
         let target_loop_depth = self.loop_stack.len() - 1;
-
-        // If inside except handlers, clean up ALL exception states
-        // Each nested except handler has pushed an exception onto the stack,
-        // so we need to clear/pop each one when continuing
-        for _ in 0..self.except_handler_depth {
-            self.code.emit(Opcode::ClearException);
-            self.code.emit(Opcode::Pop); // Pop the exception value
-        }
-
-        // Check if we need to go through any finally blocks
-        // We need to run finally if continue crosses the try boundary
-        if let Some(finally_target) = self.finally_targets.last_mut()
-            && target_loop_depth < finally_target.loop_depth_at_entry
-        {
-            // Continuing a loop that's outside (or at the start of) this try-finally,
-            // so finally must run before the continue
-            let jump = self.code.emit_jump(Opcode::Jump);
-            finally_target.continue_jumps.push(BreakContinueThruFinally {
-                jump,
-                target_loop_depth,
-            });
-            // Set stack depth for unreachable cleanup code (see comment below)
-            if self.except_handler_depth > 0 {
-                self.code
-                    .set_stack_depth(u16::try_from(self.except_handler_depth).unwrap_or(u16::MAX));
-            }
-            return Ok(());
-        }
-
-        // No finally to go through, jump directly to loop start
-        let loop_start = self.loop_stack[target_loop_depth].start;
-        self.code.emit_jump_to(Opcode::Jump, loop_start);
+        self.compile_loop_exit(target_loop_depth, false);
 
         // The code following this continue is unreachable at runtime, but the compiler
         // will still emit cleanup code for each enclosing except handler (ClearException + Pop).
@@ -1985,33 +2138,36 @@ impl<'a> Compiler<'a> {
 
     /// Compiles break or continue after a finally block has run.
     ///
-    /// Called from `compile_try` after the finally block code. Each control flow
-    /// statement may target a different loop, so we check if there's another finally
-    /// to go through or if we can jump directly to the loop's target.
-    ///
-    /// Note: All items in the list jumped to the same finally block, so they all
-    /// have the same starting point. After finally runs, we need to route each
-    /// to its target loop, potentially through more finally blocks.
+    /// Called from `compile_try` and `compile_with` after the finally code. All items in the
+    /// list jumped to the same finally block, and since break/continue only target the
+    /// innermost loop, they all target the same loop.
     fn compile_control_flow_after_finally(&mut self, items: &[BreakContinueThruFinally], is_break: bool) {
-        // All items went through the same finally, now we need to dispatch to
-        // potentially different loops. For simplicity, we assume all items in
-        // a single finally target the same loop (the innermost one at the time).
-        // This is always true since break/continue only targets the innermost loop.
         let Some(first) = items.first() else {
             return;
         };
-        let target_loop_depth = first.target_loop_depth;
+        self.compile_loop_exit(first.target_loop_depth, is_break);
+    }
 
-        // Check if there's another finally between us and the target loop
-        if let Some(finally_target) = self.finally_targets.last_mut()
+    /// Emits the jump for a break or continue, going through the innermost finally block
+    /// between here and the target loop if there is one.
+    ///
+    /// Exception state is cleaned up for the except handlers inside that finally block
+    /// (or all of them when jumping straight to the loop); the rest are cleaned up after
+    /// the finally code runs.
+    fn compile_loop_exit(&mut self, target_loop_depth: usize, is_break: bool) {
+        // Check if there's a finally between us and the target loop
+        if let Some(finally_target) = self.finally_targets.last()
             && target_loop_depth < finally_target.loop_depth_at_entry
         {
-            // Need to go through another finally
+            let handler_count = self.except_handler_depth - finally_target.except_handler_depth_at_entry;
+            self.compile_except_handler_exits(handler_count);
+
             let jump = self.code.emit_jump(Opcode::Jump);
             let jump_info = BreakContinueThruFinally {
                 jump,
                 target_loop_depth,
             };
+            let finally_target = self.finally_targets.last_mut().expect("finally target checked above");
             if is_break {
                 finally_target.break_jumps.push(jump_info);
             } else {
@@ -2021,8 +2177,14 @@ impl<'a> Compiler<'a> {
             return;
         }
 
-        // No more finally blocks, jump directly to the loop target
+        // No finally blocks, jump directly to the loop target
+        self.compile_except_handler_exits(self.except_handler_depth);
         if is_break {
+            // Pop the iterator only for `for` loops (has iterator on stack)
+            // `while` loops don't have an iterator to pop
+            if self.loop_stack[target_loop_depth].has_iterator_on_stack {
+                self.code.emit(Opcode::Pop);
+            }
             let jump = self.code.emit_jump(Opcode::Jump);
             self.loop_stack[target_loop_depth].break_jumps.push(jump);
         } else {
@@ -2032,6 +2194,18 @@ impl<'a> Compiler<'a> {
         }
     }
 
+    /// Cleans up the exception state of `count` enclosing except handlers when leaving them
+    /// with break or continue.
+    ///
+    /// Each nested except handler has pushed an exception onto the stack,
+    /// so we need to clear/pop each one.
+    fn compile_except_handler_exits(&mut self, count: usize) {
+        for _ in 0..count {
+            self.code.emit(Opcode::ClearException);
+            self.code.emit(Opcode::Pop); // Pop the exception value
+        }
+    }
+
     // ========================================================================
     // Comprehension Compilation
     // ========================================================================
@@ -2128,13 +2302,27 @@ impl<'a> Compiler<'a> {
 
         // Compile iterator expression
         self.compile_expr(&generator.iter)?;
-        self.code.emit(Opcode::GetIter);
+        let async_iter_depth = if generator.is_async {
+            self.code.emit(Opcode::GetAIter);
+            Some(self.code.stack_depth())
+        } else {
+            self.code.emit(Opcode::GetIter);
+            None
+        };
 
         // Loop start
         let loop_start = self.code.current_offset();
 
-        // FOR_ITER: advance iterator or jump to end
-        let end_jump = self.code.emit_jump(Opcode::ForIter);
+        // FOR_ITER: advance iterator or jump to end (`async for` awaits the next item instead,
+        // ending the loop on `StopAsyncIteration`)
+        let end_jump = if async_iter_depth.is_some() {
+            self.code.emit(Opcode::GetANext);
+            self.code.emit(Opcode::Await);
+            None
+        } else {
+            Some(self.code.emit_jump(Opcode::ForIter))
+        };
+        let next_end = self.code.current_offset();
 
         // Store current value to target (single variable or tuple unpacking)
         self.compile_unpack_target(&generator.target);
@@ -2159,7 +2347,11 @@ impl<'a> Compiler<'a> {
         self.code.emit_jump_to(Opcode::Jump, loop_start);
 
         // End of loop
-        self.code.patch_jump(end_jump);
+        if let Some(end_jump) = end_jump {
+            self.code.patch_jump(end_jump);
+        } else if let Some(iter_depth) = async_iter_depth {
+            self.compile_end_async_for(loop_start, next_end, iter_depth);
+        }
 
         Ok(())
     }
@@ -2340,21 +2532,499 @@ impl<'a> Compiler<'a> {
         self.code.add_const(Value::Int(marker))
     }
 
+    // ========================================================================
+    // Context Manager Compilation
+    // ========================================================================
+
+    /// Compiles a `with` statement, one item at a time.
+    ///
+    /// The context manager stays on the stack while the body runs, so `__exit__` can be
+    /// called on it however the body is left. The bytecode structure for one item is:
+    /// ```text
+    /// <context_expr>
+    /// BEFORE_WITH                    # [mgr, enter_result]
+    /// <store target> or POP          # [mgr]
+    /// <body>                         # protected range
+    /// <exit(None, None, None)>       # normal exit
+    /// JUMP end
+    /// handler:                       # [mgr, exc], exception pushed by VM
+    ///   WITH_EXCEPT_START            # [mgr, exc, exit_result]
+    ///   JUMP_IF_TRUE suppress
+    ///   RERAISE
+    /// suppress:
+    ///   CLEAR_EXCEPTION
+    ///   POP; POP
+    ///   JUMP end
+    /// <exit, then return/break/continue>  # one path for each that occurs in the body
+    /// end:
+    /// ```
+    ///
+    /// Like try-finally, the body registers a `FinallyTarget` so return/break/continue jump
+    /// to paths outside the protected range that call `__exit__` before leaving. For
+    /// `async with`, each call's result is awaited.
+    fn compile_with_items(
+        &mut self,
+        items: &[WithItem],
+        body: &[PreparedNode],
+        is_async: bool,
+    ) -> Result<(), CompileError> {
+        let Some((item, rest)) = items.split_first() else {
+            return self.compile_block(body);
+        };
+
+        self.compile_expr(&item.context_expr)?;
+        // Stack depth with the manager on the stack (for unwinding on exception)
+        let stack_depth = self.code.stack_depth();
+        self.code.set_location(item.context_expr.position, None);
+        self.code.emit_u8(Opcode::BeforeWith, u8::from(is_async));
+        if is_async {
+            self.code.emit(Opcode::Await);
+        }
+        match &item.target {
+            Some(target) => self.compile_unpack_target(target),
+            None => self.code.emit(Opcode::Pop),
+        }
+
+        self.finally_targets.push(FinallyTarget {
+            return_jumps: Vec::new(),
+            break_jumps: Vec::new(),
+            continue_jumps: Vec::new(),
+            loop_depth_at_entry: self.loop_stack.len(),
+            except_handler_depth_at_entry: self.except_handler_depth,
+        });
+
+        // === Compile body (remaining items are nested inside) ===
+        let body_start = self.code.current_offset();
+        self.compile_with_items(rest, body, is_async)?;
+        let body_end = self.code.current_offset();
+
+        // === Normal exit ===
+        self.code.set_stack_depth(stack_depth);
+        self.compile_with_exit(is_async);
+        let mut end_jumps = vec![self.code.emit_jump(Opcode::Jump)];
+
+        // === Exception handler ===
+        let handler_start = self.code.current_offset();
+        // VM pushes the exception onto the stack: [mgr, exc]
+        self.code.set_stack_depth(stack_depth + 1);
+        self.code.emit_u8(Opcode::WithExceptStart, u8::from(is_async));
+        if is_async {
+            self.code.emit(Opcode::Await);
+        }
+        let suppress_jump = self.code.emit_jump(Opcode::JumpIfTrue);
+        self.code.emit(Opcode::Reraise);
+        // __exit__ returned true: swallow the exception
+        self.code.patch_jump(suppress_jump);
+        self.code.emit(Opcode::ClearException);
+        self.code.emit(Opcode::Pop); // exception
+        self.code.emit(Opcode::Pop); // manager
+        end_jumps.push(self.code.emit_jump(Opcode::Jump));
+
+        // === Exit with return/break/continue paths ===
+        let finally_target = self.finally_targets.pop().expect("finally_targets should not be empty");
+        if !finally_target.return_jumps.is_empty() {
+            for jump in finally_target.return_jumps {
+                self.code.patch_jump(jump);
+            }
+            // Return value is on top of the manager: [mgr, value]
+            self.code.set_stack_depth(stack_depth + 1);
+            self.code.emit(Opcode::Rot2);
+            self.compile_with_exit(is_async);
+            self.compile_return();
+        }
+        if !finally_target.break_jumps.is_empty() {
+            for break_info in &finally_target.break_jumps {
+                self.code.patch_jump(break_info.jump);
+            }
+            self.code.set_stack_depth(stack_depth);
+            self.compile_with_exit(is_async);
+            self.compile_control_flow_after_finally(&finally_target.break_jumps, true);
+        }
+        if !finally_target.continue_jumps.is_empty() {
+            for continue_info in &finally_target.continue_jumps {
+                self.code.patch_jump(continue_info.jump);
+            }
+            self.code.set_stack_depth(stack_depth);
+            self.compile_with_exit(is_async);
+            self.compile_control_flow_after_finally(&finally_target.continue_jumps, false);
+        }
+
+        for jump in end_jumps {
+            self.code.patch_jump(jump);
+        }
+        self.code.set_stack_depth(stack_depth - 1);
+
+        // Body -> exception handler. Exceptions from `__exit__` itself propagate outwards.
+        self.code.add_exception_entry(ExceptionEntry::new(
+            u32::try_from(body_start).expect("bytecode offset exceeds u32"),
+            u32::try_from(body_end).expect("bytecode offset exceeds u32"),
+            u32::try_from(handler_start).expect("bytecode offset exceeds u32"),
+            stack_depth,
+        ));
+        Ok(())
+    }
+
+    /// Calls `__exit__(None, None, None)` (or awaits `__aexit__(...)`) on the manager at TOS,
+    /// discarding the result.
+    fn compile_with_exit(&mut self, is_async: bool) {
+        let exit_name = if is_async {
+            StaticStrings::DunderAexit
+        } else {
+            StaticStrings::DunderExit
+        };
+        let name_idx = u16::try_from(StringId::from(exit_name).index()).expect("name index exceeds u16");
+        self.code.emit(Opcode::LoadNone);
+        self.code.emit(Opcode::LoadNone);
+        self.code.emit(Opcode::LoadNone);
+        self.code.emit_u16_u8(Opcode::CallAttr, name_idx, 3);
+        if is_async {
+            self.code.emit(Opcode::Await);
+        }
+        self.code.emit(Opcode::Pop);
+    }
+
+    // ========================================================================
+    // Pattern Matching Compilation
+    // ========================================================================
+
+    /// Compiles a match statement.
+    ///
+    /// The subject stays on the stack while the cases are tried. Each case matches its
+    /// pattern against a copy of the subject; the pattern consumes the copy when it matches,
+    /// and failures jump to cleanup code that pops any intermediate values before falling
+    /// through to the next case. The subject is popped before a case body runs, so
+    /// `break`/`continue`/`return` in the body see the same stack as outside the match.
+    fn compile_match(&mut self, match_stmt: &Match<PreparedNode>) -> Result<(), CompileError> {
+        self.compile_expr(&match_stmt.subject)?;
+        let subject_depth = self.code.stack_depth();
+        let mut end_jumps = Vec::with_capacity(match_stmt.cases.len());
+
+        for case in &match_stmt.cases {
+            let mut fails = Vec::new();
+            self.code.emit(Opcode::Dup);
+            self.compile_pattern(&case.pattern, &mut fails)?;
+            if let Some(guard) = &case.guard {
+                self.compile_expr(guard)?;
+                self.emit_pattern_fail(Opcode::JumpIfFalse, &mut fails);
+            }
+            // Matched: discard the subject and run the body
+            self.code.emit(Opcode::Pop);
+            self.compile_block(&case.body)?;
+            end_jumps.push(self.code.emit_jump(Opcode::Jump));
+            self.compile_pattern_cleanup(fails, subject_depth);
+        }
+
+        // No case matched
+        self.code.emit(Opcode::Pop);
+        for jump in end_jumps {
+            self.code.patch_jump(jump);
+        }
+        Ok(())
+    }
+
+    /// Compiles a pattern that matches the value at TOS.
+    ///
+    /// If the pattern matches, the value is consumed and any capture names are bound.
+    /// Otherwise a jump is added to `fails`.
+    fn compile_pattern(&mut self, pattern: &Pattern, fails: &mut Vec<PatternFail>) -> Result<(), CompileError> {
+        match pattern {
+            Pattern::Wildcard => self.code.emit(Opcode::Pop),
+            Pattern::Capture(name) => self.compile_store(name),
+            Pattern::As { pattern, name } => {
+                self.code.emit(Opcode::Dup);
+                self.compile_pattern(pattern, fails)?;
+                self.compile_store(name);
+            }
+            Pattern::Value(expr) => {
+                self.compile_expr(expr)?;
+                self.code.emit(Opcode::CompareEq);
+                self.emit_pattern_fail(Opcode::JumpIfFalse, fails);
+            }
+            Pattern::Singleton(literal) => {
+                self.compile_literal(literal);
+                self.code.emit(Opcode::CompareIs);
+                self.emit_pattern_fail(Opcode::JumpIfFalse, fails);
+            }
+            Pattern::Sequence(patterns) => self.compile_sequence_pattern(patterns, fails)?,
+            Pattern::Star(_) => unreachable!("star patterns are only valid inside sequence patterns"),
+            Pattern::Mapping { keys, patterns, rest } => {
+                self.compile_mapping_pattern(keys, patterns, rest.as_ref(), fails)?;
+            }
+            Pattern::Class {
+                cls,
+                patterns,
+                kw_names,
+                kw_patterns,
+                position,
+            } => self.compile_class_pattern(cls, patterns, kw_names, kw_patterns, *position, fails)?,
+            Pattern::Or(alternatives) => self.compile_or_pattern(alternatives, fails)?,
+        }
+        Ok(())
+    }
+
+    /// Compiles a sequence pattern like `[first, *rest]`.
+    ///
+    /// Items are matched by indexing the subject, counting from the end for items after
+    /// the star, so the subject is never copied unless the star binds a name.
+    fn compile_sequence_pattern(
+        &mut self,
+        patterns: &[Pattern],
+        fails: &mut Vec<PatternFail>,
+    ) -> Result<(), CompileError> {
+        let star_index = patterns.iter().position(|p| matches!(p, Pattern::Star(_)));
+        let fixed_count = patterns.len() - usize::from(star_index.is_some());
+
+        self.code.emit(Opcode::MatchSequence);
+        self.emit_pattern_fail(Opcode::JumpIfFalse, fails);
+        self.code.emit(Opcode::GetLen);
+        self.compile_int(fixed_count);
+        if star_index.is_some() {
+            self.code.emit(Opcode::CompareGe);
+        } else {
+            self.code.emit(Opcode::CompareEq);
+        }
+        self.emit_pattern_fail(Opcode::JumpIfFalse, fails);
+
+        for (i, pattern) in patterns.iter().enumerate() {
+            let from_end = star_index.is_some_and(|star| i > star);
+            match pattern {
+                Pattern::Wildcard | Pattern::Star(None) => {}
+                Pattern::Star(Some(name)) => {
+                    // rest = list(subject[i:len-after])
+                    let after = patterns.len() - i - 1;
+                    self.code.emit(Opcode::Dup);
+                    self.compile_int(i);
+                    if after == 0 {
+                        self.code.emit(Opcode::LoadNone);
+                    } else {
+                        self.compile_negative_int(after);
+                    }
+                    self.code.emit(Opcode::LoadNone);
+                    self.code.emit(Opcode::BuildSlice);
+                    self.code.emit(Opcode::BinarySubscr);
+                    let list_id = Type::List.callable_to_u8().expect("list is callable");
+                    self.code.emit_call_builtin_type(list_id, 1);
+                    self.compile_store(name);
+                }
+                _ => {
+                    self.code.emit(Opcode::Dup);
+                    if from_end {
+                        self.compile_negative_int(patterns.len() - i);
+                    } else {
+                        self.compile_int(i);
+                    }
+                    self.code.emit(Opcode::BinarySubscr);
+                    self.compile_pattern(pattern, fails)?;
+                }
+            }
+        }
+        self.code.emit(Opcode::Pop);
+        Ok(())
+    }
+
+    /// Compiles a mapping pattern like `{'type': 'call', 'args': args, **rest}`.
+    fn compile_mapping_pattern(
+        &mut self,
+        keys: &[ExprLoc],
+        patterns: &[Pattern],
+        rest: Option<&Identifier>,
+        fails: &mut Vec<PatternFail>,
+    ) -> Result<(), CompileError> {
+        self.code.emit(Opcode::MatchMapping);
+        self.emit_pattern_fail(Opcode::JumpIfFalse, fails);
+
+        if keys.is_empty() && rest.is_none() {
+            self.code.emit(Opcode::Pop);
+            return Ok(());
+        }
+        if !keys.is_empty() {
+            // Cheap length check before looking up the keys
+            self.code.emit(Opcode::GetLen);
+            self.compile_int(keys.len());
+            self.code.emit(Opcode::CompareGe);
+            self.emit_pattern_fail(Opcode::JumpIfFalse, fails);
+        }
+
+        for key in keys {
+            self.compile_expr(key)?;
+        }
+        let key_count = u16::try_from(keys.len()).expect("too many keys in mapping pattern");
+        self.code.emit_u16(Opcode::BuildTuple, key_count);
+
+        if !keys.is_empty() {
+            // Stack: [subject, keys] -> [subject, keys, values | None]
+            self.code.emit(Opcode::MatchKeys);
+            self.code.emit(Opcode::Dup);
+            self.code.emit(Opcode::LoadNone);
+            self.code.emit(Opcode::CompareIsNot);
+            self.emit_pattern_fail(Opcode::JumpIfFalse, fails);
+            self.compile_indexed_patterns(patterns, fails)?;
+            self.code.emit(Opcode::Pop);
+        }
+
+        if let Some(rest) = rest {
+            self.code.emit(Opcode::CopyDictWithoutKeys);
+            self.compile_store(rest);
+        } else {
+            self.code.emit(Opcode::Pop);
+        }
+        self.code.emit(Opcode::Pop);
+        Ok(())
+    }
+
+    /// Compiles a class pattern like `Point(x, y=0)`.
+    fn compile_class_pattern(
+        &mut self,
+        cls: &ExprLoc,
+        patterns: &[Pattern],
+        kw_names: &[StringId],
+        kw_patterns: &[Pattern],
+        position: CodeRange,
+        fails: &mut Vec<PatternFail>,
+    ) -> Result<(), CompileError> {
+        let Ok(positional_count) = u8::try_from(patterns.len()) else {
+            return Err(CompileError::new(
+                format!("more than {} positional sub-patterns in class pattern", u8::MAX),
+                position,
+            ));
+        };
+
+        // Stack: [subject, cls, kw_names] -> [attrs | None]
+        self.compile_expr(cls)?;
+        for name_id in kw_names {
+            let idx = self.code.add_const(Value::InternString(*name_id));
+            self.code.emit_u16(Opcode::LoadConst, idx);
+        }
+        let kw_count = u16::try_from(kw_names.len()).expect("too many keyword sub-patterns");
+        self.code.emit_u16(Opcode::BuildTuple, kw_count);
+        self.code.set_location(position, None);
+        self.code.emit_u8(Opcode::MatchClass, positional_count);
+
+        self.code.emit(Opcode::Dup);
+        self.code.emit(Opcode::LoadNone);
+        self.code.emit(Opcode::CompareIsNot);
+        self.emit_pattern_fail(Opcode::JumpIfFalse, fails);
+
+        self.compile_indexed_patterns(patterns.iter().chain(kw_patterns), fails)?;
+        self.code.emit(Opcode::Pop);
+        Ok(())
+    }
+
+    /// Matches each pattern against the item at the same index of the tuple at TOS.
+    ///
+    /// The tuple is left on the stack.
+    fn compile_indexed_patterns<'p>(
+        &mut self,
+        patterns: impl IntoIterator<Item = &'p Pattern>,
+        fails: &mut Vec<PatternFail>,
+    ) -> Result<(), CompileError> {
+        for (i, pattern) in patterns.into_iter().enumerate() {
+            if matches!(pattern, Pattern::Wildcard) {
+                continue;
+            }
+            self.code.emit(Opcode::Dup);
+            self.compile_int(i);
+            self.code.emit(Opcode::BinarySubscr);
+            self.compile_pattern(pattern, fails)?;
+        }
+        Ok(())
+    }
+
+    /// Compiles an or-pattern like `'quit' | 'exit'`.
+    ///
+    /// Each alternative but the last is tried on a copy of the value; its failures are
+    /// cleaned up locally before trying the next alternative.
+    fn compile_or_pattern(
+        &mut self,
+        alternatives: &[Pattern],
+        fails: &mut Vec<PatternFail>,
+    ) -> Result<(), CompileError> {
+        let Some((last, others)) = alternatives.split_last() else {
+            return Ok(());
+        };
+        let value_depth = self.code.stack_depth();
+        let mut success_jumps = Vec::with_capacity(others.len());
+        for alternative in others {
+            let mut alternative_fails = Vec::new();
+            self.code.emit(Opcode::Dup);
+            self.compile_pattern(alternative, &mut alternative_fails)?;
+            self.code.emit(Opcode::Pop);
+            success_jumps.push(self.code.emit_jump(Opcode::Jump));
+            self.compile_pattern_cleanup(alternative_fails, value_depth);
+        }
+        self.compile_pattern(last, fails)?;
+        for jump in success_jumps {
+            self.code.patch_jump(jump);
+        }
+        Ok(())
+    }
+
+    /// Emits a conditional jump for a failed pattern check and records it in `fails`.
+    fn emit_pattern_fail(&mut self, op: Opcode, fails: &mut Vec<PatternFail>) {
+        let jump = self.code.emit_jump(op);
+        fails.push(PatternFail {
+            jump,
+            depth: self.code.stack_depth(),
+        });
+    }
+
+    /// Emits the cleanup code for pattern failures, popping down to `target_depth`.
+    ///
+    /// The pops are chained so failures at any depth share them: a failure with two extra
+    /// values on the stack enters the chain two pops before the end.
+    fn compile_pattern_cleanup(&mut self, mut fails: Vec<PatternFail>, target_depth: u16) {
+        fails.sort_by(|a, b| b.depth.cmp(&a.depth));
+        let max_depth = fails.first().map_or(target_depth, |fail| fail.depth);
+        self.code.set_stack_depth(max_depth);
+        let mut fails = fails.into_iter().peekable();
+        for depth in (target_depth..=max_depth).rev() {
+            while let Some(fail) = fails.next_if(|fail| fail.depth == depth) {
+                self.code.patch_jump(fail.jump);
+            }
+            if depth > target_depth {
+                self.code.emit(Opcode::Pop);
+            }
+        }
+        self.code.set_stack_depth(target_depth);
+    }
+
+    /// Loads a non-negative integer constant (an index or length).
+    fn compile_int(&mut self, value: usize) {
+        let value = i64::try_from(value).expect("integer exceeds i64");
+        self.compile_literal(&Literal::Int(value));
+    }
+
+    /// Loads a negative integer constant `-value` (an index from the end).
+    fn compile_negative_int(&mut self, value: usize) {
+        let value = i64::try_from(value).expect("integer exceeds i64");
+        self.compile_literal(&Literal::Int(-value));
+    }
+
     // ========================================================================
     // Exception Handling Compilation
     // ========================================================================
 
     /// Compiles a return statement, handling finally blocks properly.
     ///
-    /// If we're inside a try-finally block, the return value is kept on the stack
+    /// If we're inside a try-finally or `with` block, the return value is kept on the stack
     /// and we jump to a "finally with return" section that runs finally then returns.
     /// Otherwise, we emit a direct `ReturnValue`.
     fn compile_return(&mut self) {
-        if let Some(finally_target) = self.finally_targets.last_mut() {
+        if let Some(finally_target) = self.finally_targets.last() {
             // Inside a try-finally: jump to finally, then return
-            // Return value is already on stack
+            // Return value is already on stack, above the exceptions of any except handlers
+            // inside the try-finally - clean those up so the finally code sees its own stack
+            for _ in finally_target.except_handler_depth_at_entry..self.except_handler_depth {
+                self.code.emit(Opcode::Rot2);
+                self.code.emit(Opcode::ClearException);
+                self.code.emit(Opcode::Pop);
+            }
             let jump = self.code.emit_jump(Opcode::Jump);
-            finally_target.return_jumps.push(jump);
+            self.finally_targets
+                .last_mut()
+                .expect("finally target checked above")
+                .return_jumps
+                .push(jump);
         } else {
             // Normal return
             self.code.emit(Opcode::ReturnValue);
@@ -2409,6 +3079,7 @@ impl<'a> Compiler<'a> {
                 break_jumps: Vec::new(),
                 continue_jumps: Vec::new(),
                 loop_depth_at_entry: self.loop_stack.len(),
+                except_handler_depth_at_entry: self.except_handler_depth,
             });
         }
 
@@ -2434,7 +3105,11 @@ impl<'a> Compiler<'a> {
             // Compile exception handlers
             // handler_entry_depth = stack_depth + 1 (exception on stack)
             let handler_entry_depth = stack_depth + 1;
-            self.compile_exception_handlers(&try_block.handlers, &mut finally_jumps, handler_entry_depth)?;
+            if try_block.is_star {
+                self.compile_exception_star_handlers(&try_block.handlers, &mut finally_jumps, handler_entry_depth)?;
+            } else {
+                self.compile_exception_handlers(&try_block.handlers, &mut finally_jumps, handler_entry_depth)?;
+            }
         } else {
             // No handlers - just reraise (this only happens with try-finally)
             self.code.emit(Opcode::Reraise);
@@ -2496,9 +3171,9 @@ impl<'a> Compiler<'a> {
                 for break_info in &finally_target.break_jumps {
                     self.code.patch_jump(break_info.jump);
                 }
-                // Break already popped the iterator, so stack = stack_depth - 1
-                // (the iterator was on stack at try entry, break removed it)
-                self.code.set_stack_depth(stack_depth.saturating_sub(1));
+                // Break leaves the iterator on the stack until the finally code has run,
+                // stack = stack_depth
+                self.code.set_stack_depth(stack_depth);
                 self.compile_block(&try_block.finally)?;
                 // After finally, compile the break again (handles nested finally or direct jump)
                 self.compile_control_flow_after_finally(&finally_target.break_jumps, true);
@@ -2677,7 +3352,7 @@ impl<'a> Compiler<'a> {
 
                 // Delete exception variable (Python 3 behavior)
                 if let Some(name) = &handler.name {
-                    self.compile_delete(name);
+                    self.compile_clear_exception_var(name);
                 }
 
                 // Clear current_exception
@@ -2720,7 +3395,7 @@ impl<'a> Compiler<'a> {
 
                 // Delete exception variable
                 if let Some(name) = &handler.name {
-                    self.compile_delete(name);
+                    self.compile_clear_exception_var(name);
                 }
 
                 // Clear current_exception
@@ -2737,26 +3412,160 @@ impl<'a> Compiler<'a> {
         Ok(())
     }
 
-    /// Compiles deletion of a variable.
+    /// Compiles the `except*` handlers for a try block.
+    ///
+    /// Unlike `except`, every clause runs in turn: each one splits the part it matches off
+    /// the exception group, and runs its body with that part as the current exception.
+    /// Exceptions raised by a clause body are collected rather than propagated, and
+    /// `ReraiseStar` raises them together with the unhandled rest at the end:
+    /// ```text
+    /// DUP; BUILD_LIST 0; ROT2        # [exc, raised, rest]
+    /// # for each handler:
+    ///   <exc_type>
+    ///   CHECK_EXC_STAR_MATCH         # [exc, raised, rest, match]
+    ///   DUP; JUMP_IF_FALSE no_match
+    ///   <handler body>               # exceptions -> body_failed
+    ///   CLEAR_EXCEPTION; POP; JUMP next
+    /// body_failed:                   # [exc, raised, rest, error]
+    ///   LIST_APPEND 1; CLEAR_EXCEPTION; JUMP next
+    /// no_match:
+    ///   POP
+    /// next:
+    /// RERAISE_STAR
+    /// ```
+    ///
+    /// `break`, `continue` and `return` can't leave an `except*` body (checked by the parser),
+    /// so the bodies don't count as except handlers for their cleanup.
+    fn compile_exception_star_handlers(
+        &mut self,
+        handlers: &[ExceptHandler<PreparedNode>],
+        finally_jumps: &mut Vec<JumpLabel>,
+        handler_entry_depth: u16,
+    ) -> Result<(), CompileError> {
+        // Stack: [exception] -> [exception, raised, rest]
+        self.code.emit(Opcode::Dup);
+        self.code.emit_u16(Opcode::BuildList, 0);
+        self.code.emit(Opcode::Rot2);
+        let clause_depth = handler_entry_depth + 2;
+
+        for handler in handlers {
+            let exc_type = handler.exc_type.as_ref().expect("except* handlers always have a type");
+            self.compile_expr(exc_type)?;
+            // Stack: [exception, raised, rest, exc_type]
+            self.code.emit(Opcode::CheckExcStarMatch);
+            // Stack: [exception, raised, rest, match]
+            self.code.emit(Opcode::Dup);
+            let no_match_jump = self.code.emit_jump(Opcode::JumpIfFalse);
+
+            if let Some(name) = &handler.name {
+                self.code.emit(Opcode::Dup);
+                self.compile_store(name);
+            }
+            let body_start = self.code.current_offset();
+            self.compile_block(&handler.body)?;
+            let body_end = self.code.current_offset();
+            if let Some(name) = &handler.name {
+                self.compile_clear_exception_var(name);
+            }
+            self.code.emit(Opcode::ClearException);
+            self.code.emit(Opcode::Pop);
+            let body_done_jump = self.code.emit_jump(Opcode::Jump);
+
+            // === Exception raised by the body: add it to the raised list ===
+            let body_failed = self.code.current_offset();
+            // Stack: [exception, raised, rest, error] (pushed by VM)
+            self.code.set_stack_depth(clause_depth + 1);
+            if let Some(name) = &handler.name {
+                self.compile_clear_exception_var(name);
+            }
+            self.code.emit_u8(Opcode::ListAppend, 1);
+            self.code.emit(Opcode::ClearException);
+            let body_failed_jump = self.code.emit_jump(Opcode::Jump);
+
+            // === No match: drop the None match ===
+            self.code.patch_jump(no_match_jump);
+            self.code.set_stack_depth(clause_depth + 1);
+            self.code.emit(Opcode::Pop);
+
+            self.code.patch_jump(body_done_jump);
+            self.code.patch_jump(body_failed_jump);
+
+            self.code.add_exception_entry(ExceptionEntry::new(
+                u32::try_from(body_start).expect("bytecode offset exceeds u32"),
+                u32::try_from(body_end).expect("bytecode offset exceeds u32"),
+                u32::try_from(body_failed).expect("bytecode offset exceeds u32"),
+                clause_depth,
+            ));
+        }
+
+        // Stack: [exception, raised, rest] -> []
+        self.code.emit(Opcode::ReraiseStar);
+        finally_jumps.push(self.code.emit_jump(Opcode::Jump));
+        Ok(())
+    }
+
+    /// Compiles a single `del` target.
+    fn compile_delete_target(&mut self, target: &DeleteTarget) -> Result<(), CompileError> {
+        match target {
+            DeleteTarget::Name(ident) => {
+                // Set location to the name for proper caret in tracebacks
+                self.code.set_location(ident.position, None);
+                self.compile_delete(ident);
+            }
+            DeleteTarget::Subscript {
+                object,
+                index,
+                position,
+            } => {
+                // Stack order for DeleteSubscr: obj, index
+                self.compile_expr(object)?;
+                self.compile_expr(index)?;
+                self.code.set_location(*position, None);
+                self.code.emit(Opcode::DeleteSubscr);
+            }
+            DeleteTarget::Attr { object, attr, position } => {
+                self.compile_expr(object)?;
+                let name_id = attr.string_id().expect("DeleteAttr requires interned attr name");
+                self.code.set_location(*position, None);
+                self.code.emit_u16(
+                    Opcode::DeleteAttr,
+                    u16::try_from(name_id.index()).expect("name index exceeds u16"),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Compiles deletion of a variable, raising `NameError`/`UnboundLocalError` at runtime
+    /// if it isn't bound.
     fn compile_delete(&mut self, target: &Identifier) {
         let slot = u16::try_from(target.namespace_id().index()).expect("local slot exceeds u16");
         match target.scope {
             NameScope::Local | NameScope::LocalUnassigned => {
-                if let Ok(s) = u8::try_from(slot) {
-                    self.code.emit_u8(Opcode::DeleteLocal, s);
-                } else {
-                    // Wide variant not implemented yet
-                    todo!("DeleteLocalW for slot > 255");
-                }
+                self.code.register_local_name(slot, target.name_id);
+                self.code.emit_delete_local(slot);
             }
-            NameScope::Global | NameScope::Cell => {
-                // Delete global/cell not commonly needed
-                // For now, just store Undefined
-                self.code.emit(Opcode::LoadNone);
-                self.compile_store(target);
+            NameScope::Global => {
+                self.code.emit_u16(Opcode::DeleteGlobal, slot);
+            }
+            NameScope::Cell => {
+                self.code.register_local_name(slot, target.name_id);
+                // Convert namespace slot to cells array index
+                let cell_index = slot.saturating_sub(self.cell_base);
+                self.code.emit_u16(Opcode::DeleteCell, cell_index);
             }
         }
     }
+
+    /// Unbinds an `except ... as name` variable at the end of the handler.
+    ///
+    /// Like CPython, this stores `None` before deleting, so it can't fail even if the
+    /// handler body already deleted the name.
+    fn compile_clear_exception_var(&mut self, name: &Identifier) {
+        self.code.emit(Opcode::LoadNone);
+        self.compile_store(name);
+        self.compile_delete(name);
+    }
 }
 
 /// Error that can occur during bytecode compilation.
@@ -2810,6 +3619,22 @@ impl CompileError {
 // Operator Mapping Functions
 // ============================================================================
 
+/// Returns the number of named keyword arguments before the first `**mapping`.
+fn leading_named_kwargs(kwargs: &[KwargExpr]) -> usize {
+    kwargs
+        .iter()
+        .take_while(|kwarg| matches!(kwarg, KwargExpr::Named(_)))
+        .count()
+}
+
+/// Returns the number of elements before the first `*iterable` element.
+fn leading_unstarred(elements: &[ExprLoc]) -> usize {
+    elements
+        .iter()
+        .take_while(|elem| !matches!(elem.expr, Expr::Starred(_)))
+        .count()
+}
+
 /// Maps a binary `Operator` to its corresponding `Opcode`.
 fn operator_to_opcode(op: &Operator) -> Opcode {
     match op {
@@ -71,6 +71,18 @@ pub enum Opcode {
     StoreCell,
     /// Delete local variable. Operand: u8 slot.
     DeleteLocal,
+    /// Delete local (wide). Operand: u16 slot.
+    DeleteLocalW,
+    /// Delete from global namespace. Operand: u16 slot.
+    DeleteGlobal,
+    /// Clear closure cell. Operand: u16 slot.
+    DeleteCell,
+    /// Copy a local into the dict held in local slot 0, for code run by `exec()`.
+    /// Operands: u16 slot, u16 name_id.
+    ///
+    /// Removes the name from the dict instead if the local is unbound, and does nothing
+    /// if slot 0 doesn't hold a dict.
+    ExportLocal,
 
     // === Binary Operations (no operand) ===
     /// Add: a + b.
@@ -191,19 +203,28 @@ pub enum Opcode {
     BuildSlice,
     /// Pop iterable, pop list, extend list with iterable items.
     ///
-    /// Used for `*args` unpacking: builds a list of positional args,
-    /// then extends it with unpacked iterables.
+    /// Used for `*iterable` unpacking in calls and list/tuple displays: builds a
+    /// list of the leading items, then extends it with unpacked iterables.
     ListExtend,
     /// Pop TOS (list), push tuple containing the same elements.
     ///
     /// Used after building the args list to create the final args tuple
-    /// for `CallFunctionEx`.
+    /// for `CallFunctionEx`, and for tuple displays with `*iterable` unpacking.
     ListToTuple,
     /// Pop mapping, pop dict, update dict with mapping. Operand: u16 func_name_id.
     ///
     /// Used for `**kwargs` unpacking. The func_name_id is used for error messages
     /// when the mapping contains non-string keys.
     DictMerge,
+    /// Pop mapping, update dict below it with the mapping's items. No operand.
+    ///
+    /// Used for `**mapping` in dict displays. Unlike `DictMerge`, later keys
+    /// overwrite earlier ones and keys need not be strings.
+    DictUpdate,
+    /// Pop iterable, add all its items to the set below it. No operand.
+    ///
+    /// Used for `*iterable` in set displays.
+    SetUpdate,
 
     // === Comprehension Building ===
     /// Append TOS to list for comprehension. Operand: u8 depth (number of iterators).
@@ -231,7 +252,8 @@ pub enum Opcode {
     BinarySubscr,
     /// a[b] = c: pop value, pop index, pop obj.
     StoreSubscr,
-    // NOTE: DeleteSubscr removed - `del` statement not supported by parser
+    /// del a[b]: pop index, pop obj.
+    DeleteSubscr,
     /// Pop obj, push obj.attr. Operand: u16 name_id.
     LoadAttr,
     /// Pop module, push module.attr for `from ... import`. Operand: u16 name_id.
@@ -241,7 +263,8 @@ pub enum Opcode {
     LoadAttrImport,
     /// Pop value, pop obj, set obj.attr. Operand: u16 name_id.
     StoreAttr,
-    // NOTE: DeleteAttr removed - `del` statement not supported by parser
+    /// Pop obj, delete obj.attr. Operand: u16 name_id.
+    DeleteAttr,
 
     // === Function Calls ===
     /// Call TOS with n positional args. Operand: u8 arg_count.
@@ -321,18 +344,39 @@ pub enum Opcode {
     GetIter,
     /// Advance iterator or jump to end. Operand: i16 offset.
     ForIter,
+    /// Convert TOS to an async iterator for `async for`.
+    ///
+    /// Async generators are their own iterators; other objects have `__aiter__` called,
+    /// which may push a frame or yield a method call to the host.
+    GetAIter,
+    /// Push the awaitable for the next item of the async iterator at TOS, leaving it in place.
+    ///
+    /// Stack: [..., aiter] -> [..., aiter, awaitable]
+    /// The awaitable is awaited by a following `Await`; exhaustion raises `StopAsyncIteration`.
+    GetANext,
+    /// Exception handler ending an `async for` loop.
+    ///
+    /// Stack: [..., aiter, exc] -> [...]
+    /// Ends the loop if `exc` is `StopAsyncIteration`, otherwise re-raises it.
+    EndAsyncFor,
 
     // === Function Definition ===
     /// Create function object. Operand: u16 func_id.
     MakeFunction,
     /// Create closure. Operands: u16 func_id, u8 cell_count.
     MakeClosure,
+    /// Create a class object. Operands: u16 name_id, u8 has_base.
+    ///
+    /// Stack: [..., base?, attrs_dict] -> [..., class]
+    /// The attrs dict maps attribute and method names to their values.
+    BuildClass,
 
     // === Exception Handling ===
     // Note: No SetupTry/PopExceptHandler - we use static exception_table
     /// Raise TOS as exception.
     Raise,
-    // NOTE: RaiseFrom removed - `raise ... from ...` not supported by parser
+    /// Raise TOS1 as exception with TOS as its `__cause__` (`raise exc from cause`).
+    RaiseFrom,
     /// Re-raise current exception (bare `raise`).
     Reraise,
     /// Clear current_exception when exiting except block.
@@ -343,6 +387,35 @@ pub enum Opcode {
     /// Validates that exc_type is a valid exception type (ExcType or tuple of ExcTypes).
     /// If invalid, raises TypeError. If valid, pushes True if exception matches, else False.
     CheckExcMatch,
+    /// Split off the part of an exception group matching an `except*` clause.
+    ///
+    /// Stack: [..., rest, exc_type] -> [..., rest, match]
+    /// Validates exc_type like `CheckExcMatch`, also rejecting exception group types.
+    /// `match` is the part of `rest` that matches (None if nothing does), and `rest` is replaced
+    /// by what's left (None once everything matched). A matching exception that isn't a group
+    /// is wrapped in one. The match becomes the current exception for the clause body.
+    CheckExcStarMatch,
+    /// Finish the `except*` clauses of a try block, re-raising what wasn't handled.
+    ///
+    /// Stack: [..., exception, raised, rest] -> [...]
+    /// Also clears the current exception. `raised` is the list of exceptions raised by the
+    /// clause bodies, which are raised together with the unhandled `rest` (a single one
+    /// directly, several in a new `ExceptionGroup`). Nothing is raised if both are empty.
+    ReraiseStar,
+
+    // === Context Managers ===
+    /// Enter a `with` block. Operand: u8 is_async.
+    ///
+    /// Stack: [..., mgr] -> [..., mgr, enter_result]
+    /// Checks that the manager supports the (async) context manager protocol, then calls
+    /// `__enter__` (or `__aenter__`, whose result is awaited separately). The call may push a
+    /// frame or yield an external call to the host, like any method call.
+    BeforeWith,
+    /// Call `__exit__` (or `__aexit__`) for an exception raised in a `with` block. Operand: u8 is_async.
+    ///
+    /// Stack: [..., mgr, exc] -> [..., mgr, exc, exit_result]
+    /// Called with `(type(exc), exc, None)`; a truthy result suppresses the exception.
+    WithExceptStart,
 
     // === Return ===
     /// Return TOS from function.
@@ -351,21 +424,62 @@ pub enum Opcode {
     // === Async/Await ===
     /// Await the TOS value.
     ///
-    /// Handles `ExternalFuture`, `Coroutine`, and `GatherFuture` awaitables.
+    /// Handles `ExternalFuture`, `Coroutine`, `GatherFuture` and `AsyncGenAwaitable` awaitables.
     /// For `ExternalFuture`: if resolved, pushes result; if pending, blocks task.
     /// For `Coroutine`: validates state is `New`, then starts execution.
     /// For `GatherFuture`: spawns all coroutines as tasks and blocks until completion.
+    /// For `AsyncGenAwaitable`: resumes the async generator until its next yield.
     ///
     /// Raises `TypeError` if TOS is not awaitable.
     /// Raises `RuntimeError` if coroutine/future has already been awaited.
     Await,
 
+    // === Generators ===
+    /// Yield TOS from the current generator, suspending its frame.
+    ///
+    /// When the generator is resumed, the sent value (or None) is pushed.
+    YieldValue,
+    /// Advance the sub-iterator of a `yield from`, or jump when it is exhausted. Operand: i16 offset.
+    ///
+    /// Stack: [..., subiter, sent] -> [..., subiter, yielded]
+    /// On exhaustion: [..., subiter, sent] -> [..., result] and jump.
+    SendSub,
+
     // === Unpacking ===
     /// Unpack TOS into n values. Operand: u8 count.
     UnpackSequence,
     /// Unpack with *rest. Operands: u8 before, u8 after.
     UnpackEx,
 
+    // === Pattern Matching ===
+    /// Push whether TOS can match a sequence pattern (list, tuple, namedtuple or range).
+    ///
+    /// Stack: [..., subject] -> [..., subject, bool]
+    MatchSequence,
+    /// Push whether TOS can match a mapping pattern (a dict).
+    ///
+    /// Stack: [..., subject] -> [..., subject, bool]
+    MatchMapping,
+    /// Push the length of TOS, which is a sequence or dict that has already been matched.
+    ///
+    /// Stack: [..., subject] -> [..., subject, len]
+    GetLen,
+    /// Look up a tuple of keys in the dict below it.
+    ///
+    /// Stack: [..., subject, keys] -> [..., subject, keys, values]
+    /// `values` is a tuple of the values in key order, or None if any key is missing.
+    MatchKeys,
+    /// Copy the dict below TOS without the keys in the TOS tuple, for `**rest`.
+    ///
+    /// Stack: [..., subject, keys] -> [..., subject, rest]
+    CopyDictWithoutKeys,
+    /// Match a class pattern. Operand: u8 positional sub-pattern count.
+    ///
+    /// Stack: [..., subject, cls, kw_names] -> [..., attrs]
+    /// `attrs` is a tuple of the attributes for the positional then keyword sub-patterns,
+    /// or None if the subject isn't an instance of `cls` or lacks one of the attributes.
+    MatchClass,
+
     // === Special ===
     /// No operation (for patching/alignment).
     Nop,
@@ -405,19 +519,22 @@ impl Opcode {
     #[must_use]
     pub const fn stack_effect(self) -> Option<i16> {
         use Opcode::{
-            Await, BinaryAdd, BinaryAnd, BinaryDiv, BinaryFloorDiv, BinaryLShift, BinaryMatMul, BinaryMod, BinaryMul,
-            BinaryOr, BinaryPow, BinaryRShift, BinarySub, BinarySubscr, BinaryXor, BuildDict, BuildFString, BuildList,
-            BuildSet, BuildSlice, BuildTuple, CallAttr, CallAttrExtended, CallAttrKw, CallBuiltinFunction,
-            CallBuiltinType, CallFunction, CallFunctionExtended, CallFunctionKw, CheckExcMatch, ClearException,
-            CompareEq, CompareGe, CompareGt, CompareIn, CompareIs, CompareIsNot, CompareLe, CompareLt, CompareModEq,
-            CompareNe, CompareNotIn, DeleteLocal, DictMerge, DictSetItem, Dup, ForIter, FormatValue, GetIter,
-            InplaceAdd, InplaceAnd, InplaceDiv, InplaceFloorDiv, InplaceLShift, InplaceMod, InplaceMul, InplaceOr,
-            InplacePow, InplaceRShift, InplaceSub, InplaceXor, Jump, JumpIfFalse, JumpIfFalseOrPop, JumpIfTrue,
-            JumpIfTrueOrPop, ListAppend, ListExtend, ListToTuple, LoadAttr, LoadAttrImport, LoadCell, LoadConst,
-            LoadFalse, LoadGlobal, LoadLocal, LoadLocal0, LoadLocal1, LoadLocal2, LoadLocal3, LoadLocalW, LoadModule,
-            LoadNone, LoadSmallInt, LoadTrue, MakeClosure, MakeFunction, Nop, Pop, Raise, RaiseImportError, Reraise,
-            ReturnValue, Rot2, Rot3, SetAdd, StoreAttr, StoreCell, StoreGlobal, StoreLocal, StoreLocalW, StoreSubscr,
-            UnaryInvert, UnaryNeg, UnaryNot, UnaryPos, UnpackEx, UnpackSequence,
+            Await, BeforeWith, BinaryAdd, BinaryAnd, BinaryDiv, BinaryFloorDiv, BinaryLShift, BinaryMatMul, BinaryMod,
+            BinaryMul, BinaryOr, BinaryPow, BinaryRShift, BinarySub, BinarySubscr, BinaryXor, BuildClass, BuildDict,
+            BuildFString, BuildList, BuildSet, BuildSlice, BuildTuple, CallAttr, CallAttrExtended, CallAttrKw,
+            CallBuiltinFunction, CallBuiltinType, CallFunction, CallFunctionExtended, CallFunctionKw, CheckExcMatch,
+            CheckExcStarMatch, ClearException, CompareEq, CompareGe, CompareGt, CompareIn, CompareIs, CompareIsNot,
+            CompareLe, CompareLt, CompareModEq, CompareNe, CompareNotIn, CopyDictWithoutKeys, DeleteAttr, DeleteCell,
+            DeleteGlobal, DeleteLocal, DeleteLocalW, DeleteSubscr, DictMerge, DictSetItem, DictUpdate, Dup,
+            EndAsyncFor, ExportLocal, ForIter, FormatValue, GetAIter, GetANext, GetIter, GetLen, InplaceAdd,
+            InplaceAnd, InplaceDiv, InplaceFloorDiv, InplaceLShift, InplaceMod, InplaceMul, InplaceOr, InplacePow,
+            InplaceRShift, InplaceSub, InplaceXor, Jump, JumpIfFalse, JumpIfFalseOrPop, JumpIfTrue, JumpIfTrueOrPop,
+            ListAppend, ListExtend, ListToTuple, LoadAttr, LoadAttrImport, LoadCell, LoadConst, LoadFalse, LoadGlobal,
+            LoadLocal, LoadLocal0, LoadLocal1, LoadLocal2, LoadLocal3, LoadLocalW, LoadModule, LoadNone, LoadSmallInt,
+            LoadTrue, MakeClosure, MakeFunction, MatchClass, MatchKeys, MatchMapping, MatchSequence, Nop, Pop, Raise,
+            RaiseFrom, RaiseImportError, Reraise, ReraiseStar, ReturnValue, Rot2, Rot3, SendSub, SetAdd, SetUpdate,
+            StoreAttr, StoreCell, StoreGlobal, StoreLocal, StoreLocalW, StoreSubscr, UnaryInvert, UnaryNeg, UnaryNot,
+            UnaryPos, UnpackEx, UnpackSequence, WithExceptStart, YieldValue,
         };
         Some(match self {
             // Stack operations
@@ -432,7 +549,8 @@ impl Opcode {
             LoadLocal0 | LoadLocal1 | LoadLocal2 | LoadLocal3 => 1,
             LoadLocal | LoadLocalW | LoadGlobal | LoadCell => 1,
             StoreLocal | StoreLocalW | StoreGlobal | StoreCell => -1,
-            DeleteLocal => 0, // doesn't affect stack
+            DeleteLocal | DeleteLocalW | DeleteGlobal | DeleteCell => 0, // doesn't affect stack
+            ExportLocal => 0,                                            // reads a local, writes a dict
 
             // Binary operations: pop 2, push 1 = -1
             BinaryAdd | BinarySub | BinaryMul | BinaryDiv | BinaryFloorDiv | BinaryMod | BinaryPow | BinaryAnd
@@ -459,8 +577,8 @@ impl Opcode {
             ListExtend => -1,
             // ListToTuple: pop 1, push 1 = 0
             ListToTuple => 0,
-            // DictMerge: pop 2, push 1 = -1
-            DictMerge => -1,
+            // DictMerge/DictUpdate/SetUpdate: pop 2, push 1 = -1
+            DictMerge | DictUpdate | SetUpdate => -1,
 
             // Comprehension building - pops value, no push (stores in collection below)
             ListAppend | SetAdd => -1,
@@ -471,6 +589,8 @@ impl Opcode {
             StoreSubscr => -3,              // pop 3, push 0
             LoadAttr | LoadAttrImport => 0, // pop 1, push 1
             StoreAttr => -2,                // pop 2, push 0
+            DeleteSubscr => -2,             // pop 2, push 0
+            DeleteAttr => -1,               // pop 1, push 0
 
             // Function calls - depend on arg count
             CallFunction | CallBuiltinFunction | CallBuiltinType | CallFunctionKw | CallAttr | CallAttrKw
@@ -484,18 +604,33 @@ impl Opcode {
             // Iteration
             GetIter => 0,           // pop iterable, push iterator
             ForIter => return None, // pushes value or jumps (variable)
+            GetAIter => 0,          // pop iterable, push async iterator
+            GetANext => 1,          // push awaitable, iterator stays
+            EndAsyncFor => -2,      // pop exception and iterator
 
             // Async/await
             Await => 0, // pop awaitable, push result
 
+            // Generators
+            YieldValue => 0,        // pop yielded value, push sent value on resume
+            SendSub => return None, // replaces sent value with yielded value, or jumps (variable)
+
             // Function definition - push 1 (the function/closure)
             MakeFunction | MakeClosure => 1,
+            // Class definition - depends on whether a base is on the stack
+            BuildClass => return None,
 
             // Exception handling
-            Raise => -1,         // pop exception
-            Reraise => 0,        // no stack change (reads from exception_stack)
-            ClearException => 0, // clears exception_stack, no operand stack change
-            CheckExcMatch => 0,  // pop exc_type, push bool (net 0, but exc stays)
+            Raise => -1,            // pop exception
+            RaiseFrom => -2,        // pop exception and cause
+            Reraise => 0,           // no stack change (reads from exception_stack)
+            ClearException => 0,    // clears exception_stack, no operand stack change
+            CheckExcMatch => 0,     // pop exc_type, push bool (net 0, but exc stays)
+            CheckExcStarMatch => 0, // pop rest and exc_type, push new rest and match
+            ReraiseStar => -3,      // pop exception, raised list and rest
+
+            // Context managers - push the result of __enter__/__exit__, manager stays
+            BeforeWith | WithExceptStart => 1,
 
             // Return
             ReturnValue => -1,
@@ -503,6 +638,11 @@ impl Opcode {
             // Unpacking - depends on operand
             UnpackSequence | UnpackEx => return None,
 
+            // Pattern matching
+            MatchSequence | MatchMapping | GetLen | MatchKeys => 1, // push result, subject stays
+            CopyDictWithoutKeys => 0,                               // pop keys, push rest dict
+            MatchClass => -2,                                       // pop subject, cls, names; push attrs
+
             // Special
             Nop => 0,
 
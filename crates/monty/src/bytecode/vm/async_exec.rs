@@ -13,7 +13,7 @@ use crate::{
     asyncio::{CallId, CoroutineState, GatherItem, TaskId},
     bytecode::vm::scheduler::{PendingCallData, Scheduler, SerializedTaskFrame, TaskState},
     exception_private::{ExcType, RunError, SimpleException},
-    heap::{HeapData, HeapId},
+    heap::{DropWithHeap, HeapData, HeapId},
     intern::FunctionId,
     io::PrintWriter,
     resource::ResourceTracker,
@@ -66,6 +66,7 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     /// - `Coroutine`: validates state is New, then pushes a frame to execute it
     /// - `ExternalFuture`: blocks until resolved or yields if not ready
     /// - `GatherFuture`: spawns tasks for coroutines and tracks external futures
+    /// - `AsyncGenAwaitable`: resumes the async generator in a new frame
     ///
     /// Returns `AwaitResult` indicating what action the VM should take.
     pub(super) fn exec_get_awaitable(&mut self) -> Result<AwaitResult, RunError> {
@@ -79,12 +80,14 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
                 let heap_data_type = match self.heap.get(heap_id) {
                     HeapData::Coroutine(_) => Some(AwaitableType::Coroutine),
                     HeapData::GatherFuture(_) => Some(AwaitableType::GatherFuture),
+                    HeapData::AsyncGenAwaitable(_) => Some(AwaitableType::AsyncGen),
                     _ => None,
                 };
 
                 match heap_data_type {
                     Some(AwaitableType::Coroutine) => self.await_coroutine(heap_id, awaitable),
                     Some(AwaitableType::GatherFuture) => self.await_gather_future(heap_id, awaitable),
+                    Some(AwaitableType::AsyncGen) => self.await_async_gen(heap_id, awaitable),
                     None => {
                         // Not an awaitable type
                         let type_name = awaitable.py_type(self.heap);
@@ -545,6 +548,20 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         // Get task's gather_id before marking failed
         let gather_id = scheduler.get_task(task_id).gather_id;
 
+        // With `return_exceptions=True`, the exception is the task's result
+        if let Some(gid) = gather_id
+            && let Some(value) = self.gather_exception_result(gid, &error)
+        {
+            error.drop_with_heap(self.heap);
+            return match self.handle_task_completion(value)? {
+                AwaitResult::ValueReady(value) => {
+                    self.push(value);
+                    Ok(())
+                }
+                AwaitResult::FramePushed | AwaitResult::Yield(_) => Ok(()),
+            };
+        }
+
         // If part of a gather, propagate error to waiter
         if let Some(gid) = gather_id {
             // Get waiter and take task_ids from GatherFuture - gather is being destroyed anyway
@@ -619,6 +636,8 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
                 namespace_idx: f.namespace_idx,
                 cells: f.cells,
                 call_position: f.call_position,
+                init_instance: f.init_instance,
+                generator: f.generator,
             })
             .collect();
         let stack = std::mem::take(&mut self.stack);
@@ -676,6 +695,8 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
                         function_id: sf.function_id,
                         cells: sf.cells,
                         call_position: sf.call_position,
+                        init_instance: sf.init_instance,
+                        generator: sf.generator,
                     }
                 })
                 .collect();
@@ -767,78 +788,83 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
 
         // Check if a gather is waiting on this CallId
         if let Some((gather_id, result_idx)) = self.scheduler_mut().take_gather_waiter(call_id) {
-            // Remove from scheduler's pending_calls so it doesn't appear in get_pending_call_ids()
-            self.scheduler_mut().remove_pending_call(call_id);
-            // Store result directly in gather (move, not clone) and check completion
-            let (pending_empty, task_ids, waiter) = if let HeapData::GatherFuture(gather) = self.heap.get_mut(gather_id)
-            {
-                gather.results[result_idx] = Some(value); // Move value directly, no clone needed
-                // Remove from pending_calls
-                gather.pending_calls.retain(|&cid| cid != call_id);
-                // Take task_ids to avoid clone - we're checking completion so gather may be destroyed
-                (
-                    gather.pending_calls.is_empty(),
-                    std::mem::take(&mut gather.task_ids),
-                    gather.waiter,
-                )
-            } else {
-                (true, vec![], None)
-            };
+            self.resolve_gather_call(call_id, gather_id, result_idx, value);
+        } else {
+            // Normal resolution for single awaiter
+            self.scheduler_mut().resolve(call_id, value);
+        }
+        Ok(())
+    }
+
+    /// Stores the result of an external future a gather is waiting on, completing the
+    /// gather if it was the last item outstanding.
+    fn resolve_gather_call(&mut self, call_id: CallId, gather_id: HeapId, result_idx: usize, value: Value) {
+        // Remove from scheduler's pending_calls so it doesn't appear in get_pending_call_ids()
+        self.scheduler_mut().remove_pending_call(call_id);
+        // Store result directly in gather (move, not clone) and check completion
+        let (pending_empty, task_ids, waiter) = if let HeapData::GatherFuture(gather) = self.heap.get_mut(gather_id) {
+            gather.results[result_idx] = Some(value); // Move value directly, no clone needed
+            // Remove from pending_calls
+            gather.pending_calls.retain(|&cid| cid != call_id);
+            // Take task_ids to avoid clone - we're checking completion so gather may be destroyed
+            (
+                gather.pending_calls.is_empty(),
+                std::mem::take(&mut gather.task_ids),
+                gather.waiter,
+            )
+        } else {
+            (true, vec![], None)
+        };
+
+        // Check if gather is now complete (all external futures resolved and all tasks complete)
+        if pending_empty {
+            let all_tasks_complete = task_ids.is_empty()
+                || task_ids.iter().all(|tid| {
+                    matches!(
+                        self.scheduler().get_task(*tid).state,
+                        TaskState::Completed(_) | TaskState::Failed(_)
+                    )
+                });
+            if all_tasks_complete {
+                // Gather is complete - build result and push to waiter's stack
+                if let Some(waiter_id) = waiter {
+                    // Steal results from gather using mem::take - avoids refcount dance
+                    // (copy + inc_ref + dec_ref on gather drop). Since gather is being
+                    // destroyed, we can take ownership of the values directly.
+                    let results: Vec<Value> = if let HeapData::GatherFuture(gather) = self.heap.get_mut(gather_id) {
+                        std::mem::take(&mut gather.results)
+                            .into_iter()
+                            .map(|r| r.expect("all results should be filled when gather is complete"))
+                            .collect()
+                    } else {
+                        vec![]
+                    };
+
+                    // Create result list - if this fails, we can't do much, just skip
+                    if let Ok(list_id) = self.heap.allocate(HeapData::List(List::new(results))) {
+                        // Release the GatherFuture (results already taken, so no double-drop)
+                        self.heap.dec_ref(gather_id);
+
+                        // Push result onto waiter's stack and mark as ready
+                        // Check if the waiter's context is saved in the task or in the VM
+                        let waiter_context_in_vm = waiter_id.is_main() && !self.frames.is_empty();
 
-            // Check if gather is now complete (all external futures resolved and all tasks complete)
-            if pending_empty {
-                let all_tasks_complete = task_ids.is_empty()
-                    || task_ids.iter().all(|tid| {
-                        matches!(
-                            self.scheduler().get_task(*tid).state,
-                            TaskState::Completed(_) | TaskState::Failed(_)
-                        )
-                    });
-                if all_tasks_complete {
-                    // Gather is complete - build result and push to waiter's stack
-                    if let Some(waiter_id) = waiter {
-                        // Steal results from gather using mem::take - avoids refcount dance
-                        // (copy + inc_ref + dec_ref on gather drop). Since gather is being
-                        // destroyed, we can take ownership of the values directly.
-                        let results: Vec<Value> = if let HeapData::GatherFuture(gather) = self.heap.get_mut(gather_id) {
-                            std::mem::take(&mut gather.results)
-                                .into_iter()
-                                .map(|r| r.expect("all results should be filled when gather is complete"))
-                                .collect()
+                        if waiter_context_in_vm {
+                            // Main task's frames are in VM (external-only gather, no task switching)
+                            self.stack.push(Value::Ref(list_id));
+                            // Mark main task as ready but don't add to ready_queue
+                            self.scheduler_mut().get_task_mut(waiter_id).state = TaskState::Ready;
                         } else {
-                            vec![]
-                        };
-
-                        // Create result list - if this fails, we can't do much, just skip
-                        if let Ok(list_id) = self.heap.allocate(HeapData::List(List::new(results))) {
-                            // Release the GatherFuture (results already taken, so no double-drop)
-                            self.heap.dec_ref(gather_id);
-
-                            // Push result onto waiter's stack and mark as ready
-                            // Check if the waiter's context is saved in the task or in the VM
-                            let waiter_context_in_vm = waiter_id.is_main() && !self.frames.is_empty();
-
-                            if waiter_context_in_vm {
-                                // Main task's frames are in VM (external-only gather, no task switching)
-                                self.stack.push(Value::Ref(list_id));
-                                // Mark main task as ready but don't add to ready_queue
-                                self.scheduler_mut().get_task_mut(waiter_id).state = TaskState::Ready;
-                            } else {
-                                // Waiter's context is saved in the task (either spawned task,
-                                // or main task that was saved when switching to spawned tasks)
-                                let scheduler = self.scheduler_mut();
-                                scheduler.get_task_mut(waiter_id).stack.push(Value::Ref(list_id));
-                                scheduler.make_ready(waiter_id);
-                            }
+                            // Waiter's context is saved in the task (either spawned task,
+                            // or main task that was saved when switching to spawned tasks)
+                            let scheduler = self.scheduler_mut();
+                            scheduler.get_task_mut(waiter_id).stack.push(Value::Ref(list_id));
+                            scheduler.make_ready(waiter_id);
                         }
                     }
                 }
             }
-        } else {
-            // Normal resolution for single awaiter
-            self.scheduler_mut().resolve(call_id, value);
         }
-        Ok(())
     }
 
     /// Fails an external future with an error.
@@ -850,7 +876,14 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         let call_id = CallId::new(call_id);
 
         // Check if a gather is waiting on this CallId
-        if let Some((gather_id, _result_idx)) = self.get_or_create_scheduler().take_gather_waiter(call_id) {
+        if let Some((gather_id, result_idx)) = self.get_or_create_scheduler().take_gather_waiter(call_id) {
+            // With `return_exceptions=True`, the exception is the future's result
+            if let Some(value) = self.gather_exception_result(gather_id, &error) {
+                error.drop_with_heap(self.heap);
+                self.resolve_gather_call(call_id, gather_id, result_idx, value);
+                return;
+            }
+
             // Remove from pending_calls so it doesn't appear in get_pending_call_ids()
             // (fail_for_call handles this for the non-gather case)
             self.scheduler_mut().remove_pending_call(call_id);
@@ -915,6 +948,20 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         }
     }
 
+    /// Returns the exception to store as the result of a failed gather item, if the gather
+    /// was created with `return_exceptions=True`.
+    ///
+    /// Only catchable exceptions become results; resource errors still fail the gather.
+    fn gather_exception_result(&mut self, gather_id: HeapId, error: &RunError) -> Option<Value> {
+        let HeapData::GatherFuture(gather) = self.heap.get(gather_id) else {
+            return None;
+        };
+        match error {
+            RunError::Exc(exc) if gather.return_exceptions => self.create_exception_value(exc).ok(),
+            _ => None,
+        }
+    }
+
     /// Adds pending call data for an external function call.
     ///
     /// Called by `run_pending()` when the host chooses async resolution.
@@ -1036,4 +1083,5 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
 enum AwaitableType {
     Coroutine,
     GatherFuture,
+    AsyncGen,
 }
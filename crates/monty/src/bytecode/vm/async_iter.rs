@@ -0,0 +1,209 @@
+//! Async iteration support for the VM.
+//!
+//! An `async for` loop calls `__aiter__` once, then calls `__anext__` and awaits its result
+//! for each item until that raises `StopAsyncIteration`. The compiler covers the
+//! `GetANext`/`Await` pair with an `EndAsyncFor` exception handler, which ends the loop on
+//! `StopAsyncIteration` and re-raises anything else.
+//!
+//! Three kinds of async iterators are supported:
+//! - Async generators defined in the sandbox. `__anext__()` returns an `AsyncGenAwaitable`
+//!   that resumes the generator's frame when awaited, so the generator body runs inside the
+//!   awaiting task and can itself await coroutines and external futures.
+//! - Instances of classes defining `__aiter__` and `__anext__`.
+//! - Host-provided dataclasses listing `__aiter__` and `__anext__` among their methods. The
+//!   calls become `CallResult::MethodCall`, so the host answers each `__anext__` with an
+//!   external future and signals exhaustion by raising `StopAsyncIteration`.
+//!
+//! Differences from CPython:
+//! - Async generators have no `athrow()` method.
+//! - Leaving an `async for` loop early doesn't close the async generator it iterates.
+
+use super::{AwaitResult, VM, call::CallResult, generator::ResumeMode};
+use crate::{
+    args::ArgValues,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{HeapData, HeapId},
+    intern::{StaticStrings, StringId},
+    io::PrintWriter,
+    resource::ResourceTracker,
+    types::{AsyncGenAction, AsyncGenAwaitable, PyTrait, Type},
+    value::Value,
+};
+
+impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
+    /// Replaces the value at TOS with its async iterator, for `GetAIter`.
+    ///
+    /// Async generators are their own iterators; other objects have `__aiter__` called.
+    pub(super) fn exec_get_aiter(&mut self) -> Result<CallResult, RunError> {
+        if self.async_generator_id(self.peek()).is_some() {
+            return Ok(CallResult::Push(self.pop()));
+        }
+        let aiter: StringId = StaticStrings::DunderAiter.into();
+        if !self.tos_defines_method(aiter) {
+            let type_name = self.peek().py_type(self.heap);
+            return Err(ExcType::type_error(format!(
+                "'async for' requires an object with __aiter__ method, got {type_name}"
+            )));
+        }
+        let iterable = self.pop();
+        self.call_attr(iterable, aiter, ArgValues::Empty)
+    }
+
+    /// Pushes the awaitable for the next item of the async iterator at TOS, for `GetANext`.
+    pub(super) fn exec_get_anext(&mut self) -> Result<CallResult, RunError> {
+        if let Some(gen_id) = self.async_generator_id(self.peek()) {
+            let awaitable = self.async_gen_awaitable(gen_id, AsyncGenAction::Send(Value::None))?;
+            return Ok(CallResult::Push(awaitable));
+        }
+        let anext: StringId = StaticStrings::DunderAnext.into();
+        if !self.tos_defines_method(anext) {
+            let type_name = self.peek().py_type(self.heap);
+            return Err(ExcType::type_error(format!(
+                "'async for' received an object from __aiter__ that does not implement __anext__: {type_name}"
+            )));
+        }
+        let aiter = self.peek().clone_with_heap(self.heap);
+        self.call_attr(aiter, anext, ArgValues::Empty)
+    }
+
+    /// Ends an `async for` loop if the exception at TOS is `StopAsyncIteration`, for `EndAsyncFor`.
+    ///
+    /// Stack: `[aiter, exc]`, both are popped when the loop ends. Other exceptions are
+    /// re-raised like a bare `raise`.
+    pub(super) fn exec_end_async_for(&mut self) -> Result<(), RunError> {
+        let exc = self.pop();
+        let exhausted = matches!(
+            exc.py_type(self.heap),
+            Type::Exception(exc_type) if exc_type.is_subclass_of(ExcType::StopAsyncIteration)
+        );
+        exc.drop_with_heap(self.heap);
+        let Some(handled) = self.exception_stack.pop() else {
+            return Err(RunError::internal("EndAsyncFor: no active exception"));
+        };
+        if !exhausted {
+            return Err(self.make_reraise(handled));
+        }
+        handled.drop_with_heap(self.heap);
+        let aiter = self.pop();
+        aiter.drop_with_heap(self.heap);
+        Ok(())
+    }
+
+    /// Calls a method of an async generator: `__anext__()`, `asend()`, `aclose()` or `__aiter__()`.
+    ///
+    /// Apart from `__aiter__()`, these return an awaitable which does the work when awaited.
+    /// Takes ownership of `obj` and `args`.
+    pub(super) fn call_async_generator_method(
+        &mut self,
+        gen_id: HeapId,
+        obj: Value,
+        name_id: StringId,
+        args: ArgValues,
+    ) -> RunResult<CallResult> {
+        let action = match StaticStrings::from_string_id(name_id) {
+            Some(StaticStrings::DunderAnext) => args
+                .check_zero_args("async_generator.__anext__", self.heap)
+                .map(|()| AsyncGenAction::Send(Value::None)),
+            Some(StaticStrings::Asend) => args
+                .get_one_arg("async_generator.asend", self.heap)
+                .map(AsyncGenAction::Send),
+            Some(StaticStrings::Aclose) => args
+                .check_zero_args("async_generator.aclose", self.heap)
+                .map(|()| AsyncGenAction::Close),
+            Some(StaticStrings::DunderAiter) => {
+                return match args.check_zero_args("async_generator.__aiter__", self.heap) {
+                    Ok(()) => Ok(CallResult::Push(obj)),
+                    Err(e) => {
+                        obj.drop_with_heap(self.heap);
+                        Err(e)
+                    }
+                };
+            }
+            _ => {
+                args.drop_with_heap(self.heap);
+                Err(ExcType::attribute_error(
+                    Type::AsyncGenerator,
+                    self.interns.get_str(name_id),
+                ))
+            }
+        };
+        let result = match action {
+            Ok(action) => self.async_gen_awaitable(gen_id, action).map(CallResult::Push),
+            Err(e) => Err(e),
+        };
+        obj.drop_with_heap(self.heap);
+        result
+    }
+
+    /// Awaits the result of an async generator's `__anext__()`, `asend()` or `aclose()`.
+    ///
+    /// Resumes the generator in a new frame; the value it yields becomes the result of the
+    /// `await`, and returning raises `StopAsyncIteration`. Takes ownership of `awaitable`.
+    pub(super) fn await_async_gen(&mut self, awaitable_id: HeapId, awaitable: Value) -> Result<AwaitResult, RunError> {
+        let HeapData::AsyncGenAwaitable(data) = self.heap.get_mut(awaitable_id) else {
+            unreachable!("await_async_gen called with non-awaitable heap_id")
+        };
+        let gen_id = data.generator;
+        let action = std::mem::replace(&mut data.action, AsyncGenAction::Awaited);
+
+        // The awaitable may hold the only reference to the generator, so it's released only
+        // after the generator's frame has taken its own
+        let result = match action {
+            AsyncGenAction::Send(sent) => match self.resume_generator(gen_id, sent, ResumeMode::AsyncNext) {
+                Ok(true) => Ok(AwaitResult::FramePushed),
+                Ok(false) => Err(ExcType::stop_async_iteration()),
+                Err(e) => Err(e),
+            },
+            AsyncGenAction::Close => match self.generator_close(gen_id) {
+                Ok(CallResult::Push(value)) => Ok(AwaitResult::ValueReady(value)),
+                Ok(_) => unreachable!("generator_close either pushes a value or raises"),
+                Err(e) => Err(e),
+            },
+            AsyncGenAction::Awaited => Err(SimpleException::new_msg(
+                ExcType::RuntimeError,
+                "cannot reuse already awaited __anext__()/asend()",
+            )
+            .into()),
+        };
+        awaitable.drop_with_heap(self.heap);
+        result
+    }
+
+    /// Allocates the awaitable returned by an async generator method.
+    fn async_gen_awaitable(&mut self, gen_id: HeapId, action: AsyncGenAction) -> RunResult<Value> {
+        // The awaitable owns a reference to the generator
+        self.heap.inc_ref(gen_id);
+        let awaitable = AsyncGenAwaitable {
+            generator: gen_id,
+            action,
+        };
+        let id = self.heap.allocate(HeapData::AsyncGenAwaitable(awaitable))?;
+        Ok(Value::Ref(id))
+    }
+
+    /// Returns the heap id of `value` if it is an async generator.
+    fn async_generator_id(&self, value: &Value) -> Option<HeapId> {
+        match value {
+            Value::Ref(id) if matches!(self.heap.get(*id), HeapData::Generator(g) if g.is_async) => Some(*id),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the value at TOS has a method `name`.
+    ///
+    /// Only instances of sandbox classes and host dataclasses can define the async
+    /// iteration protocol.
+    fn tos_defines_method(&mut self, name: StringId) -> bool {
+        let &Value::Ref(id) = self.peek() else {
+            return false;
+        };
+        match self.heap.get(id) {
+            HeapData::Instance(instance) => {
+                let class_id = instance.class_id();
+                self.class_has_attr(class_id, name)
+            }
+            HeapData::Dataclass(dc) => dc.methods().contains(self.interns.get_str(name)),
+            _ => false,
+        }
+    }
+}
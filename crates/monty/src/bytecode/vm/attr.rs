@@ -2,11 +2,18 @@
 
 use super::VM;
 use crate::{
+    args::ArgValues,
+    builtins::attr_name_arg,
     bytecode::vm::CallResult,
+    defer_drop,
+    eval::CompileRequest,
     exception_private::{ExcType, RunError},
+    heap::DropWithHeap,
     intern::StringId,
     io::PrintWriter,
     resource::ResourceTracker,
+    types::PyTrait,
+    value::{EitherStr, Value},
 };
 
 impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
@@ -58,4 +65,101 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         obj.drop_with_heap(self.heap);
         result
     }
+
+    /// Deletes an attribute from the object at TOS (`del obj.attr`).
+    ///
+    /// Returns an AttributeError if the attribute cannot be deleted.
+    pub(super) fn delete_attr(&mut self, name_id: StringId) -> Result<(), RunError> {
+        let obj = self.pop();
+        let result = obj.py_del_attr(name_id, self.heap, self.interns);
+        obj.drop_with_heap(self.heap);
+        result
+    }
+
+    /// Calls `getattr(obj, name[, default])`.
+    ///
+    /// Handled by the VM rather than as a builtin since, like `obj.name`, the attribute may
+    /// be computed by the host. The default is returned in place of an `AttributeError`.
+    pub(super) fn call_getattr(&mut self, args: ArgValues) -> Result<CallResult, RunError> {
+        let values: Vec<Value> = args.into_pos_only("getattr", self.heap)?.collect();
+        let count = values.len();
+        if !(2..=3).contains(&count) {
+            values.drop_with_heap(self.heap);
+            let msg = if count < 2 {
+                format!("getattr expected at least 2 arguments, got {count}")
+            } else {
+                format!("getattr expected at most 3 arguments, got {count}")
+            };
+            return Err(ExcType::type_error(msg));
+        }
+        let mut values = values.into_iter();
+        let (Some(obj), Some(name)) = (values.next(), values.next()) else {
+            unreachable!("argument count checked above")
+        };
+        let default = values.next();
+        let this = self;
+        defer_drop!(obj, this);
+        defer_drop!(name, this);
+
+        let result = match attr_name_arg(name, this.heap, this.interns) {
+            Ok(EitherStr::Interned(name_id)) => obj.py_getattr(name_id, this.heap, this.interns),
+            // Only interned names can be attributes of any value
+            Ok(EitherStr::Heap(name)) => Err(ExcType::attribute_error(obj.py_type(this.heap), &name)),
+            Err(e) => {
+                default.drop_with_heap(this.heap);
+                return Err(e);
+            }
+        };
+        match (result, default) {
+            (Ok(result), default) => {
+                default.drop_with_heap(this.heap);
+                Ok(result.into())
+            }
+            (Err(RunError::Exc(exc)), Some(default)) if exc.exc.exc_type() == ExcType::AttributeError => {
+                Ok(CallResult::Push(default))
+            }
+            (Err(e), default) => {
+                default.drop_with_heap(this.heap);
+                Err(e)
+            }
+        }
+    }
+
+    /// Calls `setattr(obj, name, value)`.
+    ///
+    /// Handled by the VM rather than as a builtin since attributes are stored by interned
+    /// name: a name built at runtime that isn't interned yet pauses the VM with
+    /// `FrameExit::Compile` to intern it, then the call is retried by `resume_compile`.
+    pub(super) fn call_setattr(&mut self, args: ArgValues) -> Result<CallResult, RunError> {
+        let values: Vec<Value> = args.into_pos_only("setattr", self.heap)?.collect();
+        let [obj, name, value] = match <[Value; 3]>::try_from(values) {
+            Ok(values) => values,
+            Err(values) => {
+                let count = values.len();
+                values.drop_with_heap(self.heap);
+                return Err(ExcType::type_error_arg_count("setattr", 3, count));
+            }
+        };
+
+        match attr_name_arg(&name, self.heap, self.interns) {
+            Ok(EitherStr::Interned(name_id)) => {
+                name.drop_with_heap(self.heap);
+                // py_set_attr takes ownership of value and drops it on error
+                let result = obj.py_set_attr(name_id, value, self.heap, self.interns);
+                obj.drop_with_heap(self.heap);
+                result.map(|()| CallResult::Push(Value::None))
+            }
+            Ok(EitherStr::Heap(name_str)) => {
+                debug_assert!(self.pending_compile.is_empty(), "setattr() arguments already pending");
+                self.pending_compile = vec![obj, name, value];
+                Ok(CallResult::Compile(CompileRequest::Name(name_str)))
+            }
+            Err(e) => {
+                obj.drop_with_heap(self.heap);
+                name.drop_with_heap(self.heap);
+                value.drop_with_heap(self.heap);
+                Err(e)
+            }
+        }
+    }
 }
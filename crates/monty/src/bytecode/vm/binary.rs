@@ -4,11 +4,12 @@ use super::VM;
 use crate::{
     defer_drop,
     exception_private::{ExcType, RunError},
+    expressions::Operator,
     heap::HeapGuard,
     io::PrintWriter,
     resource::ResourceTracker,
-    types::PyTrait,
-    value::BitwiseOp,
+    types::{PyTrait, counter},
+    value::{BitwiseOp, Value},
 };
 
 impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
@@ -24,6 +25,11 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         let lhs = this.pop();
         defer_drop!(lhs, this);
 
+        if let Some(v) = counter::binary_op(lhs, rhs, Operator::Add, this.heap, this.interns)? {
+            this.push(v);
+            return Ok(());
+        }
+
         match lhs.py_add(rhs, this.heap, this.interns) {
             Ok(Some(v)) => {
                 this.push(v);
@@ -34,7 +40,7 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
                 let rhs_type = rhs.py_type(this.heap);
                 Err(ExcType::binary_type_error("+", lhs_type, rhs_type))
             }
-            Err(e) => Err(e.into()),
+            Err(e) => Err(e),
         }
     }
 
@@ -49,6 +55,11 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         let lhs = this.pop();
         defer_drop!(lhs, this);
 
+        if let Some(v) = counter::binary_op(lhs, rhs, Operator::Sub, this.heap, this.interns)? {
+            this.push(v);
+            return Ok(());
+        }
+
         match lhs.py_sub(rhs, this.heap) {
             Ok(Some(v)) => {
                 this.push(v);
@@ -59,7 +70,7 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
                 let rhs_type = rhs.py_type(this.heap);
                 Err(ExcType::binary_type_error("-", lhs_type, rhs_type))
             }
-            Err(e) => Err(e.into()),
+            Err(e) => Err(e),
         }
     }
 
@@ -149,7 +160,7 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         let lhs = this.pop();
         defer_drop!(lhs, this);
 
-        match lhs.py_mod(rhs, this.heap) {
+        match this.mod_values(lhs, rhs) {
             Ok(Some(v)) => {
                 this.push(v);
                 Ok(())
@@ -163,6 +174,16 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         }
     }
 
+    /// Computes `lhs % rhs`, using printf-style formatting when `lhs` is a str or bytes.
+    ///
+    /// Returns `None` when the operand types don't support `%`.
+    pub(super) fn mod_values(&mut self, lhs: &Value, rhs: &Value) -> Result<Option<Value>, RunError> {
+        match self.percent_format(lhs, rhs)? {
+            Some(formatted) => Ok(Some(formatted)),
+            None => lhs.py_mod(rhs, self.heap),
+        }
+    }
+
     /// Binary power with proper refcount handling.
     ///
     /// Uses lazy type capture: only calls `py_type()` in error paths.
@@ -192,6 +213,7 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     /// Binary bitwise operation on integers.
     ///
     /// Pops two values, performs the bitwise operation, and pushes the result.
+    /// `&` and `|` between counters are handled by `Counter` first.
     pub(super) fn binary_bitwise(&mut self, op: BitwiseOp) -> Result<(), RunError> {
         let this = self;
 
@@ -200,6 +222,18 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         let lhs = this.pop();
         defer_drop!(lhs, this);
 
+        let counter_op = match op {
+            BitwiseOp::And => Some(Operator::BitAnd),
+            BitwiseOp::Or => Some(Operator::BitOr),
+            _ => None,
+        };
+        if let Some(counter_op) = counter_op
+            && let Some(v) = counter::binary_op(lhs, rhs, counter_op, this.heap, this.interns)?
+        {
+            this.push(v);
+            return Ok(());
+        }
+
         let result = lhs.py_bitwise(rhs, op, this.heap)?;
         this.push(result);
         Ok(())
@@ -231,7 +265,11 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
             return Ok(());
         }
 
-        // Next try regular addition
+        // Next try regular addition, counters build a new counter
+        if let Some(v) = counter::binary_op(lhs, rhs, Operator::Add, this.heap, this.interns)? {
+            this.push(v);
+            return Ok(());
+        }
         if let Some(v) = lhs.py_add(rhs, this.heap, this.interns)? {
             this.push(v);
             return Ok(());
@@ -4,22 +4,40 @@
 //! functions for executing function calls. The main entry points are the `exec_*`
 //! methods which are called from the VM's main dispatch loop.
 
-use super::{CallFrame, VM};
+use super::{
+    CallFrame, VM,
+    generator::{builtin_consumes_iterable, method_consumes_iterable, module_function_consumes_iterable},
+    iter::DeferredCall,
+};
 use crate::{
     args::{ArgValues, KwargsValues},
     asyncio::Coroutine,
     builtins::{Builtins, BuiltinsFunctions},
-    exception_private::{ExcType, RunError},
+    eval::{CompileRequest, EvalMode},
+    exception_private::{ExcType, RunError, RunResult},
     heap::{DropWithHeap, Heap, HeapData, HeapId},
     intern::{ExtFunctionId, FunctionId, Interns, StaticStrings, StringId},
     io::PrintWriter,
+    modules::{
+        CallbackHost, ModuleFunctions,
+        json::{self, JsonFunctions},
+        re::{self, ReFunctions},
+    },
     os::OsFunction,
     resource::ResourceTracker,
     types::{
-        AttrCallResult, Dict, PyTrait, Type,
+        AttrCallResult, Dict, Generator, PyTrait, Type,
+        bytearray::{bytearray_fromhex, call_bytearray_method},
         bytes::{bytes_fromhex, call_bytes_method},
+        counter::counter_fromkeys,
+        date::{date_fromisoformat, date_fromordinal, date_fromtimestamp},
+        datetime::{
+            ClockRead, datetime_fromisoformat, datetime_fromordinal, datetime_fromtimestamp, datetime_strptime,
+        },
+        deque::call_deque_method,
         dict::dict_fromkeys,
         list::do_list_sort,
+        namedtuple::call_namedtuple_factory,
         str::call_str_method,
     },
     value::{EitherStr, Value},
@@ -41,6 +59,15 @@ pub(super) enum CallResult {
     ///
     /// The host executes the OS operation and resumes the VM with the result.
     OsCall(OsFunction, ArgValues),
+    /// Method call on a host-provided dataclass - VM should yield `FrameExit::MethodCall` to host.
+    ///
+    /// The arguments include the dataclass instance as the first positional argument.
+    MethodCall(StringId, ArgValues),
+    /// Code passed to `eval()`/`exec()` must be compiled, or a name passed to `setattr()`
+    /// interned - VM should yield `FrameExit::Compile`.
+    ///
+    /// The call's arguments are kept in the VM until the interns are extended.
+    Compile(CompileRequest),
 }
 
 impl From<AttrCallResult> for CallResult {
@@ -66,20 +93,30 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     /// Pops the callable and arguments from the stack, calls the function,
     /// and returns the result.
     pub(super) fn exec_call_function(&mut self, arg_count: usize) -> Result<CallResult, RunError> {
+        if let Some(result) = self.resume_iter_consumer() {
+            return result;
+        }
         let args = self.pop_n_args(arg_count);
         let callable = self.pop();
-        self.call_function(callable, args)
+        self.call_function_for_op(callable, args)
     }
 
     /// Executes `CallBuiltinFunction` opcode.
     ///
     /// Calls a builtin function directly without stack manipulation for the callable.
     /// This is an optimization that avoids constant pool lookup and stack manipulation.
-    pub(super) fn exec_call_builtin_function(&mut self, builtin_id: u8, arg_count: usize) -> Result<Value, RunError> {
+    pub(super) fn exec_call_builtin_function(
+        &mut self,
+        builtin_id: u8,
+        arg_count: usize,
+    ) -> Result<CallResult, RunError> {
+        if let Some(result) = self.resume_iter_consumer() {
+            return result;
+        }
         // Convert u8 to BuiltinsFunctions via FromRepr
         if let Some(builtin) = BuiltinsFunctions::from_repr(builtin_id) {
             let args = self.pop_n_args(arg_count);
-            builtin.call(self.heap, args, self.interns, self.print_writer)
+            self.call_function_for_op(Value::Builtin(Builtins::Function(builtin)), args)
         } else {
             Err(RunError::internal("CallBuiltinFunction: invalid builtin_id"))
         }
@@ -89,11 +126,14 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     ///
     /// Calls a builtin type constructor directly without stack manipulation for the callable.
     /// This is an optimization for type constructors like `list()`, `int()`, `str()`.
-    pub(super) fn exec_call_builtin_type(&mut self, type_id: u8, arg_count: usize) -> Result<Value, RunError> {
+    pub(super) fn exec_call_builtin_type(&mut self, type_id: u8, arg_count: usize) -> Result<CallResult, RunError> {
+        if let Some(result) = self.resume_iter_consumer() {
+            return result;
+        }
         // Convert u8 to Type via callable_from_u8
         if let Some(t) = Type::callable_from_u8(type_id) {
             let args = self.pop_n_args(arg_count);
-            t.call(self.heap, args, self.interns)
+            self.call_function_for_op(Value::Builtin(Builtins::Type(t)), args)
         } else {
             Err(RunError::internal("CallBuiltinType: invalid type_id"))
         }
@@ -108,6 +148,9 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         pos_count: usize,
         kwname_ids: Vec<StringId>,
     ) -> Result<CallResult, RunError> {
+        if let Some(result) = self.resume_iter_consumer() {
+            return result;
+        }
         let kw_count = kwname_ids.len();
 
         // Pop keyword values (TOS is last kwarg value)
@@ -134,7 +177,7 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
             }
         };
 
-        self.call_function(callable, args)
+        self.call_function_for_op(callable, args)
     }
 
     /// Executes `CallAttr` opcode.
@@ -142,9 +185,12 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     /// Pops the object and arguments from the stack, calls the attribute,
     /// and returns a `CallResult` which may indicate an OS or external call.
     pub(super) fn exec_call_attr(&mut self, name_id: StringId, arg_count: usize) -> Result<CallResult, RunError> {
+        if let Some(result) = self.resume_iter_consumer() {
+            return result;
+        }
         let args = self.pop_n_args(arg_count);
         let obj = self.pop();
-        self.call_attr(obj, name_id, args)
+        self.call_attr_for_op(obj, name_id, args)
     }
 
     /// Executes `CallAttrKw` opcode.
@@ -158,6 +204,9 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         pos_count: usize,
         kwname_ids: Vec<StringId>,
     ) -> Result<CallResult, RunError> {
+        if let Some(result) = self.resume_iter_consumer() {
+            return result;
+        }
         let kw_count = kwname_ids.len();
 
         // Pop keyword values (TOS is last kwarg value)
@@ -184,7 +233,7 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
             }
         };
 
-        self.call_attr(obj, name_id, args)
+        self.call_attr_for_op(obj, name_id, args)
     }
 
     /// Executes `CallFunctionExtended` opcode.
@@ -229,6 +278,38 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     // Internal Call Helpers
     // ========================================================================
 
+    /// Calls `callable` for a call opcode.
+    ///
+    /// Builtins and module functions that consume iterables get the items of generator and
+    /// `map()`-style iterator arguments in steps driven by the run loop (see `iter`), so the
+    /// code producing the items can call external functions.
+    fn call_function_for_op(&mut self, callable: Value, args: ArgValues) -> Result<CallResult, RunError> {
+        let consumes = match &callable {
+            // `next()` resumes a generator in the run loop itself (see `call_next_generator`)
+            Value::Builtin(Builtins::Function(BuiltinsFunctions::Next)) => {
+                matches!(&args, ArgValues::One(v) | ArgValues::Two(v, _) if self.is_calling_iterator(v))
+            }
+            Value::Builtin(builtin) => builtin_consumes_iterable(*builtin),
+            Value::ModuleFunction(mf) => module_function_consumes_iterable(*mf),
+            _ => false,
+        };
+        if consumes && self.has_stepped_arg(&args) {
+            self.call_with_iter_steps(DeferredCall::Function(callable), args)
+        } else {
+            self.call_function(callable, args)
+        }
+    }
+
+    /// Calls method `name_id` of `obj` for a call opcode, getting the items of iterator
+    /// arguments to methods like `str.join()` in steps driven by the run loop.
+    fn call_attr_for_op(&mut self, obj: Value, name_id: StringId, args: ArgValues) -> Result<CallResult, RunError> {
+        if method_consumes_iterable(name_id) && self.has_stepped_arg(&args) {
+            self.call_with_iter_steps(DeferredCall::Method(obj, name_id), args)
+        } else {
+            self.call_attr(obj, name_id, args)
+        }
+    }
+
     /// Pops n arguments from the stack and wraps them in `ArgValues`.
     fn pop_n_args(&mut self, n: usize) -> ArgValues {
         match n {
@@ -258,10 +339,69 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     ///
     /// Special handling: `list.sort(key=...)` is intercepted here to allow calling
     /// builtin key functions with VM access.
-    fn call_attr(&mut self, obj: Value, name_id: StringId, args: ArgValues) -> Result<CallResult, RunError> {
+    ///
+    /// Methods of user-defined classes, instances and `super()` proxies are looked up
+    /// as attributes (binding `self`) and then called like any other callable.
+    /// Methods of host-provided dataclasses become `CallResult::MethodCall` for the host to run.
+    pub(super) fn call_attr(&mut self, obj: Value, name_id: StringId, args: ArgValues) -> Result<CallResult, RunError> {
         let attr = EitherStr::Interned(name_id);
 
+        // Methods like `list.extend()` and `str.join()` can't run a generator themselves
+        let args = if method_consumes_iterable(name_id) && self.args_contain_iterator(&args) {
+            match self.iterator_args_to_lists(args) {
+                Ok(args) => args,
+                Err(e) => {
+                    obj.drop_with_heap(self.heap);
+                    return Err(e);
+                }
+            }
+        } else {
+            args
+        };
+
         match obj {
+            // str.format() formats instances with their special methods, which needs the VM
+            _ if (name_id == StaticStrings::Format || name_id == StaticStrings::FormatMap) && obj.is_str(self.heap) => {
+                let template = obj
+                    .as_either_str(self.heap)
+                    .expect("value checked to be a str")
+                    .as_str(self.interns)
+                    .to_owned();
+                obj.drop_with_heap(self.heap);
+                self.call_str_format(&template, name_id, args).map(CallResult::Push)
+            }
+            Value::Ref(heap_id)
+                if matches!(
+                    self.heap.get(heap_id),
+                    HeapData::Instance(_) | HeapData::ClassObject(_) | HeapData::SuperProxy(_)
+                ) =>
+            {
+                let method = obj.py_getattr(name_id, self.heap, self.interns);
+                obj.drop_with_heap(self.heap);
+                match method {
+                    Ok(AttrCallResult::Value(method)) => self.call_function(method, args),
+                    Ok(_) => unreachable!("user-defined objects only have plain attributes"),
+                    Err(e) => {
+                        args.drop_with_heap(self.heap);
+                        Err(e)
+                    }
+                }
+            }
+            Value::Ref(heap_id) if matches!(self.heap.get(heap_id), HeapData::Generator(g) if g.is_async) => {
+                self.call_async_generator_method(heap_id, obj, name_id, args)
+            }
+            Value::Ref(heap_id) if matches!(self.heap.get(heap_id), HeapData::Generator(_)) => {
+                self.call_generator_method(heap_id, obj, name_id, args)
+            }
+            Value::Ref(heap_id)
+                if matches!(
+                    self.heap.get(heap_id),
+                    HeapData::Dataclass(dc) if dc.methods().contains(self.interns.get_str(name_id))
+                ) =>
+            {
+                // Methods of host dataclasses are implemented by the host, `self` is passed along
+                Ok(CallResult::MethodCall(name_id, args.prepend(obj)))
+            }
             Value::Ref(heap_id) => {
                 // Check for list.sort - needs special handling for key functions
                 if name_id == StaticStrings::Sort && matches!(self.heap.get(heap_id), HeapData::List(_)) {
@@ -269,6 +409,49 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
                     obj.drop_with_heap(self.heap);
                     return result.map(|()| CallResult::Push(Value::None));
                 }
+                // json.dumps() and re.sub() are called through the VM so `default=` and replacement
+                // functions can run Python code
+                if (name_id == StaticStrings::Dumps || name_id == StaticStrings::Sub || name_id == StaticStrings::Subn)
+                    && matches!(self.heap.get(heap_id), HeapData::Module(_))
+                {
+                    let function = obj.py_getattr(name_id, self.heap, self.interns);
+                    obj.drop_with_heap(self.heap);
+                    return match function {
+                        Ok(AttrCallResult::Value(function)) => self.call_function(function, args),
+                        Ok(_) => unreachable!("module attributes are plain values"),
+                        Err(e) => {
+                            args.drop_with_heap(self.heap);
+                            Err(e)
+                        }
+                    };
+                }
+                // Pattern methods create matches that refer to the pattern, and sub() may call Python code
+                if matches!(self.heap.get(heap_id), HeapData::RePattern(_)) {
+                    let interns = self.interns;
+                    let result = re::call_pattern_method(self, heap_id, name_id, args, interns);
+                    obj.drop_with_heap(self.heap);
+                    return result.map(CallResult::Push);
+                }
+                // Bytearray methods copy arguments that alias the bytearray before it leaves the heap
+                if matches!(self.heap.get(heap_id), HeapData::ByteArray(_)) {
+                    let result = call_bytearray_method(heap_id, &attr, args, self.heap, self.interns);
+                    obj.drop_with_heap(self.heap);
+                    return result.map(CallResult::Push);
+                }
+                // Deque methods copy arguments that alias the deque before it leaves the heap
+                if matches!(self.heap.get(heap_id), HeapData::Deque(_)) {
+                    let result = call_deque_method(heap_id, &attr, args, self.heap, self.interns);
+                    obj.drop_with_heap(self.heap);
+                    return result.map(CallResult::Push);
+                }
+                // Dict and set methods can't call `__eq__` to find keys that are instances
+                let args = match self.canonical_key_arg(heap_id, name_id, args) {
+                    Ok(args) => args,
+                    Err(e) => {
+                        obj.drop_with_heap(self.heap);
+                        return Err(e);
+                    }
+                };
                 // Call the method on the heap object using call_attr_raw to support OS/external calls
                 let result = self.heap.call_attr_raw(heap_id, &attr, args, self.interns);
                 obj.drop_with_heap(self.heap);
@@ -286,6 +469,12 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
                 call_bytes_method(b, name_id, args, self.heap, self.interns).map(CallResult::Push)
             }
             Value::Builtin(Builtins::Type(t)) => {
+                // Reading the current time (e.g. `datetime.now()`) asks the host for a timestamp,
+                // which is converted by `resume()` once the host answers
+                if ClockRead::is_clock_method(t, name_id) {
+                    self.pending_clock = Some(ClockRead::from_call(t, name_id, args, self.heap, self.interns)?);
+                    return Ok(CallResult::OsCall(OsFunction::Time, ArgValues::Empty));
+                }
                 // Handle classmethods on type objects like dict.fromkeys()
                 call_type_method(t, name_id, args, self.heap, self.interns).map(CallResult::Push)
             }
@@ -305,15 +494,43 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     /// - `Value::ModuleFunction`: calls module function directly, returns `Push`
     /// - `Value::ExtFunction`: returns `External` for caller to execute
     /// - `Value::DefFunction`: pushes a new frame, returns `FramePushed`
-    /// - `Value::Ref`: checks for closure/function on heap
-    fn call_function(&mut self, callable: Value, args: ArgValues) -> Result<CallResult, RunError> {
+    /// - `Value::Ref`: checks for closure/function/class/bound method on heap
+    pub(super) fn call_function(&mut self, callable: Value, args: ArgValues) -> Result<CallResult, RunError> {
         match callable {
+            Value::Builtin(Builtins::Function(BuiltinsFunctions::Next)) if matches!(&args, ArgValues::One(v) | ArgValues::Two(v, _) if self.is_generator(v)) =>
+            {
+                // Resume the generator in the run loop rather than a nested run
+                self.call_next_generator(args)
+            }
+            // eval() and exec() run in the caller's scope, so they need the VM
+            Value::Builtin(Builtins::Function(BuiltinsFunctions::Eval)) => self.call_eval(EvalMode::Eval, args),
+            Value::Builtin(Builtins::Function(BuiltinsFunctions::Exec)) => self.call_eval(EvalMode::Exec, args),
+            // getattr() may need the host to compute the attribute, like `obj.name`
+            Value::Builtin(Builtins::Function(BuiltinsFunctions::Getattr)) => self.call_getattr(args),
+            // setattr() may need the VM to pause to intern a new attribute name
+            Value::Builtin(Builtins::Function(BuiltinsFunctions::Setattr)) => self.call_setattr(args),
             Value::Builtin(builtin) => {
-                let result = builtin.call(self.heap, args, self.interns, self.print_writer)?;
+                let result = self.call_builtin(builtin, args)?;
                 Ok(CallResult::Push(result))
             }
+            // json.dumps() calls `default=` for values it can't serialize, which needs the VM
+            Value::ModuleFunction(ModuleFunctions::Json(JsonFunctions::Dumps)) => {
+                let interns = self.interns;
+                json::dumps(self, args, interns).map(CallResult::Push)
+            }
+            // re.sub() and re.subn() call replacement functions, which needs the VM
+            Value::ModuleFunction(ModuleFunctions::Re(function @ (ReFunctions::Sub | ReFunctions::Subn))) => {
+                let interns = self.interns;
+                re::call_function(self, function, args, interns).map(CallResult::Push)
+            }
             Value::ModuleFunction(mf) => {
-                let result = mf.call(self.heap, args)?;
+                // Functions like `math.fsum()` can't run a generator themselves
+                let args = if module_function_consumes_iterable(mf) && self.args_contain_iterator(&args) {
+                    self.iterator_args_to_lists(args)?
+                } else {
+                    args
+                };
+                let result = mf.call(self.heap, args, self.interns)?;
                 Ok(result.into())
             }
             Value::ExtFunction(ext_id) => {
@@ -335,7 +552,8 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         }
     }
 
-    /// Handles calling a heap-allocated callable (closure or function with defaults).
+    /// Handles calling a heap-allocated callable (closure, function with defaults,
+    /// user-defined class, namedtuple class or bound method).
     ///
     /// Uses a two-phase approach to avoid borrow conflicts:
     /// 1. Copy data without incrementing refcounts
@@ -357,6 +575,24 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
                 let cloned_defaults: Vec<Value> = defaults.iter().map(Value::copy_for_extend).collect();
                 (*fid, Vec::new(), cloned_defaults)
             }
+            HeapData::ClassObject(_) => return self.call_class(heap_id, callable, args),
+            HeapData::NamedTupleFactory(_) => {
+                let result = call_namedtuple_factory(heap_id, args, self.heap, self.interns);
+                callable.drop_with_heap(self.heap);
+                return result.map(CallResult::Push);
+            }
+            HeapData::BoundMethod(method) => {
+                // Call the underlying function with the bound object prepended
+                let func = method.func().copy_for_extend();
+                let self_value = method.self_value().copy_for_extend();
+                for value in [&func, &self_value] {
+                    if let Value::Ref(id) = value {
+                        self.heap.inc_ref(*id);
+                    }
+                }
+                callable.drop_with_heap(self.heap);
+                return self.call_function(func, args.prepend(self_value));
+            }
             _ => {
                 callable.drop_with_heap(self.heap);
                 args.drop_with_heap(self.heap);
@@ -516,7 +752,7 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     }
 
     /// Builds `ArgValues` from positional args only.
-    fn build_args_positional_only(copied_args: Vec<Value>) -> ArgValues {
+    pub(super) fn build_args_positional_only(copied_args: Vec<Value>) -> ArgValues {
         match copied_args.len() {
             0 => ArgValues::Empty,
             1 => ArgValues::One(copied_args.into_iter().next().unwrap()),
@@ -624,7 +860,11 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         // Get function info (interns is a shared reference so no conflict)
         let func = self.interns.get_function(func_id);
 
-        if func.is_async {
+        if func.is_generator {
+            // Generator function (sync or async): create a Generator; the body runs when it's iterated
+            let is_async = func.is_async;
+            self.create_generator(func_id, cells, defaults, args, is_async)
+        } else if func.is_async {
             // Async function: create a Coroutine instead of pushing a frame
             self.create_coroutine(func_id, cells, defaults, args)
         } else {
@@ -645,6 +885,43 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         defaults: Vec<Value>,
         args: ArgValues,
     ) -> Result<CallResult, RunError> {
+        let (namespace, frame_cells) = self.bind_unregistered_namespace(func_id, cells, defaults, args)?;
+        let coroutine = Coroutine::new(func_id, namespace, frame_cells);
+        let coroutine_id = self.heap.allocate(HeapData::Coroutine(coroutine))?;
+
+        Ok(CallResult::Push(Value::Ref(coroutine_id)))
+    }
+
+    /// Creates a Generator for a generator function call.
+    ///
+    /// Like coroutines, arguments are bound at call time and the body only starts
+    /// running when the generator is first resumed.
+    fn create_generator(
+        &mut self,
+        func_id: FunctionId,
+        cells: &[HeapId],
+        defaults: Vec<Value>,
+        args: ArgValues,
+        is_async: bool,
+    ) -> Result<CallResult, RunError> {
+        let (namespace, frame_cells) = self.bind_unregistered_namespace(func_id, cells, defaults, args)?;
+        let generator = Generator::new(func_id, namespace, frame_cells, is_async);
+        let generator_id = self.heap.allocate(HeapData::Generator(generator))?;
+
+        Ok(CallResult::Push(Value::Ref(generator_id)))
+    }
+
+    /// Builds a function's namespace without registering it with `Namespaces`.
+    ///
+    /// Used for coroutines and generators, whose frames start later. Returns the namespace
+    /// and the HeapIds of the frame's cells.
+    fn bind_unregistered_namespace(
+        &mut self,
+        func_id: FunctionId,
+        cells: &[HeapId],
+        defaults: Vec<Value>,
+        args: ArgValues,
+    ) -> Result<(Vec<Value>, Vec<HeapId>), RunError> {
         let func = self.interns.get_function(func_id);
 
         // 1. Create namespace vector (not registered with Namespaces)
@@ -673,7 +950,7 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
             default.drop_with_heap(self.heap);
         }
 
-        // Track created cell HeapIds for the coroutine
+        // Track created cell HeapIds for the frame
         let mut frame_cells: Vec<HeapId> = Vec::with_capacity(func.cell_var_count + cells.len());
 
         // 3. Create cells for variables captured by nested functions
@@ -706,18 +983,14 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
             namespace.resize_with(func.namespace_size, || Value::Undefined);
         }
 
-        // 6. Create Coroutine on heap
-        let coroutine = Coroutine::new(func_id, namespace, frame_cells);
-        let coroutine_id = self.heap.allocate(HeapData::Coroutine(coroutine))?;
-
-        Ok(CallResult::Push(Value::Ref(coroutine_id)))
+        Ok((namespace, frame_cells))
     }
 
     /// Calls a sync function by pushing a new frame.
     ///
     /// Sets up the function's namespace with bound arguments, cell variables,
     /// and free variables (captured from enclosing scope for closures).
-    fn call_sync_function(
+    pub(super) fn call_sync_function(
         &mut self,
         func_id: FunctionId,
         cells: &[HeapId],
@@ -802,10 +1075,17 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     }
 }
 
+impl<T: ResourceTracker, P: PrintWriter> CallbackHost<T> for VM<'_, T, P> {
+    fn call_one(&mut self, function: &Value, arg: Value) -> RunResult<Value> {
+        let function = function.clone_with_heap(self.heap);
+        self.call_sync(function, ArgValues::One(arg))
+    }
+}
+
 /// Dispatches a classmethod call on a type object.
 ///
-/// Handles classmethods like `dict.fromkeys()` and `bytes.fromhex()` that are
-/// called on the type itself rather than on an instance.
+/// Handles classmethods like `dict.fromkeys()`, `bytes.fromhex()` and `date.fromisoformat()`
+/// that are called on the type itself rather than on an instance.
 fn call_type_method(
     t: Type,
     method_id: StringId,
@@ -814,8 +1094,21 @@ fn call_type_method(
     interns: &Interns,
 ) -> Result<Value, RunError> {
     match (t, method_id) {
-        (Type::Dict, m) if m == StaticStrings::Fromkeys => return dict_fromkeys(args, heap, interns),
+        (Type::Dict | Type::OrderedDict, m) if m == StaticStrings::Fromkeys => {
+            return dict_fromkeys(args, heap, interns);
+        }
+        (Type::Counter, m) if m == StaticStrings::Fromkeys => return counter_fromkeys(args, heap),
         (Type::Bytes, m) if m == StaticStrings::Fromhex => return bytes_fromhex(args, heap, interns),
+        (Type::ByteArray, m) if m == StaticStrings::Fromhex => return bytearray_fromhex(args, heap, interns),
+        (Type::Date, m) if m == StaticStrings::Fromisoformat => return date_fromisoformat(args, heap, interns),
+        (Type::Date, m) if m == StaticStrings::Fromordinal => return date_fromordinal(args, heap),
+        (Type::Date, m) if m == StaticStrings::Fromtimestamp => return date_fromtimestamp(args, heap),
+        (Type::DateTime, m) if m == StaticStrings::Fromisoformat => return datetime_fromisoformat(args, heap, interns),
+        (Type::DateTime, m) if m == StaticStrings::Fromordinal => return datetime_fromordinal(args, heap),
+        (Type::DateTime, m) if m == StaticStrings::Fromtimestamp => {
+            return datetime_fromtimestamp(args, heap, interns);
+        }
+        (Type::DateTime, m) if m == StaticStrings::Strptime => return datetime_strptime(args, heap, interns),
         _ => {}
     }
     // Other types or unknown methods - report actual type name, not 'type'
@@ -0,0 +1,775 @@
+//! User-defined class support for the VM.
+//!
+//! Covers the `BuildClass` opcode, calling a class to create an instance, and dispatching
+//! special methods (`__repr__`, `__str__`, `__eq__`, `__hash__`, `__bool__`, `__len__` and the
+//! ordering methods) from operators and builtins.
+//!
+//! Special methods need their result in the middle of an operation, so they are run to
+//! completion by [`VM::call_sync`], which executes a nested run of the VM loop.
+//!
+//! Dicts and sets can't call `__hash__` or `__eq__` themselves. The result of `__hash__` is
+//! cached as the hash of the instance's heap entry once `__init__` returns and whenever the VM
+//! hashes the instance, and the VM replaces keys that are instances with the equal key already
+//! stored in the container (see [`VM::canonical_key`]) before builtin lookups.
+
+use super::{CallResult, FrameExit, VM, compare::OrdOp, generator::builtin_consumes_iterable};
+use crate::{
+    args::{ArgPosIter, ArgValues, KwargsValues},
+    builtins::{Builtins, BuiltinsFunctions, format_spec_arg},
+    eval::CompileRequest,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    fstring::ascii_escape,
+    heap::{DropWithHeap, HeapData, HeapId},
+    intern::{StaticStrings, StringId},
+    io::PrintWriter,
+    resource::{ResourceError, ResourceTracker},
+    types::{
+        ClassObject, Instance, PyTrait, Type,
+        class::{class_name, lookup_class_attr},
+        str::allocate_string,
+    },
+    value::Value,
+};
+
+/// Maximum nesting depth of [`VM::call_sync`].
+///
+/// Each level recurses on the native stack, so this is deliberately far lower than the
+/// Python recursion limit.
+const MAX_NESTED_CALLS: usize = 200;
+
+impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
+    /// Executes the `BuildClass` opcode.
+    ///
+    /// Pops the class attributes dict (and the base class if `has_base`) and pushes the
+    /// new class object. `object` as a base is the same as no base.
+    pub(super) fn build_class(&mut self, name_id: StringId, has_base: bool) -> RunResult<()> {
+        let attrs_value = self.pop();
+        let base = if has_base { Some(self.pop()) } else { None };
+
+        let base_id = match base {
+            None | Some(Value::Builtin(Builtins::Type(Type::Object))) => None,
+            Some(base) => {
+                // mut needed for dec_ref_forget when ref-count-panic feature is enabled
+                #[cfg_attr(not(feature = "ref-count-panic"), expect(unused_mut))]
+                let mut base = base;
+                match base {
+                    Value::Ref(id) if matches!(self.heap.get(id), HeapData::ClassObject(_)) => {
+                        // The class takes over the reference to its base
+                        #[cfg(feature = "ref-count-panic")]
+                        base.dec_ref_forget();
+                        Some(id)
+                    }
+                    _ => {
+                        let is_builtin_type = matches!(base, Value::Builtin(Builtins::Type(_) | Builtins::ExcType(_)));
+                        base.drop_with_heap(self.heap);
+                        attrs_value.drop_with_heap(self.heap);
+                        return Err(if is_builtin_type {
+                            ExcType::type_error("subclassing builtin types is not supported")
+                        } else {
+                            ExcType::type_error("bases must be types")
+                        });
+                    }
+                }
+            }
+        };
+
+        // Move the entries out of the dict built by `BuildDict`, then release the empty dict
+        let Value::Ref(attrs_id) = attrs_value else {
+            return Err(RunError::internal("BuildClass: expected attrs dict on stack"));
+        };
+        let HeapData::Dict(dict) = self.heap.get_mut(attrs_id) else {
+            return Err(RunError::internal("BuildClass: expected attrs dict on stack"));
+        };
+        let mut attrs = std::mem::take(dict);
+        attrs_value.drop_with_heap(self.heap);
+
+        // Like CPython, a class defining `__eq__` but not `__hash__` has unhashable instances
+        if attrs.get_by_str("__eq__", self.heap, self.interns).is_some()
+            && attrs.get_by_str("__hash__", self.heap, self.interns).is_none()
+        {
+            attrs
+                .set(StaticStrings::DunderHash.into(), Value::None, self.heap, self.interns)?
+                .drop_with_heap(self.heap);
+        }
+
+        let class = ClassObject::new(name_id, base_id, attrs);
+        let class_id = self.heap.allocate(HeapData::ClassObject(class))?;
+        self.push(Value::Ref(class_id));
+        Ok(())
+    }
+
+    /// Calls a user-defined class, creating a new instance.
+    ///
+    /// If the class defines `__init__`, it is called with the new instance prepended to
+    /// the arguments. When `__init__` runs in a new frame, the frame records the instance
+    /// so that returning from it produces the instance (see `finish_init`).
+    pub(super) fn call_class(
+        &mut self,
+        class_id: HeapId,
+        callable: Value,
+        args: ArgValues,
+    ) -> Result<CallResult, RunError> {
+        let init = lookup_class_attr(class_id, "__init__", self.heap, self.interns);
+        if init.is_none() && !matches!(args, ArgValues::Empty) {
+            let name = class_name(class_id, self.heap, self.interns);
+            let err = ExcType::type_error(format!("{name}() takes no arguments"));
+            callable.drop_with_heap(self.heap);
+            args.drop_with_heap(self.heap);
+            return Err(err);
+        }
+
+        // The instance takes over the callable's reference to the class
+        #[cfg_attr(not(feature = "ref-count-panic"), expect(unused_mut))]
+        let mut callable = callable;
+        let instance_id = match self.heap.allocate(HeapData::Instance(Instance::new(class_id))) {
+            Ok(id) => id,
+            Err(e) => {
+                callable.drop_with_heap(self.heap);
+                args.drop_with_heap(self.heap);
+                init.drop_with_heap(self.heap);
+                return Err(e.into());
+            }
+        };
+        #[cfg(feature = "ref-count-panic")]
+        callable.dec_ref_forget();
+
+        let Some(init) = init else {
+            let instance = Value::Ref(instance_id);
+            self.cache_instance_hash(&instance);
+            return Ok(CallResult::Push(instance));
+        };
+
+        self.heap.inc_ref(instance_id);
+        match self.call_function(init, args.prepend(Value::Ref(instance_id))) {
+            Ok(CallResult::FramePushed) => {
+                self.current_frame_mut().init_instance = Some(instance_id);
+                Ok(CallResult::FramePushed)
+            }
+            Ok(CallResult::Push(value)) => self.finish_init(value, instance_id).map(CallResult::Push),
+            Ok(CallResult::External(_, args) | CallResult::OsCall(_, args) | CallResult::MethodCall(_, args)) => {
+                args.drop_with_heap(self.heap);
+                self.heap.dec_ref(instance_id);
+                Err(ExcType::type_error("__init__ must be a Python function"))
+            }
+            Ok(CallResult::Compile(_)) => {
+                self.drop_pending_compile();
+                self.heap.dec_ref(instance_id);
+                Err(ExcType::type_error("__init__ must be a Python function"))
+            }
+            Err(e) => {
+                self.heap.dec_ref(instance_id);
+                Err(e)
+            }
+        }
+    }
+
+    /// Completes a class call once `__init__` has returned `value`.
+    ///
+    /// Takes ownership of `value` and of one reference to the instance, returning the
+    /// instance, or a `TypeError` if `__init__` returned something other than `None`.
+    pub(super) fn finish_init(&mut self, value: Value, instance_id: HeapId) -> RunResult<Value> {
+        if matches!(value, Value::None) {
+            let instance = Value::Ref(instance_id);
+            self.cache_instance_hash(&instance);
+            Ok(instance)
+        } else {
+            let type_name = value.py_type(self.heap);
+            value.drop_with_heap(self.heap);
+            self.heap.dec_ref(instance_id);
+            Err(ExcType::type_error(format!(
+                "__init__() should return None, not '{type_name}'"
+            )))
+        }
+    }
+
+    /// Caches the result of `__hash__` for a newly created instance, so dicts and sets filled
+    /// by builtins (which can't call `__hash__`) store it under the same hash as the VM uses.
+    ///
+    /// Errors are ignored here: they are raised when the VM hashes the instance.
+    fn cache_instance_hash(&mut self, instance: &Value) {
+        let Some(func) = self.lookup_dunder(instance, "__hash__") else {
+            return;
+        };
+        let disabled = matches!(func, Value::None);
+        func.drop_with_heap(self.heap);
+        if !disabled && let Err(e) = self.instance_hash(instance) {
+            e.drop_with_heap(self.heap);
+        }
+    }
+
+    /// Calls `callable` and runs it to completion, returning its result.
+    ///
+    /// If the call pushes a frame, a nested run of the VM loop executes until that frame
+    /// returns or raises. External function calls, OS calls, unresolved futures and compiling
+    /// eval/exec code can't be suspended inside a nested run, so they raise `RuntimeError` instead.
+    pub(super) fn call_sync(&mut self, callable: Value, args: ArgValues) -> RunResult<Value> {
+        if let Err(e) = self.check_nested_depth() {
+            callable.drop_with_heap(self.heap);
+            args.drop_with_heap(self.heap);
+            return Err(e);
+        }
+
+        let floor = self.frames.len();
+        match self.call_function(callable, args)? {
+            CallResult::Push(value) => Ok(value),
+            CallResult::FramePushed => self.run_nested(floor),
+            CallResult::External(_, args) | CallResult::OsCall(_, args) | CallResult::MethodCall(_, args) => {
+                args.drop_with_heap(self.heap);
+                Err(nested_suspend_error())
+            }
+            CallResult::Compile(request) => {
+                self.drop_pending_compile();
+                Err(nested_compile_error(&request))
+            }
+        }
+    }
+
+    /// Returns a `RecursionError` if another nested run would exceed [`MAX_NESTED_CALLS`].
+    pub(super) fn check_nested_depth(&self) -> RunResult<()> {
+        if self.nested_calls >= MAX_NESTED_CALLS {
+            Err(ResourceError::Recursion {
+                limit: MAX_NESTED_CALLS,
+                depth: self.nested_calls + 1,
+            }
+            .into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Runs the VM loop until the frames above `floor` have returned or raised.
+    ///
+    /// The caller must have just pushed the frame at index `floor`, and checked the
+    /// nesting depth with `check_nested_depth`.
+    pub(super) fn run_nested(&mut self, floor: usize) -> RunResult<Value> {
+        let saved_floor = std::mem::replace(&mut self.frame_floor, floor);
+        let saved_ip = self.instruction_ip;
+        self.nested_calls += 1;
+
+        let result = match self.run() {
+            Ok(FrameExit::Return(value)) => Ok(value),
+            Ok(
+                FrameExit::ExternalCall { args, .. }
+                | FrameExit::OsCall { args, .. }
+                | FrameExit::MethodCall { args, .. },
+            ) => {
+                args.drop_with_heap(self.heap);
+                self.pending_clock = None;
+                self.abort_nested_run();
+                Err(nested_suspend_error())
+            }
+            Ok(FrameExit::ResolveFutures(_)) => {
+                self.abort_nested_run();
+                Err(nested_suspend_error())
+            }
+            Ok(FrameExit::Compile(request)) => {
+                self.drop_pending_compile();
+                self.abort_nested_run();
+                Err(nested_compile_error(&request))
+            }
+            Err(e) => Err(e),
+        };
+
+        self.nested_calls -= 1;
+        self.frame_floor = saved_floor;
+        self.instruction_ip = saved_ip;
+        result
+    }
+
+    /// Pops the frames of a nested run that was suspended instead of returning.
+    fn abort_nested_run(&mut self) {
+        while self.frames.len() > self.frame_floor {
+            self.pop_frame();
+        }
+    }
+
+    /// Looks up a special method on the class of `obj`, if `obj` is an instance of a
+    /// user-defined class.
+    ///
+    /// Returns the unbound function (an owned value), or `None` if not defined.
+    pub(super) fn lookup_dunder(&mut self, obj: &Value, name: &str) -> Option<Value> {
+        let Value::Ref(id) = obj else {
+            return None;
+        };
+        let HeapData::Instance(instance) = self.heap.get(*id) else {
+            return None;
+        };
+        let class_id = instance.class_id();
+        lookup_class_attr(class_id, name, self.heap, self.interns)
+    }
+
+    /// Calls the special method `func` with `obj` as `self` and an optional extra argument.
+    ///
+    /// Takes ownership of `func`; `obj` and `other` are borrowed.
+    pub(super) fn call_dunder(&mut self, func: Value, obj: &Value, other: Option<&Value>) -> RunResult<Value> {
+        let obj = obj.clone_with_heap(self.heap);
+        let args = match other {
+            Some(other) => ArgValues::Two(obj, other.clone_with_heap(self.heap)),
+            None => ArgValues::One(obj),
+        };
+        self.call_sync(func, args)
+    }
+
+    /// Converts an instance of a user-defined class to a string using `__str__` or `__repr__`.
+    ///
+    /// `__str__` falls back to `__repr__`, matching `object.__str__`. Values that aren't
+    /// instances, or whose class defines neither method, are returned unchanged.
+    /// Takes ownership of `value`.
+    pub(super) fn instance_to_str(&mut self, value: Value, use_repr: bool) -> RunResult<Value> {
+        let (func, method_name) = match self.lookup_dunder(&value, if use_repr { "__repr__" } else { "__str__" }) {
+            Some(func) => (func, if use_repr { "__repr__" } else { "__str__" }),
+            None if !use_repr => match self.lookup_dunder(&value, "__repr__") {
+                Some(func) => (func, "__repr__"),
+                None => return Ok(value),
+            },
+            None => return Ok(value),
+        };
+        let result = self.call_dunder(func, &value, None);
+        value.drop_with_heap(self.heap);
+        let result = result?;
+
+        let is_str = match &result {
+            Value::InternString(_) => true,
+            Value::Ref(id) => matches!(self.heap.get(*id), HeapData::Str(_)),
+            _ => false,
+        };
+        if is_str {
+            Ok(result)
+        } else {
+            let type_name = result.py_type(self.heap);
+            result.drop_with_heap(self.heap);
+            Err(ExcType::type_error(format!(
+                "{method_name} returned non-string (type {type_name})"
+            )))
+        }
+    }
+
+    /// Calls a builtin, dispatching to special methods of user-defined instances where
+    /// the builtin needs them (`repr`, `ascii`, `str`, `len`, `hash`, `bool`, `format`, `print`).
+    pub(super) fn call_builtin(&mut self, builtin: Builtins, args: ArgValues) -> RunResult<Value> {
+        if builtin_consumes_iterable(builtin) && self.args_contain_iterator(&args) {
+            return self.call_builtin_with_iterator(builtin, args);
+        }
+        match builtin {
+            Builtins::Function(BuiltinsFunctions::Repr | BuiltinsFunctions::Ascii) | Builtins::Type(Type::Str) => {
+                if let ArgValues::One(value) = &args
+                    && self.is_instance(value)
+                {
+                    let ArgValues::One(value) = args else {
+                        unreachable!("args checked above")
+                    };
+                    let use_repr = builtin != Builtins::Type(Type::Str);
+                    let value = self.instance_to_str(value, use_repr)?;
+                    if self.is_instance(&value) {
+                        // Neither `__str__` nor `__repr__` is defined, so use the default repr
+                        return builtin.call(self.heap, ArgValues::One(value), self.interns, self.print_writer);
+                    }
+                    if builtin != Builtins::Function(BuiltinsFunctions::Ascii) {
+                        return Ok(value);
+                    }
+                    let escaped = ascii_escape(
+                        value
+                            .as_either_str(self.heap)
+                            .expect("checked to be a str")
+                            .as_str(self.interns),
+                    );
+                    value.drop_with_heap(self.heap);
+                    return allocate_string(escaped, self.heap);
+                }
+            }
+            // Without an argument these list the caller's locals
+            Builtins::Function(BuiltinsFunctions::Dir) if matches!(args, ArgValues::Empty) => return self.dir_locals(),
+            Builtins::Function(BuiltinsFunctions::Vars) if matches!(args, ArgValues::Empty) => {
+                return self.vars_locals();
+            }
+            Builtins::Function(BuiltinsFunctions::Len) => {
+                if let ArgValues::One(value) = &args
+                    && let Some(func) = self.lookup_dunder(value, "__len__")
+                {
+                    let result = self.call_dunder(func, value, None);
+                    args.drop_with_heap(self.heap);
+                    return self.check_len_result(result?);
+                }
+            }
+            Builtins::Function(BuiltinsFunctions::Hash) => {
+                // Instances whose class doesn't define `__hash__` hash by identity in the builtin
+                if let ArgValues::One(value) = &args
+                    && self.is_instance(value)
+                    && let Some(hash) = self.call_hash(value).transpose()
+                {
+                    args.drop_with_heap(self.heap);
+                    return hash;
+                }
+            }
+            Builtins::Type(Type::Bool) => {
+                if let ArgValues::One(value) = &args
+                    && self.is_instance(value)
+                {
+                    let result = self.value_is_true(value);
+                    args.drop_with_heap(self.heap);
+                    return Ok(Value::Bool(result?));
+                }
+            }
+            Builtins::Function(BuiltinsFunctions::Format) => {
+                if let ArgValues::One(value) | ArgValues::Two(value, _) = &args
+                    && self.is_instance(value)
+                {
+                    let (value, spec) = args.get_one_two_args("format", self.heap)?;
+                    let formatted = format_spec_arg(spec, self.heap, self.interns)
+                        .and_then(|spec| self.format_object(&value, &spec));
+                    value.drop_with_heap(self.heap);
+                    return allocate_string(formatted?, self.heap);
+                }
+            }
+            Builtins::Function(BuiltinsFunctions::Print) => {
+                if self.args_contain_instance(&args) {
+                    let args = self.instances_to_str(args)?;
+                    return builtin.call(self.heap, args, self.interns, self.print_writer);
+                }
+            }
+            _ => {}
+        }
+        builtin.call(self.heap, args, self.interns, self.print_writer)
+    }
+
+    /// Validates the result of a `__len__` call, which must be a non-negative integer.
+    fn check_len_result(&mut self, result: Value) -> RunResult<Value> {
+        match result {
+            Value::Int(n) if n >= 0 => Ok(Value::Int(n)),
+            Value::Int(_) => Err(SimpleException::new_msg(ExcType::ValueError, "__len__() should return >= 0").into()),
+            Value::Bool(b) => Ok(Value::Int(i64::from(b))),
+            other => {
+                let type_name = other.py_type(self.heap);
+                other.drop_with_heap(self.heap);
+                Err(ExcType::type_error(format!(
+                    "'{type_name}' object cannot be interpreted as an integer"
+                )))
+            }
+        }
+    }
+
+    /// Returns whether `value` is an instance of a user-defined class.
+    pub(super) fn is_instance(&self, value: &Value) -> bool {
+        matches!(value, Value::Ref(id) if matches!(self.heap.get(*id), HeapData::Instance(_)))
+    }
+
+    /// Returns whether any positional argument is an instance of a user-defined class.
+    fn args_contain_instance(&self, args: &ArgValues) -> bool {
+        match args {
+            ArgValues::Empty | ArgValues::Kwargs(_) => false,
+            ArgValues::One(v) => self.is_instance(v),
+            ArgValues::Two(v1, v2) => self.is_instance(v1) || self.is_instance(v2),
+            ArgValues::ArgsKargs { args, .. } => args.iter().any(|v| self.is_instance(v)),
+        }
+    }
+
+    /// Converts positional arguments that are user-defined instances to strings via
+    /// `__str__`/`__repr__`, leaving keyword arguments untouched.
+    fn instances_to_str(&mut self, args: ArgValues) -> RunResult<ArgValues> {
+        let (mut positional, kwargs): (ArgPosIter, KwargsValues) = args.into_parts();
+        let mut converted = Vec::with_capacity(positional.len());
+        while let Some(value) = positional.next() {
+            match self.instance_to_str(value, false) {
+                Ok(value) => converted.push(value),
+                Err(e) => {
+                    converted.drop_with_heap(self.heap);
+                    positional.drop_with_heap(self.heap);
+                    kwargs.drop_with_heap(self.heap);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(ArgValues::ArgsKargs {
+            args: converted,
+            kwargs,
+        })
+    }
+
+    /// Equality via `__eq__` when either operand is a user-defined instance defining it.
+    ///
+    /// Returns `Ok(None)` when no `__eq__` applies, so the caller falls back to the
+    /// builtin comparison.
+    pub(super) fn dunder_eq(&mut self, lhs: &Value, rhs: &Value) -> RunResult<Option<bool>> {
+        let (func, obj, other) = if let Some(func) = self.lookup_dunder(lhs, "__eq__") {
+            (func, lhs, rhs)
+        } else if let Some(func) = self.lookup_dunder(rhs, "__eq__") {
+            // Reflected comparison: `a == b` tries `b.__eq__(a)` when only `b` defines it
+            (func, rhs, lhs)
+        } else {
+            return Ok(None);
+        };
+        let result = self.call_dunder(func, obj, Some(other))?;
+        let is_true = result.py_bool(self.heap, self.interns);
+        result.drop_with_heap(self.heap);
+        Ok(Some(is_true))
+    }
+
+    /// Ordering comparison via `__lt__`, `__le__`, `__gt__` or `__ge__` when either operand is
+    /// a user-defined instance.
+    ///
+    /// Tries the method of the left operand, then the reflected method of the right operand
+    /// (`a < b` tries `b.__gt__(a)`), and raises `TypeError` if neither is defined. The
+    /// method's result is returned as is.
+    pub(super) fn dunder_compare(&mut self, lhs: &Value, rhs: &Value, op: OrdOp) -> RunResult<Value> {
+        if let Some(func) = self.lookup_dunder(lhs, op.dunder()) {
+            return self.call_dunder(func, lhs, Some(rhs));
+        }
+        if let Some(func) = self.lookup_dunder(rhs, op.reflected().dunder()) {
+            return self.call_dunder(func, rhs, Some(lhs));
+        }
+        Err(ExcType::type_error(format!(
+            "'{}' not supported between instances of '{}' and '{}'",
+            op.symbol(),
+            self.type_name(lhs),
+            self.type_name(rhs)
+        )))
+    }
+
+    /// Returns the truthiness of `value`, calling `__bool__` or else `__len__` for instances
+    /// of user-defined classes.
+    pub(super) fn value_is_true(&mut self, value: &Value) -> RunResult<bool> {
+        if !self.is_instance(value) {
+            return Ok(value.py_bool(self.heap, self.interns));
+        }
+        if let Some(func) = self.lookup_dunder(value, "__bool__") {
+            return match self.call_dunder(func, value, None)? {
+                Value::Bool(b) => Ok(b),
+                other => {
+                    let type_name = other.py_type(self.heap);
+                    other.drop_with_heap(self.heap);
+                    Err(ExcType::type_error(format!(
+                        "__bool__ should return bool, returned {type_name}"
+                    )))
+                }
+            };
+        }
+        if let Some(func) = self.lookup_dunder(value, "__len__") {
+            let result = self.call_dunder(func, value, None)?;
+            return Ok(!matches!(self.check_len_result(result)?, Value::Int(0)));
+        }
+        Ok(true)
+    }
+
+    /// Returns the hash of `value`, an instance of a user-defined class, as stored by dicts
+    /// and sets.
+    ///
+    /// See [`VM::call_hash`]; without `__hash__` the instance hashes by identity.
+    pub(super) fn instance_hash(&mut self, value: &Value) -> RunResult<u64> {
+        if let Some(result) = self.call_hash(value)? {
+            result.drop_with_heap(self.heap);
+        }
+        value
+            .py_hash(self.heap, self.interns)
+            .ok_or_else(|| RunError::internal("instance_hash: expected a hashable instance"))
+    }
+
+    /// Calls `__hash__` on `value`, an instance of a user-defined class, returning the integer
+    /// it returned, or `None` if the class doesn't define `__hash__`.
+    ///
+    /// The hash of the returned integer is cached as the hash of the instance's heap entry,
+    /// which dicts and sets use. `__hash__ = None` makes the instance unhashable.
+    fn call_hash(&mut self, value: &Value) -> RunResult<Option<Value>> {
+        let Value::Ref(id) = value else {
+            return Err(RunError::internal("call_hash: expected an instance"));
+        };
+        let func = match self.lookup_dunder(value, "__hash__") {
+            Some(Value::None) => {
+                return Err(ExcType::type_error(format!(
+                    "unhashable type: '{}'",
+                    self.type_name(value)
+                )));
+            }
+            Some(func) => func,
+            None => return Ok(None),
+        };
+        let result = match self.call_dunder(func, value, None)? {
+            Value::Bool(b) => Value::Int(i64::from(b)),
+            result => result,
+        };
+        let is_int = match &result {
+            Value::Int(_) => true,
+            Value::Ref(result_id) => matches!(self.heap.get(*result_id), HeapData::LongInt(_)),
+            _ => false,
+        };
+        if !is_int {
+            result.drop_with_heap(self.heap);
+            return Err(ExcType::type_error("__hash__ method should return an integer"));
+        }
+        let hash = result.py_hash(self.heap, self.interns).expect("integers are hashable");
+        self.heap.set_instance_hash(*id, hash);
+        Ok(Some(result))
+    }
+
+    /// Finds the key of a dict or set that is equal to `key`, an instance of a user-defined
+    /// class, by calling `__eq__` on the keys stored under its hash.
+    ///
+    /// Returns a new reference to the stored key, or `None` if `key` isn't an instance,
+    /// `container_id` isn't a dict or set, or no stored key other than `key` itself is equal
+    /// (the builtin lookup finds `key` itself).
+    pub(super) fn find_equal_key(&mut self, container_id: HeapId, key: &Value) -> RunResult<Option<Value>> {
+        if !self.is_instance(key)
+            || !matches!(
+                self.heap.get(container_id),
+                HeapData::Dict(_)
+                    | HeapData::DefaultDict(_)
+                    | HeapData::Counter(_)
+                    | HeapData::OrderedDict(_)
+                    | HeapData::Set(_)
+                    | HeapData::FrozenSet(_)
+            )
+        {
+            return Ok(None);
+        }
+        let hash = self.instance_hash(key)?;
+
+        let candidates: Vec<Value> = match self.heap.get(container_id) {
+            HeapData::Set(set) => set
+                .storage()
+                .values_with_hash(hash)
+                .filter(|v| !v.is(key))
+                .map(Value::copy_for_extend)
+                .collect(),
+            HeapData::FrozenSet(set) => set
+                .storage()
+                .values_with_hash(hash)
+                .filter(|v| !v.is(key))
+                .map(Value::copy_for_extend)
+                .collect(),
+            data => data.as_dict().map_or_else(Vec::new, |dict| {
+                dict.keys_with_hash(hash)
+                    .filter(|k| !k.is(key))
+                    .map(Value::copy_for_extend)
+                    .collect()
+            }),
+        };
+        for candidate in &candidates {
+            if let Value::Ref(id) = candidate {
+                self.heap.inc_ref(*id);
+            }
+        }
+
+        let mut candidates = candidates.into_iter();
+        while let Some(candidate) = candidates.next() {
+            match self.values_eq(&candidate, key) {
+                Ok(true) => {
+                    candidates.drop_with_heap(self.heap);
+                    return Ok(Some(candidate));
+                }
+                Ok(false) => candidate.drop_with_heap(self.heap),
+                Err(e) => {
+                    candidate.drop_with_heap(self.heap);
+                    candidates.drop_with_heap(self.heap);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Replaces `key` with the equal key already stored in the dict or set `container_id`,
+    /// so the builtin lookups of dicts and sets find keys that are equal via `__eq__`.
+    ///
+    /// Takes ownership of `key`; see [`VM::find_equal_key`].
+    pub(super) fn canonical_key(&mut self, container_id: HeapId, key: Value) -> RunResult<Value> {
+        match self.find_equal_key(container_id, &key) {
+            Ok(Some(existing)) => {
+                key.drop_with_heap(self.heap);
+                Ok(existing)
+            }
+            Ok(None) => Ok(key),
+            Err(e) => {
+                key.drop_with_heap(self.heap);
+                Err(e)
+            }
+        }
+    }
+
+    /// Applies [`VM::canonical_key`] to the key argument of dict and set methods such as
+    /// `dict.get()` and `set.remove()`.
+    pub(super) fn canonical_key_arg(
+        &mut self,
+        container_id: HeapId,
+        name_id: StringId,
+        args: ArgValues,
+    ) -> RunResult<ArgValues> {
+        let is_key_method = [
+            StaticStrings::Get,
+            StaticStrings::Pop,
+            StaticStrings::Setdefault,
+            StaticStrings::Add,
+            StaticStrings::Remove,
+            StaticStrings::Discard,
+            StaticStrings::MoveToEnd,
+        ]
+        .into_iter()
+        .any(|method| name_id == method);
+        let key = match &args {
+            ArgValues::One(key) | ArgValues::Two(key, _) => Some(key),
+            ArgValues::ArgsKargs { args, .. } => args.first(),
+            ArgValues::Empty | ArgValues::Kwargs(_) => None,
+        };
+        if !is_key_method || !key.is_some_and(|key| self.is_instance(key)) {
+            return Ok(args);
+        }
+        match args {
+            ArgValues::One(key) => Ok(ArgValues::One(self.canonical_key(container_id, key)?)),
+            ArgValues::Two(key, other) => match self.canonical_key(container_id, key) {
+                Ok(key) => Ok(ArgValues::Two(key, other)),
+                Err(e) => {
+                    other.drop_with_heap(self.heap);
+                    Err(e)
+                }
+            },
+            ArgValues::ArgsKargs { mut args, kwargs } => match self.canonical_key(container_id, args.remove(0)) {
+                Ok(key) => {
+                    args.insert(0, key);
+                    Ok(ArgValues::ArgsKargs { args, kwargs })
+                }
+                Err(e) => {
+                    args.drop_with_heap(self.heap);
+                    kwargs.drop_with_heap(self.heap);
+                    Err(e)
+                }
+            },
+            args => Ok(args),
+        }
+    }
+
+    /// Returns the type name of `value` for error messages, which is the class name for
+    /// instances of user-defined classes.
+    pub(super) fn type_name(&self, value: &Value) -> String {
+        if let Value::Ref(id) = value
+            && let HeapData::Instance(instance) = self.heap.get(*id)
+        {
+            return class_name(instance.class_id(), self.heap, self.interns).to_owned();
+        }
+        value.py_type(self.heap).to_string()
+    }
+}
+
+/// Error raised when code run by a nested run tries to suspend the VM.
+fn nested_suspend_error() -> RunError {
+    SimpleException::new_msg(
+        ExcType::RuntimeError,
+        "external and OS calls are not supported inside special methods like __repr__ or __eq__, \
+         callbacks of builtins, or iterators consumed by `in` or unpacking",
+    )
+    .into()
+}
+
+/// Error raised when code run by a nested run calls `eval()`/`exec()` with code not compiled yet,
+/// or `setattr()` with a name not interned yet.
+fn nested_compile_error(request: &CompileRequest) -> RunError {
+    let what = match request {
+        CompileRequest::Eval(_) => "eval() and exec() of new code are",
+        CompileRequest::Name(_) => "setattr() with new attribute names is",
+    };
+    SimpleException::new_msg(
+        ExcType::RuntimeError,
+        format!(
+            "{what} not supported inside special methods like __repr__ or __eq__, \
+             callbacks of builtins, or iterators consumed by `in` or unpacking"
+        ),
+    )
+    .into()
+}
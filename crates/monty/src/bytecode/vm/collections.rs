@@ -4,12 +4,16 @@ use smallvec::SmallVec;
 
 use super::VM;
 use crate::{
-    exception_private::{ExcType, RunError, SimpleException},
-    heap::HeapData,
+    args::ArgValues,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{DropWithHeap, HeapData, HeapId},
     intern::StringId,
     io::PrintWriter,
     resource::ResourceTracker,
-    types::{Dict, List, PyTrait, Set, Slice, Type, allocate_tuple, slice::value_to_option_i64, str::allocate_char},
+    types::{
+        Dict, List, MontyIter, PyTrait, Set, Slice, Type, allocate_tuple, defaultdict::missing_key_factory,
+        slice::value_to_option_i64, str::allocate_char,
+    },
     value::Value,
 };
 
@@ -37,6 +41,9 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     /// Builds a dict from the top 2n stack values (key/value pairs).
     pub(super) fn build_dict(&mut self, count: usize) -> Result<(), RunError> {
         let items = self.pop_n(count * 2);
+        if items.iter().step_by(2).any(|key| self.is_instance(key)) {
+            return self.build_with_instances(HeapData::Dict(Dict::new()), items, 2);
+        }
         let mut dict = Dict::new();
         // Use into_iter to consume items by value, avoiding clone and proper ownership transfer
         let mut iter = items.into_iter();
@@ -51,6 +58,9 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     /// Builds a set from the top n stack values.
     pub(super) fn build_set(&mut self, count: usize) -> Result<(), RunError> {
         let items = self.pop_n(count);
+        if items.iter().any(|item| self.is_instance(item)) {
+            return self.build_with_instances(HeapData::Set(Set::new()), items, 1);
+        }
         let mut set = Set::new();
         for item in items {
             set.add(item, self.heap, self.interns)?;
@@ -60,6 +70,56 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         Ok(())
     }
 
+    /// Builds a dict (`stride` 2, items are key/value pairs) or set (`stride` 1) whose keys
+    /// include instances of user-defined classes.
+    ///
+    /// The empty container is allocated first so each key can be matched against the keys
+    /// inserted before it with `canonical_key`.
+    fn build_with_instances(&mut self, container: HeapData, items: Vec<Value>, stride: usize) -> RunResult<()> {
+        let container_id = match self.heap.allocate(container) {
+            Ok(id) => id,
+            Err(e) => {
+                items.drop_with_heap(self.heap);
+                return Err(e.into());
+            }
+        };
+        let mut items = items.into_iter();
+        while let Some(key) = items.next() {
+            let value = if stride == 2 { items.next() } else { None };
+            if let Err(e) = self.store_key(container_id, key, value) {
+                items.drop_with_heap(self.heap);
+                self.heap.dec_ref(container_id);
+                return Err(e);
+            }
+        }
+        self.push(Value::Ref(container_id));
+        Ok(())
+    }
+
+    /// Stores `key` (mapped to `value` for a dict) in the dict or set `container_id`,
+    /// reusing an equal key that is already stored.
+    ///
+    /// Takes ownership of `key` and `value`.
+    fn store_key(&mut self, container_id: HeapId, key: Value, value: Option<Value>) -> RunResult<()> {
+        let key = match self.canonical_key(container_id, key) {
+            Ok(key) => key,
+            Err(e) => {
+                value.drop_with_heap(self.heap);
+                return Err(e);
+            }
+        };
+        let interns = self.interns;
+        self.heap
+            .with_entry_mut(container_id, |heap, data| match (data, value) {
+                (HeapData::Set(set), None) => set.add(key, heap, interns).map(|_| ()),
+                (data, Some(value)) => data.py_setitem(key, value, heap, interns),
+                (_, None) => {
+                    key.drop_with_heap(heap);
+                    Err(RunError::internal("store_key: expected a set"))
+                }
+            })
+    }
+
     /// Builds a slice object from the top 3 stack values.
     ///
     /// Stack: [start, stop, step] -> [slice]
@@ -88,79 +148,38 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     /// Extends a list with items from an iterable.
     ///
     /// Stack: [list, iterable] -> [list]
-    /// Pops the iterable, extends the list in place, leaves list on stack.
+    ///
+    /// Used for `*iterable` in calls and in list/tuple displays.
     pub(super) fn list_extend(&mut self) -> Result<(), RunError> {
+        // A generator or iterator is run to completion first (the list stays on the stack for unwinding)
         let iterable = self.pop();
+        let iterable = self.iterator_to_list(iterable)?;
         let list_ref = self.pop();
 
-        // Two-phase approach to avoid borrow conflicts:
-        // Phase 1: Copy items without refcount changes
-        let copied_items: Vec<Value> = match &iterable {
-            Value::Ref(id) => match self.heap.get(*id) {
-                HeapData::List(list) => list.as_slice().iter().map(Value::copy_for_extend).collect(),
-                HeapData::Tuple(tuple) => tuple.as_slice().iter().map(Value::copy_for_extend).collect(),
-                HeapData::Set(set) => set.storage().iter().map(Value::copy_for_extend).collect(),
-                HeapData::Dict(dict) => dict.iter().map(|(k, _)| Value::copy_for_extend(k)).collect(),
-                HeapData::Str(s) => {
-                    // Need to allocate strings for each character
-                    let chars: Vec<char> = s.as_str().chars().collect();
-                    let mut items = Vec::with_capacity(chars.len());
-                    for c in chars {
-                        items.push(allocate_char(c, self.heap)?);
-                    }
-                    items
-                }
-                _ => {
-                    let type_ = iterable.py_type(self.heap);
-                    iterable.drop_with_heap(self.heap);
-                    list_ref.drop_with_heap(self.heap);
-                    return Err(ExcType::type_error_not_iterable(type_));
-                }
-            },
-            Value::InternString(id) => {
-                let s = self.interns.get_str(*id);
-                let chars: Vec<char> = s.chars().collect();
-                let mut items = Vec::with_capacity(chars.len());
-                for c in chars {
-                    items.push(allocate_char(c, self.heap)?);
-                }
-                items
-            }
-            _ => {
-                let type_ = iterable.py_type(self.heap);
-                iterable.drop_with_heap(self.heap);
+        let items = match self.collect_iterable(iterable) {
+            Ok(items) => items,
+            Err(e) => {
                 list_ref.drop_with_heap(self.heap);
-                return Err(ExcType::type_error_not_iterable(type_));
+                return Err(e);
             }
         };
 
-        // Phase 2: Increment refcounts now that the borrow has ended
-        for item in &copied_items {
-            if let Value::Ref(id) = item {
-                self.heap.inc_ref(*id);
-            }
-        }
-
-        // Check if any copied items are refs (for updating contains_refs)
-        let has_refs = copied_items.iter().any(|v| matches!(v, Value::Ref(_)));
-
-        // Extend the list
-        if let Value::Ref(id) = &list_ref
-            && let HeapData::List(list) = self.heap.get_mut(*id)
-        {
-            // Update contains_refs before extending
-            if has_refs {
-                list.set_contains_refs();
+        let Value::Ref(list_id) = list_ref else {
+            items.drop_with_heap(self.heap);
+            return Err(RunError::internal("ListExtend: expected list ref"));
+        };
+        self.heap.with_entry_mut(list_id, |heap, data| {
+            if let HeapData::List(list) = data {
+                for item in items {
+                    list.append(heap, item);
+                }
+                Ok(())
+            } else {
+                items.drop_with_heap(heap);
+                Err(RunError::internal("ListExtend: expected list on heap"))
             }
-            list.as_vec_mut().extend(copied_items);
-        }
-
-        // Mark potential cycle after the mutable borrow ends
-        if has_refs {
-            self.heap.mark_potential_cycle();
-        }
+        })?;
 
-        iterable.drop_with_heap(self.heap);
         self.push(list_ref);
         Ok(())
     }
@@ -215,7 +234,7 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         // Phase 1: Copy key-value pairs without refcount changes
         // Check that mapping is a dict (Ref pointing to Dict)
         let copied_items: Vec<(Value, Value)> = if let Value::Ref(id) = &mapping {
-            if let HeapData::Dict(dict) = self.heap.get(*id) {
+            if let Some(dict) = self.heap.get(*id).as_dict() {
                 dict.iter()
                     .map(|(k, v)| (Value::copy_for_extend(k), Value::copy_for_extend(v)))
                     .collect()
@@ -302,6 +321,115 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         Ok(())
     }
 
+    /// Updates a dict with the items of a mapping for `**mapping` in dict displays.
+    ///
+    /// Stack: [dict, mapping] -> [dict]
+    /// Later keys overwrite earlier ones, matching `dict.update`.
+    pub(super) fn dict_update(&mut self) -> Result<(), RunError> {
+        let mapping = self.pop();
+        let dict_ref = self.pop();
+        let Value::Ref(dict_id) = dict_ref else {
+            mapping.drop_with_heap(self.heap);
+            return Err(RunError::internal("DictUpdate: expected dict ref"));
+        };
+
+        // Phase 1: Copy key-value pairs without refcount changes
+        let copied_items: Vec<(Value, Value)> = if let Value::Ref(id) = &mapping
+            && let Some(dict) = self.heap.get(*id).as_dict()
+        {
+            dict.iter()
+                .map(|(k, v)| (Value::copy_for_extend(k), Value::copy_for_extend(v)))
+                .collect()
+        } else {
+            let type_ = mapping.py_type(self.heap);
+            mapping.drop_with_heap(self.heap);
+            dict_ref.drop_with_heap(self.heap);
+            return Err(ExcType::type_error_not_mapping(type_));
+        };
+
+        // Phase 2: Increment refcounts now that the borrow has ended
+        for (key, value) in &copied_items {
+            if let Value::Ref(id) = key {
+                self.heap.inc_ref(*id);
+            }
+            if let Value::Ref(id) = value {
+                self.heap.inc_ref(*id);
+            }
+        }
+        mapping.drop_with_heap(self.heap);
+
+        for (key, value) in copied_items {
+            let old_value = self.heap.with_entry_mut(dict_id, |heap, data| {
+                if let HeapData::Dict(dict) = data {
+                    dict.set(key, value, heap, self.interns)
+                } else {
+                    key.drop_with_heap(heap);
+                    value.drop_with_heap(heap);
+                    Err(RunError::internal("DictUpdate: expected dict on heap"))
+                }
+            })?;
+            if let Some(old) = old_value {
+                old.drop_with_heap(self.heap);
+            }
+        }
+
+        self.push(dict_ref);
+        Ok(())
+    }
+
+    /// Adds all items of an iterable to a set for `*iterable` in set displays.
+    ///
+    /// Stack: [set, iterable] -> [set]
+    /// May raise TypeError if an item is unhashable.
+    pub(super) fn set_update(&mut self) -> Result<(), RunError> {
+        // A generator or iterator is run to completion first (the set stays on the stack for unwinding)
+        let iterable = self.pop();
+        let iterable = self.iterator_to_list(iterable)?;
+        let set_ref = self.pop();
+
+        let items = match self.collect_iterable(iterable) {
+            Ok(items) => items,
+            Err(e) => {
+                set_ref.drop_with_heap(self.heap);
+                return Err(e);
+            }
+        };
+
+        let Value::Ref(set_id) = set_ref else {
+            items.drop_with_heap(self.heap);
+            return Err(RunError::internal("SetUpdate: expected set ref"));
+        };
+        let result = self.heap.with_entry_mut(set_id, |heap, data| {
+            let HeapData::Set(set) = data else {
+                items.drop_with_heap(heap);
+                return Err(RunError::internal("SetUpdate: expected set on heap"));
+            };
+            let mut items = items.into_iter();
+            while let Some(item) = items.next() {
+                if let Err(e) = set.add(item, heap, self.interns) {
+                    items.drop_with_heap(heap);
+                    return Err(e);
+                }
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            set_ref.drop_with_heap(self.heap);
+            return Err(e);
+        }
+
+        self.push(set_ref);
+        Ok(())
+    }
+
+    /// Collects all items of an iterable into a Vec, consuming the iterable.
+    fn collect_iterable(&mut self, iterable: Value) -> Result<Vec<Value>, RunError> {
+        let mut iter = MontyIter::new(iterable, self.heap, self.interns)?;
+        let items = iter.collect(self.heap, self.interns);
+        iter.drop_with_heap(self.heap);
+        items
+    }
+
     // ========================================================================
     // Comprehension Building
     // ========================================================================
@@ -349,6 +477,9 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
             value.drop_with_heap(self.heap);
             return Err(RunError::internal("SetAdd: expected set ref on stack"));
         };
+        if self.is_instance(&value) {
+            return self.store_key(set_id, value, None);
+        }
 
         // Add to the set using with_entry_mut to avoid borrow conflicts
         self.heap.with_entry_mut(set_id, |heap, data| {
@@ -380,6 +511,9 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
             value.drop_with_heap(self.heap);
             return Err(RunError::internal("DictSetItem: expected dict ref on stack"));
         };
+        if self.is_instance(&key) {
+            return self.store_key(dict_id, key, Some(value));
+        }
 
         // Set item in the dict using with_entry_mut to avoid borrow conflicts
         let old_value = self.heap.with_entry_mut(dict_id, |heap, data| {
@@ -400,16 +534,79 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         Ok(())
     }
 
+    /// Implements `obj[index]`.
+    ///
+    /// Looking up a missing key in a `defaultdict` calls its default factory, which may be
+    /// Python code, so it is handled here rather than in `py_getitem`.
+    pub(super) fn binary_subscr(&mut self, obj: &Value, index: &Value) -> RunResult<Value> {
+        if let Value::Ref(id) = obj
+            && let Some(key) = self.find_equal_key(*id, index)?
+        {
+            let result = obj.py_getitem(&key, self.heap, self.interns);
+            key.drop_with_heap(self.heap);
+            return result;
+        }
+        if let Value::Ref(id) = obj
+            && matches!(self.heap.get(*id), HeapData::DefaultDict(_))
+            && let Some(factory) = missing_key_factory(*id, index, self.heap, self.interns)?
+        {
+            let value = self.call_sync(factory, ArgValues::Empty)?;
+            let key = index.clone_with_heap(self.heap);
+            let stored = value.clone_with_heap(self.heap);
+            let interns = self.interns;
+            let result = self
+                .heap
+                .with_entry_mut(*id, |heap, data| data.py_setitem(key, stored, heap, interns));
+            if let Err(err) = result {
+                value.drop_with_heap(self.heap);
+                return Err(err);
+            }
+            return Ok(value);
+        }
+        obj.py_getitem(index, self.heap, self.interns)
+    }
+
+    /// Implements `obj[index] = value`, reusing an equal dict key when `index` is an instance
+    /// of a user-defined class.
+    pub(super) fn store_subscr(&mut self, obj: &mut Value, index: Value, value: Value) -> RunResult<()> {
+        let index = if let Value::Ref(id) = obj {
+            match self.canonical_key(*id, index) {
+                Ok(index) => index,
+                Err(e) => {
+                    value.drop_with_heap(self.heap);
+                    return Err(e);
+                }
+            }
+        } else {
+            index
+        };
+        obj.py_setitem(index, value, self.heap, self.interns)
+    }
+
+    /// Implements `del obj[index]`, matching equal dict keys when `index` is an instance of a
+    /// user-defined class.
+    pub(super) fn delete_subscr(&mut self, obj: &mut Value, index: Value) -> RunResult<()> {
+        let index = if let Value::Ref(id) = obj {
+            self.canonical_key(*id, index)?
+        } else {
+            index
+        };
+        let result = obj.py_delitem(&index, self.heap, self.interns);
+        index.drop_with_heap(self.heap);
+        result
+    }
+
     // ========================================================================
     // Unpacking
     // ========================================================================
 
     /// Unpacks a sequence into n values on the stack.
     ///
-    /// Supports lists, tuples, and strings. For strings, each character becomes
-    /// a separate single-character string.
+    /// Supports lists, tuples, named tuples, deques and strings. For strings, each character becomes
+    /// a separate single-character string. Generators and iterators are run to completion first.
     pub(super) fn unpack_sequence(&mut self, count: usize) -> Result<(), RunError> {
         let value = self.pop();
+        let value = self.iterator_to_list(value)?;
 
         // Copy values without incrementing refcounts (avoids borrow conflict with heap.get).
         // For strings, we allocate new string values for each character.
@@ -450,6 +647,22 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
                     }
                     tuple.as_slice().iter().map(Value::copy_for_extend).collect()
                 }
+                HeapData::NamedTuple(named_tuple) => {
+                    let tuple_len = named_tuple.as_vec().len();
+                    if tuple_len != count {
+                        value.drop_with_heap(self.heap);
+                        return Err(unpack_size_error(count, tuple_len));
+                    }
+                    named_tuple.as_vec().iter().map(Value::copy_for_extend).collect()
+                }
+                HeapData::Deque(deque) => {
+                    let deque_len = deque.len();
+                    if deque_len != count {
+                        value.drop_with_heap(self.heap);
+                        return Err(unpack_size_error(count, deque_len));
+                    }
+                    deque.iter().map(Value::copy_for_extend).collect()
+                }
                 HeapData::Str(s) => {
                     let str_len = s.as_str().chars().count();
                     if str_len != count {
@@ -514,6 +727,7 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     /// After execution, the stack has: first (top), rest_list, last.
     pub(super) fn unpack_ex(&mut self, before: usize, after: usize) -> Result<(), RunError> {
         let value = self.pop();
+        let value = self.iterator_to_list(value)?;
         let min_items = before + after;
 
         // Extract items from the sequence
@@ -551,6 +765,22 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
                     }
                     tuple.as_slice().iter().map(Value::copy_for_extend).collect()
                 }
+                HeapData::NamedTuple(named_tuple) => {
+                    let tuple_len = named_tuple.as_vec().len();
+                    if tuple_len < min_items {
+                        value.drop_with_heap(self.heap);
+                        return Err(unpack_ex_too_few_error(min_items, tuple_len));
+                    }
+                    named_tuple.as_vec().iter().map(Value::copy_for_extend).collect()
+                }
+                HeapData::Deque(deque) => {
+                    let deque_len = deque.len();
+                    if deque_len < min_items {
+                        value.drop_with_heap(self.heap);
+                        return Err(unpack_ex_too_few_error(min_items, deque_len));
+                    }
+                    deque.iter().map(Value::copy_for_extend).collect()
+                }
                 HeapData::Str(s) => {
                     // Collect chars once to avoid double iteration over UTF-8 data
                     let chars: Vec<char> = s.as_str().chars().collect();
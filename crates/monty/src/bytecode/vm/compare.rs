@@ -1,53 +1,353 @@
 //! Comparison operation helpers for the VM.
 
+use std::cmp::Ordering;
+
+use ahash::AHashSet;
+
 use super::VM;
 use crate::{
-    exception_private::{ExcType, RunError},
+    exception_private::{ExcType, RunError, RunResult},
+    heap::{DropWithHeap, HeapData, HeapId},
     io::PrintWriter,
     resource::{DepthGuard, ResourceTracker},
     types::{LongInt, PyTrait},
     value::Value,
 };
 
+/// An ordering comparison operator.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum OrdOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl OrdOp {
+    /// Returns whether the operator holds for operands ordered as `ordering`.
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Lt => ordering.is_lt(),
+            Self::Le => ordering.is_le(),
+            Self::Gt => ordering.is_gt(),
+            Self::Ge => ordering.is_ge(),
+        }
+    }
+
+    /// The special method implementing the operator.
+    pub(super) fn dunder(self) -> &'static str {
+        match self {
+            Self::Lt => "__lt__",
+            Self::Le => "__le__",
+            Self::Gt => "__gt__",
+            Self::Ge => "__ge__",
+        }
+    }
+
+    /// The operator with swapped operands, tried on the right operand: `a < b` is `b > a`.
+    pub(super) fn reflected(self) -> Self {
+        match self {
+            Self::Lt => Self::Gt,
+            Self::Le => Self::Ge,
+            Self::Gt => Self::Lt,
+            Self::Ge => Self::Le,
+        }
+    }
+
+    /// The operator as written in Python source, for error messages.
+    pub(super) fn symbol(self) -> &'static str {
+        match self {
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+        }
+    }
+}
+
 impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     /// Equality comparison.
+    ///
+    /// Instances of user-defined classes defining `__eq__` are compared by calling it.
     pub(super) fn compare_eq(&mut self) -> Result<(), RunError> {
         let rhs = self.pop();
         let lhs = self.pop();
-        let mut guard = DepthGuard::default();
-        let result = lhs.py_eq(&rhs, self.heap, &mut guard, self.interns)?;
+        let result = self.values_eq(&lhs, &rhs);
         lhs.drop_with_heap(self.heap);
         rhs.drop_with_heap(self.heap);
-        self.push(Value::Bool(result));
+        self.push(Value::Bool(result?));
         Ok(())
     }
 
     /// Inequality comparison.
+    ///
+    /// Like CPython's default `__ne__`, this negates the result of `__eq__` for
+    /// user-defined classes.
     pub(super) fn compare_ne(&mut self) -> Result<(), RunError> {
         let rhs = self.pop();
         let lhs = self.pop();
-        let mut guard = DepthGuard::default();
-        let result = !lhs.py_eq(&rhs, self.heap, &mut guard, self.interns)?;
+        let result = self.values_eq(&lhs, &rhs);
         lhs.drop_with_heap(self.heap);
         rhs.drop_with_heap(self.heap);
-        self.push(Value::Bool(result));
+        self.push(Value::Bool(!result?));
         Ok(())
     }
 
-    /// Ordering comparison with a predicate.
-    pub(super) fn compare_ord<F>(&mut self, check: F) -> Result<(), RunError>
-    where
-        F: FnOnce(std::cmp::Ordering) -> bool,
-    {
+    /// Compares two values for equality, dispatching to `__eq__` where defined.
+    ///
+    /// Lists, tuples and dicts holding instances of user-defined classes are compared item
+    /// by item here, so the instances' `__eq__` is called.
+    pub(super) fn values_eq(&mut self, lhs: &Value, rhs: &Value) -> Result<bool, RunError> {
+        let mut guard = DepthGuard::default();
+        self.values_eq_guarded(lhs, rhs, &mut guard)
+    }
+
+    /// Implements `values_eq`, tracking the nesting depth of containers in `guard`.
+    fn values_eq_guarded(&mut self, lhs: &Value, rhs: &Value, guard: &mut DepthGuard) -> RunResult<bool> {
+        if let Some(result) = self.dunder_eq(lhs, rhs)? {
+            return Ok(result);
+        }
+        if let (Value::Ref(lhs_id), Value::Ref(rhs_id)) = (lhs, rhs)
+            && lhs_id != rhs_id
+            && self.same_container_kind(*lhs_id, *rhs_id)
+            && (self.holds_instance(lhs) || self.holds_instance(rhs))
+        {
+            guard.increase_err()?;
+            let result = self.items_eq(*lhs_id, *rhs_id, guard);
+            guard.decrease();
+            return result;
+        }
+        Ok(lhs.py_eq(rhs, self.heap, guard, self.interns)?)
+    }
+
+    /// Returns whether both heap entries are lists, both tuples, both dicts or both sets.
+    fn same_container_kind(&self, lhs_id: HeapId, rhs_id: HeapId) -> bool {
+        matches!(
+            (self.heap.get(lhs_id), self.heap.get(rhs_id)),
+            (HeapData::List(_), HeapData::List(_))
+                | (HeapData::Tuple(_), HeapData::Tuple(_))
+                | (HeapData::Dict(_), HeapData::Dict(_))
+                | (
+                    HeapData::Set(_) | HeapData::FrozenSet(_),
+                    HeapData::Set(_) | HeapData::FrozenSet(_)
+                )
+        )
+    }
+
+    /// Returns whether `value` is, or is a list, tuple, dict or set containing (at any depth),
+    /// an instance of a user-defined class.
+    fn holds_instance(&self, value: &Value) -> bool {
+        let mut visited = AHashSet::new();
+        let mut guard = DepthGuard::default();
+        self.holds_instance_inner(value, &mut visited, &mut guard)
+    }
+
+    /// Implements `holds_instance`, skipping containers already visited.
+    fn holds_instance_inner(&self, value: &Value, visited: &mut AHashSet<HeapId>, guard: &mut DepthGuard) -> bool {
+        let Value::Ref(id) = value else {
+            return false;
+        };
+        if !visited.insert(*id) || !guard.increase() {
+            return false;
+        }
+        let found = match self.heap.get(*id) {
+            HeapData::Instance(_) => true,
+            HeapData::List(list) => list
+                .as_slice()
+                .iter()
+                .any(|item| self.holds_instance_inner(item, visited, guard)),
+            HeapData::Tuple(tuple) => tuple
+                .as_slice()
+                .iter()
+                .any(|item| self.holds_instance_inner(item, visited, guard)),
+            HeapData::Set(set) => set
+                .storage()
+                .iter()
+                .any(|item| self.holds_instance_inner(item, visited, guard)),
+            HeapData::FrozenSet(set) => set
+                .storage()
+                .iter()
+                .any(|item| self.holds_instance_inner(item, visited, guard)),
+            HeapData::Dict(dict) => dict.iter().any(|(key, value)| {
+                self.holds_instance_inner(key, visited, guard) || self.holds_instance_inner(value, visited, guard)
+            }),
+            _ => false,
+        };
+        guard.decrease();
+        found
+    }
+
+    /// Compares the items of two lists, two tuples, two dicts or two sets with `values_eq_guarded`.
+    fn items_eq(&mut self, lhs_id: HeapId, rhs_id: HeapId, guard: &mut DepthGuard) -> RunResult<bool> {
+        match self.heap.get(lhs_id) {
+            HeapData::Dict(_) => return self.dicts_eq(lhs_id, rhs_id, guard),
+            HeapData::Set(_) | HeapData::FrozenSet(_) => return self.sets_eq(lhs_id, rhs_id),
+            _ => {}
+        }
+        let lhs_items = self.clone_items(lhs_id);
+        let rhs_items = self.clone_items(rhs_id);
+        let result = self.sequences_eq(&lhs_items, &rhs_items, guard);
+        lhs_items.drop_with_heap(self.heap);
+        rhs_items.drop_with_heap(self.heap);
+        result
+    }
+
+    /// Compares two slices item by item, treating identical items as equal like CPython.
+    fn sequences_eq(&mut self, lhs: &[Value], rhs: &[Value], guard: &mut DepthGuard) -> RunResult<bool> {
+        if lhs.len() != rhs.len() {
+            return Ok(false);
+        }
+        for (lhs_item, rhs_item) in lhs.iter().zip(rhs) {
+            if !lhs_item.is(rhs_item) && !self.values_eq_guarded(lhs_item, rhs_item, guard)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Compares two dicts by looking up each key of `lhs_id` in `rhs_id`.
+    fn dicts_eq(&mut self, lhs_id: HeapId, rhs_id: HeapId, guard: &mut DepthGuard) -> RunResult<bool> {
+        let (HeapData::Dict(lhs), HeapData::Dict(rhs)) = (self.heap.get(lhs_id), self.heap.get(rhs_id)) else {
+            return Ok(false);
+        };
+        if lhs.len() != rhs.len() {
+            return Ok(false);
+        }
+        // Keys and values alternate
+        let items: Vec<Value> = lhs
+            .iter()
+            .flat_map(|(key, value)| [key.copy_for_extend(), value.copy_for_extend()])
+            .collect();
+        self.inc_ref_all(&items);
+
+        let mut items = items.into_iter();
+        while let (Some(key), Some(value)) = (items.next(), items.next()) {
+            let result = self.dict_value_eq(rhs_id, &key, &value, guard);
+            key.drop_with_heap(self.heap);
+            value.drop_with_heap(self.heap);
+            if !matches!(result, Ok(true)) {
+                items.drop_with_heap(self.heap);
+                return result;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Compares two sets by looking up each element of `lhs_id` in `rhs_id`.
+    fn sets_eq(&mut self, lhs_id: HeapId, rhs_id: HeapId) -> RunResult<bool> {
+        let (lhs, rhs) = match (self.heap.get(lhs_id), self.heap.get(rhs_id)) {
+            (HeapData::Set(lhs), HeapData::Set(rhs)) => (lhs.storage(), rhs.storage()),
+            (HeapData::Set(lhs), HeapData::FrozenSet(rhs)) => (lhs.storage(), rhs.storage()),
+            (HeapData::FrozenSet(lhs), HeapData::Set(rhs)) => (lhs.storage(), rhs.storage()),
+            (HeapData::FrozenSet(lhs), HeapData::FrozenSet(rhs)) => (lhs.storage(), rhs.storage()),
+            _ => return Ok(false),
+        };
+        if lhs.len() != rhs.len() {
+            return Ok(false);
+        }
+        let items: Vec<Value> = lhs.iter().map(Value::copy_for_extend).collect();
+        self.inc_ref_all(&items);
+
+        let mut items = items.into_iter();
+        while let Some(item) = items.next() {
+            let result = self.set_contains(rhs_id, &item);
+            item.drop_with_heap(self.heap);
+            if !matches!(result, Ok(true)) {
+                items.drop_with_heap(self.heap);
+                return result;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Returns whether the set `set_id` contains `item`, finding instances of user-defined
+    /// classes with `find_equal_key`.
+    fn set_contains(&mut self, set_id: HeapId, item: &Value) -> RunResult<bool> {
+        if let Some(key) = self.find_equal_key(set_id, item)? {
+            key.drop_with_heap(self.heap);
+            return Ok(true);
+        }
+        let interns = self.interns;
+        self.heap.with_entry_mut(set_id, |heap, data| match data {
+            HeapData::Set(set) => set.contains(item, heap, interns),
+            HeapData::FrozenSet(set) => set.contains(item, heap, interns),
+            _ => Ok(false),
+        })
+    }
+
+    /// Returns whether the dict `dict_id` maps `key` to a value equal to `value`.
+    fn dict_value_eq(
+        &mut self,
+        dict_id: HeapId,
+        key: &Value,
+        value: &Value,
+        guard: &mut DepthGuard,
+    ) -> RunResult<bool> {
+        let equal_key = self.find_equal_key(dict_id, key)?;
+        let interns = self.interns;
+        let lookup = self
+            .heap
+            .with_entry_mut(dict_id, |heap, data| -> RunResult<Option<Value>> {
+                let HeapData::Dict(dict) = data else {
+                    return Ok(None);
+                };
+                let found = dict.get(equal_key.as_ref().unwrap_or(key), heap, interns)?;
+                Ok(found.map(Value::copy_for_extend))
+            });
+        equal_key.drop_with_heap(self.heap);
+        let Some(other) = lookup? else {
+            return Ok(false);
+        };
+        if let Value::Ref(id) = &other {
+            self.heap.inc_ref(*id);
+        }
+        let result = if value.is(&other) {
+            Ok(true)
+        } else {
+            self.values_eq_guarded(value, &other, guard)
+        };
+        other.drop_with_heap(self.heap);
+        result
+    }
+
+    /// Returns new references to the items of a list or tuple.
+    fn clone_items(&mut self, id: HeapId) -> Vec<Value> {
+        let items: Vec<Value> = match self.heap.get(id) {
+            HeapData::List(list) => list.as_slice().iter().map(Value::copy_for_extend).collect(),
+            HeapData::Tuple(tuple) => tuple.as_slice().iter().map(Value::copy_for_extend).collect(),
+            _ => Vec::new(),
+        };
+        self.inc_ref_all(&items);
+        items
+    }
+
+    /// Increments the refcounts of values produced by `copy_for_extend`.
+    fn inc_ref_all(&mut self, values: &[Value]) {
+        for value in values {
+            if let Value::Ref(id) = value {
+                self.heap.inc_ref(*id);
+            }
+        }
+    }
+
+    /// Ordering comparison.
+    ///
+    /// Instances of user-defined classes are compared with their `__lt__`, `__le__`, `__gt__`
+    /// or `__ge__` method, see `dunder_compare`.
+    pub(super) fn compare_ord(&mut self, op: OrdOp) -> Result<(), RunError> {
         let rhs = self.pop();
         let lhs = self.pop();
-        let mut guard = DepthGuard::default();
-        let result = lhs
-            .py_cmp(&rhs, self.heap, &mut guard, self.interns)?
-            .is_some_and(check);
+        let result = if self.is_instance(&lhs) || self.is_instance(&rhs) {
+            self.dunder_compare(&lhs, &rhs, op)
+        } else {
+            let mut guard = DepthGuard::default();
+            lhs.py_cmp(&rhs, self.heap, &mut guard, self.interns)
+                .map(|ordering| Value::Bool(ordering.is_some_and(|ordering| op.holds(ordering))))
+                .map_err(Into::into)
+        };
         lhs.drop_with_heap(self.heap);
         rhs.drop_with_heap(self.heap);
-        self.push(Value::Bool(result));
+        self.push(result?);
         Ok(())
     }
 
@@ -76,7 +376,11 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         let container = self.pop(); // container (rhs)
         let item = self.pop(); // item to find (lhs)
 
-        let result = container.py_contains(&item, self.heap, self.interns);
+        let result = if self.is_iterator(&container) {
+            self.iterator_contains(&container, &item)
+        } else {
+            self.container_contains(&container, &item)
+        };
 
         item.drop_with_heap(self.heap);
         container.drop_with_heap(self.heap);
@@ -86,6 +390,43 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         Ok(())
     }
 
+    /// Membership test for containers other than iterators.
+    ///
+    /// Instances of user-defined classes are found in dicts and sets via `find_equal_key`, and
+    /// lists and tuples involving instances are searched with `values_eq`.
+    fn container_contains(&mut self, container: &Value, item: &Value) -> RunResult<bool> {
+        if let Value::Ref(id) = container {
+            if let Some(key) = self.find_equal_key(*id, item)? {
+                key.drop_with_heap(self.heap);
+                return Ok(true);
+            }
+            let elements = match self.heap.get(*id) {
+                HeapData::List(list) => Some(list.as_slice()),
+                HeapData::Tuple(tuple) => Some(tuple.as_slice()),
+                _ => None,
+            };
+            if let Some(elements) = elements
+                && (elements.iter().any(|element| self.is_instance(element)) || self.holds_instance(item))
+            {
+                let items = self.clone_items(*id);
+                let result = self.sequence_contains(&items, item);
+                items.drop_with_heap(self.heap);
+                return result;
+            }
+        }
+        container.py_contains(item, self.heap, self.interns)
+    }
+
+    /// Returns whether any of `items` is `item` or equal to it.
+    fn sequence_contains(&mut self, items: &[Value], item: &Value) -> RunResult<bool> {
+        for element in items {
+            if element.is(item) || self.values_eq(element, item)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
     /// Modulo equality comparison: a % b == k
     ///
     /// This is an optimization for patterns like `x % 3 == 0`. The constant k
@@ -111,9 +452,9 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
             self.push(Value::Bool(is_equal));
             Ok(())
         } else {
-            // Fallback: compute py_mod then compare with py_eq
-            // This handles LongInt and other Ref types
-            let mod_value = lhs.py_mod(&rhs, self.heap);
+            // Fallback: compute the modulo then compare with py_eq
+            // This handles LongInt, other Ref types and `%` formatting
+            let mod_value = self.mod_values(&lhs, &rhs);
             lhs.drop_with_heap(self.heap);
             rhs.drop_with_heap(self.heap);
 
@@ -0,0 +1,122 @@
+//! Context manager helpers for the VM.
+//!
+//! The compiler keeps the context manager on the stack while the body of a `with`
+//! statement runs, and calls `__exit__(None, None, None)` itself on the normal, `return`,
+//! `break` and `continue` paths. These helpers implement the two steps that need more
+//! than a plain method call: checking the protocol before `__enter__`, and passing the
+//! active exception to `__exit__`.
+//!
+//! Both context managers defined in the sandbox (classes with `__enter__`/`__exit__`)
+//! and host-provided dataclasses listing the methods are supported. For the latter,
+//! the method calls become `CallResult::MethodCall`, so the host runs them.
+
+use super::{VM, call::CallResult};
+use crate::{
+    args::{ArgValues, KwargsValues},
+    builtins::Builtins,
+    exception_private::{ExcType, RunError},
+    heap::{DropWithHeap, HeapData, HeapId},
+    intern::{StaticStrings, StringId},
+    io::PrintWriter,
+    resource::ResourceTracker,
+    types::{
+        PyTrait, Type,
+        class::{class_name, lookup_class_attr},
+    },
+    value::Value,
+};
+
+impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
+    /// Calls `__enter__` (or `__aenter__`) on the context manager at TOS, leaving it on the stack.
+    ///
+    /// Raises `TypeError` before calling anything if the manager doesn't define both
+    /// halves of the protocol, matching CPython.
+    pub(super) fn exec_before_with(&mut self, is_async: bool) -> Result<CallResult, RunError> {
+        let (enter, exit) = if is_async {
+            (StaticStrings::DunderAenter, StaticStrings::DunderAexit)
+        } else {
+            (StaticStrings::DunderEnter, StaticStrings::DunderExit)
+        };
+        self.check_context_manager(is_async, enter.into(), exit.into())?;
+
+        let manager = self.peek().clone_with_heap(self.heap);
+        self.call_attr(manager, enter.into(), ArgValues::Empty)
+    }
+
+    /// Calls `__exit__(type(exc), exc, None)` (or `__aexit__`) for the exception at TOS.
+    ///
+    /// Stack: `[manager, exc]`, both are left in place. The result of the call is pushed
+    /// by the caller.
+    pub(super) fn exec_with_except_start(&mut self, is_async: bool) -> Result<CallResult, RunError> {
+        let exit: StringId = if is_async {
+            StaticStrings::DunderAexit
+        } else {
+            StaticStrings::DunderExit
+        }
+        .into();
+
+        let exc = self.peek().clone_with_heap(self.heap);
+        // Pass the exception class itself, so `exc_type is ValueError` holds in `__exit__`
+        let exc_type = match exc.py_type(self.heap) {
+            Type::Exception(exc_type) => Value::Builtin(Builtins::ExcType(exc_type)),
+            other => Value::Builtin(Builtins::Type(other)),
+        };
+        let manager = self.stack[self.stack.len() - 2].clone_with_heap(self.heap);
+        let args = ArgValues::ArgsKargs {
+            args: vec![exc_type, exc, Value::None],
+            kwargs: KwargsValues::Empty,
+        };
+        self.call_attr(manager, exit, args)
+    }
+
+    /// Checks that the manager at TOS defines both the enter and exit methods.
+    fn check_context_manager(&mut self, is_async: bool, enter: StringId, exit: StringId) -> Result<(), RunError> {
+        let heap_data = match self.peek() {
+            Value::Ref(id) => Some(self.heap.get(*id)),
+            _ => None,
+        };
+        let (type_name, has_enter, has_exit) = match heap_data {
+            Some(HeapData::Instance(instance)) => {
+                let class_id = instance.class_id();
+                let has_enter = self.class_has_attr(class_id, enter);
+                let has_exit = self.class_has_attr(class_id, exit);
+                (
+                    class_name(class_id, self.heap, self.interns).to_string(),
+                    has_enter,
+                    has_exit,
+                )
+            }
+            Some(HeapData::Dataclass(dc)) => (
+                dc.name(self.interns).to_string(),
+                dc.methods().contains(self.interns.get_str(enter)),
+                dc.methods().contains(self.interns.get_str(exit)),
+            ),
+            _ => (self.peek().py_type(self.heap).to_string(), false, false),
+        };
+
+        if has_enter && has_exit {
+            return Ok(());
+        }
+        let protocol = if is_async {
+            "asynchronous context manager"
+        } else {
+            "context manager"
+        };
+        let missed = if has_enter {
+            format!(" (missed {} method)", self.interns.get_str(exit))
+        } else {
+            String::new()
+        };
+        Err(ExcType::type_error(format!(
+            "'{type_name}' object does not support the {protocol} protocol{missed}"
+        )))
+    }
+
+    /// Returns whether the class or one of its bases defines `name`.
+    pub(super) fn class_has_attr(&mut self, class_id: HeapId, name: StringId) -> bool {
+        let value = lookup_class_attr(class_id, self.interns.get_str(name), self.heap, self.interns);
+        let found = value.is_some();
+        value.drop_with_heap(self.heap);
+        found
+    }
+}
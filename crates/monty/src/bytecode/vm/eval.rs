@@ -0,0 +1,410 @@
+//! `eval()` and `exec()` for the VM.
+//!
+//! The VM holds the interns immutably while it runs, so it can't compile code itself. The
+//! first time some code is passed to `eval()`/`exec()` in a given kind of scope, the VM keeps
+//! the call's arguments and pauses with `FrameExit::Compile`. The caller of the VM compiles
+//! the code into the interns (see [`crate::eval`]), restores the VM and calls `resume_compile`,
+//! which calls the new function. Later calls find the compiled function and call it directly.
+//!
+//! Names are passed to the compiled code as arguments, so the code sees:
+//! - at module level without dicts, the module's globals, which it can assign
+//! - in a function without dicts, a copy of the caller's locals and the module's globals
+//! - with a globals dict, only the names in the globals and locals dicts
+//!
+//! `exec()` copies the names its code binds back into the locals dict, or the globals dict if
+//! no locals dict was given. Differences from CPython:
+//! - `exec()` in a function without dicts can't change the function's locals (as in CPython),
+//!   and there's no `locals()` to read the names it bound (`vars()` doesn't show them either)
+//! - at module level, names bound by the code that the module itself never uses are dropped
+//! - no `__builtins__` key is added to the dicts, and a globals dict can't hide builtins
+//! - code not compiled yet can't run inside special methods like `__repr__` or generators
+//!   consumed by builtins, since the VM can't pause there
+
+use std::{iter, mem};
+
+use super::{FrameExit, VM, call::CallResult};
+use crate::{
+    args::{ArgValues, KwargsValues, bind_args_into},
+    builtins::names_to_list,
+    eval::{CompileRequest, Compiled, EvalKey, EvalMode, EvalScope},
+    exception_private::{ExcType, RunError, RunResult},
+    heap::{DropWithHeap, HeapData},
+    intern::{FunctionId, StringId},
+    io::PrintWriter,
+    namespace::NamespaceId,
+    resource::ResourceTracker,
+    types::{Dict, PyTrait, str::allocate_string},
+    value::Value,
+};
+
+impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
+    /// Calls `eval()` or `exec()`.
+    ///
+    /// Calls the compiled code if it's been compiled for this kind of scope before. Otherwise
+    /// keeps the arguments in `pending_compile` and returns `CallResult::Compile`, so the VM
+    /// pauses for the code to be compiled and continues in `resume_compile`.
+    pub(super) fn call_eval(&mut self, mode: EvalMode, args: ArgValues) -> Result<CallResult, RunError> {
+        let name = match mode {
+            EvalMode::Eval => "eval",
+            EvalMode::Exec => "exec",
+        };
+        let count = args.count();
+        if !(1..=3).contains(&count) {
+            args.drop_with_heap(self.heap);
+            let msg = match (count, mode) {
+                (0, _) => format!("{name}() takes at least 1 positional argument (0 given)"),
+                (_, EvalMode::Eval) => format!("{name}() takes at most 3 arguments ({count} given)"),
+                (_, EvalMode::Exec) => format!("{name}() takes at most 3 positional arguments ({count} given)"),
+            };
+            return Err(ExcType::type_error(msg));
+        }
+        // `source` is positional-only, the count check above makes sure it's passed by position
+        let params: &[&str] = match mode {
+            EvalMode::Eval => &["source", "globals", "locals"],
+            EvalMode::Exec => &["source", "globals", "locals", "closure"],
+        };
+        let mut slots: [Option<Value>; 4] = Default::default();
+        bind_args_into(
+            name,
+            params,
+            1,
+            args,
+            &mut slots[..params.len()],
+            self.heap,
+            self.interns,
+        )?;
+        let [source, globals, locals, closure] = slots;
+        let source = source.expect("source is required");
+        if let Some(closure) = closure {
+            let is_none = matches!(closure, Value::None);
+            closure.drop_with_heap(self.heap);
+            if !is_none {
+                let is_code =
+                    matches!(&source, Value::Ref(id) if matches!(self.heap.get(*id), HeapData::CodeObject(_)));
+                source.drop_with_heap(self.heap);
+                globals.drop_with_heap(self.heap);
+                locals.drop_with_heap(self.heap);
+                return Err(ExcType::type_error(if is_code {
+                    "cannot use a closure with this code object"
+                } else {
+                    "closure can only be used when source is a code object"
+                }));
+            }
+        }
+        let globals = globals.filter(|v| !matches!(v, Value::None));
+        let locals = locals.filter(|v| !matches!(v, Value::None));
+
+        let prepared = self.prepare_eval_call(name, mode, &source, globals.as_ref(), locals.as_ref());
+        source.drop_with_heap(self.heap);
+        let (key, params) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                globals.drop_with_heap(self.heap);
+                locals.drop_with_heap(self.heap);
+                return Err(e);
+            }
+        };
+
+        // The hidden first argument is the dict `exec()` copies bound names into
+        let namespace = match (locals, globals) {
+            (Some(locals), globals) => {
+                globals.drop_with_heap(self.heap);
+                locals
+            }
+            (None, Some(globals)) => globals,
+            (None, None) => Value::None,
+        };
+        let args: Vec<Value> = iter::once(namespace).chain(params).collect();
+
+        if let Some(func_id) = self.interns.get_eval_function(&key) {
+            self.call_eval_function(func_id, args)
+        } else {
+            debug_assert!(self.pending_compile.is_empty(), "eval() arguments already pending");
+            self.pending_compile = args;
+            Ok(CallResult::Compile(CompileRequest::Eval(key)))
+        }
+    }
+
+    /// Resumes execution after the VM paused with `FrameExit::Compile`.
+    ///
+    /// Calls the compiled function of an `eval()`/`exec()` call, or retries a `setattr()` call
+    /// whose name is now interned, with the arguments kept in `pending_compile`. Raises the
+    /// error from compiling at the call instead if there was one.
+    pub fn resume_compile(&mut self, compiled: RunResult<Compiled>) -> Result<FrameExit, RunError> {
+        let args = mem::take(&mut self.pending_compile);
+        let result = match compiled {
+            Ok(Compiled::Eval(func_id)) => self.call_eval_function(func_id, args),
+            Ok(Compiled::Name) => self.call_setattr(ArgValues::ArgsKargs {
+                args,
+                kwargs: KwargsValues::Empty,
+            }),
+            Err(e) => {
+                args.drop_with_heap(self.heap);
+                Err(e)
+            }
+        };
+        match result {
+            Ok(CallResult::Push(value)) => {
+                self.push(value);
+                self.run()
+            }
+            Ok(_) => self.run(),
+            Err(e) => self.resume_with_exception(e),
+        }
+    }
+
+    /// Drops the arguments of an `eval()`/`exec()` or `setattr()` call that can't pause for
+    /// the interns to be extended.
+    pub(super) fn drop_pending_compile(&mut self) {
+        let args = mem::take(&mut self.pending_compile);
+        args.drop_with_heap(self.heap);
+    }
+
+    /// Copies local `slot` into the dict in local slot 0 as `name_id`, for `ExportLocal`.
+    ///
+    /// Removes the name from the dict if the local is unbound. Does nothing if slot 0
+    /// doesn't hold a dict, when `exec()` was called without one.
+    pub(super) fn export_local(&mut self, namespace_idx: NamespaceId, slot: u16, name_id: StringId) -> RunResult<()> {
+        let namespace = self.namespaces.get(namespace_idx);
+        let Value::Ref(dict_id) = *namespace.get(NamespaceId::new(0)) else {
+            return Ok(());
+        };
+        if !matches!(self.heap.get(dict_id), HeapData::Dict(_)) {
+            return Ok(());
+        }
+        let mut value = namespace.get(NamespaceId::new(usize::from(slot))).copy_for_extend();
+        // Locals captured by functions defined in the code are stored in cells
+        if let Value::Ref(id) = value
+            && let HeapData::Cell(cell_value) = self.heap.get(id)
+        {
+            value = cell_value.copy_for_extend();
+        }
+
+        let interns = self.interns;
+        let key = Value::InternString(name_id);
+        if matches!(value, Value::Undefined) {
+            let removed = self.heap.with_entry_mut(dict_id, |heap, data| {
+                let HeapData::Dict(dict) = data else {
+                    return Err(RunError::internal("ExportLocal: namespace is not a dict"));
+                };
+                dict.pop(&key, heap, interns)
+            })?;
+            if let Some((key, value)) = removed {
+                key.drop_with_heap(self.heap);
+                value.drop_with_heap(self.heap);
+            }
+        } else {
+            if let Value::Ref(id) = &value {
+                self.heap.inc_ref(*id);
+            }
+            let old = self.heap.with_entry_mut(dict_id, |heap, data| {
+                let HeapData::Dict(dict) = data else {
+                    value.drop_with_heap(heap);
+                    return Err(RunError::internal("ExportLocal: namespace is not a dict"));
+                };
+                dict.set(key, value, heap, interns)
+            })?;
+            old.drop_with_heap(self.heap);
+        }
+        Ok(())
+    }
+
+    /// Calls the function compiled for an `eval()`/`exec()` call with `args`.
+    fn call_eval_function(&mut self, func_id: FunctionId, args: Vec<Value>) -> Result<CallResult, RunError> {
+        let args = ArgValues::ArgsKargs {
+            args,
+            kwargs: KwargsValues::Empty,
+        };
+        self.call_sync_function(func_id, &[], Vec::new(), args)
+    }
+
+    /// Checks the arguments of an `eval()`/`exec()` call, returning the key of the code to run
+    /// and the values of the names passed to it.
+    fn prepare_eval_call(
+        &mut self,
+        name: &str,
+        mode: EvalMode,
+        source: &Value,
+        globals: Option<&Value>,
+        locals: Option<&Value>,
+    ) -> RunResult<(EvalKey, Vec<Value>)> {
+        let (source, filename, mode) = if let Value::Ref(id) = source
+            && let HeapData::CodeObject(code) = self.heap.get(*id)
+        {
+            // `exec()` runs code compiled for `eval()` as an expression statement
+            let mode = match mode {
+                EvalMode::Eval => code.mode(),
+                EvalMode::Exec => EvalMode::Exec,
+            };
+            (code.source().to_owned(), code.filename().to_owned(), mode)
+        } else if let Some(source) = source.as_either_str(self.heap) {
+            (source.as_str(self.interns).to_owned(), "<string>".to_owned(), mode)
+        } else {
+            return Err(ExcType::type_error(format!(
+                "{name}() arg 1 must be a string, bytes or code object"
+            )));
+        };
+        if let Some(globals) = globals
+            && !self.is_dict(globals)
+        {
+            return Err(ExcType::type_error(format!(
+                "{name}() globals must be a dict, not {}",
+                globals.py_type(self.heap)
+            )));
+        }
+        if let Some(locals) = locals
+            && !self.is_dict(locals)
+        {
+            return Err(ExcType::type_error("locals must be a mapping"));
+        }
+
+        let scope = if globals.is_some() {
+            EvalScope::Isolated
+        } else if locals.is_some() || self.current_frame().function_id.is_some() {
+            EvalScope::Local
+        } else {
+            EvalScope::Module
+        };
+
+        let mut params: Vec<(String, Value)> = Vec::new();
+        match scope {
+            EvalScope::Module => {}
+            EvalScope::Local if locals.is_none() => self.caller_locals(&mut params),
+            EvalScope::Local | EvalScope::Isolated => {
+                // Later dicts take precedence, so locals shadow globals
+                for dict in [globals, locals].into_iter().flatten() {
+                    self.dict_params(dict, &mut params);
+                }
+            }
+        }
+        for (_, value) in &params {
+            if let Value::Ref(id) = value {
+                self.heap.inc_ref(*id);
+            }
+        }
+
+        let (names, values) = params.into_iter().unzip();
+        let key = EvalKey {
+            source,
+            filename,
+            mode,
+            scope,
+            names,
+        };
+        Ok((key, values))
+    }
+
+    /// Calls `dir()` without an argument, returning a sorted list of the caller's local names.
+    pub(super) fn dir_locals(&mut self) -> RunResult<Value> {
+        let mut names = Vec::new();
+        self.for_each_caller_local(|name, _| names.push(name.to_owned()));
+        names.sort();
+        names_to_list(names, self.heap)
+    }
+
+    /// Calls `vars()` without an argument, returning a dict of the caller's local variables.
+    ///
+    /// Like `locals()` in a function, the dict is a copy: changing it doesn't change the variables.
+    pub(super) fn vars_locals(&mut self) -> RunResult<Value> {
+        let mut locals = Vec::new();
+        self.caller_locals(&mut locals);
+        for (_, value) in &locals {
+            if let Value::Ref(id) = value {
+                self.heap.inc_ref(*id);
+            }
+        }
+        let mut pairs = Vec::with_capacity(locals.len());
+        let mut locals = locals.into_iter();
+        while let Some((name, value)) = locals.next() {
+            match allocate_string(name, self.heap) {
+                Ok(key) => pairs.push((key, value)),
+                Err(e) => {
+                    value.drop_with_heap(self.heap);
+                    for (_, value) in locals {
+                        value.drop_with_heap(self.heap);
+                    }
+                    for (key, value) in pairs {
+                        key.drop_with_heap(self.heap);
+                        value.drop_with_heap(self.heap);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        let dict = Dict::from_pairs(pairs, self.heap, self.interns)?;
+        let dict_id = self.heap.allocate(HeapData::Dict(dict))?;
+        Ok(Value::Ref(dict_id))
+    }
+
+    /// Returns whether `value` is a dict.
+    fn is_dict(&self, value: &Value) -> bool {
+        matches!(value, Value::Ref(id) if matches!(self.heap.get(*id), HeapData::Dict(_)))
+    }
+
+    /// Adds the bound locals of the current frame to `params`.
+    ///
+    /// Values are copied without incrementing their reference counts.
+    fn caller_locals(&self, params: &mut Vec<(String, Value)>) {
+        self.for_each_caller_local(|name, value| add_param(params, name, value.copy_for_extend()));
+    }
+
+    /// Calls `f` with the name and value of each bound local of the current frame.
+    fn for_each_caller_local(&self, mut f: impl FnMut(&str, &Value)) {
+        let frame = self.current_frame();
+        let namespace = self.namespaces.get(frame.namespace_idx);
+        let mut slot = 0;
+        while let Some(name_id) = frame.code.local_name(slot) {
+            let index = NamespaceId::new(usize::from(slot));
+            slot += 1;
+            if name_id == StringId::default() {
+                continue;
+            }
+            let mut value = namespace.get(index);
+            if let Value::Ref(id) = value
+                && let HeapData::Cell(cell_value) = self.heap.get(*id)
+            {
+                value = cell_value;
+            }
+            if !matches!(value, Value::Undefined) {
+                f(self.interns.get_str(name_id), value);
+            }
+        }
+    }
+
+    /// Adds the entries of `dict` with identifier keys to `params`, replacing earlier ones.
+    ///
+    /// Values are copied without incrementing their reference counts.
+    fn dict_params(&self, dict: &Value, params: &mut Vec<(String, Value)>) {
+        let Value::Ref(dict_id) = dict else {
+            return;
+        };
+        let HeapData::Dict(dict) = self.heap.get(*dict_id) else {
+            return;
+        };
+        for (key, value) in dict {
+            let Some(key) = key.as_either_str(self.heap) else {
+                continue;
+            };
+            let key = key.as_str(self.interns);
+            if is_identifier(key) && key != "__builtins__" {
+                add_param(params, key, value.copy_for_extend());
+            }
+        }
+    }
+}
+
+/// Sets `name` to `value` in `params`, replacing an earlier value for the same name.
+fn add_param(params: &mut Vec<(String, Value)>, name: &str, value: Value) {
+    if let Some((_, existing)) = params.iter_mut().find(|(existing, _)| existing == name) {
+        *existing = value;
+    } else {
+        params.push((name.to_owned(), value));
+    }
+}
+
+/// Returns whether `name` is a valid Python identifier, which names passed to eval/exec code
+/// must be.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c == '_' || c.is_alphabetic()) && chars.all(|c| c == '_' || c.is_alphanumeric())
+}
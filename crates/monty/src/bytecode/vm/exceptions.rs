@@ -1,10 +1,10 @@
 //! Exception handling helpers for the VM.
 
-use super::VM;
+use super::{ResumeMode, VM};
 use crate::{
     builtins::Builtins,
-    exception_private::{ExcType, ExceptionRaise, RawStackFrame, RunError, SimpleException},
-    heap::HeapData,
+    exception_private::{ExcType, ExceptionRaise, RawStackFrame, RunError, SimpleException, exc_types_from_value},
+    heap::{DropWithHeap, HeapData, HeapId},
     intern::{StaticStrings, StringId},
     io::PrintWriter,
     resource::ResourceTracker,
@@ -68,31 +68,108 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     /// Takes ownership of the exception value and drops it properly.
     /// The `is_raise` flag indicates if this is from a `raise` statement (hide caret).
     pub(super) fn make_exception(&mut self, exc_value: Value, is_raise: bool) -> RunError {
-        let simple_exc = match &exc_value {
-            // Exception instance on heap
-            Value::Ref(heap_id) => {
-                if let HeapData::Exception(exc) = self.heap.get(*heap_id) {
-                    // Clone the exception
-                    let exc_clone = exc.clone();
-                    // Drop the value with proper heap cleanup
-                    exc_value.drop_with_heap(self.heap);
-                    exc_clone
-                } else {
-                    // Not an exception type
-                    exc_value.drop_with_heap(self.heap);
-                    SimpleException::new_msg(ExcType::TypeError, "exceptions must derive from BaseException")
+        let simple_exc = self.take_exception(exc_value).unwrap_or_else(not_an_exception);
+        self.raise_exception(simple_exc, is_raise)
+    }
+
+    /// Creates a RunError to re-raise a handled exception (bare `raise`).
+    ///
+    /// Keeps the traceback recorded when the exception was caught, so it still points
+    /// at where the exception was originally raised.
+    pub(super) fn make_reraise(&mut self, exc_value: Value) -> RunError {
+        let exc = self.take_exception(exc_value).unwrap_or_else(not_an_exception);
+        match exc.traceback().cloned() {
+            Some(frame) => RunError::Exc(ExceptionRaise {
+                exc,
+                frame: Some(frame),
+                hide_caret: false,
+            }),
+            None => self.raise_exception(exc, true),
+        }
+    }
+
+    /// Creates a RunError for `raise exc from cause`.
+    ///
+    /// The cause may be an exception instance, an exception type (instantiated with no
+    /// message), or `None` which just hides the context. Takes ownership of both values.
+    pub(super) fn make_exception_from(&mut self, exc_value: Value, cause_value: Value) -> RunError {
+        let exc = self.take_exception(exc_value);
+        let cause = self.take_cause(cause_value);
+        let simple_exc = match (exc, cause) {
+            (Some(mut exc), Ok(cause)) => {
+                exc.set_cause(cause, self.heap);
+                exc
+            }
+            (Some(exc), Err(RunError::Exc(err))) => {
+                exc.drop_with_heap(self.heap);
+                err.exc
+            }
+            (Some(exc), Err(err)) => {
+                exc.drop_with_heap(self.heap);
+                return err;
+            }
+            (None, cause) => {
+                if let Ok(Some(cause)) = cause {
+                    self.heap.dec_ref(cause);
                 }
+                not_an_exception()
             }
+        };
+        self.raise_exception(simple_exc, true)
+    }
+
+    /// Converts an exception instance or type into the exception to raise.
+    ///
+    /// Takes ownership of the value and drops it properly. Returns `None` if the
+    /// value isn't an exception.
+    fn take_exception(&mut self, exc_value: Value) -> Option<SimpleException> {
+        let exc = match &exc_value {
+            // Exception instance on heap
+            Value::Ref(heap_id) => match self.heap.get(*heap_id) {
+                HeapData::Exception(exc) => Some(exc.clone()),
+                _ => None,
+            },
             // Exception type (e.g., `raise ValueError` instead of `raise ValueError()`)
             // Instantiate with no message
-            Value::Builtin(Builtins::ExcType(exc_type)) => SimpleException::new_none(*exc_type),
-            // Invalid exception value
-            _ => {
-                exc_value.drop_with_heap(self.heap);
-                SimpleException::new_msg(ExcType::TypeError, "exceptions must derive from BaseException")
+            Value::Builtin(Builtins::ExcType(exc_type)) => Some(SimpleException::new_none(*exc_type)),
+            _ => None,
+        };
+        if let Some(exc) = &exc {
+            exc.inc_chain_refs(self.heap);
+        }
+        exc_value.drop_with_heap(self.heap);
+        exc
+    }
+
+    /// Converts the value after `raise ... from` into the heap exception to use as the cause,
+    /// or `None` for `raise ... from None`.
+    ///
+    /// Takes ownership of the value. An exception type is instantiated with no message, and
+    /// any other value is a `TypeError`.
+    fn take_cause(&mut self, cause_value: Value) -> Result<Option<HeapId>, RunError> {
+        let cause = match &cause_value {
+            Value::None => Ok(None),
+            Value::Ref(heap_id) if matches!(self.heap.get(*heap_id), HeapData::Exception(_)) => {
+                self.heap.inc_ref(*heap_id);
+                Ok(Some(*heap_id))
             }
+            Value::Builtin(Builtins::ExcType(exc_type)) => {
+                let exc = SimpleException::new_none(*exc_type);
+                self.heap
+                    .allocate(HeapData::Exception(exc))
+                    .map(Some)
+                    .map_err(Into::into)
+            }
+            _ => Err(
+                SimpleException::new_msg(ExcType::TypeError, "exception causes must derive from BaseException").into(),
+            ),
         };
+        cause_value.drop_with_heap(self.heap);
+        cause
+    }
 
+    /// Wraps an exception in a RunError with a frame for the current execution point.
+    fn raise_exception(&self, exc: SimpleException, is_raise: bool) -> RunError {
         // Create frame with appropriate hide_caret setting
         let frame = if is_raise {
             RawStackFrame::from_raise(self.current_position(), self.current_frame_name())
@@ -101,12 +178,52 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         };
 
         RunError::Exc(ExceptionRaise {
-            exc: simple_exc,
+            exc,
             frame: Some(frame),
             hide_caret: false,
         })
     }
 
+    /// Records the exception currently being handled as the context of a newly raised one.
+    ///
+    /// Exceptions that already have a context (e.g. re-raised ones) keep it, and an exception
+    /// re-raised from its own handler (`except E as e: raise e`) isn't its own context.
+    fn set_implicit_context(&mut self, exc: &mut SimpleException) {
+        if exc.context().is_some() {
+            return;
+        }
+        if let Some(Value::Ref(handled_id)) = self.exception_stack.last()
+            && let HeapData::Exception(handled) = self.heap.get(*handled_id)
+            && handled != exc
+        {
+            let handled_id = *handled_id;
+            self.heap.inc_ref(handled_id);
+            exc.set_context(handled_id);
+        }
+    }
+
+    /// Drops operand stack values down to `depth`, along with the handled exceptions of
+    /// any except handlers being left.
+    ///
+    /// An except handler keeps its exception on the operand stack while the handler body
+    /// runs, and on `exception_stack` for bare `raise` and exception chaining. A `return`
+    /// or an exception leaving the handler body skips its `ClearException`, so the handled
+    /// exception is released here once its last operand stack slot is gone - otherwise it
+    /// would become the context of unrelated later exceptions.
+    pub(super) fn truncate_stack(&mut self, depth: usize) {
+        while self.stack.len() > depth {
+            let value = self.stack.pop().unwrap();
+            if let Some(id) = value.ref_id()
+                && self.exception_stack.last().and_then(Value::ref_id) == Some(id)
+                && !self.stack.iter().any(|v| v.ref_id() == Some(id))
+            {
+                let handled = self.exception_stack.pop().unwrap();
+                handled.drop_with_heap(self.heap);
+            }
+            value.drop_with_heap(self.heap);
+        }
+    }
+
     /// Handles an exception by searching for a handler in the exception table.
     ///
     /// Returns:
@@ -128,21 +245,23 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
             return Some(self.unwind_for_traceback(error));
         }
 
-        // Only catchable exceptions can be handled
-        let exc_info = match &error {
-            RunError::Exc(exc) => exc.clone(),
-            RunError::UncatchableExc(_) | RunError::Internal(_) => unreachable!(),
-        };
+        // Exceptions raised while another is being handled record it as their context
+        if let RunError::Exc(exc) = &mut error {
+            self.set_implicit_context(&mut exc.exc);
+        }
 
-        // Create exception value to push on stack
-        let exc_value = self.create_exception_value(&exc_info);
-        let exc_value = match exc_value {
-            Ok(v) => v,
-            Err(e) => return Some(e),
-        };
+        self.propagate_exception(error)
+    }
 
-        // Search for handler in current and outer frames
+    /// Searches the current and outer frames for a handler for a catchable exception.
+    ///
+    /// Unlike `handle_exception`, the error is used as is, without adding the current frame
+    /// or the exception being handled: exceptions re-raised at the end of `except*` clauses
+    /// already have both.
+    pub(super) fn propagate_exception(&mut self, mut error: RunError) -> Option<RunError> {
         loop {
+            // The instruction the exception is raised at won't run again for its iterator steps
+            self.drop_iter_steps(self.frames.len());
             let frame = self.current_frame();
             let ip = u32::try_from(self.instruction_ip).expect("instruction IP exceeds u32");
 
@@ -152,11 +271,20 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
                 let handler_offset = usize::try_from(entry.handler()).expect("handler offset exceeds usize");
                 let target_stack_depth = frame.stack_base + entry.stack_depth() as usize;
 
+                // Create exception value to push on stack, recording the traceback so far
+                let RunError::Exc(exc) = &error else {
+                    unreachable!("only catchable exceptions are handled")
+                };
+                let exc_value = self.create_exception_value(exc);
+                // The exception value took its own references to the exception's chain
+                error.drop_with_heap(self.heap);
+                let exc_value = match exc_value {
+                    Ok(v) => v,
+                    Err(e) => return Some(e),
+                };
+
                 // Unwind stack to target depth (drop excess values)
-                while self.stack.len() > target_stack_depth {
-                    let value = self.stack.pop().unwrap();
-                    value.drop_with_heap(self.heap);
-                }
+                self.truncate_stack(target_stack_depth);
 
                 // Push exception value onto stack (handler expects it)
                 let exc_for_stack = exc_value.clone_with_heap(self.heap);
@@ -172,11 +300,48 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
                 return None; // Continue execution at handler
             }
 
+            // No handler in a generator frame - the exception finishes the generator
+            if let Some(gen_frame) = frame.generator
+                && let RunError::Exc(exc) = &mut error
+            {
+                let exc_type = exc.exc.exc_type();
+                if gen_frame.resume == ResumeMode::Close
+                    && matches!(
+                        exc_type,
+                        ExcType::GeneratorExit | ExcType::StopIteration | ExcType::StopAsyncIteration
+                    )
+                {
+                    // `close()` succeeded: the generator exited via `GeneratorExit`
+                    self.pop_frame();
+                    self.push(Value::None);
+                    return None;
+                }
+                let is_async = matches!(self.heap.get(gen_frame.id), HeapData::Generator(g) if g.is_async);
+                if exc_type == ExcType::StopIteration || (is_async && exc_type == ExcType::StopAsyncIteration) {
+                    // A `StopIteration` escaping a generator would look like exhaustion (PEP 479),
+                    // as would a `StopAsyncIteration` escaping an async generator
+                    let kind = if is_async { "async generator" } else { "generator" };
+                    let mut runtime_error =
+                        SimpleException::new_msg(ExcType::RuntimeError, format!("{kind} raised {exc_type}"));
+                    let mut stop_iteration = std::mem::replace(&mut exc.exc, SimpleException::new_none(exc_type));
+                    stop_iteration.set_traceback(exc.frame.clone());
+                    match self.heap.allocate(HeapData::Exception(stop_iteration)) {
+                        Ok(id) => runtime_error.set_cause(Some(id), self.heap),
+                        Err(err) => return self.handle_exception(err.into()),
+                    }
+                    exc.exc = runtime_error;
+                }
+            }
+
+            // No handler in this frame - an exception leaving the frame that started a
+            // nested run propagates to the Rust caller of `call_sync`
+            if self.frame_floor > 0 && self.frames.len() == self.frame_floor + 1 {
+                return Some(self.pop_frame_for_traceback(error));
+            }
+
             // No handler in this frame - pop frame and try outer
             if self.frames.len() <= 1 {
                 // No more frames - exception is unhandled
-                exc_value.drop_with_heap(self.heap);
-
                 // For spawned tasks, fail the task instead of propagating
                 if self.is_spawned_task() {
                     match self.handle_task_failure(error) {
@@ -194,28 +359,18 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
                 return Some(error);
             }
 
-            // Get the call site position before popping frame
-            // This is where the caller invoked the function that's failing
-            let call_position = self.current_frame().call_position;
-
-            // Pop this frame
-            self.pop_frame();
+            // Pop this frame, adding the caller's frame info to the traceback
+            error = self.pop_frame_for_traceback(error);
 
-            // Add caller frame info to traceback (if we have call position)
-            if let Some(pos) = call_position {
-                let frame_name = self.current_frame_name();
-                match &mut error {
-                    RunError::Exc(exc) => exc.add_caller_frame(pos, frame_name),
-                    RunError::UncatchableExc(exc) => exc.add_caller_frame(pos, frame_name),
-                    RunError::Internal(_) => {}
-                }
-            }
-
-            // Update instruction_ip for the new frame
-            self.instruction_ip = self
-                .current_frame()
-                .call_position
-                .map_or(0, |p| p.start().line as usize);
+            // Continue the search at the caller's call instruction (its IP was synced
+            // past the instruction before the call, or rewound to an instruction waiting
+            // for its iterator steps)
+            let ip = self.current_frame().ip;
+            self.instruction_ip = if self.iter_steps_waiting() {
+                ip
+            } else {
+                ip.saturating_sub(1)
+            };
         }
     }
 
@@ -223,23 +378,33 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     ///
     /// Used for uncatchable exceptions (like RecursionError) that can't be handled
     /// but still need a complete traceback showing all active call frames.
+    ///
+    /// Inside a nested run, only the frames above the run's floor are unwound; the
+    /// caller of `call_sync` continues unwinding its own frames.
     fn unwind_for_traceback(&mut self, mut error: RunError) -> RunError {
         // Pop frames and add caller frame info to the traceback
-        while self.frames.len() > 1 {
-            // Get the call site position before popping frame
-            let call_position = self.current_frame().call_position;
-
-            // Pop this frame (cleans up namespace, etc.)
-            self.pop_frame();
-
-            // Add caller frame info to traceback
-            if let Some(pos) = call_position {
-                let frame_name = self.current_frame_name();
-                match &mut error {
-                    RunError::Exc(exc) => exc.add_caller_frame(pos, frame_name),
-                    RunError::UncatchableExc(exc) => exc.add_caller_frame(pos, frame_name),
-                    RunError::Internal(_) => {}
-                }
+        while self.frames.len() > self.frame_floor.max(1) {
+            error = self.pop_frame_for_traceback(error);
+        }
+        error
+    }
+
+    /// Pops the current frame and adds the caller's frame info to the error's traceback.
+    fn pop_frame_for_traceback(&mut self, mut error: RunError) -> RunError {
+        // Get the call site position before popping frame
+        // This is where the caller invoked the function that's failing
+        let call_position = self.current_frame().call_position;
+
+        // Pop this frame (cleans up namespace, etc.)
+        self.pop_frame();
+
+        // Add caller frame info to traceback (if we have call position)
+        if let Some(pos) = call_position {
+            let frame_name = self.current_frame_name();
+            match &mut error {
+                RunError::Exc(exc) => exc.add_caller_frame(pos, frame_name),
+                RunError::UncatchableExc(exc) => exc.add_caller_frame(pos, frame_name),
+                RunError::Internal(_) => {}
             }
         }
         error
@@ -247,13 +412,128 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
 
     /// Creates an exception Value from exception info.
     ///
-    /// Allocates an Exception on the heap and returns a Value::Ref to it.
-    fn create_exception_value(&mut self, exc: &ExceptionRaise) -> Result<Value, RunError> {
-        let exception = exc.exc.clone();
+    /// Allocates an Exception on the heap and returns a Value::Ref to it. The traceback
+    /// is kept on the exception in case it's later chained to another one. The new exception
+    /// takes its own references to the chained exceptions.
+    pub(super) fn create_exception_value(&mut self, exc: &ExceptionRaise) -> Result<Value, RunError> {
+        let mut exception = exc.exc.clone();
+        exception.inc_chain_refs(self.heap);
+        exception.set_traceback(exc.frame.clone());
         let heap_id = self.heap.allocate(HeapData::Exception(exception))?;
         Ok(Value::Ref(heap_id))
     }
 
+    /// Splits the part of an exception matching an `except*` clause off the unhandled rest.
+    ///
+    /// Returns the new rest and the match, either of which may be `None`. A matching exception
+    /// that isn't a group is wrapped in a new group, as in CPython. The match also becomes the
+    /// exception being handled, for bare `raise` and exception chaining in the clause body.
+    pub(super) fn check_exc_star_match(&mut self, rest: Value, exc_type: &Value) -> Result<(Value, Value), RunError> {
+        let handler_types = match exc_types_from_value(exc_type, self.heap) {
+            Some(types) if types.iter().any(|t| t.is_group()) => Err(ExcType::type_error(
+                "catching ExceptionGroup with except* is not allowed. Use except instead.",
+            )),
+            Some(types) => Ok(types),
+            None => Err(ExcType::except_invalid_type_error()),
+        };
+        let handler_types = match handler_types {
+            Ok(types) => types,
+            Err(err) => {
+                rest.drop_with_heap(self.heap);
+                return Err(err);
+            }
+        };
+
+        let matches = |exc_type: ExcType| handler_types.iter().any(|t| exc_type.is_subclass_of(*t));
+        let split = match &rest {
+            Value::Ref(id) => match self.heap.get(*id) {
+                HeapData::Exception(exc) if exc.exc_type().is_group() => match exc.split(&matches) {
+                    (Some(matched), unmatched) => Some((matched, unmatched)),
+                    (None, _) => None,
+                },
+                HeapData::Exception(exc) if matches(exc.exc_type()) => Some((exc.clone().wrap_in_group(), None)),
+                _ => None,
+            },
+            // Everything was already handled by earlier clauses
+            _ => None,
+        };
+        let Some((matched, unmatched)) = split else {
+            return Ok((rest, Value::None));
+        };
+        matched.inc_chain_refs(self.heap);
+        if let Some(exc) = &unmatched {
+            exc.inc_chain_refs(self.heap);
+        }
+        rest.drop_with_heap(self.heap);
+
+        let new_rest = match unmatched {
+            Some(exc) => Value::Ref(self.heap.allocate(HeapData::Exception(exc))?),
+            None => Value::None,
+        };
+        let match_id = match self.heap.allocate(HeapData::Exception(matched)) {
+            Ok(id) => id,
+            Err(err) => {
+                new_rest.drop_with_heap(self.heap);
+                return Err(err.into());
+            }
+        };
+        let match_value = Value::Ref(match_id);
+        self.exception_stack.push(match_value.clone_with_heap(self.heap));
+        Ok((new_rest, match_value))
+    }
+
+    /// Works out the exception to raise once all `except*` clauses have run, if any.
+    ///
+    /// Takes the exception the clauses handled, the list of exceptions raised in their bodies
+    /// and the unhandled rest, and stops handling the exception. Parts of the original
+    /// exception that are re-raised keep its traceback, while a new group combining them with
+    /// other exceptions gets frames only as it propagates, like in CPython.
+    pub(super) fn reraise_star(&mut self, exception: Value, raised: Value, rest: Value) -> Option<RunError> {
+        if let Some(handled) = self.exception_stack.pop() {
+            handled.drop_with_heap(self.heap);
+        }
+        let orig = self.heap_exception(&exception).cloned();
+        let mut raised_excs: Vec<SimpleException> = match &raised {
+            Value::Ref(id) => match self.heap.get(*id) {
+                HeapData::List(list) => list
+                    .as_slice()
+                    .iter()
+                    .filter_map(|v| self.heap_exception(v))
+                    .cloned()
+                    .collect(),
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+        raised_excs.extend(self.heap_exception(&rest).cloned());
+        let exc = orig.and_then(|orig| SimpleException::reraise_star(&orig, raised_excs));
+        if let Some(exc) = &exc {
+            exc.inc_chain_refs(self.heap);
+        }
+        exception.drop_with_heap(self.heap);
+        raised.drop_with_heap(self.heap);
+        rest.drop_with_heap(self.heap);
+
+        let exc = exc?;
+        let frame = exc.traceback().cloned();
+        Some(RunError::Exc(ExceptionRaise {
+            exc,
+            frame,
+            hide_caret: false,
+        }))
+    }
+
+    /// Returns the exception a value refers to, if it is one.
+    fn heap_exception(&self, value: &Value) -> Option<&SimpleException> {
+        match value {
+            Value::Ref(id) => match self.heap.get(*id) {
+                HeapData::Exception(exc) => Some(exc),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     /// Checks if an exception matches an exception type for except clause matching.
     ///
     /// Validates that `exc_type` is a valid exception type (ExcType or tuple of ExcTypes).
@@ -290,3 +570,8 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         }
     }
 }
+
+/// The TypeError raised for `raise` with a value that isn't an exception.
+fn not_an_exception() -> SimpleException {
+    SimpleException::new_msg(ExcType::TypeError, "exceptions must derive from BaseException")
+}
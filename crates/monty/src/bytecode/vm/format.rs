@@ -1,15 +1,71 @@
-//! F-string and value formatting helpers for the VM.
+//! F-string, `str.format()`, `%` and value formatting helpers for the VM.
+//!
+//! Formatting runs in the VM so instances of user-defined classes can be formatted with
+//! their `__format__`, `__str__` and `__repr__` methods.
+
+use std::borrow::Cow;
+
+use num_bigint::BigInt;
+use num_traits::{FromPrimitive, ToPrimitive};
 
 use super::VM;
 use crate::{
-    exception_private::{ExcType, RunError, SimpleException},
-    fstring::{ParsedFormatSpec, ascii_escape, decode_format_spec, format_string, format_with_spec},
+    args::ArgValues,
+    defer_drop,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    fstring::{
+        ConversionFlag, FieldAccessor, ParsedFormatSpec, TemplateField, TemplatePart, ascii_escape, decode_format_spec,
+        format_string, format_with_spec, format_with_spec_str, invalid_format_spec, parse_format_template,
+    },
+    heap::{DropWithHeap, Heap, HeapData},
+    intern::{StaticStrings, StringId},
     io::PrintWriter,
-    resource::{DepthGuard, ResourceTracker},
-    types::{PyTrait, str::allocate_string},
+    percent_format::{
+        PercentCount, PercentLayout, PercentPart, PercentSpec, parse_percent_template, percent_format_float,
+        percent_format_int, percent_pad_bytes, percent_pad_str,
+    },
+    resource::{DepthGuard, ResourceTracker, check_format_size, check_output_size},
+    types::{AttrCallResult, Bytes, PyTrait, Type, class::class_name, str::allocate_string},
     value::Value,
 };
 
+/// How deeply format specs of `str.format()` fields may nest fields, as in CPython.
+const MAX_FORMAT_DEPTH: u8 = 2;
+
+/// How the replacement fields of a `str.format()` template number positional arguments.
+///
+/// A template can't mix automatic (`{}`) and manual (`{0}`) numbering.
+#[derive(Debug, Clone, Copy)]
+enum FieldNumbering {
+    /// No positional field seen yet.
+    Unknown,
+    /// Automatic numbering, holding the index of the next `{}` field.
+    Auto(usize),
+    /// Manual numbering.
+    Manual,
+}
+
+/// The arguments a `str.format()`, `str.format_map()` or `%` format looks values up in.
+struct FormatArgs {
+    /// Positional arguments of `format()`, or the values on the right of `%`.
+    positional: Vec<Value>,
+    /// Keyword arguments of `format()`, as `(name, value)` pairs.
+    keywords: Vec<(Value, Value)>,
+    /// The mapping passed to `format_map()`, or on the right of `%`.
+    mapping: Option<Value>,
+}
+
+impl<T: ResourceTracker> DropWithHeap<T> for FormatArgs {
+    fn drop_with_heap(self, heap: &mut Heap<T>) {
+        self.positional.drop_with_heap(heap);
+        for (key, value) in self.keywords {
+            key.drop_with_heap(heap);
+            value.drop_with_heap(heap);
+        }
+        self.mapping.drop_with_heap(heap);
+    }
+}
+
 impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
     /// Builds an f-string by concatenating n string parts from the stack.
     pub(super) fn build_fstring(&mut self, count: usize) -> Result<(), RunError> {
@@ -59,6 +115,33 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
 
         let value = self.pop();
 
+        // Without a conversion, instances of user-defined classes format themselves
+        if conversion == 0 && self.is_instance(&value) {
+            let spec = format_spec
+                .as_ref()
+                .map_or_else(String::new, |spec_value| self.format_spec_text(spec_value));
+            let result = self.format_object(&value, &spec);
+            format_spec.drop_with_heap(self.heap);
+            value.drop_with_heap(self.heap);
+            let value = allocate_string(result?, self.heap)?;
+            self.push(value);
+            return Ok(());
+        }
+
+        // With a conversion, instances are converted via `__str__`/`__repr__` up front,
+        // after which the result is formatted as a plain string
+        let (value, conversion) = if self.is_instance(&value) {
+            match self.instance_to_str_for_format(value, conversion) {
+                Ok(value) => (value, 1),
+                Err(e) => {
+                    format_spec.drop_with_heap(self.heap);
+                    return Err(e);
+                }
+            }
+        } else {
+            (value, conversion)
+        };
+
         // Format with spec applied to original value type, or convert and format as string
         let formatted = if let Some(spec_value) = format_spec {
             // Get the parsed format spec
@@ -127,6 +210,21 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
         Ok(())
     }
 
+    /// Converts a user-defined instance to a string for f-string interpolation.
+    ///
+    /// `!r` and `!a` use `__repr__` (with `!a` escaping non-ASCII characters), everything
+    /// else uses `__str__`. Takes ownership of `value`.
+    fn instance_to_str_for_format(&mut self, value: Value, conversion: u8) -> Result<Value, RunError> {
+        let value = self.instance_to_str(value, conversion >= 2)?;
+        if conversion != 3 {
+            return Ok(value);
+        }
+        let mut guard = DepthGuard::default();
+        let escaped = ascii_escape(&value.py_str(self.heap, &mut guard, self.interns));
+        value.drop_with_heap(self.heap);
+        Ok(allocate_string(escaped, self.heap)?)
+    }
+
     /// Gets a ParsedFormatSpec from a format spec value.
     ///
     /// The `value_for_error` parameter is used to include the value type in error messages.
@@ -142,18 +240,624 @@ impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
                 // Dynamic format spec - parse the string
                 let mut guard = DepthGuard::default();
                 let spec_str = spec_value.py_str(self.heap, &mut guard, self.interns);
-                spec_str.parse::<ParsedFormatSpec>().map_err(|invalid| {
+                let spec = spec_str
+                    .parse::<ParsedFormatSpec>()
                     // Only fetch type in error path
-                    let value_type = value_for_error.py_type(self.heap);
-                    RunError::Exc(
-                        SimpleException::new_msg(
+                    .map_err(|invalid| invalid_format_spec(&invalid, value_for_error.py_type(self.heap)))?;
+                // Unlike constant specs, the width and precision of dynamic specs aren't limited to 16 bits
+                check_format_size(spec.width, spec.precision.unwrap_or(0), self.heap.tracker())?;
+                Ok(spec)
+            }
+        }
+    }
+
+    /// Returns the text of an f-string format spec value, to pass to `__format__`.
+    fn format_spec_text(&self, spec_value: &Value) -> String {
+        match spec_value {
+            Value::Int(n) if *n < 0 => {
+                let encoded = u64::try_from((-*n) - 1).expect("format spec encoding validated non-negative");
+                decode_format_spec(encoded).to_string()
+            }
+            _ => {
+                let mut guard = DepthGuard::default();
+                spec_value.py_str(self.heap, &mut guard, self.interns).into_owned()
+            }
+        }
+    }
+
+    /// Formats `value` with `spec` like `format(value, spec)`.
+    ///
+    /// Instances of user-defined classes call their `__format__` method. Without one, an
+    /// empty spec gives `str(value)` and any other spec is an error, as with `object.__format__`.
+    pub(super) fn format_object(&mut self, value: &Value, spec: &str) -> RunResult<String> {
+        if !self.is_instance(value) {
+            let mut guard = DepthGuard::default();
+            return format_with_spec_str(value, spec, self.heap, &mut guard, self.interns);
+        }
+
+        if let Some(func) = self.lookup_dunder(value, "__format__") {
+            let spec_value = match allocate_string(spec.to_owned(), self.heap) {
+                Ok(spec_value) => spec_value,
+                Err(e) => {
+                    func.drop_with_heap(self.heap);
+                    return Err(e);
+                }
+            };
+            let result = self.call_dunder(func, value, Some(&spec_value));
+            spec_value.drop_with_heap(self.heap);
+            let result = result?;
+            let formatted = result
+                .as_either_str(self.heap)
+                .map(|s| s.as_str(self.interns).to_owned());
+            return match formatted {
+                Some(formatted) => {
+                    result.drop_with_heap(self.heap);
+                    Ok(formatted)
+                }
+                None => {
+                    let type_name = result.py_type(self.heap);
+                    result.drop_with_heap(self.heap);
+                    Err(ExcType::type_error(format!(
+                        "__format__ must return a str, not {type_name}"
+                    )))
+                }
+            };
+        }
+
+        if !spec.is_empty() {
+            let Value::Ref(id) = value else {
+                unreachable!("instances are heap values")
+            };
+            let HeapData::Instance(instance) = self.heap.get(*id) else {
+                unreachable!("value checked to be an instance")
+            };
+            let name = class_name(instance.class_id(), self.heap, self.interns);
+            return Err(ExcType::type_error(format!(
+                "unsupported format string passed to {name}.__format__"
+            )));
+        }
+        let value = self.instance_to_str(value.clone_with_heap(self.heap), false)?;
+        let mut guard = DepthGuard::default();
+        let formatted = value.py_str(self.heap, &mut guard, self.interns).into_owned();
+        value.drop_with_heap(self.heap);
+        Ok(formatted)
+    }
+
+    /// Calls `str.format()` or `str.format_map()` with the string `template`.
+    pub(super) fn call_str_format(&mut self, template: &str, name_id: StringId, args: ArgValues) -> RunResult<Value> {
+        let this = self;
+        let args = if name_id == StaticStrings::FormatMap {
+            let mapping = args.get_one_arg("str.format_map", this.heap)?;
+            FormatArgs {
+                positional: Vec::new(),
+                keywords: Vec::new(),
+                mapping: Some(mapping),
+            }
+        } else {
+            let (positional, keywords) = args.into_parts();
+            FormatArgs {
+                positional: positional.collect(),
+                keywords: keywords.into_iter().collect(),
+                mapping: None,
+            }
+        };
+        defer_drop!(args, this);
+
+        let mut numbering = FieldNumbering::Unknown;
+        let formatted = this.render_template(template, args, &mut numbering, MAX_FORMAT_DEPTH)?;
+        allocate_string(formatted, this.heap)
+    }
+
+    /// Renders a `str.format()` template, or a format spec containing replacement fields.
+    ///
+    /// `depth` limits how deeply specs may nest fields.
+    fn render_template(
+        &mut self,
+        template: &str,
+        args: &FormatArgs,
+        numbering: &mut FieldNumbering,
+        depth: u8,
+    ) -> RunResult<String> {
+        if depth == 0 {
+            return Err(SimpleException::new_msg(ExcType::ValueError, "Max string recursion exceeded").into());
+        }
+        let parts = parse_format_template(template)
+            .map_err(|msg| RunError::from(SimpleException::new_msg(ExcType::ValueError, msg)))?;
+
+        let mut result = String::with_capacity(template.len());
+        for part in parts {
+            match part {
+                TemplatePart::Literal(text) => result.push_str(text),
+                TemplatePart::Field(field) => {
+                    let value = self.lookup_field(&field, args, numbering)?;
+                    let spec = if field.spec.contains('{') {
+                        match self.render_template(field.spec, args, numbering, depth - 1) {
+                            Ok(spec) => Cow::Owned(spec),
+                            Err(e) => {
+                                value.drop_with_heap(self.heap);
+                                return Err(e);
+                            }
+                        }
+                    } else {
+                        Cow::Borrowed(field.spec)
+                    };
+                    result.push_str(&self.format_field(value, field.conversion, &spec)?);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Looks up the value of a replacement field, returning an owned value.
+    fn lookup_field(
+        &mut self,
+        field: &TemplateField<'_>,
+        args: &FormatArgs,
+        numbering: &mut FieldNumbering,
+    ) -> RunResult<Value> {
+        let arg_name = field.arg_name;
+        let mut value = if arg_name.bytes().all(|b| b.is_ascii_digit()) {
+            let index = if arg_name.is_empty() {
+                match *numbering {
+                    FieldNumbering::Manual => {
+                        return Err(SimpleException::new_msg(
                             ExcType::ValueError,
-                            format!("Invalid format specifier '{invalid}' for object of type '{value_type}'"),
+                            "cannot switch from manual field specification to automatic field numbering",
                         )
-                        .into(),
+                        .into());
+                    }
+                    FieldNumbering::Unknown => {
+                        *numbering = FieldNumbering::Auto(1);
+                        0
+                    }
+                    FieldNumbering::Auto(next) => {
+                        *numbering = FieldNumbering::Auto(next + 1);
+                        next
+                    }
+                }
+            } else {
+                if matches!(numbering, FieldNumbering::Auto(_)) {
+                    return Err(SimpleException::new_msg(
+                        ExcType::ValueError,
+                        "cannot switch from automatic field numbering to manual field specification",
+                    )
+                    .into());
+                }
+                *numbering = FieldNumbering::Manual;
+                arg_name.parse().unwrap_or(usize::MAX)
+            };
+            if args.mapping.is_some() {
+                return Err(
+                    SimpleException::new_msg(ExcType::ValueError, "Format string contains positional fields").into(),
+                );
+            }
+            match args.positional.get(index) {
+                Some(value) => value.clone_with_heap(self.heap),
+                None => {
+                    return Err(SimpleException::new_msg(
+                        ExcType::IndexError,
+                        format!("Replacement index {index} out of range for positional args tuple"),
                     )
-                })
+                    .into());
+                }
+            }
+        } else if let Some(mapping) = &args.mapping {
+            let key = allocate_string(arg_name.to_owned(), self.heap)?;
+            let value = mapping.py_getitem(&key, self.heap, self.interns);
+            key.drop_with_heap(self.heap);
+            value?
+        } else {
+            let found = args.keywords.iter().find(|(key, _)| {
+                key.as_either_str(self.heap)
+                    .is_some_and(|key| key.as_str(self.interns) == arg_name)
+            });
+            match found {
+                Some((_, value)) => value.clone_with_heap(self.heap),
+                None => return Err(SimpleException::new_msg(ExcType::KeyError, arg_name).into()),
+            }
+        };
+
+        for accessor in &field.accessors {
+            let next = match accessor {
+                FieldAccessor::Attr(name) => self.format_field_attr(&value, name),
+                FieldAccessor::Index(key) => self.format_field_item(&value, key),
+            };
+            value.drop_with_heap(self.heap);
+            value = next?;
+        }
+        Ok(value)
+    }
+
+    /// Looks up the attribute `name` of `value` for a `.name` accessor in a replacement field.
+    fn format_field_attr(&mut self, value: &Value, name: &str) -> RunResult<Value> {
+        // Only interned names can be attributes of any value
+        let Some(name_id) = self.interns.find_str(name) else {
+            return Err(ExcType::attribute_error(value.py_type(self.heap), name));
+        };
+        match value.py_getattr(name_id, self.heap, self.interns)? {
+            AttrCallResult::Value(value) => Ok(value),
+            AttrCallResult::OsCall(_, args) | AttrCallResult::ExternalCall(_, args) => {
+                args.drop_with_heap(self.heap);
+                Err(SimpleException::new_msg(
+                    ExcType::RuntimeError,
+                    format!("attribute '{name}' can't be looked up in a format string"),
+                )
+                .into())
+            }
+        }
+    }
+
+    /// Looks up `value[key]` for a `[key]` accessor in a replacement field.
+    ///
+    /// Keys made of digits are ints, any other key is a string.
+    fn format_field_item(&mut self, value: &Value, key: &str) -> RunResult<Value> {
+        let key = match key.parse::<i64>() {
+            Ok(n) if key.bytes().all(|b| b.is_ascii_digit()) => Value::Int(n),
+            _ => allocate_string(key.to_owned(), self.heap)?,
+        };
+        let item = value.py_getitem(&key, self.heap, self.interns);
+        key.drop_with_heap(self.heap);
+        item
+    }
+
+    /// Applies the conversion and format spec of a replacement field to `value`.
+    ///
+    /// Takes ownership of `value`.
+    fn format_field(&mut self, value: Value, conversion: ConversionFlag, spec: &str) -> RunResult<String> {
+        if conversion == ConversionFlag::None {
+            let formatted = self.format_object(&value, spec);
+            value.drop_with_heap(self.heap);
+            return formatted;
+        }
+
+        let text = self.value_to_text(value, conversion != ConversionFlag::Str)?;
+        let text = if conversion == ConversionFlag::Ascii {
+            ascii_escape(&text)
+        } else {
+            text
+        };
+        if spec.is_empty() {
+            return Ok(text);
+        }
+        let spec = spec
+            .parse::<ParsedFormatSpec>()
+            .map_err(|invalid| invalid_format_spec(&invalid, Type::Str))?;
+        Ok(format_string(&text, &spec)?)
+    }
+
+    /// Converts `value` to text with `str()`, or `repr()` if `use_repr` is set.
+    ///
+    /// Instances of user-defined classes use their `__str__`/`__repr__` methods. Takes
+    /// ownership of `value`.
+    fn value_to_text(&mut self, value: Value, use_repr: bool) -> RunResult<String> {
+        let mut guard = DepthGuard::default();
+        if self.is_instance(&value) {
+            let converted = self.instance_to_str(value, use_repr)?;
+            let text = converted.py_str(self.heap, &mut guard, self.interns).into_owned();
+            converted.drop_with_heap(self.heap);
+            return Ok(text);
+        }
+        let text = if use_repr {
+            value.py_repr(self.heap, &mut guard, self.interns)
+        } else {
+            value.py_str(self.heap, &mut guard, self.interns)
+        }
+        .into_owned();
+        value.drop_with_heap(self.heap);
+        Ok(text)
+    }
+
+    /// Applies printf-style formatting for `template % args` when `template` is a str or bytes.
+    ///
+    /// Returns `None` for other left operands, which use `py_mod`.
+    pub(super) fn percent_format(&mut self, template: &Value, args: &Value) -> RunResult<Option<Value>> {
+        let (template, is_bytes) = if let Some(s) = template.as_either_str(self.heap) {
+            (s.as_str(self.interns).as_bytes().to_vec(), false)
+        } else if let Some(b) = self.bytes_of(template) {
+            (b, true)
+        } else {
+            return Ok(None);
+        };
+        let parts = parse_percent_template(&template, is_bytes)
+            .map_err(|msg| RunError::from(SimpleException::new_msg(ExcType::ValueError, msg)))?;
+
+        // A tuple holds the positional arguments, anything else is the only one
+        let positional = match args {
+            Value::Ref(id) => match self.heap.get(*id) {
+                HeapData::Tuple(tuple) => tuple.as_slice().iter().map(Value::copy_for_extend).collect(),
+                _ => vec![args.copy_for_extend()],
+            },
+            _ => vec![args.copy_for_extend()],
+        };
+        for value in &positional {
+            if let Value::Ref(id) = value {
+                self.heap.inc_ref(*id);
+            }
+        }
+        let is_mapping =
+            matches!(args, Value::Ref(id) if matches!(self.heap.get(*id), HeapData::Dict(_) | HeapData::List(_)));
+        let format_args = FormatArgs {
+            positional,
+            keywords: Vec::new(),
+            mapping: is_mapping.then(|| args.clone_with_heap(self.heap)),
+        };
+
+        let this = self;
+        defer_drop!(format_args, this);
+        let mut next_arg = 0;
+        let mut result = Vec::with_capacity(template.len());
+        for part in parts {
+            // Each conversion is checked on its own, so check the whole result as it grows too
+            match part {
+                PercentPart::Literal(text) => {
+                    check_output_size(result.len() + text.len(), this.heap.tracker())?;
+                    result.extend_from_slice(text);
+                }
+                PercentPart::Spec(spec) => {
+                    let formatted = this.percent_format_spec(&spec, format_args, &mut next_arg, is_bytes)?;
+                    check_output_size(result.len() + formatted.len(), this.heap.tracker())?;
+                    result.extend_from_slice(&formatted);
+                }
+            }
+        }
+
+        // Unused arguments are an error, unless they're a mapping for `%(key)s` specifiers
+        if next_arg < format_args.positional.len() && format_args.mapping.is_none() {
+            let kind = if is_bytes { "bytes" } else { "string" };
+            return Err(ExcType::type_error(format!(
+                "not all arguments converted during {kind} formatting"
+            )));
+        }
+        let value = if is_bytes {
+            Value::Ref(this.heap.allocate(HeapData::Bytes(Bytes::new(result)))?)
+        } else {
+            let result = String::from_utf8(result).expect("str template and conversions are UTF-8");
+            allocate_string(result, this.heap)?
+        };
+        Ok(Some(value))
+    }
+
+    /// Formats the value of one `%` conversion specifier.
+    ///
+    /// Takes the value and any `*` width or precision from the positional arguments at
+    /// `next_arg`, or the value from the mapping for `%(key)s`.
+    fn percent_format_spec(
+        &mut self,
+        spec: &PercentSpec<'_>,
+        args: &FormatArgs,
+        next_arg: &mut usize,
+        is_bytes: bool,
+    ) -> RunResult<Vec<u8>> {
+        let mut layout = PercentLayout {
+            flags: spec.flags,
+            width: 0,
+            precision: None,
+        };
+        match spec.width {
+            Some(PercentCount::Fixed(width)) => layout.width = width,
+            Some(PercentCount::FromArgs) => {
+                // A negative width from `*` left-aligns
+                let width = self.percent_star_arg(args, next_arg)?;
+                layout.flags.left |= width < 0;
+                layout.width = usize::try_from(width.unsigned_abs()).unwrap_or(usize::MAX);
+            }
+            None => {}
+        }
+        layout.precision = match spec.precision {
+            Some(PercentCount::Fixed(precision)) => Some(precision),
+            Some(PercentCount::FromArgs) => {
+                let precision = self.percent_star_arg(args, next_arg)?;
+                Some(usize::try_from(precision).unwrap_or(0))
+            }
+            None => None,
+        };
+        check_format_size(layout.width, layout.precision.unwrap_or(0), self.heap.tracker())?;
+
+        let value = if let Some(key) = spec.key {
+            let Some(mapping) = &args.mapping else {
+                return Err(ExcType::type_error("format requires a mapping"));
+            };
+            let key = if is_bytes {
+                Value::Ref(self.heap.allocate(HeapData::Bytes(Bytes::new(key.to_vec())))?)
+            } else {
+                let key = std::str::from_utf8(key).expect("keys of str templates are UTF-8");
+                allocate_string(key.to_owned(), self.heap)?
+            };
+            let value = mapping.py_getitem(&key, self.heap, self.interns);
+            key.drop_with_heap(self.heap);
+            // As in CPython, positional specifiers can't follow a mapping key
+            *next_arg = args.positional.len();
+            value?
+        } else {
+            self.percent_next_arg(args, next_arg)?
+        };
+        self.percent_convert(value, spec.conversion, &layout, is_bytes)
+    }
+
+    /// Returns a clone of the next positional argument of a `%` format.
+    fn percent_next_arg(&mut self, args: &FormatArgs, next_arg: &mut usize) -> RunResult<Value> {
+        let Some(value) = args.positional.get(*next_arg) else {
+            return Err(ExcType::type_error("not enough arguments for format string"));
+        };
+        *next_arg += 1;
+        Ok(value.clone_with_heap(self.heap))
+    }
+
+    /// Returns the next positional argument of a `%` format as a `*` width or precision.
+    fn percent_star_arg(&mut self, args: &FormatArgs, next_arg: &mut usize) -> RunResult<i64> {
+        let value = self.percent_next_arg(args, next_arg)?;
+        let count = match value {
+            Value::Int(n) => Ok(n),
+            Value::Bool(b) => Ok(i64::from(b)),
+            _ => Err(ExcType::type_error("* wants int")),
+        };
+        value.drop_with_heap(self.heap);
+        count
+    }
+
+    /// Converts `value` for a `%` conversion type and pads it to the layout.
+    ///
+    /// Takes ownership of `value`.
+    fn percent_convert(
+        &mut self,
+        value: Value,
+        conversion: u8,
+        layout: &PercentLayout,
+        is_bytes: bool,
+    ) -> RunResult<Vec<u8>> {
+        match conversion {
+            b's' | b'b' if is_bytes => {
+                let bytes = self.bytes_of(&value);
+                let type_name = value.py_type(self.heap);
+                value.drop_with_heap(self.heap);
+                match bytes {
+                    Some(bytes) => Ok(percent_pad_bytes(&bytes, layout)),
+                    None => Err(ExcType::type_error(format!(
+                        "%b requires a bytes-like object, or an object that implements __bytes__, not '{type_name}'"
+                    ))),
+                }
+            }
+            b's' | b'r' | b'a' => {
+                let text = self.value_to_text(value, conversion != b's')?;
+                // `bytes` templates always use `ascii()` for `%r`
+                if conversion == b'a' || is_bytes {
+                    Ok(percent_pad_str(&ascii_escape(&text), layout).into_bytes())
+                } else {
+                    Ok(percent_pad_str(&text, layout).into_bytes())
+                }
+            }
+            b'c' => {
+                let formatted = self.percent_char(&value, layout, is_bytes);
+                value.drop_with_heap(self.heap);
+                formatted
+            }
+            b'd' | b'i' | b'u' | b'o' | b'x' | b'X' => {
+                let n = self.percent_int(&value, conversion);
+                value.drop_with_heap(self.heap);
+                Ok(percent_format_int(&n?, conversion, layout).into_bytes())
+            }
+            _ => {
+                let f = self.percent_float(&value);
+                value.drop_with_heap(self.heap);
+                Ok(percent_format_float(f?, conversion, layout).into_bytes())
+            }
+        }
+    }
+
+    /// Formats `value` for `%c`: an int code point (or byte), or a single character.
+    fn percent_char(&self, value: &Value, layout: &PercentLayout, is_bytes: bool) -> RunResult<Vec<u8>> {
+        let code = match value {
+            Value::Int(n) => Some(*n),
+            Value::Bool(b) => Some(i64::from(*b)),
+            _ if self.long_int_of(value).is_some() => Some(i64::MAX),
+            _ => None,
+        };
+        if is_bytes {
+            let byte = match code {
+                Some(code) => Some(u8::try_from(code).map_err(|_| {
+                    RunError::from(SimpleException::new_msg(
+                        ExcType::OverflowError,
+                        "%c arg not in range(256)",
+                    ))
+                })?),
+                None => self.bytes_of(value).filter(|b| b.len() == 1).map(|b| b[0]),
+            };
+            let Some(byte) = byte else {
+                return Err(ExcType::type_error(
+                    "%c requires an integer in range(256) or a single byte",
+                ));
+            };
+            return Ok(percent_pad_bytes(&[byte], layout));
+        }
+
+        let c = match code {
+            Some(code) => Some(u32::try_from(code).ok().and_then(char::from_u32).ok_or_else(|| {
+                RunError::from(SimpleException::new_msg(
+                    ExcType::OverflowError,
+                    "%c arg not in range(0x110000)",
+                ))
+            })?),
+            None => value.as_either_str(self.heap).and_then(|s| {
+                let mut chars = s.as_str(self.interns).chars();
+                chars.next().filter(|_| chars.next().is_none())
+            }),
+        };
+        let Some(c) = c else {
+            return Err(ExcType::type_error("%c requires int or char"));
+        };
+        Ok(percent_pad_str(c.encode_utf8(&mut [0; 4]), layout).into_bytes())
+    }
+
+    /// Converts `value` to an integer for `%d`, `%o`, `%x` and the like.
+    ///
+    /// Decimal conversions also accept floats, truncating them.
+    fn percent_int(&self, value: &Value, conversion: u8) -> RunResult<BigInt> {
+        let decimal = matches!(conversion, b'd' | b'i' | b'u');
+        if let Some(n) = self.long_int_of(value) {
+            return Ok(n.clone());
+        }
+        match value {
+            Value::Int(n) => Ok(BigInt::from(*n)),
+            Value::Bool(b) => Ok(BigInt::from(i64::from(*b))),
+            Value::Float(f) if decimal => {
+                if f.is_nan() {
+                    Err(SimpleException::new_msg(ExcType::ValueError, "cannot convert float NaN to integer").into())
+                } else {
+                    BigInt::from_f64(f.trunc()).ok_or_else(|| {
+                        SimpleException::new_msg(ExcType::OverflowError, "cannot convert float infinity to integer")
+                            .into()
+                    })
+                }
             }
+            _ => {
+                let conversion = char::from(conversion);
+                let expected = if decimal { "a real number" } else { "an integer" };
+                Err(ExcType::type_error(format!(
+                    "%{conversion} format: {expected} is required, not {}",
+                    value.py_type(self.heap)
+                )))
+            }
+        }
+    }
+
+    /// Converts `value` to a float for `%e`, `%f`, `%g` and the like.
+    fn percent_float(&self, value: &Value) -> RunResult<f64> {
+        if let Some(n) = self.long_int_of(value) {
+            return n.to_f64().filter(|f| f.is_finite()).ok_or_else(|| {
+                SimpleException::new_msg(ExcType::OverflowError, "int too large to convert to float").into()
+            });
+        }
+        match value {
+            Value::Float(f) => Ok(*f),
+            Value::Int(n) => Ok(*n as f64),
+            Value::Bool(b) => Ok(f64::from(u8::from(*b))),
+            _ => Err(ExcType::type_error(format!(
+                "must be real number, not {}",
+                value.py_type(self.heap)
+            ))),
+        }
+    }
+
+    /// Returns the value of `value` if it's a long int.
+    fn long_int_of(&self, value: &Value) -> Option<&BigInt> {
+        match value {
+            Value::Ref(id) => match self.heap.get(*id) {
+                HeapData::LongInt(li) => Some(li.inner()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the contents of `value` if it's a bytes object.
+    fn bytes_of(&self, value: &Value) -> Option<Vec<u8>> {
+        match value {
+            Value::InternBytes(id) => Some(self.interns.get_bytes(*id).to_vec()),
+            Value::Ref(id) => match self.heap.get(*id) {
+                HeapData::Bytes(b) => Some(b.as_slice().to_vec()),
+                _ => None,
+            },
+            _ => None,
         }
     }
 }
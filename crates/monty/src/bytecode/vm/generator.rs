@@ -0,0 +1,745 @@
+//! Generator support for the VM.
+//!
+//! A running generator executes in an ordinary `CallFrame` whose `generator` field records
+//! which generator the frame belongs to and how the result should be delivered to whoever
+//! resumed it. `YieldValue` pops the frame and moves its namespace, operand stack region,
+//! exception stack region and instruction pointer back into the `Generator` heap object;
+//! resuming registers the namespace again and pushes a fresh frame. This is the same
+//! save/restore approach the scheduler uses for task frames, so a suspended generator is
+//! plain heap data and serializes with the heap.
+//!
+//! `for` loops, `yield from`, `next()`, the generator methods and calls to builtins that
+//! consume a generator (`sum()`, `list()`, ...) resume generators inside the main run loop
+//! (see `iter` for the latter). Operations that consume a generator in Rust (`in`, unpacking,
+//! builtins called by other builtins) can't return to the run loop, so they step it with a
+//! nested run (see `generator_next_sync`).
+//! Async generators use the same machinery, resumed by awaiting their `__anext__()` (see
+//! `async_iter`).
+//!
+//! Differences from CPython:
+//! - `throw()` and `close()` are not forwarded to a sub-generator of `yield from`.
+//! - A suspended generator that is freed doesn't run its `finally` blocks.
+//! - Builtins other than `next()`, `sum()`, `any()` and `all()` collect the generator into a
+//!   list before iterating.
+
+use super::{CallFrame, CallResult, VM};
+use crate::{
+    args::ArgValues,
+    builtins::{Builtins, BuiltinsFunctions},
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{StaticStrings, StringId},
+    io::PrintWriter,
+    modules::{ModuleFunctions, collections::CollectionsFunctions, math::MathFunctions},
+    resource::{DepthGuard, ResourceTracker},
+    types::{GeneratorState, List, PyTrait, Type},
+    value::Value,
+};
+
+/// Number of items pulled from a generator per call to the `sum()` builtin.
+///
+/// Summing in chunks keeps memory bounded for huge generators while reusing the
+/// builtin's own addition and error handling.
+pub(super) const SUM_CHUNK_SIZE: usize = 256;
+
+/// How the result of resuming a generator is delivered to the code that resumed it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) enum ResumeMode {
+    /// Resumed by `ForIter`: a yielded value is pushed for the loop body; on return the
+    /// generator is popped from the caller's stack and execution jumps to `end_ip`.
+    ForIter { end_ip: usize },
+    /// Resumed by `SendSub` for `yield from`: on return the sub-generator on the caller's
+    /// stack is replaced by the return value and execution jumps to `end_ip`.
+    YieldFrom { end_ip: usize },
+    /// Resumed by `next()`, `__next__()`, `send()` or `throw()`: on return `StopIteration`
+    /// is raised in the caller.
+    Next,
+    /// Resumed by `next(gen, default)`: the default sits on top of the caller's stack and
+    /// is replaced by a yielded value, or left as the result on return.
+    NextDefault,
+    /// Resumed by `close()` or `aclose()` with `GeneratorExit` raised at the suspended `yield`.
+    Close,
+    /// Resumed by awaiting the result of an async generator's `__anext__()` or `asend()`:
+    /// a yielded value is the result of the `await`; on return `StopAsyncIteration` is
+    /// raised in the caller.
+    AsyncNext,
+    /// Resumed for an iterator step of the caller's instruction (see `iter`): a yielded value
+    /// is pushed for the instruction, which runs again; a return is recorded in the step.
+    Step,
+    /// Resumed from Rust by `generator_next_sync`: the yielded or returned value ends the
+    /// nested run.
+    Sync,
+}
+
+/// Links a call frame to the generator it is running.
+///
+/// The frame owns a reference to the generator for as long as it runs.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub(crate) struct GeneratorFrame {
+    /// The generator being run.
+    pub id: HeapId,
+    /// How to deliver the next yielded or returned value.
+    pub resume: ResumeMode,
+    /// Length of the VM's exception stack when the generator was resumed.
+    ///
+    /// Exceptions above this belong to the generator and are saved when it yields.
+    pub exc_base: usize,
+}
+
+impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
+    /// Returns whether `value` is a (synchronous) generator object.
+    ///
+    /// Async generators are excluded: they can only be iterated with `async for`.
+    pub(super) fn is_generator(&self, value: &Value) -> bool {
+        matches!(value, Value::Ref(id) if matches!(self.heap.get(*id), HeapData::Generator(g) if !g.is_async))
+    }
+
+    /// Resumes a generator, pushing a frame that continues its body.
+    ///
+    /// `sent` becomes the value of the suspended `yield` expression; it must be `None` for a
+    /// generator that hasn't started. Returns `Ok(false)` without pushing a frame if the
+    /// generator has already completed.
+    pub(super) fn resume_generator(&mut self, gen_id: HeapId, sent: Value, resume: ResumeMode) -> RunResult<bool> {
+        let HeapData::Generator(generator) = self.heap.get(gen_id) else {
+            unreachable!("resume_generator called with non-generator heap_id")
+        };
+        let state = generator.state;
+        let error = match state {
+            GeneratorState::Running => Some(SimpleException::new_msg(
+                ExcType::ValueError,
+                "generator already executing",
+            )),
+            GeneratorState::Created if !matches!(sent, Value::None) => Some(SimpleException::new_msg(
+                ExcType::TypeError,
+                "can't send non-None value to a just-started generator",
+            )),
+            _ => None,
+        };
+        if let Some(error) = error {
+            sent.drop_with_heap(self.heap);
+            return Err(error.into());
+        }
+        if state == GeneratorState::Completed {
+            sent.drop_with_heap(self.heap);
+            return Ok(false);
+        }
+
+        let HeapData::Generator(generator) = self.heap.get_mut(gen_id) else {
+            unreachable!("generator checked above")
+        };
+        generator.state = GeneratorState::Running;
+        let func_id = generator.func_id;
+        let ip = generator.ip;
+        let namespace = std::mem::take(&mut generator.namespace);
+        let stack = std::mem::take(&mut generator.stack);
+        let exception_stack = std::mem::take(&mut generator.exception_stack);
+        let cells = generator.frame_cells.clone();
+
+        let namespace_idx = match self.namespaces.register_prebuilt(namespace, self.heap) {
+            Ok(idx) => idx,
+            Err(e) => {
+                if let HeapData::Generator(generator) = self.heap.get_mut(gen_id) {
+                    generator.state = GeneratorState::Completed;
+                }
+                stack.drop_with_heap(self.heap);
+                exception_stack.drop_with_heap(self.heap);
+                sent.drop_with_heap(self.heap);
+                return Err(e.into());
+            }
+        };
+
+        let call_position = self.current_position();
+        let exc_base = self.exception_stack.len();
+        self.exception_stack.extend(exception_stack);
+        let stack_base = self.stack.len();
+        self.stack.extend(stack);
+        if state == GeneratorState::Suspended {
+            // The value of the `yield` expression the generator is suspended at
+            self.push(sent);
+        }
+
+        // The frame holds its own reference to the generator while it runs
+        self.heap.inc_ref(gen_id);
+        let code = &self.interns.get_function(func_id).code;
+        let mut frame = CallFrame::new_function(code, stack_base, namespace_idx, func_id, cells, Some(call_position));
+        frame.ip = ip;
+        frame.generator = Some(GeneratorFrame {
+            id: gen_id,
+            resume,
+            exc_base,
+        });
+        self.frames.push(frame);
+        Ok(true)
+    }
+
+    /// Suspends the generator running in the current frame after it yielded `value`.
+    ///
+    /// The frame's state is saved into the generator and the value is delivered according
+    /// to the frame's resume mode. Returns `Some(value)` when the generator was resumed by
+    /// `generator_next_sync`, in which case the run loop must return the value.
+    pub(super) fn yield_value(&mut self, value: Value) -> RunResult<Option<Value>> {
+        let Some(gen_frame) = self.current_frame().generator else {
+            value.drop_with_heap(self.heap);
+            return Err(RunError::internal("YieldValue: current frame is not a generator"));
+        };
+        let frame = self.frames.pop().expect("no frame to pop");
+
+        let stack = self.stack.split_off(frame.stack_base);
+        let exc_base = gen_frame.exc_base.min(self.exception_stack.len());
+        let exception_stack = self.exception_stack.split_off(exc_base);
+        let namespace = self.namespaces.take(frame.namespace_idx, self.heap);
+
+        let HeapData::Generator(generator) = self.heap.get_mut(gen_frame.id) else {
+            unreachable!("generator frame must reference a generator")
+        };
+        generator.namespace = namespace;
+        generator.stack = stack;
+        generator.exception_stack = exception_stack;
+        generator.ip = frame.ip;
+        generator.state = GeneratorState::Suspended;
+        let is_async = generator.is_async;
+        // Release the frame's reference; this frees the generator if nothing else holds it
+        self.heap.dec_ref(gen_frame.id);
+
+        match gen_frame.resume {
+            ResumeMode::ForIter { .. }
+            | ResumeMode::YieldFrom { .. }
+            | ResumeMode::Next
+            | ResumeMode::AsyncNext
+            | ResumeMode::Step => {
+                self.push(value);
+                Ok(None)
+            }
+            ResumeMode::NextDefault => {
+                let default = self.pop();
+                default.drop_with_heap(self.heap);
+                self.push(value);
+                Ok(None)
+            }
+            ResumeMode::Close => {
+                value.drop_with_heap(self.heap);
+                let msg = if is_async {
+                    "async generator ignored GeneratorExit"
+                } else {
+                    "generator ignored GeneratorExit"
+                };
+                Err(SimpleException::new_msg(ExcType::RuntimeError, msg).into())
+            }
+            ResumeMode::Sync => Ok(Some(value)),
+        }
+    }
+
+    /// Delivers the return value of a generator whose frame has just been popped.
+    ///
+    /// Must not be called for `ResumeMode::Sync`, which ends the nested run instead.
+    pub(super) fn generator_returned(&mut self, resume: ResumeMode, value: Value) -> RunResult<()> {
+        match resume {
+            ResumeMode::ForIter { end_ip } => {
+                value.drop_with_heap(self.heap);
+                let generator = self.pop();
+                generator.drop_with_heap(self.heap);
+                self.current_frame_mut().ip = end_ip;
+                Ok(())
+            }
+            ResumeMode::YieldFrom { end_ip } => {
+                let generator = self.pop();
+                generator.drop_with_heap(self.heap);
+                self.push(value);
+                self.current_frame_mut().ip = end_ip;
+                Ok(())
+            }
+            ResumeMode::Next => Err(self.stop_iteration(value)),
+            ResumeMode::NextDefault => {
+                // The default left on the stack is the result
+                value.drop_with_heap(self.heap);
+                Ok(())
+            }
+            ResumeMode::Close => {
+                value.drop_with_heap(self.heap);
+                self.push(Value::None);
+                Ok(())
+            }
+            ResumeMode::AsyncNext => {
+                // Async generators can only `return` without a value
+                value.drop_with_heap(self.heap);
+                Err(ExcType::stop_async_iteration())
+            }
+            ResumeMode::Step => {
+                value.drop_with_heap(self.heap);
+                self.generator_step_returned();
+                Ok(())
+            }
+            ResumeMode::Sync => unreachable!("generator_returned called for a nested run"),
+        }
+    }
+
+    /// Creates the `StopIteration` raised when a generator returns `value`.
+    fn stop_iteration(&mut self, value: Value) -> RunError {
+        if matches!(value, Value::None) {
+            return ExcType::stop_iteration();
+        }
+        let mut guard = DepthGuard::default();
+        let msg = value.py_str(self.heap, &mut guard, self.interns).into_owned();
+        value.drop_with_heap(self.heap);
+        SimpleException::new_msg(ExcType::StopIteration, msg).into()
+    }
+
+    /// Calls a method of a generator object: `send()`, `throw()`, `close()` or `__next__()`.
+    ///
+    /// Takes ownership of `obj` and `args`.
+    pub(super) fn call_generator_method(
+        &mut self,
+        gen_id: HeapId,
+        obj: Value,
+        name_id: StringId,
+        args: ArgValues,
+    ) -> RunResult<CallResult> {
+        let result = match StaticStrings::from_string_id(name_id) {
+            Some(StaticStrings::Send) => match args.get_one_arg("generator.send", self.heap) {
+                Ok(sent) => self.generator_next(gen_id, sent),
+                Err(e) => Err(e),
+            },
+            Some(StaticStrings::DunderNext) => match args.check_zero_args("generator.__next__", self.heap) {
+                Ok(()) => self.generator_next(gen_id, Value::None),
+                Err(e) => Err(e),
+            },
+            Some(StaticStrings::Throw) => match args.get_one_arg("generator.throw", self.heap) {
+                Ok(exc) => self.generator_throw(gen_id, exc),
+                Err(e) => Err(e),
+            },
+            Some(StaticStrings::Close) => match args.check_zero_args("generator.close", self.heap) {
+                Ok(()) => self.generator_close(gen_id),
+                Err(e) => Err(e),
+            },
+            _ => {
+                args.drop_with_heap(self.heap);
+                Err(ExcType::attribute_error(Type::Generator, self.interns.get_str(name_id)))
+            }
+        };
+        obj.drop_with_heap(self.heap);
+        result
+    }
+
+    /// Resumes a generator for `next()`, `__next__()` or `send()`.
+    fn generator_next(&mut self, gen_id: HeapId, sent: Value) -> RunResult<CallResult> {
+        if self.resume_generator(gen_id, sent, ResumeMode::Next)? {
+            Ok(CallResult::FramePushed)
+        } else {
+            Err(ExcType::stop_iteration())
+        }
+    }
+
+    /// Raises `exc` inside a generator at the `yield` it is suspended at.
+    ///
+    /// A generator that hasn't started (or has finished) doesn't run: it is marked completed
+    /// and the exception propagates straight to the caller.
+    fn generator_throw(&mut self, gen_id: HeapId, exc: Value) -> RunResult<CallResult> {
+        let HeapData::Generator(generator) = self.heap.get_mut(gen_id) else {
+            unreachable!("generator_throw called with non-generator heap_id")
+        };
+        match generator.state {
+            GeneratorState::Suspended => {
+                let ip = generator.ip;
+                if let Err(e) = self.resume_generator(gen_id, Value::None, ResumeMode::Next) {
+                    exc.drop_with_heap(self.heap);
+                    return Err(e);
+                }
+                // Discard the sent `None` again - the `yield` raises instead of producing a value
+                self.stack.pop();
+                // Raise at the `YieldValue` instruction so the generator's handlers apply
+                self.instruction_ip = ip - 1;
+                Err(self.make_exception(exc, true))
+            }
+            GeneratorState::Running => {
+                exc.drop_with_heap(self.heap);
+                Err(SimpleException::new_msg(ExcType::ValueError, "generator already executing").into())
+            }
+            GeneratorState::Created | GeneratorState::Completed => {
+                generator.state = GeneratorState::Completed;
+                Err(self.make_exception(exc, true))
+            }
+        }
+    }
+
+    /// Raises `GeneratorExit` inside a suspended generator so it can clean up.
+    ///
+    /// Returns `None` once the generator has finished; a generator that yields again raises
+    /// `RuntimeError`.
+    pub(super) fn generator_close(&mut self, gen_id: HeapId) -> RunResult<CallResult> {
+        let HeapData::Generator(generator) = self.heap.get_mut(gen_id) else {
+            unreachable!("generator_close called with non-generator heap_id")
+        };
+        match generator.state {
+            GeneratorState::Suspended => {
+                let ip = generator.ip;
+                self.resume_generator(gen_id, Value::None, ResumeMode::Close)?;
+                self.stack.pop();
+                self.instruction_ip = ip - 1;
+                Err(SimpleException::new_none(ExcType::GeneratorExit).into())
+            }
+            GeneratorState::Running => {
+                Err(SimpleException::new_msg(ExcType::ValueError, "generator already executing").into())
+            }
+            GeneratorState::Created | GeneratorState::Completed => {
+                generator.state = GeneratorState::Completed;
+                Ok(CallResult::Push(Value::None))
+            }
+        }
+    }
+
+    /// Handles `next()` when its first argument is a generator, resuming it in the run loop.
+    ///
+    /// Takes ownership of `args`.
+    pub(super) fn call_next_generator(&mut self, args: ArgValues) -> RunResult<CallResult> {
+        let (generator, default) = args.get_one_two_args("next", self.heap)?;
+        let Value::Ref(gen_id) = generator else {
+            unreachable!("call_next_generator called without a generator")
+        };
+        let has_default = default.is_some();
+        if let Some(default) = default {
+            // Kept below the generator's frame until we know whether it's needed
+            self.push(default);
+        }
+        let mode = if has_default {
+            ResumeMode::NextDefault
+        } else {
+            ResumeMode::Next
+        };
+        let result = self.resume_generator(gen_id, Value::None, mode);
+        generator.drop_with_heap(self.heap);
+        match result {
+            Ok(true) => Ok(CallResult::FramePushed),
+            Ok(false) if has_default => Ok(CallResult::Push(self.pop())),
+            Ok(false) => Err(ExcType::stop_iteration()),
+            Err(e) => {
+                if has_default {
+                    let default = self.pop();
+                    default.drop_with_heap(self.heap);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Runs a generator until it yields, using a nested run of the VM loop.
+    ///
+    /// Returns `Ok(None)` once the generator is exhausted. Used by builtins and operations
+    /// that consume a generator in Rust.
+    pub(super) fn generator_next_sync(&mut self, gen_id: HeapId) -> RunResult<Option<Value>> {
+        self.check_nested_depth()?;
+        let floor = self.frames.len();
+        if !self.resume_generator(gen_id, Value::None, ResumeMode::Sync)? {
+            return Ok(None);
+        }
+        let value = self.run_nested(floor)?;
+        let HeapData::Generator(generator) = self.heap.get(gen_id) else {
+            unreachable!("generator_next_sync called with non-generator heap_id")
+        };
+        if generator.state == GeneratorState::Completed {
+            // The generator returned - its return value is discarded
+            value.drop_with_heap(self.heap);
+            Ok(None)
+        } else {
+            Ok(Some(value))
+        }
+    }
+
+    /// Runs a generator or iterator to completion, collecting its values into a list.
+    ///
+    /// Takes ownership of `value`; values that aren't generators or iterators are returned
+    /// unchanged.
+    pub(super) fn iterator_to_list(&mut self, value: Value) -> RunResult<Value> {
+        let Value::Ref(gen_id) = value else {
+            return Ok(value);
+        };
+        if !self.is_iterator(&value) {
+            return Ok(value);
+        }
+        let mut items = Vec::new();
+        loop {
+            match self.iterator_next_sync(gen_id) {
+                Ok(Some(item)) => items.push(item),
+                Ok(None) => break,
+                Err(e) => {
+                    items.drop_with_heap(self.heap);
+                    value.drop_with_heap(self.heap);
+                    return Err(e);
+                }
+            }
+        }
+        value.drop_with_heap(self.heap);
+        let list_id = self.heap.allocate(HeapData::List(List::new(items)))?;
+        Ok(Value::Ref(list_id))
+    }
+
+    /// Replaces generators and iterators among the positional arguments with lists of their values.
+    pub(super) fn iterator_args_to_lists(&mut self, args: ArgValues) -> RunResult<ArgValues> {
+        match args {
+            ArgValues::One(a) => Ok(ArgValues::One(self.iterator_to_list(a)?)),
+            ArgValues::Two(a, b) => {
+                let a = match self.iterator_to_list(a) {
+                    Ok(a) => a,
+                    Err(e) => {
+                        b.drop_with_heap(self.heap);
+                        return Err(e);
+                    }
+                };
+                match self.iterator_to_list(b) {
+                    Ok(b) => Ok(ArgValues::Two(a, b)),
+                    Err(e) => {
+                        a.drop_with_heap(self.heap);
+                        Err(e)
+                    }
+                }
+            }
+            ArgValues::ArgsKargs { args, kwargs } => {
+                let mut converted = Vec::with_capacity(args.len());
+                let mut remaining = args.into_iter();
+                while let Some(arg) = remaining.next() {
+                    match self.iterator_to_list(arg) {
+                        Ok(arg) => converted.push(arg),
+                        Err(e) => {
+                            converted.drop_with_heap(self.heap);
+                            remaining.drop_with_heap(self.heap);
+                            kwargs.drop_with_heap(self.heap);
+                            return Err(e);
+                        }
+                    }
+                }
+                Ok(ArgValues::ArgsKargs {
+                    args: converted,
+                    kwargs,
+                })
+            }
+            args @ (ArgValues::Empty | ArgValues::Kwargs(_)) => Ok(args),
+        }
+    }
+
+    /// Returns whether the first positional argument is a generator or iterator.
+    fn first_arg_is_iterator(&self, args: &ArgValues) -> bool {
+        matches!(args, ArgValues::One(v) | ArgValues::Two(v, _) if self.is_iterator(v))
+    }
+
+    /// Returns whether any positional argument is a generator or iterator.
+    pub(super) fn args_contain_iterator(&self, args: &ArgValues) -> bool {
+        match args {
+            ArgValues::Empty | ArgValues::Kwargs(_) => false,
+            ArgValues::One(v) => self.is_iterator(v),
+            ArgValues::Two(v1, v2) => self.is_iterator(v1) || self.is_iterator(v2),
+            ArgValues::ArgsKargs { args, .. } => args.iter().any(|v| self.is_iterator(v)),
+        }
+    }
+
+    /// Calls a builtin that iterates its arguments when they include a generator or iterator.
+    ///
+    /// `next()`, `sum()`, `any()` and `all()` step through the iterator lazily; the other
+    /// builtins accepted by `builtin_consumes_iterable` receive a list of its values.
+    pub(super) fn call_builtin_with_iterator(&mut self, builtin: Builtins, args: ArgValues) -> RunResult<Value> {
+        match builtin {
+            Builtins::Function(BuiltinsFunctions::Next) if self.first_arg_is_iterator(&args) => {
+                let (generator, default) = args.get_one_two_args("next", self.heap)?;
+                let Value::Ref(gen_id) = generator else {
+                    unreachable!("argument checked to be an iterator")
+                };
+                let result = self.iterator_next_sync(gen_id);
+                generator.drop_with_heap(self.heap);
+                match (result, default) {
+                    (Ok(Some(value)), default) => {
+                        default.drop_with_heap(self.heap);
+                        Ok(value)
+                    }
+                    (Ok(None), Some(default)) => Ok(default),
+                    (Ok(None), None) => Err(ExcType::stop_iteration()),
+                    (Err(e), default) => {
+                        default.drop_with_heap(self.heap);
+                        Err(e)
+                    }
+                }
+            }
+            Builtins::Function(BuiltinsFunctions::Any | BuiltinsFunctions::All)
+                if self.first_arg_is_iterator(&args) =>
+            {
+                let name = if builtin == Builtins::Function(BuiltinsFunctions::Any) {
+                    "any"
+                } else {
+                    "all"
+                };
+                let generator = args.get_one_arg(name, self.heap)?;
+                let Value::Ref(gen_id) = generator else {
+                    unreachable!("argument checked to be an iterator")
+                };
+                // any() stops at the first truthy item, all() at the first falsy one
+                let stop_on = name == "any";
+                let result = loop {
+                    match self.iterator_next_sync(gen_id) {
+                        Ok(Some(item)) => {
+                            let truthy = item.py_bool(self.heap, self.interns);
+                            item.drop_with_heap(self.heap);
+                            if truthy == stop_on {
+                                break Ok(Value::Bool(stop_on));
+                            }
+                        }
+                        Ok(None) => break Ok(Value::Bool(!stop_on)),
+                        Err(e) => break Err(e),
+                    }
+                };
+                generator.drop_with_heap(self.heap);
+                result
+            }
+            Builtins::Function(BuiltinsFunctions::Sum) if self.first_arg_is_iterator(&args) => {
+                let (generator, start) = args.get_one_two_args("sum", self.heap)?;
+                let result = self.sum_iterator(&generator, start);
+                generator.drop_with_heap(self.heap);
+                result
+            }
+            _ => {
+                let args = self.iterator_args_to_lists(args)?;
+                builtin.call(self.heap, args, self.interns, self.print_writer)
+            }
+        }
+    }
+
+    /// Sums the values of a generator or iterator in chunks using the `sum()` builtin.
+    ///
+    /// Takes ownership of `start`; `generator` is borrowed.
+    fn sum_iterator(&mut self, generator: &Value, start: Option<Value>) -> RunResult<Value> {
+        let Value::Ref(gen_id) = *generator else {
+            unreachable!("argument checked to be an iterator")
+        };
+        let mut total = start.unwrap_or(Value::Int(0));
+        loop {
+            let mut chunk = Vec::with_capacity(SUM_CHUNK_SIZE);
+            let mut exhausted = false;
+            while chunk.len() < SUM_CHUNK_SIZE {
+                match self.iterator_next_sync(gen_id) {
+                    Ok(Some(item)) => chunk.push(item),
+                    Ok(None) => {
+                        exhausted = true;
+                        break;
+                    }
+                    Err(e) => {
+                        chunk.drop_with_heap(self.heap);
+                        total.drop_with_heap(self.heap);
+                        return Err(e);
+                    }
+                }
+            }
+            total = self.sum_chunk(total, chunk)?;
+            if exhausted {
+                return Ok(total);
+            }
+        }
+    }
+
+    /// Adds the values of `chunk` to `total` using the `sum()` builtin. Takes ownership of both.
+    pub(super) fn sum_chunk(&mut self, total: Value, chunk: Vec<Value>) -> RunResult<Value> {
+        let chunk_id = match self.heap.allocate(HeapData::List(List::new(chunk))) {
+            Ok(id) => id,
+            Err(e) => {
+                total.drop_with_heap(self.heap);
+                return Err(e.into());
+            }
+        };
+        let args = ArgValues::Two(Value::Ref(chunk_id), total);
+        Builtins::Function(BuiltinsFunctions::Sum).call(self.heap, args, self.interns, self.print_writer)
+    }
+
+    /// Membership test against a generator or iterator, consuming it up to the first match.
+    ///
+    /// `item` and `generator` are borrowed.
+    pub(super) fn iterator_contains(&mut self, generator: &Value, item: &Value) -> RunResult<bool> {
+        let Value::Ref(gen_id) = *generator else {
+            unreachable!("argument checked to be an iterator")
+        };
+        while let Some(value) = self.iterator_next_sync(gen_id)? {
+            let found = self.values_eq(&value, item);
+            value.drop_with_heap(self.heap);
+            if found? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Returns whether a builtin iterates over its positional arguments.
+///
+/// Generators and iterators passed to these builtins are consumed by the VM (see
+/// `VM::call_builtin_with_iterator`) since the builtins themselves can't run bytecode.
+pub(super) fn builtin_consumes_iterable(builtin: Builtins) -> bool {
+    matches!(
+        builtin,
+        Builtins::Function(
+            BuiltinsFunctions::All
+                | BuiltinsFunctions::Any
+                | BuiltinsFunctions::Enumerate
+                | BuiltinsFunctions::Max
+                | BuiltinsFunctions::Min
+                | BuiltinsFunctions::Next
+                | BuiltinsFunctions::Sorted
+                | BuiltinsFunctions::Sum
+                | BuiltinsFunctions::Zip
+        ) | Builtins::Type(
+            Type::List
+                | Type::Tuple
+                | Type::Set
+                | Type::FrozenSet
+                | Type::Dict
+                | Type::Bytes
+                | Type::ByteArray
+                | Type::Deque
+                | Type::DefaultDict
+                | Type::Counter
+                | Type::OrderedDict
+        )
+    )
+}
+
+/// Returns whether a method consumes iterable arguments, e.g. `list.extend()` or `str.join()`,
+/// or a module function called as an attribute like `math.prod()`.
+///
+/// Generator and iterator arguments to these methods are collected into lists before the call.
+pub(super) fn method_consumes_iterable(name_id: StringId) -> bool {
+    matches!(
+        StaticStrings::from_string_id(name_id),
+        Some(
+            StaticStrings::Extend
+                | StaticStrings::Join
+                | StaticStrings::Update
+                | StaticStrings::Union
+                | StaticStrings::Intersection
+                | StaticStrings::Difference
+                | StaticStrings::SymmetricDifference
+                | StaticStrings::Issubset
+                | StaticStrings::Issuperset
+                | StaticStrings::Isdisjoint
+                | StaticStrings::Fromkeys
+                | StaticStrings::Prod
+                | StaticStrings::Fsum
+                | StaticStrings::Extendleft
+                | StaticStrings::Subtract
+                | StaticStrings::UnderscoreMake
+        )
+    )
+}
+
+/// Returns whether a module function consumes iterable arguments, like `math.fsum()`.
+///
+/// Generator and iterator arguments to these functions are collected into lists before the call.
+pub(super) fn module_function_consumes_iterable(function: ModuleFunctions) -> bool {
+    matches!(
+        function,
+        ModuleFunctions::Math(MathFunctions::Prod | MathFunctions::Fsum)
+            | ModuleFunctions::Collections(CollectionsFunctions::Namedtuple)
+    )
+}
+
+/// Marks a generator as completed and releases a frame's reference to it.
+///
+/// Used when a generator's frame is discarded rather than suspended.
+pub(super) fn finish_generator_frame(heap: &mut Heap<impl ResourceTracker>, gen_frame: GeneratorFrame) {
+    if let HeapData::Generator(generator) = heap.get_mut(gen_frame.id) {
+        generator.state = GeneratorState::Completed;
+    }
+    heap.dec_ref(gen_frame.id);
+}
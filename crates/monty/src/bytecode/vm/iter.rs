@@ -0,0 +1,992 @@
+//! Iterator support for the VM.
+//!
+//! Most iterators are advanced by `advance_on_heap()`, but the iterators returned by
+//! `map()`, `filter()` and `iter(callable, sentinel)` get their items by calling a function
+//! (see `CallingIter`), which only the VM can do.
+//!
+//! `for` loops over these iterators and calls to builtins and methods that consume them or a
+//! generator, like `sum(tool(x) for x in xs)` or `list(map(tool, xs))`, get the items in steps
+//! driven by the run loop, so the code producing them can call the host:
+//! - An iterator waiting for a generator to yield or a function to return records an
+//!   `IterStep` in `VM::iter_steps`, and the instruction rewinds its frame's IP to itself.
+//! - Once the value has arrived, the instruction runs again and continues with the recorded
+//!   steps (see `resume_iter_steps`) instead of starting over.
+//!
+//! Operations that consume an iterator in Rust (`in`, unpacking, builtins called by other
+//! builtins) advance these iterators with nested runs instead (see `iter_next`).
+
+use super::{
+    CallResult, VM,
+    generator::{ResumeMode, SUM_CHUNK_SIZE},
+};
+use crate::{
+    args::{ArgValues, KwargsValues},
+    builtins::{Builtins, BuiltinsFunctions},
+    defer_drop,
+    exception_private::{ExcType, RunResult},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::StringId,
+    io::PrintWriter,
+    resource::ResourceTracker,
+    types::{
+        List, PyTrait,
+        iter::{CallingIter, advance_on_heap},
+    },
+    value::Value,
+};
+
+/// An iterator waiting for a frame or the host to produce a value before it has its next item.
+///
+/// The steps of the current instruction are the last entries of `VM::iter_steps`, innermost
+/// last. Each step owns a reference to its iterator.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(super) struct IterStep {
+    /// Number of frames when the step was recorded, the last being the instruction's frame.
+    depth: usize,
+    /// The iterator and what it waits for.
+    kind: StepKind,
+}
+
+/// What an [`IterStep`] waits for.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+enum StepKind {
+    /// Generator `id` runs until it yields; `returned` is set if it returns instead.
+    Generator { id: HeapId, returned: bool },
+    /// `map()` iterator `id` waits for an item of its iterable at index `args.len()`; `args`
+    /// holds the items of the iterables before it.
+    Map { id: HeapId, args: Vec<Value> },
+    /// `filter()` iterator `id` waits for an item of its iterable.
+    Filter { id: HeapId },
+    /// Calling iterator `id` waits for its function to return; `item` is the item `filter()`
+    /// passed to its function.
+    Call {
+        id: HeapId,
+        kind: CallingIter,
+        item: Option<Value>,
+    },
+    /// A call instruction consumes iterator `id`, which waits for its next item.
+    Consumer { id: HeapId, consumer: IterConsumer },
+}
+
+/// How a call instruction consumes the items of an iterator argument.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+enum IterConsumer {
+    /// `any()` (`stop_on` is true) or `all()`, which stop at the first item with that truthiness.
+    AnyAll { stop_on: bool },
+    /// `sum()`, adding up the items in chunks of [`SUM_CHUNK_SIZE`].
+    Sum { total: Value, chunk: Vec<Value> },
+    /// `next()`, which returns the first item, or `default` if the iterator is exhausted.
+    Next { default: Option<Value> },
+    /// Collects the items of positional argument `index` into a list; `call` is made once
+    /// every such argument has been collected.
+    Collect {
+        call: DeferredCall,
+        args: Vec<Value>,
+        kwargs: KwargsValues,
+        index: usize,
+        items: Vec<Value>,
+    },
+}
+
+/// A call made once its iterator arguments have been collected into lists.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(super) enum DeferredCall {
+    /// Calling a builtin or module function.
+    Function(Value),
+    /// Calling method `name_id` of the object.
+    Method(Value, StringId),
+}
+
+/// Result of getting the next item of an iterator in steps.
+pub(super) enum StepResult {
+    /// The iterator produced an item.
+    Item(Value),
+    /// The iterator is exhausted.
+    Exhausted,
+    /// The iterator waits for a frame or the host: the instruction returns to the run loop
+    /// with this call result and runs again once the value has arrived.
+    Suspended(CallResult),
+}
+
+/// How a function called by a calling iterator ended (see `VM::call_step`).
+enum CallStep {
+    /// The function returned `value` right away; `item` is the item `filter()` passed to it.
+    Returned { item: Option<Value>, value: Value },
+    /// The function waits for a frame or the host.
+    Suspended(CallResult),
+}
+
+impl StepKind {
+    /// Returns the iterator the step belongs to.
+    fn iter_id(&self) -> HeapId {
+        match self {
+            Self::Generator { id, .. }
+            | Self::Map { id, .. }
+            | Self::Filter { id }
+            | Self::Call { id, .. }
+            | Self::Consumer { id, .. } => *id,
+        }
+    }
+}
+
+impl IterStep {
+    /// Adds the heap objects the step references to `roots`, for garbage collection.
+    pub(super) fn collect_roots(&self, roots: &mut Vec<HeapId>) {
+        roots.push(self.kind.iter_id());
+        let consumer = match &self.kind {
+            StepKind::Generator { .. } | StepKind::Filter { .. } => return,
+            StepKind::Map { args, .. } => {
+                roots.extend(args.iter().filter_map(Value::ref_id));
+                return;
+            }
+            StepKind::Call { item, .. } => {
+                roots.extend(item.as_ref().and_then(Value::ref_id));
+                return;
+            }
+            StepKind::Consumer { consumer, .. } => consumer,
+        };
+        match consumer {
+            IterConsumer::AnyAll { .. } => {}
+            IterConsumer::Sum { total, chunk } => {
+                roots.extend(total.ref_id());
+                roots.extend(chunk.iter().filter_map(Value::ref_id));
+            }
+            IterConsumer::Next { default } => roots.extend(default.as_ref().and_then(Value::ref_id)),
+            IterConsumer::Collect {
+                call,
+                args,
+                kwargs,
+                items,
+                ..
+            } => {
+                let (DeferredCall::Function(callable) | DeferredCall::Method(callable, _)) = call;
+                roots.extend(callable.ref_id());
+                roots.extend(args.iter().chain(items).filter_map(Value::ref_id));
+                match kwargs {
+                    KwargsValues::Empty => {}
+                    KwargsValues::Inline(kvs) => roots.extend(kvs.iter().filter_map(|(_, v)| v.ref_id())),
+                    KwargsValues::Dict(dict) => {
+                        roots.extend(
+                            dict.into_iter()
+                                .flat_map(|(k, v)| k.ref_id().into_iter().chain(v.ref_id())),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T: ResourceTracker> DropWithHeap<T> for IterStep {
+    fn drop_with_heap(self, heap: &mut Heap<T>) {
+        heap.dec_ref(self.kind.iter_id());
+        let consumer = match self.kind {
+            StepKind::Generator { .. } | StepKind::Filter { .. } => return,
+            StepKind::Map { args, .. } => {
+                args.drop_with_heap(heap);
+                return;
+            }
+            StepKind::Call { item, .. } => {
+                item.drop_with_heap(heap);
+                return;
+            }
+            StepKind::Consumer { consumer, .. } => consumer,
+        };
+        match consumer {
+            IterConsumer::AnyAll { .. } => {}
+            IterConsumer::Sum { total, chunk } => {
+                total.drop_with_heap(heap);
+                chunk.drop_with_heap(heap);
+            }
+            IterConsumer::Next { default } => default.drop_with_heap(heap),
+            IterConsumer::Collect {
+                call,
+                args,
+                kwargs,
+                items,
+                ..
+            } => {
+                call.drop_with_heap(heap);
+                args.drop_with_heap(heap);
+                kwargs.drop_with_heap(heap);
+                items.drop_with_heap(heap);
+            }
+        }
+    }
+}
+
+impl<T: ResourceTracker> DropWithHeap<T> for DeferredCall {
+    fn drop_with_heap(self, heap: &mut Heap<T>) {
+        match self {
+            Self::Function(callable) => callable.drop_with_heap(heap),
+            Self::Method(obj, _) => obj.drop_with_heap(heap),
+        }
+    }
+}
+
+impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
+    /// Returns whether `value` is an iterator: a (non-async) generator or a `MontyIter`.
+    pub(super) fn is_iterator(&self, value: &Value) -> bool {
+        match value {
+            Value::Ref(id) => match self.heap.get(*id) {
+                HeapData::Generator(generator) => !generator.is_async,
+                HeapData::Iter(_) => true,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Gets the next item of a generator or iterator, returning `Ok(None)` once it's exhausted.
+    ///
+    /// Generators are resumed with a nested run of the VM loop.
+    pub(super) fn iterator_next_sync(&mut self, iter_id: HeapId) -> RunResult<Option<Value>> {
+        if matches!(self.heap.get(iter_id), HeapData::Generator(_)) {
+            self.generator_next_sync(iter_id)
+        } else {
+            self.iter_next(iter_id)
+        }
+    }
+
+    /// Gets the next item of a `MontyIter`, returning `Ok(None)` once it's exhausted.
+    ///
+    /// Iterators that call functions make their calls with nested runs of the VM loop.
+    pub(super) fn iter_next(&mut self, iter_id: HeapId) -> RunResult<Option<Value>> {
+        let HeapData::Iter(iter) = self.heap.get(iter_id) else {
+            unreachable!("iter_next called with non-iterator heap_id")
+        };
+        let Some(kind) = iter.calling() else {
+            return advance_on_heap(self.heap, iter_id, self.interns);
+        };
+
+        // Take references to the iterator's parts, so they survive whatever the calls do
+        let parts = self.calling_parts(iter_id).expect("iterator checked above");
+        let this = self;
+        defer_drop!(parts, this);
+
+        let item = match kind {
+            CallingIter::Map => this.map_next(parts)?,
+            CallingIter::Filter => this.filter_next(&parts[0], &parts[1])?,
+            CallingIter::Sentinel => this.sentinel_next(&parts[0], &parts[1])?,
+        };
+        if item.is_none() {
+            this.exhaust_iter(iter_id);
+        }
+        Ok(item)
+    }
+
+    /// Returns new references to the parts of calling iterator `iter_id` (see `CallingIter`),
+    /// or `None` if it's exhausted.
+    fn calling_parts(&mut self, iter_id: HeapId) -> Option<Vec<Value>> {
+        let HeapData::Iter(iter) = self.heap.get(iter_id) else {
+            unreachable!("calling_parts called with non-iterator heap_id")
+        };
+        iter.calling()?;
+        let Value::Ref(parts_id) = iter.value() else {
+            unreachable!("calling iterator holds a tuple")
+        };
+        let HeapData::Tuple(tuple) = self.heap.get(*parts_id) else {
+            unreachable!("calling iterator holds a tuple")
+        };
+        let parts: Vec<Value> = tuple.as_slice().iter().map(Value::copy_for_extend).collect();
+        for part in &parts {
+            if let Value::Ref(id) = part {
+                self.heap.inc_ref(*id);
+            }
+        }
+        Some(parts)
+    }
+
+    /// Marks calling iterator `iter_id` as exhausted, so it stops for good even if its iterables
+    /// (or function) would produce more items.
+    fn exhaust_iter(&mut self, iter_id: HeapId) {
+        if let HeapData::Iter(iter) = self.heap.get_mut(iter_id) {
+            let value = iter.exhaust();
+            value.drop_with_heap(self.heap);
+        }
+    }
+
+    /// Gets the next item of `map(func, *iterables)`, calling `func` with an item from each iterable.
+    fn map_next(&mut self, parts: &[Value]) -> RunResult<Option<Value>> {
+        let (func, iters) = parts.split_first().expect("map() has a function");
+        let mut args = Vec::with_capacity(iters.len());
+        for iter in iters {
+            let Value::Ref(iter_id) = iter else {
+                unreachable!("map() holds iterators")
+            };
+            match self.iterator_next_sync(*iter_id) {
+                Ok(Some(item)) => args.push(item),
+                Ok(None) => {
+                    args.drop_with_heap(self.heap);
+                    return Ok(None);
+                }
+                Err(e) => {
+                    args.drop_with_heap(self.heap);
+                    return Err(e);
+                }
+            }
+        }
+        let args = Self::build_args_positional_only(args);
+        self.call_sync(func.clone_with_heap(self.heap), args).map(Some)
+    }
+
+    /// Gets the next item of `filter(func, iterable)` for which `func` returns a truthy value,
+    /// or the next truthy item if `func` is `None`.
+    fn filter_next(&mut self, func: &Value, iter: &Value) -> RunResult<Option<Value>> {
+        let Value::Ref(iter_id) = iter else {
+            unreachable!("filter() holds an iterator")
+        };
+        while let Some(item) = self.iterator_next_sync(*iter_id)? {
+            let keep = if matches!(func, Value::None) {
+                item.py_bool(self.heap, self.interns)
+            } else {
+                let arg = item.clone_with_heap(self.heap);
+                match self.call_sync(func.clone_with_heap(self.heap), ArgValues::One(arg)) {
+                    Ok(result) => {
+                        let keep = result.py_bool(self.heap, self.interns);
+                        result.drop_with_heap(self.heap);
+                        keep
+                    }
+                    Err(e) => {
+                        item.drop_with_heap(self.heap);
+                        return Err(e);
+                    }
+                }
+            };
+            if keep {
+                return Ok(Some(item));
+            }
+            item.drop_with_heap(self.heap);
+        }
+        Ok(None)
+    }
+
+    /// Gets the next item of `iter(callable, sentinel)`, calling `callable` with no arguments.
+    fn sentinel_next(&mut self, callable: &Value, sentinel: &Value) -> RunResult<Option<Value>> {
+        let item = self.call_sync(callable.clone_with_heap(self.heap), ArgValues::Empty)?;
+        match self.values_eq(&item, sentinel) {
+            Ok(false) => Ok(Some(item)),
+            Ok(true) => {
+                item.drop_with_heap(self.heap);
+                Ok(None)
+            }
+            Err(e) => {
+                item.drop_with_heap(self.heap);
+                Err(e)
+            }
+        }
+    }
+
+    /// Returns whether a call consuming `value` gets its items in steps driven by the run loop:
+    /// `value` is a (non-async) generator or a calling iterator.
+    pub(super) fn is_stepped_iterator(&self, value: &Value) -> bool {
+        self.is_generator(value) || self.is_calling_iterator(value)
+    }
+
+    /// Returns whether `value` is an iterator that gets its items by calling a function, like
+    /// `map()` (see `CallingIter`).
+    pub(super) fn is_calling_iterator(&self, value: &Value) -> bool {
+        matches!(value, Value::Ref(id) if matches!(self.heap.get(*id), HeapData::Iter(iter) if iter.calling().is_some()))
+    }
+
+    /// Returns whether any positional argument is an iterator whose items a consuming call
+    /// gets in steps.
+    pub(super) fn has_stepped_arg(&self, args: &ArgValues) -> bool {
+        match args {
+            ArgValues::Empty | ArgValues::Kwargs(_) => false,
+            ArgValues::One(v) => self.is_stepped_iterator(v),
+            ArgValues::Two(v1, v2) => self.is_stepped_iterator(v1) || self.is_stepped_iterator(v2),
+            ArgValues::ArgsKargs { args, .. } => args.iter().any(|v| self.is_stepped_iterator(v)),
+        }
+    }
+
+    /// Returns whether the current instruction runs again after its iterator steps waited
+    /// for a frame or the host.
+    pub(super) fn iter_steps_waiting(&self) -> bool {
+        self.iter_steps
+            .last()
+            .is_some_and(|step| step.depth == self.frames.len())
+    }
+
+    /// Returns the IP the current frame continues at once the host answers a call made at
+    /// `ip`: the instruction itself if it waits for its iterator steps.
+    pub(super) fn host_resume_ip(&self, ip: usize) -> usize {
+        if self.iter_steps_waiting() {
+            self.instruction_ip
+        } else {
+            ip
+        }
+    }
+
+    /// Drops the iterator steps of the frames from `depth` up, whose instructions won't run again.
+    pub(super) fn drop_iter_steps(&mut self, depth: usize) {
+        while self.iter_steps.last().is_some_and(|step| step.depth >= depth) {
+            let step = self.iter_steps.pop().expect("step checked above");
+            step.drop_with_heap(self.heap);
+        }
+    }
+
+    /// Records that the generator of the innermost iterator step returned instead of yielding.
+    pub(super) fn generator_step_returned(&mut self) {
+        if let Some(IterStep {
+            kind: StepKind::Generator { returned, .. },
+            ..
+        }) = self.iter_steps.last_mut()
+        {
+            *returned = true;
+        }
+    }
+
+    /// Calls a builtin or method that consumes iterables, getting the items of its generator
+    /// and calling iterator arguments in steps driven by the run loop.
+    ///
+    /// `any()`, `all()`, `next()` and `sum()` consume the iterator item by item; other calls
+    /// receive a list of its items. Takes ownership of `call` and `args`.
+    pub(super) fn call_with_iter_steps(&mut self, call: DeferredCall, args: ArgValues) -> RunResult<CallResult> {
+        let first_stepped = matches!(&args, ArgValues::One(v) | ArgValues::Two(v, _) if self.is_stepped_iterator(v));
+        match call {
+            DeferredCall::Function(Value::Builtin(Builtins::Function(
+                function @ (BuiltinsFunctions::Any | BuiltinsFunctions::All),
+            ))) if first_stepped => {
+                let stop_on = function == BuiltinsFunctions::Any;
+                let iter = args.get_one_arg(if stop_on { "any" } else { "all" }, self.heap)?;
+                self.start_iter_consumer(iter, IterConsumer::AnyAll { stop_on })
+            }
+            DeferredCall::Function(Value::Builtin(Builtins::Function(BuiltinsFunctions::Next))) => {
+                let (iter, default) = args.get_one_two_args("next", self.heap)?;
+                self.start_iter_consumer(iter, IterConsumer::Next { default })
+            }
+            DeferredCall::Function(Value::Builtin(Builtins::Function(BuiltinsFunctions::Sum))) if first_stepped => {
+                let (iter, start) = args.get_one_two_args("sum", self.heap)?;
+                let total = start.unwrap_or(Value::Int(0));
+                self.start_iter_consumer(
+                    iter,
+                    IterConsumer::Sum {
+                        total,
+                        chunk: Vec::new(),
+                    },
+                )
+            }
+            call => {
+                let (args, kwargs) = args.into_parts();
+                let mut args: Vec<Value> = args.collect();
+                let index = args
+                    .iter()
+                    .position(|arg| self.is_stepped_iterator(arg))
+                    .expect("call has an iterator argument");
+                let iter = std::mem::replace(&mut args[index], Value::None);
+                let consumer = IterConsumer::Collect {
+                    call,
+                    args,
+                    kwargs,
+                    index,
+                    items: Vec::new(),
+                };
+                self.start_iter_consumer(iter, consumer)
+            }
+        }
+    }
+
+    /// Continues a call instruction that runs again after its iterator steps waited for a frame
+    /// or the host.
+    ///
+    /// Returns `None` if the current instruction isn't waiting.
+    pub(super) fn resume_iter_consumer(&mut self) -> Option<RunResult<CallResult>> {
+        if !self.iter_steps_waiting() {
+            return None;
+        }
+        let next = self.resume_iter_steps();
+        Some(self.run_iter_consumer(next))
+    }
+
+    /// Records that the current instruction waits for `kind`, taking a reference to its iterator.
+    fn push_iter_step(&mut self, kind: StepKind) {
+        self.heap.inc_ref(kind.iter_id());
+        self.iter_steps.push(IterStep {
+            depth: self.frames.len(),
+            kind,
+        });
+    }
+
+    /// Removes the innermost iterator step, releasing its reference to the iterator.
+    ///
+    /// The caller takes ownership of the values the step holds.
+    fn pop_iter_step(&mut self) -> StepKind {
+        let step = self.iter_steps.pop().expect("no iterator step to pop");
+        self.heap.dec_ref(step.kind.iter_id());
+        step.kind
+    }
+
+    /// Makes the instruction waiting for its iterator steps run again when its frame continues.
+    pub(super) fn rewind_to_instruction(&mut self) {
+        let depth = self.iter_steps.last().expect("no iterator step to wait for").depth;
+        self.frames[depth - 1].ip = self.instruction_ip;
+    }
+
+    /// Starts getting the next item of iterator `iter_id` for the current instruction.
+    ///
+    /// Generators are resumed in the run loop, and calling iterators call their functions like
+    /// a call instruction: if the item isn't ready, the result is `Suspended` and
+    /// `resume_iter_steps` gets it once the frame or the host has produced the value the
+    /// innermost step waits for.
+    pub(super) fn next_item_step(&mut self, iter_id: HeapId) -> RunResult<StepResult> {
+        let kind = match self.heap.get(iter_id) {
+            HeapData::Generator(_) => return self.generator_step(iter_id),
+            HeapData::Iter(iter) => iter.calling(),
+            _ => unreachable!("next_item_step called with non-iterator heap_id"),
+        };
+        match kind {
+            None => Ok(match advance_on_heap(self.heap, iter_id, self.interns)? {
+                Some(item) => StepResult::Item(item),
+                None => StepResult::Exhausted,
+            }),
+            Some(CallingIter::Map) => self.map_step(iter_id, Vec::new()),
+            Some(CallingIter::Filter) => self.filter_step(iter_id),
+            Some(CallingIter::Sentinel) => self.sentinel_step(iter_id),
+        }
+    }
+
+    /// Resumes generator `gen_id` until it yields the next item.
+    fn generator_step(&mut self, gen_id: HeapId) -> RunResult<StepResult> {
+        self.push_iter_step(StepKind::Generator {
+            id: gen_id,
+            returned: false,
+        });
+        let result = self.resume_generator(gen_id, Value::None, ResumeMode::Step);
+        if !matches!(result, Ok(true)) {
+            self.pop_iter_step();
+        }
+        if result? {
+            Ok(StepResult::Suspended(CallResult::FramePushed))
+        } else {
+            Ok(StepResult::Exhausted)
+        }
+    }
+
+    /// Gets the next item of `map(func, *iterables)`, calling `func` with an item from each
+    /// iterable. `args` holds the items already taken from the first iterables.
+    fn map_step(&mut self, map_id: HeapId, mut args: Vec<Value>) -> RunResult<StepResult> {
+        let Some(parts) = self.calling_parts(map_id) else {
+            args.drop_with_heap(self.heap);
+            return Ok(StepResult::Exhausted);
+        };
+        let this = self;
+        defer_drop!(parts, this);
+
+        let (func, iters) = parts.split_first().expect("map() has a function");
+        while let Some(iter) = iters.get(args.len()) {
+            let Value::Ref(iter_id) = iter else {
+                unreachable!("map() holds iterators")
+            };
+            this.push_iter_step(StepKind::Map { id: map_id, args });
+            let next = this.next_item_step(*iter_id);
+            if matches!(next, Ok(StepResult::Suspended(_))) {
+                return next;
+            }
+            let StepKind::Map { args: taken, .. } = this.pop_iter_step() else {
+                unreachable!("map step pushed above")
+            };
+            args = taken;
+            match next {
+                Ok(StepResult::Item(item)) => args.push(item),
+                next => {
+                    args.drop_with_heap(this.heap);
+                    return this.end_iter_step(map_id, next);
+                }
+            }
+        }
+
+        let call = StepKind::Call {
+            id: map_id,
+            kind: CallingIter::Map,
+            item: None,
+        };
+        let args = Self::build_args_positional_only(args);
+        match this.call_step(call, func.clone_with_heap(this.heap), args)? {
+            CallStep::Returned { value, .. } => Ok(StepResult::Item(value)),
+            CallStep::Suspended(call) => Ok(StepResult::Suspended(call)),
+        }
+    }
+
+    /// Gets the next item of `filter(func, iterable)` for which `func` returns a truthy value,
+    /// or the next truthy item if `func` is `None`.
+    fn filter_step(&mut self, filter_id: HeapId) -> RunResult<StepResult> {
+        loop {
+            let Some(parts) = self.calling_parts(filter_id) else {
+                return Ok(StepResult::Exhausted);
+            };
+            let Value::Ref(iter_id) = parts[1] else {
+                unreachable!("filter() holds an iterator")
+            };
+            self.push_iter_step(StepKind::Filter { id: filter_id });
+            let next = self.next_item_step(iter_id);
+            parts.drop_with_heap(self.heap);
+            if matches!(next, Ok(StepResult::Suspended(_))) {
+                return next;
+            }
+            self.pop_iter_step();
+            let item = match next {
+                Ok(StepResult::Item(item)) => item,
+                next => return self.end_iter_step(filter_id, next),
+            };
+            if let Some(result) = self.filter_item(filter_id, item)? {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Decides whether `filter()` iterator `filter_id` produces `item`, calling its function
+    /// unless it's `None`.
+    ///
+    /// Returns `None` if the item is filtered out.
+    fn filter_item(&mut self, filter_id: HeapId, item: Value) -> RunResult<Option<StepResult>> {
+        let Some(parts) = self.calling_parts(filter_id) else {
+            item.drop_with_heap(self.heap);
+            return Ok(Some(StepResult::Exhausted));
+        };
+        let mut parts = parts.into_iter();
+        let func = parts.next().expect("filter() has a function");
+        parts.drop_with_heap(self.heap);
+        if matches!(func, Value::None) {
+            let keep = item.py_bool(self.heap, self.interns);
+            return Ok(self.filter_verdict(item, keep));
+        }
+
+        let arg = item.clone_with_heap(self.heap);
+        let call = StepKind::Call {
+            id: filter_id,
+            kind: CallingIter::Filter,
+            item: Some(item),
+        };
+        match self.call_step(call, func, ArgValues::One(arg))? {
+            CallStep::Returned { item, value } => self.call_step_returned(filter_id, CallingIter::Filter, item, value),
+            CallStep::Suspended(call) => Ok(Some(StepResult::Suspended(call))),
+        }
+    }
+
+    /// Returns `item` as the item of a `filter()` iterator if `keep` is true, and drops it otherwise.
+    fn filter_verdict(&mut self, item: Value, keep: bool) -> Option<StepResult> {
+        if keep {
+            Some(StepResult::Item(item))
+        } else {
+            item.drop_with_heap(self.heap);
+            None
+        }
+    }
+
+    /// Gets the next item of `iter(callable, sentinel)`, calling `callable` with no arguments.
+    fn sentinel_step(&mut self, iter_id: HeapId) -> RunResult<StepResult> {
+        let Some(parts) = self.calling_parts(iter_id) else {
+            return Ok(StepResult::Exhausted);
+        };
+        let mut parts = parts.into_iter();
+        let callable = parts.next().expect("iter() has a callable");
+        parts.drop_with_heap(self.heap);
+
+        let call = StepKind::Call {
+            id: iter_id,
+            kind: CallingIter::Sentinel,
+            item: None,
+        };
+        match self.call_step(call, callable, ArgValues::Empty)? {
+            CallStep::Returned { value, .. } => self.sentinel_item(iter_id, value),
+            CallStep::Suspended(call) => Ok(StepResult::Suspended(call)),
+        }
+    }
+
+    /// Returns `value` returned by the callable of `iter(callable, sentinel)` as its item,
+    /// unless it's equal to the sentinel.
+    fn sentinel_item(&mut self, iter_id: HeapId, value: Value) -> RunResult<StepResult> {
+        let Some(parts) = self.calling_parts(iter_id) else {
+            value.drop_with_heap(self.heap);
+            return Ok(StepResult::Exhausted);
+        };
+        let this = self;
+        defer_drop!(parts, this);
+        match this.values_eq(&value, &parts[1]) {
+            Ok(false) => Ok(StepResult::Item(value)),
+            Ok(true) => {
+                value.drop_with_heap(this.heap);
+                this.exhaust_iter(iter_id);
+                Ok(StepResult::Exhausted)
+            }
+            Err(e) => {
+                value.drop_with_heap(this.heap);
+                Err(e)
+            }
+        }
+    }
+
+    /// Calls `func` for the calling iterator of `step`, which records that the current
+    /// instruction waits for the call if it needs a frame or the host. Takes ownership of
+    /// `func` and `args`.
+    fn call_step(&mut self, step: StepKind, func: Value, args: ArgValues) -> RunResult<CallStep> {
+        self.push_iter_step(step);
+        match self.call_function(func, args) {
+            Ok(CallResult::Push(value)) => {
+                let StepKind::Call { item, .. } = self.pop_iter_step() else {
+                    unreachable!("call step pushed above")
+                };
+                Ok(CallStep::Returned { item, value })
+            }
+            Ok(call) => Ok(CallStep::Suspended(call)),
+            Err(e) => {
+                let step = self.iter_steps.pop().expect("call step pushed above");
+                step.drop_with_heap(self.heap);
+                Err(e)
+            }
+        }
+    }
+
+    /// Continues calling iterator `iter_id` after its function returned `value`.
+    ///
+    /// Returns `None` if `filter()` filtered out its item.
+    fn call_step_returned(
+        &mut self,
+        iter_id: HeapId,
+        kind: CallingIter,
+        item: Option<Value>,
+        value: Value,
+    ) -> RunResult<Option<StepResult>> {
+        match kind {
+            CallingIter::Map => Ok(Some(StepResult::Item(value))),
+            CallingIter::Filter => {
+                let item = item.expect("filter() keeps its item while calling its function");
+                let keep = value.py_bool(self.heap, self.interns);
+                value.drop_with_heap(self.heap);
+                Ok(self.filter_verdict(item, keep))
+            }
+            CallingIter::Sentinel => self.sentinel_item(iter_id, value).map(Some),
+        }
+    }
+
+    /// Finishes calling iterator `iter_id` after one of its iterables is exhausted or failed
+    /// with `next`, marking it exhausted in the former case.
+    fn end_iter_step(&mut self, iter_id: HeapId, next: RunResult<StepResult>) -> RunResult<StepResult> {
+        if matches!(next, Ok(StepResult::Exhausted)) {
+            self.exhaust_iter(iter_id);
+        }
+        next
+    }
+
+    /// Gets the result of the iterator steps of the current instruction once the value the
+    /// innermost step waited for has arrived, passing it on to the iterators consuming its
+    /// iterator until one of them has to wait again.
+    pub(super) fn resume_iter_steps(&mut self) -> RunResult<StepResult> {
+        let mut next = match self.pop_iter_step() {
+            StepKind::Generator { returned: true, .. } => Ok(StepResult::Exhausted),
+            // The value the generator yielded
+            StepKind::Generator { returned: false, .. } => Ok(StepResult::Item(self.pop())),
+            StepKind::Call { id, kind, item } => {
+                // The value the function returned
+                let value = self.pop();
+                match self.call_step_returned(id, kind, item, value) {
+                    Ok(Some(result)) => Ok(result),
+                    Ok(None) => self.filter_step(id),
+                    Err(e) => Err(e),
+                }
+            }
+            StepKind::Map { .. } | StepKind::Filter { .. } | StepKind::Consumer { .. } => {
+                unreachable!("only generators and calls wait for a frame or the host")
+            }
+        };
+        while !matches!(next, Ok(StepResult::Suspended(_))) && self.iter_steps_waiting() {
+            next = match self.iter_steps.last().map(|step| &step.kind) {
+                Some(StepKind::Map { .. }) => {
+                    let StepKind::Map { id, mut args } = self.pop_iter_step() else {
+                        unreachable!("map step checked above")
+                    };
+                    match next {
+                        Ok(StepResult::Item(item)) => {
+                            args.push(item);
+                            self.map_step(id, args)
+                        }
+                        next => {
+                            args.drop_with_heap(self.heap);
+                            self.end_iter_step(id, next)
+                        }
+                    }
+                }
+                Some(StepKind::Filter { id }) => {
+                    let id = *id;
+                    self.pop_iter_step();
+                    match next {
+                        Ok(StepResult::Item(item)) => match self.filter_item(id, item) {
+                            Ok(Some(result)) => Ok(result),
+                            Ok(None) => self.filter_step(id),
+                            Err(e) => Err(e),
+                        },
+                        next => self.end_iter_step(id, next),
+                    }
+                }
+                // A consumer of the instruction takes it from here
+                _ => break,
+            };
+        }
+        next
+    }
+
+    /// Records `consumer` for iterator `iter`, returning its id. Takes ownership of `iter`.
+    fn push_iter_consumer(&mut self, iter: Value, consumer: IterConsumer) -> HeapId {
+        let Value::Ref(id) = iter else {
+            unreachable!("argument checked to be an iterator")
+        };
+        self.push_iter_step(StepKind::Consumer { id, consumer });
+        // The step holds its own reference
+        iter.drop_with_heap(self.heap);
+        id
+    }
+
+    /// Records `consumer` for iterator `iter` and starts passing it items. Takes ownership of `iter`.
+    fn start_iter_consumer(&mut self, iter: Value, consumer: IterConsumer) -> RunResult<CallResult> {
+        let id = self.push_iter_consumer(iter, consumer);
+        let next = self.next_item_step(id);
+        self.run_iter_consumer(next)
+    }
+
+    /// Passes items to the consumer of the current instruction, starting with `next`, until
+    /// it's done or an iterator step has to wait.
+    fn run_iter_consumer(&mut self, mut next: RunResult<StepResult>) -> RunResult<CallResult> {
+        loop {
+            let done = match next {
+                Ok(StepResult::Item(item)) => self.feed_iter_consumer(item),
+                Ok(StepResult::Exhausted) => self.finish_iter_consumer(),
+                Ok(StepResult::Suspended(call)) => {
+                    self.rewind_to_instruction();
+                    return Ok(call);
+                }
+                Err(e) => {
+                    self.drop_iter_consumer();
+                    return Err(e);
+                }
+            };
+            if let Some(result) = done {
+                return result;
+            }
+            let Some(IterStep {
+                kind: StepKind::Consumer { id, .. },
+                ..
+            }) = self.iter_steps.last()
+            else {
+                unreachable!("no iterator consumer")
+            };
+            let id = *id;
+            next = self.next_item_step(id);
+        }
+    }
+
+    /// Returns the consumer of the current instruction.
+    fn iter_consumer(&mut self) -> &mut IterConsumer {
+        match self.iter_steps.last_mut() {
+            Some(IterStep {
+                kind: StepKind::Consumer { consumer, .. },
+                ..
+            }) => consumer,
+            _ => unreachable!("no iterator consumer"),
+        }
+    }
+
+    /// Drops the consumer of the current instruction after an error.
+    fn drop_iter_consumer(&mut self) {
+        let step = self.iter_steps.pop().expect("no iterator consumer");
+        step.drop_with_heap(self.heap);
+    }
+
+    /// Passes an item to the consumer of the current instruction, returning the call's result
+    /// if that ends the call.
+    fn feed_iter_consumer(&mut self, item: Value) -> Option<RunResult<CallResult>> {
+        match self.iter_consumer() {
+            IterConsumer::AnyAll { stop_on } => {
+                let stop_on = *stop_on;
+                let truthy = item.py_bool(self.heap, self.interns);
+                item.drop_with_heap(self.heap);
+                if truthy != stop_on {
+                    return None;
+                }
+                self.drop_iter_consumer();
+                Some(Ok(CallResult::Push(Value::Bool(stop_on))))
+            }
+            IterConsumer::Sum { total, chunk } => {
+                chunk.push(item);
+                if chunk.len() < SUM_CHUNK_SIZE {
+                    return None;
+                }
+                let total = std::mem::replace(total, Value::None);
+                let chunk = std::mem::take(chunk);
+                match self.sum_chunk(total, chunk) {
+                    Ok(new_total) => {
+                        if let IterConsumer::Sum { total, .. } = self.iter_consumer() {
+                            *total = new_total;
+                        }
+                        None
+                    }
+                    Err(e) => {
+                        self.drop_iter_consumer();
+                        Some(Err(e))
+                    }
+                }
+            }
+            IterConsumer::Next { .. } => {
+                self.drop_iter_consumer();
+                Some(Ok(CallResult::Push(item)))
+            }
+            IterConsumer::Collect { items, .. } => {
+                items.push(item);
+                None
+            }
+        }
+    }
+
+    /// Finishes the consumer of the current instruction once its iterator is exhausted.
+    ///
+    /// Returns the call's result, or `None` if the call has another iterator argument to collect.
+    fn finish_iter_consumer(&mut self) -> Option<RunResult<CallResult>> {
+        let StepKind::Consumer { consumer, .. } = self.pop_iter_step() else {
+            unreachable!("no iterator consumer")
+        };
+        match consumer {
+            IterConsumer::AnyAll { stop_on } => Some(Ok(CallResult::Push(Value::Bool(!stop_on)))),
+            IterConsumer::Sum { total, chunk } => Some(self.sum_chunk(total, chunk).map(CallResult::Push)),
+            IterConsumer::Next { default } => Some(default.map(CallResult::Push).ok_or_else(ExcType::stop_iteration)),
+            IterConsumer::Collect {
+                call,
+                mut args,
+                kwargs,
+                index,
+                items,
+            } => {
+                match self.heap.allocate(HeapData::List(List::new(items))) {
+                    Ok(list_id) => args[index] = Value::Ref(list_id),
+                    Err(e) => {
+                        call.drop_with_heap(self.heap);
+                        args.drop_with_heap(self.heap);
+                        kwargs.drop_with_heap(self.heap);
+                        return Some(Err(e.into()));
+                    }
+                }
+                if let Some(offset) = args[index + 1..].iter().position(|arg| self.is_stepped_iterator(arg)) {
+                    let index = index + 1 + offset;
+                    let iter = std::mem::replace(&mut args[index], Value::None);
+                    let consumer = IterConsumer::Collect {
+                        call,
+                        args,
+                        kwargs,
+                        index,
+                        items: Vec::new(),
+                    };
+                    self.push_iter_consumer(iter, consumer);
+                    return None;
+                }
+                let args = if kwargs.is_empty() {
+                    Self::build_args_positional_only(args)
+                } else {
+                    ArgValues::ArgsKargs { args, kwargs }
+                };
+                Some(match call {
+                    DeferredCall::Function(callable) => self.call_function(callable, args),
+                    DeferredCall::Method(obj, name_id) => self.call_attr(obj, name_id, args),
+                })
+            }
+        }
+    }
+}
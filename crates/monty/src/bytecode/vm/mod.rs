@@ -4,18 +4,26 @@
 //! and a call stack for function frames. Each frame owns its instruction pointer (IP).
 
 mod async_exec;
+mod async_iter;
 mod attr;
 mod binary;
 mod call;
+mod class;
 mod collections;
 mod compare;
+mod context;
+mod eval;
 mod exceptions;
 mod format;
+mod generator;
+mod iter;
+mod pattern;
 mod scheduler;
 
-use std::cmp::Ordering;
-
 use call::CallResult;
+use compare::OrdOp;
+use generator::{GeneratorFrame, ResumeMode, finish_generator_frame};
+use iter::{IterStep, StepResult};
 use scheduler::Scheduler;
 
 use crate::{
@@ -23,6 +31,7 @@ use crate::{
     args::ArgValues,
     asyncio::{CallId, TaskId},
     bytecode::{code::Code, op::Opcode},
+    eval::CompileRequest,
     exception_private::{ExcType, RunError, RunResult, SimpleException},
     heap::{ContainsHeap, Heap, HeapData, HeapId},
     intern::{ExtFunctionId, FunctionId, Interns, StringId},
@@ -32,7 +41,7 @@ use crate::{
     os::OsFunction,
     parse::CodeRange,
     resource::ResourceTracker,
-    types::{LongInt, MontyIter, PyTrait, iter::advance_on_heap},
+    types::{Complex, LongInt, MontyIter, PyTrait, datetime::ClockRead},
     value::{BitwiseOp, Value},
 };
 
@@ -63,7 +72,7 @@ macro_rules! try_catch_sync {
                 return Err(result);
             }
             // Exception was caught - handler may be in different frame, reload cache
-            reload_cache!($self, $cached_frame);
+            reload_after_catch!($self, $cached_frame);
         }
     };
 }
@@ -81,6 +90,20 @@ macro_rules! catch_sync {
             return Err(result);
         }
         // Exception was caught - handler may be in different frame, reload cache
+        reload_after_catch!($self, $cached_frame);
+    }};
+}
+
+/// Reloads cached frame state after an exception was handled.
+///
+/// An exception that finishes a spawned task switches to the next ready task. If every
+/// remaining task is blocked on external futures, there are no frames left, so control
+/// returns to the host instead.
+macro_rules! reload_after_catch {
+    ($self:expr, $cached_frame:ident) => {{
+        if $self.frames.is_empty() {
+            return Ok(FrameExit::ResolveFutures($self.scheduler().pending_call_ids()));
+        }
         reload_cache!($self, $cached_frame);
     }};
 }
@@ -161,6 +184,7 @@ macro_rules! jump_relative {
 /// - `FramePushed`: Reload the cached frame (a new frame was pushed)
 /// - `External(ext_id, args)`: Return `FrameExit::ExternalCall` to yield to host
 /// - `OsCall(func, args)`: Return `FrameExit::OsCall` to yield to host
+/// - `Compile(request)`: Return `FrameExit::Compile` to compile eval/exec code or intern a name
 /// - `Err(err)`: Handle the exception via `catch_sync!`
 macro_rules! handle_call_result {
     ($self:expr, $cached_frame:ident, $result:expr) => {
@@ -170,7 +194,7 @@ macro_rules! handle_call_result {
             Ok(CallResult::External(ext_id, args)) => {
                 let call_id = $self.allocate_call_id();
                 // Sync cached IP back to frame before snapshot for resume
-                $self.current_frame_mut().ip = $cached_frame.ip;
+                $self.current_frame_mut().ip = $self.host_resume_ip($cached_frame.ip);
                 return Ok(FrameExit::ExternalCall {
                     ext_function_id: ext_id,
                     args,
@@ -180,13 +204,28 @@ macro_rules! handle_call_result {
             Ok(CallResult::OsCall(func, args)) => {
                 let call_id = $self.allocate_call_id();
                 // Sync cached IP back to frame before snapshot for resume
-                $self.current_frame_mut().ip = $cached_frame.ip;
+                $self.current_frame_mut().ip = $self.host_resume_ip($cached_frame.ip);
                 return Ok(FrameExit::OsCall {
                     function: func,
                     args,
                     call_id,
                 });
             }
+            Ok(CallResult::MethodCall(method_name, args)) => {
+                let call_id = $self.allocate_call_id();
+                // Sync cached IP back to frame before snapshot for resume
+                $self.current_frame_mut().ip = $self.host_resume_ip($cached_frame.ip);
+                return Ok(FrameExit::MethodCall {
+                    method_name,
+                    args,
+                    call_id,
+                });
+            }
+            Ok(CallResult::Compile(request)) => {
+                // Sync cached IP back to frame before snapshot for resume
+                $self.current_frame_mut().ip = $self.host_resume_ip($cached_frame.ip);
+                return Ok(FrameExit::Compile(request));
+            }
             Err(err) => catch_sync!($self, $cached_frame, err),
         }
     };
@@ -225,12 +264,32 @@ pub enum FrameExit {
         call_id: CallId,
     },
 
+    /// Execution paused for a method call on a host-provided dataclass.
+    ///
+    /// Handled like `ExternalCall`, except the host looks the method up on the
+    /// dataclass instance passed as the first positional argument.
+    MethodCall {
+        /// Name of the method to call.
+        method_name: StringId,
+        /// Arguments for the method, starting with the dataclass instance.
+        args: ArgValues,
+        /// Unique ID for this call, used for async correlation.
+        call_id: CallId,
+    },
+
     /// All tasks are blocked waiting for external futures to resolve.
     ///
     /// The caller must resolve the pending CallIds before calling `resume()`.
     /// This happens when await is called on an ExternalFuture that hasn't
     /// been resolved yet, and there are no other ready tasks to switch to.
     ResolveFutures(Vec<CallId>),
+
+    /// Execution paused to compile the code passed to `eval()` or `exec()`, or to intern an
+    /// attribute name passed to `setattr()`.
+    ///
+    /// Not seen by the host: the caller adds to the interns, restores the VM from its
+    /// snapshot and calls `resume_compile()`.
+    Compile(CompileRequest),
 }
 
 /// A single function activation record.
@@ -261,6 +320,17 @@ pub struct CallFrame<'code> {
 
     /// Call site position (for tracebacks).
     call_position: Option<CodeRange>,
+
+    /// Instance being initialized when this frame runs a class's `__init__`.
+    ///
+    /// The frame owns a reference to the instance; on return the instance replaces
+    /// `__init__`'s return value as the result of the class call.
+    init_instance: Option<HeapId>,
+
+    /// Generator this frame is running, if any.
+    ///
+    /// On `yield` the frame's state is saved back into the generator instead of being dropped.
+    generator: Option<GeneratorFrame>,
 }
 
 impl<'code> CallFrame<'code> {
@@ -274,6 +344,8 @@ impl<'code> CallFrame<'code> {
             function_id: None,
             cells: Vec::new(),
             call_position: None,
+            init_instance: None,
+            generator: None,
         }
     }
 
@@ -294,6 +366,8 @@ impl<'code> CallFrame<'code> {
             function_id: Some(function_id),
             cells,
             call_position,
+            init_instance: None,
+            generator: None,
         }
     }
 }
@@ -344,6 +418,12 @@ pub struct SerializedFrame {
 
     /// Call site position (for tracebacks).
     call_position: Option<CodeRange>,
+
+    /// Instance being initialized by this frame's `__init__` call, if any.
+    init_instance: Option<HeapId>,
+
+    /// Generator this frame is running, if any.
+    generator: Option<GeneratorFrame>,
 }
 
 impl CallFrame<'_> {
@@ -356,6 +436,8 @@ impl CallFrame<'_> {
             namespace_idx: self.namespace_idx,
             cells: self.cells.clone(),
             call_position: self.call_position,
+            init_instance: self.init_instance,
+            generator: self.generator,
         }
     }
 }
@@ -398,6 +480,15 @@ pub struct VMSnapshot {
     /// This enables async execution to be paused and resumed across host calls.
     /// None if no async operations have been performed yet.
     scheduler: Option<Scheduler>,
+
+    /// Arguments of the `eval()`/`exec()` or `setattr()` call waiting for the interns to be extended.
+    pending_compile: Vec<Value>,
+
+    /// The `datetime.now()`-style call waiting for the host to return the current time.
+    pending_clock: Option<ClockRead>,
+
+    /// Iterator steps of instructions waiting for a frame or the host to produce a value.
+    iter_steps: Vec<IterStep>,
 }
 
 // ============================================================================
@@ -459,6 +550,30 @@ pub struct VM<'a, T: ResourceTracker, P: PrintWriter> {
     /// Stored here because the main task's frames have `function_id: None` and
     /// need a reference to the module code when being restored after task switching.
     module_code: Option<&'a Code>,
+
+    /// Number of frames below the innermost nested run started by `call_sync`.
+    ///
+    /// Zero when not inside a nested run. Returning from or raising out of the frame just
+    /// above the floor ends the nested run instead of continuing in the caller's frame.
+    frame_floor: usize,
+
+    /// Current nesting depth of `call_sync` runs, bounded to protect the native stack.
+    nested_calls: usize,
+
+    /// Arguments of the `eval()`/`exec()` or `setattr()` call waiting for the interns to be extended.
+    ///
+    /// Set when the VM pauses with `FrameExit::Compile`, taken by `resume_compile()`.
+    pending_compile: Vec<Value>,
+
+    /// The clock read waiting for the host to answer an `OsFunction::Time` call.
+    ///
+    /// Set by `date.today()`, `datetime.today()` and `datetime.now()`, taken by `resume()`,
+    /// which converts the returned timestamp instead of pushing it.
+    pending_clock: Option<ClockRead>,
+
+    /// Iterators of instructions that run again once a frame or the host produced the value
+    /// the iterator waits for, innermost last (see `iter`).
+    iter_steps: Vec<IterStep>,
 }
 
 impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
@@ -481,6 +596,11 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
             next_call_id: 0,
             scheduler: None, // Lazy - no allocation for sync code
             module_code: None,
+            frame_floor: 0,
+            nested_calls: 0,
+            pending_compile: Vec::new(),
+            pending_clock: None,
+            iter_steps: Vec::new(),
         }
     }
 
@@ -522,6 +642,8 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                     function_id: sf.function_id,
                     cells: sf.cells,
                     call_position: sf.call_position,
+                    init_instance: sf.init_instance,
+                    generator: sf.generator,
                 }
             })
             .collect();
@@ -538,13 +660,22 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
             next_call_id: snapshot.next_call_id,
             scheduler: snapshot.scheduler,
             module_code: Some(module_code),
+            frame_floor: 0,
+            nested_calls: 0,
+            pending_compile: snapshot.pending_compile,
+            pending_clock: snapshot.pending_clock,
+            iter_steps: snapshot.iter_steps,
         }
     }
     /// Consumes the VM and creates a snapshot for pause/resume if needed.
     pub fn check_snapshot(mut self, result: &RunResult<FrameExit>) -> Option<VMSnapshot> {
         if matches!(
             result,
-            Ok(FrameExit::ExternalCall { .. } | FrameExit::OsCall { .. } | FrameExit::ResolveFutures(_))
+            Ok(FrameExit::ExternalCall { .. }
+                | FrameExit::OsCall { .. }
+                | FrameExit::MethodCall { .. }
+                | FrameExit::ResolveFutures(_)
+                | FrameExit::Compile(_))
         ) {
             Some(self.snapshot())
         } else {
@@ -553,6 +684,18 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
         }
     }
 
+    /// Consumes the VM and creates a snapshot only if it paused with `FrameExit::Compile`.
+    ///
+    /// Used by callers that can't pause for the host, which treat other exits as final.
+    pub fn check_compile(mut self, result: &RunResult<FrameExit>) -> Option<VMSnapshot> {
+        if matches!(result, Ok(FrameExit::Compile(_))) {
+            Some(self.snapshot())
+        } else {
+            self.cleanup();
+            None
+        }
+    }
+
     /// Consumes the VM and creates a snapshot for pause/resume.
     ///
     /// **Ownership transfer:** This method takes `self` by value, consuming the VM.
@@ -571,6 +714,9 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
             instruction_ip: self.instruction_ip,
             next_call_id: self.next_call_id,
             scheduler: self.scheduler,
+            pending_compile: self.pending_compile,
+            pending_clock: self.pending_clock,
+            iter_steps: self.iter_steps,
         }
     }
 
@@ -595,6 +741,9 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
         for value in self.stack.drain(..) {
             value.drop_with_heap(self.heap);
         }
+        self.drop_pending_compile();
+        self.pending_clock = None;
+        self.drop_iter_steps(0);
         // Clean up current frames (main module frame after return, or any remaining frames)
         self.cleanup_current_frames();
         // Clean up task frame namespaces (scheduler doesn't have access to namespaces)
@@ -619,9 +768,17 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
             let task_id = TaskId::new(u32::try_from(task_idx).expect("task_idx exceeds u32"));
             let task = scheduler.get_task_mut(task_id);
             for frame in std::mem::take(&mut task.frames) {
-                // Clean up cell references
-                for cell_id in frame.cells {
-                    self.heap.dec_ref(cell_id);
+                if let Some(gen_frame) = frame.generator {
+                    // A generator frame's cells are owned by its namespace
+                    finish_generator_frame(self.heap, gen_frame);
+                } else {
+                    // Clean up cell references
+                    for cell_id in frame.cells {
+                        self.heap.dec_ref(cell_id);
+                    }
+                }
+                if let Some(instance_id) = frame.init_instance {
+                    self.heap.dec_ref(instance_id);
                 }
                 // Clean up the namespace (but not the global namespace)
                 if frame.namespace_idx != GLOBAL_NS_IDX {
@@ -676,7 +833,9 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
             // For NoLimitTracker, these are inlined no-ops that compile away.
             self.heap.tracker_mut().check_time()?;
 
-            if self.heap.should_gc() {
+            // GC is deferred during nested runs since the outer operation may hold
+            // values that aren't reachable from any root
+            if self.frame_floor == 0 && self.heap.should_gc() {
                 // Sync IP before GC for safety
                 self.current_frame_mut().ip = cached_frame.ip;
                 self.run_gc();
@@ -734,6 +893,12 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                             Ok(v) => self.push(v),
                             Err(e) => catch_sync!(self, cached_frame, RunError::from(e)),
                         }
+                    } else if let Value::Imaginary(imag) = value {
+                        // Imaginary literals are heap-allocated complex numbers too
+                        match Complex::new(0.0, imag).into_value(self.heap) {
+                            Ok(v) => self.push(v),
+                            Err(e) => catch_sync!(self, cached_frame, RunError::from(e)),
+                        }
                     } else {
                         // Now we can safely increment refcount for Ref values
                         if let Value::Ref(id) = &value {
@@ -773,7 +938,20 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                 }
                 Opcode::DeleteLocal => {
                     let slot = u16::from(fetch_u8!(cached_frame));
-                    self.delete_local(&cached_frame, slot);
+                    try_catch_sync!(self, cached_frame, self.delete_local(&cached_frame, slot));
+                }
+                Opcode::DeleteLocalW => {
+                    let slot = fetch_u16!(cached_frame);
+                    try_catch_sync!(self, cached_frame, self.delete_local(&cached_frame, slot));
+                }
+                Opcode::ExportLocal => {
+                    let slot = fetch_u16!(cached_frame);
+                    let name_id = StringId::from_index(fetch_u16!(cached_frame));
+                    try_catch_sync!(
+                        self,
+                        cached_frame,
+                        self.export_local(cached_frame.namespace_idx, slot, name_id)
+                    );
                 }
                 // Variables - Global Operations
                 Opcode::LoadGlobal => {
@@ -784,6 +962,10 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                     let slot = fetch_u16!(cached_frame);
                     self.store_global(slot);
                 }
+                Opcode::DeleteGlobal => {
+                    let slot = fetch_u16!(cached_frame);
+                    try_catch_sync!(self, cached_frame, self.delete_global(slot));
+                }
                 // Variables - Cell Operations (closures)
                 Opcode::LoadCell => {
                     let slot = fetch_u16!(cached_frame);
@@ -793,6 +975,10 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                     let slot = fetch_u16!(cached_frame);
                     self.store_cell(slot);
                 }
+                Opcode::DeleteCell => {
+                    let slot = fetch_u16!(cached_frame);
+                    try_catch_sync!(self, cached_frame, self.delete_cell(slot));
+                }
                 // Binary Operations - route through exception handling for tracebacks
                 Opcode::BinaryAdd => try_catch_sync!(self, cached_frame, self.binary_add()),
                 Opcode::BinarySub => try_catch_sync!(self, cached_frame, self.binary_sub()),
@@ -815,10 +1001,10 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                 // Comparison Operations
                 Opcode::CompareEq => try_catch_sync!(self, cached_frame, self.compare_eq()),
                 Opcode::CompareNe => try_catch_sync!(self, cached_frame, self.compare_ne()),
-                Opcode::CompareLt => try_catch_sync!(self, cached_frame, self.compare_ord(Ordering::is_lt)),
-                Opcode::CompareLe => try_catch_sync!(self, cached_frame, self.compare_ord(Ordering::is_le)),
-                Opcode::CompareGt => try_catch_sync!(self, cached_frame, self.compare_ord(Ordering::is_gt)),
-                Opcode::CompareGe => try_catch_sync!(self, cached_frame, self.compare_ord(Ordering::is_ge)),
+                Opcode::CompareLt => try_catch_sync!(self, cached_frame, self.compare_ord(OrdOp::Lt)),
+                Opcode::CompareLe => try_catch_sync!(self, cached_frame, self.compare_ord(OrdOp::Le)),
+                Opcode::CompareGt => try_catch_sync!(self, cached_frame, self.compare_ord(OrdOp::Gt)),
+                Opcode::CompareGe => try_catch_sync!(self, cached_frame, self.compare_ord(OrdOp::Ge)),
                 Opcode::CompareIs => self.compare_is(false),
                 Opcode::CompareIsNot => self.compare_is(true),
                 Opcode::CompareIn => try_catch_sync!(self, cached_frame, self.compare_in(false)),
@@ -831,9 +1017,12 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                 // Unary Operations
                 Opcode::UnaryNot => {
                     let value = self.pop();
-                    let result = !value.py_bool(self.heap, self.interns);
+                    let result = self.value_is_true(&value);
                     value.drop_with_heap(self.heap);
-                    self.push(Value::Bool(result));
+                    match result {
+                        Ok(is_true) => self.push(Value::Bool(!is_true)),
+                        Err(e) => catch_sync!(self, cached_frame, e),
+                    }
                 }
                 Opcode::UnaryNeg => {
                     // Unary minus - negate numeric value
@@ -862,6 +1051,20 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                                     Ok(v) => self.push(v),
                                     Err(e) => catch_sync!(self, cached_frame, RunError::from(e)),
                                 }
+                            } else if let HeapData::Complex(c) = self.heap.get(id) {
+                                let negated = c.neg();
+                                value.drop_with_heap(self.heap);
+                                match negated.into_value(self.heap) {
+                                    Ok(v) => self.push(v),
+                                    Err(e) => catch_sync!(self, cached_frame, RunError::from(e)),
+                                }
+                            } else if let HeapData::TimeDelta(td) = self.heap.get(id) {
+                                let negated = td.neg();
+                                value.drop_with_heap(self.heap);
+                                match negated.and_then(|td| Ok(td.into_value(self.heap)?)) {
+                                    Ok(v) => self.push(v),
+                                    Err(e) => catch_sync!(self, cached_frame, e),
+                                }
                             } else {
                                 let value_type = value.py_type(self.heap);
                                 value.drop_with_heap(self.heap);
@@ -882,8 +1085,11 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                         Value::Int(_) | Value::Float(_) => self.push(value),
                         Value::Bool(b) => self.push(Value::Int(i64::from(b))),
                         Value::Ref(id) => {
-                            if matches!(self.heap.get(id), HeapData::LongInt(_)) {
-                                // LongInt - return as-is (value already has correct refcount)
+                            if matches!(
+                                self.heap.get(id),
+                                HeapData::LongInt(_) | HeapData::Complex(_) | HeapData::TimeDelta(_)
+                            ) {
+                                // LongInt, Complex or TimeDelta - return as-is (value already has correct refcount)
                                 self.push(value);
                             } else {
                                 let value_type = value.py_type(self.heap);
@@ -986,6 +1192,12 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                     let func_name_id = fetch_u16!(cached_frame);
                     try_catch_sync!(self, cached_frame, self.dict_merge(func_name_id));
                 }
+                Opcode::DictUpdate => {
+                    try_catch_sync!(self, cached_frame, self.dict_update());
+                }
+                Opcode::SetUpdate => {
+                    try_catch_sync!(self, cached_frame, self.set_update());
+                }
                 // Comprehension Building - append/add/set items during iteration
                 Opcode::ListAppend => {
                     let depth = fetch_u8!(cached_frame) as usize;
@@ -1003,7 +1215,7 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                 Opcode::BinarySubscr => {
                     let index = self.pop();
                     let obj = self.pop();
-                    let result = obj.py_getitem(&index, self.heap, self.interns);
+                    let result = self.binary_subscr(&obj, &index);
                     obj.drop_with_heap(self.heap);
                     index.drop_with_heap(self.heap);
                     match result {
@@ -1016,7 +1228,17 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                     let index = self.pop();
                     let mut obj = self.pop();
                     let value = self.pop();
-                    let result = obj.py_setitem(index, value, self.heap, self.interns);
+                    let result = self.store_subscr(&mut obj, index, value);
+                    obj.drop_with_heap(self.heap);
+                    if let Err(e) = result {
+                        catch_sync!(self, cached_frame, e);
+                    }
+                }
+                Opcode::DeleteSubscr => {
+                    // Stack order: obj, index (TOS)
+                    let index = self.pop();
+                    let mut obj = self.pop();
+                    let result = self.delete_subscr(&mut obj, index);
                     obj.drop_with_heap(self.heap);
                     if let Err(e) = result {
                         catch_sync!(self, cached_frame, e);
@@ -1025,6 +1247,8 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                 Opcode::LoadAttr => {
                     let name_idx = fetch_u16!(cached_frame);
                     let name_id = StringId::from_index(name_idx);
+                    // Sync IP before call (property getters push a frame)
+                    self.current_frame_mut().ip = cached_frame.ip;
                     handle_call_result!(self, cached_frame, self.load_attr(name_id));
                 }
                 Opcode::LoadAttrImport => {
@@ -1037,48 +1261,77 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                     let name_id = StringId::from_index(name_idx);
                     try_catch_sync!(self, cached_frame, self.store_attr(name_id));
                 }
+                Opcode::DeleteAttr => {
+                    let name_idx = fetch_u16!(cached_frame);
+                    let name_id = StringId::from_index(name_idx);
+                    try_catch_sync!(self, cached_frame, self.delete_attr(name_id));
+                }
                 // Control Flow - use cached_frame.ip directly for jumps
                 Opcode::Jump => {
                     let offset = fetch_i16!(cached_frame);
                     jump_relative!(cached_frame.ip, offset);
                 }
+                // Conditions of user-defined instances may call `__bool__` or `__len__`
                 Opcode::JumpIfTrue => {
                     let offset = fetch_i16!(cached_frame);
                     let cond = self.pop();
-                    if cond.py_bool(self.heap, self.interns) {
-                        jump_relative!(cached_frame.ip, offset);
-                    }
+                    let result = self.value_is_true(&cond);
                     cond.drop_with_heap(self.heap);
+                    match result {
+                        Ok(true) => jump_relative!(cached_frame.ip, offset),
+                        Ok(false) => {}
+                        Err(e) => catch_sync!(self, cached_frame, e),
+                    }
                 }
                 Opcode::JumpIfFalse => {
                     let offset = fetch_i16!(cached_frame);
                     let cond = self.pop();
-                    if !cond.py_bool(self.heap, self.interns) {
-                        jump_relative!(cached_frame.ip, offset);
-                    }
+                    let result = self.value_is_true(&cond);
                     cond.drop_with_heap(self.heap);
+                    match result {
+                        Ok(true) => {}
+                        Ok(false) => jump_relative!(cached_frame.ip, offset),
+                        Err(e) => catch_sync!(self, cached_frame, e),
+                    }
                 }
                 Opcode::JumpIfTrueOrPop => {
                     let offset = fetch_i16!(cached_frame);
-                    if self.peek().py_bool(self.heap, self.interns) {
-                        jump_relative!(cached_frame.ip, offset);
-                    } else {
-                        let value = self.pop();
-                        value.drop_with_heap(self.heap);
+                    let value = self.pop();
+                    match self.value_is_true(&value) {
+                        Ok(true) => {
+                            self.push(value);
+                            jump_relative!(cached_frame.ip, offset);
+                        }
+                        Ok(false) => value.drop_with_heap(self.heap),
+                        Err(e) => {
+                            value.drop_with_heap(self.heap);
+                            catch_sync!(self, cached_frame, e);
+                        }
                     }
                 }
                 Opcode::JumpIfFalseOrPop => {
                     let offset = fetch_i16!(cached_frame);
-                    if self.peek().py_bool(self.heap, self.interns) {
-                        let value = self.pop();
-                        value.drop_with_heap(self.heap);
-                    } else {
-                        jump_relative!(cached_frame.ip, offset);
+                    let value = self.pop();
+                    match self.value_is_true(&value) {
+                        Ok(true) => value.drop_with_heap(self.heap),
+                        Ok(false) => {
+                            self.push(value);
+                            jump_relative!(cached_frame.ip, offset);
+                        }
+                        Err(e) => {
+                            value.drop_with_heap(self.heap);
+                            catch_sync!(self, cached_frame, e);
+                        }
                     }
                 }
                 // Iteration - route through exception handling
                 Opcode::GetIter => {
                     let value = self.pop();
+                    if self.is_iterator(&value) {
+                        // Generators and iterators are their own iterators
+                        self.push(value);
+                        continue;
+                    }
                     // Create a MontyIter from the value and store on heap
                     match MontyIter::new(value, self.heap, self.interns) {
                         Ok(iter) => match self.heap.allocate(HeapData::Iter(iter)) {
@@ -1090,21 +1343,52 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                 }
                 Opcode::ForIter => {
                     let offset = fetch_i16!(cached_frame);
-                    // Peek at the iterator on TOS and extract heap_id
-                    let Value::Ref(heap_id) = *self.peek() else {
-                        return Err(RunError::internal("ForIter: expected iterator ref on stack"));
+                    // Sync IP before advancing (`map()` and `filter()` call functions)
+                    self.current_frame_mut().ip = cached_frame.ip;
+                    let next = if self.iter_steps_waiting() {
+                        // The generator or function the iterator waited for produced its value
+                        self.resume_iter_steps()
+                    } else {
+                        // Peek at the iterator on TOS and extract heap_id
+                        let Value::Ref(heap_id) = *self.peek() else {
+                            return Err(RunError::internal("ForIter: expected iterator ref on stack"));
+                        };
+                        if matches!(self.heap.get(heap_id), HeapData::Generator(_)) {
+                            // Run the generator's body until its next yield, continuing after the
+                            // loop once it returns
+                            let mut end_ip = cached_frame.ip;
+                            jump_relative!(end_ip, offset);
+                            match self.resume_generator(heap_id, Value::None, ResumeMode::ForIter { end_ip }) {
+                                Ok(true) => reload_cache!(self, cached_frame),
+                                Ok(false) => {
+                                    let generator = self.pop();
+                                    generator.drop_with_heap(self.heap);
+                                    cached_frame.ip = end_ip;
+                                }
+                                Err(e) => {
+                                    let generator = self.pop();
+                                    generator.drop_with_heap(self.heap);
+                                    catch_sync!(self, cached_frame, e);
+                                }
+                            }
+                            continue;
+                        }
+                        self.next_item_step(heap_id)
                     };
 
-                    // Use advance_iterator which avoids std::mem::replace overhead
-                    // by using a two-phase approach: read state, get value, update index
-                    match advance_on_heap(self.heap, heap_id, self.interns) {
-                        Ok(Some(value)) => self.push(value),
-                        Ok(None) => {
+                    match next {
+                        Ok(StepResult::Item(value)) => self.push(value),
+                        Ok(StepResult::Exhausted) => {
                             // Iterator exhausted - pop it and jump to end
                             let iter = self.pop();
                             iter.drop_with_heap(self.heap);
                             jump_relative!(cached_frame.ip, offset);
                         }
+                        Ok(StepResult::Suspended(call)) => {
+                            // Run again once the iterator has the value it waits for
+                            self.rewind_to_instruction();
+                            handle_call_result!(self, cached_frame, Ok(call));
+                        }
                         Err(e) => {
                             // Error during iteration (e.g., dict size changed)
                             let iter = self.pop();
@@ -1127,22 +1411,24 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                     let builtin_id = fetch_u8!(cached_frame);
                     let arg_count = fetch_u8!(cached_frame) as usize;
 
-                    match self.exec_call_builtin_function(builtin_id, arg_count) {
-                        Ok(result) => self.push(result),
-                        // IP sync deferred to error path (no frame push possible)
-                        Err(err) => catch_sync!(self, cached_frame, err),
-                    }
+                    // Sync IP before call (`next()` on a generator pushes a frame)
+                    self.current_frame_mut().ip = cached_frame.ip;
+
+                    handle_call_result!(
+                        self,
+                        cached_frame,
+                        self.exec_call_builtin_function(builtin_id, arg_count)
+                    );
                 }
                 Opcode::CallBuiltinType => {
                     // Fetch operands: type_id (u8) + arg_count (u8)
                     let type_id = fetch_u8!(cached_frame);
                     let arg_count = fetch_u8!(cached_frame) as usize;
 
-                    match self.exec_call_builtin_type(type_id, arg_count) {
-                        Ok(result) => self.push(result),
-                        // IP sync deferred to error path (no frame push possible)
-                        Err(err) => catch_sync!(self, cached_frame, err),
-                    }
+                    // Sync IP before call (`list(gen)` resumes the generator in the run loop)
+                    self.current_frame_mut().ip = cached_frame.ip;
+
+                    handle_call_result!(self, cached_frame, self.exec_call_builtin_type(type_id, arg_count));
                 }
                 Opcode::CallFunctionKw => {
                     // Fetch operands: pos_count, kw_count, then kw_count name indices
@@ -1233,6 +1519,11 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                         self.push(Value::Ref(heap_id));
                     }
                 }
+                Opcode::BuildClass => {
+                    let name_id = StringId::from_index(fetch_u16!(cached_frame));
+                    let has_base = fetch_u8!(cached_frame) != 0;
+                    try_catch_sync!(self, cached_frame, self.build_class(name_id, has_base));
+                }
                 Opcode::MakeClosure => {
                     let func_idx = fetch_u16!(cached_frame);
                     let defaults_count = fetch_u8!(cached_frame) as usize;
@@ -1278,11 +1569,17 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                     let error = self.make_exception(exc, true); // is_raise=true, hide caret
                     catch_sync!(self, cached_frame, error);
                 }
+                Opcode::RaiseFrom => {
+                    let cause = self.pop();
+                    let exc = self.pop();
+                    let error = self.make_exception_from(exc, cause);
+                    catch_sync!(self, cached_frame, error);
+                }
                 Opcode::Reraise => {
                     // Pop the current exception from the stack to re-raise it
                     // If caught, handle_exception will push it back
                     let error = if let Some(exc) = self.exception_stack.pop() {
-                        self.make_exception(exc, true) // is_raise=true for reraise
+                        self.make_reraise(exc)
                     } else {
                         // No active exception - create a RuntimeError
                         SimpleException::new_msg(ExcType::RuntimeError, "No active exception to reraise").into()
@@ -1305,9 +1602,77 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                     let result = result?;
                     self.push(Value::Bool(result));
                 }
+                Opcode::CheckExcStarMatch => {
+                    // Stack: [rest, exc_type] -> [rest', match]
+                    let exc_type = self.pop();
+                    let rest = self.pop();
+                    let result = self.check_exc_star_match(rest, &exc_type);
+                    exc_type.drop_with_heap(self.heap);
+                    match result {
+                        Ok((rest, matched)) => {
+                            self.push(rest);
+                            self.push(matched);
+                        }
+                        Err(e) => catch_sync!(self, cached_frame, e),
+                    }
+                }
+                Opcode::ReraiseStar => {
+                    // Stack: [exception, raised, rest] -> []
+                    let rest = self.pop();
+                    let raised = self.pop();
+                    let exception = self.pop();
+                    if let Some(error) = self.reraise_star(exception, raised, rest) {
+                        if let Some(result) = self.propagate_exception(error) {
+                            return Err(result);
+                        }
+                        reload_after_catch!(self, cached_frame);
+                    }
+                }
+                // Context Managers
+                Opcode::BeforeWith => {
+                    // Stack: [mgr] -> [mgr, enter_result]
+                    let is_async = fetch_u8!(cached_frame) != 0;
+                    // Sync IP before call (may yield to host for dataclass methods)
+                    self.current_frame_mut().ip = cached_frame.ip;
+                    handle_call_result!(self, cached_frame, self.exec_before_with(is_async));
+                }
+                Opcode::WithExceptStart => {
+                    // Stack: [mgr, exc] -> [mgr, exc, exit_result]
+                    let is_async = fetch_u8!(cached_frame) != 0;
+                    self.current_frame_mut().ip = cached_frame.ip;
+                    handle_call_result!(self, cached_frame, self.exec_with_except_start(is_async));
+                }
                 // Return - reload cache after popping frame
                 Opcode::ReturnValue => {
-                    let value = self.pop();
+                    let mut value = self.pop();
+                    if let Some(instance_id) = self.current_frame_mut().init_instance.take() {
+                        // Returning from `__init__` - the class call evaluates to the instance
+                        match self.finish_init(value, instance_id) {
+                            Ok(instance) => value = instance,
+                            Err(e) => {
+                                catch_sync!(self, cached_frame, e);
+                                continue;
+                            }
+                        }
+                    }
+                    if let Some(gen_frame) = self.current_frame().generator
+                        && gen_frame.resume != ResumeMode::Sync
+                    {
+                        // Generator finished - deliver the return value to whoever resumed it
+                        self.pop_frame();
+                        let result = self.generator_returned(gen_frame.resume, value);
+                        reload_cache!(self, cached_frame);
+                        if let Err(e) = result {
+                            self.instruction_ip = cached_frame.ip.saturating_sub(1);
+                            catch_sync!(self, cached_frame, e);
+                        }
+                        continue;
+                    }
+                    if self.frame_floor > 0 && self.frames.len() == self.frame_floor + 1 {
+                        // Returning from the frame that started a nested run
+                        self.pop_frame();
+                        return Ok(FrameExit::Return(value));
+                    }
                     if self.frames.len() == 1 {
                         // Last frame - check if this is main task or spawned task
                         let is_main_task = self.is_main_task();
@@ -1343,6 +1708,74 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                     // Reload cache from parent frame
                     reload_cache!(self, cached_frame);
                 }
+                // Generators
+                Opcode::YieldValue => {
+                    let value = self.pop();
+                    // Sync IP so the generator resumes after the yield
+                    self.current_frame_mut().ip = cached_frame.ip;
+                    match self.yield_value(value) {
+                        Ok(None) => reload_cache!(self, cached_frame),
+                        // Yielding to `generator_next_sync`, which started a nested run
+                        Ok(Some(value)) => return Ok(FrameExit::Return(value)),
+                        Err(e) => {
+                            reload_cache!(self, cached_frame);
+                            self.instruction_ip = cached_frame.ip.saturating_sub(1);
+                            catch_sync!(self, cached_frame, e);
+                        }
+                    }
+                }
+                Opcode::SendSub => {
+                    // Stack: [subiter, sent] -> [subiter, yielded] or [result] (jump to end)
+                    let offset = fetch_i16!(cached_frame);
+                    let mut end_ip = cached_frame.ip;
+                    jump_relative!(end_ip, offset);
+                    let sent = self.pop();
+                    let Value::Ref(heap_id) = *self.peek() else {
+                        return Err(RunError::internal("SendSub: expected iterator ref on stack"));
+                    };
+                    if matches!(self.heap.get(heap_id), HeapData::Generator(_)) {
+                        self.current_frame_mut().ip = cached_frame.ip;
+                        match self.resume_generator(heap_id, sent, ResumeMode::YieldFrom { end_ip }) {
+                            Ok(true) => reload_cache!(self, cached_frame),
+                            Ok(false) => {
+                                let generator = self.pop();
+                                generator.drop_with_heap(self.heap);
+                                self.push(Value::None);
+                                cached_frame.ip = end_ip;
+                            }
+                            Err(e) => catch_sync!(self, cached_frame, e),
+                        }
+                        continue;
+                    }
+                    // Other iterators can't receive values, so `sent` is always `None` here
+                    sent.drop_with_heap(self.heap);
+                    self.current_frame_mut().ip = cached_frame.ip;
+                    match self.iter_next(heap_id) {
+                        Ok(Some(value)) => self.push(value),
+                        Ok(None) => {
+                            let iter = self.pop();
+                            iter.drop_with_heap(self.heap);
+                            self.push(Value::None);
+                            cached_frame.ip = end_ip;
+                        }
+                        Err(e) => catch_sync!(self, cached_frame, e),
+                    }
+                }
+                Opcode::GetAIter => {
+                    // Sync IP before call (`__aiter__` may push a frame)
+                    self.current_frame_mut().ip = cached_frame.ip;
+                    handle_call_result!(self, cached_frame, self.exec_get_aiter());
+                }
+                Opcode::GetANext => {
+                    // Sync IP before call (`__anext__` may push a frame)
+                    self.current_frame_mut().ip = cached_frame.ip;
+                    handle_call_result!(self, cached_frame, self.exec_get_anext());
+                }
+                Opcode::EndAsyncFor => {
+                    if let Err(e) = self.exec_end_async_for() {
+                        catch_sync!(self, cached_frame, e);
+                    }
+                }
                 // Async/Await
                 Opcode::Await => {
                     // Sync IP before exec (may push new frame for coroutine)
@@ -1375,6 +1808,22 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
                     let after = fetch_u8!(cached_frame) as usize;
                     try_catch_sync!(self, cached_frame, self.unpack_ex(before, after));
                 }
+                // Pattern Matching
+                Opcode::MatchSequence => self.match_sequence(),
+                Opcode::MatchMapping => self.match_mapping(),
+                Opcode::GetLen => {
+                    try_catch_sync!(self, cached_frame, self.get_len());
+                }
+                Opcode::MatchKeys => {
+                    try_catch_sync!(self, cached_frame, self.match_keys());
+                }
+                Opcode::CopyDictWithoutKeys => {
+                    try_catch_sync!(self, cached_frame, self.copy_dict_without_keys());
+                }
+                Opcode::MatchClass => {
+                    let positional_count = fetch_u8!(cached_frame) as usize;
+                    try_catch_sync!(self, cached_frame, self.match_class(positional_count));
+                }
                 // Special
                 Opcode::Nop => {
                     // No operation
@@ -1412,25 +1861,48 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
 
     /// Resumes execution after an external call completes.
     ///
-    /// Pushes the return value onto the stack and continues execution.
+    /// Pushes the return value onto the stack and continues execution. If the call was a
+    /// clock read like `datetime.now()`, the returned timestamp is converted first.
     pub fn resume(&mut self, obj: MontyObject) -> Result<FrameExit, RunError> {
         let value = obj
             .to_value(self.heap, self.interns)
             .map_err(|e| SimpleException::new(ExcType::RuntimeError, Some(format!("invalid return type: {e}"))))?;
+        let value = match self.pending_clock.take() {
+            Some(clock) => {
+                let result = clock.into_value(&value, self.heap);
+                value.drop_with_heap(self.heap);
+                match result {
+                    Ok(value) => value,
+                    Err(error) => return self.resume_with_exception(error),
+                }
+            }
+            None => value,
+        };
         self.push(value);
         self.run()
     }
 
+    /// Returns whether the VM is waiting for the host to return the current time.
+    #[must_use]
+    pub fn has_pending_clock(&self) -> bool {
+        self.pending_clock.is_some()
+    }
+
     /// Resumes execution after an external call raised an exception.
     ///
     /// Uses the exception handling mechanism to try to catch the exception.
     /// If caught, continues execution at the handler. If not, propagates the error.
     pub fn resume_with_exception(&mut self, error: RunError) -> Result<FrameExit, RunError> {
+        self.pending_clock = None;
         // Use the normal exception handling mechanism
         // handle_exception returns None if caught, Some(error) if not caught
         if let Some(uncaught_error) = self.handle_exception(error) {
             return Err(uncaught_error);
         }
+        // The exception may have finished a task, leaving only tasks blocked on futures
+        if self.frames.is_empty() {
+            return Ok(FrameExit::ResolveFutures(self.scheduler().pending_call_ids()));
+        }
         // Exception was caught, continue execution
         self.run()
     }
@@ -1491,14 +1963,21 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
     pub(super) fn pop_frame(&mut self) {
         let frame = self.frames.pop().expect("no frame to pop");
         // Clean up frame's stack region
-        while self.stack.len() > frame.stack_base {
-            let value = self.stack.pop().unwrap();
-            value.drop_with_heap(self.heap);
-        }
+        self.truncate_stack(frame.stack_base);
         // Clean up the namespace (but not the global namespace)
         if frame.namespace_idx != GLOBAL_NS_IDX {
             self.namespaces.drop_with_heap(frame.namespace_idx, self.heap);
         }
+        // Release an instance whose `__init__` didn't complete
+        if let Some(instance_id) = frame.init_instance {
+            self.heap.dec_ref(instance_id);
+        }
+        // A generator whose frame is discarded (return or uncaught exception) is finished
+        if let Some(gen_frame) = frame.generator {
+            finish_generator_frame(self.heap, gen_frame);
+        }
+        // The frame's instruction won't run again for its iterator steps
+        self.drop_iter_steps(self.frames.len() + 1);
     }
 
     /// Cleans up all frames for the current task before switching tasks.
@@ -1507,9 +1986,17 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
     /// Properly cleans up each frame's namespace and cell references.
     pub(super) fn cleanup_current_frames(&mut self) {
         for frame in self.frames.drain(..) {
-            // Clean up cell references
-            for cell_id in frame.cells {
-                self.heap.dec_ref(cell_id);
+            if let Some(gen_frame) = frame.generator {
+                // A generator frame's cells are owned by its namespace
+                finish_generator_frame(self.heap, gen_frame);
+            } else {
+                // Clean up cell references
+                for cell_id in frame.cells {
+                    self.heap.dec_ref(cell_id);
+                }
+            }
+            if let Some(instance_id) = frame.init_instance {
+                self.heap.dec_ref(instance_id);
             }
             // Clean up the namespace (but not the global namespace)
             if frame.namespace_idx != GLOBAL_NS_IDX {
@@ -1520,15 +2007,28 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
 
     /// Runs garbage collection with proper GC roots.
     ///
-    /// GC roots include values in namespaces, the operand stack, and exception stack.
+    /// GC roots include values in namespaces, the operand stack, exception stack,
+    /// instances being initialized by `__init__` frames, running generators and the
+    /// iterators of pending iterator steps.
     fn run_gc(&mut self) {
         // Collect roots from all reachable values
         let stack_roots = self.stack.iter().filter_map(Value::ref_id);
         let exc_roots = self.exception_stack.iter().filter_map(Value::ref_id);
         let ns_roots = self.namespaces.iter_heap_ids();
+        let frame_roots = self
+            .frames
+            .iter()
+            .flat_map(|frame| frame.init_instance.into_iter().chain(frame.generator.map(|g| g.id)));
 
         // Collect all roots into a vec to avoid lifetime issues
-        let roots: Vec<HeapId> = stack_roots.chain(exc_roots).chain(ns_roots).collect();
+        let mut roots: Vec<HeapId> = stack_roots
+            .chain(exc_roots)
+            .chain(ns_roots)
+            .chain(frame_roots)
+            .collect();
+        for step in &self.iter_steps {
+            step.collect_roots(&mut roots);
+        }
 
         self.heap.collect_garbage(roots);
     }
@@ -1620,11 +2120,22 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
     }
 
     /// Deletes a local variable (sets it to Undefined).
-    fn delete_local(&mut self, cached_frame: &CachedFrame<'a>, slot: u16) {
+    ///
+    /// Raises the same errors as `load_local` if the variable isn't bound.
+    fn delete_local(&mut self, cached_frame: &CachedFrame<'a>, slot: u16) -> RunResult<()> {
         let namespace = self.namespaces.get_mut(cached_frame.namespace_idx);
         let ns_slot = NamespaceId::new(slot as usize);
         let old_value = std::mem::replace(namespace.get_mut(ns_slot), Value::Undefined);
+        if matches!(old_value, Value::Undefined) {
+            let name = cached_frame.code.local_name(slot);
+            return Err(if cached_frame.code.is_assigned_local(slot) {
+                self.unbound_local_error(slot, name)
+            } else {
+                self.name_error_for_local(slot, name)
+            });
+        }
         old_value.drop_with_heap(self.heap);
+        Ok(())
     }
 
     /// Loads a global variable and pushes it onto the stack.
@@ -1657,6 +2168,21 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
         old_value.drop_with_heap(self.heap);
     }
 
+    /// Deletes a global variable.
+    ///
+    /// Returns a NameError if the variable is undefined.
+    fn delete_global(&mut self, slot: u16) -> RunResult<()> {
+        let namespace = self.namespaces.get_mut(GLOBAL_NS_IDX);
+        let ns_slot = NamespaceId::new(slot as usize);
+        let old_value = std::mem::replace(namespace.get_mut(ns_slot), Value::Undefined);
+        if matches!(old_value, Value::Undefined) {
+            let name = self.current_frame().code.local_name(slot);
+            return Err(self.name_error(slot, name));
+        }
+        old_value.drop_with_heap(self.heap);
+        Ok(())
+    }
+
     /// Loads from a closure cell and pushes onto the stack.
     ///
     /// Returns a NameError if the cell value is undefined (free variable not bound).
@@ -1667,16 +2193,25 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
 
         // Check for undefined value - raise NameError for unbound free variable
         if matches!(value, Value::Undefined) {
-            let name = self.current_frame().code.local_name(slot);
-            return Err(self.free_var_error(name));
+            return Err(self.free_var_error(slot));
         }
 
         self.push(value);
         Ok(())
     }
 
-    /// Creates a NameError for an unbound free variable.
-    fn free_var_error(&self, name: Option<StringId>) -> RunError {
+    /// Creates a NameError for the unbound free variable in cell `slot`.
+    ///
+    /// Cell variable names are registered under their namespace slot, which is the cell
+    /// index offset by the number of parameters.
+    fn free_var_error(&self, slot: u16) -> RunError {
+        let frame = self.current_frame();
+        let cell_base = frame
+            .function_id
+            .map_or(0, |func_id| self.interns.get_function(func_id).signature.param_count());
+        let name = u16::try_from(cell_base + usize::from(slot))
+            .ok()
+            .and_then(|slot| frame.code.local_name(slot));
         let name_str = match name {
             Some(id) => self.interns.get_str(id).to_string(),
             None => "<free var>".to_string(),
@@ -1690,6 +2225,20 @@ impl<'a, T: ResourceTracker, P: PrintWriter> VM<'a, T, P> {
         let cell_id = self.current_frame().cells[slot as usize];
         self.heap.set_cell_value(cell_id, value);
     }
+
+    /// Clears a closure cell.
+    ///
+    /// Returns a NameError if the cell value is already undefined.
+    fn delete_cell(&mut self, slot: u16) -> RunResult<()> {
+        let cell_id = self.current_frame().cells[slot as usize];
+        let value = self.heap.get_cell_value(cell_id);
+        if matches!(value, Value::Undefined) {
+            return Err(self.free_var_error(slot));
+        }
+        value.drop_with_heap(self.heap);
+        self.heap.set_cell_value(cell_id, Value::Undefined);
+        Ok(())
+    }
 }
 
 // `heap` is not a public field on VM, so this implementation needs to go here rather than in `heap.rs`
@@ -0,0 +1,470 @@
+//! Structural pattern matching helpers for the VM.
+//!
+//! The compiler lowers most of a `match` statement to ordinary opcodes (`Dup`, `CompareEq`,
+//! `BinarySubscr`, jumps); these helpers implement the few checks that have no Python-level
+//! equivalent: whether a subject can match a sequence or mapping pattern, looking up several
+//! dict keys at once, and the isinstance check and attribute extraction of class patterns.
+
+use super::VM;
+use crate::{
+    builtins::Builtins,
+    exception_private::{ExcType, RunError, RunResult},
+    heap::{DropWithHeap, HeapData, HeapId},
+    intern::StringId,
+    io::PrintWriter,
+    resource::ResourceTracker,
+    types::{
+        AttrCallResult, Dict, PyTrait, Type, allocate_tuple,
+        class::{class_name, is_subclass, lookup_class_attr},
+    },
+    value::Value,
+};
+
+impl<T: ResourceTracker, P: PrintWriter> VM<'_, T, P> {
+    /// Pushes whether TOS can match a sequence pattern.
+    ///
+    /// Like CPython, strings and bytes never match sequence patterns even though they
+    /// support indexing.
+    pub(super) fn match_sequence(&mut self) {
+        let ty = self.peek().py_type(self.heap);
+        let is_sequence = matches!(ty, Type::List | Type::Tuple | Type::NamedTuple | Type::Range);
+        self.push(Value::Bool(is_sequence));
+    }
+
+    /// Pushes whether TOS can match a mapping pattern.
+    pub(super) fn match_mapping(&mut self) {
+        let is_mapping = self.peek().py_type(self.heap).is_instance_of(Type::Dict);
+        self.push(Value::Bool(is_mapping));
+    }
+
+    /// Pushes the length of TOS, which has already been checked to be a sequence or dict.
+    pub(super) fn get_len(&mut self) -> RunResult<()> {
+        let len = self
+            .peek()
+            .py_len(self.heap, self.interns)
+            .ok_or_else(|| RunError::internal("GetLen: subject has no length"))?;
+        self.push(Value::Int(i64::try_from(len).expect("length exceeds i64")));
+        Ok(())
+    }
+
+    /// Looks up the keys tuple at TOS in the dict below it.
+    ///
+    /// Pushes a tuple of the values in key order, or None if any key is missing.
+    pub(super) fn match_keys(&mut self) -> RunResult<()> {
+        let keys = self.pop();
+        let key_values = self.tuple_items(&keys);
+        let result = match self.stack.last() {
+            Some(Value::Ref(dict_id)) => self.lookup_keys(*dict_id, &key_values),
+            _ => Err(RunError::internal("MatchKeys: subject is not a dict")),
+        };
+        key_values.drop_with_heap(self.heap);
+        self.push(keys);
+
+        let value = match result? {
+            Some(values) => allocate_tuple(values.into(), self.heap)?,
+            None => Value::None,
+        };
+        self.push(value);
+        Ok(())
+    }
+
+    /// Replaces the keys tuple at TOS with a copy of the dict below it without those keys.
+    ///
+    /// Used to bind `**rest` in mapping patterns.
+    pub(super) fn copy_dict_without_keys(&mut self) -> RunResult<()> {
+        let keys = self.pop();
+        let key_values = self.tuple_items(&keys);
+        keys.drop_with_heap(self.heap);
+
+        // Copy the subject's items first, then take references once the borrow has ended
+        let items: Vec<(Value, Value)> = match self.stack.last() {
+            Some(Value::Ref(id)) => match self.heap.get(*id).as_dict() {
+                Some(dict) => dict
+                    .iter()
+                    .map(|(k, v)| (Value::copy_for_extend(k), Value::copy_for_extend(v)))
+                    .collect(),
+                None => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+        for (key, value) in &items {
+            if let Value::Ref(id) = key {
+                self.heap.inc_ref(*id);
+            }
+            if let Value::Ref(id) = value {
+                self.heap.inc_ref(*id);
+            }
+        }
+
+        // Allocate the dict up front so that on error, dropping it frees whatever was copied
+        let rest_id = match self.heap.allocate(HeapData::Dict(Dict::with_capacity(items.len()))) {
+            Ok(id) => id,
+            Err(e) => {
+                for (key, value) in items {
+                    key.drop_with_heap(self.heap);
+                    value.drop_with_heap(self.heap);
+                }
+                key_values.drop_with_heap(self.heap);
+                return Err(e.into());
+            }
+        };
+        let interns = self.interns;
+        let result = self.heap.with_entry_mut(rest_id, |heap, data| -> RunResult<()> {
+            let HeapData::Dict(rest) = data else {
+                unreachable!("type changed during borrow");
+            };
+            let mut items = items.into_iter();
+            while let Some((key, value)) = items.next() {
+                if let Err(e) = rest.set(key, value, heap, interns) {
+                    for (key, value) in items {
+                        key.drop_with_heap(heap);
+                        value.drop_with_heap(heap);
+                    }
+                    return Err(e);
+                }
+            }
+            for key in &key_values {
+                if let Some((key, value)) = rest.pop(key, heap, interns)? {
+                    key.drop_with_heap(heap);
+                    value.drop_with_heap(heap);
+                }
+            }
+            Ok(())
+        });
+        key_values.drop_with_heap(self.heap);
+
+        if let Err(e) = result {
+            Value::Ref(rest_id).drop_with_heap(self.heap);
+            return Err(e);
+        }
+        self.push(Value::Ref(rest_id));
+        Ok(())
+    }
+
+    /// Matches a class pattern.
+    ///
+    /// Stack: `[subject, cls, kw_names]` -> `[attrs]`, where `attrs` is a tuple of the
+    /// values for the `positional_count` positional sub-patterns followed by the keyword
+    /// sub-patterns, or None if the subject doesn't match.
+    pub(super) fn match_class(&mut self, positional_count: usize) -> RunResult<()> {
+        let kw_names = self.pop();
+        let cls = self.pop();
+        let subject = self.pop();
+        let result = self.match_class_attrs(&subject, &cls, &kw_names, positional_count);
+        kw_names.drop_with_heap(self.heap);
+        cls.drop_with_heap(self.heap);
+        subject.drop_with_heap(self.heap);
+
+        let value = match result? {
+            Some(attrs) => allocate_tuple(attrs.into(), self.heap)?,
+            None => Value::None,
+        };
+        self.push(value);
+        Ok(())
+    }
+
+    /// Checks `subject` against the class of a class pattern and collects the attributes
+    /// for its sub-patterns.
+    fn match_class_attrs(
+        &mut self,
+        subject: &Value,
+        cls: &Value,
+        kw_names: &Value,
+        positional_count: usize,
+    ) -> RunResult<Option<Vec<Value>>> {
+        let subject_type = subject.py_type(self.heap);
+        let is_instance = match cls {
+            Value::Builtin(Builtins::Type(t)) => subject_type.is_instance_of(*t),
+            Value::Builtin(Builtins::ExcType(exc_type)) => {
+                matches!(subject_type, Type::Exception(e) if e.is_subclass_of(*exc_type))
+            }
+            Value::Ref(class_id) => {
+                let subject_data = match subject {
+                    Value::Ref(id) => Some(self.heap.get(*id)),
+                    _ => None,
+                };
+                match (self.heap.get(*class_id), subject_data) {
+                    (HeapData::ClassObject(_), Some(HeapData::Instance(instance))) => {
+                        is_subclass(instance.class_id(), *class_id, self.heap)
+                    }
+                    (HeapData::DataclassType(class), Some(HeapData::Dataclass(dc))) => class.is_class_of(dc),
+                    (HeapData::NamedTupleFactory(factory), Some(HeapData::NamedTuple(nt))) => factory.is_class_of(nt),
+                    (HeapData::ClassObject(_) | HeapData::DataclassType(_) | HeapData::NamedTupleFactory(_), _) => {
+                        false
+                    }
+                    _ => return Err(ExcType::type_error("called match pattern must be a class")),
+                }
+            }
+            _ => return Err(ExcType::type_error("called match pattern must be a class")),
+        };
+        if !is_instance {
+            return Ok(None);
+        }
+
+        let mut attrs = Vec::new();
+        if positional_count > 0 && !self.positional_match_attrs(subject, cls, positional_count, &mut attrs)? {
+            attrs.drop_with_heap(self.heap);
+            return Ok(None);
+        }
+
+        let kw_ids: Vec<StringId> = match kw_names {
+            Value::Ref(id) => match self.heap.get(*id) {
+                HeapData::Tuple(tuple) => tuple
+                    .as_slice()
+                    .iter()
+                    .filter_map(|name| match name {
+                        Value::InternString(name_id) => Some(*name_id),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+        for name_id in kw_ids {
+            match self.match_attr(subject, name_id) {
+                Ok(Some(value)) => attrs.push(value),
+                Ok(None) => {
+                    attrs.drop_with_heap(self.heap);
+                    return Ok(None);
+                }
+                Err(e) => {
+                    attrs.drop_with_heap(self.heap);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(Some(attrs))
+    }
+
+    /// Collects the attributes for the positional sub-patterns of a class pattern into `attrs`.
+    ///
+    /// Builtin types like `int(x)` match the subject itself, dataclasses and namedtuples use
+    /// their fields in definition order, and user-defined classes use `__match_args__`.
+    /// Returns `Ok(false)` if the subject is missing one of the attributes.
+    fn positional_match_attrs(
+        &mut self,
+        subject: &Value,
+        cls: &Value,
+        positional_count: usize,
+        attrs: &mut Vec<Value>,
+    ) -> RunResult<bool> {
+        match cls {
+            Value::Builtin(Builtins::Type(t)) if is_self_matching(*t) => {
+                check_positional_count(&t.to_string(), 1, positional_count)?;
+                attrs.push(subject.clone_with_heap(self.heap));
+                Ok(true)
+            }
+            Value::Builtin(Builtins::Type(Type::Dataclass | Type::NamedTuple)) => {
+                self.field_match_attrs(subject, positional_count, attrs)
+            }
+            Value::Ref(class_id)
+                if matches!(
+                    self.heap.get(*class_id),
+                    HeapData::DataclassType(_) | HeapData::NamedTupleFactory(_)
+                ) =>
+            {
+                self.field_match_attrs(subject, positional_count, attrs)
+            }
+            Value::Ref(class_id) => self.match_args_attrs(subject, *class_id, positional_count, attrs),
+            Value::Builtin(Builtins::Type(t)) => {
+                check_positional_count(&t.to_string(), 0, positional_count)?;
+                Ok(true)
+            }
+            Value::Builtin(Builtins::ExcType(exc_type)) => {
+                check_positional_count(&exc_type.to_string(), 0, positional_count)?;
+                Ok(true)
+            }
+            _ => Err(ExcType::type_error("called match pattern must be a class")),
+        }
+    }
+
+    /// Collects the first `positional_count` fields of a dataclass or namedtuple subject, in
+    /// definition order.
+    fn field_match_attrs(
+        &mut self,
+        subject: &Value,
+        positional_count: usize,
+        attrs: &mut Vec<Value>,
+    ) -> RunResult<bool> {
+        let Value::Ref(id) = subject else {
+            return Ok(false);
+        };
+        let fields: Vec<Option<Value>> = match self.heap.get(*id) {
+            HeapData::Dataclass(dc) => {
+                check_positional_count(dc.name(self.interns), dc.field_names().len(), positional_count)?;
+                dc.field_names()[..positional_count]
+                    .iter()
+                    .map(|name| {
+                        dc.attrs()
+                            .get_by_str(name, self.heap, self.interns)
+                            .map(Value::copy_for_extend)
+                    })
+                    .collect()
+            }
+            HeapData::NamedTuple(nt) => {
+                check_positional_count(nt.name(self.interns), nt.len(), positional_count)?;
+                nt.as_vec()[..positional_count]
+                    .iter()
+                    .map(|value| Some(value.copy_for_extend()))
+                    .collect()
+            }
+            _ => return Ok(false),
+        };
+        let mut found_all = true;
+        for field in fields {
+            match field {
+                Some(value) => {
+                    if let Value::Ref(id) = &value {
+                        self.heap.inc_ref(*id);
+                    }
+                    attrs.push(value);
+                }
+                None => found_all = false,
+            }
+        }
+        Ok(found_all)
+    }
+
+    /// Collects the attributes named by a user-defined class's `__match_args__`.
+    fn match_args_attrs(
+        &mut self,
+        subject: &Value,
+        class_id: HeapId,
+        positional_count: usize,
+        attrs: &mut Vec<Value>,
+    ) -> RunResult<bool> {
+        let class_name = class_name(class_id, self.heap, self.interns).to_string();
+        let Some(match_args) = lookup_class_attr(class_id, "__match_args__", self.heap, self.interns) else {
+            check_positional_count(&class_name, 0, positional_count)?;
+            return Ok(true);
+        };
+        let is_tuple = match &match_args {
+            Value::Ref(id) => matches!(self.heap.get(*id), HeapData::Tuple(_)),
+            _ => false,
+        };
+        if !is_tuple {
+            let ty = match_args.py_type(self.heap);
+            match_args.drop_with_heap(self.heap);
+            return Err(ExcType::type_error(format!(
+                "{class_name}.__match_args__ must be a tuple (got {ty})"
+            )));
+        }
+        let names = self.tuple_items(&match_args);
+        match_args.drop_with_heap(self.heap);
+        let result = self.match_args_values(subject, &class_name, &names, positional_count, attrs);
+        names.drop_with_heap(self.heap);
+        result
+    }
+
+    /// Looks up the first `positional_count` names of `__match_args__` on the subject.
+    fn match_args_values(
+        &mut self,
+        subject: &Value,
+        class_name: &str,
+        names: &[Value],
+        positional_count: usize,
+        attrs: &mut Vec<Value>,
+    ) -> RunResult<bool> {
+        check_positional_count(class_name, names.len(), positional_count)?;
+        for name in &names[..positional_count] {
+            let Value::InternString(name_id) = name else {
+                let ty = name.py_type(self.heap);
+                return Err(ExcType::type_error(format!(
+                    "__match_args__ elements must be strings (got {ty})"
+                )));
+            };
+            match self.match_attr(subject, *name_id)? {
+                Some(value) => attrs.push(value),
+                None => return Ok(false),
+            }
+        }
+        Ok(true)
+    }
+
+    /// Gets an attribute for a class pattern, returning `Ok(None)` if it doesn't exist.
+    fn match_attr(&mut self, subject: &Value, name_id: StringId) -> RunResult<Option<Value>> {
+        match subject.py_getattr(name_id, self.heap, self.interns) {
+            Ok(AttrCallResult::Value(value)) => Ok(Some(value)),
+            Ok(AttrCallResult::OsCall(_, args) | AttrCallResult::ExternalCall(_, args)) => {
+                args.drop_with_heap(self.heap);
+                Ok(None)
+            }
+            Err(RunError::Exc(exc)) if exc.exc.exc_type() == ExcType::AttributeError => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns owned copies of the items of a tuple.
+    fn tuple_items(&mut self, value: &Value) -> Vec<Value> {
+        let items: Vec<Value> = match value {
+            Value::Ref(id) => match self.heap.get(*id) {
+                HeapData::Tuple(tuple) => tuple.as_slice().iter().map(Value::copy_for_extend).collect(),
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+        for item in &items {
+            if let Value::Ref(id) = item {
+                self.heap.inc_ref(*id);
+            }
+        }
+        items
+    }
+
+    /// Looks up each key in the dict `dict_id`, returning the values or None if any is missing.
+    fn lookup_keys(&mut self, dict_id: HeapId, keys: &[Value]) -> RunResult<Option<Vec<Value>>> {
+        let interns = self.interns;
+        self.heap.with_entry_mut(dict_id, |heap, data| {
+            let Some(dict) = data.as_dict() else {
+                return Err(RunError::internal("MatchKeys: subject is not a dict"));
+            };
+            let mut values = Vec::with_capacity(keys.len());
+            for key in keys {
+                match dict.get(key, heap, interns) {
+                    Ok(Some(value)) => values.push(value.clone_with_heap(heap)),
+                    Ok(None) => {
+                        values.drop_with_heap(heap);
+                        return Ok(None);
+                    }
+                    Err(e) => {
+                        values.drop_with_heap(heap);
+                        return Err(e);
+                    }
+                }
+            }
+            Ok(Some(values))
+        })
+    }
+}
+
+/// Returns whether a builtin type matches its subject against a single positional
+/// sub-pattern, e.g. `case int(n):`.
+fn is_self_matching(ty: Type) -> bool {
+    matches!(
+        ty,
+        Type::Bool
+            | Type::ByteArray
+            | Type::Bytes
+            | Type::Dict
+            | Type::Float
+            | Type::FrozenSet
+            | Type::Int
+            | Type::List
+            | Type::Set
+            | Type::Str
+            | Type::Tuple
+    )
+}
+
+/// Raises a `TypeError` if a class pattern has more positional sub-patterns than the class accepts.
+fn check_positional_count(class_name: &str, accepted: usize, given: usize) -> RunResult<()> {
+    if given > accepted {
+        let plural = if accepted == 1 { "" } else { "s" };
+        Err(ExcType::type_error(format!(
+            "{class_name}() accepts {accepted} positional sub-pattern{plural} ({given} given)"
+        )))
+    } else {
+        Ok(())
+    }
+}
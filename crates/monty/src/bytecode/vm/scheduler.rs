@@ -14,6 +14,7 @@ use std::collections::VecDeque;
 
 use ahash::{AHashMap, AHashSet};
 
+use super::generator::{GeneratorFrame, finish_generator_frame};
 use crate::{
     args::ArgValues,
     asyncio::{CallId, TaskId},
@@ -100,6 +101,10 @@ pub(crate) struct SerializedTaskFrame {
     pub cells: Vec<HeapId>,
     /// Call site position (for tracebacks).
     pub call_position: Option<CodeRange>,
+    /// Instance being initialized by this frame's `__init__` call, if any.
+    pub init_instance: Option<HeapId>,
+    /// Generator this frame is running, if any.
+    pub generator: Option<GeneratorFrame>,
 }
 
 impl Task {
@@ -475,13 +480,15 @@ impl Scheduler {
         heap: &mut crate::heap::Heap<impl crate::resource::ResourceTracker>,
         namespaces: &mut Namespaces,
     ) {
-        // If task already finished, clean up its result value and return
+        // If task already finished, clean up its result value or error and return
         if self.get_task(task_id).is_finished() {
             let task = self.get_task_mut(task_id);
-            if let TaskState::Completed(value) = std::mem::replace(&mut task.state, TaskState::Ready) {
-                value.drop_with_heap(heap);
+            match std::mem::replace(&mut task.state, TaskState::Ready) {
+                TaskState::Completed(value) => value.drop_with_heap(heap),
+                // Errors hold references to the causes and contexts of their exceptions
+                TaskState::Failed(error) => error.drop_with_heap(heap),
+                _ => {}
             }
-            // Note: Failed tasks don't have values to clean up (RunError doesn't contain Values)
             return;
         }
 
@@ -545,8 +552,16 @@ impl Scheduler {
 
         // Clean up frame cell references and namespaces
         for frame in std::mem::take(&mut task.frames) {
-            for cell_id in frame.cells {
-                heap.dec_ref(cell_id);
+            if let Some(gen_frame) = frame.generator {
+                // A generator frame's cells are owned by its namespace
+                finish_generator_frame(heap, gen_frame);
+            } else {
+                for cell_id in frame.cells {
+                    heap.dec_ref(cell_id);
+                }
+            }
+            if let Some(instance_id) = frame.init_instance {
+                heap.dec_ref(instance_id);
             }
             // Clean up the namespace (but not the global namespace)
             if frame.namespace_idx != GLOBAL_NS_IDX {
@@ -614,9 +629,11 @@ impl Scheduler {
             for value in std::mem::take(&mut task.exception_stack) {
                 value.drop_with_heap(heap);
             }
-            // Drop completed task results
-            if let TaskState::Completed(value) = std::mem::replace(&mut task.state, TaskState::Ready) {
-                value.drop_with_heap(heap);
+            // Drop completed task results and failed task errors
+            match std::mem::replace(&mut task.state, TaskState::Ready) {
+                TaskState::Completed(value) => value.drop_with_heap(heap),
+                TaskState::Failed(error) => error.drop_with_heap(heap),
+                _ => {}
             }
         }
     }
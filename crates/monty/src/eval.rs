@@ -0,0 +1,160 @@
+//! Compilation of code passed to `eval()` and `exec()` while a program runs.
+//!
+//! The code goes through the same `parse`/`prepare`/`Compiler` pipeline as the program, and
+//! becomes a function appended to the program's `Interns`. It runs as a normal call on the
+//! same heap, under the same resource tracker, so it shares the program's limits.
+//!
+//! The function's parameters carry the names the code can see: the keys of the namespace dicts
+//! passed to `eval()`/`exec()`, or the caller's locals. A hidden first parameter holds the dict
+//! that `exec()` copies the names it binds back into. Compiled code is cached by [`EvalKey`],
+//! so evaluating the same code in the same kind of scope again doesn't compile it again.
+//!
+//! The same pause interns attribute names built at runtime and passed to `setattr()`, see
+//! [`CompileRequest`].
+
+use std::iter;
+
+use ahash::AHashMap;
+
+use crate::{
+    bytecode::Compiler,
+    exception_private::{ExcType, RunError, SimpleException},
+    expressions::{Identifier, Node},
+    intern::{FunctionId, INTERN_STRING_ID_OFFSET, InternerBuilder, Interns, StaticStrings, StringId},
+    namespace::NamespaceId,
+    parse::{CodeRange, ParseResult, parse_eval},
+    prepare::prepare_eval,
+};
+
+/// Name of the hidden first parameter of eval/exec code, holding the dict names are exported to.
+///
+/// Not a valid identifier, so it can't clash with names in the code.
+const NAMESPACE_PARAM: &str = ".namespace";
+
+/// Maximum number of different pieces of code `eval()`/`exec()` can compile in one run.
+///
+/// Compiled code is kept for the rest of the run, so without a limit a loop evaluating a
+/// different string each time would keep growing the program.
+const MAX_EVAL_CODES: usize = 10_000;
+
+/// What the code passed to `eval()`/`exec()` must be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub(crate) enum EvalMode {
+    /// A single expression, whose value is returned.
+    Eval,
+    /// Any statements; `None` is returned.
+    Exec,
+}
+
+/// How names in eval/exec code resolve, decided by the caller and the dicts passed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub(crate) enum EvalScope {
+    /// Called from module-level code without dicts.
+    ///
+    /// The code reads and assigns the module's globals directly.
+    Module,
+    /// Called from a function, or given only a locals dict.
+    ///
+    /// The parameters are the caller's locals or the dict's keys, other names read the
+    /// module's globals.
+    Local,
+    /// Given a globals dict: only names in the dicts are visible.
+    Isolated,
+}
+
+/// Identifies compiled eval/exec code, so the same code run in the same kind of scope is
+/// compiled once.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub(crate) struct EvalKey {
+    pub source: String,
+    pub filename: String,
+    pub mode: EvalMode,
+    pub scope: EvalScope,
+    /// Names passed as parameters after the hidden one, in order.
+    pub names: Vec<String>,
+}
+
+/// What the VM paused for with `FrameExit::Compile`, since it holds the interns immutably.
+#[derive(Debug)]
+pub(crate) enum CompileRequest {
+    /// Code passed to `eval()`/`exec()`, to compile into a function.
+    Eval(EvalKey),
+    /// An attribute name passed to `setattr()` that isn't interned yet.
+    Name(String),
+}
+
+/// What was added to the interns for a [`CompileRequest`], passed to `VM::resume_compile`.
+#[derive(Debug)]
+pub(crate) enum Compiled {
+    /// The function running the code passed to `eval()`/`exec()`.
+    Eval(FunctionId),
+    /// The attribute name is now interned.
+    Name,
+}
+
+/// Compiles the code described by `key` into `interns`, returning the ID of the function running it.
+///
+/// `module_names` is the module's name map, used to resolve globals unless the code was given
+/// its own globals dict. Syntax and compile errors are returned as exceptions to raise at the
+/// `eval()`/`exec()` call.
+pub(crate) fn compile_eval(
+    key: EvalKey,
+    interns: &mut Interns,
+    module_names: &AHashMap<String, NamespaceId>,
+    allowed_modules: Option<&[String]>,
+) -> Result<FunctionId, RunError> {
+    if interns.eval_function_count() >= MAX_EVAL_CODES {
+        return Err(SimpleException::new_msg(ExcType::RuntimeError, "too much code compiled by eval()/exec()").into());
+    }
+    let interner = InternerBuilder::from_interns(interns);
+    let ParseResult {
+        mut nodes,
+        mut interner,
+    } = parse_eval(&key.source, &key.filename, key.mode, interner)
+        .map_err(|e| e.into_python_exc(&key.filename, &key.source))?;
+
+    let namespace_param = interner.intern(NAMESPACE_PARAM);
+    let params: Vec<StringId> = iter::once(namespace_param)
+        .chain(key.names.iter().map(|name| interner.intern(name)))
+        .collect();
+    let global_name_map = match key.scope {
+        EvalScope::Module => {
+            // Assignments write the module's globals, as if every global was declared `global`
+            let names = module_names.keys().map(|name| interner.intern(name)).collect();
+            nodes.insert(
+                0,
+                Node::Global {
+                    position: CodeRange::default(),
+                    names,
+                },
+            );
+            module_names.clone()
+        }
+        EvalScope::Local => module_names.clone(),
+        EvalScope::Isolated => AHashMap::new(),
+    };
+
+    let name = Identifier::new(StaticStrings::Module.into(), CodeRange::default());
+    let (func_def, bound) = prepare_eval(name, params, nodes, global_name_map, allowed_modules, &interner)
+        .map_err(|e| e.into_python_exc(&key.filename, &key.source))?;
+
+    // Names bound at module scope are already globals, everything else is exported to the dict
+    let exports: Vec<(NamespaceId, StringId)> = match (key.mode, key.scope) {
+        (EvalMode::Exec, EvalScope::Local | EvalScope::Isolated) => bound
+            .into_iter()
+            .filter(|(name, _)| name != NAMESPACE_PARAM)
+            .map(|(name, slot)| (slot, interner.intern(&name)))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    // Names are stored as u16 operands in bytecode
+    if interner.string_count() + INTERN_STRING_ID_OFFSET > usize::from(u16::MAX) {
+        return Err(SimpleException::new_msg(ExcType::RuntimeError, "too much code compiled by eval()/exec()").into());
+    }
+
+    interns.extend_from_interner(interner);
+    let functions = Compiler::compile_eval(&func_def, key.mode, &exports, interns, interns.function_count())
+        .map_err(|e| e.into_python_exc(&key.filename, &key.source))?;
+    Ok(interns.add_eval_functions(key, functions))
+}
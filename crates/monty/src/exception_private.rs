@@ -9,18 +9,19 @@ use strum::{Display, EnumString, IntoStaticStr};
 
 use crate::{
     args::ArgValues,
+    builtins::Builtins,
     defer_drop,
     exception_public::{MontyException, StackFrame},
     fstring::FormatError,
-    heap::{Heap, HeapData},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
     intern::{Interns, StaticStrings, StringId},
     parse::CodeRange,
     resource::{DepthGuard, ResourceTracker},
     types::{
-        AttrCallResult, PyTrait, Str, Type, allocate_tuple,
+        AttrCallResult, List, PyTrait, Str, Type, allocate_tuple,
         str::{StringRepr, string_repr_fmt},
     },
-    value::Value,
+    value::{EitherStr, Value},
 };
 
 /// Result type alias for operations that can produce a runtime error.
@@ -39,6 +40,14 @@ pub enum ExcType {
     BaseException,
     SystemExit,
     KeyboardInterrupt,
+    /// Raised inside a generator when it is closed.
+    GeneratorExit,
+
+    // --- Exception groups ---
+    /// Group of unrelated exceptions raised together, e.g. by concurrent tasks.
+    BaseExceptionGroup,
+    /// Subclass of BaseExceptionGroup and Exception - a group of `Exception` subclasses only.
+    ExceptionGroup,
 
     // --- ArithmeticError hierarchy ---
     /// Intermediate class for arithmetic errors.
@@ -78,6 +87,8 @@ pub enum ExcType {
     ValueError,
     /// Subclass of ValueError - for encoding/decoding errors.
     UnicodeDecodeError,
+    /// Subclass of ValueError (from the json module) - for invalid JSON documents.
+    JSONDecodeError,
 
     // --- ImportError hierarchy ---
     /// Import-related errors (module not found, name not in module).
@@ -101,9 +112,12 @@ pub enum ExcType {
     AssertionError,
     MemoryError,
     StopIteration,
+    StopAsyncIteration,
     SyntaxError,
     TimeoutError,
     TypeError,
+    /// Raised by the re module for invalid patterns, also available as `re.error`.
+    PatternError,
 }
 
 impl ExcType {
@@ -124,8 +138,18 @@ impl ExcType {
         match handler_type {
             // BaseException catches all exceptions
             Self::BaseException => true,
-            // Exception catches everything except BaseException, and direct subclasses: KeyboardInterrupt, SystemExit
-            Self::Exception => !matches!(self, Self::BaseException | Self::KeyboardInterrupt | Self::SystemExit),
+            // Exception catches everything except BaseException, and direct subclasses: KeyboardInterrupt,
+            // SystemExit, GeneratorExit, BaseExceptionGroup
+            Self::Exception => !matches!(
+                self,
+                Self::BaseException
+                    | Self::KeyboardInterrupt
+                    | Self::SystemExit
+                    | Self::GeneratorExit
+                    | Self::BaseExceptionGroup
+            ),
+            // BaseExceptionGroup catches ExceptionGroup
+            Self::BaseExceptionGroup => matches!(self, Self::ExceptionGroup),
             // LookupError catches KeyError and IndexError
             Self::LookupError => matches!(self, Self::KeyError | Self::IndexError),
             // ArithmeticError catches ZeroDivisionError and OverflowError
@@ -136,8 +160,8 @@ impl ExcType {
             Self::AttributeError => matches!(self, Self::FrozenInstanceError),
             // NameError catches UnboundLocalError
             Self::NameError => matches!(self, Self::UnboundLocalError),
-            // ValueError catches UnicodeDecodeError
-            Self::ValueError => matches!(self, Self::UnicodeDecodeError),
+            // ValueError catches UnicodeDecodeError and JSONDecodeError
+            Self::ValueError => matches!(self, Self::UnicodeDecodeError | Self::JSONDecodeError),
             // ImportError catches ModuleNotFoundError
             Self::ImportError => matches!(self, Self::ModuleNotFoundError),
             // OSError catches FileNotFoundError, FileExistsError, IsADirectoryError, NotADirectoryError
@@ -150,10 +174,17 @@ impl ExcType {
         }
     }
 
+    /// Whether this is `BaseExceptionGroup` or `ExceptionGroup`.
+    #[must_use]
+    pub fn is_group(self) -> bool {
+        matches!(self, Self::BaseExceptionGroup | Self::ExceptionGroup)
+    }
+
     /// Creates an exception instance from an exception type and arguments.
     ///
     /// Handles exception constructors like `ValueError('message')`.
-    /// Currently supports zero or one string argument.
+    /// Currently supports zero or one string argument, except for exception groups
+    /// which take a message and a sequence of exceptions.
     ///
     /// The `interns` parameter provides access to interned string content.
     /// Returns a heap-allocated exception value.
@@ -163,6 +194,9 @@ impl ExcType {
         args: ArgValues,
         interns: &Interns,
     ) -> RunResult<Value> {
+        if self.is_group() {
+            return self.call_group(heap, args, interns);
+        }
         defer_drop!(args, heap);
         let exc = match args {
             ArgValues::Empty => Ok(SimpleException::new_none(self)),
@@ -191,6 +225,66 @@ impl ExcType {
         Ok(Value::Ref(heap_id))
     }
 
+    /// Creates an exception group from `ExceptionGroup(message, exceptions)`.
+    ///
+    /// Like CPython, `BaseExceptionGroup` creates an `ExceptionGroup` when all the exceptions
+    /// are `Exception` subclasses, and `ExceptionGroup` refuses to nest `BaseException`s.
+    fn call_group(self, heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let ArgValues::Two(message, exceptions) = args else {
+            let count = args.count();
+            args.drop_with_heap(heap);
+            return Err(Self::type_error(format!(
+                "BaseExceptionGroup.__new__() takes exactly 2 arguments ({count} given)"
+            )));
+        };
+        defer_drop!(message, heap);
+        defer_drop!(exceptions, heap);
+
+        let Some(message) = message.as_either_str(heap) else {
+            return Err(Self::type_error(format!(
+                "BaseExceptionGroup.__new__() argument 1 must be str, not {}",
+                message.py_type(heap)
+            )));
+        };
+        let items = match exceptions {
+            Value::Ref(id) => match heap.get(*id) {
+                HeapData::List(list) => list.as_slice(),
+                HeapData::Tuple(tuple) => tuple.as_slice(),
+                _ => return Err(Self::type_error("second argument (exceptions) must be a sequence")),
+            },
+            _ => return Err(Self::type_error("second argument (exceptions) must be a sequence")),
+        };
+        if items.is_empty() {
+            return Err(SimpleException::new_msg(
+                Self::ValueError,
+                "second argument (exceptions) must be a non-empty sequence",
+            )
+            .into());
+        }
+        let mut sub_exceptions = Vec::with_capacity(items.len());
+        for (index, item) in items.iter().enumerate() {
+            if let Value::Ref(id) = item
+                && let HeapData::Exception(exc) = heap.get(*id)
+            {
+                sub_exceptions.push(exc.clone());
+            } else {
+                return Err(SimpleException::new_msg(
+                    Self::ValueError,
+                    format!("Item {index} of second argument (exceptions) is not an exception"),
+                )
+                .into());
+            }
+        }
+
+        let group_type = SimpleException::group_type(&sub_exceptions);
+        if self == Self::ExceptionGroup && group_type == Self::BaseExceptionGroup {
+            return Err(Self::type_error("Cannot nest BaseExceptions in an ExceptionGroup"));
+        }
+        let exc = SimpleException::new_msg(group_type, message.as_str(interns)).with_exceptions(sub_exceptions);
+        exc.inc_chain_refs(heap);
+        Ok(Value::Ref(heap.allocate(HeapData::Exception(exc))?))
+    }
+
     /// Creates an AttributeError for when an attribute is not found (GET operation).
     ///
     /// Sets `hide_caret: true` because CPython doesn't show carets for attribute GET errors.
@@ -232,6 +326,23 @@ impl ExcType {
         .into()
     }
 
+    /// Creates an AttributeError for a missing attribute on a user-defined class.
+    ///
+    /// Matches CPython's format: `AttributeError: type object 'Foo' has no attribute 'attr'`
+    /// Sets `hide_caret: true` because CPython doesn't show carets for attribute GET errors.
+    #[must_use]
+    pub(crate) fn attribute_error_type_object(class_name: &str, attr_name: &str) -> RunError {
+        let exc = SimpleException::new_msg(
+            Self::AttributeError,
+            format!("type object '{class_name}' has no attribute '{attr_name}'"),
+        );
+        RunError::Exc(ExceptionRaise {
+            exc,
+            frame: None,
+            hide_caret: true,
+        })
+    }
+
     /// Creates an AttributeError for a missing module attribute.
     ///
     /// Matches CPython's format: `AttributeError: module 'name' has no attribute 'attr'`
@@ -262,6 +373,14 @@ impl ExcType {
         .into()
     }
 
+    /// Creates a FrozenInstanceError for deleting a field of a frozen dataclass.
+    ///
+    /// Message format: "cannot delete field 'attr_name'"
+    #[must_use]
+    pub(crate) fn frozen_instance_delete_error(attr_name: &str) -> RunError {
+        SimpleException::new_msg(Self::FrozenInstanceError, format!("cannot delete field '{attr_name}'")).into()
+    }
+
     #[must_use]
     pub(crate) fn type_error_not_sub(type_: Type) -> RunError {
         SimpleException::new_msg(Self::TypeError, format!("'{type_}' object is not subscriptable")).into()
@@ -287,6 +406,18 @@ impl ExcType {
         .into()
     }
 
+    /// Creates a TypeError for item deletion on types that don't support it.
+    ///
+    /// Matches CPython's format: `TypeError: '{type}' object doesn't support item deletion`
+    #[must_use]
+    pub(crate) fn type_error_not_sub_deletion(type_: Type) -> RunError {
+        SimpleException::new_msg(
+            Self::TypeError,
+            format!("'{type_}' object doesn't support item deletion"),
+        )
+        .into()
+    }
+
     /// Creates a TypeError for unhashable types when calling `hash()`.
     ///
     /// This matches Python 3.14's error message: `TypeError: unhashable type: 'list'`
@@ -561,6 +692,14 @@ impl ExcType {
         .into()
     }
 
+    /// Creates a TypeError for `**` unpacking of a non-mapping in a dict display.
+    ///
+    /// Matches CPython's format: `'{type}' object is not a mapping`
+    #[must_use]
+    pub(crate) fn type_error_not_mapping(type_: Type) -> RunError {
+        SimpleException::new_msg(Self::TypeError, format!("'{type_}' object is not a mapping")).into()
+    }
+
     /// Creates a TypeError for **kwargs with non-string keys.
     ///
     /// Matches CPython's format: `{name}() keywords must be strings`
@@ -575,12 +714,22 @@ impl ExcType {
         SimpleException::new_msg(Self::TypeError, msg).into()
     }
 
-    /// Creates a TypeError for bytes() constructor with invalid type.
+    /// Creates a TypeError for a bytes() or bytearray() constructor with an invalid type.
     ///
     /// Matches CPython's format: `TypeError: cannot convert '{type}' object to bytes`
     #[must_use]
-    pub(crate) fn type_error_bytes_init(type_: Type) -> RunError {
-        SimpleException::new_msg(Self::TypeError, format!("cannot convert '{type_}' object to bytes")).into()
+    pub(crate) fn type_error_bytes_init(target: Type, type_: Type) -> RunError {
+        SimpleException::new_msg(Self::TypeError, format!("cannot convert '{type_}' object to {target}")).into()
+    }
+
+    /// Creates a ValueError for an int that doesn't fit in a byte.
+    ///
+    /// Matches CPython's format: `ValueError: bytes must be in range(0, 256)` for `bytes()`,
+    /// and `ValueError: byte must be in range(0, 256)` for `bytearray` operations.
+    #[must_use]
+    pub(crate) fn value_error_byte_range(target: Type) -> RunError {
+        let noun = if target == Type::Bytes { "bytes" } else { "byte" };
+        SimpleException::new_msg(Self::ValueError, format!("{noun} must be in range(0, 256)")).into()
     }
 
     /// Creates a TypeError for calling a non-callable type.
@@ -671,6 +820,27 @@ impl ExcType {
         SimpleException::new_msg(Self::ValueError, "slice step cannot be zero").into()
     }
 
+    /// Creates a TypeError for assigning a non-iterable to a slice.
+    ///
+    /// Matches CPython's format: `TypeError: must assign iterable to extended slice`
+    #[must_use]
+    pub(crate) fn type_error_slice_assign_not_iterable() -> RunError {
+        SimpleException::new_msg(Self::TypeError, "must assign iterable to extended slice").into()
+    }
+
+    /// Creates a ValueError for an extended slice assignment with the wrong number of items.
+    ///
+    /// Matches CPython's format:
+    /// `ValueError: attempt to assign sequence of size {got} to extended slice of size {expected}`
+    #[must_use]
+    pub(crate) fn value_error_extended_slice_size(got: usize, expected: usize) -> RunError {
+        SimpleException::new_msg(
+            Self::ValueError,
+            format!("attempt to assign sequence of size {got} to extended slice of size {expected}"),
+        )
+        .into()
+    }
+
     /// Creates a TypeError for slice indices that are not integers or None.
     ///
     /// Matches CPython's format: `TypeError: slice indices must be integers or None or have an __index__ method`
@@ -699,6 +869,14 @@ impl ExcType {
         SimpleException::new_msg(Self::RuntimeError, "Set changed size during iteration").into()
     }
 
+    /// Creates a RuntimeError for deque mutation during iteration.
+    ///
+    /// Matches CPython's format: `RuntimeError: deque mutated during iteration`
+    #[must_use]
+    pub(crate) fn runtime_error_deque_mutated() -> RunError {
+        SimpleException::new_msg(Self::RuntimeError, "deque mutated during iteration").into()
+    }
+
     /// Creates a TypeError for functions that don't accept keyword arguments.
     ///
     /// Matches CPython's format: `TypeError: {name}() takes no keyword arguments`
@@ -747,6 +925,14 @@ impl ExcType {
         SimpleException::new_msg(Self::IndexError, "index out of range").into()
     }
 
+    /// Creates an IndexError for bytearray index out of range.
+    ///
+    /// Matches CPython's format: `IndexError('bytearray index out of range')`
+    #[must_use]
+    pub(crate) fn bytearray_index_error() -> RunError {
+        SimpleException::new_msg(Self::IndexError, "bytearray index out of range").into()
+    }
+
     /// Creates an IndexError for range index out of range.
     ///
     /// Matches CPython's format: `IndexError('range object index out of range')`
@@ -1017,6 +1203,14 @@ impl ExcType {
         SimpleException::new_none(Self::StopIteration).into()
     }
 
+    /// Creates a StopAsyncIteration exception for when an async iterator is exhausted.
+    ///
+    /// Matches CPython's format: `StopAsyncIteration`
+    #[must_use]
+    pub(crate) fn stop_async_iteration() -> RunError {
+        SimpleException::new_none(Self::StopAsyncIteration).into()
+    }
+
     /// Creates a ValueError for list.index() when item is not found.
     ///
     /// Matches CPython's format: `ValueError: list.index(x): x not in list`
@@ -1110,6 +1304,31 @@ impl ExcType {
 pub(crate) struct SimpleException {
     exc_type: ExcType,
     arg: Option<String>,
+    /// Traceback and chained exceptions, only set once the exception has been raised or chained.
+    #[serde(default)]
+    chain: Option<Box<ExceptionChain>>,
+    /// Sub-exceptions of a `BaseExceptionGroup`/`ExceptionGroup`, empty for other exceptions.
+    #[serde(default)]
+    exceptions: Vec<Self>,
+}
+
+/// Exception chaining state of a [`SimpleException`].
+///
+/// Boxed on the exception since most exceptions are never chained or caught. The cause and
+/// context are heap exceptions, so `__cause__` and `__context__` return the same object each
+/// time. An exception holds a reference to each of them, which is released when it's dropped
+/// with [`DropWithHeap`] or freed from the heap.
+#[derive(Debug, Clone, Default, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ExceptionChain {
+    /// Traceback recorded when the exception was caught, shown when it's later displayed
+    /// as the cause or context of another exception.
+    traceback: Option<RawStackFrame>,
+    /// Explicit cause from `raise ... from cause` (`__cause__`).
+    cause: Option<HeapId>,
+    /// The exception being handled when this one was raised (`__context__`).
+    context: Option<HeapId>,
+    /// Set by `raise ... from ...` to hide the context when displaying (`__suppress_context__`).
+    suppress_context: bool,
 }
 
 impl fmt::Display for SimpleException {
@@ -1119,9 +1338,12 @@ impl fmt::Display for SimpleException {
 }
 impl From<MontyException> for SimpleException {
     fn from(exc: MontyException) -> Self {
+        let (exc_type, arg, exceptions) = exc.into_parts();
         Self {
-            exc_type: exc.exc_type(),
-            arg: exc.into_message(),
+            exc_type,
+            arg,
+            chain: None,
+            exceptions: exceptions.into_iter().map(Self::from).collect(),
         }
     }
 }
@@ -1130,7 +1352,12 @@ impl SimpleException {
     /// Creates a new exception with the given type and optional argument message.
     #[must_use]
     pub fn new(exc_type: ExcType, arg: Option<String>) -> Self {
-        Self { exc_type, arg }
+        Self {
+            exc_type,
+            arg,
+            chain: None,
+            exceptions: Vec::new(),
+        }
     }
 
     /// Creates a new exception with the given type and argument message.
@@ -1139,13 +1366,41 @@ impl SimpleException {
         Self {
             exc_type,
             arg: Some(arg.to_string()),
+            chain: None,
+            exceptions: Vec::new(),
         }
     }
 
     /// Creates a new exception with the given type and no argument message.
     #[must_use]
     pub fn new_none(exc_type: ExcType) -> Self {
-        Self { exc_type, arg: None }
+        Self {
+            exc_type,
+            arg: None,
+            chain: None,
+            exceptions: Vec::new(),
+        }
+    }
+
+    /// Sets the sub-exceptions of an exception group.
+    #[must_use]
+    pub(crate) fn with_exceptions(mut self, exceptions: Vec<Self>) -> Self {
+        self.exceptions = exceptions;
+        self
+    }
+
+    /// The group type for `exceptions`: `ExceptionGroup` if they're all `Exception` subclasses,
+    /// `BaseExceptionGroup` otherwise.
+    #[must_use]
+    pub(crate) fn group_type(exceptions: &[Self]) -> ExcType {
+        if exceptions
+            .iter()
+            .all(|exc| exc.exc_type.is_subclass_of(ExcType::Exception))
+        {
+            ExcType::ExceptionGroup
+        } else {
+            ExcType::BaseExceptionGroup
+        }
     }
 
     #[must_use]
@@ -1158,12 +1413,259 @@ impl SimpleException {
         self.arg.as_ref()
     }
 
+    /// The sub-exceptions of an exception group, empty for other exceptions.
+    #[must_use]
+    pub fn exceptions(&self) -> &[Self] {
+        &self.exceptions
+    }
+
+    /// Splits an exception group into the parts that match and don't match `matches`,
+    /// like `BaseExceptionGroup.split()`.
+    ///
+    /// Nested groups keep their structure, message and chaining, with empty groups left out.
+    /// An exception that isn't a group is either wholly matching or not.
+    /// The parts don't own references to their chained exceptions yet, see [`Self::inc_chain_refs`].
+    pub(crate) fn split(&self, matches: &impl Fn(ExcType) -> bool) -> (Option<Self>, Option<Self>) {
+        if matches(self.exc_type) {
+            return (Some(self.clone()), None);
+        }
+        if !self.exc_type.is_group() {
+            return (None, Some(self.clone()));
+        }
+        let mut matched = Vec::new();
+        let mut rest = Vec::new();
+        for exc in &self.exceptions {
+            let (exc_match, exc_rest) = exc.split(matches);
+            matched.extend(exc_match);
+            rest.extend(exc_rest);
+        }
+        (self.derive(matched), self.derive(rest))
+    }
+
+    /// Creates a copy of this group with other sub-exceptions, or `None` if there are none.
+    fn derive(&self, exceptions: Vec<Self>) -> Option<Self> {
+        if exceptions.is_empty() {
+            return None;
+        }
+        Some(Self {
+            exc_type: Self::group_type(&exceptions),
+            arg: self.arg.clone(),
+            chain: self.chain.clone(),
+            exceptions,
+        })
+    }
+
+    /// Wraps an exception that isn't a group in a new group without a message, as `except*`
+    /// does when it matches one.
+    #[must_use]
+    pub(crate) fn wrap_in_group(self) -> Self {
+        let exceptions = vec![self];
+        Self::new_msg(Self::group_type(&exceptions), "").with_exceptions(exceptions)
+    }
+
+    /// Works out what to raise at the end of the `except*` clauses handling `orig`, like
+    /// CPython's `_PyExc_PrepReraiseStar`.
+    ///
+    /// `raised` holds the exceptions raised by the clause bodies followed by the unhandled rest.
+    /// Those that are parts of `orig` (the rest, and matches re-raised with bare `raise`) are
+    /// merged back into one group with the structure of `orig`. That is raised along with the
+    /// new exceptions, in a new `ExceptionGroup` if there are several.
+    pub(crate) fn reraise_star(orig: &Self, raised: Vec<Self>) -> Option<Self> {
+        if !orig.exc_type.is_group() {
+            // A naked exception was wrapped, so only one clause can have run
+            return raised.into_iter().next();
+        }
+        let mut new_raised = Vec::new();
+        let mut reraised = Vec::new();
+        for exc in &raised {
+            if exc.chain == orig.chain {
+                reraised.extend(exc.leaves());
+            } else {
+                new_raised.push(exc.clone());
+            }
+        }
+        new_raised.extend(orig.project(&reraised));
+        match new_raised.len() {
+            0 => None,
+            1 => new_raised.pop(),
+            _ => Some(Self::new_msg(Self::group_type(&new_raised), "").with_exceptions(new_raised)),
+        }
+    }
+
+    /// Returns the parts of this group whose leaf exceptions are in `leaves`, keeping its structure.
+    ///
+    /// Used by `except*` to re-raise the unhandled and re-raised parts of the original group
+    /// as one group, rather than as separate groups.
+    fn project(&self, leaves: &[&Self]) -> Option<Self> {
+        if !self.exc_type.is_group() {
+            return leaves.contains(&self).then(|| self.clone());
+        }
+        let exceptions = self.exceptions.iter().filter_map(|exc| exc.project(leaves)).collect();
+        self.derive(exceptions)
+    }
+
+    /// The leaf (non-group) exceptions of this exception, which is just itself if it's not a group.
+    fn leaves(&self) -> Vec<&Self> {
+        if self.exc_type.is_group() {
+            self.exceptions.iter().flat_map(Self::leaves).collect()
+        } else {
+            vec![self]
+        }
+    }
+
+    /// The explicit cause set by `raise ... from cause` (`__cause__`).
+    #[must_use]
+    pub fn cause(&self) -> Option<HeapId> {
+        self.chain.as_ref()?.cause
+    }
+
+    /// The exception being handled when this one was raised (`__context__`).
+    #[must_use]
+    pub fn context(&self) -> Option<HeapId> {
+        self.chain.as_ref()?.context
+    }
+
+    /// Whether the context is hidden when displaying this exception (`__suppress_context__`).
+    #[must_use]
+    pub fn suppress_context(&self) -> bool {
+        self.chain.as_ref().is_some_and(|chain| chain.suppress_context)
+    }
+
+    /// The traceback recorded when this exception was last caught.
+    #[must_use]
+    pub(crate) fn traceback(&self) -> Option<&RawStackFrame> {
+        self.chain.as_ref()?.traceback.as_ref()
+    }
+
+    /// Sets the cause from `raise ... from cause`, which also suppresses the context.
+    ///
+    /// `None` is `raise ... from None`. Takes over the caller's reference to the cause, and
+    /// releases the reference to any previous cause.
+    pub(crate) fn set_cause(&mut self, cause: Option<HeapId>, heap: &mut Heap<impl ResourceTracker>) {
+        let chain = self.chain_mut();
+        let old_cause = std::mem::replace(&mut chain.cause, cause);
+        chain.suppress_context = true;
+        if let Some(old_cause) = old_cause {
+            heap.dec_ref(old_cause);
+        }
+    }
+
+    /// Sets the exception that was being handled when this one was raised.
+    ///
+    /// Takes over the caller's reference to the context. Only called for exceptions that
+    /// don't have a context yet.
+    pub(crate) fn set_context(&mut self, context: HeapId) {
+        self.chain_mut().context = Some(context);
+    }
+
+    /// Records the traceback of a caught exception, so it can be shown if the exception is chained.
+    pub(crate) fn set_traceback(&mut self, traceback: Option<RawStackFrame>) {
+        if traceback.is_some() || self.chain.is_some() {
+            self.chain_mut().traceback = traceback;
+        }
+    }
+
+    fn chain_mut(&mut self) -> &mut ExceptionChain {
+        self.chain.get_or_insert_with(Box::default)
+    }
+
+    /// Collects the ids of the exceptions chained to this one and to its sub-exceptions,
+    /// each of which this exception holds a reference to.
+    pub(crate) fn collect_chain_ids(&self, ids: &mut Vec<HeapId>) {
+        if let Some(chain) = &self.chain {
+            ids.extend(chain.cause);
+            ids.extend(chain.context);
+        }
+        for exc in &self.exceptions {
+            exc.collect_chain_ids(ids);
+        }
+    }
+
+    /// Whether this exception or one of its sub-exceptions has a cause or context.
+    #[must_use]
+    pub(crate) fn has_refs(&self) -> bool {
+        self.chain
+            .as_ref()
+            .is_some_and(|chain| chain.cause.is_some() || chain.context.is_some())
+            || self.exceptions.iter().any(Self::has_refs)
+    }
+
+    /// Takes another reference to each chained exception, for a copy of an exception made
+    /// with `clone()`.
+    pub(crate) fn inc_chain_refs(&self, heap: &mut Heap<impl ResourceTracker>) {
+        let mut ids = Vec::new();
+        self.collect_chain_ids(&mut ids);
+        for id in ids {
+            heap.inc_ref(id);
+        }
+    }
+
+    /// Estimates the memory used by this exception, including the sub-exceptions of groups.
+    ///
+    /// Chained exceptions are separate heap objects and aren't included.
+    pub(crate) fn estimate_size(&self) -> usize {
+        let chain_size = if self.chain.is_some() {
+            std::mem::size_of::<ExceptionChain>()
+        } else {
+            0
+        };
+        let exceptions_size: usize = self.exceptions.iter().map(Self::estimate_size).sum();
+        std::mem::size_of::<Self>() + self.arg.as_ref().map_or(0, String::len) + chain_size + exceptions_size
+    }
+
+    /// Converts this exception to a `MontyException`, with `frame` as the innermost frame
+    /// of its traceback.
+    ///
+    /// Chained exceptions are looked up on the heap. They and the sub-exceptions of groups
+    /// use the traceback recorded when they were caught.
+    pub(crate) fn into_python_exception(
+        self,
+        frame: Option<&RawStackFrame>,
+        heap: &Heap<impl ResourceTracker>,
+        interns: &Interns,
+        source: &str,
+    ) -> MontyException {
+        let traceback = frame
+            .map(|frame| traceback_frames(frame, interns, source))
+            .unwrap_or_default();
+        let exceptions = self
+            .exceptions
+            .into_iter()
+            .map(|exc| exc.into_chained_exception(heap, interns, source))
+            .collect();
+        let exc = MontyException::new_full(self.exc_type, self.arg, traceback).with_exceptions(exceptions);
+        let Some(chain) = self.chain else {
+            return exc;
+        };
+        let chained = |id: Option<HeapId>| match heap.get(id?) {
+            HeapData::Exception(exc) => Some(exc.clone().into_chained_exception(heap, interns, source)),
+            _ => None,
+        };
+        exc.with_chain(chained(chain.cause), chained(chain.context), chain.suppress_context)
+    }
+
+    fn into_chained_exception(
+        self,
+        heap: &Heap<impl ResourceTracker>,
+        interns: &Interns,
+        source: &str,
+    ) -> MontyException {
+        let traceback = self.chain.as_ref().and_then(|chain| chain.traceback.clone());
+        self.into_python_exception(traceback.as_ref(), heap, interns, source)
+    }
+
     /// str() for an exception
     #[must_use]
     pub fn py_str(&self) -> String {
         match (self.exc_type, &self.arg) {
             // KeyError expecificaly uses repr of the key for str(exc)
             (ExcType::KeyError, Some(exc)) => StringRepr(exc).to_string(),
+            // Exception groups include the number of sub-exceptions
+            (ExcType::BaseExceptionGroup | ExcType::ExceptionGroup, arg) => {
+                let count = self.exceptions.len();
+                let plural = if count == 1 { "" } else { "s" };
+                format!("{} ({count} sub-exception{plural})", arg.as_deref().unwrap_or_default())
+            }
             (_, Some(arg)) => arg.to_owned(),
             (_, None) => String::new(),
         }
@@ -1181,6 +1683,16 @@ impl SimpleException {
         if let Some(arg) = &self.arg {
             string_repr_fmt(arg, f)?;
         }
+        if self.exc_type.is_group() {
+            f.write_str(", [")?;
+            for (index, exc) in self.exceptions.iter().enumerate() {
+                if index > 0 {
+                    f.write_str(", ")?;
+                }
+                exc.py_repr_fmt(f)?;
+            }
+            f.write_char(']')?;
+        }
 
         f.write_char(')')
     }
@@ -1203,14 +1715,32 @@ impl SimpleException {
 
     /// Gets an attribute from this exception.
     ///
-    /// Handles the `.args` attribute by allocating a tuple containing the message.
-    /// Returns `Err(AttributeError)` for all other attributes.
+    /// Handles the `.args` attribute by allocating a tuple containing the message, and the
+    /// `__cause__`, `__context__` and `__suppress_context__` chaining attributes.
+    /// Exception groups also have `.message` and `.exceptions`, and their `.args` includes
+    /// a list of the sub-exceptions.
+    /// Returns `Ok(None)` for all other attributes.
     pub fn py_getattr(
         &self,
         attr_id: StringId,
         heap: &mut Heap<impl ResourceTracker>,
         _interns: &Interns,
     ) -> RunResult<Option<AttrCallResult>> {
+        if self.exc_type.is_group() {
+            if attr_id == StaticStrings::Args {
+                let message = self.allocate_message(heap)?;
+                let exceptions = self.allocate_exceptions(heap)?;
+                let list_id = heap.allocate(HeapData::List(List::new(exceptions)))?;
+                let args = allocate_tuple(smallvec![message, Value::Ref(list_id)], heap)?;
+                return Ok(Some(AttrCallResult::Value(args)));
+            } else if attr_id == StaticStrings::Message {
+                return Ok(Some(AttrCallResult::Value(self.allocate_message(heap)?)));
+            } else if attr_id == StaticStrings::Exceptions {
+                let exceptions = self.allocate_exceptions(heap)?;
+                let tuple = allocate_tuple(exceptions.into(), heap)?;
+                return Ok(Some(AttrCallResult::Value(tuple)));
+            }
+        }
         if attr_id == StaticStrings::Args {
             // Construct tuple with 0 or 1 elements based on whether arg exists
             let elements = if let Some(arg_str) = &self.arg {
@@ -1220,10 +1750,128 @@ impl SimpleException {
                 smallvec![]
             };
             Ok(Some(AttrCallResult::Value(allocate_tuple(elements, heap)?)))
+        } else if attr_id == StaticStrings::DunderCause {
+            Ok(Some(AttrCallResult::Value(chained_value(self.cause(), heap))))
+        } else if attr_id == StaticStrings::DunderContext {
+            Ok(Some(AttrCallResult::Value(chained_value(self.context(), heap))))
+        } else if attr_id == StaticStrings::DunderSuppressContext {
+            Ok(Some(AttrCallResult::Value(Value::Bool(self.suppress_context()))))
         } else {
             Ok(None)
         }
     }
+
+    /// Calls a method on this exception, only `split()` and `subgroup()` of exception groups.
+    pub(crate) fn py_call_attr(
+        &self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let method = attr.static_string();
+        let is_split = method == Some(StaticStrings::Split);
+        if !self.exc_type.is_group() || !(is_split || method == Some(StaticStrings::Subgroup)) {
+            args.drop_with_heap(heap);
+            return Err(ExcType::attribute_error(self.py_type(), attr.as_str(interns)));
+        }
+
+        let condition = args.get_one_arg(if is_split { "split" } else { "subgroup" }, heap)?;
+        defer_drop!(condition, heap);
+        let Some(handler_types) = exc_types_from_value(condition, heap) else {
+            return Err(ExcType::type_error(
+                "expected an exception type, a tuple of exception types, or a callable (other than a class)",
+            ));
+        };
+        let (matched, rest) = self.split(&|exc_type| handler_types.iter().any(|t| exc_type.is_subclass_of(*t)));
+        for exc in matched.iter().chain(&rest) {
+            exc.inc_chain_refs(heap);
+        }
+        let matched = allocate_optional(matched, heap)?;
+        if is_split {
+            let rest = match allocate_optional(rest, heap) {
+                Ok(rest) => rest,
+                Err(err) => {
+                    matched.drop_with_heap(heap);
+                    return Err(err);
+                }
+            };
+            allocate_tuple(smallvec![matched, rest], heap)
+        } else {
+            Ok(matched)
+        }
+    }
+
+    fn allocate_message(&self, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+        let message = self.arg.clone().unwrap_or_default();
+        Ok(Value::Ref(heap.allocate(HeapData::Str(Str::from(message)))?))
+    }
+
+    /// Allocates the sub-exceptions of a group on the heap.
+    fn allocate_exceptions(&self, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Vec<Value>> {
+        let mut values = Vec::with_capacity(self.exceptions.len());
+        for exc in &self.exceptions {
+            exc.inc_chain_refs(heap);
+            match heap.allocate(HeapData::Exception(exc.clone())) {
+                Ok(id) => values.push(Value::Ref(id)),
+                Err(err) => {
+                    values.drop_with_heap(heap);
+                    return Err(err.into());
+                }
+            }
+        }
+        Ok(values)
+    }
+}
+
+impl<T: ResourceTracker> DropWithHeap<T> for SimpleException {
+    fn drop_with_heap(self, heap: &mut Heap<T>) {
+        let mut ids = Vec::new();
+        self.collect_chain_ids(&mut ids);
+        for id in ids {
+            heap.dec_ref(id);
+        }
+    }
+}
+
+/// Returns a new reference to a chained exception, or `None` if there isn't one.
+fn chained_value(id: Option<HeapId>, heap: &mut Heap<impl ResourceTracker>) -> Value {
+    match id {
+        Some(id) => {
+            heap.inc_ref(id);
+            Value::Ref(id)
+        }
+        None => Value::None,
+    }
+}
+
+/// Allocates an optional exception on the heap, or returns `None` if there isn't one.
+fn allocate_optional(exc: Option<SimpleException>, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    match exc {
+        Some(exc) => Ok(Value::Ref(heap.allocate(HeapData::Exception(exc))?)),
+        None => Ok(Value::None),
+    }
+}
+
+/// Collects the exception types from an exception type or tuple of exception types, as
+/// used by `except`, `except*` and the `split()`/`subgroup()` methods of exception groups.
+///
+/// Returns `None` if the value isn't an exception type or a tuple of them.
+pub(crate) fn exc_types_from_value(value: &Value, heap: &Heap<impl ResourceTracker>) -> Option<Vec<ExcType>> {
+    match value {
+        Value::Builtin(Builtins::ExcType(exc_type)) => Some(vec![*exc_type]),
+        Value::Ref(id) => {
+            let HeapData::Tuple(tuple) = heap.get(*id) else {
+                return None;
+            };
+            let mut exc_types = Vec::new();
+            for item in tuple.as_slice() {
+                exc_types.extend(exc_types_from_value(item, heap)?);
+            }
+            Some(exc_types)
+        }
+        _ => None,
+    }
 }
 
 /// A raised exception with optional stack frame for traceback.
@@ -1303,34 +1951,39 @@ impl ExceptionRaise {
 
     /// Converts this exception to a `MontyException` for the public API.
     ///
-    /// Uses `Interns` to resolve `StringId` references to actual strings.
+    /// Uses `Interns` to resolve `StringId` references to actual strings, and `heap` to
+    /// resolve chained exceptions.
     /// Extracts preview lines from the source code for traceback display.
     #[must_use]
-    pub fn into_python_exception(self, interns: &Interns, source: &str) -> MontyException {
-        let traceback = self
-            .frame
-            .map(|frame| {
-                let mut frames = Vec::new();
-                let mut current = Some(&frame);
-                while let Some(f) = current {
-                    frames.push(StackFrame::from_raw(f, interns, source));
-                    current = f.parent.as_deref();
-                }
-                // Reverse so outermost frame is first (Python's "most recent call last" ordering)
-                frames.reverse();
-                frames
-            })
-            .unwrap_or_default();
-
-        MontyException::new_full(self.exc.exc_type(), self.exc.arg().cloned(), traceback)
+    pub fn into_python_exception(
+        self,
+        heap: &Heap<impl ResourceTracker>,
+        interns: &Interns,
+        source: &str,
+    ) -> MontyException {
+        self.exc
+            .into_python_exception(self.frame.as_ref(), heap, interns, source)
     }
 }
 
+/// Builds the public traceback for a chain of frames, starting from the innermost `frame`.
+fn traceback_frames(frame: &RawStackFrame, interns: &Interns, source: &str) -> Vec<StackFrame> {
+    let mut frames = Vec::new();
+    let mut current = Some(frame);
+    while let Some(f) = current {
+        frames.push(StackFrame::from_raw(f, interns, source));
+        current = f.parent.as_deref();
+    }
+    // Reverse so outermost frame is first (Python's "most recent call last" ordering)
+    frames.reverse();
+    frames
+}
+
 /// A stack frame for traceback information.
 ///
 /// Stores position information and optional function name as StringId.
 /// The actual name string must be looked up externally when formatting the traceback.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct RawStackFrame {
     pub position: CodeRange,
     /// The name of the frame (function name StringId, or None for module-level code).
@@ -1422,14 +2075,28 @@ impl From<FormatError> for RunError {
     }
 }
 
+impl<T: ResourceTracker> DropWithHeap<T> for RunError {
+    fn drop_with_heap(self, heap: &mut Heap<T>) {
+        match self {
+            Self::Exc(exc) | Self::UncatchableExc(exc) => exc.exc.drop_with_heap(heap),
+            Self::Internal(_) => {}
+        }
+    }
+}
+
 impl RunError {
     /// Converts this runtime error to a `MontyException` for the public API.
     ///
     /// Internal errors are converted to `RuntimeError` exceptions with no traceback.
     #[must_use]
-    pub fn into_python_exception(self, interns: &Interns, source: &str) -> MontyException {
+    pub fn into_python_exception(
+        self,
+        heap: &Heap<impl ResourceTracker>,
+        interns: &Interns,
+        source: &str,
+    ) -> MontyException {
         match self {
-            Self::Exc(exc) | Self::UncatchableExc(exc) => exc.into_python_exception(interns, source),
+            Self::Exc(exc) | Self::UncatchableExc(exc) => exc.into_python_exception(heap, interns, source),
             Self::Internal(err) => MontyException::runtime_error(format!("Internal error in monty: {err}")),
         }
     }
@@ -16,6 +16,14 @@ pub struct MontyException {
     message: Option<String>,
     /// Stack trace of the exception, first is the outermost frame shown first in the traceback
     traceback: Vec<StackFrame>,
+    /// The exception this one was raised from with `raise ... from cause`
+    cause: Option<Box<MontyException>>,
+    /// The exception that was being handled when this one was raised
+    context: Option<Box<MontyException>>,
+    /// Whether the context is hidden in the traceback, set by `raise ... from ...`
+    suppress_context: bool,
+    /// Sub-exceptions of an exception group, empty for other exceptions
+    exceptions: Vec<MontyException>,
 }
 
 /// Number of identical consecutive frames to show before collapsing.
@@ -23,48 +31,51 @@ pub struct MontyException {
 /// CPython shows 3 identical frames, then "[Previous line repeated N more times]".
 const REPEAT_FRAMES_SHOWN: usize = 3;
 
+/// Maximum number of sub-exceptions shown for an exception group, like CPython.
+const MAX_GROUP_WIDTH: usize = 15;
+
+/// Maximum depth of nested exception groups shown, like CPython.
+const MAX_GROUP_DEPTH: usize = 10;
+
 /// Display implementation for MontyException should exactly match python traceback format.
 impl fmt::Display for MontyException {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // Print the traceback header if we have frames
-        if !self.traceback.is_empty() {
-            writeln!(f, "Traceback (most recent call last):")?;
-        }
+        let mut out = String::new();
+        self.format_traceback(&mut out, &mut GroupContext::default())?;
+        // Every emitted line ends with a newline, but the traceback as a whole doesn't
+        f.write_str(out.strip_suffix('\n').unwrap_or(&out))
+    }
+}
 
-        // Print frames, collapsing consecutive identical frames like CPython does
-        let mut i = 0;
-        while i < self.traceback.len() {
-            let frame = &self.traceback[i];
+/// Nesting state while formatting the traceback of an exception group.
+///
+/// Mirrors CPython's `_ExceptionPrintContext`: lines of exceptions nested in a group are
+/// indented and prefixed with `|`, with separators between the sub-exceptions.
+#[derive(Default)]
+struct GroupContext {
+    /// How many exception groups the exception being formatted is nested in.
+    depth: usize,
+    /// Whether the closing separator still needs to be written after the last sub-exception.
+    need_close: bool,
+}
 
-            // Count consecutive identical frames
-            let mut repeat_count = 1;
-            while i + repeat_count < self.traceback.len()
-                && frames_are_identical(frame, &self.traceback[i + repeat_count])
-            {
-                repeat_count += 1;
-            }
+impl GroupContext {
+    fn indent(&self) -> String {
+        " ".repeat(2 * self.depth)
+    }
 
-            if repeat_count > REPEAT_FRAMES_SHOWN {
-                // Show first REPEAT_FRAMES_SHOWN frames, then collapse the rest
-                for j in 0..REPEAT_FRAMES_SHOWN {
-                    write!(f, "{}", &self.traceback[i + j])?;
-                }
-                let collapsed = repeat_count - REPEAT_FRAMES_SHOWN;
-                writeln!(f, "  [Previous line repeated {collapsed} more times]")?;
-                i += repeat_count;
-            } else {
-                // Show all frames in this group
-                for j in 0..repeat_count {
-                    write!(f, "{}", &self.traceback[i + j])?;
-                }
-                i += repeat_count;
-            }
+    /// Writes `text` with each line prefixed by the group indent and `margin`.
+    fn emit(&self, out: &mut String, text: &str, margin: char) {
+        if self.depth == 0 {
+            out.push_str(text);
+            return;
         }
-
-        if let Some(msg) = &self.message {
-            write!(f, "{}: {}", self.exc_type, msg)
-        } else {
-            write!(f, "{}", self.exc_type)
+        let indent = self.indent();
+        for line in text.split_inclusive('\n') {
+            out.push_str(&indent);
+            out.push(margin);
+            out.push(' ');
+            out.push_str(line);
         }
     }
 }
@@ -77,11 +88,7 @@ impl MontyException {
     /// You can't provide a traceback here, it's send when raising the exception.
     #[must_use]
     pub fn new(exc_type: ExcType, message: Option<String>) -> Self {
-        Self {
-            exc_type,
-            message,
-            traceback: vec![],
-        }
+        Self::new_full(exc_type, message, vec![])
     }
 
     /// The exception type raised.
@@ -114,13 +121,62 @@ impl MontyException {
         &self.traceback
     }
 
+    /// The exception this one was explicitly raised from.
+    ///
+    /// Equivalent of python's `exc.__cause__`, set by `raise ... from cause`.
+    #[must_use]
+    pub fn cause(&self) -> Option<&Self> {
+        self.cause.as_deref()
+    }
+
+    /// The exception that was being handled when this one was raised.
+    ///
+    /// Equivalent of python's `exc.__context__`.
+    #[must_use]
+    pub fn context(&self) -> Option<&Self> {
+        self.context.as_deref()
+    }
+
+    /// Whether the context is hidden when displaying the traceback.
+    ///
+    /// Equivalent of python's `exc.__suppress_context__`, set by `raise ... from ...`.
+    #[must_use]
+    pub fn suppress_context(&self) -> bool {
+        self.suppress_context
+    }
+
+    /// The sub-exceptions of an exception group, empty for other exceptions.
+    ///
+    /// Equivalent of python's `exc.exceptions`.
+    #[must_use]
+    pub fn exceptions(&self) -> &[Self] {
+        &self.exceptions
+    }
+
+    /// Splits the exception into its type, message and sub-exceptions, dropping the traceback
+    /// and chained exceptions.
+    pub(crate) fn into_parts(self) -> (ExcType, Option<String>, Vec<Self>) {
+        (self.exc_type, self.message, self.exceptions)
+    }
+
+    /// The context shown in the traceback, if it isn't suppressed.
+    fn displayed_context(&self) -> Option<&Self> {
+        self.context.as_deref().filter(|_| !self.suppress_context)
+    }
+
     /// Returns a compact summary of the exception.
     ///
     /// Format: `ExceptionType: message` (e.g., `NotImplementedError: feature not supported`)
     /// If there's no message, just returns the exception type name.
+    /// Exception groups include the number of sub-exceptions, e.g. `ExceptionGroup: msg (2 sub-exceptions)`.
     #[must_use]
     pub fn summary(&self) -> String {
-        if let Some(msg) = &self.message {
+        if self.exc_type.is_group() {
+            let count = self.exceptions.len();
+            let plural = if count == 1 { "" } else { "s" };
+            let msg = self.message.as_deref().unwrap_or_default();
+            format!("{}: {msg} ({count} sub-exception{plural})", self.exc_type)
+        } else if let Some(msg) = &self.message {
             format!("{}: {}", self.exc_type, msg)
         } else {
             self.exc_type.to_string()
@@ -131,30 +187,168 @@ impl MontyException {
     ///
     /// Format: `ExceptionType('message')` (e.g., `ValueError('invalid value')`)
     /// Uses appropriate quoting for messages containing quotes.
+    /// Exception groups also show their sub-exceptions, e.g. `ExceptionGroup('msg', [ValueError()])`.
     #[must_use]
     pub fn py_repr(&self) -> String {
         let type_str: &'static str = self.exc_type.into();
-        if let Some(msg) = &self.message {
+        if self.exc_type.is_group() {
+            let msg = self.message.as_deref().unwrap_or_default();
+            let exceptions: Vec<String> = self.exceptions.iter().map(Self::py_repr).collect();
+            format!("{}({}, [{}])", type_str, StringRepr(msg), exceptions.join(", "))
+        } else if let Some(msg) = &self.message {
             format!("{}({})", type_str, StringRepr(msg))
         } else {
             format!("{type_str}()")
         }
     }
 
+    /// Writes the full traceback of this exception, including chained exceptions and
+    /// the sub-exceptions of groups, following CPython's `TracebackException.format()`.
+    fn format_traceback(&self, out: &mut String, ctx: &mut GroupContext) -> fmt::Result {
+        // Print the chained exception first, like CPython
+        if let Some(cause) = &self.cause {
+            cause.format_traceback(out, ctx)?;
+            ctx.emit(
+                out,
+                "\nThe above exception was the direct cause of the following exception:\n\n",
+                '|',
+            );
+        } else if let Some(context) = self.displayed_context() {
+            context.format_traceback(out, ctx)?;
+            ctx.emit(
+                out,
+                "\nDuring handling of the above exception, another exception occurred:\n\n",
+                '|',
+            );
+        }
+
+        if !self.exc_type.is_group() {
+            if !self.traceback.is_empty() {
+                ctx.emit(out, "Traceback (most recent call last):\n", '|');
+                ctx.emit(out, &self.format_frames()?, '|');
+            }
+            ctx.emit(out, &format!("{}\n", self.summary()), '|');
+            return Ok(());
+        }
+        if ctx.depth > MAX_GROUP_DEPTH {
+            ctx.emit(out, &format!("... (max_group_depth is {MAX_GROUP_DEPTH})\n"), '|');
+            return Ok(());
+        }
+
+        let is_toplevel = ctx.depth == 0;
+        if is_toplevel {
+            ctx.depth += 1;
+        }
+        if !self.traceback.is_empty() {
+            let margin = if is_toplevel { '+' } else { '|' };
+            ctx.emit(out, "Exception Group Traceback (most recent call last):\n", margin);
+            ctx.emit(out, &self.format_frames()?, '|');
+        }
+        ctx.emit(out, &format!("{}\n", self.summary()), '|');
+
+        let shown = self.exceptions.len().min(MAX_GROUP_WIDTH + 1);
+        ctx.need_close = false;
+        for i in 0..shown {
+            let last = i == shown - 1;
+            if last {
+                // The closing separator may be written by a nested group instead
+                ctx.need_close = true;
+            }
+            let truncated = i >= MAX_GROUP_WIDTH;
+            let title = if truncated {
+                "...".to_owned()
+            } else {
+                (i + 1).to_string()
+            };
+            let corner = if i == 0 { "+-" } else { "  " };
+            writeln!(
+                out,
+                "{}{corner}+---------------- {title} ----------------",
+                ctx.indent()
+            )?;
+            ctx.depth += 1;
+            if truncated {
+                let remaining = self.exceptions.len() - MAX_GROUP_WIDTH;
+                let plural = if remaining == 1 { "" } else { "s" };
+                ctx.emit(out, &format!("and {remaining} more exception{plural}\n"), '|');
+            } else {
+                self.exceptions[i].format_traceback(out, ctx)?;
+            }
+            if last && ctx.need_close {
+                writeln!(out, "{}+------------------------------------", ctx.indent())?;
+                ctx.need_close = false;
+            }
+            ctx.depth -= 1;
+        }
+
+        if is_toplevel {
+            ctx.depth = 0;
+        }
+        Ok(())
+    }
+
+    /// Formats the traceback frames, collapsing consecutive identical frames like CPython does.
+    fn format_frames(&self) -> Result<String, fmt::Error> {
+        let mut out = String::new();
+        let mut i = 0;
+        while i < self.traceback.len() {
+            let frame = &self.traceback[i];
+
+            // Count consecutive identical frames
+            let mut repeat_count = 1;
+            while i + repeat_count < self.traceback.len()
+                && frames_are_identical(frame, &self.traceback[i + repeat_count])
+            {
+                repeat_count += 1;
+            }
+
+            if repeat_count > REPEAT_FRAMES_SHOWN {
+                // Show first REPEAT_FRAMES_SHOWN frames, then collapse the rest
+                for j in 0..REPEAT_FRAMES_SHOWN {
+                    write!(out, "{}", &self.traceback[i + j])?;
+                }
+                let collapsed = repeat_count - REPEAT_FRAMES_SHOWN;
+                writeln!(out, "  [Previous line repeated {collapsed} more times]")?;
+            } else {
+                // Show all frames in this group
+                for j in 0..repeat_count {
+                    write!(out, "{}", &self.traceback[i + j])?;
+                }
+            }
+            i += repeat_count;
+        }
+        Ok(out)
+    }
+
     pub(crate) fn new_full(exc_type: ExcType, message: Option<String>, traceback: Vec<StackFrame>) -> Self {
         Self {
             exc_type,
             message,
             traceback,
+            cause: None,
+            context: None,
+            suppress_context: false,
+            exceptions: Vec::new(),
         }
     }
 
+    /// Sets the sub-exceptions, for an `ExceptionGroup` or `BaseExceptionGroup`.
+    #[must_use]
+    pub fn with_exceptions(mut self, exceptions: Vec<Self>) -> Self {
+        self.exceptions = exceptions;
+        self
+    }
+
+    /// Attaches the exceptions this one is chained to.
+    pub(crate) fn with_chain(mut self, cause: Option<Self>, context: Option<Self>, suppress_context: bool) -> Self {
+        self.cause = cause.map(Box::new);
+        self.context = context.map(Box::new);
+        self.suppress_context = suppress_context;
+        self
+    }
+
     pub(crate) fn runtime_error(err: impl fmt::Display) -> Self {
-        Self {
-            exc_type: ExcType::RuntimeError,
-            message: Some(err.to_string()),
-            traceback: vec![],
-        }
+        Self::new(ExcType::RuntimeError, Some(err.to_string()))
     }
 }
 
@@ -4,7 +4,7 @@ use crate::{
     fstring::FStringPart,
     intern::{BytesId, LongIntId, StringId},
     namespace::NamespaceId,
-    parse::{CodeRange, ParsedSignature, Try},
+    parse::{CodeRange, ImportedModule, Match, ParsedSignature, Try, With},
     signature::Signature,
     value::{EitherStr, Marker, Value},
 };
@@ -180,11 +180,17 @@ pub enum Expr {
         upper: Option<Box<ExprLoc>>,
         step: Option<Box<ExprLoc>>,
     },
-    Dict(Vec<(ExprLoc, ExprLoc)>),
+    /// Dict literal expression: `{k: v, **mapping}`.
+    ///
+    /// A `None` key marks a `**mapping` unpacking whose entries are merged in order.
+    Dict(Vec<(Option<ExprLoc>, ExprLoc)>),
     /// Set literal expression: `{1, 2, 3}`.
     ///
     /// Note: `{}` is always a dict, not an empty set. Use `set()` for empty sets.
     Set(Vec<ExprLoc>),
+    /// Starred expression `*iterable` inside a list, tuple or set display, or a call's
+    /// positional arguments. The iterable's items are unpacked into the enclosing sequence.
+    Starred(Box<ExprLoc>),
     /// Unary `not` expression - evaluates to the boolean negation of the operand's truthiness.
     Not(Box<ExprLoc>),
     /// Unary minus expression - negates a numeric value.
@@ -266,6 +272,36 @@ pub enum Expr {
         /// The body is wrapped as `[Node::Return(body_expr)]` during preparation.
         func_def: Box<PreparedFunctionDef>,
     },
+    /// Raw generator expression from the parser, before preparation: `(elt for target in iter ...)`
+    ///
+    /// During the prepare phase this is desugared into a nested generator function
+    /// taking the first iterable as its only argument, producing `Expr::GeneratorExp`.
+    GeneratorExpRaw {
+        /// The interned `<genexpr>` name ID.
+        name_id: StringId,
+        /// The interned `.0` name ID used for the implicit iterator parameter.
+        iter_name_id: StringId,
+        elt: Box<ExprLoc>,
+        generators: Vec<Comprehension>,
+    },
+    /// Generator expression (prepared form).
+    ///
+    /// `func_def` is the synthesized generator function whose body loops over its
+    /// single `.0` parameter; `iter` is the first iterable, evaluated eagerly in the
+    /// enclosing scope (as in CPython) and passed to the function as an iterator.
+    GeneratorExp {
+        func_def: Box<PreparedFunctionDef>,
+        iter: Box<ExprLoc>,
+    },
+    /// Yield expression: `yield` or `yield value`.
+    ///
+    /// Suspends the enclosing generator, producing `value` (or `None`) to the consumer.
+    /// Evaluates to the value passed to `send()`, or `None` when resumed by `next()`.
+    Yield(Option<Box<ExprLoc>>),
+    /// Delegating yield expression: `yield from iterable`.
+    ///
+    /// Yields every value of the sub-iterator and evaluates to its return value.
+    YieldFrom(Box<ExprLoc>),
     /// Named expression (walrus operator): `(target := value)`
     ///
     /// Evaluates `value`, assigns it to `target`, and returns the value as the
@@ -302,6 +338,30 @@ pub enum UnpackTarget {
     Starred(Identifier),
 }
 
+/// Target of a `del` statement.
+///
+/// Tuple and list targets (`del a, (b, c)`) are flattened by the parser, so targets are
+/// deleted left to right.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum DeleteTarget {
+    /// Name deletion: `del x`
+    Name(Identifier),
+    /// Item deletion: `del obj[index]`
+    Subscript {
+        object: ExprLoc,
+        index: ExprLoc,
+        /// Position of the subscript expression for traceback carets.
+        position: CodeRange,
+    },
+    /// Attribute deletion: `del obj.attr`
+    Attr {
+        object: ExprLoc,
+        attr: EitherStr,
+        /// Position of the attribute expression for traceback carets.
+        position: CodeRange,
+    },
+}
+
 /// A generator clause in a comprehension: `for target in iter [if cond1] [if cond2]...`
 ///
 /// Represents one `for` clause with zero or more `if` filters. Multiple generators
@@ -314,6 +374,8 @@ pub struct Comprehension {
     pub iter: ExprLoc,
     /// Zero or more filter conditions (all must be truthy for the element to be included).
     pub ifs: Vec<ExprLoc>,
+    /// Whether this is an `async for` clause.
+    pub is_async: bool,
 }
 
 impl Expr {
@@ -345,8 +407,12 @@ pub enum Literal {
     /// An interned long integer literal. The `LongIntId` references the value in the Interns table.
     /// Used for integer literals that exceed the i64 range.
     LongInt(LongIntId),
+    /// An imaginary literal such as `3j`, holding its imaginary part.
+    Imaginary(f64),
     /// A marker value (e.g., typing constructs like Any, Optional, etc.).
     Marker(Marker),
+    /// A PEP 695 type alias, holding the alias's name.
+    TypeAlias(StringId),
 }
 
 impl From<Literal> for Value {
@@ -364,7 +430,9 @@ impl From<Literal> for Value {
             Literal::Str(string_id) => Self::InternString(string_id),
             Literal::Bytes(bytes_id) => Self::InternBytes(bytes_id),
             Literal::LongInt(long_int_id) => Self::InternLongInt(long_int_id),
+            Literal::Imaginary(imag) => Self::Imaginary(imag),
             Literal::Marker(marker) => Self::Marker(marker),
+            Literal::TypeAlias(name_id) => Self::TypeAlias(name_id),
         }
     }
 }
@@ -398,6 +466,11 @@ pub enum Node<F> {
     Return(ExprLoc),
     ReturnNone,
     Raise(Option<ExprLoc>),
+    /// `raise exc from cause`, where `cause` may be `None` to suppress the exception context.
+    RaiseFrom {
+        exc: ExprLoc,
+        cause: ExprLoc,
+    },
     Assert {
         test: ExprLoc,
         msg: Option<ExprLoc>,
@@ -440,12 +513,38 @@ pub enum Node<F> {
         target_position: CodeRange,
         value: ExprLoc,
     },
+    /// Augmented assignment to a subscript (e.g., `counts[word] += 1`).
+    ///
+    /// The container and index are evaluated once, and used for both the load and the store.
+    SubscriptOpAssign {
+        object: ExprLoc,
+        index: ExprLoc,
+        op: Operator,
+        value: ExprLoc,
+        /// Position of the subscript expression for traceback carets.
+        target_position: CodeRange,
+    },
+    /// Augmented assignment to an attribute (e.g., `self.total += x`).
+    ///
+    /// The object is evaluated once, and used for both the load and the store.
+    AttrOpAssign {
+        object: ExprLoc,
+        attr: EitherStr,
+        op: Operator,
+        value: ExprLoc,
+        /// Position of the attribute expression for traceback carets.
+        target_position: CodeRange,
+    },
+    /// `del` statement with one or more targets, deleted in order.
+    Delete(Vec<DeleteTarget>),
     For {
         /// Loop target - either a single identifier or tuple unpacking pattern.
         target: UnpackTarget,
         iter: ExprLoc,
         body: Vec<Self>,
         or_else: Vec<Self>,
+        /// Whether this is `async for`, which uses `__aiter__`/`__anext__` and awaits each item.
+        is_async: bool,
     },
     /// While loop statement: `while test: body [else: orelse]`
     ///
@@ -476,6 +575,11 @@ pub enum Node<F> {
         or_else: Vec<Self>,
     },
     FunctionDef(F),
+    /// Class definition statement: `class Name(Base): body`.
+    ///
+    /// The class body is restricted to attribute assignments and method definitions,
+    /// see [`ClassItem`].
+    ClassDef(ClassDef<F>),
     /// Global variable declaration. Only present in parsed form, consumed during prepare.
     ///
     /// Declares that the listed names refer to module-level (global) variables,
@@ -497,16 +601,19 @@ pub enum Node<F> {
     /// Executes body, catches matching exceptions with handlers, runs else if no exception,
     /// and always runs finally.
     Try(Try<Self>),
-    /// Import statement (e.g., `import sys`, `import sys as s`).
+    /// Match statement: `match subject:` followed by `case pattern [if guard]:` blocks.
     ///
-    /// Loads a module and binds it to a name in the current namespace.
-    Import {
-        /// The module name to import (e.g., "sys", "typing").
-        module_name: StringId,
-        /// The binding target - contains the name (or alias), position, and namespace slot.
-        /// After prepare phase, this includes the resolved namespace slot for storing the module.
-        binding: Identifier,
-    },
+    /// Patterns are compiled to bytecode that tests and destructures a copy of the subject.
+    Match(Match<Self>),
+    /// `with` or `async with` statement.
+    ///
+    /// Calls `__exit__` (or `__aexit__`) however the body is left: normally, by an exception,
+    /// or through `return`/`break`/`continue`.
+    With(With<Self>),
+    /// Import statement (e.g., `import sys`, `import sys as s`, `import os.path, sys`).
+    ///
+    /// Loads each module and binds it to a name in the current namespace.
+    Import(Vec<ImportedModule>),
     /// From-import statement (e.g., `from typing import TYPE_CHECKING`).
     ///
     /// Imports specific names from a module into the current namespace.
@@ -522,6 +629,32 @@ pub enum Node<F> {
     },
 }
 
+/// A class definition.
+///
+/// Generic over the function definition type so the same structure is used for both
+/// parsed (`RawFunctionDef`) and prepared (`PreparedFunctionDef`) methods.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ClassDef<F> {
+    /// The class name, bound in the enclosing scope once the class is built.
+    pub name: Identifier,
+    /// Optional single base class expression.
+    pub base: Option<ExprLoc>,
+    /// Class body items in source order.
+    pub body: Vec<ClassItem<F>>,
+}
+
+/// A single item in a class body.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum ClassItem<F> {
+    /// Class attribute assignment, e.g. `count = 0`.
+    ///
+    /// The value expression is evaluated in the scope enclosing the class statement.
+    Attr { name: StringId, value: ExprLoc },
+    /// Method definition. The method name is not bound in any namespace, it's stored
+    /// on the class object instead.
+    Method(F),
+}
+
 /// A prepared function definition with resolved names and scope information.
 ///
 /// This is created during the prepare phase and contains everything needed to
@@ -565,6 +698,14 @@ pub struct PreparedFunctionDef {
     /// When true, calling this function creates a `Coroutine` object instead of
     /// immediately pushing a frame.
     pub is_async: bool,
+    /// Whether this is a generator function (its body contains `yield`).
+    ///
+    /// When true, calling this function creates a `Generator` object instead of
+    /// immediately pushing a frame.
+    pub is_generator: bool,
+    /// Prepared decorator expressions in source order, evaluated in the enclosing scope
+    /// before the function is created. Always empty for lambdas.
+    pub decorators: Vec<ExprLoc>,
 }
 
 /// Type alias for prepared AST nodes (output of prepare phase).
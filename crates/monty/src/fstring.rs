@@ -13,7 +13,7 @@ use crate::{
     expressions::ExprLoc,
     heap::Heap,
     intern::{Interns, StringId},
-    resource::{DepthGuard, ResourceTracker},
+    resource::{DepthGuard, ResourceTracker, check_format_size},
     types::{PyTrait, Type},
     value::Value,
 };
@@ -79,7 +79,9 @@ pub enum FormatSpec {
     /// Pre-parsed static format spec (e.g., ">10s", ".2f")
     ///
     /// Parsing happens at parse time to avoid runtime string parsing overhead.
-    /// Invalid specs cause a parse error immediately.
+    /// Specs that don't parse, or whose text can't be rebuilt from the parsed form
+    /// (e.g. `#x` or `,d`), are kept as `Dynamic` string literals instead, since
+    /// `__format__` methods receive the spec text and can accept any spec.
     Static(ParsedFormatSpec),
     /// Dynamic format spec with nested f-string parts
     ///
@@ -221,6 +223,37 @@ impl FromStr for ParsedFormatSpec {
     }
 }
 
+/// Writes the spec back in format mini-language syntax, for passing to `__format__`.
+///
+/// The parser only keeps a spec pre-parsed when this reproduces its text exactly, so
+/// `__format__` always receives the spec as written.
+impl std::fmt::Display for ParsedFormatSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(align) = self.align {
+            if self.fill != ' ' {
+                write!(f, "{}", self.fill)?;
+            }
+            write!(f, "{align}")?;
+        }
+        if let Some(sign) = self.sign {
+            write!(f, "{sign}")?;
+        }
+        if self.zero_pad {
+            write!(f, "0")?;
+        }
+        if self.width > 0 {
+            write!(f, "{}", self.width)?;
+        }
+        if let Some(precision) = self.precision {
+            write!(f, ".{precision}")?;
+        }
+        if let Some(type_char) = self.type_char {
+            write!(f, "{type_char}")?;
+        }
+        Ok(())
+    }
+}
+
 // ============================================================================
 // Format errors
 // ============================================================================
@@ -313,6 +346,37 @@ pub fn format_with_spec(
     }
 }
 
+/// Formats a value like `format(value, spec)`, parsing `spec` first.
+///
+/// An empty spec gives `str(value)`, as in Python. Instances of user-defined classes are
+/// formatted by the VM, which can call their `__format__` method. As with `%` formatting, the
+/// width and precision are checked against the resource limits before any padding is built.
+pub fn format_with_spec_str(
+    value: &Value,
+    spec: &str,
+    heap: &Heap<impl ResourceTracker>,
+    guard: &mut DepthGuard,
+    interns: &Interns,
+) -> Result<String, RunError> {
+    if spec.is_empty() {
+        return Ok(value.py_str(heap, guard, interns).into_owned());
+    }
+    let parsed = spec
+        .parse::<ParsedFormatSpec>()
+        .map_err(|invalid| invalid_format_spec(&invalid, value.py_type(heap)))?;
+    check_format_size(parsed.width, parsed.precision.unwrap_or(0), heap.tracker())?;
+    format_with_spec(value, &parsed, heap, guard, interns)
+}
+
+/// Creates the `ValueError` for a format spec that can't be parsed.
+pub fn invalid_format_spec(spec: &str, value_type: Type) -> RunError {
+    SimpleException::new_msg(
+        ExcType::ValueError,
+        format!("Invalid format specifier '{spec}' for object of type '{value_type}'"),
+    )
+    .into()
+}
+
 /// Encodes a ParsedFormatSpec into a u64 for storage in bytecode constants.
 ///
 /// Encoding layout (fits in 48 bits):
@@ -854,3 +918,178 @@ fn f64_to_i32_trunc(value: f64) -> i32 {
         result
     }
 }
+
+// ============================================================================
+// str.format() templates
+// ============================================================================
+
+/// A piece of a `str.format()` template.
+#[derive(Debug)]
+pub enum TemplatePart<'a> {
+    /// Literal text; escaped braces (`{{` and `}}`) become a piece ending with a single brace.
+    Literal(&'a str),
+    /// A replacement field: `{field_name!conversion:format_spec}`.
+    Field(TemplateField<'a>),
+}
+
+/// A replacement field of a `str.format()` template.
+#[derive(Debug)]
+pub struct TemplateField<'a> {
+    /// Positional index or keyword of the argument, empty for automatic numbering.
+    pub arg_name: &'a str,
+    /// Attribute and index lookups applied to the argument, in order.
+    pub accessors: Vec<FieldAccessor<'a>>,
+    /// Conversion applied before formatting.
+    pub conversion: ConversionFlag,
+    /// Format spec, which may itself contain replacement fields.
+    pub spec: &'a str,
+}
+
+/// A lookup in a replacement field name, after the argument name.
+#[derive(Debug)]
+pub enum FieldAccessor<'a> {
+    /// `.name` - attribute lookup.
+    Attr(&'a str),
+    /// `[key]` - item lookup; the key is an int if it's all digits, otherwise a string.
+    Index(&'a str),
+}
+
+/// Splits a `str.format()` template into literal text and replacement fields.
+///
+/// Returns the message of the `ValueError` to raise for malformed templates.
+pub fn parse_format_template(template: &str) -> Result<Vec<TemplatePart<'_>>, String> {
+    let bytes = template.as_bytes();
+    let mut parts = Vec::new();
+    let mut literal_start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            // Escaped brace: keep the literal up to and including the first brace
+            b'{' | b'}' if bytes.get(i + 1) == Some(&bytes[i]) => {
+                parts.push(TemplatePart::Literal(&template[literal_start..=i]));
+                i += 2;
+                literal_start = i;
+            }
+            b'}' => return Err("Single '}' encountered in format string".to_owned()),
+            b'{' => {
+                if literal_start < i {
+                    parts.push(TemplatePart::Literal(&template[literal_start..i]));
+                }
+                if i + 1 == bytes.len() {
+                    return Err("Single '{' encountered in format string".to_owned());
+                }
+                let end = find_field_end(bytes, i + 1)?;
+                parts.push(TemplatePart::Field(parse_field(&template[i + 1..end])?));
+                i = end + 1;
+                literal_start = i;
+            }
+            _ => i += 1,
+        }
+    }
+    if literal_start < bytes.len() {
+        parts.push(TemplatePart::Literal(&template[literal_start..]));
+    }
+    Ok(parts)
+}
+
+/// Returns the index of the `}` closing the field starting at `start`.
+///
+/// Braces in the format spec nest, and `[...]` in the field name is skipped so keys can
+/// contain braces.
+fn find_field_end(bytes: &[u8], start: usize) -> Result<usize, String> {
+    let mut depth = 1;
+    let mut in_name = true;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'[' if in_name => {
+                while i < bytes.len() && bytes[i] != b']' {
+                    i += 1;
+                }
+            }
+            b':' | b'!' => in_name = false,
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    Err("expected '}' before end of string".to_owned())
+}
+
+/// Parses the text between the braces of a replacement field.
+fn parse_field(field: &str) -> Result<TemplateField<'_>, String> {
+    // The field name ends at the first `!` or `:` outside brackets
+    let mut name_end = field.len();
+    let mut in_brackets = false;
+    for (i, c) in field.char_indices() {
+        match c {
+            '[' => in_brackets = true,
+            ']' => in_brackets = false,
+            '!' | ':' if !in_brackets => {
+                name_end = i;
+                break;
+            }
+            _ => {}
+        }
+    }
+    let (name, rest) = field.split_at(name_end);
+
+    let (conversion, spec) = if let Some(rest) = rest.strip_prefix('!') {
+        let mut chars = rest.chars();
+        let conversion = match chars.next() {
+            Some('s') => ConversionFlag::Str,
+            Some('r') => ConversionFlag::Repr,
+            Some('a') => ConversionFlag::Ascii,
+            Some(c) => return Err(format!("Unknown conversion specifier {c}")),
+            None => return Err("unmatched '{' in format spec".to_owned()),
+        };
+        let rest = chars.as_str();
+        if rest.is_empty() {
+            (conversion, "")
+        } else if let Some(spec) = rest.strip_prefix(':') {
+            (conversion, spec)
+        } else {
+            return Err("expected ':' after conversion specifier".to_owned());
+        }
+    } else {
+        (ConversionFlag::None, rest.strip_prefix(':').unwrap_or(rest))
+    };
+
+    let arg_end = name.find(['.', '[']).unwrap_or(name.len());
+    let (arg_name, mut rest) = name.split_at(arg_end);
+    let mut accessors = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                return Err("Empty attribute in format string".to_owned());
+            }
+            accessors.push(FieldAccessor::Attr(&after[..end]));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let Some(end) = after.find(']') else {
+                return Err("Missing ']' in format string".to_owned());
+            };
+            if end == 0 {
+                return Err("Empty attribute in format string".to_owned());
+            }
+            accessors.push(FieldAccessor::Index(&after[..end]));
+            rest = &after[end + 1..];
+        } else {
+            return Err("Only '.' or '[' may follow ']' in format field specifier".to_owned());
+        }
+    }
+
+    Ok(TemplateField {
+        arg_name,
+        accessors,
+        conversion,
+        spec,
+    })
+}
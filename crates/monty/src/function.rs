@@ -63,6 +63,11 @@ pub(crate) struct Function {
     /// immediately pushing a frame. The coroutine captures the bound arguments
     /// and starts execution only when awaited.
     pub is_async: bool,
+    /// Whether this is a generator function (its body contains `yield`).
+    ///
+    /// When true, calling this function creates a `Generator` object that runs
+    /// the body lazily as values are requested.
+    pub is_generator: bool,
     /// Compiled bytecode for this function body.
     pub code: Code,
 }
@@ -81,6 +86,7 @@ impl Function {
     /// * `cell_param_indices` - Maps cell indices to parameter indices for captured parameters
     /// * `defaults_count` - Number of default parameter values
     /// * `is_async` - Whether this is an async function
+    /// * `is_generator` - Whether this is a generator function
     /// * `code` - The compiled bytecode for the function body
     #[expect(clippy::too_many_arguments)]
     pub fn new(
@@ -92,6 +98,7 @@ impl Function {
         cell_param_indices: Vec<Option<usize>>,
         defaults_count: usize,
         is_async: bool,
+        is_generator: bool,
         code: Code,
     ) -> Self {
         Self {
@@ -103,10 +110,20 @@ impl Function {
             cell_param_indices,
             defaults_count,
             is_async,
+            is_generator,
             code,
         }
     }
 
+    /// Estimates the memory used by this function, for charging code compiled at runtime.
+    #[must_use]
+    pub fn estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.free_var_enclosing_slots.len() * std::mem::size_of::<NamespaceId>()
+            + self.cell_param_indices.len() * std::mem::size_of::<Option<usize>>()
+            + self.code.estimate_size()
+    }
+
     /// Writes the Python repr() string for this function to a formatter.
     pub fn py_repr_fmt<W: Write>(&self, f: &mut W, interns: &Interns, py_id: usize) -> std::fmt::Result {
         write!(
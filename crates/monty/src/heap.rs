@@ -1,5 +1,6 @@
 use std::{
     borrow::Cow,
+    cmp::Ordering,
     collections::hash_map::DefaultHasher,
     fmt::Write,
     hash::{Hash, Hasher},
@@ -19,8 +20,11 @@ use crate::{
     intern::{FunctionId, Interns, StringId},
     resource::{DepthGuard, ResourceError, ResourceTracker, check_mult_size, check_repeat_size},
     types::{
-        AttrCallResult, Bytes, Dataclass, Dict, FrozenSet, List, LongInt, Module, MontyIter, NamedTuple, Path, PyTrait,
-        Range, Set, Slice, Str, Tuple, Type, allocate_tuple,
+        AsyncGenAwaitable, AttrCallResult, BoundMethod, ByteArray, Bytes, ClassObject, CodeObject, Complex, Counter,
+        Dataclass, DataclassType, Date, DateTime, DefaultDict, Deque, Dict, FrozenSet, Generator, Instance, List,
+        LongInt, MemoryView, Module, MontyIter, NamedTuple, NamedTupleFactory, OrderedDict, Path, PyTrait, Range,
+        ReMatch, RePattern, Set, Slice, Str, SuperProxy, TimeDelta, TimeZone, Tuple, Type, allocate_tuple,
+        class::class_disables_hash,
     },
     value::{EitherStr, Value},
 };
@@ -52,10 +56,28 @@ const EMPTY_TUPLE_ID: HeapId = HeapId(0);
 pub(crate) enum HeapData {
     Str(Str),
     Bytes(Bytes),
+    /// A mutable byte sequence.
+    ByteArray(ByteArray),
+    /// A read-only view onto a `bytes` or `bytearray` object, which it holds a reference to.
+    MemoryView(MemoryView),
+    /// A compiled regular expression from the `re` module.
+    RePattern(RePattern),
+    /// A match found by a regular expression, which holds references to the pattern and the searched string.
+    ReMatch(ReMatch),
     List(List),
     Tuple(Tuple),
     NamedTuple(NamedTuple),
+    /// A class created by `collections.namedtuple()`, which creates `NamedTuple` instances.
+    NamedTupleFactory(NamedTupleFactory),
     Dict(Dict),
+    /// A `collections.defaultdict`, which holds its default factory alongside the dict.
+    DefaultDict(DefaultDict),
+    /// A `collections.Counter`.
+    Counter(Counter),
+    /// A `collections.OrderedDict`.
+    OrderedDict(OrderedDict),
+    /// A `collections.deque`.
+    Deque(Deque),
     Set(Set),
     FrozenSet(FrozenSet),
     /// A closure: a function that captures variables from enclosing scopes.
@@ -98,6 +120,8 @@ pub(crate) enum HeapData {
     /// Contains a class name, a Dict of field name -> value mappings, and a set
     /// of method names that trigger external function calls when invoked.
     Dataclass(Dataclass),
+    /// The class of a dataclass instance, returned by `type()`.
+    DataclassType(DataclassType),
     /// An iterator for for-loop iteration and the `iter()` type constructor.
     ///
     /// Created by the `GetIter` opcode or `iter()` builtin, advanced by `ForIter`.
@@ -110,6 +134,18 @@ pub(crate) enum HeapData {
     /// when values fit, and promote to LongInt on overflow. When LongInt results fit back
     /// in i64, they are demoted back to `Value::Int` for performance.
     LongInt(LongInt),
+    /// A complex number.
+    ///
+    /// Stored on the heap (rather than inline in `Value`) to keep `Value` at 16 bytes.
+    Complex(Complex),
+    /// A `datetime.date`.
+    Date(Date),
+    /// A `datetime.datetime`, which stores its timezone inline.
+    DateTime(DateTime),
+    /// A `datetime.timedelta`.
+    TimeDelta(TimeDelta),
+    /// A `datetime.timezone`.
+    TimeZone(TimeZone),
     /// A Python module (e.g., `sys`, `typing`).
     ///
     /// Modules have a name and a dictionary of attributes. They are created by
@@ -124,20 +160,41 @@ pub(crate) enum HeapData {
     ///
     /// Created by asyncio.gather() and spawns tasks when awaited.
     GatherFuture(GatherFuture),
+    /// A generator object from a generator function call or generator expression.
+    ///
+    /// Holds the saved frame state of the generator while it is suspended.
+    Generator(Generator),
+    /// The awaitable returned by `__anext__()`, `asend()` or `aclose()` of an async generator.
+    AsyncGenAwaitable(AsyncGenAwaitable),
     /// A filesystem path from `pathlib.Path`.
     ///
     /// Stored on the heap to provide Python-compatible path operations.
     /// Pure methods (name, parent, etc.) are handled directly by the VM.
     /// I/O methods (exists, read_text, etc.) yield external function calls.
     Path(Path),
+    /// A user-defined class created by a `class` statement.
+    ///
+    /// Holds the class name, optional base class and the class attributes/methods.
+    ClassObject(ClassObject),
+    /// An instance of a user-defined class.
+    ///
+    /// Holds a reference to its class and a Dict of instance attributes.
+    Instance(Instance),
+    /// A function bound to the object it was looked up on (e.g. `obj.method`).
+    BoundMethod(BoundMethod),
+    /// The proxy object returned by `super()`.
+    SuperProxy(SuperProxy),
+    /// A code object returned by `compile()`.
+    CodeObject(CodeObject),
 }
 
 impl HeapData {
     /// Returns whether this heap data type can participate in reference cycles.
     ///
     /// Only container types that can hold references to other heap objects need to be
-    /// tracked for GC purposes. Leaf types like Str, Bytes, Range, and Exception cannot
-    /// form cycles and should not count toward the GC allocation threshold.
+    /// tracked for GC purposes. Leaf types like Str, Bytes and Range cannot form cycles
+    /// and should not count toward the GC allocation threshold. Neither can exceptions,
+    /// whose causes and contexts are always exceptions created before them.
     ///
     /// This optimization allows programs that allocate many leaf objects (like strings)
     /// to avoid triggering unnecessary GC cycles.
@@ -148,17 +205,28 @@ impl HeapData {
             Self::List(_)
                 | Self::Tuple(_)
                 | Self::NamedTuple(_)
+                | Self::NamedTupleFactory(_)
                 | Self::Dict(_)
+                | Self::DefaultDict(_)
+                | Self::Counter(_)
+                | Self::OrderedDict(_)
+                | Self::Deque(_)
                 | Self::Set(_)
                 | Self::FrozenSet(_)
                 | Self::Closure(_, _, _)
                 | Self::FunctionDefaults(_, _)
                 | Self::Cell(_)
                 | Self::Dataclass(_)
+                | Self::ClassObject(_)
+                | Self::Instance(_)
+                | Self::BoundMethod(_)
+                | Self::SuperProxy(_)
                 | Self::Iter(_)
                 | Self::Module(_)
                 | Self::Coroutine(_)
                 | Self::GatherFuture(_)
+                | Self::Generator(_)
+                | Self::AsyncGenAwaitable(_)
         )
     }
 
@@ -175,7 +243,12 @@ impl HeapData {
             Self::List(list) => list.contains_refs(),
             Self::Tuple(tuple) => tuple.contains_refs(),
             Self::NamedTuple(nt) => nt.contains_refs(),
+            Self::NamedTupleFactory(factory) => factory.has_refs(),
             Self::Dict(dict) => dict.has_refs(),
+            Self::DefaultDict(dd) => dd.has_refs(),
+            Self::Counter(counter) => counter.dict().has_refs(),
+            Self::OrderedDict(od) => od.dict().has_refs(),
+            Self::Deque(deque) => deque.contains_refs(),
             Self::Set(set) => set.has_refs(),
             Self::FrozenSet(fset) => fset.has_refs(),
             // Closures always have refs when they have captured cells (HeapIds)
@@ -185,6 +258,11 @@ impl HeapData {
             Self::FunctionDefaults(_, defaults) => defaults.iter().any(|v| matches!(v, Value::Ref(_))),
             Self::Cell(value) => matches!(value, Value::Ref(_)),
             Self::Dataclass(dc) => dc.has_refs(),
+            Self::ClassObject(cls) => cls.has_refs(),
+            // Instances, bound methods and super proxies always reference a class or object
+            Self::Instance(_) | Self::BoundMethod(_) | Self::SuperProxy(_) => true,
+            // Memoryviews always reference their source object, and matches their pattern and string
+            Self::MemoryView(_) | Self::ReMatch(_) => true,
             Self::Iter(iter) => iter.has_refs(),
             Self::Module(m) => m.has_refs(),
             // Coroutines always have refs (namespace values, frame_cells)
@@ -202,14 +280,26 @@ impl HeapData {
                         .iter()
                         .any(|r| r.as_ref().is_some_and(|v| matches!(v, Value::Ref(_))))
             }
+            Self::Generator(generator) => generator.has_refs(),
+            // Awaitables always reference their generator
+            Self::AsyncGenAwaitable(_) => true,
+            Self::Exception(exc) => exc.has_refs(),
             // Leaf types cannot have refs
             Self::Str(_)
             | Self::Bytes(_)
+            | Self::ByteArray(_)
+            | Self::RePattern(_)
             | Self::Range(_)
             | Self::Slice(_)
-            | Self::Exception(_)
             | Self::LongInt(_)
-            | Self::Path(_) => false,
+            | Self::Complex(_)
+            | Self::Date(_)
+            | Self::DateTime(_)
+            | Self::TimeDelta(_)
+            | Self::TimeZone(_)
+            | Self::Path(_)
+            | Self::DataclassType(_)
+            | Self::CodeObject(_) => false,
         }
     }
 
@@ -219,6 +309,18 @@ impl HeapData {
         matches!(self, Self::Coroutine(_))
     }
 
+    /// Returns the dict of a `dict` or of one of the `collections` dict subclasses.
+    #[must_use]
+    pub fn as_dict(&self) -> Option<&Dict> {
+        match self {
+            Self::Dict(dict) => Some(dict),
+            Self::DefaultDict(dd) => Some(dd.dict()),
+            Self::Counter(counter) => Some(counter.dict()),
+            Self::OrderedDict(od) => Some(od.dict()),
+            _ => None,
+        }
+    }
+
     /// Computes hash for immutable heap types that can be used as dict keys.
     ///
     /// Returns Some(hash) for immutable types (Str, Bytes, Tuple of hashables).
@@ -240,30 +342,23 @@ impl HeapData {
                 b.as_slice().hash(&mut hasher);
                 Some(hasher.finish())
             }
-            Self::FrozenSet(fs) => {
-                // FrozenSet hash is XOR of element hashes (order-independent)
-                fs.compute_hash(heap, interns)
-            }
-            Self::Tuple(t) => {
-                let mut hasher = DefaultHasher::new();
-                discriminant(self).hash(&mut hasher);
-                // Tuple is hashable only if all elements are hashable
-                for obj in t.as_slice() {
-                    let h = obj.py_hash(heap, interns)?;
-                    h.hash(&mut hasher);
+            // Views of bytes hash like the bytes they select; views of bytearrays are unhashable
+            Self::MemoryView(view) => {
+                if !matches!(heap.get(view.obj()), HeapData::Bytes(_)) {
+                    return None;
                 }
-                Some(hasher.finish())
-            }
-            Self::NamedTuple(nt) => {
                 let mut hasher = DefaultHasher::new();
-                discriminant(self).hash(&mut hasher);
-                // Hash only by elements (not type_name) to match equality semantics
-                for obj in nt.as_vec() {
-                    let h = obj.py_hash(heap, interns)?;
-                    h.hash(&mut hasher);
-                }
+                view.bytes(heap).hash(&mut hasher);
                 Some(hasher.finish())
             }
+            Self::FrozenSet(fs) => {
+                // FrozenSet hash is XOR of element hashes (order-independent)
+                fs.compute_hash(heap, interns)
+            }
+            Self::Tuple(t) => hash_tuple_items(t.as_slice(), heap, interns),
+            // Hash only by elements (not type_name) to match equality semantics, named tuples
+            // compare equal to tuples so they must hash the same
+            Self::NamedTuple(nt) => hash_tuple_items(nt.as_vec(), heap, interns),
             Self::Closure(f, _, _) | Self::FunctionDefaults(f, _) => {
                 let mut hasher = DefaultHasher::new();
                 discriminant(self).hash(&mut hasher);
@@ -281,6 +376,13 @@ impl HeapData {
             }
             // Dataclass hashability depends on the mutable flag
             Self::Dataclass(dc) => dc.compute_hash(heap, interns),
+            // Dataclass classes are only equal when their type ids are, so hashing the type id is enough
+            Self::DataclassType(class) => {
+                let mut hasher = DefaultHasher::new();
+                discriminant(self).hash(&mut hasher);
+                class.type_id().hash(&mut hasher);
+                Some(hasher.finish())
+            }
             // Slices are immutable and hashable (like in CPython)
             Self::Slice(slice) => {
                 let mut hasher = DefaultHasher::new();
@@ -297,19 +399,46 @@ impl HeapData {
                 path.as_str().hash(&mut hasher);
                 Some(hasher.finish())
             }
+            // Patterns hash like they compare, by pattern and flags
+            Self::RePattern(pattern) => {
+                let mut hasher = DefaultHasher::new();
+                discriminant(self).hash(&mut hasher);
+                pattern.pattern().hash(&mut hasher);
+                pattern.flags().hash(&mut hasher);
+                Some(hasher.finish())
+            }
             // Mutable types, exceptions, iterators, modules, and async types cannot be hashed
-            // (Cell is handled specially in get_or_compute_hash)
+            // (Cell and user-defined class objects are handled specially in get_or_compute_hash)
             Self::List(_)
+            | Self::ByteArray(_)
             | Self::Dict(_)
+            | Self::DefaultDict(_)
+            | Self::Counter(_)
+            | Self::OrderedDict(_)
+            | Self::Deque(_)
             | Self::Set(_)
             | Self::Cell(_)
+            | Self::ClassObject(_)
+            | Self::NamedTupleFactory(_)
+            | Self::Instance(_)
+            | Self::BoundMethod(_)
+            | Self::SuperProxy(_)
+            | Self::ReMatch(_)
+            | Self::CodeObject(_)
             | Self::Exception(_)
             | Self::Iter(_)
             | Self::Module(_)
             | Self::Coroutine(_)
-            | Self::GatherFuture(_) => None,
-            // LongInt is immutable and hashable
+            | Self::GatherFuture(_)
+            | Self::Generator(_)
+            | Self::AsyncGenAwaitable(_) => None,
+            // LongInt, Complex and the datetime types are immutable and hashable
             Self::LongInt(li) => Some(li.hash()),
+            Self::Complex(c) => Some(c.hash()),
+            Self::Date(d) => Some(d.hash()),
+            Self::DateTime(dt) => Some(dt.hash()),
+            Self::TimeDelta(td) => Some(td.hash()),
+            Self::TimeZone(tz) => Some(tz.hash()),
         }
     }
 }
@@ -323,10 +452,19 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_type(heap),
             Self::Bytes(b) => b.py_type(heap),
+            Self::ByteArray(b) => b.py_type(heap),
+            Self::MemoryView(view) => view.py_type(heap),
+            Self::RePattern(pattern) => pattern.py_type(heap),
+            Self::ReMatch(m) => m.py_type(heap),
             Self::List(l) => l.py_type(heap),
             Self::Tuple(t) => t.py_type(heap),
             Self::NamedTuple(nt) => nt.py_type(heap),
+            Self::NamedTupleFactory(factory) => factory.py_type(heap),
             Self::Dict(d) => d.py_type(heap),
+            Self::DefaultDict(dd) => dd.py_type(heap),
+            Self::Counter(c) => c.py_type(heap),
+            Self::OrderedDict(od) => od.py_type(heap),
+            Self::Deque(dq) => dq.py_type(heap),
             Self::Set(s) => s.py_type(heap),
             Self::FrozenSet(fs) => fs.py_type(heap),
             Self::Closure(_, _, _) | Self::FunctionDefaults(_, _) => Type::Function,
@@ -335,12 +473,25 @@ impl PyTrait for HeapData {
             Self::Slice(_) => Type::Slice,
             Self::Exception(e) => e.py_type(),
             Self::Dataclass(dc) => dc.py_type(heap),
+            Self::DataclassType(class) => class.py_type(heap),
             Self::Iter(_) => Type::Iterator,
             // LongInt is still `int` in Python - it's an implementation detail
             Self::LongInt(_) => Type::Int,
+            Self::Complex(c) => c.py_type(heap),
+            Self::Date(d) => d.py_type(heap),
+            Self::DateTime(dt) => dt.py_type(heap),
+            Self::TimeDelta(td) => td.py_type(heap),
+            Self::TimeZone(tz) => tz.py_type(heap),
             Self::Module(_) => Type::Module,
-            Self::Coroutine(_) | Self::GatherFuture(_) => Type::Coroutine,
+            Self::Coroutine(_) | Self::GatherFuture(_) | Self::AsyncGenAwaitable(_) => Type::Coroutine,
+            Self::Generator(generator) if generator.is_async => Type::AsyncGenerator,
+            Self::Generator(_) => Type::Generator,
             Self::Path(p) => p.py_type(heap),
+            Self::ClassObject(cls) => cls.py_type(heap),
+            Self::Instance(inst) => inst.py_type(heap),
+            Self::BoundMethod(bm) => bm.py_type(heap),
+            Self::SuperProxy(sp) => sp.py_type(heap),
+            Self::CodeObject(code) => code.py_type(heap),
         }
     }
 
@@ -348,10 +499,19 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_estimate_size(),
             Self::Bytes(b) => b.py_estimate_size(),
+            Self::ByteArray(b) => b.py_estimate_size(),
+            Self::MemoryView(view) => view.py_estimate_size(),
+            Self::RePattern(pattern) => pattern.py_estimate_size(),
+            Self::ReMatch(m) => m.py_estimate_size(),
             Self::List(l) => l.py_estimate_size(),
             Self::Tuple(t) => t.py_estimate_size(),
             Self::NamedTuple(nt) => nt.py_estimate_size(),
+            Self::NamedTupleFactory(factory) => factory.py_estimate_size(),
             Self::Dict(d) => d.py_estimate_size(),
+            Self::DefaultDict(dd) => dd.py_estimate_size(),
+            Self::Counter(c) => c.py_estimate_size(),
+            Self::OrderedDict(od) => od.py_estimate_size(),
+            Self::Deque(dq) => dq.py_estimate_size(),
             Self::Set(s) => s.py_estimate_size(),
             Self::FrozenSet(fs) => fs.py_estimate_size(),
             // TODO: should include size of captured cells and defaults
@@ -359,10 +519,16 @@ impl PyTrait for HeapData {
             Self::Cell(v) => std::mem::size_of::<Value>() + v.py_estimate_size(),
             Self::Range(_) => std::mem::size_of::<Range>(),
             Self::Slice(s) => s.py_estimate_size(),
-            Self::Exception(e) => std::mem::size_of::<SimpleException>() + e.arg().map_or(0, String::len),
+            Self::Exception(e) => e.estimate_size(),
             Self::Dataclass(dc) => dc.py_estimate_size(),
+            Self::DataclassType(class) => class.py_estimate_size(),
             Self::Iter(_) => std::mem::size_of::<MontyIter>(),
             Self::LongInt(li) => li.estimate_size(),
+            Self::Complex(c) => c.py_estimate_size(),
+            Self::Date(d) => d.py_estimate_size(),
+            Self::DateTime(dt) => dt.py_estimate_size(),
+            Self::TimeDelta(td) => td.py_estimate_size(),
+            Self::TimeZone(tz) => tz.py_estimate_size(),
             Self::Module(m) => std::mem::size_of::<Module>() + m.attrs().py_estimate_size(),
             Self::Coroutine(coro) => {
                 std::mem::size_of::<Coroutine>()
@@ -375,7 +541,14 @@ impl PyTrait for HeapData {
                     + gather.results.len() * std::mem::size_of::<Option<Value>>()
                     + gather.pending_calls.len() * std::mem::size_of::<crate::asyncio::CallId>()
             }
+            Self::Generator(generator) => generator.py_estimate_size(),
+            Self::AsyncGenAwaitable(_) => std::mem::size_of::<AsyncGenAwaitable>(),
             Self::Path(p) => p.py_estimate_size(),
+            Self::ClassObject(cls) => cls.py_estimate_size(),
+            Self::Instance(inst) => inst.py_estimate_size(),
+            Self::BoundMethod(bm) => bm.py_estimate_size(),
+            Self::SuperProxy(sp) => sp.py_estimate_size(),
+            Self::CodeObject(code) => code.py_estimate_size(),
         }
     }
 
@@ -383,26 +556,49 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => PyTrait::py_len(s, heap, interns),
             Self::Bytes(b) => PyTrait::py_len(b, heap, interns),
+            Self::ByteArray(b) => PyTrait::py_len(b, heap, interns),
+            Self::MemoryView(view) => PyTrait::py_len(view, heap, interns),
+            Self::RePattern(pattern) => PyTrait::py_len(pattern, heap, interns),
+            Self::ReMatch(m) => PyTrait::py_len(m, heap, interns),
             Self::List(l) => PyTrait::py_len(l, heap, interns),
             Self::Tuple(t) => PyTrait::py_len(t, heap, interns),
             Self::NamedTuple(nt) => PyTrait::py_len(nt, heap, interns),
             Self::Dict(d) => PyTrait::py_len(d, heap, interns),
+            Self::DefaultDict(dd) => PyTrait::py_len(dd, heap, interns),
+            Self::Counter(c) => PyTrait::py_len(c, heap, interns),
+            Self::OrderedDict(od) => PyTrait::py_len(od, heap, interns),
+            Self::Deque(dq) => PyTrait::py_len(dq, heap, interns),
             Self::Set(s) => PyTrait::py_len(s, heap, interns),
             Self::FrozenSet(fs) => PyTrait::py_len(fs, heap, interns),
             Self::Range(r) => Some(r.len()),
             // Cells, Slices, Exceptions, Dataclasses, Iterators, LongInts, Modules, Paths, and async types don't have length
+            // (`__len__` on user-defined instances is dispatched by the VM)
             Self::Cell(_)
             | Self::Closure(_, _, _)
             | Self::FunctionDefaults(_, _)
+            | Self::NamedTupleFactory(_)
             | Self::Slice(_)
             | Self::Exception(_)
             | Self::Dataclass(_)
+            | Self::DataclassType(_)
             | Self::Iter(_)
             | Self::LongInt(_)
+            | Self::Complex(_)
+            | Self::Date(_)
+            | Self::DateTime(_)
+            | Self::TimeDelta(_)
+            | Self::TimeZone(_)
             | Self::Module(_)
             | Self::Coroutine(_)
             | Self::GatherFuture(_)
-            | Self::Path(_) => None,
+            | Self::Generator(_)
+            | Self::AsyncGenAwaitable(_)
+            | Self::Path(_)
+            | Self::ClassObject(_)
+            | Self::Instance(_)
+            | Self::BoundMethod(_)
+            | Self::SuperProxy(_)
+            | Self::CodeObject(_) => None,
         }
     }
 
@@ -416,6 +612,9 @@ impl PyTrait for HeapData {
         match (self, other) {
             (Self::Str(a), Self::Str(b)) => a.py_eq(b, heap, guard, interns),
             (Self::Bytes(a), Self::Bytes(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::ByteArray(a), Self::ByteArray(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::MemoryView(a), Self::MemoryView(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::RePattern(a), Self::RePattern(b)) => a.py_eq(b, heap, guard, interns),
             (Self::List(a), Self::List(b)) => a.py_eq(b, heap, guard, interns),
             (Self::Tuple(a), Self::Tuple(b)) => a.py_eq(b, heap, guard, interns),
             (Self::NamedTuple(a), Self::NamedTuple(b)) => a.py_eq(b, heap, guard, interns),
@@ -437,6 +636,9 @@ impl PyTrait for HeapData {
                 Ok(true)
             }
             (Self::Dict(a), Self::Dict(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::Counter(a), Self::Counter(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::OrderedDict(a), Self::OrderedDict(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::Deque(a), Self::Deque(b)) => a.py_eq(b, heap, guard, interns),
             (Self::Set(a), Self::Set(b)) => a.py_eq(b, heap, guard, interns),
             (Self::FrozenSet(a), Self::FrozenSet(b)) => a.py_eq(b, heap, guard, interns),
             (Self::Closure(a_id, a_cells, _), Self::Closure(b_id, b_cells, _)) => {
@@ -445,20 +647,58 @@ impl PyTrait for HeapData {
             (Self::FunctionDefaults(a_id, _), Self::FunctionDefaults(b_id, _)) => Ok(*a_id == *b_id),
             (Self::Range(a), Self::Range(b)) => a.py_eq(b, heap, guard, interns),
             (Self::Dataclass(a), Self::Dataclass(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::DataclassType(a), Self::DataclassType(b)) => a.py_eq(b, heap, guard, interns),
             // LongInt equality
             (Self::LongInt(a), Self::LongInt(b)) => Ok(a == b),
+            // Complex equality, including with LongInts (complex numbers equal ints when their imaginary part is zero)
+            (Self::Complex(a), Self::Complex(b)) => a.py_eq(b, heap, guard, interns),
+            // A datetime never equals a date, even though it's a subclass
+            (Self::Date(a), Self::Date(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::DateTime(a), Self::DateTime(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::TimeDelta(a), Self::TimeDelta(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::TimeZone(a), Self::TimeZone(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::Complex(c), Self::LongInt(li)) | (Self::LongInt(li), Self::Complex(c)) => {
+                Ok(c.imag == 0.0 && li.to_f64() == Some(c.real))
+            }
             // Slice equality
             (Self::Slice(a), Self::Slice(b)) => a.py_eq(b, heap, guard, interns),
             // Path equality
             (Self::Path(a), Self::Path(b)) => a.py_eq(b, heap, guard, interns),
-            // Cells, Exceptions, Iterators, Modules, and async types compare by identity only (handled at Value level via HeapId comparison)
+            (Self::CodeObject(a), Self::CodeObject(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::BoundMethod(a), Self::BoundMethod(b)) => a.py_eq(b, heap, guard, interns),
+            // Cells, Exceptions, Iterators, Modules, regex matches and async types compare by identity only (handled at Value level via HeapId comparison)
             (Self::Cell(_), Self::Cell(_))
             | (Self::Exception(_), Self::Exception(_))
             | (Self::Iter(_), Self::Iter(_))
             | (Self::Module(_), Self::Module(_))
             | (Self::Coroutine(_), Self::Coroutine(_))
-            | (Self::GatherFuture(_), Self::GatherFuture(_)) => Ok(false),
-            _ => Ok(false), // Different types are never equal
+            | (Self::GatherFuture(_), Self::GatherFuture(_))
+            | (Self::Generator(_), Self::Generator(_))
+            | (Self::AsyncGenAwaitable(_), Self::AsyncGenAwaitable(_))
+            | (Self::ClassObject(_), Self::ClassObject(_))
+            | (Self::Instance(_), Self::Instance(_))
+            | (Self::SuperProxy(_), Self::SuperProxy(_))
+            | (Self::ReMatch(_), Self::ReMatch(_)) => Ok(false),
+            // Other combinations of dict types compare as plain dicts, other different types are never equal
+            _ => match (self.as_dict(), other.as_dict()) {
+                (Some(a), Some(b)) => a.py_eq(b, heap, guard, interns),
+                _ => Ok(false),
+            },
+        }
+    }
+
+    fn py_cmp(
+        &self,
+        other: &Self,
+        heap: &mut Heap<impl ResourceTracker>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> Result<Option<Ordering>, ResourceError> {
+        match (self, other) {
+            (Self::Date(a), Self::Date(b)) => a.py_cmp(b, heap, guard, interns),
+            (Self::DateTime(a), Self::DateTime(b)) => a.py_cmp(b, heap, guard, interns),
+            (Self::TimeDelta(a), Self::TimeDelta(b)) => a.py_cmp(b, heap, guard, interns),
+            _ => Ok(None),
         }
     }
 
@@ -466,10 +706,19 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_dec_ref_ids(stack),
             Self::Bytes(b) => b.py_dec_ref_ids(stack),
+            Self::ByteArray(b) => b.py_dec_ref_ids(stack),
+            Self::MemoryView(view) => view.py_dec_ref_ids(stack),
+            Self::RePattern(pattern) => pattern.py_dec_ref_ids(stack),
+            Self::ReMatch(m) => m.py_dec_ref_ids(stack),
             Self::List(l) => l.py_dec_ref_ids(stack),
             Self::Tuple(t) => t.py_dec_ref_ids(stack),
             Self::NamedTuple(nt) => nt.py_dec_ref_ids(stack),
+            Self::NamedTupleFactory(factory) => factory.py_dec_ref_ids(stack),
             Self::Dict(d) => d.py_dec_ref_ids(stack),
+            Self::DefaultDict(dd) => dd.py_dec_ref_ids(stack),
+            Self::Counter(c) => c.py_dec_ref_ids(stack),
+            Self::OrderedDict(od) => od.py_dec_ref_ids(stack),
+            Self::Deque(dq) => dq.py_dec_ref_ids(stack),
             Self::Set(s) => s.py_dec_ref_ids(stack),
             Self::FrozenSet(fs) => fs.py_dec_ref_ids(stack),
             Self::Closure(_, cells, defaults) => {
@@ -510,8 +759,26 @@ impl PyTrait for HeapData {
                     result.py_dec_ref_ids(stack);
                 }
             }
-            // Range, Slice, Exception, LongInt, and Path have no nested heap references
-            Self::Range(_) | Self::Slice(_) | Self::Exception(_) | Self::LongInt(_) | Self::Path(_) => {}
+            Self::Generator(generator) => generator.py_dec_ref_ids(stack),
+            Self::AsyncGenAwaitable(awaitable) => awaitable.py_dec_ref_ids(stack),
+            Self::ClassObject(cls) => cls.py_dec_ref_ids(stack),
+            Self::Instance(inst) => inst.py_dec_ref_ids(stack),
+            Self::BoundMethod(bm) => bm.py_dec_ref_ids(stack),
+            Self::SuperProxy(sp) => sp.py_dec_ref_ids(stack),
+            // Decrement ref count for the cause and context of the exception and its sub-exceptions
+            Self::Exception(exc) => exc.collect_chain_ids(stack),
+            // Range, Slice, LongInt, Complex, Path, and code objects have no nested heap references
+            Self::Range(_)
+            | Self::Slice(_)
+            | Self::LongInt(_)
+            | Self::Complex(_)
+            | Self::Date(_)
+            | Self::DateTime(_)
+            | Self::TimeDelta(_)
+            | Self::TimeZone(_)
+            | Self::Path(_)
+            | Self::DataclassType(_)
+            | Self::CodeObject(_) => {}
         }
     }
 
@@ -519,10 +786,19 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_bool(heap, interns),
             Self::Bytes(b) => b.py_bool(heap, interns),
+            Self::ByteArray(b) => b.py_bool(heap, interns),
+            Self::MemoryView(view) => view.py_bool(heap, interns),
+            Self::RePattern(pattern) => pattern.py_bool(heap, interns),
+            Self::ReMatch(m) => m.py_bool(heap, interns),
             Self::List(l) => l.py_bool(heap, interns),
             Self::Tuple(t) => t.py_bool(heap, interns),
             Self::NamedTuple(nt) => nt.py_bool(heap, interns),
+            Self::NamedTupleFactory(factory) => factory.py_bool(heap, interns),
             Self::Dict(d) => d.py_bool(heap, interns),
+            Self::DefaultDict(dd) => dd.py_bool(heap, interns),
+            Self::Counter(c) => c.py_bool(heap, interns),
+            Self::OrderedDict(od) => od.py_bool(heap, interns),
+            Self::Deque(dq) => dq.py_bool(heap, interns),
             Self::Set(s) => s.py_bool(heap, interns),
             Self::FrozenSet(fs) => fs.py_bool(heap, interns),
             Self::Closure(_, _, _) | Self::FunctionDefaults(_, _) => true,
@@ -531,12 +807,25 @@ impl PyTrait for HeapData {
             Self::Slice(s) => s.py_bool(heap, interns),
             Self::Exception(_) => true, // Exceptions are always truthy
             Self::Dataclass(dc) => dc.py_bool(heap, interns),
+            Self::DataclassType(class) => class.py_bool(heap, interns),
             Self::Iter(_) => true, // Iterators are always truthy
             Self::LongInt(li) => !li.is_zero(),
-            Self::Module(_) => true,       // Modules are always truthy
-            Self::Coroutine(_) => true,    // Coroutines are always truthy
-            Self::GatherFuture(_) => true, // GatherFutures are always truthy
+            Self::Complex(c) => c.py_bool(heap, interns),
+            Self::Date(d) => d.py_bool(heap, interns),
+            Self::DateTime(dt) => dt.py_bool(heap, interns),
+            Self::TimeDelta(td) => td.py_bool(heap, interns),
+            Self::TimeZone(tz) => tz.py_bool(heap, interns),
+            Self::Module(_) => true,            // Modules are always truthy
+            Self::Coroutine(_) => true,         // Coroutines are always truthy
+            Self::GatherFuture(_) => true,      // GatherFutures are always truthy
+            Self::Generator(_) => true,         // Generators are always truthy
+            Self::AsyncGenAwaitable(_) => true, // Awaitables are always truthy
             Self::Path(p) => p.py_bool(heap, interns),
+            Self::ClassObject(cls) => cls.py_bool(heap, interns),
+            Self::Instance(inst) => inst.py_bool(heap, interns),
+            Self::BoundMethod(bm) => bm.py_bool(heap, interns),
+            Self::SuperProxy(sp) => sp.py_bool(heap, interns),
+            Self::CodeObject(code) => code.py_bool(heap, interns),
         }
     }
 
@@ -551,10 +840,19 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::Bytes(b) => b.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::ByteArray(b) => b.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::MemoryView(view) => view.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::RePattern(pattern) => pattern.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::ReMatch(m) => m.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::List(l) => l.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::Tuple(t) => t.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::NamedTuple(nt) => nt.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::NamedTupleFactory(factory) => factory.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::Dict(d) => d.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::DefaultDict(dd) => dd.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::Counter(c) => c.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::OrderedDict(od) => od.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::Deque(dq) => dq.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::Set(s) => s.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::FrozenSet(fs) => fs.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::Closure(f_id, _, _) | Self::FunctionDefaults(f_id, _) => {
@@ -566,8 +864,14 @@ impl PyTrait for HeapData {
             Self::Slice(s) => s.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::Exception(e) => e.py_repr_fmt(f),
             Self::Dataclass(dc) => dc.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::DataclassType(class) => class.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::Iter(_) => write!(f, "<iterator>"),
             Self::LongInt(li) => write!(f, "{li}"),
+            Self::Complex(c) => c.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::Date(d) => d.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::DateTime(dt) => dt.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::TimeDelta(td) => td.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::TimeZone(tz) => tz.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::Module(m) => write!(f, "<module '{}'>", interns.get_str(m.name())),
             Self::Coroutine(coro) => {
                 let func = interns.get_function(coro.func_id);
@@ -575,7 +879,23 @@ impl PyTrait for HeapData {
                 write!(f, "<coroutine object {name}>")
             }
             Self::GatherFuture(gather) => write!(f, "<gather({})>", gather.item_count()),
+            Self::Generator(generator) => {
+                let func = interns.get_function(generator.func_id);
+                let name = interns.get_str(func.name.name_id);
+                let kind = if generator.is_async {
+                    "async_generator"
+                } else {
+                    "generator"
+                };
+                write!(f, "<{kind} object {name}>")
+            }
+            Self::AsyncGenAwaitable(_) => write!(f, "<async_generator_asend object>"),
             Self::Path(p) => p.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::ClassObject(cls) => cls.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::Instance(inst) => inst.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::BoundMethod(bm) => bm.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::SuperProxy(sp) => sp.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::CodeObject(code) => code.py_repr_fmt(f, heap, heap_ids, guard, interns),
         }
     }
 
@@ -594,6 +914,11 @@ impl PyTrait for HeapData {
             Self::Exception(e) => Cow::Owned(e.py_str()),
             // Paths return the path string without the PosixPath() wrapper
             Self::Path(p) => Cow::Owned(p.as_str().to_owned()),
+            // The datetime types return their ISO format or, for timezones, their name
+            Self::Date(d) => Cow::Owned(d.to_string()),
+            Self::DateTime(dt) => Cow::Owned(dt.to_string()),
+            Self::TimeDelta(td) => Cow::Owned(td.to_string()),
+            Self::TimeZone(tz) => Cow::Owned(tz.to_string()),
             // All other types use repr
             _ => self.py_repr(heap, guard, interns),
         }
@@ -604,7 +929,7 @@ impl PyTrait for HeapData {
         other: &Self,
         heap: &mut Heap<impl ResourceTracker>,
         interns: &Interns,
-    ) -> Result<Option<Value>, crate::resource::ResourceError> {
+    ) -> RunResult<Option<Value>> {
         match (self, other) {
             (Self::Str(a), Self::Str(b)) => a.py_add(b, heap, interns),
             (Self::Bytes(a), Self::Bytes(b)) => a.py_add(b, heap, interns),
@@ -616,11 +941,7 @@ impl PyTrait for HeapData {
         }
     }
 
-    fn py_sub(
-        &self,
-        other: &Self,
-        heap: &mut Heap<impl ResourceTracker>,
-    ) -> Result<Option<Value>, crate::resource::ResourceError> {
+    fn py_sub(&self, other: &Self, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Option<Value>> {
         match (self, other) {
             (Self::Str(a), Self::Str(b)) => a.py_sub(b, heap),
             (Self::Bytes(a), Self::Bytes(b)) => a.py_sub(b, heap),
@@ -680,6 +1001,7 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_iadd(other, heap, self_id, interns),
             Self::Bytes(b) => b.py_iadd(other, heap, self_id, interns),
+            Self::ByteArray(b) => b.py_iadd(other, heap, self_id, interns),
             Self::List(l) => l.py_iadd(other, heap, self_id, interns),
             Self::Tuple(t) => t.py_iadd(other, heap, self_id, interns),
             Self::Dict(d) => d.py_iadd(other, heap, self_id, interns),
@@ -701,13 +1023,28 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_call_attr(heap, attr, args, interns),
             Self::Bytes(b) => b.py_call_attr(heap, attr, args, interns),
+            Self::ByteArray(b) => b.py_call_attr(heap, attr, args, interns),
+            Self::MemoryView(view) => view.py_call_attr(heap, attr, args, interns),
+            Self::ReMatch(m) => m.py_call_attr(heap, attr, args, interns),
             Self::List(l) => l.py_call_attr(heap, attr, args, interns),
             Self::Tuple(t) => t.py_call_attr(heap, attr, args, interns),
+            Self::NamedTuple(nt) => nt.py_call_attr(heap, attr, args, interns),
+            Self::NamedTupleFactory(factory) => factory.py_call_attr(heap, attr, args, interns),
             Self::Dict(d) => d.py_call_attr(heap, attr, args, interns),
+            Self::DefaultDict(dd) => dd.py_call_attr(heap, attr, args, interns),
+            Self::Counter(c) => c.py_call_attr(heap, attr, args, interns),
+            Self::OrderedDict(od) => od.py_call_attr(heap, attr, args, interns),
+            Self::Deque(dq) => dq.py_call_attr(heap, attr, args, interns),
             Self::Set(s) => s.py_call_attr(heap, attr, args, interns),
             Self::FrozenSet(fs) => fs.py_call_attr(heap, attr, args, interns),
             Self::Dataclass(dc) => dc.py_call_attr(heap, attr, args, interns),
             Self::Path(p) => p.py_call_attr(heap, attr, args, interns),
+            Self::Exception(e) => e.py_call_attr(heap, attr, args, interns),
+            Self::Complex(c) => c.py_call_attr(heap, attr, args, interns),
+            Self::Date(d) => d.py_call_attr(heap, attr, args, interns),
+            Self::DateTime(dt) => dt.py_call_attr(heap, attr, args, interns),
+            Self::TimeDelta(td) => td.py_call_attr(heap, attr, args, interns),
+            Self::TimeZone(tz) => tz.py_call_attr(heap, attr, args, interns),
             _ => Err(ExcType::attribute_error(self.py_type(heap), attr.as_str(interns))),
         }
     }
@@ -735,10 +1072,17 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_getitem(key, heap, interns),
             Self::Bytes(b) => b.py_getitem(key, heap, interns),
+            Self::ByteArray(b) => b.py_getitem(key, heap, interns),
+            Self::MemoryView(view) => view.py_getitem(key, heap, interns),
+            Self::ReMatch(m) => m.py_getitem(key, heap, interns),
             Self::List(l) => l.py_getitem(key, heap, interns),
             Self::Tuple(t) => t.py_getitem(key, heap, interns),
             Self::NamedTuple(nt) => nt.py_getitem(key, heap, interns),
             Self::Dict(d) => d.py_getitem(key, heap, interns),
+            Self::DefaultDict(dd) => dd.py_getitem(key, heap, interns),
+            Self::Counter(c) => c.py_getitem(key, heap, interns),
+            Self::OrderedDict(od) => od.py_getitem(key, heap, interns),
+            Self::Deque(dq) => dq.py_getitem(key, heap, interns),
             Self::Range(r) => r.py_getitem(key, heap, interns),
             _ => Err(ExcType::type_error_not_sub(self.py_type(heap))),
         }
@@ -754,13 +1098,32 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_setitem(key, value, heap, interns),
             Self::Bytes(b) => b.py_setitem(key, value, heap, interns),
+            Self::ByteArray(b) => b.py_setitem(key, value, heap, interns),
+            Self::MemoryView(view) => view.py_setitem(key, value, heap, interns),
             Self::List(l) => l.py_setitem(key, value, heap, interns),
             Self::Tuple(t) => t.py_setitem(key, value, heap, interns),
             Self::Dict(d) => d.py_setitem(key, value, heap, interns),
+            Self::DefaultDict(dd) => dd.py_setitem(key, value, heap, interns),
+            Self::Counter(c) => c.py_setitem(key, value, heap, interns),
+            Self::OrderedDict(od) => od.py_setitem(key, value, heap, interns),
+            Self::Deque(dq) => dq.py_setitem(key, value, heap, interns),
             _ => Err(ExcType::type_error_not_sub_assignment(self.py_type(heap))),
         }
     }
 
+    fn py_delitem(&mut self, key: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<()> {
+        match self {
+            Self::List(l) => l.py_delitem(key, heap, interns),
+            Self::ByteArray(b) => b.py_delitem(key, heap, interns),
+            Self::Dict(d) => d.py_delitem(key, heap, interns),
+            Self::DefaultDict(dd) => dd.py_delitem(key, heap, interns),
+            Self::Counter(c) => c.py_delitem(key, heap, interns),
+            Self::OrderedDict(od) => od.py_delitem(key, heap, interns),
+            Self::Deque(dq) => dq.py_delitem(key, heap, interns),
+            _ => Err(ExcType::type_error_not_sub_deletion(self.py_type(heap))),
+        }
+    }
+
     fn py_getattr(
         &self,
         attr_id: StringId,
@@ -769,11 +1132,23 @@ impl PyTrait for HeapData {
     ) -> RunResult<Option<AttrCallResult>> {
         match self {
             Self::Dataclass(dc) => dc.py_getattr(attr_id, heap, interns),
+            Self::DataclassType(class) => class.py_getattr(attr_id, heap, interns),
             Self::Module(m) => Ok(m.py_getattr(attr_id, heap, interns)),
             Self::NamedTuple(nt) => nt.py_getattr(attr_id, heap, interns),
+            Self::NamedTupleFactory(factory) => factory.py_getattr(attr_id, heap, interns),
+            Self::DefaultDict(dd) => dd.py_getattr(attr_id, heap, interns),
+            Self::Deque(dq) => dq.py_getattr(attr_id, heap, interns),
             Self::Slice(s) => s.py_getattr(attr_id, heap, interns),
             Self::Exception(exc) => exc.py_getattr(attr_id, heap, interns),
             Self::Path(p) => p.py_getattr(attr_id, heap, interns),
+            Self::Complex(c) => c.py_getattr(attr_id, heap, interns),
+            Self::Date(d) => d.py_getattr(attr_id, heap, interns),
+            Self::DateTime(dt) => dt.py_getattr(attr_id, heap, interns),
+            Self::TimeDelta(td) => td.py_getattr(attr_id, heap, interns),
+            Self::TimeZone(tz) => tz.py_getattr(attr_id, heap, interns),
+            Self::MemoryView(view) => view.py_getattr(attr_id, heap, interns),
+            Self::RePattern(pattern) => pattern.py_getattr(attr_id, heap, interns),
+            Self::ReMatch(m) => m.py_getattr(attr_id, heap, interns),
             // All other types don't support attribute access via py_getattr
             _ => Ok(None),
         }
@@ -798,8 +1173,10 @@ impl HashState {
             // FrozenSet is immutable and hashable
             // Range is immutable and hashable
             // Slice is immutable and hashable (like in CPython)
-            // LongInt is immutable and hashable
+            // LongInt, Complex and the datetime types are immutable and hashable
             // NamedTuple is immutable and hashable (like Tuple)
+            // MemoryView is hashable when its source is bytes
+            // RePattern is immutable and hashable
             HeapData::Str(_)
             | HeapData::Bytes(_)
             | HeapData::Tuple(_)
@@ -810,7 +1187,14 @@ impl HashState {
             | HeapData::FunctionDefaults(_, _)
             | HeapData::Range(_)
             | HeapData::Slice(_)
-            | HeapData::LongInt(_) => Self::Unknown,
+            | HeapData::LongInt(_)
+            | HeapData::Complex(_)
+            | HeapData::Date(_)
+            | HeapData::DateTime(_)
+            | HeapData::TimeDelta(_)
+            | HeapData::TimeZone(_)
+            | HeapData::MemoryView(_)
+            | HeapData::RePattern(_) => Self::Unknown,
             // Dataclass hashability depends on the mutable flag
             HeapData::Dataclass(dc) => {
                 if dc.is_frozen() {
@@ -819,17 +1203,32 @@ impl HashState {
                     Self::Unhashable
                 }
             }
-            // Path is immutable and hashable
-            HeapData::Path(_) => Self::Unknown,
+            // Path and dataclass classes are immutable and hashable
+            HeapData::Path(_) | HeapData::DataclassType(_) => Self::Unknown,
+            // User-defined classes and their instances are hashable by identity
+            HeapData::ClassObject(_)
+            | HeapData::Instance(_)
+            | HeapData::BoundMethod(_)
+            | HeapData::SuperProxy(_)
+            | HeapData::ReMatch(_)
+            | HeapData::CodeObject(_)
+            | HeapData::NamedTupleFactory(_) => Self::Unknown,
             // Mutable containers, exceptions, iterators, modules, and async types are unhashable
             HeapData::List(_)
+            | HeapData::ByteArray(_)
             | HeapData::Dict(_)
+            | HeapData::DefaultDict(_)
+            | HeapData::Counter(_)
+            | HeapData::OrderedDict(_)
+            | HeapData::Deque(_)
             | HeapData::Set(_)
             | HeapData::Exception(_)
             | HeapData::Iter(_)
             | HeapData::Module(_)
             | HeapData::Coroutine(_)
-            | HeapData::GatherFuture(_) => Self::Unhashable,
+            | HeapData::GatherFuture(_)
+            | HeapData::Generator(_)
+            | HeapData::AsyncGenAwaitable(_) => Self::Unhashable,
         }
     }
 }
@@ -1146,9 +1545,18 @@ impl<T: ResourceTracker> Heap<T> {
     /// Some(hash) for immutable types (Str, Bytes, hashable Tuple), None
     /// for mutable types (List, Dict).
     ///
+    /// Instances of user-defined classes hash by identity unless the VM has cached the
+    /// result of their `__hash__` method with [`Heap::set_instance_hash`].
+    ///
     /// # Panics
     /// Panics if the value ID is invalid or the value has already been freed.
     pub fn get_or_compute_hash(&mut self, id: HeapId, interns: &Interns) -> Option<u64> {
+        if let HeapData::Instance(instance) = self.get(id)
+            && class_disables_hash(instance.class_id(), self, interns)
+        {
+            return None;
+        }
+
         let entry = self
             .entries
             .get_mut(id.index())
@@ -1162,8 +1570,19 @@ impl<T: ResourceTracker> Heap<T> {
             HashState::Unknown => {}
         }
 
-        // Handle Cell specially - uses identity-based hashing (like Python cell objects)
-        if let Some(HeapData::Cell(_)) = &entry.data {
+        // Handle Cell and user-defined class objects specially - they use identity-based hashing
+        // (like Python objects without a `__hash__` override)
+        if let Some(
+            HeapData::Cell(_)
+            | HeapData::ClassObject(_)
+            | HeapData::Instance(_)
+            | HeapData::BoundMethod(_)
+            | HeapData::SuperProxy(_)
+            | HeapData::ReMatch(_)
+            | HeapData::CodeObject(_)
+            | HeapData::NamedTupleFactory(_),
+        ) = &entry.data
+        {
             let mut hasher = DefaultHasher::new();
             id.hash(&mut hasher);
             let hash = hasher.finish();
@@ -1190,6 +1609,22 @@ impl<T: ResourceTracker> Heap<T> {
         hash
     }
 
+    /// Caches the result of an instance's `__hash__` method as the hash of its heap entry.
+    ///
+    /// Dicts and sets then store the instance under this hash.
+    ///
+    /// # Panics
+    /// Panics if the value ID is invalid or the value has already been freed.
+    pub fn set_instance_hash(&mut self, id: HeapId, hash: u64) {
+        let entry = self
+            .entries
+            .get_mut(id.index())
+            .expect("Heap::set_instance_hash: slot missing")
+            .as_mut()
+            .expect("Heap::set_instance_hash: object already freed");
+        entry.hash_state = HashState::Cached(hash);
+    }
+
     /// Calls an attribute on the heap entry, returning an `AttrCallResult` that may signal
     /// OS or external calls.
     ///
@@ -1445,7 +1880,7 @@ impl<T: ResourceTracker> Heap<T> {
     /// Multiplies (repeats) a sequence by an integer count.
     ///
     /// This method handles sequence repetition for Python's `*` operator when applied
-    /// to sequences (str, bytes, list, tuple). It creates a new heap-allocated sequence
+    /// to sequences (str, bytes, bytearray, list, tuple). It creates a new heap-allocated sequence
     /// with the elements repeated `count` times.
     ///
     /// # Arguments
@@ -1473,6 +1908,13 @@ impl<T: ResourceTracker> Heap<T> {
                 restore_data!(self, id, data, "mult_sequence");
                 Ok(Some(Value::Ref(self.allocate(HeapData::Bytes(repeated.into()))?)))
             }
+            HeapData::ByteArray(b) => {
+                let result = check_repeat_size(b.as_slice().len(), count, &self.tracker);
+                let repeated = b.as_slice().repeat(if result.is_ok() { count } else { 0 });
+                restore_data!(self, id, data, "mult_sequence");
+                result?;
+                Ok(Some(Value::Ref(self.allocate(HeapData::ByteArray(repeated.into()))?)))
+            }
             HeapData::List(list) => {
                 if count == 0 {
                     restore_data!(self, id, data, "mult_sequence");
@@ -1644,6 +2086,17 @@ impl<T: ResourceTracker> Heap<T> {
     }
 }
 
+/// Hashes the items of a tuple or named tuple, `None` if any item is unhashable.
+fn hash_tuple_items(items: &[Value], heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Option<u64> {
+    let mut hasher = DefaultHasher::new();
+    Type::Tuple.hash(&mut hasher);
+    for obj in items {
+        let h = obj.py_hash(heap, interns)?;
+        h.hash(&mut hasher);
+    }
+    Some(hasher.finish())
+}
+
 /// Computes the number of significant bits in an `i64`.
 ///
 /// Returns 0 for zero, otherwise returns the position of the highest set bit
@@ -1688,11 +2141,22 @@ fn collect_child_ids(data: &HeapData, work_list: &mut Vec<HeapId>) {
         // Leaf types with no heap references
         HeapData::Str(_)
         | HeapData::Bytes(_)
+        | HeapData::ByteArray(_)
+        | HeapData::RePattern(_)
         | HeapData::Range(_)
-        | HeapData::Exception(_)
         | HeapData::LongInt(_)
+        | HeapData::Complex(_)
+        | HeapData::Date(_)
+        | HeapData::DateTime(_)
+        | HeapData::TimeDelta(_)
+        | HeapData::TimeZone(_)
         | HeapData::Slice(_)
-        | HeapData::Path(_) => {}
+        | HeapData::Path(_)
+        | HeapData::DataclassType(_)
+        | HeapData::CodeObject(_) => {}
+        HeapData::MemoryView(view) => work_list.push(view.obj()),
+        HeapData::ReMatch(m) => work_list.extend(m.child_ids()),
+        HeapData::Exception(exc) => exc.collect_chain_ids(work_list),
         HeapData::List(list) => {
             // Skip iteration if no refs - major GC optimization for lists of primitives
             if !list.contains_refs() {
@@ -1726,16 +2190,38 @@ fn collect_child_ids(data: &HeapData, work_list: &mut Vec<HeapId>) {
                 }
             }
         }
-        HeapData::Dict(dict) => {
+        HeapData::Dict(_) | HeapData::DefaultDict(_) | HeapData::Counter(_) | HeapData::OrderedDict(_) => {
+            let dict = data.as_dict().expect("dict types have a dict");
             // Skip iteration if no refs - major GC optimization for dicts of primitives
-            if !dict.has_refs() {
+            if dict.has_refs() {
+                for (k, v) in dict {
+                    if let Value::Ref(id) = k {
+                        work_list.push(*id);
+                    }
+                    if let Value::Ref(id) = v {
+                        work_list.push(*id);
+                    }
+                }
+            }
+            if let HeapData::DefaultDict(dd) = data
+                && let Value::Ref(id) = dd.default_factory()
+            {
+                work_list.push(*id);
+            }
+        }
+        HeapData::Deque(deque) => {
+            if !deque.contains_refs() {
                 return;
             }
-            for (k, v) in dict {
-                if let Value::Ref(id) = k {
+            for value in deque.iter() {
+                if let Value::Ref(id) = value {
                     work_list.push(*id);
                 }
-                if let Value::Ref(id) = v {
+            }
+        }
+        HeapData::NamedTupleFactory(factory) => {
+            for default in factory.defaults() {
+                if let Value::Ref(id) = default {
                     work_list.push(*id);
                 }
             }
@@ -1791,6 +2277,44 @@ fn collect_child_ids(data: &HeapData, work_list: &mut Vec<HeapId>) {
                 }
             }
         }
+        HeapData::ClassObject(cls) => {
+            if let Some(base) = cls.base() {
+                work_list.push(base);
+            }
+            for (k, v) in cls.attrs() {
+                if let Value::Ref(id) = k {
+                    work_list.push(*id);
+                }
+                if let Value::Ref(id) = v {
+                    work_list.push(*id);
+                }
+            }
+        }
+        HeapData::Instance(inst) => {
+            work_list.push(inst.class_id());
+            for (k, v) in inst.attrs() {
+                if let Value::Ref(id) = k {
+                    work_list.push(*id);
+                }
+                if let Value::Ref(id) = v {
+                    work_list.push(*id);
+                }
+            }
+        }
+        HeapData::BoundMethod(bm) => {
+            if let Value::Ref(id) = bm.func() {
+                work_list.push(*id);
+            }
+            if let Value::Ref(id) = bm.self_value() {
+                work_list.push(*id);
+            }
+        }
+        HeapData::SuperProxy(sp) => {
+            work_list.push(sp.class_id());
+            if let Value::Ref(id) = sp.instance() {
+                work_list.push(*id);
+            }
+        }
         HeapData::Iter(iter) => {
             // Iterator holds a reference to the iterable being iterated
             if let Value::Ref(id) = iter.value() {
@@ -1837,6 +2361,11 @@ fn collect_child_ids(data: &HeapData, work_list: &mut Vec<HeapId>) {
                 }
             }
         }
+        HeapData::Generator(generator) => {
+            // Add saved namespace, stack and exception values (cells are in the namespace)
+            work_list.extend(generator.values().filter_map(Value::ref_id));
+        }
+        HeapData::AsyncGenAwaitable(awaitable) => work_list.extend(awaitable.ref_ids()),
     }
 }
 
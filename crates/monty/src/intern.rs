@@ -18,7 +18,7 @@ use ahash::AHashMap;
 use num_bigint::BigInt;
 use strum::{EnumString, FromRepr, IntoStaticStr};
 
-use crate::{function::Function, value::Value};
+use crate::{eval::EvalKey, function::Function, value::Value};
 
 /// Index into the string interner's storage.
 ///
@@ -52,7 +52,7 @@ impl StringId {
 
 /// StringId offsets
 const STATIC_STRING_ID_OFFSET: u32 = 1000;
-const INTERN_STRING_ID_OFFSET: usize = 10_000;
+pub(crate) const INTERN_STRING_ID_OFFSET: usize = 10_000;
 
 /// Static strings for all 128 ASCII characters, built once on first access.
 ///
@@ -69,7 +69,7 @@ static ASCII_STRS: LazyLock<[&'static str; 128]> = LazyLock::new(|| {
 });
 
 /// Static string values which are known at compile time and don't need to be interned.
-#[repr(u8)]
+#[repr(u16)]
 #[derive(
     Debug, Clone, Copy, FromRepr, EnumString, IntoStaticStr, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
 )]
@@ -175,6 +175,9 @@ pub enum StaticStrings {
     Encode,
     Isidentifier,
     Istitle,
+    Format,
+    #[strum(serialize = "format_map")]
+    FormatMap,
 
     // ==========================
     // Bytes methods
@@ -188,6 +191,31 @@ pub enum StaticStrings {
     Hex,
     Fromhex,
 
+    // ==========================
+    // Generator methods
+    Send,
+    Throw,
+    Close,
+    #[strum(serialize = "__next__")]
+    DunderNext,
+    Asend,
+    Aclose,
+    #[strum(serialize = "__aiter__")]
+    DunderAiter,
+    #[strum(serialize = "__anext__")]
+    DunderAnext,
+
+    // ==========================
+    // Context manager protocol
+    #[strum(serialize = "__enter__")]
+    DunderEnter,
+    #[strum(serialize = "__exit__")]
+    DunderExit,
+    #[strum(serialize = "__aenter__")]
+    DunderAenter,
+    #[strum(serialize = "__aexit__")]
+    DunderAexit,
+
     // ==========================
     // sys module strings
     #[strum(serialize = "sys")]
@@ -321,14 +349,239 @@ pub enum StaticStrings {
     #[strum(serialize = "default")]
     Default,
 
+    // ==========================
+    // os.path module strings
+    #[strum(serialize = "os.path")]
+    OsPath,
+    Path,
+    Basename,
+    Dirname,
+    Splitext,
+    Isabs,
+    Isfile,
+    Isdir,
+
+    // ==========================
+    // json module strings
+    #[strum(serialize = "json")]
+    Json,
+    Dumps,
+    Loads,
+    #[strum(serialize = "JSONDecodeError")]
+    JsonDecodeError,
+
+    // ==========================
+    // re module strings
+    // Single letter flag aliases (`re.I`, `re.M`...) use the ASCII string ids
+    Re,
+    Compile,
+    Search,
+    Match,
+    Fullmatch,
+    Findall,
+    Finditer,
+    Sub,
+    Subn,
+    Escape,
+    Error,
+    #[strum(serialize = "PatternError")]
+    PatternError,
+    #[strum(serialize = "Pattern")]
+    PatternClass,
+    #[strum(serialize = "Match")]
+    MatchClass,
+    #[strum(serialize = "NOFLAG")]
+    Noflag,
+    #[strum(serialize = "IGNORECASE")]
+    Ignorecase,
+    #[strum(serialize = "MULTILINE")]
+    Multiline,
+    #[strum(serialize = "DOTALL")]
+    Dotall,
+    #[strum(serialize = "VERBOSE")]
+    Verbose,
+    #[strum(serialize = "ASCII")]
+    Ascii,
+    #[strum(serialize = "UNICODE")]
+    Unicode,
+    // Pattern and match attributes and methods
+    // Also uses shared: SPLIT, START
+    Pattern,
+    Flags,
+    Groups,
+    Groupindex,
+    Group,
+    Groupdict,
+    End,
+    Span,
+    Expand,
+    #[strum(serialize = "string")]
+    StringAttr,
+    Pos,
+    Endpos,
+    Lastindex,
+    Lastgroup,
+
+    // ==========================
+    // math module strings
+    // `math.e` uses the ASCII string id
+    Math,
+    Pi,
+    Tau,
+    Inf,
+    Nan,
+    Sqrt,
+    Isqrt,
+    Exp,
+    Log,
+    #[strum(serialize = "log2")]
+    Log2,
+    #[strum(serialize = "log10")]
+    Log10,
+    Pow,
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    #[strum(serialize = "atan2")]
+    Atan2,
+    Sinh,
+    Cosh,
+    Tanh,
+    Hypot,
+    Degrees,
+    Radians,
+    Fabs,
+    Fmod,
+    Copysign,
+    Floor,
+    Ceil,
+    Trunc,
+    Isclose,
+    Isnan,
+    Isinf,
+    Isfinite,
+    Gcd,
+    Lcm,
+    Factorial,
+    Comb,
+    Perm,
+    Prod,
+    Fsum,
+
+    // ==========================
+    // datetime and time module strings
+    // `datetime.datetime` and `time.time` reuse the module names
+    Datetime,
+    Time,
+    Date,
+    Timedelta,
+    Timezone,
+    #[strum(serialize = "MINYEAR")]
+    Minyear,
+    #[strum(serialize = "MAXYEAR")]
+    Maxyear,
+    #[strum(serialize = "UTC")]
+    UtcConst,
+    Monotonic,
+    Sleep,
+    // Class methods and attributes
+    Today,
+    Now,
+    Fromisoformat,
+    Fromordinal,
+    Fromtimestamp,
+    Strptime,
+    Min,
+    Max,
+    Resolution,
+    Utc,
+    // Instance methods and attributes
+    // Also uses shared: REPLACE
+    Isoformat,
+    Strftime,
+    Weekday,
+    Isoweekday,
+    Toordinal,
+    Isocalendar,
+    Timestamp,
+    Utcoffset,
+    Tzname,
+    Dst,
+    Astimezone,
+    TotalSeconds,
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    Microsecond,
+    Tzinfo,
+    Days,
+    Seconds,
+    Microseconds,
+
+    // ==========================
+    // collections module strings
+    Collections,
+    Namedtuple,
+    Defaultdict,
+    Deque,
+    #[strum(serialize = "Counter")]
+    Counter,
+    #[strum(serialize = "OrderedDict")]
+    OrderedDict,
+    // deque methods and attributes
+    // Also uses shared: APPEND, POP, EXTEND, INSERT, REMOVE, INDEX, COUNT, CLEAR, COPY, REVERSE
+    Appendleft,
+    Popleft,
+    Extendleft,
+    Rotate,
+    Maxlen,
+    // Counter, OrderedDict and defaultdict methods and attributes
+    MostCommon,
+    Elements,
+    Total,
+    Subtract,
+    MoveToEnd,
+    DefaultFactory,
+    // namedtuple methods and attributes
+    #[strum(serialize = "_asdict")]
+    UnderscoreAsdict,
+    #[strum(serialize = "_replace")]
+    UnderscoreReplace,
+    #[strum(serialize = "_make")]
+    UnderscoreMake,
+    #[strum(serialize = "_fields")]
+    UnderscoreFields,
+    #[strum(serialize = "_field_defaults")]
+    UnderscoreFieldDefaults,
+
     // ==========================
     // Exception attributes
     Args,
+    #[strum(serialize = "__cause__")]
+    DunderCause,
+    #[strum(serialize = "__context__")]
+    DunderContext,
+    #[strum(serialize = "__suppress_context__")]
+    DunderSuppressContext,
+    // Exception group attributes and methods
+    Exceptions,
+    Message,
+    Subgroup,
 
     // ==========================
     // Type attributes
     #[strum(serialize = "__name__")]
     DunderName,
+    #[strum(serialize = "__class__")]
+    DunderClass,
+    #[strum(serialize = "__hash__")]
+    DunderHash,
 
     // ==========================
     // pathlib module strings
@@ -407,6 +660,19 @@ pub enum StaticStrings {
     Start,
     Stop,
     Step,
+
+    // Complex attributes and methods
+    Real,
+    Imag,
+    Conjugate,
+
+    // Memoryview attributes and methods
+    Tobytes,
+    Tolist,
+    Release,
+    Readonly,
+    Nbytes,
+    Obj,
 }
 
 impl StaticStrings {
@@ -416,7 +682,7 @@ impl StaticStrings {
     /// (e.g., it's an ASCII char or a dynamically interned string).
     pub fn from_string_id(id: StringId) -> Option<Self> {
         let enum_id = id.0.checked_sub(STATIC_STRING_ID_OFFSET)?;
-        u8::try_from(enum_id).ok().and_then(Self::from_repr)
+        u16::try_from(enum_id).ok().and_then(Self::from_repr)
     }
 }
 
@@ -563,6 +829,33 @@ impl InternerBuilder {
         }
     }
 
+    /// Creates an interner holding the strings, bytes and long integers of `interns`.
+    ///
+    /// Used to parse code passed to `eval()` or `exec()`, whose names must get the same
+    /// `StringId`s as in the running program.
+    pub(crate) fn from_interns(interns: &Interns) -> Self {
+        let string_map = interns
+            .strings
+            .iter()
+            .enumerate()
+            .map(|(index, s)| {
+                let id = StringId((index + INTERN_STRING_ID_OFFSET).try_into().expect("StringId overflow"));
+                (s.clone(), id)
+            })
+            .collect();
+        Self {
+            string_map,
+            strings: interns.strings.clone(),
+            bytes: interns.bytes.clone(),
+            long_ints: interns.long_ints.clone(),
+        }
+    }
+
+    /// Returns the number of strings interned beyond the pre-interned ones.
+    pub fn string_count(&self) -> usize {
+        self.strings.len()
+    }
+
     /// Interns a string, returning its `StringId`.
     ///
     /// * If the string is ascii, return the pre-interned string id
@@ -635,6 +928,9 @@ pub(crate) struct Interns {
     long_ints: Vec<BigInt>,
     functions: Vec<Function>,
     external_functions: Vec<String>,
+    /// Functions compiled from code passed to `eval()` or `exec()`, so running the same code
+    /// again reuses them.
+    eval_functions: AHashMap<EvalKey, FunctionId>,
 }
 
 impl Interns {
@@ -645,6 +941,7 @@ impl Interns {
             long_ints: interner.long_ints,
             functions,
             external_functions,
+            eval_functions: AHashMap::new(),
         }
     }
 
@@ -658,6 +955,31 @@ impl Interns {
         get_str(&self.strings, id)
     }
 
+    /// Returns the `StringId` of `s` if it was interned, without interning it.
+    ///
+    /// Used to look up attributes named by runtime strings, e.g. in `str.format()` fields.
+    pub fn find_str(&self, s: &str) -> Option<StringId> {
+        if s.len() == 1 {
+            Some(StringId::from_ascii(s.as_bytes()[0]))
+        } else if let Ok(ss) = StaticStrings::from_str(s) {
+            Some(ss.into())
+        } else {
+            let index = self.strings.iter().position(|interned| interned == s)?;
+            Some(StringId(
+                (index + INTERN_STRING_ID_OFFSET).try_into().expect("StringId overflow"),
+            ))
+        }
+    }
+
+    /// Interns an attribute name built at runtime and passed to `setattr()`, returning its `StringId`.
+    pub fn intern_name(&mut self, name: &str) -> StringId {
+        self.find_str(name).unwrap_or_else(|| {
+            let index = self.strings.len() + INTERN_STRING_ID_OFFSET;
+            self.strings.push(name.to_owned());
+            StringId(index.try_into().expect("StringId overflow"))
+        })
+    }
+
     /// Looks up bytes by their `BytesId`.
     ///
     /// # Panics
@@ -708,4 +1030,64 @@ impl Interns {
     pub fn set_functions(&mut self, functions: Vec<Function>) {
         self.functions = functions;
     }
+
+    /// Returns the number of compiled functions, which is the ID the next one will get.
+    pub fn function_count(&self) -> usize {
+        self.functions.len()
+    }
+
+    /// Looks up the function compiled for code passed to `eval()` or `exec()`.
+    pub fn get_eval_function(&self, key: &EvalKey) -> Option<FunctionId> {
+        self.eval_functions.get(key).copied()
+    }
+
+    /// Returns how many pieces of code passed to `eval()` or `exec()` have been compiled.
+    pub fn eval_function_count(&self) -> usize {
+        self.eval_functions.len()
+    }
+
+    /// Returns the number of interned strings.
+    pub fn string_count(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Estimates the memory used by the functions and strings added since there were
+    /// `function_count` functions and `string_count` strings.
+    ///
+    /// Used to charge code compiled by `eval()` or `exec()`, and names interned by `setattr()`,
+    /// to the resource tracker.
+    pub fn size_since(&self, function_count: usize, string_count: usize) -> usize {
+        let functions: usize = self.functions[function_count..]
+            .iter()
+            .map(Function::estimate_size)
+            .sum();
+        let strings: usize = self.strings[string_count..]
+            .iter()
+            .map(|s| s.len() + std::mem::size_of::<String>())
+            .sum();
+        functions + strings
+    }
+
+    /// Replaces the strings, bytes and long integers with those of `interner`.
+    ///
+    /// Used to add the literals and names of code passed to `eval()` or `exec()`; `interner`
+    /// must have been created with [`InternerBuilder::from_interns`] from these interns, so
+    /// existing IDs stay valid.
+    pub fn extend_from_interner(&mut self, interner: InternerBuilder) {
+        self.strings = interner.strings;
+        self.bytes = interner.bytes;
+        self.long_ints = interner.long_ints;
+    }
+
+    /// Adds the functions compiled for code passed to `eval()` or `exec()`.
+    ///
+    /// `functions` must start at ID `function_count()`. The last of them runs the code and
+    /// is recorded under `key`, its ID is returned.
+    pub fn add_eval_functions(&mut self, key: EvalKey, functions: Vec<Function>) -> FunctionId {
+        self.functions.extend(functions);
+        let index = self.functions.len() - 1;
+        let id = FunctionId(index.try_into().expect("FunctionId overflow"));
+        self.eval_functions.insert(key, id);
+        id
+    }
 }
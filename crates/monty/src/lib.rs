@@ -7,6 +7,7 @@ mod asyncio;
 mod ffi;
 mod builtins;
 mod bytecode;
+mod eval;
 mod exception_private;
 mod exception_public;
 mod expressions;
@@ -19,6 +20,7 @@ mod namespace;
 mod object;
 mod os;
 mod parse;
+mod percent_format;
 mod prepare;
 mod resource;
 mod run;
@@ -1,21 +1,21 @@
 //! Implementation of the `asyncio` module.
 //!
 //! Provides a minimal implementation of Python's `asyncio` module with only:
-//! - `gather(*awaitables)`: Collects coroutines for concurrent execution
+//! - `gather(*awaitables, return_exceptions=False)`: Collects coroutines for concurrent execution
 //!
 //! Other asyncio functions (`create_task`, `sleep`, `wait`, etc.) are not implemented.
 //! The host acts as the event loop - Monty yields control when tasks are blocked.
 
 use crate::{
-    args::ArgValues,
+    args::{ArgValues, KwargsValues},
     asyncio::{GatherFuture, GatherItem},
-    defer_drop_mut,
+    defer_drop, defer_drop_mut,
     exception_private::{ExcType, RunResult},
     heap::{Heap, HeapData, HeapId},
     intern::{Interns, StaticStrings},
     modules::ModuleFunctions,
     resource::{ResourceError, ResourceTracker},
-    types::{AttrCallResult, Module},
+    types::{AttrCallResult, Module, PyTrait},
     value::Value,
 };
 
@@ -53,13 +53,14 @@ pub(super) fn call(
     heap: &mut Heap<impl ResourceTracker>,
     functions: AsyncioFunctions,
     args: ArgValues,
+    interns: &Interns,
 ) -> RunResult<AttrCallResult> {
     match functions {
-        AsyncioFunctions::Gather => gather(heap, args).map(AttrCallResult::Value),
+        AsyncioFunctions::Gather => gather(heap, args, interns).map(AttrCallResult::Value),
     }
 }
 
-/// Implementation of `asyncio.gather(*awaitables)`.
+/// Implementation of `asyncio.gather(*awaitables, return_exceptions=False)`.
 ///
 /// Collects coroutines and external futures for concurrent execution. Does NOT
 /// spawn tasks immediately - just validates and stores the references. Tasks are
@@ -71,20 +72,22 @@ pub(super) fn call(
 /// 2. External futures are tracked for resolution by the host
 /// 3. The current task blocks until all items complete
 /// 4. Results are collected in order and returned as a list
-/// 5. On any task failure, sibling tasks are cancelled and the exception propagates
+/// 5. On any task failure, sibling tasks are cancelled and the exception propagates,
+///    unless `return_exceptions` is true, in which case the exception is the task's result
 ///
 /// # Arguments
 /// * `heap` - The heap for allocating the GatherFuture
-/// * `args` - Variadic awaitable arguments (coroutines or external futures)
+/// * `args` - Variadic awaitable arguments (coroutines or external futures), and the
+///   optional `return_exceptions` keyword argument
+/// * `interns` - For reading keyword argument names
 ///
 /// # Errors
-/// Returns `TypeError` if any argument is not awaitable.
-pub(crate) fn gather(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+/// Returns `TypeError` if any argument is not awaitable, or for unknown keyword arguments.
+pub(crate) fn gather(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
     let (pos_args, kwargs) = args.into_parts();
     defer_drop_mut!(pos_args, heap);
 
-    // TODO: support keyword arguments (e.g. return_exceptions)
-    kwargs.not_supported_yet("gather", heap)?;
+    let return_exceptions = gather_return_exceptions(kwargs, heap, interns)?;
 
     // Validate all positional args are awaitable and collect them
     let mut items = Vec::new();
@@ -119,7 +122,35 @@ pub(crate) fn gather(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) ->
     }
 
     // Create GatherFuture on heap
-    let gather_future = GatherFuture::new(items);
+    let gather_future = GatherFuture::new(items, return_exceptions);
     let id = heap.allocate(HeapData::GatherFuture(gather_future))?;
     Ok(Value::Ref(id))
 }
+
+/// Parses the keyword arguments of `gather()`, returning whether `return_exceptions` is set.
+fn gather_return_exceptions(
+    kwargs: KwargsValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<bool> {
+    let kwargs = kwargs.into_iter();
+    defer_drop_mut!(kwargs, heap);
+
+    let mut return_exceptions = false;
+    for (key, value) in kwargs {
+        defer_drop!(key, heap);
+        defer_drop!(value, heap);
+
+        let Some(keyword_name) = key.as_either_str(heap) else {
+            return Err(ExcType::type_error("keywords must be strings"));
+        };
+        let key_str = keyword_name.as_str(interns);
+        if key_str != "return_exceptions" {
+            return Err(ExcType::type_error(format!(
+                "gather() got an unexpected keyword argument '{key_str}'"
+            )));
+        }
+        return_exceptions = value.py_bool(heap, interns);
+    }
+    Ok(return_exceptions)
+}
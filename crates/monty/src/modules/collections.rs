@@ -0,0 +1,262 @@
+//! Implementation of the `collections` module.
+//!
+//! Provides native implementations of Python's container datatypes:
+//! - `deque`: a double-ended queue with an optional `maxlen`
+//! - `defaultdict`: a dict that calls `default_factory` for missing keys
+//! - `Counter`: a dict of counts with `most_common()`, `elements()`, `total()` and
+//!   counter arithmetic (`+`, `-`, `|`, `&`)
+//! - `OrderedDict`: a dict with `move_to_end()`, `popitem(last=...)` and order-sensitive equality
+//! - `namedtuple(typename, field_names, *, rename=False, defaults=None, module=None)`: creates
+//!   tuple classes with named fields
+//!
+//! The container types are builtin types, see `types::deque`, `types::defaultdict`,
+//! `types::counter`, `types::ordereddict` and `types::namedtuple`.
+//!
+//! Differences from CPython:
+//! - the types can't be subclassed
+//! - `c += d` on counters builds a new counter instead of updating `c` in place
+//! - `OrderedDict.fromkeys()` returns a plain dict
+//! - named tuple classes can't be subclassed and only have `_make()`, `_fields` and
+//!   `_field_defaults` as class attributes
+
+use crate::{
+    args::{ArgValues, bind_args},
+    builtins::Builtins,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    modules::ModuleFunctions,
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{
+        AttrCallResult, Module, MontyIter, NamedTupleFactory, PyTrait, Type,
+        str::{StringRepr, str_isidentifier},
+    },
+    value::{EitherStr, Value},
+};
+
+/// `collections` module functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum CollectionsFunctions {
+    Namedtuple,
+}
+
+/// Python's hard keywords, which can't be used as named tuple type or field names.
+const KEYWORDS: [&str; 35] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+    "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal",
+    "not", "or", "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+/// Creates the `collections` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Collections);
+
+    let types = [
+        (StaticStrings::Deque, Type::Deque),
+        (StaticStrings::Defaultdict, Type::DefaultDict),
+        (StaticStrings::Counter, Type::Counter),
+        (StaticStrings::OrderedDict, Type::OrderedDict),
+    ];
+    for (name, ty) in types {
+        module.set_attr(name, Value::Builtin(Builtins::Type(ty)), heap, interns);
+    }
+    module.set_attr(
+        StaticStrings::Namedtuple,
+        Value::ModuleFunction(ModuleFunctions::Collections(CollectionsFunctions::Namedtuple)),
+        heap,
+        interns,
+    );
+
+    heap.allocate(HeapData::Module(module))
+}
+
+/// Dispatches a call to a `collections` module function.
+pub(super) fn call(
+    heap: &mut Heap<impl ResourceTracker>,
+    function: CollectionsFunctions,
+    args: ArgValues,
+    interns: &Interns,
+) -> RunResult<AttrCallResult> {
+    let value = match function {
+        CollectionsFunctions::Namedtuple => namedtuple(heap, args, interns)?,
+    };
+    Ok(AttrCallResult::Value(value))
+}
+
+/// Implementation of `collections.namedtuple(typename, field_names, *, rename=False, defaults=None, module=None)`.
+///
+/// `field_names` is either a string of names separated by whitespace and/or commas, or an
+/// iterable of names. Names are validated in the same order as CPython so the same error is raised.
+fn namedtuple(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    // `rename`, `defaults` and `module` are keyword-only
+    let count = args.count();
+    if count > 2 {
+        args.drop_with_heap(heap);
+        return Err(ExcType::type_error_too_many_positional("namedtuple", 2, count, 0));
+    }
+    let [typename, field_names, rename, defaults, module] = bind_args(
+        "namedtuple",
+        ["typename", "field_names", "rename", "defaults", "module"],
+        2,
+        args,
+        heap,
+        interns,
+    )?
+    .0;
+    let typename = typename.expect("typename is required");
+    let field_names = field_names.expect("field_names is required");
+
+    let mut guard = DepthGuard::default();
+    let typename_str = typename.py_str(heap, &mut guard, interns).into_owned();
+    typename.drop_with_heap(heap);
+    let module_str = match module {
+        None | Some(Value::None) => None,
+        Some(module) => {
+            let result = module.py_str(heap, &mut guard, interns).into_owned();
+            module.drop_with_heap(heap);
+            Some(result)
+        }
+    };
+    let rename = rename.is_some_and(|rename| {
+        let result = rename.py_bool(heap, interns);
+        rename.drop_with_heap(heap);
+        result
+    });
+
+    let names = match field_name_strings(field_names, heap, interns) {
+        Ok(names) => names,
+        Err(err) => {
+            defaults.drop_with_heap(heap);
+            return Err(err);
+        }
+    };
+    let names = match validate_names(&typename_str, names, rename) {
+        Ok(names) => names,
+        Err(err) => {
+            defaults.drop_with_heap(heap);
+            return Err(err);
+        }
+    };
+
+    let defaults: Vec<Value> = match defaults {
+        None | Some(Value::None) => Vec::new(),
+        Some(defaults) => {
+            let mut iter = MontyIter::new(defaults, heap, interns)?;
+            let items: RunResult<Vec<Value>> = iter.collect(heap, interns);
+            iter.drop_with_heap(heap);
+            items?
+        }
+    };
+    if defaults.len() > names.len() {
+        defaults.drop_with_heap(heap);
+        return Err(ExcType::type_error("Got more default values than field names"));
+    }
+
+    let factory = NamedTupleFactory::new(
+        EitherStr::from(typename_str),
+        module_str.map(EitherStr::from),
+        names.into_iter().map(EitherStr::from).collect(),
+        defaults,
+    );
+    Ok(Value::Ref(heap.allocate(HeapData::NamedTupleFactory(factory))?))
+}
+
+/// Converts the `field_names` argument to a list of names.
+///
+/// A string is split on whitespace and commas, any other iterable has its items converted with `str()`.
+fn field_name_strings(
+    field_names: Value,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Vec<String>> {
+    if let Some(names) = field_names.as_either_str(heap) {
+        let names = names
+            .as_str(interns)
+            .replace(',', " ")
+            .split_whitespace()
+            .map(str::to_owned)
+            .collect();
+        field_names.drop_with_heap(heap);
+        return Ok(names);
+    }
+
+    let mut iter = MontyIter::new(field_names, heap, interns)?;
+    let mut guard = DepthGuard::default();
+    let mut names = Vec::new();
+    loop {
+        match iter.for_next(heap, interns) {
+            Ok(Some(item)) => {
+                names.push(item.py_str(heap, &mut guard, interns).into_owned());
+                item.drop_with_heap(heap);
+            }
+            Ok(None) => break,
+            Err(err) => {
+                iter.drop_with_heap(heap);
+                return Err(err);
+            }
+        }
+    }
+    iter.drop_with_heap(heap);
+    Ok(names)
+}
+
+/// Renames invalid field names if `rename` is set, then checks the type and field names.
+fn validate_names(typename: &str, mut names: Vec<String>, rename: bool) -> RunResult<Vec<String>> {
+    if rename {
+        let mut seen = Vec::with_capacity(names.len());
+        for (index, name) in names.iter_mut().enumerate() {
+            let original = name.clone();
+            if !str_isidentifier(name)
+                || KEYWORDS.contains(&name.as_str())
+                || name.starts_with('_')
+                || seen.contains(name)
+            {
+                *name = format!("_{index}");
+            }
+            seen.push(original);
+        }
+    }
+
+    for name in std::iter::once(typename).chain(names.iter().map(String::as_str)) {
+        if !str_isidentifier(name) {
+            return Err(value_error(format!(
+                "Type names and field names must be valid identifiers: {}",
+                StringRepr(name)
+            )));
+        }
+        if KEYWORDS.contains(&name) {
+            return Err(value_error(format!(
+                "Type names and field names cannot be a keyword: {}",
+                StringRepr(name)
+            )));
+        }
+    }
+
+    for (index, name) in names.iter().enumerate() {
+        if name.starts_with('_') && !rename {
+            return Err(value_error(format!(
+                "Field names cannot start with an underscore: {}",
+                StringRepr(name)
+            )));
+        }
+        if names[..index].contains(name) {
+            return Err(value_error(format!(
+                "Encountered duplicate field name: {}",
+                StringRepr(name)
+            )));
+        }
+    }
+    Ok(names)
+}
+
+/// Creates a `ValueError` with the given message.
+fn value_error(msg: String) -> RunError {
+    SimpleException::new_msg(ExcType::ValueError, msg).into()
+}
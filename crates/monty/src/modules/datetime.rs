@@ -0,0 +1,54 @@
+//! Implementation of the `datetime` module.
+//!
+//! Provides Python's `datetime` module with:
+//! - the types `date`, `datetime`, `timedelta` and `timezone`
+//! - the constants `MINYEAR`, `MAXYEAR` and `UTC`
+//!
+//! The types live in `crate::types`; this module only exposes them. Reading the current time with
+//! `date.today()`, `datetime.today()` or `datetime.now()` yields `OsFunction::Time` to the host.
+//!
+//! Differences from CPython:
+//! - `timezone` only supports fixed offsets, and there is no `time` or `tzinfo` type
+//! - there is no local timezone: the host's seconds since the epoch are read as UTC, so
+//!   `date.today()`, `datetime.today()` and `datetime.now()` without a timezone return the UTC
+//!   date and time, and `fromtimestamp()` and `timestamp()` treat naive datetimes as UTC
+
+use crate::{
+    builtins::Builtins,
+    heap::{Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    resource::{ResourceError, ResourceTracker},
+    types::{
+        Module, TimeZone, Type,
+        date::{MAX_YEAR, MIN_YEAR},
+    },
+    value::Value,
+};
+
+/// Creates the `datetime` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Datetime);
+
+    let types = [
+        (StaticStrings::Date, Type::Date),
+        (StaticStrings::Datetime, Type::DateTime),
+        (StaticStrings::Timedelta, Type::TimeDelta),
+        (StaticStrings::Timezone, Type::TimeZone),
+    ];
+    for (name, t) in types {
+        module.set_attr(name, Value::Builtin(Builtins::Type(t)), heap, interns);
+    }
+
+    module.set_attr(StaticStrings::Minyear, Value::Int(i64::from(MIN_YEAR)), heap, interns);
+    module.set_attr(StaticStrings::Maxyear, Value::Int(i64::from(MAX_YEAR)), heap, interns);
+    let utc = TimeZone::UTC.into_value(heap)?;
+    module.set_attr(StaticStrings::UtcConst, utc, heap, interns);
+
+    heap.allocate(HeapData::Module(module))
+}
@@ -0,0 +1,995 @@
+//! Implementation of the `json` module.
+//!
+//! Provides a native implementation of Python's `json` module with:
+//! - `dumps(obj, *, skipkeys, ensure_ascii, check_circular, allow_nan, indent, separators,
+//!   default, sort_keys)`: serializes `obj` to a JSON string
+//! - `loads(s)`: parses a `str`, `bytes` or `bytearray` JSON document
+//! - `JSONDecodeError`: the `ValueError` subclass raised by `loads()`, its message has the
+//!   line and column of the error
+//!
+//! Values map to JSON the same way as `MontyObject`'s natural JSON serialization: `None` is
+//! `null`, `bool`, `int`, `float` and `str` are scalars, lists are arrays and dicts are objects.
+//! `dumps()` also writes tuples as arrays, like CPython.
+//!
+//! Differences from CPython:
+//! - `loads()` doesn't support `cls` or the `object_hook`/`parse_*` hooks
+//! - `sort_keys=True` sorts keys after they're converted to strings
+//! - lone surrogates in `\uXXXX` escapes are decoded as U+FFFD
+//! - `JSONDecodeError` has no `msg`, `doc`, `pos`, `lineno` or `colno` attributes
+//! - documents nested deeper than the data recursion limit raise `RecursionError`
+
+use num_bigint::BigInt;
+
+use crate::{
+    args::{ArgValues, KwargsValues},
+    builtins::Builtins,
+    defer_drop, defer_drop_mut,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    modules::{CallbackHost, ModuleFunctions},
+    resource::{DepthGuard, ResourceError, ResourceTracker, check_output_size},
+    types::{
+        AttrCallResult, Dict, List, LongInt, Module, PyTrait, bytes::bytes_like, class::class_name,
+        str::allocate_string,
+    },
+    value::Value,
+};
+
+/// `json` module functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum JsonFunctions {
+    Dumps,
+    Loads,
+}
+
+/// Creates the `json` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Json);
+
+    module.set_attr(
+        StaticStrings::Dumps,
+        Value::ModuleFunction(ModuleFunctions::Json(JsonFunctions::Dumps)),
+        heap,
+        interns,
+    );
+    module.set_attr(
+        StaticStrings::Loads,
+        Value::ModuleFunction(ModuleFunctions::Json(JsonFunctions::Loads)),
+        heap,
+        interns,
+    );
+    module.set_attr(
+        StaticStrings::JsonDecodeError,
+        Value::Builtin(Builtins::ExcType(ExcType::JSONDecodeError)),
+        heap,
+        interns,
+    );
+
+    heap.allocate(HeapData::Module(module))
+}
+
+/// Dispatches a call to a `json` module function.
+///
+/// The VM calls `dumps()` itself so that `default=` can run Python code, this path is only
+/// used where no VM is available, with the heap as a host that can't call `default=`.
+pub(super) fn call(
+    heap: &mut Heap<impl ResourceTracker>,
+    function: JsonFunctions,
+    args: ArgValues,
+    interns: &Interns,
+) -> RunResult<AttrCallResult> {
+    let value = match function {
+        JsonFunctions::Dumps => dumps(heap, args, interns)?,
+        JsonFunctions::Loads => loads(heap, args, interns)?,
+    };
+    Ok(AttrCallResult::Value(value))
+}
+
+/// Implementation of `json.dumps(obj, **kwargs)`.
+pub(crate) fn dumps<T: ResourceTracker>(
+    host: &mut impl CallbackHost<T>,
+    args: ArgValues,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let (mut positional, kwargs) = args.into_parts();
+    let heap = host.heap_mut();
+
+    let mut options = DumpsOptions::default();
+    if let Err(err) = options.set_kwargs(kwargs, heap, interns) {
+        options.default.drop_with_heap(heap);
+        positional.drop_with_heap(heap);
+        return Err(err);
+    }
+    if positional.len() != 1 {
+        let count = positional.len();
+        options.default.drop_with_heap(heap);
+        positional.drop_with_heap(heap);
+        return Err(ExcType::type_error_arg_count("dumps", 1, count));
+    }
+    let obj = positional.next().expect("length checked above");
+
+    let mut encoder = JsonEncoder::new(options);
+    let result = encoder.encode(&obj, host, interns);
+    let heap = host.heap_mut();
+    obj.drop_with_heap(heap);
+    encoder.options.default.drop_with_heap(heap);
+    result?;
+    allocate_string(encoder.out, heap)
+}
+
+/// Implementation of `json.loads(s)`.
+///
+/// `bytes` and `bytearray` documents must be UTF-8.
+fn loads(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let arg = args.get_one_arg("loads", heap)?;
+    defer_drop!(arg, heap);
+
+    let doc = if let Some(s) = arg.as_either_str(heap) {
+        s.as_str(interns).to_owned()
+    } else if let Some(bytes) = bytes_like(arg, heap, interns) {
+        String::from_utf8(bytes.into_owned()).map_err(|_| ExcType::unicode_decode_error_invalid_utf8())?
+    } else {
+        let type_name = arg.py_type(heap);
+        return Err(ExcType::type_error(format!(
+            "the JSON object must be str, bytes or bytearray, not {type_name}"
+        )));
+    };
+    JsonParser::new(&doc).parse(heap, interns)
+}
+
+/// Keyword arguments of `dumps()`.
+#[derive(Debug)]
+struct DumpsOptions {
+    skipkeys: bool,
+    ensure_ascii: bool,
+    allow_nan: bool,
+    sort_keys: bool,
+    /// The string repeated once per nesting level, `None` for single-line output.
+    indent: Option<String>,
+    /// Explicit `(item_separator, key_separator)`, the defaults depend on `indent`.
+    separators: Option<(String, String)>,
+    /// Function called with values that can't be serialized, owned by the options.
+    default: Option<Value>,
+}
+
+impl Default for DumpsOptions {
+    fn default() -> Self {
+        Self {
+            skipkeys: false,
+            ensure_ascii: true,
+            allow_nan: true,
+            sort_keys: false,
+            indent: None,
+            separators: None,
+            default: None,
+        }
+    }
+}
+
+impl DumpsOptions {
+    /// Sets the options from the keyword arguments of `dumps()`.
+    ///
+    /// On error the caller still owns `self.default` and must drop it.
+    fn set_kwargs(
+        &mut self,
+        kwargs: KwargsValues,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        let kwargs = kwargs.into_iter();
+        defer_drop_mut!(kwargs, heap);
+
+        for (key, value) in kwargs {
+            defer_drop!(key, heap);
+            defer_drop!(value, heap);
+
+            let Some(keyword_name) = key.as_either_str(heap) else {
+                return Err(ExcType::type_error("keywords must be strings"));
+            };
+            match keyword_name.as_str(interns) {
+                "skipkeys" => self.skipkeys = value.py_bool(heap, interns),
+                "ensure_ascii" => self.ensure_ascii = value.py_bool(heap, interns),
+                // Circular references are always checked
+                "check_circular" => {}
+                "allow_nan" => self.allow_nan = value.py_bool(heap, interns),
+                "sort_keys" => self.sort_keys = value.py_bool(heap, interns),
+                "indent" => self.indent = indent_from_value(value, heap, interns)?,
+                "separators" => self.separators = separators_from_value(value, heap, interns)?,
+                "default" => {
+                    let default = match value {
+                        Value::None => None,
+                        other => Some(other.clone_with_heap(heap)),
+                    };
+                    std::mem::replace(&mut self.default, default).drop_with_heap(heap);
+                }
+                key_str => return Err(ExcType::type_error_unexpected_keyword("dumps", key_str)),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Converts the `indent` argument to the string written once per nesting level.
+///
+/// Like CPython, an integer is a number of spaces and a string is used as is.
+fn indent_from_value(value: &Value, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Option<String>> {
+    match value {
+        Value::None => Ok(None),
+        Value::Int(n) => Ok(Some(" ".repeat(usize::try_from(*n).unwrap_or(0)))),
+        Value::Bool(b) => Ok(Some(" ".repeat(usize::from(*b)))),
+        other => match other.as_either_str(heap) {
+            Some(s) => Ok(Some(s.as_str(interns).to_owned())),
+            None => {
+                let type_name = other.py_type(heap);
+                Err(ExcType::type_error(format!(
+                    "can't multiply sequence by non-int of type '{type_name}'"
+                )))
+            }
+        },
+    }
+}
+
+/// Converts the `separators` argument to an `(item_separator, key_separator)` pair.
+fn separators_from_value(
+    value: &Value,
+    heap: &Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Option<(String, String)>> {
+    let items = match value {
+        Value::None => return Ok(None),
+        Value::Ref(id) => match heap.get(*id) {
+            HeapData::Tuple(tuple) => tuple.as_slice(),
+            HeapData::List(list) => list.as_slice(),
+            _ => {
+                return Err(ExcType::type_error(
+                    "separators must be a (item_separator, key_separator) pair",
+                ));
+            }
+        },
+        _ => {
+            return Err(ExcType::type_error(
+                "separators must be a (item_separator, key_separator) pair",
+            ));
+        }
+    };
+    let [item_separator, key_separator] = items else {
+        let message = if items.len() < 2 {
+            format!("not enough values to unpack (expected 2, got {})", items.len())
+        } else {
+            format!("too many values to unpack (expected 2, got {})", items.len())
+        };
+        return Err(SimpleException::new_msg(ExcType::ValueError, message).into());
+    };
+    let separator = |value: &Value| match value.as_either_str(heap) {
+        Some(s) => Ok(s.as_str(interns).to_owned()),
+        None => {
+            let type_name = value.py_type(heap);
+            Err(ExcType::type_error(format!("separators must be str, not {type_name}")))
+        }
+    };
+    Ok(Some((separator(item_separator)?, separator(key_separator)?)))
+}
+
+/// Serializes values to JSON text for `dumps()`.
+///
+/// Containers are snapshotted (with their refcounts incremented) before their items are
+/// encoded, since `default=` can run arbitrary code which may mutate them.
+struct JsonEncoder {
+    options: DumpsOptions,
+    item_separator: String,
+    key_separator: String,
+    out: String,
+    /// Current nesting level, for indentation.
+    level: usize,
+    /// Containers currently being encoded, to detect circular references.
+    containers: Vec<HeapId>,
+    guard: DepthGuard,
+}
+
+/// A snapshot of the items of a container being encoded.
+enum ContainerItems {
+    Array(Vec<Value>),
+    /// Keys converted to strings (`None` if skipped), and the values in the same order.
+    Object(Vec<Option<String>>, Vec<Value>),
+}
+
+impl<T: ResourceTracker> DropWithHeap<T> for ContainerItems {
+    fn drop_with_heap(self, heap: &mut Heap<T>) {
+        let (Self::Array(values) | Self::Object(_, values)) = self;
+        values.drop_with_heap(heap);
+    }
+}
+
+impl JsonEncoder {
+    fn new(options: DumpsOptions) -> Self {
+        let (item_separator, key_separator) = match &options.separators {
+            Some((item, key)) => (item.clone(), key.clone()),
+            None if options.indent.is_some() => (",".to_owned(), ": ".to_owned()),
+            None => (", ".to_owned(), ": ".to_owned()),
+        };
+        Self {
+            options,
+            item_separator,
+            key_separator,
+            out: String::new(),
+            level: 0,
+            containers: Vec::new(),
+            guard: DepthGuard::default(),
+        }
+    }
+
+    /// Appends the JSON encoding of `value` to the output.
+    fn encode<T: ResourceTracker>(
+        &mut self,
+        value: &Value,
+        host: &mut impl CallbackHost<T>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        let heap = host.heap_mut();
+        match value {
+            Value::None => self.out.push_str("null"),
+            Value::Bool(true) => self.out.push_str("true"),
+            Value::Bool(false) => self.out.push_str("false"),
+            Value::Int(i) => self.out.push_str(&i.to_string()),
+            Value::InternLongInt(id) => self.out.push_str(&interns.get_long_int(*id).to_string()),
+            Value::Float(f) => {
+                let float = float_to_json(*f, self.options.allow_nan, heap, interns)?;
+                self.out.push_str(&float);
+            }
+            Value::InternString(id) => self.encode_str(interns.get_str(*id)),
+            Value::Ref(id) => return self.encode_ref(*id, value, host, interns),
+            _ => return self.encode_default(value, host, interns),
+        }
+        Ok(())
+    }
+
+    /// Encodes a heap value: a string, long integer or container.
+    fn encode_ref<T: ResourceTracker>(
+        &mut self,
+        id: HeapId,
+        value: &Value,
+        host: &mut impl CallbackHost<T>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        let heap = host.heap_mut();
+        let items = match heap.get(id) {
+            HeapData::Str(s) => {
+                self.encode_str(s.as_str());
+                return self.check_size(heap);
+            }
+            HeapData::LongInt(long_int) => {
+                self.out.push_str(&long_int.inner().to_string());
+                return self.check_size(heap);
+            }
+            HeapData::List(list) => ContainerItems::Array(copy_values(list.as_slice())),
+            HeapData::Tuple(tuple) => ContainerItems::Array(copy_values(tuple.as_slice())),
+            HeapData::NamedTuple(named_tuple) => ContainerItems::Array(copy_values(named_tuple.as_vec())),
+            HeapData::Deque(deque) => ContainerItems::Array(copy_values(deque.iter())),
+            data => {
+                let Some(dict) = data.as_dict() else {
+                    return self.encode_default(value, host, interns);
+                };
+                let keys = self.object_keys(dict, heap, interns)?;
+                ContainerItems::Object(keys, copy_values(dict.iter().map(|(_, value)| value)))
+            }
+        };
+        // The snapshot was copied while the container was borrowed, take the references now
+        let (ContainerItems::Array(values) | ContainerItems::Object(_, values)) = &items;
+        for value in values {
+            if let Value::Ref(value_id) = value {
+                heap.inc_ref(*value_id);
+            }
+        }
+
+        if self.containers.contains(&id) {
+            items.drop_with_heap(heap);
+            return Err(SimpleException::new_msg(ExcType::ValueError, "Circular reference detected").into());
+        }
+        if let Err(err) = self.guard.increase_err() {
+            items.drop_with_heap(heap);
+            return Err(err.into());
+        }
+        self.containers.push(id);
+        let result = match items {
+            ContainerItems::Array(values) => self.encode_array(values, host, interns),
+            ContainerItems::Object(keys, values) => self.encode_object(keys, values, host, interns),
+        };
+        self.containers.pop();
+        self.guard.decrease();
+        result?;
+        self.check_size(host.heap_mut())
+    }
+
+    /// Encodes the items of a list or tuple, consuming them.
+    fn encode_array<T: ResourceTracker>(
+        &mut self,
+        values: Vec<Value>,
+        host: &mut impl CallbackHost<T>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        if values.is_empty() {
+            self.out.push_str("[]");
+            return Ok(());
+        }
+        self.out.push('[');
+        self.level += 1;
+        let mut result = Ok(());
+        for (index, value) in values.into_iter().enumerate() {
+            if result.is_ok() {
+                if index > 0 {
+                    self.out.push_str(&self.item_separator);
+                }
+                self.newline_indent();
+                result = self.encode(&value, host, interns);
+            }
+            value.drop_with_heap(host.heap_mut());
+        }
+        result?;
+        self.level -= 1;
+        self.newline_indent();
+        self.out.push(']');
+        Ok(())
+    }
+
+    /// Encodes the items of a dict, consuming the values.
+    ///
+    /// Values whose key is `None` in `keys` were skipped by `skipkeys=True`.
+    fn encode_object<T: ResourceTracker>(
+        &mut self,
+        keys: Vec<Option<String>>,
+        values: Vec<Value>,
+        host: &mut impl CallbackHost<T>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        let mut pairs = Vec::with_capacity(values.len());
+        for (key, value) in keys.into_iter().zip(values) {
+            match key {
+                Some(key) => pairs.push((key, value)),
+                None => value.drop_with_heap(host.heap_mut()),
+            }
+        }
+        if pairs.is_empty() {
+            self.out.push_str("{}");
+            return Ok(());
+        }
+        if self.options.sort_keys {
+            pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
+        }
+        self.out.push('{');
+        self.level += 1;
+        let mut result = Ok(());
+        for (index, (key, value)) in pairs.into_iter().enumerate() {
+            if result.is_ok() {
+                if index > 0 {
+                    self.out.push_str(&self.item_separator);
+                }
+                self.newline_indent();
+                self.encode_str(&key);
+                self.out.push_str(&self.key_separator);
+                result = self.encode(&value, host, interns);
+            }
+            value.drop_with_heap(host.heap_mut());
+        }
+        result?;
+        self.level -= 1;
+        self.newline_indent();
+        self.out.push('}');
+        Ok(())
+    }
+
+    /// Converts the keys of a dict to strings, `None` for keys skipped by `skipkeys=True`.
+    ///
+    /// Like CPython, `int`, `float`, `bool` and `None` keys are converted to their JSON text.
+    fn object_keys(
+        &self,
+        dict: &Dict,
+        heap: &Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Vec<Option<String>>> {
+        let mut keys = Vec::with_capacity(dict.len());
+        for (key, _) in dict {
+            let converted = match key {
+                Value::None => Some("null".to_owned()),
+                Value::Bool(b) => Some(b.to_string()),
+                Value::Int(i) => Some(i.to_string()),
+                Value::InternLongInt(id) => Some(interns.get_long_int(*id).to_string()),
+                Value::Float(f) => Some(float_to_json(*f, self.options.allow_nan, heap, interns)?),
+                Value::InternString(id) => Some(interns.get_str(*id).to_owned()),
+                Value::Ref(id) => match heap.get(*id) {
+                    HeapData::Str(s) => Some(s.as_str().to_owned()),
+                    HeapData::LongInt(long_int) => Some(long_int.inner().to_string()),
+                    _ => None,
+                },
+                _ => None,
+            };
+            if converted.is_none() && !self.options.skipkeys {
+                let type_name = key.py_type(heap);
+                return Err(ExcType::type_error(format!(
+                    "keys must be str, int, float, bool or None, not {type_name}"
+                )));
+            }
+            keys.push(converted);
+        }
+        Ok(keys)
+    }
+
+    /// Encodes a value JSON doesn't support by calling `default=`, or raises a `TypeError`.
+    fn encode_default<T: ResourceTracker>(
+        &mut self,
+        value: &Value,
+        host: &mut impl CallbackHost<T>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        let heap = host.heap_mut();
+        let Some(default) = &self.options.default else {
+            let type_name = type_name(value, heap, interns);
+            return Err(ExcType::type_error(format!(
+                "Object of type {type_name} is not JSON serializable"
+            )));
+        };
+        // `default` may keep returning values it can't serialize, bound how often it's chained
+        self.guard.increase_err()?;
+        let arg = value.clone_with_heap(heap);
+        let result = host.call_one(default, arg).and_then(|converted| {
+            let result = self.encode(&converted, host, interns);
+            converted.drop_with_heap(host.heap_mut());
+            result
+        });
+        self.guard.decrease();
+        result
+    }
+
+    /// Appends a JSON string literal, escaping it like CPython.
+    fn encode_str(&mut self, s: &str) {
+        self.out.push('"');
+        for c in s.chars() {
+            match c {
+                '"' => self.out.push_str("\\\""),
+                '\\' => self.out.push_str("\\\\"),
+                '\n' => self.out.push_str("\\n"),
+                '\r' => self.out.push_str("\\r"),
+                '\t' => self.out.push_str("\\t"),
+                '\u{8}' => self.out.push_str("\\b"),
+                '\u{c}' => self.out.push_str("\\f"),
+                c if c < ' ' || (self.options.ensure_ascii && !(' '..='~').contains(&c)) => {
+                    let mut units = [0; 2];
+                    for unit in c.encode_utf16(&mut units) {
+                        self.out.push_str(&format!("\\u{unit:04x}"));
+                    }
+                }
+                c => self.out.push(c),
+            }
+        }
+        self.out.push('"');
+    }
+
+    /// Starts a new line at the current nesting level when indenting.
+    fn newline_indent(&mut self) {
+        if let Some(indent) = &self.options.indent {
+            self.out.push('\n');
+            for _ in 0..self.level {
+                self.out.push_str(indent);
+            }
+        }
+    }
+
+    /// Checks that the output built so far fits within the memory limit.
+    fn check_size(&self, heap: &Heap<impl ResourceTracker>) -> RunResult<()> {
+        Ok(check_output_size(self.out.len(), heap.tracker())?)
+    }
+}
+
+/// Copies values out of a borrowed container without incrementing their refcounts.
+fn copy_values<'v>(values: impl IntoIterator<Item = &'v Value>) -> Vec<Value> {
+    values.into_iter().map(Value::copy_for_extend).collect()
+}
+
+/// Formats a float as JSON, where non-finite values are written as JavaScript literals.
+///
+/// Raises `ValueError` for non-finite values if `allow_nan` is false.
+fn float_to_json(f: f64, allow_nan: bool, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<String> {
+    if f.is_finite() {
+        return Ok(Value::Float(f)
+            .py_repr(heap, &mut DepthGuard::default(), interns)
+            .into_owned());
+    }
+    let (json, repr) = if f.is_nan() {
+        ("NaN", "nan")
+    } else if f > 0.0 {
+        ("Infinity", "inf")
+    } else {
+        ("-Infinity", "-inf")
+    };
+    if allow_nan {
+        Ok(json.to_owned())
+    } else {
+        Err(SimpleException::new_msg(
+            ExcType::ValueError,
+            format!("Out of range float values are not JSON compliant: {repr}"),
+        )
+        .into())
+    }
+}
+
+/// Returns the type name used in "not JSON serializable" errors, the class name for instances.
+fn type_name(value: &Value, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> String {
+    if let Value::Ref(id) = value
+        && let HeapData::Instance(instance) = heap.get(*id)
+    {
+        return class_name(instance.class_id(), heap, interns).to_owned();
+    }
+    value.py_type(heap).to_string()
+}
+
+/// Maximum number of digits of an integer parsed by `loads()`, CPython's default
+/// `sys.get_int_max_str_digits()`.
+///
+/// Converting a decimal string to an int takes quadratic time, so longer integers are
+/// rejected before they're converted.
+const MAX_INT_DIGITS: usize = 4300;
+
+/// Recursive descent parser for `loads()`.
+///
+/// Positions are byte offsets into the document, they're converted to character offsets
+/// for error messages.
+struct JsonParser<'a> {
+    doc: &'a str,
+    pos: usize,
+    guard: DepthGuard,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(doc: &'a str) -> Self {
+        Self {
+            doc,
+            pos: 0,
+            guard: DepthGuard::default(),
+        }
+    }
+
+    /// Parses the whole document, which must hold exactly one value.
+    fn parse(mut self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        self.skip_whitespace();
+        let value = self.parse_value(heap, interns)?;
+        self.skip_whitespace();
+        if self.pos < self.doc.len() {
+            value.drop_with_heap(heap);
+            return Err(self.error("Extra data", self.pos));
+        }
+        Ok(value)
+    }
+
+    fn parse_value(&mut self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        match self.peek() {
+            Some(b'"') => {
+                let s = self.parse_string()?;
+                allocate_string(s, heap)
+            }
+            Some(b'[') => {
+                self.guard.increase_err()?;
+                let result = self.parse_array(heap, interns);
+                self.guard.decrease();
+                result
+            }
+            Some(b'{') => {
+                self.guard.increase_err()?;
+                let result = self.parse_object(heap, interns);
+                self.guard.decrease();
+                result
+            }
+            Some(b'n') if self.eat("null") => Ok(Value::None),
+            Some(b't') if self.eat("true") => Ok(Value::Bool(true)),
+            Some(b'f') if self.eat("false") => Ok(Value::Bool(false)),
+            Some(b'N') if self.eat("NaN") => Ok(Value::Float(f64::NAN)),
+            Some(b'I') if self.eat("Infinity") => Ok(Value::Float(f64::INFINITY)),
+            Some(b'-') if self.eat("-Infinity") => Ok(Value::Float(f64::NEG_INFINITY)),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(heap),
+            _ => Err(self.error("Expecting value", self.pos)),
+        }
+    }
+
+    /// Parses an array, the current character is `[`.
+    fn parse_array(&mut self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let mut items = Vec::new();
+        match self.parse_array_items(&mut items, heap, interns) {
+            Ok(()) => Ok(Value::Ref(heap.allocate(HeapData::List(List::new(items)))?)),
+            Err(err) => {
+                items.drop_with_heap(heap);
+                Err(err)
+            }
+        }
+    }
+
+    fn parse_array_items(
+        &mut self,
+        items: &mut Vec<Value>,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(());
+        }
+        loop {
+            items.push(self.parse_value(heap, interns)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(b',') => {
+                    let comma = self.pos;
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    if self.peek() == Some(b']') {
+                        return Err(self.error("Illegal trailing comma before end of array", comma));
+                    }
+                }
+                _ => return Err(self.error("Expecting ',' delimiter", self.pos)),
+            }
+        }
+    }
+
+    /// Parses an object, the current character is `{`.
+    ///
+    /// Like CPython, the last value wins when a key is repeated.
+    fn parse_object(&mut self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let mut pairs = Vec::new();
+        match self.parse_object_items(&mut pairs, heap, interns) {
+            Ok(()) => {
+                let dict = Dict::from_pairs(pairs, heap, interns)?;
+                Ok(Value::Ref(heap.allocate(HeapData::Dict(dict))?))
+            }
+            Err(err) => {
+                for (key, value) in pairs {
+                    key.drop_with_heap(heap);
+                    value.drop_with_heap(heap);
+                }
+                Err(err)
+            }
+        }
+    }
+
+    fn parse_object_items(
+        &mut self,
+        pairs: &mut Vec<(Value, Value)>,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(());
+        }
+        loop {
+            if self.peek() != Some(b'"') {
+                return Err(self.error("Expecting property name enclosed in double quotes", self.pos));
+            }
+            let key = self.parse_string()?;
+            let key = allocate_string(key, heap)?;
+            self.skip_whitespace();
+            if self.peek() != Some(b':') {
+                key.drop_with_heap(heap);
+                return Err(self.error("Expecting ':' delimiter", self.pos));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let value = match self.parse_value(heap, interns) {
+                Ok(value) => value,
+                Err(err) => {
+                    key.drop_with_heap(heap);
+                    return Err(err);
+                }
+            };
+            pairs.push((key, value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(b',') => {
+                    let comma = self.pos;
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    if self.peek() == Some(b'}') {
+                        return Err(self.error("Illegal trailing comma before end of object", comma));
+                    }
+                }
+                _ => return Err(self.error("Expecting ',' delimiter", self.pos)),
+            }
+        }
+    }
+
+    /// Parses a string literal, the current character is `"`.
+    fn parse_string(&mut self) -> RunResult<String> {
+        let start = self.pos;
+        let bytes = self.doc.as_bytes();
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            // Quotes, backslashes and control characters are ASCII, so `end` is a char boundary
+            let Some(end) = bytes[self.pos..]
+                .iter()
+                .position(|&b| b == b'"' || b == b'\\' || b < b' ')
+                .map(|offset| self.pos + offset)
+            else {
+                return Err(self.error("Unterminated string starting at", start));
+            };
+            out.push_str(&self.doc[self.pos..end]);
+            self.pos = end;
+            match bytes[end] {
+                b'"' => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                b'\\' => {
+                    let c = match bytes.get(end + 1) {
+                        None => return Err(self.error("Unterminated string starting at", start)),
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            out.push(self.parse_unicode_escape()?);
+                            continue;
+                        }
+                        Some(_) => return Err(self.error("Invalid \\escape", end)),
+                    };
+                    out.push(c);
+                    self.pos += 2;
+                }
+                _ => return Err(self.error("Invalid control character at", end)),
+            }
+        }
+    }
+
+    /// Parses a `\uXXXX` escape at the current position, combining surrogate pairs.
+    ///
+    /// Leaves the position after the escape (or pair of escapes).
+    fn parse_unicode_escape(&mut self) -> RunResult<char> {
+        let high = self.hex_escape(self.pos)?;
+        self.pos += 6;
+        if (0xD800..0xDC00).contains(&high)
+            && self.doc.as_bytes()[self.pos..].starts_with(b"\\u")
+            && let Ok(low) = self.hex_escape(self.pos)
+            && (0xDC00..0xE000).contains(&low)
+        {
+            self.pos += 6;
+            let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+            return Ok(char::from_u32(code).expect("surrogate pairs decode to valid chars"));
+        }
+        Ok(char::from_u32(high).unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    /// Reads the four hex digits of the `\uXXXX` escape starting at `backslash`.
+    fn hex_escape(&self, backslash: usize) -> RunResult<u32> {
+        self.doc
+            .get(backslash + 2..backslash + 6)
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .ok_or_else(|| self.error("Invalid \\uXXXX escape", backslash + 1))
+    }
+
+    /// Parses a number, the current character is `-` or a digit.
+    ///
+    /// Numbers without a fraction or exponent are integers, like in CPython. Integers with
+    /// more than [`MAX_INT_DIGITS`] digits raise `ValueError`.
+    fn parse_number(&mut self, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+        let bytes = self.doc.as_bytes();
+        let start = self.pos;
+        let is_digit = |index: usize| bytes.get(index).is_some_and(u8::is_ascii_digit);
+
+        let mut end = start;
+        if bytes[end] == b'-' {
+            end += 1;
+        }
+        match bytes.get(end) {
+            Some(b'0') => end += 1,
+            Some(b'1'..=b'9') => {
+                while is_digit(end) {
+                    end += 1;
+                }
+            }
+            _ => return Err(self.error("Expecting value", start)),
+        }
+        let mut is_float = false;
+        if bytes.get(end) == Some(&b'.') && is_digit(end + 1) {
+            end += 1;
+            while is_digit(end) {
+                end += 1;
+            }
+            is_float = true;
+        }
+        if matches!(bytes.get(end), Some(b'e' | b'E')) {
+            let mut exponent = end + 1;
+            if matches!(bytes.get(exponent), Some(b'+' | b'-')) {
+                exponent += 1;
+            }
+            if is_digit(exponent) {
+                end = exponent;
+                while is_digit(end) {
+                    end += 1;
+                }
+                is_float = true;
+            }
+        }
+        self.pos = end;
+
+        let text = &self.doc[start..end];
+        if is_float {
+            Ok(Value::Float(text.parse().expect("JSON numbers are valid floats")))
+        } else if let Ok(int) = text.parse::<i64>() {
+            Ok(Value::Int(int))
+        } else {
+            let digits = text.trim_start_matches('-').len();
+            if digits > MAX_INT_DIGITS {
+                return Err(SimpleException::new_msg(
+                    ExcType::ValueError,
+                    format!(
+                        "Exceeds the limit ({MAX_INT_DIGITS} digits) for integer string conversion: \
+                         value has {digits} digits; use sys.set_int_max_str_digits() to increase the limit"
+                    ),
+                )
+                .into());
+            }
+            let int = text.parse::<BigInt>().expect("JSON integers are valid integers");
+            Ok(LongInt::new(int).into_value(heap)?)
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.doc.as_bytes().get(self.pos).copied()
+    }
+
+    /// Consumes `literal` if the document continues with it.
+    fn eat(&mut self, literal: &str) -> bool {
+        if self.doc[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Creates a `JSONDecodeError` for an error at byte offset `pos`.
+    ///
+    /// Matches CPython's format: `{msg}: line {lineno} column {colno} (char {pos})`, where
+    /// the position and column count characters.
+    fn error(&self, msg: &str, pos: usize) -> RunError {
+        let before = &self.doc[..pos];
+        let char_pos = before.chars().count();
+        let lineno = before.matches('\n').count() + 1;
+        let colno = match before.rfind('\n') {
+            Some(newline) => before[newline + 1..].chars().count() + 1,
+            None => char_pos + 1,
+        };
+        SimpleException::new_msg(
+            ExcType::JSONDecodeError,
+            format!("{msg}: line {lineno} column {colno} (char {char_pos})"),
+        )
+        .into()
+    }
+}
@@ -0,0 +1,754 @@
+//! Implementation of the `math` module.
+//!
+//! Provides a native implementation of Python's `math` module with:
+//! - the constants `pi`, `e`, `tau`, `inf` and `nan`
+//! - float functions: `sqrt()`, `exp()`, `log()`, `log2()`, `log10()`, `pow()`, the trigonometric
+//!   and hyperbolic functions, `degrees()`, `radians()`, `hypot()`, `fabs()`, `fmod()` and `copysign()`
+//! - `floor()`, `ceil()` and `trunc()`, which return an `int`
+//! - `isclose()`, `isnan()`, `isinf()` and `isfinite()`
+//! - integer functions: `gcd()`, `lcm()`, `isqrt()`, `factorial()`, `comb()` and `perm()`
+//! - `prod()` and `fsum()` over an iterable
+//!
+//! Errors match CPython: `ValueError: math domain error` for arguments outside a function's
+//! domain and `OverflowError: math range error` for results too large for a float. The integer
+//! functions work on ints of any size, and results that could be huge are checked against the
+//! resource limits before they're computed.
+//!
+//! Differences from CPython:
+//! - arguments must be `int`, `bool` or `float`; `__float__`, `__index__`, `__floor__`, `__ceil__`
+//!   and `__trunc__` aren't used
+//! - `isclose()` only takes `a` and `b` by position
+
+use std::f64::consts::{E, PI, TAU};
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{FromPrimitive, One, Signed, ToPrimitive, Zero};
+
+use crate::{
+    args::{ArgValues, KwargsValues},
+    defer_drop, defer_drop_mut,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    modules::ModuleFunctions,
+    resource::{ResourceError, ResourceTracker, check_int_size, check_mult_size},
+    types::{AttrCallResult, LongInt, Module, MontyIter, PyTrait},
+    value::Value,
+};
+
+/// `math` module functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum MathFunctions {
+    Sqrt,
+    Isqrt,
+    Exp,
+    Log,
+    Log2,
+    Log10,
+    Pow,
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Atan2,
+    Sinh,
+    Cosh,
+    Tanh,
+    Hypot,
+    Degrees,
+    Radians,
+    Fabs,
+    Fmod,
+    Copysign,
+    Floor,
+    Ceil,
+    Trunc,
+    Isclose,
+    Isnan,
+    Isinf,
+    Isfinite,
+    Gcd,
+    Lcm,
+    Factorial,
+    Comb,
+    Perm,
+    Prod,
+    Fsum,
+}
+
+/// Creates the `math` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Math);
+
+    let constants = [
+        (StaticStrings::Pi.into(), PI),
+        (StringId::from_ascii(b'e'), E),
+        (StaticStrings::Tau.into(), TAU),
+        (StaticStrings::Inf.into(), f64::INFINITY),
+        (StaticStrings::Nan.into(), f64::NAN),
+    ];
+    for (name, value) in constants {
+        module.set_attr(name, Value::Float(value), heap, interns);
+    }
+
+    let functions = [
+        (StaticStrings::Sqrt, MathFunctions::Sqrt),
+        (StaticStrings::Isqrt, MathFunctions::Isqrt),
+        (StaticStrings::Exp, MathFunctions::Exp),
+        (StaticStrings::Log, MathFunctions::Log),
+        (StaticStrings::Log2, MathFunctions::Log2),
+        (StaticStrings::Log10, MathFunctions::Log10),
+        (StaticStrings::Pow, MathFunctions::Pow),
+        (StaticStrings::Sin, MathFunctions::Sin),
+        (StaticStrings::Cos, MathFunctions::Cos),
+        (StaticStrings::Tan, MathFunctions::Tan),
+        (StaticStrings::Asin, MathFunctions::Asin),
+        (StaticStrings::Acos, MathFunctions::Acos),
+        (StaticStrings::Atan, MathFunctions::Atan),
+        (StaticStrings::Atan2, MathFunctions::Atan2),
+        (StaticStrings::Sinh, MathFunctions::Sinh),
+        (StaticStrings::Cosh, MathFunctions::Cosh),
+        (StaticStrings::Tanh, MathFunctions::Tanh),
+        (StaticStrings::Hypot, MathFunctions::Hypot),
+        (StaticStrings::Degrees, MathFunctions::Degrees),
+        (StaticStrings::Radians, MathFunctions::Radians),
+        (StaticStrings::Fabs, MathFunctions::Fabs),
+        (StaticStrings::Fmod, MathFunctions::Fmod),
+        (StaticStrings::Copysign, MathFunctions::Copysign),
+        (StaticStrings::Floor, MathFunctions::Floor),
+        (StaticStrings::Ceil, MathFunctions::Ceil),
+        (StaticStrings::Trunc, MathFunctions::Trunc),
+        (StaticStrings::Isclose, MathFunctions::Isclose),
+        (StaticStrings::Isnan, MathFunctions::Isnan),
+        (StaticStrings::Isinf, MathFunctions::Isinf),
+        (StaticStrings::Isfinite, MathFunctions::Isfinite),
+        (StaticStrings::Gcd, MathFunctions::Gcd),
+        (StaticStrings::Lcm, MathFunctions::Lcm),
+        (StaticStrings::Factorial, MathFunctions::Factorial),
+        (StaticStrings::Comb, MathFunctions::Comb),
+        (StaticStrings::Perm, MathFunctions::Perm),
+        (StaticStrings::Prod, MathFunctions::Prod),
+        (StaticStrings::Fsum, MathFunctions::Fsum),
+    ];
+    for (name, function) in functions {
+        module.set_attr(
+            name,
+            Value::ModuleFunction(ModuleFunctions::Math(function)),
+            heap,
+            interns,
+        );
+    }
+
+    heap.allocate(HeapData::Module(module))
+}
+
+/// Dispatches a call to a `math` module function.
+pub(super) fn call(
+    heap: &mut Heap<impl ResourceTracker>,
+    function: MathFunctions,
+    args: ArgValues,
+    interns: &Interns,
+) -> RunResult<AttrCallResult> {
+    let value = match function {
+        MathFunctions::Log => log(heap, args)?,
+        MathFunctions::Pow | MathFunctions::Atan2 | MathFunctions::Fmod | MathFunctions::Copysign => {
+            let (x, y) = args.get_two_args(&function.to_string(), heap)?;
+            defer_drop!(x, heap);
+            defer_drop!(y, heap);
+            let result = binary_float(function, real_arg(x, heap)?, real_arg(y, heap)?)?;
+            Value::Float(result)
+        }
+        MathFunctions::Hypot => hypot(heap, args)?,
+        MathFunctions::Isclose => isclose(heap, args, interns)?,
+        MathFunctions::Gcd | MathFunctions::Lcm => gcd_lcm(function, heap, args)?,
+        MathFunctions::Comb => {
+            let (n, k) = args.get_two_args("comb", heap)?;
+            defer_drop!(n, heap);
+            defer_drop!(k, heap);
+            comb_perm(function, n, Some(k), heap)?
+        }
+        MathFunctions::Perm => {
+            let (n, k) = args.get_one_two_args("perm", heap)?;
+            defer_drop!(n, heap);
+            defer_drop!(k, heap);
+            comb_perm(function, n, k.as_ref().filter(|k| !matches!(k, Value::None)), heap)?
+        }
+        MathFunctions::Prod => prod(heap, args, interns)?,
+        MathFunctions::Fsum => {
+            let iterable = args.get_one_arg("math.fsum", heap)?;
+            fsum(iterable, heap, interns)?
+        }
+        _ => {
+            let x = args.get_one_arg(&format!("math.{function}"), heap)?;
+            unary(function, x, heap)?
+        }
+    };
+    Ok(AttrCallResult::Value(value))
+}
+
+/// Implementation of the functions taking a single argument.
+fn unary(function: MathFunctions, x: Value, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    match function {
+        MathFunctions::Floor | MathFunctions::Ceil | MathFunctions::Trunc => return round_to_int(function, x, heap),
+        MathFunctions::Isqrt | MathFunctions::Factorial => {
+            defer_drop!(x, heap);
+            let n = int_arg(x, heap)?;
+            if function == MathFunctions::Factorial {
+                return factorial(&n, heap);
+            }
+            if n.is_negative() {
+                return Err(
+                    SimpleException::new_msg(ExcType::ValueError, "isqrt() argument must be nonnegative").into(),
+                );
+            }
+            return Ok(LongInt::new(n.sqrt()).into_value(heap)?);
+        }
+        _ => {}
+    }
+
+    defer_drop!(x, heap);
+    if matches!(function, MathFunctions::Log2 | MathFunctions::Log10) {
+        let log = if function == MathFunctions::Log2 {
+            f64::log2
+        } else {
+            f64::log10
+        };
+        return Ok(Value::Float(log_of(x, log, heap)?));
+    }
+    let x = real_arg(x, heap)?;
+    let (result, can_overflow) = match function {
+        MathFunctions::Isnan => return Ok(Value::Bool(x.is_nan())),
+        MathFunctions::Isinf => return Ok(Value::Bool(x.is_infinite())),
+        MathFunctions::Isfinite => return Ok(Value::Bool(x.is_finite())),
+        MathFunctions::Fabs => return Ok(Value::Float(x.abs())),
+        MathFunctions::Degrees => return Ok(Value::Float(x.to_degrees())),
+        MathFunctions::Radians => return Ok(Value::Float(x.to_radians())),
+        MathFunctions::Sqrt => (x.sqrt(), false),
+        MathFunctions::Exp => (x.exp(), true),
+        MathFunctions::Sin => (x.sin(), false),
+        MathFunctions::Cos => (x.cos(), false),
+        MathFunctions::Tan => (x.tan(), false),
+        MathFunctions::Asin => (x.asin(), false),
+        MathFunctions::Acos => (x.acos(), false),
+        MathFunctions::Atan => (x.atan(), false),
+        MathFunctions::Sinh => (x.sinh(), true),
+        MathFunctions::Cosh => (x.cosh(), true),
+        MathFunctions::Tanh => (x.tanh(), false),
+        _ => unreachable!("{function} takes more than one argument"),
+    };
+    Ok(Value::Float(check_result(x, result, can_overflow)?))
+}
+
+/// Checks the result of a float function of `x` like CPython.
+///
+/// A NaN from a non-NaN argument is a domain error, and an infinity from a finite argument is
+/// either a range error for functions that can overflow like `exp()`, or a domain error for
+/// functions with a pole like `log()`.
+fn check_result(x: f64, result: f64, can_overflow: bool) -> RunResult<f64> {
+    if result.is_nan() && !x.is_nan() {
+        Err(math_domain_error())
+    } else if result.is_infinite() && x.is_finite() {
+        Err(if can_overflow {
+            math_range_error()
+        } else {
+            math_domain_error()
+        })
+    } else {
+        Ok(result)
+    }
+}
+
+/// Implementation of the float functions taking two arguments.
+fn binary_float(function: MathFunctions, x: f64, y: f64) -> RunResult<f64> {
+    match function {
+        MathFunctions::Pow => {
+            let result = x.powf(y);
+            if x.is_finite() && y.is_finite() && !result.is_finite() {
+                // 0 ** -1 is a pole rather than an overflow
+                if result.is_nan() || x == 0.0 {
+                    return Err(math_domain_error());
+                }
+                return Err(math_range_error());
+            }
+            Ok(result)
+        }
+        MathFunctions::Atan2 => Ok(y.atan2(x)),
+        MathFunctions::Fmod => {
+            let result = x % y;
+            if result.is_nan() && !x.is_nan() && !y.is_nan() {
+                return Err(math_domain_error());
+            }
+            Ok(result)
+        }
+        MathFunctions::Copysign => Ok(x.copysign(y)),
+        _ => unreachable!("{function} doesn't take two floats"),
+    }
+}
+
+/// Implementation of `math.log(x[, base])`.
+fn log(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    let (x, base) = args.get_one_two_args("log", heap)?;
+    defer_drop!(x, heap);
+    defer_drop!(base, heap);
+    let log_x = log_of(x, f64::ln, heap)?;
+    let Some(base) = base else {
+        return Ok(Value::Float(log_x));
+    };
+    let log_base = log_of(base, f64::ln, heap)?;
+    if log_base == 0.0 {
+        return Err(SimpleException::new_msg(ExcType::ZeroDivisionError, "float division by zero").into());
+    }
+    Ok(Value::Float(log_x / log_base))
+}
+
+/// Takes a logarithm of `value` with `log`, which may be `ln`, `log2` or `log10`.
+///
+/// Ints too large for a float are split into their top 64 bits and a power of two, so their
+/// logarithm can still be computed.
+fn log_of(value: &Value, log: fn(f64) -> f64, heap: &Heap<impl ResourceTracker>) -> RunResult<f64> {
+    if let Value::Ref(id) = value
+        && let HeapData::LongInt(li) = heap.get(*id)
+    {
+        if !li.inner().is_positive() {
+            return Err(math_domain_error());
+        }
+        let shift = li.bits().saturating_sub(64);
+        let top = (li.inner() >> shift).to_f64().expect("64 bits fit in a float");
+        return Ok(log(top) + shift as f64 * log(2.0));
+    }
+    let x = real_arg(value, heap)?;
+    // log(0) is a pole, not an overflow
+    check_result(x, log(x), false)
+}
+
+/// Implementation of `math.hypot(*coordinates)`.
+fn hypot(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    let coordinates = args.into_pos_only("hypot", heap)?;
+    defer_drop_mut!(coordinates, heap);
+    let mut result = 0.0_f64;
+    for coordinate in coordinates {
+        defer_drop!(coordinate, heap);
+        result = result.hypot(real_arg(coordinate, heap)?);
+    }
+    Ok(Value::Float(result))
+}
+
+/// Implementation of `math.floor()`, `math.ceil()` and `math.trunc()`, which return ints
+/// unchanged and round floats to an int.
+fn round_to_int(function: MathFunctions, x: Value, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    let f = match x {
+        Value::Int(_) => return Ok(x),
+        Value::Bool(b) => return Ok(Value::Int(i64::from(b))),
+        Value::Ref(id) if matches!(heap.get(id), HeapData::LongInt(_)) => return Ok(x),
+        Value::Float(f) => f,
+        _ => {
+            let type_name = x.py_type(heap);
+            x.drop_with_heap(heap);
+            return Err(if function == MathFunctions::Trunc {
+                ExcType::type_error(format!("type {type_name} doesn't define __trunc__ method"))
+            } else {
+                ExcType::type_error(format!("must be real number, not {type_name}"))
+            });
+        }
+    };
+    let rounded = match function {
+        MathFunctions::Floor => f.floor(),
+        MathFunctions::Ceil => f.ceil(),
+        _ => f.trunc(),
+    };
+    if rounded.is_nan() {
+        return Err(SimpleException::new_msg(ExcType::ValueError, "cannot convert float NaN to integer").into());
+    }
+    let Some(n) = BigInt::from_f64(rounded) else {
+        return Err(
+            SimpleException::new_msg(ExcType::OverflowError, "cannot convert float infinity to integer").into(),
+        );
+    };
+    Ok(LongInt::new(n).into_value(heap)?)
+}
+
+/// Implementation of `math.isclose(a, b, *, rel_tol=1e-09, abs_tol=0.0)`.
+fn isclose(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let (positional, kwargs) = args.into_parts();
+    defer_drop_mut!(positional, heap);
+    if positional.len() != 2 {
+        let count = positional.len();
+        kwargs.drop_with_heap(heap);
+        return Err(match count {
+            0 => ExcType::type_error("isclose() missing required argument 'a' (pos 1)"),
+            1 => ExcType::type_error("isclose() missing required argument 'b' (pos 2)"),
+            _ => ExcType::type_error(format!(
+                "isclose() takes exactly 2 positional arguments ({count} given)"
+            )),
+        });
+    }
+    let [rel_tol, abs_tol] = keyword_args("isclose", kwargs, ["rel_tol", "abs_tol"], heap, interns)?;
+    defer_drop!(rel_tol, heap);
+    defer_drop!(abs_tol, heap);
+    let a = positional.next().expect("length checked above");
+    defer_drop!(a, heap);
+    let b = positional.next().expect("length checked above");
+    defer_drop!(b, heap);
+
+    let a = real_arg(a, heap)?;
+    let b = real_arg(b, heap)?;
+    let rel_tol = rel_tol.as_ref().map_or(Ok(1e-9), |value| real_arg(value, heap))?;
+    let abs_tol = abs_tol.as_ref().map_or(Ok(0.0), |value| real_arg(value, heap))?;
+    if rel_tol < 0.0 || abs_tol < 0.0 {
+        return Err(SimpleException::new_msg(ExcType::ValueError, "tolerances must be non-negative").into());
+    }
+
+    if a == b {
+        return Ok(Value::Bool(true));
+    }
+    // Infinities of the same sign are caught above, any other difference to one is infinite
+    if a.is_infinite() || b.is_infinite() {
+        return Ok(Value::Bool(false));
+    }
+    let diff = (b - a).abs();
+    Ok(Value::Bool(
+        diff <= (rel_tol * b).abs() || diff <= (rel_tol * a).abs() || diff <= abs_tol,
+    ))
+}
+
+/// Implementation of `math.gcd(*integers)` and `math.lcm(*integers)`.
+fn gcd_lcm(function: MathFunctions, heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    let integers = args.into_pos_only(&function.to_string(), heap)?;
+    defer_drop_mut!(integers, heap);
+    let is_gcd = function == MathFunctions::Gcd;
+    let mut result = if is_gcd { BigInt::zero() } else { BigInt::one() };
+    for value in integers {
+        defer_drop!(value, heap);
+        let n = int_arg(value, heap)?;
+        result = if is_gcd {
+            result.gcd(&n)
+        } else if result.is_zero() || n.is_zero() {
+            BigInt::zero()
+        } else {
+            check_mult_size(result.bits(), n.bits(), heap.tracker())?;
+            (&result / result.gcd(&n) * n).abs()
+        };
+    }
+    Ok(LongInt::new(result).into_value(heap)?)
+}
+
+/// Implementation of `math.factorial(n)`.
+fn factorial(n: &BigInt, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    if n.is_negative() {
+        return Err(
+            SimpleException::new_msg(ExcType::ValueError, "factorial() not defined for negative values").into(),
+        );
+    }
+    let Some(n) = n.to_u64().filter(|n| i64::try_from(*n).is_ok()) else {
+        return Err(SimpleException::new_msg(
+            ExcType::OverflowError,
+            format!("factorial() argument should not exceed {}", i64::MAX),
+        )
+        .into());
+    };
+    // n! < n ** n
+    check_int_size(
+        n.saturating_mul(u64::from(u64::BITS - n.leading_zeros())),
+        heap.tracker(),
+    )?;
+    let mut result = BigInt::one();
+    for i in 2..=n {
+        heap.tracker_mut().check_time()?;
+        result *= i;
+    }
+    Ok(LongInt::new(result).into_value(heap)?)
+}
+
+/// Implementation of `math.comb(n, k)` and `math.perm(n, k=None)`.
+///
+/// Both are computed with `k` multiplications of factors up to `n`, so the result has
+/// at most `k * n.bits()` bits, which is checked before computing it.
+fn comb_perm(
+    function: MathFunctions,
+    n: &Value,
+    k: Option<&Value>,
+    heap: &mut Heap<impl ResourceTracker>,
+) -> RunResult<Value> {
+    let n = int_arg(n, heap)?;
+    let Some(k) = k else {
+        return factorial(&n, heap);
+    };
+    let k = int_arg(k, heap)?;
+    if n.is_negative() {
+        return Err(SimpleException::new_msg(ExcType::ValueError, "n must be a non-negative integer").into());
+    }
+    if k.is_negative() {
+        return Err(SimpleException::new_msg(ExcType::ValueError, "k must be a non-negative integer").into());
+    }
+    if k > n {
+        return Ok(Value::Int(0));
+    }
+    let is_comb = function == MathFunctions::Comb;
+    // comb(n, k) == comb(n, n - k), so take the fewer factors
+    let k = if is_comb { (&n - &k).min(k) } else { k };
+    let Some(k) = k.to_u64().filter(|k| i64::try_from(*k).is_ok()) else {
+        let bound = if is_comb { "min(n - k, k)" } else { "k" };
+        return Err(
+            SimpleException::new_msg(ExcType::OverflowError, format!("{bound} must not exceed {}", i64::MAX)).into(),
+        );
+    };
+    check_int_size(k.saturating_mul(n.bits()), heap.tracker())?;
+
+    let mut result = BigInt::one();
+    for i in 0..k {
+        heap.tracker_mut().check_time()?;
+        result *= &n - i;
+        if is_comb {
+            // Exact: the product of i + 1 consecutive ints is divisible by (i + 1)!
+            result /= i + 1;
+        }
+    }
+    Ok(LongInt::new(result).into_value(heap)?)
+}
+
+/// Implementation of `math.prod(iterable, *, start=1)`.
+fn prod(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let (mut positional, kwargs) = args.into_parts();
+    if positional.len() != 1 {
+        let count = positional.len();
+        positional.drop_with_heap(heap);
+        kwargs.drop_with_heap(heap);
+        return Err(if count == 0 {
+            ExcType::type_error("prod() missing required argument 'iterable' (pos 1)")
+        } else {
+            ExcType::type_error(format!("prod() takes exactly 1 positional argument ({count} given)"))
+        });
+    }
+    let iterable = positional.next().expect("length checked above");
+    let [start] = match keyword_args("prod", kwargs, ["start"], heap, interns) {
+        Ok(start) => start,
+        Err(err) => {
+            iterable.drop_with_heap(heap);
+            return Err(err);
+        }
+    };
+
+    let mut accumulator = start.unwrap_or(Value::Int(1));
+    let mut iter = match MontyIter::new(iterable, heap, interns) {
+        Ok(iter) => iter,
+        Err(err) => {
+            accumulator.drop_with_heap(heap);
+            return Err(err);
+        }
+    };
+    loop {
+        let item = match iter.for_next(heap, interns) {
+            Ok(Some(item)) => item,
+            Ok(None) => break,
+            Err(err) => {
+                accumulator.drop_with_heap(heap);
+                iter.drop_with_heap(heap);
+                return Err(err);
+            }
+        };
+        let product = accumulator.py_mult(&item, heap, interns);
+        let result = match product {
+            Ok(Some(product)) => Ok(product),
+            Ok(None) => Err(ExcType::binary_type_error(
+                "*",
+                accumulator.py_type(heap),
+                item.py_type(heap),
+            )),
+            Err(err) => Err(err),
+        };
+        item.drop_with_heap(heap);
+        accumulator.drop_with_heap(heap);
+        match result {
+            Ok(product) => accumulator = product,
+            Err(err) => {
+                iter.drop_with_heap(heap);
+                return Err(err);
+            }
+        }
+    }
+    iter.drop_with_heap(heap);
+    Ok(accumulator)
+}
+
+/// Implementation of `math.fsum(iterable)`.
+///
+/// Uses Shewchuk's algorithm like CPython: the sum is kept exactly as a list of
+/// non-overlapping partial sums, which are only rounded once at the end.
+fn fsum(iterable: Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+    let mut iter = MontyIter::new(iterable, heap, interns)?;
+    let mut partials: Vec<f64> = Vec::new();
+    // Infinities and NaNs are summed separately, `inf_sum` only sees infinities
+    let mut special_sum = 0.0_f64;
+    let mut inf_sum = 0.0_f64;
+    loop {
+        let item = match iter.for_next(heap, interns) {
+            Ok(Some(item)) => item,
+            Ok(None) => break,
+            Err(err) => {
+                iter.drop_with_heap(heap);
+                return Err(err);
+            }
+        };
+        let x = real_arg(&item, heap);
+        item.drop_with_heap(heap);
+        let mut x = match x {
+            Ok(x) => x,
+            Err(err) => {
+                iter.drop_with_heap(heap);
+                return Err(err);
+            }
+        };
+
+        let original = x;
+        for mut y in std::mem::take(&mut partials) {
+            if x.abs() < y.abs() {
+                std::mem::swap(&mut x, &mut y);
+            }
+            let hi = x + y;
+            let lo = y - (hi - x);
+            if lo != 0.0 {
+                partials.push(lo);
+            }
+            x = hi;
+        }
+        if x != 0.0 {
+            if x.is_finite() {
+                partials.push(x);
+            } else if original.is_finite() {
+                iter.drop_with_heap(heap);
+                return Err(SimpleException::new_msg(ExcType::OverflowError, "intermediate overflow in fsum").into());
+            } else {
+                if original.is_infinite() {
+                    inf_sum += original;
+                }
+                special_sum += original;
+                partials.clear();
+            }
+        }
+    }
+    iter.drop_with_heap(heap);
+
+    if special_sum != 0.0 {
+        if inf_sum.is_nan() {
+            return Err(SimpleException::new_msg(ExcType::ValueError, "-inf + inf in fsum").into());
+        }
+        return Ok(Value::Float(special_sum));
+    }
+
+    // Add the partials from the largest, stopping once the sum is inexact
+    let Some(mut hi) = partials.pop() else {
+        return Ok(Value::Float(0.0));
+    };
+    let mut lo = 0.0;
+    while let Some(y) = partials.pop() {
+        let x = hi;
+        hi = x + y;
+        lo = y - (hi - x);
+        if lo != 0.0 {
+            break;
+        }
+    }
+    // Correct a half-way case where the rounding of `hi + lo` went the wrong way
+    if let Some(&next) = partials.last()
+        && ((lo < 0.0 && next < 0.0) || (lo > 0.0 && next > 0.0))
+    {
+        let y = lo * 2.0;
+        let x = hi + y;
+        if y == x - hi {
+            hi = x;
+        }
+    }
+    Ok(Value::Float(hi))
+}
+
+/// Binds keyword-only arguments to `params`, `None` for those that weren't passed.
+fn keyword_args<const N: usize>(
+    name: &str,
+    kwargs: KwargsValues,
+    params: [&str; N],
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<[Option<Value>; N]> {
+    let mut bound: [Option<Value>; N] = std::array::from_fn(|_| None);
+    let mut kwargs = kwargs.into_iter();
+    while let Some((key, value)) = kwargs.next() {
+        let index = match key.as_either_str(heap) {
+            Some(key_name) => {
+                let key_name = key_name.as_str(interns);
+                match params.iter().position(|&param| param == key_name) {
+                    Some(index) if bound[index].is_none() => Ok(index),
+                    Some(index) => Err(ExcType::type_error_duplicate_arg(name, params[index])),
+                    None => Err(ExcType::type_error_unexpected_keyword(name, key_name)),
+                }
+            }
+            None => Err(ExcType::type_error("keywords must be strings")),
+        };
+        key.drop_with_heap(heap);
+        match index {
+            Ok(index) => bound[index] = Some(value),
+            Err(err) => {
+                value.drop_with_heap(heap);
+                kwargs.drop_with_heap(heap);
+                for value in bound {
+                    value.drop_with_heap(heap);
+                }
+                return Err(err);
+            }
+        }
+    }
+    Ok(bound)
+}
+
+/// Converts a real number argument to a float.
+fn real_arg(value: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<f64> {
+    match value {
+        Value::Float(f) => Ok(*f),
+        Value::Int(i) => Ok(*i as f64),
+        Value::Bool(b) => Ok(f64::from(u8::from(*b))),
+        Value::Ref(id) => match heap.get(*id) {
+            HeapData::LongInt(li) => li.to_f64().filter(|f| f.is_finite()).ok_or_else(|| {
+                SimpleException::new_msg(ExcType::OverflowError, "int too large to convert to float").into()
+            }),
+            _ => Err(type_error_real(value, heap)),
+        },
+        _ => Err(type_error_real(value, heap)),
+    }
+}
+
+/// Creates the `TypeError` for an argument that isn't a real number.
+fn type_error_real(value: &Value, heap: &Heap<impl ResourceTracker>) -> RunError {
+    ExcType::type_error(format!("must be real number, not {}", value.py_type(heap)))
+}
+
+/// Converts an integer argument to a `BigInt`, so the integer functions work on ints of any size.
+fn int_arg(value: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<BigInt> {
+    match value {
+        Value::Int(i) => Ok(BigInt::from(*i)),
+        Value::Bool(b) => Ok(BigInt::from(u8::from(*b))),
+        Value::Ref(id) => match heap.get(*id) {
+            HeapData::LongInt(li) => Ok(li.inner().clone()),
+            _ => Err(ExcType::type_error_not_integer(value.py_type(heap))),
+        },
+        _ => Err(ExcType::type_error_not_integer(value.py_type(heap))),
+    }
+}
+
+/// Creates the `ValueError` for arguments outside the domain of a function.
+fn math_domain_error() -> RunError {
+    SimpleException::new_msg(ExcType::ValueError, "math domain error").into()
+}
+
+/// Creates the `OverflowError` for results too large for a float.
+fn math_range_error() -> RunError {
+    SimpleException::new_msg(ExcType::OverflowError, "math range error").into()
+}
@@ -1,7 +1,8 @@
 //! Built-in module implementations.
 //!
 //! This module provides implementations for Python built-in modules like `sys`, `typing`,
-//! and `asyncio`. These are created on-demand when import statements are executed.
+//! `asyncio`, `json`, `re`, `math`, `datetime`, `time` and `collections`. These are created
+//! on-demand when import statements are executed.
 
 use std::fmt::{self, Write};
 
@@ -9,17 +10,25 @@ use strum::FromRepr;
 
 use crate::{
     args::ArgValues,
-    exception_private::RunResult,
-    heap::{Heap, HeapId},
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{ContainsHeap, DropWithHeap, Heap, HeapId},
     intern::{Interns, StaticStrings, StringId},
     resource::{ResourceError, ResourceTracker},
     types::AttrCallResult,
+    value::Value,
 };
 
 pub(crate) mod asyncio;
+pub(crate) mod collections;
+pub(crate) mod datetime;
+pub(crate) mod json;
+pub(crate) mod math;
 pub(crate) mod os;
+pub(crate) mod os_path;
 pub(crate) mod pathlib;
+pub(crate) mod re;
 pub(crate) mod sys;
+pub(crate) mod time;
 pub(crate) mod typing;
 
 /// Built-in modules that can be imported.
@@ -36,6 +45,21 @@ pub(crate) enum BuiltinModule {
     Pathlib,
     /// The `os` module providing operating system interface (only `getenv()` implemented).
     Os,
+    /// The `os.path` module providing path manipulation, also available as `os.path`.
+    OsPath,
+    /// The `json` module providing JSON serialization (`dumps()` and `loads()`).
+    Json,
+    /// The `re` module providing regular expressions.
+    Re,
+    /// The `math` module providing mathematical functions and constants.
+    Math,
+    /// The `datetime` module providing dates, times and durations.
+    Datetime,
+    /// The `time` module providing the host-controlled clock (`time()`, `monotonic()` and `sleep()`).
+    Time,
+    /// The `collections` module providing container types (`deque`, `defaultdict`, `Counter`,
+    /// `OrderedDict` and `namedtuple()`).
+    Collections,
 }
 
 impl BuiltinModule {
@@ -47,6 +71,13 @@ impl BuiltinModule {
             StaticStrings::Asyncio => Some(Self::Asyncio),
             StaticStrings::Pathlib => Some(Self::Pathlib),
             StaticStrings::Os => Some(Self::Os),
+            StaticStrings::OsPath => Some(Self::OsPath),
+            StaticStrings::Json => Some(Self::Json),
+            StaticStrings::Re => Some(Self::Re),
+            StaticStrings::Math => Some(Self::Math),
+            StaticStrings::Datetime => Some(Self::Datetime),
+            StaticStrings::Time => Some(Self::Time),
+            StaticStrings::Collections => Some(Self::Collections),
             _ => None,
         }
     }
@@ -65,6 +96,13 @@ impl BuiltinModule {
             Self::Asyncio => asyncio::create_module(heap, interns),
             Self::Pathlib => pathlib::create_module(heap, interns),
             Self::Os => os::create_module(heap, interns),
+            Self::OsPath => os_path::create_module(heap, interns),
+            Self::Json => json::create_module(heap, interns),
+            Self::Re => re::create_module(heap, interns),
+            Self::Math => math::create_module(heap, interns),
+            Self::Datetime => datetime::create_module(heap, interns),
+            Self::Time => time::create_module(heap, interns),
+            Self::Collections => collections::create_module(heap, interns),
         }
     }
 }
@@ -74,6 +112,12 @@ impl BuiltinModule {
 pub(crate) enum ModuleFunctions {
     Asyncio(asyncio::AsyncioFunctions),
     Os(os::OsFunctions),
+    OsPath(os_path::OsPathFunctions),
+    Json(json::JsonFunctions),
+    Re(re::ReFunctions),
+    Math(math::MathFunctions),
+    Time(time::TimeFunctions),
+    Collections(collections::CollectionsFunctions),
 }
 
 impl fmt::Display for ModuleFunctions {
@@ -81,6 +125,12 @@ impl fmt::Display for ModuleFunctions {
         match self {
             Self::Asyncio(func) => write!(f, "{func}"),
             Self::Os(func) => write!(f, "{func}"),
+            Self::OsPath(func) => write!(f, "{func}"),
+            Self::Json(func) => write!(f, "{func}"),
+            Self::Re(func) => write!(f, "{func}"),
+            Self::Math(func) => write!(f, "{func}"),
+            Self::Time(func) => write!(f, "{func}"),
+            Self::Collections(func) => write!(f, "{func}"),
         }
     }
 }
@@ -90,10 +140,21 @@ impl ModuleFunctions {
     ///
     /// Returns `AttrCallResult` to support both immediate values and OS calls that
     /// require host involvement (e.g., `os.getenv()` needs the host to provide environment variables).
-    pub fn call(self, heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<AttrCallResult> {
+    pub fn call(
+        self,
+        heap: &mut Heap<impl ResourceTracker>,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<AttrCallResult> {
         match self {
-            Self::Asyncio(functions) => asyncio::call(heap, functions, args),
+            Self::Asyncio(functions) => asyncio::call(heap, functions, args, interns),
             Self::Os(functions) => os::call(heap, functions, args),
+            Self::OsPath(functions) => os_path::call(heap, functions, args, interns),
+            Self::Json(functions) => json::call(heap, functions, args, interns),
+            Self::Re(functions) => re::call(heap, functions, args, interns),
+            Self::Math(functions) => math::call(heap, functions, args, interns),
+            Self::Time(functions) => time::call(heap, functions, args),
+            Self::Collections(functions) => collections::call(heap, functions, args, interns),
         }
     }
 
@@ -102,3 +163,18 @@ impl ModuleFunctions {
         write!(f, "<function {self} at 0x{py_id:x}>")
     }
 }
+
+/// Context that module functions which call back into Python code run in: it owns the heap,
+/// and can call functions passed as arguments, like `default=` of `json.dumps()`.
+pub(crate) trait CallbackHost<T: ResourceTracker>: ContainsHeap<T> {
+    /// Calls `function(arg)`.
+    fn call_one(&mut self, function: &Value, arg: Value) -> RunResult<Value>;
+}
+
+/// The heap on its own can't run Python code, so calling back raises an error.
+impl<T: ResourceTracker> CallbackHost<T> for Heap<T> {
+    fn call_one(&mut self, _function: &Value, arg: Value) -> RunResult<Value> {
+        arg.drop_with_heap(self);
+        Err(SimpleException::new_msg(ExcType::RuntimeError, "can't call a Python function here").into())
+    }
+}
@@ -3,6 +3,7 @@
 //! Provides a minimal implementation of Python's `os` module with:
 //! - `getenv(key, default=None)`: Get a single environment variable
 //! - `environ`: Property that returns the entire environment as a dict
+//! - `path`: The `os.path` module
 //!
 //! Other os functions are not implemented. OS operations require host involvement
 //! via the `OsFunction` callback mechanism - Monty yields control to the host
@@ -32,8 +33,9 @@ pub(crate) enum OsFunctions {
 /// The module provides:
 /// - `getenv(key, default=None)`: Get a single environment variable
 /// - `environ`: Property that returns the entire environment as a dict
+/// - `path`: The `os.path` module
 ///
-/// Both operations yield to the host via `OsFunction` callbacks.
+/// `getenv` and `environ` yield to the host via `OsFunction` callbacks.
 ///
 /// # Returns
 /// A HeapId pointing to the newly allocated module.
@@ -59,6 +61,10 @@ pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -
         interns,
     );
 
+    // os.path - submodule for path manipulation
+    let path_id = super::os_path::create_module(heap, interns)?;
+    module.set_attr(StaticStrings::Path, Value::Ref(path_id), heap, interns);
+
     heap.allocate(HeapData::Module(module))
 }
 
@@ -0,0 +1,215 @@
+//! Implementation of the `os.path` module.
+//!
+//! Provides a minimal implementation of Python's `os.path` module (POSIX flavour) with:
+//! - `join(a, *p)`, `split(p)`, `splitext(p)`, `basename(p)`, `dirname(p)`, `isabs(p)`:
+//!   pure string operations computed directly
+//! - `exists(p)`, `isfile(p)`, `isdir(p)`: filesystem checks which yield to the host
+//!   as the corresponding `Path` operations
+//!
+//! The module is reachable as `import os.path`, `from os import path`, or `os.path`.
+
+use smallvec::smallvec;
+
+use crate::{
+    args::ArgValues,
+    defer_drop, defer_drop_mut,
+    exception_private::{ExcType, RunResult},
+    heap::{Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    modules::ModuleFunctions,
+    os::OsFunction,
+    resource::{ResourceError, ResourceTracker},
+    types::{AttrCallResult, Module, Path, PyTrait, allocate_tuple, str::allocate_string},
+    value::Value,
+};
+
+/// `os.path` module functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum OsPathFunctions {
+    Join,
+    Split,
+    Splitext,
+    Basename,
+    Dirname,
+    Isabs,
+    Exists,
+    Isfile,
+    Isdir,
+}
+
+/// Creates the `os.path` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::OsPath);
+
+    let functions = [
+        (StaticStrings::Join, OsPathFunctions::Join),
+        (StaticStrings::Split, OsPathFunctions::Split),
+        (StaticStrings::Splitext, OsPathFunctions::Splitext),
+        (StaticStrings::Basename, OsPathFunctions::Basename),
+        (StaticStrings::Dirname, OsPathFunctions::Dirname),
+        (StaticStrings::Isabs, OsPathFunctions::Isabs),
+        (StaticStrings::Exists, OsPathFunctions::Exists),
+        (StaticStrings::Isfile, OsPathFunctions::Isfile),
+        (StaticStrings::Isdir, OsPathFunctions::Isdir),
+    ];
+    for (name, function) in functions {
+        module.set_attr(
+            name,
+            Value::ModuleFunction(ModuleFunctions::OsPath(function)),
+            heap,
+            interns,
+        );
+    }
+
+    heap.allocate(HeapData::Module(module))
+}
+
+/// Dispatches a call to an `os.path` module function.
+///
+/// Returns `AttrCallResult::OsCall` for the filesystem checks, which need the host,
+/// or `AttrCallResult::Value` for the pure string operations.
+pub(super) fn call(
+    heap: &mut Heap<impl ResourceTracker>,
+    function: OsPathFunctions,
+    args: ArgValues,
+    interns: &Interns,
+) -> RunResult<AttrCallResult> {
+    if function == OsPathFunctions::Join {
+        return join(heap, args, interns).map(AttrCallResult::Value);
+    }
+    let path = path_arg(&function.to_string(), heap, args, interns)?;
+    let value = match function {
+        OsPathFunctions::Split => allocate_pair(split(&path), heap)?,
+        OsPathFunctions::Splitext => allocate_pair(splitext(&path), heap)?,
+        OsPathFunctions::Basename => allocate_string(split(&path).1.to_owned(), heap)?,
+        OsPathFunctions::Dirname => allocate_string(split(&path).0.to_owned(), heap)?,
+        OsPathFunctions::Isabs => Value::Bool(path.starts_with('/')),
+        // Filesystem checks are delegated to the host as the equivalent `Path` method
+        OsPathFunctions::Exists => return os_call(OsFunction::Exists, path, heap),
+        OsPathFunctions::Isfile => return os_call(OsFunction::IsFile, path, heap),
+        OsPathFunctions::Isdir => return os_call(OsFunction::IsDir, path, heap),
+        OsPathFunctions::Join => unreachable!("join is handled above"),
+    };
+    Ok(AttrCallResult::Value(value))
+}
+
+/// Builds the OS call for a filesystem check, passing the path as a `Path` like `Path` methods do.
+fn os_call(os_function: OsFunction, path: String, heap: &mut Heap<impl ResourceTracker>) -> RunResult<AttrCallResult> {
+    let path = Value::Ref(heap.allocate(HeapData::Path(Path::new(path)))?);
+    Ok(AttrCallResult::OsCall(os_function, ArgValues::One(path)))
+}
+
+/// Allocates the `(head, tail)` tuple returned by `split()` and `splitext()`.
+fn allocate_pair((first, second): (&str, &str), heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    let first = allocate_string(first.to_owned(), heap)?;
+    let second = allocate_string(second.to_owned(), heap)?;
+    Ok(allocate_tuple(smallvec![first, second], heap)?)
+}
+
+/// Implementation of `os.path.join(a, *p)`.
+///
+/// An absolute component discards everything before it, and a separator is only
+/// inserted when the path so far doesn't already end with one.
+fn join(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let pos_args = args.into_pos_only("join", heap)?;
+    defer_drop_mut!(pos_args, heap);
+
+    let Some(first) = pos_args.next() else {
+        return Err(ExcType::type_error(
+            "join() missing 1 required positional argument: 'a'",
+        ));
+    };
+    defer_drop!(first, heap);
+    let Some(first) = path_str(first, heap, interns) else {
+        return Err(ExcType::type_error(format!(
+            "expected str, bytes or os.PathLike object, not {}",
+            first.py_type(heap)
+        )));
+    };
+    let mut path = first.to_owned();
+
+    for part in pos_args {
+        defer_drop!(part, heap);
+        let Some(part) = path_str(part, heap, interns) else {
+            return Err(ExcType::type_error(format!(
+                "join() argument must be str, bytes, or os.PathLike object, not '{}'",
+                part.py_type(heap)
+            )));
+        };
+        if part.starts_with('/') {
+            path = part.to_owned();
+        } else if path.is_empty() || path.ends_with('/') {
+            path.push_str(part);
+        } else {
+            path.push('/');
+            path.push_str(part);
+        }
+    }
+    allocate_string(path, heap)
+}
+
+/// Splits a path into `(head, tail)` where `tail` is everything after the final slash.
+///
+/// Trailing slashes are stripped from `head` unless it consists only of slashes.
+fn split(path: &str) -> (&str, &str) {
+    let index = path.rfind('/').map_or(0, |i| i + 1);
+    let (head, tail) = path.split_at(index);
+    if head.bytes().all(|b| b == b'/') {
+        (head, tail)
+    } else {
+        (head.trim_end_matches('/'), tail)
+    }
+}
+
+/// Splits a path into `(root, ext)` where `ext` starts at the last dot of the final component.
+///
+/// Leading dots of the final component don't start an extension, so `.bashrc` has none.
+fn splitext(path: &str) -> (&str, &str) {
+    let name_start = path.rfind('/').map_or(0, |i| i + 1);
+    if let Some(dot) = path.rfind('.')
+        && dot > name_start
+        && path[name_start..dot].bytes().any(|b| b != b'.')
+    {
+        path.split_at(dot)
+    } else {
+        (path, "")
+    }
+}
+
+/// Extracts the single path argument of an `os.path` function as a string.
+fn path_arg(
+    name: &str,
+    heap: &mut Heap<impl ResourceTracker>,
+    args: ArgValues,
+    interns: &Interns,
+) -> RunResult<String> {
+    let value = args.get_one_arg(name, heap)?;
+    defer_drop!(value, heap);
+    match path_str(value, heap, interns) {
+        Some(path) => Ok(path.to_owned()),
+        None => Err(ExcType::type_error(format!(
+            "expected str, bytes or os.PathLike object, not {}",
+            value.py_type(heap)
+        ))),
+    }
+}
+
+/// Returns the path a `str` or `Path` value represents, or `None` for other types.
+fn path_str<'a>(value: &Value, heap: &'a Heap<impl ResourceTracker>, interns: &'a Interns) -> Option<&'a str> {
+    match value {
+        Value::InternString(string_id) => Some(interns.get_str(*string_id)),
+        Value::Ref(heap_id) => match heap.get(*heap_id) {
+            HeapData::Str(s) => Some(s.as_str()),
+            HeapData::Path(p) => Some(p.as_str()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
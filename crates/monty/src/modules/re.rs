@@ -0,0 +1,709 @@
+//! Implementation of the `re` module.
+//!
+//! Provides a native implementation of Python's `re` module with:
+//! - `compile(pattern, flags=0)`: compiles a pattern to an `re.Pattern`
+//! - `search()`, `match()`, `fullmatch()`: find a single match, returning an `re.Match` or `None`
+//! - `findall()`, `finditer()`: find all non-overlapping matches
+//! - `sub()`, `subn()`: replace matches with a template or the result of a function
+//! - `split()`: split a string by the matches of a pattern
+//! - `escape(pattern)`: escapes special characters
+//! - `error`/`PatternError`: the exception raised for invalid patterns
+//! - the `IGNORECASE`, `MULTILINE`, `DOTALL`, `VERBOSE`, `ASCII`, `UNICODE` and `NOFLAG` flags,
+//!   with their single letter aliases
+//!
+//! Patterns run on a linear-time engine, see [`RePattern`] for the syntax that's supported.
+//!
+//! Differences from CPython:
+//! - flags are plain `int`s rather than `re.RegexFlag` members
+//! - bytes patterns and subjects aren't supported, nor are `LOCALE` and `DEBUG`
+//! - `purge()` and the pattern cache aren't implemented: module functions compile their pattern
+//!   on every call, so compile patterns that are used repeatedly
+//! - methods of patterns and matches can only be called directly, not looked up as values
+
+use crate::{
+    args::{ArgValues, BoundArgs, bind_args},
+    builtins::{Builtins, is_callable},
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    modules::{CallbackHost, ModuleFunctions},
+    resource::{ResourceError, ResourceTracker, check_output_size},
+    types::{
+        AttrCallResult, List, Module, MontyIter, PyTrait, ReMatch, RePattern, Str, Type,
+        bytes::bytes_like,
+        re_match::{Template, type_error_expected_str},
+        re_pattern::{
+            FLAG_ASCII, FLAG_DOTALL, FLAG_IGNORECASE, FLAG_MULTILINE, FLAG_UNICODE, FLAG_VERBOSE, GroupSpans,
+            MatchMode, Matcher,
+        },
+        str::allocate_string,
+        tuple::{TupleVec, allocate_tuple},
+    },
+    value::Value,
+};
+
+/// `re` module functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum ReFunctions {
+    Compile,
+    Search,
+    Match,
+    Fullmatch,
+    Findall,
+    Finditer,
+    Sub,
+    Subn,
+    Split,
+    Escape,
+}
+
+/// Creates the `re` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Re);
+
+    let functions = [
+        (StaticStrings::Compile, ReFunctions::Compile),
+        (StaticStrings::Search, ReFunctions::Search),
+        (StaticStrings::Match, ReFunctions::Match),
+        (StaticStrings::Fullmatch, ReFunctions::Fullmatch),
+        (StaticStrings::Findall, ReFunctions::Findall),
+        (StaticStrings::Finditer, ReFunctions::Finditer),
+        (StaticStrings::Sub, ReFunctions::Sub),
+        (StaticStrings::Subn, ReFunctions::Subn),
+        (StaticStrings::Split, ReFunctions::Split),
+        (StaticStrings::Escape, ReFunctions::Escape),
+    ];
+    for (name, function) in functions {
+        module.set_attr(
+            name,
+            Value::ModuleFunction(ModuleFunctions::Re(function)),
+            heap,
+            interns,
+        );
+    }
+
+    for name in [StaticStrings::Error, StaticStrings::PatternError] {
+        module.set_attr(
+            name,
+            Value::Builtin(Builtins::ExcType(ExcType::PatternError)),
+            heap,
+            interns,
+        );
+    }
+    module.set_attr(
+        StaticStrings::PatternClass,
+        Value::Builtin(Builtins::Type(Type::RePattern)),
+        heap,
+        interns,
+    );
+    module.set_attr(
+        StaticStrings::MatchClass,
+        Value::Builtin(Builtins::Type(Type::ReMatch)),
+        heap,
+        interns,
+    );
+
+    module.set_attr(StaticStrings::Noflag, Value::Int(0), heap, interns);
+    let flags = [
+        (StaticStrings::Ignorecase, b'I', FLAG_IGNORECASE),
+        (StaticStrings::Multiline, b'M', FLAG_MULTILINE),
+        (StaticStrings::Dotall, b'S', FLAG_DOTALL),
+        (StaticStrings::Verbose, b'X', FLAG_VERBOSE),
+        (StaticStrings::Ascii, b'A', FLAG_ASCII),
+        (StaticStrings::Unicode, b'U', FLAG_UNICODE),
+    ];
+    for (name, alias, flag) in flags {
+        module.set_attr(name, Value::Int(flag), heap, interns);
+        module.set_attr(StringId::from_ascii(alias), Value::Int(flag), heap, interns);
+    }
+
+    heap.allocate(HeapData::Module(module))
+}
+
+/// Dispatches a call to an `re` module function.
+///
+/// The VM calls `sub()` and `subn()` itself so that replacement functions can run Python code,
+/// this path is used where no VM is available, with the heap as a host that can't call them.
+pub(super) fn call(
+    heap: &mut Heap<impl ResourceTracker>,
+    function: ReFunctions,
+    args: ArgValues,
+    interns: &Interns,
+) -> RunResult<AttrCallResult> {
+    call_function(heap, function, args, interns).map(AttrCallResult::Value)
+}
+
+/// Calls an `re` module function.
+pub(crate) fn call_function<T: ResourceTracker>(
+    host: &mut impl CallbackHost<T>,
+    function: ReFunctions,
+    args: ArgValues,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let name = function.to_string();
+    match function {
+        ReFunctions::Compile => {
+            let heap = host.heap_mut();
+            let args = bind_args(&name, ["pattern", "flags"], 1, args, heap, interns)?;
+            let result = int_arg(args.get(1), 0, heap).and_then(|flags| {
+                let pattern = args.get(0).expect("required argument");
+                pattern_arg(pattern, flags, heap, interns).map(Value::Ref)
+            });
+            args.drop_with_heap(heap);
+            result
+        }
+        ReFunctions::Escape => {
+            let heap = host.heap_mut();
+            let args = bind_args(&name, ["pattern"], 1, args, heap, interns)?;
+            let result = str_arg(args.get(0).expect("required argument"), heap, interns)
+                .and_then(|pattern| allocate_string(escape(&pattern), heap));
+            args.drop_with_heap(heap);
+            result
+        }
+        ReFunctions::Search
+        | ReFunctions::Match
+        | ReFunctions::Fullmatch
+        | ReFunctions::Findall
+        | ReFunctions::Finditer => call_with_pattern(host, function, ["pattern", "string", "flags"], 2, args, interns),
+        ReFunctions::Sub | ReFunctions::Subn => call_with_pattern(
+            host,
+            function,
+            ["pattern", "repl", "string", "count", "flags"],
+            3,
+            args,
+            interns,
+        ),
+        ReFunctions::Split => call_with_pattern(
+            host,
+            function,
+            ["pattern", "string", "maxsplit", "flags"],
+            2,
+            args,
+            interns,
+        ),
+    }
+}
+
+/// Calls a module function that takes a pattern as its first argument and flags as its last.
+///
+/// The pattern is compiled, and the function run on it with the arguments in between.
+fn call_with_pattern<T: ResourceTracker, const N: usize>(
+    host: &mut impl CallbackHost<T>,
+    function: ReFunctions,
+    params: [&str; N],
+    required: usize,
+    args: ArgValues,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let heap = host.heap_mut();
+    let args = bind_args(&function.to_string(), params, required, args, heap, interns)?;
+    let pattern = int_arg(args.get(N - 1), 0, heap).and_then(|flags| {
+        let pattern = args.get(0).expect("required argument");
+        pattern_arg(pattern, flags, heap, interns)
+    });
+    let result = pattern.and_then(|pattern_id| {
+        let result = run(host, function, pattern_id, &args.0[1..N - 1], interns);
+        Value::Ref(pattern_id).drop_with_heap(host.heap_mut());
+        result
+    });
+    args.drop_with_heap(host.heap_mut());
+    result
+}
+
+/// Calls a method of the `re.Pattern` at `pattern_id`.
+///
+/// Patterns are handled here rather than by the heap, since matches need the id of their pattern.
+pub(crate) fn call_pattern_method<T: ResourceTracker>(
+    host: &mut impl CallbackHost<T>,
+    pattern_id: HeapId,
+    name_id: StringId,
+    args: ArgValues,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let heap = host.heap_mut();
+    let (function, args) = match StaticStrings::from_string_id(name_id) {
+        Some(StaticStrings::Search) => (ReFunctions::Search, bind_find_args("search", args, heap, interns)?),
+        Some(StaticStrings::Match) => (ReFunctions::Match, bind_find_args("match", args, heap, interns)?),
+        Some(StaticStrings::Fullmatch) => (
+            ReFunctions::Fullmatch,
+            bind_find_args("fullmatch", args, heap, interns)?,
+        ),
+        Some(StaticStrings::Findall) => (ReFunctions::Findall, bind_find_args("findall", args, heap, interns)?),
+        Some(StaticStrings::Finditer) => (ReFunctions::Finditer, bind_find_args("finditer", args, heap, interns)?),
+        Some(StaticStrings::Sub) => (
+            ReFunctions::Sub,
+            bind_args("sub", ["repl", "string", "count"], 2, args, heap, interns)?,
+        ),
+        Some(StaticStrings::Subn) => (
+            ReFunctions::Subn,
+            bind_args("subn", ["repl", "string", "count"], 2, args, heap, interns)?,
+        ),
+        Some(StaticStrings::Split) => {
+            let BoundArgs([string, maxsplit]) = bind_args("split", ["string", "maxsplit"], 1, args, heap, interns)?;
+            (ReFunctions::Split, BoundArgs([string, maxsplit, None]))
+        }
+        _ => {
+            args.drop_with_heap(heap);
+            return Err(ExcType::attribute_error(Type::RePattern, interns.get_str(name_id)));
+        }
+    };
+    let result = run(host, function, pattern_id, &args.0, interns);
+    args.drop_with_heap(host.heap_mut());
+    result
+}
+
+/// Binds the `(string, pos=0, endpos=None)` arguments of the pattern methods that find matches.
+fn bind_find_args(
+    name: &str,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<BoundArgs<3>> {
+    bind_args(name, ["string", "pos", "endpos"], 1, args, heap, interns)
+}
+
+/// Runs `function` with the pattern at `pattern_id`.
+///
+/// `args` are the function's arguments after the pattern and before the flags, which are
+/// the same for module functions and pattern methods apart from the `pos` and `endpos`
+/// arguments that only methods have.
+fn run<T: ResourceTracker>(
+    host: &mut impl CallbackHost<T>,
+    function: ReFunctions,
+    pattern_id: HeapId,
+    args: &[Option<Value>],
+    interns: &Interns,
+) -> RunResult<Value> {
+    let arg = |i: usize| args.get(i).and_then(Option::as_ref);
+    let heap = host.heap_mut();
+    match function {
+        ReFunctions::Search
+        | ReFunctions::Match
+        | ReFunctions::Fullmatch
+        | ReFunctions::Findall
+        | ReFunctions::Finditer => {
+            let string = arg(0).expect("required argument");
+            let pos = int_arg(arg(1), 0, heap)?;
+            let endpos = arg(2).map(|endpos| int_arg(Some(endpos), 0, heap)).transpose()?;
+            match function {
+                ReFunctions::Search => find(heap, pattern_id, MatchMode::Search, string, pos, endpos, interns),
+                ReFunctions::Match => find(heap, pattern_id, MatchMode::Match, string, pos, endpos, interns),
+                ReFunctions::Fullmatch => find(heap, pattern_id, MatchMode::FullMatch, string, pos, endpos, interns),
+                ReFunctions::Findall => findall(heap, pattern_id, string, pos, endpos, interns),
+                _ => finditer(heap, pattern_id, string, pos, endpos, interns),
+            }
+        }
+        ReFunctions::Sub | ReFunctions::Subn => {
+            let repl = arg(0).expect("required argument");
+            let string = arg(1).expect("required argument");
+            let count = int_arg(arg(2), 0, heap)?;
+            sub(
+                host,
+                pattern_id,
+                repl,
+                string,
+                count,
+                function == ReFunctions::Subn,
+                interns,
+            )
+        }
+        ReFunctions::Split => {
+            let string = arg(0).expect("required argument");
+            let maxsplit = int_arg(arg(1), 0, heap)?;
+            split(heap, pattern_id, string, maxsplit, interns)
+        }
+        ReFunctions::Compile | ReFunctions::Escape => unreachable!("{function}() doesn't take a pattern to run"),
+    }
+}
+
+/// Implements `search()`, `match()` and `fullmatch()`.
+fn find(
+    heap: &mut Heap<impl ResourceTracker>,
+    pattern_id: HeapId,
+    mode: MatchMode,
+    string: &Value,
+    pos: i64,
+    endpos: Option<i64>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let text = str_arg(string, heap, interns)?;
+    let range = SearchRange::new(&text, pos, endpos);
+    let Some(groups) = matcher(heap, pattern_id)?.find(&text, range.start, range.end, mode) else {
+        return Ok(Value::None);
+    };
+    let string_id = subject_id(string, text, heap)?;
+    let result = allocate_match(heap, pattern_id, string_id, groups, &range);
+    Value::Ref(string_id).drop_with_heap(heap);
+    result
+}
+
+/// Implements `findall()`: the matched strings, or the groups of each match if the pattern has groups.
+fn findall(
+    heap: &mut Heap<impl ResourceTracker>,
+    pattern_id: HeapId,
+    string: &Value,
+    pos: i64,
+    endpos: Option<i64>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let text = str_arg(string, heap, interns)?;
+    let range = SearchRange::new(&text, pos, endpos);
+    let matcher = matcher(heap, pattern_id)?;
+    let matches = matcher.find_all(&text, range.start, range.end, 0, heap.tracker_mut())?;
+    let group_count = matcher.group_count();
+    let group_text = |groups: &GroupSpans, index: usize| {
+        groups[index].map_or(String::new(), |(start, end)| text[start..end].to_owned())
+    };
+
+    let mut items = Vec::with_capacity(matches.len());
+    for groups in &matches {
+        let item = match group_count {
+            0 => allocate_string(group_text(groups, 0), heap),
+            1 => allocate_string(group_text(groups, 1), heap),
+            _ => {
+                let mut values = TupleVec::with_capacity(group_count);
+                let mut error = None;
+                for index in 1..=group_count {
+                    match allocate_string(group_text(groups, index), heap) {
+                        Ok(value) => values.push(value),
+                        Err(err) => {
+                            error = Some(err);
+                            break;
+                        }
+                    }
+                }
+                match error {
+                    None => allocate_tuple(values, heap).map_err(Into::into),
+                    Some(err) => {
+                        values.into_iter().for_each(|value| value.drop_with_heap(heap));
+                        Err(err)
+                    }
+                }
+            }
+        };
+        match item {
+            Ok(item) => items.push(item),
+            Err(err) => {
+                items.drop_with_heap(heap);
+                return Err(err);
+            }
+        }
+    }
+    Ok(Value::Ref(heap.allocate(HeapData::List(List::new(items)))?))
+}
+
+/// Implements `finditer()`, returning an iterator over a list of the matches.
+fn finditer(
+    heap: &mut Heap<impl ResourceTracker>,
+    pattern_id: HeapId,
+    string: &Value,
+    pos: i64,
+    endpos: Option<i64>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let text = str_arg(string, heap, interns)?;
+    let range = SearchRange::new(&text, pos, endpos);
+    let matches = matcher(heap, pattern_id)?.find_all(&text, range.start, range.end, 0, heap.tracker_mut())?;
+    let string_id = subject_id(string, text, heap)?;
+
+    let mut items = Vec::with_capacity(matches.len());
+    let mut error = None;
+    for groups in matches {
+        match allocate_match(heap, pattern_id, string_id, groups, &range) {
+            Ok(item) => items.push(item),
+            Err(err) => {
+                error = Some(err);
+                break;
+            }
+        }
+    }
+    Value::Ref(string_id).drop_with_heap(heap);
+    if let Some(err) = error {
+        items.drop_with_heap(heap);
+        return Err(err);
+    }
+    let list = Value::Ref(heap.allocate(HeapData::List(List::new(items)))?);
+    let iter = MontyIter::new(list, heap, interns)?;
+    Ok(Value::Ref(heap.allocate(HeapData::Iter(iter))?))
+}
+
+/// Implements `sub()` and `subn()`.
+///
+/// `repl` is either a template string or a function called with each match, which needs
+/// the host to call it.
+fn sub<T: ResourceTracker>(
+    host: &mut impl CallbackHost<T>,
+    pattern_id: HeapId,
+    repl: &Value,
+    string: &Value,
+    count: i64,
+    with_count: bool,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let heap = host.heap_mut();
+    let matcher = matcher(heap, pattern_id)?;
+    let template = if is_callable(repl, heap) {
+        None
+    } else {
+        let Some(repl) = repl.as_either_str(heap) else {
+            return Err(type_error_expected_str(repl.py_type(heap)));
+        };
+        Some(Template::parse(repl.as_str(interns), &matcher)?)
+    };
+    let text = str_arg(string, heap, interns)?;
+
+    // A negative count replaces nothing
+    let matches = match usize::try_from(count) {
+        Ok(limit) => matcher.find_all(&text, 0, text.len(), limit, heap.tracker_mut())?,
+        Err(_) => Vec::new(),
+    };
+    let replaced = matches.len();
+
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    if let Some(template) = template {
+        for groups in &matches {
+            let (start, end) = groups[0].expect("the whole match is always set");
+            out.push_str(&text[last..start]);
+            template.expand(&text, groups, &mut out);
+            check_output_size(out.len(), heap.tracker())?;
+            last = end;
+        }
+    } else if !matches.is_empty() {
+        let string_id = subject_id(string, text.clone(), heap)?;
+        let result = sub_with_function(host, pattern_id, string_id, repl, &text, matches, &mut out, interns);
+        Value::Ref(string_id).drop_with_heap(host.heap_mut());
+        last = result?;
+    }
+    out.push_str(&text[last..]);
+
+    let heap = host.heap_mut();
+    check_output_size(out.len(), heap.tracker())?;
+    let result = allocate_string(out, heap)?;
+    if with_count {
+        let count = Value::Int(i64::try_from(replaced).expect("match count fits in i64"));
+        Ok(allocate_tuple(TupleVec::from_iter([result, count]), heap)?)
+    } else {
+        Ok(result)
+    }
+}
+
+/// Replaces `matches` with the results of calling `function` on them, appending the text up
+/// to the end of the last match to `out`.
+///
+/// Returns the end of the last match.
+#[expect(clippy::too_many_arguments)]
+fn sub_with_function<T: ResourceTracker>(
+    host: &mut impl CallbackHost<T>,
+    pattern_id: HeapId,
+    string_id: HeapId,
+    function: &Value,
+    text: &str,
+    matches: Vec<GroupSpans>,
+    out: &mut String,
+    interns: &Interns,
+) -> RunResult<usize> {
+    let range = SearchRange::new(text, 0, None);
+    let mut last = 0;
+    for groups in matches {
+        let (start, end) = groups[0].expect("the whole match is always set");
+        let match_value = allocate_match(host.heap_mut(), pattern_id, string_id, groups, &range)?;
+        let replacement = host.call_one(function, match_value)?;
+        let heap = host.heap_mut();
+        let Some(replacement_text) = replacement.as_either_str(heap) else {
+            let type_ = replacement.py_type(heap);
+            replacement.drop_with_heap(heap);
+            return Err(type_error_expected_str(type_));
+        };
+        out.push_str(&text[last..start]);
+        out.push_str(replacement_text.as_str(interns));
+        replacement.drop_with_heap(heap);
+        check_output_size(out.len(), heap.tracker())?;
+        last = end;
+    }
+    Ok(last)
+}
+
+/// Implements `split()`: the text between matches, with the groups of each match in between.
+fn split(
+    heap: &mut Heap<impl ResourceTracker>,
+    pattern_id: HeapId,
+    string: &Value,
+    maxsplit: i64,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let text = str_arg(string, heap, interns)?;
+    let matcher = matcher(heap, pattern_id)?;
+    // A negative maxsplit splits nothing
+    let matches = match usize::try_from(maxsplit) {
+        Ok(limit) => matcher.find_all(&text, 0, text.len(), limit, heap.tracker_mut())?,
+        Err(_) => Vec::new(),
+    };
+
+    let mut pieces: Vec<Option<&str>> = Vec::new();
+    let mut last = 0;
+    for groups in &matches {
+        let (start, end) = groups[0].expect("the whole match is always set");
+        pieces.push(Some(&text[last..start]));
+        // Groups that didn't take part in the match are None
+        pieces.extend(
+            groups[1..]
+                .iter()
+                .map(|span| span.map(|(start, end)| &text[start..end])),
+        );
+        last = end;
+    }
+    pieces.push(Some(&text[last..]));
+
+    let mut items = Vec::with_capacity(pieces.len());
+    for piece in pieces {
+        let item = match piece {
+            Some(piece) => match allocate_string(piece.to_owned(), heap) {
+                Ok(item) => item,
+                Err(err) => {
+                    items.drop_with_heap(heap);
+                    return Err(err);
+                }
+            },
+            None => Value::None,
+        };
+        items.push(item);
+    }
+    Ok(Value::Ref(heap.allocate(HeapData::List(List::new(items)))?))
+}
+
+/// Implements `escape()`, backslash-escaping the characters that are special in patterns.
+fn escape(pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    for c in pattern.chars() {
+        if "()[]{}?*+-|^$\\.&~# \t\n\r\x0b\x0c".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// The part of a string searched, from the `pos` and `endpos` arguments.
+struct SearchRange {
+    /// Byte offset the search starts at.
+    start: usize,
+    /// Byte offset the search ends at, no match extends past it.
+    end: usize,
+    /// `pos` clamped to the string, as a character index.
+    pos: usize,
+    /// `endpos` clamped to the string, as a character index.
+    endpos: usize,
+}
+
+impl SearchRange {
+    /// Clamps `pos` and `endpos` to `text` like CPython, with `endpos` defaulting to the end.
+    fn new(text: &str, pos: i64, endpos: Option<i64>) -> Self {
+        let len = text.chars().count();
+        let clamp = |index: i64| usize::try_from(index).unwrap_or(0).min(len);
+        let pos = clamp(pos);
+        let endpos = endpos.map_or(len, clamp);
+        let byte_offset = |index: usize| text.char_indices().nth(index).map_or(text.len(), |(offset, _)| offset);
+        Self {
+            start: byte_offset(pos),
+            end: byte_offset(endpos),
+            pos,
+            endpos,
+        }
+    }
+}
+
+/// Allocates an `re.Match`, taking new references to the pattern and string.
+fn allocate_match(
+    heap: &mut Heap<impl ResourceTracker>,
+    pattern_id: HeapId,
+    string_id: HeapId,
+    groups: GroupSpans,
+    range: &SearchRange,
+) -> RunResult<Value> {
+    heap.inc_ref(pattern_id);
+    heap.inc_ref(string_id);
+    let m = ReMatch::new(pattern_id, string_id, groups, range.pos, range.endpos);
+    Ok(Value::Ref(heap.allocate(HeapData::ReMatch(m))?))
+}
+
+/// Returns the compiled pattern at `pattern_id`.
+fn matcher(heap: &Heap<impl ResourceTracker>, pattern_id: HeapId) -> RunResult<Matcher> {
+    match heap.get(pattern_id) {
+        HeapData::RePattern(pattern) => pattern.matcher().cloned(),
+        _ => unreachable!("pattern_id always refers to an re.Pattern"),
+    }
+}
+
+/// Resolves the pattern argument of a module function to a compiled pattern, returning a
+/// new reference to it.
+///
+/// Strings are compiled with `flags`; compiled patterns are used as they are.
+fn pattern_arg(
+    pattern: &Value,
+    flags: i64,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<HeapId> {
+    if let Value::Ref(id) = pattern
+        && matches!(heap.get(*id), HeapData::RePattern(_))
+    {
+        if flags != 0 {
+            return Err(SimpleException::new_msg(
+                ExcType::ValueError,
+                "cannot process flags argument with a compiled pattern",
+            )
+            .into());
+        }
+        heap.inc_ref(*id);
+        return Ok(*id);
+    }
+    let Some(source) = pattern.as_either_str(heap) else {
+        return Err(ExcType::type_error("first argument must be string or compiled pattern"));
+    };
+    let compiled = RePattern::compile(source.as_str(interns), flags, heap.tracker())?;
+    Ok(heap.allocate(HeapData::RePattern(compiled))?)
+}
+
+/// Returns the text of the string a pattern is matched against.
+fn str_arg(value: &Value, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<String> {
+    if let Some(s) = value.as_either_str(heap) {
+        return Ok(s.as_str(interns).to_owned());
+    }
+    if bytes_like(value, heap, interns).is_some() {
+        return Err(ExcType::type_error(
+            "cannot use a string pattern on a bytes-like object",
+        ));
+    }
+    Err(ExcType::type_error(format!(
+        "expected string or bytes-like object, got '{}'",
+        value.py_type(heap)
+    )))
+}
+
+/// Returns a new reference to the searched string as a heap `str`, for matches to refer to.
+///
+/// Heap strings are shared, interned strings are copied to the heap from their `text`.
+fn subject_id(string: &Value, text: String, heap: &mut Heap<impl ResourceTracker>) -> RunResult<HeapId> {
+    if let Value::Ref(id) = string {
+        heap.inc_ref(*id);
+        return Ok(*id);
+    }
+    Ok(heap.allocate(HeapData::Str(Str::new(text)))?)
+}
+
+/// Returns the value of an optional `int` argument.
+fn int_arg(value: Option<&Value>, default: i64, heap: &Heap<impl ResourceTracker>) -> RunResult<i64> {
+    match value {
+        None => Ok(default),
+        Some(Value::Bool(b)) => Ok(i64::from(*b)),
+        Some(value) => value.as_int(heap),
+    }
+}
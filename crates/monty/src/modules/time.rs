@@ -0,0 +1,121 @@
+//! Implementation of the `time` module.
+//!
+//! Provides a minimal implementation of Python's `time` module with:
+//! - `time()`: Seconds since the epoch as a float
+//! - `monotonic()`: Seconds from a monotonic clock as a float
+//! - `sleep(secs)`: Pause for `secs` seconds
+//!
+//! None of these touch the host clock: they yield to the host via `OsFunction` callbacks,
+//! so the host decides what time it is and whether sleeping actually waits. This lets
+//! hosts freeze or fast-forward time, e.g. in tests.
+
+use crate::{
+    args::ArgValues,
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    modules::ModuleFunctions,
+    os::OsFunction,
+    resource::{ResourceError, ResourceTracker},
+    types::{AttrCallResult, Module, PyTrait},
+    value::Value,
+};
+
+/// `time` module functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum TimeFunctions {
+    Time,
+    Monotonic,
+    Sleep,
+}
+
+/// Creates the `time` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Time);
+
+    let functions = [
+        (StaticStrings::Time, TimeFunctions::Time),
+        (StaticStrings::Monotonic, TimeFunctions::Monotonic),
+        (StaticStrings::Sleep, TimeFunctions::Sleep),
+    ];
+    for (name, function) in functions {
+        module.set_attr(
+            name,
+            Value::ModuleFunction(ModuleFunctions::Time(function)),
+            heap,
+            interns,
+        );
+    }
+
+    heap.allocate(HeapData::Module(module))
+}
+
+/// Dispatches a call to a time module function.
+///
+/// All functions return `AttrCallResult::OsCall`, the host provides the result.
+pub(super) fn call(
+    heap: &mut Heap<impl ResourceTracker>,
+    functions: TimeFunctions,
+    args: ArgValues,
+) -> RunResult<AttrCallResult> {
+    match functions {
+        TimeFunctions::Time => {
+            args.check_zero_args("time.time", heap)?;
+            Ok(AttrCallResult::OsCall(OsFunction::Time, ArgValues::Empty))
+        }
+        TimeFunctions::Monotonic => {
+            args.check_zero_args("time.monotonic", heap)?;
+            Ok(AttrCallResult::OsCall(OsFunction::Monotonic, ArgValues::Empty))
+        }
+        TimeFunctions::Sleep => sleep(heap, args),
+    }
+}
+
+/// Implementation of `time.sleep(secs)`.
+///
+/// Checks the argument and passes it to the host as a float, which decides how long to
+/// actually wait and returns `None`.
+///
+/// # Errors
+/// Returns `TypeError` if `secs` isn't a number, `ValueError` if it's negative or NaN,
+/// and `OverflowError` if it's too large.
+fn sleep(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<AttrCallResult> {
+    let secs = args.get_one_arg("time.sleep", heap)?;
+    let secs_f64 = match &secs {
+        Value::Int(i) => *i as f64,
+        Value::Bool(b) => f64::from(u8::from(*b)),
+        Value::Float(f) => *f,
+        Value::Ref(id) if matches!(heap.get(*id), HeapData::LongInt(_)) => {
+            secs.drop_with_heap(heap);
+            return Err(SimpleException::new_msg(
+                ExcType::OverflowError,
+                "timestamp too large to convert to C PyTime_t",
+            )
+            .into());
+        }
+        _ => {
+            let type_name = secs.py_type(heap);
+            secs.drop_with_heap(heap);
+            return Err(ExcType::type_error(format!(
+                "'{type_name}' object cannot be interpreted as an integer"
+            )));
+        }
+    };
+    if secs_f64.is_nan() {
+        return Err(SimpleException::new_msg(ExcType::ValueError, "Invalid value NaN (not a number)").into());
+    }
+    if secs_f64 < 0.0 {
+        return Err(SimpleException::new_msg(ExcType::ValueError, "sleep length must be non-negative").into());
+    }
+    Ok(AttrCallResult::OsCall(
+        OsFunction::Sleep,
+        ArgValues::One(Value::Float(secs_f64)),
+    ))
+}
@@ -236,6 +236,18 @@ impl Namespaces {
         self.reuse_ids.push(namespace_id);
     }
 
+    /// Removes a namespace's values without dropping them (when a generator suspends).
+    ///
+    /// The caller takes ownership of the values; the slot is freed for reuse and
+    /// the memory is released from the tracker (it is re-tracked by `register_prebuilt`).
+    pub fn take(&mut self, namespace_id: NamespaceId, heap: &mut Heap<impl ResourceTracker>) -> Vec<Value> {
+        let values = std::mem::take(&mut self.stack[namespace_id.index()].0);
+        let size = values.len() * std::mem::size_of::<Value>();
+        heap.tracker_mut().on_free(|| size);
+        self.reuse_ids.push(namespace_id);
+        values
+    }
+
     /// Cleans up the global namespace by dropping all values with proper ref counting.
     ///
     /// Call this before the namespaces is dropped to properly decrement reference counts
@@ -15,7 +15,7 @@ use crate::{
     intern::Interns,
     resource::{DepthGuard, ResourceError, ResourceTracker},
     types::{
-        LongInt, NamedTuple, Path, PyTrait, Type, allocate_tuple,
+        ByteArray, Complex, LongInt, NamedTuple, Path, PyTrait, Type, allocate_tuple,
         bytes::{Bytes, bytes_repr},
         dict::Dict,
         list::List,
@@ -40,8 +40,8 @@ use crate::{
 ///
 /// # Hashability
 ///
-/// Only immutable variants (`None`, `Ellipsis`, `Bool`, `Int`, `Float`, `String`, `Bytes`)
-/// implement `Hash`. Attempting to hash mutable variants (`List`, `Dict`) will panic.
+/// Only immutable variants (`None`, `Ellipsis`, `Bool`, `Int`, `Float`, `Complex`, `String`, `Bytes`)
+/// implement `Hash`. Attempting to hash mutable variants (`ByteArray`, `List`, `Dict`) will panic.
 ///
 /// # JSON Serialization
 ///
@@ -81,10 +81,19 @@ pub enum MontyObject {
     BigInt(BigInt),
     /// Python float (64-bit IEEE 754).
     Float(f64),
+    /// Python complex number.
+    Complex {
+        /// The real part.
+        real: f64,
+        /// The imaginary part.
+        imag: f64,
+    },
     /// Python string (UTF-8).
     String(String),
     /// Python bytes object.
     Bytes(Vec<u8>),
+    /// Python bytearray object (mutable bytes).
+    ByteArray(Vec<u8>),
     /// Python list (mutable sequence).
     List(Vec<Self>),
     /// Python tuple (immutable sequence).
@@ -115,6 +124,15 @@ pub enum MontyObject {
         /// Optional string argument passed to the exception constructor.
         arg: Option<String>,
     },
+    /// Python exception group with its message and sub-exceptions.
+    ExceptionGroup {
+        /// The group type, `ExceptionGroup` or `BaseExceptionGroup`.
+        exc_type: ExcType,
+        /// The message passed to the group constructor.
+        message: String,
+        /// The sub-exceptions, each an `Exception` or a nested `ExceptionGroup`.
+        exceptions: Vec<Self>,
+    },
     /// A Python type object (e.g., `int`, `str`, `list`).
     ///
     /// Returned by the `type()` builtin and can be compared with other types.
@@ -130,6 +148,10 @@ pub enum MontyObject {
         name: String,
         /// Identifier of the type, from `id(type(dc))` in python.
         type_id: u64,
+        /// Identifier of the host instance, from `id(dc)` in python, so method calls can be made
+        /// on the original object. `None` when the host doesn't track the instance.
+        #[serde(default)]
+        instance_id: Option<u64>,
         /// Declared field names in definition order (for repr).
         field_names: Vec<String>,
         /// All attribute name -> value mapping (includes fields and extra attrs).
@@ -205,8 +227,10 @@ impl MontyObject {
             Self::Int(i) => Ok(Value::Int(i)),
             Self::BigInt(bi) => Ok(LongInt::new(bi).into_value(heap)?),
             Self::Float(f) => Ok(Value::Float(f)),
+            Self::Complex { real, imag } => Ok(Complex::new(real, imag).into_value(heap)?),
             Self::String(s) => Ok(Value::Ref(heap.allocate(HeapData::Str(Str::new(s)))?)),
             Self::Bytes(b) => Ok(Value::Ref(heap.allocate(HeapData::Bytes(Bytes::new(b)))?)),
+            Self::ByteArray(b) => Ok(Value::Ref(heap.allocate(HeapData::ByteArray(ByteArray::new(b)))?)),
             Self::List(items) => {
                 let values: Vec<Value> = items
                     .into_iter()
@@ -267,9 +291,18 @@ impl MontyObject {
                 let exc = SimpleException::new(exc_type, arg);
                 Ok(Value::Ref(heap.allocate(HeapData::Exception(exc))?))
             }
+            Self::ExceptionGroup {
+                exc_type,
+                message,
+                exceptions,
+            } => {
+                let exc = exception_group_from_object(exc_type, message, exceptions);
+                Ok(Value::Ref(heap.allocate(HeapData::Exception(exc))?))
+            }
             Self::Dataclass {
                 name,
                 type_id,
+                instance_id,
                 field_names,
                 attrs,
                 methods,
@@ -285,7 +318,7 @@ impl MontyObject {
                     .map_err(|_| InvalidInputError::invalid_type("unhashable dataclass attr keys"))?;
                 // Convert methods Vec to AHashSet
                 let methods_set: AHashSet<String> = methods.into_iter().collect();
-                let dc = Dataclass::new(name, type_id, field_names, dict, methods_set, frozen);
+                let dc = Dataclass::new(name, type_id, instance_id, field_names, dict, methods_set, frozen);
                 Ok(Value::Ref(heap.allocate(HeapData::Dataclass(dc))?))
             }
             Self::Path(s) => Ok(Value::Ref(heap.allocate(HeapData::Path(Path::new(s)))?)),
@@ -341,6 +374,7 @@ impl MontyObject {
             Value::Bool(b) => Self::Bool(*b),
             Value::Int(i) => Self::Int(*i),
             Value::Float(f) => Self::Float(*f),
+            Value::Imaginary(imag) => Self::Complex { real: 0.0, imag: *imag },
             Value::InternString(string_id) => Self::String(interns.get_str(*string_id).to_owned()),
             Value::InternBytes(bytes_id) => Self::Bytes(interns.get_bytes(*bytes_id).to_owned()),
             Value::Ref(id) => {
@@ -350,7 +384,11 @@ impl MontyObject {
                     return match heap.get(*id) {
                         HeapData::List(_) => Self::Cycle(*id, "[...]".to_owned()),
                         HeapData::Tuple(_) | HeapData::NamedTuple(_) => Self::Cycle(*id, "(...)".to_owned()),
-                        HeapData::Dict(_) => Self::Cycle(*id, "{...}".to_owned()),
+                        HeapData::Dict(_)
+                        | HeapData::DefaultDict(_)
+                        | HeapData::Counter(_)
+                        | HeapData::OrderedDict(_) => Self::Cycle(*id, "{...}".to_owned()),
+                        HeapData::Deque(_) => Self::Cycle(*id, "[...]".to_owned()),
                         _ => Self::Cycle(*id, "...".to_owned()),
                     };
                 }
@@ -361,6 +399,7 @@ impl MontyObject {
                 let result = match heap.get(*id) {
                     HeapData::Str(s) => Self::String(s.as_str().to_owned()),
                     HeapData::Bytes(b) => Self::Bytes(b.as_slice().to_owned()),
+                    HeapData::ByteArray(b) => Self::ByteArray(b.as_slice().to_owned()),
                     HeapData::List(list) => Self::List(
                         list.as_slice()
                             .iter()
@@ -387,8 +426,13 @@ impl MontyObject {
                             .map(|obj| Self::from_value_inner(obj, heap, visited, guard, interns))
                             .collect(),
                     },
-                    HeapData::Dict(dict) => Self::Dict(DictPairs(
-                        dict.into_iter()
+                    data @ (HeapData::Dict(_)
+                    | HeapData::DefaultDict(_)
+                    | HeapData::Counter(_)
+                    | HeapData::OrderedDict(_)) => Self::Dict(DictPairs(
+                        data.as_dict()
+                            .expect("dict types have a dict")
+                            .into_iter()
                             .map(|(k, v)| {
                                 (
                                     Self::from_value_inner(k, heap, visited, guard, interns),
@@ -397,6 +441,12 @@ impl MontyObject {
                             })
                             .collect(),
                     )),
+                    HeapData::Deque(deque) => Self::List(
+                        deque
+                            .iter()
+                            .map(|obj| Self::from_value_inner(obj, heap, visited, guard, interns))
+                            .collect(),
+                    ),
                     HeapData::Set(set) => Self::Set(
                         set.storage()
                             .iter()
@@ -424,10 +474,7 @@ impl MontyObject {
                         let _ = range.py_repr_fmt(&mut s, heap, visited, guard, interns);
                         Self::Repr(s)
                     }
-                    HeapData::Exception(exc) => Self::Exception {
-                        exc_type: exc.exc_type(),
-                        arg: exc.arg().map(ToString::to_string),
-                    },
+                    HeapData::Exception(exc) => exception_to_object(exc),
                     HeapData::Dataclass(dc) => {
                         // Convert attrs to DictPairs
                         let attrs = DictPairs(
@@ -447,6 +494,7 @@ impl MontyObject {
                         Self::Dataclass {
                             name: dc.name(interns).to_owned(),
                             type_id: dc.type_id(),
+                            instance_id: dc.instance_id(),
                             field_names: dc.field_names().to_vec(),
                             attrs,
                             methods,
@@ -458,6 +506,10 @@ impl MontyObject {
                         Self::Repr("<iterator>".to_owned())
                     }
                     HeapData::LongInt(li) => Self::BigInt(li.inner().clone()),
+                    HeapData::Complex(c) => Self::Complex {
+                        real: c.real,
+                        imag: c.imag,
+                    },
                     HeapData::Module(m) => {
                         // Modules are represented as a repr string
                         Self::Repr(format!("<module '{}'>", interns.get_str(m.name())))
@@ -478,7 +530,27 @@ impl MontyObject {
                         // GatherFutures are represented as a repr string
                         Self::Repr(format!("<gather({})>", gather.item_count()))
                     }
+                    // Generators and the awaitables of async generators are represented as repr strings
+                    HeapData::Generator(_) | HeapData::AsyncGenAwaitable(_) => {
+                        Self::Repr(object.py_repr(heap, guard, interns).into_owned())
+                    }
                     HeapData::Path(path) => Self::Path(path.as_str().to_owned()),
+                    // User-defined classes, their instances, memoryviews, regex objects, datetime objects and code
+                    // objects are represented as repr strings
+                    HeapData::MemoryView(_)
+                    | HeapData::Date(_)
+                    | HeapData::DateTime(_)
+                    | HeapData::TimeDelta(_)
+                    | HeapData::TimeZone(_)
+                    | HeapData::RePattern(_)
+                    | HeapData::ReMatch(_)
+                    | HeapData::ClassObject(_)
+                    | HeapData::Instance(_)
+                    | HeapData::BoundMethod(_)
+                    | HeapData::SuperProxy(_)
+                    | HeapData::CodeObject(_)
+                    | HeapData::NamedTupleFactory(_)
+                    | HeapData::DataclassType(_) => Self::Repr(object.py_repr(heap, guard, interns).into_owned()),
                 };
 
                 // Remove from visited set after processing
@@ -521,8 +593,10 @@ impl MontyObject {
                 }
                 Ok(())
             }
+            Self::Complex { real, imag } => write!(f, "{}", Complex::new(*real, *imag)),
             Self::String(s) => string_repr_fmt(s, f),
             Self::Bytes(b) => f.write_str(&bytes_repr(b)),
+            Self::ByteArray(b) => write!(f, "bytearray({})", bytes_repr(b)),
             Self::List(l) => {
                 f.write_char('[')?;
                 let mut iter = l.iter();
@@ -624,6 +698,23 @@ impl MontyObject {
                 }
                 f.write_char(')')
             }
+            Self::ExceptionGroup {
+                exc_type,
+                message,
+                exceptions,
+            } => {
+                let type_str: &'static str = exc_type.into();
+                write!(f, "{type_str}(")?;
+                string_repr_fmt(message, f)?;
+                f.write_str(", [")?;
+                for (i, exc) in exceptions.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    exc.repr_fmt(f)?;
+                }
+                f.write_str("])")
+            }
             Self::Dataclass {
                 name,
                 field_names,
@@ -678,15 +769,16 @@ impl MontyObject {
             Self::Int(i) => *i != 0,
             Self::BigInt(bi) => !bi.is_zero(),
             Self::Float(f) => *f != 0.0,
+            Self::Complex { real, imag } => *real != 0.0 || *imag != 0.0,
             Self::String(s) => !s.is_empty(),
-            Self::Bytes(b) => !b.is_empty(),
+            Self::Bytes(b) | Self::ByteArray(b) => !b.is_empty(),
             Self::List(l) => !l.is_empty(),
             Self::Tuple(t) => !t.is_empty(),
             Self::NamedTuple { values, .. } => !values.is_empty(),
             Self::Dict(d) => !d.is_empty(),
             Self::Set(s) => !s.is_empty(),
             Self::FrozenSet(fs) => !fs.is_empty(),
-            Self::Exception { .. } => true,
+            Self::Exception { .. } | Self::ExceptionGroup { .. } => true,
             Self::Path(_) => true,          // Path instances are always truthy
             Self::Dataclass { .. } => true, // Dataclass instances are always truthy
             Self::Type(_) | Self::BuiltinFunction(_) | Self::Repr(_) | Self::Cycle(_, _) => true,
@@ -704,8 +796,10 @@ impl MontyObject {
             Self::Bool(_) => "bool",
             Self::Int(_) | Self::BigInt(_) => "int",
             Self::Float(_) => "float",
+            Self::Complex { .. } => "complex",
             Self::String(_) => "str",
             Self::Bytes(_) => "bytes",
+            Self::ByteArray(_) => "bytearray",
             Self::List(_) => "list",
             Self::Tuple(_) => "tuple",
             Self::NamedTuple { .. } => "namedtuple",
@@ -713,6 +807,7 @@ impl MontyObject {
             Self::Set(_) => "set",
             Self::FrozenSet(_) => "frozenset",
             Self::Exception { .. } => "Exception",
+            Self::ExceptionGroup { .. } => "ExceptionGroup",
             Self::Path(_) => "PosixPath",
             Self::Dataclass { .. } => "dataclass",
             Self::Type(_) => "type",
@@ -723,6 +818,42 @@ impl MontyObject {
     }
 }
 
+/// Converts a heap exception to a `MontyObject`, with exception groups converted to
+/// `MontyObject::ExceptionGroup` along with their sub-exceptions.
+fn exception_to_object(exc: &SimpleException) -> MontyObject {
+    if exc.exc_type().is_group() {
+        MontyObject::ExceptionGroup {
+            exc_type: exc.exc_type(),
+            message: exc.arg().cloned().unwrap_or_default(),
+            exceptions: exc.exceptions().iter().map(exception_to_object).collect(),
+        }
+    } else {
+        MontyObject::Exception {
+            exc_type: exc.exc_type(),
+            arg: exc.arg().map(ToString::to_string),
+        }
+    }
+}
+
+/// Converts the fields of a `MontyObject::ExceptionGroup` to a heap exception group.
+///
+/// Sub-exceptions which aren't exceptions themselves are ignored.
+fn exception_group_from_object(exc_type: ExcType, message: String, exceptions: Vec<MontyObject>) -> SimpleException {
+    let exceptions = exceptions
+        .into_iter()
+        .filter_map(|exc| match exc {
+            MontyObject::Exception { exc_type, arg } => Some(SimpleException::new(exc_type, arg)),
+            MontyObject::ExceptionGroup {
+                exc_type,
+                message,
+                exceptions,
+            } => Some(exception_group_from_object(exc_type, message, exceptions)),
+            _ => None,
+        })
+        .collect();
+    SimpleException::new_msg(exc_type, message).with_exceptions(exceptions)
+}
+
 impl Hash for MontyObject {
     fn hash<H: Hasher>(&self, state: &mut H) {
         // Hash the discriminant first (but Int and BigInt share discriminant for consistency)
@@ -748,6 +879,10 @@ impl Hash for MontyObject {
                 }
             }
             Self::Float(f) => f.to_bits().hash(state),
+            Self::Complex { real, imag } => {
+                real.to_bits().hash(state);
+                imag.to_bits().hash(state);
+            }
             Self::String(string) => string.hash(state),
             Self::Bytes(bytes) => bytes.hash(state),
             Self::Path(path) => path.hash(state),
@@ -770,8 +905,19 @@ impl PartialEq for MontyObject {
             (Self::Int(a), Self::BigInt(b)) | (Self::BigInt(b), Self::Int(a)) => BigInt::from(*a) == *b,
             // Use to_bits() for float comparison to be consistent with Hash
             (Self::Float(a), Self::Float(b)) => a.to_bits() == b.to_bits(),
+            (
+                Self::Complex {
+                    real: a_real,
+                    imag: a_imag,
+                },
+                Self::Complex {
+                    real: b_real,
+                    imag: b_imag,
+                },
+            ) => a_real.to_bits() == b_real.to_bits() && a_imag.to_bits() == b_imag.to_bits(),
             (Self::String(a), Self::String(b)) => a == b,
             (Self::Bytes(a), Self::Bytes(b)) => a == b,
+            (Self::ByteArray(a), Self::ByteArray(b)) => a == b,
             (Self::List(a), Self::List(b)) => a == b,
             (Self::Tuple(a), Self::Tuple(b)) => a == b,
             (
@@ -803,6 +949,19 @@ impl PartialEq for MontyObject {
                     arg: b_arg,
                 },
             ) => a_type == b_type && a_arg == b_arg,
+            (
+                Self::ExceptionGroup {
+                    exc_type: a_type,
+                    message: a_message,
+                    exceptions: a_exceptions,
+                },
+                Self::ExceptionGroup {
+                    exc_type: b_type,
+                    message: b_message,
+                    exceptions: b_exceptions,
+                },
+            ) => a_type == b_type && a_message == b_message && a_exceptions == b_exceptions,
+            // `instance_id` is host bookkeeping, two values with equal fields are equal
             (
                 Self::Dataclass {
                     name: a_name,
@@ -811,6 +970,7 @@ impl PartialEq for MontyObject {
                     attrs: a_attrs,
                     methods: a_methods,
                     frozen: a_frozen,
+                    ..
                 },
                 Self::Dataclass {
                     name: b_name,
@@ -819,6 +979,7 @@ impl PartialEq for MontyObject {
                     attrs: b_attrs,
                     methods: b_methods,
                     frozen: b_frozen,
+                    ..
                 },
             ) => {
                 a_name == b_name
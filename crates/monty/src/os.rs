@@ -81,6 +81,15 @@ pub enum OsFunction {
     /// Get the entire environment as a dictionary
     #[strum(serialize = "os.environ")]
     GetEnviron,
+    /// Get the current time as seconds since the Unix epoch, also used to read "now" for `datetime`
+    #[strum(serialize = "time.time")]
+    Time,
+    /// Get the value of a monotonic clock in seconds
+    #[strum(serialize = "time.monotonic")]
+    Monotonic,
+    /// Suspend execution for a number of seconds
+    #[strum(serialize = "time.sleep")]
+    Sleep,
 }
 
 impl TryFrom<StaticStrings> for OsFunction {
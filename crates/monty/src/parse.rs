@@ -6,19 +6,21 @@ use ruff_python_ast::{
     InterpolatedStringElement, Keyword, Number, Operator as AstOperator, ParameterWithDefault, Stmt, UnaryOp,
     name::Name,
 };
-use ruff_python_parser::parse_module;
+use ruff_python_parser::{parse_expression, parse_module};
 use ruff_text_size::{Ranged, TextRange};
 
 use crate::{
     StackFrame,
-    args::{ArgExprs, Kwarg},
+    args::{ArgExprs, Kwarg, KwargExpr},
     builtins::Builtins,
+    eval::EvalMode,
     exception_private::ExcType,
     exception_public::{CodeLoc, MontyException},
     expressions::{
-        Callable, CmpOperator, Comprehension, Expr, ExprLoc, Identifier, Literal, Node, Operator, UnpackTarget,
+        Callable, ClassDef, ClassItem, CmpOperator, Comprehension, DeleteTarget, Expr, ExprLoc, Identifier, Literal,
+        Node, Operator, UnpackTarget,
     },
-    fstring::{ConversionFlag, FStringPart, FormatSpec},
+    fstring::{ConversionFlag, FStringPart, FormatSpec, ParsedFormatSpec},
     intern::{InternerBuilder, StringId},
     value::EitherStr,
 };
@@ -92,6 +94,8 @@ pub struct RawFunctionDef {
     pub body: Vec<ParseNode>,
     /// Whether this is an async function (`async def`).
     pub is_async: bool,
+    /// Decorator expressions in source order (the last one is applied first).
+    pub decorators: Vec<ExprLoc>,
 }
 
 /// Type alias for parsed AST nodes (output of the parser).
@@ -107,6 +111,8 @@ pub struct Try<N> {
     pub handlers: Vec<ExceptHandler<N>>,
     pub or_else: Vec<N>,
     pub finally: Vec<N>,
+    /// Whether the handlers are `except*` clauses, which handle parts of exception groups.
+    pub is_star: bool,
 }
 
 /// A parsed exception handler (except clause).
@@ -123,6 +129,186 @@ pub struct ExceptHandler<N> {
     pub body: Vec<N>,
 }
 
+/// A parsed `with` or `async with` statement.
+///
+/// `with a as x, b as y:` is represented with both items; they are entered left to right
+/// and exited in reverse order, exactly like nested `with` statements.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct With<N> {
+    pub items: Vec<WithItem>,
+    pub body: Vec<N>,
+    /// Whether this is `async with`, which uses `__aenter__`/`__aexit__` and awaits their results.
+    pub is_async: bool,
+}
+
+/// A single `expr [as target]` item of a `with` statement.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WithItem {
+    /// The expression producing the context manager.
+    pub context_expr: ExprLoc,
+    /// Target bound to the result of `__enter__`. None = result is discarded.
+    pub target: Option<UnpackTarget>,
+}
+
+/// A single module of an `import` statement, e.g. `os.path` or `sys as s`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ImportedModule {
+    /// The full module name to import (e.g., "sys", "os.path").
+    pub module_name: StringId,
+    /// The module stored in `binding`: the top-level package for an unaliased dotted import
+    /// (`import os.path` binds `os`), otherwise `module_name`.
+    pub bound_module: StringId,
+    /// The binding target - contains the name (or alias), position, and namespace slot.
+    /// After prepare phase, this includes the resolved namespace slot for storing the module.
+    pub binding: Identifier,
+}
+
+/// A parsed `match` statement.
+///
+/// The subject is evaluated once, then the cases are tried in order: the body of the first
+/// case whose pattern matches (and whose guard, if any, is truthy) runs.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Match<N> {
+    /// The subject expression being matched.
+    pub subject: ExprLoc,
+    /// Case blocks in source order.
+    pub cases: Vec<MatchCase<N>>,
+}
+
+/// A single `case pattern [if guard]:` block of a match statement.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MatchCase<N> {
+    pub pattern: Pattern,
+    /// Optional guard expression, evaluated after the pattern has bound its names.
+    pub guard: Option<ExprLoc>,
+    pub body: Vec<N>,
+}
+
+/// A pattern in a `case` clause.
+///
+/// Capture names are plain identifiers, resolved during the prepare phase like any
+/// other assignment target.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum Pattern {
+    /// `_` - matches anything without binding a name.
+    Wildcard,
+    /// `name` - matches anything and binds it to `name`.
+    Capture(Identifier),
+    /// `pattern as name` - binds the subject to `name` if the inner pattern matches.
+    As { pattern: Box<Self>, name: Identifier },
+    /// A literal or dotted name, compared with `==`.
+    Value(ExprLoc),
+    /// `None`, `True` or `False`, compared with `is`.
+    Singleton(Literal),
+    /// `[a, b, *rest]` or `(a, b)` - matches lists, tuples and ranges, but not strings or bytes.
+    Sequence(Vec<Self>),
+    /// `*rest` or `*_` inside a sequence pattern, at most one per sequence.
+    Star(Option<Identifier>),
+    /// `{'key': pattern, **rest}` - matches dicts containing all of the keys.
+    Mapping {
+        keys: Vec<ExprLoc>,
+        patterns: Vec<Self>,
+        rest: Option<Identifier>,
+    },
+    /// `Cls(p1, p2, attr=p3)` - an isinstance check followed by patterns for attributes.
+    ///
+    /// Positional patterns are mapped to attribute names with `__match_args__`, the field
+    /// order of dataclasses and namedtuples, or the subject itself for builtin types like `int`.
+    Class {
+        cls: ExprLoc,
+        patterns: Vec<Self>,
+        kw_names: Vec<StringId>,
+        kw_patterns: Vec<Self>,
+        /// Position of the whole class pattern, for errors raised while matching it.
+        position: CodeRange,
+    },
+    /// `p1 | p2` - the first alternative that matches wins.
+    Or(Vec<Self>),
+}
+
+impl Pattern {
+    /// Returns whether the pattern matches any subject.
+    pub fn is_irrefutable(&self) -> bool {
+        match self {
+            Self::Wildcard | Self::Capture(_) => true,
+            Self::As { pattern, .. } => pattern.is_irrefutable(),
+            Self::Or(alternatives) => alternatives.iter().any(Self::is_irrefutable),
+            _ => false,
+        }
+    }
+
+    /// Collects the names bound by the pattern, in source order.
+    ///
+    /// Alternatives of an or-pattern all bind the same names (checked by the parser),
+    /// so only the first alternative is visited.
+    pub fn bindings<'a>(&'a self, names: &mut Vec<&'a Identifier>) {
+        match self {
+            Self::Wildcard | Self::Value(_) | Self::Singleton(_) | Self::Star(None) => {}
+            Self::Capture(name) | Self::Star(Some(name)) => names.push(name),
+            Self::As { pattern, name } => {
+                pattern.bindings(names);
+                names.push(name);
+            }
+            Self::Sequence(patterns) => {
+                for pattern in patterns {
+                    pattern.bindings(names);
+                }
+            }
+            Self::Mapping { patterns, rest, .. } => {
+                for pattern in patterns {
+                    pattern.bindings(names);
+                }
+                names.extend(rest);
+            }
+            Self::Class {
+                patterns, kw_patterns, ..
+            } => {
+                for pattern in patterns.iter().chain(kw_patterns) {
+                    pattern.bindings(names);
+                }
+            }
+            Self::Or(alternatives) => {
+                if let Some(first) = alternatives.first() {
+                    first.bindings(names);
+                }
+            }
+        }
+    }
+
+    /// Calls `f` for every expression in the pattern (values, mapping keys and classes).
+    pub fn for_each_expr(&self, f: &mut impl FnMut(&ExprLoc)) {
+        match self {
+            Self::Wildcard | Self::Capture(_) | Self::Singleton(_) | Self::Star(_) => {}
+            Self::Value(expr) => f(expr),
+            Self::As { pattern, .. } => pattern.for_each_expr(f),
+            Self::Sequence(patterns) | Self::Or(patterns) => {
+                for pattern in patterns {
+                    pattern.for_each_expr(f);
+                }
+            }
+            Self::Mapping { keys, patterns, .. } => {
+                for key in keys {
+                    f(key);
+                }
+                for pattern in patterns {
+                    pattern.for_each_expr(f);
+                }
+            }
+            Self::Class {
+                cls,
+                patterns,
+                kw_patterns,
+                ..
+            } => {
+                f(cls);
+                for pattern in patterns.iter().chain(kw_patterns) {
+                    pattern.for_each_expr(f);
+                }
+            }
+        }
+    }
+}
+
 /// Result of parsing: the AST nodes and the string interner with all interned names.
 #[derive(Debug)]
 pub struct ParseResult {
@@ -141,6 +327,45 @@ pub(crate) fn parse(code: &str, filename: &str) -> Result<ParseResult, ParseErro
     })
 }
 
+/// Parses code passed to `eval()` or `exec()` while the program runs.
+///
+/// Names are interned into `interner`, which holds the running program's strings so their
+/// IDs stay valid. Ranges record no preview line because tracebacks preview lines from
+/// the program's source, not this code.
+///
+/// In `eval` mode the code must be a single expression, which becomes a `return` of it.
+/// Leading spaces and tabs are ignored, as in CPython.
+pub(crate) fn parse_eval(
+    code: &str,
+    filename: &str,
+    mode: EvalMode,
+    interner: InternerBuilder,
+) -> Result<ParseResult, ParseError> {
+    let code = match mode {
+        EvalMode::Eval => code.trim_start_matches([' ', '\t']),
+        EvalMode::Exec => code,
+    };
+    let mut parser = Parser::with_interner(code, filename, interner);
+    parser.preview_lines = false;
+    let nodes = match mode {
+        EvalMode::Eval => {
+            let parsed = parse_expression(code)
+                .map_err(|e| ParseError::syntax(e.to_string(), parser.convert_range(e.range())))?;
+            let expr = parsed.into_syntax().body;
+            vec![Node::Return(parser.parse_expression(*expr)?)]
+        }
+        EvalMode::Exec => {
+            let parsed =
+                parse_module(code).map_err(|e| ParseError::syntax(e.to_string(), parser.convert_range(e.range())))?;
+            parser.parse_statements(parsed.into_syntax().body)?
+        }
+    };
+    Ok(ParseResult {
+        nodes,
+        interner: parser.interner,
+    })
+}
+
 /// Parser for converting ruff AST to Monty's intermediate ParseNode representation.
 ///
 /// Holds references to the source code and owns a string interner for names.
@@ -156,10 +381,20 @@ pub struct Parser<'a> {
     /// Starts at MAX_NESTING_DEPTH and decrements on each nested level.
     /// When it reaches zero, we return a "too many nested parentheses" error.
     depth_remaining: u16,
+    /// Class name and first parameter of the method currently being parsed, if any.
+    ///
+    /// Used to rewrite zero-argument `super()` into `super(ClassName, first_param)`.
+    super_context: Option<(StringId, StringId)>,
+    /// Whether single-line ranges record their line number for traceback previews.
+    preview_lines: bool,
 }
 
 impl<'a> Parser<'a> {
     fn new(code: &'a str, filename: &'a str) -> Self {
+        Self::with_interner(code, filename, InternerBuilder::new(code))
+    }
+
+    fn with_interner(code: &'a str, filename: &'a str, mut interner: InternerBuilder) -> Self {
         // Position of each line in the source code, to convert indexes to line number and column number
         let mut line_ends = vec![];
         for (i, c) in code.chars().enumerate() {
@@ -167,7 +402,6 @@ impl<'a> Parser<'a> {
                 line_ends.push(i);
             }
         }
-        let mut interner = InternerBuilder::new(code);
         let filename_id = interner.intern(filename);
         Self {
             line_ends,
@@ -175,6 +409,8 @@ impl<'a> Parser<'a> {
             filename_id,
             interner,
             depth_remaining: MAX_NESTING_DEPTH,
+            super_context: None,
+            preview_lines: true,
         }
     }
 
@@ -218,74 +454,363 @@ impl<'a> Parser<'a> {
         Ok(ExceptHandler { exc_type, name, body })
     }
 
-    fn parse_statement(&mut self, statement: Stmt) -> Result<ParseNode, ParseError> {
-        self.decr_depth_remaining(|| statement.range())?;
-        let result = self.parse_statement_impl(statement);
+    /// Checks the `except*` clauses of a try statement, which must have an exception type and
+    /// can't be left with `break`, `continue` or `return` (the other clauses still need to run).
+    fn check_except_star_handlers(&self, handlers: &[ast::ExceptHandler], range: TextRange) -> Result<(), ParseError> {
+        for handler in handlers {
+            let ast::ExceptHandler::ExceptHandler(h) = handler;
+            if h.type_.is_none() {
+                return Err(ParseError::syntax(
+                    "expected one or more exception types",
+                    self.convert_range(range),
+                ));
+            }
+            if let Some(exit_range) = find_except_star_exit(&h.body, false) {
+                return Err(ParseError::syntax(
+                    "'break', 'continue' and 'return' cannot appear in an except* block",
+                    self.convert_range(exit_range),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses a single `case` block of a match statement.
+    ///
+    /// Like CPython, an irrefutable pattern without a guard is only allowed in the last case,
+    /// and a pattern may not bind the same name twice.
+    fn parse_match_case(&mut self, case: ast::MatchCase, is_last: bool) -> Result<MatchCase<ParseNode>, ParseError> {
+        let pattern_range = case.pattern.range();
+        let pattern = self.parse_pattern(case.pattern)?;
+        if !is_last && case.guard.is_none() && pattern.is_irrefutable() {
+            return Err(self.unreachable_pattern_error(&pattern, pattern_range));
+        }
+
+        let mut names = Vec::new();
+        pattern.bindings(&mut names);
+        for (index, name) in names.iter().enumerate() {
+            if names[..index].iter().any(|other| other.name_id == name.name_id) {
+                return Err(ParseError::syntax(
+                    format!(
+                        "multiple assignments to name '{}' in pattern",
+                        self.interner.get_str(name.name_id)
+                    ),
+                    name.position,
+                ));
+            }
+        }
+
+        let guard = match case.guard {
+            Some(guard) => Some(self.parse_expression(*guard)?),
+            None => None,
+        };
+        let body = self.parse_statements(case.body)?;
+        Ok(MatchCase { pattern, guard, body })
+    }
+
+    fn parse_pattern(&mut self, pattern: ast::Pattern) -> Result<Pattern, ParseError> {
+        self.decr_depth_remaining(|| pattern.range())?;
+        let result = self.parse_pattern_impl(pattern);
         self.depth_remaining += 1;
         result
     }
 
-    fn parse_statement_impl(&mut self, statement: Stmt) -> Result<ParseNode, ParseError> {
-        match statement {
-            Stmt::FunctionDef(function) => {
-                let params = &function.parameters;
+    fn parse_pattern_impl(&mut self, pattern: ast::Pattern) -> Result<Pattern, ParseError> {
+        match pattern {
+            ast::Pattern::MatchValue(ast::PatternMatchValue { value, .. }) => {
+                Ok(Pattern::Value(self.parse_expression(*value)?))
+            }
+            ast::Pattern::MatchSingleton(ast::PatternMatchSingleton { value, .. }) => {
+                Ok(Pattern::Singleton(match value {
+                    ast::Singleton::None => Literal::None,
+                    ast::Singleton::True => Literal::Bool(true),
+                    ast::Singleton::False => Literal::Bool(false),
+                }))
+            }
+            ast::Pattern::MatchSequence(ast::PatternMatchSequence { patterns, range, .. }) => {
+                let patterns = self.parse_patterns(patterns)?;
+                if patterns.iter().filter(|p| matches!(p, Pattern::Star(_))).count() > 1 {
+                    return Err(ParseError::syntax(
+                        "multiple starred names in sequence pattern",
+                        self.convert_range(range),
+                    ));
+                }
+                Ok(Pattern::Sequence(patterns))
+            }
+            ast::Pattern::MatchMapping(ast::PatternMatchMapping {
+                keys, patterns, rest, ..
+            }) => {
+                let keys = keys
+                    .into_iter()
+                    .map(|key| self.parse_expression(key))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let patterns = self.parse_patterns(patterns)?;
+                let rest = rest.map(|name| self.identifier(&name.id, name.range));
+                Ok(Pattern::Mapping { keys, patterns, rest })
+            }
+            ast::Pattern::MatchClass(ast::PatternMatchClass {
+                cls, arguments, range, ..
+            }) => {
+                let cls = self.parse_expression(*cls)?;
+                let patterns = self.parse_patterns(arguments.patterns)?;
+                let mut kw_names = Vec::with_capacity(arguments.keywords.len());
+                let mut kw_patterns = Vec::with_capacity(arguments.keywords.len());
+                for keyword in arguments.keywords {
+                    kw_names.push(self.interner.intern(&keyword.attr.id));
+                    kw_patterns.push(self.parse_pattern(keyword.pattern)?);
+                }
+                Ok(Pattern::Class {
+                    cls,
+                    patterns,
+                    kw_names,
+                    kw_patterns,
+                    position: self.convert_range(range),
+                })
+            }
+            ast::Pattern::MatchStar(ast::PatternMatchStar { name, .. }) => {
+                Ok(Pattern::Star(name.map(|name| self.identifier(&name.id, name.range))))
+            }
+            ast::Pattern::MatchAs(ast::PatternMatchAs { pattern, name, .. }) => {
+                let name = name.map(|name| self.identifier(&name.id, name.range));
+                match (pattern, name) {
+                    (Some(pattern), Some(name)) => Ok(Pattern::As {
+                        pattern: Box::new(self.parse_pattern(*pattern)?),
+                        name,
+                    }),
+                    (Some(pattern), None) => self.parse_pattern(*pattern),
+                    (None, Some(name)) => Ok(Pattern::Capture(name)),
+                    (None, None) => Ok(Pattern::Wildcard),
+                }
+            }
+            ast::Pattern::MatchOr(ast::PatternMatchOr { patterns, range, .. }) => {
+                let count = patterns.len();
+                let mut alternatives = Vec::with_capacity(count);
+                for (index, pattern) in patterns.into_iter().enumerate() {
+                    let pattern_range = pattern.range();
+                    let pattern = self.parse_pattern(pattern)?;
+                    if index + 1 < count && pattern.is_irrefutable() {
+                        return Err(self.unreachable_pattern_error(&pattern, pattern_range));
+                    }
+                    alternatives.push(pattern);
+                }
 
-                // Parse positional-only parameters (before /)
-                let pos_args = self.parse_params_with_defaults(&params.posonlyargs)?;
+                // Every alternative must bind the same set of names
+                let sorted_names = |pattern: &Pattern| {
+                    let mut names = Vec::new();
+                    pattern.bindings(&mut names);
+                    let mut ids: Vec<StringId> = names.iter().map(|name| name.name_id).collect();
+                    ids.sort_by_key(|id| id.index());
+                    ids
+                };
+                if let Some((first, rest)) = alternatives.split_first() {
+                    let expected = sorted_names(first);
+                    if rest.iter().any(|pattern| sorted_names(pattern) != expected) {
+                        return Err(ParseError::syntax(
+                            "alternative patterns bind different names",
+                            self.convert_range(range),
+                        ));
+                    }
+                }
+                Ok(Pattern::Or(alternatives))
+            }
+        }
+    }
 
-                // Parse positional-or-keyword parameters
-                let args = self.parse_params_with_defaults(&params.args)?;
+    fn parse_patterns(&mut self, patterns: Vec<ast::Pattern>) -> Result<Vec<Pattern>, ParseError> {
+        patterns
+            .into_iter()
+            .map(|pattern| self.parse_pattern(pattern))
+            .collect()
+    }
 
-                // Parse *args
-                let var_args = params.vararg.as_ref().map(|p| self.interner.intern(&p.name.id));
+    /// Builds the `SyntaxError` for an irrefutable pattern that is followed by other patterns.
+    fn unreachable_pattern_error(&self, pattern: &Pattern, range: TextRange) -> ParseError {
+        let message = unreachable_pattern_message(pattern, &self.interner);
+        ParseError::syntax(message, self.convert_range(range))
+    }
 
-                // Parse keyword-only parameters (after * or *args)
-                let kwargs = self.parse_params_with_defaults(&params.kwonlyargs)?;
+    /// Parses a function definition.
+    ///
+    /// `class_name` is set when the function is a method defined directly in a class body,
+    /// it enables the zero-argument `super()` form inside the method body.
+    ///
+    /// Annotations and type parameter lists (`def first[T](xs: list[T]) -> T`) are erased,
+    /// they're only used by type checkers.
+    fn parse_function_def(
+        &mut self,
+        function: ast::StmtFunctionDef,
+        class_name: Option<StringId>,
+    ) -> Result<RawFunctionDef, ParseError> {
+        let params = &function.parameters;
 
-                // Parse **kwargs
-                let var_kwargs = params.kwarg.as_ref().map(|p| self.interner.intern(&p.name.id));
+        // Parse positional-only parameters (before /)
+        let pos_args = self.parse_params_with_defaults(&params.posonlyargs)?;
 
-                let signature = ParsedSignature {
-                    pos_args,
-                    args,
-                    var_args,
-                    kwargs,
-                    var_kwargs,
-                };
+        // Parse positional-or-keyword parameters
+        let args = self.parse_params_with_defaults(&params.args)?;
 
-                let name = self.identifier(&function.name.id, function.name.range);
-                // Parse function body recursively
-                let body = self.parse_statements(function.body)?;
-                let is_async = function.is_async;
+        // Parse *args
+        let var_args = params.vararg.as_ref().map(|p| self.interner.intern(&p.name.id));
 
-                Ok(Node::FunctionDef(RawFunctionDef {
-                    name,
-                    signature,
-                    body,
-                    is_async,
-                }))
+        // Parse keyword-only parameters (after * or *args)
+        let kwargs = self.parse_params_with_defaults(&params.kwonlyargs)?;
+
+        // Parse **kwargs
+        let var_kwargs = params.kwarg.as_ref().map(|p| self.interner.intern(&p.name.id));
+
+        let signature = ParsedSignature {
+            pos_args,
+            args,
+            var_args,
+            kwargs,
+            var_kwargs,
+        };
+
+        let name = self.identifier(&function.name.id, function.name.range);
+
+        // Decorators are evaluated in the enclosing scope, before the function is created
+        let decorators = function
+            .decorator_list
+            .into_iter()
+            .map(|decorator| self.parse_expression(decorator.expression))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // `super()` needs the class and the first parameter, nested functions don't get them
+        let super_context = class_name.and_then(|class_name| {
+            signature
+                .pos_args
+                .iter()
+                .chain(signature.args.iter())
+                .next()
+                .map(|first| (class_name, first.name))
+        });
+        let outer_super_context = std::mem::replace(&mut self.super_context, super_context);
+        // Parse function body recursively
+        let body = self.parse_statements(function.body);
+        self.super_context = outer_super_context;
+        let body = body?;
+        let is_async = function.is_async;
+
+        Ok(RawFunctionDef {
+            name,
+            signature,
+            body,
+            is_async,
+            decorators,
+        })
+    }
+
+    /// Parses a class definition.
+    ///
+    /// Only a subset of class syntax is supported: at most one base class, no keywords
+    /// (e.g. `metaclass=`), no decorators, and a body consisting of attribute assignments,
+    /// method definitions, `pass` and a docstring. Type parameter lists (`class Box[T]:`)
+    /// are erased along with annotations.
+    fn parse_class_def(&mut self, class: ast::StmtClassDef) -> Result<ParseNode, ParseError> {
+        let position = self.convert_range(class.range);
+        if !class.decorator_list.is_empty() {
+            return Err(ParseError::not_implemented("class decorators", position));
+        }
+        let base = match class.arguments {
+            Some(arguments) => {
+                let ast::Arguments { args, keywords, .. } = *arguments;
+                if !keywords.is_empty() {
+                    return Err(ParseError::not_implemented("class keyword arguments", position));
+                }
+                let mut args = args.into_vec();
+                if args.len() > 1 {
+                    return Err(ParseError::not_implemented("multiple inheritance", position));
+                }
+                match args.pop() {
+                    Some(base) => Some(self.parse_expression(base)?),
+                    None => None,
+                }
             }
-            Stmt::ClassDef(c) => Err(ParseError::not_implemented(
-                "class definitions",
-                self.convert_range(c.range),
-            )),
+            None => None,
+        };
+
+        let name = self.identifier(&class.name.id, class.name.range);
+        let mut body = Vec::with_capacity(class.body.len());
+        for statement in class.body {
+            match statement {
+                Stmt::FunctionDef(function) => {
+                    self.decr_depth_remaining(|| function.range)?;
+                    let method = self.parse_function_def(function, Some(name.name_id));
+                    self.depth_remaining += 1;
+                    body.push(ClassItem::Method(method?));
+                }
+                Stmt::Assign(ast::StmtAssign { mut targets, value, .. })
+                    if targets.len() == 1 && targets[0].is_name_expr() =>
+                {
+                    let Some(AstExpr::Name(target)) = targets.pop() else {
+                        unreachable!("target checked to be a name")
+                    };
+                    body.push(ClassItem::Attr {
+                        name: self.interner.intern(&target.id),
+                        value: self.parse_expression(*value)?,
+                    });
+                }
+                Stmt::AnnAssign(ast::StmtAnnAssign { target, value, .. }) if target.is_name_expr() => {
+                    // Annotation-only declarations (`x: int`) don't create an attribute
+                    if let (AstExpr::Name(target), Some(value)) = (*target, value) {
+                        body.push(ClassItem::Attr {
+                            name: self.interner.intern(&target.id),
+                            value: self.parse_expression(*value)?,
+                        });
+                    }
+                }
+                // Docstrings and `pass` are ignored
+                Stmt::Pass(_) => {}
+                Stmt::Expr(ast::StmtExpr { value, .. }) if value.is_string_literal_expr() => {}
+                other => {
+                    return Err(ParseError::not_implemented(
+                        "this statement in class bodies",
+                        self.convert_range(other.range()),
+                    ));
+                }
+            }
+        }
+        Ok(Node::ClassDef(ClassDef { name, base, body }))
+    }
+
+    fn parse_statement(&mut self, statement: Stmt) -> Result<ParseNode, ParseError> {
+        self.decr_depth_remaining(|| statement.range())?;
+        let result = self.parse_statement_impl(statement);
+        self.depth_remaining += 1;
+        result
+    }
+
+    fn parse_statement_impl(&mut self, statement: Stmt) -> Result<ParseNode, ParseError> {
+        match statement {
+            Stmt::FunctionDef(function) => Ok(Node::FunctionDef(self.parse_function_def(function, None)?)),
+            Stmt::ClassDef(class) => self.parse_class_def(class),
             Stmt::Return(ast::StmtReturn { value, .. }) => match value {
                 Some(value) => Ok(Node::Return(self.parse_expression(*value)?)),
                 None => Ok(Node::ReturnNone),
             },
-            Stmt::Delete(d) => Err(ParseError::not_implemented(
-                "the 'del' statement",
-                self.convert_range(d.range),
-            )),
-            Stmt::TypeAlias(t) => Err(ParseError::not_implemented("type aliases", self.convert_range(t.range))),
+            Stmt::Delete(ast::StmtDelete { targets, .. }) => {
+                let mut delete_targets = Vec::with_capacity(targets.len());
+                for target in targets {
+                    self.parse_delete_target(target, &mut delete_targets)?;
+                }
+                Ok(Node::Delete(delete_targets))
+            }
+            // PEP 695 type aliases only matter to type checkers, so the name is bound to a
+            // type alias carrying only its name and the aliased type is never evaluated
+            Stmt::TypeAlias(ast::StmtTypeAlias { name, range, .. }) => {
+                let target = self.parse_identifier(*name)?;
+                let object = ExprLoc::new(
+                    self.convert_range(range),
+                    Expr::Literal(Literal::TypeAlias(target.name_id)),
+                );
+                Ok(Node::Assign { target, object })
+            }
             Stmt::Assign(ast::StmtAssign {
                 targets, value, range, ..
             }) => self.parse_assignment(first(targets, self.convert_range(range))?, *value),
-            Stmt::AugAssign(ast::StmtAugAssign { target, op, value, .. }) => Ok(Node::OpAssign {
-                target: self.parse_identifier(*target)?,
-                op: convert_op(op),
-                object: self.parse_expression(*value)?,
-            }),
+            Stmt::AugAssign(ast::StmtAugAssign { target, op, value, .. }) => {
+                self.parse_aug_assignment(*target, convert_op(op), *value)
+            }
             Stmt::AnnAssign(ast::StmtAnnAssign { target, value, .. }) => match value {
                 Some(value) => self.parse_assignment(*target, *value),
                 None => Ok(Node::Pass),
@@ -296,22 +821,14 @@ impl<'a> Parser<'a> {
                 iter,
                 body,
                 orelse,
-                range,
                 ..
-            }) => {
-                if is_async {
-                    return Err(ParseError::not_implemented(
-                        "async for loops",
-                        self.convert_range(range),
-                    ));
-                }
-                Ok(Node::For {
-                    target: self.parse_unpack_target(*target)?,
-                    iter: self.parse_expression(*iter)?,
-                    body: self.parse_statements(body)?,
-                    or_else: self.parse_statements(orelse)?,
-                })
-            }
+            }) => Ok(Node::For {
+                target: self.parse_unpack_target(*target)?,
+                iter: self.parse_expression(*iter)?,
+                body: self.parse_statements(body)?,
+                or_else: self.parse_statements(orelse)?,
+                is_async,
+            }),
             Stmt::While(ast::StmtWhile { test, body, orelse, .. }) => Ok(Node::While {
                 test: self.parse_expression(*test)?,
                 body: self.parse_statements(body)?,
@@ -328,30 +845,49 @@ impl<'a> Parser<'a> {
                 let or_else = self.parse_elif_else_clauses(elif_else_clauses)?;
                 Ok(Node::If { test, body, or_else })
             }
-            Stmt::With(ast::StmtWith { is_async, range, .. }) => {
-                if is_async {
-                    Err(ParseError::not_implemented(
-                        "async context managers (async with)",
-                        self.convert_range(range),
-                    ))
-                } else {
-                    Err(ParseError::not_implemented(
-                        "context managers (with statements)",
-                        self.convert_range(range),
-                    ))
-                }
+            Stmt::With(ast::StmtWith {
+                is_async, items, body, ..
+            }) => {
+                let items = items
+                    .into_iter()
+                    .map(|item| {
+                        Ok(WithItem {
+                            context_expr: self.parse_expression(item.context_expr)?,
+                            target: item
+                                .optional_vars
+                                .map(|target| self.parse_unpack_target(*target))
+                                .transpose()?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, ParseError>>()?;
+                Ok(Node::With(With {
+                    items,
+                    body: self.parse_statements(body)?,
+                    is_async,
+                }))
             }
-            Stmt::Match(m) => Err(ParseError::not_implemented(
-                "pattern matching (match statements)",
-                self.convert_range(m.range),
-            )),
-            Stmt::Raise(ast::StmtRaise { exc, .. }) => {
-                // TODO add cause to Node::Raise
+            Stmt::Match(ast::StmtMatch { subject, cases, .. }) => {
+                let subject = self.parse_expression(*subject)?;
+                let case_count = cases.len();
+                let cases = cases
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, case)| self.parse_match_case(case, index + 1 == case_count))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Node::Match(Match { subject, cases }))
+            }
+            Stmt::Raise(ast::StmtRaise { exc, cause, .. }) => {
                 let expr = match exc {
                     Some(expr) => Some(self.parse_expression(*expr)?),
                     None => None,
                 };
-                Ok(Node::Raise(expr))
+                match (expr, cause) {
+                    (Some(exc), Some(cause)) => Ok(Node::RaiseFrom {
+                        exc,
+                        cause: self.parse_expression(*cause)?,
+                    }),
+                    (expr, _) => Ok(Node::Raise(expr)),
+                }
             }
             Stmt::Try(ast::StmtTry {
                 body,
@@ -363,25 +899,22 @@ impl<'a> Parser<'a> {
                 ..
             }) => {
                 if is_star {
-                    Err(ParseError::not_implemented(
-                        "exception groups (try*/except*)",
-                        self.convert_range(range),
-                    ))
-                } else {
-                    let body = self.parse_statements(body)?;
-                    let handlers = handlers
-                        .into_iter()
-                        .map(|h| self.parse_except_handler(h))
-                        .collect::<Result<Vec<_>, _>>()?;
-                    let or_else = self.parse_statements(orelse)?;
-                    let finally = self.parse_statements(finalbody)?;
-                    Ok(Node::Try(Try {
-                        body,
-                        handlers,
-                        or_else,
-                        finally,
-                    }))
+                    self.check_except_star_handlers(&handlers, range)?;
                 }
+                let body = self.parse_statements(body)?;
+                let handlers = handlers
+                    .into_iter()
+                    .map(|h| self.parse_except_handler(h))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let or_else = self.parse_statements(orelse)?;
+                let finally = self.parse_statements(finalbody)?;
+                Ok(Node::Try(Try {
+                    body,
+                    handlers,
+                    or_else,
+                    finally,
+                    is_star,
+                }))
             }
             Stmt::Assert(ast::StmtAssert { test, msg, .. }) => {
                 let test = self.parse_expression(*test)?;
@@ -392,22 +925,31 @@ impl<'a> Parser<'a> {
                 Ok(Node::Assert { test, msg })
             }
             Stmt::Import(ast::StmtImport { names, range, .. }) => {
-                // We only support single module imports (e.g., `import sys`)
-                // Multi-module imports (e.g., `import sys, os`) are not supported
                 let position = self.convert_range(range);
-                if names.len() != 1 {
-                    return Err(ParseError::not_implemented("multi-module import statements", position));
-                }
-                let alias_node = &names[0];
-                let module_name = self.interner.intern(&alias_node.name);
-                // The binding name is the alias if present, otherwise the module name
-                let binding_name = alias_node
-                    .asname
-                    .as_ref()
-                    .map_or(module_name, |n| self.interner.intern(&n.id));
-                // Create an unresolved identifier (namespace slot will be set during prepare)
-                let binding = Identifier::new(binding_name, position);
-                Ok(Node::Import { module_name, binding })
+                let modules = names
+                    .iter()
+                    .map(|alias| {
+                        let module_name = self.interner.intern(&alias.name);
+                        // `import a.b` binds `a`, `import a.b as c` binds `a.b` to `c`
+                        let (binding_name, bound_module) = match &alias.asname {
+                            Some(asname) => (self.interner.intern(&asname.id), module_name),
+                            None => match alias.name.split_once('.') {
+                                Some((package, _)) => {
+                                    let package = self.interner.intern(package);
+                                    (package, package)
+                                }
+                                None => (module_name, module_name),
+                            },
+                        };
+                        // Create an unresolved identifier (namespace slot will be set during prepare)
+                        ImportedModule {
+                            module_name,
+                            bound_module,
+                            binding: Identifier::new(binding_name, position),
+                        }
+                    })
+                    .collect();
+                Ok(Node::Import(modules))
             }
             Stmt::ImportFrom(ast::StmtImportFrom {
                 module,
@@ -494,6 +1036,64 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// `lhs op= rhs` for name, subscript (`counts[key] += 1`) and attribute (`obj.total += x`) targets.
+    fn parse_aug_assignment(&mut self, lhs: AstExpr, op: Operator, rhs: AstExpr) -> Result<ParseNode, ParseError> {
+        match lhs {
+            AstExpr::Subscript(ast::ExprSubscript {
+                value, slice, range, ..
+            }) => Ok(Node::SubscriptOpAssign {
+                object: self.parse_expression(*value)?,
+                index: self.parse_expression(*slice)?,
+                op,
+                value: self.parse_expression(rhs)?,
+                target_position: self.convert_range(range),
+            }),
+            AstExpr::Attribute(ast::ExprAttribute { value, attr, range, .. }) => Ok(Node::AttrOpAssign {
+                object: self.parse_expression(*value)?,
+                attr: EitherStr::Interned(self.interner.intern(attr.id())),
+                op,
+                value: self.parse_expression(rhs)?,
+                target_position: self.convert_range(range),
+            }),
+            _ => Ok(Node::OpAssign {
+                target: self.parse_identifier(lhs)?,
+                op,
+                object: self.parse_expression(rhs)?,
+            }),
+        }
+    }
+
+    /// Parses a `del` target, flattening tuple and list targets into `targets`.
+    fn parse_delete_target(&mut self, target: AstExpr, targets: &mut Vec<DeleteTarget>) -> Result<(), ParseError> {
+        match target {
+            AstExpr::Subscript(ast::ExprSubscript {
+                value, slice, range, ..
+            }) => targets.push(DeleteTarget::Subscript {
+                object: self.parse_expression(*value)?,
+                index: self.parse_expression(*slice)?,
+                position: self.convert_range(range),
+            }),
+            AstExpr::Attribute(ast::ExprAttribute { value, attr, range, .. }) => targets.push(DeleteTarget::Attr {
+                object: self.parse_expression(*value)?,
+                attr: EitherStr::Interned(self.interner.intern(attr.id())),
+                position: self.convert_range(range),
+            }),
+            AstExpr::Tuple(ast::ExprTuple { elts, .. }) | AstExpr::List(ast::ExprList { elts, .. }) => {
+                for elt in elts {
+                    self.parse_delete_target(elt, targets)?;
+                }
+            }
+            AstExpr::Name(_) => targets.push(DeleteTarget::Name(self.parse_identifier(target)?)),
+            other => {
+                return Err(ParseError::syntax(
+                    "cannot delete expression",
+                    self.convert_range(other.range()),
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// `lhs = rhs` -> `lhs, rhs`
     /// Handles simple assignments (x = value), subscript assignments (dict[key] = value),
     /// attribute assignments (obj.attr = value), and tuple unpacking (a, b = value)
@@ -699,26 +1299,17 @@ impl<'a> Parser<'a> {
                 },
             )),
             AstExpr::Dict(ast::ExprDict { items, range, .. }) => {
-                let position = self.convert_range(range);
-                let mut pairs = Vec::new();
+                let mut pairs = Vec::with_capacity(items.len());
                 for ast::DictItem { key, value } in items {
-                    // key is Option<Expr> - None represents ** unpacking which we don't support yet
-                    if let Some(key_expr_ast) = key {
-                        let key_expr = self.parse_expression(key_expr_ast)?;
-                        let value_expr = self.parse_expression(value)?;
-                        pairs.push((key_expr, value_expr));
-                    } else {
-                        return Err(ParseError::not_implemented(
-                            "dictionary unpacking in literals",
-                            position,
-                        ));
-                    }
+                    // A `None` key represents `**mapping` unpacking
+                    let key = key.map(|key| self.parse_expression(key)).transpose()?;
+                    pairs.push((key, self.parse_expression(value)?));
                 }
-                Ok(ExprLoc::new(position, Expr::Dict(pairs)))
+                Ok(ExprLoc::new(self.convert_range(range), Expr::Dict(pairs)))
             }
             AstExpr::Set(ast::ExprSet { elts, range, .. }) => {
-                let elements: Result<Vec<_>, _> = elts.into_iter().map(|e| self.parse_expression(e)).collect();
-                Ok(ExprLoc::new(self.convert_range(range), Expr::Set(elements?)))
+                let elements = self.parse_display_elements(elts)?;
+                Ok(ExprLoc::new(self.convert_range(range), Expr::Set(elements)))
             }
             AstExpr::ListComp(ast::ExprListComp {
                 elt, generators, range, ..
@@ -758,28 +1349,38 @@ impl<'a> Parser<'a> {
             AstExpr::Generator(ast::ExprGenerator {
                 elt, generators, range, ..
             }) => {
-                // TODO: When proper generators are implemented, this should produce
-                // Expr::Generator instead of Expr::ListComp. Currently we treat generator
-                // expressions as list comprehensions since we don't have generator support.
                 let elt = Box::new(self.parse_expression(*elt)?);
                 let generators = self.parse_comprehension_generators(generators)?;
+                let name_id = self.interner.intern("<genexpr>");
+                let iter_name_id = self.interner.intern(".0");
                 Ok(ExprLoc::new(
                     self.convert_range(range),
-                    Expr::ListComp { elt, generators },
+                    Expr::GeneratorExpRaw {
+                        name_id,
+                        iter_name_id,
+                        elt,
+                        generators,
+                    },
                 ))
             }
             AstExpr::Await(a) => {
                 let value = self.parse_expression(*a.value)?;
                 Ok(ExprLoc::new(self.convert_range(a.range), Expr::Await(Box::new(value))))
             }
-            AstExpr::Yield(y) => Err(ParseError::not_implemented(
-                "yield expressions",
-                self.convert_range(y.range),
-            )),
-            AstExpr::YieldFrom(y) => Err(ParseError::not_implemented(
-                "yield from expressions",
-                self.convert_range(y.range),
-            )),
+            AstExpr::Yield(y) => {
+                let value = match y.value {
+                    Some(value) => Some(Box::new(self.parse_expression(*value)?)),
+                    None => None,
+                };
+                Ok(ExprLoc::new(self.convert_range(y.range), Expr::Yield(value)))
+            }
+            AstExpr::YieldFrom(y) => {
+                let value = self.parse_expression(*y.value)?;
+                Ok(ExprLoc::new(
+                    self.convert_range(y.range),
+                    Expr::YieldFrom(Box::new(value)),
+                ))
+            }
             AstExpr::Compare(ast::ExprCompare {
                 left,
                 ops,
@@ -811,33 +1412,20 @@ impl<'a> Parser<'a> {
             }) => {
                 let position = self.convert_range(range);
                 let ast::Arguments { args, keywords, .. } = arguments;
-                let mut positional_args = Vec::new();
-                let mut var_args_expr: Option<ExprLoc> = None;
-                let mut seen_star = false;
-
-                for arg_expr in args.into_vec() {
-                    match arg_expr {
-                        AstExpr::Starred(ast::ExprStarred { value, .. }) => {
-                            if var_args_expr.is_some() {
-                                return Err(ParseError::not_implemented("multiple *args unpacking", position));
-                            }
-                            var_args_expr = Some(self.parse_expression(*value)?);
-                            seen_star = true;
-                        }
-                        other => {
-                            if seen_star {
-                                return Err(ParseError::not_implemented(
-                                    "positional arguments after *args unpacking",
-                                    position,
-                                ));
-                            }
-                            positional_args.push(self.parse_expression(other)?);
-                        }
-                    }
+                let positional_args = self.parse_display_elements(args)?;
+                let kwargs = self.parse_keywords(keywords.into_vec())?;
+                let mut args = ArgExprs::new(positional_args, kwargs);
+                // Zero-argument `super()` inside a method becomes `super(ClassName, self)`
+                if let (ArgExprs::Empty, Some((class_name, first_param)), AstExpr::Name(name)) =
+                    (&args, self.super_context, func.as_ref())
+                    && name.id.as_str() == "super"
+                {
+                    let name_position = self.convert_range(name.range);
+                    args = ArgExprs::Two(
+                        ExprLoc::new(name_position, Expr::Name(Identifier::new(class_name, name_position))),
+                        ExprLoc::new(name_position, Expr::Name(Identifier::new(first_param, name_position))),
+                    );
                 }
-                // Separate regular kwargs (key=value) from var_kwargs (**expr)
-                let (kwargs, var_kwargs) = self.parse_keywords(keywords.into_vec())?;
-                let args = ArgExprs::new_with_var_kwargs(positional_args, var_args_expr, kwargs, var_kwargs);
                 match *func {
                     AstExpr::Name(ast::ExprName { id, range, .. }) => {
                         let name = id.to_string();
@@ -918,7 +1506,8 @@ impl<'a> Parser<'a> {
                         }
                     }
                     Number::Float(f) => Literal::Float(f),
-                    Number::Complex { .. } => return Err(ParseError::not_implemented("complex constants", position)),
+                    // The parser only produces imaginary literals, e.g. `1+2j` is `1 + (2j)`
+                    Number::Complex { imag, .. } => Literal::Imaginary(imag),
                 };
                 Ok(ExprLoc::new(position, Expr::Literal(const_value)))
             }
@@ -954,8 +1543,8 @@ impl<'a> Parser<'a> {
                     Expr::Subscript { object, index },
                 ))
             }
-            AstExpr::Starred(s) => Err(ParseError::not_implemented(
-                "starred expressions (*expr)",
+            AstExpr::Starred(s) => Err(ParseError::syntax(
+                "can't use starred expression here",
                 self.convert_range(s.range),
             )),
             AstExpr::Name(ast::ExprName { id, range, .. }) => {
@@ -970,18 +1559,11 @@ impl<'a> Parser<'a> {
                 Ok(ExprLoc::new(position, expr))
             }
             AstExpr::List(ast::ExprList { elts, range, .. }) => {
-                let items = elts
-                    .into_iter()
-                    .map(|f| self.parse_expression(f))
-                    .collect::<Result<_, ParseError>>()?;
-
+                let items = self.parse_display_elements(elts)?;
                 Ok(ExprLoc::new(self.convert_range(range), Expr::List(items)))
             }
             AstExpr::Tuple(ast::ExprTuple { elts, range, .. }) => {
-                let items = elts
-                    .into_iter()
-                    .map(|f| self.parse_expression(f))
-                    .collect::<Result<_, ParseError>>()?;
+                let items = self.parse_display_elements(elts)?;
 
                 Ok(ExprLoc::new(self.convert_range(range), Expr::Tuple(items)))
             }
@@ -1011,33 +1593,36 @@ impl<'a> Parser<'a> {
         }
     }
 
-    /// Parses keyword arguments, separating regular kwargs from var_kwargs (`**expr`).
-    ///
-    /// Returns `(kwargs, var_kwargs)` where kwargs is a vec of named keyword arguments
-    /// and var_kwargs is an optional expression for `**expr` unpacking.
-    fn parse_keywords(&mut self, keywords: Vec<Keyword>) -> Result<(Vec<Kwarg>, Option<ExprLoc>), ParseError> {
-        let mut kwargs = Vec::new();
-        let mut var_kwargs = None;
-
-        for kwarg in keywords {
-            if let Some(key) = kwarg.arg {
-                // Regular kwarg: key=value
-                let key = self.identifier(&key.id, key.range);
-                let value = self.parse_expression(kwarg.value)?;
-                kwargs.push(Kwarg { key, value });
-            } else {
-                // Var kwargs: **expr
-                if var_kwargs.is_some() {
-                    return Err(ParseError::not_implemented(
-                        "multiple **kwargs unpacking",
-                        self.convert_range(kwarg.range),
-                    ));
+    /// Parses keyword arguments in source order, including `**expr` unpacking.
+    fn parse_keywords(&mut self, keywords: Vec<Keyword>) -> Result<Vec<KwargExpr>, ParseError> {
+        keywords
+            .into_iter()
+            .map(|kwarg| {
+                if let Some(key) = kwarg.arg {
+                    let key = self.identifier(&key.id, key.range);
+                    let value = self.parse_expression(kwarg.value)?;
+                    Ok(KwargExpr::Named(Kwarg { key, value }))
+                } else {
+                    Ok(KwargExpr::Unpack(self.parse_expression(kwarg.value)?))
                 }
-                var_kwargs = Some(self.parse_expression(kwarg.value)?);
-            }
-        }
+            })
+            .collect()
+    }
 
-        Ok((kwargs, var_kwargs))
+    /// Parses the elements of a list, tuple or set display, or a call's positional arguments.
+    ///
+    /// Unlike `parse_expression`, this accepts `*iterable` elements and represents them
+    /// as `Expr::Starred`.
+    fn parse_display_elements(&mut self, elts: impl IntoIterator<Item = AstExpr>) -> Result<Vec<ExprLoc>, ParseError> {
+        elts.into_iter()
+            .map(|elt| match elt {
+                AstExpr::Starred(ast::ExprStarred { value, range, .. }) => {
+                    let value = self.parse_expression(*value)?;
+                    Ok(ExprLoc::new(self.convert_range(range), Expr::Starred(Box::new(value))))
+                }
+                other => self.parse_expression(other),
+            })
+            .collect()
     }
 
     fn parse_identifier(&mut self, ast: AstExpr) -> Result<Identifier, ParseError> {
@@ -1187,12 +1772,6 @@ impl<'a> Parser<'a> {
         generators
             .into_iter()
             .map(|comp| {
-                if comp.is_async {
-                    return Err(ParseError::not_implemented(
-                        "async comprehensions",
-                        self.convert_range(comp.range),
-                    ));
-                }
                 let target = self.parse_unpack_target(comp.target)?;
                 let iter = self.parse_expression(comp.iter)?;
                 let ifs = comp
@@ -1200,7 +1779,12 @@ impl<'a> Parser<'a> {
                     .into_iter()
                     .map(|cond| self.parse_expression(cond))
                     .collect::<Result<Vec<_>, _>>()?;
-                Ok(Comprehension { target, iter, ifs })
+                Ok(Comprehension {
+                    target,
+                    iter,
+                    ifs,
+                    is_async: comp.is_async,
+                })
             })
             .collect()
     }
@@ -1282,6 +1866,7 @@ impl<'a> Parser<'a> {
     ///
     /// For static specs (no interpolations), parses the format string into a
     /// `ParsedFormatSpec` at parse time to avoid runtime parsing overhead.
+    /// Static specs that don't parse are left for the value's `__format__` to handle.
     fn parse_format_spec(&mut self, spec: &ast::InterpolatedStringFormatSpec) -> Result<FormatSpec, ParseError> {
         let mut parts = Vec::new();
         let mut has_interpolation = false;
@@ -1324,13 +1909,16 @@ impl<'a> Parser<'a> {
                     }
                 })
                 .collect();
-            let parsed = static_spec.parse().map_err(|spec_str| {
-                ParseError::syntax(
-                    format!("Invalid format specifier '{spec_str}'"),
-                    self.convert_range(spec.range),
-                )
-            })?;
-            Ok(FormatSpec::Static(parsed))
+            match static_spec.parse::<ParsedFormatSpec>() {
+                // Only pre-parse specs that can be written back unchanged for `__format__`
+                Ok(parsed) if parsed.to_string() == static_spec => Ok(FormatSpec::Static(parsed)),
+                // Not a standard spec, or one parsing doesn't fully keep, but a `__format__`
+                // method may understand it, so it's passed as a string and parsed at runtime
+                // for other values
+                _ => Ok(FormatSpec::Dynamic(vec![FStringPart::Literal(
+                    self.interner.intern(&static_spec),
+                )])),
+            }
         }
     }
 
@@ -1344,7 +1932,7 @@ impl<'a> Parser<'a> {
         let end = CodeLoc::new(end_line_no, end - end_line_start);
 
         // Store line number for single-line ranges, None for multi-line
-        let preview_line = if start_line_no == end_line_no {
+        let preview_line = if self.preview_lines && start_line_no == end_line_no {
             Some(u32::try_from(start_line_no).expect("line number exceeds u32"))
         } else {
             None
@@ -1379,6 +1967,65 @@ impl<'a> Parser<'a> {
     }
 }
 
+/// Describes why an irrefutable pattern makes later patterns unreachable, using CPython's wording.
+fn unreachable_pattern_message(pattern: &Pattern, interner: &InternerBuilder) -> String {
+    match pattern {
+        Pattern::Capture(name) | Pattern::As { name, .. } => format!(
+            "name capture '{}' makes remaining patterns unreachable",
+            interner.get_str(name.name_id)
+        ),
+        Pattern::Or(alternatives) => match alternatives.iter().find(|p| p.is_irrefutable()) {
+            Some(alternative) => unreachable_pattern_message(alternative, interner),
+            None => "wildcard makes remaining patterns unreachable".to_string(),
+        },
+        _ => "wildcard makes remaining patterns unreachable".to_string(),
+    }
+}
+
+/// Finds a `break`, `continue` or `return` that would leave an `except*` block.
+///
+/// Nested functions and classes aren't searched, and `break`/`continue` are allowed
+/// inside loops started within the block.
+fn find_except_star_exit(stmts: &[Stmt], in_loop: bool) -> Option<TextRange> {
+    stmts.iter().find_map(|stmt| match stmt {
+        Stmt::Return(ret) => Some(ret.range),
+        Stmt::Break(brk) if !in_loop => Some(brk.range),
+        Stmt::Continue(cont) if !in_loop => Some(cont.range),
+        Stmt::For(ast::StmtFor { body, orelse, .. }) | Stmt::While(ast::StmtWhile { body, orelse, .. }) => {
+            find_except_star_exit(body, true).or_else(|| find_except_star_exit(orelse, in_loop))
+        }
+        Stmt::If(ast::StmtIf {
+            body,
+            elif_else_clauses,
+            ..
+        }) => find_except_star_exit(body, in_loop).or_else(|| {
+            elif_else_clauses
+                .iter()
+                .find_map(|clause| find_except_star_exit(&clause.body, in_loop))
+        }),
+        Stmt::With(ast::StmtWith { body, .. }) => find_except_star_exit(body, in_loop),
+        Stmt::Try(ast::StmtTry {
+            body,
+            handlers,
+            orelse,
+            finalbody,
+            ..
+        }) => find_except_star_exit(body, in_loop)
+            .or_else(|| {
+                handlers.iter().find_map(|handler| {
+                    let ast::ExceptHandler::ExceptHandler(h) = handler;
+                    find_except_star_exit(&h.body, in_loop)
+                })
+            })
+            .or_else(|| find_except_star_exit(orelse, in_loop))
+            .or_else(|| find_except_star_exit(finalbody, in_loop)),
+        Stmt::Match(ast::StmtMatch { cases, .. }) => {
+            cases.iter().find_map(|case| find_except_star_exit(&case.body, in_loop))
+        }
+        _ => None,
+    })
+}
+
 fn first<T: fmt::Debug>(v: Vec<T>, position: CodeRange) -> Result<T, ParseError> {
     if v.len() == 1 {
         v.into_iter()
@@ -1522,10 +2169,12 @@ pub enum ParseError {
         msg: Cow<'static, str>,
         position: CodeRange,
     },
+    /// Import of a module the import allowlist doesn't permit.
+    ModuleNotFound { module_name: String, position: CodeRange },
 }
 
 impl ParseError {
-    fn not_implemented(msg: impl Into<Cow<'static, str>>, position: CodeRange) -> Self {
+    pub(crate) fn not_implemented(msg: impl Into<Cow<'static, str>>, position: CodeRange) -> Self {
         Self::NotImplemented {
             msg: msg.into(),
             position,
@@ -1546,6 +2195,13 @@ impl ParseError {
         }
     }
 
+    pub(crate) fn module_not_found(module_name: impl Into<String>, position: CodeRange) -> Self {
+        Self::ModuleNotFound {
+            module_name: module_name.into(),
+            position,
+        }
+    }
+
     pub(crate) fn syntax(msg: impl Into<Cow<'static, str>>, position: CodeRange) -> Self {
         Self::Syntax {
             msg: msg.into(),
@@ -1577,6 +2233,11 @@ impl ParseError {
                 Some(msg.into_owned()),
                 vec![StackFrame::from_position_no_caret(position, filename, source)],
             ),
+            Self::ModuleNotFound { module_name, position } => MontyException::new_full(
+                ExcType::ModuleNotFoundError,
+                Some(format!("No module named '{module_name}'")),
+                vec![StackFrame::from_position_no_caret(position, filename, source)],
+            ),
         }
     }
 }
@@ -0,0 +1,389 @@
+//! printf-style `%` formatting for `str` and `bytes`, e.g. `'%s: %05.2f' % (name, value)`.
+//!
+//! This module parses templates and formats a single converted value. Looking up the
+//! arguments and converting them is done by the VM, since `%s` and `%r` call `__str__` and
+//! `__repr__` of user-defined instances.
+
+use num_bigint::BigInt;
+use num_traits::Signed;
+
+// ============================================================================
+// Templates
+// ============================================================================
+
+/// A piece of a `%` format template.
+#[derive(Debug)]
+pub enum PercentPart<'a> {
+    /// Literal text; `%%` becomes a piece ending with a single `%`.
+    Literal(&'a [u8]),
+    /// A conversion specifier like `%-5d` or `%(name)s`.
+    Spec(PercentSpec<'a>),
+}
+
+/// A conversion specifier: `%[(key)][flags][width][.precision][length]type`.
+#[derive(Debug)]
+pub struct PercentSpec<'a> {
+    /// Mapping key from `%(key)s`, looked up in the mapping on the right of `%`.
+    pub key: Option<&'a [u8]>,
+    /// Conversion flags.
+    pub flags: PercentFlags,
+    /// Minimum field width.
+    pub width: Option<PercentCount>,
+    /// Precision: digits for numbers, maximum length for text.
+    pub precision: Option<PercentCount>,
+    /// Conversion type character, e.g. `b'd'` or `b's'`.
+    pub conversion: u8,
+}
+
+/// Flags of a conversion specifier.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PercentFlags {
+    /// `-`: left-align in the field.
+    pub left: bool,
+    /// `+`: always show the sign of numbers.
+    pub sign: bool,
+    /// ` `: show a space before positive numbers.
+    pub space: bool,
+    /// `#`: alternate form, e.g. `0x` prefixes for hex.
+    pub alternate: bool,
+    /// `0`: pad numbers with zeros.
+    pub zero: bool,
+}
+
+/// A width or precision of a conversion specifier.
+#[derive(Debug, Clone, Copy)]
+pub enum PercentCount {
+    /// A number written in the template.
+    Fixed(usize),
+    /// `*`: taken from the next positional argument.
+    FromArgs,
+}
+
+/// The resolved layout of a conversion, with `*` counts replaced by their arguments.
+#[derive(Debug, Clone, Copy)]
+pub struct PercentLayout {
+    pub flags: PercentFlags,
+    pub width: usize,
+    pub precision: Option<usize>,
+}
+
+/// Splits a `%` template into literal text and conversion specifiers.
+///
+/// `is_bytes` selects the conversions `bytes` supports (`%b` is bytes-only). Returns the
+/// message of the `ValueError` to raise for malformed templates.
+pub fn parse_percent_template(template: &[u8], is_bytes: bool) -> Result<Vec<PercentPart<'_>>, String> {
+    let mut parts = Vec::new();
+    let mut literal_start = 0;
+    let mut i = 0;
+    while i < template.len() {
+        if template[i] != b'%' {
+            i += 1;
+            continue;
+        }
+        if template.get(i + 1) == Some(&b'%') {
+            parts.push(PercentPart::Literal(&template[literal_start..=i]));
+            i += 2;
+            literal_start = i;
+            continue;
+        }
+        if literal_start < i {
+            parts.push(PercentPart::Literal(&template[literal_start..i]));
+        }
+        let (spec, end) = parse_spec(template, i + 1, is_bytes)?;
+        parts.push(PercentPart::Spec(spec));
+        i = end;
+        literal_start = i;
+    }
+    if literal_start < template.len() {
+        parts.push(PercentPart::Literal(&template[literal_start..]));
+    }
+    Ok(parts)
+}
+
+/// Parses the specifier starting after a `%` at `start`, returning it and the index after it.
+fn parse_spec(template: &[u8], start: usize, is_bytes: bool) -> Result<(PercentSpec<'_>, usize), String> {
+    let mut i = start;
+
+    // Mapping key, where parentheses nest
+    let key = if template.get(i) == Some(&b'(') {
+        let key_start = i + 1;
+        let mut depth = 1;
+        i += 1;
+        while depth > 0 {
+            match template.get(i) {
+                Some(b'(') => depth += 1,
+                Some(b')') => depth -= 1,
+                Some(_) => {}
+                None => return Err("incomplete format key".to_owned()),
+            }
+            i += 1;
+        }
+        Some(&template[key_start..i - 1])
+    } else {
+        None
+    };
+
+    let mut flags = PercentFlags::default();
+    loop {
+        match template.get(i) {
+            Some(b'-') => flags.left = true,
+            Some(b'+') => flags.sign = true,
+            Some(b' ') => flags.space = true,
+            Some(b'#') => flags.alternate = true,
+            Some(b'0') => flags.zero = true,
+            _ => break,
+        }
+        i += 1;
+    }
+
+    let width = parse_count(template, &mut i, "width too big")?;
+    let precision = if template.get(i) == Some(&b'.') {
+        i += 1;
+        // A `.` without digits means a precision of zero
+        Some(parse_count(template, &mut i, "precision too big")?.unwrap_or(PercentCount::Fixed(0)))
+    } else {
+        None
+    };
+
+    // Length modifiers are accepted and ignored, as in C
+    while matches!(template.get(i), Some(b'h' | b'l' | b'L')) {
+        i += 1;
+    }
+
+    let Some(&conversion) = template.get(i) else {
+        return Err("incomplete format".to_owned());
+    };
+    let supported = matches!(
+        conversion,
+        b'd' | b'i' | b'u' | b'o' | b'x' | b'X' | b'e' | b'E' | b'f' | b'F' | b'g' | b'G' | b'c' | b'r' | b's' | b'a'
+    ) || (is_bytes && conversion == b'b');
+    if !supported {
+        return Err(unsupported_character(template, i, is_bytes));
+    }
+
+    let spec = PercentSpec {
+        key,
+        flags,
+        width,
+        precision,
+        conversion,
+    };
+    Ok((spec, i + 1))
+}
+
+/// Parses a width or precision at `*i`: digits, `*`, or nothing.
+fn parse_count(template: &[u8], i: &mut usize, too_big: &str) -> Result<Option<PercentCount>, String> {
+    if template.get(*i) == Some(&b'*') {
+        *i += 1;
+        return Ok(Some(PercentCount::FromArgs));
+    }
+    let mut count: Option<usize> = None;
+    while let Some(&digit) = template.get(*i)
+        && digit.is_ascii_digit()
+    {
+        let next = count
+            .unwrap_or(0)
+            .checked_mul(10)
+            .and_then(|n| n.checked_add(usize::from(digit - b'0')));
+        match next {
+            Some(n) if i32::try_from(n).is_ok() => count = Some(n),
+            _ => return Err(too_big.to_owned()),
+        }
+        *i += 1;
+    }
+    Ok(count.map(PercentCount::Fixed))
+}
+
+/// Builds the error message for an unsupported conversion character at byte `index`.
+///
+/// `str` templates report the character and its index in characters, like CPython.
+fn unsupported_character(template: &[u8], index: usize, is_bytes: bool) -> String {
+    let (c, char_index) = if is_bytes {
+        (char::from(template[index]), index)
+    } else {
+        let c = std::str::from_utf8(&template[index..])
+            .ok()
+            .and_then(|s| s.chars().next())
+            .unwrap_or(char::REPLACEMENT_CHARACTER);
+        // Every character starts with exactly one byte that isn't a continuation byte
+        let char_index = template[..index].iter().filter(|b| (**b & 0xC0) != 0x80).count();
+        (c, char_index)
+    };
+    format!(
+        "unsupported format character '{c}' ({:#x}) at index {char_index}",
+        u32::from(c)
+    )
+}
+
+// ============================================================================
+// Formatting
+// ============================================================================
+
+/// Pads text for `%s`, `%r`, `%a` and `%c`, counting characters.
+///
+/// The precision, if any, is the maximum number of characters kept.
+pub fn percent_pad_str(text: &str, layout: &PercentLayout) -> String {
+    let text = match layout.precision {
+        Some(precision) => text.chars().take(precision).collect(),
+        None => text.to_owned(),
+    };
+    let padding = layout.width.saturating_sub(text.chars().count());
+    pad(text, padding, layout.flags.left)
+}
+
+/// Pads bytes for `%b`, `%s`, `%r`, `%a` and `%c` in `bytes` templates.
+///
+/// The precision, if any, is the maximum number of bytes kept.
+pub fn percent_pad_bytes(bytes: &[u8], layout: &PercentLayout) -> Vec<u8> {
+    let bytes = match layout.precision {
+        Some(precision) => &bytes[..bytes.len().min(precision)],
+        None => bytes,
+    };
+    let padding = layout.width.saturating_sub(bytes.len());
+    let spaces = std::iter::repeat_n(b' ', padding);
+    if layout.flags.left {
+        bytes.iter().copied().chain(spaces).collect()
+    } else {
+        spaces.chain(bytes.iter().copied()).collect()
+    }
+}
+
+/// Formats an integer for `%d`, `%i`, `%u`, `%o`, `%x` and `%X`.
+///
+/// The precision is the minimum number of digits.
+pub fn percent_format_int(n: &BigInt, conversion: u8, layout: &PercentLayout) -> String {
+    let (radix, prefix) = match conversion {
+        b'o' => (8, "0o"),
+        b'x' => (16, "0x"),
+        b'X' => (16, "0X"),
+        _ => (10, ""),
+    };
+    let mut digits = n.abs().to_str_radix(radix);
+    if conversion == b'X' {
+        digits.make_ascii_uppercase();
+    }
+    if let Some(precision) = layout.precision
+        && digits.len() < precision
+    {
+        digits.insert_str(0, &"0".repeat(precision - digits.len()));
+    }
+    let prefix = if layout.flags.alternate { prefix } else { "" };
+    pad_number(sign(n.is_negative(), layout.flags), prefix, &digits, layout)
+}
+
+/// Formats a float for `%e`, `%E`, `%f`, `%F`, `%g` and `%G`.
+///
+/// The precision defaults to 6 digits.
+pub fn percent_format_float(f: f64, conversion: u8, layout: &PercentLayout) -> String {
+    let precision = layout.precision.unwrap_or(6);
+    let uppercase = conversion.is_ascii_uppercase();
+    let abs = f.abs();
+    let mut digits = if !f.is_finite() {
+        if f.is_nan() { "nan" } else { "inf" }.to_owned()
+    } else {
+        match conversion.to_ascii_lowercase() {
+            b'e' => exponent_notation(abs, precision, layout.flags.alternate),
+            b'f' => {
+                let mut digits = format!("{abs:.precision$}");
+                if layout.flags.alternate && precision == 0 {
+                    digits.push('.');
+                }
+                digits
+            }
+            _ => general_notation(abs, precision, layout.flags.alternate),
+        }
+    };
+    if uppercase {
+        digits.make_ascii_uppercase();
+    }
+    let negative = f.is_sign_negative() && !f.is_nan();
+    pad_number(sign(negative, layout.flags), "", &digits, layout)
+}
+
+/// Formats a non-negative finite float like `%e`, with `precision` digits after the point.
+fn exponent_notation(abs: f64, precision: usize, alternate: bool) -> String {
+    let formatted = format!("{abs:.precision$e}");
+    let (mantissa, exponent) = formatted.split_once('e').expect("exponent notation has an 'e'");
+    let point = if alternate && precision == 0 { "." } else { "" };
+    let exponent: i32 = exponent.parse().expect("exponent is an integer");
+    let exp_sign = if exponent < 0 { '-' } else { '+' };
+    format!("{mantissa}{point}e{exp_sign}{:02}", exponent.unsigned_abs())
+}
+
+/// Formats a non-negative finite float like `%g`, with `precision` significant digits.
+///
+/// Uses exponent notation when the exponent is below -4 or not below the precision, and
+/// drops trailing zeros unless `alternate` is set.
+fn general_notation(abs: f64, precision: usize, alternate: bool) -> String {
+    let precision = precision.max(1);
+    // The exponent after rounding to the precision decides the notation
+    let rounded = format!("{abs:.prec$e}", prec = precision - 1);
+    let (_, exponent) = rounded.split_once('e').expect("exponent notation has an 'e'");
+    let exponent: i64 = exponent.parse().expect("exponent is an integer");
+    let precision_i64 = i64::try_from(precision).unwrap_or(i64::MAX);
+
+    if (-4..precision_i64).contains(&exponent) {
+        let decimals = usize::try_from(precision_i64 - 1 - exponent).expect("exponent checked above");
+        let mut digits = format!("{abs:.decimals$}");
+        if !alternate {
+            strip_fraction_zeros(&mut digits);
+        } else if decimals == 0 {
+            digits.push('.');
+        }
+        digits
+    } else {
+        let mut digits = exponent_notation(abs, precision - 1, alternate);
+        if !alternate {
+            let e_pos = digits.find('e').expect("exponent notation has an 'e'");
+            let mut mantissa = digits[..e_pos].to_owned();
+            strip_fraction_zeros(&mut mantissa);
+            digits.replace_range(..e_pos, &mantissa);
+        }
+        digits
+    }
+}
+
+/// Removes trailing zeros after the decimal point, and the point if nothing follows it.
+fn strip_fraction_zeros(digits: &mut String) {
+    if digits.contains('.') {
+        let trimmed = digits.trim_end_matches('0').trim_end_matches('.').len();
+        digits.truncate(trimmed);
+    }
+}
+
+/// Returns the sign to show for a number.
+fn sign(negative: bool, flags: PercentFlags) -> &'static str {
+    if negative {
+        "-"
+    } else if flags.sign {
+        "+"
+    } else if flags.space {
+        " "
+    } else {
+        ""
+    }
+}
+
+/// Pads a number made of a sign, a prefix and digits to the field width.
+///
+/// Zero padding goes between the prefix and the digits; left alignment overrides it.
+fn pad_number(sign: &str, prefix: &str, digits: &str, layout: &PercentLayout) -> String {
+    let padding = layout.width.saturating_sub(sign.len() + prefix.len() + digits.len());
+    if layout.flags.zero && !layout.flags.left {
+        format!("{sign}{prefix}{}{digits}", "0".repeat(padding))
+    } else {
+        pad(format!("{sign}{prefix}{digits}"), padding, layout.flags.left)
+    }
+}
+
+/// Adds `padding` spaces to the left of `text`, or to the right if `left` is set.
+fn pad(mut text: String, padding: usize, left: bool) -> String {
+    if left {
+        text.extend(std::iter::repeat_n(' ', padding));
+        text
+    } else {
+        let mut padded = " ".repeat(padding);
+        padded.push_str(&text);
+        padded
+    }
+}
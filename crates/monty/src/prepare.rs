@@ -1,17 +1,21 @@
-use std::collections::hash_map::Entry;
+use std::{collections::hash_map::Entry, mem};
 
 use ahash::{AHashMap, AHashSet};
 
 use crate::{
-    args::ArgExprs,
+    args::{ArgExprs, KwargExpr},
     expressions::{
-        Callable, CmpOperator, Comprehension, Expr, ExprLoc, Identifier, Literal, NameScope, Node, Operator,
-        PreparedFunctionDef, PreparedNode, UnpackTarget,
+        Callable, ClassDef, ClassItem, CmpOperator, Comprehension, DeleteTarget, Expr, ExprLoc, Identifier, Literal,
+        NameScope, Node, Operator, PreparedFunctionDef, PreparedNode, UnpackTarget,
     },
     fstring::{FStringPart, FormatSpec},
     intern::{InternerBuilder, StringId},
+    modules::BuiltinModule,
     namespace::NamespaceId,
-    parse::{CodeRange, ExceptHandler, ParseError, ParseNode, ParseResult, ParsedSignature, RawFunctionDef, Try},
+    parse::{
+        CodeRange, ExceptHandler, ImportedModule, Match, MatchCase, ParseError, ParseNode, ParseResult, ParsedParam,
+        ParsedSignature, Pattern, RawFunctionDef, Try, With, WithItem,
+    },
     signature::Signature,
 };
 
@@ -19,16 +23,14 @@ use crate::{
 ///
 /// This struct holds the outputs of name resolution and AST transformation:
 /// - The namespace size (number of slots needed at module level)
-/// - A mapping from variable names to their namespace indices (for ref-count testing)
+/// - A mapping from variable names to their namespace indices
 /// - The transformed AST nodes with all names resolved, ready for compilation
 /// - The string interner containing all interned identifiers and filenames
 pub struct PrepareResult {
     /// Number of items in the namespace (at module level, this IS the global namespace)
     pub namespace_size: usize,
     /// Maps variable names to their indices in the namespace.
-    /// Used for ref-count testing to look up variables by name.
-    /// Only available when the `ref-count-return` feature is enabled.
-    #[cfg(feature = "ref-count-return")]
+    /// Used to resolve globals in code passed to `eval()`/`exec()`, and for ref-count testing.
     pub name_map: AHashMap<String, NamespaceId>,
     /// The prepared AST nodes with all names resolved to namespace indices.
     /// Function definitions are inline as `PreparedFunctionDef` variants.
@@ -45,9 +47,10 @@ pub(crate) fn prepare(
     parse_result: ParseResult,
     input_names: Vec<String>,
     external_functions: &[String],
+    allowed_modules: Option<&[String]>,
 ) -> Result<PrepareResult, ParseError> {
     let ParseResult { nodes, interner } = parse_result;
-    let mut p = Prepare::new_module(input_names, external_functions, &interner);
+    let mut p = Prepare::new_module(input_names, external_functions, allowed_modules, &interner);
     let mut prepared_nodes = p.prepare_nodes(nodes)?;
 
     // In the root frame, the last expression is implicitly returned
@@ -63,13 +66,55 @@ pub(crate) fn prepare(
 
     Ok(PrepareResult {
         namespace_size: p.namespace_size,
-        #[cfg(feature = "ref-count-return")]
         name_map: p.name_map,
         nodes: prepared_nodes,
         interner,
     })
 }
 
+/// Prepares code passed to `eval()` or `exec()` as the body of a function.
+///
+/// `params` become the function's parameters, filled when the code is called. Names the code
+/// doesn't bind resolve through `global_name_map`, which is the module's name map, or empty when
+/// the code was given its own globals dict.
+///
+/// Returns the function along with the names bound in its namespace and their slots, which
+/// `exec()` copies back into its namespace dict.
+pub(crate) fn prepare_eval(
+    name: Identifier,
+    params: Vec<StringId>,
+    body: Vec<ParseNode>,
+    global_name_map: AHashMap<String, NamespaceId>,
+    allowed_modules: Option<&[String]>,
+    interner: &InternerBuilder,
+) -> Result<(PreparedFunctionDef, Vec<(String, NamespaceId)>), ParseError> {
+    let mut module = Prepare::new_module(Vec::new(), &[], allowed_modules, interner);
+    module.namespace_size = global_name_map.len();
+    module.name_map = global_name_map;
+
+    let signature = ParsedSignature {
+        args: params
+            .into_iter()
+            .map(|name| ParsedParam { name, default: None })
+            .collect(),
+        ..ParsedSignature::default()
+    };
+    let (func_def, inner) = module.prepare_function_scope(name, &signature, body, false, Vec::new())?;
+    if let Some(position) = inner.first_yield {
+        return Err(ParseError::syntax("'yield' outside function", position));
+    }
+
+    let mut bound: Vec<(String, NamespaceId)> = inner
+        .name_map
+        .into_iter()
+        .chain(inner.cell_var_map)
+        .filter(|(name, _)| !inner.global_names.contains(name))
+        .collect();
+    bound.sort_by_key(|(_, slot)| *slot);
+    bound.dedup_by_key(|(_, slot)| *slot);
+    Ok((func_def, bound))
+}
+
 /// State machine for the preparation phase that transforms parsed AST nodes into a prepared form.
 ///
 /// This struct maintains the mapping between variable names and their namespace indices,
@@ -85,6 +130,8 @@ pub(crate) fn prepare(
 struct Prepare<'i> {
     /// Reference to the string interner for looking up names in error messages.
     interner: &'i InternerBuilder,
+    /// Builtin modules the code may import, `None` allows all of them.
+    allowed_modules: Option<&'i [String]>,
     /// Maps variable names to their indices in this scope's namespace vector
     name_map: AHashMap<String, NamespaceId>,
     /// Number of items in the namespace
@@ -118,6 +165,14 @@ struct Prepare<'i> {
     /// that are both nonlocal and captured by nested functions), then extended as new
     /// captures are discovered during nested function preparation.
     cell_var_map: AHashMap<String, NamespaceId>,
+    /// Position of the first `yield`/`yield from` in this scope, if any.
+    ///
+    /// A function containing a yield is a generator function.
+    first_yield: Option<CodeRange>,
+    /// Position of the first `yield from` in this scope, which async functions can't contain.
+    first_yield_from: Option<CodeRange>,
+    /// Position of the first `return` with a value, which async generators can't contain.
+    first_return_value: Option<CodeRange>,
 }
 
 impl<'i> Prepare<'i> {
@@ -129,8 +184,14 @@ impl<'i> Prepare<'i> {
     /// # Arguments
     /// * `input_names` - Names that should be pre-registered in the namespace (e.g., external variables)
     /// * `external_functions` - Names of external functions to pre-register
+    /// * `allowed_modules` - Builtin modules the code may import, `None` allows all of them
     /// * `interner` - Reference to the string interner for looking up names
-    fn new_module(input_names: Vec<String>, external_functions: &[String], interner: &'i InternerBuilder) -> Self {
+    fn new_module(
+        input_names: Vec<String>,
+        external_functions: &[String],
+        allowed_modules: Option<&'i [String]>,
+        interner: &'i InternerBuilder,
+    ) -> Self {
         let mut name_map = AHashMap::with_capacity(input_names.len() + external_functions.len());
         for (index, name) in external_functions.iter().enumerate() {
             name_map.insert(name.clone(), NamespaceId::new(index));
@@ -141,6 +202,7 @@ impl<'i> Prepare<'i> {
         let namespace_size = name_map.len();
         Self {
             interner,
+            allowed_modules,
             name_map,
             namespace_size,
             is_module_scope: true,
@@ -151,6 +213,9 @@ impl<'i> Prepare<'i> {
             enclosing_locals: None,
             free_var_map: AHashMap::new(),
             cell_var_map: AHashMap::new(),
+            first_yield: None,
+            first_yield_from: None,
+            first_return_value: None,
         }
     }
 
@@ -169,6 +234,7 @@ impl<'i> Prepare<'i> {
     /// * `global_name_map` - Copy of the module-level name map for global resolution
     /// * `enclosing_locals` - Names that exist as locals in the enclosing function (for nonlocal resolution)
     /// * `cell_var_names` - Names that are captured by nested functions (must be stored in cells)
+    /// * `allowed_modules` - Builtin modules the code may import, `None` allows all of them
     /// * `interner` - Reference to the string interner for looking up names
     #[expect(clippy::too_many_arguments)]
     fn new_function(
@@ -181,6 +247,7 @@ impl<'i> Prepare<'i> {
         global_name_map: AHashMap<String, NamespaceId>,
         enclosing_locals: Option<AHashSet<String>>,
         cell_var_names: AHashSet<String>,
+        allowed_modules: Option<&'i [String]>,
         interner: &'i InternerBuilder,
     ) -> Self {
         let mut name_map = AHashMap::with_capacity(capacity);
@@ -227,6 +294,7 @@ impl<'i> Prepare<'i> {
 
         Self {
             interner,
+            allowed_modules,
             name_map,
             namespace_size,
             is_module_scope: false,
@@ -237,6 +305,33 @@ impl<'i> Prepare<'i> {
             enclosing_locals,
             free_var_map,
             cell_var_map,
+            first_yield: None,
+            first_yield_from: None,
+            first_return_value: None,
+        }
+    }
+
+    /// Checks that the import allowlist permits importing `module_name`.
+    ///
+    /// Allowing a package also allows its submodules, so `os` allows `os.path`. Only builtin
+    /// modules are checked: importing an unknown module already raises `ModuleNotFoundError`
+    /// when executed, which keeps imports under `if TYPE_CHECKING:` working.
+    fn check_module_allowed(&self, module_name: StringId, position: CodeRange) -> Result<(), ParseError> {
+        let Some(allowed_modules) = self.allowed_modules else {
+            return Ok(());
+        };
+        if BuiltinModule::from_string_id(module_name).is_none() {
+            return Ok(());
+        }
+        let name = self.interner.get_str(module_name);
+        let allowed = allowed_modules.iter().any(|allowed| {
+            name.strip_prefix(allowed.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        });
+        if allowed {
+            Ok(())
+        } else {
+            Err(ParseError::module_not_found(name, position))
         }
     }
 
@@ -257,37 +352,23 @@ impl<'i> Prepare<'i> {
             match node {
                 Node::Pass => (),
                 Node::Expr(expr) => new_nodes.push(Node::Expr(self.prepare_expression(expr)?)),
-                Node::Return(expr) => new_nodes.push(Node::Return(self.prepare_expression(expr)?)),
+                Node::Return(expr) => {
+                    self.first_return_value.get_or_insert(expr.position);
+                    new_nodes.push(Node::Return(self.prepare_expression(expr)?));
+                }
                 Node::ReturnNone => new_nodes.push(Node::ReturnNone),
                 Node::Raise(exc) => {
                     let expr = match exc {
-                        Some(expr) => {
-                            match expr.expr {
-                                // Handle raising an exception type constant without instantiation,
-                                // e.g. `raise TypeError`. This is transformed into a call: `raise TypeError()`
-                                // so the exception is properly instantiated before being raised.
-                                // Also handle raising a builtin constant (unlikely but consistent)
-                                Expr::Builtin(b) => {
-                                    let call_expr = Expr::Call {
-                                        callable: Callable::Builtin(b),
-                                        args: Box::new(ArgExprs::Empty),
-                                    };
-                                    Some(ExprLoc::new(expr.position, call_expr))
-                                }
-                                Expr::Name(id) => {
-                                    // Handle raising a variable - could be an exception type or instance.
-                                    // The runtime will determine whether to call it (type) or raise it directly (instance).
-                                    let position = id.position;
-                                    let (resolved_id, _is_new) = self.get_id(id);
-                                    Some(ExprLoc::new(position, Expr::Name(resolved_id)))
-                                }
-                                _ => Some(self.prepare_expression(expr)?),
-                            }
-                        }
+                        Some(expr) => Some(self.prepare_raise_exc(expr)?),
                         None => None,
                     };
                     new_nodes.push(Node::Raise(expr));
                 }
+                Node::RaiseFrom { exc, cause } => {
+                    let exc = self.prepare_raise_exc(exc)?;
+                    let cause = self.prepare_expression(cause)?;
+                    new_nodes.push(Node::RaiseFrom { exc, cause });
+                }
                 Node::Assert { test, msg } => {
                     let test = self.prepare_expression(test)?;
                     let msg = match msg {
@@ -362,11 +443,54 @@ impl<'i> Prepare<'i> {
                         value,
                     });
                 }
+                Node::SubscriptOpAssign {
+                    object,
+                    index,
+                    op,
+                    value,
+                    target_position,
+                } => {
+                    let object = self.prepare_expression(object)?;
+                    let index = self.prepare_expression(index)?;
+                    let value = self.prepare_expression(value)?;
+                    new_nodes.push(Node::SubscriptOpAssign {
+                        object,
+                        index,
+                        op,
+                        value,
+                        target_position,
+                    });
+                }
+                Node::AttrOpAssign {
+                    object,
+                    attr,
+                    op,
+                    value,
+                    target_position,
+                } => {
+                    let object = self.prepare_expression(object)?;
+                    let value = self.prepare_expression(value)?;
+                    new_nodes.push(Node::AttrOpAssign {
+                        object,
+                        attr,
+                        op,
+                        value,
+                        target_position,
+                    });
+                }
+                Node::Delete(targets) => {
+                    let targets = targets
+                        .into_iter()
+                        .map(|target| self.prepare_delete_target(target))
+                        .collect::<Result<_, _>>()?;
+                    new_nodes.push(Node::Delete(targets));
+                }
                 Node::For {
                     target,
                     iter,
                     body,
                     or_else,
+                    is_async,
                 } => {
                     // Prepare target with normal scoping (not comprehension isolation)
                     let target = self.prepare_unpack_target(target);
@@ -375,6 +499,7 @@ impl<'i> Prepare<'i> {
                         iter: self.prepare_expression(iter)?,
                         body: self.prepare_nodes(body)?,
                         or_else: self.prepare_nodes(or_else)?,
+                        is_async,
                     });
                 }
                 Node::Break { position } => {
@@ -401,10 +526,42 @@ impl<'i> Prepare<'i> {
                     signature,
                     body,
                     is_async,
+                    decorators,
                 }) => {
-                    let func_node = self.prepare_function_def(name, &signature, body, is_async)?;
+                    let func_node = self.prepare_function_def(name, &signature, body, is_async, decorators)?;
                     new_nodes.push(func_node);
                 }
+                Node::ClassDef(ClassDef { name, base, body }) => {
+                    // The base is evaluated before the class name is bound
+                    let base = base.map(|base| self.prepare_expression(base)).transpose()?;
+                    // Register the class name before preparing methods so references to the
+                    // class from method bodies (including rewritten `super()` calls) resolve
+                    let (name, _) = self.get_id(name);
+                    let mut items = Vec::with_capacity(body.len());
+                    for item in body {
+                        items.push(match item {
+                            ClassItem::Attr { name, value } => ClassItem::Attr {
+                                name,
+                                value: self.prepare_expression(value)?,
+                            },
+                            ClassItem::Method(RawFunctionDef {
+                                name,
+                                signature,
+                                body,
+                                is_async,
+                                decorators,
+                            }) => {
+                                let decorators = self.prepare_decorators(decorators)?;
+                                ClassItem::Method(self.prepare_function(name, &signature, body, is_async, decorators)?)
+                            }
+                        });
+                    }
+                    new_nodes.push(Node::ClassDef(ClassDef {
+                        name,
+                        base,
+                        body: items,
+                    }));
+                }
                 Node::Global { names, position } => {
                     // At module level, `global` is a no-op since all variables are already global.
                     // In functions, the global declarations are already collected in the first pass
@@ -480,6 +637,7 @@ impl<'i> Prepare<'i> {
                     handlers,
                     or_else,
                     finally,
+                    is_star,
                 }) => {
                     let body = self.prepare_nodes(body)?;
                     let handlers = handlers
@@ -493,21 +651,53 @@ impl<'i> Prepare<'i> {
                         handlers,
                         or_else,
                         finally,
+                        is_star,
                     }));
                 }
-                Node::Import { module_name, binding } => {
-                    // Resolve the binding identifier to get the namespace slot
-                    let (resolved_binding, _) = self.get_id(binding);
-                    new_nodes.push(Node::Import {
-                        module_name,
-                        binding: resolved_binding,
-                    });
+                Node::Match(Match { subject, cases }) => {
+                    let subject = self.prepare_expression(subject)?;
+                    let cases = cases
+                        .into_iter()
+                        .map(|case| self.prepare_match_case(case))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    new_nodes.push(Node::Match(Match { subject, cases }));
+                }
+                Node::With(With { items, body, is_async }) => {
+                    let items = items
+                        .into_iter()
+                        .map(|item| {
+                            Ok(WithItem {
+                                context_expr: self.prepare_expression(item.context_expr)?,
+                                target: item.target.map(|target| self.prepare_unpack_target(target)),
+                            })
+                        })
+                        .collect::<Result<Vec<_>, ParseError>>()?;
+                    new_nodes.push(Node::With(With {
+                        items,
+                        body: self.prepare_nodes(body)?,
+                        is_async,
+                    }));
+                }
+                Node::Import(modules) => {
+                    let modules = modules
+                        .into_iter()
+                        .map(|module| {
+                            let position = module.binding.position;
+                            self.check_module_allowed(module.module_name, position)?;
+                            self.check_module_allowed(module.bound_module, position)?;
+                            // Resolve the binding identifier to get the namespace slot
+                            let (binding, _) = self.get_id(module.binding);
+                            Ok(ImportedModule { binding, ..module })
+                        })
+                        .collect::<Result<Vec<_>, ParseError>>()?;
+                    new_nodes.push(Node::Import(modules));
                 }
                 Node::ImportFrom {
                     module_name,
                     names,
                     position,
                 } => {
+                    self.check_module_allowed(module_name, position)?;
                     // Resolve each binding identifier to get namespace slots
                     let resolved_names = names
                         .into_iter()
@@ -552,6 +742,69 @@ impl<'i> Prepare<'i> {
         Ok(ExceptHandler { exc_type, name, body })
     }
 
+    /// Prepares a match case by resolving names in its pattern, guard and body.
+    fn prepare_match_case(&mut self, case: MatchCase<ParseNode>) -> Result<MatchCase<PreparedNode>, ParseError> {
+        let pattern = self.prepare_pattern(case.pattern)?;
+        let guard = match case.guard {
+            Some(guard) => Some(self.prepare_expression(guard)?),
+            None => None,
+        };
+        let body = self.prepare_nodes(case.body)?;
+        Ok(MatchCase { pattern, guard, body })
+    }
+
+    /// Prepares a pattern: value expressions are resolved as reads, capture names as assignments.
+    fn prepare_pattern(&mut self, pattern: Pattern) -> Result<Pattern, ParseError> {
+        Ok(match pattern {
+            Pattern::Wildcard => Pattern::Wildcard,
+            Pattern::Capture(name) => Pattern::Capture(self.prepare_capture(name)),
+            Pattern::As { pattern, name } => Pattern::As {
+                pattern: Box::new(self.prepare_pattern(*pattern)?),
+                name: self.prepare_capture(name),
+            },
+            Pattern::Value(expr) => Pattern::Value(self.prepare_expression(expr)?),
+            Pattern::Singleton(literal) => Pattern::Singleton(literal),
+            Pattern::Sequence(patterns) => Pattern::Sequence(self.prepare_patterns(patterns)?),
+            Pattern::Star(name) => Pattern::Star(name.map(|name| self.prepare_capture(name))),
+            Pattern::Mapping { keys, patterns, rest } => Pattern::Mapping {
+                keys: keys
+                    .into_iter()
+                    .map(|key| self.prepare_expression(key))
+                    .collect::<Result<Vec<_>, _>>()?,
+                patterns: self.prepare_patterns(patterns)?,
+                rest: rest.map(|name| self.prepare_capture(name)),
+            },
+            Pattern::Class {
+                cls,
+                patterns,
+                kw_names,
+                kw_patterns,
+                position,
+            } => Pattern::Class {
+                cls: self.prepare_expression(cls)?,
+                patterns: self.prepare_patterns(patterns)?,
+                kw_names,
+                kw_patterns: self.prepare_patterns(kw_patterns)?,
+                position,
+            },
+            Pattern::Or(alternatives) => Pattern::Or(self.prepare_patterns(alternatives)?),
+        })
+    }
+
+    fn prepare_patterns(&mut self, patterns: Vec<Pattern>) -> Result<Vec<Pattern>, ParseError> {
+        patterns
+            .into_iter()
+            .map(|pattern| self.prepare_pattern(pattern))
+            .collect()
+    }
+
+    /// Resolves a name bound by a pattern, which is an assignment in the current scope.
+    fn prepare_capture(&mut self, name: Identifier) -> Identifier {
+        self.names_assigned_in_order
+            .insert(self.interner.get_str(name.name_id).to_string());
+        self.get_id(name).0
+    }
+
     /// Prepares an expression by resolving names, transforming calls, and applying optimizations.
     ///
     /// Key transformations performed:
@@ -637,7 +890,10 @@ impl<'i> Prepare<'i> {
             Expr::Dict(pairs) => {
                 let prepared_pairs = pairs
                     .into_iter()
-                    .map(|(k, v)| Ok((self.prepare_expression(k)?, self.prepare_expression(v)?)))
+                    .map(|(k, v)| {
+                        let k = k.map(|k| self.prepare_expression(k)).transpose()?;
+                        Ok((k, self.prepare_expression(v)?))
+                    })
                     .collect::<Result<_, ParseError>>()?;
                 Expr::Dict(prepared_pairs)
             }
@@ -648,6 +904,7 @@ impl<'i> Prepare<'i> {
                     .collect::<Result<_, ParseError>>()?;
                 Expr::Set(expressions)
             }
+            Expr::Starred(value) => Expr::Starred(Box::new(self.prepare_expression(*value)?)),
             Expr::Not(operand) => Expr::Not(Box::new(self.prepare_expression(*operand)?)),
             Expr::UnaryMinus(operand) => Expr::UnaryMinus(Box::new(self.prepare_expression(*operand)?)),
             Expr::UnaryPlus(operand) => Expr::UnaryPlus(Box::new(self.prepare_expression(*operand)?)),
@@ -716,6 +973,34 @@ impl<'i> Prepare<'i> {
                 }
             }
             Expr::Await(value) => Expr::Await(Box::new(self.prepare_expression(*value)?)),
+            Expr::Yield(value) => {
+                self.record_yield(position)?;
+                Expr::Yield(value.map(|v| self.prepare_expression(*v)).transpose()?.map(Box::new))
+            }
+            Expr::YieldFrom(value) => {
+                self.record_yield(position)?;
+                self.first_yield_from.get_or_insert(position);
+                Expr::YieldFrom(Box::new(self.prepare_expression(*value)?))
+            }
+            Expr::GeneratorExpRaw {
+                name_id,
+                iter_name_id,
+                elt,
+                generators,
+            } => {
+                let (signature, body, first_iter, is_async) = genexp_function_parts(iter_name_id, *elt, generators);
+                // The first iterable is evaluated eagerly in the enclosing scope
+                let iter = Box::new(self.prepare_expression(first_iter)?);
+                let name = Identifier::new_with_scope(name_id, position, NamespaceId::new(0), NameScope::Local);
+                let func_def = self.prepare_function(name, &signature, body, is_async, Vec::new())?;
+                Expr::GeneratorExp {
+                    func_def: Box::new(func_def),
+                    iter,
+                }
+            }
+            Expr::GeneratorExp { .. } => {
+                unreachable!("Expr::GeneratorExp should not exist before prepare phase")
+            }
         };
 
         // Optimization: Transform `(x % n) == value` with any constant right-hand side into a
@@ -839,6 +1124,7 @@ impl<'i> Prepare<'i> {
             target: first_target,
             iter: first_iter,
             ifs: first_ifs,
+            is_async: first_gen.is_async,
         });
 
         // Step 3: Process remaining generators - their iters now see all loop vars as local
@@ -854,6 +1140,7 @@ impl<'i> Prepare<'i> {
                 target: preshadowed_target,
                 iter,
                 ifs,
+                is_async: generator.is_async,
             });
         }
 
@@ -905,6 +1192,58 @@ impl<'i> Prepare<'i> {
         }
     }
 
+    /// Prepares the exception expression of a `raise` statement.
+    fn prepare_raise_exc(&mut self, expr: ExprLoc) -> Result<ExprLoc, ParseError> {
+        match expr.expr {
+            // Handle raising an exception type constant without instantiation,
+            // e.g. `raise TypeError`. This is transformed into a call: `raise TypeError()`
+            // so the exception is properly instantiated before being raised.
+            // Also handle raising a builtin constant (unlikely but consistent)
+            Expr::Builtin(b) => {
+                let call_expr = Expr::Call {
+                    callable: Callable::Builtin(b),
+                    args: Box::new(ArgExprs::Empty),
+                };
+                Ok(ExprLoc::new(expr.position, call_expr))
+            }
+            Expr::Name(id) => {
+                // Handle raising a variable - could be an exception type or instance.
+                // The runtime will determine whether to call it (type) or raise it directly (instance).
+                let position = id.position;
+                let (resolved_id, _is_new) = self.get_id(id);
+                Ok(ExprLoc::new(position, Expr::Name(resolved_id)))
+            }
+            _ => self.prepare_expression(expr),
+        }
+    }
+
+    /// Resolves the names and expressions in a `del` target.
+    ///
+    /// Deleting a name counts as binding it, so `del x` makes `x` local to the function.
+    fn prepare_delete_target(&mut self, target: DeleteTarget) -> Result<DeleteTarget, ParseError> {
+        Ok(match target {
+            DeleteTarget::Name(ident) => {
+                self.names_assigned_in_order
+                    .insert(self.interner.get_str(ident.name_id).to_string());
+                DeleteTarget::Name(self.get_id(ident).0)
+            }
+            DeleteTarget::Subscript {
+                object,
+                index,
+                position,
+            } => DeleteTarget::Subscript {
+                object: self.prepare_expression(object)?,
+                index: self.prepare_expression(index)?,
+                position,
+            },
+            DeleteTarget::Attr { object, attr, position } => DeleteTarget::Attr {
+                object: self.prepare_expression(object)?,
+                attr,
+                position,
+            },
+        })
+    }
+
     /// Prepares an unpack target for comprehension by allocating fresh namespace slots.
     ///
     /// Unlike regular unpack targets, comprehension targets need new slots to shadow
@@ -1052,10 +1391,62 @@ impl<'i> Prepare<'i> {
         parsed_sig: &ParsedSignature,
         body: Vec<ParseNode>,
         is_async: bool,
+        decorators: Vec<ExprLoc>,
     ) -> Result<PreparedNode, ParseError> {
+        // Decorators are evaluated before the function name is bound
+        let decorators = self.prepare_decorators(decorators)?;
         // Register the function name in the current scope
         let (name, _) = self.get_id(name);
+        let func_def = self.prepare_function(name, parsed_sig, body, is_async, decorators)?;
+        Ok(Node::FunctionDef(func_def))
+    }
+
+    /// Resolves decorator expressions in the current (enclosing) scope.
+    fn prepare_decorators(&mut self, decorators: Vec<ExprLoc>) -> Result<Vec<ExprLoc>, ParseError> {
+        decorators
+            .into_iter()
+            .map(|decorator| self.prepare_expression(decorator))
+            .collect()
+    }
+
+    /// Records a `yield` in this scope, marking the enclosing function as a generator.
+    ///
+    /// # Errors
+    /// Returns a SyntaxError if the yield is at module level.
+    fn record_yield(&mut self, position: CodeRange) -> Result<(), ParseError> {
+        if self.is_module_scope {
+            return Err(ParseError::syntax("'yield' outside function", position));
+        }
+        self.first_yield.get_or_insert(position);
+        Ok(())
+    }
 
+    /// Prepares a function body and signature without binding the function name.
+    ///
+    /// Shared by `prepare_function_def` and class methods, which are stored on the class
+    /// object rather than in a namespace.
+    fn prepare_function(
+        &mut self,
+        name: Identifier,
+        parsed_sig: &ParsedSignature,
+        body: Vec<ParseNode>,
+        is_async: bool,
+        decorators: Vec<ExprLoc>,
+    ) -> Result<PreparedFunctionDef, ParseError> {
+        self.prepare_function_scope(name, parsed_sig, body, is_async, decorators)
+            .map(|(func_def, _)| func_def)
+    }
+
+    /// Like `prepare_function`, but also returns the preparer used for the function body,
+    /// so callers can inspect the function's scope.
+    fn prepare_function_scope(
+        &mut self,
+        name: Identifier,
+        parsed_sig: &ParsedSignature,
+        body: Vec<ParseNode>,
+        is_async: bool,
+        decorators: Vec<ExprLoc>,
+    ) -> Result<(PreparedFunctionDef, Self), ParseError> {
         // Extract param names from the parsed signature for scope analysis
         let param_names: Vec<StringId> = parsed_sig.param_names().collect();
 
@@ -1083,6 +1474,10 @@ impl<'i> Prepare<'i> {
             for key in self.name_map.keys() {
                 locals.insert(key.clone());
             }
+            // Include free_vars so nested functions can capture pass-through variables
+            for key in self.free_var_map.keys() {
+                locals.insert(key.clone());
+            }
             locals
         };
 
@@ -1106,11 +1501,22 @@ impl<'i> Prepare<'i> {
             global_name_map,
             Some(enclosing_locals),
             scope_info.cell_var_names,
+            self.allowed_modules,
             self.interner,
         );
 
         // Prepare the function body
         let prepared_body = inner_prepare.prepare_nodes(body)?;
+        let is_generator = inner_prepare.first_yield.is_some();
+        if is_async && let Some(position) = inner_prepare.first_yield_from {
+            return Err(ParseError::syntax("'yield from' inside async function", position));
+        }
+        if is_async
+            && is_generator
+            && let Some(position) = inner_prepare.first_return_value
+        {
+            return Err(ParseError::syntax("'return' with value in async generator", position));
+        }
 
         // Mark variables that the inner function captures as our cell_vars
         // These are the names that appear in inner_prepare.free_var_map
@@ -1135,7 +1541,7 @@ impl<'i> Prepare<'i> {
         // Build free_var_enclosing_slots: enclosing namespace slots for captured variables
         // At call time, cells are pushed sequentially, so we only need the enclosing slots.
         // Sort by our slot index to ensure consistent ordering (matches namespace layout).
-        let mut free_var_entries: Vec<_> = inner_prepare.free_var_map.into_iter().collect();
+        let mut free_var_entries: Vec<_> = mem::take(&mut inner_prepare.free_var_map).into_iter().collect();
         free_var_entries.sort_by_key(|(_, our_slot)| *our_slot);
 
         let free_var_enclosing_slots: Vec<NamespaceId> = free_var_entries
@@ -1231,8 +1637,7 @@ impl<'i> Prepare<'i> {
             }
         }
 
-        // Return the prepared function definition inline in the AST
-        Ok(Node::FunctionDef(PreparedFunctionDef {
+        let func_def = PreparedFunctionDef {
             name,
             signature,
             body: prepared_body,
@@ -1242,7 +1647,10 @@ impl<'i> Prepare<'i> {
             cell_param_indices,
             default_exprs,
             is_async,
-        }))
+            is_generator,
+            decorators,
+        };
+        Ok((func_def, inner_prepare))
     }
 
     /// Prepares a lambda expression, converting it into a prepared function definition.
@@ -1320,11 +1728,13 @@ impl<'i> Prepare<'i> {
             global_name_map,
             Some(enclosing_locals),
             scope_info.cell_var_names,
+            self.allowed_modules,
             self.interner,
         );
 
         // Prepare the lambda body
         let prepared_body = inner_prepare.prepare_nodes(body_nodes)?;
+        let is_generator = inner_prepare.first_yield.is_some();
 
         // Mark variables that the inner function captures as our cell_vars
         for captured_name in inner_prepare.free_var_map.keys() {
@@ -1439,6 +1849,8 @@ impl<'i> Prepare<'i> {
             cell_param_indices,
             default_exprs,
             is_async: false,
+            is_generator,
+            decorators: Vec::new(),
         };
 
         Ok(ExprLoc::new(
@@ -1668,6 +2080,76 @@ impl<'i> Prepare<'i> {
     }
 }
 
+/// Desugars a generator expression into the parts of an equivalent generator function.
+///
+/// `(elt for a in xs if c for b in ys)` becomes:
+///
+/// ```text
+/// def <genexpr>(.0):
+///     for a in .0:
+///         if c:
+///             for b in ys:
+///                 yield elt
+/// ```
+///
+/// Returns the function signature, its body, the first iterable, which the caller
+/// evaluates in the enclosing scope and passes as the `.0` argument, and whether the
+/// function is an async generator (any clause is `async for`).
+fn genexp_function_parts(
+    iter_name_id: StringId,
+    elt: ExprLoc,
+    generators: Vec<Comprehension>,
+) -> (ParsedSignature, Vec<ParseNode>, ExprLoc, bool) {
+    let mut body = vec![Node::Expr(ExprLoc::new(elt.position, Expr::Yield(Some(Box::new(elt)))))];
+    let mut first_iter = None;
+    let is_async = generators.iter().any(|generator| generator.is_async);
+    for (
+        index,
+        Comprehension {
+            target,
+            iter,
+            ifs,
+            is_async,
+        },
+    ) in generators.into_iter().enumerate().rev()
+    {
+        for test in ifs.into_iter().rev() {
+            body = vec![Node::If {
+                test,
+                body,
+                or_else: Vec::new(),
+            }];
+        }
+        let iter = if index == 0 {
+            let iter_name = ExprLoc::new(iter.position, Expr::Name(Identifier::new(iter_name_id, iter.position)));
+            first_iter = Some(iter);
+            iter_name
+        } else {
+            iter
+        };
+        body = vec![Node::For {
+            target,
+            iter,
+            body,
+            or_else: Vec::new(),
+            is_async,
+        }];
+    }
+    let signature = ParsedSignature {
+        args: vec![ParsedParam {
+            name: iter_name_id,
+            default: None,
+        }],
+        ..ParsedSignature::default()
+    };
+    (
+        signature,
+        body,
+        first_iter.expect("generator expression must have at least one generator"),
+        is_async,
+    )
+}
+
 /// Information collected from first-pass scan of a function body.
 ///
 /// This struct holds the scope-related information needed for the second pass
@@ -1821,11 +2303,41 @@ fn collect_scope_info_from_node(
             collect_assigned_names_from_expr(object, assigned_names, interner);
             collect_assigned_names_from_expr(value, assigned_names, interner);
         }
+        Node::SubscriptOpAssign {
+            object, index, value, ..
+        } => {
+            // Modifies an existing container, only walrus operators can bind names here
+            collect_assigned_names_from_expr(object, assigned_names, interner);
+            collect_assigned_names_from_expr(index, assigned_names, interner);
+            collect_assigned_names_from_expr(value, assigned_names, interner);
+        }
+        Node::AttrOpAssign { object, value, .. } => {
+            collect_assigned_names_from_expr(object, assigned_names, interner);
+            collect_assigned_names_from_expr(value, assigned_names, interner);
+        }
+        Node::Delete(targets) => {
+            // Deleting a name binds it in this scope, like assignment
+            for target in targets {
+                match target {
+                    DeleteTarget::Name(ident) => {
+                        assigned_names.insert(interner.get_str(ident.name_id).to_string());
+                    }
+                    DeleteTarget::Subscript { object, index, .. } => {
+                        collect_assigned_names_from_expr(object, assigned_names, interner);
+                        collect_assigned_names_from_expr(index, assigned_names, interner);
+                    }
+                    DeleteTarget::Attr { object, .. } => {
+                        collect_assigned_names_from_expr(object, assigned_names, interner);
+                    }
+                }
+            }
+        }
         Node::For {
             target,
             iter,
             body,
             or_else,
+            ..
         } => {
             // For loop target is assigned - collect all names from the target
             collect_names_from_unpack_target(target, assigned_names, interner);
@@ -1861,16 +2373,39 @@ fn collect_scope_info_from_node(
                 collect_scope_info_from_node(n, global_names, nonlocal_names, assigned_names, interner);
             }
         }
-        Node::FunctionDef(RawFunctionDef { name, .. }) => {
+        Node::FunctionDef(RawFunctionDef { name, decorators, .. }) => {
             // Function definition creates a local binding for the function name
             // But we don't recurse into the function body - that's a separate scope
             assigned_names.insert(interner.get_str(name.name_id).to_string());
+            // Decorators are evaluated in this scope
+            for decorator in decorators {
+                collect_assigned_names_from_expr(decorator, assigned_names, interner);
+            }
+        }
+        Node::ClassDef(ClassDef { name, base, body }) => {
+            // Class definition binds the class name; method bodies are separate scopes,
+            // but the base, attribute values and method decorators are evaluated in this scope
+            assigned_names.insert(interner.get_str(name.name_id).to_string());
+            if let Some(base) = base {
+                collect_assigned_names_from_expr(base, assigned_names, interner);
+            }
+            for item in body {
+                match item {
+                    ClassItem::Attr { value, .. } => collect_assigned_names_from_expr(value, assigned_names, interner),
+                    ClassItem::Method(func) => {
+                        for decorator in &func.decorators {
+                            collect_assigned_names_from_expr(decorator, assigned_names, interner);
+                        }
+                    }
+                }
+            }
         }
         Node::Try(Try {
             body,
             handlers,
             or_else,
             finally,
+            ..
         }) => {
             // Recurse into all blocks
             for n in body {
@@ -1892,9 +2427,40 @@ fn collect_scope_info_from_node(
                 collect_scope_info_from_node(n, global_names, nonlocal_names, assigned_names, interner);
             }
         }
-        // Import creates a binding for the module name (or alias)
-        Node::Import { binding, .. } => {
-            assigned_names.insert(interner.get_str(binding.name_id).to_string());
+        Node::Match(Match { subject, cases }) => {
+            collect_assigned_names_from_expr(subject, assigned_names, interner);
+            for case in cases {
+                // Capture names in the pattern are assigned
+                let mut names = Vec::new();
+                case.pattern.bindings(&mut names);
+                for name in names {
+                    assigned_names.insert(interner.get_str(name.name_id).to_string());
+                }
+                if let Some(guard) = &case.guard {
+                    collect_assigned_names_from_expr(guard, assigned_names, interner);
+                }
+                for n in &case.body {
+                    collect_scope_info_from_node(n, global_names, nonlocal_names, assigned_names, interner);
+                }
+            }
+        }
+        Node::With(With { items, body, .. }) => {
+            for item in items {
+                collect_assigned_names_from_expr(&item.context_expr, assigned_names, interner);
+                // The `as` target is assigned
+                if let Some(target) = &item.target {
+                    collect_names_from_unpack_target(target, assigned_names, interner);
+                }
+            }
+            for n in body {
+                collect_scope_info_from_node(n, global_names, nonlocal_names, assigned_names, interner);
+            }
+        }
+        // Import creates a binding for each module name (or alias)
+        Node::Import(modules) => {
+            for module in modules {
+                assigned_names.insert(interner.get_str(module.binding.name_id).to_string());
+            }
         }
         // ImportFrom creates bindings for each imported name (or alias)
         Node::ImportFrom { names, .. } => {
@@ -1909,6 +2475,10 @@ fn collect_scope_info_from_node(
         Node::Raise(Some(expr)) => {
             collect_assigned_names_from_expr(expr, assigned_names, interner);
         }
+        Node::RaiseFrom { exc, cause } => {
+            collect_assigned_names_from_expr(exc, assigned_names, interner);
+            collect_assigned_names_from_expr(cause, assigned_names, interner);
+        }
         Node::Assert { test, msg } => {
             collect_assigned_names_from_expr(test, assigned_names, interner);
             if let Some(m) = msg {
@@ -1941,7 +2511,9 @@ fn collect_assigned_names_from_expr(expr: &ExprLoc, assigned_names: &mut AHashSe
         }
         Expr::Dict(pairs) => {
             for (key, value) in pairs {
-                collect_assigned_names_from_expr(key, assigned_names, interner);
+                if let Some(key) = key {
+                    collect_assigned_names_from_expr(key, assigned_names, interner);
+                }
                 collect_assigned_names_from_expr(value, assigned_names, interner);
             }
         }
@@ -1959,7 +2531,10 @@ fn collect_assigned_names_from_expr(expr: &ExprLoc, assigned_names: &mut AHashSe
         | Expr::UnaryMinus(operand)
         | Expr::UnaryPlus(operand)
         | Expr::UnaryInvert(operand)
-        | Expr::Await(operand) => {
+        | Expr::Starred(operand)
+        | Expr::Await(operand)
+        | Expr::Yield(Some(operand))
+        | Expr::YieldFrom(operand) => {
             collect_assigned_names_from_expr(operand, assigned_names, interner);
         }
         Expr::Subscript { object, index } => {
@@ -2023,10 +2598,19 @@ fn collect_assigned_names_from_expr(expr: &ExprLoc, assigned_names: &mut AHashSe
                 collect_assigned_names_from_expr(e, assigned_names, interner);
             }
         }
+        // Only the first iterable of a generator expression is evaluated in our scope
+        Expr::GeneratorExpRaw { generators, .. } => {
+            if let Some(first) = generators.first() {
+                collect_assigned_names_from_expr(&first.iter, assigned_names, interner);
+            }
+        }
+        Expr::GeneratorExp { iter, .. } => {
+            collect_assigned_names_from_expr(iter, assigned_names, interner);
+        }
         // Lambda bodies have their own scope - walrus inside them doesn't affect us
         Expr::LambdaRaw { .. } | Expr::Lambda { .. } => {}
         // Leaf expressions don't contain walrus operators
-        Expr::Literal(_) | Expr::Builtin(_) | Expr::Name(_) => {}
+        Expr::Literal(_) | Expr::Builtin(_) | Expr::Name(_) | Expr::Yield(None) => {}
     }
 }
 
@@ -2053,27 +2637,23 @@ fn collect_assigned_names_from_args(
                 collect_assigned_names_from_expr(&kwarg.value, assigned_names, interner);
             }
         }
-        ArgExprs::ArgsKargs {
-            args,
-            kwargs,
-            var_args,
-            var_kwargs,
-        } => {
-            if let Some(args) = args {
-                for arg in args {
-                    collect_assigned_names_from_expr(arg, assigned_names, interner);
-                }
+        ArgExprs::ArgsKargs { args, kwargs } => {
+            for arg in args {
+                collect_assigned_names_from_expr(arg, assigned_names, interner);
             }
-            if let Some(kwargs) = kwargs {
-                for kwarg in kwargs {
-                    collect_assigned_names_from_expr(&kwarg.value, assigned_names, interner);
-                }
+            for kwarg in kwargs {
+                collect_assigned_names_from_expr(&kwarg.value, assigned_names, interner);
             }
-            if let Some(var_args) = var_args {
-                collect_assigned_names_from_expr(var_args, assigned_names, interner);
+        }
+        ArgExprs::Unpack { args, kwargs } => {
+            for arg in args {
+                collect_assigned_names_from_expr(arg, assigned_names, interner);
             }
-            if let Some(var_kwargs) = var_kwargs {
-                collect_assigned_names_from_expr(var_kwargs, assigned_names, interner);
+            for kwarg in kwargs {
+                match kwarg {
+                    KwargExpr::Named(kwarg) => collect_assigned_names_from_expr(&kwarg.value, assigned_names, interner),
+                    KwargExpr::Unpack(mapping) => collect_assigned_names_from_expr(mapping, assigned_names, interner),
+                }
             }
         }
     }
@@ -2091,52 +2671,35 @@ fn collect_cell_vars_from_node(
     interner: &InternerBuilder,
 ) {
     match node {
-        Node::FunctionDef(RawFunctionDef { signature, body, .. }) => {
-            // Find what names are referenced inside this nested function
-            let mut referenced = AHashSet::new();
-            for n in body {
-                collect_referenced_names_from_node(n, &mut referenced, interner);
+        Node::FunctionDef(func) => {
+            for decorator in &func.decorators {
+                collect_cell_vars_from_expr(decorator, our_locals, cell_vars, interner);
             }
-
-            // Extract param names from signature for scope analysis
-            let param_names: Vec<StringId> = signature.param_names().collect();
-
-            // Collect the nested function's own locals (params + assigned)
-            let nested_scope = collect_function_scope_info(body, &param_names, interner);
-
-            // Any name that is:
-            // - Referenced by the nested function
-            // - Not a local of the nested function
-            // - Not declared global in the nested function
-            // - In our locals
-            // becomes a cell_var
-            for name in &referenced {
-                if !nested_scope.assigned_names.contains(name)
-                    && !param_names.iter().any(|p| interner.get_str(*p) == name)
-                    && !nested_scope.global_names.contains(name)
-                    && our_locals.contains(name)
-                {
-                    cell_vars.insert(name.clone());
-                }
+            collect_cell_vars_from_function(func, our_locals, cell_vars, interner);
+        }
+        Node::ClassDef(ClassDef { base, body, .. }) => {
+            if let Some(base) = base {
+                collect_cell_vars_from_expr(base, our_locals, cell_vars, interner);
             }
-
-            // Also check what the nested function explicitly declares as nonlocal
-            for name in &nested_scope.nonlocal_names {
-                if our_locals.contains(name) {
-                    cell_vars.insert(name.clone());
+            for item in body {
+                match item {
+                    ClassItem::Attr { value, .. } => {
+                        collect_cell_vars_from_expr(value, our_locals, cell_vars, interner)
+                    }
+                    ClassItem::Method(func) => {
+                        for decorator in &func.decorators {
+                            collect_cell_vars_from_expr(decorator, our_locals, cell_vars, interner);
+                        }
+                        collect_cell_vars_from_function(func, our_locals, cell_vars, interner);
+                    }
                 }
             }
         }
         // Recurse into control flow structures
-        Node::For { body, or_else, .. } => {
-            for n in body {
-                collect_cell_vars_from_node(n, our_locals, cell_vars, interner);
-            }
-            for n in or_else {
-                collect_cell_vars_from_node(n, our_locals, cell_vars, interner);
-            }
-        }
-        Node::While { body, or_else, .. } => {
+        Node::For {
+            iter, body, or_else, ..
+        } => {
+            collect_cell_vars_from_expr(iter, our_locals, cell_vars, interner);
             for n in body {
                 collect_cell_vars_from_node(n, our_locals, cell_vars, interner);
             }
@@ -2144,7 +2707,8 @@ fn collect_cell_vars_from_node(
                 collect_cell_vars_from_node(n, our_locals, cell_vars, interner);
             }
         }
-        Node::If { body, or_else, .. } => {
+        Node::While { test, body, or_else } | Node::If { test, body, or_else } => {
+            collect_cell_vars_from_expr(test, our_locals, cell_vars, interner);
             for n in body {
                 collect_cell_vars_from_node(n, our_locals, cell_vars, interner);
             }
@@ -2157,6 +2721,7 @@ fn collect_cell_vars_from_node(
             handlers,
             or_else,
             finally,
+            ..
         }) => {
             for n in body {
                 collect_cell_vars_from_node(n, our_locals, cell_vars, interner);
@@ -2173,10 +2738,39 @@ fn collect_cell_vars_from_node(
                 collect_cell_vars_from_node(n, our_locals, cell_vars, interner);
             }
         }
+        Node::Match(Match { subject, cases }) => {
+            collect_cell_vars_from_expr(subject, our_locals, cell_vars, interner);
+            for case in cases {
+                if let Some(guard) = &case.guard {
+                    collect_cell_vars_from_expr(guard, our_locals, cell_vars, interner);
+                }
+                for n in &case.body {
+                    collect_cell_vars_from_node(n, our_locals, cell_vars, interner);
+                }
+            }
+        }
+        Node::With(With { items, body, .. }) => {
+            for item in items {
+                collect_cell_vars_from_expr(&item.context_expr, our_locals, cell_vars, interner);
+            }
+            for n in body {
+                collect_cell_vars_from_node(n, our_locals, cell_vars, interner);
+            }
+        }
         // Handle expressions that may contain lambdas
-        Node::Expr(expr) | Node::Return(expr) => {
+        Node::Expr(expr) | Node::Return(expr) | Node::Raise(Some(expr)) => {
             collect_cell_vars_from_expr(expr, our_locals, cell_vars, interner);
         }
+        Node::RaiseFrom { exc, cause } => {
+            collect_cell_vars_from_expr(exc, our_locals, cell_vars, interner);
+            collect_cell_vars_from_expr(cause, our_locals, cell_vars, interner);
+        }
+        Node::Assert { test, msg } => {
+            collect_cell_vars_from_expr(test, our_locals, cell_vars, interner);
+            if let Some(msg) = msg {
+                collect_cell_vars_from_expr(msg, our_locals, cell_vars, interner);
+            }
+        }
         Node::Assign { object, .. } | Node::UnpackAssign { object, .. } => {
             collect_cell_vars_from_expr(object, our_locals, cell_vars, interner);
         }
@@ -2191,11 +2785,80 @@ fn collect_cell_vars_from_node(
             collect_cell_vars_from_expr(object, our_locals, cell_vars, interner);
             collect_cell_vars_from_expr(value, our_locals, cell_vars, interner);
         }
+        Node::SubscriptOpAssign {
+            object, index, value, ..
+        } => {
+            collect_cell_vars_from_expr(object, our_locals, cell_vars, interner);
+            collect_cell_vars_from_expr(index, our_locals, cell_vars, interner);
+            collect_cell_vars_from_expr(value, our_locals, cell_vars, interner);
+        }
+        Node::AttrOpAssign { object, value, .. } => {
+            collect_cell_vars_from_expr(object, our_locals, cell_vars, interner);
+            collect_cell_vars_from_expr(value, our_locals, cell_vars, interner);
+        }
+        Node::Delete(targets) => {
+            for target in targets {
+                match target {
+                    DeleteTarget::Name(_) => {}
+                    DeleteTarget::Subscript { object, index, .. } => {
+                        collect_cell_vars_from_expr(object, our_locals, cell_vars, interner);
+                        collect_cell_vars_from_expr(index, our_locals, cell_vars, interner);
+                    }
+                    DeleteTarget::Attr { object, .. } => {
+                        collect_cell_vars_from_expr(object, our_locals, cell_vars, interner);
+                    }
+                }
+            }
+        }
         // Other nodes don't contain nested function definitions or lambdas
         _ => {}
     }
 }
 
+/// Collects cell_vars captured from our scope by a single nested function (or method).
+fn collect_cell_vars_from_function(
+    func: &RawFunctionDef,
+    our_locals: &AHashSet<String>,
+    cell_vars: &mut AHashSet<String>,
+    interner: &InternerBuilder,
+) {
+    let RawFunctionDef { signature, body, .. } = func;
+    // Find what names are referenced inside this nested function
+    let mut referenced = AHashSet::new();
+    for n in body {
+        collect_referenced_names_from_node(n, &mut referenced, interner);
+    }
+
+    // Extract param names from signature for scope analysis
+    let param_names: Vec<StringId> = signature.param_names().collect();
+
+    // Collect the nested function's own locals (params + assigned)
+    let nested_scope = collect_function_scope_info(body, &param_names, interner);
+
+    // Any name that is:
+    // - Referenced by the nested function
+    // - Not a local of the nested function
+    // - Not declared global in the nested function
+    // - In our locals
+    // becomes a cell_var
+    for name in &referenced {
+        if !nested_scope.assigned_names.contains(name)
+            && !param_names.iter().any(|p| interner.get_str(*p) == name)
+            && !nested_scope.global_names.contains(name)
+            && our_locals.contains(name)
+        {
+            cell_vars.insert(name.clone());
+        }
+    }
+
+    // Also check what the nested function explicitly declares as nonlocal
+    for name in &nested_scope.nonlocal_names {
+        if our_locals.contains(name) {
+            cell_vars.insert(name.clone());
+        }
+    }
+}
+
 /// Collects cell_vars from lambda expressions within an expression.
 ///
 /// Recursively searches through an expression tree to find lambda expressions
@@ -2261,7 +2924,9 @@ fn collect_cell_vars_from_expr(
         }
         Expr::Dict(pairs) => {
             for (key, value) in pairs {
-                collect_cell_vars_from_expr(key, our_locals, cell_vars, interner);
+                if let Some(key) = key {
+                    collect_cell_vars_from_expr(key, our_locals, cell_vars, interner);
+                }
                 collect_cell_vars_from_expr(value, our_locals, cell_vars, interner);
             }
         }
@@ -2275,7 +2940,11 @@ fn collect_cell_vars_from_expr(
                 collect_cell_vars_from_expr(expr, our_locals, cell_vars, interner);
             }
         }
-        Expr::Not(operand) | Expr::UnaryMinus(operand) | Expr::UnaryPlus(operand) | Expr::UnaryInvert(operand) => {
+        Expr::Not(operand)
+        | Expr::UnaryMinus(operand)
+        | Expr::UnaryPlus(operand)
+        | Expr::UnaryInvert(operand)
+        | Expr::Starred(operand) => {
             collect_cell_vars_from_expr(operand, our_locals, cell_vars, interner);
         }
         Expr::Subscript { object, index } => {
@@ -2331,11 +3000,37 @@ fn collect_cell_vars_from_expr(
             // Only scan the value expression for cell vars
             collect_cell_vars_from_expr(value, our_locals, cell_vars, interner);
         }
-        Expr::Await(value) => {
+        Expr::Await(value) | Expr::Yield(Some(value)) | Expr::YieldFrom(value) => {
             collect_cell_vars_from_expr(value, our_locals, cell_vars, interner);
         }
+        Expr::GeneratorExpRaw {
+            name_id,
+            iter_name_id,
+            elt,
+            generators,
+        } => {
+            // A generator expression is a nested function; only its first iterable
+            // is evaluated in our scope
+            let (signature, body, first_iter, is_async) =
+                genexp_function_parts(*iter_name_id, (**elt).clone(), generators.clone());
+            collect_cell_vars_from_expr(&first_iter, our_locals, cell_vars, interner);
+            let func = RawFunctionDef {
+                name: Identifier::new(*name_id, expr.position),
+                signature,
+                body,
+                is_async,
+                decorators: Vec::new(),
+            };
+            collect_cell_vars_from_function(&func, our_locals, cell_vars, interner);
+        }
         // Leaf expressions
-        Expr::Literal(_) | Expr::Builtin(_) | Expr::Name(_) | Expr::Lambda { .. } | Expr::Slice { .. } => {}
+        Expr::Literal(_)
+        | Expr::Builtin(_)
+        | Expr::Name(_)
+        | Expr::Lambda { .. }
+        | Expr::GeneratorExp { .. }
+        | Expr::Slice { .. }
+        | Expr::Yield(None) => {}
     }
 }
 
@@ -2364,27 +3059,25 @@ fn collect_cell_vars_from_args(
                 collect_cell_vars_from_expr(&kwarg.value, our_locals, cell_vars, interner);
             }
         }
-        ArgExprs::ArgsKargs {
-            args,
-            kwargs,
-            var_args,
-            var_kwargs,
-        } => {
-            if let Some(args) = args {
-                for arg in args {
-                    collect_cell_vars_from_expr(arg, our_locals, cell_vars, interner);
-                }
+        ArgExprs::ArgsKargs { args, kwargs } => {
+            for arg in args {
+                collect_cell_vars_from_expr(arg, our_locals, cell_vars, interner);
             }
-            if let Some(kwargs) = kwargs {
-                for kwarg in kwargs {
-                    collect_cell_vars_from_expr(&kwarg.value, our_locals, cell_vars, interner);
-                }
+            for kwarg in kwargs {
+                collect_cell_vars_from_expr(&kwarg.value, our_locals, cell_vars, interner);
             }
-            if let Some(var_args) = var_args {
-                collect_cell_vars_from_expr(var_args, our_locals, cell_vars, interner);
+        }
+        ArgExprs::Unpack { args, kwargs } => {
+            for arg in args {
+                collect_cell_vars_from_expr(arg, our_locals, cell_vars, interner);
             }
-            if let Some(var_kwargs) = var_kwargs {
-                collect_cell_vars_from_expr(var_kwargs, our_locals, cell_vars, interner);
+            for kwarg in kwargs {
+                match kwarg {
+                    KwargExpr::Named(kwarg) => {
+                        collect_cell_vars_from_expr(&kwarg.value, our_locals, cell_vars, interner)
+                    }
+                    KwargExpr::Unpack(mapping) => collect_cell_vars_from_expr(mapping, our_locals, cell_vars, interner),
+                }
             }
         }
     }
@@ -2399,6 +3092,10 @@ fn collect_referenced_names_from_node(node: &ParseNode, referenced: &mut AHashSe
         Node::Return(expr) => collect_referenced_names_from_expr(expr, referenced, interner),
         Node::Raise(Some(expr)) => collect_referenced_names_from_expr(expr, referenced, interner),
         Node::Raise(None) => {}
+        Node::RaiseFrom { exc, cause } => {
+            collect_referenced_names_from_expr(exc, referenced, interner);
+            collect_referenced_names_from_expr(cause, referenced, interner);
+        }
         Node::Assert { test, msg } => {
             collect_referenced_names_from_expr(test, referenced, interner);
             if let Some(m) = msg {
@@ -2427,6 +3124,31 @@ fn collect_referenced_names_from_node(node: &ParseNode, referenced: &mut AHashSe
             collect_referenced_names_from_expr(object, referenced, interner);
             collect_referenced_names_from_expr(value, referenced, interner);
         }
+        Node::SubscriptOpAssign {
+            object, index, value, ..
+        } => {
+            collect_referenced_names_from_expr(object, referenced, interner);
+            collect_referenced_names_from_expr(index, referenced, interner);
+            collect_referenced_names_from_expr(value, referenced, interner);
+        }
+        Node::AttrOpAssign { object, value, .. } => {
+            collect_referenced_names_from_expr(object, referenced, interner);
+            collect_referenced_names_from_expr(value, referenced, interner);
+        }
+        Node::Delete(targets) => {
+            for target in targets {
+                match target {
+                    DeleteTarget::Name(_) => {}
+                    DeleteTarget::Subscript { object, index, .. } => {
+                        collect_referenced_names_from_expr(object, referenced, interner);
+                        collect_referenced_names_from_expr(index, referenced, interner);
+                    }
+                    DeleteTarget::Attr { object, .. } => {
+                        collect_referenced_names_from_expr(object, referenced, interner);
+                    }
+                }
+            }
+        }
         Node::For {
             iter, body, or_else, ..
         } => {
@@ -2456,14 +3178,36 @@ fn collect_referenced_names_from_node(node: &ParseNode, referenced: &mut AHashSe
                 collect_referenced_names_from_node(n, referenced, interner);
             }
         }
-        Node::FunctionDef(_) => {
-            // Don't recurse into nested function bodies - they have their own scope
+        Node::FunctionDef(RawFunctionDef { decorators, .. }) => {
+            // Don't recurse into nested function bodies - they have their own scope,
+            // but decorators are evaluated in this one
+            for decorator in decorators {
+                collect_referenced_names_from_expr(decorator, referenced, interner);
+            }
+        }
+        Node::ClassDef(ClassDef { base, body, .. }) => {
+            // Method bodies have their own scope, but the base, attribute values and
+            // method decorators don't
+            if let Some(base) = base {
+                collect_referenced_names_from_expr(base, referenced, interner);
+            }
+            for item in body {
+                match item {
+                    ClassItem::Attr { value, .. } => collect_referenced_names_from_expr(value, referenced, interner),
+                    ClassItem::Method(func) => {
+                        for decorator in &func.decorators {
+                            collect_referenced_names_from_expr(decorator, referenced, interner);
+                        }
+                    }
+                }
+            }
         }
         Node::Try(Try {
             body,
             handlers,
             or_else,
             finally,
+            ..
         }) => {
             for n in body {
                 collect_referenced_names_from_node(n, referenced, interner);
@@ -2484,8 +3228,30 @@ fn collect_referenced_names_from_node(node: &ParseNode, referenced: &mut AHashSe
                 collect_referenced_names_from_node(n, referenced, interner);
             }
         }
+        Node::Match(Match { subject, cases }) => {
+            collect_referenced_names_from_expr(subject, referenced, interner);
+            for case in cases {
+                // Value patterns and class patterns reference names like `Color.RED`
+                case.pattern
+                    .for_each_expr(&mut |expr| collect_referenced_names_from_expr(expr, referenced, interner));
+                if let Some(guard) = &case.guard {
+                    collect_referenced_names_from_expr(guard, referenced, interner);
+                }
+                for n in &case.body {
+                    collect_referenced_names_from_node(n, referenced, interner);
+                }
+            }
+        }
+        Node::With(With { items, body, .. }) => {
+            for item in items {
+                collect_referenced_names_from_expr(&item.context_expr, referenced, interner);
+            }
+            for n in body {
+                collect_referenced_names_from_node(n, referenced, interner);
+            }
+        }
         // Imports create bindings but don't reference names
-        Node::Import { .. } | Node::ImportFrom { .. } => {}
+        Node::Import(_) | Node::ImportFrom { .. } => {}
         Node::Pass
         | Node::ReturnNone
         | Node::Global { .. }
@@ -2515,7 +3281,9 @@ fn collect_referenced_names_from_expr(
         }
         Expr::Dict(pairs) => {
             for (key, value) in pairs {
-                collect_referenced_names_from_expr(key, referenced, interner);
+                if let Some(key) = key {
+                    collect_referenced_names_from_expr(key, referenced, interner);
+                }
                 collect_referenced_names_from_expr(value, referenced, interner);
             }
         }
@@ -2529,7 +3297,11 @@ fn collect_referenced_names_from_expr(
                 collect_referenced_names_from_expr(expr, referenced, interner);
             }
         }
-        Expr::Not(operand) | Expr::UnaryMinus(operand) | Expr::UnaryPlus(operand) | Expr::UnaryInvert(operand) => {
+        Expr::Not(operand)
+        | Expr::UnaryMinus(operand)
+        | Expr::UnaryPlus(operand)
+        | Expr::UnaryInvert(operand)
+        | Expr::Starred(operand) => {
             collect_referenced_names_from_expr(operand, referenced, interner);
         }
         Expr::FString(parts) => {
@@ -2563,7 +3335,9 @@ fn collect_referenced_names_from_expr(
             collect_referenced_names_from_expr(body, referenced, interner);
             collect_referenced_names_from_expr(orelse, referenced, interner);
         }
-        Expr::ListComp { elt, generators } | Expr::SetComp { elt, generators } => {
+        Expr::ListComp { elt, generators }
+        | Expr::SetComp { elt, generators }
+        | Expr::GeneratorExpRaw { elt, generators, .. } => {
             collect_referenced_names_from_comprehension(generators, Some(elt), None, referenced, interner);
         }
         Expr::DictComp { key, value, generators } => {
@@ -2606,9 +3380,9 @@ fn collect_referenced_names_from_expr(
                 }
             }
         }
-        Expr::Lambda { .. } => {
-            // Lambda should only exist after preparation; this function operates on raw expressions
-            unreachable!("Expr::Lambda should not exist during scope analysis")
+        Expr::Lambda { .. } | Expr::GeneratorExp { .. } => {
+            // These only exist after preparation; this function operates on raw expressions
+            unreachable!("prepared function expressions should not exist during scope analysis")
         }
         Expr::Named { value, .. } => {
             // Only the value is referenced; target is being assigned, not read
@@ -2625,9 +3399,10 @@ fn collect_referenced_names_from_expr(
                 collect_referenced_names_from_expr(expr, referenced, interner);
             }
         }
-        Expr::Await(value) => {
+        Expr::Await(value) | Expr::YieldFrom(value) | Expr::Yield(Some(value)) => {
             collect_referenced_names_from_expr(value, referenced, interner);
         }
+        Expr::Yield(None) => {}
     }
 }
 
@@ -2707,8 +3482,29 @@ fn collect_referenced_names_from_args(
                 collect_referenced_names_from_expr(e, referenced, interner);
             }
         }
-        ArgExprs::Kwargs(_) | ArgExprs::ArgsKargs { .. } => {
-            // TODO: handle kwargs when needed
+        ArgExprs::Kwargs(kwargs) => {
+            for kwarg in kwargs {
+                collect_referenced_names_from_expr(&kwarg.value, referenced, interner);
+            }
+        }
+        ArgExprs::ArgsKargs { args, kwargs } => {
+            for e in args {
+                collect_referenced_names_from_expr(e, referenced, interner);
+            }
+            for kwarg in kwargs {
+                collect_referenced_names_from_expr(&kwarg.value, referenced, interner);
+            }
+        }
+        ArgExprs::Unpack { args, kwargs } => {
+            for e in args {
+                collect_referenced_names_from_expr(e, referenced, interner);
+            }
+            for kwarg in kwargs {
+                match kwarg {
+                    KwargExpr::Named(kwarg) => collect_referenced_names_from_expr(&kwarg.value, referenced, interner),
+                    KwargExpr::Unpack(mapping) => collect_referenced_names_from_expr(mapping, referenced, interner),
+                }
+            }
         }
     }
 }
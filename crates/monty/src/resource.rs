@@ -44,6 +44,15 @@ pub fn check_mult_size(a_bits: u64, b_bits: u64, tracker: &impl ResourceTracker)
     check_estimated_size(estimate_bits_to_bytes(a_bits.saturating_add(b_bits)), tracker)
 }
 
+/// Pre-checks that an integer result with at most `bits` significant bits won't exceed
+/// resource limits.
+///
+/// Used where an upper bound of the result size is known before computing it, like
+/// `math.factorial(n)` which has fewer than `n * n.bits()` bits.
+pub fn check_int_size(bits: u64, tracker: &impl ResourceTracker) -> Result<(), ResourceError> {
+    check_estimated_size(estimate_bits_to_bytes(bits), tracker)
+}
+
 /// Pre-checks that a left shift won't exceed resource limits.
 ///
 /// The result of `value << shift` has approximately `value_bits + shift` bits.
@@ -59,6 +68,22 @@ pub fn check_lshift_size(
     check_estimated_size(estimate_bits_to_bytes(value_bits.saturating_add(shift_amount)), tracker)
 }
 
+/// Pre-checks that the width or precision of a `%` format won't exceed resource limits.
+///
+/// This prevents DoS via expressions like `'%999999999d' % 1`, whose padding would
+/// otherwise be built before the allocation check can catch it.
+pub fn check_format_size(width: usize, precision: usize, tracker: &impl ResourceTracker) -> Result<(), ResourceError> {
+    check_estimated_size(width.max(precision), tracker)
+}
+
+/// Checks the size of output being built incrementally, such as a `json.dumps()` result.
+///
+/// The output isn't on the heap until it's complete, so this stops a serializer from
+/// building a string larger than the memory limit first.
+pub fn check_output_size(len: usize, tracker: &impl ResourceTracker) -> Result<(), ResourceError> {
+    check_estimated_size(len, tracker)
+}
+
 /// Checks an estimated result size against the resource tracker.
 ///
 /// Only calls the tracker when the estimate exceeds `LARGE_RESULT_THRESHOLD`
@@ -1,15 +1,19 @@
 //! Public interface for running Monty code.
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{
+    borrow::Cow,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use crate::{
     ExcType, MontyException,
     asyncio::CallId,
     bytecode::{Code, Compiler, FrameExit, VM, VMSnapshot},
+    eval::{CompileRequest, Compiled, compile_eval},
     exception_private::RunResult,
     heap::Heap,
     intern::{ExtFunctionId, Interns},
     io::{PrintWriter, StdPrint},
-    namespace::Namespaces,
+    namespace::{NamespaceId, Namespaces},
     object::MontyObject,
     os::OsFunction,
     parse::parse,
@@ -58,7 +62,43 @@ impl MontyRun {
         input_names: Vec<String>,
         external_functions: Vec<String>,
     ) -> Result<Self, MontyException> {
-        Executor::new(code, script_name, input_names, external_functions).map(|executor| Self { executor })
+        Self::new_with_allowed_modules(code, script_name, input_names, external_functions, None)
+    }
+
+    /// Creates a new run snapshot which may only import the given builtin modules.
+    ///
+    /// Listing a package also allows its submodules, so `"os"` allows `os.path`. Importing any
+    /// other builtin module raises `ModuleNotFoundError` before execution starts. With
+    /// `allowed_modules` set to `None` this is the same as `new()`.
+    ///
+    /// # Example
+    /// ```
+    /// use monty::MontyRun;
+    ///
+    /// let allowed = Some(vec!["sys".to_owned()]);
+    /// let runner = MontyRun::new_with_allowed_modules("import sys".to_owned(), "test.py", vec![], vec![], allowed.clone());
+    /// assert!(runner.is_ok());
+    /// let runner = MontyRun::new_with_allowed_modules("import os".to_owned(), "test.py", vec![], vec![], allowed);
+    /// assert!(runner.is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `MontyException` if the code cannot be parsed or imports a module that isn't allowed.
+    pub fn new_with_allowed_modules(
+        code: String,
+        script_name: &str,
+        input_names: Vec<String>,
+        external_functions: Vec<String>,
+        allowed_modules: Option<Vec<String>>,
+    ) -> Result<Self, MontyException> {
+        Executor::new(
+            code,
+            script_name,
+            input_names,
+            external_functions,
+            allowed_modules.as_deref(),
+        )
+        .map(|executor| Self { executor })
     }
 
     /// Returns the code that was parsed to create this snapshot.
@@ -149,7 +189,7 @@ impl MontyRun {
         resource_tracker: T,
         print: &mut impl PrintWriter,
     ) -> Result<RunProgress<T>, MontyException> {
-        let executor = self.executor;
+        let mut executor = self.executor;
 
         // Create heap and prepare namespaces
         let mut heap = Heap::new(executor.namespace_size, resource_tracker);
@@ -162,6 +202,7 @@ impl MontyRun {
         let vm_result = vm.run_module(&executor.module_code);
 
         let vm_state = vm.check_snapshot(&vm_result);
+        let (vm_result, vm_state) = executor.compile_evals(vm_result, vm_state, &mut heap, &mut namespaces, print);
 
         // Handle the result using the destructured parts
         handle_vm_result(vm_result, vm_state, executor, heap, namespaces)
@@ -197,6 +238,11 @@ pub enum RunProgress<T: ResourceTracker> {
         args: Vec<MontyObject>,
         /// The keyword arguments passed to the function (key, value pairs).
         kwargs: Vec<(MontyObject, MontyObject)>,
+        /// Whether this is a call to a method of a dataclass passed in by the host.
+        ///
+        /// If true, `function_name` is the method name and the first positional argument
+        /// is the dataclass instance the method was called on.
+        method_call: bool,
         /// Unique identifier for this call (used for async correlation).
         call_id: u32,
         /// The execution state that can be resumed with a return value.
@@ -253,6 +299,7 @@ impl<T: ResourceTracker> RunProgress<T> {
                 kwargs,
                 call_id,
                 state,
+                ..
             } => Some((function_name, args, kwargs, call_id, state)),
             _ => None,
         }
@@ -393,6 +440,10 @@ impl<T: ResourceTracker> Snapshot<T> {
         let vm_result = match ext_result {
             ExternalResult::Return(obj) => vm.resume(obj),
             ExternalResult::Error(exc) => vm.resume_with_exception(exc.into()),
+            // The current time is needed immediately to build the date or datetime, it can't be awaited later
+            ExternalResult::Future if vm.has_pending_clock() => vm.resume_with_exception(
+                MontyException::runtime_error("the current time can't be resolved as a future".to_owned()).into(),
+            ),
             ExternalResult::Future => {
                 // Get the call_id and ext_function_id that were stored when this Snapshot was created
                 let call_id = CallId::new(self.pending_call_id);
@@ -411,6 +462,9 @@ impl<T: ResourceTracker> Snapshot<T> {
         };
 
         let vm_state = vm.check_snapshot(&vm_result);
+        let (vm_result, vm_state) =
+            self.executor
+                .compile_evals(vm_result, vm_state, &mut self.heap, &mut self.namespaces, print);
 
         // Handle the result using the destructured parts
         handle_vm_result(vm_result, vm_state, self.executor, self.heap, self.namespaces)
@@ -506,7 +560,7 @@ impl<T: ResourceTracker> FutureSnapshot<T> {
 
         // Destructure self to avoid partial move issues
         let Self {
-            executor,
+            mut executor,
             vm_state,
             mut heap,
             mut namespaces,
@@ -558,7 +612,7 @@ impl<T: ResourceTracker> FutureSnapshot<T> {
             vm.cleanup();
             #[cfg(feature = "ref-count-panic")]
             namespaces.drop_global_with_heap(&mut heap);
-            return Err(error.into_python_exception(&executor.interns, &executor.code));
+            return Err(error.into_python_exception(&heap, &executor.interns, &executor.code));
         }
 
         // Push resolved value for main task if it was blocked.
@@ -573,7 +627,7 @@ impl<T: ResourceTracker> FutureSnapshot<T> {
                 vm.cleanup();
                 #[cfg(feature = "ref-count-panic")]
                 namespaces.drop_global_with_heap(&mut heap);
-                return Err(e.into_python_exception(&executor.interns, &executor.code));
+                return Err(e.into_python_exception(&heap, &executor.interns, &executor.code));
             }
         };
 
@@ -600,6 +654,7 @@ impl<T: ResourceTracker> FutureSnapshot<T> {
         let result = vm.run();
 
         let vm_state = vm.check_snapshot(&result);
+        let (result, vm_state) = executor.compile_evals(result, vm_state, &mut heap, &mut namespaces, print);
 
         // Handle the result using the destructured parts
         handle_vm_result(result, vm_state, executor, heap, namespaces)
@@ -649,6 +704,24 @@ fn handle_vm_result<T: ResourceTracker>(
                 function_name,
                 args: args_py,
                 kwargs: kwargs_py,
+                method_call: false,
+                call_id: call_id.raw(),
+                state: new_snapshot!(call_id),
+            })
+        }
+        Ok(FrameExit::MethodCall {
+            method_name,
+            args,
+            call_id,
+        }) => {
+            let function_name = executor.interns.get_str(method_name).to_owned();
+            let (args_py, kwargs_py) = args.into_py_objects(&mut heap, &executor.interns);
+
+            Ok(RunProgress::FunctionCall {
+                function_name,
+                args: args_py,
+                kwargs: kwargs_py,
+                method_call: true,
                 call_id: call_id.raw(),
                 state: new_snapshot!(call_id),
             })
@@ -678,11 +751,12 @@ fn handle_vm_result<T: ResourceTracker>(
                 pending_call_ids,
             }))
         }
+        Ok(FrameExit::Compile(_)) => unreachable!("eval/exec code is compiled by Executor::compile_evals"),
         Err(err) => {
             #[cfg(feature = "ref-count-panic")]
             namespaces.drop_global_with_heap(&mut heap);
 
-            Err(err.into_python_exception(&executor.interns, &executor.code))
+            Err(err.into_python_exception(&heap, &executor.interns, &executor.code))
         }
     }
 }
@@ -695,9 +769,12 @@ fn handle_vm_result<T: ResourceTracker>(
 struct Executor {
     /// Number of slots needed in the global namespace.
     namespace_size: usize,
-    /// Maps variable names to their indices in the namespace. Used for ref-count testing.
-    #[cfg(feature = "ref-count-return")]
-    name_map: ahash::AHashMap<String, crate::namespace::NamespaceId>,
+    /// Maps variable names to their indices in the namespace.
+    ///
+    /// Used to resolve globals in code passed to `eval()`/`exec()`, and for ref-count testing.
+    name_map: ahash::AHashMap<String, NamespaceId>,
+    /// Modules `import` may load, `None` to allow all, also applied to eval/exec code.
+    allowed_modules: Option<Vec<String>>,
     /// Compiled bytecode for the module.
     module_code: Code,
     /// Interned strings used for looking up names and filenames during execution.
@@ -715,8 +792,8 @@ impl Clone for Executor {
     fn clone(&self) -> Self {
         Self {
             namespace_size: self.namespace_size,
-            #[cfg(feature = "ref-count-return")]
             name_map: self.name_map.clone(),
+            allowed_modules: self.allowed_modules.clone(),
             module_code: self.module_code.clone(),
             interns: self.interns.clone(),
             external_function_ids: self.external_function_ids.clone(),
@@ -727,15 +804,17 @@ impl Clone for Executor {
 }
 
 impl Executor {
-    /// Creates a new executor with the given code, filename, input names, and external functions.
+    /// Creates a new executor with the given code, filename, input names, external functions
+    /// and optional import allowlist.
     fn new(
         code: String,
         script_name: &str,
         input_names: Vec<String>,
         external_functions: Vec<String>,
+        allowed_modules: Option<&[String]>,
     ) -> Result<Self, MontyException> {
         let parse_result = parse(&code, script_name).map_err(|e| e.into_python_exc(script_name, &code))?;
-        let prepared = prepare(parse_result, input_names, &external_functions)
+        let prepared = prepare(parse_result, input_names, &external_functions, allowed_modules)
             .map_err(|e| e.into_python_exc(script_name, &code))?;
 
         // Incrementing order matches the indexes used in intern::Interns::get_external_function_name
@@ -754,8 +833,8 @@ impl Executor {
 
         Ok(Self {
             namespace_size: prepared.namespace_size,
-            #[cfg(feature = "ref-count-return")]
             name_map: prepared.name_map,
+            allowed_modules: allowed_modules.map(<[String]>::to_vec),
             module_code: compile_result.code,
             interns,
             external_function_ids,
@@ -788,8 +867,17 @@ impl Executor {
         let mut vm = VM::new(&mut heap, &mut namespaces, &self.interns, print);
         let frame_exit_result = vm.run_module(&self.module_code);
 
-        // Clean up VM state before it goes out of scope
-        vm.cleanup();
+        // Clean up VM state before it goes out of scope, unless it paused to compile eval/exec code
+        let vm_state = vm.check_compile(&frame_exit_result);
+        let mut interns = Cow::Borrowed(&self.interns);
+        let frame_exit_result = self.compile_evals_borrowed(
+            frame_exit_result,
+            vm_state,
+            &mut interns,
+            &mut heap,
+            &mut namespaces,
+            print,
+        );
 
         if heap.size() > heap_capacity {
             self.heap_capacity.store(heap.size(), Ordering::Relaxed);
@@ -799,8 +887,8 @@ impl Executor {
         #[cfg(feature = "ref-count-panic")]
         namespaces.drop_global_with_heap(&mut heap);
 
-        frame_exit_to_object(frame_exit_result, &mut heap, &self.interns)
-            .map_err(|e| e.into_python_exception(&self.interns, &self.code))
+        frame_exit_to_object(frame_exit_result, &mut heap, &interns)
+            .map_err(|e| e.into_python_exception(&heap, &interns, &self.code))
     }
 
     /// Executes the code and returns both the result and reference count data, used for testing only.
@@ -827,6 +915,16 @@ impl Executor {
         let mut print = StdPrint;
         let mut vm = VM::new(&mut heap, &mut namespaces, &self.interns, &mut print);
         let frame_exit_result = vm.run_module(&self.module_code);
+        let vm_state = vm.check_compile(&frame_exit_result);
+        let mut interns = Cow::Borrowed(&self.interns);
+        let frame_exit_result = self.compile_evals_borrowed(
+            frame_exit_result,
+            vm_state,
+            &mut interns,
+            &mut heap,
+            &mut namespaces,
+            &mut print,
+        );
 
         // Compute ref counts before consuming the heap - return value is still alive
         let final_namespace = namespaces.into_global();
@@ -848,8 +946,8 @@ impl Executor {
         }
 
         // Now convert the return value to MontyObject (this drops the Value, decrementing refcount)
-        let py_object = frame_exit_to_object(frame_exit_result, &mut heap, &self.interns)
-            .map_err(|e| e.into_python_exception(&self.interns, &self.code))?;
+        let py_object = frame_exit_to_object(frame_exit_result, &mut heap, &interns)
+            .map_err(|e| e.into_python_exception(&heap, &interns, &self.code))?;
 
         let allocations_since_gc = heap.get_allocations_since_gc();
 
@@ -862,6 +960,70 @@ impl Executor {
         })
     }
 
+    /// Compiles the code passed to `eval()`/`exec()`, or interns the name passed to `setattr()`,
+    /// whenever the VM pauses for it, resuming the VM until it exits for another reason.
+    ///
+    /// Compiled code and names are added to the executor's interns, so they're kept for the
+    /// rest of this run and any later run of a snapshot.
+    fn compile_evals<T: ResourceTracker>(
+        &mut self,
+        mut result: RunResult<FrameExit>,
+        mut vm_state: Option<VMSnapshot>,
+        heap: &mut Heap<T>,
+        namespaces: &mut Namespaces,
+        print: &mut impl PrintWriter,
+    ) -> (RunResult<FrameExit>, Option<VMSnapshot>) {
+        loop {
+            let request = match result {
+                Ok(FrameExit::Compile(request)) => request,
+                result => return (result, vm_state),
+            };
+            let snapshot = vm_state.take().expect("snapshot should exist for Compile");
+            let compiled = compile_request(
+                request,
+                &mut self.interns,
+                &self.name_map,
+                self.allowed_modules.as_deref(),
+                heap,
+            );
+            let mut vm = VM::restore(snapshot, &self.module_code, heap, namespaces, &self.interns, print);
+            result = vm.resume_compile(compiled);
+            vm_state = vm.check_snapshot(&result);
+        }
+    }
+
+    /// Like `compile_evals`, for runs that borrow the executor and complete without pausing.
+    ///
+    /// The interns are cloned the first time they're extended, so compiled code and names are
+    /// only kept for this run.
+    fn compile_evals_borrowed<T: ResourceTracker>(
+        &self,
+        mut result: RunResult<FrameExit>,
+        mut vm_state: Option<VMSnapshot>,
+        interns: &mut Cow<'_, Interns>,
+        heap: &mut Heap<T>,
+        namespaces: &mut Namespaces,
+        print: &mut impl PrintWriter,
+    ) -> RunResult<FrameExit> {
+        loop {
+            let request = match result {
+                Ok(FrameExit::Compile(request)) => request,
+                result => return result,
+            };
+            let snapshot = vm_state.take().expect("snapshot should exist for Compile");
+            let compiled = compile_request(
+                request,
+                interns.to_mut(),
+                &self.name_map,
+                self.allowed_modules.as_deref(),
+                heap,
+            );
+            let mut vm = VM::restore(snapshot, &self.module_code, heap, namespaces, interns, print);
+            result = vm.resume_compile(compiled);
+            vm_state = vm.check_compile(&result);
+        }
+    }
+
     /// Prepares the namespace namespaces for execution.
     ///
     /// Converts each `MontyObject` input to a `Value`, allocating on the heap if needed.
@@ -897,6 +1059,35 @@ impl Executor {
     }
 }
 
+/// Compiles the code for an `eval()`/`exec()` call, or interns the name for a `setattr()`
+/// call, into `interns`.
+///
+/// The source, which is kept as the cache key, and the compiled functions and names count
+/// towards the memory limit, since they're kept for the rest of the run.
+fn compile_request(
+    request: CompileRequest,
+    interns: &mut Interns,
+    name_map: &ahash::AHashMap<String, NamespaceId>,
+    allowed_modules: Option<&[String]>,
+    heap: &mut Heap<impl ResourceTracker>,
+) -> RunResult<Compiled> {
+    let (function_count, string_count) = (interns.function_count(), interns.string_count());
+    let compiled = match request {
+        CompileRequest::Eval(key) => {
+            let size = key.source.len();
+            heap.tracker_mut().on_allocate(|| size)?;
+            Compiled::Eval(compile_eval(key, interns, name_map, allowed_modules)?)
+        }
+        CompileRequest::Name(name) => {
+            interns.intern_name(&name);
+            Compiled::Name
+        }
+    };
+    heap.tracker_mut()
+        .on_allocate(|| interns.size_since(function_count, string_count))?;
+    Ok(compiled)
+}
+
 fn frame_exit_to_object(
     frame_exit_result: RunResult<FrameExit>,
     heap: &mut Heap<impl ResourceTracker>,
@@ -915,9 +1106,15 @@ fn frame_exit_to_object(
             "OS function '{function}' not implemented with standard execution"
         ))
         .into()),
+        FrameExit::MethodCall { method_name, .. } => Err(ExcType::not_implemented(format!(
+            "Method '{}' not implemented with standard execution",
+            interns.get_str(method_name)
+        ))
+        .into()),
         FrameExit::ResolveFutures(_) => {
             Err(ExcType::not_implemented("async futures not supported by standard execution.").into())
         }
+        FrameExit::Compile(_) => unreachable!("eval/exec code is compiled by Executor::compile_evals_borrowed"),
     }
 }
 
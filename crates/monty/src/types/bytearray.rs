@@ -0,0 +1,595 @@
+//! Python `bytearray` type implementation.
+//!
+//! A `bytearray` is a mutable sequence of bytes. It implements the mutation methods
+//! itself and shares every other method with `bytes` through `call_bytes_method_impl`,
+//! converting `bytes` results back to `bytearray` as CPython does.
+//!
+//! # Implemented Methods
+//! - `append(item)` - Add a byte to the end
+//! - `extend(iterable)` - Append bytes from a bytes-like object or an iterable of ints
+//! - `insert(index, item)` - Insert a byte at index
+//! - `pop([index])` - Remove and return a byte (default: last)
+//! - `remove(value)` - Remove the first occurrence of a byte
+//! - `clear()` - Remove all bytes
+//! - `reverse()` - Reverse in place
+//! - `copy()` - Return a copy
+//! - all non-mutating `bytes` methods, e.g. `find()`, `split()`, `decode()` and `hex()`
+//!
+//! Item and slice assignment, item deletion and `+=` are also supported.
+//!
+//! # Memory Tracking
+//! The buffer grows like a `Vec`, doubling its capacity when full. Each time it grows,
+//! the new capacity is reported to the resource tracker before the memory is reserved,
+//! so memory limits apply to bytearrays built up in place.
+
+use std::{borrow::Cow, fmt::Write};
+
+use ahash::AHashSet;
+
+use crate::{
+    args::ArgValues,
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{
+        Bytes, List, MontyIter, PyTrait, Slice, Type, allocate_tuple,
+        bytes::{
+            byte_from_value, bytes_from_init_arg, bytes_from_iter, bytes_fromhex, bytes_like, bytes_repr_fmt,
+            call_bytes_method_impl, get_bytes_slice,
+        },
+        list::slice_positions,
+    },
+    value::{EitherStr, Value},
+};
+
+/// Python bytearray value stored on the heap.
+///
+/// `capacity` is the buffer size reported to the resource tracker, which may differ from
+/// the capacity of the `Vec` after a snapshot round trip. Size estimates use it so the
+/// memory freed with the bytearray always matches the memory it was charged for.
+#[derive(Debug, Clone, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ByteArray {
+    bytes: Vec<u8>,
+    capacity: usize,
+}
+
+impl ByteArray {
+    /// Creates a new ByteArray from a byte vector.
+    #[must_use]
+    pub fn new(bytes: Vec<u8>) -> Self {
+        let capacity = bytes.len();
+        Self { bytes, capacity }
+    }
+
+    /// Returns a reference to the inner byte slice.
+    #[must_use]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Creates a bytearray from the `bytearray()` constructor call.
+    ///
+    /// Accepts the same arguments as `bytes()`: nothing, a count, a string, a bytes-like
+    /// object or an iterable of ints.
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let bytes = match args.get_zero_one_arg("bytearray", heap)? {
+            None => Vec::new(),
+            Some(v) => bytes_from_init_arg(v, Type::ByteArray, heap, interns)?,
+        };
+        allocate_bytearray(bytes, heap)
+    }
+
+    /// Makes room for `additional` more bytes, charging any growth to the resource tracker.
+    fn reserve(&mut self, additional: usize, heap: &mut Heap<impl ResourceTracker>) -> Result<(), ResourceError> {
+        let required = self.bytes.len().saturating_add(additional);
+        if required <= self.capacity {
+            return Ok(());
+        }
+        let new_capacity = required.max(self.capacity.saturating_mul(2));
+        heap.tracker_mut().on_allocate(|| new_capacity - self.capacity)?;
+        self.bytes.reserve_exact(new_capacity - self.bytes.len());
+        self.capacity = new_capacity;
+        Ok(())
+    }
+
+    /// Appends `bytes` to the end of the bytearray.
+    fn extend_from_slice(&mut self, bytes: &[u8], heap: &mut Heap<impl ResourceTracker>) -> Result<(), ResourceError> {
+        self.reserve(bytes.len(), heap)?;
+        self.bytes.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Replaces the `(start, stop, step)` indices of the bytearray, as returned by
+    /// `slice.indices(len)`, with `bytes`.
+    ///
+    /// Simple slices may change the length of the bytearray; extended slices must be
+    /// assigned exactly as many bytes as they select.
+    fn set_slice(&mut self, slice: &Slice, bytes: &[u8], heap: &mut Heap<impl ResourceTracker>) -> RunResult<()> {
+        let len = self.bytes.len();
+        let (start, stop, step) = slice
+            .indices(len)
+            .map_err(|()| ExcType::value_error_slice_step_zero())?;
+
+        if step == 1 {
+            // An empty slice (stop before start) inserts at start
+            let stop = stop.max(start);
+            self.reserve(bytes.len().saturating_sub(stop - start), heap)?;
+            self.bytes.splice(start..stop, bytes.iter().copied());
+            return Ok(());
+        }
+
+        let positions = slice_positions(start, stop, step, len);
+        if positions.len() != bytes.len() {
+            return Err(SimpleException::new_msg(
+                ExcType::ValueError,
+                format!(
+                    "attempt to assign bytes of size {} to extended slice of size {}",
+                    bytes.len(),
+                    positions.len()
+                ),
+            )
+            .into());
+        }
+        for (position, byte) in positions.into_iter().zip(bytes) {
+            self.bytes[position] = *byte;
+        }
+        Ok(())
+    }
+
+    /// Converts an index argument to a position in the bytearray, raising IndexError if it is out of range.
+    fn position(&self, index: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<usize> {
+        let index = index.as_index(heap, Type::ByteArray)?;
+        let len = i64::try_from(self.bytes.len()).expect("bytearray length exceeds i64::MAX");
+        let normalized = if index < 0 { index + len } else { index };
+        if normalized < 0 || normalized >= len {
+            return Err(ExcType::bytearray_index_error());
+        }
+        Ok(usize::try_from(normalized).expect("index validated non-negative"))
+    }
+}
+
+impl From<Vec<u8>> for ByteArray {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl PyTrait for ByteArray {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::ByteArray
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.capacity
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        Some(self.bytes.len())
+    }
+
+    fn py_getitem(&self, key: &Value, heap: &mut Heap<impl ResourceTracker>, _interns: &Interns) -> RunResult<Value> {
+        if let Value::Ref(id) = key
+            && let HeapData::Slice(slice) = heap.get(*id)
+        {
+            let (start, stop, step) = slice
+                .indices(self.bytes.len())
+                .map_err(|()| ExcType::value_error_slice_step_zero())?;
+            return allocate_bytearray(get_bytes_slice(&self.bytes, start, stop, step), heap);
+        }
+
+        let position = self.position(key, heap)?;
+        Ok(Value::Int(i64::from(self.bytes[position])))
+    }
+
+    fn py_setitem(
+        &mut self,
+        key: Value,
+        value: Value,
+        heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<()> {
+        let result = self
+            .position(&key, heap)
+            .and_then(|position| byte_from_value(&value, Type::ByteArray, heap).map(|byte| (position, byte)));
+        key.drop_with_heap(heap);
+        value.drop_with_heap(heap);
+        let (position, byte) = result?;
+        self.bytes[position] = byte;
+        Ok(())
+    }
+
+    fn py_delitem(&mut self, key: &Value, heap: &mut Heap<impl ResourceTracker>, _interns: &Interns) -> RunResult<()> {
+        if let Value::Ref(id) = key
+            && let HeapData::Slice(slice) = heap.get(*id)
+        {
+            let (start, stop, step) = slice
+                .indices(self.bytes.len())
+                .map_err(|()| ExcType::value_error_slice_step_zero())?;
+            if step == 1 {
+                self.bytes.drain(start..stop.max(start));
+                return Ok(());
+            }
+            let mut positions = slice_positions(start, stop, step, self.bytes.len());
+            positions.sort_unstable();
+            // Remove from the back so earlier positions stay valid
+            for position in positions.into_iter().rev() {
+                self.bytes.remove(position);
+            }
+            return Ok(());
+        }
+
+        let position = self.position(key, heap)?;
+        self.bytes.remove(position);
+        Ok(())
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self.bytes == other.bytes)
+    }
+
+    /// Bytearrays don't contain nested heap references.
+    fn py_dec_ref_ids(&mut self, _stack: &mut Vec<HeapId>) {
+        // No-op: bytearrays don't hold Value references
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        !self.bytes.is_empty()
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> std::fmt::Result {
+        f.write_str("bytearray(")?;
+        bytes_repr_fmt(&self.bytes, f)?;
+        f.write_char(')')
+    }
+
+    fn py_iadd(
+        &mut self,
+        other: Value,
+        heap: &mut Heap<impl ResourceTracker>,
+        self_id: Option<HeapId>,
+        interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        // The bytearray is out of the heap here, so read `ba += ba` and views of `ba` from `self`
+        let bytes = match &other {
+            Value::Ref(id) if Some(*id) == self_id => Some(self.bytes.clone()),
+            Value::Ref(id) => match heap.get(*id) {
+                HeapData::MemoryView(view) if Some(view.obj()) == self_id => {
+                    Some(view.bytes_from(&self.bytes).into_owned())
+                }
+                _ => bytes_like(&other, heap, interns).map(Cow::into_owned),
+            },
+            _ => bytes_like(&other, heap, interns).map(Cow::into_owned),
+        };
+        other.drop_with_heap(heap);
+        let Some(bytes) = bytes else {
+            return Ok(false);
+        };
+        self.extend_from_slice(&bytes, heap)?;
+        Ok(true)
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let Some(method) = attr.static_string() else {
+            args.drop_with_heap(heap);
+            return Err(ExcType::attribute_error(Type::ByteArray, attr.as_str(interns)));
+        };
+
+        match method {
+            StaticStrings::Append => {
+                let item = args.get_one_arg("bytearray.append", heap)?;
+                let byte = byte_from_value(&item, Type::ByteArray, heap);
+                item.drop_with_heap(heap);
+                self.extend_from_slice(&[byte?], heap)?;
+                Ok(Value::None)
+            }
+            StaticStrings::Extend => bytearray_extend(self, args, heap, interns),
+            StaticStrings::Insert => bytearray_insert(self, args, heap),
+            StaticStrings::Pop => bytearray_pop(self, args, heap),
+            StaticStrings::Remove => {
+                let item = args.get_one_arg("bytearray.remove", heap)?;
+                let byte = byte_from_value(&item, Type::ByteArray, heap);
+                item.drop_with_heap(heap);
+                let byte = byte?;
+                let Some(position) = self.bytes.iter().position(|&b| b == byte) else {
+                    return Err(SimpleException::new_msg(ExcType::ValueError, "value not found in bytearray").into());
+                };
+                self.bytes.remove(position);
+                Ok(Value::None)
+            }
+            StaticStrings::Clear => {
+                args.check_zero_args("bytearray.clear", heap)?;
+                self.bytes.clear();
+                Ok(Value::None)
+            }
+            StaticStrings::Reverse => {
+                args.check_zero_args("bytearray.reverse", heap)?;
+                self.bytes.reverse();
+                Ok(Value::None)
+            }
+            StaticStrings::Copy => {
+                args.check_zero_args("bytearray.copy", heap)?;
+                allocate_bytearray(self.bytes.clone(), heap)
+            }
+            // Everything else is shared with bytes
+            _ => {
+                let result = call_bytes_method_impl(&self.bytes, Type::ByteArray, method, args, heap, interns)?;
+                bytearray_result(result, heap)
+            }
+        }
+    }
+}
+
+/// Calls a method on the bytearray at `id`.
+///
+/// Methods shared with `bytes` run on a copy of the contents with the bytearray left in
+/// the heap, so their arguments may refer to it. The mutation methods take the bytearray
+/// out of the heap while they run, so arguments that refer to it, directly or through a
+/// memoryview (e.g. `ba.extend(ba)`), are first replaced by `bytes` copies.
+pub(crate) fn call_bytearray_method(
+    id: HeapId,
+    attr: &EitherStr,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    if let Some(method) = attr.static_string()
+        && !is_bytearray_own_method(method)
+    {
+        let HeapData::ByteArray(bytearray) = heap.get(id) else {
+            unreachable!("call_bytearray_method called on a non-bytearray")
+        };
+        let bytes = bytearray.bytes.clone();
+        let result = call_bytes_method_impl(&bytes, Type::ByteArray, method, args, heap, interns)?;
+        return bytearray_result(result, heap);
+    }
+
+    let args = match args {
+        ArgValues::One(a) => ArgValues::One(detach_arg(a, id, heap, interns)?),
+        ArgValues::Two(a, b) => match detach_arg(a, id, heap, interns) {
+            Ok(a) => match detach_arg(b, id, heap, interns) {
+                Ok(b) => ArgValues::Two(a, b),
+                Err(e) => {
+                    a.drop_with_heap(heap);
+                    return Err(e.into());
+                }
+            },
+            Err(e) => {
+                b.drop_with_heap(heap);
+                return Err(e.into());
+            }
+        },
+        args => args,
+    };
+    heap.with_entry_mut(id, |heap, data| data.py_call_attr(heap, attr, args, interns))
+}
+
+/// Returns whether `method` is implemented by `bytearray` itself rather than shared with `bytes`.
+fn is_bytearray_own_method(method: StaticStrings) -> bool {
+    matches!(
+        method,
+        StaticStrings::Append
+            | StaticStrings::Extend
+            | StaticStrings::Insert
+            | StaticStrings::Pop
+            | StaticStrings::Remove
+            | StaticStrings::Clear
+            | StaticStrings::Reverse
+            | StaticStrings::Copy
+    )
+}
+
+/// Replaces an argument that refers to the bytearray at `id` with a `bytes` copy. Consumes `value`.
+fn detach_arg(
+    value: Value,
+    id: HeapId,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> Result<Value, ResourceError> {
+    let refers_to_id = match &value {
+        Value::Ref(arg_id) => {
+            *arg_id == id || matches!(heap.get(*arg_id), HeapData::MemoryView(view) if view.obj() == id)
+        }
+        _ => false,
+    };
+    if !refers_to_id {
+        return Ok(value);
+    }
+    let bytes = bytes_like(&value, heap, interns)
+        .expect("bytearrays and memoryviews are bytes-like")
+        .into_owned();
+    value.drop_with_heap(heap);
+    Ok(Value::Ref(heap.allocate(HeapData::Bytes(Bytes::new(bytes)))?))
+}
+
+/// Implements Python's `bytearray[slice] = value`.
+///
+/// The value is read while the bytearray is still in the heap, so assigning a bytearray
+/// (or a view of it) to a slice of itself sees the original bytes. Consumes `value`.
+pub(crate) fn bytearray_set_slice(
+    id: HeapId,
+    slice: &Slice,
+    value: Value,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<()> {
+    // CPython rejects a zero step before looking at the value
+    if slice.step == Some(0) {
+        value.drop_with_heap(heap);
+        return Err(ExcType::value_error_slice_step_zero());
+    }
+    let bytes = if let Some(bytes) = bytes_like(&value, heap, interns).map(Cow::into_owned) {
+        value.drop_with_heap(heap);
+        bytes
+    } else {
+        let Ok(iter) = MontyIter::new(value, heap, interns) else {
+            return Err(ExcType::type_error(
+                "can assign only bytes, buffers, or iterables of ints in range(0, 256)",
+            ));
+        };
+        bytes_from_iter(iter, Type::ByteArray, heap, interns)?
+    };
+
+    heap.with_entry_mut(id, |heap, data| match data {
+        HeapData::ByteArray(bytearray) => bytearray.set_slice(slice, &bytes, heap),
+        _ => unreachable!("bytearray_set_slice called on a non-bytearray"),
+    })
+}
+
+/// Implements Python's `bytearray.fromhex(string)` classmethod.
+pub(crate) fn bytearray_fromhex(
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let result = bytes_fromhex(args, heap, interns)?;
+    bytearray_result(result, heap)
+}
+
+/// Implements Python's `bytearray.extend(iterable)` method.
+///
+/// Accepts a bytes-like object or an iterable of ints in `range(0, 256)`.
+fn bytearray_extend(
+    bytearray: &mut ByteArray,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let iterable = args.get_one_arg("bytearray.extend", heap)?;
+    let bytes = if let Some(bytes) = bytes_like(&iterable, heap, interns).map(Cow::into_owned) {
+        iterable.drop_with_heap(heap);
+        bytes
+    } else {
+        let iterable_type = iterable.py_type(heap);
+        let Ok(iter) = MontyIter::new(iterable, heap, interns) else {
+            return Err(ExcType::type_error(format!(
+                "can't extend bytearray with {iterable_type}"
+            )));
+        };
+        bytes_from_iter(iter, Type::ByteArray, heap, interns)?
+    };
+    bytearray.extend_from_slice(&bytes, heap)?;
+    Ok(Value::None)
+}
+
+/// Implements Python's `bytearray.insert(index, item)` method.
+///
+/// Like `list.insert()`, out of range indices insert at the start or end.
+fn bytearray_insert(
+    bytearray: &mut ByteArray,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+) -> RunResult<Value> {
+    let (index_obj, item) = args.get_two_args("bytearray.insert", heap)?;
+    let index = index_obj.as_int(heap);
+    let byte = byte_from_value(&item, Type::ByteArray, heap);
+    index_obj.drop_with_heap(heap);
+    item.drop_with_heap(heap);
+    let (index, byte) = (index?, byte?);
+
+    let len = bytearray.bytes.len();
+    let len_i64 = i64::try_from(len).expect("bytearray length exceeds i64::MAX");
+    let position = if index < 0 {
+        usize::try_from(index + len_i64).unwrap_or(0)
+    } else {
+        usize::try_from(index).map_or(len, |i| i.min(len))
+    };
+    bytearray.reserve(1, heap)?;
+    bytearray.bytes.insert(position, byte);
+    Ok(Value::None)
+}
+
+/// Implements Python's `bytearray.pop([index])` method.
+fn bytearray_pop(
+    bytearray: &mut ByteArray,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+) -> RunResult<Value> {
+    let index_arg = args.get_zero_one_arg("bytearray.pop", heap)?;
+    let index = if let Some(v) = index_arg {
+        let result = v.as_int(heap);
+        v.drop_with_heap(heap);
+        result?
+    } else {
+        -1
+    };
+
+    if bytearray.bytes.is_empty() {
+        return Err(SimpleException::new_msg(ExcType::IndexError, "pop from empty bytearray").into());
+    }
+    let len = i64::try_from(bytearray.bytes.len()).expect("bytearray length exceeds i64::MAX");
+    let normalized = if index < 0 { index + len } else { index };
+    if normalized < 0 || normalized >= len {
+        return Err(ExcType::index_error_pop_out_of_range());
+    }
+    let position = usize::try_from(normalized).expect("index validated non-negative");
+    Ok(Value::Int(i64::from(bytearray.bytes.remove(position))))
+}
+
+/// Converts the result of a shared `bytes` method to what the `bytearray` method returns.
+///
+/// `bytes` results become `bytearray`, as do the `bytes` items of the lists and tuples
+/// returned by methods like `split()` and `partition()`. Other results are unchanged.
+fn bytearray_result(value: Value, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    let Value::Ref(id) = &value else {
+        return Ok(value);
+    };
+    // The lists and tuples returned by bytes methods only hold heap-allocated bytes
+    let (item_ids, is_tuple): (Vec<HeapId>, bool) = match heap.get(*id) {
+        HeapData::Bytes(b) => {
+            let bytes = b.as_slice().to_vec();
+            value.drop_with_heap(heap);
+            return allocate_bytearray(bytes, heap);
+        }
+        HeapData::List(list) => (list.as_slice().iter().filter_map(Value::ref_id).collect(), false),
+        HeapData::Tuple(tuple) => (tuple.as_slice().iter().filter_map(Value::ref_id).collect(), true),
+        _ => return Ok(value),
+    };
+
+    let mut items = Vec::with_capacity(item_ids.len());
+    for item_id in item_ids {
+        let HeapData::Bytes(b) = heap.get(item_id) else {
+            unreachable!("bytes methods only return lists and tuples of bytes")
+        };
+        let bytes = b.as_slice().to_vec();
+        match allocate_bytearray(bytes, heap) {
+            Ok(item) => items.push(item),
+            Err(e) => {
+                items.drop_with_heap(heap);
+                value.drop_with_heap(heap);
+                return Err(e);
+            }
+        }
+    }
+    value.drop_with_heap(heap);
+    if is_tuple {
+        Ok(allocate_tuple(items.into(), heap)?)
+    } else {
+        Ok(Value::Ref(heap.allocate(HeapData::List(List::new(items)))?))
+    }
+}
+
+/// Allocates a bytearray on the heap.
+fn allocate_bytearray(bytes: Vec<u8>, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    let heap_id = heap.allocate(HeapData::ByteArray(ByteArray::new(bytes)))?;
+    Ok(Value::Ref(heap_id))
+}
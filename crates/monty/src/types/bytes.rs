@@ -65,7 +65,7 @@
 /// - `expandtabs(tabsize=8)` - Tab expansion
 /// - `translate(table[, delete])` - Character translation
 /// - `maketrans(frm, to)` - Create translation table (staticmethod)
-use std::fmt::Write;
+use std::{borrow::Cow, fmt::Write};
 
 use ahash::AHashSet;
 use smallvec::smallvec;
@@ -76,7 +76,7 @@ use crate::{
     exception_private::{ExcType, RunResult, SimpleException},
     heap::{DropWithHeap, Heap, HeapData, HeapId},
     intern::{Interns, StaticStrings, StringId},
-    resource::{DepthGuard, ResourceError, ResourceTracker},
+    resource::{DepthGuard, ResourceError, ResourceTracker, check_repeat_size},
     types::List,
     value::{EitherStr, Value},
 };
@@ -155,6 +155,105 @@ pub(crate) fn get_bytes_slice(bytes: &[u8], start: usize, stop: usize, step: i64
     result
 }
 
+/// Returns the contents of a bytes-like value: `bytes`, `bytearray` or `memoryview`.
+///
+/// Returns `None` for any other value, including `str`.
+pub(crate) fn bytes_like<'a>(
+    value: &Value,
+    heap: &'a Heap<impl ResourceTracker>,
+    interns: &'a Interns,
+) -> Option<Cow<'a, [u8]>> {
+    match value {
+        Value::InternBytes(id) => Some(Cow::Borrowed(interns.get_bytes(*id))),
+        Value::Ref(id) => match heap.get(*id) {
+            HeapData::Bytes(b) => Some(Cow::Borrowed(b.as_slice())),
+            HeapData::ByteArray(b) => Some(Cow::Borrowed(b.as_slice())),
+            HeapData::MemoryView(view) => Some(view.bytes(heap)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Converts an int item to a byte for `bytes()` and the `bytearray` mutation methods.
+///
+/// Raises `TypeError` for non-integers and `ValueError` for ints outside `range(0, 256)`.
+pub(crate) fn byte_from_value(value: &Value, target: Type, heap: &Heap<impl ResourceTracker>) -> RunResult<u8> {
+    let n = match value {
+        Value::Int(i) => *i,
+        Value::Bool(b) => i64::from(*b),
+        Value::Ref(id) if matches!(heap.get(*id), HeapData::LongInt(_)) => {
+            return Err(ExcType::value_error_byte_range(target));
+        }
+        _ => return Err(ExcType::type_error_not_integer(value.py_type(heap))),
+    };
+    u8::try_from(n).map_err(|_| ExcType::value_error_byte_range(target))
+}
+
+/// Converts the argument of a `bytes()` or `bytearray()` call to the new object's contents.
+///
+/// Accepts a bytes-like object (copied), a non-negative count (zero-filled), a `str`
+/// (encoded as UTF-8) or an iterable of ints in `range(0, 256)`. Consumes `value`.
+pub(crate) fn bytes_from_init_arg(
+    value: Value,
+    target: Type,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Vec<u8>> {
+    let direct = match &value {
+        Value::Int(n) => {
+            let Ok(size) = usize::try_from(*n) else {
+                return Err(ExcType::value_error_negative_bytes_count());
+            };
+            check_repeat_size(1, size, heap.tracker())?;
+            Some(vec![0u8; size])
+        }
+        Value::Bool(b) => Some(vec![0u8; usize::from(*b)]),
+        Value::InternString(id) => Some(interns.get_str(*id).as_bytes().to_vec()),
+        Value::Ref(id) => match heap.get(*id) {
+            HeapData::Str(s) => Some(s.as_str().as_bytes().to_vec()),
+            _ => bytes_like(&value, heap, interns).map(Cow::into_owned),
+        },
+        _ => bytes_like(&value, heap, interns).map(Cow::into_owned),
+    };
+    if let Some(bytes) = direct {
+        value.drop_with_heap(heap);
+        return Ok(bytes);
+    }
+
+    let value_type = value.py_type(heap);
+    let Ok(iter) = MontyIter::new(value, heap, interns) else {
+        return Err(ExcType::type_error_bytes_init(target, value_type));
+    };
+    bytes_from_iter(iter, target, heap, interns)
+}
+
+/// Collects an iterator of ints in `range(0, 256)` into bytes. Consumes `iter`.
+pub(crate) fn bytes_from_iter(
+    mut iter: MontyIter,
+    target: Type,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let result = loop {
+        match iter.for_next(heap, interns) {
+            Ok(Some(item)) => {
+                let byte = byte_from_value(&item, target, heap);
+                item.drop_with_heap(heap);
+                match byte {
+                    Ok(byte) => bytes.push(byte),
+                    Err(e) => break Err(e),
+                }
+            }
+            Ok(None) => break Ok(bytes),
+            Err(e) => break Err(e),
+        }
+    };
+    iter.drop_with_heap(heap);
+    result
+}
+
 /// Python bytes value stored on the heap.
 ///
 /// Wraps a `Vec<u8>` and provides Python-compatible operations.
@@ -185,53 +284,16 @@ impl Bytes {
     /// - `bytes()` with no args returns empty bytes
     /// - `bytes(int)` returns bytes of that length filled with zeros
     /// - `bytes(string)` encodes the string as UTF-8 (simplified, no encoding param)
-    /// - `bytes(bytes)` returns a copy of the bytes
+    /// - `bytes(bytes_like)` returns a copy of a `bytes`, `bytearray` or `memoryview`
+    /// - `bytes(iterable)` takes each item as an int in `range(0, 256)`
     ///
     /// Note: Full Python semantics for bytes() are more complex (encoding, errors params).
     pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
-        let value = args.get_zero_one_arg("bytes", heap)?;
-        match value {
-            None => {
-                let heap_id = heap.allocate(HeapData::Bytes(Self::new(Vec::new())))?;
-                Ok(Value::Ref(heap_id))
-            }
-            Some(v) => {
-                let result = match &v {
-                    Value::Int(n) => {
-                        if *n < 0 {
-                            return Err(ExcType::value_error_negative_bytes_count());
-                        }
-                        let size = usize::try_from(*n).expect("bytes count validated non-negative");
-                        let bytes = vec![0u8; size];
-                        heap.allocate(HeapData::Bytes(Self::new(bytes)))
-                    }
-                    Value::InternString(string_id) => {
-                        let s = interns.get_str(*string_id);
-                        heap.allocate(HeapData::Bytes(Self::new(s.as_bytes().to_vec())))
-                    }
-                    Value::InternBytes(bytes_id) => {
-                        let b = interns.get_bytes(*bytes_id);
-                        heap.allocate(HeapData::Bytes(Self::new(b.to_vec())))
-                    }
-                    Value::Ref(id) => match heap.get(*id) {
-                        HeapData::Str(s) => heap.allocate(HeapData::Bytes(Self::new(s.as_str().as_bytes().to_vec()))),
-                        HeapData::Bytes(b) => heap.allocate(HeapData::Bytes(Self::new(b.as_slice().to_vec()))),
-                        _ => {
-                            let err = ExcType::type_error_bytes_init(v.py_type(heap));
-                            v.drop_with_heap(heap);
-                            return Err(err);
-                        }
-                    },
-                    _ => {
-                        let err = ExcType::type_error_bytes_init(v.py_type(heap));
-                        v.drop_with_heap(heap);
-                        return Err(err);
-                    }
-                };
-                v.drop_with_heap(heap);
-                Ok(Value::Ref(result?))
-            }
-        }
+        let bytes = match args.get_zero_one_arg("bytes", heap)? {
+            None => Vec::new(),
+            Some(v) => bytes_from_init_arg(v, Type::Bytes, heap, interns)?,
+        };
+        allocate_bytes(bytes, heap)
     }
 }
 
@@ -338,7 +400,7 @@ impl PyTrait for Bytes {
             return Err(ExcType::attribute_error(Type::Bytes, attr.as_str(interns)));
         };
 
-        call_bytes_method_impl(self.as_slice(), method, args, heap, interns)
+        call_bytes_method_impl(self.as_slice(), Type::Bytes, method, args, heap, interns)
     }
 }
 
@@ -357,16 +419,18 @@ pub fn call_bytes_method(
         args.drop_with_heap(heap);
         return Err(ExcType::attribute_error(Type::Bytes, interns.get_str(method_id)));
     };
-    call_bytes_method_impl(bytes, method, args, heap, interns)
+    call_bytes_method_impl(bytes, Type::Bytes, method, args, heap, interns)
 }
 
 /// Calls a bytes method on a byte slice.
 ///
 /// This is the unified implementation for bytes method calls, used by both
 /// heap-allocated `Bytes` (via `py_call_attr`) and interned bytes literals
-/// (`Value::InternBytes`).
-fn call_bytes_method_impl(
+/// (`Value::InternBytes`). `bytearray` also uses it for its non-mutating methods, passing
+/// its own type as `type_`, which is the type named when the method doesn't exist.
+pub(crate) fn call_bytes_method_impl(
     bytes: &[u8],
+    type_: Type,
     method: StaticStrings,
     args: ArgValues,
     heap: &mut Heap<impl ResourceTracker>,
@@ -466,7 +530,7 @@ fn call_bytes_method_impl(
         StaticStrings::Fromhex => bytes_fromhex(args, heap, interns),
         _ => {
             args.drop_with_heap(heap);
-            Err(ExcType::attribute_error(Type::Bytes, method.into()))
+            Err(ExcType::attribute_error(type_, method.into()))
         }
     }
 }
@@ -826,13 +890,14 @@ fn extract_bytes_for_prefix_suffix(
     // Extract the method name (e.g., "startswith" from "bytes.startswith")
     let method_name = method.strip_prefix("bytes.").unwrap_or(method);
 
+    if let Some(bytes) = bytes_like(value, heap, interns) {
+        return Ok(PrefixSuffixArg::Single(bytes.into_owned()));
+    }
     match value {
-        Value::InternBytes(id) => Ok(PrefixSuffixArg::Single(interns.get_bytes(*id).to_vec())),
         Value::InternString(_) => Err(ExcType::type_error(format!(
             "{method_name} first arg must be bytes or a tuple of bytes, not str"
         ))),
         Value::Ref(id) => match heap.get(*id) {
-            HeapData::Bytes(b) => Ok(PrefixSuffixArg::Single(b.as_slice().to_vec())),
             HeapData::Str(_) => Err(ExcType::type_error(format!(
                 "{method_name} first arg must be bytes or a tuple of bytes, not str"
             ))),
@@ -871,13 +936,11 @@ fn extract_single_bytes_for_prefix_suffix(
     heap: &Heap<impl ResourceTracker>,
     interns: &Interns,
 ) -> RunResult<Vec<u8>> {
+    if let Some(bytes) = bytes_like(value, heap, interns) {
+        return Ok(bytes.into_owned());
+    }
     match value {
-        Value::InternBytes(id) => Ok(interns.get_bytes(*id).to_vec()),
         Value::InternString(_) => Err(ExcType::type_error("expected bytes, not str")),
-        Value::Ref(id) => match heap.get(*id) {
-            HeapData::Bytes(b) => Ok(b.as_slice().to_vec()),
-            _ => Err(ExcType::type_error("expected bytes")),
-        },
         _ => Err(ExcType::type_error("expected bytes")),
     }
 }
@@ -887,11 +950,12 @@ fn extract_single_bytes_for_prefix_suffix(
 /// CPython raises `TypeError: a bytes-like object is required, not 'str'` when
 /// a str is passed to bytes methods like find, count, index, startswith, endswith.
 fn extract_bytes_only(value: &Value, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Vec<u8>> {
+    if let Some(bytes) = bytes_like(value, heap, interns) {
+        return Ok(bytes.into_owned());
+    }
     match value {
-        Value::InternBytes(id) => Ok(interns.get_bytes(*id).to_vec()),
         Value::InternString(_) => Err(ExcType::type_error("a bytes-like object is required, not 'str'")),
         Value::Ref(id) => match heap.get(*id) {
-            HeapData::Bytes(b) => Ok(b.as_slice().to_vec()),
             HeapData::Str(_) => Err(ExcType::type_error("a bytes-like object is required, not 'str'")),
             _ => Err(ExcType::type_error("a bytes-like object is required")),
         },
@@ -2415,33 +2479,18 @@ fn bytes_join(
             result.extend_from_slice(separator);
         }
 
-        // Check item is bytes and extract its content
-        match &item {
-            Value::InternBytes(id) => {
-                result.extend_from_slice(interns.get_bytes(*id));
-                item.drop_with_heap(heap);
-            }
-            Value::Ref(heap_id) => {
-                if let HeapData::Bytes(b) = heap.get(*heap_id) {
-                    result.extend_from_slice(b.as_slice());
-                    item.drop_with_heap(heap);
-                } else {
-                    let t = item.py_type(heap);
-                    item.drop_with_heap(heap);
-                    iter.drop_with_heap(heap);
-                    return Err(ExcType::type_error(format!(
-                        "sequence item {index}: expected a bytes-like object, {t} found"
-                    )));
-                }
-            }
-            _ => {
-                let t = item.py_type(heap);
-                item.drop_with_heap(heap);
-                iter.drop_with_heap(heap);
-                return Err(ExcType::type_error(format!(
-                    "sequence item {index}: expected a bytes-like object, {t} found"
-                )));
-            }
+        // Check item is bytes-like and extract its content
+        if let Some(bytes) = bytes_like(&item, heap, interns) {
+            result.extend_from_slice(&bytes);
+            drop(bytes);
+            item.drop_with_heap(heap);
+        } else {
+            let t = item.py_type(heap);
+            item.drop_with_heap(heap);
+            iter.drop_with_heap(heap);
+            return Err(ExcType::type_error(format!(
+                "sequence item {index}: expected a bytes-like object, {t} found"
+            )));
         }
 
         index += 1;
@@ -0,0 +1,638 @@
+//! User-defined classes.
+//!
+//! A `class` statement creates a [`ClassObject`] holding the class attributes and methods.
+//! Calling the class creates an [`Instance`], which stores its own attributes and falls back
+//! to its class (and the class's bases) for everything else. Functions found on the class
+//! are bound to the instance as a [`BoundMethod`] when accessed as attributes, and `super()`
+//! produces a [`SuperProxy`] which starts attribute lookup after a given class.
+//!
+//! Only single inheritance is supported, and builtin types can't be subclassed.
+use std::fmt::Write;
+
+use ahash::AHashSet;
+
+use super::{Dict, PyTrait};
+use crate::{
+    exception_private::{ExcType, RunResult},
+    heap::{Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::Type,
+    value::Value,
+};
+
+/// A class object created by a `class` statement.
+///
+/// # Fields
+/// - `name`: The class name, as written in the `class` statement
+/// - `base`: The single base class, if any (`object` is treated as no base)
+/// - `attrs`: Class attributes and methods, keyed by name
+///
+/// Class objects are hashable by identity and compare equal only to themselves.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ClassObject {
+    /// The class name.
+    name: StringId,
+    /// The base class, which must also be a `ClassObject`.
+    base: Option<HeapId>,
+    /// Class attributes and methods (owned values).
+    attrs: Dict,
+}
+
+impl ClassObject {
+    /// Creates a new class object, taking ownership of `base` and `attrs`.
+    #[must_use]
+    pub fn new(name: StringId, base: Option<HeapId>, attrs: Dict) -> Self {
+        Self { name, base, attrs }
+    }
+
+    /// Returns the class name.
+    #[must_use]
+    pub fn name(&self) -> StringId {
+        self.name
+    }
+
+    /// Returns the base class, if any.
+    #[must_use]
+    pub fn base(&self) -> Option<HeapId> {
+        self.base
+    }
+
+    /// Returns a reference to the class attributes.
+    #[must_use]
+    pub fn attrs(&self) -> &Dict {
+        &self.attrs
+    }
+
+    /// Returns whether this class holds any heap references.
+    #[inline]
+    #[must_use]
+    pub fn has_refs(&self) -> bool {
+        self.base.is_some() || self.attrs.has_refs()
+    }
+
+    /// Sets a class attribute.
+    ///
+    /// The caller transfers ownership of both `name` and `value`. Returns the old
+    /// value if the attribute existed (caller must drop it).
+    pub fn set_attr(
+        &mut self,
+        name: Value,
+        value: Value,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Option<Value>> {
+        self.attrs.set(name, value, heap, interns)
+    }
+}
+
+impl PyTrait for ClassObject {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::Type
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.attrs.py_estimate_size()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        _other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        // Classes compare by identity, which is handled at the Value level
+        Ok(false)
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        if let Some(base) = self.base {
+            stack.push(base);
+        }
+        self.attrs.py_dec_ref_ids(stack);
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        write!(f, "<class '__main__.{}'>", interns.get_str(self.name))
+    }
+}
+
+/// An instance of a user-defined class.
+///
+/// Instance attributes live in `attrs`; attribute lookups that miss fall back to the
+/// class chain via [`lookup_class_attr`].
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Instance {
+    /// The instance's class (always a `ClassObject`).
+    class_id: HeapId,
+    /// Instance attributes (owned values).
+    attrs: Dict,
+}
+
+impl Instance {
+    /// Creates a new instance with no attributes, taking ownership of the `class_id` reference.
+    #[must_use]
+    pub fn new(class_id: HeapId) -> Self {
+        Self {
+            class_id,
+            attrs: Dict::new(),
+        }
+    }
+
+    /// Returns the instance's class.
+    #[must_use]
+    pub fn class_id(&self) -> HeapId {
+        self.class_id
+    }
+
+    /// Returns a reference to the instance attributes.
+    #[must_use]
+    pub fn attrs(&self) -> &Dict {
+        &self.attrs
+    }
+
+    /// Sets an instance attribute.
+    ///
+    /// The caller transfers ownership of both `name` and `value`. Returns the old
+    /// value if the attribute existed (caller must drop it).
+    pub fn set_attr(
+        &mut self,
+        name: Value,
+        value: Value,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Option<Value>> {
+        self.attrs.set(name, value, heap, interns)
+    }
+}
+
+impl PyTrait for Instance {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::Object
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.attrs.py_estimate_size()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        // `__len__` is dispatched by the VM
+        None
+    }
+
+    fn py_eq(
+        &self,
+        _other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        // Without `__eq__`, instances compare by identity, which is handled at the Value level
+        Ok(false)
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        stack.push(self.class_id);
+        self.attrs.py_dec_ref_ids(stack);
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        // `__bool__` and `__len__` are dispatched by the VM
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        write!(f, "<__main__.{} object>", class_name(self.class_id, heap, interns))
+    }
+}
+
+/// A function bound to the object it was looked up on, e.g. `obj.method`.
+///
+/// Calling a bound method calls `func` with `self_value` prepended to the arguments.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct BoundMethod {
+    /// The underlying function (owned).
+    func: Value,
+    /// The bound `self` argument (owned).
+    self_value: Value,
+}
+
+impl BoundMethod {
+    /// Creates a new bound method, taking ownership of both values.
+    #[must_use]
+    pub fn new(func: Value, self_value: Value) -> Self {
+        Self { func, self_value }
+    }
+
+    /// Returns the underlying function.
+    #[must_use]
+    pub fn func(&self) -> &Value {
+        &self.func
+    }
+
+    /// Returns the bound `self` argument.
+    #[must_use]
+    pub fn self_value(&self) -> &Value {
+        &self.self_value
+    }
+}
+
+impl PyTrait for BoundMethod {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::Method
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        // Bound methods are equal if they wrap the same function bound to the same object
+        Ok(self.self_value.is(&other.self_value) && self.func.is(&other.func))
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        self.func.py_dec_ref_ids(stack);
+        self.self_value.py_dec_ref_ids(stack);
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        heap: &Heap<impl ResourceTracker>,
+        heap_ids: &mut AHashSet<HeapId>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        f.write_str("<bound method ")?;
+        match function_name(&self.func, heap, interns) {
+            Some(name) => f.write_str(name)?,
+            None => f.write_char('?')?,
+        }
+        f.write_str(" of ")?;
+        self.self_value.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+        f.write_char('>')
+    }
+}
+
+/// The object returned by `super(cls, obj)`.
+///
+/// Attribute lookups on the proxy search the bases of `class_id` (skipping `class_id`
+/// itself), and functions found there are bound to `instance`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct SuperProxy {
+    /// The class to start searching after (owned reference).
+    class_id: HeapId,
+    /// The object methods are bound to (owned).
+    instance: Value,
+}
+
+impl SuperProxy {
+    /// Creates a new super proxy, taking ownership of both references.
+    #[must_use]
+    pub fn new(class_id: HeapId, instance: Value) -> Self {
+        Self { class_id, instance }
+    }
+
+    /// Returns the class lookups start after.
+    #[must_use]
+    pub fn class_id(&self) -> HeapId {
+        self.class_id
+    }
+
+    /// Returns the object methods are bound to.
+    #[must_use]
+    pub fn instance(&self) -> &Value {
+        &self.instance
+    }
+}
+
+impl PyTrait for SuperProxy {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::Super
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        _other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(false)
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        stack.push(self.class_id);
+        self.instance.py_dec_ref_ids(stack);
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        heap: &Heap<impl ResourceTracker>,
+        heap_ids: &mut AHashSet<HeapId>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        write!(f, "<super: <class '{}'>, ", class_name(self.class_id, heap, interns))?;
+        self.instance.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+        f.write_char('>')
+    }
+}
+
+/// Gets an attribute from a user-defined class, instance or super proxy.
+///
+/// Instance attributes take precedence over class attributes; functions found on the
+/// class are bound to the instance. Returns `Ok(None)` if `heap_id` isn't one of these
+/// objects, so the caller can fall back to the generic attribute handling.
+pub(crate) fn getattr_user_object(
+    heap_id: HeapId,
+    name_id: StringId,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Option<Value>> {
+    let name = interns.get_str(name_id);
+    match heap.get(heap_id) {
+        HeapData::Instance(inst) => {
+            let class_id = inst.class_id;
+            if let Some(value) = inst.attrs.get_by_str(name, heap, interns) {
+                return Ok(Some(inc_ref_copy(value.copy_for_extend(), heap)));
+            }
+            if name_id == StaticStrings::DunderClass {
+                heap.inc_ref(class_id);
+                return Ok(Some(Value::Ref(class_id)));
+            }
+            match lookup_class_attr(class_id, name, heap, interns) {
+                Some(value) => {
+                    heap.inc_ref(heap_id);
+                    Ok(Some(bind_method(value, Value::Ref(heap_id), heap)?))
+                }
+                None => Err(ExcType::attribute_error(class_name(class_id, heap, interns), name)),
+            }
+        }
+        HeapData::ClassObject(cls) => {
+            if name_id == StaticStrings::DunderName {
+                return Ok(Some(Value::InternString(cls.name)));
+            }
+            match lookup_class_attr(heap_id, name, heap, interns) {
+                Some(value) => Ok(Some(value)),
+                None => Err(ExcType::attribute_error_type_object(
+                    class_name(heap_id, heap, interns),
+                    name,
+                )),
+            }
+        }
+        HeapData::SuperProxy(proxy) => {
+            let instance = proxy.instance.copy_for_extend();
+            let class_id = proxy.class_id;
+            let instance = inc_ref_copy(instance, heap);
+            let base = match heap.get(class_id) {
+                HeapData::ClassObject(cls) => cls.base,
+                _ => None,
+            };
+            match base.and_then(|base| lookup_class_attr(base, name, heap, interns)) {
+                Some(value) => Ok(Some(bind_method(value, instance, heap)?)),
+                None => {
+                    instance.drop_with_heap(heap);
+                    Err(ExcType::attribute_error(Type::Super, name))
+                }
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Sets an attribute on a user-defined class or instance.
+///
+/// Takes ownership of `value`. Returns `Ok(Err(value))` (handing the value back) if
+/// `heap_id` isn't a user-defined class or instance.
+pub(crate) fn setattr_user_object(
+    heap_id: HeapId,
+    name_id: StringId,
+    value: Value,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Result<(), Value>> {
+    if !matches!(heap.get(heap_id), HeapData::Instance(_) | HeapData::ClassObject(_)) {
+        return Ok(Err(value));
+    }
+    let name_value = Value::InternString(name_id);
+    let old_value = heap.with_entry_mut(heap_id, |heap, data| match data {
+        HeapData::Instance(inst) => inst.set_attr(name_value, value, heap, interns),
+        HeapData::ClassObject(cls) => cls.set_attr(name_value, value, heap, interns),
+        _ => unreachable!("type changed during borrow"),
+    })?;
+    if let Some(old) = old_value {
+        old.drop_with_heap(heap);
+    }
+    Ok(Ok(()))
+}
+
+/// Deletes an attribute from a user-defined class or instance.
+///
+/// Returns `Ok(false)` if `heap_id` isn't a user-defined class or instance, and
+/// `AttributeError` if the object's own attributes don't include `name_id`.
+pub(crate) fn delattr_user_object(
+    heap_id: HeapId,
+    name_id: StringId,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<bool> {
+    let name_value = Value::InternString(name_id);
+    let removed = heap.with_entry_mut(heap_id, |heap, data| match data {
+        HeapData::Instance(inst) => inst.attrs.pop(&name_value, heap, interns).map(Some),
+        HeapData::ClassObject(cls) => cls.attrs.pop(&name_value, heap, interns).map(Some),
+        _ => Ok(None),
+    })?;
+    match removed {
+        None => Ok(false),
+        Some(Some((key, value))) => {
+            key.drop_with_heap(heap);
+            value.drop_with_heap(heap);
+            Ok(true)
+        }
+        Some(None) => {
+            let attr_name = interns.get_str(name_id);
+            match heap.get(heap_id) {
+                HeapData::ClassObject(cls) => Err(ExcType::attribute_error_type_object(
+                    interns.get_str(cls.name),
+                    attr_name,
+                )),
+                HeapData::Instance(inst) => Err(ExcType::attribute_error(
+                    class_name(inst.class_id, heap, interns),
+                    attr_name,
+                )),
+                _ => unreachable!("type changed during borrow"),
+            }
+        }
+    }
+}
+
+/// Binds `value` to `self_value` if it's a function, otherwise returns it unchanged.
+///
+/// Takes ownership of both values.
+pub(crate) fn bind_method(
+    value: Value,
+    self_value: Value,
+    heap: &mut Heap<impl ResourceTracker>,
+) -> Result<Value, ResourceError> {
+    if is_function(&value, heap) {
+        Ok(Value::Ref(heap.allocate(HeapData::BoundMethod(BoundMethod::new(
+            value, self_value,
+        )))?))
+    } else {
+        self_value.drop_with_heap(heap);
+        Ok(value)
+    }
+}
+
+/// Looks up `name` on a class and its bases, in method resolution order.
+///
+/// Returns a new reference to the value (the caller owns it), or `None` if no class
+/// in the chain defines the attribute.
+pub(crate) fn lookup_class_attr(
+    class_id: HeapId,
+    name: &str,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> Option<Value> {
+    let mut current = Some(class_id);
+    while let Some(id) = current {
+        let HeapData::ClassObject(class) = heap.get(id) else {
+            return None;
+        };
+        if let Some(value) = class.attrs.get_by_str(name, heap, interns) {
+            return Some(inc_ref_copy(value.copy_for_extend(), heap));
+        }
+        current = class.base;
+    }
+    None
+}
+
+/// Returns whether instances of `class_id` are unhashable because the class chain sets
+/// `__hash__` to `None`, as `build_class` does for classes defining `__eq__` but not `__hash__`.
+pub(crate) fn class_disables_hash(class_id: HeapId, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> bool {
+    let mut current = Some(class_id);
+    while let Some(id) = current {
+        let HeapData::ClassObject(class) = heap.get(id) else {
+            return false;
+        };
+        if let Some(value) = class.attrs.get_by_str("__hash__", heap, interns) {
+            return matches!(value, Value::None);
+        }
+        current = class.base;
+    }
+    false
+}
+
+/// Takes a value produced by `copy_for_extend` and increments its refcount,
+/// turning it into an owned reference.
+fn inc_ref_copy(value: Value, heap: &mut Heap<impl ResourceTracker>) -> Value {
+    if let Value::Ref(id) = value {
+        heap.inc_ref(id);
+    }
+    value
+}
+
+/// Returns whether `class_id` is `target_id` or inherits from it.
+pub(crate) fn is_subclass(class_id: HeapId, target_id: HeapId, heap: &Heap<impl ResourceTracker>) -> bool {
+    let mut current = Some(class_id);
+    while let Some(id) = current {
+        if id == target_id {
+            return true;
+        }
+        current = match heap.get(id) {
+            HeapData::ClassObject(class) => class.base,
+            _ => None,
+        };
+    }
+    false
+}
+
+/// Returns whether `value` is a Python function that should be bound when looked up
+/// through an instance, i.e. a `def` function, closure, or function with defaults.
+pub(crate) fn is_function(value: &Value, heap: &Heap<impl ResourceTracker>) -> bool {
+    match value {
+        Value::DefFunction(_) => true,
+        Value::Ref(id) => matches!(
+            heap.get(*id),
+            HeapData::Closure(_, _, _) | HeapData::FunctionDefaults(_, _)
+        ),
+        _ => false,
+    }
+}
+
+/// Returns the name of the class with the given id, for reprs and error messages.
+pub(crate) fn class_name<'a>(class_id: HeapId, heap: &Heap<impl ResourceTracker>, interns: &'a Interns) -> &'a str {
+    match heap.get(class_id) {
+        HeapData::ClassObject(class) => interns.get_str(class.name),
+        _ => "?",
+    }
+}
+
+/// Returns the name of a Python function value, if `value` is one.
+pub(crate) fn function_name<'a>(
+    value: &Value,
+    heap: &Heap<impl ResourceTracker>,
+    interns: &'a Interns,
+) -> Option<&'a str> {
+    let func_id = match value {
+        Value::DefFunction(func_id) => *func_id,
+        Value::Ref(id) => match heap.get(*id) {
+            HeapData::Closure(func_id, _, _) | HeapData::FunctionDefaults(func_id, _) => *func_id,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(interns.get_str(interns.get_function(func_id).name.name_id))
+}
@@ -0,0 +1,87 @@
+//! Python code objects, as returned by `compile()`.
+//!
+//! A code object only records the source it was compiled from: the code is compiled again,
+//! in the scope it runs in, when passed to `eval()` or `exec()`, and the compiled function is
+//! cached from then on. `compile()` still parses the source so syntax errors are raised early.
+
+use std::fmt::Write;
+
+use ahash::AHashSet;
+
+use crate::{
+    eval::EvalMode,
+    heap::{Heap, HeapId},
+    intern::Interns,
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{PyTrait, Type},
+};
+
+/// A code object created by `compile()`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct CodeObject {
+    /// The source code.
+    source: String,
+    /// Filename used in tracebacks.
+    filename: String,
+    /// Whether the source is an expression (`'eval'`) or statements (`'exec'` or `'single'`).
+    mode: EvalMode,
+}
+
+impl CodeObject {
+    pub fn new(source: String, filename: String, mode: EvalMode) -> Self {
+        Self { source, filename, mode }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    pub fn mode(&self) -> EvalMode {
+        self.mode
+    }
+}
+
+impl PyTrait for CodeObject {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::Code
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.source.len() + self.filename.len()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self.source == other.source && self.filename == other.filename && self.mode == other.mode)
+    }
+
+    fn py_dec_ref_ids(&mut self, _stack: &mut Vec<HeapId>) {}
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> std::fmt::Result {
+        write!(f, "<code object <module>, file \"{}\", line 1>", self.filename)
+    }
+}
@@ -0,0 +1,450 @@
+//! Python `complex` type implementation.
+//!
+//! Complex numbers are stored on the heap as `HeapData::Complex` to keep the `Value` enum
+//! small. Imaginary literals such as `3j` are stored in the constant pool as
+//! `Value::Imaginary` and converted to a heap-allocated `Complex` on load, like `InternLongInt`.
+//!
+//! Arithmetic follows CPython's algorithms (e.g. Smith's method for division and repeated
+//! squaring for small integer powers) so results match CPython bit for bit. Mixed arithmetic
+//! converts `int`, `bool` and `float` operands to `complex` first, as CPython does.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt::{self, Display, Write},
+    hash::{Hash, Hasher},
+};
+
+use ahash::AHashSet;
+
+use crate::{
+    args::ArgValues,
+    defer_drop,
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{AttrCallResult, PyTrait, Type},
+    value::{EitherStr, Value},
+};
+
+/// Python complex number with `f64` real and imaginary parts.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Complex {
+    pub real: f64,
+    pub imag: f64,
+}
+
+impl Complex {
+    /// Creates a new complex number from its real and imaginary parts.
+    #[must_use]
+    pub fn new(real: f64, imag: f64) -> Self {
+        Self { real, imag }
+    }
+
+    /// Allocates this complex number on the heap.
+    pub fn into_value(self, heap: &mut Heap<impl ResourceTracker>) -> Result<Value, ResourceError> {
+        Ok(Value::Ref(heap.allocate(HeapData::Complex(self))?))
+    }
+
+    /// Converts a numeric value (`bool`, `int`, `float` or `complex`) to a complex number.
+    ///
+    /// Returns `None` for non-numeric values, and for ints too large to convert to a float.
+    pub fn from_value(value: &Value, heap: &Heap<impl ResourceTracker>) -> Option<Self> {
+        match value {
+            Value::Bool(b) => Some(Self::new(f64::from(u8::from(*b)), 0.0)),
+            Value::Int(i) => Some(Self::new(*i as f64, 0.0)),
+            Value::Float(f) => Some(Self::new(*f, 0.0)),
+            Value::Ref(id) => match heap.get(*id) {
+                HeapData::Complex(c) => Some(*c),
+                HeapData::LongInt(li) => li.to_f64().map(|f| Self::new(f, 0.0)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns both operands of a binary operation as complex numbers, if at least one of
+    /// them is a `complex` and the other is a number.
+    ///
+    /// This is checked before the other numeric cases of binary operations, so it returns
+    /// early unless one of the operands is on the heap.
+    pub fn operands(lhs: &Value, rhs: &Value, heap: &Heap<impl ResourceTracker>) -> Option<(Self, Self)> {
+        if !is_complex_value(lhs, heap) && !is_complex_value(rhs, heap) {
+            return None;
+        }
+        Some((Self::from_value(lhs, heap)?, Self::from_value(rhs, heap)?))
+    }
+
+    /// Returns `self + other`.
+    #[must_use]
+    pub fn add(self, other: Self) -> Self {
+        Self::new(self.real + other.real, self.imag + other.imag)
+    }
+
+    /// Returns `self - other`.
+    #[must_use]
+    pub fn sub(self, other: Self) -> Self {
+        Self::new(self.real - other.real, self.imag - other.imag)
+    }
+
+    /// Returns `self * other`.
+    #[must_use]
+    pub fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.real * other.real - self.imag * other.imag,
+            self.real * other.imag + self.imag * other.real,
+        )
+    }
+
+    /// Returns `-self`.
+    #[must_use]
+    pub fn neg(self) -> Self {
+        Self::new(-self.real, -self.imag)
+    }
+
+    /// Returns the complex conjugate, as returned by `complex.conjugate()`.
+    #[must_use]
+    pub fn conjugate(self) -> Self {
+        Self::new(self.real, -self.imag)
+    }
+
+    /// Returns `self / other`, using Smith's method to avoid needless overflow.
+    ///
+    /// Raises `ZeroDivisionError` if `other` is zero.
+    pub fn div(self, other: Self) -> RunResult<Self> {
+        let abs_breal = other.real.abs();
+        let abs_bimag = other.imag.abs();
+        if abs_breal >= abs_bimag {
+            if abs_breal == 0.0 {
+                return Err(SimpleException::new_msg(ExcType::ZeroDivisionError, "complex division by zero").into());
+            }
+            let ratio = other.imag / other.real;
+            let denom = other.real + other.imag * ratio;
+            Ok(Self::new(
+                (self.real + self.imag * ratio) / denom,
+                (self.imag - self.real * ratio) / denom,
+            ))
+        } else if abs_bimag >= abs_breal {
+            let ratio = other.real / other.imag;
+            let denom = other.real * ratio + other.imag;
+            Ok(Self::new(
+                (self.real * ratio + self.imag) / denom,
+                (self.imag * ratio - self.real) / denom,
+            ))
+        } else {
+            // At least one of the parts of `other` is a NaN
+            Ok(Self::new(f64::NAN, f64::NAN))
+        }
+    }
+
+    /// Returns `self ** exponent`.
+    ///
+    /// Small integer exponents use repeated squaring like CPython, so e.g. `(1+2j) ** 2`
+    /// is exactly `(-3+4j)`.
+    pub fn pow(self, exponent: Self) -> RunResult<Self> {
+        let result = if exponent.real == 0.0 && exponent.imag == 0.0 {
+            Self::new(1.0, 0.0)
+        } else if self.real == 0.0 && self.imag == 0.0 {
+            if exponent.imag != 0.0 || exponent.real < 0.0 {
+                return Err(
+                    SimpleException::new_msg(ExcType::ZeroDivisionError, "0.0 to a negative or complex power").into(),
+                );
+            }
+            Self::new(0.0, 0.0)
+        } else if exponent.imag == 0.0 && exponent.real == exponent.real.floor() && exponent.real.abs() <= 100.0 {
+            #[expect(clippy::cast_possible_truncation, reason = "exponent is an integer in [-100, 100]")]
+            let n = exponent.real as i32;
+            if n >= 0 {
+                self.powu(n.unsigned_abs())
+            } else {
+                Self::new(1.0, 0.0).div(self.powu(n.unsigned_abs()))?
+            }
+        } else {
+            let vabs = self.real.hypot(self.imag);
+            let mut len = vabs.powf(exponent.real);
+            let at = self.imag.atan2(self.real);
+            let mut phase = at * exponent.real;
+            if exponent.imag != 0.0 {
+                len /= (at * exponent.imag).exp();
+                phase += exponent.imag * vabs.ln();
+            }
+            Self::new(len * phase.cos(), len * phase.sin())
+        };
+        if self.is_finite() && exponent.is_finite() && !result.is_finite() {
+            return Err(SimpleException::new_msg(ExcType::OverflowError, "complex exponentiation").into());
+        }
+        Ok(result)
+    }
+
+    /// Raises `self` to a non-negative integer power by repeated squaring.
+    fn powu(self, n: u32) -> Self {
+        let mut result = Self::new(1.0, 0.0);
+        let mut power = self;
+        let mut mask = 1;
+        while mask > 0 && n >= mask {
+            if n & mask != 0 {
+                result = result.mul(power);
+            }
+            mask <<= 1;
+            power = power.mul(power);
+        }
+        result
+    }
+
+    /// Returns the magnitude of the complex number, as returned by `abs()`.
+    ///
+    /// Raises `OverflowError` if the magnitude of a finite number doesn't fit in a float.
+    pub fn abs(self) -> RunResult<f64> {
+        let result = self.real.hypot(self.imag);
+        if result.is_infinite() && self.is_finite() {
+            return Err(SimpleException::new_msg(ExcType::OverflowError, "absolute value too large").into());
+        }
+        Ok(result)
+    }
+
+    /// Returns whether both parts are finite.
+    fn is_finite(self) -> bool {
+        self.real.is_finite() && self.imag.is_finite()
+    }
+
+    /// Computes a hash consistent with float hashing.
+    ///
+    /// Complex numbers with a zero imaginary part hash like the equal float, so that
+    /// `complex(2.5, 0)` and `2.5` find the same dict entry.
+    pub fn hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        if self.imag == 0.0 {
+            std::mem::discriminant(&Value::Float(0.0)).hash(&mut hasher);
+            self.real.to_bits().hash(&mut hasher);
+        } else {
+            self.real.to_bits().hash(&mut hasher);
+            self.imag.to_bits().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Creates a complex number from the `complex()` constructor call.
+    ///
+    /// Supports:
+    /// - `complex()` - returns `0j`
+    /// - `complex(string)` - parses strings like `"1+2j"`, `"-3.5j"` or `"(1e3-j)"`
+    /// - `complex(real, imag=0)` - where each argument is a number (including a complex),
+    ///   returning `real + imag*1j`
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let (real, imag) = args.get_zero_one_two_args("complex", heap)?;
+        defer_drop!(real, heap);
+        defer_drop!(imag, heap);
+
+        let Some(real) = real else {
+            return Ok(Self::new(0.0, 0.0).into_value(heap)?);
+        };
+        if let Some(s) = real.as_either_str(heap) {
+            if imag.is_some() {
+                return Err(ExcType::type_error(
+                    "complex() can't take second arg if first is a string",
+                ));
+            }
+            let parsed = parse_complex(s.as_str(interns))
+                .ok_or_else(|| SimpleException::new_msg(ExcType::ValueError, "complex() arg is a malformed string"))?;
+            return Ok(parsed.into_value(heap)?);
+        }
+
+        let Some(mut result) = Self::from_value(real, heap) else {
+            return Err(ExcType::type_error(format!(
+                "complex() first argument must be a string or a number, not '{}'",
+                real.py_type(heap)
+            )));
+        };
+        if let Some(imag) = imag {
+            if imag.as_either_str(heap).is_some() {
+                return Err(ExcType::type_error("complex() second arg can't be a string"));
+            }
+            let Some(other) = Self::from_value(imag, heap) else {
+                return Err(ExcType::type_error(format!(
+                    "complex() second argument must be a number, not '{}'",
+                    imag.py_type(heap)
+                )));
+            };
+            // Like CPython, only add the parts that came from a complex argument, so that
+            // e.g. `complex(1, -0.0)` keeps the sign of its imaginary part
+            if is_complex_value(imag, heap) {
+                result.real -= other.imag;
+            }
+            if is_complex_value(real, heap) {
+                result.imag += other.real;
+            } else {
+                result.imag = other.real;
+            }
+        }
+        Ok(result.into_value(heap)?)
+    }
+}
+
+/// Returns whether `value` is a heap-allocated `complex`.
+fn is_complex_value(value: &Value, heap: &Heap<impl ResourceTracker>) -> bool {
+    matches!(value, Value::Ref(id) if matches!(heap.get(*id), HeapData::Complex(_)))
+}
+
+/// Parses the string argument of `complex()`, returning `None` if it's malformed.
+///
+/// Accepts surrounding whitespace and parentheses, a real part, an imaginary part ending
+/// in `j` (or `J`) or both, e.g. `"1"`, `"2j"`, `"-j"`, `"1.5-2e3j"`, `" (inf+nanj) "`.
+fn parse_complex(s: &str) -> Option<Complex> {
+    let mut s = s.trim();
+    if let Some(inner) = s.strip_prefix('(') {
+        s = inner.strip_suffix(')')?.trim();
+    }
+    let Some(body) = s.strip_suffix(['j', 'J']) else {
+        return Some(Complex::new(parse_float_part(s)?, 0.0));
+    };
+
+    // The imaginary part starts at the last sign which isn't part of an exponent
+    let bytes = body.as_bytes();
+    let split = (1..bytes.len())
+        .rev()
+        .find(|&i| matches!(bytes[i], b'+' | b'-') && !matches!(bytes[i - 1], b'e' | b'E'));
+    let (real, imag) = match split {
+        Some(i) => (parse_float_part(&body[..i])?, &body[i..]),
+        None => (0.0, body),
+    };
+    let imag = match imag {
+        "" | "+" => 1.0,
+        "-" => -1.0,
+        _ => parse_float_part(imag)?,
+    };
+    Some(Complex::new(real, imag))
+}
+
+/// Parses one part of a complex string, rejecting empty strings and whitespace.
+fn parse_float_part(s: &str) -> Option<f64> {
+    if s.is_empty() || s.contains(char::is_whitespace) {
+        None
+    } else {
+        s.parse().ok()
+    }
+}
+
+/// Formats one part of a complex number like CPython's `repr()` of a float, but without
+/// adding `.0` to integral values, e.g. `1`, `2.5`, `1e+20`, `inf`, `nan`.
+fn fmt_part(f: &mut impl Write, value: f64) -> fmt::Result {
+    if value.is_nan() {
+        return f.write_str("nan");
+    }
+    if value.is_infinite() {
+        return f.write_str(if value < 0.0 { "-inf" } else { "inf" });
+    }
+    let abs = value.abs();
+    if abs != 0.0 && !(1e-4..1e16).contains(&abs) {
+        // Scientific notation with an explicitly signed exponent of at least two digits
+        let formatted = format!("{value:e}");
+        let (mantissa, exp) = formatted.split_once('e').expect("exponent formatting contains 'e'");
+        let (sign, digits) = match exp.strip_prefix('-') {
+            Some(digits) => ('-', digits),
+            None => ('+', exp),
+        };
+        write!(f, "{mantissa}e{sign}{digits:0>2}")
+    } else {
+        write!(f, "{value}")
+    }
+}
+
+impl Display for Complex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.real == 0.0 && self.real.is_sign_positive() {
+            fmt_part(f, self.imag)?;
+            return f.write_char('j');
+        }
+        f.write_char('(')?;
+        fmt_part(f, self.real)?;
+        if self.imag.is_sign_negative() && !self.imag.is_nan() {
+            f.write_char('-')?;
+        } else {
+            f.write_char('+')?;
+        }
+        fmt_part(f, self.imag.abs())?;
+        f.write_str("j)")
+    }
+}
+
+impl PyTrait for Complex {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::Complex
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self == other)
+    }
+
+    fn py_dec_ref_ids(&mut self, _stack: &mut Vec<HeapId>) {
+        // Complex numbers don't contain heap references
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        self.real != 0.0 || self.imag != 0.0
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> fmt::Result {
+        write!(f, "{self}")
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        if attr.static_string() == Some(StaticStrings::Conjugate) {
+            args.check_zero_args("complex.conjugate", heap)?;
+            Ok(self.conjugate().into_value(heap)?)
+        } else {
+            args.drop_with_heap(heap);
+            Err(ExcType::attribute_error(Type::Complex, attr.as_str(interns)))
+        }
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::Real) => Ok(Some(AttrCallResult::Value(Value::Float(self.real)))),
+            Some(StaticStrings::Imag) => Ok(Some(AttrCallResult::Value(Value::Float(self.imag)))),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Raises a negative `float` to a non-integral power, which gives a `complex` result.
+///
+/// Returns `None` if `base ** exponent` is a real number, to let the caller compute it.
+pub(crate) fn negative_float_pow(base: f64, exponent: f64) -> RunResult<Option<Complex>> {
+    if base < 0.0 && exponent.is_finite() && exponent != exponent.floor() {
+        Complex::new(base, 0.0).pow(Complex::new(exponent, 0.0)).map(Some)
+    } else {
+        Ok(None)
+    }
+}
@@ -0,0 +1,595 @@
+use std::{cmp::Ordering, fmt::Write};
+
+use ahash::AHashSet;
+use smallvec::smallvec;
+
+use super::{Dict, List, MontyIter, PyTrait, allocate_tuple, dict::is_dict_method};
+use crate::{
+    args::{ArgValues, KwargsValues},
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    expressions::Operator,
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::Type,
+    value::{EitherStr, Value},
+};
+
+/// Python `collections.Counter` type, a dict that maps elements to their counts.
+///
+/// # Implemented Methods
+/// - `most_common([n])` - Pairs of `(element, count)`, most common first
+/// - `elements()` - Iterator over the elements, each repeated by its count
+/// - `total()` - Sum of all counts
+/// - `update([iterable-or-mapping], **kwargs)` / `subtract(...)` - Add or subtract counts
+/// - All other `dict` methods, except `fromkeys()`
+///
+/// Looking up a missing element returns `0` instead of raising `KeyError`. The `+`, `-`,
+/// `|` and `&` operators between two counters return a new counter keeping only positive
+/// counts; augmented assignment (`c += d`) rebinds the name to the new counter rather than
+/// mutating `c` in place.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Counter {
+    dict: Dict,
+}
+
+impl Counter {
+    /// Creates a new counter, taking ownership of `dict`.
+    #[must_use]
+    pub fn new(dict: Dict) -> Self {
+        Self { dict }
+    }
+
+    /// Returns the underlying dict.
+    #[must_use]
+    pub fn dict(&self) -> &Dict {
+        &self.dict
+    }
+
+    /// Creates a counter from the `Counter()` constructor call.
+    ///
+    /// Accepts an optional iterable or mapping plus keyword arguments, like `Counter.update()`.
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let (mut pos, kwargs) = args.into_parts();
+        if pos.len() > 1 {
+            let given = pos.len() + 1;
+            pos.drop_with_heap(heap);
+            kwargs.drop_with_heap(heap);
+            return Err(too_many_args_error("__init__", given));
+        }
+
+        let mut counter = Self::new(Dict::new());
+        if let Err(err) = counter.update(pos.next(), kwargs, false, heap, interns) {
+            counter.dict.drop_with_heap(heap);
+            return Err(err);
+        }
+        Ok(Value::Ref(heap.allocate(HeapData::Counter(counter))?))
+    }
+
+    /// Adds (or, if `subtract` is true, subtracts) counts from `other` and `kwargs`.
+    ///
+    /// `other` may be a mapping of elements to counts or an iterable of elements, each of
+    /// which counts once. Takes ownership of `other` and `kwargs`.
+    fn update(
+        &mut self,
+        other: Option<Value>,
+        kwargs: KwargsValues,
+        subtract: bool,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        let mut pairs: Vec<(Value, Value)> = Vec::new();
+        if let Some(other) = other {
+            if let Value::Ref(id) = &other
+                && let Some(dict) = heap.get(*id).as_dict()
+            {
+                pairs = dict
+                    .iter()
+                    .map(|(k, v)| (k.copy_for_extend(), v.copy_for_extend()))
+                    .collect();
+                for (k, v) in &pairs {
+                    if let Value::Ref(key_id) = k {
+                        heap.inc_ref(*key_id);
+                    }
+                    if let Value::Ref(value_id) = v {
+                        heap.inc_ref(*value_id);
+                    }
+                }
+                other.drop_with_heap(heap);
+            } else {
+                let mut iter = match MontyIter::new(other, heap, interns) {
+                    Ok(iter) => iter,
+                    Err(err) => {
+                        kwargs.drop_with_heap(heap);
+                        return Err(err);
+                    }
+                };
+                let items: RunResult<Vec<Value>> = iter.collect(heap, interns);
+                iter.drop_with_heap(heap);
+                match items {
+                    Ok(items) => pairs.extend(items.into_iter().map(|item| (item, Value::Int(1)))),
+                    Err(err) => {
+                        kwargs.drop_with_heap(heap);
+                        return Err(err);
+                    }
+                }
+            }
+        }
+        pairs.extend(kwargs);
+
+        let mut pairs = pairs.into_iter();
+        while let Some((key, count)) = pairs.next() {
+            if let Err(err) = self.add_count(key, count, subtract, heap, interns) {
+                for (key, count) in pairs {
+                    key.drop_with_heap(heap);
+                    count.drop_with_heap(heap);
+                }
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds (or subtracts) `count` to the count of `key`. Takes ownership of both.
+    fn add_count(
+        &mut self,
+        key: Value,
+        count: Value,
+        subtract: bool,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        let zero = Value::Int(0);
+        let new_count = match self.dict.get(&key, heap, interns) {
+            Ok(current) => {
+                let current = current.unwrap_or(&zero);
+                if subtract {
+                    sub_counts(current, &count, heap)
+                } else {
+                    add_counts(current, &count, heap, interns)
+                }
+            }
+            Err(err) => Err(err),
+        };
+        count.drop_with_heap(heap);
+        match new_count {
+            Ok(new_count) => {
+                if let Some(old) = self.dict.set(key, new_count, heap, interns)? {
+                    old.drop_with_heap(heap);
+                }
+                Ok(())
+            }
+            Err(err) => {
+                key.drop_with_heap(heap);
+                Err(err)
+            }
+        }
+    }
+
+    /// Returns the entry indices ordered by descending count, keeping insertion order for ties.
+    fn most_common_order(&self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Vec<usize>> {
+        let counts: Vec<&Value> = self.dict.iter().map(|(_, count)| count).collect();
+        let mut indices: Vec<usize> = (0..counts.len()).collect();
+        let mut sort_error: Option<RunError> = None;
+        indices.sort_by(|&a, &b| {
+            if sort_error.is_some() {
+                return Ordering::Equal;
+            }
+            match compare_counts(counts[b], counts[a], "<", heap, interns) {
+                Ok(ord) => ord,
+                Err(err) => {
+                    sort_error = Some(err);
+                    Ordering::Equal
+                }
+            }
+        });
+        match sort_error {
+            Some(err) => Err(err),
+            None => Ok(indices),
+        }
+    }
+
+    /// Implements `Counter.most_common([n])`, returning a list of `(element, count)` tuples.
+    fn most_common(
+        &self,
+        args: ArgValues,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let n = args.get_zero_one_arg("Counter.most_common", heap)?;
+        let limit = match n {
+            None | Some(Value::None) => None,
+            Some(n) => {
+                let result = n.as_int(heap);
+                n.drop_with_heap(heap);
+                Some(usize::try_from(result?).unwrap_or(0))
+            }
+        };
+
+        let mut order = self.most_common_order(heap, interns)?;
+        if let Some(limit) = limit {
+            order.truncate(limit);
+        }
+        let entries: Vec<(&Value, &Value)> = self.dict.iter().collect();
+        let mut items = Vec::with_capacity(order.len());
+        for index in order {
+            let (key, count) = entries[index];
+            let pair = smallvec![key.clone_with_heap(heap), count.clone_with_heap(heap)];
+            items.push(allocate_tuple(pair, heap)?);
+        }
+        Ok(Value::Ref(heap.allocate(HeapData::List(List::new(items)))?))
+    }
+
+    /// Implements `Counter.elements()`, returning an iterator over the elements.
+    ///
+    /// Each element is repeated as many times as its count; elements with a count below
+    /// one are skipped.
+    fn elements(&self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let mut items = Vec::new();
+        for (key, count) in &self.dict {
+            let count = match count.as_int(heap) {
+                Ok(count) => count,
+                Err(err) => {
+                    items.drop_with_heap(heap);
+                    return Err(err);
+                }
+            };
+            for _ in 0..count {
+                items.push(key.clone_with_heap(heap));
+            }
+        }
+        let list = Value::Ref(heap.allocate(HeapData::List(List::new(items)))?);
+        MontyIter::iterator_for(list, heap, interns)
+    }
+
+    /// Implements `Counter.total()`, the sum of all counts.
+    fn total(&self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let mut total = Value::Int(0);
+        for (_, count) in &self.dict {
+            let result = add_counts(&total, count, heap, interns);
+            total.drop_with_heap(heap);
+            total = result?;
+        }
+        Ok(total)
+    }
+}
+
+impl PyTrait for Counter {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::Counter
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        self.dict.py_estimate_size()
+    }
+
+    fn py_len(&self, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> Option<usize> {
+        self.dict.py_len(heap, interns)
+    }
+
+    /// Counters compare equal if all counts are equal, treating missing elements as zero.
+    fn py_eq(
+        &self,
+        other: &Self,
+        heap: &mut Heap<impl ResourceTracker>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        let zero = Value::Int(0);
+        guard.increase_err()?;
+        for (left, right) in [(&self.dict, &other.dict), (&other.dict, &self.dict)] {
+            for (key, count) in left {
+                let other_count = right.get(key, heap, interns).ok().flatten().unwrap_or(&zero);
+                if !count.py_eq(other_count, heap, guard, interns)? {
+                    guard.decrease();
+                    return Ok(false);
+                }
+            }
+        }
+        guard.decrease();
+        Ok(true)
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        self.dict.py_dec_ref_ids(stack);
+    }
+
+    fn py_bool(&self, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> bool {
+        self.dict.py_bool(heap, interns)
+    }
+
+    /// Formats as `Counter({...})` with the most common elements first.
+    ///
+    /// Only numeric counts can be ordered without mutable heap access; if any count isn't
+    /// a number, the elements are shown in insertion order.
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        heap: &Heap<impl ResourceTracker>,
+        heap_ids: &mut AHashSet<HeapId>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        if self.dict.is_empty() {
+            return f.write_str("Counter()");
+        }
+        if !guard.increase() {
+            return f.write_str("Counter({...})");
+        }
+
+        let mut entries: Vec<(&Value, &Value)> = self.dict.iter().collect();
+        let numeric = entries
+            .iter()
+            .all(|(_, count)| matches!(count, Value::Int(_) | Value::Float(_) | Value::Bool(_)));
+        if numeric {
+            entries.sort_by(|(_, a), (_, b)| numeric_count(b).total_cmp(&numeric_count(a)));
+        }
+
+        f.write_str("Counter({")?;
+        let mut first = true;
+        for (key, count) in entries {
+            if !first {
+                f.write_str(", ")?;
+            }
+            first = false;
+            key.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+            f.write_str(": ")?;
+            count.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+        }
+        f.write_str("})")?;
+
+        guard.decrease();
+        Ok(())
+    }
+
+    /// Returns the count for `key`, `0` if the key is missing.
+    fn py_getitem(&self, key: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        match self.dict.get(key, heap, interns)? {
+            Some(count) => Ok(count.clone_with_heap(heap)),
+            None => Ok(Value::Int(0)),
+        }
+    }
+
+    fn py_setitem(
+        &mut self,
+        key: Value,
+        value: Value,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        self.dict.py_setitem(key, value, heap, interns)
+    }
+
+    /// Deletes `key`; unlike a dict, deleting a missing key is not an error.
+    fn py_delitem(&mut self, key: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<()> {
+        if let Some((old_key, old_value)) = self.dict.pop(key, heap, interns)? {
+            old_key.drop_with_heap(heap);
+            old_value.drop_with_heap(heap);
+        }
+        Ok(())
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        match attr.static_string() {
+            Some(StaticStrings::MostCommon) => self.most_common(args, heap, interns),
+            Some(StaticStrings::Elements) => {
+                args.check_zero_args("Counter.elements", heap)?;
+                self.elements(heap, interns)
+            }
+            Some(StaticStrings::Total) => {
+                args.check_zero_args("Counter.total", heap)?;
+                self.total(heap, interns)
+            }
+            Some(method @ (StaticStrings::Update | StaticStrings::Subtract)) => {
+                let (mut pos, kwargs) = args.into_parts();
+                if pos.len() > 1 {
+                    let given = pos.len() + 1;
+                    pos.drop_with_heap(heap);
+                    kwargs.drop_with_heap(heap);
+                    return Err(too_many_args_error(attr.as_str(interns), given));
+                }
+                let subtract = method == StaticStrings::Subtract;
+                self.update(pos.next(), kwargs, subtract, heap, interns)?;
+                Ok(Value::None)
+            }
+            Some(StaticStrings::Copy) => {
+                args.check_zero_args("Counter.copy", heap)?;
+                let copy = Self::new(self.dict.clone_with_heap(heap));
+                Ok(Value::Ref(heap.allocate(HeapData::Counter(copy))?))
+            }
+            Some(StaticStrings::Fromkeys) => counter_fromkeys(args, heap),
+            Some(method) if is_dict_method(method) => self.dict.py_call_attr(heap, attr, args, interns),
+            _ => {
+                args.drop_with_heap(heap);
+                Err(ExcType::attribute_error(Type::Counter, attr.as_str(interns)))
+            }
+        }
+    }
+}
+
+/// Implements `Counter.fromkeys()`, which CPython leaves undefined.
+pub(crate) fn counter_fromkeys(args: ArgValues, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    args.drop_with_heap(heap);
+    Err(SimpleException::new_msg(
+        ExcType::NotImplementedError,
+        "Counter.fromkeys() is undefined.  Use Counter(iterable) instead.",
+    )
+    .into())
+}
+
+/// Creates the `TypeError` for passing more than one positional argument to `Counter.{method}()`.
+///
+/// Like CPython, `given` counts `self`.
+fn too_many_args_error(method: &str, given: usize) -> RunError {
+    ExcType::type_error(format!(
+        "Counter.{method}() takes from 1 to 2 positional arguments but {given} were given"
+    ))
+}
+
+/// Implements the `+`, `-`, `|` and `&` operators between two counters.
+///
+/// Returns `Ok(None)` if either operand isn't a counter or the operator isn't supported,
+/// so the caller can try other types or raise `TypeError`. Like CPython, the result only
+/// keeps elements with a positive count.
+pub(crate) fn binary_op(
+    lhs: &Value,
+    rhs: &Value,
+    op: Operator,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Option<Value>> {
+    let (Value::Ref(lhs_id), Value::Ref(rhs_id)) = (lhs, rhs) else {
+        return Ok(None);
+    };
+    if !matches!(op, Operator::Add | Operator::Sub | Operator::BitOr | Operator::BitAnd)
+        || !matches!(heap.get(*lhs_id), HeapData::Counter(_))
+        || !matches!(heap.get(*rhs_id), HeapData::Counter(_))
+    {
+        return Ok(None);
+    }
+
+    let dict = heap.with_two(*lhs_id, *rhs_id, |heap, left, right| {
+        let (HeapData::Counter(left), HeapData::Counter(right)) = (left, right) else {
+            return Err(RunError::internal("expected counters in counter binary_op"));
+        };
+        let mut result = Dict::new();
+        if let Err(err) = combine_counts(&mut result, &left.dict, &right.dict, op, heap, interns) {
+            result.drop_with_heap(heap);
+            return Err(err);
+        }
+        Ok(result)
+    })?;
+    Ok(Some(Value::Ref(heap.allocate(HeapData::Counter(Counter::new(dict)))?)))
+}
+
+/// Fills `result` with the positive counts of `left <op> right`, following CPython's algorithm.
+fn combine_counts(
+    result: &mut Dict,
+    left: &Dict,
+    right: &Dict,
+    op: Operator,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<()> {
+    let zero = Value::Int(0);
+    for (key, count) in left {
+        let other_count = right.get(key, heap, interns)?.unwrap_or(&zero);
+        let new_count = match op {
+            Operator::Add => add_counts(count, other_count, heap, interns)?,
+            Operator::Sub => sub_counts(count, other_count, heap)?,
+            Operator::BitOr => {
+                let larger = if compare_counts(count, other_count, "<", heap, interns)?.is_lt() {
+                    other_count
+                } else {
+                    count
+                };
+                larger.clone_with_heap(heap)
+            }
+            _ => {
+                let smaller = if compare_counts(count, other_count, "<", heap, interns)?.is_lt() {
+                    count
+                } else {
+                    other_count
+                };
+                smaller.clone_with_heap(heap)
+            }
+        };
+        insert_if_positive(result, key, new_count, heap, interns)?;
+    }
+
+    if matches!(op, Operator::BitAnd) {
+        return Ok(());
+    }
+    for (key, count) in right {
+        if left.get(key, heap, interns)?.is_some() {
+            continue;
+        }
+        if matches!(op, Operator::Sub) {
+            if compare_counts(count, &zero, "<", heap, interns)?.is_lt() {
+                let new_count = sub_counts(&zero, count, heap)?;
+                insert_if_positive(result, key, new_count, heap, interns)?;
+            }
+        } else {
+            insert_if_positive(result, key, count.clone_with_heap(heap), heap, interns)?;
+        }
+    }
+    Ok(())
+}
+
+/// Inserts a copy of `key` with `count` into `result` if the count is positive. Takes
+/// ownership of `count`.
+fn insert_if_positive(
+    result: &mut Dict,
+    key: &Value,
+    count: Value,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<()> {
+    match compare_counts(&count, &Value::Int(0), ">", heap, interns) {
+        Ok(Ordering::Greater) => {
+            if let Some(old) = result.set(key.clone_with_heap(heap), count, heap, interns)? {
+                old.drop_with_heap(heap);
+            }
+            Ok(())
+        }
+        Ok(_) => {
+            count.drop_with_heap(heap);
+            Ok(())
+        }
+        Err(err) => {
+            count.drop_with_heap(heap);
+            Err(err)
+        }
+    }
+}
+
+/// Returns `a + b`, raising `TypeError` if the counts can't be added.
+fn add_counts(a: &Value, b: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+    match a.py_add(b, heap, interns)? {
+        Some(value) => Ok(value),
+        None => Err(ExcType::binary_type_error("+", a.py_type(heap), b.py_type(heap))),
+    }
+}
+
+/// Returns `a - b`, raising `TypeError` if the counts can't be subtracted.
+fn sub_counts(a: &Value, b: &Value, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    match a.py_sub(b, heap)? {
+        Some(value) => Ok(value),
+        None => Err(ExcType::binary_type_error("-", a.py_type(heap), b.py_type(heap))),
+    }
+}
+
+/// Compares two counts, raising `TypeError` (mentioning `op`) if they can't be ordered.
+fn compare_counts(
+    a: &Value,
+    b: &Value,
+    op: &str,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Ordering> {
+    let mut guard = DepthGuard::default();
+    match a.py_cmp(b, heap, &mut guard, interns)? {
+        Some(ordering) => Ok(ordering),
+        None => Err(ExcType::type_error(format!(
+            "'{op}' not supported between instances of '{}' and '{}'",
+            a.py_type(heap),
+            b.py_type(heap)
+        ))),
+    }
+}
+
+/// Returns a numeric count as a float for ordering the repr.
+fn numeric_count(count: &Value) -> f64 {
+    match count {
+        Value::Int(i) => *i as f64,
+        Value::Float(f) => *f,
+        Value::Bool(b) => f64::from(u8::from(*b)),
+        _ => 0.0,
+    }
+}
@@ -2,13 +2,13 @@ use std::fmt::Write;
 
 use ahash::AHashSet;
 
-use super::{Dict, PyTrait};
+use super::{Dict, PyTrait, str::allocate_string};
 use crate::{
     args::ArgValues,
     defer_drop,
     exception_private::{ExcType, RunResult},
     heap::{Heap, HeapId},
-    intern::{Interns, StringId},
+    intern::{Interns, StaticStrings, StringId},
     resource::{DepthGuard, ResourceError, ResourceTracker},
     types::{AttrCallResult, Type},
     value::{EitherStr, Value},
@@ -21,6 +21,7 @@ use crate::{
 ///
 /// # Fields
 /// - `name`: The class name (e.g., "Point", "User")
+/// - `instance_id`: Host identifier of the original instance, if the host tracks it
 /// - `field_names`: Declared field names in definition order (used for repr)
 /// - `attrs`: All attributes including declared fields and dynamically added ones
 /// - `methods`: Set of method names that should trigger external calls
@@ -47,6 +48,9 @@ pub(crate) struct Dataclass {
     name: EitherStr,
     /// Identifier of the type, from `id(type(dc))` in python.
     type_id: u64,
+    /// Identifier of the host instance this value was converted from, passed back to the host
+    /// so method calls reach the same object.
+    instance_id: Option<u64>,
     /// Declared field names in definition order (for repr and hashing)
     field_names: Vec<String>,
     /// All attributes (both declared fields and dynamically added)
@@ -63,6 +67,7 @@ impl Dataclass {
     /// # Arguments
     /// * `name` - The class name
     /// * `type_id` - The type ID of the dataclass
+    /// * `instance_id` - The host's ID of the original instance, if any
     /// * `field_names` - Declared field names in definition order
     /// * `attrs` - Dict of attribute name -> value pairs (ownership transferred)
     /// * `methods` - Set of method names that trigger external calls
@@ -71,6 +76,7 @@ impl Dataclass {
     pub fn new(
         name: impl Into<EitherStr>,
         type_id: u64,
+        instance_id: Option<u64>,
         field_names: Vec<String>,
         attrs: Dict,
         methods: AHashSet<String>,
@@ -79,6 +85,7 @@ impl Dataclass {
         Self {
             name: name.into(),
             type_id,
+            instance_id,
             field_names,
             attrs,
             methods,
@@ -98,6 +105,12 @@ impl Dataclass {
         self.type_id
     }
 
+    /// Returns the host's ID of the instance this dataclass was converted from.
+    #[must_use]
+    pub fn instance_id(&self) -> Option<u64> {
+        self.instance_id
+    }
+
     /// Returns a reference to the declared field names.
     #[must_use]
     pub fn field_names(&self) -> &[String] {
@@ -125,6 +138,16 @@ impl Dataclass {
         &self.attrs
     }
 
+    /// Returns the class of this instance, as returned by `type(dc)`.
+    #[must_use]
+    pub fn class(&self) -> DataclassType {
+        DataclassType {
+            name: self.name.clone(),
+            type_id: self.type_id,
+            field_names: self.field_names.clone(),
+        }
+    }
+
     /// Returns whether this dataclass instance is frozen (immutable).
     #[must_use]
     pub fn is_frozen(&self) -> bool {
@@ -159,6 +182,30 @@ impl Dataclass {
         self.attrs.set(name, value, heap, interns)
     }
 
+    /// Deletes an attribute.
+    ///
+    /// Returns `FrozenInstanceError` if the dataclass is frozen, and `AttributeError`
+    /// if the attribute doesn't exist.
+    pub fn del_attr(
+        &mut self,
+        name_id: StringId,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        let attr_name = interns.get_str(name_id);
+        if self.frozen {
+            return Err(ExcType::frozen_instance_delete_error(attr_name));
+        }
+        match self.attrs.pop(&Value::InternString(name_id), heap, interns)? {
+            Some((key, value)) => {
+                key.drop_with_heap(heap);
+                value.drop_with_heap(heap);
+                Ok(())
+            }
+            None => Err(ExcType::attribute_error(self.name(interns), attr_name)),
+        }
+    }
+
     /// Computes the hash for this dataclass if it's frozen.
     ///
     /// Returns Some(hash) for frozen (immutable) dataclasses, None for mutable ones.
@@ -283,8 +330,8 @@ impl PyTrait for Dataclass {
         defer_drop!(args, heap);
 
         if self.methods.contains(method_name) {
-            // TODO: Integrate with external call system
-            // For now return an error indicating this needs implementation
+            // The VM turns calls to these methods into `FrameExit::MethodCall` for the host,
+            // we only get here if the call happens somewhere execution can't be paused
             Err(ExcType::attribute_error_method_not_implemented(
                 self.name(interns),
                 method_name,
@@ -310,13 +357,14 @@ impl PyTrait for Dataclass {
 }
 
 // Custom serde implementation for Dataclass.
-// Serializes all six fields; methods set is serialized as a Vec for determinism.
+// Serializes all seven fields; methods set is serialized as a Vec for determinism.
 impl serde::Serialize for Dataclass {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("Dataclass", 6)?;
+        let mut state = serializer.serialize_struct("Dataclass", 7)?;
         state.serialize_field("name", &self.name)?;
         state.serialize_field("type_id", &self.type_id)?;
+        state.serialize_field("instance_id", &self.instance_id)?;
         state.serialize_field("field_names", &self.field_names)?;
         state.serialize_field("attrs", &self.attrs)?;
         // Serialize methods as sorted Vec for deterministic output
@@ -334,6 +382,7 @@ impl<'de> serde::Deserialize<'de> for Dataclass {
         struct DataclassData {
             name: EitherStr,
             type_id: u64,
+            instance_id: Option<u64>,
             field_names: Vec<String>,
             attrs: Dict,
             methods: Vec<String>,
@@ -343,6 +392,7 @@ impl<'de> serde::Deserialize<'de> for Dataclass {
         Ok(Self {
             name: dc.name,
             type_id: dc.type_id,
+            instance_id: dc.instance_id,
             field_names: dc.field_names,
             attrs: dc.attrs,
             methods: dc.methods.into_iter().collect(),
@@ -350,3 +400,98 @@ impl<'de> serde::Deserialize<'de> for Dataclass {
         })
     }
 }
+
+/// The class of a dataclass instance, returned by `type(dc)`.
+///
+/// Dataclass classes live on the host, so this only records enough to identify the class:
+/// two `DataclassType`s are equal if they have the same name and `type_id`, and an instance
+/// belongs to the class if both of those match. This is what lets class patterns like `case P(x, y)`
+/// and `isinstance(value, P)` tell different dataclasses apart.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct DataclassType {
+    /// The class name (e.g., "Point", "User")
+    name: EitherStr,
+    /// Identifier of the type, from `id(type(dc))` in python.
+    type_id: u64,
+    /// Declared field names in definition order, used for positional class patterns
+    field_names: Vec<String>,
+}
+
+impl DataclassType {
+    /// Returns the class name.
+    #[must_use]
+    pub fn name<'a>(&'a self, interns: &'a Interns) -> &'a str {
+        self.name.as_str(interns)
+    }
+
+    /// Returns the type ID of the dataclass.
+    #[must_use]
+    pub fn type_id(&self) -> u64 {
+        self.type_id
+    }
+
+    /// Returns whether `dc` is an instance of this class.
+    #[must_use]
+    pub fn is_class_of(&self, dc: &Dataclass) -> bool {
+        self.name == dc.name && self.type_id == dc.type_id
+    }
+}
+
+impl PyTrait for DataclassType {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::Type
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.name.py_estimate_size()
+            + self.field_names.iter().map(String::len).sum::<usize>()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self.name == other.name && self.type_id == other.type_id)
+    }
+
+    fn py_dec_ref_ids(&mut self, _stack: &mut Vec<HeapId>) {}
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        write!(f, "<class '{}'>", self.name(interns))
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        if StaticStrings::from_string_id(attr_id) != Some(StaticStrings::DunderName) {
+            return Ok(None);
+        }
+        let name = match &self.name {
+            EitherStr::Interned(id) => Value::InternString(*id),
+            EitherStr::Heap(name) => allocate_string(name.clone(), heap)?,
+        };
+        Ok(Some(AttrCallResult::Value(name)))
+    }
+}
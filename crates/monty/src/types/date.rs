@@ -0,0 +1,486 @@
+//! Python `datetime.date` type and the proleptic Gregorian calendar helpers shared with `datetime`.
+//!
+//! Dates convert to and from ordinals (day 1 is January 1 of year 1) using the same algorithms
+//! as CPython's `datetime` module, so date arithmetic is done as integer arithmetic on ordinals.
+
+use std::{
+    cmp::Ordering,
+    collections::hash_map::DefaultHasher,
+    fmt::{self, Display, Write},
+    hash::{Hash, Hasher},
+};
+
+use ahash::AHashSet;
+
+use crate::{
+    args::{ArgValues, bind_args},
+    defer_drop,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{
+        AttrCallResult, DateTime, PyTrait, Type, allocate_tuple,
+        datetime::{isoformat_arg, str_arg, timestamp_to_micros},
+        str::{StringRepr, allocate_string},
+        strftime::strftime,
+    },
+    value::{EitherStr, Value},
+};
+
+/// The smallest year allowed in a date, `datetime.MINYEAR`.
+pub(crate) const MIN_YEAR: i32 = 1;
+/// The largest year allowed in a date, `datetime.MAXYEAR`.
+pub(crate) const MAX_YEAR: i32 = 9999;
+/// The ordinal of December 31, 9999.
+const MAX_ORDINAL: i64 = 3_652_059;
+/// The ordinal of January 1, 1970.
+pub(crate) const EPOCH_ORDINAL: i64 = 719_163;
+
+/// Number of days before the first of each month in a non-leap year, indexed by month (1-based).
+const DAYS_BEFORE_MONTH: [i32; 13] = [0, 0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+/// Number of days in each month in a non-leap year, indexed by month (1-based).
+const DAYS_IN_MONTH: [i32; 13] = [0, 31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/// Python `datetime.date`: a year, month and day in the proleptic Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Date {
+    year: i32,
+    month: i32,
+    day: i32,
+}
+
+impl Date {
+    /// `date.min`, January 1 of year 1.
+    pub const MIN: Self = Self {
+        year: MIN_YEAR,
+        month: 1,
+        day: 1,
+    };
+    /// `date.max`, December 31 of year 9999.
+    pub const MAX: Self = Self {
+        year: MAX_YEAR,
+        month: 12,
+        day: 31,
+    };
+
+    /// Creates a date, raising `ValueError` if any component is out of range.
+    pub fn new(year: i32, month: i32, day: i32) -> RunResult<Self> {
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+            return Err(value_error(format!("year {year} is out of range")));
+        }
+        if !(1..=12).contains(&month) {
+            return Err(value_error("month must be in 1..12"));
+        }
+        if day < 1 || day > days_in_month(year, month) {
+            return Err(value_error("day is out of range for month"));
+        }
+        Ok(Self { year, month, day })
+    }
+
+    /// Creates a date from a proleptic Gregorian ordinal, where January 1 of year 1 is day 1.
+    pub fn from_ordinal(ordinal: i64) -> RunResult<Self> {
+        if ordinal < 1 {
+            return Err(value_error("ordinal must be >= 1"));
+        }
+        if ordinal > MAX_ORDINAL {
+            return Err(value_error(format!("year {} is out of range", MAX_YEAR + 1)));
+        }
+        Ok(Self::from_valid_ordinal(ordinal))
+    }
+
+    /// Creates a date from an ordinal, raising `OverflowError` if it is out of range.
+    ///
+    /// This is the error CPython raises when date arithmetic leaves the supported range.
+    pub fn from_ordinal_checked(ordinal: i64) -> RunResult<Self> {
+        if (1..=MAX_ORDINAL).contains(&ordinal) {
+            Ok(Self::from_valid_ordinal(ordinal))
+        } else {
+            Err(SimpleException::new_msg(ExcType::OverflowError, "date value out of range").into())
+        }
+    }
+
+    /// Converts an ordinal in `1..=MAX_ORDINAL` to a date, following CPython's `_ord2ymd`.
+    fn from_valid_ordinal(ordinal: i64) -> Self {
+        const DAYS_IN_400_YEARS: i64 = 146_097;
+        const DAYS_IN_100_YEARS: i64 = 36_524;
+        const DAYS_IN_4_YEARS: i64 = 1_461;
+
+        let n = ordinal - 1;
+        let (n400, n) = (n / DAYS_IN_400_YEARS, n % DAYS_IN_400_YEARS);
+        let (n100, n) = (n / DAYS_IN_100_YEARS, n % DAYS_IN_100_YEARS);
+        let (n4, n) = (n / DAYS_IN_4_YEARS, n % DAYS_IN_4_YEARS);
+        let (n1, n) = (n / 365, n % 365);
+        let year = i32::try_from(n400 * 400 + n100 * 100 + n4 * 4 + n1 + 1).expect("ordinal is in range");
+        if n1 == 4 || n100 == 4 {
+            // The last day of a leap year
+            return Self {
+                year: year - 1,
+                month: 12,
+                day: 31,
+            };
+        }
+        let n = i32::try_from(n).expect("day of year is below 366");
+        let mut month = (n + 50) >> 5;
+        let mut preceding = days_before_month(year, month);
+        if preceding > n {
+            month -= 1;
+            preceding -= days_in_month(year, month);
+        }
+        Self {
+            year,
+            month,
+            day: n - preceding + 1,
+        }
+    }
+
+    /// Returns the proleptic Gregorian ordinal, where January 1 of year 1 is day 1.
+    #[must_use]
+    pub fn to_ordinal(self) -> i64 {
+        let y = i64::from(self.year - 1);
+        y * 365 + y / 4 - y / 100 + y / 400 + i64::from(days_before_month(self.year, self.month)) + i64::from(self.day)
+    }
+
+    /// Returns the year.
+    #[must_use]
+    pub fn year(self) -> i32 {
+        self.year
+    }
+
+    /// Returns the month, in `1..=12`.
+    #[must_use]
+    pub fn month(self) -> i32 {
+        self.month
+    }
+
+    /// Returns the day of the month.
+    #[must_use]
+    pub fn day(self) -> i32 {
+        self.day
+    }
+
+    /// Returns the day of the week, where Monday is 0 and Sunday is 6.
+    #[must_use]
+    pub fn weekday(self) -> i32 {
+        i32::try_from((self.to_ordinal() + 6) % 7).expect("weekday is below 7")
+    }
+
+    /// Returns the day of the year, where January 1 is day 1.
+    #[must_use]
+    pub fn day_of_year(self) -> i32 {
+        days_before_month(self.year, self.month) + self.day
+    }
+
+    /// Returns the date `days` days later, raising `OverflowError` outside the supported range.
+    pub fn add_days(self, days: i64) -> RunResult<Self> {
+        Self::from_ordinal_checked(self.to_ordinal() + days)
+    }
+
+    /// Allocates this date on the heap.
+    pub fn into_value(self, heap: &mut Heap<impl ResourceTracker>) -> Result<Value, ResourceError> {
+        Ok(Value::Ref(heap.allocate(HeapData::Date(self))?))
+    }
+
+    /// Computes a hash consistent with equality.
+    #[must_use]
+    pub fn hash(self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        Hash::hash(&self, &mut hasher);
+        hasher.finish()
+    }
+
+    /// Parses the date at the start of an ISO 8601 string, `YYYY-MM-DD` or `YYYYMMDD`.
+    ///
+    /// Returns the date and the number of bytes consumed, or `None` if `s` doesn't start with
+    /// a well-formed date. The date's fields are validated by the caller.
+    pub fn parse_iso_prefix(s: &str) -> Option<((i32, i32, i32), usize)> {
+        let bytes = s.as_bytes();
+        let extended = bytes.get(4) == Some(&b'-');
+        let (month_at, day_at, len) = if extended { (5, 8, 10) } else { (4, 6, 8) };
+        if bytes.len() < len || (extended && bytes[7] != b'-') {
+            return None;
+        }
+        let year = parse_digits(&bytes[..4])?;
+        let month = parse_digits(&bytes[month_at..month_at + 2])?;
+        let day = parse_digits(&bytes[day_at..day_at + 2])?;
+        Some(((year, month, day), len))
+    }
+
+    /// Creates a date from the `date(year, month, day)` constructor call.
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let args = bind_args("date", ["year", "month", "day"], 3, args, heap, interns)?;
+        defer_drop!(args, heap);
+        let [year, month, day] = int_fields(&args.0, heap)?;
+        Ok(Self::new(year, month, day)?.into_value(heap)?)
+    }
+
+    /// Implements `date.replace(year=..., month=..., day=...)`.
+    fn replace(self, heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let args = bind_args("replace", ["year", "month", "day"], 0, args, heap, interns)?;
+        defer_drop!(args, heap);
+        let defaults = [self.year, self.month, self.day];
+        let [year, month, day] = int_fields_or(&args.0, defaults, heap)?;
+        Ok(Self::new(year, month, day)?.into_value(heap)?)
+    }
+}
+
+/// Formats the date in ISO 8601 format, `YYYY-MM-DD`, as `str()` and `isoformat()` do.
+impl Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Returns whether `year` is a leap year.
+#[must_use]
+pub(crate) fn is_leap(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Returns the number of days in `month` of `year`.
+#[must_use]
+pub(crate) fn days_in_month(year: i32, month: i32) -> i32 {
+    if month == 2 && is_leap(year) {
+        29
+    } else {
+        DAYS_IN_MONTH[usize::try_from(month).expect("month is in 1..=12")]
+    }
+}
+
+/// Returns the number of days in `year` before the first of `month`.
+fn days_before_month(year: i32, month: i32) -> i32 {
+    DAYS_BEFORE_MONTH[usize::try_from(month).expect("month is in 1..=12")] + i32::from(month > 2 && is_leap(year))
+}
+
+/// Parses a run of ASCII digits, returning `None` if any byte isn't a digit.
+pub(crate) fn parse_digits(bytes: &[u8]) -> Option<i32> {
+    bytes.iter().try_fold(0i32, |acc, &b| {
+        b.is_ascii_digit().then(|| acc * 10 + i32::from(b - b'0'))
+    })
+}
+
+/// Creates the `ValueError` for a date whose ordinal is outside the supported range.
+pub(crate) fn year_out_of_range(ordinal: i64) -> RunError {
+    // Follows `from_valid_ordinal`, which only handles ordinals within range
+    let n = ordinal - 1;
+    let (n400, n) = (n.div_euclid(146_097), n.rem_euclid(146_097));
+    let (n100, n) = (n / 36_524, n % 36_524);
+    let (n4, n) = (n / 1_461, n % 1_461);
+    let n1 = n / 365;
+    let year = n400 * 400 + n100 * 100 + n4 * 4 + n1 + 1;
+    let year = if n1 == 4 || n100 == 4 { year - 1 } else { year };
+    value_error(format!("year {year} is out of range"))
+}
+
+/// Creates a `ValueError` with the given message.
+pub(crate) fn value_error(msg: impl Display) -> RunError {
+    SimpleException::new_msg(ExcType::ValueError, msg).into()
+}
+
+/// Converts an integer argument to an `i32` date or time field.
+///
+/// Raises `TypeError` for non-integers and `OverflowError` for integers outside the `i32` range.
+pub(crate) fn int_field(value: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<i32> {
+    let i = match value {
+        Value::Bool(b) => i64::from(*b),
+        _ => value.as_int(heap)?,
+    };
+    i32::try_from(i).map_err(|_| {
+        let msg = if i < 0 {
+            "signed integer is less than minimum"
+        } else {
+            "signed integer is greater than maximum"
+        };
+        SimpleException::new_msg(ExcType::OverflowError, msg).into()
+    })
+}
+
+/// Converts required integer arguments, which `bind_args` guarantees are present.
+fn int_fields<const N: usize>(values: &[Option<Value>; N], heap: &Heap<impl ResourceTracker>) -> RunResult<[i32; N]> {
+    int_fields_or(values, [0; N], heap)
+}
+
+/// Converts optional integer arguments, using `defaults` for missing ones.
+pub(crate) fn int_fields_or<const N: usize>(
+    values: &[Option<Value>; N],
+    defaults: [i32; N],
+    heap: &Heap<impl ResourceTracker>,
+) -> RunResult<[i32; N]> {
+    let mut fields = defaults;
+    for (field, value) in fields.iter_mut().zip(values) {
+        if let Some(value) = value {
+            *field = int_field(value, heap)?;
+        }
+    }
+    Ok(fields)
+}
+
+/// Implements the `date.fromisoformat(s)` classmethod.
+pub fn date_fromisoformat(
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let arg = args.get_one_arg("date.fromisoformat", heap)?;
+    defer_drop!(arg, heap);
+    let s = isoformat_arg(arg, heap, interns)?;
+    match Date::parse_iso_prefix(&s) {
+        Some(((year, month, day), len)) if len == s.len() => Ok(Date::new(year, month, day)?.into_value(heap)?),
+        _ => Err(value_error(format!("Invalid isoformat string: {}", StringRepr(&s)))),
+    }
+}
+
+/// Implements the `date.fromordinal(n)` classmethod.
+pub fn date_fromordinal(args: ArgValues, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    let arg = args.get_one_arg("date.fromordinal", heap)?;
+    defer_drop!(arg, heap);
+    let ordinal = arg.as_int(heap)?;
+    Ok(Date::from_ordinal(ordinal)?.into_value(heap)?)
+}
+
+/// Implements the `date.fromtimestamp(t)` classmethod.
+///
+/// The sandbox has no local timezone, so timestamps are converted as UTC.
+pub fn date_fromtimestamp(args: ArgValues, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    let arg = args.get_one_arg("date.fromtimestamp", heap)?;
+    defer_drop!(arg, heap);
+    let micros = timestamp_to_micros(arg, heap)?;
+    Ok(DateTime::from_timestamp_micros(micros, None)?.date().into_value(heap)?)
+}
+
+impl PyTrait for Date {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::Date
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self == other)
+    }
+
+    fn py_cmp(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<Option<Ordering>, ResourceError> {
+        Ok(Some(self.cmp(other)))
+    }
+
+    fn py_dec_ref_ids(&mut self, _stack: &mut Vec<HeapId>) {
+        // Dates don't contain heap references
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> fmt::Result {
+        write!(f, "datetime.date({}, {}, {})", self.year, self.month, self.day)
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let value = match attr.static_string() {
+            Some(StaticStrings::Isoformat) => {
+                args.check_zero_args("date.isoformat", heap)?;
+                return allocate_string(self.to_string(), heap);
+            }
+            Some(StaticStrings::Strftime) => {
+                let format = args.get_one_arg("date.strftime", heap)?;
+                defer_drop!(format, heap);
+                let format = str_arg(format, "strftime", heap, interns)?;
+                return allocate_string(strftime(&DateTime::from_date(*self), &format), heap);
+            }
+            Some(StaticStrings::Replace) => return self.replace(heap, args, interns),
+            Some(StaticStrings::Weekday) => {
+                args.check_zero_args("date.weekday", heap)?;
+                self.weekday()
+            }
+            Some(StaticStrings::Isoweekday) => {
+                args.check_zero_args("date.isoweekday", heap)?;
+                self.weekday() + 1
+            }
+            Some(StaticStrings::Toordinal) => {
+                args.check_zero_args("date.toordinal", heap)?;
+                return Ok(Value::Int(self.to_ordinal()));
+            }
+            Some(StaticStrings::Isocalendar) => {
+                args.check_zero_args("date.isocalendar", heap)?;
+                let (year, week, weekday) = iso_calendar(*self);
+                let items = [year, week, weekday].map(|i| Value::Int(i64::from(i)));
+                return Ok(allocate_tuple(items.into_iter().collect(), heap)?);
+            }
+            _ => {
+                args.drop_with_heap(heap);
+                return Err(ExcType::attribute_error(Type::Date, attr.as_str(interns)));
+            }
+        };
+        Ok(Value::Int(i64::from(value)))
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        let value = match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::Year) => self.year,
+            Some(StaticStrings::Month) => self.month,
+            Some(StaticStrings::Day) => self.day,
+            _ => return Ok(None),
+        };
+        Ok(Some(AttrCallResult::Value(Value::Int(i64::from(value)))))
+    }
+}
+
+/// Returns the ISO calendar `(year, week, weekday)` of a date, as `isocalendar()` does.
+///
+/// ISO weeks start on Monday, and week 1 of a year is the week containing its first Thursday.
+pub(crate) fn iso_calendar(date: Date) -> (i32, i32, i32) {
+    let week1_monday = |year: i32| {
+        let first = Date { year, month: 1, day: 1 }.to_ordinal();
+        let first_weekday = (first + 6) % 7;
+        let monday = first - first_weekday;
+        if first_weekday > 3 { monday + 7 } else { monday }
+    };
+    let ordinal = date.to_ordinal();
+    let mut year = date.year;
+    let mut monday = week1_monday(year);
+    if ordinal < monday {
+        year -= 1;
+        monday = week1_monday(year);
+    } else if year < MAX_YEAR && ordinal >= week1_monday(year + 1) {
+        year += 1;
+        monday = week1_monday(year);
+    }
+    let days = i32::try_from(ordinal - monday).expect("days within an ISO year fit in i32");
+    (year, days / 7 + 1, days % 7 + 1)
+}
@@ -0,0 +1,909 @@
+//! Python `datetime.datetime` type: a date and a time of day, optionally with a fixed-offset timezone.
+//!
+//! Datetimes without a timezone are "naive"; those with one are "aware". Aware datetimes compare
+//! and subtract by their UTC instant, while mixing naive and aware datetimes is an error, as in
+//! CPython. The sandbox has no local timezone, so wherever CPython would use local time (e.g.
+//! `fromtimestamp()` without a timezone, or `timestamp()` of a naive datetime) UTC is used.
+//!
+//! Reading the current time never touches the host clock: `datetime.now()`, `datetime.today()`
+//! and `date.today()` yield an `OsFunction::Time` call to the host, and the VM converts the
+//! returned timestamp according to the pending [`ClockRead`].
+
+use std::{
+    cmp::Ordering,
+    collections::hash_map::DefaultHasher,
+    fmt::{self, Display, Write},
+    hash::{Hash, Hasher},
+};
+
+use ahash::AHashSet;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+
+use crate::{
+    args::{ArgValues, bind_args},
+    defer_drop,
+    exception_private::{ExcType, RunResult, SimpleException},
+    expressions::Operator,
+    heap::{Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{
+        AttrCallResult, Date, LongInt, PyTrait, TimeDelta, TimeZone, Type, allocate_tuple,
+        date::{EPOCH_ORDINAL, int_fields_or, iso_calendar, parse_digits, value_error, year_out_of_range},
+        str::{StringRepr, allocate_string},
+        strftime::{strftime, strptime},
+        timedelta::{MICROS_PER_DAY, MICROS_PER_SECOND, int_too_large},
+        timezone::format_offset,
+    },
+    value::{EitherStr, Value},
+};
+
+/// Python `datetime.datetime`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct DateTime {
+    date: Date,
+    hour: i32,
+    minute: i32,
+    second: i32,
+    microsecond: i32,
+    /// The timezone of an aware datetime, or `None` for a naive one.
+    tzinfo: Option<TimeZone>,
+}
+
+impl DateTime {
+    /// Creates a datetime, raising `ValueError` if any time field is out of range.
+    pub fn new(date: Date, [hour, minute, second, microsecond]: [i32; 4], tzinfo: Option<TimeZone>) -> RunResult<Self> {
+        for (value, max, name) in [
+            (hour, 23, "hour"),
+            (minute, 59, "minute"),
+            (second, 59, "second"),
+            (microsecond, 999_999, "microsecond"),
+        ] {
+            if !(0..=max).contains(&value) {
+                return Err(value_error(format!("{name} must be in 0..{max}")));
+            }
+        }
+        Ok(Self {
+            date,
+            hour,
+            minute,
+            second,
+            microsecond,
+            tzinfo,
+        })
+    }
+
+    /// Creates a naive datetime at midnight of `date`.
+    #[must_use]
+    pub fn from_date(date: Date) -> Self {
+        Self {
+            date,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            microsecond: 0,
+            tzinfo: None,
+        }
+    }
+
+    /// Creates a datetime from the number of microseconds since midnight of day 0 (the day before
+    /// January 1 of year 1), in its own timezone.
+    ///
+    /// Raises `OverflowError` if the result is out of range, as datetime arithmetic does.
+    pub fn from_local_micros(micros: i128, tzinfo: Option<TimeZone>) -> RunResult<Self> {
+        let ordinal = i64::try_from(micros.div_euclid(MICROS_PER_DAY)).unwrap_or(i64::MAX);
+        let date = Date::from_ordinal_checked(ordinal)?;
+        let rest = micros.rem_euclid(MICROS_PER_DAY);
+        let seconds = i32::try_from(rest / MICROS_PER_SECOND).expect("a day has fewer than 2**31 seconds");
+        Ok(Self {
+            date,
+            hour: seconds / 3600,
+            minute: seconds / 60 % 60,
+            second: seconds % 60,
+            microsecond: i32::try_from(rest % MICROS_PER_SECOND).expect("a second has fewer than 2**31 microseconds"),
+            tzinfo,
+        })
+    }
+
+    /// Creates a datetime from a POSIX timestamp in microseconds, in `tzinfo` or naive UTC.
+    ///
+    /// Raises `ValueError` if the year is out of range, as `fromtimestamp()` does.
+    pub fn from_timestamp_micros(micros: i128, tzinfo: Option<TimeZone>) -> RunResult<Self> {
+        let offset = tzinfo.as_ref().map_or(0, |tz| tz.offset().total_micros());
+        let local = micros + offset + i128::from(EPOCH_ORDINAL) * MICROS_PER_DAY;
+        Self::from_local_micros(local, tzinfo).map_err(|_| {
+            let ordinal = i64::try_from(local.div_euclid(MICROS_PER_DAY)).unwrap_or(i64::MAX);
+            year_out_of_range(ordinal)
+        })
+    }
+
+    /// Returns the number of microseconds since midnight of day 0, ignoring the timezone.
+    #[must_use]
+    pub fn local_micros(&self) -> i128 {
+        i128::from(self.date.to_ordinal()) * MICROS_PER_DAY
+            + i128::from(self.hour * 3600 + self.minute * 60 + self.second) * MICROS_PER_SECOND
+            + i128::from(self.microsecond)
+    }
+
+    /// Returns the number of microseconds since midnight of day 0 in UTC, treating naive datetimes as UTC.
+    #[must_use]
+    pub fn utc_micros(&self) -> i128 {
+        self.local_micros() - self.utcoffset().map_or(0, TimeDelta::total_micros)
+    }
+
+    /// Returns the date part.
+    #[must_use]
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    /// Returns the hour, minute, second and microsecond fields.
+    #[must_use]
+    pub fn time_fields(&self) -> [i32; 4] {
+        [self.hour, self.minute, self.second, self.microsecond]
+    }
+
+    /// Returns the timezone of an aware datetime.
+    #[must_use]
+    pub fn tzinfo(&self) -> Option<&TimeZone> {
+        self.tzinfo.as_ref()
+    }
+
+    /// Returns the offset from UTC of an aware datetime.
+    #[must_use]
+    pub fn utcoffset(&self) -> Option<TimeDelta> {
+        self.tzinfo.as_ref().map(TimeZone::offset)
+    }
+
+    /// Allocates this datetime on the heap.
+    pub fn into_value(self, heap: &mut Heap<impl ResourceTracker>) -> Result<Value, ResourceError> {
+        Ok(Value::Ref(heap.allocate(HeapData::DateTime(self))?))
+    }
+
+    /// Computes a hash consistent with equality, so equal instants in different timezones hash equally.
+    #[must_use]
+    pub fn hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.utc_micros().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns whether both datetimes are naive or both are aware.
+    fn same_awareness(&self, other: &Self) -> bool {
+        self.tzinfo.is_some() == other.tzinfo.is_some()
+    }
+
+    /// Compares two datetimes, returning `None` when one is naive and the other aware.
+    #[must_use]
+    pub fn compare(&self, other: &Self) -> Option<Ordering> {
+        self.same_awareness(other)
+            .then(|| self.utc_micros().cmp(&other.utc_micros()))
+    }
+
+    /// Returns `self + delta`, raising `OverflowError` if the result is out of range.
+    pub fn add(&self, delta: TimeDelta) -> RunResult<Self> {
+        Self::from_local_micros(self.local_micros() + delta.total_micros(), self.tzinfo.clone())
+    }
+
+    /// Returns `self - other`, which must both be naive or both be aware.
+    pub fn sub(&self, other: &Self) -> RunResult<TimeDelta> {
+        if !self.same_awareness(other) {
+            return Err(ExcType::type_error(
+                "can't subtract offset-naive and offset-aware datetimes",
+            ));
+        }
+        TimeDelta::from_micros(self.utc_micros() - other.utc_micros())
+    }
+
+    /// Returns the POSIX timestamp, treating naive datetimes as UTC.
+    #[must_use]
+    pub fn timestamp(&self) -> f64 {
+        let micros = self.utc_micros() - i128::from(EPOCH_ORDINAL) * MICROS_PER_DAY;
+        micros as f64 / MICROS_PER_SECOND as f64
+    }
+
+    /// Formats the datetime in ISO 8601 format, as `isoformat(sep, timespec)` does.
+    ///
+    /// Raises `ValueError` for an unknown `timespec`.
+    pub fn isoformat(&self, sep: char, timespec: &str) -> RunResult<String> {
+        let mut out = format!("{}{sep}{:02}", self.date, self.hour);
+        let timespec = match timespec {
+            "auto" if self.microsecond == 0 => "seconds",
+            "auto" => "microseconds",
+            other => other,
+        };
+        match timespec {
+            "hours" => {}
+            "minutes" => write!(out, ":{:02}", self.minute).expect("writing to a string can't fail"),
+            "seconds" => write!(out, ":{:02}:{:02}", self.minute, self.second).expect("writing to a string can't fail"),
+            "milliseconds" => write!(
+                out,
+                ":{:02}:{:02}.{:03}",
+                self.minute,
+                self.second,
+                self.microsecond / 1000
+            )
+            .expect("writing to a string can't fail"),
+            "microseconds" => write!(out, ":{:02}:{:02}.{:06}", self.minute, self.second, self.microsecond)
+                .expect("writing to a string can't fail"),
+            _ => return Err(value_error("Unknown timespec value")),
+        }
+        if let Some(offset) = self.utcoffset() {
+            out.push_str(&format_offset(offset, ":"));
+        }
+        Ok(out)
+    }
+
+    /// Parses an ISO 8601 string, as `datetime.fromisoformat()` does.
+    ///
+    /// Accepts a date (`YYYY-MM-DD` or `YYYYMMDD`), optionally followed by any single separator
+    /// character and a time (`HH[:MM[:SS[.ffffff]]]` or `HH[MM[SS[.ffffff]]]`, with `,` also allowed
+    /// before the fraction), optionally followed by `Z` or a UTC offset in the same format as the time.
+    pub fn parse_isoformat(s: &str) -> RunResult<Self> {
+        let invalid = || value_error(format!("Invalid isoformat string: {}", StringRepr(s)));
+        let ((year, month, day), date_len) = Date::parse_iso_prefix(s).ok_or_else(invalid)?;
+        let bytes = s.as_bytes();
+        let (fields, offset) = if date_len == bytes.len() {
+            ([0; 4], None)
+        } else {
+            parse_iso_time(bytes.get(date_len + 1..).ok_or_else(invalid)?).ok_or_else(invalid)?
+        };
+        let date = Date::new(year, month, day)?;
+        let tzinfo = match offset {
+            Some(micros) => Some(TimeZone::new(TimeDelta::from_micros(micros)?, None)?),
+            None => None,
+        };
+        Self::new(date, fields, tzinfo)
+    }
+
+    /// Creates a datetime from the `datetime(year, month, day, hour=0, ...)` constructor call.
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let args = bind_args(
+            "datetime",
+            [
+                "year",
+                "month",
+                "day",
+                "hour",
+                "minute",
+                "second",
+                "microsecond",
+                "tzinfo",
+            ],
+            3,
+            args,
+            heap,
+            interns,
+        )?;
+        defer_drop!(args, heap);
+        let [fields @ .., tzinfo] = &args.0;
+        let [year, month, day, hour, minute, second, microsecond] = int_fields_or(fields, [0; 7], heap)?;
+        let tzinfo = match tzinfo {
+            Some(tzinfo) => TimeZone::from_tzinfo_arg(tzinfo, heap)?,
+            None => None,
+        };
+        let date = Date::new(year, month, day)?;
+        Ok(Self::new(date, [hour, minute, second, microsecond], tzinfo)?.into_value(heap)?)
+    }
+
+    /// Implements `datetime.replace(...)`, where passing `tzinfo=None` makes the result naive.
+    fn replace(&self, heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let args = bind_args(
+            "replace",
+            [
+                "year",
+                "month",
+                "day",
+                "hour",
+                "minute",
+                "second",
+                "microsecond",
+                "tzinfo",
+            ],
+            0,
+            args,
+            heap,
+            interns,
+        )?;
+        defer_drop!(args, heap);
+        let [fields @ .., tzinfo] = &args.0;
+        let defaults = [
+            self.date.year(),
+            self.date.month(),
+            self.date.day(),
+            self.hour,
+            self.minute,
+            self.second,
+            self.microsecond,
+        ];
+        let [year, month, day, hour, minute, second, microsecond] = int_fields_or(fields, defaults, heap)?;
+        let tzinfo = match tzinfo {
+            Some(tzinfo) => TimeZone::from_tzinfo_arg(tzinfo, heap)?,
+            None => self.tzinfo.clone(),
+        };
+        let date = Date::new(year, month, day)?;
+        Ok(Self::new(date, [hour, minute, second, microsecond], tzinfo)?.into_value(heap)?)
+    }
+
+    /// Implements `datetime.isoformat(sep='T', timespec='auto')`.
+    fn py_isoformat(
+        &self,
+        heap: &mut Heap<impl ResourceTracker>,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let args = bind_args("isoformat", ["sep", "timespec"], 0, args, heap, interns)?;
+        defer_drop!(args, heap);
+        let sep = match args.get(0) {
+            Some(sep) => {
+                let sep_str = sep.as_either_str(heap).map(|s| s.as_str(interns).to_owned());
+                let mut chars = sep_str.as_deref().unwrap_or_default().chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => {
+                        let found = if sep_str.is_some() {
+                            Type::Str
+                        } else {
+                            sep.py_type(heap)
+                        };
+                        return Err(ExcType::type_error(format!(
+                            "isoformat() argument 1 must be a unicode character, not {found}"
+                        )));
+                    }
+                }
+            }
+            None => 'T',
+        };
+        let timespec = match args.get(1) {
+            Some(timespec) => str_arg_n(timespec, "isoformat", 2, heap, interns)?,
+            None => "auto".to_owned(),
+        };
+        allocate_string(self.isoformat(sep, &timespec)?, heap)
+    }
+
+    /// Implements `datetime.astimezone(tz=None)`, converting to UTC when no timezone is given.
+    fn astimezone(
+        &self,
+        heap: &mut Heap<impl ResourceTracker>,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let args = bind_args("astimezone", ["tz"], 0, args, heap, interns)?;
+        defer_drop!(args, heap);
+        let tz = match args.get(0) {
+            Some(tz) => TimeZone::from_tzinfo_arg(tz, heap)?,
+            None => None,
+        };
+        let tz = tz.unwrap_or(TimeZone::UTC);
+        let local = self.utc_micros() + tz.offset().total_micros();
+        Ok(Self::from_local_micros(local, Some(tz))?.into_value(heap)?)
+    }
+}
+
+/// Formats the datetime like `str()`, which is `isoformat(' ')`.
+impl Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.isoformat(' ', "auto").expect("'auto' is a valid timespec"))
+    }
+}
+
+/// Parses the time and UTC offset of an ISO 8601 string, returning the time fields and the
+/// offset in microseconds.
+fn parse_iso_time(s: &[u8]) -> Option<([i32; 4], Option<i128>)> {
+    let tz_at = s.iter().position(|&b| matches!(b, b'+' | b'-' | b'Z'));
+    let (time, tz) = match tz_at {
+        Some(at) => (&s[..at], Some(&s[at..])),
+        None => (s, None),
+    };
+    let fields = parse_iso_clock(time)?;
+    let offset = match tz {
+        None => None,
+        Some(b"Z") => Some(0),
+        Some(tz) => {
+            let [hours, minutes, seconds, micros] = parse_iso_clock(&tz[1..])?;
+            let micros = i128::from(hours * 3600 + minutes * 60 + seconds) * MICROS_PER_SECOND + i128::from(micros);
+            Some(if tz[0] == b'-' { -micros } else { micros })
+        }
+    };
+    Some((fields, offset))
+}
+
+/// Parses `HH[:MM[:SS[.ffffff]]]` or `HH[MM[SS[.ffffff]]]`, where extra fraction digits are ignored.
+fn parse_iso_clock(s: &[u8]) -> Option<[i32; 4]> {
+    let extended = s.get(2) == Some(&b':');
+    let mut fields = [0; 4];
+    let mut pos = 0;
+    for (index, field) in fields.iter_mut().take(3).enumerate() {
+        if index > 0 && extended {
+            if s.get(pos) != Some(&b':') {
+                return None;
+            }
+            pos += 1;
+        }
+        *field = parse_digits(s.get(pos..pos + 2)?)?;
+        pos += 2;
+        if pos == s.len() {
+            return Some(fields);
+        }
+    }
+    if !matches!(s[pos], b'.' | b',') {
+        return None;
+    }
+    let digits = &s[pos + 1..];
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let mut micros = parse_digits(&digits[..digits.len().min(6)])?;
+    for _ in digits.len()..6 {
+        micros *= 10;
+    }
+    fields[3] = micros;
+    Some(fields)
+}
+
+/// Extracts the string argument of a `datetime` function or method.
+///
+/// Raises `TypeError` like CPython's `"strftime() argument 1 must be str, not int"`.
+pub(crate) fn str_arg(
+    value: &Value,
+    func: &str,
+    heap: &Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<String> {
+    str_arg_n(value, func, 1, heap, interns)
+}
+
+/// Extracts the string argument of `date.fromisoformat()` or `datetime.fromisoformat()`.
+pub(crate) fn isoformat_arg(value: &Value, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<String> {
+    match value.as_either_str(heap) {
+        Some(s) => Ok(s.as_str(interns).to_owned()),
+        None => Err(ExcType::type_error("fromisoformat: argument must be str")),
+    }
+}
+
+/// Like [`str_arg`], for the `position`th argument.
+fn str_arg_n(
+    value: &Value,
+    func: &str,
+    position: usize,
+    heap: &Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<String> {
+    match value.as_either_str(heap) {
+        Some(s) => Ok(s.as_str(interns).to_owned()),
+        None => Err(ExcType::type_error(format!(
+            "{func}() argument {position} must be str, not {}",
+            value.py_type(heap)
+        ))),
+    }
+}
+
+/// Converts a POSIX timestamp (an int or a float) to microseconds, rounding half to even.
+pub(crate) fn timestamp_to_micros(value: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<i128> {
+    match value {
+        Value::Int(i) => Ok(i128::from(*i) * MICROS_PER_SECOND),
+        Value::Bool(b) => Ok(i128::from(*b) * MICROS_PER_SECOND),
+        Value::Float(f) => {
+            if f.is_nan() {
+                return Err(value_error("Invalid value NaN (not a number)"));
+            }
+            let micros = (f * MICROS_PER_SECOND as f64).round_ties_even();
+            // Anything this large is far outside the range of a datetime
+            if !micros.is_finite() || micros.abs() >= 1e30 {
+                return Err(SimpleException::new_msg(
+                    ExcType::OverflowError,
+                    "timestamp out of range for platform time_t",
+                )
+                .into());
+            }
+            #[expect(clippy::cast_possible_truncation, reason = "magnitude checked above")]
+            Ok(micros as i128)
+        }
+        Value::Ref(id) if matches!(heap.get(*id), HeapData::LongInt(_)) => {
+            let HeapData::LongInt(li) = heap.get(*id) else {
+                unreachable!()
+            };
+            li.inner()
+                .to_i128()
+                .and_then(|i| i.checked_mul(MICROS_PER_SECOND))
+                .ok_or_else(int_too_large)
+        }
+        _ => Err(ExcType::type_error(format!(
+            "'{}' object cannot be interpreted as an integer",
+            value.py_type(heap)
+        ))),
+    }
+}
+
+/// Implements the `datetime.fromisoformat(s)` classmethod.
+pub fn datetime_fromisoformat(
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let arg = args.get_one_arg("datetime.fromisoformat", heap)?;
+    defer_drop!(arg, heap);
+    let s = isoformat_arg(arg, heap, interns)?;
+    Ok(DateTime::parse_isoformat(&s)?.into_value(heap)?)
+}
+
+/// Implements the `datetime.fromordinal(n)` classmethod.
+pub fn datetime_fromordinal(args: ArgValues, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    let arg = args.get_one_arg("datetime.fromordinal", heap)?;
+    defer_drop!(arg, heap);
+    let date = Date::from_ordinal(arg.as_int(heap)?)?;
+    Ok(DateTime::from_date(date).into_value(heap)?)
+}
+
+/// Implements the `datetime.fromtimestamp(t, tz=None)` classmethod.
+///
+/// Without a timezone, the result is a naive datetime in UTC, as the sandbox has no local timezone.
+pub fn datetime_fromtimestamp(
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let args = bind_args("fromtimestamp", ["timestamp", "tz"], 1, args, heap, interns)?;
+    defer_drop!(args, heap);
+    let micros = timestamp_to_micros(args.get(0).expect("timestamp is required"), heap)?;
+    let tz = match args.get(1) {
+        Some(tz) => TimeZone::from_tzinfo_arg(tz, heap)?,
+        None => None,
+    };
+    Ok(DateTime::from_timestamp_micros(micros, tz)?.into_value(heap)?)
+}
+
+/// Implements the `datetime.strptime(date_string, format)` classmethod.
+pub fn datetime_strptime(
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let (date_string, format) = args.get_two_args("datetime.strptime", heap)?;
+    defer_drop!(date_string, heap);
+    defer_drop!(format, heap);
+    let date_string = str_arg_n(date_string, "strptime", 1, heap, interns)?;
+    let format = str_arg_n(format, "strptime", 2, heap, interns)?;
+    Ok(strptime(&date_string, &format)?.into_value(heap)?)
+}
+
+/// A pending read of the current time, for `date.today()`, `datetime.today()` and `datetime.now(tz)`.
+///
+/// These calls yield `OsFunction::Time` to the host instead of reading the host clock, and the VM
+/// stores the `ClockRead` until the host resumes it with the timestamp to convert.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) enum ClockRead {
+    /// `date.today()`, the current UTC date.
+    Date,
+    /// `datetime.now(tz)` or `datetime.today()`, an aware datetime in `tz` or a naive one in UTC.
+    DateTime(Option<TimeZone>),
+}
+
+impl ClockRead {
+    /// Returns whether calling `method_id` on type `t` reads the current time.
+    #[must_use]
+    pub fn is_clock_method(t: Type, method_id: StringId) -> bool {
+        match t {
+            Type::Date => method_id == StaticStrings::Today,
+            Type::DateTime => method_id == StaticStrings::Today || method_id == StaticStrings::Now,
+            _ => false,
+        }
+    }
+
+    /// Creates the clock read for a call of `method_id` on type `t`, checking its arguments.
+    pub fn from_call(
+        t: Type,
+        method_id: StringId,
+        args: ArgValues,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Self> {
+        if t == Type::Date {
+            args.check_zero_args("date.today", heap)?;
+            return Ok(Self::Date);
+        }
+        if method_id == StaticStrings::Today {
+            args.check_zero_args("datetime.today", heap)?;
+            return Ok(Self::DateTime(None));
+        }
+        let args = bind_args("now", ["tz"], 0, args, heap, interns)?;
+        defer_drop!(args, heap);
+        match args.get(0) {
+            Some(tz) => Ok(Self::DateTime(TimeZone::from_tzinfo_arg(tz, heap)?)),
+            None => Ok(Self::DateTime(None)),
+        }
+    }
+
+    /// Converts the POSIX timestamp returned by the host to the date or datetime that was asked for.
+    pub fn into_value(self, timestamp: &Value, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+        let micros = timestamp_to_micros(timestamp, heap)?;
+        match self {
+            Self::Date => Ok(DateTime::from_timestamp_micros(micros, None)?.date.into_value(heap)?),
+            Self::DateTime(tz) => Ok(DateTime::from_timestamp_micros(micros, tz)?.into_value(heap)?),
+        }
+    }
+}
+
+/// Looks up the class attributes of the datetime types, such as `date.min` and `timezone.utc`.
+///
+/// Returns `Ok(None)` if `t` has no such attribute.
+pub(crate) fn type_attr(t: Type, name_id: StringId, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Option<Value>> {
+    const MAX_TZ_OFFSET: i128 = (23 * 3600 + 59 * 60) * MICROS_PER_SECOND;
+    let Some(name) = StaticStrings::from_string_id(name_id) else {
+        return Ok(None);
+    };
+    let value = match (t, name) {
+        (Type::Date, StaticStrings::Min) => Date::MIN.into_value(heap)?,
+        (Type::Date, StaticStrings::Max) => Date::MAX.into_value(heap)?,
+        (Type::Date, StaticStrings::Resolution) => TimeDelta::from_days(1).into_value(heap)?,
+        (Type::DateTime, StaticStrings::Min) => DateTime::from_date(Date::MIN).into_value(heap)?,
+        (Type::DateTime, StaticStrings::Max) => {
+            DateTime::new(Date::MAX, [23, 59, 59, 999_999], None)?.into_value(heap)?
+        }
+        (Type::DateTime | Type::TimeDelta, StaticStrings::Resolution) => TimeDelta::RESOLUTION.into_value(heap)?,
+        (Type::TimeDelta, StaticStrings::Min) => TimeDelta::MIN.into_value(heap)?,
+        (Type::TimeDelta, StaticStrings::Max) => TimeDelta::MAX.into_value(heap)?,
+        (Type::TimeZone, StaticStrings::Utc) => TimeZone::UTC.into_value(heap)?,
+        (Type::TimeZone, StaticStrings::Min) => {
+            TimeZone::from_offset(TimeDelta::from_micros(-MAX_TZ_OFFSET)?).into_value(heap)?
+        }
+        (Type::TimeZone, StaticStrings::Max) => {
+            TimeZone::from_offset(TimeDelta::from_micros(MAX_TZ_OFFSET)?).into_value(heap)?
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(value))
+}
+
+impl PyTrait for DateTime {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::DateTime
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self.compare(other) == Some(Ordering::Equal))
+    }
+
+    fn py_cmp(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<Option<Ordering>, ResourceError> {
+        Ok(self.compare(other))
+    }
+
+    fn py_dec_ref_ids(&mut self, _stack: &mut Vec<HeapId>) {
+        // Datetimes store their timezone inline, so they don't contain heap references
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        heap: &Heap<impl ResourceTracker>,
+        heap_ids: &mut AHashSet<HeapId>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "datetime.datetime({}, {}, {}, {}, {}",
+            self.date.year(),
+            self.date.month(),
+            self.date.day(),
+            self.hour,
+            self.minute
+        )?;
+        if self.second != 0 || self.microsecond != 0 {
+            write!(f, ", {}", self.second)?;
+        }
+        if self.microsecond != 0 {
+            write!(f, ", {}", self.microsecond)?;
+        }
+        if let Some(tz) = &self.tzinfo {
+            f.write_str(", tzinfo=")?;
+            tz.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+        }
+        f.write_char(')')
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        match attr.static_string() {
+            Some(StaticStrings::Isoformat) => self.py_isoformat(heap, args, interns),
+            Some(StaticStrings::Strftime) => {
+                let format = args.get_one_arg("datetime.strftime", heap)?;
+                defer_drop!(format, heap);
+                let format = str_arg(format, "strftime", heap, interns)?;
+                allocate_string(strftime(self, &format), heap)
+            }
+            Some(StaticStrings::Replace) => self.replace(heap, args, interns),
+            Some(StaticStrings::Astimezone) => self.astimezone(heap, args, interns),
+            Some(StaticStrings::Date) => {
+                args.check_zero_args("datetime.date", heap)?;
+                Ok(self.date.into_value(heap)?)
+            }
+            Some(StaticStrings::Timestamp) => {
+                args.check_zero_args("datetime.timestamp", heap)?;
+                Ok(Value::Float(self.timestamp()))
+            }
+            Some(StaticStrings::Weekday) => {
+                args.check_zero_args("datetime.weekday", heap)?;
+                Ok(Value::Int(i64::from(self.date.weekday())))
+            }
+            Some(StaticStrings::Isoweekday) => {
+                args.check_zero_args("datetime.isoweekday", heap)?;
+                Ok(Value::Int(i64::from(self.date.weekday() + 1)))
+            }
+            Some(StaticStrings::Toordinal) => {
+                args.check_zero_args("datetime.toordinal", heap)?;
+                Ok(Value::Int(self.date.to_ordinal()))
+            }
+            Some(StaticStrings::Isocalendar) => {
+                args.check_zero_args("datetime.isocalendar", heap)?;
+                let (year, week, weekday) = iso_calendar(self.date);
+                let items = [year, week, weekday].map(|i| Value::Int(i64::from(i)));
+                Ok(allocate_tuple(items.into_iter().collect(), heap)?)
+            }
+            Some(StaticStrings::Utcoffset) => {
+                args.check_zero_args("datetime.utcoffset", heap)?;
+                match self.utcoffset() {
+                    Some(offset) => Ok(offset.into_value(heap)?),
+                    None => Ok(Value::None),
+                }
+            }
+            Some(StaticStrings::Tzname) => {
+                args.check_zero_args("datetime.tzname", heap)?;
+                match &self.tzinfo {
+                    Some(tz) => allocate_string(tz.tzname(), heap),
+                    None => Ok(Value::None),
+                }
+            }
+            Some(StaticStrings::Dst) => {
+                // Fixed-offset timezones have no daylight saving time
+                args.check_zero_args("datetime.dst", heap)?;
+                Ok(Value::None)
+            }
+            _ => {
+                args.drop_with_heap(heap);
+                Err(ExcType::attribute_error(Type::DateTime, attr.as_str(interns)))
+            }
+        }
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        let value = match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::Year) => self.date.year(),
+            Some(StaticStrings::Month) => self.date.month(),
+            Some(StaticStrings::Day) => self.date.day(),
+            Some(StaticStrings::Hour) => self.hour,
+            Some(StaticStrings::Minute) => self.minute,
+            Some(StaticStrings::Second) => self.second,
+            Some(StaticStrings::Microsecond) => self.microsecond,
+            Some(StaticStrings::Tzinfo) => {
+                let tzinfo = match &self.tzinfo {
+                    Some(tz) => tz.clone().into_value(heap)?,
+                    None => Value::None,
+                };
+                return Ok(Some(AttrCallResult::Value(tzinfo)));
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(AttrCallResult::Value(Value::Int(i64::from(value)))))
+    }
+}
+
+/// An operand of a `date`, `datetime` or `timedelta` arithmetic operation.
+enum Operand {
+    Date(Date),
+    DateTime(DateTime),
+    Delta(TimeDelta),
+    Int(i128),
+    Float(f64),
+    Other,
+}
+
+impl Operand {
+    fn new(value: &Value, heap: &Heap<impl ResourceTracker>) -> Self {
+        match value {
+            Value::Int(i) => Self::Int(i128::from(*i)),
+            Value::Bool(b) => Self::Int(i128::from(*b)),
+            Value::Float(f) => Self::Float(*f),
+            Value::Ref(id) => match heap.get(*id) {
+                HeapData::Date(date) => Self::Date(*date),
+                HeapData::DateTime(dt) => Self::DateTime(dt.clone()),
+                HeapData::TimeDelta(delta) => Self::Delta(*delta),
+                HeapData::LongInt(li) => li.inner().to_i128().map_or(Self::Other, Self::Int),
+                _ => Self::Other,
+            },
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Implements the arithmetic operators of `date`, `datetime` and `timedelta`.
+///
+/// Returns `Ok(None)` if neither operand is one of these types, or if the operator isn't
+/// supported for the operand types, so the caller can try other types or raise `TypeError`.
+pub(crate) fn binary_op(
+    lhs: &Value,
+    rhs: &Value,
+    op: Operator,
+    heap: &mut Heap<impl ResourceTracker>,
+) -> RunResult<Option<Value>> {
+    let is_datetime_type = |value: &Value| {
+        matches!(value, Value::Ref(id) if matches!(
+            heap.get(*id),
+            HeapData::Date(_) | HeapData::DateTime(_) | HeapData::TimeDelta(_)
+        ))
+    };
+    if !is_datetime_type(lhs) && !is_datetime_type(rhs) {
+        return Ok(None);
+    }
+    let value = match (op, Operand::new(lhs, heap), Operand::new(rhs, heap)) {
+        // Dates ignore the seconds and microseconds of a timedelta
+        (Operator::Add, Operand::Date(date), Operand::Delta(delta))
+        | (Operator::Add, Operand::Delta(delta), Operand::Date(date)) => {
+            date.add_days(i64::from(delta.days()))?.into_value(heap)?
+        }
+        (Operator::Sub, Operand::Date(date), Operand::Delta(delta)) => {
+            date.add_days(-i64::from(delta.days()))?.into_value(heap)?
+        }
+        (Operator::Sub, Operand::Date(a), Operand::Date(b)) => {
+            let days = i32::try_from(a.to_ordinal() - b.to_ordinal()).expect("dates are less than 2**31 days apart");
+            TimeDelta::from_days(days).into_value(heap)?
+        }
+        (Operator::Add, Operand::DateTime(dt), Operand::Delta(delta))
+        | (Operator::Add, Operand::Delta(delta), Operand::DateTime(dt)) => dt.add(delta)?.into_value(heap)?,
+        (Operator::Sub, Operand::DateTime(dt), Operand::Delta(delta)) => {
+            DateTime::from_local_micros(dt.local_micros() - delta.total_micros(), dt.tzinfo)?.into_value(heap)?
+        }
+        (Operator::Sub, Operand::DateTime(a), Operand::DateTime(b)) => a.sub(&b)?.into_value(heap)?,
+        (Operator::Add, Operand::Delta(a), Operand::Delta(b)) => a.add(b)?.into_value(heap)?,
+        (Operator::Sub, Operand::Delta(a), Operand::Delta(b)) => a.sub(b)?.into_value(heap)?,
+        (Operator::Mult, Operand::Delta(delta), Operand::Int(n))
+        | (Operator::Mult, Operand::Int(n), Operand::Delta(delta)) => delta.mul_int(n)?.into_value(heap)?,
+        (Operator::Mult, Operand::Delta(delta), Operand::Float(f))
+        | (Operator::Mult, Operand::Float(f), Operand::Delta(delta)) => delta.mul_float(f)?.into_value(heap)?,
+        (Operator::Div, Operand::Delta(a), Operand::Delta(b)) => Value::Float(a.div(b)?),
+        (Operator::Div, Operand::Delta(delta), Operand::Int(n)) => delta.div_int(n)?.into_value(heap)?,
+        (Operator::Div, Operand::Delta(delta), Operand::Float(f)) => delta.div_float(f)?.into_value(heap)?,
+        (Operator::FloorDiv, Operand::Delta(a), Operand::Delta(b)) => {
+            LongInt::new(BigInt::from(a.floordiv(b)?)).into_value(heap)?
+        }
+        (Operator::FloorDiv, Operand::Delta(delta), Operand::Int(n)) => delta.floordiv_int(n)?.into_value(heap)?,
+        (Operator::Mod, Operand::Delta(a), Operand::Delta(b)) => a.rem(b)?.into_value(heap)?,
+        _ => return Ok(None),
+    };
+    Ok(Some(value))
+}
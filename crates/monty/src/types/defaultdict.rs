@@ -0,0 +1,208 @@
+use std::fmt::Write;
+
+use ahash::AHashSet;
+
+use super::{Dict, PyTrait, dict::dict_update, dict::is_dict_method};
+use crate::{
+    args::ArgValues,
+    builtins::is_callable,
+    exception_private::{ExcType, RunResult},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{AttrCallResult, Type},
+    value::{EitherStr, Value},
+};
+
+/// Python `collections.defaultdict` type, a dict that creates values for missing keys.
+///
+/// Looking up a missing key with `d[key]` calls `default_factory` with no arguments,
+/// stores the result under `key` and returns it. Since the factory can be any callable,
+/// the VM handles this in its subscript opcode; here `py_getitem` raises `KeyError` like
+/// a plain dict. All other operations behave like `dict`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct DefaultDict {
+    dict: Dict,
+    /// Called to produce values for missing keys, `Value::None` to raise `KeyError` instead.
+    default_factory: Value,
+}
+
+impl DefaultDict {
+    /// Creates a new defaultdict, taking ownership of `dict` and `default_factory`.
+    #[must_use]
+    pub fn new(dict: Dict, default_factory: Value) -> Self {
+        Self { dict, default_factory }
+    }
+
+    /// Returns the underlying dict.
+    #[must_use]
+    pub fn dict(&self) -> &Dict {
+        &self.dict
+    }
+
+    /// Returns the default factory, `Value::None` if there is none.
+    #[must_use]
+    pub fn default_factory(&self) -> &Value {
+        &self.default_factory
+    }
+
+    /// Returns whether the defaultdict contains any heap references.
+    #[must_use]
+    pub fn has_refs(&self) -> bool {
+        self.dict.has_refs() || matches!(self.default_factory, Value::Ref(_))
+    }
+
+    /// Creates a defaultdict from the `defaultdict()` constructor call.
+    ///
+    /// The first positional argument is the default factory; any remaining arguments are
+    /// handled like `dict.update()`.
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let (mut pos, kwargs) = args.into_parts();
+        let default_factory = pos.next().unwrap_or(Value::None);
+        if !matches!(default_factory, Value::None) && !is_callable(&default_factory, heap) {
+            default_factory.drop_with_heap(heap);
+            pos.drop_with_heap(heap);
+            kwargs.drop_with_heap(heap);
+            return Err(ExcType::type_error("first argument must be callable or None"));
+        }
+
+        let mut dict = Dict::new();
+        let update_args = ArgValues::ArgsKargs {
+            args: pos.collect(),
+            kwargs,
+        };
+        if let Err(err) = dict_update(&mut dict, update_args, heap, interns) {
+            default_factory.drop_with_heap(heap);
+            dict.drop_with_heap(heap);
+            return Err(err);
+        }
+        Ok(Value::Ref(
+            heap.allocate(HeapData::DefaultDict(Self::new(dict, default_factory)))?,
+        ))
+    }
+}
+
+impl PyTrait for DefaultDict {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::DefaultDict
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        self.dict.py_estimate_size() + std::mem::size_of::<Value>()
+    }
+
+    fn py_len(&self, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> Option<usize> {
+        self.dict.py_len(heap, interns)
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        heap: &mut Heap<impl ResourceTracker>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        self.dict.py_eq(&other.dict, heap, guard, interns)
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        self.dict.py_dec_ref_ids(stack);
+        self.default_factory.py_dec_ref_ids(stack);
+    }
+
+    fn py_bool(&self, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> bool {
+        self.dict.py_bool(heap, interns)
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        heap: &Heap<impl ResourceTracker>,
+        heap_ids: &mut AHashSet<HeapId>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        f.write_str("defaultdict(")?;
+        self.default_factory.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+        f.write_str(", ")?;
+        self.dict.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+        f.write_char(')')
+    }
+
+    fn py_getitem(&self, key: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        self.dict.py_getitem(key, heap, interns)
+    }
+
+    fn py_setitem(
+        &mut self,
+        key: Value,
+        value: Value,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        self.dict.py_setitem(key, value, heap, interns)
+    }
+
+    fn py_delitem(&mut self, key: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<()> {
+        self.dict.py_delitem(key, heap, interns)
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        match attr.static_string() {
+            Some(StaticStrings::Copy) => {
+                args.check_zero_args("defaultdict.copy", heap)?;
+                let copy = Self::new(
+                    self.dict.clone_with_heap(heap),
+                    self.default_factory.clone_with_heap(heap),
+                );
+                Ok(Value::Ref(heap.allocate(HeapData::DefaultDict(copy))?))
+            }
+            Some(method) if is_dict_method(method) => self.dict.py_call_attr(heap, attr, args, interns),
+            _ => {
+                args.drop_with_heap(heap);
+                Err(ExcType::attribute_error(Type::DefaultDict, attr.as_str(interns)))
+            }
+        }
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::DefaultFactory) => {
+                Ok(Some(AttrCallResult::Value(self.default_factory.clone_with_heap(heap))))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Returns the default factory to call for `defaultdict[key]`, if the key is missing.
+///
+/// Returns `Ok(None)` if the key is present or the defaultdict has no factory, in which
+/// case the lookup behaves like a plain dict. The returned factory is owned by the caller.
+pub(crate) fn missing_key_factory(
+    id: HeapId,
+    key: &Value,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Option<Value>> {
+    heap.with_entry_mut(id, |heap, data| {
+        let HeapData::DefaultDict(default_dict) = data else {
+            return Ok(None);
+        };
+        if matches!(default_dict.default_factory, Value::None) || default_dict.dict.get(key, heap, interns)?.is_some() {
+            return Ok(None);
+        }
+        Ok(Some(default_dict.default_factory.clone_with_heap(heap)))
+    })
+}
@@ -0,0 +1,547 @@
+use std::{collections::VecDeque, fmt::Write};
+
+use ahash::AHashSet;
+
+use super::{List, MontyIter, PyTrait, list::parse_index_count_args};
+use crate::{
+    args::{ArgValues, bind_args},
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{AttrCallResult, Type},
+    value::{EitherStr, Value},
+};
+
+/// Python `collections.deque` type, a double-ended queue with an optional maximum length.
+///
+/// # Implemented Methods
+/// - `append(x)` / `appendleft(x)` - Add an item to either end
+/// - `pop()` / `popleft()` - Remove and return an item from either end
+/// - `extend(iterable)` / `extendleft(iterable)` - Add items to either end
+/// - `rotate([n])` - Rotate `n` steps to the right (left if negative)
+/// - `insert(i, x)` - Insert an item at position `i`
+/// - `remove(x)`, `index(x[, start[, stop]])`, `count(x)`
+/// - `clear()`, `copy()`, `reverse()`
+/// - `maxlen` attribute
+///
+/// When the deque is full, adding an item to one end discards an item from the other.
+///
+/// # GC Optimization
+/// Like `List`, `contains_refs` records whether any item has ever been a `Value::Ref`, so
+/// `py_dec_ref_ids` can skip iterating deques of primitives.
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Deque {
+    items: VecDeque<Value>,
+    /// The maximum length, `None` for an unbounded deque.
+    maxlen: Option<usize>,
+    /// True if any item in the deque is a `Value::Ref`.
+    contains_refs: bool,
+}
+
+impl Deque {
+    /// Creates a new empty deque with the given maximum length.
+    #[must_use]
+    pub fn new(maxlen: Option<usize>) -> Self {
+        Self {
+            items: VecDeque::new(),
+            maxlen,
+            contains_refs: false,
+        }
+    }
+
+    /// Returns the number of items.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns whether the deque contains any heap references.
+    #[inline]
+    #[must_use]
+    pub fn contains_refs(&self) -> bool {
+        self.contains_refs
+    }
+
+    /// Returns the item at `index`, if any.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&Value> {
+        self.items.get(index)
+    }
+
+    /// Returns an iterator over the items from left to right.
+    pub fn iter(&self) -> std::collections::vec_deque::Iter<'_, Value> {
+        self.items.iter()
+    }
+
+    /// Appends an item to the right end, discarding the leftmost item if the deque is full.
+    ///
+    /// The caller transfers ownership of `item` to the deque.
+    pub fn push_back(&mut self, item: Value, heap: &mut Heap<impl ResourceTracker>) {
+        if self.maxlen == Some(0) {
+            item.drop_with_heap(heap);
+            return;
+        }
+        if self.maxlen == Some(self.items.len()) {
+            self.items.pop_front().drop_with_heap(heap);
+        }
+        self.mark_ref(&item, heap);
+        self.items.push_back(item);
+    }
+
+    /// Appends an item to the left end, discarding the rightmost item if the deque is full.
+    ///
+    /// The caller transfers ownership of `item` to the deque.
+    pub fn push_front(&mut self, item: Value, heap: &mut Heap<impl ResourceTracker>) {
+        if self.maxlen == Some(0) {
+            item.drop_with_heap(heap);
+            return;
+        }
+        if self.maxlen == Some(self.items.len()) {
+            self.items.pop_back().drop_with_heap(heap);
+        }
+        self.mark_ref(&item, heap);
+        self.items.push_front(item);
+    }
+
+    /// Updates `contains_refs` before `item` is stored in the deque.
+    fn mark_ref(&mut self, item: &Value, heap: &mut Heap<impl ResourceTracker>) {
+        if matches!(item, Value::Ref(_)) {
+            self.contains_refs = true;
+            heap.mark_potential_cycle();
+        }
+    }
+
+    /// Creates a deque from the `deque()` constructor call.
+    ///
+    /// - `deque()` returns an empty, unbounded deque
+    /// - `deque(iterable)` fills the deque from the iterable
+    /// - `deque(iterable, maxlen)` keeps only the last `maxlen` items
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let [iterable, maxlen] = bind_args("deque", ["iterable", "maxlen"], 0, args, heap, interns)?.0;
+        let maxlen = match maxlen {
+            None | Some(Value::None) => None,
+            Some(value) => {
+                let result = maxlen_from_value(&value);
+                value.drop_with_heap(heap);
+                match result {
+                    Ok(maxlen) => Some(maxlen),
+                    Err(err) => {
+                        iterable.drop_with_heap(heap);
+                        return Err(err);
+                    }
+                }
+            }
+        };
+
+        let mut deque = Self::new(maxlen);
+        if let Some(iterable) = iterable {
+            let mut iter = MontyIter::new(iterable, heap, interns)?;
+            let items: RunResult<Vec<Value>> = iter.collect(heap, interns);
+            iter.drop_with_heap(heap);
+            for item in items? {
+                deque.push_back(item, heap);
+            }
+        }
+        Ok(Value::Ref(heap.allocate(HeapData::Deque(deque))?))
+    }
+
+    /// Converts a Python index into a position in the deque.
+    fn index_position(&self, key: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<usize> {
+        let index = match key {
+            Value::Int(i) => *i,
+            Value::Bool(b) => i64::from(*b),
+            Value::Ref(id) if matches!(heap.get(*id), HeapData::LongInt(_)) => return Err(index_error()),
+            _ => {
+                return Err(ExcType::type_error(format!(
+                    "sequence index must be integer, not '{}'",
+                    key.py_type(heap)
+                )));
+            }
+        };
+        let len = i64::try_from(self.items.len()).expect("deque length exceeds i64::MAX");
+        let normalized = if index < 0 { index + len } else { index };
+        usize::try_from(normalized)
+            .ok()
+            .filter(|&position| position < self.items.len())
+            .ok_or_else(index_error)
+    }
+
+    /// Returns the position of the first item equal to `value`, if any.
+    fn find(
+        &self,
+        value: &Value,
+        range: std::ops::Range<usize>,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> Result<Option<usize>, ResourceError> {
+        let mut guard = DepthGuard::default();
+        for position in range {
+            if value.py_eq(&self.items[position], heap, &mut guard, interns)? {
+                return Ok(Some(position));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Parses the `maxlen` argument of `deque()`.
+fn maxlen_from_value(value: &Value) -> RunResult<usize> {
+    let maxlen = match value {
+        Value::Int(i) => *i,
+        Value::Bool(b) => i64::from(*b),
+        _ => return Err(ExcType::type_error("an integer is required")),
+    };
+    usize::try_from(maxlen)
+        .map_err(|_| SimpleException::new_msg(ExcType::ValueError, "maxlen must be non-negative").into())
+}
+
+/// The `IndexError` raised for deque indices out of range.
+fn index_error() -> RunError {
+    SimpleException::new_msg(ExcType::IndexError, "deque index out of range").into()
+}
+
+/// The `IndexError` raised when popping from an empty deque.
+fn pop_empty_error() -> RunError {
+    SimpleException::new_msg(ExcType::IndexError, "pop from an empty deque").into()
+}
+
+/// The `ValueError` raised by `remove()` and `index()` when the value isn't in the deque.
+fn not_in_deque_error(value: &Value, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> RunError {
+    let repr = value.py_repr(heap, &mut DepthGuard::default(), interns);
+    SimpleException::new_msg(ExcType::ValueError, format!("{repr} is not in deque")).into()
+}
+
+impl PyTrait for Deque {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::Deque
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.items.len() * std::mem::size_of::<Value>()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        Some(self.items.len())
+    }
+
+    fn py_getitem(&self, key: &Value, heap: &mut Heap<impl ResourceTracker>, _interns: &Interns) -> RunResult<Value> {
+        let position = self.index_position(key, heap)?;
+        Ok(self.items[position].clone_with_heap(heap))
+    }
+
+    fn py_setitem(
+        &mut self,
+        key: Value,
+        value: Value,
+        heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<()> {
+        let position = self.index_position(&key, heap);
+        key.drop_with_heap(heap);
+        let position = match position {
+            Ok(position) => position,
+            Err(err) => {
+                value.drop_with_heap(heap);
+                return Err(err);
+            }
+        };
+        self.mark_ref(&value, heap);
+        std::mem::replace(&mut self.items[position], value).drop_with_heap(heap);
+        Ok(())
+    }
+
+    fn py_delitem(&mut self, key: &Value, heap: &mut Heap<impl ResourceTracker>, _interns: &Interns) -> RunResult<()> {
+        let position = self.index_position(key, heap)?;
+        self.items.remove(position).drop_with_heap(heap);
+        Ok(())
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        heap: &mut Heap<impl ResourceTracker>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        if self.items.len() != other.items.len() {
+            return Ok(false);
+        }
+        guard.increase_err()?;
+        for (a, b) in self.items.iter().zip(&other.items) {
+            if !a.py_eq(b, heap, guard, interns)? {
+                guard.decrease();
+                return Ok(false);
+            }
+        }
+        guard.decrease();
+        Ok(true)
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        if !self.contains_refs {
+            return;
+        }
+        for obj in &mut self.items {
+            if let Value::Ref(id) = obj {
+                stack.push(*id);
+                #[cfg(feature = "ref-count-panic")]
+                obj.dec_ref_forget();
+            }
+        }
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        !self.items.is_empty()
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        heap: &Heap<impl ResourceTracker>,
+        heap_ids: &mut AHashSet<HeapId>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        if !guard.increase() {
+            return f.write_str("...");
+        }
+
+        f.write_str("deque([")?;
+        let mut first = true;
+        for item in &self.items {
+            if !first {
+                f.write_str(", ")?;
+            }
+            first = false;
+            item.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+        }
+        f.write_char(']')?;
+        if let Some(maxlen) = self.maxlen {
+            write!(f, ", maxlen={maxlen}")?;
+        }
+        f.write_char(')')?;
+
+        guard.decrease();
+        Ok(())
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let Some(method) = attr.static_string() else {
+            args.drop_with_heap(heap);
+            return Err(ExcType::attribute_error(Type::Deque, attr.as_str(interns)));
+        };
+
+        match method {
+            StaticStrings::Append => {
+                let item = args.get_one_arg("deque.append", heap)?;
+                self.push_back(item, heap);
+                Ok(Value::None)
+            }
+            StaticStrings::Appendleft => {
+                let item = args.get_one_arg("deque.appendleft", heap)?;
+                self.push_front(item, heap);
+                Ok(Value::None)
+            }
+            StaticStrings::Pop => {
+                args.check_zero_args("deque.pop", heap)?;
+                self.items.pop_back().ok_or_else(pop_empty_error)
+            }
+            StaticStrings::Popleft => {
+                args.check_zero_args("deque.popleft", heap)?;
+                self.items.pop_front().ok_or_else(pop_empty_error)
+            }
+            StaticStrings::Extend | StaticStrings::Extendleft => {
+                let left = method == StaticStrings::Extendleft;
+                let name = if left { "deque.extendleft" } else { "deque.extend" };
+                let iterable = args.get_one_arg(name, heap)?;
+                let mut iter = MontyIter::new(iterable, heap, interns)?;
+                let items: RunResult<Vec<Value>> = iter.collect(heap, interns);
+                iter.drop_with_heap(heap);
+                for item in items? {
+                    if left {
+                        self.push_front(item, heap);
+                    } else {
+                        self.push_back(item, heap);
+                    }
+                }
+                Ok(Value::None)
+            }
+            StaticStrings::Rotate => {
+                let steps = match args.get_zero_one_arg("deque.rotate", heap)? {
+                    Some(value) => {
+                        let result = value.as_int(heap);
+                        value.drop_with_heap(heap);
+                        result?
+                    }
+                    None => 1,
+                };
+                let len = i64::try_from(self.items.len()).expect("deque length exceeds i64::MAX");
+                if len > 0 {
+                    let steps = usize::try_from(steps.rem_euclid(len)).expect("rem_euclid is non-negative");
+                    self.items.rotate_right(steps);
+                }
+                Ok(Value::None)
+            }
+            StaticStrings::Insert => {
+                let (index, item) = args.get_two_args("deque.insert", heap)?;
+                let index_result = index.as_int(heap);
+                index.drop_with_heap(heap);
+                let index = match index_result {
+                    Ok(index) => index,
+                    Err(err) => {
+                        item.drop_with_heap(heap);
+                        return Err(err);
+                    }
+                };
+                if self.maxlen == Some(self.items.len()) {
+                    item.drop_with_heap(heap);
+                    return Err(
+                        SimpleException::new_msg(ExcType::IndexError, "deque already at its maximum size").into(),
+                    );
+                }
+                let len = self.items.len();
+                let len_i64 = i64::try_from(len).expect("deque length exceeds i64::MAX");
+                let position = if index < 0 {
+                    usize::try_from(index + len_i64).unwrap_or(0)
+                } else {
+                    usize::try_from(index).unwrap_or(len).min(len)
+                };
+                self.mark_ref(&item, heap);
+                self.items.insert(position, item);
+                Ok(Value::None)
+            }
+            StaticStrings::Remove => {
+                let value = args.get_one_arg("deque.remove", heap)?;
+                let found = self.find(&value, 0..self.items.len(), heap, interns);
+                let result = match found {
+                    Ok(Some(position)) => {
+                        self.items.remove(position).drop_with_heap(heap);
+                        Ok(Value::None)
+                    }
+                    Ok(None) => Err(not_in_deque_error(&value, heap, interns)),
+                    Err(err) => Err(err.into()),
+                };
+                value.drop_with_heap(heap);
+                result
+            }
+            StaticStrings::Index => deque_index(self, args, heap, interns),
+            StaticStrings::Count => {
+                let value = args.get_one_arg("deque.count", heap)?;
+                let mut guard = DepthGuard::default();
+                let mut count: i64 = 0;
+                let mut result = Ok(());
+                for item in &self.items {
+                    match value.py_eq(item, heap, &mut guard, interns) {
+                        Ok(true) => count += 1,
+                        Ok(false) => {}
+                        Err(err) => {
+                            result = Err(err);
+                            break;
+                        }
+                    }
+                }
+                value.drop_with_heap(heap);
+                result?;
+                Ok(Value::Int(count))
+            }
+            StaticStrings::Clear => {
+                args.check_zero_args("deque.clear", heap)?;
+                for item in self.items.drain(..) {
+                    item.drop_with_heap(heap);
+                }
+                Ok(Value::None)
+            }
+            StaticStrings::Copy => {
+                args.check_zero_args("deque.copy", heap)?;
+                let copy = Self {
+                    items: self.items.iter().map(|item| item.clone_with_heap(heap)).collect(),
+                    maxlen: self.maxlen,
+                    contains_refs: self.contains_refs,
+                };
+                Ok(Value::Ref(heap.allocate(HeapData::Deque(copy))?))
+            }
+            StaticStrings::Reverse => {
+                args.check_zero_args("deque.reverse", heap)?;
+                self.items.make_contiguous().reverse();
+                Ok(Value::None)
+            }
+            _ => {
+                args.drop_with_heap(heap);
+                Err(ExcType::attribute_error(Type::Deque, method.into()))
+            }
+        }
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::Maxlen) => {
+                let maxlen = self.maxlen.map_or(Value::None, |maxlen| {
+                    Value::Int(i64::try_from(maxlen).expect("maxlen exceeds i64::MAX"))
+                });
+                Ok(Some(AttrCallResult::Value(maxlen)))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Implements `deque.index(value[, start[, stop]])`.
+fn deque_index(
+    deque: &Deque,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let (value, start, stop) = parse_index_count_args("deque.index", deque.items.len(), args, heap)?;
+    let found = deque.find(&value, start..stop, heap, interns);
+    let result = match found {
+        Ok(Some(position)) => Ok(Value::Int(i64::try_from(position).expect("index exceeds i64::MAX"))),
+        Ok(None) => Err(not_in_deque_error(&value, heap, interns)),
+        Err(err) => Err(err.into()),
+    };
+    value.drop_with_heap(heap);
+    result
+}
+
+/// Calls a method on the deque at `id`.
+///
+/// `d.extend(d)` and `d.extendleft(d)` would iterate over the deque while it is taken out of
+/// the heap for the call, so those arguments are copied into a list first.
+pub(crate) fn call_deque_method(
+    id: HeapId,
+    attr: &EitherStr,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let args = match args {
+        ArgValues::One(Value::Ref(arg_id)) if arg_id == id => {
+            let HeapData::Deque(deque) = heap.get(id) else {
+                unreachable!("call_deque_method called on a non-deque")
+            };
+            let items: Vec<Value> = deque.iter().map(Value::copy_for_extend).collect();
+            for item in &items {
+                if let Value::Ref(item_id) = item {
+                    heap.inc_ref(*item_id);
+                }
+            }
+            Value::Ref(arg_id).drop_with_heap(heap);
+            let list_id = heap.allocate(HeapData::List(List::new(items)))?;
+            ArgValues::One(Value::Ref(list_id))
+        }
+        args => args,
+    };
+    heap.with_entry_mut(id, |heap, data| data.py_call_attr(heap, attr, args, interns))
+}
@@ -12,7 +12,7 @@ use super::{List, MontyIter, PyTrait, allocate_tuple};
 use crate::{
     args::{ArgValues, KwargsValues},
     exception_private::{ExcType, RunResult},
-    heap::{Heap, HeapData, HeapId},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
     intern::{Interns, StaticStrings},
     resource::{DepthGuard, ResourceError, ResourceTracker},
     types::Type,
@@ -309,8 +309,15 @@ impl Dict {
         );
 
         if let Entry::Occupied(occ_entry) = entry {
-            let entry = self.entries.remove(*occ_entry.get());
+            let index = *occ_entry.get();
+            let entry = self.entries.remove(index);
             occ_entry.remove();
+            // Entries after the removed one have shifted down by one
+            for other in self.indices.iter_mut() {
+                if *other > index {
+                    *other -= 1;
+                }
+            }
             // Don't decrement refcounts - caller now owns the values
             Ok(Some((entry.key, entry.value)))
         } else {
@@ -371,6 +378,14 @@ impl Dict {
         self.into_iter()
     }
 
+    /// Returns the keys stored under `hash`, in insertion order.
+    ///
+    /// Used by the VM to find keys equal to an instance of a user-defined class, whose
+    /// `__eq__` can't be called from here.
+    pub fn keys_with_hash(&self, hash: u64) -> impl Iterator<Item = &Value> {
+        self.entries.iter().filter(move |e| e.hash == hash).map(|e| &e.key)
+    }
+
     /// Returns the key at the given iteration index, or None if out of bounds.
     ///
     /// Used for index-based iteration in for loops. Returns a reference to
@@ -379,6 +394,73 @@ impl Dict {
         self.entries.get(index).map(|e| &e.key)
     }
 
+    /// Returns a shallow copy of the dict, incrementing the refcounts of all keys and values.
+    pub fn clone_with_heap(&self, heap: &mut Heap<impl ResourceTracker>) -> Self {
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| DictEntry {
+                key: entry.key.clone_with_heap(heap),
+                value: entry.value.clone_with_heap(heap),
+                hash: entry.hash,
+            })
+            .collect();
+        Self {
+            indices: self.indices.clone(),
+            entries,
+            contains_refs: self.contains_refs,
+        }
+    }
+
+    /// Removes and returns the last (or, if `last` is false, the first) inserted pair.
+    ///
+    /// Returns `None` if the dict is empty. The caller takes ownership of the pair.
+    pub fn pop_end(&mut self, last: bool) -> Option<(Value, Value)> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let entry = if last {
+            self.entries.pop().expect("dict is not empty")
+        } else {
+            self.entries.remove(0)
+        };
+        // TODO: This O(n) rebuild could be optimized by finding and removing the
+        // specific hash entry directly from the hashbrown table.
+        self.rebuild_indices();
+        Some((entry.key, entry.value))
+    }
+
+    /// Moves an existing key to the end (or, if `last` is false, the start) of the dict.
+    ///
+    /// Returns `Ok(false)` if the key isn't in the dict.
+    pub fn move_to_end(
+        &mut self,
+        key: &Value,
+        last: bool,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<bool> {
+        let Some(index) = self.find_index_hash(key, heap, interns)?.0 else {
+            return Ok(false);
+        };
+        let entry = self.entries.remove(index);
+        if last {
+            self.entries.push(entry);
+        } else {
+            self.entries.insert(0, entry);
+        }
+        self.rebuild_indices();
+        Ok(true)
+    }
+
+    /// Rebuilds the hash table after entries have been removed or reordered.
+    fn rebuild_indices(&mut self) {
+        self.indices.clear();
+        for (idx, e) in self.entries.iter().enumerate() {
+            self.indices.insert_unique(e.hash, idx, |&i| self.entries[i].hash);
+        }
+    }
+
     /// Creates a dict from the `dict()` constructor call.
     ///
     /// - `dict()` with no args returns an empty dict
@@ -401,8 +483,8 @@ impl Dict {
                 };
                 let id = *id;
 
-                // Check if it's a dict and get key-value pairs
-                let HeapData::Dict(dict) = heap.get(id) else {
+                // Check if it's a dict (or dict subclass) and get key-value pairs
+                let Some(dict) = heap.get(id).as_dict() else {
                     let err = ExcType::type_error_not_iterable(v.py_type(heap));
                     v.drop_with_heap(heap);
                     return Err(err);
@@ -458,6 +540,13 @@ impl Dict {
     }
 }
 
+impl<T: ResourceTracker> DropWithHeap<T> for Dict {
+    /// Drops all keys and values of a dict that was never allocated on the heap.
+    fn drop_with_heap(mut self, heap: &mut Heap<T>) {
+        self.drop_all_entries(heap);
+    }
+}
+
 /// Iterator over borrowed (key, value) pairs in a dict.
 pub(crate) struct DictIter<'a>(std::slice::Iter<'a, DictEntry>);
 
@@ -628,6 +717,17 @@ impl PyTrait for Dict {
         Ok(())
     }
 
+    fn py_delitem(&mut self, key: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<()> {
+        match self.pop(key, heap, interns)? {
+            Some((old_key, old_value)) => {
+                old_key.drop_with_heap(heap);
+                old_value.drop_with_heap(heap);
+                Ok(())
+            }
+            None => Err(ExcType::key_error(key, heap, interns)),
+        }
+    }
+
     fn py_call_attr(
         &mut self,
         heap: &mut Heap<impl ResourceTracker>,
@@ -744,6 +844,24 @@ impl PyTrait for Dict {
     }
 }
 
+/// Returns whether `method` is a `dict` method, so dict subclasses can delegate to it.
+pub(crate) fn is_dict_method(method: StaticStrings) -> bool {
+    matches!(
+        method,
+        StaticStrings::Get
+            | StaticStrings::Keys
+            | StaticStrings::Values
+            | StaticStrings::Items
+            | StaticStrings::Pop
+            | StaticStrings::Clear
+            | StaticStrings::Copy
+            | StaticStrings::Update
+            | StaticStrings::Setdefault
+            | StaticStrings::Popitem
+            | StaticStrings::Fromkeys
+    )
+}
+
 /// Implements Python's `dict.clear()` method.
 ///
 /// Removes all items from the dict.
@@ -777,7 +895,7 @@ fn dict_copy(dict: &Dict, heap: &mut Heap<impl ResourceTracker>, interns: &Inter
 /// If `other` is a dict, copies its key-value pairs.
 /// If `other` is an iterable, expects pairs of (key, value).
 /// Keyword arguments are also added to the dict.
-fn dict_update(
+pub(crate) fn dict_update(
     dict: &mut Dict,
     args: ArgValues,
     heap: &mut Heap<impl ResourceTracker>,
@@ -807,20 +925,14 @@ fn dict_update(
         return dict_update_from_kwargs(dict, kwargs, heap, interns);
     };
 
-    // Check if it's a dict first
+    // Check if it's a dict (or dict subclass) first
     if let Value::Ref(id) = &other_value {
-        let is_dict = matches!(heap.get(*id), HeapData::Dict(_));
-        if is_dict {
+        if let Some(src_dict) = heap.get(*id).as_dict() {
             // Get key-value pairs from the source dict
-            let pairs: Vec<(Value, Value)> = {
-                let HeapData::Dict(src_dict) = heap.get(*id) else {
-                    unreachable!()
-                };
-                src_dict
-                    .iter()
-                    .map(|(k, v)| (k.copy_for_extend(), v.copy_for_extend()))
-                    .collect()
-            };
+            let pairs: Vec<(Value, Value)> = src_dict
+                .iter()
+                .map(|(k, v)| (k.copy_for_extend(), v.copy_for_extend()))
+                .collect();
 
             // Increment refcounts after releasing the borrow
             for (k, v) in &pairs {
@@ -1040,25 +1152,13 @@ fn dict_setdefault(
 /// Removes and returns the last inserted key-value pair as a tuple.
 /// Raises KeyError if the dict is empty.
 fn dict_popitem(dict: &mut Dict, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
-    if dict.is_empty() {
-        return Err(ExcType::key_error_popitem_empty_dict());
-    }
-
     // Remove the last entry (LIFO order)
-    let entry = dict.entries.pop().expect("dict is not empty");
-
-    // Remove from indices - need to find the entry with this index
-    // Since we removed the last entry, we need to clear and rebuild indices
-    // (This is simpler than trying to find and remove the specific hash entry)
-    // TODO: This O(n) rebuild could be optimized by finding and removing the
-    // specific hash entry directly from the hashbrown table.
-    dict.indices.clear();
-    for (idx, e) in dict.entries.iter().enumerate() {
-        dict.indices.insert_unique(e.hash, idx, |&i| dict.entries[i].hash);
-    }
+    let Some((key, value)) = dict.pop_end(true) else {
+        return Err(ExcType::key_error_popitem_empty_dict());
+    };
 
     // Create tuple (key, value)
-    Ok(allocate_tuple(smallvec![entry.key, entry.value], heap)?)
+    Ok(allocate_tuple(smallvec![key, value], heap)?)
 }
 
 // Custom serde implementation for Dict.
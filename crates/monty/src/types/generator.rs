@@ -0,0 +1,148 @@
+//! Generator objects created by calling generator functions.
+//!
+//! A generator owns the saved state of its suspended frame: the namespace, the frame's
+//! operand stack region, any active exceptions, and the instruction pointer to resume at.
+//! While the generator is running this state lives in a VM frame instead, and the
+//! generator's own fields are empty.
+//!
+//! Async generators (`async def` functions containing `yield`) use the same object with
+//! `is_async` set. They are stepped by awaiting the `AsyncGenAwaitable` returned by
+//! `__anext__()`, `asend()` or `aclose()`, so their bodies can `await` between yields.
+
+use crate::{heap::HeapId, intern::FunctionId, value::Value};
+
+/// Execution state of a generator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) enum GeneratorState {
+    /// Created but not yet started; the body runs on the first `next()`/`send(None)`.
+    Created,
+    /// Paused at a `yield`.
+    Suspended,
+    /// Currently executing in a VM frame.
+    Running,
+    /// Returned or raised; further `next()` calls raise `StopIteration`.
+    Completed,
+}
+
+/// A generator object representing a generator function call.
+///
+/// Arguments are bound when the generator function is called; the body runs lazily as
+/// values are requested. The namespace layout is the same as for a regular function frame:
+/// ```text
+/// [params...][cell_vars...][free_vars...][locals...]
+/// ```
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Generator {
+    /// The generator function being executed.
+    pub func_id: FunctionId,
+    /// Saved namespace values while the generator is not running.
+    pub namespace: Vec<Value>,
+    /// HeapIds of the frame's cells.
+    ///
+    /// Like a regular frame's cells these don't own a reference - the namespace does.
+    pub frame_cells: Vec<HeapId>,
+    /// Instruction pointer to resume at.
+    pub ip: usize,
+    /// Saved operand stack region of the frame (e.g. iterators of active `for` loops).
+    pub stack: Vec<Value>,
+    /// Saved exceptions being handled by the frame (a `yield` inside an `except` block).
+    pub exception_stack: Vec<Value>,
+    /// Current execution state.
+    pub state: GeneratorState,
+    /// Whether this is an async generator, which is stepped by awaiting `__anext__()`.
+    pub is_async: bool,
+}
+
+impl Generator {
+    /// Creates a new generator for a generator function call.
+    ///
+    /// # Arguments
+    /// * `func_id` - The generator function to execute
+    /// * `namespace` - Pre-bound namespace with parameters and captured variables
+    /// * `frame_cells` - HeapIds of the frame's cells
+    /// * `is_async` - Whether the function is an async generator function
+    pub fn new(func_id: FunctionId, namespace: Vec<Value>, frame_cells: Vec<HeapId>, is_async: bool) -> Self {
+        Self {
+            func_id,
+            namespace,
+            frame_cells,
+            ip: 0,
+            stack: Vec::new(),
+            exception_stack: Vec::new(),
+            state: GeneratorState::Created,
+            is_async,
+        }
+    }
+
+    /// Iterates over all values owned by the generator's saved frame state.
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.namespace.iter().chain(&self.stack).chain(&self.exception_stack)
+    }
+
+    /// Returns whether the saved frame state contains any heap references.
+    pub fn has_refs(&self) -> bool {
+        self.values().any(|v| matches!(v, Value::Ref(_)))
+    }
+
+    /// Collects the heap references owned by the generator for decrementing.
+    pub fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        for value in self
+            .namespace
+            .iter_mut()
+            .chain(&mut self.stack)
+            .chain(&mut self.exception_stack)
+        {
+            value.py_dec_ref_ids(stack);
+        }
+    }
+
+    /// Estimates the memory used by the generator and its saved frame state.
+    pub fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + (self.namespace.len() + self.stack.len() + self.exception_stack.len()) * std::mem::size_of::<Value>()
+            + self.frame_cells.len() * std::mem::size_of::<HeapId>()
+    }
+}
+
+/// What awaiting an `AsyncGenAwaitable` does to its async generator.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) enum AsyncGenAction {
+    /// Resume the generator with a value, from `__anext__()` (`None`) or `asend(value)`.
+    Send(Value),
+    /// Raise `GeneratorExit` at the suspended `yield`, from `aclose()`.
+    Close,
+    /// Already awaited; awaiting again raises `RuntimeError`.
+    Awaited,
+}
+
+/// The awaitable returned by `__anext__()`, `asend()` and `aclose()` of an async generator.
+///
+/// Awaiting it runs the generator until its next `yield`, whose value becomes the result
+/// of the `await`. CPython has separate `async_generator_asend`/`async_generator_athrow`
+/// types; here both are this object and report their type as `coroutine`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct AsyncGenAwaitable {
+    /// The async generator to step, the awaitable owns a reference to it.
+    pub generator: HeapId,
+    /// What to do when awaited.
+    pub action: AsyncGenAction,
+}
+
+impl AsyncGenAwaitable {
+    /// Collects the heap references owned by the awaitable for decrementing.
+    pub fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        stack.push(self.generator);
+        if let AsyncGenAction::Send(value) = &mut self.action {
+            value.py_dec_ref_ids(stack);
+        }
+    }
+
+    /// Returns the heap references owned by the awaitable, for GC marking.
+    pub fn ref_ids(&self) -> impl Iterator<Item = HeapId> {
+        let sent = match &self.action {
+            AsyncGenAction::Send(value) => value.ref_id(),
+            AsyncGenAction::Close | AsyncGenAction::Awaited => None,
+        };
+        std::iter::once(self.generator).chain(sent)
+    }
+}
@@ -29,14 +29,21 @@
 //! ## Builtin Support
 //!
 //! The `iterator_next()` helper implements the `next()` builtin.
+//!
+//! `map()`, `filter()` and `iter(callable, sentinel)` create iterators whose items come from
+//! calling a function (see [`CallingIter`]). Only the VM can make those calls, so it
+//! advances these iterators itself rather than through `advance_on_heap()`.
+
+use smallvec::smallvec;
 
 use crate::{
     args::ArgValues,
-    exception_private::{ExcType, RunResult},
+    builtins::is_callable,
+    exception_private::{ExcType, RunError, RunResult},
     heap::{DropWithHeap, Heap, HeapData, HeapId},
     intern::{BytesId, Interns, StringId},
     resource::ResourceTracker,
-    types::{PyTrait, Range, str::allocate_char},
+    types::{PyTrait, Range, allocate_tuple, str::allocate_char, tuple::TupleVec},
     value::Value,
 };
 
@@ -61,21 +68,35 @@ impl MontyIter {
     ///
     /// - `iter(iterable)` - Returns an iterator for the iterable. If the argument is
     ///   already an iterator, returns the same object.
-    /// - `iter(callable, sentinel)` - Not yet supported.
+    /// - `iter(callable, sentinel)` - Returns an iterator that calls `callable` with no
+    ///   arguments until it returns `sentinel`.
     pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
         let (iterable, sentinel) = args.get_one_two_args("iter", heap)?;
 
-        if let Some(s) = sentinel {
-            // Two-argument form: iter(callable, sentinel)
-            // This is the sentinel iteration protocol, not yet supported
-            iterable.drop_with_heap(heap);
-            s.drop_with_heap(heap);
-            return Err(ExcType::type_error("iter(callable, sentinel) is not yet supported"));
+        if let Some(sentinel) = sentinel {
+            if !is_callable(&iterable, heap) {
+                iterable.drop_with_heap(heap);
+                sentinel.drop_with_heap(heap);
+                return Err(ExcType::type_error("iter(v, w): v must be callable"));
+            }
+            let parts = smallvec![iterable, sentinel];
+            return Self::new_calling(CallingIter::Sentinel, parts, heap);
         }
+        Self::iterator_for(iterable, heap, interns)
+    }
 
-        // Check if already an iterator - return self
+    /// Returns an iterator over `iterable`, like `iter(iterable)`.
+    ///
+    /// Iterators and (non-async) generators are returned unchanged. Takes ownership of
+    /// `iterable`.
+    pub fn iterator_for(iterable: Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        // Check if already an iterator (or a generator, but not an async one) - return self
         if let Value::Ref(id) = &iterable
-            && matches!(heap.get(*id), HeapData::Iter(_))
+            && match heap.get(*id) {
+                HeapData::Iter(_) => true,
+                HeapData::Generator(generator) => !generator.is_async,
+                _ => false,
+            }
         {
             // Already an iterator - return it (refcount already correct from caller)
             return Ok(iterable);
@@ -87,6 +108,21 @@ impl MontyIter {
         Ok(Value::Ref(id))
     }
 
+    /// Allocates an iterator whose items come from calling a function.
+    ///
+    /// `parts` holds the values the VM needs to produce items, as described on
+    /// [`CallingIter`]. Takes ownership of `parts`.
+    pub fn new_calling(kind: CallingIter, parts: TupleVec, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+        let value = allocate_tuple(parts, heap)?;
+        let iter = Self {
+            index: 0,
+            iter_value: IterValue::Calling(kind),
+            value,
+        };
+        let id = heap.allocate(HeapData::Iter(iter))?;
+        Ok(Value::Ref(id))
+    }
+
     /// Creates a new MontyIter from a Value.
     ///
     /// Returns an error if the value is not iterable.
@@ -140,6 +176,23 @@ impl MontyIter {
         &self.value
     }
 
+    /// Returns how the items are produced if this iterator calls a function.
+    ///
+    /// The VM advances these iterators, using the tuple returned by [`Self::value`].
+    #[must_use]
+    pub fn calling(&self) -> Option<CallingIter> {
+        match self.iter_value {
+            IterValue::Calling(kind) => Some(kind),
+            _ => None,
+        }
+    }
+
+    /// Marks the iterator as exhausted, returning the value it held for the caller to drop.
+    pub fn exhaust(&mut self) -> Value {
+        self.iter_value = IterValue::Exhausted;
+        std::mem::replace(&mut self.value, Value::None)
+    }
+
     /// Returns the current iterator state without mutation.
     ///
     /// This is used by the multi-phase approach in `advance_on_heap()` for complex
@@ -150,9 +203,10 @@ impl MontyIter {
     fn iter_state(&self) -> Option<IterState> {
         match &self.iter_value {
             // Range, InternBytes, and ASCII IterStr are handled by try_advance_simple() fast path
-            IterValue::Range { .. } | IterValue::InternBytes { .. } => {
-                unreachable!("Range and InternBytes use fast path, not iter_state")
+            IterValue::Range { .. } | IterValue::InternBytes { .. } | IterValue::Exhausted => {
+                unreachable!("Range, InternBytes and exhausted iterators use fast path, not iter_state")
             }
+            IterValue::Calling(_) => unreachable!("calling iterators are advanced by the VM"),
             IterValue::IterStr {
                 string,
                 byte_offset,
@@ -260,6 +314,8 @@ impl MontyIter {
                 }
             }
             IterValue::HeapRef { .. } => None,
+            IterValue::Calling(_) => Some(Err(calling_iter_error())),
+            IterValue::Exhausted => Some(Ok(None)),
         }
     }
 
@@ -333,24 +389,27 @@ impl MontyIter {
                 self.index += 1;
                 Ok(Some(clone_and_inc_ref(item, heap)))
             }
+            IterValue::Calling(_) => Err(calling_iter_error()),
+            IterValue::Exhausted => Ok(None),
         }
     }
 
     /// Returns the remaining size for iterables based on current state.
     ///
     /// For immutable types (Range, Tuple, Str, Bytes, FrozenSet), returns the exact remaining count.
-    /// For List, returns current length minus index (may change if list is mutated).
+    /// For List and ByteArray, returns current length minus index (may change if they are mutated).
     /// For Dict and Set, returns the captured length minus index (used for size-change detection).
     pub fn size_hint(&self, heap: &Heap<impl ResourceTracker>) -> usize {
         let len = match &self.iter_value {
+            // The number of items is unknown until the function calls are made
+            IterValue::Calling(_) | IterValue::Exhausted => return 0,
             IterValue::Range { len, .. } | IterValue::IterStr { len, .. } | IterValue::InternBytes { len, .. } => *len,
             IterValue::HeapRef { heap_id, len, .. } => {
-                // For List (len=None), check current length dynamically
-                len.unwrap_or_else(|| {
-                    let HeapData::List(list) = heap.get(*heap_id) else {
-                        panic!("HeapRef with len=None should only be List")
-                    };
-                    list.len()
+                // For List and ByteArray (len=None), check current length dynamically
+                len.unwrap_or_else(|| match heap.get(*heap_id) {
+                    HeapData::List(list) => list.len(),
+                    HeapData::ByteArray(bytearray) => bytearray.as_slice().len(),
+                    _ => panic!("HeapRef with len=None should only be List or ByteArray"),
                 })
             }
         };
@@ -461,9 +520,14 @@ pub(crate) fn advance_on_heap(
     Ok(Some(value))
 }
 
+/// Returns the error for advancing a [`CallingIter`] iterator without the VM.
+fn calling_iter_error() -> RunError {
+    RunError::internal("map(), filter() and iter(callable, sentinel) iterators must be advanced by the VM")
+}
+
 /// Gets an item from a heap-allocated container at the given index.
 ///
-/// Returns `Ok(None)` if the index is out of bounds (for lists that shrunk during iteration).
+/// Returns `Ok(None)` if the index is out of bounds (for lists and bytearrays that shrunk during iteration).
 /// Returns `Err` if a dict/set changed size during iteration (RuntimeError).
 fn get_heap_item(
     heap: &Heap<impl ResourceTracker>,
@@ -481,7 +545,8 @@ fn get_heap_item(
         }
         HeapData::Tuple(tuple) => Ok(Some(tuple.as_slice()[index].copy_for_extend())),
         HeapData::NamedTuple(namedtuple) => Ok(Some(namedtuple.as_vec()[index].copy_for_extend())),
-        HeapData::Dict(dict) => {
+        data @ (HeapData::Dict(_) | HeapData::DefaultDict(_) | HeapData::Counter(_) | HeapData::OrderedDict(_)) => {
+            let dict = data.as_dict().expect("dict types have a dict");
             // Check for dict mutation
             if let Some(expected) = expected_len
                 && dict.len() != expected
@@ -492,7 +557,19 @@ fn get_heap_item(
                 dict.key_at(index).expect("index should be valid").copy_for_extend(),
             ))
         }
+        HeapData::Deque(deque) => {
+            // Check for deque mutation
+            if let Some(expected) = expected_len
+                && deque.len() != expected
+            {
+                return Err(ExcType::runtime_error_deque_mutated());
+            }
+            Ok(Some(deque.get(index).expect("index should be valid").copy_for_extend()))
+        }
         HeapData::Bytes(bytes) => Ok(Some(Value::Int(i64::from(bytes.as_slice()[index])))),
+        // Bytearrays can shrink during iteration, and so can the bytearrays memoryviews read from
+        HeapData::ByteArray(bytearray) => Ok(bytearray.as_slice().get(index).map(|&byte| Value::Int(i64::from(byte)))),
+        HeapData::MemoryView(view) => Ok(view.get(index, heap).map(|byte| Value::Int(i64::from(byte)))),
         HeapData::Set(set) => {
             // Check for set mutation
             if let Some(expected) = expected_len
@@ -590,7 +667,7 @@ pub fn iterator_next(
 enum IterState {
     /// String iterator yields this character; char_len is UTF-8 byte length for advance().
     IterStr { char: char, char_len: usize },
-    /// Heap-based iterator (List, Tuple, NamedTuple, Dict, Bytes, Set, FrozenSet).
+    /// Heap-based iterator (List, Tuple, NamedTuple, Dict, Bytes, ByteArray, MemoryView, Set, FrozenSet).
     /// The expected_len is Some for types that check for mutation (Dict, Set).
     HeapIndex {
         heap_id: HeapId,
@@ -611,6 +688,19 @@ fn clone_and_inc_ref(value: Value, heap: &mut Heap<impl ResourceTracker>) -> Val
     value
 }
 
+/// The kind of an iterator whose items come from calling a function.
+///
+/// The iterator's value is a tuple holding what's needed to produce the items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) enum CallingIter {
+    /// `map(func, *iterables)`: the tuple holds `func` followed by an iterator per iterable.
+    Map,
+    /// `filter(func, iterable)`: the tuple holds `func` (or `None`) and an iterator.
+    Filter,
+    /// `iter(callable, sentinel)`: the tuple holds `callable` and `sentinel`.
+    Sentinel,
+}
+
 /// Type-specific iteration data for different Python iterable types.
 ///
 /// Each variant stores the data needed to iterate over a specific type,
@@ -655,6 +745,10 @@ enum IterValue {
         len: Option<usize>,
         checks_mutation: bool,
     },
+    /// Iterating by calling a function, advanced by the VM.
+    Calling(CallingIter),
+    /// An iterator that has stopped for good, such as a `map()` whose iterable ran out.
+    Exhausted,
 }
 
 impl IterValue {
@@ -702,8 +796,8 @@ impl IterValue {
     /// Creates an iterator value from heap data.
     fn from_heap_data(heap_id: HeapId, heap: &Heap<impl ResourceTracker>) -> Option<Self> {
         match heap.get(heap_id) {
-            // List: no captured len (checked dynamically), no mutation check
-            HeapData::List(_) => Some(Self::HeapRef {
+            // List/ByteArray: no captured len (checked dynamically), no mutation check
+            HeapData::List(_) | HeapData::ByteArray(_) => Some(Self::HeapRef {
                 heap_id,
                 len: None,
                 checks_mutation: false,
@@ -729,10 +823,23 @@ impl IterValue {
                 len: Some(frozenset.len()),
                 checks_mutation: false,
             }),
-            // Dict/Set: captured len, WITH mutation check
-            HeapData::Dict(dict) => Some(Self::HeapRef {
+            // MemoryView: captured len, items past the end of a shrunk source end iteration
+            HeapData::MemoryView(view) => Some(Self::HeapRef {
+                heap_id,
+                len: Some(view.len()),
+                checks_mutation: false,
+            }),
+            // Dict/Set/Deque: captured len, WITH mutation check
+            data @ (HeapData::Dict(_) | HeapData::DefaultDict(_) | HeapData::Counter(_) | HeapData::OrderedDict(_)) => {
+                Some(Self::HeapRef {
+                    heap_id,
+                    len: Some(data.as_dict().expect("dict types have a dict").len()),
+                    checks_mutation: true,
+                })
+            }
+            HeapData::Deque(deque) => Some(Self::HeapRef {
                 heap_id,
-                len: Some(dict.len()),
+                len: Some(deque.len()),
                 checks_mutation: true,
             }),
             HeapData::Set(set) => Some(Self::HeapRef {
@@ -744,20 +851,37 @@ impl IterValue {
             HeapData::Str(s) => Some(Self::from_str(s.as_str())),
             // Range: copy values for iteration
             HeapData::Range(range) => Some(Self::from_range(range)),
-            // Closures, FunctionDefaults, Cells, Exceptions, Dataclasses, Iterators, LongInts, Slices, Modules,
-            // Paths, and async types are not iterable
+            // Closures, FunctionDefaults, Cells, Exceptions, Dataclasses, Iterators, LongInts, Complexes, datetime
+            // objects, Slices, Modules, Paths, async types, generators, user-defined classes/instances, regex objects and
+            // code objects are not iterable
             HeapData::Closure(_, _, _)
             | HeapData::FunctionDefaults(_, _)
+            | HeapData::NamedTupleFactory(_)
             | HeapData::Cell(_)
             | HeapData::Exception(_)
             | HeapData::Dataclass(_)
+            | HeapData::DataclassType(_)
             | HeapData::Iter(_)
             | HeapData::LongInt(_)
+            | HeapData::Complex(_)
+            | HeapData::Date(_)
+            | HeapData::DateTime(_)
+            | HeapData::TimeDelta(_)
+            | HeapData::TimeZone(_)
             | HeapData::Slice(_)
             | HeapData::Module(_)
             | HeapData::Path(_)
             | HeapData::Coroutine(_)
-            | HeapData::GatherFuture(_) => None,
+            | HeapData::GatherFuture(_)
+            | HeapData::Generator(_)
+            | HeapData::AsyncGenAwaitable(_)
+            | HeapData::ClassObject(_)
+            | HeapData::Instance(_)
+            | HeapData::BoundMethod(_)
+            | HeapData::SuperProxy(_)
+            | HeapData::RePattern(_)
+            | HeapData::ReMatch(_)
+            | HeapData::CodeObject(_) => None,
         }
     }
 }
@@ -11,8 +11,8 @@ use crate::{
     heap::{DropWithHeap, Heap, HeapData, HeapId},
     intern::{Interns, StaticStrings},
     io::PrintWriter,
-    resource::{DepthGuard, ResourceError, ResourceTracker},
-    types::Type,
+    resource::{DepthGuard, ResourceError, ResourceTracker, check_repeat_size},
+    types::{Slice, Type},
     value::{EitherStr, Value},
 };
 
@@ -133,6 +133,53 @@ impl List {
         self.items.push(item);
     }
 
+    /// Replaces the items selected by `slice` with `items`.
+    ///
+    /// A step of 1 splices in any number of items, resizing the list. Extended slices
+    /// require exactly one item per selected position, matching CPython.
+    /// The caller transfers ownership of `items` to the list.
+    fn set_slice(&mut self, slice: &Slice, items: Vec<Value>, heap: &mut Heap<impl ResourceTracker>) -> RunResult<()> {
+        let len = self.items.len();
+        let Ok((start, stop, step)) = slice.indices(len) else {
+            items.drop_with_heap(heap);
+            return Err(ExcType::value_error_slice_step_zero());
+        };
+
+        if step == 1 {
+            // An empty slice (stop before start) inserts at start
+            let stop = stop.max(start);
+            let new_len = len - (stop - start) + items.len();
+            if let Err(err) = check_repeat_size(std::mem::size_of::<Value>(), new_len, heap.tracker()) {
+                items.drop_with_heap(heap);
+                return Err(err.into());
+            }
+            self.mark_refs(&items, heap);
+            let removed: Vec<Value> = self.items.splice(start..stop, items).collect();
+            removed.drop_with_heap(heap);
+            return Ok(());
+        }
+
+        let positions = slice_positions(start, stop, step, len);
+        if positions.len() != items.len() {
+            let got = items.len();
+            items.drop_with_heap(heap);
+            return Err(ExcType::value_error_extended_slice_size(got, positions.len()));
+        }
+        self.mark_refs(&items, heap);
+        for (position, item) in positions.into_iter().zip(items) {
+            std::mem::replace(&mut self.items[position], item).drop_with_heap(heap);
+        }
+        Ok(())
+    }
+
+    /// Updates `contains_refs` before `items` are stored in the list.
+    fn mark_refs(&mut self, items: &[Value], heap: &mut Heap<impl ResourceTracker>) {
+        if items.iter().any(|v| matches!(v, Value::Ref(_))) {
+            self.contains_refs = true;
+            heap.mark_potential_cycle();
+        }
+    }
+
     /// Inserts an element at the specified index.
     ///
     /// The caller transfers ownership of `item` to the list. The item's refcount
@@ -306,6 +353,33 @@ impl PyTrait for List {
         Ok(())
     }
 
+    fn py_delitem(&mut self, key: &Value, heap: &mut Heap<impl ResourceTracker>, _interns: &Interns) -> RunResult<()> {
+        if let Value::Ref(id) = key
+            && let HeapData::Slice(slice) = heap.get(*id)
+        {
+            let (start, stop, step) = slice
+                .indices(self.items.len())
+                .map_err(|()| ExcType::value_error_slice_step_zero())?;
+            let mut positions = slice_positions(start, stop, step, self.items.len());
+            positions.sort_unstable();
+            // Remove from the back so earlier positions stay valid
+            for position in positions.into_iter().rev() {
+                self.items.remove(position).drop_with_heap(heap);
+            }
+            return Ok(());
+        }
+
+        let index = key.as_index(heap, Type::List)?;
+        let len = i64::try_from(self.items.len()).expect("list length exceeds i64::MAX");
+        let normalized_index = if index < 0 { index + len } else { index };
+        if normalized_index < 0 || normalized_index >= len {
+            return Err(ExcType::list_assignment_index_error());
+        }
+        let idx = usize::try_from(normalized_index).expect("index validated non-negative");
+        self.items.remove(idx).drop_with_heap(heap);
+        Ok(())
+    }
+
     fn py_eq(
         &self,
         other: &Self,
@@ -362,7 +436,7 @@ impl PyTrait for List {
         other: &Self,
         heap: &mut Heap<impl ResourceTracker>,
         _interns: &Interns,
-    ) -> Result<Option<Value>, crate::resource::ResourceError> {
+    ) -> RunResult<Option<Value>> {
         // Clone both lists' contents with proper refcounting
         let mut result: Vec<Value> = self.items.iter().map(|obj| obj.clone_with_heap(heap)).collect();
         let other_cloned: Vec<Value> = other.items.iter().map(|obj| obj.clone_with_heap(heap)).collect();
@@ -607,6 +681,35 @@ fn list_copy(list: &List, heap: &mut Heap<impl ResourceTracker>) -> Result<Value
     Ok(Value::Ref(heap_id))
 }
 
+/// Implements Python's `list[slice] = iterable`.
+///
+/// The iterable is collected while the list is still in the heap, so assigning a list to a
+/// slice of itself (`xs[:] = xs`) sees the original items. Consumes `value`.
+pub(crate) fn list_set_slice(
+    list_id: HeapId,
+    slice: &Slice,
+    value: Value,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<()> {
+    // CPython rejects a zero step before looking at the value
+    if slice.step == Some(0) {
+        value.drop_with_heap(heap);
+        return Err(ExcType::value_error_slice_step_zero());
+    }
+    let Ok(mut iter) = MontyIter::new(value, heap, interns) else {
+        return Err(ExcType::type_error_slice_assign_not_iterable());
+    };
+    let items: RunResult<Vec<Value>> = iter.collect(heap, interns);
+    iter.drop_with_heap(heap);
+    let items = items?;
+
+    heap.with_entry_mut(list_id, |heap, data| match data {
+        HeapData::List(list) => list.set_slice(slice, items, heap),
+        _ => unreachable!("list_set_slice called on a non-list"),
+    })
+}
+
 /// Implements Python's `list.extend(iterable)` method.
 ///
 /// Extends the list by appending all items from the iterable.
@@ -688,7 +791,7 @@ fn list_count(
 ///
 /// Returns (value, start, end) where start and end are normalized indices.
 /// Guarantees `start <= end` to prevent slice panics.
-fn parse_index_count_args(
+pub(crate) fn parse_index_count_args(
     method: &str,
     len: usize,
     args: ArgValues,
@@ -1055,34 +1158,48 @@ pub(crate) fn get_slice_items(
     step: i64,
     heap: &mut Heap<impl ResourceTracker>,
 ) -> Vec<Value> {
+    slice_positions(start, stop, step, items.len())
+        .into_iter()
+        .map(|i| items[i].clone_with_heap(heap))
+        .collect()
+}
+
+/// Returns the indices selected by a slice, in slice order.
+///
+/// Takes the `(start, stop, step)` returned by `slice.indices(len)`. For negative step,
+/// positions go backward from start down to (but not including) stop, where a stop
+/// greater than `len` means "go to the beginning".
+///
+/// Note: step must be non-zero (callers should validate this via `slice.indices()`).
+pub(crate) fn slice_positions(start: usize, stop: usize, step: i64, len: usize) -> Vec<usize> {
     let mut result = Vec::new();
 
     // try_from succeeds for non-negative step; step==0 rejected upstream by slice.indices()
     if let Ok(step_usize) = usize::try_from(step) {
         // Positive step: iterate forward
         let mut i = start;
-        while i < stop && i < items.len() {
-            result.push(items[i].clone_with_heap(heap));
+        while i < stop && i < len {
+            result.push(i);
             i += step_usize;
         }
     } else {
         // Negative step: iterate backward
         // start is the highest index, stop is the sentinel
-        // stop > items.len() means "go to the beginning"
+        // stop > len means "go to the beginning"
         let step_abs = usize::try_from(-step).expect("step is negative so -step is positive");
         let step_abs_i64 = i64::try_from(step_abs).expect("step magnitude fits in i64");
         let mut i = i64::try_from(start).expect("start index fits in i64");
-        let stop_i64 = if stop > items.len() {
+        let stop_i64 = if stop > len {
             -1
         } else {
-            i64::try_from(stop).expect("stop bounded by items.len() fits in i64")
+            i64::try_from(stop).expect("stop bounded by len fits in i64")
         };
 
         while let Ok(i_usize) = usize::try_from(i) {
-            if i_usize >= items.len() || i <= stop_i64 {
+            if i_usize >= len || i <= stop_i64 {
                 break;
             }
-            result.push(items[i_usize].clone_with_heap(heap));
+            result.push(i_usize);
             i -= step_abs_i64;
         }
     }
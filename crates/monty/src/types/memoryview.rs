@@ -0,0 +1,340 @@
+//! Python `memoryview` type implementation.
+//!
+//! A `memoryview` is a read-only window onto the contents of a `bytes` or `bytearray`
+//! object. Slicing a view creates a new view over the same object rather than copying
+//! bytes, so a view is just the source object plus the `start`, `len` and `step` that
+//! select its bytes.
+//!
+//! Unlike CPython, resizing a `bytearray` that has live views is allowed; bytes that
+//! fall outside the resized object are no longer visible through its views.
+
+use std::{borrow::Cow, fmt::Write};
+
+use ahash::AHashSet;
+
+use crate::{
+    args::ArgValues,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{AttrCallResult, Bytes, List, PyTrait, Type, bytes::call_bytes_method_impl},
+    value::{EitherStr, Value},
+};
+
+/// Python memoryview over a heap-allocated `bytes` or `bytearray`.
+///
+/// Position `i` of the view reads byte `start + i * step` of the source object.
+/// The view holds a reference to its source object, released when the view is freed.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct MemoryView {
+    /// The `bytes` or `bytearray` this view reads from.
+    obj: HeapId,
+    /// Position in the source of the first byte of the view.
+    start: usize,
+    /// Number of bytes in the view.
+    len: usize,
+    /// Distance in the source between consecutive bytes of the view.
+    step: i64,
+}
+
+impl MemoryView {
+    /// Creates a view over all `len` bytes of `obj`.
+    ///
+    /// The caller transfers a reference to `obj` to the view.
+    #[must_use]
+    fn new(obj: HeapId, len: usize) -> Self {
+        Self {
+            obj,
+            start: 0,
+            len,
+            step: 1,
+        }
+    }
+
+    /// Creates a memoryview from the `memoryview()` constructor call.
+    ///
+    /// Accepts `bytes`, `bytearray` and `memoryview` objects. Interned bytes literals
+    /// are copied to the heap first so the view has a heap object to reference.
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let value = args.get_one_arg("memoryview", heap)?;
+        let view = match &value {
+            Value::InternBytes(bytes_id) => {
+                let bytes = interns.get_bytes(*bytes_id);
+                let len = bytes.len();
+                let obj = heap.allocate(HeapData::Bytes(Bytes::from(bytes)))?;
+                Self::new(obj, len)
+            }
+            Value::Ref(id) => {
+                let view = match heap.get(*id) {
+                    HeapData::Bytes(b) => Self::new(*id, b.len()),
+                    HeapData::ByteArray(b) => Self::new(*id, b.as_slice().len()),
+                    HeapData::MemoryView(view) => view.clone(),
+                    _ => {
+                        let err = type_error_not_bytes_like(value.py_type(heap));
+                        value.drop_with_heap(heap);
+                        return Err(err);
+                    }
+                };
+                heap.inc_ref(view.obj);
+                view
+            }
+            _ => {
+                let err = type_error_not_bytes_like(value.py_type(heap));
+                value.drop_with_heap(heap);
+                return Err(err);
+            }
+        };
+        value.drop_with_heap(heap);
+        Ok(Value::Ref(heap.allocate(HeapData::MemoryView(view))?))
+    }
+
+    /// Returns the id of the `bytes` or `bytearray` this view reads from.
+    #[must_use]
+    pub fn obj(&self) -> HeapId {
+        self.obj
+    }
+
+    /// Returns the number of bytes in the view.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the view selects no bytes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the bytes of the source object.
+    fn source<'a>(&self, heap: &'a Heap<impl ResourceTracker>) -> &'a [u8] {
+        match heap.get(self.obj) {
+            HeapData::Bytes(b) => b.as_slice(),
+            HeapData::ByteArray(b) => b.as_slice(),
+            _ => unreachable!("memoryview source must be bytes or bytearray"),
+        }
+    }
+
+    /// Returns the position in the source of byte `index` of the view.
+    fn position(&self, index: usize) -> usize {
+        let start = i64::try_from(self.start).expect("memoryview start fits in i64");
+        let index = i64::try_from(index).expect("memoryview index fits in i64");
+        usize::try_from(start + index * self.step).expect("memoryview positions are non-negative")
+    }
+
+    /// Returns the bytes selected by the view, borrowing them from the source when the view is contiguous.
+    pub fn bytes<'a>(&self, heap: &'a Heap<impl ResourceTracker>) -> Cow<'a, [u8]> {
+        self.bytes_from(self.source(heap))
+    }
+
+    /// Returns the bytes the view selects from `source`, the contents of its source object.
+    ///
+    /// Used directly by `bytearray` methods that extend the bytearray a view reads from.
+    pub fn bytes_from<'a>(&self, source: &'a [u8]) -> Cow<'a, [u8]> {
+        if self.step == 1 {
+            let start = self.start.min(source.len());
+            let end = (self.start + self.len).min(source.len());
+            Cow::Borrowed(&source[start..end])
+        } else {
+            Cow::Owned(
+                (0..self.len)
+                    .filter_map(|i| source.get(self.position(i)).copied())
+                    .collect(),
+            )
+        }
+    }
+
+    /// Returns byte `index` of the view, or `None` if it is out of range.
+    pub fn get(&self, index: usize, heap: &Heap<impl ResourceTracker>) -> Option<u8> {
+        if index >= self.len {
+            return None;
+        }
+        self.source(heap).get(self.position(index)).copied()
+    }
+
+    /// Creates a new view selecting the `(start, stop, step)` indices of this one,
+    /// as returned by `slice.indices(len)`.
+    fn slice(&self, start: usize, stop: usize, step: i64) -> Self {
+        let len = slice_len(start, stop, step, self.len);
+        if len == 0 {
+            return Self::new(self.obj, 0);
+        }
+        Self {
+            obj: self.obj,
+            start: self.position(start),
+            len,
+            // The step only matters between bytes, which also keeps it from overflowing
+            step: if len > 1 { self.step * step } else { 1 },
+        }
+    }
+}
+
+impl PyTrait for MemoryView {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::MemoryView
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        Some(self.len)
+    }
+
+    fn py_getitem(&self, key: &Value, heap: &mut Heap<impl ResourceTracker>, _interns: &Interns) -> RunResult<Value> {
+        if let Value::Ref(id) = key
+            && let HeapData::Slice(slice) = heap.get(*id)
+        {
+            let (start, stop, step) = slice
+                .indices(self.len)
+                .map_err(|()| ExcType::value_error_slice_step_zero())?;
+            let view = self.slice(start, stop, step);
+            heap.inc_ref(view.obj);
+            return Ok(Value::Ref(heap.allocate(HeapData::MemoryView(view))?));
+        }
+
+        let index = match key {
+            Value::Int(i) => *i,
+            Value::Bool(b) => i64::from(*b),
+            _ => return Err(ExcType::type_error("memoryview: invalid slice key")),
+        };
+        let len = i64::try_from(self.len).expect("memoryview length exceeds i64::MAX");
+        let normalized = if index < 0 { index + len } else { index };
+        usize::try_from(normalized)
+            .ok()
+            .and_then(|i| self.get(i, heap))
+            .map(|byte| Value::Int(i64::from(byte)))
+            .ok_or_else(|| SimpleException::new_msg(ExcType::IndexError, "index out of bounds on dimension 1").into())
+    }
+
+    fn py_setitem(
+        &mut self,
+        key: Value,
+        value: Value,
+        heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<()> {
+        key.drop_with_heap(heap);
+        value.drop_with_heap(heap);
+        Err(ExcType::type_error("cannot modify read-only memory"))
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self.bytes(heap) == other.bytes(heap))
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        stack.push(self.obj);
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        !self.is_empty()
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> std::fmt::Result {
+        f.write_str("<memory>")
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        match attr.static_string() {
+            Some(StaticStrings::Tobytes) => {
+                args.check_zero_args("memoryview.tobytes", heap)?;
+                let bytes = self.bytes(heap).into_owned();
+                Ok(Value::Ref(heap.allocate(HeapData::Bytes(Bytes::new(bytes)))?))
+            }
+            Some(StaticStrings::Tolist) => {
+                args.check_zero_args("memoryview.tolist", heap)?;
+                let items = self.bytes(heap).iter().map(|&b| Value::Int(i64::from(b))).collect();
+                Ok(Value::Ref(heap.allocate(HeapData::List(List::new(items)))?))
+            }
+            Some(StaticStrings::Hex) => {
+                let bytes = self.bytes(heap).into_owned();
+                call_bytes_method_impl(&bytes, Type::MemoryView, StaticStrings::Hex, args, heap, interns)
+            }
+            // Views don't pin their source, so there is nothing to release
+            Some(StaticStrings::Release) => {
+                args.check_zero_args("memoryview.release", heap)?;
+                Ok(Value::None)
+            }
+            _ => {
+                args.drop_with_heap(heap);
+                Err(ExcType::attribute_error(Type::MemoryView, attr.as_str(interns)))
+            }
+        }
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        let value = match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::Readonly) => Value::Bool(true),
+            Some(StaticStrings::Nbytes) => {
+                Value::Int(i64::try_from(self.len).expect("memoryview length exceeds i64::MAX"))
+            }
+            Some(StaticStrings::Obj) => {
+                heap.inc_ref(self.obj);
+                Value::Ref(self.obj)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(AttrCallResult::Value(value)))
+    }
+}
+
+/// Returns the number of indices selected by `(start, stop, step)`, as returned by `slice.indices(len)`.
+///
+/// Counts the same indices as `slice_positions` without collecting them.
+fn slice_len(start: usize, stop: usize, step: i64, len: usize) -> usize {
+    let step_abs = usize::try_from(step.unsigned_abs()).expect("slice step fits in usize");
+    if step > 0 {
+        let stop = stop.min(len);
+        if start < stop {
+            (stop - start - 1) / step_abs + 1
+        } else {
+            0
+        }
+    } else {
+        // For negative steps a stop greater than `len` means "go to the beginning"
+        let start = i64::try_from(start).expect("slice start fits in i64");
+        let stop = if stop > len {
+            -1
+        } else {
+            i64::try_from(stop).expect("slice stop fits in i64")
+        };
+        let len = i64::try_from(len).expect("slice length fits in i64");
+        if start > stop && start < len {
+            usize::try_from(start - stop - 1).expect("slice span is non-negative") / step_abs + 1
+        } else {
+            0
+        }
+    }
+}
+
+/// Creates the TypeError raised when `memoryview()` is called on an object that isn't bytes-like.
+fn type_error_not_bytes_like(type_: Type) -> RunError {
+    ExcType::type_error(format!("memoryview: a bytes-like object is required, not '{type_}'"))
+}
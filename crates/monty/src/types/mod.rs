@@ -5,38 +5,71 @@
 ///
 /// The `AbstractValue` trait provides a common interface for all heap-allocated
 /// types, enabling efficient dispatch via `enum_dispatch`.
+pub mod bytearray;
 pub mod bytes;
+pub mod class;
+pub mod code;
+pub mod complex;
+pub mod counter;
 pub mod dataclass;
+pub mod date;
+pub mod datetime;
+pub mod defaultdict;
+pub mod deque;
 pub mod dict;
+pub mod generator;
 pub mod iter;
 pub mod list;
 pub mod long_int;
+pub mod memoryview;
 pub mod module;
 pub mod namedtuple;
+pub mod ordereddict;
 pub mod path;
 pub mod property;
 pub mod py_trait;
 pub mod range;
+pub mod re_match;
+pub mod re_pattern;
 pub mod set;
 pub mod slice;
 pub mod str;
+pub mod strftime;
+pub mod timedelta;
+pub mod timezone;
 pub mod tuple;
 pub mod r#type;
 
+pub(crate) use bytearray::ByteArray;
 pub(crate) use bytes::Bytes;
-pub(crate) use dataclass::Dataclass;
+pub(crate) use class::{BoundMethod, ClassObject, Instance, SuperProxy};
+pub(crate) use code::CodeObject;
+pub(crate) use complex::Complex;
+pub(crate) use counter::Counter;
+pub(crate) use dataclass::{Dataclass, DataclassType};
+pub(crate) use date::Date;
+pub(crate) use datetime::DateTime;
+pub(crate) use defaultdict::DefaultDict;
+pub(crate) use deque::Deque;
 pub(crate) use dict::Dict;
+pub(crate) use generator::{AsyncGenAction, AsyncGenAwaitable, Generator, GeneratorState};
 pub(crate) use iter::MontyIter;
 pub(crate) use list::List;
 pub(crate) use long_int::LongInt;
+pub(crate) use memoryview::MemoryView;
 pub(crate) use module::Module;
-pub(crate) use namedtuple::NamedTuple;
+pub(crate) use namedtuple::{NamedTuple, NamedTupleFactory};
+pub(crate) use ordereddict::OrderedDict;
 pub(crate) use path::Path;
 pub(crate) use property::Property;
 pub(crate) use py_trait::{AttrCallResult, PyTrait};
 pub(crate) use range::Range;
+pub(crate) use re_match::ReMatch;
+pub(crate) use re_pattern::RePattern;
 pub(crate) use set::{FrozenSet, Set};
 pub(crate) use slice::Slice;
 pub(crate) use str::Str;
+pub(crate) use timedelta::TimeDelta;
+pub(crate) use timezone::TimeZone;
 pub(crate) use tuple::{Tuple, allocate_tuple};
 pub(crate) use r#type::Type;
@@ -14,16 +14,22 @@
 /// # Use Case
 ///
 /// This type is used for `sys.version_info` and similar structured tuples where
-/// named access improves usability and readability.
+/// named access improves usability and readability, and for instances of the
+/// classes created by `collections.namedtuple()` (see [`NamedTupleFactory`]).
 use std::fmt::Write;
 
 use ahash::AHashSet;
 
-use super::PyTrait;
+use super::{
+    Dict, MontyIter, PyTrait, allocate_tuple,
+    str::allocate_string,
+    tuple::{tuple_count, tuple_index},
+};
 use crate::{
-    exception_private::{ExcType, RunResult},
-    heap::{Heap, HeapId},
-    intern::{Interns, StringId},
+    args::{ArgValues, bind_args_into},
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
     resource::{DepthGuard, ResourceError, ResourceTracker},
     types::{AttrCallResult, Type},
     value::{EitherStr, Value},
@@ -147,6 +153,64 @@ impl NamedTuple {
         }
         self.items.get(usize::try_from(normalized).ok()?)
     }
+
+    /// Implements `_asdict()`, returning a dict mapping field names to values.
+    fn asdict(&self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let mut pairs: Vec<(Value, Value)> = Vec::with_capacity(self.items.len());
+        for (field_name, value) in self.field_names.iter().zip(&self.items) {
+            let key = match field_name_value(field_name, heap) {
+                Ok(key) => key,
+                Err(err) => {
+                    for (key, value) in pairs {
+                        key.drop_with_heap(heap);
+                        value.drop_with_heap(heap);
+                    }
+                    return Err(err);
+                }
+            };
+            pairs.push((key, value.clone_with_heap(heap)));
+        }
+        let dict = Dict::from_pairs(pairs, heap, interns)?;
+        Ok(Value::Ref(heap.allocate(HeapData::Dict(dict))?))
+    }
+
+    /// Implements `_replace(**kwargs)`, returning a copy with the given fields replaced.
+    fn replace(&self, args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let given = args.count();
+        if given > 0 {
+            args.drop_with_heap(heap);
+            let name = format!("{}._replace", self.name(interns));
+            return Err(ExcType::type_error_too_many_positional(&name, 1, given + 1, 0));
+        }
+        let (_, kwargs) = args.into_parts();
+
+        let mut items: Vec<Value> = self.items.iter().map(|item| item.clone_with_heap(heap)).collect();
+        let mut unexpected = Vec::new();
+        for (key, value) in kwargs {
+            let index = key.as_either_str(heap).and_then(|key_name| {
+                let key_name = key_name.as_str(interns);
+                self.field_names
+                    .iter()
+                    .position(|field_name| field_name.as_str(interns) == key_name)
+            });
+            if let Some(index) = index {
+                std::mem::replace(&mut items[index], value).drop_with_heap(heap);
+            } else {
+                let mut guard = DepthGuard::default();
+                unexpected.push(key.py_repr(heap, &mut guard, interns).into_owned());
+                value.drop_with_heap(heap);
+            }
+            key.drop_with_heap(heap);
+        }
+
+        if !unexpected.is_empty() {
+            items.drop_with_heap(heap);
+            let msg = format!("Got unexpected field names: [{}]", unexpected.join(", "));
+            return Err(ExcType::type_error(msg));
+        }
+        let named_tuple = Self::new(self.name.clone(), self.field_names.clone(), items);
+        Ok(Value::Ref(heap.allocate(HeapData::NamedTuple(named_tuple))?))
+    }
 }
 
 impl PyTrait for NamedTuple {
@@ -256,13 +320,38 @@ impl PyTrait for NamedTuple {
         Ok(())
     }
 
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        match attr.static_string() {
+            Some(StaticStrings::Index) => tuple_index(&self.items, args, heap, interns),
+            Some(StaticStrings::Count) => tuple_count(&self.items, args, heap, interns),
+            Some(StaticStrings::UnderscoreAsdict) => {
+                args.check_zero_args("_asdict", heap)?;
+                self.asdict(heap, interns)
+            }
+            Some(StaticStrings::UnderscoreReplace) => self.replace(args, heap, interns),
+            _ => {
+                args.drop_with_heap(heap);
+                Err(ExcType::attribute_error(self.name(interns), attr.as_str(interns)))
+            }
+        }
+    }
+
     fn py_getattr(
         &self,
         attr_id: StringId,
         heap: &mut Heap<impl ResourceTracker>,
         interns: &Interns,
     ) -> RunResult<Option<AttrCallResult>> {
-        if let Some(value) = self.get_by_name(attr_id, interns) {
+        if attr_id == StaticStrings::UnderscoreFields {
+            let fields = fields_tuple(&self.field_names, heap)?;
+            Ok(Some(AttrCallResult::Value(fields)))
+        } else if let Some(value) = self.get_by_name(attr_id, interns) {
             Ok(Some(AttrCallResult::Value(value.clone_with_heap(heap))))
         } else {
             // we use name here, not `self.py_type(heap)` hence returning a Ok(None)
@@ -270,3 +359,263 @@ impl PyTrait for NamedTuple {
         }
     }
 }
+
+/// A class created by `collections.namedtuple()`.
+///
+/// Calling it creates a [`NamedTuple`] with its name and field names, binding arguments
+/// by position or keyword like a function and filling missing trailing fields from
+/// `defaults`. Also provides the `_make()` class method and the `_fields`,
+/// `_field_defaults` and `__name__` attributes.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct NamedTupleFactory {
+    /// The type name, used for the repr of instances.
+    name: EitherStr,
+    /// The module shown in the class repr, `None` for `__main__`.
+    module: Option<EitherStr>,
+    /// Field names in order.
+    field_names: Vec<EitherStr>,
+    /// Default values for the last `defaults.len()` fields.
+    defaults: Vec<Value>,
+}
+
+impl NamedTupleFactory {
+    /// Creates a new named tuple class, taking ownership of `defaults`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more defaults than field names.
+    #[must_use]
+    pub fn new(name: EitherStr, module: Option<EitherStr>, field_names: Vec<EitherStr>, defaults: Vec<Value>) -> Self {
+        assert!(
+            defaults.len() <= field_names.len(),
+            "NamedTupleFactory can't have more defaults than field names"
+        );
+        Self {
+            name,
+            module,
+            field_names,
+            defaults,
+        }
+    }
+
+    /// Returns whether `named_tuple` is an instance of this class.
+    ///
+    /// Instances don't keep a reference to their class, so this compares the type name and
+    /// field names, which every instance copies from the class that created it.
+    #[must_use]
+    pub fn is_class_of(&self, named_tuple: &NamedTuple) -> bool {
+        self.name == named_tuple.name && self.field_names == named_tuple.field_names
+    }
+
+    /// Returns the default values of the last `defaults().len()` fields.
+    #[must_use]
+    pub fn defaults(&self) -> &[Value] {
+        &self.defaults
+    }
+
+    /// Returns whether any default value is a heap reference.
+    #[must_use]
+    pub fn has_refs(&self) -> bool {
+        self.defaults.iter().any(|value| matches!(value, Value::Ref(_)))
+    }
+
+    /// Creates an instance from the call arguments, e.g. `Point(1, y=2)`.
+    fn call(&self, args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let name = format!("{}.__new__", self.name.as_str(interns));
+        let field_count = self.field_names.len();
+        // CPython counts `cls` as a positional argument of `__new__`
+        let given = args.count();
+        if given > field_count {
+            args.drop_with_heap(heap);
+            return Err(ExcType::type_error_too_many_positional(
+                &name,
+                field_count + 1,
+                given + 1,
+                0,
+            ));
+        }
+
+        let params: Vec<&str> = self.field_names.iter().map(|field| field.as_str(interns)).collect();
+        let required = field_count - self.defaults.len();
+        let mut slots: Vec<Option<Value>> = Vec::with_capacity(field_count);
+        slots.resize_with(field_count, || None);
+        bind_args_into(&name, &params, required, args, &mut slots, heap, interns)?;
+
+        let mut items = Vec::with_capacity(field_count);
+        for (index, slot) in slots.into_iter().enumerate() {
+            let value = match slot {
+                Some(value) => value,
+                None => self.defaults[index - required].clone_with_heap(heap),
+            };
+            items.push(value);
+        }
+        self.instance(items, heap)
+    }
+
+    /// Implements `_make(iterable)`, creating an instance from an iterable of field values.
+    fn make(&self, args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let iterable = args.get_one_arg("_make", heap)?;
+        let mut iter = MontyIter::new(iterable, heap, interns)?;
+        let items: RunResult<Vec<Value>> = iter.collect(heap, interns);
+        iter.drop_with_heap(heap);
+        let items = items?;
+        if items.len() != self.field_names.len() {
+            let msg = format!("Expected {} arguments, got {}", self.field_names.len(), items.len());
+            items.drop_with_heap(heap);
+            return Err(ExcType::type_error(msg));
+        }
+        self.instance(items, heap)
+    }
+
+    /// Allocates an instance holding `items`, which must match the field names.
+    fn instance(&self, items: Vec<Value>, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+        let named_tuple = NamedTuple::new(self.name.clone(), self.field_names.clone(), items);
+        Ok(Value::Ref(heap.allocate(HeapData::NamedTuple(named_tuple))?))
+    }
+
+    /// Returns the `_field_defaults` dict, mapping field names to their default values.
+    fn field_defaults(&self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let first_default = self.field_names.len() - self.defaults.len();
+        let mut pairs: Vec<(Value, Value)> = Vec::with_capacity(self.defaults.len());
+        for (field_name, value) in self.field_names[first_default..].iter().zip(&self.defaults) {
+            let key = match field_name_value(field_name, heap) {
+                Ok(key) => key,
+                Err(err) => {
+                    for (key, value) in pairs {
+                        key.drop_with_heap(heap);
+                        value.drop_with_heap(heap);
+                    }
+                    return Err(err);
+                }
+            };
+            pairs.push((key, value.clone_with_heap(heap)));
+        }
+        let dict = Dict::from_pairs(pairs, heap, interns)?;
+        Ok(Value::Ref(heap.allocate(HeapData::Dict(dict))?))
+    }
+}
+
+impl PyTrait for NamedTupleFactory {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::Type
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.name.py_estimate_size()
+            + self.field_names.iter().map(EitherStr::py_estimate_size).sum::<usize>()
+            + self.field_names.len() * std::mem::size_of::<EitherStr>()
+            + self.defaults.len() * std::mem::size_of::<Value>()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    /// Named tuple classes are only equal to themselves, which the heap checks by identity.
+    fn py_eq(
+        &self,
+        _other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(false)
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        for value in &mut self.defaults {
+            value.py_dec_ref_ids(stack);
+        }
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        let module = self.module.as_ref().map_or("__main__", |module| module.as_str(interns));
+        write!(f, "<class '{module}.{}'>", self.name.as_str(interns))
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        if attr.static_string() == Some(StaticStrings::UnderscoreMake) {
+            self.make(args, heap, interns)
+        } else {
+            args.drop_with_heap(heap);
+            let msg = format!(
+                "type object '{}' has no attribute '{}'",
+                self.name.as_str(interns),
+                attr.as_str(interns)
+            );
+            Err(SimpleException::new_msg(ExcType::AttributeError, msg).into())
+        }
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        let value = match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::UnderscoreFields) => fields_tuple(&self.field_names, heap)?,
+            Some(StaticStrings::UnderscoreFieldDefaults) => self.field_defaults(heap, interns)?,
+            Some(StaticStrings::DunderName) => field_name_value(&self.name, heap)?,
+            _ => return Ok(None),
+        };
+        Ok(Some(AttrCallResult::Value(value)))
+    }
+}
+
+/// Calls the named tuple class at `id`, e.g. `Point(1, 2)`.
+pub(crate) fn call_namedtuple_factory(
+    id: HeapId,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    heap.with_entry_mut(id, |heap, data| {
+        let HeapData::NamedTupleFactory(factory) = data else {
+            args.drop_with_heap(heap);
+            return Err(ExcType::type_error("object is not callable"));
+        };
+        factory.call(args, heap, interns)
+    })
+}
+
+/// Returns the field names as a tuple of strings, for the `_fields` attribute.
+fn fields_tuple(field_names: &[EitherStr], heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    let mut items = Vec::with_capacity(field_names.len());
+    for field_name in field_names {
+        match field_name_value(field_name, heap) {
+            Ok(item) => items.push(item),
+            Err(err) => {
+                items.drop_with_heap(heap);
+                return Err(err);
+            }
+        }
+    }
+    Ok(allocate_tuple(items.into(), heap)?)
+}
+
+/// Returns a field (or type) name as a `str` value.
+fn field_name_value(name: &EitherStr, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    match name {
+        EitherStr::Interned(id) => Ok(Value::InternString(*id)),
+        EitherStr::Heap(name) => allocate_string(name.clone(), heap),
+    }
+}
@@ -0,0 +1,196 @@
+use std::fmt::Write;
+
+use ahash::AHashSet;
+use smallvec::smallvec;
+
+use super::{Dict, PyTrait, allocate_tuple, dict::is_dict_method};
+use crate::{
+    args::{ArgValues, bind_args},
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::Type,
+    value::{EitherStr, Value},
+};
+
+/// Python `collections.OrderedDict` type, a dict with order-aware operations.
+///
+/// Adds `move_to_end(key, last=True)` and `popitem(last=True)` to the `dict` methods, and
+/// two ordered dicts only compare equal if their items are in the same order.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct OrderedDict {
+    dict: Dict,
+}
+
+impl OrderedDict {
+    /// Creates a new ordered dict, taking ownership of `dict`.
+    #[must_use]
+    pub fn new(dict: Dict) -> Self {
+        Self { dict }
+    }
+
+    /// Returns the underlying dict.
+    #[must_use]
+    pub fn dict(&self) -> &Dict {
+        &self.dict
+    }
+
+    /// Creates an ordered dict from the `OrderedDict()` constructor call.
+    ///
+    /// Accepts the same arguments as `dict.update()`.
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let mut dict = Dict::new();
+        if let Err(err) = super::dict::dict_update(&mut dict, args, heap, interns) {
+            dict.drop_with_heap(heap);
+            return Err(err);
+        }
+        Ok(Value::Ref(heap.allocate(HeapData::OrderedDict(Self::new(dict)))?))
+    }
+
+    /// Implements `OrderedDict.move_to_end(key, last=True)`.
+    fn move_to_end(
+        &mut self,
+        args: ArgValues,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let [key, last] = bind_args("move_to_end", ["key", "last"], 1, args, heap, interns)?.0;
+        let key = key.expect("key is required");
+        let last = last.is_none_or(|last| {
+            let result = last.py_bool(heap, interns);
+            last.drop_with_heap(heap);
+            result
+        });
+
+        let result = match self.dict.move_to_end(&key, last, heap, interns) {
+            Ok(true) => Ok(Value::None),
+            Ok(false) => Err(ExcType::key_error(&key, heap, interns)),
+            Err(err) => Err(err),
+        };
+        key.drop_with_heap(heap);
+        result
+    }
+
+    /// Implements `OrderedDict.popitem(last=True)`.
+    fn popitem(
+        &mut self,
+        args: ArgValues,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let [last] = bind_args("popitem", ["last"], 0, args, heap, interns)?.0;
+        let last = last.is_none_or(|last| {
+            let result = last.py_bool(heap, interns);
+            last.drop_with_heap(heap);
+            result
+        });
+
+        let Some((key, value)) = self.dict.pop_end(last) else {
+            return Err(SimpleException::new_msg(ExcType::KeyError, "'dictionary is empty'").into());
+        };
+        Ok(allocate_tuple(smallvec![key, value], heap)?)
+    }
+}
+
+impl PyTrait for OrderedDict {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::OrderedDict
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        self.dict.py_estimate_size()
+    }
+
+    fn py_len(&self, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> Option<usize> {
+        self.dict.py_len(heap, interns)
+    }
+
+    /// Two ordered dicts are equal if they have equal items in the same order.
+    fn py_eq(
+        &self,
+        other: &Self,
+        heap: &mut Heap<impl ResourceTracker>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        if self.dict.len() != other.dict.len() {
+            return Ok(false);
+        }
+        guard.increase_err()?;
+        for ((key, value), (other_key, other_value)) in self.dict.iter().zip(other.dict.iter()) {
+            if !key.py_eq(other_key, heap, guard, interns)? || !value.py_eq(other_value, heap, guard, interns)? {
+                guard.decrease();
+                return Ok(false);
+            }
+        }
+        guard.decrease();
+        Ok(true)
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        self.dict.py_dec_ref_ids(stack);
+    }
+
+    fn py_bool(&self, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> bool {
+        self.dict.py_bool(heap, interns)
+    }
+
+    /// Formats as `OrderedDict({key: value, ...})`, like CPython.
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        heap: &Heap<impl ResourceTracker>,
+        heap_ids: &mut AHashSet<HeapId>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        if self.dict.is_empty() {
+            return f.write_str("OrderedDict()");
+        }
+        f.write_str("OrderedDict(")?;
+        self.dict.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+        f.write_char(')')
+    }
+
+    fn py_getitem(&self, key: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        self.dict.py_getitem(key, heap, interns)
+    }
+
+    fn py_setitem(
+        &mut self,
+        key: Value,
+        value: Value,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        self.dict.py_setitem(key, value, heap, interns)
+    }
+
+    fn py_delitem(&mut self, key: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<()> {
+        self.dict.py_delitem(key, heap, interns)
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        match attr.static_string() {
+            Some(StaticStrings::MoveToEnd) => self.move_to_end(args, heap, interns),
+            Some(StaticStrings::Popitem) => self.popitem(args, heap, interns),
+            Some(StaticStrings::Copy) => {
+                args.check_zero_args("OrderedDict.copy", heap)?;
+                let copy = Self::new(self.dict.clone_with_heap(heap));
+                Ok(Value::Ref(heap.allocate(HeapData::OrderedDict(copy))?))
+            }
+            Some(method) if is_dict_method(method) => self.dict.py_call_attr(heap, attr, args, interns),
+            _ => {
+                args.drop_with_heap(heap);
+                Err(ExcType::attribute_error(Type::OrderedDict, attr.as_str(interns)))
+            }
+        }
+    }
+}
@@ -201,7 +201,7 @@ pub trait PyTrait {
     /// Python addition (`__add__`).
     ///
     /// Returns `Ok(None)` if the operation is not supported for these types,
-    /// `Ok(Some(value))` on success, or `Err(ResourceError)` if allocation fails.
+    /// `Ok(Some(value))` on success, or `Err(RunError)` if an error occurs.
     ///
     /// The `interns` parameter provides access to interned string content for InternString/InternBytes.
     fn py_add(
@@ -209,15 +209,15 @@ pub trait PyTrait {
         _other: &Self,
         _heap: &mut Heap<impl ResourceTracker>,
         _interns: &Interns,
-    ) -> Result<Option<Value>, ResourceError> {
+    ) -> RunResult<Option<Value>> {
         Ok(None)
     }
 
     /// Python subtraction (`__sub__`).
     ///
     /// Returns `Ok(None)` if the operation is not supported for these types,
-    /// `Ok(Some(value))` on success, or `Err(ResourceError)` if allocation fails.
-    fn py_sub(&self, _other: &Self, _heap: &mut Heap<impl ResourceTracker>) -> Result<Option<Value>, ResourceError> {
+    /// `Ok(Some(value))` on success, or `Err(RunError)` if an error occurs.
+    fn py_sub(&self, _other: &Self, _heap: &mut Heap<impl ResourceTracker>) -> RunResult<Option<Value>> {
         Ok(None)
     }
 
@@ -385,6 +385,16 @@ pub trait PyTrait {
         .into())
     }
 
+    /// Python subscript delete operation (`__delitem__`), e.g., `del d[key]`.
+    ///
+    /// Removes the item for the key, or returns an error if the key is missing or
+    /// the type doesn't support item deletion. The caller keeps ownership of `key`.
+    ///
+    /// Default implementation returns TypeError.
+    fn py_delitem(&mut self, _key: &Value, heap: &mut Heap<impl ResourceTracker>, _interns: &Interns) -> RunResult<()> {
+        Err(ExcType::type_error_not_sub_deletion(self.py_type(heap)))
+    }
+
     /// Python attribute get operation (`__getattr__`), e.g., `obj.attr`.
     ///
     /// Returns the value associated with the attribute (owned), or `Ok(None)` if the type
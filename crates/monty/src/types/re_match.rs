@@ -0,0 +1,613 @@
+//! Python `re.Match` type: the result of a successful match.
+//!
+//! A match keeps references to the pattern and the searched string, and the byte spans of
+//! its groups within the string. Offsets are converted to character indices when they're
+//! returned to Python code.
+
+use std::fmt::{self, Write};
+
+use ahash::AHashSet;
+
+use crate::{
+    args::ArgValues,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{
+        AttrCallResult, Dict, PyTrait, Type,
+        re_pattern::{GroupSpans, Matcher, pattern_error},
+        str::{allocate_string, string_repr_fmt},
+        tuple::{TupleVec, allocate_tuple},
+    },
+    value::{EitherStr, Value},
+};
+
+/// Python `re.Match`: the groups matched by a pattern in a string.
+///
+/// Holds a reference to both the pattern and the string, released when the match is freed.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ReMatch {
+    /// The `re.Pattern` that produced the match.
+    pattern: HeapId,
+    /// The heap `str` that was searched.
+    string: HeapId,
+    /// Byte spans of the groups within the string, group 0 being the whole match.
+    groups: GroupSpans,
+    /// The `pos` passed to the search, as a character index clamped to the string.
+    pos: usize,
+    /// The `endpos` passed to the search, as a character index clamped to the string.
+    endpos: usize,
+}
+
+impl ReMatch {
+    /// Creates a match, taking ownership of the references to `pattern` and `string`.
+    #[must_use]
+    pub fn new(pattern: HeapId, string: HeapId, groups: GroupSpans, pos: usize, endpos: usize) -> Self {
+        Self {
+            pattern,
+            string,
+            groups,
+            pos,
+            endpos,
+        }
+    }
+
+    /// Returns the ids of the pattern and string the match references.
+    #[must_use]
+    pub fn child_ids(&self) -> [HeapId; 2] {
+        [self.pattern, self.string]
+    }
+
+    /// Returns the text of the searched string.
+    fn text<'h>(&self, heap: &'h Heap<impl ResourceTracker>) -> &'h str {
+        match heap.get(self.string) {
+            HeapData::Str(s) => s.as_str(),
+            _ => unreachable!("re.Match string is always a str"),
+        }
+    }
+
+    /// Returns the compiled pattern that produced the match.
+    fn matcher(&self, heap: &Heap<impl ResourceTracker>) -> RunResult<Matcher> {
+        match heap.get(self.pattern) {
+            HeapData::RePattern(pattern) => pattern.matcher().cloned(),
+            _ => unreachable!("re.Match pattern is always an re.Pattern"),
+        }
+    }
+
+    /// Returns the character span of group `index`, or `None` if it didn't take part in the match.
+    fn char_span(&self, index: usize, heap: &Heap<impl ResourceTracker>) -> Option<(usize, usize)> {
+        let (start, end) = self.groups[index]?;
+        let text = self.text(heap);
+        let start_chars = text[..start].chars().count();
+        Some((start_chars, start_chars + text[start..end].chars().count()))
+    }
+
+    /// Resolves a group given as an index or name to its index.
+    fn group_index(&self, key: &Value, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<usize> {
+        let index = match key {
+            Value::Int(i) => usize::try_from(*i).ok(),
+            Value::Bool(b) => Some(usize::from(*b)),
+            _ => match key.as_either_str(heap) {
+                Some(name) => self.matcher(heap)?.group_index(name.as_str(interns)),
+                None => None,
+            },
+        };
+        index
+            .filter(|&i| i < self.groups.len())
+            .ok_or_else(|| SimpleException::new_msg(ExcType::IndexError, "no such group").into())
+    }
+
+    /// Returns the text matched by group `index`, or `default` if the group didn't take part in the match.
+    fn group_value(&self, index: usize, default: &Value, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+        match self.groups[index] {
+            Some((start, end)) => {
+                let text = self.text(heap)[start..end].to_owned();
+                allocate_string(text, heap)
+            }
+            None => Ok(default.clone_with_heap(heap)),
+        }
+    }
+
+    /// Implements `group(*groups)`: one group's text, or a tuple of several.
+    fn group(&self, keys: &[Value], heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        match keys {
+            [] => self.group_value(0, &Value::None, heap),
+            [key] => {
+                let index = self.group_index(key, heap, interns)?;
+                self.group_value(index, &Value::None, heap)
+            }
+            keys => {
+                let mut items = TupleVec::with_capacity(keys.len());
+                for key in keys {
+                    let value = self
+                        .group_index(key, heap, interns)
+                        .and_then(|index| self.group_value(index, &Value::None, heap));
+                    match value {
+                        Ok(value) => items.push(value),
+                        Err(err) => {
+                            items.into_iter().for_each(|item| item.drop_with_heap(heap));
+                            return Err(err);
+                        }
+                    }
+                }
+                Ok(allocate_tuple(items, heap)?)
+            }
+        }
+    }
+
+    /// Implements `groups(default=None)`.
+    fn groups(&self, default: &Value, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+        let mut items = TupleVec::with_capacity(self.groups.len() - 1);
+        for index in 1..self.groups.len() {
+            match self.group_value(index, default, heap) {
+                Ok(value) => items.push(value),
+                Err(err) => {
+                    items.into_iter().for_each(|item| item.drop_with_heap(heap));
+                    return Err(err);
+                }
+            }
+        }
+        Ok(allocate_tuple(items, heap)?)
+    }
+
+    /// Implements `groupdict(default=None)`.
+    fn groupdict(&self, default: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let names: Vec<(usize, String)> = self
+            .matcher(heap)?
+            .group_names()
+            .enumerate()
+            .filter_map(|(index, name)| Some((index, name?.to_owned())))
+            .collect();
+        let mut dict = Dict::new();
+        for (index, name) in names {
+            let entry = allocate_string(name, heap).and_then(|key| match self.group_value(index, default, heap) {
+                Ok(value) => Ok((key, value)),
+                Err(err) => {
+                    key.drop_with_heap(heap);
+                    Err(err)
+                }
+            });
+            match entry.and_then(|(key, value)| dict.set(key, value, heap, interns)) {
+                Ok(replaced) => replaced.drop_with_heap(heap),
+                Err(err) => {
+                    for (key, value) in dict {
+                        key.drop_with_heap(heap);
+                        value.drop_with_heap(heap);
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        Ok(Value::Ref(heap.allocate(HeapData::Dict(dict))?))
+    }
+
+    /// Returns the group that closed last: the matched group that ends furthest, the
+    /// outermost one if several end at the same place.
+    ///
+    /// Unlike CPython, an empty group straight after another group isn't counted as closing later.
+    fn lastindex(&self) -> Option<usize> {
+        let mut last: Option<(usize, usize)> = None;
+        for (index, span) in self.groups.iter().enumerate().skip(1) {
+            if let Some((_, end)) = span
+                && last.is_none_or(|(_, last_end)| *end > last_end)
+            {
+                last = Some((index, *end));
+            }
+        }
+        last.map(|(index, _)| index)
+    }
+
+    /// Implements `expand(template)`.
+    fn expand(&self, template: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let Some(template) = template.as_either_str(heap) else {
+            return Err(type_error_expected_str(template.py_type(heap)));
+        };
+        let template = Template::parse(template.as_str(interns), &self.matcher(heap)?)?;
+        let mut out = String::new();
+        template.expand(self.text(heap), &self.groups, &mut out);
+        allocate_string(out, heap)
+    }
+}
+
+/// Extracts the optional `default` argument of `groups()` and `groupdict()`, which may be
+/// passed by keyword.
+fn default_arg(
+    args: ArgValues,
+    name: &str,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let (positional, kwargs) = args.into_parts();
+    let mut values: Vec<Value> = positional.collect();
+    let positional_count = values.len();
+    let mut error = None;
+    for (key, value) in kwargs {
+        if error.is_some() {
+            key.drop_with_heap(heap);
+            value.drop_with_heap(heap);
+            continue;
+        }
+        let key_name = key.as_either_str(heap);
+        key.drop_with_heap(heap);
+        match key_name {
+            Some(key_name) if key_name.as_str(interns) == "default" => values.push(value),
+            Some(key_name) => {
+                error = Some(ExcType::type_error_unexpected_keyword(name, key_name.as_str(interns)));
+                value.drop_with_heap(heap);
+            }
+            None => {
+                error = Some(ExcType::type_error("keywords must be strings"));
+                value.drop_with_heap(heap);
+            }
+        }
+    }
+    if error.is_none() && values.len() > 1 {
+        error = Some(if positional_count > 1 {
+            ExcType::type_error_too_many_positional(name, 1, positional_count, 0)
+        } else {
+            ExcType::type_error_duplicate_arg(name, "default")
+        });
+    }
+    if let Some(error) = error {
+        values.drop_with_heap(heap);
+        return Err(error);
+    }
+    Ok(values.pop().unwrap_or(Value::None))
+}
+
+impl PyTrait for ReMatch {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::ReMatch
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.groups.len() * std::mem::size_of::<Option<(usize, usize)>>()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        _other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        // Matches compare by identity, which is checked at the Value level
+        Ok(false)
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        stack.push(self.pattern);
+        stack.push(self.string);
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> fmt::Result {
+        let (start, end) = self.char_span(0, heap).expect("the whole match is always set");
+        let (byte_start, byte_end) = self.groups[0].expect("the whole match is always set");
+        let mut matched = String::new();
+        string_repr_fmt(&self.text(heap)[byte_start..byte_end], &mut matched)?;
+        // Like CPython, long matches are cut off, along with their closing quote
+        let matched: String = matched.chars().take(50).collect();
+        write!(f, "<re.Match object; span=({start}, {end}), match={matched}>")
+    }
+
+    fn py_getitem(&self, key: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        self.group(std::slice::from_ref(key), heap, interns)
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        match attr.static_string() {
+            Some(StaticStrings::Group) => {
+                let keys: Vec<Value> = args.into_pos_only("Match.group", heap)?.collect();
+                let result = self.group(&keys, heap, interns);
+                keys.drop_with_heap(heap);
+                result
+            }
+            Some(StaticStrings::Groups) => {
+                let default = default_arg(args, "groups", heap, interns)?;
+                let result = self.groups(&default, heap);
+                default.drop_with_heap(heap);
+                result
+            }
+            Some(StaticStrings::Groupdict) => {
+                let default = default_arg(args, "groupdict", heap, interns)?;
+                let result = self.groupdict(&default, heap, interns);
+                default.drop_with_heap(heap);
+                result
+            }
+            Some(name @ (StaticStrings::Start | StaticStrings::End | StaticStrings::Span)) => {
+                let method = match name {
+                    StaticStrings::Start => "start",
+                    StaticStrings::End => "end",
+                    _ => "span",
+                };
+                let key = args.get_zero_one_arg(method, heap)?;
+                let index = match &key {
+                    Some(key) => self.group_index(key, heap, interns),
+                    None => Ok(0),
+                };
+                key.drop_with_heap(heap);
+                let (start, end) = self
+                    .char_span(index?, heap)
+                    .map_or((-1, -1), |(start, end)| (to_py_int(start), to_py_int(end)));
+                match name {
+                    StaticStrings::Start => Ok(Value::Int(start)),
+                    StaticStrings::End => Ok(Value::Int(end)),
+                    _ => Ok(allocate_tuple(
+                        TupleVec::from_iter([Value::Int(start), Value::Int(end)]),
+                        heap,
+                    )?),
+                }
+            }
+            Some(StaticStrings::Expand) => {
+                let template = args.get_one_arg("Match.expand", heap)?;
+                let result = self.expand(&template, heap, interns);
+                template.drop_with_heap(heap);
+                result
+            }
+            _ => {
+                args.drop_with_heap(heap);
+                Err(ExcType::attribute_error(Type::ReMatch, attr.as_str(interns)))
+            }
+        }
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        let value = match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::StringAttr) => {
+                heap.inc_ref(self.string);
+                Value::Ref(self.string)
+            }
+            Some(StaticStrings::Re) => {
+                heap.inc_ref(self.pattern);
+                Value::Ref(self.pattern)
+            }
+            Some(StaticStrings::Pos) => Value::Int(to_py_int(self.pos)),
+            Some(StaticStrings::Endpos) => Value::Int(to_py_int(self.endpos)),
+            Some(StaticStrings::Lastindex) => match self.lastindex() {
+                Some(index) => Value::Int(to_py_int(index)),
+                None => Value::None,
+            },
+            Some(StaticStrings::Lastgroup) => {
+                let name = match self.lastindex() {
+                    Some(index) => self
+                        .matcher(heap)?
+                        .group_names()
+                        .nth(index)
+                        .flatten()
+                        .map(str::to_owned),
+                    None => None,
+                };
+                match name {
+                    Some(name) => allocate_string(name, heap)?,
+                    None => Value::None,
+                }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(AttrCallResult::Value(value)))
+    }
+}
+
+/// Converts an index to a Python `int`.
+fn to_py_int(index: usize) -> i64 {
+    i64::try_from(index).expect("index fits in i64")
+}
+
+/// A parsed replacement template, as used by `sub()` and `Match.expand()`.
+#[derive(Debug)]
+pub(crate) struct Template {
+    parts: Vec<TemplatePart>,
+}
+
+/// A piece of a replacement template.
+#[derive(Debug)]
+enum TemplatePart {
+    /// Text copied to the output.
+    Literal(String),
+    /// The text matched by a group, empty if the group didn't take part in the match.
+    Group(usize),
+}
+
+impl Template {
+    /// Parses `template`, resolving group references against the groups of `matcher`.
+    ///
+    /// Supports `\g<name>`, `\g<number>`, `\number` and the character escapes allowed in
+    /// templates; other escaped punctuation is kept as is, with its backslash.
+    pub fn parse(template: &str, matcher: &Matcher) -> RunResult<Self> {
+        let chars: Vec<char> = template.chars().collect();
+        let group_count = matcher.group_count();
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if c != '\\' {
+                literal.push(c);
+                i += 1;
+                continue;
+            }
+            let Some(&next) = chars.get(i + 1) else {
+                return Err(pattern_error(format!("bad escape (end of pattern) at position {i}")));
+            };
+            let group = match next {
+                'g' => {
+                    let (group, end) = parse_named_group(&chars, i + 2, matcher)?;
+                    i = end;
+                    Some(group)
+                }
+                '0' => {
+                    let digits: String = chars[i + 1..]
+                        .iter()
+                        .take(3)
+                        .take_while(|c| ('0'..='7').contains(*c))
+                        .collect();
+                    let code = u32::from_str_radix(&digits, 8).expect("checked to be octal digits");
+                    literal.push(char::from_u32(code).expect("two octal digits are a valid char"));
+                    i += 1 + digits.len();
+                    None
+                }
+                '1'..='9' => {
+                    let octal: String = chars[i + 1..]
+                        .iter()
+                        .take(3)
+                        .take_while(|c| ('0'..='7').contains(*c))
+                        .collect();
+                    if octal.len() == 3 {
+                        let code = u32::from_str_radix(&octal, 8).expect("checked to be octal digits");
+                        if code > 0o377 {
+                            return Err(pattern_error(format!(
+                                "octal escape value \\{octal} outside of range 0-0o377 at position {i}"
+                            )));
+                        }
+                        literal.push(char::from_u32(code).expect("octal escapes are below 0o400"));
+                        i += 4;
+                        None
+                    } else {
+                        let digits: String = chars[i + 1..]
+                            .iter()
+                            .take(2)
+                            .take_while(|c| c.is_ascii_digit())
+                            .collect();
+                        let group: usize = digits.parse().expect("checked to be digits");
+                        if group > group_count {
+                            return Err(pattern_error(format!(
+                                "invalid group reference {group} at position {}",
+                                i + 1
+                            )));
+                        }
+                        i += 1 + digits.len();
+                        Some(group)
+                    }
+                }
+                _ => {
+                    let escaped = match next {
+                        'a' => Some('\x07'),
+                        'b' => Some('\x08'),
+                        'f' => Some('\x0c'),
+                        'n' => Some('\n'),
+                        'r' => Some('\r'),
+                        't' => Some('\t'),
+                        'v' => Some('\x0b'),
+                        '\\' => Some('\\'),
+                        c if c.is_ascii_alphanumeric() => {
+                            return Err(pattern_error(format!("bad escape \\{c} at position {i}")));
+                        }
+                        _ => None,
+                    };
+                    match escaped {
+                        Some(c) => literal.push(c),
+                        None => {
+                            literal.push('\\');
+                            literal.push(next);
+                        }
+                    }
+                    i += 2;
+                    None
+                }
+            };
+            if let Some(group) = group {
+                if !literal.is_empty() {
+                    parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(TemplatePart::Group(group));
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Literal(literal));
+        }
+        Ok(Self { parts })
+    }
+
+    /// Appends the template, filled in with the groups of a match in `haystack`, to `out`.
+    pub fn expand(&self, haystack: &str, groups: &GroupSpans, out: &mut String) {
+        for part in &self.parts {
+            match part {
+                TemplatePart::Literal(text) => out.push_str(text),
+                TemplatePart::Group(index) => {
+                    if let Some((start, end)) = groups[*index] {
+                        out.push_str(&haystack[start..end]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Parses the `<name>` of a `\g<name>` template reference starting at `chars[start]`.
+///
+/// Returns the group index and the position after the closing `>`.
+fn parse_named_group(chars: &[char], start: usize, matcher: &Matcher) -> RunResult<(usize, usize)> {
+    if chars.get(start) != Some(&'<') {
+        return Err(pattern_error(format!("missing < at position {start}")));
+    }
+    let name_start = start + 1;
+    let Some(len) = chars[name_start..].iter().position(|&c| c == '>') else {
+        if name_start == chars.len() {
+            return Err(pattern_error(format!("missing group name at position {name_start}")));
+        }
+        return Err(pattern_error(format!(
+            "missing >, unterminated name at position {name_start}"
+        )));
+    };
+    let name: String = chars[name_start..name_start + len].iter().collect();
+    if name.is_empty() {
+        return Err(pattern_error(format!("missing group name at position {name_start}")));
+    }
+    let end = name_start + len + 1;
+    if name.chars().all(|c| c.is_ascii_digit()) {
+        let group = name
+            .parse::<usize>()
+            .ok()
+            .filter(|&group| group <= matcher.group_count());
+        return match group {
+            Some(group) => Ok((group, end)),
+            None => Err(pattern_error(format!(
+                "invalid group reference {name} at position {name_start}"
+            ))),
+        };
+    }
+    let mut name_chars = name.chars();
+    let is_identifier = name_chars.next().is_some_and(|c| c == '_' || c.is_alphabetic())
+        && name_chars.all(|c| c == '_' || c.is_alphanumeric());
+    if !is_identifier {
+        return Err(pattern_error(format!(
+            "bad character in group name '{name}' at position {name_start}"
+        )));
+    }
+    match matcher.group_index(&name) {
+        Some(group) => Ok((group, end)),
+        None => Err(SimpleException::new_msg(ExcType::IndexError, format!("unknown group name '{name}'")).into()),
+    }
+}
+
+/// Creates the `TypeError` raised when a replacement function returns something other than a `str`.
+pub(crate) fn type_error_expected_str(found: Type) -> RunError {
+    ExcType::type_error(format!("expected str instance, {found} found"))
+}
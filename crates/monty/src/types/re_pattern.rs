@@ -0,0 +1,709 @@
+//! Python `re.Pattern` type: a compiled regular expression.
+//!
+//! Patterns are matched by the `regex-automata` meta engine, which never backtracks: matching
+//! takes time linear in the length of the searched string whatever the pattern, so a pattern
+//! can't hang the host the way catastrophic backtracking can in CPython. Python's regex syntax
+//! is translated to the engine's before compiling.
+//!
+//! Differences from CPython:
+//! - backreferences, lookaround assertions, conditional groups and atomic groups raise `re.error`
+//! - after an empty match, the next match can't start at the same position
+//! - `\s` doesn't match the `\x1c`-`\x1f` separator characters
+//! - bytes patterns aren't supported
+//!
+//! Only the pattern and flags are serialized in snapshots; the compiled regexes are rebuilt
+//! the first time a restored pattern is used.
+//!
+//! The compiled regexes count towards the memory limit, and room for them is checked before
+//! compiling, so patterns can't be used to allocate memory the limit doesn't cover.
+
+use std::{
+    fmt::{self, Write},
+    sync::OnceLock,
+};
+
+use ahash::AHashSet;
+use regex_automata::{Anchored, Input, PatternID, meta};
+use regex_syntax::{
+    ParserBuilder,
+    hir::{Hir, Look},
+};
+
+use crate::{
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{
+        AttrCallResult, Dict, PyTrait, Type,
+        str::{allocate_string, string_repr_fmt},
+    },
+    value::Value,
+};
+
+/// `re.IGNORECASE`: case-insensitive matching.
+pub(crate) const FLAG_IGNORECASE: i64 = 2;
+/// `re.LOCALE`: locale dependent matching, only valid for bytes patterns.
+pub(crate) const FLAG_LOCALE: i64 = 4;
+/// `re.MULTILINE`: `^` and `$` also match at the start and end of lines.
+pub(crate) const FLAG_MULTILINE: i64 = 8;
+/// `re.DOTALL`: `.` also matches a newline.
+pub(crate) const FLAG_DOTALL: i64 = 16;
+/// `re.UNICODE`: Unicode matching, the default for `str` patterns.
+pub(crate) const FLAG_UNICODE: i64 = 32;
+/// `re.VERBOSE`: whitespace and `#` comments in the pattern are ignored.
+pub(crate) const FLAG_VERBOSE: i64 = 64;
+/// `re.ASCII`: `\w`, `\d`, `\s` and `\b` only match ASCII characters.
+pub(crate) const FLAG_ASCII: i64 = 256;
+
+/// Limit on the size of a compiled pattern, so patterns like `(a{1000}){1000}` raise
+/// `re.error` rather than using a lot of memory.
+const NFA_SIZE_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Smallest limit a pattern is compiled with when the memory limit doesn't leave room for
+/// `NFA_SIZE_LIMIT`; below this, compiling raises `MemoryError` instead.
+const MIN_NFA_SIZE_LIMIT: usize = 16 * 1024;
+
+/// Python `re.Pattern`: a pattern and flags, with the regexes compiled from them.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct RePattern {
+    pattern: String,
+    /// The flags passed to `compile()` combined with the pattern's leading inline flags.
+    flags: i64,
+    /// Compiled when the pattern is created, and again on first use after a snapshot is restored.
+    #[serde(skip)]
+    matcher: OnceLock<Matcher>,
+}
+
+impl RePattern {
+    /// Compiles `pattern` with `flags`, as done by `re.compile()`.
+    ///
+    /// The memory the compiled regexes may use is checked against `tracker` before compiling,
+    /// see `nfa_size_limit()`.
+    ///
+    /// Returns `re.error` for invalid or unsupported patterns, `ValueError` for invalid
+    /// combinations of flags, and `MemoryError` when there's no room to compile the pattern.
+    pub fn compile(pattern: &str, flags: i64, tracker: &impl ResourceTracker) -> RunResult<Self> {
+        let (inline_flags, _) = split_global_flags(pattern);
+        let mut flags = flags | inline_flags;
+        if flags & FLAG_LOCALE != 0 {
+            return Err(
+                SimpleException::new_msg(ExcType::ValueError, "cannot use LOCALE flag with a str pattern").into(),
+            );
+        }
+        if flags & FLAG_ASCII != 0 {
+            if flags & FLAG_UNICODE != 0 {
+                return Err(
+                    SimpleException::new_msg(ExcType::ValueError, "ASCII and UNICODE flags are incompatible").into(),
+                );
+            }
+        } else {
+            flags |= FLAG_UNICODE;
+        }
+        let matcher = Matcher::new(pattern, flags, nfa_size_limit(tracker)?)?;
+        Ok(Self {
+            pattern: pattern.to_owned(),
+            flags,
+            matcher: OnceLock::from(matcher),
+        })
+    }
+
+    /// Returns the source of the pattern.
+    #[must_use]
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns the pattern's flags, including `re.UNICODE` unless `re.ASCII` is set.
+    #[must_use]
+    pub fn flags(&self) -> i64 {
+        self.flags
+    }
+
+    /// Returns the compiled pattern, compiling it again if it was restored from a snapshot.
+    ///
+    /// The matcher is cheap to clone, so callers can release their borrow of the heap while matching.
+    /// Returns `re.error` if the restored pattern doesn't compile, e.g. because the snapshot was
+    /// made by another version of Monty.
+    pub fn matcher(&self) -> RunResult<&Matcher> {
+        if let Some(matcher) = self.matcher.get() {
+            return Ok(matcher);
+        }
+        let matcher = Matcher::new(&self.pattern, self.flags, NFA_SIZE_LIMIT)?;
+        Ok(self.matcher.get_or_init(|| matcher))
+    }
+}
+
+/// Picks the size limit for the regexes of a new pattern.
+///
+/// Both regexes can grow up to the limit while compiling, so this checks the tracker has room for
+/// them before any of that memory is used, lowering the limit when the memory limit is close.
+fn nfa_size_limit(tracker: &impl ResourceTracker) -> Result<usize, ResourceError> {
+    let mut limit = NFA_SIZE_LIMIT;
+    loop {
+        match tracker.check_large_result(2 * limit) {
+            Ok(()) => return Ok(limit),
+            Err(err) if limit <= MIN_NFA_SIZE_LIMIT => return Err(err),
+            Err(_) => limit /= 2,
+        }
+    }
+}
+
+impl PyTrait for RePattern {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::RePattern
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        // a restored pattern isn't compiled until it's used, it was charged for when it was created
+        let compiled = self.matcher.get().map_or(0, Matcher::memory_usage);
+        std::mem::size_of::<Self>() + self.pattern.len() + compiled
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self.pattern == other.pattern && self.flags == other.flags)
+    }
+
+    fn py_dec_ref_ids(&mut self, _stack: &mut Vec<HeapId>) {}
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> fmt::Result {
+        f.write_str("re.compile(")?;
+        string_repr_fmt(&self.pattern, f)?;
+        // Like CPython, `re.UNICODE` is left out since it's the default
+        let flags = self.flags & !FLAG_UNICODE;
+        if flags != 0 {
+            f.write_str(", ")?;
+            let names = [
+                (FLAG_IGNORECASE, "re.IGNORECASE"),
+                (FLAG_MULTILINE, "re.MULTILINE"),
+                (FLAG_DOTALL, "re.DOTALL"),
+                (FLAG_VERBOSE, "re.VERBOSE"),
+                (FLAG_ASCII, "re.ASCII"),
+            ];
+            let mut remaining = flags;
+            let mut first = true;
+            for (flag, name) in names {
+                if flags & flag != 0 {
+                    if !first {
+                        f.write_char('|')?;
+                    }
+                    f.write_str(name)?;
+                    remaining &= !flag;
+                    first = false;
+                }
+            }
+            if remaining != 0 {
+                if !first {
+                    f.write_char('|')?;
+                }
+                write!(f, "0x{remaining:x}")?;
+            }
+        }
+        f.write_char(')')
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        let value = match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::Pattern) => allocate_string(self.pattern.clone(), heap)?,
+            Some(StaticStrings::Flags) => Value::Int(self.flags),
+            Some(StaticStrings::Groups) => {
+                Value::Int(i64::try_from(self.matcher()?.group_count()).expect("group count fits in i64"))
+            }
+            Some(StaticStrings::Groupindex) => {
+                let names: Vec<(String, usize)> = self
+                    .matcher()?
+                    .group_names()
+                    .enumerate()
+                    .filter_map(|(index, name)| Some((name?.to_owned(), index)))
+                    .collect();
+                let mut dict = Dict::new();
+                for (name, index) in names {
+                    let index = Value::Int(i64::try_from(index).expect("group index fits in i64"));
+                    let result = allocate_string(name, heap).and_then(|key| dict.set(key, index, heap, interns));
+                    if let Err(err) = result {
+                        for (key, value) in dict {
+                            key.drop_with_heap(heap);
+                            value.drop_with_heap(heap);
+                        }
+                        return Err(err);
+                    }
+                }
+                Value::Ref(heap.allocate(HeapData::Dict(dict))?)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(AttrCallResult::Value(value)))
+    }
+}
+
+/// Which part of the searched range a match has to cover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MatchMode {
+    /// `search()`: the match can start anywhere.
+    Search,
+    /// `match()`: the match must start at the start of the range.
+    Match,
+    /// `fullmatch()`: the match must cover the whole range.
+    FullMatch,
+}
+
+/// The byte spans of the groups of a match, group 0 being the whole match.
+///
+/// Groups that didn't take part in the match are `None`.
+pub(crate) type GroupSpans = Vec<Option<(usize, usize)>>;
+
+/// The regexes compiled from a pattern.
+///
+/// Cloning is cheap: the compiled regexes are shared.
+#[derive(Debug, Clone)]
+pub(crate) struct Matcher {
+    /// Finds matches anywhere, and is run anchored for `match()`.
+    regex: meta::Regex,
+    /// The pattern followed by `\z`, run anchored for `fullmatch()`.
+    full: meta::Regex,
+    /// Indices of the groups capturing the final newline a `$` may match before, ascending.
+    ///
+    /// Without `MULTILINE`, Python's `$` also matches before a newline at the end of the
+    /// string. The engine has no lookahead, so `$` is translated to `(?:(\n)?\z)` and these
+    /// groups are hidden from the pattern's groups, see `python_groups()`.
+    eol_groups: Vec<usize>,
+}
+
+impl Matcher {
+    /// Translates and compiles `pattern` with the (already validated) `flags`, each regex limited
+    /// to `size_limit` bytes.
+    fn new(pattern: &str, flags: i64, size_limit: usize) -> RunResult<Self> {
+        let (_, body) = split_global_flags(pattern);
+        let verbose = flags & FLAG_VERBOSE != 0;
+        let multi_line = flags & FLAG_MULTILINE != 0;
+        let (translated, eol_groups) = translate(body, flags & FLAG_ASCII != 0, verbose, multi_line)?;
+        let hir = ParserBuilder::new()
+            .case_insensitive(flags & FLAG_IGNORECASE != 0)
+            .multi_line(multi_line)
+            .dot_matches_new_line(flags & FLAG_DOTALL != 0)
+            .ignore_whitespace(verbose)
+            .build()
+            .parse(&translated)
+            .map_err(syntax_error)?;
+
+        let config = meta::Config::new().nfa_size_limit(Some(size_limit));
+        let regex = meta::Builder::new()
+            .configure(config.clone())
+            .build_from_hir(&hir)
+            .map_err(|e| pattern_error(e.to_string()))?;
+        let full_hir = Hir::concat(vec![hir, Hir::look(Look::End)]);
+        let full = meta::Builder::new()
+            .configure(config)
+            .build_from_hir(&full_hir)
+            .map_err(|e| pattern_error(e.to_string()))?;
+        Ok(Self {
+            regex,
+            full,
+            eol_groups,
+        })
+    }
+
+    /// Returns the heap memory used by the compiled regexes.
+    #[must_use]
+    pub fn memory_usage(&self) -> usize {
+        self.regex.memory_usage() + self.full.memory_usage()
+    }
+
+    /// Returns the number of capturing groups, not counting the whole match.
+    #[must_use]
+    pub fn group_count(&self) -> usize {
+        self.regex.group_info().group_len(PatternID::ZERO) - 1 - self.eol_groups.len()
+    }
+
+    /// Returns the index of the group called `name`.
+    #[must_use]
+    pub fn group_index(&self, name: &str) -> Option<usize> {
+        let index = self.regex.group_info().to_index(PatternID::ZERO, name)?;
+        Some(index - self.eol_groups.iter().filter(|&&group| group < index).count())
+    }
+
+    /// Returns the names of the groups in index order, `None` for unnamed groups and the whole match.
+    pub fn group_names(&self) -> impl Iterator<Item = Option<&str>> {
+        self.regex
+            .group_info()
+            .pattern_names(PatternID::ZERO)
+            .enumerate()
+            .filter(|(index, _)| !self.eol_groups.contains(index))
+            .map(|(_, name)| name)
+    }
+
+    /// Finds the first match in `haystack[start..end]`.
+    ///
+    /// Both offsets must be on character boundaries; there's no match when `start > end`.
+    /// Assertions like `\b` still see the text before `start`, while `end` acts as the end
+    /// of the string.
+    #[must_use]
+    pub fn find(&self, haystack: &str, start: usize, end: usize, mode: MatchMode) -> Option<GroupSpans> {
+        let (regex, anchored) = match mode {
+            MatchMode::Search => (&self.regex, Anchored::No),
+            MatchMode::Match => (&self.regex, Anchored::Yes),
+            MatchMode::FullMatch => (&self.full, Anchored::Yes),
+        };
+        if start > end {
+            return None;
+        }
+        let input = Input::new(&haystack[..end]).range(start..).anchored(anchored);
+        let mut caps = regex.create_captures();
+        regex.search_captures(&input, &mut caps);
+        if !caps.is_match() {
+            return None;
+        }
+        let groups = (0..caps.group_len())
+            .map(|group| caps.get_group(group).map(|span| (span.start, span.end)))
+            .collect();
+        self.python_groups(groups, mode)
+    }
+
+    /// Removes the groups `$` was translated with from the spans of a match.
+    ///
+    /// If a `$` matched the final newline, spans ending after it end before it instead, as
+    /// Python's `$` doesn't consume the newline. Such a match doesn't reach the end of the
+    /// string, so there's no match for `fullmatch()`.
+    fn python_groups(&self, mut groups: GroupSpans, mode: MatchMode) -> Option<GroupSpans> {
+        if let Some((newline_start, newline_end)) = self.eol_groups.iter().find_map(|&group| groups[group]) {
+            if mode == MatchMode::FullMatch {
+                return None;
+            }
+            for (start, end) in groups.iter_mut().flatten() {
+                if *end == newline_end {
+                    *start = (*start).min(newline_start);
+                    *end = newline_start;
+                }
+            }
+        }
+        for &group in self.eol_groups.iter().rev() {
+            groups.remove(group);
+        }
+        Some(groups)
+    }
+
+    /// Finds successive non-overlapping matches in `haystack[start..end]`, at most `limit`
+    /// of them when `limit` isn't zero.
+    ///
+    /// Each match counts as an instruction against the resource tracker. Like CPython, an
+    /// empty match may directly follow a non-empty one, but after an empty match the search
+    /// moves on by a character.
+    pub fn find_all(
+        &self,
+        haystack: &str,
+        start: usize,
+        end: usize,
+        limit: usize,
+        tracker: &mut impl ResourceTracker,
+    ) -> Result<Vec<GroupSpans>, ResourceError> {
+        let mut matches = Vec::new();
+        let mut pos = start;
+        while limit == 0 || matches.len() < limit {
+            tracker.check_time()?;
+            let Some(groups) = self.find(haystack, pos, end, MatchMode::Search) else {
+                break;
+            };
+            let (match_start, match_end) = groups[0].expect("the whole match is always set");
+            matches.push(groups);
+            pos = if match_start == match_end {
+                match haystack[match_end..end].chars().next() {
+                    Some(c) => match_end + c.len_utf8(),
+                    None => break,
+                }
+            } else {
+                match_end
+            };
+        }
+        Ok(matches)
+    }
+}
+
+/// Splits leading global inline flags like `(?im)` off `pattern`.
+///
+/// Returns the flags they set and the rest of the pattern. Groups containing letters that
+/// aren't flags are left in the pattern for the parser to reject.
+fn split_global_flags(pattern: &str) -> (i64, &str) {
+    let mut flags = 0;
+    let mut rest = pattern;
+    while let Some(group) = rest.strip_prefix("(?")
+        && let Some(end) = group.find(')')
+        && end > 0
+    {
+        let mut group_flags = 0;
+        for c in group[..end].chars() {
+            group_flags |= match c {
+                'a' => FLAG_ASCII,
+                'i' => FLAG_IGNORECASE,
+                'L' => FLAG_LOCALE,
+                'm' => FLAG_MULTILINE,
+                's' => FLAG_DOTALL,
+                'u' => FLAG_UNICODE,
+                'x' => FLAG_VERBOSE,
+                _ => return (flags, rest),
+            };
+        }
+        flags |= group_flags;
+        rest = &group[end + 1..];
+    }
+    (flags, rest)
+}
+
+/// Translates a Python regular expression to the syntax of the `regex-syntax` parser.
+///
+/// Most of the syntax is shared. This rewrites the escapes and repeats that differ, escapes
+/// characters that are special in the engine's character classes but not Python's, applies
+/// `re.ASCII` to the `\w`, `\d`, `\s` and `\b` escapes, and rejects the features that need
+/// backtracking.
+///
+/// Without `MULTILINE`, `$` becomes `(?:(\n)?\z)`, so it also matches before a final newline;
+/// the indices of the groups this adds are returned with the translated pattern.
+fn translate(pattern: &str, ascii: bool, verbose: bool, multi_line: bool) -> RunResult<(String, Vec<usize>)> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut out = String::with_capacity(pattern.len());
+    let mut in_class = false;
+    let mut group_count = 0;
+    let mut eol_groups = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\\' => i = translate_escape(&chars, i, in_class, ascii, &mut out)?,
+            '[' if !in_class => {
+                in_class = true;
+                out.push('[');
+                i += 1;
+                if chars.get(i) == Some(&'^') {
+                    out.push('^');
+                    i += 1;
+                }
+                // A `]` straight after the opening bracket is a literal
+                if chars.get(i) == Some(&']') {
+                    out.push_str("\\]");
+                    i += 1;
+                }
+            }
+            ']' if in_class => {
+                in_class = false;
+                out.push(']');
+                i += 1;
+            }
+            // Nested classes and set operations aren't Python syntax
+            '[' | '&' | '~' if in_class => {
+                out.push('\\');
+                out.push(c);
+                i += 1;
+            }
+            // Verbose mode keeps whitespace and `#` inside classes
+            c if in_class && verbose && (c.is_whitespace() || c == '#') => {
+                push_literal(&mut out, c);
+                i += 1;
+            }
+            '#' if verbose && !in_class => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '{' if !in_class => i = translate_repeat(&chars, i, &mut out),
+            '}' if !in_class => {
+                out.push_str("\\}");
+                i += 1;
+            }
+            '(' if !in_class && chars.get(i + 1) == Some(&'?') => {
+                // Named groups capture
+                if matches!(
+                    (chars.get(i + 2), chars.get(i + 3)),
+                    (Some('<'), _) | (Some('P'), Some('<'))
+                ) {
+                    group_count += 1;
+                }
+                i = translate_group(&chars, i, &mut out)?;
+            }
+            '(' if !in_class => {
+                group_count += 1;
+                out.push('(');
+                i += 1;
+            }
+            '$' if !in_class && !multi_line => {
+                group_count += 1;
+                eol_groups.push(group_count);
+                out.push_str("(?:(\\n)?\\z)");
+                i += 1;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    Ok((out, eol_groups))
+}
+
+/// Translates the escape starting with the backslash at `chars[i]`, returning the index after it.
+fn translate_escape(chars: &[char], i: usize, in_class: bool, ascii: bool, out: &mut String) -> RunResult<usize> {
+    let Some(&c) = chars.get(i + 1) else {
+        return Err(pattern_error("bad escape (end of pattern)"));
+    };
+    match c {
+        'd' | 'D' | 's' | 'S' | 'w' | 'W' if ascii => out.push_str(match c {
+            'd' => "[0-9]",
+            'D' => "[^0-9]",
+            's' => "[\\t\\n\\x0B\\x0C\\r\\x20]",
+            'S' => "[^\\t\\n\\x0B\\x0C\\r\\x20]",
+            'w' => "[0-9A-Za-z_]",
+            _ => "[^0-9A-Za-z_]",
+        }),
+        // In a class `\b` is a backspace
+        'b' if in_class => out.push_str("\\x08"),
+        'b' if ascii => out.push_str("(?-u:\\b)"),
+        'B' if ascii => out.push_str("(?-u:\\B)"),
+        'Z' => out.push_str("\\z"),
+        'A' | 'b' | 'B' | 'd' | 'D' | 's' | 'S' | 'w' | 'W' | 'a' | 'f' | 'n' | 'r' | 't' | 'v' => {
+            out.push('\\');
+            out.push(c);
+        }
+        'x' | 'u' | 'U' => {
+            let digits = match c {
+                'x' => 2,
+                'u' => 4,
+                _ => 8,
+            };
+            let hex: String = chars[i + 2..]
+                .iter()
+                .take(digits)
+                .take_while(|c| c.is_ascii_hexdigit())
+                .collect();
+            let escape: String = chars[i..(i + 2 + hex.len()).min(chars.len())].iter().collect();
+            if hex.len() < digits {
+                return Err(pattern_error(format!("incomplete escape {escape} at position {i}")));
+            }
+            let code = u32::from_str_radix(&hex, 16).expect("checked to be hex digits");
+            let Some(literal) = char::from_u32(code) else {
+                return Err(pattern_error(format!("bad escape {escape} at position {i}")));
+            };
+            push_literal(out, literal);
+            return Ok(i + 2 + digits);
+        }
+        'N' => return Err(pattern_error("named Unicode escapes are not supported")),
+        '0'..='9' => {
+            let octal: String = chars[i + 1..]
+                .iter()
+                .take(3)
+                .take_while(|c| ('0'..='7').contains(*c))
+                .collect();
+            // Outside classes `\1` to `\99` are backreferences unless there are three octal digits
+            if !(c == '0' || in_class || octal.len() == 3) {
+                return Err(pattern_error("backreferences are not supported"));
+            }
+            if octal.is_empty() {
+                return Err(pattern_error(format!("bad escape \\{c} at position {i}")));
+            }
+            let code = u32::from_str_radix(&octal, 8).expect("checked to be octal digits");
+            if code > 0o377 {
+                return Err(pattern_error(format!(
+                    "octal escape value \\{octal} outside of range 0-0o377 at position {i}"
+                )));
+            }
+            push_literal(out, char::from_u32(code).expect("octal escapes are below 0o400"));
+            return Ok(i + 1 + octal.len());
+        }
+        c if c.is_ascii_alphanumeric() => return Err(pattern_error(format!("bad escape \\{c} at position {i}"))),
+        // Any other escaped character is a literal
+        c => push_literal(out, c),
+    }
+    Ok(i + 2)
+}
+
+/// Translates the `{` at `chars[i]`, returning the index after what was translated.
+///
+/// Python treats a `{` that doesn't start a valid repeat as a literal, while the engine
+/// rejects it, and spells `{0,n}` as `{,n}`.
+fn translate_repeat(chars: &[char], i: usize, out: &mut String) -> usize {
+    if let Some(len) = chars[i..].iter().position(|&c| c == '}') {
+        let inner: String = chars[i + 1..i + len].iter().collect();
+        let (min, max) = match inner.split_once(',') {
+            Some((min, max)) => (min, Some(max)),
+            None => (inner.as_str(), None),
+        };
+        let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        if is_digits(min) && max.is_none_or(is_digits) && !(min.is_empty() && max.is_none()) {
+            out.push('{');
+            out.push_str(if min.is_empty() { "0" } else { min });
+            if let Some(max) = max {
+                out.push(',');
+                out.push_str(max);
+            }
+            out.push('}');
+            return i + len + 1;
+        }
+    }
+    out.push_str("\\{");
+    i + 1
+}
+
+/// Translates the `(?` group opening at `chars[i]`, returning the index after what was translated.
+fn translate_group(chars: &[char], i: usize, out: &mut String) -> RunResult<usize> {
+    match (chars.get(i + 2), chars.get(i + 3)) {
+        (Some('#'), _) => match chars[i..].iter().position(|&c| c == ')') {
+            Some(len) => Ok(i + len + 1),
+            None => Err(pattern_error(format!(
+                "missing ), unterminated comment at position {i}"
+            ))),
+        },
+        (Some('=' | '!'), _) | (Some('<'), Some('=' | '!')) => {
+            Err(pattern_error("lookaround assertions are not supported"))
+        }
+        (Some('P'), Some('=')) => Err(pattern_error("backreferences are not supported")),
+        (Some('('), _) => Err(pattern_error("conditional groups are not supported")),
+        (Some('>'), _) => Err(pattern_error("atomic groups are not supported")),
+        _ => {
+            out.push_str("(?");
+            Ok(i + 2)
+        }
+    }
+}
+
+/// Writes `c` as an escape that matches it literally, inside or outside a class.
+fn push_literal(out: &mut String, c: char) {
+    write!(out, "\\x{{{:X}}}", u32::from(c)).expect("writing to a String can't fail");
+}
+
+/// Creates the `re.error` raised when the engine can't parse a translated pattern.
+fn syntax_error(err: regex_syntax::Error) -> RunError {
+    let message = match &err {
+        regex_syntax::Error::Parse(e) => e.kind().to_string(),
+        regex_syntax::Error::Translate(e) => e.kind().to_string(),
+        _ => err.to_string(),
+    };
+    pattern_error(message)
+}
+
+/// Creates a `re.error` with the given message.
+pub(crate) fn pattern_error(message: impl Into<String>) -> RunError {
+    SimpleException::new_msg(ExcType::PatternError, message.into()).into()
+}
@@ -92,8 +92,15 @@ impl SetStorage {
         }
     }
 
+    /// Returns the elements stored under `hash`, in insertion order.
+    ///
+    /// Used by the VM to find elements equal to an instance of a user-defined class.
+    pub(crate) fn values_with_hash(&self, hash: u64) -> impl Iterator<Item = &Value> {
+        self.entries.iter().filter(move |e| e.hash == hash).map(|e| &e.value)
+    }
+
     /// Returns the number of elements in the set.
-    fn len(&self) -> usize {
+    pub(crate) fn len(&self) -> usize {
         self.entries.len()
     }
 
@@ -750,11 +757,7 @@ impl PyTrait for Set {
         }
     }
 
-    fn py_sub(
-        &self,
-        _other: &Self,
-        _heap: &mut Heap<impl ResourceTracker>,
-    ) -> Result<Option<Value>, crate::resource::ResourceError> {
+    fn py_sub(&self, _other: &Self, _heap: &mut Heap<impl ResourceTracker>) -> RunResult<Option<Value>> {
         // This is called from heap.rs with two Sets
         // We need interns for contains check, but py_sub doesn't have it
         // This is a limitation - we'll need to handle this differently
@@ -1243,11 +1246,7 @@ impl PyTrait for FrozenSet {
         }
     }
 
-    fn py_sub(
-        &self,
-        _other: &Self,
-        _heap: &mut Heap<impl ResourceTracker>,
-    ) -> Result<Option<Value>, crate::resource::ResourceError> {
+    fn py_sub(&self, _other: &Self, _heap: &mut Heap<impl ResourceTracker>) -> RunResult<Option<Value>> {
         // Same limitation as Set - needs interns
         Ok(None)
     }
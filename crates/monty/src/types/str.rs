@@ -282,7 +282,7 @@ impl PyTrait for Str {
         other: &Self,
         heap: &mut Heap<impl ResourceTracker>,
         _interns: &Interns,
-    ) -> Result<Option<Value>, crate::resource::ResourceError> {
+    ) -> RunResult<Option<Value>> {
         let result = format!("{}{}", self.0, other.0);
         let id = heap.allocate(HeapData::Str(result.into()))?;
         Ok(Some(Value::Ref(id)))
@@ -357,14 +357,13 @@ pub fn call_str_method(
 /// - `Str::py_call_attr()` for heap-allocated strings
 /// - `call_str_method()` for interned string literals from the VM
 ///
+/// `format()` and `format_map()` are handled by the VM before reaching this, since
+/// formatting fields can call special methods of user-defined instances.
+///
 /// # Not Yet Implemented
 ///
 /// The following Python string methods are not yet implemented:
 ///
-/// - `format()` - Requires implementing the format spec mini-language (PEP 3101),
-///   which is complex and involves parsing format specifications like `{:>10.2f}`.
-/// - `format_map(mapping)` - Similar to `format()` but takes a mapping; depends on
-///   `format()` implementation.
 /// - `maketrans()` / `translate()` - Character translation tables; moderate complexity,
 ///   requires building and applying Unicode translation maps.
 /// - `expandtabs(tabsize=8)` - Tab expansion; simple but rarely used in practice.
@@ -2174,7 +2173,7 @@ fn parse_encode_args(
 /// Returns True if the string is a valid Python identifier according to
 /// the language definition (starts with letter or underscore, followed by
 /// letters, digits, or underscores). Empty strings return False.
-fn str_isidentifier(s: &str) -> bool {
+pub(crate) fn str_isidentifier(s: &str) -> bool {
     if s.is_empty() {
         return false;
     }
@@ -0,0 +1,426 @@
+//! `strftime()` formatting and `strptime()` parsing for `datetime` and `date`.
+//!
+//! Both follow CPython on Linux in the C locale: `strftime` supports the glibc directives (and
+//! the `-` flag to drop padding), passing unknown directives through unchanged, while `strptime`
+//! supports the directives of CPython's `_strptime` module except the ISO week ones
+//! (`%G`, `%V` and `%u` as input), raising `ValueError` for anything else.
+
+use std::{fmt::Write, ops::RangeInclusive};
+
+use crate::{
+    exception_private::RunResult,
+    types::{
+        Date, DateTime, TimeDelta, TimeZone,
+        date::{iso_calendar, value_error},
+        str::StringRepr,
+        timedelta::MICROS_PER_SECOND,
+        timezone::format_offset,
+    },
+};
+
+/// Full English day names, starting on Monday.
+const DAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+/// Full English month names.
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Formats a datetime according to a `strftime()` format string.
+pub(crate) fn strftime(dt: &DateTime, format: &str) -> String {
+    let mut out = String::new();
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        let no_pad = chars.next_if_eq(&'-').is_some();
+        let Some(directive) = chars.next() else {
+            out.push('%');
+            if no_pad {
+                out.push('-');
+            }
+            break;
+        };
+        let start = out.len();
+        if !format_directive(&mut out, dt, directive) {
+            out.push('%');
+            if no_pad {
+                out.push('-');
+            }
+            out.push(directive);
+        } else if no_pad {
+            let written = &out[start..];
+            let padding = written.len() - written.trim_start_matches(['0', ' ']).len();
+            // Keep a single zero rather than producing an empty number
+            let padding = padding.min(written.len().saturating_sub(1));
+            out.replace_range(start..start + padding, "");
+        }
+    }
+    out
+}
+
+/// Writes the expansion of a single `strftime()` directive, returning `false` if it's unknown.
+fn format_directive(out: &mut String, dt: &DateTime, directive: char) -> bool {
+    let date = dt.date();
+    let [hour, minute, second, microsecond] = dt.time_fields();
+    let weekday = usize::try_from(date.weekday()).expect("weekday is in 0..7");
+    let month = usize::try_from(date.month() - 1).expect("month is in 1..=12");
+    let hour12 = if hour % 12 == 0 { 12 } else { hour % 12 };
+    let result = match directive {
+        'a' => out.write_str(&DAY_NAMES[weekday][..3]),
+        'A' => out.write_str(DAY_NAMES[weekday]),
+        'b' | 'h' => out.write_str(&MONTH_NAMES[month][..3]),
+        'B' => out.write_str(MONTH_NAMES[month]),
+        'c' => {
+            out.push_str(&strftime(dt, "%a %b %e %H:%M:%S %Y"));
+            Ok(())
+        }
+        'C' => write!(out, "{}", date.year() / 100),
+        'd' => write!(out, "{:02}", date.day()),
+        'D' | 'x' => {
+            out.push_str(&strftime(dt, "%m/%d/%y"));
+            Ok(())
+        }
+        'e' => write!(out, "{:2}", date.day()),
+        'f' => write!(out, "{microsecond:06}"),
+        'F' => {
+            out.push_str(&strftime(dt, "%Y-%m-%d"));
+            Ok(())
+        }
+        'g' => write!(out, "{:02}", iso_calendar(date).0 % 100),
+        'G' => write!(out, "{}", iso_calendar(date).0),
+        'H' => write!(out, "{hour:02}"),
+        'I' => write!(out, "{hour12:02}"),
+        'j' => write!(out, "{:03}", date.day_of_year()),
+        'k' => write!(out, "{hour:2}"),
+        'l' => write!(out, "{hour12:2}"),
+        'm' => write!(out, "{:02}", date.month()),
+        'M' => write!(out, "{minute:02}"),
+        'n' => out.write_char('\n'),
+        'p' => out.write_str(if hour < 12 { "AM" } else { "PM" }),
+        'P' => out.write_str(if hour < 12 { "am" } else { "pm" }),
+        'r' => {
+            out.push_str(&strftime(dt, "%I:%M:%S %p"));
+            Ok(())
+        }
+        'R' => write!(out, "{hour:02}:{minute:02}"),
+        // Like glibc, this ignores the timezone and treats the fields as local time, which is UTC here
+        's' => write!(out, "{}", dt_local_timestamp(dt)),
+        'S' => write!(out, "{second:02}"),
+        't' => out.write_char('\t'),
+        'T' | 'X' => write!(out, "{hour:02}:{minute:02}:{second:02}"),
+        'u' => write!(out, "{}", weekday + 1),
+        'U' => write!(out, "{:02}", (date.day_of_year() + 6 - (date.weekday() + 1) % 7) / 7),
+        'V' => write!(out, "{:02}", iso_calendar(date).1),
+        'w' => write!(out, "{}", (weekday + 1) % 7),
+        'W' => write!(out, "{:02}", (date.day_of_year() + 6 - date.weekday()) / 7),
+        'y' => write!(out, "{:02}", date.year() % 100),
+        'Y' => write!(out, "{}", date.year()),
+        'z' => {
+            if let Some(offset) = dt.utcoffset() {
+                out.push_str(&format_offset(offset, ""));
+            }
+            Ok(())
+        }
+        'Z' => {
+            if let Some(tz) = dt.tzinfo() {
+                out.push_str(&tz.tzname());
+            }
+            Ok(())
+        }
+        '%' => out.write_char('%'),
+        _ => return false,
+    };
+    result.expect("writing to a string can't fail");
+    true
+}
+
+/// Returns the whole seconds since the epoch of a datetime's fields, ignoring its timezone.
+fn dt_local_timestamp(dt: &DateTime) -> i128 {
+    let epoch = DateTime::from_date(Date::new(1970, 1, 1).expect("the epoch is a valid date"));
+    (dt.local_micros() - epoch.local_micros()).div_euclid(MICROS_PER_SECOND)
+}
+
+/// Fields parsed by `strptime()`, with CPython's defaults for missing ones.
+struct Parsed {
+    year: i32,
+    month: Option<i32>,
+    day: Option<i32>,
+    day_of_year: Option<i32>,
+    hour: i32,
+    hour12: Option<i32>,
+    pm: Option<bool>,
+    minute: i32,
+    second: i32,
+    microsecond: i32,
+    offset: Option<i128>,
+    tzname: Option<String>,
+}
+
+/// Parses a string according to a `strptime()` format string, as `datetime.strptime()` does.
+///
+/// Raises `ValueError` if the format has a bad directive, if the string doesn't match the format,
+/// or if the parsed fields don't form a valid datetime.
+pub(crate) fn strptime(data: &str, format: &str) -> RunResult<DateTime> {
+    let format = expand_format(format)?;
+    let mismatch = || {
+        value_error(format!(
+            "time data {} does not match format {}",
+            StringRepr(data),
+            StringRepr(format.original)
+        ))
+    };
+    let mut parsed = Parsed {
+        year: 1900,
+        month: None,
+        day: None,
+        day_of_year: None,
+        hour: 0,
+        hour12: None,
+        pm: None,
+        minute: 0,
+        second: 0,
+        microsecond: 0,
+        offset: None,
+        tzname: None,
+    };
+    let mut input = data;
+    let mut chars = format.expanded.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            let directive = chars.next().expect("format was validated");
+            parse_directive(&mut input, directive, &mut parsed).ok_or_else(mismatch)?;
+        } else if c.is_whitespace() {
+            // Like CPython, a run of whitespace in the format matches one or more whitespace characters
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            let rest = input.trim_start();
+            if rest.len() == input.len() {
+                return Err(mismatch());
+            }
+            input = rest;
+        } else {
+            let mut input_chars = input.chars();
+            match input_chars.next() {
+                Some(i) if i.to_lowercase().eq(c.to_lowercase()) => input = input_chars.as_str(),
+                _ => return Err(mismatch()),
+            }
+        }
+    }
+    if !input.is_empty() {
+        return Err(value_error(format!("unconverted data remains: {input}")));
+    }
+    parsed.into_datetime()
+}
+
+/// A `strptime()` format string with `%c`, `%x` and `%X` expanded.
+struct ExpandedFormat<'a> {
+    original: &'a str,
+    expanded: String,
+}
+
+/// Validates a `strptime()` format string and expands its locale directives.
+fn expand_format(format: &str) -> RunResult<ExpandedFormat<'_>> {
+    let mut expanded = String::with_capacity(format.len());
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            expanded.push(c);
+            continue;
+        }
+        match chars.next() {
+            None => return Err(value_error(format!("stray % in format {}", StringRepr(format)))),
+            Some('c') => expanded.push_str("%a %b %d %H:%M:%S %Y"),
+            Some('x') => expanded.push_str("%m/%d/%y"),
+            Some('X') => expanded.push_str("%H:%M:%S"),
+            Some(
+                d @ ('a' | 'A' | 'b' | 'B' | 'd' | 'f' | 'H' | 'I' | 'j' | 'm' | 'M' | 'p' | 'S' | 'U' | 'w' | 'W'
+                | 'y' | 'Y' | 'z' | 'Z' | '%'),
+            ) => {
+                expanded.push('%');
+                expanded.push(d);
+            }
+            Some(d) => {
+                return Err(value_error(format!(
+                    "{} is a bad directive in format {}",
+                    StringRepr(&d.to_string()),
+                    StringRepr(format)
+                )));
+            }
+        }
+    }
+    Ok(ExpandedFormat {
+        original: format,
+        expanded,
+    })
+}
+
+/// Parses the input for one directive, returning `None` if it doesn't match.
+fn parse_directive(input: &mut &str, directive: char, parsed: &mut Parsed) -> Option<()> {
+    match directive {
+        'a' | 'A' => {
+            name(input, &DAY_NAMES)?;
+        }
+        'b' | 'B' => parsed.month = Some(name(input, &MONTH_NAMES)? + 1),
+        'd' => parsed.day = Some(number(input, 2, 1..=31)?),
+        'f' => parsed.microsecond = fraction(input)?,
+        'H' => parsed.hour = number(input, 2, 0..=23)?,
+        'I' => parsed.hour12 = Some(number(input, 2, 1..=12)?),
+        'j' => parsed.day_of_year = Some(number(input, 3, 1..=366)?),
+        'm' => parsed.month = Some(number(input, 2, 1..=12)?),
+        'M' => parsed.minute = number(input, 2, 0..=59)?,
+        'p' => {
+            let prefix = input.get(..2)?;
+            parsed.pm = Some(match prefix.to_ascii_lowercase().as_str() {
+                "am" => false,
+                "pm" => true,
+                _ => return None,
+            });
+            *input = &input[2..];
+        }
+        'S' => parsed.second = number(input, 2, 0..=61)?,
+        'U' | 'W' => {
+            number(input, 2, 0..=53)?;
+        }
+        'w' => {
+            number(input, 1, 0..=6)?;
+        }
+        'y' => {
+            let year = fixed_number(input, 2)?;
+            // POSIX's pivot: 69-99 are 1969-1999 and 00-68 are 2000-2068
+            parsed.year = if year >= 69 { 1900 + year } else { 2000 + year };
+        }
+        'Y' => parsed.year = fixed_number(input, 4)?,
+        'z' => parsed.offset = Some(utc_offset(input)?),
+        'Z' => {
+            let prefix = input.get(..3)?;
+            if !prefix.eq_ignore_ascii_case("utc") && !prefix.eq_ignore_ascii_case("gmt") {
+                return None;
+            }
+            parsed.tzname = Some(prefix.to_owned());
+            *input = &input[3..];
+        }
+        '%' => *input = input.strip_prefix('%')?,
+        _ => unreachable!("format was validated"),
+    }
+    Some(())
+}
+
+/// Parses a number of up to `max_width` digits in `range`, preferring the longest match.
+fn number(input: &mut &str, max_width: usize, range: RangeInclusive<i32>) -> Option<i32> {
+    let digits = input.bytes().take(max_width).take_while(u8::is_ascii_digit).count();
+    for width in (1..=digits).rev() {
+        let value = input[..width].parse::<i32>().ok()?;
+        if range.contains(&value) {
+            *input = &input[width..];
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Parses a number of exactly `width` digits.
+fn fixed_number(input: &mut &str, width: usize) -> Option<i32> {
+    let digits = input.get(..width)?;
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    *input = &input[width..];
+    digits.parse().ok()
+}
+
+/// Parses a full or abbreviated English name case-insensitively, returning its index.
+fn name(input: &mut &str, names: &[&str]) -> Option<i32> {
+    let (index, len) = names
+        .iter()
+        .enumerate()
+        .flat_map(|(index, name)| [(index, name.len()), (index, 3)])
+        .filter(|&(index, len)| {
+            input
+                .get(..len)
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&names[index][..len]))
+        })
+        .max_by_key(|&(_, len)| len)?;
+    *input = &input[len..];
+    i32::try_from(index).ok()
+}
+
+/// Parses 1 to 6 digits of a fraction of a second as microseconds.
+fn fraction(input: &mut &str) -> Option<i32> {
+    let digits = input.bytes().take(6).take_while(u8::is_ascii_digit).count();
+    let mut micros = input[..digits].parse::<i32>().ok()?;
+    for _ in digits..6 {
+        micros *= 10;
+    }
+    *input = &input[digits..];
+    Some(micros)
+}
+
+/// Parses a UTC offset for `%z`: `Z`, or `+HHMM[SS[.ffffff]]` with optional colons, in microseconds.
+fn utc_offset(input: &mut &str) -> Option<i128> {
+    if let Some(rest) = input.strip_prefix('Z') {
+        *input = rest;
+        return Some(0);
+    }
+    let (negative, mut rest) = match input.strip_prefix('+') {
+        Some(rest) => (false, rest),
+        None => (true, input.strip_prefix('-')?),
+    };
+    let hours = fixed_number(&mut rest, 2)?;
+    rest = rest.strip_prefix(':').unwrap_or(rest);
+    let minutes = fixed_number(&mut rest, 2).filter(|m| *m <= 59)?;
+    let (mut seconds, mut micros) = (0, 0);
+    let mut after = rest.strip_prefix(':').unwrap_or(rest);
+    if let Some(s) = fixed_number(&mut after, 2).filter(|s| *s <= 59) {
+        seconds = s;
+        rest = after;
+        if let Some(mut after) = rest.strip_prefix('.')
+            && let Some(m) = fraction(&mut after)
+        {
+            micros = m;
+            rest = after;
+        }
+    }
+    *input = rest;
+    let total = i128::from(hours * 3600 + minutes * 60 + seconds) * MICROS_PER_SECOND + i128::from(micros);
+    Some(if negative { -total } else { total })
+}
+
+impl Parsed {
+    /// Converts the parsed fields to a datetime, validating them.
+    fn into_datetime(self) -> RunResult<DateTime> {
+        // Like CPython, the day of the year takes precedence over the month and day
+        let date = match self.day_of_year {
+            Some(day_of_year) => Date::new(self.year, 1, 1)?.add_days(i64::from(day_of_year - 1))?,
+            None => Date::new(self.year, self.month.unwrap_or(1), self.day.unwrap_or(1))?,
+        };
+        let hour = match (self.hour12, self.pm) {
+            (Some(hour12), pm) => hour12 % 12 + if pm == Some(true) { 12 } else { 0 },
+            (None, _) => self.hour,
+        };
+        let tzinfo = match self.offset {
+            Some(micros) => Some(TimeZone::new(TimeDelta::from_micros(micros)?, self.tzname)?),
+            None => None,
+        };
+        DateTime::new(date, [hour, self.minute, self.second, self.microsecond], tzinfo)
+    }
+}
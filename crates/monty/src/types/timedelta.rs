@@ -0,0 +1,476 @@
+//! Python `datetime.timedelta` type: a duration, as a number of days, seconds and microseconds.
+//!
+//! Like CPython, timedeltas are normalized so that `0 <= seconds < 86400` and
+//! `0 <= microseconds < 1000000`, with only `days` carrying the sign, which makes the derived
+//! ordering match Python's. Arithmetic is done on the total number of microseconds as an `i128`,
+//! and results whose magnitude exceeds 999999999 days raise `OverflowError`.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt::{self, Display, Write},
+    hash::{Hash, Hasher},
+};
+
+use ahash::AHashSet;
+use num_traits::ToPrimitive;
+
+use crate::{
+    args::{ArgValues, bind_args},
+    defer_drop,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{AttrCallResult, PyTrait, Type},
+    value::{EitherStr, Value},
+};
+
+/// The largest magnitude of `timedelta.days`.
+pub(crate) const MAX_DAYS: i32 = 999_999_999;
+/// Microseconds in a second.
+pub(crate) const MICROS_PER_SECOND: i128 = 1_000_000;
+/// Microseconds in a day.
+pub(crate) const MICROS_PER_DAY: i128 = 86_400 * MICROS_PER_SECOND;
+
+/// Python `datetime.timedelta`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
+pub(crate) struct TimeDelta {
+    days: i32,
+    seconds: i32,
+    microseconds: i32,
+}
+
+impl TimeDelta {
+    /// `timedelta(0)`.
+    pub const ZERO: Self = Self {
+        days: 0,
+        seconds: 0,
+        microseconds: 0,
+    };
+    /// `timedelta.min`, the most negative timedelta.
+    pub const MIN: Self = Self {
+        days: -MAX_DAYS,
+        seconds: 0,
+        microseconds: 0,
+    };
+    /// `timedelta.max`, the most positive timedelta.
+    pub const MAX: Self = Self {
+        days: MAX_DAYS,
+        seconds: 86_399,
+        microseconds: 999_999,
+    };
+    /// `timedelta.resolution`, the smallest difference between non-equal timedeltas.
+    pub const RESOLUTION: Self = Self {
+        days: 0,
+        seconds: 0,
+        microseconds: 1,
+    };
+
+    /// Creates a normalized timedelta from a total number of microseconds.
+    ///
+    /// Raises `OverflowError` if the number of days is out of range.
+    pub fn from_micros(micros: i128) -> RunResult<Self> {
+        let days = micros.div_euclid(MICROS_PER_DAY);
+        let rest = micros.rem_euclid(MICROS_PER_DAY);
+        let days = i32::try_from(days)
+            .ok()
+            .filter(|days| days.abs() <= MAX_DAYS)
+            .ok_or_else(|| {
+                SimpleException::new_msg(
+                    ExcType::OverflowError,
+                    format!("days={days}; must have magnitude <= {MAX_DAYS}"),
+                )
+            })?;
+        Ok(Self {
+            days,
+            seconds: i32::try_from(rest / MICROS_PER_SECOND).expect("a day has fewer than 2**31 seconds"),
+            microseconds: i32::try_from(rest % MICROS_PER_SECOND).expect("a second has fewer than 2**31 microseconds"),
+        })
+    }
+
+    /// Creates a timedelta of whole days, which must be within range.
+    #[must_use]
+    pub fn from_days(days: i32) -> Self {
+        debug_assert!(days.abs() <= MAX_DAYS, "days out of range");
+        Self {
+            days,
+            seconds: 0,
+            microseconds: 0,
+        }
+    }
+
+    /// Returns the total number of microseconds.
+    #[must_use]
+    pub fn total_micros(self) -> i128 {
+        i128::from(self.days) * MICROS_PER_DAY
+            + i128::from(self.seconds) * MICROS_PER_SECOND
+            + i128::from(self.microseconds)
+    }
+
+    /// Returns the `days` attribute, which carries the sign.
+    #[must_use]
+    pub fn days(self) -> i32 {
+        self.days
+    }
+
+    /// Returns the `seconds` attribute, in `0..86400`.
+    #[must_use]
+    pub fn seconds(self) -> i32 {
+        self.seconds
+    }
+
+    /// Returns the `microseconds` attribute, in `0..1000000`.
+    #[must_use]
+    pub fn microseconds(self) -> i32 {
+        self.microseconds
+    }
+
+    /// Allocates this timedelta on the heap.
+    pub fn into_value(self, heap: &mut Heap<impl ResourceTracker>) -> Result<Value, ResourceError> {
+        Ok(Value::Ref(heap.allocate(HeapData::TimeDelta(self))?))
+    }
+
+    /// Returns the timedelta `value` refers to, if it is one.
+    pub fn from_value(value: &Value, heap: &Heap<impl ResourceTracker>) -> Option<Self> {
+        match value {
+            Value::Ref(id) => match heap.get(*id) {
+                HeapData::TimeDelta(delta) => Some(*delta),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Computes a hash consistent with equality.
+    #[must_use]
+    pub fn hash(self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        Hash::hash(&self, &mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns `self + other`.
+    pub fn add(self, other: Self) -> RunResult<Self> {
+        Self::from_micros(self.total_micros() + other.total_micros())
+    }
+
+    /// Returns `self - other`.
+    pub fn sub(self, other: Self) -> RunResult<Self> {
+        Self::from_micros(self.total_micros() - other.total_micros())
+    }
+
+    /// Returns `-self`, which overflows for `-timedelta.max`.
+    pub fn neg(self) -> RunResult<Self> {
+        Self::from_micros(-self.total_micros())
+    }
+
+    /// Returns `abs(self)`.
+    pub fn abs(self) -> RunResult<Self> {
+        if self.days < 0 { self.neg() } else { Ok(self) }
+    }
+
+    /// Returns `self * n`.
+    pub fn mul_int(self, n: i128) -> RunResult<Self> {
+        let micros = self.total_micros().checked_mul(n).ok_or_else(int_too_large)?;
+        Self::from_micros(micros)
+    }
+
+    /// Returns `self * f`, rounding to the nearest microsecond with ties going to even.
+    pub fn mul_float(self, f: f64) -> RunResult<Self> {
+        check_ratio(f)?;
+        Self::from_micros(round_micros(self.total_micros() as f64 * f)?)
+    }
+
+    /// Returns `self / n`, rounding to the nearest microsecond with ties going to even.
+    pub fn div_int(self, n: i128) -> RunResult<Self> {
+        if n == 0 {
+            return Err(integer_division_by_zero());
+        }
+        Self::from_micros(div_round_half_even(self.total_micros(), n))
+    }
+
+    /// Returns `self / f`, rounding to the nearest microsecond with ties going to even.
+    pub fn div_float(self, f: f64) -> RunResult<Self> {
+        check_ratio(f)?;
+        if f == 0.0 {
+            return Err(integer_division_by_zero());
+        }
+        Self::from_micros(round_micros(self.total_micros() as f64 / f)?)
+    }
+
+    /// Returns `self // n`.
+    pub fn floordiv_int(self, n: i128) -> RunResult<Self> {
+        if n == 0 {
+            return Err(integer_division_by_zero());
+        }
+        Self::from_micros(floor_div(self.total_micros(), n))
+    }
+
+    /// Returns `self / other` as a float.
+    pub fn div(self, other: Self) -> RunResult<f64> {
+        if other == Self::ZERO {
+            return Err(ExcType::zero_division().into());
+        }
+        Ok(self.total_micros() as f64 / other.total_micros() as f64)
+    }
+
+    /// Returns `self // other`, the number of whole `other`s in `self`.
+    pub fn floordiv(self, other: Self) -> RunResult<i128> {
+        if other == Self::ZERO {
+            return Err(integer_division_by_zero());
+        }
+        Ok(floor_div(self.total_micros(), other.total_micros()))
+    }
+
+    /// Returns `self % other`, which has the sign of `other`.
+    pub fn rem(self, other: Self) -> RunResult<Self> {
+        if other == Self::ZERO {
+            return Err(SimpleException::new_msg(ExcType::ZeroDivisionError, "integer modulo by zero").into());
+        }
+        let (a, b) = (self.total_micros(), other.total_micros());
+        Self::from_micros(a - floor_div(a, b) * b)
+    }
+
+    /// Returns the total duration in seconds, as returned by `total_seconds()`.
+    #[must_use]
+    pub fn total_seconds(self) -> f64 {
+        self.total_micros() as f64 / MICROS_PER_SECOND as f64
+    }
+
+    /// Creates a timedelta from the `timedelta()` constructor call.
+    ///
+    /// Accepts `days`, `seconds`, `microseconds`, `milliseconds`, `minutes`, `hours` and `weeks`,
+    /// by position or keyword, each an int or a float. Fractional microseconds are rounded
+    /// to the nearest microsecond, with ties going to even.
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        const PARAMS: [&str; 7] = [
+            "days",
+            "seconds",
+            "microseconds",
+            "milliseconds",
+            "minutes",
+            "hours",
+            "weeks",
+        ];
+        const UNITS: [i128; 7] = [
+            MICROS_PER_DAY,
+            MICROS_PER_SECOND,
+            1,
+            1000,
+            60 * MICROS_PER_SECOND,
+            3600 * MICROS_PER_SECOND,
+            7 * MICROS_PER_DAY,
+        ];
+
+        let args = bind_args("timedelta", PARAMS, 0, args, heap, interns)?;
+        defer_drop!(args, heap);
+
+        let mut micros: i128 = 0;
+        let mut float_micros: Option<f64> = None;
+        for (index, (param, unit)) in PARAMS.iter().zip(UNITS).enumerate() {
+            let Some(value) = args.get(index) else { continue };
+            match value {
+                Value::Float(f) => *float_micros.get_or_insert(0.0) += f * unit as f64,
+                Value::Int(i) => micros += i128::from(*i) * unit,
+                Value::Bool(b) => micros += i128::from(*b) * unit,
+                Value::Ref(id) if matches!(heap.get(*id), HeapData::LongInt(_)) => {
+                    let HeapData::LongInt(li) = heap.get(*id) else {
+                        unreachable!()
+                    };
+                    let i = li.inner().to_i128().ok_or_else(int_too_large)?;
+                    micros = i
+                        .checked_mul(unit)
+                        .and_then(|m| m.checked_add(micros))
+                        .ok_or_else(int_too_large)?;
+                }
+                _ => {
+                    return Err(ExcType::type_error(format!(
+                        "unsupported type for timedelta {param} component: {}",
+                        value.py_type(heap)
+                    )));
+                }
+            }
+        }
+        if let Some(float_micros) = float_micros {
+            micros += round_micros(float_micros)?;
+        }
+        Ok(Self::from_micros(micros)?.into_value(heap)?)
+    }
+
+    /// Writes the `repr()` of this timedelta, e.g. `datetime.timedelta(days=1, seconds=5)`.
+    pub fn fmt_repr(self, f: &mut impl Write) -> fmt::Result {
+        f.write_str("datetime.timedelta(")?;
+        let mut sep = "";
+        for (name, value) in [
+            ("days", self.days),
+            ("seconds", self.seconds),
+            ("microseconds", self.microseconds),
+        ] {
+            if value != 0 {
+                write!(f, "{sep}{name}={value}")?;
+                sep = ", ";
+            }
+        }
+        if sep.is_empty() {
+            f.write_char('0')?;
+        }
+        f.write_char(')')
+    }
+}
+
+/// Formats the timedelta like `str()`, e.g. `1 day, 0:00:05` or `-2 days, 23:59:59.500000`.
+impl Display for TimeDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.days != 0 {
+            let plural = if self.days.abs() == 1 { "" } else { "s" };
+            write!(f, "{} day{plural}, ", self.days)?;
+        }
+        let (hours, minutes, seconds) = (self.seconds / 3600, self.seconds / 60 % 60, self.seconds % 60);
+        write!(f, "{hours}:{minutes:02}:{seconds:02}")?;
+        if self.microseconds != 0 {
+            write!(f, ".{:06}", self.microseconds)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns `floor(a / b)`.
+pub(crate) fn floor_div(a: i128, b: i128) -> i128 {
+    if b < 0 { (-a).div_euclid(-b) } else { a.div_euclid(b) }
+}
+
+/// Returns `a / b` rounded to the nearest integer, with ties going to even.
+fn div_round_half_even(a: i128, b: i128) -> i128 {
+    let (a, b) = if b < 0 { (-a, -b) } else { (a, b) };
+    let quotient = a.div_euclid(b);
+    let twice_rest = 2 * a.rem_euclid(b);
+    if twice_rest > b || (twice_rest == b && quotient % 2 != 0) {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+/// Rounds a float number of microseconds to an integer, with ties going to even.
+fn round_micros(micros: f64) -> RunResult<i128> {
+    if micros.is_nan() {
+        return Err(SimpleException::new_msg(ExcType::ValueError, "cannot convert float NaN to integer").into());
+    }
+    if micros.is_infinite() {
+        return Err(
+            SimpleException::new_msg(ExcType::OverflowError, "cannot convert float infinity to integer").into(),
+        );
+    }
+    let rounded = micros.round_ties_even();
+    // Anything this large is far outside the range of a timedelta
+    if rounded.abs() >= 1e30 {
+        return Err(int_too_large());
+    }
+    #[expect(clippy::cast_possible_truncation, reason = "magnitude checked above")]
+    Ok(rounded as i128)
+}
+
+/// Checks that a float can multiply or divide a timedelta, as CPython converts it to an integer ratio.
+fn check_ratio(f: f64) -> RunResult<()> {
+    if f.is_nan() {
+        Err(SimpleException::new_msg(ExcType::ValueError, "cannot convert NaN to integer ratio").into())
+    } else if f.is_infinite() {
+        Err(SimpleException::new_msg(ExcType::OverflowError, "cannot convert Infinity to integer ratio").into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Creates the `OverflowError` for integers too large to be a number of microseconds.
+pub(crate) fn int_too_large() -> RunError {
+    SimpleException::new_msg(ExcType::OverflowError, "Python int too large to convert to C int").into()
+}
+
+/// Creates the `ZeroDivisionError` for integer division of a timedelta by zero.
+fn integer_division_by_zero() -> RunError {
+    SimpleException::new_msg(ExcType::ZeroDivisionError, "integer division or modulo by zero").into()
+}
+
+impl PyTrait for TimeDelta {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::TimeDelta
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self == other)
+    }
+
+    fn py_cmp(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<Option<std::cmp::Ordering>, ResourceError> {
+        Ok(Some(self.cmp(other)))
+    }
+
+    fn py_dec_ref_ids(&mut self, _stack: &mut Vec<HeapId>) {
+        // Timedeltas don't contain heap references
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        *self != Self::ZERO
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> fmt::Result {
+        self.fmt_repr(f)
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        if attr.static_string() == Some(StaticStrings::TotalSeconds) {
+            args.check_zero_args("timedelta.total_seconds", heap)?;
+            Ok(Value::Float(self.total_seconds()))
+        } else {
+            args.drop_with_heap(heap);
+            Err(ExcType::attribute_error(Type::TimeDelta, attr.as_str(interns)))
+        }
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        let value = match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::Days) => self.days,
+            Some(StaticStrings::Seconds) => self.seconds,
+            Some(StaticStrings::Microseconds) => self.microseconds,
+            _ => return Ok(None),
+        };
+        Ok(Some(AttrCallResult::Value(Value::Int(i64::from(value)))))
+    }
+}
@@ -0,0 +1,250 @@
+//! Python `datetime.timezone` type: a fixed offset from UTC with an optional name.
+//!
+//! This is the only `tzinfo` implementation; there is no timezone database in the sandbox.
+//! Datetimes store their timezone inline rather than as a heap reference, so `dt.tzinfo`
+//! returns a new, equal, timezone object each time.
+
+use std::fmt::{self, Display, Write};
+
+use ahash::AHashSet;
+
+use crate::{
+    args::{ArgValues, bind_args},
+    defer_drop,
+    exception_private::{ExcType, RunError, RunResult},
+    heap::{Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{
+        PyTrait, TimeDelta, Type,
+        date::value_error,
+        str::{StringRepr, allocate_string},
+        timedelta::MICROS_PER_DAY,
+    },
+    value::{EitherStr, Value},
+};
+
+/// Python `datetime.timezone`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct TimeZone {
+    /// The offset from UTC, strictly between -24 and 24 hours.
+    offset: TimeDelta,
+    /// The name passed to the constructor, returned by `tzname()` instead of the default name.
+    name: Option<String>,
+}
+
+impl TimeZone {
+    /// `timezone.utc`.
+    pub const UTC: Self = Self {
+        offset: TimeDelta::ZERO,
+        name: None,
+    };
+
+    /// Creates a timezone, raising `ValueError` if the offset is 24 hours or more.
+    pub fn new(offset: TimeDelta, name: Option<String>) -> RunResult<Self> {
+        if offset.total_micros().abs() >= MICROS_PER_DAY {
+            let mut repr = String::new();
+            offset.fmt_repr(&mut repr).expect("writing to a string can't fail");
+            return Err(value_error(format!(
+                "offset must be a timedelta strictly between -timedelta(hours=24) and timedelta(hours=24), not {repr}."
+            )));
+        }
+        Ok(Self { offset, name })
+    }
+
+    /// Creates an unnamed timezone from an offset known to be in range.
+    #[must_use]
+    pub fn from_offset(offset: TimeDelta) -> Self {
+        Self { offset, name: None }
+    }
+
+    /// Returns the offset from UTC.
+    #[must_use]
+    pub fn offset(&self) -> TimeDelta {
+        self.offset
+    }
+
+    /// Returns the name returned by `tzname()`: the constructor's name, `UTC`, or e.g. `UTC+05:30`.
+    #[must_use]
+    pub fn tzname(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None if self.offset == TimeDelta::ZERO => "UTC".to_owned(),
+            None => format!("UTC{}", format_offset(self.offset, ":")),
+        }
+    }
+
+    /// Allocates this timezone on the heap.
+    pub fn into_value(self, heap: &mut Heap<impl ResourceTracker>) -> Result<Value, ResourceError> {
+        Ok(Value::Ref(heap.allocate(HeapData::TimeZone(self))?))
+    }
+
+    /// Computes a hash consistent with equality, which only considers the offset.
+    #[must_use]
+    pub fn hash(&self) -> u64 {
+        self.offset.hash()
+    }
+
+    /// Creates a timezone from the `timezone(offset, name=None)` constructor call.
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let args = bind_args("timezone", ["offset", "name"], 1, args, heap, interns)?;
+        defer_drop!(args, heap);
+        let offset = args.get(0).expect("offset is required");
+        let Some(offset) = TimeDelta::from_value(offset, heap) else {
+            return Err(ExcType::type_error(format!(
+                "timezone() argument 1 must be datetime.timedelta, not {}",
+                offset.py_type(heap)
+            )));
+        };
+        let name = match args.get(1) {
+            Some(name) => match name.as_either_str(heap) {
+                Some(name) => Some(name.as_str(interns).to_owned()),
+                None => {
+                    return Err(ExcType::type_error(format!(
+                        "timezone() argument 2 must be str, not {}",
+                        name.py_type(heap)
+                    )));
+                }
+            },
+            None => None,
+        };
+        Ok(Self::new(offset, name)?.into_value(heap)?)
+    }
+
+    /// Converts the `tzinfo` argument of a datetime constructor or method, which must be a timezone or `None`.
+    pub fn from_tzinfo_arg(value: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<Option<Self>> {
+        match value {
+            Value::None => Ok(None),
+            Value::Ref(id) if matches!(heap.get(*id), HeapData::TimeZone(_)) => {
+                let HeapData::TimeZone(tz) = heap.get(*id) else {
+                    unreachable!()
+                };
+                Ok(Some(tz.clone()))
+            }
+            _ => Err(ExcType::type_error(format!(
+                "tzinfo argument must be None or of a tzinfo subclass, not type '{}'",
+                value.py_type(heap)
+            ))),
+        }
+    }
+}
+
+/// Formats the timezone like `str()`, which is its `tzname()`.
+impl Display for TimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.tzname())
+    }
+}
+
+/// Formats a UTC offset as `+HH<sep>MM`, adding seconds and microseconds only when non-zero.
+///
+/// This is the format used by `isoformat()` and `tzname()` (with `:`) and `%z` (with no separator).
+pub(crate) fn format_offset(offset: TimeDelta, sep: &str) -> String {
+    let (sign, offset) = if offset.days() < 0 {
+        ('-', offset.neg().expect("offsets are less than a day"))
+    } else {
+        ('+', offset)
+    };
+    let seconds = offset.seconds();
+    let mut out = format!("{sign}{:02}{sep}{:02}", seconds / 3600, seconds / 60 % 60);
+    if seconds % 60 != 0 || offset.microseconds() != 0 {
+        write!(out, "{sep}{:02}", seconds % 60).expect("writing to a string can't fail");
+        if offset.microseconds() != 0 {
+            write!(out, ".{:06}", offset.microseconds()).expect("writing to a string can't fail");
+        }
+    }
+    out
+}
+
+/// Checks the `dt` argument of `utcoffset()`, `tzname()` and `dst()`, which must be a datetime or `None`.
+fn check_dt_arg(method: &str, value: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<()> {
+    match value {
+        Value::None => Ok(()),
+        Value::Ref(id) if matches!(heap.get(*id), HeapData::DateTime(_)) => Ok(()),
+        _ => Err(dt_arg_error(method, value.py_type(heap))),
+    }
+}
+
+/// Creates the `TypeError` for a bad `dt` argument.
+fn dt_arg_error(method: &str, found: Type) -> RunError {
+    ExcType::type_error(format!(
+        "{method}(dt) argument must be a datetime instance or None, not {found}"
+    ))
+}
+
+impl PyTrait for TimeZone {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::TimeZone
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.name.as_ref().map_or(0, String::len)
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self.offset == other.offset)
+    }
+
+    fn py_dec_ref_ids(&mut self, _stack: &mut Vec<HeapId>) {
+        // Timezones don't contain heap references
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> fmt::Result {
+        if *self == Self::UTC {
+            return f.write_str("datetime.timezone.utc");
+        }
+        f.write_str("datetime.timezone(")?;
+        self.offset.fmt_repr(f)?;
+        if let Some(name) = &self.name {
+            write!(f, ", {}", StringRepr(name))?;
+        }
+        f.write_char(')')
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let method = attr.static_string();
+        if !matches!(
+            method,
+            Some(StaticStrings::Utcoffset | StaticStrings::Tzname | StaticStrings::Dst)
+        ) {
+            args.drop_with_heap(heap);
+            return Err(ExcType::attribute_error(Type::TimeZone, attr.as_str(interns)));
+        }
+        let name = attr.as_str(interns);
+        let dt = args.get_one_arg(&format!("timezone.{name}"), heap)?;
+        defer_drop!(dt, heap);
+        check_dt_arg(name, dt, heap)?;
+        match method {
+            Some(StaticStrings::Utcoffset) => Ok(self.offset.into_value(heap)?),
+            Some(StaticStrings::Tzname) => allocate_string(self.tzname(), heap),
+            _ => Ok(Value::None),
+        }
+    }
+}
@@ -226,7 +226,7 @@ impl PyTrait for Tuple {
         other: &Self,
         heap: &mut Heap<impl ResourceTracker>,
         _interns: &Interns,
-    ) -> Result<Option<Value>, crate::resource::ResourceError> {
+    ) -> RunResult<Option<Value>> {
         // Clone both tuples' contents with proper refcounting
         let mut result: TupleVec = self.items.iter().map(|obj| obj.clone_with_heap(heap)).collect();
         let other_cloned = other.items.iter().map(|obj| obj.clone_with_heap(heap));
@@ -260,8 +260,8 @@ impl PyTrait for Tuple {
         interns: &Interns,
     ) -> RunResult<Value> {
         match attr.static_string() {
-            Some(StaticStrings::Index) => tuple_index(self, args, heap, interns),
-            Some(StaticStrings::Count) => tuple_count(self, args, heap, interns),
+            Some(StaticStrings::Index) => tuple_index(self.as_slice(), args, heap, interns),
+            Some(StaticStrings::Count) => tuple_count(self.as_slice(), args, heap, interns),
             _ => {
                 args.drop_with_heap(heap);
                 Err(ExcType::attribute_error(Type::Tuple, attr.as_str(interns)))
@@ -288,18 +288,18 @@ impl PyTrait for Tuple {
 /// Implements Python's `tuple.index(value[, start[, end]])` method.
 ///
 /// Returns the index of the first occurrence of value.
-/// Raises ValueError if the value is not found.
-fn tuple_index(
-    tuple: &Tuple,
+/// Raises ValueError if the value is not found. Shared with named tuples.
+pub(crate) fn tuple_index(
+    items: &[Value],
     args: ArgValues,
     heap: &mut Heap<impl ResourceTracker>,
     interns: &Interns,
 ) -> RunResult<Value> {
-    let (value, start, end) = parse_tuple_index_args("tuple.index", tuple.as_slice().len(), args, heap)?;
+    let (value, start, end) = parse_tuple_index_args("tuple.index", items.len(), args, heap)?;
 
     let mut guard = DepthGuard::default();
     // Search for the value in the specified range
-    for (i, item) in tuple.as_slice()[start..end].iter().enumerate() {
+    for (i, item) in items[start..end].iter().enumerate() {
         if value.py_eq(item, heap, &mut guard, interns)? {
             value.drop_with_heap(heap);
             let idx = i64::try_from(start + i).expect("index exceeds i64::MAX");
@@ -313,9 +313,9 @@ fn tuple_index(
 
 /// Implements Python's `tuple.count(value)` method.
 ///
-/// Returns the number of occurrences of value in the tuple.
-fn tuple_count(
-    tuple: &Tuple,
+/// Returns the number of occurrences of value in the tuple. Shared with named tuples.
+pub(crate) fn tuple_count(
+    items: &[Value],
     args: ArgValues,
     heap: &mut Heap<impl ResourceTracker>,
     interns: &Interns,
@@ -325,7 +325,7 @@ fn tuple_count(
 
     let mut guard = DepthGuard::default();
     let mut count = 0usize;
-    for item in tuple.as_slice() {
+    for item in items {
         if value.py_eq(item, heap, &mut guard, interns)? {
             count += 1;
         }
@@ -11,7 +11,9 @@ use crate::{
     intern::Interns,
     resource::ResourceTracker,
     types::{
-        Bytes, Dict, FrozenSet, List, LongInt, MontyIter, Path, PyTrait, Range, Set, Slice, Str, Tuple, str::StringRepr,
+        ByteArray, Bytes, Complex, Counter, Date, DateTime, DefaultDict, Deque, Dict, FrozenSet, List, LongInt,
+        MemoryView, MontyIter, OrderedDict, Path, PyTrait, Range, Set, Slice, Str, TimeDelta, TimeZone, Tuple,
+        str::StringRepr,
     },
     value::Value,
 };
@@ -33,10 +35,13 @@ pub enum Type {
     Bool,
     Int,
     Float,
+    Complex,
     Range,
     Slice,
     Str,
     Bytes,
+    ByteArray,
+    MemoryView,
     List,
     Tuple,
     NamedTuple,
@@ -53,6 +58,12 @@ pub enum Type {
     Iterator,
     /// Coroutine type for async functions and external futures.
     Coroutine,
+    /// A generator object created by calling a generator function
+    #[strum(disabled)]
+    Generator,
+    /// An async generator object created by calling an async generator function
+    #[strum(disabled)]
+    AsyncGenerator,
     Module,
     /// Marker types like stdout/stderr - displays as "TextIOWrapper"
     #[strum(serialize = "TextIOWrapper")]
@@ -60,12 +71,56 @@ pub enum Type {
     /// typing module special forms (Any, Optional, Union, etc.) - displays as "typing._SpecialForm"
     #[strum(serialize = "typing._SpecialForm")]
     SpecialForm,
+    /// PEP 695 type aliases created by `type X = ...` - displays as "typing.TypeAliasType"
+    #[strum(serialize = "typing.TypeAliasType")]
+    TypeAliasType,
     /// A filesystem path from `pathlib.Path` - displays as "PosixPath"
     #[strum(serialize = "PosixPath")]
     Path,
     /// A property descriptor - displays as "property"
     #[strum(serialize = "property")]
     Property,
+    /// The `object` base type, also the type reported for instances of user-defined classes
+    Object,
+    /// A function bound to an object (e.g. `obj.method`)
+    #[strum(disabled)]
+    Method,
+    /// The proxy object returned by `super()`
+    #[strum(disabled)]
+    Super,
+    /// A code object returned by `compile()`
+    #[strum(disabled)]
+    Code,
+    /// A compiled regular expression from `re.compile()` - displays as "re.Pattern"
+    #[strum(disabled)]
+    RePattern,
+    /// The result of a successful regular expression match - displays as "re.Match"
+    #[strum(disabled)]
+    ReMatch,
+    /// A calendar date from `datetime.date` - displays as "datetime.date"
+    #[strum(disabled)]
+    Date,
+    /// A date and time from `datetime.datetime` - displays as "datetime.datetime"
+    #[strum(disabled)]
+    DateTime,
+    /// A duration from `datetime.timedelta` - displays as "datetime.timedelta"
+    #[strum(disabled)]
+    TimeDelta,
+    /// A fixed-offset timezone from `datetime.timezone` - displays as "datetime.timezone"
+    #[strum(disabled)]
+    TimeZone,
+    /// A double-ended queue from `collections.deque` - displays as "collections.deque"
+    #[strum(disabled)]
+    Deque,
+    /// A dict with a default factory from `collections.defaultdict` - displays as "collections.defaultdict"
+    #[strum(disabled)]
+    DefaultDict,
+    /// A dict of counts from `collections.Counter` - displays as "Counter"
+    #[strum(disabled)]
+    Counter,
+    /// An ordered dict from `collections.OrderedDict` - displays as "collections.OrderedDict"
+    #[strum(disabled)]
+    OrderedDict,
 }
 
 impl fmt::Display for Type {
@@ -77,10 +132,13 @@ impl fmt::Display for Type {
             Self::Bool => f.write_str("bool"),
             Self::Int => f.write_str("int"),
             Self::Float => f.write_str("float"),
+            Self::Complex => f.write_str("complex"),
             Self::Range => f.write_str("range"),
             Self::Slice => f.write_str("slice"),
             Self::Str => f.write_str("str"),
             Self::Bytes => f.write_str("bytes"),
+            Self::ByteArray => f.write_str("bytearray"),
+            Self::MemoryView => f.write_str("memoryview"),
             Self::List => f.write_str("list"),
             Self::Tuple => f.write_str("tuple"),
             Self::NamedTuple => f.write_str("namedtuple"),
@@ -94,11 +152,28 @@ impl fmt::Display for Type {
             Self::Cell => f.write_str("cell"),
             Self::Iterator => f.write_str("iterator"),
             Self::Coroutine => f.write_str("coroutine"),
+            Self::Generator => f.write_str("generator"),
+            Self::AsyncGenerator => f.write_str("async_generator"),
             Self::Module => f.write_str("module"),
             Self::TextIOWrapper => f.write_str("_io.TextIOWrapper"),
             Self::SpecialForm => f.write_str("typing._SpecialForm"),
+            Self::TypeAliasType => f.write_str("typing.TypeAliasType"),
             Self::Path => f.write_str("PosixPath"),
             Self::Property => f.write_str("property"),
+            Self::Object => f.write_str("object"),
+            Self::Method => f.write_str("method"),
+            Self::Super => f.write_str("super"),
+            Self::Code => f.write_str("code"),
+            Self::RePattern => f.write_str("re.Pattern"),
+            Self::ReMatch => f.write_str("re.Match"),
+            Self::Date => f.write_str("datetime.date"),
+            Self::DateTime => f.write_str("datetime.datetime"),
+            Self::TimeDelta => f.write_str("datetime.timedelta"),
+            Self::TimeZone => f.write_str("datetime.timezone"),
+            Self::Deque => f.write_str("collections.deque"),
+            Self::DefaultDict => f.write_str("collections.defaultdict"),
+            Self::Counter => f.write_str("Counter"),
+            Self::OrderedDict => f.write_str("collections.OrderedDict"),
         }
     }
 }
@@ -108,15 +183,25 @@ impl Type {
     ///
     /// This handles Python's subtype relationships:
     /// - `bool` is a subtype of `int` (so `isinstance(True, int)` returns True)
+    /// - `datetime.datetime` is a subtype of `datetime.date`
+    /// - `defaultdict`, `Counter` and `OrderedDict` are subtypes of `dict`
+    /// - named tuples are subtypes of `tuple`
+    /// - every type is a subtype of `object`
     #[must_use]
     pub fn is_instance_of(self, other: Self) -> bool {
-        if self == other {
+        if self == other || other == Self::Object {
             true
         } else if self == Self::Bool && other == Self::Int {
             // bool is a subtype of int in Python
             true
+        } else if self == Self::DateTime && other == Self::Date {
+            // datetime is a subtype of date in Python
+            true
+        } else if other == Self::Dict {
+            // defaultdict, Counter and OrderedDict are dict subclasses
+            matches!(self, Self::DefaultDict | Self::Counter | Self::OrderedDict)
         } else {
-            false
+            self == Self::NamedTuple && other == Self::Tuple
         }
     }
 
@@ -141,6 +226,9 @@ impl Type {
             Self::Slice => Some(11),
             Self::Iterator => Some(12),
             Self::Path => Some(13),
+            Self::Complex => Some(14),
+            Self::ByteArray => Some(15),
+            Self::MemoryView => Some(16),
             _ => None,
         }
     }
@@ -165,6 +253,9 @@ impl Type {
             11 => Some(Self::Slice),
             12 => Some(Self::Iterator),
             13 => Some(Self::Path),
+            14 => Some(Self::Complex),
+            15 => Some(Self::ByteArray),
+            16 => Some(Self::MemoryView),
             _ => None,
         }
     }
@@ -192,6 +283,17 @@ impl Type {
             Self::Slice => Slice::init(heap, args),
             Self::Iterator => MontyIter::init(heap, args, interns),
             Self::Path => Path::init(heap, args, interns),
+            Self::Complex => Complex::init(heap, args, interns),
+            Self::ByteArray => ByteArray::init(heap, args, interns),
+            Self::MemoryView => MemoryView::init(heap, args, interns),
+            Self::Date => Date::init(heap, args, interns),
+            Self::DateTime => DateTime::init(heap, args, interns),
+            Self::TimeDelta => TimeDelta::init(heap, args, interns),
+            Self::TimeZone => TimeZone::init(heap, args, interns),
+            Self::Deque => Deque::init(heap, args, interns),
+            Self::DefaultDict => DefaultDict::init(heap, args, interns),
+            Self::Counter => Counter::init(heap, args, interns),
+            Self::OrderedDict => OrderedDict::init(heap, args, interns),
 
             // Primitive types - inline implementation
             Self::Int => {
@@ -17,13 +17,19 @@ use crate::{
     asyncio::CallId,
     builtins::Builtins,
     exception_private::{ExcType, RunError, RunResult, SimpleException},
+    expressions::Operator,
     heap::{Heap, HeapData, HeapId},
     intern::{BytesId, ExtFunctionId, FunctionId, Interns, LongIntId, StaticStrings, StringId},
     modules::ModuleFunctions,
     resource::{DepthGuard, ResourceError, ResourceTracker, check_lshift_size, check_pow_size, check_repeat_size},
     types::{
-        AttrCallResult, LongInt, Property, PyTrait, Str, Type,
-        bytes::{bytes_repr_fmt, get_byte_at_index, get_bytes_slice},
+        AttrCallResult, ByteArray, Complex, LongInt, Property, PyTrait, Str, Type,
+        bytearray::bytearray_set_slice,
+        bytes::{byte_from_value, bytes_like, bytes_repr_fmt, get_byte_at_index, get_bytes_slice},
+        class::{delattr_user_object, function_name, getattr_user_object, setattr_user_object},
+        complex::negative_float_pow,
+        datetime,
+        list::list_set_slice,
         path,
         str::{allocate_char, get_char_at_index, get_str_slice, string_repr_fmt},
     },
@@ -58,6 +64,9 @@ pub(crate) enum Value {
     /// An interned long integer literal. The `LongIntId` references the `BigInt` in the Interns table.
     /// Used for integer literals exceeding i64 range. Converted to heap-allocated `LongInt` on load.
     InternLongInt(LongIntId),
+    /// An imaginary literal such as `3j`, holding its imaginary part.
+    /// Like `InternLongInt`, only found in constant pools; converted to a heap-allocated `Complex` on load.
+    Imaginary(f64),
     /// A builtin function or exception type
     Builtin(Builtins),
     /// A function from a module (not a global builtin).
@@ -70,6 +79,9 @@ pub(crate) enum Value {
     /// A marker value representing special objects like sys.stdout/stderr.
     /// These exist but have minimal functionality in the sandboxed environment.
     Marker(Marker),
+    /// A PEP 695 type alias (`type Name = ...`), holding the alias's name.
+    /// The aliased type only matters to type checkers, so it isn't kept.
+    TypeAlias(StringId),
     /// A property descriptor that computes its value when accessed.
     /// When retrieved via `py_getattr`, the property's getter is invoked.
     Property(Property),
@@ -121,12 +133,14 @@ impl PyTrait for Value {
             Self::Bool(_) => Type::Bool,
             Self::Int(_) | Self::InternLongInt(_) => Type::Int,
             Self::Float(_) => Type::Float,
+            Self::Imaginary(_) => Type::Complex,
             Self::InternString(_) => Type::Str,
             Self::InternBytes(_) => Type::Bytes,
             Self::Builtin(c) => c.py_type(),
             Self::ModuleFunction(_) => Type::BuiltinFunction,
             Self::DefFunction(_) | Self::ExtFunction(_) => Type::Function,
             Self::Marker(m) => m.py_type(),
+            Self::TypeAlias(_) => Type::TypeAliasType,
             Self::Property(_) => Type::Property,
             Self::ExternalFuture(_) => Type::Coroutine,
             Self::Ref(id) => heap.get(*id).py_type(heap),
@@ -180,7 +194,7 @@ impl PyTrait for Value {
                 if let HeapData::LongInt(li) = heap.get(*id) {
                     Ok(BigInt::from(*a) == *li.inner())
                 } else {
-                    Ok(false)
+                    Ok(complex_eq(self, other, heap))
                 }
             }
             // LongInt == Int comparison
@@ -188,9 +202,13 @@ impl PyTrait for Value {
                 if let HeapData::LongInt(li) = heap.get(*id) {
                     Ok(*li.inner() == BigInt::from(*b))
                 } else {
-                    Ok(false)
+                    Ok(complex_eq(self, other, heap))
                 }
             }
+            // Float/Bool == Complex comparison
+            (Self::Float(_) | Self::Bool(_), Self::Ref(_)) | (Self::Ref(_), Self::Float(_) | Self::Bool(_)) => {
+                Ok(complex_eq(self, other, heap))
+            }
 
             // For interned interns, compare by StringId first (fast path for same interned string)
             (Self::InternString(s1), Self::InternString(s2)) => Ok(s1 == s2),
@@ -220,14 +238,14 @@ impl PyTrait for Value {
                 if let HeapData::Bytes(b2) = heap.get(*id2) {
                     Ok(interns.get_bytes(*bytes_id) == b2.as_slice())
                 } else {
-                    Ok(false)
+                    Ok(bytes_like_eq(self, other, heap, interns).unwrap_or(false))
                 }
             }
             (Self::Ref(id1), Self::InternBytes(bytes_id)) => {
                 if let HeapData::Bytes(b1) = heap.get(*id1) {
                     Ok(b1.as_slice() == interns.get_bytes(*bytes_id))
                 } else {
-                    Ok(false)
+                    Ok(bytes_like_eq(self, other, heap, interns).unwrap_or(false))
                 }
             }
 
@@ -235,6 +253,9 @@ impl PyTrait for Value {
                 if *id1 == *id2 {
                     return Ok(true);
                 }
+                if let Some(eq) = bytes_like_eq(self, other, heap, interns) {
+                    return Ok(eq);
+                }
                 // Need to use with_two for proper borrow management
                 heap.with_two(*id1, *id2, |heap, left, right| left.py_eq(right, heap, guard, interns))
             }
@@ -246,6 +267,8 @@ impl PyTrait for Value {
             (Self::DefFunction(f1), Self::DefFunction(f2)) => Ok(f1 == f2),
             // Markers compare equal if they're the same variant
             (Self::Marker(m1), Self::Marker(m2)) => Ok(m1 == m2),
+            // Type aliases compare equal if they have the same name
+            (Self::TypeAlias(n1), Self::TypeAlias(n2)) => Ok(n1 == n2),
             // Properties compare equal if they're the same variant
             (Self::Property(p1), Self::Property(p2)) => Ok(p1 == p2),
 
@@ -288,7 +311,7 @@ impl PyTrait for Value {
                     Ok(None)
                 }
             }
-            // LongInt vs LongInt comparison
+            // LongInt vs LongInt comparison, other heap types compare themselves
             (Self::Ref(id1), Self::Ref(id2)) => {
                 let is_longint1 = matches!(heap.get(*id1), HeapData::LongInt(_));
                 let is_longint2 = matches!(heap.get(*id2), HeapData::LongInt(_));
@@ -301,7 +324,7 @@ impl PyTrait for Value {
                         }
                     }))
                 } else {
-                    Ok(None)
+                    heap.with_two(*id1, *id2, |heap, left, right| left.py_cmp(right, heap, guard, interns))
                 }
             }
             (Self::InternString(s1), Self::InternString(s2)) => {
@@ -331,11 +354,13 @@ impl PyTrait for Value {
             Self::Bool(b) => *b,
             Self::Int(v) => *v != 0,
             Self::Float(f) => *f != 0.0,
+            Self::Imaginary(imag) => *imag != 0.0,
             // InternLongInt is always truthy (if it were zero, it would fit in i64)
             Self::InternLongInt(_) => true,
             Self::Builtin(_) | Self::ModuleFunction(_) => true, // Builtins are always truthy
             Self::DefFunction(_) | Self::ExtFunction(_) => true, // Functions are always truthy
             Self::Marker(_) => true,                            // Markers are always truthy
+            Self::TypeAlias(_) => true,                         // Type aliases are always truthy
             Self::Property(_) => true,                          // Properties are always truthy
             Self::ExternalFuture(_) => true,                    // ExternalFutures are always truthy
             Self::InternString(string_id) => !interns.get_str(*string_id).is_empty(),
@@ -362,6 +387,7 @@ impl PyTrait for Value {
             Self::Bool(false) => f.write_str("False"),
             Self::Int(v) => write!(f, "{v}"),
             Self::InternLongInt(long_int_id) => write!(f, "{}", interns.get_long_int(*long_int_id)),
+            Self::Imaginary(imag) => write!(f, "{}", Complex::new(0.0, *imag)),
             Self::Float(v) => {
                 let s = v.to_string();
                 if s.contains('.') {
@@ -379,6 +405,7 @@ impl PyTrait for Value {
             Self::InternString(string_id) => string_repr_fmt(interns.get_str(*string_id), f),
             Self::InternBytes(bytes_id) => bytes_repr_fmt(interns.get_bytes(*bytes_id), f),
             Self::Marker(m) => m.py_repr_fmt(f),
+            Self::TypeAlias(name_id) => f.write_str(interns.get_str(*name_id)),
             Self::Property(p) => write!(f, "<property {p:?}>"),
             Self::ExternalFuture(call_id) => write!(f, "<coroutine external_future({})>", call_id.raw()),
             Self::Ref(id) => {
@@ -421,7 +448,16 @@ impl PyTrait for Value {
         other: &Self,
         heap: &mut Heap<impl ResourceTracker>,
         interns: &Interns,
-    ) -> Result<Option<Value>, crate::resource::ResourceError> {
+    ) -> RunResult<Option<Value>> {
+        if let Some(value) = datetime::binary_op(self, other, Operator::Add, heap)? {
+            return Ok(Some(value));
+        }
+        if let Some((a, b)) = Complex::operands(self, other, heap) {
+            return Ok(Some(a.add(b).into_value(heap)?));
+        }
+        if let Some(data) = bytes_like_concat(self, other, heap, interns) {
+            return Ok(Some(Self::Ref(heap.allocate(data)?)));
+        }
         match (self, other) {
             // Int + Int with overflow detection
             (Self::Int(a), Self::Int(b)) => {
@@ -430,14 +466,14 @@ impl PyTrait for Value {
                 } else {
                     // Overflow - promote to LongInt
                     let li = LongInt::from(*a) + LongInt::from(*b);
-                    li.into_value(heap).map(Some)
+                    Ok(Some(li.into_value(heap)?))
                 }
             }
             // Int + LongInt
             (Self::Int(a), Self::Ref(id)) => {
                 if let HeapData::LongInt(li) = heap.get(*id) {
                     let result = LongInt::from(*a) + LongInt::new(li.inner().clone());
-                    result.into_value(heap).map(Some)
+                    Ok(Some(result.into_value(heap)?))
                 } else {
                     Ok(None)
                 }
@@ -446,7 +482,7 @@ impl PyTrait for Value {
             (Self::Ref(id), Self::Int(b)) => {
                 if let HeapData::LongInt(li) = heap.get(*id) {
                     let result = LongInt::new(li.inner().clone()) + LongInt::from(*b);
-                    result.into_value(heap).map(Some)
+                    Ok(Some(result.into_value(heap)?))
                 } else {
                     Ok(None)
                 }
@@ -463,7 +499,7 @@ impl PyTrait for Value {
                     heap.with_two(*id1, *id2, |heap, left, right| {
                         if let (HeapData::LongInt(a), HeapData::LongInt(b)) = (left, right) {
                             let result = LongInt::new(a.inner() + b.inner());
-                            result.into_value(heap).map(Some)
+                            Ok(Some(result.into_value(heap)?))
                         } else {
                             Ok(None)
                         }
@@ -528,11 +564,13 @@ impl PyTrait for Value {
         }
     }
 
-    fn py_sub(
-        &self,
-        other: &Self,
-        heap: &mut Heap<impl ResourceTracker>,
-    ) -> Result<Option<Self>, crate::resource::ResourceError> {
+    fn py_sub(&self, other: &Self, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Option<Self>> {
+        if let Some(value) = datetime::binary_op(self, other, Operator::Sub, heap)? {
+            return Ok(Some(value));
+        }
+        if let Some((a, b)) = Complex::operands(self, other, heap) {
+            return Ok(Some(a.sub(b).into_value(heap)?));
+        }
         match (self, other) {
             // Int - Int with overflow detection
             (Self::Int(a), Self::Int(b)) => {
@@ -541,14 +579,14 @@ impl PyTrait for Value {
                 } else {
                     // Overflow - promote to LongInt
                     let li = LongInt::from(*a) - LongInt::from(*b);
-                    li.into_value(heap).map(Some)
+                    Ok(Some(li.into_value(heap)?))
                 }
             }
             // Int - LongInt
             (Self::Int(a), Self::Ref(id)) => {
                 if let HeapData::LongInt(li) = heap.get(*id) {
                     let result = LongInt::from(*a) - LongInt::new(li.inner().clone());
-                    result.into_value(heap).map(Some)
+                    Ok(Some(result.into_value(heap)?))
                 } else {
                     Ok(None)
                 }
@@ -557,7 +595,7 @@ impl PyTrait for Value {
             (Self::Ref(id), Self::Int(b)) => {
                 if let HeapData::LongInt(li) = heap.get(*id) {
                     let result = LongInt::new(li.inner().clone()) - LongInt::from(*b);
-                    result.into_value(heap).map(Some)
+                    Ok(Some(result.into_value(heap)?))
                 } else {
                     Ok(None)
                 }
@@ -570,7 +608,7 @@ impl PyTrait for Value {
                     heap.with_two(*id1, *id2, |heap, left, right| {
                         if let (HeapData::LongInt(a), HeapData::LongInt(b)) = (left, right) {
                             let result = LongInt::new(a.inner() - b.inner());
-                            result.into_value(heap).map(Some)
+                            Ok(Some(result.into_value(heap)?))
                         } else {
                             Ok(None)
                         }
@@ -589,6 +627,9 @@ impl PyTrait for Value {
     }
 
     fn py_mod(&self, other: &Self, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Option<Self>> {
+        if let Some(value) = datetime::binary_op(self, other, Operator::Mod, heap)? {
+            return Ok(Some(value));
+        }
         match (self, other) {
             (Self::Int(a), Self::Int(b)) => {
                 if *b == 0 {
@@ -763,14 +804,17 @@ impl PyTrait for Value {
                 other.drop_with_heap(heap);
                 Ok(result)
             }
-            (Self::Ref(id1), Self::InternBytes(bytes_id)) => {
-                if let HeapData::Bytes(b1) = heap.get_mut(*id1) {
+            (Self::Ref(id1), Self::InternBytes(bytes_id)) => match heap.get_mut(*id1) {
+                HeapData::Bytes(b1) => {
                     b1.as_vec_mut().extend_from_slice(interns.get_bytes(*bytes_id));
                     Ok(true)
-                } else {
-                    Ok(false)
                 }
-            }
+                // Bytearrays track their own growth
+                HeapData::ByteArray(_) => {
+                    heap.with_entry_mut(*id1, |heap, data| data.py_iadd(other, heap, Some(*id1), interns))
+                }
+                _ => Ok(false),
+            },
             (Self::Ref(id), Self::Ref(_)) => {
                 heap.with_entry_mut(*id, |heap, data| data.py_iadd(other, heap, Some(*id), interns))
             }
@@ -788,6 +832,12 @@ impl PyTrait for Value {
         heap: &mut Heap<impl ResourceTracker>,
         interns: &Interns,
     ) -> RunResult<Option<Value>> {
+        if let Some(value) = datetime::binary_op(self, other, Operator::Mult, heap)? {
+            return Ok(Some(value));
+        }
+        if let Some((a, b)) = Complex::operands(self, other, heap) {
+            return Ok(Some(a.mul(b).into_value(heap)?));
+        }
         match (self, other) {
             // Numeric multiplication with overflow promotion to LongInt
             (Self::Int(a), Self::Int(b)) => {
@@ -885,6 +935,12 @@ impl PyTrait for Value {
         heap: &mut Heap<impl ResourceTracker>,
         interns: &Interns,
     ) -> RunResult<Option<Value>> {
+        if let Some(value) = datetime::binary_op(self, other, Operator::Div, heap)? {
+            return Ok(Some(value));
+        }
+        if let Some((a, b)) = Complex::operands(self, other, heap) {
+            return Ok(Some(a.div(b)?.into_value(heap)?));
+        }
         match (self, other) {
             // True division always returns float
             (Self::Int(a), Self::Int(b)) => {
@@ -1044,6 +1100,9 @@ impl PyTrait for Value {
     }
 
     fn py_floordiv(&self, other: &Self, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Option<Value>> {
+        if let Some(value) = datetime::binary_op(self, other, Operator::FloorDiv, heap)? {
+            return Ok(Some(value));
+        }
         match (self, other) {
             // Floor division: int // int returns int
             (Self::Int(a), Self::Int(b)) => {
@@ -1174,6 +1233,9 @@ impl PyTrait for Value {
     }
 
     fn py_pow(&self, other: &Self, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Option<Value>> {
+        if let Some((a, b)) = Complex::operands(self, other, heap) {
+            return Ok(Some(a.pow(b)?.into_value(heap)?));
+        }
         match (self, other) {
             (Self::Int(base), Self::Int(exp)) => {
                 if *base == 0 && *exp < 0 {
@@ -1297,6 +1359,9 @@ impl PyTrait for Value {
             (Self::Float(base), Self::Float(exp)) => {
                 if *base == 0.0 && *exp < 0.0 {
                     Err(ExcType::zero_negative_power())
+                } else if let Some(c) = negative_float_pow(*base, *exp)? {
+                    // A negative base with a fractional exponent gives a complex result
+                    Ok(Some(c.into_value(heap)?))
                 } else {
                     Ok(Some(Self::Float(base.powf(*exp))))
                 }
@@ -1304,6 +1369,8 @@ impl PyTrait for Value {
             (Self::Int(base), Self::Float(exp)) => {
                 if *base == 0 && *exp < 0.0 {
                     Err(ExcType::zero_negative_power())
+                } else if let Some(c) = negative_float_pow(*base as f64, *exp)? {
+                    Ok(Some(c.into_value(heap)?))
                 } else {
                     Ok(Some(Self::Float((*base as f64).powf(*exp))))
                 }
@@ -1453,6 +1520,24 @@ impl PyTrait for Value {
         match self {
             Self::Ref(id) => {
                 let id = *id;
+                // Slice assignment collects the value before the list or bytearray leaves the heap
+                if let Self::Ref(key_id) = &key
+                    && let HeapData::Slice(slice) = heap.get(*key_id)
+                {
+                    match heap.get(id) {
+                        HeapData::List(_) => {
+                            let slice = slice.clone();
+                            key.drop_with_heap(heap);
+                            return list_set_slice(id, &slice, value, heap, interns);
+                        }
+                        HeapData::ByteArray(_) => {
+                            let slice = slice.clone();
+                            key.drop_with_heap(heap);
+                            return bytearray_set_slice(id, &slice, value, heap, interns);
+                        }
+                        _ => {}
+                    }
+                }
                 heap.with_entry_mut(id, |heap, data| data.py_setitem(key, value, heap, interns))
             }
             _ => Err(ExcType::type_error(format!(
@@ -1461,6 +1546,22 @@ impl PyTrait for Value {
             ))),
         }
     }
+
+    fn py_delitem(&mut self, key: &Self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<()> {
+        match self {
+            Self::Ref(id) => {
+                let id = *id;
+                heap.with_entry_mut(id, |heap, data| data.py_delitem(key, heap, interns))
+            }
+            Self::InternString(_) | Self::InternBytes(_) => {
+                Err(ExcType::type_error_not_sub_deletion(self.py_type(heap)))
+            }
+            _ => Err(ExcType::type_error(format!(
+                "'{}' object does not support item deletion",
+                self.py_type(heap)
+            ))),
+        }
+    }
 }
 
 impl Value {
@@ -1499,12 +1600,17 @@ impl Value {
             // Value-based IDs for immediate types (no heap allocation!)
             Self::Int(v) => int_value_id(*v),
             Self::Float(v) => float_value_id(*v),
+            // Imaginary literals become heap-allocated complex numbers when loaded, so their
+            // identity is never observable; reuse the float ID of their imaginary part
+            Self::Imaginary(imag) => float_value_id(*imag),
             Self::Builtin(c) => builtin_value_id(*c),
             Self::ModuleFunction(mf) => module_function_value_id(*mf),
             Self::DefFunction(f_id) => function_value_id(*f_id),
             Self::ExtFunction(f_id) => ext_function_value_id(*f_id),
             // Markers get deterministic IDs based on discriminant
             Self::Marker(m) => marker_value_id(*m),
+            // Type aliases get deterministic IDs based on their name
+            Self::TypeAlias(name_id) => type_alias_value_id(*name_id),
             // Properties get deterministic IDs based on discriminant
             Self::Property(p) => property_value_id(*p),
             // ExternalFutures get IDs based on their call_id
@@ -1567,6 +1673,8 @@ impl Value {
                 interns.get_bytes(*bytes_id).hash(&mut hasher);
                 return Some(hasher.finish());
             }
+            // Hash imaginary literals consistently with Complex
+            Self::Imaginary(imag) => return Some(Complex::new(0.0, *imag).hash()),
             // Hash BigInt consistently with LongInt (using sign and bytes for large values)
             Self::InternLongInt(long_int_id) => {
                 let bi = interns.get_long_int(*long_int_id);
@@ -1598,11 +1706,17 @@ impl Value {
             Self::ExtFunction(f_id) => f_id.hash(&mut hasher),
             // Markers are hashable based on their discriminant (already included above)
             Self::Marker(m) => m.hash(&mut hasher),
+            // Type aliases are hashable based on their name
+            Self::TypeAlias(name_id) => name_id.hash(&mut hasher),
             // Properties are hashable based on their OS function discriminant
             Self::Property(p) => p.hash(&mut hasher),
             // ExternalFutures are hashable based on their call ID
             Self::ExternalFuture(call_id) => call_id.raw().hash(&mut hasher),
-            Self::InternString(_) | Self::InternBytes(_) | Self::InternLongInt(_) | Self::Ref(_) => {
+            Self::InternString(_)
+            | Self::InternBytes(_)
+            | Self::InternLongInt(_)
+            | Self::Imaginary(_)
+            | Self::Ref(_) => {
                 unreachable!("covered above")
             }
             #[cfg(feature = "ref-count-panic")]
@@ -1627,6 +1741,19 @@ impl Value {
     ) -> RunResult<bool> {
         match self {
             Self::Ref(heap_id) => {
+                // Bytes-like containers are read in place, before the container is taken out of the heap
+                if let Some(haystack) = bytes_like(self, heap, interns) {
+                    // memoryview compares element by element, so only integers can be found
+                    if matches!(heap.get(*heap_id), HeapData::MemoryView(_)) {
+                        let byte = match item {
+                            Self::Int(i) => u8::try_from(*i).ok(),
+                            Self::Bool(b) => Some(u8::from(*b)),
+                            _ => None,
+                        };
+                        return Ok(byte.is_some_and(|byte| haystack.contains(&byte)));
+                    }
+                    return bytes_contains(&haystack, item, heap, interns);
+                }
                 // Use with_entry_mut to temporarily take ownership of the container.
                 // This allows iterating over container elements while calling py_eq
                 // (which needs &mut Heap for comparing nested heap values).
@@ -1649,7 +1776,31 @@ impl Value {
                         }
                         Ok(false)
                     }
-                    HeapData::Dict(dict) => dict.get(item, heap, interns).map(|m| m.is_some()),
+                    HeapData::NamedTuple(nt) => {
+                        let mut guard = DepthGuard::default();
+                        for el in nt.as_vec() {
+                            if item.py_eq(el, heap, &mut guard, interns)? {
+                                return Ok(true);
+                            }
+                        }
+                        Ok(false)
+                    }
+                    HeapData::Deque(deque) => {
+                        let mut guard = DepthGuard::default();
+                        for el in deque.iter() {
+                            if item.py_eq(el, heap, &mut guard, interns)? {
+                                return Ok(true);
+                            }
+                        }
+                        Ok(false)
+                    }
+                    data @ (HeapData::Dict(_)
+                    | HeapData::DefaultDict(_)
+                    | HeapData::Counter(_)
+                    | HeapData::OrderedDict(_)) => {
+                        let dict = data.as_dict().expect("dict types have a dict");
+                        dict.get(item, heap, interns).map(|m| m.is_some())
+                    }
                     HeapData::Set(set) => set.contains(item, heap, interns),
                     HeapData::FrozenSet(fset) => fset.contains(item, heap, interns),
                     HeapData::Str(s) => str_contains(s.as_str(), item, heap, interns),
@@ -1691,6 +1842,7 @@ impl Value {
                 let container_str = interns.get_str(*string_id);
                 str_contains(container_str, item, heap, interns)
             }
+            Self::InternBytes(bytes_id) => bytes_contains(interns.get_bytes(*bytes_id), item, heap, interns),
             _ => {
                 let type_name = self.py_type(heap);
                 Err(ExcType::type_error(format!(
@@ -1713,6 +1865,11 @@ impl Value {
     ) -> RunResult<AttrCallResult> {
         match self {
             Self::Ref(heap_id) => {
+                // User-defined classes and instances look up attributes through the class chain,
+                // which needs the whole heap (including this entry) to be accessible
+                if let Some(value) = getattr_user_object(*heap_id, name_id, heap, interns)? {
+                    return Ok(AttrCallResult::Value(value));
+                }
                 // Use with_entry_mut to get access to both data and heap without borrow conflicts.
                 // This allows py_getattr to allocate (for computed attributes) while we hold the data.
                 let opt_result = heap.with_entry_mut(*heap_id, |heap, data| data.py_getattr(name_id, heap, interns))?;
@@ -1721,22 +1878,44 @@ impl Value {
                 }
             }
             Self::Builtin(Builtins::Type(t)) => {
-                // Handle type object attributes like __name__
+                // Handle type object attributes like __name__, which omits the module of e.g. `datetime.date`
                 if name_id == StaticStrings::DunderName {
-                    let name_str = t.to_string();
+                    let qualified_name = t.to_string();
+                    let name_str = qualified_name.rsplit('.').next().unwrap_or_default();
                     let str_id = heap.allocate(HeapData::Str(Str::from(name_str)))?;
                     return Ok(AttrCallResult::Value(Self::Ref(str_id)));
                 }
+                if let Some(value) = datetime::type_attr(*t, name_id, heap)? {
+                    return Ok(AttrCallResult::Value(value));
+                }
+            }
+            Self::Builtin(Builtins::ExcType(exc_type)) => {
+                if name_id == StaticStrings::DunderName {
+                    let str_id = heap.allocate(HeapData::Str(Str::from(exc_type.to_string())))?;
+                    return Ok(AttrCallResult::Value(Self::Ref(str_id)));
+                }
+            }
+            Self::TypeAlias(alias_name_id) => {
+                if name_id == StaticStrings::DunderName {
+                    return Ok(AttrCallResult::Value(Self::InternString(*alias_name_id)));
+                }
             }
             _ => {}
         }
+        // Functions expose their name, which decorators commonly rely on
+        if name_id == StaticStrings::DunderName
+            && let Some(name) = function_name(self, heap, interns)
+        {
+            let str_id = heap.allocate(HeapData::Str(Str::from(name)))?;
+            return Ok(AttrCallResult::Value(Self::Ref(str_id)));
+        }
         let type_name = self.py_type(heap);
         Err(ExcType::attribute_error(type_name, interns.get_str(name_id)))
     }
 
     /// Sets an attribute on this value.
     ///
-    /// Currently only Dataclass objects support attribute setting.
+    /// Dataclass objects, user-defined classes and their instances support attribute setting.
     /// Returns AttributeError for other types.
     ///
     /// Takes ownership of `value` and drops it on error.
@@ -1752,6 +1931,10 @@ impl Value {
 
         if let Self::Ref(heap_id) = self {
             let heap_id = *heap_id;
+            let value = match setattr_user_object(heap_id, name_id, value, heap, interns)? {
+                Ok(()) => return Ok(()),
+                Err(value) => value,
+            };
             let is_dataclass = matches!(heap.get(heap_id), HeapData::Dataclass(_));
 
             if is_dataclass {
@@ -1783,6 +1966,34 @@ impl Value {
         }
     }
 
+    /// Deletes an attribute on this value (`del obj.attr`).
+    ///
+    /// Supported for user-defined classes and instances, and for non-frozen dataclasses.
+    pub fn py_del_attr(
+        &self,
+        name_id: StringId,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        if let Self::Ref(heap_id) = self {
+            let heap_id = *heap_id;
+            if delattr_user_object(heap_id, name_id, heap, interns)? {
+                return Ok(());
+            }
+            if matches!(heap.get(heap_id), HeapData::Dataclass(_)) {
+                return heap.with_entry_mut(heap_id, |heap, data| {
+                    if let HeapData::Dataclass(dc) = data {
+                        dc.del_attr(name_id, heap, interns)
+                    } else {
+                        unreachable!("type changed during borrow")
+                    }
+                });
+            }
+        }
+        let type_name = self.py_type(heap);
+        Err(ExcType::attribute_error_no_setattr(type_name, interns.get_str(name_id)))
+    }
+
     /// Extracts an integer value from the Value.
     ///
     /// Accepts `Int` and `LongInt` (if it fits in i64). Returns a `TypeError` for other types
@@ -2004,7 +2215,9 @@ impl Value {
             Self::InternString(s) => Self::InternString(*s),
             Self::InternBytes(b) => Self::InternBytes(*b),
             Self::InternLongInt(bi) => Self::InternLongInt(*bi),
+            Self::Imaginary(imag) => Self::Imaginary(*imag),
             Self::Marker(m) => Self::Marker(*m),
+            Self::TypeAlias(name_id) => Self::TypeAlias(*name_id),
             Self::Property(p) => Self::Property(*p),
             Self::ExternalFuture(call_id) => Self::ExternalFuture(*call_id),
             Self::Ref(id) => Self::Ref(*id), // Caller must increment refcount!
@@ -2229,6 +2442,8 @@ const MODULE_FUNCTION_ID_TAG: usize = 1usize << (usize::BITS - 12);
 const INTERN_LONG_INT_ID_TAG: usize = 1usize << (usize::BITS - 13);
 /// High-bit tag for Property value-based IDs.
 const PROPERTY_ID_TAG: usize = 1usize << (usize::BITS - 14);
+/// High-bit tag for TypeAlias value-based IDs.
+const TYPE_ALIAS_ID_TAG: usize = 1usize << (usize::BITS - 15);
 
 /// Masks for value-based ID tags (keep bits below the tag bit).
 const INT_ID_MASK: usize = INT_ID_TAG - 1;
@@ -2241,6 +2456,7 @@ const EXTERNAL_FUTURE_ID_MASK: usize = EXTERNAL_FUTURE_ID_TAG - 1;
 const MODULE_FUNCTION_ID_MASK: usize = MODULE_FUNCTION_ID_TAG - 1;
 const INTERN_LONG_INT_ID_MASK: usize = INTERN_LONG_INT_ID_TAG - 1;
 const PROPERTY_ID_MASK: usize = PROPERTY_ID_TAG - 1;
+const TYPE_ALIAS_ID_MASK: usize = TYPE_ALIAS_ID_TAG - 1;
 
 /// Enumerates singleton literal slots so we can issue stable `id()` values without heap allocation.
 #[repr(usize)]
@@ -2321,6 +2537,12 @@ fn marker_value_id(m: Marker) -> usize {
     MARKER_ID_TAG | ((m.0 as usize) & MARKER_ID_MASK)
 }
 
+/// Computes a deterministic ID for a type alias based on its name.
+#[inline]
+fn type_alias_value_id(name_id: StringId) -> usize {
+    TYPE_ALIAS_ID_TAG | (name_id.index() & TYPE_ALIAS_ID_MASK)
+}
+
 /// Computes a deterministic ID for a property value based on its discriminant.
 #[inline]
 fn property_value_id(p: Property) -> usize {
@@ -2395,6 +2617,13 @@ fn extract_bigint(value: &Value, heap: &Heap<impl ResourceTracker>) -> Option<Bi
     }
 }
 
+/// Compares a number with a complex number for equality.
+///
+/// Returns `false` unless one of the values is a `complex` and the other is a number.
+fn complex_eq(lhs: &Value, rhs: &Value, heap: &Heap<impl ResourceTracker>) -> bool {
+    matches!(Complex::operands(lhs, rhs, heap), Some((a, b)) if a == b)
+}
+
 /// Helper for substring containment check in strings.
 ///
 /// Called by `py_contains` when the container is a string.
@@ -2421,6 +2650,80 @@ fn str_contains(
     }
 }
 
+/// Returns whether `value` is a `bytearray` or `memoryview`.
+fn is_buffer(value: &Value, heap: &Heap<impl ResourceTracker>) -> bool {
+    matches!(value, Value::Ref(id) if matches!(heap.get(*id), HeapData::ByteArray(_) | HeapData::MemoryView(_)))
+}
+
+/// Compares the contents of two bytes-like values when at least one is a `bytearray` or `memoryview`.
+///
+/// Returns `None` when the comparison isn't between bytes-like values, or involves only
+/// `bytes` (which the caller compares directly).
+fn bytes_like_eq(lhs: &Value, rhs: &Value, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> Option<bool> {
+    if !is_buffer(lhs, heap) && !is_buffer(rhs, heap) {
+        return None;
+    }
+    Some(bytes_like(lhs, heap, interns)? == bytes_like(rhs, heap, interns)?)
+}
+
+/// Concatenates two bytes-like values when at least one is a `bytearray` or `memoryview`.
+///
+/// The result has the type of the left operand: `bytearray + bytes` is a `bytearray` and
+/// `bytes + bytearray` is `bytes`. Returns `None` when the operands can't be concatenated
+/// this way, including when the left operand is a `memoryview`, which doesn't support `+`.
+fn bytes_like_concat(
+    lhs: &Value,
+    rhs: &Value,
+    heap: &Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> Option<HeapData> {
+    if !is_buffer(lhs, heap) && !is_buffer(rhs, heap) {
+        return None;
+    }
+    let lhs_is_bytearray = match lhs {
+        Value::Ref(id) => match heap.get(*id) {
+            HeapData::ByteArray(_) => true,
+            HeapData::MemoryView(_) => return None,
+            _ => false,
+        },
+        _ => false,
+    };
+    let mut bytes = bytes_like(lhs, heap, interns)?.into_owned();
+    bytes.extend_from_slice(&bytes_like(rhs, heap, interns)?);
+    Some(if lhs_is_bytearray {
+        HeapData::ByteArray(ByteArray::new(bytes))
+    } else {
+        HeapData::Bytes(bytes.into())
+    })
+}
+
+/// Checks if a byte sequence or a single byte is contained in a bytes-like container.
+///
+/// Called by `py_contains` when the container is `bytes`, `bytearray` or `memoryview`.
+/// The item must be bytes-like or an int in `range(0, 256)`.
+fn bytes_contains(
+    haystack: &[u8],
+    item: &Value,
+    heap: &Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<bool> {
+    if let Some(needle) = bytes_like(item, heap, interns) {
+        return Ok(needle.is_empty() || haystack.windows(needle.len()).any(|window| window == &*needle));
+    }
+    match item {
+        Value::Int(_) | Value::Bool(_) => {
+            let byte = byte_from_value(item, Type::ByteArray, heap)?;
+            Ok(haystack.contains(&byte))
+        }
+        _ => {
+            let type_name = item.py_type(heap);
+            Err(ExcType::type_error(format!(
+                "a bytes-like object is required, not '{type_name}'"
+            )))
+        }
+    }
+}
+
 /// Computes the number of significant bits in an i64.
 ///
 /// Returns 0 for 0, otherwise returns ceil(log2(|value|)) + 1 (accounting for sign).
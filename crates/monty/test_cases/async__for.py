@@ -0,0 +1,162 @@
+# run-async
+log = []
+
+
+async def double(x):
+    return x * 2
+
+
+async def agen(n):
+    for i in range(n):
+        yield await double(i)
+    log.append('agen done')
+
+
+async def main():
+    result = []
+    async for x in agen(3):
+        result.append(x)
+    return result
+
+
+assert await main() == [0, 2, 4], 'async for over async generator'  # pyright: ignore
+assert log == ['agen done'], 'async generator runs to completion'
+
+# async for at module level with break, continue and else
+items = []
+async for x in agen(5):  # pyright: ignore
+    if x == 2:
+        continue
+    if x == 6:
+        break
+    items.append(x)
+else:
+    items.append('else')
+assert items == [0, 4], f'break skips else {items=}'
+
+items = []
+async for x in agen(2):  # pyright: ignore
+    items.append(x)
+else:
+    items.append('else')
+assert items == [0, 2, 'else'], f'else runs on exhaustion {items=}'
+
+# async comprehensions
+assert [x async for x in agen(3)] == [0, 2, 4], 'async list comprehension'  # pyright: ignore
+assert {x async for x in agen(3) if x} == {2, 4}, 'async set comprehension'  # pyright: ignore
+assert {x: x + 1 async for x in agen(2)} == {0: 1, 2: 3}, 'async dict comprehension'  # pyright: ignore
+assert [(x, y) async for x in agen(2) for y in 'ab'] == [(0, 'a'), (0, 'b'), (2, 'a'), (2, 'b')], (
+    'async comprehension with sync clause'  # pyright: ignore
+)
+assert [await double(x) for x in range(3)] == [0, 2, 4], 'await in comprehension'  # pyright: ignore
+
+# async generator expressions are async generators
+genexp = (x + 1 async for x in agen(3))
+assert [x async for x in genexp] == [1, 3, 5], 'async generator expression'  # pyright: ignore
+
+
+# asend, __anext__ and aclose
+async def echo():
+    received = yield 'ready'
+    while True:
+        try:
+            received = yield received * 2
+        except GeneratorExit:
+            log.append('echo closed')
+            raise
+
+
+gen = echo()
+assert await gen.__anext__() == 'ready', 'first __anext__'  # pyright: ignore
+assert await gen.asend(5) == 10, 'asend'  # pyright: ignore
+assert await gen.asend(7) == 14, 'second asend'  # pyright: ignore
+assert await gen.aclose() is None, 'aclose returns None'  # pyright: ignore
+assert log[-1] == 'echo closed', 'aclose raises GeneratorExit in the generator'
+
+try:
+    await gen.__anext__()  # pyright: ignore
+    assert False, 'closed async generator should be exhausted'
+except StopAsyncIteration:
+    pass
+
+
+async def one():
+    yield 1
+
+
+gen = one()
+assert gen.__aiter__() is gen, 'async generator is its own async iterator'
+assert await gen.__anext__() == 1, '__anext__ yields'  # pyright: ignore
+try:
+    await gen.__anext__()  # pyright: ignore
+    assert False, 'exhausted async generator should raise StopAsyncIteration'
+except StopAsyncIteration as e:
+    assert e.args == (), 'StopAsyncIteration has no args'
+
+awaitable = one().__anext__()
+assert await awaitable == 1, 'awaitable from __anext__'  # pyright: ignore
+try:
+    await awaitable  # pyright: ignore
+    assert False, 'awaiting twice should fail'
+except RuntimeError as e:
+    assert str(e) == 'cannot reuse already awaited __anext__()/asend()', f'reuse message {e}'
+
+
+# exceptions propagate out of async for
+async def failing():
+    yield 1
+    raise ValueError('boom')
+
+
+items = []
+try:
+    async for x in failing():  # pyright: ignore
+        items.append(x)
+except ValueError as e:
+    items.append(str(e))
+assert items == [1, 'boom'], f'exception from async generator {items=}'
+
+
+async def stops():
+    yield 1
+    raise StopAsyncIteration
+
+
+try:
+    async for x in stops():  # pyright: ignore
+        pass
+    assert False, 'StopAsyncIteration in async generator should become RuntimeError'
+except RuntimeError as e:
+    assert str(e) == 'async generator raised StopAsyncIteration', f'PEP 479 message {e}'
+
+
+# classes implementing __aiter__ and __anext__
+class Countdown:
+    def __init__(self, start):
+        self.current = start
+
+    def __aiter__(self):
+        return self
+
+    async def __anext__(self):
+        if self.current <= 0:
+            raise StopAsyncIteration
+        self.current -= 1
+        return await double(self.current + 1)
+
+
+assert [x async for x in Countdown(3)] == [6, 4, 2], 'class-based async iterator'  # pyright: ignore
+
+total = 0
+async for x in Countdown(2):  # pyright: ignore
+    async for y in agen(2):
+        total += x * y
+assert total == 12, f'nested async for {total=}'
+
+# async generators aren't sync iterables
+try:
+    for x in agen(1):  # pyright: ignore
+        pass
+    assert False, 'for over async generator should fail'
+except TypeError as e:
+    assert str(e) == "'async_generator' object is not iterable", f'sync iteration message {e}'
@@ -0,0 +1,4 @@
+# run-async
+async for x in 42:  # pyright: ignore
+    pass
+# Raise=TypeError("'async for' requires an object with __aiter__ method, got int")
@@ -0,0 +1,54 @@
+# run-async
+import asyncio
+
+
+async def ok(value):
+    return value
+
+
+async def fail(message):
+    raise ValueError(message)
+
+
+# === Exceptions are returned as results ===
+results = await asyncio.gather(ok(1), fail('bad'), ok(3), return_exceptions=True)  # pyright: ignore
+assert len(results) == 3, 'one result per awaitable'
+assert results[0] == 1, 'first result'
+assert isinstance(results[1], ValueError), 'exception is the result'
+assert str(results[1]) == 'bad', 'exception message kept'
+assert results[2] == 3, 'siblings still run'
+
+# === Every item failing ===
+results = await asyncio.gather(fail('a'), fail('b'), return_exceptions=True)  # pyright: ignore
+assert [str(r) for r in results] == ['a', 'b'], f'all failed: {results}'
+
+# === No failures ===
+results = await asyncio.gather(ok('a'), ok('b'), return_exceptions=True)  # pyright: ignore
+assert results == ['a', 'b'], 'same as without return_exceptions'
+
+# === return_exceptions=False raises ===
+try:
+    await asyncio.gather(ok(1), fail('raised'), return_exceptions=False)  # pyright: ignore
+    assert False, 'should have raised ValueError'
+except ValueError as e:
+    assert str(e) == 'raised', 'exception propagates'
+
+
+# === Returned exceptions can be raised again ===
+async def reraise():
+    results = await asyncio.gather(fail('again'), return_exceptions=True)  # pyright: ignore
+    raise results[0]
+
+
+try:
+    await reraise()  # pyright: ignore
+    assert False, 'should have raised ValueError'
+except ValueError as e:
+    assert str(e) == 'again', 're-raised returned exception'
+
+# === Unknown keyword argument ===
+try:
+    await asyncio.gather(foo=True)  # pyright: ignore
+    assert False, 'should have raised TypeError'
+except TypeError as e:
+    assert str(e) == "gather() got an unexpected keyword argument 'foo'", f'unknown kwarg: {e}'
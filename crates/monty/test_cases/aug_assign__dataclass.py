@@ -0,0 +1,6 @@
+# call-external
+point = make_mutable_point()
+point.x += 10
+point.y *= add_ints(1, 2)
+assert point.x == 11, 'dataclass field augmented assignment'
+assert point.y == 6, 'augmented assignment with external call'
@@ -0,0 +1,7 @@
+class Empty:
+    pass
+
+
+e = Empty()
+e.total += 1
+# Raise=AttributeError("'Empty' object has no attribute 'total'")
@@ -0,0 +1,3 @@
+counts = {}
+counts['missing'] += 1
+# Raise=KeyError('missing')
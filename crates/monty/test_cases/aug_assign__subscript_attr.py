@@ -0,0 +1,96 @@
+# === Subscript targets ===
+counts = {}
+for word in ['a', 'b', 'a', 'c', 'a']:
+    counts[word] = counts.get(word, 0)
+    counts[word] += 1
+assert counts == {'a': 3, 'b': 1, 'c': 1}, 'dict counter'
+
+lst = [1, 2, 3]
+lst[0] += 10
+lst[-1] *= 2
+lst[1] -= 5
+assert lst == [11, -3, 6], 'list item augmented assignment'
+
+grid = [[0, 0], [0, 0]]
+grid[1][0] += 7
+assert grid == [[0, 0], [7, 0]], 'nested subscript target'
+
+strings = {'k': 'ab'}
+strings['k'] += 'cd'
+assert strings['k'] == 'abcd', 'str concatenation'
+
+lists = {'k': [1]}
+alias = lists['k']
+lists['k'] += [2]
+assert alias == [1, 2], 'list += extends in place'
+
+bits = [0b1100]
+bits[0] &= 0b1010
+bits[0] |= 0b0001
+bits[0] ^= 0b1111
+bits[0] <<= 1
+bits[0] >>= 2
+assert bits == [0b0011], 'bitwise augmented assignment'
+
+nums = [7]
+nums[0] //= 2
+nums[0] **= 3
+nums[0] %= 5
+assert nums == [2], 'arithmetic augmented assignment'
+
+# === Container and key are evaluated once ===
+calls = []
+
+
+def get_container():
+    calls.append('container')
+    return counts
+
+
+def get_key():
+    calls.append('key')
+    return 'a'
+
+
+get_container()[get_key()] += 100
+assert counts['a'] == 103, 'value updated'
+assert calls == ['container', 'key'], 'container and key evaluated once each'
+
+
+# === Attribute targets ===
+class Totals:
+    def __init__(self):
+        self.total = 0
+        self.items = []
+
+    def add(self, x):
+        self.total += x
+        self.items += [x]
+        return self
+
+
+t = Totals()
+t.add(3).add(4)
+assert t.total == 7, 'attribute augmented assignment'
+assert t.items == [3, 4], 'attribute list extend'
+
+holder = Totals()
+holder.inner = Totals()
+holder.inner.total += 5
+assert holder.inner.total == 5, 'chained attribute target'
+
+Totals.created = 0
+Totals.created += 1
+assert Totals.created == 1, 'class attribute target'
+
+attr_calls = []
+
+
+def get_holder():
+    attr_calls.append('holder')
+    return holder
+
+
+get_holder().total -= 2
+assert holder.total == -2, 'attribute value updated'
+assert attr_calls == ['holder'], 'object evaluated once'
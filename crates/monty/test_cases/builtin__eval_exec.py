@@ -0,0 +1,150 @@
+# === eval() ===
+assert eval('1 + 2') == 3, 'eval simple expression'
+assert eval('  [n * n for n in range(4)]') == [0, 1, 4, 9], 'eval ignores leading spaces'
+
+x = 10
+assert eval('x * 2') == 20, 'eval reads module globals'
+
+assert eval('a + b', {'a': 1, 'b': 2}) == 3, 'eval with globals dict'
+assert eval('len(s)', {'s': 'abc'}) == 3, 'builtins are available with a globals dict'
+assert eval('a', {'a': 1}, {'a': 2}) == 2, 'locals dict shadows globals dict'
+assert eval('x', None, {'x': 5}) == 5, 'locals dict without globals dict'
+
+try:
+    eval('missing', {})
+    assert False, 'eval of an undefined name should raise'
+except NameError as e:
+    assert str(e) == "name 'missing' is not defined", f'eval NameError message: {e}'
+
+try:
+    eval('1 / 0')
+    assert False, 'eval should propagate exceptions'
+except ZeroDivisionError:
+    pass
+
+# === exec() ===
+assert exec('pass') is None, 'exec returns None'
+
+ns = {}
+exec('y = 5\ndef f(n):\n    return n + y', ns)
+assert ns['y'] == 5, 'exec binds names in the globals dict'
+assert ns['f'](1) == 6, 'functions defined by exec see names bound by exec'
+
+loc = {'a': 1}
+exec('w = a + 1', {}, loc)
+assert loc['w'] == 2, 'exec binds names in the locals dict'
+
+counter = 0
+exec('counter = counter + 1')
+assert counter == 1, 'module-level exec assigns module globals'
+
+# === eval() and exec() in functions ===
+
+
+def read_local():
+    v = 3
+    return eval('v + 1')
+
+
+assert read_local() == 4, 'eval in a function reads its locals'
+
+
+def read_both():
+    v = 2
+    return eval('v * x')
+
+
+assert read_both() == 20, 'eval in a function reads locals and globals'
+
+
+def exec_into_dict():
+    d = {}
+    exec('for i in range(3):\n    total = i', d)
+    return d['total']
+
+
+assert exec_into_dict() == 2, 'exec in a function binds names in the dict'
+
+# === compile() ===
+code = compile('2 * 21', '<test>', 'eval')
+assert eval(code) == 42, 'eval of compiled expression'
+
+code = compile('z = 7', '<test>', 'exec')
+ns = {}
+exec(code, ns)
+assert ns['z'] == 7, 'exec of compiled statements'
+
+try:
+    compile('1 +', '<test>', 'eval')
+    assert False, 'compile should raise SyntaxError'
+except SyntaxError:
+    pass
+
+try:
+    compile('1', '<test>', 'bad')
+    assert False, 'compile should reject unknown modes'
+except ValueError as e:
+    assert str(e) == "compile() mode must be 'exec', 'eval' or 'single'", f'compile mode message: {e}'
+
+# === keyword and optional arguments ===
+assert eval('x + 1', globals={'x': 1}) == 2, 'eval globals keyword'
+assert eval('x + y', {'x': 1}, locals={'y': 2}) == 3, 'eval locals keyword'
+ns = {}
+exec('w = 3', globals=ns, closure=None)
+assert ns['w'] == 3, 'exec globals keyword'
+
+code = compile(source='1 + 1', filename='<test>', mode='eval', flags=0, dont_inherit=True, optimize=-1)
+assert eval(code) == 2, 'compile with keyword and optional arguments'
+code = compile('y = 5', '<test>', 'exec', 0, False, 0)
+ns = {}
+exec(code, ns)
+assert ns['y'] == 5, 'compile with positional optional arguments'
+
+try:
+    eval(source='1')
+    assert False, 'eval source is positional-only'
+except TypeError as e:
+    assert str(e) == 'eval() takes at least 1 positional argument (0 given)', f'eval source message: {e}'
+
+try:
+    eval('1', {}, {}, {})
+    assert False, 'eval takes at most 3 arguments'
+except TypeError as e:
+    assert str(e) == 'eval() takes at most 3 arguments (4 given)', f'eval count message: {e}'
+
+try:
+    exec('pass', closure=())
+    assert False, 'exec closure needs a code object'
+except TypeError as e:
+    assert str(e) == 'closure can only be used when source is a code object', f'exec closure message: {e}'
+
+try:
+    compile('1', '<test>', 'eval', optimize=5)
+    assert False, 'compile should reject bad optimize values'
+except ValueError as e:
+    assert str(e) == 'compile(): invalid optimize value', f'compile optimize message: {e}'
+
+# === errors ===
+try:
+    eval('1 +')
+    assert False, 'eval should raise SyntaxError'
+except SyntaxError:
+    pass
+
+try:
+    eval(1)
+    assert False, 'eval of a non-string should raise'
+except TypeError as e:
+    assert str(e) == 'eval() arg 1 must be a string, bytes or code object', f'eval arg message: {e}'
+
+try:
+    exec('pass', [])
+    assert False, 'exec with a non-dict globals should raise'
+except TypeError as e:
+    assert str(e) == 'exec() globals must be a dict, not list', f'exec globals message: {e}'
+
+# === compiled code is reused ===
+total = 0
+for i in range(3):
+    total += eval('i * 2')
+assert total == 6, 'eval in a loop'
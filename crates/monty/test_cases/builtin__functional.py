@@ -0,0 +1,228 @@
+import sys
+
+# === map() ===
+assert list(map(abs, [-1, 2, -3])) == [1, 2, 3], 'map with builtin'
+assert list(map(lambda x: x * 2, (1, 2, 3))) == [2, 4, 6], 'map with lambda'
+assert list(map(lambda a, b: a + b, [1, 2, 3], [10, 20])) == [11, 22], 'map stops at shortest iterable'
+assert list(map(str, [])) == [], 'map over empty list'
+assert tuple(map(len, ['a', 'bb'])) == (1, 2), 'map into tuple'
+assert sum(map(lambda x: x * x, range(4))) == 14, 'sum of map'
+assert ','.join(map(str, [1, 2, 3])) == '1,2,3', 'join of map'
+
+m = map(lambda x: x + 1, [1, 2, 3])
+assert next(m) == 2, 'next on map'
+assert list(m) == [3, 4], 'map continues where next left off'
+assert list(m) == [], 'exhausted map stays exhausted'
+assert iter(m) is m, 'map is its own iterator'
+
+calls = []
+
+
+def record(x):
+    calls.append(x)
+    return x
+
+
+m = map(record, [1, 2, 3])
+assert calls == [], 'map is lazy'
+for x in m:
+    if x == 2:
+        break
+assert calls == [1, 2], 'map only calls the function for items taken'
+
+
+def gen():
+    yield 1
+    yield 2
+
+
+assert list(map(lambda x: -x, gen())) == [-1, -2], 'map over generator'
+a, b = map(int, ['4', '5'])
+assert (a, b) == (4, 5), 'unpack map'
+
+# === filter() ===
+assert list(filter(lambda x: x % 2, range(6))) == [1, 3, 5], 'filter with lambda'
+assert list(filter(None, [0, 1, '', 'a', None, [], [0]])) == [1, 'a', [0]], 'filter with None'
+assert list(filter(lambda s: s.isdigit(), ['1', 'a', '22'])) == ['1', '22'], 'filter with str method'
+f = filter(lambda x: x > 1, [1, 2, 3])
+assert next(f) == 2, 'next on filter'
+assert list(f) == [3], 'filter continues where next left off'
+assert 3 in filter(None, [1, 2, 3]), 'in on filter'
+assert sorted(filter(lambda s: len(s) > 1, ['ccc', 'a', 'bb'])) == ['bb', 'ccc'], 'sorted filter'
+assert list(map(lambda x: x * 10, filter(None, [0, 1, 2]))) == [10, 20], 'map of filter'
+
+# === iter(callable, sentinel) ===
+counter = [0]
+
+
+def tick():
+    counter[0] += 1
+    return counter[0]
+
+
+assert list(iter(tick, 4)) == [1, 2, 3], 'iter with sentinel'
+items = [3, 2, 1, 0, 9]
+pos = [0]
+
+
+def pop_item():
+    value = items[pos[0]]
+    pos[0] += 1
+    return value
+
+
+collected = []
+for value in iter(pop_item, 0):
+    collected.append(value)
+assert collected == [3, 2, 1], 'for loop over iter with sentinel'
+assert next(iter(pop_item, 0), 'done') == 9, 'next on iter with sentinel'
+
+# === getattr(), setattr(), hasattr() ===
+
+
+class Point:
+    kind = 'point'
+
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+    def total(self):
+        return self.x + self.y
+
+
+p = Point(1, 2)
+assert getattr(p, 'x') == 1, 'getattr instance attribute'
+assert getattr(p, 'kind') == 'point', 'getattr class attribute'
+assert getattr(p, 'total')() == 3, 'getattr bound method'
+assert getattr(p, 'missing', None) is None, 'getattr default'
+assert getattr(Point, 'kind') == 'point', 'getattr on class'
+assert getattr(sys, 'platform') == sys.platform, 'getattr on module'
+assert getattr(sys.version_info, 'major') == 3, 'getattr on namedtuple'
+
+setattr(p, 'x', 10)
+assert p.x == 10, 'setattr existing attribute'
+setattr(p, 'z', 5)
+assert p.z == 5, 'setattr new attribute'
+assert setattr(p, 'y', 0) is None, 'setattr returns None'
+
+q = Point(3, 4)
+for i in range(3):
+    setattr(q, f'field_{i}', i * 10)
+assert q.field_1 == 10, 'setattr name built at runtime'
+assert getattr(q, f'field_{2}') == 20, 'getattr name built at runtime'
+assert hasattr(q, 'field_' + '0'), 'hasattr name built at runtime'
+assert not hasattr(q, f'field_{3}'), 'hasattr missing name built at runtime'
+
+
+def set_fields(obj, names):
+    for name in names:
+        setattr(obj, name.upper(), len(name))
+    return [getattr(obj, name.upper()) for name in names]
+
+
+assert set_fields(q, ['ab', 'cde']) == [2, 3], 'setattr runtime names in a function'
+setattr(Point, 'class_' + 'attr', 7)
+assert Point(0, 0).class_attr == 7, 'setattr runtime name on class'
+
+assert hasattr(p, 'x'), 'hasattr instance attribute'
+assert hasattr(p, 'total'), 'hasattr method'
+assert not hasattr(p, 'nope'), 'hasattr missing attribute'
+assert hasattr(sys, 'version_info'), 'hasattr module attribute'
+assert not hasattr(sys, 'nope'), 'hasattr missing module attribute'
+assert not hasattr(1, 'nope'), 'hasattr missing int attribute'
+
+try:
+    getattr(p, 1)
+    assert False, 'getattr with non-str name should raise'
+except TypeError as e:
+    assert str(e) == "attribute name must be string, not 'int'", f'getattr name type error: {e}'
+
+try:
+    getattr(p, 'missing')
+    assert False, 'getattr of missing attribute should raise'
+except AttributeError as e:
+    assert str(e) == "'Point' object has no attribute 'missing'", f'getattr missing: {e}'
+
+try:
+    getattr(p)
+    assert False, 'getattr with one argument should raise'
+except TypeError as e:
+    assert str(e) == 'getattr expected at least 2 arguments, got 1', f'getattr arg count: {e}'
+
+# === callable() ===
+assert callable(len), 'builtin is callable'
+assert callable(Point), 'class is callable'
+assert callable(p.total), 'bound method is callable'
+assert callable(lambda: 1), 'lambda is callable'
+assert callable(tick), 'function is callable'
+assert callable(int), 'type is callable'
+assert not callable(1), 'int is not callable'
+assert not callable('abc'), 'str is not callable'
+assert not callable(p), 'instance without __call__ is not callable'
+
+# === dir() and vars() ===
+names = dir(p)
+assert 'x' in names and 'kind' in names and 'total' in names, f'dir of instance: {names}'
+assert names == sorted(names), 'dir is sorted'
+assert 'kind' in dir(Point), 'dir of class'
+assert 'version_info' in dir(sys), 'dir of module'
+assert vars(p) == {'x': 10, 'y': 0, 'z': 5}, 'vars of instance'
+
+
+def local_scope():
+    b = 2
+    a = 1
+    return dir(), vars()
+
+
+assert local_scope() == (['a', 'b'], {'a': 1, 'b': 2}), 'dir and vars without arguments'
+
+try:
+    vars(1)
+    assert False, 'vars of int should raise'
+except TypeError as e:
+    assert str(e) == 'vars() argument must have __dict__ attribute', f'vars error: {e}'
+
+# === issubclass() ===
+
+
+class Base:
+    pass
+
+
+class Child(Base):
+    pass
+
+
+assert issubclass(Child, Base), 'subclass'
+assert issubclass(Child, Child), 'class is its own subclass'
+assert not issubclass(Base, Child), 'base is not subclass of child'
+assert issubclass(Child, object), 'classes subclass object'
+assert issubclass(Child, (int, Base)), 'subclass of tuple member'
+assert issubclass(bool, int), 'bool subclasses int'
+assert not issubclass(int, bool), 'int does not subclass bool'
+assert issubclass(KeyError, LookupError), 'exception hierarchy'
+assert not issubclass(KeyError, ValueError), 'unrelated exceptions'
+
+try:
+    issubclass(1, int)
+    assert False, 'issubclass with non-class should raise'
+except TypeError as e:
+    assert str(e) == 'issubclass() arg 1 must be a class', f'issubclass error: {e}'
+
+# === ascii() ===
+assert ascii('abc') == "'abc'", 'ascii of ascii str'
+assert ascii('café') == "'caf\\xe9'", 'ascii escapes latin-1'
+assert ascii('日本') == "'\\u65e5\\u672c'", 'ascii escapes bmp'
+assert ascii(['é']) == "['\\xe9']", 'ascii of list'
+assert ascii(1) == '1', 'ascii of int'
+
+
+class Named:
+    def __repr__(self):
+        return 'Named(é)'
+
+
+assert repr(Named()) == 'Named(é)', 'repr uses __repr__'
+assert ascii(Named()) == 'Named(\\xe9)', 'ascii uses __repr__'
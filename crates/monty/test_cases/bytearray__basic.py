@@ -0,0 +1,201 @@
+# === Construction and repr ===
+assert bytearray() == bytearray(b''), 'empty bytearray'
+assert repr(bytearray()) == "bytearray(b'')", 'empty repr'
+assert repr(bytearray(b'abc')) == "bytearray(b'abc')", 'repr from bytes'
+assert bytearray(3) == bytearray(b'\x00\x00\x00'), 'zero-filled from count'
+assert bytearray([104, 105]) == bytearray(b'hi'), 'from list of ints'
+assert bytearray(range(3)) == bytearray(b'\x00\x01\x02'), 'from range'
+assert bytearray(bytearray(b'xy')) == bytearray(b'xy'), 'from bytearray'
+assert bytearray.fromhex('6869') == bytearray(b'hi'), 'fromhex'
+assert type(bytearray.fromhex('00')) is bytearray, 'fromhex returns bytearray'
+assert type(bytearray(b'a')) is bytearray, 'type is bytearray'
+assert isinstance(bytearray(), bytearray), 'isinstance bytearray'
+assert str(bytearray(b'a')) == "bytearray(b'a')", 'str matches repr'
+
+# === Equality with bytes ===
+assert bytearray(b'abc') == b'abc', 'bytearray equals bytes'
+assert b'abc' == bytearray(b'abc'), 'bytes equals bytearray'
+assert bytearray(b'abc') != b'abd', 'different contents'
+assert bytearray(b'a') != 'a', 'bytearray not equal to str'
+
+# === Length, truthiness, indexing ===
+ba = bytearray(b'hello')
+assert len(ba) == 5, 'len'
+assert ba, 'non-empty is truthy'
+assert not bytearray(), 'empty is falsy'
+assert ba[0] == 104, 'index returns int'
+assert ba[-1] == 111, 'negative index'
+assert ba[1:3] == bytearray(b'el'), 'slice'
+assert type(ba[1:3]) is bytearray, 'slice returns bytearray'
+assert ba[::-1] == b'olleh', 'reversed slice'
+assert list(ba) == [104, 101, 108, 108, 111], 'iteration yields ints'
+assert 101 in ba, 'int in bytearray'
+assert b'll' in ba, 'bytes in bytearray'
+assert bytearray(b'el') in b'hello', 'bytearray in bytes'
+assert 120 not in ba, 'int not in bytearray'
+
+# === Item and slice assignment ===
+ba = bytearray(b'hello')
+ba[0] = 106
+assert ba == b'jello', 'set item'
+ba[-1] = 121
+assert ba == b'jelly', 'set negative item'
+ba[1:3] = b'EE'
+assert ba == b'jEEly', 'set slice same length'
+ba[1:3] = b''
+assert ba == b'jly', 'set slice shrinks'
+ba[1:1] = [1, 2]
+assert ba == b'j\x01\x02ly', 'set slice inserts ints'
+ba[::2] = b'abc'
+assert ba == b'a\x01blc', 'set extended slice'
+ba[:] = ba
+assert ba == b'a\x01blc', 'assign bytearray to its own slice'
+del ba[1]
+assert ba == b'ablc', 'delete item'
+del ba[1:3]
+assert ba == b'ac', 'delete slice'
+ba = bytearray(b'abcdef')
+del ba[::2]
+assert ba == b'bdf', 'delete extended slice'
+
+# === Mutation methods ===
+ba = bytearray(b'ab')
+ba.append(99)
+assert ba == b'abc', 'append'
+ba.extend(b'de')
+assert ba == b'abcde', 'extend with bytes'
+ba.extend([102, 103])
+assert ba == b'abcdefg', 'extend with list of ints'
+ba.extend(ba)
+assert ba == b'abcdefgabcdefg', 'extend with itself'
+ba = bytearray(b'ac')
+ba.insert(1, 98)
+assert ba == b'abc', 'insert'
+ba.insert(100, 100)
+assert ba == b'abcd', 'insert past end appends'
+ba.insert(-100, 95)
+assert ba == b'_abcd', 'insert before start prepends'
+assert ba.pop() == 100, 'pop returns last'
+assert ba.pop(0) == 95, 'pop with index'
+assert ba == b'abc', 'after pops'
+ba.remove(98)
+assert ba == b'ac', 'remove'
+ba.reverse()
+assert ba == b'ca', 'reverse'
+c = ba.copy()
+c.append(120)
+assert ba == b'ca', 'copy is independent'
+assert c == b'cax', 'copy was modified'
+ba.clear()
+assert ba == b'', 'clear'
+
+# === Operators ===
+ba = bytearray(b'ab')
+assert ba + b'cd' == b'abcd', 'bytearray + bytes'
+assert type(ba + b'cd') is bytearray, 'bytearray + bytes is bytearray'
+assert type(b'cd' + ba) is bytes, 'bytes + bytearray is bytes'
+assert ba * 2 == b'abab', 'repeat'
+assert type(ba * 2) is bytearray, 'repeat is bytearray'
+alias = ba
+ba += b'cd'
+assert alias == b'abcd', 'in-place add mutates'
+
+# === Methods shared with bytes ===
+ba = bytearray(b'Hello World')
+assert ba.upper() == b'HELLO WORLD', 'upper'
+assert type(ba.upper()) is bytearray, 'upper returns bytearray'
+assert ba.find(b'o') == 4, 'find'
+assert ba.count(b'o') == 2, 'count'
+assert ba.startswith(b'Hell'), 'startswith'
+assert ba.decode() == 'Hello World', 'decode'
+assert ba.hex() == '48656c6c6f20576f726c64', 'hex'
+parts = ba.split()
+assert parts == [b'Hello', b'World'], 'split'
+assert type(parts[0]) is bytearray, 'split items are bytearrays'
+head, sep, tail = ba.partition(b' ')
+assert type(head) is bytearray and sep == b' ' and tail == b'World', 'partition'
+assert bytearray(b', ').join([b'a', bytearray(b'b')]) == b'a, b', 'join'
+assert ba.replace(b'World', b'There') == b'Hello There', 'replace'
+assert ba.find(ba) == 0, 'find with itself'
+assert ba == b'Hello World', 'shared methods leave the bytearray unchanged'
+
+# === Errors ===
+try:
+    hash(bytearray())
+    assert False, 'hashing should raise'
+except TypeError as e:
+    assert str(e) == "unhashable type: 'bytearray'", 'unhashable message'
+
+try:
+    bytearray(b'a')[5]
+    assert False, 'index out of range should raise'
+except IndexError as e:
+    assert str(e) == 'bytearray index out of range', 'index error message'
+
+try:
+    bytearray(b'a')[0] = 256
+    assert False, 'byte out of range should raise'
+except ValueError as e:
+    assert str(e) == 'byte must be in range(0, 256)', 'byte range message'
+
+try:
+    bytearray([300])
+    assert False, 'constructor with big int should raise'
+except ValueError as e:
+    assert str(e) == 'byte must be in range(0, 256)', 'constructor range message'
+
+try:
+    bytearray(1.5)
+    assert False, 'float arg should raise'
+except TypeError as e:
+    assert str(e) == "cannot convert 'float' object to bytearray", 'constructor type message'
+
+try:
+    bytearray(b'a').append('b')
+    assert False, 'append str should raise'
+except TypeError as e:
+    assert str(e) == "'str' object cannot be interpreted as an integer", 'append type message'
+
+try:
+    bytearray().extend(1)
+    assert False, 'extend with int should raise'
+except TypeError as e:
+    assert str(e) == "can't extend bytearray with int", 'extend type message'
+
+try:
+    bytearray().pop()
+    assert False, 'pop from empty should raise'
+except IndexError as e:
+    assert str(e) == 'pop from empty bytearray', 'pop empty message'
+
+try:
+    bytearray(b'a').remove(98)
+    assert False, 'remove missing should raise'
+except ValueError as e:
+    assert str(e) == 'value not found in bytearray', 'remove message'
+
+try:
+    ba = bytearray(b'abc')
+    ba[::2] = b'x'
+    assert False, 'extended slice size mismatch should raise'
+except ValueError as e:
+    assert str(e) == 'attempt to assign bytes of size 1 to extended slice of size 2', 'extended slice message'
+
+try:
+    ba = bytearray(b'abc')
+    ba[0:1] = 5
+    assert False, 'assigning an int to a slice should raise'
+except TypeError as e:
+    assert str(e) == 'can assign only bytes, buffers, or iterables of ints in range(0, 256)', 'slice assign message'
+
+try:
+    bytearray(b'a').sort()
+    assert False, 'unknown method should raise'
+except AttributeError as e:
+    assert str(e) == "'bytearray' object has no attribute 'sort'", 'attribute error message'
+
+try:
+    'a' in bytearray(b'a')
+    assert False, 'str in bytearray should raise'
+except TypeError as e:
+    assert str(e) == "a bytes-like object is required, not 'str'", 'contains type message'
@@ -0,0 +1,83 @@
+# === Class definition and instantiation ===
+class Point:
+    """A point in 2D space."""
+
+    dims = 2
+
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+    def norm_sq(self):
+        return self.x * self.x + self.y * self.y
+
+    def move(self, dx, dy=0):
+        self.x = self.x + dx
+        self.y = self.y + dy
+        return self
+
+
+p = Point(3, 4)
+assert p.x == 3, 'instance attribute x'
+assert p.y == 4, 'instance attribute y'
+assert p.norm_sq() == 25, 'method call'
+
+# === Class attributes ===
+assert Point.dims == 2, 'class attribute via class'
+assert p.dims == 2, 'class attribute via instance'
+p.dims = 3
+assert p.dims == 3, 'instance attribute shadows class attribute'
+assert Point.dims == 2, 'class attribute unchanged'
+Point.dims = 5
+q = Point(0, 0)
+assert q.dims == 5, 'class attribute updated'
+
+# === Methods with defaults and keyword args ===
+p.move(1)
+assert p.x == 4 and p.y == 4, 'method default arg'
+p.move(dy=2, dx=-1)
+assert p.x == 3 and p.y == 6, 'method keyword args'
+assert p.move(0) is p, 'method returning self'
+
+# === Bound methods ===
+m = p.norm_sq
+assert m() == 45, 'bound method called later'
+assert Point.norm_sq(p) == 45, 'function called through class'
+
+# === Class without __init__ ===
+class Empty:
+    pass
+
+
+e = Empty()
+e.value = 'set later'
+assert e.value == 'set later', 'attribute set on empty instance'
+assert e is not Empty(), 'separate instances'
+
+# === Names and identity ===
+assert Point.__name__ == 'Point', 'class __name__'
+assert p.__class__ is Point, 'instance __class__'
+assert type(p) is Point, 'type() of instance'
+assert bool(p), 'instances are truthy'
+assert p == p, 'instance equal to itself'
+assert p != q, 'distinct instances are not equal'
+assert repr(e).startswith('<__main__.Empty object'), 'default repr'
+assert repr(Point) == "<class '__main__.Point'>", 'class repr'
+
+
+# === Classes defined in functions ===
+def make_counter(start):
+    class Counter:
+        def __init__(self):
+            self.count = start
+
+        def incr(self):
+            self.count = self.count + 1
+            return self.count
+
+    return Counter()
+
+
+c = make_counter(10)
+assert c.incr() == 11, 'class closing over function local'
+assert c.incr() == 12, 'state kept between calls'
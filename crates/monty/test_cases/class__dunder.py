@@ -0,0 +1,166 @@
+class Vec:
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+    def __repr__(self):
+        return f'Vec({self.x}, {self.y})'
+
+    def __eq__(self, other):
+        return isinstance(other, Vec) and self.x == other.x and self.y == other.y
+
+    def __len__(self):
+        return 2
+
+
+v = Vec(1, 2)
+
+# === __repr__ ===
+assert repr(v) == 'Vec(1, 2)', 'repr uses __repr__'
+assert str(v) == 'Vec(1, 2)', 'str falls back to __repr__'
+assert f'{v}' == 'Vec(1, 2)', 'f-string falls back to __repr__'
+assert f'{v!r}' == 'Vec(1, 2)', 'f-string !r uses __repr__'
+
+# === __eq__ ===
+assert v == Vec(1, 2), '__eq__ equal'
+assert not (v == Vec(2, 1)), '__eq__ not equal'
+assert v != Vec(2, 1), '__ne__ derived from __eq__'
+assert not (v != Vec(1, 2)), '__ne__ derived from __eq__ when equal'
+assert v != 5, '__eq__ with other type'
+
+# === __len__ ===
+assert len(v) == 2, 'len uses __len__'
+
+
+class Named:
+    def __init__(self, name):
+        self.name = name
+
+    def __str__(self):
+        return 'Named ' + self.name
+
+    def __repr__(self):
+        return f'Named({self.name!r})'
+
+
+n = Named('a')
+assert str(n) == 'Named a', 'str uses __str__'
+assert repr(n) == "Named('a')", 'repr uses __repr__'
+assert f'{n}' == 'Named a', 'f-string uses __str__'
+assert f'{n!r}' == "Named('a')", 'f-string !r uses __repr__'
+assert f'[{n!s:>10}]' == '[   Named a]', 'f-string with format spec'
+
+# === __hash__ ===
+try:
+    hash(v)
+    assert False, 'defining __eq__ without __hash__ makes instances unhashable'
+except TypeError as e:
+    assert str(e) == "unhashable type: 'Vec'", f'unhashable message {e}'
+
+
+class Key:
+    def __init__(self, name):
+        self.name = name
+
+    def __eq__(self, other):
+        return isinstance(other, Key) and self.name == other.name
+
+    def __hash__(self):
+        return hash(self.name)
+
+
+assert hash(Key('a')) == hash('a'), 'hash uses __hash__'
+assert len({Key('a'), Key('a'), Key('b')}) == 2, 'set display dedupes equal instances'
+assert len(set([Key('a'), Key('a')])) == 1, 'set() dedupes equal instances'
+assert len({Key(c) for c in 'aab'}) == 2, 'set comprehension dedupes equal instances'
+assert {Key('a'), Key('b')} == {Key('b'), Key('a')}, 'set equality uses __eq__'
+
+d = {Key('a'): 1}
+d[Key('a')] = 2
+assert len(d) == 1, 'assigning to an equal key replaces the value'
+assert d[Key('a')] == 2, 'subscript finds an equal key'
+assert Key('a') in d, 'in finds an equal key'
+assert Key('b') not in d, 'not in with a different key'
+assert d.get(Key('a')) == 2, 'dict.get finds an equal key'
+assert {Key('a'): 1, Key('a'): 3} == {Key('a'): 3}, 'dict display keeps one equal key'
+del d[Key('a')]
+assert len(d) == 0, 'del finds an equal key'
+
+s = {Key('a'), Key('b')}
+s.discard(Key('a'))
+s.add(Key('b'))
+assert len(s) == 1, 'set methods find equal elements'
+
+
+class Plain:
+    pass
+
+
+p = Plain()
+assert {p: 1}[p] == 1, 'instances without __eq__ hash by identity'
+assert Plain() not in {p}, 'instances without __eq__ compare by identity'
+
+# === __eq__ in containers ===
+assert [v] == [Vec(1, 2)], 'list equality uses __eq__'
+assert [v] != [Vec(2, 1)], 'list inequality uses __eq__'
+assert (v, 1) == (Vec(1, 2), 1), 'tuple equality uses __eq__'
+assert [[v]] == [[Vec(1, 2)]], 'nested list equality uses __eq__'
+assert {'k': v} == {'k': Vec(1, 2)}, 'dict value equality uses __eq__'
+assert Vec(1, 2) in [v], 'in on a list uses __eq__'
+assert Vec(3, 4) not in (v,), 'not in on a tuple uses __eq__'
+
+
+# === ordering ===
+class Version:
+    def __init__(self, n):
+        self.n = n
+
+    def __lt__(self, other):
+        return self.n < other.n
+
+    def __le__(self, other):
+        return self.n <= other.n
+
+
+assert Version(1) < Version(2), '< uses __lt__'
+assert Version(2) <= Version(2), '<= uses __le__'
+assert Version(3) > Version(2), '> uses the reflected __lt__'
+assert not (Version(1) >= Version(2)), '>= uses the reflected __le__'
+
+try:
+    n < n
+    assert False, 'ordering without __lt__ raises'
+except TypeError as e:
+    assert str(e) == "'<' not supported between instances of 'Named' and 'Named'", f'ordering message {e}'
+
+
+# === truthiness ===
+class Empty:
+    def __len__(self):
+        return 0
+
+
+class Flag:
+    def __init__(self, on):
+        self.on = on
+
+    def __bool__(self):
+        return self.on
+
+
+assert not Empty(), '__len__ of 0 is falsy'
+assert v, '__len__ of 2 is truthy'
+assert bool(Flag(True)), 'bool uses __bool__'
+assert not bool(Flag(False)), 'bool uses __bool__ when false'
+assert (Flag(False) or 'x') == 'x', 'or uses __bool__'
+assert (Flag(True) and 'y') == 'y', 'and uses __bool__'
+assert [f.on for f in [Flag(True), Flag(False)] if f] == [True], 'comprehension condition uses __bool__'
+if Flag(False):
+    assert False, 'if uses __bool__'
+assert Plain(), 'instances without __bool__ or __len__ are truthy'
+
+try:
+    bool(Flag(1))
+    assert False, '__bool__ must return a bool'
+except TypeError as e:
+    assert str(e) == '__bool__ should return bool, returned int', f'__bool__ message {e}'
@@ -0,0 +1,35 @@
+# call-external
+# === Instances of user-defined classes across external calls ===
+# the VM state, including classes and instances, is dumped and reloaded at each external call
+
+
+class Point:
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+    def __repr__(self):
+        return f'Point({self.x}, {self.y})'
+
+    def __eq__(self, other):
+        return isinstance(other, Point) and (self.x, self.y) == (other.x, other.y)
+
+    def __hash__(self):
+        return hash((self.x, self.y))
+
+    def moved(self, dx):
+        return Point(self.x + dx, self.y)
+
+
+p = Point(1, 2)
+names = {p: 'start'}
+total = add_ints(p.x, p.y)
+assert total == 3, 'attributes passed to an external call'
+assert repr(p) == 'Point(1, 2)', 'special methods dispatch after the call'
+assert names[Point(1, 2)] == 'start', 'dict lookup by an equal instance after the call'
+assert isinstance(p, Point), 'the instance keeps its class'
+
+q = p.moved(add_ints(1, 1))
+assert q == Point(3, 2), 'method called with the result of an external call'
+p.x = return_value(10)
+assert p == Point(10, 2), 'attribute set from the result of an external call'
@@ -0,0 +1,46 @@
+class Animal:
+    sound = '...'
+
+    def __init__(self, name):
+        self.name = name
+
+    def speak(self):
+        return f'{self.name} says {self.sound}'
+
+    def kind(self):
+        return 'animal'
+
+
+class Dog(Animal):
+    sound = 'woof'
+
+    def kind(self):
+        return 'dog'
+
+
+class Puppy(Dog):
+    def __init__(self, name, age):
+        self.name = name
+        self.age = age
+
+
+d = Dog('Rex')
+assert d.name == 'Rex', 'inherited __init__'
+assert d.speak() == 'Rex says woof', 'inherited method uses overridden class attribute'
+assert d.kind() == 'dog', 'overridden method'
+
+p = Puppy('Bit', 1)
+assert p.age == 1, 'overridden __init__'
+assert p.speak() == 'Bit says woof', 'method inherited through two levels'
+assert p.kind() == 'dog', 'method from direct base'
+assert Animal.kind(p) == 'animal', 'calling base implementation explicitly'
+
+a = Animal('Generic')
+assert a.speak() == 'Generic says ...', 'base class unaffected'
+
+
+class Base(object):
+    pass
+
+
+assert isinstance(Base(), Base), 'explicit object base'
@@ -0,0 +1,7 @@
+# Test: calling a class without __init__ with arguments
+class Foo:
+    pass
+
+
+Foo(1)
+# Raise=TypeError('Foo() takes no arguments')
@@ -0,0 +1,25 @@
+class A:
+    pass
+
+
+class B(A):
+    pass
+
+
+class C:
+    pass
+
+
+a = A()
+b = B()
+
+assert isinstance(a, A), 'instance of own class'
+assert isinstance(b, B), 'instance of subclass'
+assert isinstance(b, A), 'instance of base class'
+assert not isinstance(a, B), 'base instance is not a subclass instance'
+assert not isinstance(a, C), 'unrelated class'
+assert isinstance(a, (C, A)), 'tuple of classes'
+assert isinstance(b, (int, (C, A))), 'nested tuple of classes'
+assert not isinstance(1, A), 'builtin value against user class'
+assert isinstance(a, object), 'instances are objects'
+assert not isinstance(a, int), 'instance against builtin type'
@@ -0,0 +1,14 @@
+class Foo:
+    def __init__(self):
+        self.x = 1
+
+
+f = Foo()
+f.y
+"""
+TRACEBACK:
+Traceback (most recent call last):
+  File "class__missing_attr.py", line 7, in <module>
+    f.y
+AttributeError: 'Foo' object has no attribute 'y'
+"""
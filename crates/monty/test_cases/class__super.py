@@ -0,0 +1,33 @@
+class Shape:
+    def __init__(self, name):
+        self.name = name
+
+    def describe(self):
+        return f'shape {self.name}'
+
+
+class Square(Shape):
+    def __init__(self, side):
+        super().__init__('square')
+        self.side = side
+
+    def describe(self):
+        return super().describe() + f' with side {self.side}'
+
+
+class Cube(Square):
+    def describe(self):
+        return 'cube: ' + super().describe()
+
+
+s = Square(2)
+assert s.name == 'square', 'super().__init__ sets attribute'
+assert s.side == 2, 'own attribute set after super().__init__'
+assert s.describe() == 'shape square with side 2', 'super() method call'
+
+c = Cube(3)
+assert c.describe() == 'cube: shape square with side 3', 'chained super() calls'
+
+# === Explicit two-argument form ===
+assert super(Cube, c).describe() == 'shape square with side 3', 'super(cls, obj)'
+assert super(Square, c).describe() == 'shape square', 'super skipping two levels'
@@ -0,0 +1,96 @@
+from collections import Counter
+
+# === construction ===
+c = Counter('abracadabra')
+assert c['a'] == 5 and c['b'] == 2 and c['r'] == 2, 'count letters'
+assert c['z'] == 0, 'missing element counts zero'
+assert 'z' not in c, 'missing element is not stored'
+assert Counter({'x': 2, 'y': 1}) == Counter(x=2, y=1), 'from mapping and kwargs'
+assert Counter() == Counter(), 'empty counters'
+assert Counter(w for w in ['a', 'b', 'a'])['a'] == 2, 'from generator'
+assert isinstance(c, dict), 'is a dict'
+assert len(c) == 5, 'len'
+
+# === repr ===
+assert repr(Counter()) == 'Counter()', 'empty repr'
+assert repr(Counter('abbccc')) == "Counter({'c': 3, 'b': 2, 'a': 1})", 'repr in most_common order'
+assert repr(Counter(a=1, b=1)) == "Counter({'a': 1, 'b': 1})", 'ties keep insertion order'
+
+# === most_common ===
+c = Counter('abracadabra')
+assert c.most_common(1) == [('a', 5)], 'most_common(1)'
+assert c.most_common(3) == [('a', 5), ('b', 2), ('r', 2)], 'most_common(3)'
+assert c.most_common() == [('a', 5), ('b', 2), ('r', 2), ('c', 1), ('d', 1)], 'most_common()'
+assert c.most_common(0) == [], 'most_common(0)'
+assert c.most_common(-1) == [], 'most_common negative'
+assert len(c.most_common(100)) == 5, 'most_common larger than len'
+
+# === elements and total ===
+c = Counter(a=2, b=1, c=0, d=-1)
+assert list(c.elements()) == ['a', 'a', 'b'], 'elements skips non-positive counts'
+assert c.total() == 2, 'total'
+assert Counter().total() == 0, 'empty total'
+
+# === update and subtract ===
+c = Counter(a=1)
+c.update('aab')
+assert c == Counter(a=3, b=1), 'update with iterable'
+c.update({'b': 2}, c=1)
+assert c == Counter(a=3, b=3, c=1), 'update with mapping and kwargs'
+c.update(Counter(a=1))
+assert c['a'] == 4, 'update with counter'
+c.subtract('ab')
+assert c == Counter(a=3, b=2, c=1), 'subtract iterable'
+c.subtract(c=5)
+assert c['c'] == -4, 'subtract can go negative'
+try:
+    c.update([], [])
+    assert False, 'too many arguments should raise'
+except TypeError as e:
+    assert str(e) == 'Counter.update() takes from 1 to 2 positional arguments but 3 were given', f'update error: {e}'
+
+# === item access ===
+c = Counter(a=1)
+c['b'] += 3
+assert c['b'] == 3, 'increment missing key'
+del c['zz']
+del c['a']
+assert c == Counter(b=3), 'delete missing key is silent'
+
+# === arithmetic ===
+a = Counter(a=3, b=1, c=-2)
+b = Counter(a=1, b=2, d=4)
+assert a + b == Counter(a=4, b=3, d=4), 'add'
+assert a - b == Counter(a=2), 'sub'
+assert a | b == Counter(a=3, b=2, d=4), 'or'
+assert a & b == Counter(a=1, b=1), 'and'
+assert repr(a - a) == 'Counter()', 'sub to empty'
+x = Counter(a=1)
+x += Counter(a=2)
+assert x == Counter(a=3), 'inplace add'
+x -= Counter(a=5)
+assert x == Counter(), 'inplace sub drops non-positive'
+try:
+    Counter() + {}
+    assert False, 'adding a dict should raise'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for +: 'Counter' and 'dict'", f'add error: {e}'
+
+# === equality ===
+assert Counter(a=1, b=0) == Counter(a=1), 'missing elements are zero'
+assert Counter(a=1) != Counter(a=2), 'different counts'
+assert Counter(a=1) == {'a': 1}, 'equal to dict'
+
+# === copy and other dict methods ===
+c = Counter('aab')
+d = c.copy()
+d['a'] = 10
+assert c['a'] == 2 and type(d) is Counter, 'copy'
+assert sorted(c.items()) == [('a', 2), ('b', 1)], 'items'
+assert c.get('a') == 2 and c.get('z') is None, 'get'
+assert c.pop('b') == 1, 'pop'
+try:
+    Counter.fromkeys('ab')
+    assert False, 'fromkeys should raise'
+except NotImplementedError as e:
+    assert str(e) == 'Counter.fromkeys() is undefined.  Use Counter(iterable) instead.', f'fromkeys error: {e}'
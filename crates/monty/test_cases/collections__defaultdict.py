@@ -0,0 +1,70 @@
+from collections import defaultdict
+
+# === default factory ===
+d = defaultdict(list)
+d['a'].append(1)
+d['a'].append(2)
+d['b'].append(3)
+assert d == {'a': [1, 2], 'b': [3]}, 'list factory'
+assert d.default_factory is list, 'default_factory attribute'
+assert repr(d) == "defaultdict(<class 'list'>, {'a': [1, 2], 'b': [3]})", 'repr'
+
+counts = defaultdict(int)
+for word in ['a', 'b', 'a']:
+    counts[word] += 1
+assert counts == {'a': 2, 'b': 1}, 'int factory'
+
+
+def make_default():
+    return 'x'
+
+
+d = defaultdict(make_default)
+assert d[1] == 'x', 'function factory'
+assert d[2] == 'x', 'function factory again'
+assert len(d) == 2, 'missing keys are stored'
+
+d = defaultdict(lambda: [0])
+d['k'][0] += 5
+assert d['k'] == [5], 'lambda factory'
+
+# === no factory ===
+d = defaultdict()
+assert d.default_factory is None, 'no factory'
+assert repr(d) == 'defaultdict(None, {})', 'repr without factory'
+try:
+    d['missing']
+    assert False, 'missing key without a factory should raise'
+except KeyError as e:
+    assert str(e) == "'missing'", f'key error: {e}'
+try:
+    defaultdict(5)
+    assert False, 'non-callable factory should raise'
+except TypeError as e:
+    assert str(e) == 'first argument must be callable or None', f'factory error: {e}'
+
+# === dict behaviour ===
+d = defaultdict(int, {'a': 1}, b=2)
+assert d == {'a': 1, 'b': 2}, 'initial items'
+assert isinstance(d, dict), 'is a dict'
+assert d.get('z') is None, 'get does not call the factory'
+assert 'z' not in d, 'in does not call the factory'
+assert d.setdefault('c', 3) == 3, 'setdefault'
+assert sorted(d.keys()) == ['a', 'b', 'c'], 'keys'
+assert d.pop('a') == 1, 'pop'
+c = d.copy()
+assert type(c) is defaultdict and c.default_factory is int, 'copy keeps the type'
+assert c == d, 'copy equal'
+assert dict(d) == {'b': 2, 'c': 3}, 'convert to dict'
+assert {**d} == {'b': 2, 'c': 3}, 'unpack'
+try:
+    d.missing_attr
+    assert False, 'missing attribute should raise'
+except AttributeError as e:
+    assert str(e) == "'collections.defaultdict' object has no attribute 'missing_attr'", f'attr error: {e}'
+
+# === nested ===
+tree = defaultdict(lambda: defaultdict(int))
+tree['x']['y'] += 1
+tree['x']['z'] += 2
+assert tree['x'] == {'y': 1, 'z': 2}, 'nested defaultdict'
@@ -0,0 +1,125 @@
+from collections import deque
+
+# === construction ===
+d = deque()
+assert len(d) == 0, 'empty deque'
+assert repr(d) == 'deque([])', 'empty repr'
+d = deque([1, 2, 3])
+assert list(d) == [1, 2, 3], 'from list'
+assert repr(d) == 'deque([1, 2, 3])', 'repr'
+assert deque('abc') == deque(['a', 'b', 'c']), 'from str'
+assert deque(x * 2 for x in range(3)) == deque([0, 2, 4]), 'from generator'
+assert d.maxlen is None, 'unbounded maxlen'
+
+# === append and pop at both ends ===
+d = deque([2])
+d.append(3)
+d.appendleft(1)
+assert list(d) == [1, 2, 3], 'append/appendleft'
+assert d.pop() == 3, 'pop'
+assert d.popleft() == 1, 'popleft'
+assert list(d) == [2], 'after pops'
+d.extend([3, 4])
+d.extendleft([1, 0])
+assert list(d) == [0, 1, 2, 3, 4], 'extendleft reverses order'
+
+# === indexing ===
+d = deque([10, 20, 30])
+assert d[0] == 10 and d[-1] == 30, 'getitem'
+d[1] = 25
+assert d[1] == 25, 'setitem'
+del d[0]
+assert list(d) == [25, 30], 'delitem'
+try:
+    d[5]
+    assert False, 'index out of range should raise'
+except IndexError as e:
+    assert str(e) == 'deque index out of range', f'index error: {e}'
+
+# === maxlen ===
+d = deque([1, 2, 3], maxlen=3)
+assert d.maxlen == 3, 'maxlen attribute'
+d.append(4)
+assert list(d) == [2, 3, 4], 'append discards from the left'
+d.appendleft(1)
+assert list(d) == [1, 2, 3], 'appendleft discards from the right'
+d.extend([5, 6])
+assert list(d) == [3, 5, 6], 'extend discards'
+assert repr(d) == 'deque([3, 5, 6], maxlen=3)', 'repr with maxlen'
+assert list(deque(range(10), 2)) == [8, 9], 'maxlen positional'
+assert list(deque([1, 2], maxlen=0)) == [], 'maxlen zero'
+try:
+    deque([], -1)
+    assert False, 'negative maxlen should raise'
+except ValueError as e:
+    assert str(e) == 'maxlen must be non-negative', f'maxlen error: {e}'
+try:
+    d.insert(0, 9)
+    assert False, 'insert into a full deque should raise'
+except IndexError as e:
+    assert str(e) == 'deque already at its maximum size', f'insert error: {e}'
+
+# === rotate ===
+d = deque([1, 2, 3, 4, 5])
+d.rotate()
+assert list(d) == [5, 1, 2, 3, 4], 'rotate default'
+d.rotate(2)
+assert list(d) == [3, 4, 5, 1, 2], 'rotate right'
+d.rotate(-3)
+assert list(d) == [1, 2, 3, 4, 5], 'rotate left'
+d.rotate(12)
+assert list(d) == [4, 5, 1, 2, 3], 'rotate more than len'
+e = deque()
+e.rotate(3)
+assert list(e) == [], 'rotate empty'
+
+# === other methods ===
+d = deque([1, 2, 3, 2])
+assert d.count(2) == 2, 'count'
+assert d.index(2) == 1, 'index'
+assert d.index(2, 2) == 3, 'index with start'
+d.remove(2)
+assert list(d) == [1, 3, 2], 'remove'
+d.insert(1, 9)
+assert list(d) == [1, 9, 3, 2], 'insert'
+d.reverse()
+assert list(d) == [2, 3, 9, 1], 'reverse'
+c = d.copy()
+c.append(0)
+assert len(d) == 4 and len(c) == 5, 'copy is independent'
+d.clear()
+assert len(d) == 0 and not d, 'clear'
+try:
+    d.pop()
+    assert False, 'pop from empty should raise'
+except IndexError as e:
+    assert str(e) == 'pop from an empty deque', f'pop error: {e}'
+try:
+    deque([1]).remove(5)
+    assert False, 'remove missing should raise'
+except ValueError as e:
+    assert str(e) == '5 is not in deque', f'remove error: {e}'
+
+# === self-extend and iteration ===
+d = deque([1, 2])
+d.extend(d)
+assert list(d) == [1, 2, 1, 2], 'extend with itself'
+d = deque([1, 2])
+d.extendleft(d)
+assert list(d) == [2, 1, 1, 2], 'extendleft with itself'
+assert 2 in d and 5 not in d, 'contains'
+assert [x + 1 for x in deque([1, 2])] == [2, 3], 'iteration'
+try:
+    for x in d:
+        d.append(x)
+    assert False, 'mutating during iteration should raise'
+except RuntimeError as e:
+    assert str(e) == 'deque mutated during iteration', f'mutation error: {e}'
+
+# === comparison and unpacking ===
+assert deque([1, 2]) == deque([1, 2]), 'equal'
+assert deque([1, 2]) != deque([2, 1]), 'not equal'
+assert deque([1, 2]) != [1, 2], 'not equal to list'
+a, b = deque([1, 2])
+assert (a, b) == (1, 2), 'unpacking'
+assert bool(deque([0])) and not deque(), 'truthiness'
@@ -0,0 +1,120 @@
+from collections import namedtuple
+
+# === definition and construction ===
+Point = namedtuple('Point', 'x y')
+p = Point(1, 2)
+assert p.x == 1 and p.y == 2, 'field access'
+assert p[0] == 1 and p[-1] == 2, 'index access'
+assert repr(p) == 'Point(x=1, y=2)', 'repr'
+assert Point(y=2, x=1) == p, 'keyword arguments'
+assert Point._fields == ('x', 'y'), '_fields'
+assert p._fields == ('x', 'y'), '_fields on instance'
+assert Point.__name__ == 'Point', '__name__'
+assert repr(Point) == "<class '__main__.Point'>", 'class repr'
+assert namedtuple('P', 'a, b,c')._fields == ('a', 'b', 'c'), 'commas and spaces'
+assert namedtuple('P', ['a', 'b'])._fields == ('a', 'b'), 'list of names'
+assert namedtuple('P', (n for n in ['a', 'b']))._fields == ('a', 'b'), 'generator of names'
+Empty = namedtuple('Empty', '')
+assert repr(Empty()) == 'Empty()', 'no fields'
+assert repr(namedtuple('Q', 'a', module='m')) == "<class 'm.Q'>", 'module argument'
+
+# === tuple behaviour ===
+assert p == (1, 2), 'equal to tuple'
+assert isinstance(p, tuple), 'is a tuple'
+assert isinstance(p, Point), 'instance of its namedtuple type'
+assert isinstance(p, (int, Point)), 'namedtuple type in a tuple'
+assert not isinstance(p, Empty), 'not an instance of another namedtuple type'
+assert not isinstance((1, 2), Point), 'a plain tuple is not an instance of a namedtuple type'
+assert not isinstance(1, Point), 'an int is not an instance of a namedtuple type'
+assert len(p) == 2, 'len'
+assert list(p) == [1, 2], 'iteration'
+x, y = p
+assert (x, y) == (1, 2), 'unpacking'
+first, *rest = Point(3, 4)
+assert first == 3 and rest == [4], 'star unpacking'
+assert 2 in p and 3 not in p, 'contains'
+assert p.count(1) == 1 and p.index(2) == 1, 'count and index'
+assert hash(p) == hash((1, 2)), 'hash matches tuple'
+assert {p: 'v'}[(1, 2)] == 'v', 'usable as dict key'
+
+# === methods ===
+assert p._asdict() == {'x': 1, 'y': 2}, '_asdict'
+q = p._replace(y=5)
+assert q == Point(1, 5) and p == Point(1, 2), '_replace'
+assert Point._make([7, 8]) == Point(7, 8), '_make'
+assert Point._make(range(2)) == Point(0, 1), '_make with iterable'
+try:
+    p._replace(z=1)
+    assert False, 'unknown field should raise'
+except TypeError as e:
+    assert str(e) == "Got unexpected field names: ['z']", f'_replace error: {e}'
+try:
+    Point._make([1, 2, 3])
+    assert False, 'wrong length should raise'
+except TypeError as e:
+    assert str(e) == 'Expected 2 arguments, got 3', f'_make error: {e}'
+
+# === defaults ===
+P3 = namedtuple('P3', 'x y z', defaults=[0, 1])
+assert P3(5) == (5, 0, 1), 'defaults fill trailing fields'
+assert P3(5, z=9) == (5, 0, 9), 'defaults with keyword'
+assert P3._field_defaults == {'y': 0, 'z': 1}, '_field_defaults'
+assert Point._field_defaults == {}, 'no defaults'
+
+# === rename ===
+R = namedtuple('R', ['a', 'a', 'def', '_x'], rename=True)
+assert R._fields == ('a', '_1', '_2', '_3'), 'rename invalid names'
+
+# === construction errors ===
+try:
+    Point(1)
+    assert False, 'missing argument should raise'
+except TypeError as e:
+    assert str(e) == "Point.__new__() missing 1 required positional argument: 'y'", f'missing error: {e}'
+try:
+    Point(1, 2, 3)
+    assert False, 'too many arguments should raise'
+except TypeError as e:
+    assert str(e) == 'Point.__new__() takes 3 positional arguments but 4 were given', f'too many error: {e}'
+try:
+    Point(1, x=2)
+    assert False, 'duplicate argument should raise'
+except TypeError as e:
+    assert str(e) == "Point.__new__() got multiple values for argument 'x'", f'duplicate error: {e}'
+
+# === definition errors ===
+try:
+    namedtuple('P', 'x x')
+    assert False, 'duplicate field should raise'
+except ValueError as e:
+    assert str(e) == "Encountered duplicate field name: 'x'", f'duplicate field: {e}'
+try:
+    namedtuple('P', 'x _y')
+    assert False, 'underscore field should raise'
+except ValueError as e:
+    assert str(e) == "Field names cannot start with an underscore: '_y'", f'underscore: {e}'
+try:
+    namedtuple('P', 'x 1y')
+    assert False, 'invalid identifier should raise'
+except ValueError as e:
+    assert str(e) == "Type names and field names must be valid identifiers: '1y'", f'identifier: {e}'
+try:
+    namedtuple('P', 'x def')
+    assert False, 'keyword should raise'
+except ValueError as e:
+    assert str(e) == "Type names and field names cannot be a keyword: 'def'", f'keyword: {e}'
+try:
+    namedtuple('P', 'x', defaults=[1, 2])
+    assert False, 'too many defaults should raise'
+except TypeError as e:
+    assert str(e) == 'Got more default values than field names', f'defaults: {e}'
+try:
+    namedtuple('P', 'x', False)
+    assert False, 'rename is keyword-only'
+except TypeError as e:
+    assert str(e) == 'namedtuple() takes 2 positional arguments but 3 were given', f'positional: {e}'
+try:
+    p.z
+    assert False, 'missing attribute should raise'
+except AttributeError as e:
+    assert str(e) == "'Point' object has no attribute 'z'", f'attribute: {e}'
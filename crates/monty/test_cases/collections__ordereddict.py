@@ -0,0 +1,58 @@
+from collections import OrderedDict
+
+# === construction and repr ===
+od = OrderedDict()
+assert repr(od) == 'OrderedDict()', 'empty repr'
+od = OrderedDict([('a', 1), ('b', 2)])
+assert repr(od) == "OrderedDict({'a': 1, 'b': 2})", 'repr'
+assert OrderedDict(a=1, b=2) == od, 'from kwargs'
+assert OrderedDict({'a': 1}) == {'a': 1}, 'from dict'
+assert isinstance(od, dict), 'is a dict'
+od['c'] = 3
+assert list(od) == ['a', 'b', 'c'], 'insertion order'
+
+# === move_to_end ===
+od = OrderedDict([(k, None) for k in 'abcd'])
+od.move_to_end('b')
+assert list(od) == ['a', 'c', 'd', 'b'], 'move_to_end'
+od.move_to_end('b', last=False)
+assert list(od) == ['b', 'a', 'c', 'd'], 'move_to_end first'
+od.move_to_end('d', False)
+assert list(od) == ['d', 'b', 'a', 'c'], 'move_to_end positional last'
+try:
+    od.move_to_end('zz')
+    assert False, 'missing key should raise'
+except KeyError as e:
+    assert str(e) == "'zz'", f'move_to_end error: {e}'
+
+# === popitem ===
+od = OrderedDict([('a', 1), ('b', 2), ('c', 3)])
+assert od.popitem() == ('c', 3), 'popitem last'
+assert od.popitem(last=False) == ('a', 1), 'popitem first'
+assert od.popitem(False) == ('b', 2), 'popitem positional'
+try:
+    od.popitem()
+    assert False, 'popitem on empty should raise'
+except KeyError as e:
+    assert str(e) == "'dictionary is empty'", f'popitem error: {e}'
+
+# === equality ===
+a = OrderedDict([('x', 1), ('y', 2)])
+b = OrderedDict([('y', 2), ('x', 1)])
+assert a != b, 'order matters between ordered dicts'
+assert a == {'y': 2, 'x': 1}, 'order ignored against dict'
+assert {'y': 2, 'x': 1} == a, 'order ignored against dict reversed'
+assert a == OrderedDict([('x', 1), ('y', 2)]), 'same order is equal'
+
+# === dict methods ===
+od = OrderedDict(a=1)
+od.update(b=2)
+od.setdefault('c', 3)
+assert list(od.items()) == [('a', 1), ('b', 2), ('c', 3)], 'update and setdefault'
+assert od.pop('a') == 1, 'pop'
+c = od.copy()
+c['d'] = 4
+assert type(c) is OrderedDict and len(od) == 2, 'copy'
+del od['b']
+assert od == OrderedDict(c=3), 'delitem'
+assert {**c} == {'b': 2, 'c': 3, 'd': 4}, 'unpack'
@@ -0,0 +1,47 @@
+# call-external
+# === Collections restored from a snapshot ===
+# Each external call dumps and loads the run, so every value below goes through a snapshot
+from collections import Counter, OrderedDict, defaultdict, deque, namedtuple
+
+groups = defaultdict(list)
+groups['a'].append(1)
+counts = Counter('abracadabra')
+queue = deque([1, 2, 3], maxlen=3)
+ordered = OrderedDict([('b', 2), ('a', 1)])
+Point = namedtuple('Point', 'x y', defaults=[0])
+p = Point(1, 2)
+assert add_ints(1, 2) == 3, 'external call result'
+
+# === defaultdict ===
+groups['b'].append(2)
+assert groups == {'a': [1], 'b': [2]}, 'factory still called after restore'
+assert groups.default_factory is list, 'default_factory after restore'
+assert repr(groups) == "defaultdict(<class 'list'>, {'a': [1], 'b': [2]})", 'defaultdict repr after restore'
+
+# === Counter ===
+assert counts.most_common(2) == [('a', 5), ('b', 2)], 'most_common after restore'
+assert counts['z'] == 0, 'missing count after restore'
+assert counts + Counter('zz') == Counter({'a': 5, 'b': 2, 'r': 2, 'z': 2, 'c': 1, 'd': 1}), 'counter arithmetic'
+
+# === deque ===
+queue.append(4)
+assert list(queue) == [2, 3, 4], 'maxlen kept after restore'
+assert queue.maxlen == 3, 'maxlen after restore'
+queue.appendleft(1)
+assert queue.popleft() == 1 and queue.pop() == 3, 'deque operations after restore'
+
+# === OrderedDict ===
+ordered['c'] = 3
+ordered.move_to_end('b')
+assert list(ordered) == ['a', 'c', 'b'], 'order kept after restore'
+assert ordered.popitem(last=False) == ('a', 1), 'popitem after restore'
+
+# === namedtuple ===
+assert p == Point(1, 2) and p.y == 2, 'instance after restore'
+assert Point(5) == (5, 0), 'defaults after restore'
+assert isinstance(p, Point), 'isinstance after restore'
+assert p._replace(x=3) == Point(3, 2), '_replace after restore'
+assert repr(p) == 'Point(x=1, y=2)', 'namedtuple repr after restore'
+
+assert add_ints(0, 0) == 0, 'second external call'
+assert groups['c'] == [] and list(queue) == [2], 'values survive a second restore'
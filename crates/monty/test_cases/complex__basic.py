@@ -0,0 +1,106 @@
+# === Literals and repr ===
+assert repr(1j) == '1j', 'imaginary literal repr'
+assert repr(1 + 2j) == '(1+2j)', 'complex repr'
+assert repr(1 - 2j) == '(1-2j)', 'complex repr negative imag'
+assert repr(-1j) == '(-0-1j)', 'negated imaginary repr'
+assert repr(0j) == '0j', 'zero imaginary repr'
+assert repr(1.5 + 0j) == '(1.5+0j)', 'real with zero imag repr'
+assert str(2.5j) == '2.5j', 'str of imaginary'
+assert repr(complex(1e20, 1e-5)) == '(1e+20+1e-05j)', 'scientific repr'
+assert repr(complex(float('inf'), float('nan'))) == '(inf+nanj)', 'inf/nan repr'
+assert type(1j) is complex, 'type of imaginary literal'
+assert type(1 + 1j) is complex, 'type of complex sum'
+
+# === Attributes ===
+z = 3 + 4j
+assert z.real == 3.0, 'real part'
+assert z.imag == 4.0, 'imag part'
+assert type(z.real) is float, 'real part is a float'
+assert z.conjugate() == 3 - 4j, 'conjugate'
+assert abs(z) == 5.0, 'abs of complex'
+
+# === Arithmetic ===
+assert (1 + 2j) + (3 + 4j) == 4 + 6j, 'add'
+assert (1 + 2j) - (3 + 4j) == -2 - 2j, 'sub'
+assert (1 + 2j) * (3 + 4j) == -5 + 10j, 'mul'
+assert (1 + 2j) / (1 + 1j) == 1.5 + 0.5j, 'div'
+assert 2 * 1j == 2j, 'int times complex'
+assert 1j * 1j == -1, 'i squared'
+assert 1.5 + 1j == complex(1.5, 1), 'float plus complex'
+assert True + 1j == 1 + 1j, 'bool plus complex'
+assert 1j**2 == -1, 'power with int exponent'
+assert (1 + 1j) ** 0 == 1, 'zero exponent'
+assert -(1 + 2j) == -1 - 2j, 'unary negation'
+assert +(1 + 2j) == 1 + 2j, 'unary plus'
+z = 1j
+z += 1
+assert z == 1 + 1j, 'in-place add'
+assert sum([1j, 2j, 3]) == 3 + 3j, 'sum of complex values'
+
+# === Equality and hashing ===
+assert 1 + 0j == 1, 'complex equals int'
+assert 1 + 0j == 1.0, 'complex equals float'
+assert 1.0 == 1 + 0j, 'float equals complex'
+assert 1j != 1, 'complex not equal to int'
+assert hash(1 + 0j) == hash(1.0), 'hash matches float for zero imag'
+assert {1 + 0j: 'a'}[1.0] == 'a', 'complex dict key found by float'
+assert len({1j, 1j, 2j}) == 2, 'complex in set'
+
+# === Truthiness ===
+assert not 0j, 'zero complex is falsy'
+assert 1j, 'non-zero complex is truthy'
+assert complex(0, 0.0) == 0, 'zero complex'
+
+# === complex() constructor ===
+assert complex() == 0j, 'no args'
+assert complex(2) == 2 + 0j, 'int arg'
+assert complex(1, 2) == 1 + 2j, 'two args'
+assert complex('1+2j') == 1 + 2j, 'string arg'
+assert complex(' 3j ') == 3j, 'string with whitespace'
+assert complex('(-1.5-2j)') == -1.5 - 2j, 'parenthesized string'
+assert complex('2') == 2 + 0j, 'real-only string'
+assert complex('-j') == -1j, 'bare j string'
+assert complex(1j, 1j) == -1 + 1j, 'complex args'
+
+# === Powers of negative floats ===
+r = (-8) ** 0.5
+assert type(r) is complex, 'negative base to fractional power gives complex'
+assert abs(r.real) < 1e-9, 'real part close to zero'
+assert abs(r.imag - 8**0.5) < 1e-9, 'imag part is sqrt(8)'
+
+# === Errors ===
+try:
+    1j / 0
+    assert False, 'division by zero should raise'
+except ZeroDivisionError as e:
+    assert str(e) == 'complex division by zero', 'complex division by zero message'
+
+try:
+    0j ** -1
+    assert False, 'zero to negative power should raise'
+except ZeroDivisionError as e:
+    assert str(e) == '0.0 to a negative or complex power', 'zero power message'
+
+try:
+    complex('abc')
+    assert False, 'malformed string should raise'
+except ValueError as e:
+    assert str(e) == 'complex() arg is a malformed string', 'malformed string message'
+
+try:
+    complex('1', 2)
+    assert False, 'string with second arg should raise'
+except TypeError as e:
+    assert str(e) == "complex() can't take second arg if first is a string", 'string second arg message'
+
+try:
+    complex([])
+    assert False, 'list arg should raise'
+except TypeError as e:
+    assert str(e) == "complex() first argument must be a string or a number, not 'list'", 'bad first arg message'
+
+try:
+    complex(1, 'a')
+    assert False, 'string second arg should raise'
+except TypeError as e:
+    assert str(e) == "complex() second arg can't be a string", 'string imag message'
@@ -130,3 +130,10 @@ try:
 except AttributeError:
     caught = True
 assert caught, 'FrozenInstanceError caught by AttributeError'
+
+# === isinstance ===
+assert isinstance(point, type(point)), 'instance of its dataclass type'
+assert isinstance(point, (int, type(point))), 'dataclass type in a tuple'
+assert not isinstance(point, type(alice)), 'not an instance of another dataclass type'
+assert not isinstance(make_mutable_point(), type(point)), 'same fields but another dataclass type'
+assert not isinstance((1, 2), type(point)), 'a tuple is not an instance of a dataclass type'
@@ -0,0 +1,308 @@
+import datetime
+from datetime import MAXYEAR, MINYEAR, UTC, date, datetime as dt, timedelta, timezone
+
+# === module attributes ===
+assert MINYEAR == 1, 'MINYEAR'
+assert MAXYEAR == 9999, 'MAXYEAR'
+assert UTC == timezone.utc, 'UTC constant'
+assert datetime.date is date, 'date type'
+assert datetime.datetime is dt, 'datetime type'
+assert date.__name__ == 'date', 'date __name__'
+assert dt.__name__ == 'datetime', 'datetime __name__'
+assert timedelta.__name__ == 'timedelta', 'timedelta __name__'
+
+# === timedelta construction ===
+assert timedelta() == timedelta(0), 'empty timedelta'
+td = timedelta(days=1, hours=2, minutes=3, seconds=4, milliseconds=5, microseconds=6)
+assert td.days == 1, 'days field'
+assert td.seconds == 2 * 3600 + 3 * 60 + 4, 'seconds field'
+assert td.microseconds == 5006, 'microseconds field'
+assert timedelta(weeks=2) == timedelta(days=14), 'weeks'
+assert timedelta(hours=-1).days == -1, 'negative days normalized'
+assert timedelta(hours=-1).seconds == 82800, 'negative seconds normalized'
+assert timedelta(days=0.5) == timedelta(hours=12), 'fractional days'
+assert timedelta(seconds=1.5).microseconds == 500000, 'fractional seconds'
+assert timedelta(microseconds=0.5) == timedelta(0), 'half a microsecond rounds to even'
+assert timedelta(microseconds=1.5) == timedelta(microseconds=2), 'one and a half microseconds rounds to even'
+assert timedelta(days=1, seconds=-1) == timedelta(seconds=86399), 'mixed signs'
+
+# === timedelta repr and str ===
+assert repr(timedelta()) == 'datetime.timedelta(0)', 'repr zero'
+assert repr(timedelta(days=1, seconds=2, microseconds=3)) == (
+    'datetime.timedelta(days=1, seconds=2, microseconds=3)'
+), 'repr all fields'
+assert repr(timedelta(hours=-1)) == 'datetime.timedelta(days=-1, seconds=82800)', 'repr negative'
+assert str(timedelta()) == '0:00:00', 'str zero'
+assert str(timedelta(hours=1, minutes=2, seconds=3)) == '1:02:03', 'str time'
+assert str(timedelta(days=2, microseconds=5)) == '2 days, 0:00:00.000005', 'str days'
+assert str(timedelta(days=1)) == '1 day, 0:00:00', 'str one day'
+assert str(timedelta(seconds=-1)) == '-1 day, 23:59:59', 'str negative'
+
+# === timedelta arithmetic ===
+assert timedelta(hours=1) + timedelta(minutes=30) == timedelta(minutes=90), 'add'
+assert timedelta(hours=1) - timedelta(minutes=90) == timedelta(minutes=-30), 'sub'
+assert -timedelta(hours=1) == timedelta(hours=-1), 'neg'
+assert +timedelta(hours=1) == timedelta(hours=1), 'pos'
+assert abs(timedelta(hours=-1)) == timedelta(hours=1), 'abs'
+assert timedelta(hours=1) * 3 == timedelta(hours=3), 'mul int'
+assert 3 * timedelta(hours=1) == timedelta(hours=3), 'rmul int'
+assert timedelta(hours=1) * 1.5 == timedelta(minutes=90), 'mul float'
+assert timedelta(hours=3) / 2 == timedelta(minutes=90), 'div int'
+assert timedelta(hours=3) / 1.5 == timedelta(hours=2), 'div float'
+assert timedelta(hours=3) / timedelta(hours=2) == 1.5, 'div timedelta'
+assert timedelta(hours=3) // timedelta(hours=2) == 1, 'floordiv timedelta'
+assert timedelta(hours=3) // 2 == timedelta(minutes=90), 'floordiv int'
+assert timedelta(hours=-3) // timedelta(hours=2) == -2, 'floordiv rounds down'
+assert timedelta(hours=3) % timedelta(hours=2) == timedelta(hours=1), 'mod'
+assert timedelta(hours=-3) % timedelta(hours=2) == timedelta(hours=1), 'mod sign follows divisor'
+assert timedelta(days=1, microseconds=1).total_seconds() == 86400.000001, 'total_seconds'
+assert timedelta(seconds=1) // timedelta(microseconds=1) == 1000000, 'floordiv to microseconds'
+assert timedelta.max // timedelta.resolution == 86399999999999999999, 'floordiv result beyond 64 bits'
+
+# === timedelta comparison and truthiness ===
+assert timedelta(hours=1) < timedelta(hours=2), 'lt'
+assert timedelta(days=-1) < timedelta(0), 'negative lt zero'
+assert timedelta(hours=24) == timedelta(days=1), 'eq normalized'
+assert timedelta(hours=1) != 3600, 'ne int'
+assert not timedelta(0), 'zero is falsy'
+assert timedelta(microseconds=1), 'nonzero is truthy'
+assert hash(timedelta(hours=24)) == hash(timedelta(days=1)), 'hash'
+assert sorted([timedelta(3), timedelta(1), timedelta(2)]) == [timedelta(1), timedelta(2), timedelta(3)], 'sorted'
+assert max(timedelta(3), timedelta(5)) == timedelta(5), 'max'
+
+# === timedelta limits ===
+assert timedelta.min == timedelta(days=-999999999), 'timedelta min'
+assert timedelta.max == timedelta(days=999999999, seconds=86399, microseconds=999999), 'timedelta max'
+assert timedelta.resolution == timedelta(microseconds=1), 'timedelta resolution'
+try:
+    timedelta(days=1000000000)
+    assert False, 'timedelta overflow should raise'
+except OverflowError as e:
+    assert str(e) == 'days=1000000000; must have magnitude <= 999999999', 'timedelta overflow message'
+try:
+    timedelta.max + timedelta(1)
+    assert False, 'timedelta add overflow should raise'
+except OverflowError as e:
+    assert str(e) == 'days=1000000000; must have magnitude <= 999999999', 'timedelta add overflow message'
+try:
+    timedelta(1) / 0
+    assert False, 'timedelta division by zero should raise'
+except ZeroDivisionError as e:
+    assert str(e) == 'integer division or modulo by zero', 'timedelta division by zero message'
+try:
+    timedelta(1) / timedelta(0)
+    assert False, 'timedelta true division by zero timedelta should raise'
+except ZeroDivisionError as e:
+    assert str(e) == 'division by zero', 'timedelta true division by zero timedelta message'
+try:
+    timedelta(1) // timedelta(0)
+    assert False, 'timedelta floordiv by zero should raise'
+except ZeroDivisionError as e:
+    assert str(e) == 'integer division or modulo by zero', 'timedelta floordiv by zero message'
+try:
+    timedelta(1) + 1
+    assert False, 'timedelta + int should raise'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for +: 'datetime.timedelta' and 'int'", 'timedelta + int message'
+
+# === date construction ===
+d = date(2024, 2, 29)
+assert d.year == 2024, 'year'
+assert d.month == 2, 'month'
+assert d.day == 29, 'day'
+assert repr(d) == 'datetime.date(2024, 2, 29)', 'date repr'
+assert str(d) == '2024-02-29', 'date str'
+assert date(year=1, month=1, day=1) == date.min, 'keyword args'
+assert date.max == date(9999, 12, 31), 'date max'
+assert date.resolution == timedelta(days=1), 'date resolution'
+try:
+    date(2023, 2, 29)
+    assert False, 'invalid day should raise'
+except ValueError as e:
+    assert str(e) == 'day is out of range for month', 'invalid day message'
+try:
+    date(2023, 13, 1)
+    assert False, 'invalid month should raise'
+except ValueError as e:
+    assert str(e) == 'month must be in 1..12', 'invalid month message'
+try:
+    date(0, 1, 1)
+    assert False, 'invalid year should raise'
+except ValueError as e:
+    assert str(e) == 'year 0 is out of range', 'invalid year message'
+try:
+    date('2024', 1, 1)
+    assert False, 'str year should raise'
+except TypeError as e:
+    assert str(e) == "'str' object cannot be interpreted as an integer", 'str year message'
+
+# === date methods ===
+assert d.weekday() == 3, 'weekday'
+assert d.isoweekday() == 4, 'isoweekday'
+assert d.toordinal() == 738945, 'toordinal'
+assert date.fromordinal(738945) == d, 'fromordinal'
+assert date.fromordinal(1) == date.min, 'fromordinal 1'
+assert d.replace(day=1) == date(2024, 2, 1), 'replace day'
+assert d.replace(year=2025, day=28) == date(2025, 2, 28), 'replace year'
+assert tuple(d.isocalendar()) == (2024, 9, 4), 'isocalendar'
+assert tuple(date(2021, 1, 3).isocalendar()) == (2020, 53, 7), 'isocalendar previous year'
+assert tuple(date(2024, 12, 30).isocalendar()) == (2025, 1, 1), 'isocalendar next year'
+assert date.fromtimestamp(0) == date(1970, 1, 1), 'fromtimestamp'
+try:
+    d.replace(month=2, day=30)
+    assert False, 'invalid replace should raise'
+except ValueError as e:
+    assert str(e) == 'day is out of range for month', 'invalid replace message'
+
+# === date arithmetic ===
+assert d + timedelta(days=1) == date(2024, 3, 1), 'add day across leap february'
+assert timedelta(days=1) + d == date(2024, 3, 1), 'radd'
+assert d - timedelta(days=60) == date(2023, 12, 31), 'sub days across year'
+assert d + timedelta(hours=23) == d, 'date add ignores time part'
+assert date(2024, 3, 1) - date(2024, 2, 1) == timedelta(days=29), 'date difference'
+assert date(2024, 1, 1) - date(2025, 1, 1) == timedelta(days=-366), 'negative date difference'
+try:
+    date.max + timedelta(days=1)
+    assert False, 'date overflow should raise'
+except OverflowError as e:
+    assert str(e) == 'date value out of range', 'date overflow message'
+
+# === date comparison and hashing ===
+assert date(2024, 1, 1) < date(2024, 1, 2), 'date lt'
+assert date(2024, 1, 1) == date(2024, 1, 1), 'date eq'
+assert date(2024, 1, 1) != date(2024, 1, 2), 'date ne'
+assert sorted([date(2024, 3, 1), date(2023, 1, 1)]) == [date(2023, 1, 1), date(2024, 3, 1)], 'date sort'
+assert {date(2024, 1, 1): 'a'}[date(2024, 1, 1)] == 'a', 'date as dict key'
+assert len({date(2024, 1, 1), date(2024, 1, 1), date(2024, 1, 2)}) == 2, 'date set'
+assert date(2024, 1, 1), 'date is truthy'
+
+# === datetime construction ===
+x = dt(2024, 2, 29, 13, 45, 30, 123456)
+assert (x.year, x.month, x.day) == (2024, 2, 29), 'datetime date fields'
+assert (x.hour, x.minute, x.second, x.microsecond) == (13, 45, 30, 123456), 'datetime time fields'
+assert x.tzinfo is None, 'naive datetime'
+assert repr(x) == 'datetime.datetime(2024, 2, 29, 13, 45, 30, 123456)', 'datetime repr'
+assert repr(dt(2024, 1, 1)) == 'datetime.datetime(2024, 1, 1, 0, 0)', 'datetime repr omits zero seconds'
+assert str(x) == '2024-02-29 13:45:30.123456', 'datetime str'
+assert x.date() == date(2024, 2, 29), 'datetime date()'
+assert isinstance(x, date), 'datetime is a date'
+assert not isinstance(d, dt), 'date is not a datetime'
+assert dt.min == dt(1, 1, 1), 'datetime min'
+assert dt.max == dt(9999, 12, 31, 23, 59, 59, 999999), 'datetime max'
+assert dt.resolution == timedelta(microseconds=1), 'datetime resolution'
+try:
+    dt(2024, 1, 1, 24)
+    assert False, 'invalid hour should raise'
+except ValueError as e:
+    assert str(e) == 'hour must be in 0..23', 'invalid hour message'
+try:
+    dt(2024, 1, 1, tzinfo='UTC')
+    assert False, 'invalid tzinfo should raise'
+except TypeError as e:
+    assert str(e) == "tzinfo argument must be None or of a tzinfo subclass, not type 'str'", 'invalid tzinfo message'
+
+# === datetime methods ===
+assert x.replace(hour=0, microsecond=0) == dt(2024, 2, 29, 0, 45, 30), 'datetime replace'
+assert x.weekday() == 3, 'datetime weekday'
+assert x.toordinal() == 738945, 'datetime toordinal'
+assert dt.fromordinal(738945) == dt(2024, 2, 29), 'datetime fromordinal'
+assert dt(1970, 1, 1, tzinfo=timezone.utc).timestamp() == 0.0, 'aware timestamp'
+assert dt(2024, 1, 1, 0, 0, 0, 500000, tzinfo=UTC).timestamp() == 1704067200.5, 'fractional timestamp'
+assert dt.fromtimestamp(1704067200.5, UTC) == dt(2024, 1, 1, 0, 0, 0, 500000, tzinfo=UTC), 'fromtimestamp aware'
+assert dt.fromtimestamp(86400, tz=UTC) == dt(1970, 1, 2, tzinfo=UTC), 'fromtimestamp tz keyword'
+
+# === datetime arithmetic ===
+assert x + timedelta(hours=11) == dt(2024, 3, 1, 0, 45, 30, 123456), 'add hours across day'
+assert timedelta(microseconds=876544) + x == dt(2024, 2, 29, 13, 45, 31), 'radd microseconds'
+assert x - timedelta(days=365) == dt(2023, 3, 1, 13, 45, 30, 123456), 'sub days'
+assert dt(2024, 1, 2) - dt(2024, 1, 1, 12) == timedelta(hours=12), 'datetime difference'
+assert dt(2024, 1, 1) - dt(2024, 1, 2) == timedelta(days=-1), 'negative datetime difference'
+try:
+    dt.max + timedelta(microseconds=1)
+    assert False, 'datetime overflow should raise'
+except OverflowError as e:
+    assert str(e) == 'date value out of range', 'datetime overflow message'
+try:
+    dt(2024, 1, 1) - date(2024, 1, 1)
+    assert False, 'datetime - date should raise'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for -: 'datetime.datetime' and 'datetime.date'", (
+        'datetime - date message'
+    )
+
+# === datetime comparison ===
+assert dt(2024, 1, 1) < dt(2024, 1, 1, 0, 0, 0, 1), 'datetime lt'
+assert dt(2024, 1, 1) == dt(2024, 1, 1), 'datetime eq'
+assert dt(2024, 1, 1) != date(2024, 1, 1), 'datetime ne date'
+assert hash(dt(2024, 1, 1, 12)) == hash(dt(2024, 1, 1, 12)), 'datetime hash'
+assert sorted([dt(2024, 1, 2), dt(2024, 1, 1)]) == [dt(2024, 1, 1), dt(2024, 1, 2)], 'datetime sort'
+
+# === timezones ===
+tz = timezone(timedelta(hours=5, minutes=30))
+assert repr(timezone.utc) == 'datetime.timezone.utc', 'utc repr'
+assert repr(tz) == 'datetime.timezone(datetime.timedelta(seconds=19800))', 'timezone repr'
+assert repr(timezone(timedelta(hours=-1), 'X')) == (
+    "datetime.timezone(datetime.timedelta(days=-1, seconds=82800), 'X')"
+), 'named timezone repr'
+assert str(tz) == 'UTC+05:30', 'timezone str'
+assert str(timezone.utc) == 'UTC', 'utc str'
+assert tz.tzname(None) == 'UTC+05:30', 'tzname'
+assert timezone(timedelta(hours=-1), 'CET').tzname(None) == 'CET', 'named tzname'
+assert tz.utcoffset(None) == timedelta(hours=5, minutes=30), 'utcoffset'
+assert tz.dst(None) is None, 'dst'
+assert timezone(timedelta(0)) == timezone.utc, 'zero offset equals utc'
+assert timezone(timedelta(0), 'Z') == timezone.utc, 'name ignored for equality'
+assert timezone.min.utcoffset(None) == timedelta(hours=-23, minutes=-59), 'timezone min'
+assert timezone.max.utcoffset(None) == timedelta(hours=23, minutes=59), 'timezone max'
+try:
+    timezone(timedelta(hours=24))
+    assert False, 'offset out of range should raise'
+except ValueError as e:
+    assert str(e) == (
+        'offset must be a timedelta strictly between -timedelta(hours=24) and timedelta(hours=24),'
+        ' not datetime.timedelta(days=1).'
+    ), 'offset out of range message'
+try:
+    timezone(5)
+    assert False, 'int offset should raise'
+except TypeError as e:
+    assert str(e) == 'timezone() argument 1 must be datetime.timedelta, not int', 'int offset message'
+
+# === aware datetimes ===
+a = dt(2024, 6, 1, 12, tzinfo=tz)
+assert repr(a) == (
+    'datetime.datetime(2024, 6, 1, 12, 0, tzinfo=datetime.timezone(datetime.timedelta(seconds=19800)))'
+), 'aware repr'
+assert str(a) == '2024-06-01 12:00:00+05:30', 'aware str'
+assert a.tzinfo == tz, 'tzinfo'
+assert a.utcoffset() == timedelta(hours=5, minutes=30), 'aware utcoffset'
+assert a.tzname() == 'UTC+05:30', 'aware tzname'
+assert a.dst() is None, 'aware dst'
+assert x.utcoffset() is None, 'naive utcoffset'
+assert x.tzname() is None, 'naive tzname'
+assert a.astimezone(UTC) == dt(2024, 6, 1, 6, 30, tzinfo=UTC), 'astimezone'
+assert a.astimezone(UTC).hour == 6, 'astimezone changes fields'
+assert a == dt(2024, 6, 1, 6, 30, tzinfo=UTC), 'aware equality compares instants'
+assert a < dt(2024, 6, 1, 7, tzinfo=UTC), 'aware ordering compares instants'
+assert hash(a) == hash(dt(2024, 6, 1, 6, 30, tzinfo=UTC)), 'aware hash compares instants'
+assert a - dt(2024, 6, 1, tzinfo=UTC) == timedelta(hours=6, minutes=30), 'aware difference'
+assert (a + timedelta(days=1)).tzinfo == tz, 'aware add keeps tzinfo'
+assert a.replace(tzinfo=None) == dt(2024, 6, 1, 12), 'replace tzinfo None makes naive'
+assert a != dt(2024, 6, 1, 12), 'aware and naive are never equal'
+try:
+    a - dt(2024, 6, 1, 12)
+    assert False, 'subtracting naive and aware should raise'
+except TypeError as e:
+    assert str(e) == "can't subtract offset-naive and offset-aware datetimes", 'naive aware subtract message'
+
+# === reading the clock: argument errors ===
+try:
+    dt.now('UTC')
+    assert False, 'str tz should raise'
+except TypeError as e:
+    assert str(e) == "tzinfo argument must be None or of a tzinfo subclass, not type 'str'", 'str tz message'
+try:
+    date.today(1)
+    assert False, 'date.today with an argument should raise'
+except TypeError as e:
+    assert str(e) == 'date.today() takes no arguments (1 given)', 'date.today argument message'
@@ -0,0 +1,132 @@
+from datetime import date, datetime, timedelta, timezone
+
+x = datetime(2024, 3, 5, 7, 8, 9, 123456)
+tz = timezone(timedelta(hours=-4, minutes=-30))
+
+# === isoformat ===
+assert date(2024, 3, 5).isoformat() == '2024-03-05', 'date isoformat'
+assert date(12, 1, 1).isoformat() == '0012-01-01', 'date isoformat pads year'
+assert x.isoformat() == '2024-03-05T07:08:09.123456', 'datetime isoformat'
+assert x.isoformat(' ') == '2024-03-05 07:08:09.123456', 'isoformat sep'
+assert x.isoformat(sep='_') == '2024-03-05_07:08:09.123456', 'isoformat sep keyword'
+assert datetime(2024, 3, 5).isoformat() == '2024-03-05T00:00:00', 'isoformat without microseconds'
+assert x.isoformat(timespec='hours') == '2024-03-05T07', 'timespec hours'
+assert x.isoformat(timespec='minutes') == '2024-03-05T07:08', 'timespec minutes'
+assert x.isoformat(timespec='seconds') == '2024-03-05T07:08:09', 'timespec seconds'
+assert x.isoformat(timespec='milliseconds') == '2024-03-05T07:08:09.123', 'timespec milliseconds'
+assert datetime(2024, 3, 5).isoformat(timespec='microseconds') == '2024-03-05T00:00:00.000000', (
+    'timespec microseconds'
+)
+assert x.replace(tzinfo=tz).isoformat() == '2024-03-05T07:08:09.123456-04:30', 'aware isoformat'
+assert x.replace(tzinfo=timezone.utc).isoformat() == '2024-03-05T07:08:09.123456+00:00', 'utc isoformat'
+try:
+    x.isoformat(timespec='days')
+    assert False, 'invalid timespec should raise'
+except ValueError as e:
+    assert str(e) == 'Unknown timespec value', 'invalid timespec message'
+try:
+    x.isoformat('ab')
+    assert False, 'long sep should raise'
+except TypeError as e:
+    assert str(e) == 'isoformat() argument 1 must be a unicode character, not str', 'long sep message'
+
+# === fromisoformat ===
+assert date.fromisoformat('2024-03-05') == date(2024, 3, 5), 'date fromisoformat'
+assert date.fromisoformat('20240305') == date(2024, 3, 5), 'date fromisoformat basic format'
+assert datetime.fromisoformat('2024-03-05') == datetime(2024, 3, 5), 'datetime fromisoformat date only'
+assert datetime.fromisoformat('2024-03-05T07:08:09.123456') == x, 'datetime fromisoformat'
+assert datetime.fromisoformat('2024-03-05 07:08') == datetime(2024, 3, 5, 7, 8), 'space separator'
+assert datetime.fromisoformat('2024-03-05T07') == datetime(2024, 3, 5, 7), 'hour only'
+assert datetime.fromisoformat('2024-03-05T07:08:09.5') == datetime(2024, 3, 5, 7, 8, 9, 500000), 'short fraction'
+assert datetime.fromisoformat('2024-03-05T07:08:09,123') == datetime(2024, 3, 5, 7, 8, 9, 123000), 'comma fraction'
+assert datetime.fromisoformat('2024-03-05T07:08:09Z') == datetime(2024, 3, 5, 7, 8, 9, tzinfo=timezone.utc), 'Z'
+assert datetime.fromisoformat('2024-03-05T07:08:09-04:30') == datetime(2024, 3, 5, 7, 8, 9, tzinfo=tz), 'offset'
+assert datetime.fromisoformat('2024-03-05T07:08:09+0530').utcoffset() == timedelta(hours=5, minutes=30), (
+    'offset without colon'
+)
+assert datetime.fromisoformat(x.isoformat()) == x, 'isoformat round trip'
+for bad in ['', '2024-3-5', '2024-03-05T', '2024-03-05T12:00:', 'not a date', '2024-03-05T07:08:09+']:
+    try:
+        datetime.fromisoformat(bad)
+        assert False, 'invalid isoformat should raise: ' + bad
+    except ValueError as e:
+        assert str(e) == 'Invalid isoformat string: ' + repr(bad), 'invalid isoformat message: ' + bad
+try:
+    datetime.fromisoformat('2024-03-05T25:00')
+    assert False, 'invalid hour should raise'
+except ValueError as e:
+    assert str(e) == 'hour must be in 0..23', 'invalid hour message'
+try:
+    date.fromisoformat('2024-03-05T07:08')
+    assert False, 'date fromisoformat with a time should raise'
+except ValueError as e:
+    assert str(e) == "Invalid isoformat string: '2024-03-05T07:08'", 'date fromisoformat with a time message'
+try:
+    date.fromisoformat(20240305)
+    assert False, 'int fromisoformat should raise'
+except TypeError as e:
+    assert str(e) == 'fromisoformat: argument must be str', 'int fromisoformat message'
+
+# === strftime ===
+assert x.strftime('%Y-%m-%d %H:%M:%S') == '2024-03-05 07:08:09', 'basic strftime'
+assert x.strftime('%y %j %f') == '24 065 123456', 'year, day of year and microseconds'
+assert x.strftime('%a %A %b %B') == 'Tue Tuesday Mar March', 'names'
+assert x.strftime('%I %p') == '07 AM', '12 hour clock'
+assert x.replace(hour=19).strftime('%I:%M %p') == '07:08 PM', '12 hour clock afternoon'
+assert x.replace(hour=0).strftime('%I %p') == '12 AM', 'midnight'
+assert x.strftime('%w %u') == '2 2', 'weekday numbers'
+assert x.strftime('%U %W %V %G') == '09 10 10 2024', 'week numbers'
+assert x.strftime('%%') == '%', 'percent'
+assert x.strftime('%c') == 'Tue Mar  5 07:08:09 2024', 'locale datetime'
+assert x.strftime('%x %X') == '03/05/24 07:08:09', 'locale date and time'
+assert x.strftime('%D %T %R %F') == '03/05/24 07:08:09 07:08 2024-03-05', 'composite directives'
+assert x.strftime('%z%Z') == '', 'naive timezone directives'
+assert x.replace(tzinfo=tz).strftime('%z %Z') == '-0430 UTC-04:30', 'aware timezone directives'
+assert x.replace(tzinfo=timezone.utc).strftime('%z %Z') == '+0000 UTC', 'utc timezone directives'
+assert date(2024, 3, 5).strftime('%d/%m/%Y %H:%M') == '05/03/2024 00:00', 'date strftime'
+assert x.strftime('day %d of %B') == 'day 05 of March', 'literal text'
+assert x.strftime('') == '', 'empty format'
+
+# === strptime ===
+assert datetime.strptime('2024-03-05 07:08:09', '%Y-%m-%d %H:%M:%S') == datetime(2024, 3, 5, 7, 8, 9), 'strptime'
+assert datetime.strptime('5/3/24', '%d/%m/%y') == datetime(2024, 3, 5), 'short fields'
+assert datetime.strptime('05 March 2024', '%d %B %Y') == datetime(2024, 3, 5), 'month name'
+assert datetime.strptime('tue mar 5 2024', '%a %b %d %Y') == datetime(2024, 3, 5), 'case insensitive names'
+assert datetime.strptime('07:08 PM', '%I:%M %p') == datetime(1900, 1, 1, 19, 8), 'pm'
+assert datetime.strptime('12:00 AM', '%I:%M %p') == datetime(1900, 1, 1, 0, 0), 'midnight'
+assert datetime.strptime('2024 065', '%Y %j') == datetime(2024, 3, 5), 'day of year'
+assert datetime.strptime('09.5', '%S.%f') == datetime(1900, 1, 1, 0, 0, 9, 500000), 'fraction'
+assert datetime.strptime('2024', '%Y') == datetime(2024, 1, 1), 'year only'
+assert datetime.strptime('2024-03-05T07:08:09+0530', '%Y-%m-%dT%H:%M:%S%z').utcoffset() == timedelta(
+    hours=5, minutes=30
+), 'numeric offset'
+assert datetime.strptime('07:08:09 -04:30', '%H:%M:%S %z').tzinfo == tz, 'offset with colon'
+assert datetime.strptime('2024-03-05 UTC', '%Y-%m-%d %Z') == datetime(2024, 3, 5), 'UTC name is accepted'
+assert datetime.strptime('  2024', ' %Y') == datetime(2024, 1, 1), 'whitespace matches runs'
+assert datetime.strptime('100%', '%j%%') == datetime(1900, 4, 10), 'percent literal'
+assert datetime.strptime(x.strftime('%c'), '%c') == x.replace(microsecond=0), 'locale round trip'
+try:
+    datetime.strptime('2024-13-01', '%Y-%m-%d')
+    assert False, 'invalid month should raise'
+except ValueError as e:
+    assert str(e) == "time data '2024-13-01' does not match format '%Y-%m-%d'", 'invalid month message'
+try:
+    datetime.strptime('2024-03-05 extra', '%Y-%m-%d')
+    assert False, 'trailing text should raise'
+except ValueError as e:
+    assert str(e) == 'unconverted data remains:  extra', 'trailing text message'
+try:
+    datetime.strptime('2023-02-29', '%Y-%m-%d')
+    assert False, 'invalid day should raise'
+except ValueError as e:
+    assert str(e) == 'day is out of range for month', 'invalid day message'
+try:
+    datetime.strptime('2024', '%Y %')
+    assert False, 'stray percent should raise'
+except ValueError as e:
+    assert str(e) == "stray % in format '%Y %'", 'stray percent message'
+try:
+    datetime.strptime('2024', '%Q')
+    assert False, 'bad directive should raise'
+except ValueError as e:
+    assert str(e) == "'Q' is a bad directive in format '%Q'", 'bad directive message'
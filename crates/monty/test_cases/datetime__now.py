@@ -0,0 +1,31 @@
+# call-external
+# The test runner's clock is frozen at 2023-11-14 22:13:20.25 UTC
+import time
+from datetime import UTC, date, datetime, timedelta, timezone
+
+# === datetime.now ===
+now = datetime.now()
+assert now == datetime(2023, 11, 14, 22, 13, 20, 250000), f'now is the UTC wall clock {now!r}'
+assert now.tzinfo is None, 'now is naive'
+assert isinstance(now, datetime), 'now returns a datetime'
+assert now.timestamp() == time.time(), 'naive now is UTC'
+
+aware = datetime.now(UTC)
+assert aware == datetime(2023, 11, 14, 22, 13, 20, 250000, tzinfo=UTC), f'now with UTC {aware!r}'
+assert aware.tzinfo == timezone.utc, 'now with a timezone is aware'
+assert aware.timestamp() == 1700000000.25, 'now matches time.time()'
+assert datetime.now(tz=UTC) == aware, 'tz keyword'
+
+tz = timezone(timedelta(hours=-5))
+shifted = datetime.now(tz)
+assert shifted.utcoffset() == timedelta(hours=-5), 'now in a fixed offset timezone'
+assert (shifted.hour, shifted.minute) == (17, 13), 'now in a fixed offset timezone shifts the fields'
+assert shifted == aware, 'aware datetimes compare by instant'
+
+# === datetime.today and date.today ===
+today = datetime.today()
+assert today == now, 'today is the same as now'
+assert today.tzinfo is None, 'today is naive'
+assert date.today() == date(2023, 11, 14), 'date.today is the UTC date'
+assert isinstance(date.today(), date), 'date.today returns a date'
+assert not isinstance(date.today(), datetime), 'date.today is not a datetime'
@@ -0,0 +1,146 @@
+# === Simple decorator ===
+def shout(func):
+    def wrapper(*args, **kwargs):
+        return func(*args, **kwargs).upper()
+
+    return wrapper
+
+
+@shout
+def greet(name):
+    return 'hello ' + name
+
+
+assert greet('monty') == 'HELLO MONTY', 'decorator wraps the function'
+
+
+# === Stacked decorators apply bottom-up ===
+def add_suffix(suffix):
+    def decorator(func):
+        def wrapper():
+            return func() + suffix
+
+        return wrapper
+
+    return decorator
+
+
+@add_suffix('!')
+@add_suffix('?')
+def word():
+    return 'what'
+
+
+assert word() == 'what?!', 'innermost decorator is applied first'
+
+# === Decorators are evaluated top-down, before the function is created ===
+order = []
+
+
+def tracked(label):
+    order.append('eval ' + label)
+
+    def decorator(func):
+        order.append('apply ' + label)
+        return func
+
+    return decorator
+
+
+@tracked('outer')
+@tracked('inner')
+def noop():
+    pass
+
+
+assert order == ['eval outer', 'eval inner', 'apply inner', 'apply outer'], 'evaluation order'
+
+# === Decorator can return any value ===
+def to_name(func):
+    return func.__name__
+
+
+@to_name
+def some_function():
+    pass
+
+
+assert some_function == 'some_function', 'name is bound to decorator result'
+
+# === Registration pattern ===
+registry = {}
+
+
+def register(func):
+    registry[func.__name__] = func
+    return func
+
+
+@register
+def handler_a():
+    return 'a'
+
+
+@register
+def handler_b():
+    return 'b'
+
+
+assert sorted(registry) == ['handler_a', 'handler_b'], 'functions registered'
+assert registry['handler_b']() == 'b', 'registered function is callable'
+
+
+# === Decorators on nested functions capture enclosing variables ===
+def make_counter():
+    calls = []
+
+    def count(func):
+        def wrapper(x):
+            calls.append(x)
+            return func(x)
+
+        return wrapper
+
+    @count
+    def double(x):
+        return x * 2
+
+    return double(1) + double(2), calls
+
+
+assert make_counter() == (6, [1, 2]), 'decorator defined in enclosing function'
+
+
+# === Decorators on methods ===
+def twice(method):
+    def wrapper(self, x):
+        return method(self, method(self, x))
+
+    return wrapper
+
+
+class Adder:
+    def __init__(self, step):
+        self.step = step
+
+    @twice
+    def add(self, x):
+        return x + self.step
+
+
+assert Adder(3).add(1) == 7, 'decorated method is bound to the instance'
+
+
+# === Async functions can be decorated ===
+def mark(func):
+    func_name = func.__name__
+    registry[func_name] = 'async'
+    return func
+
+
+@mark
+async def fetch():
+    return 1
+
+
+assert registry['fetch'] == 'async', 'async function decorated'
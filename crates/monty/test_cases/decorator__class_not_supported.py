@@ -0,0 +1,9 @@
+# xfail=cpython
+def identity(cls):
+    return cls
+
+
+@identity
+class Thing:
+    pass
+# Raise=NotImplementedError('The monty syntax parser does not yet support class decorators')
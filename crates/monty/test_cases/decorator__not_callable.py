@@ -0,0 +1,4 @@
+@42
+def f():
+    pass
+# Raise=TypeError("'int' object is not callable")
@@ -0,0 +1,141 @@
+# === Deleting names ===
+x = 1
+del x
+try:
+    x
+    assert False, 'x should be deleted'
+except NameError:
+    pass
+
+a = 1
+b = 2
+c = 3
+del a, (b, c)
+try:
+    c
+    assert False, 'c should be deleted'
+except NameError:
+    pass
+x = 10
+assert x == 10, 'name can be rebound after del'
+
+
+def local_del():
+    y = 'local'
+    del y
+    try:
+        return y
+    except UnboundLocalError:
+        return 'unbound'
+
+
+assert local_del() == 'unbound', 'del unbinds a local'
+
+
+def outer():
+    value = 1
+
+    def inner():
+        nonlocal value
+        del value
+
+    inner()
+    try:
+        return value
+    except NameError:
+        return 'deleted'
+
+
+assert outer() == 'deleted', 'del clears a nonlocal cell'
+
+counter = 5
+
+
+def del_global():
+    global counter
+    del counter
+
+
+del_global()
+try:
+    counter
+    assert False, 'counter should be deleted'
+except NameError:
+    pass
+
+# === Deleting dict items ===
+d = {'a': 1, 'b': 2, 'c': 3}
+del d['b']
+assert d == {'a': 1, 'c': 3}, 'del dict item'
+assert list(d) == ['a', 'c'], 'order kept after del'
+d['b'] = 4
+assert list(d.items()) == [('a', 1), ('c', 3), ('b', 4)], 'reinserted key goes last'
+del d['a'], d['c']
+assert d == {'b': 4}, 'del several dict items'
+
+# === Deleting list items ===
+lst = [0, 1, 2, 3, 4, 5]
+del lst[0]
+assert lst == [1, 2, 3, 4, 5], 'del first item'
+del lst[-1]
+assert lst == [1, 2, 3, 4], 'del negative index'
+
+lst = list(range(10))
+del lst[2:5]
+assert lst == [0, 1, 5, 6, 7, 8, 9], 'del slice'
+del lst[::2]
+assert lst == [1, 6, 8], 'del extended slice'
+del lst[::-1]
+assert lst == [], 'del reversed slice'
+
+lst = list(range(6))
+del lst[10:]
+assert lst == [0, 1, 2, 3, 4, 5], 'del out of range slice'
+del lst[:]
+assert lst == [], 'del full slice'
+
+nested = [[1, 2], [3, 4]]
+del nested[0][1]
+assert nested == [[1], [3, 4]], 'del nested item'
+
+# === Deleting attributes ===
+class Box:
+    kind = 'box'
+
+    def __init__(self):
+        self.content = 'stuff'
+
+
+box = Box()
+del box.content
+try:
+    box.content
+    assert False, 'instance attribute should be deleted'
+except AttributeError:
+    pass
+box.content = 'new'
+assert box.content == 'new', 'attribute can be set again'
+del Box.kind
+try:
+    box.kind
+    assert False, 'class attribute should be deleted'
+except AttributeError:
+    pass
+
+# === Deleted values are released ===
+items = [[1], [2]]
+first = items[0]
+del items[0]
+assert first == [1], 'other references survive del'
+assert items == [[2]], 'item removed'
+
+# === Exception variable is unbound after handler ===
+try:
+    raise ValueError('boom')
+except ValueError as err:
+    del err
+try:
+    err
+    assert False, 'err should be unbound'
+except NameError:
+    pass
@@ -0,0 +1,11 @@
+# call-external
+point = make_mutable_point()
+del point.x
+try:
+    point.x
+    assert False, 'x should be deleted'
+except AttributeError:
+    pass
+assert point.y == 2, 'other fields are kept'
+point.x = 5
+assert point.x == 5, 'field can be set again'
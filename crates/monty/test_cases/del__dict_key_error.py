@@ -0,0 +1,4 @@
+d = {'a': 1}
+del d['a']
+del d['a']
+# Raise=KeyError('a')
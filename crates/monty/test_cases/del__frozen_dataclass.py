@@ -0,0 +1,4 @@
+# call-external
+point = make_point()
+del point.x
+# Raise=FrozenInstanceError("cannot delete field 'x'")
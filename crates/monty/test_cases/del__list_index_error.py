@@ -0,0 +1,3 @@
+lst = [1, 2, 3]
+del lst[3]
+# Raise=IndexError('list assignment index out of range')
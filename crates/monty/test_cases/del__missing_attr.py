@@ -0,0 +1,7 @@
+class Empty:
+    pass
+
+
+e = Empty()
+del e.missing
+# Raise=AttributeError("'Empty' object has no attribute 'missing'")
@@ -0,0 +1,4 @@
+x = 1
+del x
+del x
+# Raise=NameError("name 'x' is not defined")
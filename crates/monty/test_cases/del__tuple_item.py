@@ -0,0 +1,3 @@
+t = (1, 2)
+del t[0]
+# Raise=TypeError("'tuple' object doesn't support item deletion")
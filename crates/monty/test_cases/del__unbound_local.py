@@ -0,0 +1,7 @@
+def f():
+    del y
+    y = 1
+
+
+f()
+# Raise=UnboundLocalError("cannot access local variable 'y' where it is not associated with a value")
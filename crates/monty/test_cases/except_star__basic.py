@@ -0,0 +1,181 @@
+# === A naked exception is wrapped in a group ===
+try:
+    raise ValueError('naked')
+except* ValueError as eg:
+    assert isinstance(eg, ExceptionGroup), 'match is a group'
+    assert eg.message == '', 'wrapping group has no message'
+    assert repr(eg.exceptions) == "(ValueError('naked'),)", f'wrapped: {eg.exceptions!r}'
+
+# === Each clause gets the matching part of the group ===
+seen = []
+try:
+    raise ExceptionGroup('errors', [ValueError('a'), TypeError('b'), ValueError('c')])
+except* ValueError as eg:
+    seen.append(repr(eg))
+except* TypeError as eg:
+    seen.append(repr(eg))
+assert seen == [
+    "ExceptionGroup('errors', [ValueError('a'), ValueError('c')])",
+    "ExceptionGroup('errors', [TypeError('b')])",
+], f'split between clauses: {seen}'
+
+# === A clause runs at most once, and only if something matches ===
+runs = []
+try:
+    raise ExceptionGroup('errors', [ValueError('a'), ValueError('b')])
+except* TypeError:
+    runs.append('type')
+except* ValueError:
+    runs.append('value')
+except* Exception:
+    runs.append('exception')
+assert runs == ['value'], f'clauses run: {runs}'
+
+# === Tuple of types ===
+try:
+    raise ExceptionGroup('errors', [KeyError('k'), IndexError('i')])
+except* (KeyError, IndexError) as eg:
+    assert len(eg.exceptions) == 2, 'tuple matches both'
+
+# === Unhandled exceptions are re-raised ===
+try:
+    try:
+        raise ExceptionGroup('errors', [ValueError('a'), TypeError('b')])
+    except* ValueError:
+        pass
+except ExceptionGroup as eg:
+    assert repr(eg) == "ExceptionGroup('errors', [TypeError('b')])", f'rest re-raised: {eg!r}'
+
+# === Nested structure of the rest is kept ===
+try:
+    try:
+        raise ExceptionGroup('outer', [ValueError('a'), ExceptionGroup('inner', [TypeError('b'), ValueError('c')])])
+    except* ValueError:
+        pass
+except ExceptionGroup as eg:
+    assert repr(eg) == "ExceptionGroup('outer', [ExceptionGroup('inner', [TypeError('b')])])", f'nested rest: {eg!r}'
+
+# === An unmatched naked exception propagates unwrapped ===
+try:
+    try:
+        raise KeyError('k')
+    except* ValueError:
+        assert False, 'should not match'
+except KeyError as e:
+    assert repr(e) == "KeyError('k')", f'naked rest: {e!r}'
+
+# === Bare raise re-raises the match as part of the original group ===
+try:
+    try:
+        raise ExceptionGroup('errors', [ValueError('a'), TypeError('b')])
+    except* ValueError:
+        raise
+except ExceptionGroup as eg:
+    assert repr(eg) == "ExceptionGroup('errors', [ValueError('a'), TypeError('b')])", f'bare raise: {eg!r}'
+
+# === New exceptions are raised together with the rest ===
+try:
+    try:
+        raise ExceptionGroup('errors', [ValueError('a'), TypeError('b')])
+    except* ValueError:
+        raise KeyError('new')
+except ExceptionGroup as eg:
+    assert repr(eg) == "ExceptionGroup('', [KeyError('new'), ExceptionGroup('errors', [TypeError('b')])])", (
+        f'new and rest: {eg!r}'
+    )
+
+# === A single new exception is raised on its own ===
+try:
+    try:
+        raise ExceptionGroup('errors', [ValueError('a')])
+    except* ValueError:
+        raise KeyError('only')
+except KeyError as e:
+    assert repr(e) == "KeyError('only')", f'only new: {e!r}'
+    assert repr(e.__context__) == "ExceptionGroup('errors', [ValueError('a')])", 'context is the match'
+
+# === Catching with except* in a handler ===
+try:
+    try:
+        raise ExceptionGroup('errors', [ValueError('a'), TypeError('b')])
+    except* ValueError:
+        raise KeyError('x')
+    except* TypeError:
+        raise IndexError('y')
+except ExceptionGroup as eg:
+    assert repr(eg) == "ExceptionGroup('', [KeyError('x'), IndexError('y')])", f'two new: {eg!r}'
+
+# === else and finally ===
+log = []
+try:
+    log.append('body')
+except* ValueError:
+    log.append('handler')
+else:
+    log.append('else')
+finally:
+    log.append('finally')
+assert log == ['body', 'else', 'finally'], f'no exception: {log}'
+
+log = []
+try:
+    raise ValueError('v')
+except* ValueError:
+    log.append('handler')
+else:
+    log.append('else')
+finally:
+    log.append('finally')
+assert log == ['handler', 'finally'], f'handled exception: {log}'
+
+log = []
+try:
+    try:
+        raise TypeError('t')
+    except* ValueError:
+        log.append('handler')
+    finally:
+        log.append('finally')
+except TypeError:
+    log.append('outer')
+assert log == ['finally', 'outer'], f'unhandled exception: {log}'
+
+
+# === In a function ===
+def handle():
+    try:
+        raise ExceptionGroup('errors', [ValueError('a')])
+    except* ValueError as eg:
+        result = len(eg.exceptions)
+    return result
+
+
+assert handle() == 1, 'except* in a function'
+
+# === Loops inside except* bodies ===
+total = 0
+try:
+    raise ExceptionGroup('errors', [ValueError('a'), ValueError('b')])
+except* ValueError as eg:
+    for exc in eg.exceptions:
+        if str(exc) == 'b':
+            break
+        total += 1
+assert total == 1, 'break inside a loop in except*'
+
+# === Catching an ExceptionGroup type is not allowed ===
+try:
+    try:
+        raise ValueError('v')
+    except* ExceptionGroup:
+        pass
+except TypeError as e:
+    assert str(e) == 'catching ExceptionGroup with except* is not allowed. Use except instead.', f'group type: {e}'
+
+try:
+    try:
+        raise ValueError('v')
+    except* (ValueError, BaseExceptionGroup):
+        pass
+except TypeError as e:
+    assert str(e) == 'catching ExceptionGroup with except* is not allowed. Use except instead.', f'group in tuple: {e}'
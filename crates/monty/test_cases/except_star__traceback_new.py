@@ -0,0 +1,29 @@
+def check(value):
+    if value < 0:
+        raise ExceptionGroup('invalid', [ValueError('negative'), TypeError('not a float')])
+
+
+try:
+    check(-1)
+except* ValueError:
+    raise RuntimeError('replaced') from None
+"""
+TRACEBACK:
+  | ExceptionGroup:  (2 sub-exceptions)
+  +-+---------------- 1 ----------------
+    | Traceback (most recent call last):
+    |   File "except_star__traceback_new.py", line 9, in <module>
+    |     raise RuntimeError('replaced') from None
+    | RuntimeError: replaced
+    +---------------- 2 ----------------
+    | Exception Group Traceback (most recent call last):
+    |   File "except_star__traceback_new.py", line 7, in <module>
+    |     check(-1)
+    |     ~~~~~~~~~
+    |   File "except_star__traceback_new.py", line 3, in check
+    |     raise ExceptionGroup('invalid', [ValueError('negative'), TypeError('not a float')])
+    | ExceptionGroup: invalid (1 sub-exception)
+    +-+---------------- 1 ----------------
+      | TypeError: not a float
+      +------------------------------------
+"""
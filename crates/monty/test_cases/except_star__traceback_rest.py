@@ -0,0 +1,21 @@
+def fail():
+    raise ExceptionGroup('errors', [ValueError('a'), TypeError('b')])
+
+
+try:
+    fail()
+except* ValueError:
+    pass
+"""
+TRACEBACK:
+  + Exception Group Traceback (most recent call last):
+  |   File "except_star__traceback_rest.py", line 6, in <module>
+  |     fail()
+  |     ~~~~~~
+  |   File "except_star__traceback_rest.py", line 2, in fail
+  |     raise ExceptionGroup('errors', [ValueError('a'), TypeError('b')])
+  | ExceptionGroup: errors (1 sub-exception)
+  +-+---------------- 1 ----------------
+    | TypeError: b
+    +------------------------------------
+"""
@@ -0,0 +1,148 @@
+# === raise from an exception instance ===
+try:
+    try:
+        raise ValueError('inner')
+    except ValueError as e:
+        inner = e
+        raise TypeError('outer') from e
+except TypeError as e:
+    assert e.__cause__ is inner, 'cause is the inner exception'
+    assert e.__context__ is inner, 'context is set alongside cause'
+    assert e.__cause__ is e.__cause__, 'cause is the same object each time'
+    assert e.__suppress_context__ is True, 'raise from sets __suppress_context__'
+
+# === raise from an exception class ===
+try:
+    raise RuntimeError('x') from KeyError
+except RuntimeError as e:
+    assert type(e.__cause__) is KeyError, 'cause class is instantiated'
+    assert e.__cause__ is e.__cause__, 'instantiated cause is the same object each time'
+    assert e.__context__ is None, 'no context outside a handler'
+    assert e.__suppress_context__ is True, 'raise from class sets __suppress_context__'
+
+# === raise from None ===
+try:
+    try:
+        raise ValueError('inner')
+    except ValueError as e:
+        inner = e
+        raise TypeError('outer') from None
+except TypeError as e:
+    assert e.__cause__ is None, 'from None has no cause'
+    assert e.__context__ is inner, 'from None keeps context'
+    assert e.__suppress_context__ is True, 'from None suppresses context'
+
+# === implicit context ===
+try:
+    try:
+        raise ValueError('inner')
+    except ValueError as e:
+        inner = e
+        raise TypeError('outer')
+except TypeError as e:
+    assert e.__cause__ is None, 'implicit chaining has no cause'
+    assert e.__context__ is inner, 'implicit context'
+    assert e.__context__ is e.__context__, 'context is the same object each time'
+    assert e.__suppress_context__ is False, 'implicit chaining does not suppress context'
+
+# === no chaining ===
+try:
+    raise ValueError('alone')
+except ValueError as e:
+    assert e.__cause__ is None, 'no cause'
+    assert e.__context__ is None, 'no context'
+    assert e.__suppress_context__ is False, 'not suppressed'
+
+# === context from an operation error ===
+try:
+    try:
+        1 / 0
+    except ZeroDivisionError:
+        [][0]
+except IndexError as e:
+    assert type(e.__context__) is ZeroDivisionError, 'context from a runtime error'
+
+
+# === context from a function called in a handler ===
+def fail():
+    raise KeyError('k')
+
+
+try:
+    try:
+        raise ValueError('first')
+    except ValueError as e:
+        first = e
+        fail()
+except KeyError as e:
+    assert e.__context__ is first, 'context crosses function calls'
+
+
+# === handled exceptions do not leak into later contexts ===
+def swallow():
+    try:
+        raise ValueError('swallowed')
+    except ValueError:
+        return 1
+
+
+assert swallow() == 1, 'handler returned'
+try:
+    raise TypeError('later')
+except TypeError as e:
+    assert e.__context__ is None, 'no context after return from handler'
+
+try:
+    try:
+        raise ValueError('a')
+    except ValueError:
+        raise TypeError('b')
+except TypeError:
+    pass
+try:
+    raise KeyError('c')
+except KeyError as e:
+    assert e.__context__ is None, 'no context after handler exited by raise'
+
+# === re-raising the handled exception does not chain it to itself ===
+try:
+    try:
+        raise ValueError('same')
+    except ValueError as e:
+        raise e
+except ValueError as e:
+    assert e.__context__ is None, 'no self context'
+
+# === bare raise keeps the cause ===
+cause = ValueError('inner')
+try:
+    try:
+        raise TypeError('outer') from cause
+    except TypeError:
+        raise
+except TypeError as e:
+    assert e.__cause__ is cause, 'bare raise keeps cause'
+
+# === chained exceptions outlive the handler that raised them ===
+try:
+    try:
+        raise ValueError('inner')
+    except ValueError:
+        raise TypeError('outer')
+except TypeError as e:
+    outer = e
+assert repr(outer.__context__) == "ValueError('inner')", 'context kept after handlers exit'
+assert outer.__context__ is outer.__context__, 'context still the same object'
+
+
+# === StopIteration in a generator ===
+def gen():
+    raise StopIteration('done')
+    yield 1
+
+
+try:
+    list(gen())
+except RuntimeError as e:
+    assert str(e) == 'generator raised StopIteration', 'converted to RuntimeError'
+    assert repr(e.__cause__) == "StopIteration('done')", 'StopIteration becomes the cause'
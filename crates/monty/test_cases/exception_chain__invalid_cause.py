@@ -0,0 +1,2 @@
+raise ValueError('x') from 1
+# Raise=TypeError('exception causes must derive from BaseException')
@@ -0,0 +1,33 @@
+def parse(s):
+    return int(s)
+
+
+def load(s):
+    try:
+        return parse(s)
+    except ValueError as e:
+        raise RuntimeError('bad config') from e
+
+
+load('x')
+"""
+TRACEBACK:
+Traceback (most recent call last):
+  File "exception_chain__traceback_cause.py", line 7, in load
+    return parse(s)
+           ~~~~~~~~
+  File "exception_chain__traceback_cause.py", line 2, in parse
+    return int(s)
+           ~~~~~~
+ValueError: invalid literal for int() with base 10: 'x'
+
+The above exception was the direct cause of the following exception:
+
+Traceback (most recent call last):
+  File "exception_chain__traceback_cause.py", line 12, in <module>
+    load('x')
+    ~~~~~~~~~
+  File "exception_chain__traceback_cause.py", line 9, in load
+    raise RuntimeError('bad config') from e
+RuntimeError: bad config
+"""
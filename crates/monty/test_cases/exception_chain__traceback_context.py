@@ -0,0 +1,21 @@
+items = []
+try:
+    1 / 0
+except ZeroDivisionError:
+    items[1]
+"""
+TRACEBACK:
+Traceback (most recent call last):
+  File "exception_chain__traceback_context.py", line 3, in <module>
+    1 / 0
+    ~~~~~
+ZeroDivisionError: division by zero
+
+During handling of the above exception, another exception occurred:
+
+Traceback (most recent call last):
+  File "exception_chain__traceback_context.py", line 5, in <module>
+    items[1]
+    ~~~~~~~~
+IndexError: list index out of range
+"""
@@ -0,0 +1,11 @@
+try:
+    {}['k']
+except KeyError:
+    raise ValueError('missing key') from None
+"""
+TRACEBACK:
+Traceback (most recent call last):
+  File "exception_chain__traceback_from_none.py", line 4, in <module>
+    raise ValueError('missing key') from None
+ValueError: missing key
+"""
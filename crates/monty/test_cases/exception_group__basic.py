@@ -0,0 +1,107 @@
+# === Construction and attributes ===
+eg = ExceptionGroup('errors', [ValueError('a'), TypeError('b')])
+assert eg.message == 'errors', 'message attribute'
+assert len(eg.exceptions) == 2, 'exceptions attribute'
+assert repr(eg.exceptions[0]) == "ValueError('a')", 'first sub-exception'
+assert repr(eg.exceptions[1]) == "TypeError('b')", 'second sub-exception'
+assert isinstance(eg.exceptions, tuple), 'exceptions is a tuple'
+assert len(eg.args) == 2, 'args has the message and list'
+assert isinstance(eg.args[1], list), 'args[1] is the list of exceptions'
+assert eg.args[0] == 'errors', 'args[0] is the message'
+assert isinstance(eg, ExceptionGroup), 'is an ExceptionGroup'
+assert isinstance(eg, BaseExceptionGroup), 'ExceptionGroup is a BaseExceptionGroup'
+assert isinstance(eg, Exception), 'ExceptionGroup is an Exception'
+
+# === str and repr ===
+assert str(eg) == 'errors (2 sub-exceptions)', f'str of group: {eg}'
+assert repr(eg) == "ExceptionGroup('errors', [ValueError('a'), TypeError('b')])", f'repr of group: {eg!r}'
+single = ExceptionGroup('one', [ValueError('x')])
+assert str(single) == 'one (1 sub-exception)', 'singular sub-exception'
+
+# === BaseExceptionGroup picks the most specific type ===
+beg = BaseExceptionGroup('base', [ValueError('a')])
+assert isinstance(beg, ExceptionGroup), 'BaseExceptionGroup of Exceptions is an ExceptionGroup'
+assert repr(beg) == "ExceptionGroup('base', [ValueError('a')])", f'repr: {beg!r}'
+beg = BaseExceptionGroup('base', [KeyboardInterrupt()])
+assert not isinstance(beg, Exception), 'group with a BaseException is not an Exception'
+assert repr(beg) == "BaseExceptionGroup('base', [KeyboardInterrupt()])", f'repr: {beg!r}'
+
+# === Nested groups ===
+nested = ExceptionGroup('outer', [ValueError('a'), ExceptionGroup('inner', [TypeError('b'), ValueError('c')])])
+assert str(nested) == 'outer (2 sub-exceptions)', 'nested str counts direct children'
+assert repr(nested.exceptions[1]) == "ExceptionGroup('inner', [TypeError('b'), ValueError('c')])", 'nested group'
+
+# === split ===
+match, rest = nested.split(ValueError)
+assert repr(match) == "ExceptionGroup('outer', [ValueError('a'), ExceptionGroup('inner', [ValueError('c')])])", (
+    f'split match: {match!r}'
+)
+assert repr(rest) == "ExceptionGroup('outer', [ExceptionGroup('inner', [TypeError('b')])])", f'split rest: {rest!r}'
+
+match, rest = nested.split((TypeError, ValueError))
+assert match is not None and rest is None, 'split with a tuple matching everything'
+assert repr(match) == repr(nested), 'full match keeps the structure'
+
+match, rest = nested.split(KeyError)
+assert match is None, 'split with no match'
+assert repr(rest) == repr(nested), 'rest is everything'
+
+match, rest = nested.split(Exception)
+assert rest is None, 'split on a base class matches the whole group'
+
+# === subgroup ===
+sub = nested.subgroup(TypeError)
+assert repr(sub) == "ExceptionGroup('outer', [ExceptionGroup('inner', [TypeError('b')])])", f'subgroup: {sub!r}'
+assert nested.subgroup(KeyError) is None, 'subgroup with no match'
+
+# === Raising and catching with except ===
+try:
+    raise ExceptionGroup('raised', [ValueError('v')])
+except ExceptionGroup as e:
+    assert e.message == 'raised', 'caught the group'
+    assert repr(e.exceptions[0]) == "ValueError('v')", 'sub-exception kept'
+
+# === Invalid construction ===
+try:
+    ExceptionGroup('empty', [])
+    assert False, 'should have raised ValueError'
+except ValueError as e:
+    assert str(e) == 'second argument (exceptions) must be a non-empty sequence', f'empty: {e}'
+
+try:
+    ExceptionGroup('bad', [ValueError('a'), 1])
+    assert False, 'should have raised ValueError'
+except ValueError as e:
+    assert str(e) == 'Item 1 of second argument (exceptions) is not an exception', f'not an exception: {e}'
+
+try:
+    ExceptionGroup('bad', 1)
+    assert False, 'should have raised TypeError'
+except TypeError as e:
+    assert str(e) == 'second argument (exceptions) must be a sequence', f'not a sequence: {e}'
+
+try:
+    ExceptionGroup(1, [ValueError('a')])
+    assert False, 'should have raised TypeError'
+except TypeError as e:
+    assert str(e) == 'BaseExceptionGroup.__new__() argument 1 must be str, not int', f'bad message: {e}'
+
+try:
+    ExceptionGroup('base', [KeyboardInterrupt()])
+    assert False, 'should have raised TypeError'
+except TypeError as e:
+    assert str(e) == 'Cannot nest BaseExceptions in an ExceptionGroup', f'base exception: {e}'
+
+try:
+    ExceptionGroup('one arg')
+    assert False, 'should have raised TypeError'
+except TypeError as e:
+    assert str(e) == 'BaseExceptionGroup.__new__() takes exactly 2 arguments (1 given)', f'arg count: {e}'
+
+try:
+    eg.split(1)
+    assert False, 'should have raised TypeError'
+except TypeError as e:
+    assert str(e) == 'expected an exception type, a tuple of exception types, or a callable (other than a class)', (
+        f'bad split condition: {e}'
+    )
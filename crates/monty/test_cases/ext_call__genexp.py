@@ -0,0 +1,64 @@
+# call-external
+# === External calls inside generator expressions consumed by builtins ===
+xs = [1, 2, 3]
+
+# === Item-by-item consumers ===
+assert sum(add_ints(x, 1) for x in xs) == 9, 'sum of genexp'
+assert sum((add_ints(x, x) for x in xs), 10) == 22, 'sum with start'
+assert sum(add_ints(x, 0) for x in range(1000)) == 499500, 'sum over several chunks'
+assert any(return_value(x) > 1 for x in xs), 'any'
+assert not all(return_value(x) > 1 for x in xs), 'all'
+assert all(return_value(x) for x in xs), 'all true'
+assert not any(return_value(x) for x in []), 'any of empty genexp'
+
+# any() stops at the first true item
+seen = []
+
+
+def record(x):
+    seen.append(x)
+    return return_value(x)
+
+
+assert any(record(x) == 2 for x in xs), 'any short-circuits'
+assert seen == [1, 2], 'any stopped after the matching item'
+
+# === Collecting consumers ===
+assert list(add_ints(x, 10) for x in xs) == [11, 12, 13], 'list'
+assert tuple(return_value(x) for x in xs) == (1, 2, 3), 'tuple'
+assert set(return_value(x % 2) for x in xs) == {0, 1}, 'set'
+assert sorted((add_ints(x, 0) for x in xs), reverse=True) == [3, 2, 1], 'sorted with kwargs'
+assert max(add_ints(x, 5) for x in xs) == 8, 'max'
+assert dict((concat_strings('k', str(x)), return_value(x)) for x in xs) == {'k1': 1, 'k2': 2, 'k3': 3}, 'dict'
+assert ', '.join(return_value(s) for s in ['a', 'b', 'c']) == 'a, b, c', 'str.join'
+assert list(zip((return_value(x) for x in xs), (add_ints(x, 1) for x in xs))) == [(1, 2), (2, 3), (3, 4)], (
+    'two generator arguments'
+)
+
+items = [0]
+items.extend(add_ints(x, 100) for x in xs)
+assert items == [0, 101, 102, 103], 'list.extend'
+
+
+# === Inside functions ===
+def total(values):
+    return sum(add_ints(v, v) for v in values)
+
+
+assert total(xs) == 12, 'genexp consumed inside a function'
+assert [sum(add_ints(x, y) for y in xs) for x in xs] == [9, 12, 15], 'genexp inside a comprehension'
+
+# === Exceptions ===
+try:
+    sum(raise_error('ValueError', 'bad item') for x in xs)
+    assert False, 'should have raised'
+except ValueError as e:
+    assert str(e) == 'bad item', 'error from external call in genexp'
+
+try:
+    ''.join(raise_error('KeyError', 'missing') if x == 2 else str(x) for x in xs)
+    assert False, 'should have raised'
+except KeyError:
+    pass
+
+assert sum(add_ints(x, 1) for x in xs) == 9, 'works again after an error'
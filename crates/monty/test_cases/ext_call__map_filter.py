@@ -0,0 +1,72 @@
+# call-external
+# === External functions called by map(), filter() and iter(callable, sentinel) ===
+xs = [1, 2, 3]
+
+# === map() ===
+results = []
+for r in map(return_value, xs):
+    results.append(r)
+assert results == [1, 2, 3], 'for loop over map of external function'
+
+assert list(map(add_ints, xs, [10, 20, 30])) == [11, 22, 33], 'list of map with two iterables'
+assert sum(map(return_value, range(300))) == 44850, 'sum of map'
+assert ','.join(map(concat_strings, ['a', 'b'], ['1', '2'])) == 'a1,b2', 'join of map'
+assert [r * 2 for r in map(return_value, xs)] == [2, 4, 6], 'comprehension over map'
+assert list(map(return_value, (add_ints(x, 1) for x in xs))) == [2, 3, 4], 'map over generator'
+
+m = map(return_value, xs)
+assert next(m) == 1, 'next on map'
+assert list(m) == [2, 3], 'map continues where next left off'
+assert next(m, 'done') == 'done', 'next with default on exhausted map'
+
+# === filter() ===
+assert list(filter(return_value, [0, 1, '', 'a'])) == [1, 'a'], 'filter with external function'
+kept = []
+for x in filter(lambda x: add_ints(x, 0) % 2, range(6)):
+    kept.append(x)
+assert kept == [1, 3, 5], 'for loop over filter calling external function'
+assert next(filter(return_value, [0, 0, 7])) == 7, 'next on filter'
+assert list(map(return_value, filter(return_value, [0, 5, 0, 6]))) == [5, 6], 'map of filter'
+
+# === iter(callable, sentinel) ===
+counter = [0]
+
+
+def tick():
+    counter[0] = add_ints(counter[0], 1)
+    return counter[0]
+
+
+assert list(iter(tick, 4)) == [1, 2, 3], 'iter with sentinel calling external function'
+ticks = []
+for t in iter(tick, 7):
+    ticks.append(t)
+assert ticks == [5, 6], 'for loop over iter with sentinel'
+
+
+# === Inside functions ===
+def doubled(values):
+    return [add_ints(r, r) for r in map(return_value, values)]
+
+
+assert doubled(xs) == [2, 4, 6], 'map consumed inside a function'
+
+# === Exceptions ===
+try:
+    for r in map(raise_error, ['ValueError'], ['bad item']):
+        assert False, 'should have raised'
+except ValueError as e:
+    assert str(e) == 'bad item', 'error from external function in map'
+
+try:
+    list(filter(lambda x: raise_error('KeyError', 'missing') if x == 2 else True, xs))
+    assert False, 'should have raised'
+except KeyError:
+    pass
+
+m = map(return_value, xs)
+try:
+    list(map(raise_error, ['TypeError'], ['no']))
+except TypeError:
+    pass
+assert list(m) == [1, 2, 3], 'works again after an error'
@@ -1,4 +1,3 @@
-# xfail=cpython
-# invalid format specifier with trailing characters (detected at parse time)
+# invalid format specifier with trailing characters (detected at runtime)
 f'{1:10xyz}'
-# Raise=SyntaxError("Invalid format specifier '10xyz'")
+# Raise=ValueError("Invalid format specifier '10xyz' for object of type 'int'")
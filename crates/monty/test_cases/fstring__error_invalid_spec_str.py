@@ -1,4 +1,3 @@
-# xfail=cpython
-# invalid format specifier for string (detected at parse time)
+# invalid format specifier for string (detected at runtime)
 f'{"hello":abc}'
-# Raise=SyntaxError("Invalid format specifier 'abc'")
+# Raise=ValueError("Invalid format specifier 'abc' for object of type 'str'")
@@ -0,0 +1,58 @@
+# Tests for calls with multiple `*args` / `**kwargs` unpackings
+
+
+def collect(*args, **kwargs):
+    return args, kwargs
+
+
+a = [1, 2]
+b = (3,)
+
+# === Multiple *args ===
+assert collect(*a, *b) == ((1, 2, 3), {}), 'two *args'
+assert collect(*a, *a, *b) == ((1, 2, 1, 2, 3), {}), 'three *args'
+assert collect(*[], *()) == ((), {}), 'empty *args'
+
+# === Positional arguments after *args ===
+assert collect(*a, 9) == ((1, 2, 9), {}), 'positional after *args'
+assert collect(0, *a, 9, *b, 10) == ((0, 1, 2, 9, 3, 10), {}), 'interleaved positionals'
+assert collect(*range(2), *'xy') == ((0, 1, 'x', 'y'), {}), 'any iterables'
+
+# === Multiple **kwargs ===
+d1 = {'x': 1}
+d2 = {'y': 2}
+assert collect(**d1, **d2) == ((), {'x': 1, 'y': 2}), 'two **kwargs'
+assert collect(a=0, **d1, b=3, **d2, c=4) == ((), {'a': 0, 'x': 1, 'b': 3, 'y': 2, 'c': 4}), 'interleaved kwargs'
+assert list(collect(**d2, **d1)[1]) == ['y', 'x'], 'kwargs keep source order'
+
+# === Keyword arguments before *args ===
+assert collect(*a, k=1, *b) == ((1, 2, 3), {'k': 1}), 'keyword before *args'
+
+# === Everything together ===
+assert collect(0, *a, 5, *b, k=1, **d1, **d2) == ((0, 1, 2, 5, 3), {'k': 1, 'x': 1, 'y': 2}), 'mixed'
+
+
+def add(x, y, z):
+    return x + y + z
+
+
+assert add(*[1], *[2], 3) == 6, 'multiple *args fill parameters'
+assert add(1, **{'y': 2}, **{'z': 3}) == 6, 'multiple **kwargs fill parameters'
+
+# === Builtins and methods ===
+assert max(*a, *b) == 3, 'builtin with multiple *args'
+assert (1, 2, 3, 2).index(*[2], *[2]) == 3, 'method with multiple *args'
+assert dict(**d1, **d2) == {'x': 1, 'y': 2}, 'builtin with multiple **kwargs'
+assert (lambda *args: args)(*a, 0, *b) == (1, 2, 0, 3), 'expression call with unpacking'
+
+# === Evaluation order ===
+calls = []
+
+
+def track(name, value):
+    calls.append(name)
+    return value
+
+
+collect(track('p1', 1), *track('s1', [2]), track('p2', 3), k=track('k', 4), **track('m', {'z': 5}), j=track('j', 6))
+assert calls == ['p1', 's1', 'p2', 'k', 'm', 'j'], f'evaluated in source order: {calls}'
@@ -0,0 +1,114 @@
+def count_up(n):
+    i = 0
+    while i < n:
+        yield i
+        i += 1
+
+
+# === Iteration with for ===
+result = []
+for x in count_up(3):
+    result.append(x)
+assert result == [0, 1, 2], 'for loop over generator'
+
+# === next() ===
+g = count_up(2)
+assert next(g) == 0, 'first next'
+assert next(g) == 1, 'second next'
+assert next(g, 'done') == 'done', 'next with default on exhausted generator'
+assert next(g, None) is None, 'exhausted generator stays exhausted'
+
+# === Generators are their own iterators ===
+g = count_up(3)
+assert iter(g) is g, 'iter returns the generator'
+assert next(g) == 0, 'next after iter'
+assert list(g) == [1, 2], 'list consumes the rest'
+assert list(g) == [], 'exhausted generator yields nothing'
+
+# === Body runs lazily ===
+log = []
+
+
+def logged():
+    log.append('start')
+    yield 1
+    log.append('end')
+
+
+g = logged()
+assert log == [], 'body does not run until first next'
+assert next(g) == 1, 'first value'
+assert log == ['start'], 'runs up to the first yield'
+assert list(g) == [], 'no more values'
+assert log == ['start', 'end'], 'runs to completion'
+
+
+# === Return ends iteration ===
+def early(n):
+    for i in range(10):
+        if i == n:
+            return
+        yield i
+
+
+assert list(early(3)) == [0, 1, 2], 'return stops the generator'
+
+
+# === Closures and arguments ===
+def make_gen(step):
+    def gen(n):
+        for i in range(n):
+            yield i * step
+
+    return gen
+
+
+assert list(make_gen(3)(4)) == [0, 3, 6, 9], 'generator closure'
+
+
+def with_defaults(a, b=10, *args, **kwargs):
+    yield a
+    yield b
+    yield args
+    yield kwargs
+
+
+assert list(with_defaults(1, x=2)) == [1, 10, (), {'x': 2}], 'generator arguments are bound at call time'
+
+
+# === Yield inside try/except ===
+def safe_div(pairs):
+    for a, b in pairs:
+        try:
+            yield a // b
+        except ZeroDivisionError:
+            yield None
+
+
+assert list(safe_div([(4, 2), (1, 0), (9, 3)])) == [2, None, 3], 'yield in try and except blocks'
+
+
+# === Yield inside finally ===
+def with_finally():
+    try:
+        yield 1
+    finally:
+        log.append('finally')
+
+
+log = []
+assert list(with_finally()) == [1], 'value from try block'
+assert log == ['finally'], 'finally runs on completion'
+
+# === Independent generators ===
+a = count_up(3)
+b = count_up(3)
+assert next(a) == 0, 'a first'
+assert next(a) == 1, 'a second'
+assert next(b) == 0, 'generators have independent state'
+assert list(zip(a, b)) == [(2, 1)], 'zip over generators'
+
+# === Generator object ===
+g = count_up(1)
+assert repr(g).startswith('<generator object count_up'), 'generator repr'
+assert g, 'generators are truthy'
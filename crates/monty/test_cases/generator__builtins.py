@@ -0,0 +1,52 @@
+def gen(n):
+    for i in range(n):
+        yield i
+
+
+# === Streaming builtins ===
+assert sum(gen(5)) == 10, 'sum'
+assert sum(gen(5), 10) == 20, 'sum with start'
+assert sum(gen(1000)) == 499500, 'sum over many values'
+assert any(x == 3 for x in gen(10)), 'any true'
+assert not any(x > 10 for x in gen(10)), 'any false'
+assert all(x < 10 for x in gen(10)), 'all true'
+assert not all(x < 3 for x in gen(10)), 'all false'
+
+consumed = []
+
+
+def tracking():
+    for i in range(10):
+        consumed.append(i)
+        yield i
+
+
+assert any(x == 2 for x in tracking()), 'any over tracked generator'
+assert consumed == [0, 1, 2], 'any stops consuming at the first match'
+consumed = []
+assert 1 in tracking(), 'in operator'
+assert consumed == [0, 1], 'in stops consuming at the first match'
+assert 20 not in gen(5), 'not in'
+
+# === Builtins taking iterables ===
+assert min(gen(5)) == 0, 'min'
+assert max(gen(5)) == 4, 'max'
+assert sorted(x % 3 for x in gen(6)) == [0, 0, 1, 1, 2, 2], 'sorted'
+assert list(enumerate(gen(2))) == [(0, 0), (1, 1)], 'enumerate'
+assert tuple(gen(3)) == (0, 1, 2), 'tuple'
+assert set(gen(3)) == {0, 1, 2}, 'set'
+assert dict((x, x * x) for x in gen(3)) == {0: 0, 1: 1, 2: 4}, 'dict'
+assert ', '.join(str(x) for x in gen(3)) == '0, 1, 2', 'str.join'
+
+lst = [9]
+lst.extend(gen(2))
+assert lst == [9, 0, 1], 'list.extend'
+
+# === Unpacking and comprehensions ===
+a, b, c = gen(3)
+assert (a, b, c) == (0, 1, 2), 'unpacking'
+first, *rest = gen(4)
+assert first == 0, 'starred unpacking first'
+assert rest == [1, 2, 3], 'starred unpacking rest'
+assert [*gen(3)] == [0, 1, 2], 'star in list literal'
+assert [x * 2 for x in gen(3)] == [0, 2, 4], 'list comprehension'
@@ -0,0 +1,34 @@
+# === Generator expressions create generators ===
+g = (x * 2 for x in range(5))
+assert repr(g).startswith('<generator object <genexpr>'), 'genexp repr'
+assert next(g) == 0, 'first value'
+assert list(g) == [2, 4, 6, 8], 'remaining values'
+
+# === Evaluation is lazy ===
+assert next(x for x in range(10**12) if x > 5) == 6, 'huge range is not materialized'
+assert any(x > 5 for x in range(10**12)), 'any stops early'
+assert sum(x for x in range(100000)) == 4999950000, 'sum streams values'
+
+skip = 0
+g = (x for x in range(3) if x != skip)
+skip = 1
+assert list(g) == [0, 2], 'conditions are evaluated when iterated'
+
+# === The first iterable is evaluated immediately ===
+try:
+    (x for x in 5)
+    assert False, 'should have raised TypeError'
+except TypeError as e:
+    assert str(e) == "'int' object is not iterable", 'first iterable checked at creation'
+
+
+# === Capturing local variables ===
+def scaled(values, factor):
+    return list(v * factor for v in values)
+
+
+assert scaled([1, 2, 3], 10) == [10, 20, 30], 'genexp captures locals'
+
+# === Nested generator expressions ===
+rows = ((i, j) for i in range(2) for j in range(i, 2))
+assert list(rows) == [(0, 0), (0, 1), (1, 1)], 'nested for clauses'
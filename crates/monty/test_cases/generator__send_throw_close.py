@@ -0,0 +1,129 @@
+def accumulator():
+    total = 0
+    while True:
+        value = yield total
+        if value is None:
+            return total
+        total += value
+
+
+# === send() ===
+g = accumulator()
+assert g.send(None) == 0, 'send(None) starts the generator'
+assert g.send(5) == 5, 'send delivers the value of the yield expression'
+assert g.send(10) == 15, 'send accumulates'
+try:
+    next(g)
+    assert False, 'should have raised StopIteration'
+except StopIteration as e:
+    assert str(e) == '15', 'StopIteration carries the return value'
+
+g = accumulator()
+try:
+    g.send(1)
+    assert False, 'should have raised TypeError'
+except TypeError as e:
+    assert str(e) == "can't send non-None value to a just-started generator", 'send to unstarted generator'
+
+
+# === throw() ===
+def catcher():
+    while True:
+        try:
+            yield 'waiting'
+        except ValueError as e:
+            yield f'caught {e}'
+
+
+g = catcher()
+assert next(g) == 'waiting', 'started'
+assert g.throw(ValueError('boom')) == 'caught boom', 'throw raises at the suspended yield'
+assert next(g) == 'waiting', 'generator continues after handling'
+
+
+def simple():
+    yield 1
+    yield 2
+
+
+g = simple()
+next(g)
+try:
+    g.throw(KeyError('k'))
+    assert False, 'should have raised KeyError'
+except KeyError:
+    pass
+assert next(g, 'done') == 'done', 'uncaught throw finishes the generator'
+
+g = simple()
+try:
+    g.throw(ValueError('early'))
+    assert False, 'should have raised ValueError'
+except ValueError:
+    pass
+assert next(g, 'done') == 'done', 'throw into an unstarted generator finishes it'
+
+# === close() ===
+log = []
+
+
+def closable():
+    try:
+        yield 1
+        yield 2
+    finally:
+        log.append('closed')
+
+
+g = closable()
+assert next(g) == 1, 'started'
+assert g.close() is None, 'close returns None'
+assert log == ['closed'], 'close runs finally blocks'
+assert next(g, 'done') == 'done', 'closed generator is exhausted'
+assert g.close() is None, 'closing twice is fine'
+
+g = closable()
+g.close()
+assert log == ['closed'], 'closing an unstarted generator does not run it'
+
+
+def catches_exit():
+    try:
+        yield 1
+    except GeneratorExit:
+        log.append('exit')
+
+
+g = catches_exit()
+next(g)
+g.close()
+assert log == ['closed', 'exit'], 'GeneratorExit can be caught'
+
+
+def stubborn():
+    try:
+        yield 1
+    except GeneratorExit:
+        yield 2
+
+
+g = stubborn()
+next(g)
+try:
+    g.close()
+    assert False, 'should have raised RuntimeError'
+except RuntimeError as e:
+    assert str(e) == 'generator ignored GeneratorExit', 'yield after GeneratorExit'
+
+
+# === Re-entrancy ===
+def reentrant():
+    yield next(self_gen)
+
+
+self_gen = reentrant()
+try:
+    next(self_gen)
+    assert False, 'should have raised ValueError'
+except ValueError as e:
+    assert str(e) == 'generator already executing', 'generator cannot resume itself'
@@ -0,0 +1,9 @@
+def bad():
+    yield 1
+    raise StopIteration
+
+
+g = bad()
+next(g)
+next(g)
+# Raise=RuntimeError('generator raised StopIteration')
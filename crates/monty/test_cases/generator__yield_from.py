@@ -0,0 +1,52 @@
+def inner():
+    x = yield 1
+    yield x
+    return 'inner done'
+
+
+def outer():
+    result = yield from inner()
+    yield result
+
+
+# === Delegation to a sub-generator ===
+g = outer()
+assert next(g) == 1, 'values come from the sub-generator'
+assert g.send('sent') == 'sent', 'send is forwarded to the sub-generator'
+assert next(g) == 'inner done', 'yield from evaluates to the return value'
+assert next(g, 'end') == 'end', 'outer generator finishes'
+
+
+# === Delegation to other iterables ===
+def chain(*iterables):
+    for it in iterables:
+        yield from it
+
+
+assert list(chain([1, 2], (3,), 'ab', range(2))) == [1, 2, 3, 'a', 'b', 0, 1], 'yield from iterables'
+
+
+# === Recursive delegation ===
+def nested(depth):
+    if depth == 0:
+        yield 'leaf'
+        return
+    yield depth
+    yield from nested(depth - 1)
+
+
+assert list(nested(3)) == [3, 2, 1, 'leaf'], 'recursive yield from'
+
+
+# === Empty sub-generator ===
+def empty():
+    return
+    yield
+
+
+def wrap():
+    r = yield from empty()
+    yield r
+
+
+assert list(wrap()) == [None], 'yield from an empty generator'
@@ -0,0 +1,72 @@
+# Tests for multi-module imports, dotted imports and os.path
+
+import sys, os
+
+assert isinstance(sys.version, str), 'sys bound by multi-module import'
+assert os.path.isabs('/'), 'os bound by multi-module import'
+
+# === dotted imports ===
+import os.path
+
+assert os.path.join('a', 'b') == 'a/b', 'import os.path binds os'
+
+import os.path as osp
+
+assert osp.basename('a/b') == 'b', 'import os.path as osp binds os.path'
+
+from os import path
+
+assert path.dirname('a/b') == 'a', 'from os import path'
+
+from os.path import join, splitext
+
+assert join('a', 'b') == os.path.join('a', 'b'), 'from os.path import'
+
+# === os.path.join() ===
+assert join('a') == 'a', 'join single component'
+assert join('a', 'b', 'c') == 'a/b/c', 'join several components'
+assert join('a/', 'b') == 'a/b', 'join no double separator'
+assert join('a', '/b', 'c') == '/b/c', 'join absolute component resets'
+assert join('', 'b') == 'b', 'join empty first component'
+assert join('a', '') == 'a/', 'join empty last component'
+
+# === os.path.split() ===
+assert os.path.split('/usr/lib/file.txt') == ('/usr/lib', 'file.txt'), 'split file'
+assert os.path.split('/usr/lib/') == ('/usr/lib', ''), 'split trailing slash'
+assert os.path.split('file.txt') == ('', 'file.txt'), 'split no slash'
+assert os.path.split('/file.txt') == ('/', 'file.txt'), 'split root'
+assert os.path.split('a//b') == ('a', 'b'), 'split repeated slashes'
+
+# === os.path.splitext() ===
+assert splitext('archive.tar.gz') == ('archive.tar', '.gz'), 'splitext last extension'
+assert splitext('/a.b/c') == ('/a.b/c', ''), 'splitext dot in directory'
+assert splitext('.bashrc') == ('.bashrc', ''), 'splitext leading dot'
+assert splitext('..ext') == ('..ext', ''), 'splitext only dots before extension'
+assert splitext('a.') == ('a', '.'), 'splitext trailing dot'
+
+# === basename(), dirname(), isabs() ===
+assert os.path.basename('/usr/lib/file.txt') == 'file.txt', 'basename'
+assert os.path.basename('/usr/lib/') == '', 'basename trailing slash'
+assert os.path.dirname('/usr/lib/file.txt') == '/usr/lib', 'dirname'
+assert os.path.dirname('file.txt') == '', 'dirname no slash'
+assert os.path.isabs('/usr') == True, 'isabs absolute'
+assert os.path.isabs('usr') == False, 'isabs relative'
+
+# === type errors ===
+try:
+    join(1, 'a')
+    assert False, 'join with int should fail'
+except TypeError as e:
+    assert str(e) == 'expected str, bytes or os.PathLike object, not int', f'join first arg {e}'
+
+try:
+    join('a', None)
+    assert False, 'join with None should fail'
+except TypeError as e:
+    assert str(e) == "join() argument must be str, bytes, or os.PathLike object, not 'NoneType'", f'join arg {e}'
+
+try:
+    os.path.basename(1)
+    assert False, 'basename with int should fail'
+except TypeError as e:
+    assert str(e) == 'expected str, bytes or os.PathLike object, not int', f'basename arg {e}'
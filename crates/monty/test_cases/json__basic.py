@@ -0,0 +1,272 @@
+import json
+
+# === dumps scalars ===
+assert json.dumps(None) == 'null', 'None'
+assert json.dumps(True) == 'true', 'True'
+assert json.dumps(False) == 'false', 'False'
+assert json.dumps(42) == '42', 'int'
+assert json.dumps(-7) == '-7', 'negative int'
+assert json.dumps(2**100) == '1267650600228229401496703205376', 'big int'
+assert json.dumps(1.5) == '1.5', 'float'
+assert json.dumps(2.0) == '2.0', 'whole float'
+assert json.dumps(float('nan')) == 'NaN', 'nan'
+assert json.dumps(float('inf')) == 'Infinity', 'inf'
+assert json.dumps(float('-inf')) == '-Infinity', '-inf'
+assert json.dumps('hello') == '"hello"', 'str'
+
+# === dumps string escapes ===
+assert json.dumps('a"b\\c') == '"a\\"b\\\\c"', 'quote and backslash'
+assert json.dumps('\n\r\t\b\f') == '"\\n\\r\\t\\b\\f"', 'short escapes'
+assert json.dumps('\x00\x1f') == '"\\u0000\\u001f"', 'control characters'
+assert json.dumps('café') == '"caf\\u00e9"', 'non-ascii escaped by default'
+assert json.dumps('\x7f') == '"\\u007f"', 'DEL escaped by default'
+assert json.dumps('😀') == '"\\ud83d\\ude00"', 'astral char as surrogate pair'
+assert json.dumps('café 😀', ensure_ascii=False) == '"café 😀"', 'ensure_ascii=False'
+assert json.dumps('\n', ensure_ascii=False) == '"\\n"', 'ensure_ascii=False still escapes controls'
+
+# === dumps containers ===
+assert json.dumps([]) == '[]', 'empty list'
+assert json.dumps({}) == '{}', 'empty dict'
+assert json.dumps([1, 'a', None]) == '[1, "a", null]', 'list'
+assert json.dumps((1, 2)) == '[1, 2]', 'tuple as array'
+assert json.dumps({'a': 1, 'b': [True, False]}) == '{"a": 1, "b": [true, false]}', 'dict'
+assert json.dumps({'b': 1, 'a': 2}) == '{"b": 1, "a": 2}', 'insertion order kept'
+assert json.dumps({1: 'x', 2.5: 'y', False: 'z', None: 'w'}) == '{"1": "x", "2.5": "y", "false": "z", "null": "w"}', (
+    'non-str keys converted'
+)
+assert json.dumps([[1, [2]], {'k': {}}]) == '[[1, [2]], {"k": {}}]', 'nested'
+x = [1]
+assert json.dumps([x, x]) == '[[1], [1]]', 'repeated (not circular) reference'
+
+# === dumps options ===
+assert json.dumps({'b': 1, 'a': 2}, sort_keys=True) == '{"a": 2, "b": 1}', 'sort_keys'
+assert json.dumps([1, 2], separators=(',', ':')) == '[1,2]', 'compact separators'
+assert json.dumps({'a': 1}, separators=[';', '=']) == '{"a"=1}', 'separators as list'
+assert json.dumps({(1, 2): 'x', 'a': 1}, skipkeys=True) == '{"a": 1}', 'skipkeys'
+assert json.dumps([1, {'a': [2]}], indent=2) == '[\n  1,\n  {\n    "a": [\n      2\n    ]\n  }\n]', 'indent int'
+assert json.dumps([1, 2], indent='\t') == '[\n\t1,\n\t2\n]', 'indent str'
+assert json.dumps([1, 2], indent=0) == '[\n1,\n2\n]', 'indent zero'
+assert json.dumps([], indent=2) == '[]', 'empty list with indent'
+assert json.dumps({'a': 1}, indent=1, separators=(',', '=')) == '{\n "a"=1\n}', 'indent with separators'
+assert json.dumps(1, check_circular=False) == '1', 'check_circular accepted'
+
+
+# === dumps default ===
+class Point:
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+
+def encode_point(obj):
+    if isinstance(obj, Point):
+        return {'x': obj.x, 'y': obj.y}
+    raise TypeError('cannot encode')
+
+
+assert json.dumps([Point(1, 2)], default=encode_point) == '[{"x": 1, "y": 2}]', 'default'
+assert json.dumps({1, 2}, default=sorted) == '[1, 2]', 'default with a builtin'
+assert json.dumps(b'ab', default=lambda b: b.decode()) == '"ab"', 'default with a lambda'
+dumps = json.dumps
+assert dumps(Point(3, 4), default=encode_point) == '{"x": 3, "y": 4}', 'default via an alias'
+
+try:
+    json.dumps({1, 2}, default=encode_point)
+    assert False, 'default raising should propagate'
+except TypeError as e:
+    assert str(e) == 'cannot encode', 'default error propagates'
+
+# === dumps errors ===
+try:
+    json.dumps({1, 2})
+    assert False, 'set should raise'
+except TypeError as e:
+    assert str(e) == 'Object of type set is not JSON serializable', 'unserializable message'
+
+try:
+    json.dumps(Point(1, 2))
+    assert False, 'instance should raise'
+except TypeError as e:
+    assert str(e) == 'Object of type Point is not JSON serializable', 'instance message uses class name'
+
+try:
+    json.dumps({(1, 2): 'x'})
+    assert False, 'tuple key should raise'
+except TypeError as e:
+    assert str(e) == 'keys must be str, int, float, bool or None, not tuple', 'key type message'
+
+try:
+    json.dumps(float('nan'), allow_nan=False)
+    assert False, 'nan with allow_nan=False should raise'
+except ValueError as e:
+    assert str(e) == 'Out of range float values are not JSON compliant: nan', 'allow_nan message'
+
+circular = [1]
+circular.append(circular)
+try:
+    json.dumps(circular)
+    assert False, 'circular list should raise'
+except ValueError as e:
+    assert str(e) == 'Circular reference detected', 'circular message'
+
+d = {}
+d['self'] = d
+try:
+    json.dumps(d)
+    assert False, 'circular dict should raise'
+except ValueError as e:
+    assert str(e) == 'Circular reference detected', 'circular dict message'
+
+try:
+    json.dumps(1, indent=1.5)
+    assert False, 'float indent should raise'
+except TypeError as e:
+    assert str(e) == "can't multiply sequence by non-int of type 'float'", 'indent type message'
+
+try:
+    json.dumps(1, separators=(',',))
+    assert False, 'one separator should raise'
+except ValueError as e:
+    assert str(e) == 'not enough values to unpack (expected 2, got 1)', 'separators message'
+
+try:
+    json.dumps(1, foo=True)
+    assert False, 'unknown keyword should raise'
+except TypeError as e:
+    assert "unexpected keyword argument 'foo'" in str(e), 'unknown keyword message'
+
+# === loads ===
+assert json.loads('null') is None, 'null'
+assert json.loads('true') is True, 'true'
+assert json.loads('false') is False, 'false'
+assert json.loads('42') == 42, 'int'
+assert json.loads('-0') == 0, 'negative zero int'
+assert json.loads('123456789012345678901234567890') == 123456789012345678901234567890, 'big int'
+assert json.loads('-' + '9' * 4300) % 1000 == 1, 'int at the digit limit'
+try:
+    json.loads('[' + '1' * 4301 + ']')
+    assert False, 'int over the digit limit should raise'
+except ValueError as e:
+    assert type(e) is ValueError, 'digit limit raises ValueError, not JSONDecodeError'
+    assert str(e) == (
+        'Exceeds the limit (4300 digits) for integer string conversion: value has 4301 digits; '
+        'use sys.set_int_max_str_digits() to increase the limit'
+    ), f'digit limit message: {e}'
+assert json.loads('1.5') == 1.5, 'float'
+assert json.loads('1e3') == 1000.0, 'exponent'
+assert type(json.loads('1e3')) is float, 'exponent is a float'
+assert json.loads('-2.5E-1') == -0.25, 'negative exponent'
+assert json.loads('1e400') == float('inf'), 'overflowing float'
+assert json.loads('Infinity') == float('inf'), 'Infinity'
+assert json.loads('-Infinity') == float('-inf'), '-Infinity'
+nan = json.loads('NaN')
+assert nan != nan, 'NaN'
+assert json.loads('"hi"') == 'hi', 'string'
+assert json.loads(' \t\n[1, 2] \r\n') == [1, 2], 'surrounding whitespace'
+assert json.loads('[]') == [], 'empty array'
+assert json.loads('{}') == {}, 'empty object'
+assert json.loads('{"a": [1, {"b": null}], "c": "d"}') == {'a': [1, {'b': None}], 'c': 'd'}, 'nested'
+assert json.loads('{"a": 1, "a": 2}') == {'a': 2}, 'last duplicate key wins'
+assert json.loads(b'[1, "x"]') == [1, 'x'], 'bytes'
+assert json.loads(bytearray(b'{"k": true}')) == {'k': True}, 'bytearray'
+
+# === loads string escapes ===
+assert json.loads(r'"a\"b\\c\/d"') == 'a"b\\c/d', 'simple escapes'
+assert json.loads(r'"\n\r\t\b\f"') == '\n\r\t\b\f', 'control escapes'
+assert json.loads(r'"caf\u00e9"') == 'café', 'unicode escape'
+assert json.loads(r'"\ud83d\ude00"') == '😀', 'surrogate pair'
+assert json.loads('"café 😀"') == 'café 😀', 'raw non-ascii'
+assert json.loads(json.dumps('ünïcode "quoted"\n')) == 'ünïcode "quoted"\n', 'round trip'
+
+# === loads errors ===
+try:
+    json.loads('[1,]')
+    assert False, 'trailing comma should raise'
+except json.JSONDecodeError as e:
+    assert str(e) == 'Illegal trailing comma before end of array: line 1 column 3 (char 2)', 'array trailing comma'
+
+try:
+    json.loads('{"a":1,}')
+    assert False, 'trailing comma in object should raise'
+except json.JSONDecodeError as e:
+    assert str(e) == 'Illegal trailing comma before end of object: line 1 column 7 (char 6)', 'object trailing comma'
+
+try:
+    json.loads('\n\n  x')
+    assert False, 'invalid value should raise'
+except json.JSONDecodeError as e:
+    assert str(e) == 'Expecting value: line 3 column 3 (char 4)', 'line and column'
+
+try:
+    json.loads('')
+    assert False, 'empty document should raise'
+except json.JSONDecodeError as e:
+    assert str(e) == 'Expecting value: line 1 column 1 (char 0)', 'empty document'
+
+try:
+    json.loads('[1')
+    assert False, 'unclosed array should raise'
+except json.JSONDecodeError as e:
+    assert str(e) == "Expecting ',' delimiter: line 1 column 3 (char 2)", 'unclosed array'
+
+try:
+    json.loads('{"a"')
+    assert False, 'missing colon should raise'
+except json.JSONDecodeError as e:
+    assert str(e) == "Expecting ':' delimiter: line 1 column 5 (char 4)", 'missing colon'
+
+try:
+    json.loads('{1: 2}')
+    assert False, 'unquoted key should raise'
+except json.JSONDecodeError as e:
+    assert str(e) == 'Expecting property name enclosed in double quotes: line 1 column 2 (char 1)', 'unquoted key'
+
+try:
+    json.loads('[1] 2')
+    assert False, 'extra data should raise'
+except json.JSONDecodeError as e:
+    assert str(e) == 'Extra data: line 1 column 5 (char 4)', 'extra data'
+
+try:
+    json.loads('"abc')
+    assert False, 'unterminated string should raise'
+except json.JSONDecodeError as e:
+    assert str(e) == 'Unterminated string starting at: line 1 column 1 (char 0)', 'unterminated string'
+
+try:
+    json.loads('"a\nb"')
+    assert False, 'control character should raise'
+except json.JSONDecodeError as e:
+    assert str(e) == 'Invalid control character at: line 1 column 3 (char 2)', 'control character'
+
+try:
+    json.loads(r'"\x"')
+    assert False, 'invalid escape should raise'
+except json.JSONDecodeError as e:
+    assert str(e) == 'Invalid \\escape: line 1 column 2 (char 1)', 'invalid escape'
+
+try:
+    json.loads(r'"\u12"')
+    assert False, 'short unicode escape should raise'
+except json.JSONDecodeError as e:
+    assert str(e) == 'Invalid \\uXXXX escape: line 1 column 3 (char 2)', 'invalid unicode escape'
+
+try:
+    json.loads('"é" x')
+    assert False, 'extra data after non-ascii should raise'
+except json.JSONDecodeError as e:
+    assert str(e) == 'Extra data: line 1 column 5 (char 4)', 'positions count characters'
+
+try:
+    json.loads('tru')
+    assert False, 'partial literal should raise'
+except ValueError as e:
+    assert str(e) == 'Expecting value: line 1 column 1 (char 0)', 'JSONDecodeError is a ValueError'
+
+try:
+    json.loads(1)
+    assert False, 'int document should raise'
+except TypeError as e:
+    assert str(e) == 'the JSON object must be str, bytes or bytearray, not int', 'document type message'
+
+assert issubclass(json.JSONDecodeError, ValueError), 'JSONDecodeError subclasses ValueError'
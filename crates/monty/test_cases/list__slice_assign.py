@@ -0,0 +1,81 @@
+# === Step 1 slices resize the list ===
+a = [1, 2, 3, 4, 5]
+a[1:3] = ['x']
+assert a == [1, 'x', 4, 5], 'shrink'
+
+a = [1, 2, 3]
+a[1:2] = [7, 8, 9]
+assert a == [1, 7, 8, 9, 3], 'grow'
+
+a = [1, 2, 3]
+a[1:1] = [10, 11]
+assert a == [1, 10, 11, 2, 3], 'insert with empty slice'
+
+a = [1, 2, 3]
+a[2:0] = ['y']
+assert a == [1, 2, 'y', 3], 'stop before start inserts at start'
+
+a = [1, 2, 3]
+a[:] = []
+assert a == [], 'clear'
+
+a = [1, 2, 3]
+a[len(a) :] = [4, 5]
+assert a == [1, 2, 3, 4, 5], 'append at end'
+
+a = [1, 2, 3]
+a[-2:] = (8, 9)
+assert a == [1, 8, 9], 'negative start with tuple'
+
+a = [1, 2, 3]
+a[100:200] = [4]
+assert a == [1, 2, 3, 4], 'out of range slice appends'
+
+a = [1, 2, 3]
+a[:1] = 'ab'
+assert a == ['a', 'b', 2, 3], 'string iterable'
+
+a = [1, 2, 3]
+a[1:] = range(3)
+assert a == [1, 0, 1, 2], 'range iterable'
+
+# === Assigning a list to a slice of itself ===
+a = [1, 2, 3]
+a[:] = a
+assert a == [1, 2, 3], 'self full'
+
+a = [1, 2, 3]
+a[1:1] = a
+assert a == [1, 1, 2, 3, 2, 3], 'self insert'
+
+# === Extended slices ===
+a = [0, 1, 2, 3, 4, 5]
+a[::2] = ['a', 'b', 'c']
+assert a == ['a', 1, 'b', 3, 'c', 5], 'step 2'
+
+a = [0, 1, 2, 3]
+a[::-1] = [10, 20, 30, 40]
+assert a == [40, 30, 20, 10], 'reverse'
+
+a = [0, 1, 2, 3, 4]
+a[3:0:-2] = 'xy'
+assert a == [0, 'y', 2, 'x', 4], 'negative step with bounds'
+
+a = [1, 2, 3]
+a[5::2] = []
+assert a == [1, 2, 3], 'empty extended slice'
+
+# === Slices of nested values ===
+inner = [1]
+a = [0, 0, 0]
+a[::2] = [inner, inner]
+assert a == [[1], 0, [1]], 'nested extended'
+a[0:2] = [inner]
+assert a == [[1], [1]], 'nested step 1'
+inner.append(2)
+assert a == [[1, 2], [1, 2]], 'items are shared'
+
+# === Augmented assignment to a slice ===
+a = [1, 2, 3]
+a[1:2] += [4, 5]
+assert a == [1, 2, 4, 5, 3], 'augmented slice'
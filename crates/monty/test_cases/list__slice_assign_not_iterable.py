@@ -0,0 +1,3 @@
+a = [1, 2, 3]
+a[0:1] = 5
+# Raise=TypeError('must assign iterable to extended slice')
@@ -0,0 +1,3 @@
+a = [1, 2, 3, 4]
+a[::2] = [1, 2, 3]
+# Raise=ValueError('attempt to assign sequence of size 3 to extended slice of size 2')
@@ -0,0 +1,3 @@
+a = [1, 2, 3]
+a[::0] = [1]
+# Raise=ValueError('slice step cannot be zero')
@@ -0,0 +1,117 @@
+# === Class patterns with user classes ===
+
+
+class Point:
+    __match_args__ = ('x', 'y')
+
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+
+class Circle:
+    def __init__(self, center, radius):
+        self.center = center
+        self.radius = radius
+
+
+def where(p):
+    match p:
+        case Point(0, 0):
+            return 'origin'
+        case Point(x=0, y=y):
+            return f'y axis at {y}'
+        case Point(x, 0):
+            return f'x axis at {x}'
+        case Point():
+            return 'somewhere'
+        case _:
+            return 'not a point'
+
+
+assert where(Point(0, 0)) == 'origin', 'positional sub-patterns'
+assert where(Point(0, 5)) == 'y axis at 5', 'keyword sub-patterns'
+assert where(Point(3, 0)) == 'x axis at 3', 'mixed literal and capture'
+assert where(Point(1, 1)) == 'somewhere', 'class with no sub-patterns'
+assert where((0, 0)) == 'not a point', 'isinstance check fails'
+
+# keyword patterns work without __match_args__
+match Circle(Point(1, 2), 3):
+    case Circle(center=Point(x, y), radius=r):
+        result = (x, y, r)
+assert result == (1, 2, 3), 'nested class patterns with keywords'
+
+# missing attribute means no match
+match Circle(Point(1, 2), 3):
+    case Circle(color='red'):
+        result = 'red'
+    case _:
+        result = 'no color'
+assert result == 'no color', 'missing attribute fails the match'
+
+
+# subclasses match
+class Point3D(Point):
+    pass
+
+
+match Point3D(1, 2):
+    case Point(a, b):
+        result = a + b
+assert result == 3, 'subclass instances match'
+
+# === Builtin types match themselves positionally ===
+
+
+def kind(value):
+    match value:
+        case bool(b):
+            return f'bool {b}'
+        case int(n):
+            return f'int {n}'
+        case float(f):
+            return f'float {f}'
+        case str() as s:
+            return f'str {s}'
+        case list([first, *_]):
+            return f'list starting {first}'
+        case dict({'k': v}):
+            return f'dict {v}'
+        case _:
+            return 'other'
+
+
+assert kind(True) == 'bool True', 'bool before int'
+assert kind(5) == 'int 5', 'int matches itself'
+assert kind(1.5) == 'float 1.5', 'float matches itself'
+assert kind('x') == 'str x', 'str class pattern'
+assert kind([9, 8]) == 'list starting 9', 'list self match with nested sequence'
+assert kind({'k': 1}) == 'dict 1', 'dict self match with nested mapping'
+assert kind(None) == 'other', 'None matches none of them'
+
+# === Tuple of classes in or pattern ===
+match 2.0:
+    case int() | float():
+        result = 'number'
+assert result == 'number', 'or pattern of class patterns'
+
+# === Named tuple classes ===
+from collections import namedtuple
+
+Pair = namedtuple('Pair', ['left', 'right'])
+Span = namedtuple('Span', ['left', 'right'])
+
+
+def describe_pair(value):
+    match value:
+        case Span(left, right):
+            return f'span {left}-{right}'
+        case Pair(left, right=right):
+            return f'pair {left}-{right}'
+        case _:
+            return 'other'
+
+
+assert describe_pair(Pair(1, 2)) == 'pair 1-2', 'namedtuple class pattern with positional and keyword fields'
+assert describe_pair(Span(3, 4)) == 'span 3-4', 'namedtuple classes with the same fields are distinct'
+assert describe_pair((1, 2)) == 'other', 'plain tuple does not match a namedtuple class'
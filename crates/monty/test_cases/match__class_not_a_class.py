@@ -0,0 +1,5 @@
+not_a_class = 42
+match 1:
+    case not_a_class.real():
+        pass
+# Raise=TypeError('called match pattern must be a class')
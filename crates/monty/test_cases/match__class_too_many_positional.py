@@ -0,0 +1,12 @@
+class Point:
+    __match_args__ = ('x', 'y')
+
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+
+match Point(1, 2):
+    case Point(a, b, c):
+        pass
+# Raise=TypeError('Point() accepts 2 positional sub-patterns (3 given)')
@@ -0,0 +1,31 @@
+# call-external
+# === Class patterns against host dataclasses ===
+point = make_point()
+user = make_user('alice')
+Point = type(point)
+User = type(user)
+
+assert Point == type(make_point()), 'type() of the same dataclass is equal'
+assert Point != User, 'type() of different dataclasses differ'
+assert Point.__name__ == 'Point', 'dataclass class __name__'
+
+
+def describe(value):
+    match value:
+        case User(name, active=True):
+            return f'active user {name}'
+        case Point(x, y):
+            return f'point {x} {y}'
+        case _:
+            return 'other'
+
+
+assert describe(point) == 'point 1 2', 'dataclass positional fields'
+assert describe(user) == 'active user alice', 'dataclass positional and keyword fields'
+assert describe(make_mutable_point()) == 'other', 'dataclass with the same fields but another class'
+assert describe((1, 2)) == 'other', 'non-dataclass subject'
+
+match point:
+    case Point(x=1, y=y):
+        result = y
+assert result == 2, 'dataclass keyword pattern'
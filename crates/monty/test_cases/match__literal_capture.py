@@ -0,0 +1,126 @@
+# === Literal and capture patterns ===
+
+
+def describe(value):
+    match value:
+        case True:
+            return 'true'
+        case 0:
+            return 'zero'
+        case 1 | 2 | 3:
+            return 'small'
+        case 'hello':
+            return 'greeting'
+        case None:
+            return 'none'
+        case -1:
+            return 'minus one'
+        case other:
+            return f'other {other}'
+
+
+assert describe(0) == 'zero', 'literal int pattern'
+assert describe(2) == 'small', 'or pattern of literals'
+assert describe('hello') == 'greeting', 'literal str pattern'
+assert describe(None) == 'none', 'None singleton pattern'
+assert describe(True) == 'true', 'True singleton pattern'
+assert describe(-1) == 'minus one', 'negative literal pattern'
+assert describe(42) == 'other 42', 'capture pattern binds the subject'
+assert describe(1.0) == 'small', 'value patterns compare with =='
+
+# True is matched by identity, so 1 does not match True
+match 1:
+    case True:
+        result = 'true'
+    case _:
+        result = 'not true'
+assert result == 'not true', 'singleton patterns compare with is'
+
+# === No case matches ===
+matched = False
+match 5:
+    case 1:
+        matched = True
+    case 2:
+        matched = True
+assert not matched, 'no case should match'
+
+# === Wildcard ===
+match 'anything':
+    case _:
+        result = 'wildcard'
+assert result == 'wildcard', 'wildcard matches anything'
+
+# === Value patterns with dotted names ===
+
+
+class Color:
+    RED = 'red'
+    GREEN = 'green'
+
+
+match 'green':
+    case Color.RED:
+        result = 'red'
+    case Color.GREEN:
+        result = 'green'
+assert result == 'green', 'dotted name is a value pattern'
+
+# === As patterns ===
+match 3:
+    case (1 | 2 | 3) as n:
+        result = n * 10
+assert result == 30, 'as pattern binds the matched value'
+
+# === Guards ===
+
+
+def sign(n):
+    match n:
+        case x if x < 0:
+            return 'negative'
+        case x if x > 0:
+            return 'positive'
+        case _:
+            return 'zero'
+
+
+assert sign(-5) == 'negative', 'first guard'
+assert sign(5) == 'positive', 'second guard'
+assert sign(0) == 'zero', 'guards fall through'
+
+# captures from a failed guard are still bound
+match 7:
+    case y if y > 100:
+        pass
+    case _:
+        pass
+assert y == 7, 'capture is bound before the guard runs'
+
+# === Match in a loop with break/continue ===
+seen = []
+for item in [1, 'skip', 2, 'stop', 3]:
+    match item:
+        case 'skip':
+            continue
+        case 'stop':
+            break
+        case n:
+            seen.append(n)
+assert seen == [1, 2], 'break and continue work inside case bodies'
+
+# === Subject evaluated once ===
+calls = []
+
+
+def subject():
+    calls.append(1)
+    return 2
+
+
+match subject():
+    case 1:
+        pass
+    case 2:
+        pass
+assert len(calls) == 1, 'subject is evaluated only once'
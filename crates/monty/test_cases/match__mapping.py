@@ -0,0 +1,54 @@
+# === Mapping patterns ===
+
+
+def handle(event):
+    match event:
+        case {'type': 'click', 'pos': (x, y)}:
+            return f'click at {x},{y}'
+        case {'type': 'key', 'key': key, **rest}:
+            return f'key {key} {rest}'
+        case {'type': kind}:
+            return f'other {kind}'
+        case {}:
+            return 'empty or untyped'
+        case _:
+            return 'not a mapping'
+
+
+assert handle({'type': 'click', 'pos': (1, 2)}) == 'click at 1,2', 'nested pattern in mapping'
+assert handle({'type': 'key', 'key': 'a', 'shift': True}) == "key a {'shift': True}", 'rest captures other keys'
+assert handle({'type': 'key', 'key': 'b'}) == 'key b {}', 'rest can be empty'
+assert handle({'type': 'scroll', 'extra': 1}) == 'other scroll', 'extra keys are allowed'
+assert handle({'type': 'click'}) == 'other click', 'missing key fails the case'
+assert handle({'a': 1}) == 'empty or untyped', 'empty mapping pattern matches any dict'
+assert handle([('type', 'click')]) == 'not a mapping', 'list is not a mapping'
+
+# === Non-string keys ===
+match {1: 'one', None: 'none'}:
+    case {1: a, None: b}:
+        result = a + b
+assert result == 'onenone', 'int and None keys'
+
+# === Rest does not modify the subject ===
+subject = {'a': 1, 'b': 2}
+match subject:
+    case {'a': 1, **others}:
+        pass
+assert others == {'b': 2}, 'rest has the remaining keys'
+assert subject == {'a': 1, 'b': 2}, 'subject is unchanged'
+others['c'] = 3
+assert 'c' not in subject, 'rest is a copy'
+
+# === Only rest ===
+match {'x': 1}:
+    case {**everything}:
+        pass
+assert everything == {'x': 1}, 'rest alone copies the mapping'
+
+# === Value mismatch ===
+match {'status': 404}:
+    case {'status': 200}:
+        result = 'ok'
+    case {'status': code}:
+        result = code
+assert result == 404, 'value pattern inside mapping'
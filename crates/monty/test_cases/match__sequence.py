@@ -0,0 +1,94 @@
+# === Sequence patterns ===
+
+
+def shape(value):
+    match value:
+        case []:
+            return 'empty'
+        case [x]:
+            return f'one {x}'
+        case [x, y]:
+            return f'two {x} {y}'
+        case [first, *rest]:
+            return f'many {first} {rest}'
+        case _:
+            return 'not a sequence'
+
+
+assert shape([]) == 'empty', 'empty list'
+assert shape(()) == 'empty', 'empty tuple'
+assert shape([1]) == 'one 1', 'single element'
+assert shape((1, 2)) == 'two 1 2', 'tuple of two'
+assert shape([1, 2, 3, 4]) == 'many 1 [2, 3, 4]', 'star captures the rest as a list'
+assert shape((1, 2, 3)) == 'many 1 [2, 3]', 'star from a tuple is still a list'
+assert shape(range(3)) == 'many 0 [1, 2]', 'range is a sequence'
+assert shape('ab') == 'not a sequence', 'str is not matched as a sequence'
+assert shape(b'ab') == 'not a sequence', 'bytes is not matched as a sequence'
+assert shape({1: 2}) == 'not a sequence', 'dict is not a sequence'
+assert shape(5) == 'not a sequence', 'int is not a sequence'
+
+# === Star in the middle and at the start ===
+match [1, 2, 3, 4, 5]:
+    case [a, *middle, b]:
+        pass
+assert (a, middle, b) == (1, [2, 3, 4], 5), 'star in the middle'
+
+match [1, 2, 3]:
+    case [*init, last]:
+        pass
+assert (init, last) == ([1, 2], 3), 'star at the start'
+
+match [1, 2]:
+    case [a, *middle, b]:
+        pass
+assert middle == [], 'star can match nothing'
+
+match [1, 2, 3]:
+    case [1, *_, 3]:
+        result = 'anonymous star'
+assert result == 'anonymous star', 'star wildcard'
+
+# === Nested sequences ===
+match [(1, 2), [3, [4, 5]]]:
+    case [(a, b), [c, [d, e]]]:
+        result = a + b + c + d + e
+assert result == 15, 'nested sequence patterns'
+
+# === Literals inside sequences ===
+
+
+def command(cmd):
+    match cmd.split():
+        case ['go', direction]:
+            return f'going {direction}'
+        case ['drop', *objects]:
+            return f'dropping {len(objects)}'
+        case ['quit' | 'exit']:
+            return 'bye'
+        case _:
+            return 'unknown'
+
+
+assert command('go north') == 'going north', 'literal then capture'
+assert command('drop a b c') == 'dropping 3', 'literal then star'
+assert command('exit') == 'bye', 'or pattern in sequence'
+assert command('go') == 'unknown', 'length mismatch'
+
+# === Parenthesized and open sequence patterns ===
+match 1, 2:
+    case x, y:
+        result = x + y
+assert result == 3, 'open sequence pattern'
+
+match (1, 2):
+    case (1, z):
+        result = z
+assert result == 2, 'tuple pattern'
+
+# === Partial binding on failure ===
+match [1, 2, 3]:
+    case [p, 5, q]:
+        result = 'matched'
+    case _:
+        result = 'failed'
+assert result == 'failed', 'later items must also match'
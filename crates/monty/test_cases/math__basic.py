@@ -0,0 +1,321 @@
+import math
+
+# === constants ===
+assert math.pi == 3.141592653589793, 'pi'
+assert math.e == 2.718281828459045, 'e'
+assert math.tau == 2 * math.pi, 'tau'
+assert math.inf == float('inf'), 'inf'
+assert math.inf > 0 and math.isinf(-math.inf), 'inf sign'
+assert math.nan != math.nan, 'nan'
+
+# === sqrt, exp and pow ===
+assert math.sqrt(16) == 4.0, 'sqrt int'
+assert math.sqrt(2.25) == 1.5, 'sqrt float'
+assert math.sqrt(True) == 1.0, 'sqrt bool'
+assert math.sqrt(0.0) == 0.0, 'sqrt zero'
+assert math.sqrt(math.inf) == math.inf, 'sqrt inf'
+assert math.isnan(math.sqrt(math.nan)), 'sqrt nan'
+assert math.exp(0) == 1.0, 'exp 0'
+assert math.isclose(math.exp(1), math.e), 'exp 1'
+assert math.exp(-math.inf) == 0.0, 'exp -inf'
+assert math.pow(2, 10) == 1024.0, 'pow'
+assert math.pow(4, 0.5) == 2.0, 'pow fraction'
+assert math.pow(2, -1) == 0.5, 'pow negative'
+assert math.pow(math.nan, 0) == 1.0, 'pow nan to 0'
+assert math.pow(1, math.nan) == 1.0, 'pow 1 to nan'
+assert math.pow(0.5, math.inf) == 0.0, 'pow to inf'
+assert type(math.pow(2, 2)) is float, 'pow returns float'
+
+# === logarithms ===
+assert math.log(1) == 0.0, 'log 1'
+assert math.log(math.e) == 1.0, 'log e'
+assert math.log(8, 2) == 3.0, 'log base 2'
+assert math.isclose(math.log(100, 10), 2.0), 'log base 10'
+assert math.log2(8) == 3.0, 'log2'
+assert math.log2(1024) == 10.0, 'log2 1024'
+assert math.log10(1000) == 3.0, 'log10'
+assert math.log10(0.001) == -3.0, 'log10 fraction'
+assert math.log(math.inf) == math.inf, 'log inf'
+assert math.log2(2**1100) == 1100.0, 'log2 of a huge int'
+assert math.isclose(math.log(10**400), 921.0340371976182), 'log of a huge int'
+assert math.isclose(math.log10(10**400), 400.0), 'log10 of a huge int'
+assert math.isclose(math.log(2**1100, 2), 1100.0), 'log of a huge int with base'
+
+# === trigonometry ===
+assert math.sin(0) == 0.0, 'sin 0'
+assert math.cos(0) == 1.0, 'cos 0'
+assert math.tan(0) == 0.0, 'tan 0'
+assert math.isclose(math.sin(math.pi / 2), 1.0), 'sin pi/2'
+assert math.isclose(math.cos(math.pi), -1.0), 'cos pi'
+assert math.isclose(math.tan(math.pi / 4), 1.0), 'tan pi/4'
+assert math.isclose(math.asin(1), math.pi / 2), 'asin'
+assert math.isclose(math.acos(-1), math.pi), 'acos'
+assert math.isclose(math.atan(1), math.pi / 4), 'atan'
+assert math.isclose(math.atan2(1, 1), math.pi / 4), 'atan2'
+assert math.isclose(math.atan2(-1, -1), -3 * math.pi / 4), 'atan2 quadrant'
+assert math.atan2(0.0, -1.0) == math.pi, 'atan2 on negative axis'
+assert math.atan(math.inf) == math.pi / 2, 'atan inf'
+assert math.sinh(0) == 0.0 and math.cosh(0) == 1.0 and math.tanh(0) == 0.0, 'hyperbolic at 0'
+assert math.isclose(math.sinh(1), (math.e - 1 / math.e) / 2), 'sinh'
+assert math.tanh(math.inf) == 1.0, 'tanh inf'
+assert math.degrees(math.pi) == 180.0, 'degrees'
+assert math.radians(180) == math.pi, 'radians'
+
+# === hypot, fabs, fmod and copysign ===
+assert math.hypot(3, 4) == 5.0, 'hypot'
+assert math.hypot(1, 2, 2) == 3.0, 'hypot 3d'
+assert math.hypot() == 0.0, 'hypot no args'
+assert math.hypot(-5) == 5.0, 'hypot 1d'
+assert math.hypot(math.inf, math.nan) == math.inf, 'hypot inf wins over nan'
+assert math.fabs(-3) == 3.0, 'fabs int'
+assert type(math.fabs(-3)) is float, 'fabs returns float'
+assert math.fabs(-2.5) == 2.5, 'fabs float'
+assert math.fmod(7, 3) == 1.0, 'fmod'
+assert math.fmod(-7, 3) == -1.0, 'fmod keeps sign of x'
+assert math.fmod(5, math.inf) == 5.0, 'fmod by inf'
+assert math.copysign(3, -0.0) == -3.0, 'copysign'
+assert math.copysign(-2.0, 1) == 2.0, 'copysign positive'
+
+# === floor, ceil and trunc ===
+assert math.floor(2.7) == 2, 'floor'
+assert math.floor(-2.3) == -3, 'floor negative'
+assert type(math.floor(2.7)) is int, 'floor returns int'
+assert math.ceil(2.1) == 3, 'ceil'
+assert math.ceil(-2.7) == -2, 'ceil negative'
+assert math.trunc(2.7) == 2, 'trunc'
+assert math.trunc(-2.7) == -2, 'trunc negative'
+assert math.floor(5) == 5 and math.ceil(5) == 5 and math.trunc(5) == 5, 'ints unchanged'
+assert math.floor(True) == 1, 'floor bool'
+assert math.floor(10**30) == 10**30, 'floor huge int'
+assert math.floor(1e20) == 100000000000000000000, 'floor large float'
+assert math.ceil(-1e300) == -int(1e300), 'ceil huge float'
+
+# === isclose, isnan, isinf and isfinite ===
+assert math.isclose(1.0, 1.0 + 1e-10), 'isclose within rel_tol'
+assert not math.isclose(1.0, 1.1), 'isclose outside rel_tol'
+assert math.isclose(1.0, 1.1, rel_tol=0.2), 'isclose custom rel_tol'
+assert not math.isclose(0.0, 1e-10), 'isclose near zero'
+assert math.isclose(0.0, 1e-10, abs_tol=1e-9), 'isclose abs_tol'
+assert math.isclose(math.inf, math.inf), 'isclose inf'
+assert not math.isclose(math.inf, -math.inf), 'isclose opposite infs'
+assert not math.isclose(math.nan, math.nan), 'isclose nan'
+assert math.isclose(0.1 + 0.2, 0.3), 'isclose classic'
+assert math.isnan(math.nan) and not math.isnan(1.0), 'isnan'
+assert math.isinf(math.inf) and not math.isinf(1e308), 'isinf'
+assert math.isfinite(1) and not math.isfinite(math.nan) and not math.isfinite(math.inf), 'isfinite'
+
+# === gcd and lcm ===
+assert math.gcd(12, 18) == 6, 'gcd'
+assert math.gcd(-4, 6) == 2, 'gcd negative'
+assert math.gcd(0, 5) == 5, 'gcd zero'
+assert math.gcd(12, 18, 8) == 2, 'gcd several'
+assert math.gcd() == 0, 'gcd no args'
+assert math.gcd(2**100, 2**80 * 3) == 2**80, 'gcd huge ints'
+assert math.lcm(4, 6) == 12, 'lcm'
+assert math.lcm(-4, 6) == 12, 'lcm negative'
+assert math.lcm(4, 6, 10) == 60, 'lcm several'
+assert math.lcm(0, 5) == 0, 'lcm zero'
+assert math.lcm() == 1, 'lcm no args'
+assert math.lcm(2**70, 3) == 3 * 2**70, 'lcm huge ints'
+
+# === isqrt ===
+assert math.isqrt(0) == 0, 'isqrt 0'
+assert math.isqrt(15) == 3, 'isqrt rounds down'
+assert math.isqrt(16) == 4, 'isqrt exact'
+assert math.isqrt(10**40) == 10**20, 'isqrt huge int'
+assert math.isqrt(True) == 1, 'isqrt bool'
+
+# === factorial, comb and perm ===
+assert math.factorial(0) == 1, 'factorial 0'
+assert math.factorial(5) == 120, 'factorial'
+assert math.factorial(25) == 15511210043330985984000000, 'factorial huge'
+assert math.comb(5, 2) == 10, 'comb'
+assert math.comb(10, 0) == 1, 'comb 0'
+assert math.comb(10, 10) == 1, 'comb n'
+assert math.comb(3, 5) == 0, 'comb k > n'
+assert math.comb(100, 50) == 100891344545564193334812497256, 'comb huge'
+assert math.comb(10**20, 2) == 4999999999999999999950000000000000000000, 'comb of a huge int'
+assert math.perm(5, 2) == 20, 'perm'
+assert math.perm(5) == 120, 'perm without k'
+assert math.perm(5, None) == 120, 'perm k None'
+assert math.perm(3, 5) == 0, 'perm k > n'
+assert math.perm(30, 20) == 73096577329197271449600000, 'perm huge'
+
+# === prod ===
+assert math.prod([1, 2, 3, 4]) == 24, 'prod'
+assert math.prod([]) == 1, 'prod empty'
+assert math.prod([2, 3], start=10) == 60, 'prod start'
+assert math.prod([1.5, 2]) == 3.0, 'prod floats'
+assert math.prod(range(1, 11)) == 3628800, 'prod range'
+assert math.prod([2**40, 2**40]) == 2**80, 'prod promotes to big ints'
+assert math.prod([2, 'ab']) == 'abab', 'prod works on anything multipliable'
+
+from math import prod
+
+assert prod(x for x in range(1, 6)) == 120, 'imported prod with generator'
+
+# === fsum ===
+assert math.fsum([0.1] * 10) == 1.0, 'fsum exact'
+assert math.fsum([1e100, 1.0, -1e100, 1e-100, 1e50, -1.0, -1e50]) == 1e-100, 'fsum cancellation'
+assert math.fsum([]) == 0.0, 'fsum empty'
+assert math.fsum(x / 10 for x in range(1, 11)) == 5.5, 'fsum generator'
+assert math.fsum([1, 2, 3]) == 6.0, 'fsum ints'
+assert math.fsum([math.inf, 1.0]) == math.inf, 'fsum inf'
+assert math.isnan(math.fsum([math.nan, 1.0])), 'fsum nan'
+
+# === domain and range errors ===
+for expr in [
+    lambda: math.sqrt(-1),
+    lambda: math.log(0),
+    lambda: math.log(-1),
+    lambda: math.log(-(10**400)),
+    lambda: math.log(2, 0),
+    lambda: math.log2(0),
+    lambda: math.acos(2),
+    lambda: math.asin(-2),
+    lambda: math.sin(math.inf),
+    lambda: math.pow(0, -1),
+    lambda: math.pow(-8, 1 / 3),
+    lambda: math.fmod(1, 0),
+    lambda: math.fmod(math.inf, 1),
+]:
+    try:
+        expr()
+        assert False, 'domain error expected'
+    except ValueError as e:
+        assert str(e) == 'math domain error', 'domain error message'
+
+for expr in [lambda: math.exp(1000), lambda: math.pow(10.0, 400), lambda: math.cosh(1000)]:
+    try:
+        expr()
+        assert False, 'range error expected'
+    except OverflowError as e:
+        assert str(e) == 'math range error', 'range error message'
+
+try:
+    math.log(10, 1)
+    assert False, 'log base 1 should raise'
+except ZeroDivisionError as e:
+    assert str(e) == 'float division by zero', 'log base 1 message'
+
+try:
+    math.sqrt(10**400)
+    assert False, 'huge int to float should raise'
+except OverflowError as e:
+    assert str(e) == 'int too large to convert to float', 'huge int message'
+
+try:
+    math.floor(math.inf)
+    assert False, 'floor of inf should raise'
+except OverflowError as e:
+    assert str(e) == 'cannot convert float infinity to integer', 'floor inf message'
+
+try:
+    math.ceil(math.nan)
+    assert False, 'ceil of nan should raise'
+except ValueError as e:
+    assert str(e) == 'cannot convert float NaN to integer', 'ceil nan message'
+
+try:
+    math.fsum([math.inf, -math.inf])
+    assert False, 'fsum of opposite infs should raise'
+except ValueError as e:
+    assert str(e) == '-inf + inf in fsum', 'fsum infs message'
+
+try:
+    math.fsum([1e308, 1e308])
+    assert False, 'fsum overflow should raise'
+except OverflowError as e:
+    assert str(e) == 'intermediate overflow in fsum', 'fsum overflow message'
+
+try:
+    math.isclose(1, 2, rel_tol=-1)
+    assert False, 'negative tolerance should raise'
+except ValueError as e:
+    assert str(e) == 'tolerances must be non-negative', 'tolerance message'
+
+# === integer argument errors ===
+try:
+    math.factorial(-1)
+    assert False, 'negative factorial should raise'
+except ValueError as e:
+    assert str(e) == 'factorial() not defined for negative values', 'factorial message'
+
+try:
+    math.factorial(1.5)
+    assert False, 'float factorial should raise'
+except TypeError as e:
+    assert str(e) == "'float' object cannot be interpreted as an integer", 'factorial float message'
+
+try:
+    math.isqrt(-1)
+    assert False, 'negative isqrt should raise'
+except ValueError as e:
+    assert str(e) == 'isqrt() argument must be nonnegative', 'isqrt message'
+
+try:
+    math.comb(-1, 2)
+    assert False, 'negative n should raise'
+except ValueError as e:
+    assert str(e) == 'n must be a non-negative integer', 'comb n message'
+
+try:
+    math.perm(3, -1)
+    assert False, 'negative k should raise'
+except ValueError as e:
+    assert str(e) == 'k must be a non-negative integer', 'perm k message'
+
+try:
+    math.gcd(1.5)
+    assert False, 'float gcd should raise'
+except TypeError as e:
+    assert str(e) == "'float' object cannot be interpreted as an integer", 'gcd float message'
+
+# === argument errors ===
+try:
+    math.sqrt('a')
+    assert False, 'str sqrt should raise'
+except TypeError as e:
+    assert str(e) == 'must be real number, not str', 'sqrt str message'
+
+try:
+    math.floor(None)
+    assert False, 'None floor should raise'
+except TypeError as e:
+    assert str(e) == 'must be real number, not NoneType', 'floor None message'
+
+try:
+    math.trunc('a')
+    assert False, 'str trunc should raise'
+except TypeError as e:
+    assert str(e) == "type str doesn't define __trunc__ method", 'trunc str message'
+
+try:
+    math.sqrt(1, 2)
+    assert False, 'two args should raise'
+except TypeError as e:
+    assert str(e) == 'math.sqrt() takes exactly one argument (2 given)', 'sqrt args message'
+
+try:
+    math.atan2(1)
+    assert False, 'one arg should raise'
+except TypeError as e:
+    assert str(e) == 'atan2 expected 2 arguments, got 1', 'atan2 args message'
+
+try:
+    math.log()
+    assert False, 'no args should raise'
+except TypeError as e:
+    assert str(e) == 'log expected at least 1 argument, got 0', 'log args message'
+
+try:
+    math.isclose(1)
+    assert False, 'one arg should raise'
+except TypeError as e:
+    assert str(e) == "isclose() missing required argument 'b' (pos 2)", 'isclose args message'
+
+try:
+    math.prod([1], 2)
+    assert False, 'positional start should raise'
+except TypeError as e:
+    assert str(e) == 'prod() takes exactly 1 positional argument (2 given)', 'prod args message'
@@ -0,0 +1,76 @@
+# === Construction ===
+m = memoryview(b'hello')
+assert type(m) is memoryview, 'type is memoryview'
+assert len(m) == 5, 'len'
+assert m.nbytes == 5, 'nbytes'
+assert m.readonly, 'views are read-only'
+assert m.obj == b'hello', 'obj is the source'
+assert memoryview(m) == b'hello', 'view of a view'
+assert not memoryview(b''), 'empty view is falsy'
+assert m, 'non-empty view is truthy'
+
+# === Reading ===
+assert m[0] == 104, 'index returns int'
+assert m[-1] == 111, 'negative index'
+assert m.tobytes() == b'hello', 'tobytes'
+assert m.tolist() == [104, 101, 108, 108, 111], 'tolist'
+assert m.hex() == '68656c6c6f', 'hex'
+assert list(m) == [104, 101, 108, 108, 111], 'iteration yields ints'
+assert bytes(m) == b'hello', 'bytes from view'
+assert bytearray(m) == b'hello', 'bytearray from view'
+assert 101 in m, 'int in view'
+assert b'ell' not in m, 'views only contain ints'
+
+# === Slicing ===
+s = m[1:4]
+assert type(s) is memoryview, 'slice is a view'
+assert s.tobytes() == b'ell', 'slice contents'
+assert m[::2].tobytes() == b'hlo', 'extended slice'
+assert m[::-1].tobytes() == b'olleh', 'reversed slice'
+assert m[1:4][::-1].tobytes() == b'lle', 'slice of a slice'
+assert m[3:1].tobytes() == b'', 'empty slice'
+assert len(m[::3]) == 2, 'len of extended slice'
+
+# === Equality and hashing ===
+assert m == b'hello', 'view equals bytes'
+assert b'hello' == m, 'bytes equals view'
+assert m == memoryview(b'hello'), 'view equals view'
+assert m[1:3] == b'el', 'slice equals bytes'
+assert m != b'world', 'different contents'
+assert hash(m) == hash(b'hello'), 'hash matches bytes'
+assert b'x' + m[0:2] == b'xhe', 'bytes + view'
+
+# === Views of bytearrays see changes ===
+ba = bytearray(b'abc')
+v = memoryview(ba)
+ba[0] = 120
+assert v[0] == 120, 'view sees item assignment'
+assert v.tobytes() == b'xbc', 'tobytes sees changes'
+ba[1:3] = v[0:2]
+assert ba == b'xxb', 'slice assignment from a view of itself'
+assert ba.find(v[1:3]) == 1, 'find with a view of itself'
+
+# === Errors ===
+try:
+    m[0] = 1
+    assert False, 'assignment should raise'
+except TypeError as e:
+    assert str(e) == 'cannot modify read-only memory', 'read-only message'
+
+try:
+    m[10]
+    assert False, 'index out of range should raise'
+except IndexError as e:
+    assert str(e) == 'index out of bounds on dimension 1', 'index error message'
+
+try:
+    memoryview('abc')
+    assert False, 'str arg should raise'
+except TypeError as e:
+    assert str(e) == "memoryview: a bytes-like object is required, not 'str'", 'constructor message'
+
+try:
+    hash(memoryview(bytearray(b'a')))
+    assert False, 'hashing a view of a bytearray should raise'
+except (TypeError, ValueError):
+    pass
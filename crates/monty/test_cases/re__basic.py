@@ -0,0 +1,300 @@
+import re
+
+# === search, match and fullmatch ===
+m = re.search(r'\d+', 'abc 123 def')
+assert m is not None, 'search finds digits'
+assert m.group() == '123', 'search group'
+assert m.span() == (4, 7), 'search span'
+assert m.start() == 4 and m.end() == 7, 'search start and end'
+assert re.search(r'\d', 'abc') is None, 'search without match'
+assert re.match(r'\d+', 'abc 123') is None, 'match is anchored at the start'
+assert re.match(r'[a-z]+', 'abc 123').group() == 'abc', 'match at the start'
+assert re.fullmatch(r'[a-z]+', 'abc') is not None, 'fullmatch whole string'
+assert re.fullmatch(r'[a-z]+', 'abc1') is None, 'fullmatch partial string'
+assert re.fullmatch(r'a|ab', 'ab').group() == 'ab', 'fullmatch tries longer alternatives'
+assert bool(re.search('a', 'a')), 'matches are truthy'
+
+# === groups ===
+m = re.match(r'(\w+) (\w+)(!)?', 'hello world')
+assert m.group(0) == 'hello world', 'group 0'
+assert m.group(1) == 'hello', 'group 1'
+assert m.group(2) == 'world', 'group 2'
+assert m.group(3) is None, 'unmatched group is None'
+assert m.group(1, 2) == ('hello', 'world'), 'several groups'
+assert m[1] == 'hello', 'getitem'
+assert m.groups() == ('hello', 'world', None), 'groups'
+assert m.groups('') == ('hello', 'world', ''), 'groups default'
+assert m.groups(default='-') == ('hello', 'world', '-'), 'groups default keyword'
+assert m.start(2) == 6, 'start of group'
+assert m.span(3) == (-1, -1), 'span of unmatched group'
+assert m.lastindex == 2, 'lastindex'
+assert m.lastgroup is None, 'lastgroup without names'
+
+m = re.match(r'(?P<first>\w+) (?P<last>\w+)', 'Jane Doe')
+assert m.group('first') == 'Jane', 'named group'
+assert m['last'] == 'Doe', 'named getitem'
+assert m.groupdict() == {'first': 'Jane', 'last': 'Doe'}, 'groupdict'
+assert m.lastgroup == 'last', 'lastgroup'
+assert m.start('last') == 5, 'start of named group'
+
+m = re.match(r'(?P<a>x)(?P<b>y)?', 'x')
+assert m.groupdict('?') == {'a': 'x', 'b': '?'}, 'groupdict default'
+
+m = re.search('b', 'abc')
+assert m.string == 'abc', 'match string'
+assert m.pos == 0 and m.endpos == 3, 'match pos and endpos'
+assert m.lastindex is None, 'lastindex without groups'
+
+try:
+    m.group(5)
+    assert False, 'missing group should raise'
+except IndexError as e:
+    assert str(e) == 'no such group', 'missing group message'
+
+try:
+    m.group('nope')
+    assert False, 'missing named group should raise'
+except IndexError as e:
+    assert str(e) == 'no such group', 'missing named group message'
+
+# === unicode ===
+m = re.search(r'\w+', '¡héllo wörld!')
+assert m.group() == 'héllo', 'unicode word'
+assert m.span() == (1, 6), 'spans count characters'
+assert re.findall(r'\d', 'a1٣') == ['1', '٣'], 'unicode digits'
+assert re.findall(r'\d', 'a1٣', re.ASCII) == ['1'], 'ascii digits'
+assert re.findall(r'\w+', 'naïve café', re.A) == ['na','ve', 'caf'], 'ascii words'
+assert re.sub('.', '-', 'né😀') == '---', 'dot matches any character'
+
+# === compiled patterns ===
+p = re.compile(r'(\d+)-(\d+)')
+assert p.pattern == r'(\d+)-(\d+)', 'pattern attribute'
+assert p.groups == 2, 'groups attribute'
+assert p.flags == re.UNICODE, 'default flags'
+assert p.search('call 555-1234 now').groups() == ('555', '1234'), 'pattern search'
+assert p.match('555-1234').group(2) == '1234', 'pattern match'
+assert p.fullmatch('555-1234x') is None, 'pattern fullmatch'
+assert re.compile(p) is p, 'compile returns compiled patterns'
+assert re.search(p, 'x 1-2').group() == '1-2', 'module functions accept patterns'
+assert p.search('1-2').re is p, 'match re'
+assert re.compile('(?P<x>a)(b)(?P<y>c)').groupindex == {'x': 1, 'y': 3}, 'groupindex'
+assert re.compile('a') == re.compile('a'), 'patterns compare by pattern'
+assert re.compile('a') != re.compile('a', re.I), 'patterns compare by flags'
+assert hash(re.compile('a')) == hash(re.compile('a')), 'patterns hash by pattern'
+assert repr(re.compile('a+')) == "re.compile('a+')", 'pattern repr'
+assert repr(re.compile('a', re.I | re.M)) == "re.compile('a', re.IGNORECASE|re.MULTILINE)", 'pattern repr flags'
+assert repr(re.search('b+', 'abbc')) == "<re.Match object; span=(1, 3), match='bb'>", 'match repr'
+
+# === pos and endpos ===
+p = re.compile(r'\d')
+assert p.search('a1b2', 2).group() == '2', 'search from pos'
+assert p.search('a1b2', 0, 1) is None, 'search before endpos'
+assert p.match('a1', 1).group() == '1', 'match at pos'
+assert p.findall('1234', 1, 3) == ['2', '3'], 'findall between pos and endpos'
+assert p.search('12', 5) is None, 'pos past the end'
+m = p.search('a1b2', pos=2, endpos=10)
+assert m.pos == 2 and m.endpos == 4, 'pos and endpos clamped'
+assert re.compile('^a').search('ba', 1) is None, 'caret still means start of string'
+
+# === flags ===
+assert re.search('abc', 'xABCx', re.IGNORECASE).group() == 'ABC', 'ignorecase'
+assert re.search('abc', 'xABCx', re.I) is not None, 'ignorecase alias'
+assert re.findall('^a', 'a\na', re.MULTILINE) == ['a', 'a'], 'multiline'
+assert re.findall('^a', 'a\na') == ['a'], 'no multiline'
+m = re.search(r'(\d+)$', 'value 42\n')
+assert m.group(0) == '42' and m.group(1) == '42', 'dollar matches before a final newline'
+assert m.span() == (6, 8), 'dollar match excludes the final newline'
+assert re.sub(r'\d+$', 'X', 'a 42\n') == 'a X\n', 'sub before a final newline'
+assert re.findall(r'a$', 'a\na\n') == ['a'], 'dollar only before the last newline'
+assert re.findall(r'a$', 'a\na\n', re.MULTILINE) == ['a', 'a'], 'multiline dollar'
+assert re.fullmatch(r'a$', 'a\n') is None, 'fullmatch does not skip the final newline'
+assert re.compile(r'(a)$(b)?').groups == 2, 'dollar adds no groups'
+assert re.search('a.b', 'a\nb') is None, 'dot without dotall'
+assert re.search('a.b', 'a\nb', re.DOTALL) is not None, 'dotall'
+assert re.search('(?i)abc', 'ABC') is not None, 'inline flag'
+assert re.compile('(?im)a').flags == re.I | re.M | re.U, 'inline flags in pattern flags'
+assert re.search('a(?i:b)c', 'aBc') is not None, 'scoped inline flag'
+assert re.search('a(?i:b)c', 'aBC') is None, 'scoped inline flag ends'
+verbose = re.compile(
+    r"""
+    (\d{3})  # area code
+    [-\ ]    # separator
+    (\d{4})  # number
+    """,
+    re.VERBOSE,
+)
+assert verbose.match('555 1234').groups() == ('555', '1234'), 'verbose'
+assert re.search('[# ]', 'a b', re.X).group() == ' ', 'verbose keeps classes'
+assert re.NOFLAG == 0, 'NOFLAG'
+assert re.S == re.DOTALL and re.X == re.VERBOSE and re.A == re.ASCII, 'flag aliases'
+
+# === syntax ===
+assert re.search('a{2}', 'caaat').group() == 'aa', 'repeat'
+assert re.search('a{2,}', 'caaat').group() == 'aaa', 'open repeat'
+assert re.search('a{,2}b', 'aaab').group() == 'aab', 'repeat without minimum'
+assert re.search('a{x}', 'a{x}').group() == 'a{x}', 'brace that is not a repeat'
+assert re.search('a}', 'a}').group() == 'a}', 'closing brace'
+assert re.search('[]a]+', 'x]a]').group() == ']a]', 'bracket first in class'
+assert re.search('[a-c&~]+', 'x&b~').group() == '&b~', 'class punctuation'
+assert re.search(r'[\b]', 'a\bb').group() == '\b', 'backspace in class'
+assert re.search(r'\x41é\U0001F600', 'Aé😀') is not None, 'hex escapes'
+assert re.search(r'\101\0', 'A\0') is not None, 'octal escapes'
+assert re.search(r'\.\*\-', '.*-') is not None, 'escaped punctuation'
+assert re.search(r'a\Z', 'ba') is not None, 'end of string'
+assert re.search(r'\Aa', 'ab') is not None, 'start of string'
+assert re.findall(r'\bfoo\b', 'foo foobar (foo)') == ['foo', 'foo'], 'word boundary'
+assert re.findall('a(?#comment)b', 'ab') == ['ab'], 'comment group'
+assert re.findall('(?:ab)+', 'ababxab') == ['abab', 'ab'], 'non-capturing group'
+assert re.search('a*?b', 'aaab').group() == 'aaab', 'lazy repeat'
+assert re.search('<.+?>', '<a><b>').group() == '<a>', 'lazy stops early'
+
+# === findall and finditer ===
+assert re.findall(r'\d+', 'a1b22c333') == ['1', '22', '333'], 'findall'
+assert re.findall(r'(\w)=(\d)', 'a=1 b=2') == [('a', '1'), ('b', '2')], 'findall groups'
+assert re.findall(r'(\w)=\d', 'a=1 b=2') == ['a', 'b'], 'findall one group'
+assert re.findall(r'(a)|(b)', 'ab') == [('a', ''), ('', 'b')], 'findall unmatched groups'
+assert re.findall('x*', 'axb') == ['', 'x', '', ''], 'findall empty matches'
+assert re.findall('', 'ab') == ['', '', ''], 'findall empty pattern'
+assert re.findall('z', 'abc') == [], 'findall no match'
+spans = [m.span() for m in re.finditer(r'\d+', 'a1b22')]
+assert spans == [(1, 2), (3, 5)], 'finditer spans'
+it = re.finditer('a', 'aa')
+assert next(it).start() == 0, 'finditer is an iterator'
+assert len(list(it)) == 1, 'finditer rest'
+
+# === sub and subn ===
+assert re.sub(r'\d', '#', 'a1b2') == 'a#b#', 'sub'
+assert re.sub(r'\d', '#', 'a1b2', count=1) == 'a#b2', 'sub count'
+assert re.sub(r'\d', '#', 'a1b2', 1) == 'a#b2', 'sub positional count'
+assert re.sub(r'(\w+)@(\w+)', r'\2 at \1', 'me@host') == 'host at me', 'sub group references'
+assert re.sub(r'(?P<w>\w+)', r'<\g<w>>', 'a b') == '<a> <b>', 'sub named reference'
+assert re.sub(r'(\w)', r'\g<1>\g<0>', 'ab') == 'aabb', 'sub numbered reference'
+assert re.sub('a', r'\n\t\\', 'a') == '\n\t\\', 'sub template escapes'
+assert re.sub('a', r'\%', 'a') == '\\%', 'sub keeps unknown punctuation escapes'
+assert re.sub('(a)|b', r'[\1]', 'ab') == '[a][]', 'sub unmatched group is empty'
+assert re.sub('x*', '-', 'abxd') == '-a-b--d-', 'sub empty matches'
+assert re.sub('a', 'b', 'xyz') == 'xyz', 'sub without match'
+assert re.subn(r'\d', '#', 'a1b2c3') == ('a#b#c#', 3), 'subn'
+assert re.subn(r'\d', '#', 'a1b2c3', 2) == ('a#b#c3', 2), 'subn count'
+assert re.compile('o').sub('0', 'foo') == 'f00', 'pattern sub'
+assert re.compile('o').subn('0', 'foo', count=1) == ('f0o', 1), 'pattern subn'
+
+
+def double(m):
+    return str(int(m.group()) * 2)
+
+
+assert re.sub(r'\d+', double, 'a1 b21') == 'a2 b42', 'sub with function'
+assert re.compile(r'\d+').sub(double, '5 6') == '10 12', 'pattern sub with function'
+assert re.subn('[aeiou]', lambda m: m.group().upper(), 'banana') == ('bAnAnA', 3), 'subn with lambda'
+
+try:
+    re.sub('a', lambda m: 1, 'a')
+    assert False, 'non-str replacement should raise'
+except TypeError as e:
+    assert 'expected str instance, int found' in str(e), 'non-str replacement message'
+
+# === expand ===
+m = re.match(r'(?P<k>\w+)=(\w+)', 'key=value')
+assert m.expand(r'\2 <- \g<k>') == 'value <- key', 'expand'
+
+# === split ===
+assert re.split(r'\s+', 'a b  c') == ['a', 'b', 'c'], 'split'
+assert re.split(r'(,)', 'a,b') == ['a', ',', 'b'], 'split keeps groups'
+assert re.split(r'(,)|;', 'a,b;c') == ['a', ',', 'b', None, 'c'], 'split unmatched group is None'
+assert re.split(',', 'a,b,c', maxsplit=1) == ['a', 'b,c'], 'split maxsplit'
+assert re.split(',', 'a,b,c', 1) == ['a', 'b,c'], 'split positional maxsplit'
+assert re.split('x*', 'axb') == ['', 'a', '', 'b', ''], 'split on empty matches'
+assert re.split(',', '') == [''], 'split empty string'
+assert re.compile(r'\W+').split('a, b') == ['a', 'b'], 'pattern split'
+
+# === escape ===
+assert re.escape('a.b*c') == r'a\.b\*c', 'escape'
+assert re.escape('1+1=2') == r'1\+1=2', 'escape keeps other punctuation'
+assert re.escape('a b\n') == 'a\\ b\\\n', 'escape whitespace'
+assert re.search(re.escape('(x)'), 'f(x)') is not None, 'escaped pattern matches literally'
+
+# === errors ===
+try:
+    re.compile('(')
+    assert False, 'unbalanced parenthesis should raise'
+except re.error:
+    pass
+
+try:
+    re.compile('a**')
+    assert False, 'repeated repeat should raise'
+except re.error as e:
+    assert isinstance(e, re.PatternError), 'error alias'
+
+try:
+    re.compile(r'\q')
+    assert False, 'bad escape should raise'
+except re.error as e:
+    assert str(e) == r'bad escape \q at position 0', 'bad escape message'
+
+try:
+    re.compile('a', re.ASCII | re.UNICODE)
+    assert False, 'incompatible flags should raise'
+except ValueError as e:
+    assert str(e) == 'ASCII and UNICODE flags are incompatible', 'incompatible flags message'
+
+try:
+    re.compile(re.compile('a'), re.I)
+    assert False, 'flags with compiled pattern should raise'
+except ValueError as e:
+    assert str(e) == 'cannot process flags argument with a compiled pattern', 'compiled flags message'
+
+try:
+    re.search('a', 1)
+    assert False, 'non-str string should raise'
+except TypeError as e:
+    assert str(e) == "expected string or bytes-like object, got 'int'", 'non-str string message'
+
+try:
+    re.search('a', b'a')
+    assert False, 'bytes string should raise'
+except TypeError as e:
+    assert str(e) == 'cannot use a string pattern on a bytes-like object', 'bytes string message'
+
+try:
+    re.search(1, 'a')
+    assert False, 'non-str pattern should raise'
+except TypeError as e:
+    assert str(e) == 'first argument must be string or compiled pattern', 'non-str pattern message'
+
+try:
+    re.search('a')
+    assert False, 'missing argument should raise'
+except TypeError as e:
+    assert str(e) == "search() missing 1 required positional argument: 'string'", 'missing argument message'
+
+try:
+    re.search('a', 'a', foo=1)
+    assert False, 'unknown keyword should raise'
+except TypeError as e:
+    assert str(e) == "search() got an unexpected keyword argument 'foo'", 'unknown keyword message'
+
+try:
+    re.sub('(a)', r'\2', 'a')
+    assert False, 'invalid group reference should raise'
+except re.error as e:
+    assert str(e) == 'invalid group reference 2 at position 1', 'invalid group reference message'
+
+try:
+    re.sub('(a)', r'\g<x>', 'a')
+    assert False, 'unknown group name should raise'
+except IndexError as e:
+    assert str(e) == "unknown group name 'x'", 'unknown group name message'
+
+try:
+    re.sub('(a)', r'\g<1', 'a')
+    assert False, 'unterminated group name should raise'
+except re.error as e:
+    assert str(e) == 'missing >, unterminated name at position 3', 'unterminated group name message'
+
+try:
+    re.sub('a', r'\q', 'a')
+    assert False, 'bad template escape should raise'
+except re.error as e:
+    assert str(e) == r'bad escape \q at position 0', 'bad template escape message'
@@ -0,0 +1,23 @@
+# call-external
+# === Patterns and matches restored from a snapshot ===
+# Each external call dumps and loads the run, so the compiled patterns are rebuilt on first use
+import re
+
+pattern = re.compile(r'(?P<key>\w+)=(?P<value>\d+)$')
+m = pattern.search('retries=3\n')
+total = add_ints(1, 2)
+assert total == 3, 'external call result'
+
+assert pattern.groups == 2, 'groups after restore'
+assert pattern.groupindex == {'key': 1, 'value': 2}, 'groupindex after restore'
+assert pattern.search('timeout=30').group('value') == '30', 'search after restore'
+assert pattern.sub(r'\g<key>: \g<value>', 'a=1') == 'a: 1', 'sub after restore'
+
+assert m.group('key', 'value') == ('retries', '3'), 'match groups after restore'
+assert m.groupdict() == {'key': 'retries', 'value': '3'}, 'groupdict after restore'
+assert m.lastgroup == 'value', 'lastgroup after restore'
+assert m.expand(r'\2 \1') == '3 retries', 'expand after restore'
+
+matches = re.finditer(r'\d', 'a1b2')
+assert add_ints(0, 0) == 0, 'second external call'
+assert [m.group() for m in matches] == ['1', '2'], 'finditer after restore'
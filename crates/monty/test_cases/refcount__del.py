@@ -0,0 +1,10 @@
+inner = [1]
+outer = [inner, inner, inner]
+del outer[0]
+del outer[:1]
+d = {'k': inner}
+del d['k']
+tmp = [inner]
+del tmp
+outer
+# ref-counts={'inner': 2, 'outer': 2, 'd': 1}
@@ -0,0 +1,20 @@
+# Test that exceptions hold references to their cause and context, and release them when freed.
+try:
+    try:
+        raise ValueError('inner')
+    except ValueError as e:
+        raise TypeError('outer') from e
+except TypeError as e:
+    outer = e
+cause = outer.__cause__
+context = outer.__context__
+
+# Chained exceptions that aren't kept are freed along with the exception raised from them
+try:
+    try:
+        raise KeyError('dropped')
+    except KeyError:
+        raise IndexError('dropped')
+except IndexError:
+    pass
+# ref-counts={'outer': 1, 'cause': 4, 'context': 4}
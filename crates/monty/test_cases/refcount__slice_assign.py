@@ -0,0 +1,6 @@
+inner = [1]
+outer = [inner, inner, inner]
+outer[::2] = [0, inner]
+outer[:2] = [inner]
+outer
+# ref-counts={'inner': 3, 'outer': 2}
@@ -0,0 +1,8 @@
+inner = [1]
+other = [2]
+items = [inner, other]
+lst = [*items, inner]
+t = (*items,)
+d = {**{'a': inner}, 'a': other}
+d
+# ref-counts={'inner': 5, 'other': 5, 'items': 1, 'lst': 1, 't': 1, 'd': 2}
@@ -0,0 +1,195 @@
+# === automatic and manual numbering ===
+assert '{} and {}'.format('a', 'b') == 'a and b', 'automatic numbering'
+assert '{1} {0} {1}'.format('a', 'b') == 'b a b', 'manual numbering'
+assert '{name} is {age}'.format(name='Bob', age=30) == 'Bob is 30', 'keyword fields'
+assert '{0} {x}'.format(1, x=2) == '1 2', 'positional and keyword fields'
+assert 'no fields'.format(1, 2) == 'no fields', 'unused arguments are ignored'
+assert '{{}} {{{}}}'.format(5) == '{} {5}', 'escaped braces'
+assert ''.format() == '', 'empty template'
+
+# === format specs ===
+assert '{:>6}'.format('ab') == '    ab', 'right align'
+assert '{:*^7}'.format('mid') == '**mid**', 'center with fill'
+assert '{:05d}'.format(42) == '00042', 'zero padded int'
+assert '{:.3f}'.format(3.14159) == '3.142', 'float precision'
+assert '{0:x} {0:o} {0:b}'.format(10) == 'a 12 1010', 'int bases'
+assert '{:+d}'.format(7) == '+7', 'sign'
+assert '{:.1%}'.format(0.25) == '25.0%', 'percentage'
+
+# === nested specs ===
+assert '{:>{}}'.format('x', 4) == '   x', 'nested width with automatic numbering'
+assert '{0:{1}{2}}'.format('y', '>', 3) == '  y', 'nested align and width'
+assert '{v:.{p}f}'.format(v=2.5, p=2) == '2.50', 'nested precision with keywords'
+
+# === conversions ===
+assert '{!r}'.format('hi') == "'hi'", 'repr conversion'
+assert '{!s}'.format(1.5) == '1.5', 'str conversion'
+assert '{!a}'.format('é') == "'\\xe9'", 'ascii conversion'
+assert '{!r:>6}'.format('a') == "   'a'", 'conversion with spec'
+
+# === attribute and index lookup ===
+assert '{0[1]}'.format([10, 20]) == '20', 'list index'
+assert '{[0]}'.format((7, 8)) == '7', 'tuple index with automatic numbering'
+assert '{d[key]}'.format(d={'key': 'v'}) == 'v', 'dict key'
+assert '{0[a][1]}'.format({'a': [1, 2]}) == '2', 'chained index'
+
+
+class Point:
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+
+p = Point(3, 4)
+assert '({0.x}, {0.y})'.format(p) == '(3, 4)', 'attribute lookup'
+assert '{p.x:>3}'.format(p=p) == '  3', 'attribute lookup with spec'
+
+# === format_map ===
+assert '{a}-{b}'.format_map({'a': 1, 'b': 2}) == '1-2', 'format_map with dict'
+assert '{a[0]:>3}'.format_map({'a': 'xyz'}) == '  x', 'format_map with index and spec'
+
+# === format() builtin ===
+assert format(42) == '42', 'format without spec'
+assert format(3.14159, '.2f') == '3.14', 'format float'
+assert format('abc', '>5') == '  abc', 'format str'
+assert format(None) == 'None', 'format None'
+
+
+# === __format__ ===
+class Money:
+    def __init__(self, amount):
+        self.amount = amount
+
+    def __format__(self, spec):
+        if spec == 'short':
+            return f'${self.amount}'
+        return f'{self.amount:.2f} dollars'
+
+
+m = Money(5)
+assert format(m, 'short') == '$5', 'format() calls __format__'
+assert format(m) == '5.00 dollars', 'format() passes an empty spec'
+assert f'{m:short}' == '$5', 'f-string calls __format__'
+assert f'{m}' == '5.00 dollars', 'f-string without spec calls __format__'
+assert '{:short}'.format(m) == '$5', 'str.format calls __format__'
+assert '{0:short} / {0}'.format(m) == '$5 / 5.00 dollars', 'str.format passes spec'
+
+
+class Spec:
+    def __format__(self, spec):
+        return spec
+
+
+s = Spec()
+assert f'{s:#x}' == '#x', 'f-string passes alternate form unchanged'
+assert f'{s:,d}' == ',d', 'f-string passes grouping unchanged'
+assert f'{s:>10}' == '>10', 'f-string passes align without fill unchanged'
+assert f'{s: >10}' == ' >10', 'f-string passes explicit space fill unchanged'
+assert f'{s:*^+012_.3f}' == '*^+012_.3f', 'f-string passes full spec unchanged'
+assert format(s, '#o') == '#o', 'format() passes spec unchanged'
+
+
+class Named:
+    def __str__(self):
+        return 'named'
+
+    def __repr__(self):
+        return 'Named()'
+
+
+n = Named()
+assert format(n) == 'named', 'format() without __format__ uses __str__'
+assert '{}'.format(n) == 'named', 'str.format without __format__ uses __str__'
+assert '{!r}'.format(n) == 'Named()', 'repr conversion calls __repr__'
+assert '{!s:>7}'.format(n) == '  named', 'str conversion with spec'
+
+# === errors ===
+try:
+    '{0}{}'.format(1, 2)
+    assert False, 'switching to automatic numbering should raise'
+except ValueError as e:
+    assert str(e) == 'cannot switch from manual field specification to automatic field numbering', str(e)
+
+try:
+    '{}{0}'.format(1, 2)
+    assert False, 'switching to manual numbering should raise'
+except ValueError as e:
+    assert str(e) == 'cannot switch from automatic field numbering to manual field specification', str(e)
+
+try:
+    '{} {}'.format(1)
+    assert False, 'missing positional argument should raise'
+except IndexError as e:
+    assert str(e) == 'Replacement index 1 out of range for positional args tuple', str(e)
+
+try:
+    '{missing}'.format(a=1)
+    assert False, 'missing keyword argument should raise'
+except KeyError as e:
+    assert repr(e) == "KeyError('missing')", repr(e)
+
+try:
+    '{'.format()
+    assert False, 'single open brace should raise'
+except ValueError as e:
+    assert str(e) == "Single '{' encountered in format string", str(e)
+
+try:
+    'a}'.format()
+    assert False, 'single close brace should raise'
+except ValueError as e:
+    assert str(e) == "Single '}' encountered in format string", str(e)
+
+try:
+    '{0!x}'.format(1)
+    assert False, 'unknown conversion should raise'
+except ValueError as e:
+    assert str(e) == 'Unknown conversion specifier x', str(e)
+
+try:
+    '{0:{1:{2}}}'.format(1, 2, 3)
+    assert False, 'deeply nested specs should raise'
+except ValueError as e:
+    assert str(e) == 'Max string recursion exceeded', str(e)
+
+try:
+    '{}'.format_map({})
+    assert False, 'positional field in format_map should raise'
+except ValueError as e:
+    assert str(e) == 'Format string contains positional fields', str(e)
+
+try:
+    '{:s}'.format(1)
+    assert False, 'unknown format code should raise'
+except ValueError as e:
+    assert str(e) == "Unknown format code 's' for object of type 'int'", str(e)
+
+try:
+    '{.nope}'.format([])
+    assert False, 'missing attribute should raise'
+except AttributeError as e:
+    assert str(e) == "'list' object has no attribute 'nope'", str(e)
+
+try:
+    format(1, 2)
+    assert False, 'non-str spec should raise'
+except TypeError as e:
+    assert str(e) == 'format() argument 2 must be str, not int', str(e)
+
+try:
+    format(p, '>5')
+    assert False, 'spec without __format__ should raise'
+except TypeError as e:
+    assert str(e) == 'unsupported format string passed to Point.__format__', str(e)
+
+
+class BadFormat:
+    def __format__(self, spec):
+        return 1
+
+
+try:
+    format(BadFormat())
+    assert False, 'non-str __format__ result should raise'
+except TypeError as e:
+    assert str(e) == '__format__ must return a str, not int', str(e)
@@ -0,0 +1,227 @@
+# === basic conversions ===
+assert 'hello %s' % 'world' == 'hello world', 'single non-tuple argument'
+assert '%s and %s' % ('a', 'b') == 'a and b', 'tuple of arguments'
+assert '%d%%' % 50 == '50%', 'escaped percent'
+assert '%s' % ((1, 2),) == '(1, 2)', 'tuple wrapped in a tuple'
+assert '%s' % [] == '[]', 'list as the only argument'
+assert '%s' % None == 'None', 'None argument'
+assert '%r' % 'a' == "'a'", 'repr conversion'
+assert '%a' % 'é' == "'\\xe9'", 'ascii conversion'
+assert '%i %u' % (1, 2) == '1 2', 'i and u are aliases of d'
+assert '%ld' % 3 == '3', 'length modifiers are ignored'
+assert 'abc' % () == 'abc', 'no specifiers and no arguments'
+
+# === integers ===
+assert '%.3d' % 5 == '005', 'precision sets minimum digits'
+assert '%#x' % 255 == '0xff', 'alternate hex'
+assert '%#X' % 255 == '0XFF', 'alternate upper hex'
+assert '%#o' % 8 == '0o10', 'alternate octal'
+assert '%x %X %o' % (-255, 255, -8) == '-ff FF -10', 'negative bases'
+assert '%+05d' % 3 == '+0003', 'sign with zero padding'
+assert '% d' % 3 == ' 3', 'space sign'
+assert '%-5d|' % 3 == '3    |', 'left aligned'
+assert '%d' % 3.99 == '3', 'floats are truncated for %d'
+assert '%d %x' % (True, True) == '1 1', 'bools are ints'
+assert '%d' % 10**30 == '1000000000000000000000000000000', 'long int'
+assert '%x' % 10**20 == '56bc75e2d63100000', 'long int hex'
+
+# === floats ===
+assert '%5.1f' % 3.14159 == '  3.1', 'width and precision'
+assert '%08.3f' % -3.14159 == '-003.142', 'zero padding after the sign'
+assert '%f' % 2 == '2.000000', 'int for %f'
+assert '%.0f' % 2.5 == '2', 'round half to even'
+assert '%e' % 12345.678 == '1.234568e+04', 'exponent'
+assert '%E' % 0.5 == '5.000000E-01', 'upper exponent'
+assert '%+.2e' % 1.5 == '+1.50e+00', 'exponent with sign'
+assert '%g' % 1e20 == '1e+20', 'general large'
+assert '%g' % 0.0001 == '0.0001', 'general small'
+assert '%G' % 1e-10 == '1E-10', 'upper general'
+assert '%#g' % 1.0 == '1.00000', 'alternate general keeps zeros'
+assert '%10.4g' % 3.14159265 == '     3.142', 'general with width'
+assert '%f' % float('inf') == 'inf', 'infinity'
+assert '%F' % float('inf') == 'INF', 'upper infinity'
+assert '%05f' % float('nan') == '00nan', 'nan is zero padded like numbers'
+
+# === strings ===
+assert '%5s|%-5s|' % ('a', 'b') == '    a|b    |', 'string alignment'
+assert '%.2s' % 'abc' == 'ab', 'string precision truncates'
+assert '%.3s' % 1.23456 == '1.2', 'precision truncates str() of value'
+assert '%c%c' % (72, 'i') == 'Hi', 'char from int and str'
+assert '%c' % 'é' == 'é', 'non-ascii char'
+assert '%s' % b'x' == "b'x'", 'bytes in a str template'
+
+# === star width and precision ===
+assert '%*.*f' % (8, 2, 3.14159) == '    3.14', 'width and precision from arguments'
+assert '%-*d|' % (4, 7) == '7   |', 'left aligned star width'
+assert '%*d' % (-4, 7) == '7   ', 'negative star width left aligns'
+
+# === mapping keys ===
+assert '%(x)s %(y)d' % {'x': 1, 'y': 2} == '1 2', 'mapping keys'
+assert '%(x)05.1f' % {'x': 2.25} == '002.2', 'mapping key with spec'
+assert '%s %(a)s' % {'a': 1} == "{'a': 1} 1", 'positional before mapping key uses the mapping'
+assert 'abc' % {} == 'abc', 'unused mapping is allowed'
+assert 'abc' % [] == 'abc', 'unused list is allowed'
+
+
+# === instances ===
+class Named:
+    def __str__(self):
+        return 'named'
+
+    def __repr__(self):
+        return 'Named()'
+
+
+assert '%s|%r' % (Named(), Named()) == 'named|Named()', '__str__ and __repr__'
+assert '%8s' % Named() == '   named', '__str__ with width'
+
+# === modulo comparison ===
+s = 'x%s'
+assert s % 1 == 'x1', 'format compared with a constant'
+
+# === bytes ===
+assert b'%s %r' % (b'x', b'y') == b"x b'y'", 'bytes s and r'
+assert b'%d-%x' % (3, 255) == b'3-ff', 'bytes ints'
+assert b'%c%c' % (65, b'B') == b'AB', 'bytes char'
+assert b'%a' % 'é' == b"'\\xe9'", 'bytes ascii'
+assert b'%5b|' % b'x' == b'    x|', 'bytes b conversion'
+assert b'%(k)s' % {b'k': b'v'} == b'v', 'bytes mapping key'
+
+# === errors ===
+try:
+    '%s %s' % (1,)
+    assert False, 'too few arguments should raise'
+except TypeError as e:
+    assert str(e) == 'not enough arguments for format string', str(e)
+
+try:
+    '%s' % (1, 2)
+    assert False, 'too many arguments should raise'
+except TypeError as e:
+    assert str(e) == 'not all arguments converted during string formatting', str(e)
+
+try:
+    'abc' % 1
+    assert False, 'unused argument should raise'
+except TypeError as e:
+    assert str(e) == 'not all arguments converted during string formatting', str(e)
+
+try:
+    b'x' % 1
+    assert False, 'unused bytes argument should raise'
+except TypeError as e:
+    assert str(e) == 'not all arguments converted during bytes formatting', str(e)
+
+try:
+    '%(a)s %s' % {'a': 1}
+    assert False, 'positional after mapping key should raise'
+except TypeError as e:
+    assert str(e) == 'not enough arguments for format string', str(e)
+
+try:
+    '%(a)s' % 1
+    assert False, 'mapping key without a mapping should raise'
+except TypeError as e:
+    assert str(e) == 'format requires a mapping', str(e)
+
+try:
+    '%(a)s' % {'b': 1}
+    assert False, 'missing mapping key should raise'
+except KeyError as e:
+    assert repr(e) == "KeyError('a')", repr(e)
+
+try:
+    '%' % ()
+    assert False, 'incomplete format should raise'
+except ValueError as e:
+    assert str(e) == 'incomplete format', str(e)
+
+try:
+    '%(a' % {'a': 1}
+    assert False, 'incomplete key should raise'
+except ValueError as e:
+    assert str(e) == 'incomplete format key', str(e)
+
+try:
+    'ab%q' % 1
+    assert False, 'unknown conversion should raise'
+except ValueError as e:
+    assert str(e) == "unsupported format character 'q' (0x71) at index 3", str(e)
+
+try:
+    '%.1%' % 1
+    assert False, 'percent with precision should raise'
+except ValueError as e:
+    assert str(e) == "unsupported format character '%' (0x25) at index 3", str(e)
+
+try:
+    '%*d' % ('x', 1)
+    assert False, 'non-int star width should raise'
+except TypeError as e:
+    assert str(e) == '* wants int', str(e)
+
+try:
+    '%d' % 'x'
+    assert False, 'str for %d should raise'
+except TypeError as e:
+    assert str(e) == '%d format: a real number is required, not str', str(e)
+
+try:
+    '%x' % 1.5
+    assert False, 'float for %x should raise'
+except TypeError as e:
+    assert str(e) == '%x format: an integer is required, not float', str(e)
+
+try:
+    '%f' % 'x'
+    assert False, 'str for %f should raise'
+except TypeError as e:
+    assert str(e) == 'must be real number, not str', str(e)
+
+try:
+    '%d' % float('nan')
+    assert False, 'nan for %d should raise'
+except ValueError as e:
+    assert str(e) == 'cannot convert float NaN to integer', str(e)
+
+try:
+    '%d' % float('inf')
+    assert False, 'infinity for %d should raise'
+except OverflowError as e:
+    assert str(e) == 'cannot convert float infinity to integer', str(e)
+
+try:
+    '%c' % 'ab'
+    assert False, 'long str for %c should raise'
+except TypeError as e:
+    assert str(e) == '%c requires int or char', str(e)
+
+try:
+    '%c' % 0x110000
+    assert False, 'out of range %c should raise'
+except OverflowError as e:
+    assert str(e) == '%c arg not in range(0x110000)', str(e)
+
+try:
+    b'%c' % 256
+    assert False, 'out of range bytes %c should raise'
+except OverflowError as e:
+    assert str(e) == '%c arg not in range(256)', str(e)
+
+try:
+    b'%c' % 'a'
+    assert False, 'str for bytes %c should raise'
+except TypeError as e:
+    assert str(e) == '%c requires an integer in range(256) or a single byte', str(e)
+
+try:
+    b'%s' % 1
+    assert False, 'int for bytes %s should raise'
+except TypeError as e:
+    assert str(e) == "%b requires a bytes-like object, or an object that implements __bytes__, not 'int'", str(e)
+
+try:
+    1 % 'x'
+    assert False, 'int % str should raise'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for %: 'int' and 'str'", str(e)
@@ -0,0 +1,32 @@
+# call-external
+# The test runner's clocks are frozen, time.time() at 2023-11-14 22:13:20.25 UTC
+import time
+
+# === time ===
+assert time.time() == 1700000000.25, 'time is seconds since the epoch from the host'
+assert isinstance(time.time(), float), 'time returns a float'
+
+# === monotonic ===
+assert time.monotonic() == 1000.5, 'monotonic is from the host'
+assert isinstance(time.monotonic(), float), 'monotonic returns a float'
+
+# === sleep ===
+assert time.sleep(0.01) is None, 'sleep returns None'
+assert time.time() == 1700000000.25, 'the host decides whether sleep advances the clock'
+time.sleep(0)
+time.sleep(False)
+try:
+    time.sleep(-1)
+    assert False, 'negative sleep should raise'
+except ValueError as e:
+    assert str(e) == 'sleep length must be non-negative', 'negative sleep message'
+try:
+    time.sleep(float('nan'))
+    assert False, 'nan sleep should raise'
+except ValueError as e:
+    assert str(e) == 'Invalid value NaN (not a number)', 'nan sleep message'
+try:
+    time.sleep('1')
+    assert False, 'str sleep should raise'
+except TypeError as e:
+    assert str(e) == "'str' object cannot be interpreted as an integer", 'str sleep message'
@@ -0,0 +1,80 @@
+# PEP 695 type aliases and type parameter lists only matter to type checkers,
+# Monty accepts them, binding alias names but erasing type parameters.
+from typing import Callable
+
+# === Type aliases ===
+type Vector = list[float]
+type Pair[T] = tuple[T, T]
+type Tree = dict[str, Tree] | None
+
+
+def scale(v: Vector, k: float) -> Vector:
+    return [x * k for x in v]
+
+
+assert scale([1.0, 2.0], 2.0) == [2.0, 4.0], 'function annotated with a type alias'
+
+
+def swap[T](p: Pair[T]) -> Pair[T]:
+    return (p[1], p[0])
+
+
+assert swap((1, 2)) == (2, 1), 'function annotated with a generic alias'
+
+# === Type alias names are bound at runtime ===
+assert Vector is not None, 'type alias is bound'
+assert Vector is Vector, 'type alias keeps its identity'
+assert repr(type(Vector)) == "<class 'typing.TypeAliasType'>", 'type of alias'
+assert Vector.__name__ == 'Vector', 'type alias __name__'
+assert repr(Pair) == 'Pair', 'type alias repr'
+assert Vector is not Pair, 'each type alias is its own object'
+assert Vector != Tree, 'different type aliases are not equal'
+aliases = [Vector, Pair, Tree]
+assert len(aliases) == 3, 'type aliases can be stored as values'
+
+
+def local_alias():
+    type Local = int
+    return Local
+
+
+assert local_alias().__name__ == 'Local', 'type alias in a function binds a local name'
+
+# === Generic functions ===
+
+
+def first[T](xs: list[T]) -> T:
+    return xs[0]
+
+
+assert first([3, 4]) == 3, 'generic function'
+assert first(['a']) == 'a', 'generic function with another type'
+
+
+def pick[K, V: int, *Ts, **P](d: dict[K, V], key: K) -> V:
+    return d[key]
+
+
+assert pick({'a': 1}, 'a') == 1, 'generic function with bounds, TypeVarTuple and ParamSpec'
+
+
+# === Generic classes ===
+class Box[T]:
+    def __init__(self, item: T) -> None:
+        self.item = item
+
+    def get(self) -> T:
+        return self.item
+
+    def map[U](self, f: Callable[[T], U]) -> 'Box[U]':
+        return Box(f(self.item))
+
+
+b = Box(5)
+assert b.get() == 5, 'generic class'
+assert b.map(str).get() == '5', 'generic method on generic class'
+
+# === The `type` soft keyword doesn't shadow the builtin ===
+assert type(1) is int, 'type() still works'
+type = 'shadowed'
+assert type == 'shadowed', 'type can still be used as a name'
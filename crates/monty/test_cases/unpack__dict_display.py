@@ -0,0 +1,34 @@
+# Tests for PEP 448 `**mapping` unpacking in dict displays
+
+defaults = {'host': 'localhost', 'port': 80}
+overrides = {'port': 8080, 'debug': True}
+
+# === Merging ===
+merged = {**defaults, **overrides}
+assert merged == {'host': 'localhost', 'port': 8080, 'debug': True}, 'later mapping wins'
+assert list(merged) == ['host', 'port', 'debug'], 'keys keep first-insertion order'
+assert {**defaults} == defaults, 'single unpack copies'
+assert {**defaults} is not defaults, 'unpacking creates a new dict'
+assert {**{}} == {}, 'empty unpack'
+
+# === Mixed with key/value pairs ===
+assert {'port': 1, **defaults} == {'port': 80, 'host': 'localhost'}, 'unpack overrides earlier pair'
+assert {**defaults, 'port': 1} == {'host': 'localhost', 'port': 1}, 'later pair overrides unpack'
+assert {'a': 1, **{'b': 2}, 'c': 3} == {'a': 1, 'b': 2, 'c': 3}, 'pairs around unpack'
+
+# === Non-string keys are allowed ===
+assert {**{1: 'one'}, 2: 'two'} == {1: 'one', 2: 'two'}, 'int keys'
+assert {**{(1, 2): 'pair'}} == {(1, 2): 'pair'}, 'tuple keys'
+
+# === Evaluation order ===
+calls = []
+
+
+def track(name, value):
+    calls.append(name)
+    return value
+
+
+d = {track('k1', 'a'): track('v1', 1), **track('m', {'b': 2}), track('k2', 'c'): track('v2', 3)}
+assert d == {'a': 1, 'b': 2, 'c': 3}, 'tracked dict'
+assert calls == ['k1', 'v1', 'm', 'k2', 'v2'], f'evaluated in source order: {calls}'
@@ -0,0 +1,2 @@
+x = {'a': 1, **[1, 2]}
+# Raise=TypeError("'list' object is not a mapping")
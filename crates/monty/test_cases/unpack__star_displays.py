@@ -0,0 +1,45 @@
+# Tests for PEP 448 `*iterable` unpacking in list, tuple and set displays
+
+# === List displays ===
+a = [1, 2]
+b = (3, 4)
+assert [*a] == [1, 2], 'single unpack copies the list'
+assert [*a] is not a, 'unpacking creates a new list'
+assert [*a, *b] == [1, 2, 3, 4], 'multiple unpacks'
+assert [0, *a, 5, *b, 6] == [0, 1, 2, 5, 3, 4, 6], 'unpacks mixed with plain items'
+assert [*a, *a] == [1, 2, 1, 2], 'same iterable twice'
+assert [*[]] == [], 'empty unpack'
+assert [*'ab', 'c'] == ['a', 'b', 'c'], 'string unpack'
+assert [*range(3)] == [0, 1, 2], 'range unpack'
+assert [*{'x': 1, 'y': 2}] == ['x', 'y'], 'dict unpack yields keys'
+assert [*(x * 2 for x in a)] == [2, 4], 'generator unpack'
+assert [*iter(b)] == [3, 4], 'iterator unpack'
+
+# === Tuple displays ===
+assert (*a,) == (1, 2), 'single unpack into tuple'
+assert (*a, *b) == (1, 2, 3, 4), 'multiple unpacks into tuple'
+assert (0, *a, 9) == (0, 1, 2, 9), 'tuple with plain items around unpack'
+t = *a, 3
+assert t == (1, 2, 3), 'bare tuple with unpack'
+assert type((*a,)) is tuple, 'unpacked tuple display is a tuple'
+
+
+def pair():
+    return *a, *b
+
+
+assert pair() == (1, 2, 3, 4), 'return with unpacks'
+
+# === Set displays ===
+assert {*a} == {1, 2}, 'single unpack into set'
+assert {*a, *b, 2} == {1, 2, 3, 4}, 'set unpack dedupes'
+assert {0, *a} == {0, 1, 2}, 'plain item before unpack'
+assert {*'aba'} == {'a', 'b'}, 'string unpack into set'
+assert type({*a}) is set, 'unpacked set display is a set'
+
+# === Unpacking in loops and nested displays ===
+total = 0
+for x in *a, *b:
+    total += x
+assert total == 10, 'loop over tuple display with unpacks'
+assert [[*a], *[b]] == [[1, 2], (3, 4)], 'nested displays'
@@ -0,0 +1,45 @@
+# run-async
+log = []
+
+
+class AsyncManager:
+    def __init__(self, name):
+        self.name = name
+
+    async def __aenter__(self):
+        log.append('aenter ' + self.name)
+        return self.name
+
+    async def __aexit__(self, exc_type, exc, tb):
+        log.append('aexit ' + self.name + ('' if exc is None else ' ' + str(exc)))
+        return exc_type is KeyError
+
+
+async def main():
+    async with AsyncManager('a') as name:
+        log.append('body ' + name)
+    async with AsyncManager('b'):
+        raise KeyError('suppressed')
+    try:
+        async with AsyncManager('c'):
+            raise ValueError('propagated')
+    except ValueError:
+        log.append('caught')
+    async with AsyncManager('d'):
+        return 'done'
+
+
+result = await main()  # pyright: ignore
+assert result == 'done', 'return from async with'
+assert log == [
+    'aenter a',
+    'body a',
+    'aexit a',
+    'aenter b',
+    "aexit b 'suppressed'",
+    'aenter c',
+    'aexit c propagated',
+    'caught',
+    'aenter d',
+    'aexit d',
+], f'async with ordering {log=}'
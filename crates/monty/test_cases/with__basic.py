@@ -0,0 +1,179 @@
+# === Enter/exit ordering and `as` target ===
+log = []
+
+
+class Manager:
+    def __init__(self, name, suppress=False):
+        self.name = name
+        self.suppress = suppress
+
+    def __enter__(self):
+        log.append('enter ' + self.name)
+        return self.name.upper()
+
+    def __exit__(self, exc_type, exc, tb):
+        if exc_type is None:
+            log.append('exit ' + self.name)
+        else:
+            log.append('exit ' + self.name + ' ' + exc_type.__name__ + ' ' + str(exc))
+        return self.suppress
+
+
+with Manager('a') as value:
+    log.append('body ' + value)
+assert log == ['enter a', 'body A', 'exit a'], f'basic ordering {log=}'
+
+# no target
+log = []
+with Manager('b'):
+    log.append('body')
+assert log == ['enter b', 'body', 'exit b'], 'no target'
+
+# tuple target
+class Pair:
+    def __enter__(self):
+        return (1, 2)
+
+    def __exit__(self, *args):
+        return None
+
+
+with Pair() as (x, y):
+    pass
+assert (x, y) == (1, 2), 'tuple unpacking target'
+
+# === Exceptions ===
+log = []
+try:
+    with Manager('c'):
+        log.append('body')
+        raise ValueError('boom')
+    log.append('unreachable')
+except ValueError as e:
+    log.append('caught ' + str(e))
+assert log == ['enter c', 'body', 'exit c ValueError boom', 'caught boom'], f'exception propagates {log=}'
+
+log = []
+with Manager('d', suppress=True):
+    raise KeyError('k')
+log.append('after')
+assert log == ['enter d', "exit d KeyError 'k'", 'after'], f'exception suppressed {log=}'
+
+
+class Checker:
+    def __enter__(self):
+        return self
+
+    def __exit__(self, exc_type, exc, tb):
+        self.args = (exc_type, exc, tb)
+        return True
+
+
+with Checker() as c:
+    raise TypeError('bad')
+assert c.args[0] is TypeError, 'exit receives the exception type'
+assert isinstance(c.args[1], TypeError), 'exit receives the exception'
+
+# exception in __enter__ skips __exit__
+class BadEnter:
+    def __enter__(self):
+        raise RuntimeError('enter failed')
+
+    def __exit__(self, *args):
+        log.append('exit called')
+
+
+log = []
+try:
+    with BadEnter():
+        log.append('body')
+except RuntimeError as e:
+    log.append(str(e))
+assert log == ['enter failed'], f'exit not called when enter fails {log=}'
+
+# exception in __exit__ replaces the body's exception
+class BadExit:
+    def __enter__(self):
+        return self
+
+    def __exit__(self, *args):
+        raise RuntimeError('exit failed')
+
+
+try:
+    with BadExit():
+        raise ValueError('body')
+except RuntimeError as e:
+    assert str(e) == 'exit failed', 'exit exception wins'
+
+# the active exception is cleared after suppression
+try:
+    raise ValueError('outer')
+except ValueError:
+    with Manager('e', suppress=True):
+        raise KeyError('inner')
+    log.append('handled')
+
+
+# === return/break/continue ===
+def returns():
+    with Manager('f'):
+        return 'result'
+    return 'unreachable'
+
+
+log = []
+assert returns() == 'result', 'return value from inside with'
+assert log == ['enter f', 'exit f'], f'exit called on return {log=}'
+
+
+def returns_in_loop():
+    for i in range(3):
+        with Manager(str(i)):
+            if i == 1:
+                return i
+    return -1
+
+
+log = []
+assert returns_in_loop() == 1, 'return from with inside loop'
+assert log == ['enter 0', 'exit 0', 'enter 1', 'exit 1'], f'return in loop {log=}'
+
+log = []
+for i in range(5):
+    with Manager(str(i)):
+        if i == 0:
+            continue
+        if i == 2:
+            break
+        log.append('body')
+assert log == ['enter 0', 'exit 0', 'enter 1', 'body', 'exit 1', 'enter 2', 'exit 2'], f'break/continue {log=}'
+
+log = []
+for i in range(2):
+    try:
+        with Manager(str(i)):
+            break
+    finally:
+        log.append('finally')
+assert log == ['enter 0', 'exit 0', 'finally'], f'break through with and finally {log=}'
+
+log = []
+with Manager('outer'):
+    for i in range(3):
+        with Manager(str(i)):
+            if i == 1:
+                break
+assert log == ['enter outer', 'enter 0', 'exit 0', 'enter 1', 'exit 1', 'exit outer'], f'loop inside with {log=}'
+
+# === Multiple items and nesting ===
+log = []
+with Manager('x') as a, Manager('y') as b:
+    log.append(a + b)
+assert log == ['enter x', 'enter y', 'XY', 'exit y', 'exit x'], f'multiple items exit in reverse {log=}'
+
+log = []
+with Manager('p', suppress=True):
+    with Manager('q'):
+        raise ValueError('v')
+assert log == ['enter p', 'enter q', 'exit q ValueError v', 'exit p ValueError v'], f'nested {log=}'
@@ -0,0 +1,27 @@
+# call-external
+# === Context managers provided by the host ===
+# `__enter__` and `__exit__` of a host dataclass are method calls the host runs
+
+tx = make_transaction('orders')
+with tx as handle:
+    result = handle
+assert result == 'tx:orders', f'__enter__ result is bound {result=}'
+
+# host __exit__ suppresses KeyError
+with make_transaction('suppressing'):
+    raise KeyError('missing')
+
+# and propagates other exceptions
+try:
+    with make_transaction('failing'):
+        raise ValueError('boom')
+except ValueError as e:
+    assert str(e) == 'boom', 'exception propagates through host __exit__'
+
+
+def in_function():
+    with make_transaction('fn') as h:
+        return h
+
+
+assert in_function() == 'tx:fn', 'return through host context manager'
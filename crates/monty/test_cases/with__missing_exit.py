@@ -0,0 +1,8 @@
+class OnlyEnter:
+    def __enter__(self):
+        return self
+
+
+with OnlyEnter():
+    pass
+# Raise=TypeError("'OnlyEnter' object does not support the context manager protocol (missed __exit__ method)")
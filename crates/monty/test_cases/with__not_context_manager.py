@@ -0,0 +1,3 @@
+with 1:
+    pass
+# Raise=TypeError("'int' object does not support the context manager protocol")
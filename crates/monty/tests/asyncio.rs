@@ -737,3 +737,200 @@ fn gather_incremental_error_on_last() {
     assert_eq!(exc.exc_type(), ExcType::RuntimeError);
     assert_eq!(exc.message(), Some("last one failed"));
 }
+
+// === Test: Gather with return_exceptions - failed external becomes a result ===
+
+#[test]
+fn gather_return_exceptions_external_fails() {
+    let code = r"
+import asyncio
+
+async def main():
+    return await asyncio.gather(foo(), bar(), return_exceptions=True)
+
+await main()
+";
+    let runner = MontyRun::new(
+        code.to_owned(),
+        "test.py",
+        vec![],
+        vec!["foo".to_owned(), "bar".to_owned()],
+    )
+    .unwrap();
+    let progress = runner.start(vec![], NoLimitTracker, &mut StdPrint).unwrap();
+
+    let (state, call_ids) = drive_to_resolve_futures(progress);
+
+    // Round 1: bar fails, which shouldn't stop the gather
+    let results = vec![(
+        call_ids[1],
+        ExternalResult::Error(MontyException::new(ExcType::ValueError, Some("bar failed".to_string()))),
+    )];
+    let progress = state.resume(results, &mut StdPrint).unwrap();
+    let state = progress.into_resolve_futures().expect("should still wait for foo");
+
+    // Round 2: foo succeeds
+    let results = vec![(call_ids[0], ExternalResult::Return(MontyObject::Int(1)))];
+    let progress = state.resume(results, &mut StdPrint).unwrap();
+
+    let result = progress.into_complete().expect("should complete");
+    assert_eq!(
+        result,
+        MontyObject::List(vec![
+            MontyObject::Int(1),
+            MontyObject::Exception {
+                exc_type: ExcType::ValueError,
+                arg: Some("bar failed".to_string()),
+            },
+        ])
+    );
+}
+
+// === Test: Gather with return_exceptions - failed task while an external is pending ===
+
+#[test]
+fn gather_return_exceptions_task_fails_external_pending() {
+    let code = r"
+import asyncio
+
+async def fail():
+    raise ValueError('task failed')
+
+async def main():
+    return await asyncio.gather(fail(), foo(), return_exceptions=True)
+
+await main()
+";
+    let runner = MontyRun::new(code.to_owned(), "test.py", vec![], vec!["foo".to_owned()]).unwrap();
+    let progress = runner.start(vec![], NoLimitTracker, &mut StdPrint).unwrap();
+
+    // The failed task finishes while foo is pending, so control returns to the host
+    let (state, call_ids) = drive_to_resolve_futures(progress);
+
+    let results = vec![(call_ids[0], ExternalResult::Return(MontyObject::Int(5)))];
+    let progress = state.resume(results, &mut StdPrint).unwrap();
+
+    let result = progress.into_complete().expect("should complete");
+    assert_eq!(
+        result,
+        MontyObject::List(vec![
+            MontyObject::Exception {
+                exc_type: ExcType::ValueError,
+                arg: Some("task failed".to_string()),
+            },
+            MontyObject::Int(5),
+        ])
+    );
+}
+
+// =============================================================================
+// Async Iteration Tests
+// =============================================================================
+
+/// Builds a host dataclass implementing the async iterator protocol.
+fn stream_dataclass() -> MontyObject {
+    MontyObject::Dataclass {
+        name: "Stream".to_owned(),
+        type_id: 1,
+        instance_id: None,
+        field_names: vec![],
+        attrs: vec![].into(),
+        methods: vec!["__aiter__".to_owned(), "__anext__".to_owned()],
+        frozen: true,
+    }
+}
+
+// === Test: async generator awaiting externals survives a snapshot round-trip ===
+
+#[test]
+fn async_generator_awaits_external_across_snapshots() {
+    let code = r"
+async def numbers():
+    for i in range(3):
+        yield await foo(i)
+
+async def main():
+    return [x async for x in numbers()]
+
+await main()
+";
+    let runner = MontyRun::new(code.to_owned(), "test.py", vec![], vec!["foo".to_owned()]).unwrap();
+    let mut progress = runner.start(vec![], NoLimitTracker, &mut StdPrint).unwrap();
+
+    for i in 0..3 {
+        let (function_name, args, _, _, state) = progress.into_function_call().expect("should call foo");
+        assert_eq!(function_name, "foo");
+        assert_eq!(args, vec![MontyObject::Int(i)]);
+        progress = state.run_pending(&mut StdPrint).unwrap();
+
+        // The suspended async generator frame is part of the serialized state
+        let bytes = progress.dump().unwrap();
+        progress = RunProgress::load(&bytes).unwrap();
+
+        let state = progress.into_resolve_futures().expect("should wait for foo");
+        let call_id = state.pending_call_ids()[0];
+        let results = vec![(call_id, ExternalResult::Return(MontyObject::Int(i * 10)))];
+        progress = state.resume(results, &mut StdPrint).unwrap();
+    }
+
+    let result = progress.into_complete().expect("should complete");
+    assert_eq!(
+        result,
+        MontyObject::List(vec![MontyObject::Int(0), MontyObject::Int(10), MontyObject::Int(20)])
+    );
+}
+
+// === Test: async for over a host dataclass, ended by StopAsyncIteration from the host ===
+
+#[test]
+fn async_for_over_host_dataclass() {
+    let code = r"
+async def main():
+    items = []
+    async for item in stream:
+        items.append(item)
+    return items
+
+await main()
+";
+    let runner = MontyRun::new(code.to_owned(), "test.py", vec!["stream".to_owned()], vec![]).unwrap();
+    let progress = runner
+        .start(vec![stream_dataclass()], NoLimitTracker, &mut StdPrint)
+        .unwrap();
+
+    // `__aiter__` is called synchronously and returns the stream itself
+    let RunProgress::FunctionCall {
+        function_name,
+        method_call,
+        state,
+        ..
+    } = progress
+    else {
+        panic!("expected __aiter__ call");
+    };
+    assert_eq!(function_name, "__aiter__");
+    assert!(method_call);
+    let mut progress = state.run(stream_dataclass(), &mut StdPrint).unwrap();
+
+    // Each `__anext__` returns a future the host resolves with the next item
+    let items = vec![MontyObject::String("a".to_owned()), MontyObject::String("b".to_owned())];
+    let mut results: Vec<ExternalResult> = items.iter().cloned().map(ExternalResult::Return).collect();
+    results.push(ExternalResult::Error(MontyException::new(
+        ExcType::StopAsyncIteration,
+        None,
+    )));
+    for result in results {
+        let (function_name, _, _, _, state) = progress.into_function_call().expect("should call __anext__");
+        assert_eq!(function_name, "__anext__");
+        let state = state
+            .run_pending(&mut StdPrint)
+            .unwrap()
+            .into_resolve_futures()
+            .expect("should wait for __anext__");
+        let call_id = state.pending_call_ids()[0];
+        progress = state.resume(vec![(call_id, result)], &mut StdPrint).unwrap();
+    }
+
+    let result = progress.into_complete().expect("should complete");
+    assert_eq!(result, MontyObject::List(items));
+}
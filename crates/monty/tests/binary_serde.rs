@@ -181,3 +181,36 @@ fn run_progress_complete_roundtrip() {
 
     assert_eq!(loaded.into_complete().unwrap(), MontyObject::Int(3));
 }
+
+#[test]
+fn run_progress_dump_load_with_generators() {
+    // Both a suspended generator and a running generator frame survive a snapshot
+    let code = r"
+def count():
+    yield 10
+    yield 20
+
+def gen():
+    yield 1
+    yield ext_fn(2)
+    yield 3
+
+c = count()
+a = next(c)
+values = [v for v in gen()]
+a + next(c) + sum(values)
+"
+    .to_owned();
+    let runner = MontyRun::new(code, "test.py", vec![], vec!["ext_fn".to_owned()]).unwrap();
+
+    let progress = runner.start(vec![], NoLimitTracker, &mut StdPrint).unwrap();
+    let bytes = progress.dump().unwrap();
+    let loaded: RunProgress<NoLimitTracker> = RunProgress::load(&bytes).unwrap();
+
+    let (fn_name, args, _, _call_id, state) = loaded.into_function_call().expect("should be at function call");
+    assert_eq!(fn_name, "ext_fn");
+    assert_eq!(args, vec![MontyObject::Int(2)]);
+
+    let result = state.run(MontyObject::Int(100), &mut StdPrint).unwrap();
+    assert_eq!(result.into_complete().unwrap(), MontyObject::Int(134)); // 10 + 20 + (1 + 100 + 3)
+}
@@ -241,6 +241,7 @@ const ITER_EXT_FUNCTIONS: &[&str] = &[
     "make_mutable_point", // () -> Dataclass Point(x=1, y=2) (mutable)
     "make_user",          // (name) -> Dataclass User(name=name, active=True) (immutable)
     "make_empty",         // () -> Dataclass Empty() (immutable, no fields)
+    "make_transaction",   // (name) -> Dataclass Transaction(name=name) with __enter__/__exit__ methods
     "async_call",         // (x) -> async: returns x (coroutine that returns its argument)
 ];
 
@@ -283,6 +284,46 @@ fn ensure_python_modules_imported() {
     });
 }
 
+/// Dispatches a method call on a dataclass returned by one of the `ITER_EXT_FUNCTIONS`.
+///
+/// `args[0]` is the dataclass instance. Mirrors the methods of the dataclasses in
+/// `scripts/iter_test_methods.py`.
+///
+/// # Panics
+/// Panics if the method is unknown or arguments are invalid types.
+fn dispatch_method_call(name: &str, args: &[MontyObject]) -> ExternalResult {
+    let Some(MontyObject::Dataclass {
+        name: dc_name, attrs, ..
+    }) = args.first()
+    else {
+        panic!("method call {name}: first arg must be a dataclass");
+    };
+    match (dc_name.as_str(), name) {
+        ("Transaction", "__enter__") => {
+            assert!(args.len() == 1, "Transaction.__enter__ requires no arguments");
+            let tx_name = attrs
+                .into_iter()
+                .find_map(|(k, v)| matches!(k, MontyObject::String(k) if k == "name").then_some(v))
+                .and_then(|v| String::try_from(v).ok())
+                .expect("Transaction must have a str name");
+            MontyObject::String(format!("tx:{tx_name}")).into()
+        }
+        ("Transaction", "__exit__") => {
+            // suppress KeyError, propagate everything else
+            assert!(args.len() == 4, "Transaction.__exit__ requires 3 arguments");
+            let suppress = matches!(
+                args[2],
+                MontyObject::Exception {
+                    exc_type: ExcType::KeyError,
+                    ..
+                }
+            );
+            MontyObject::Bool(suppress).into()
+        }
+        _ => panic!("Unknown method: {dc_name}.{name}"),
+    }
+}
+
 /// Result from dispatching an external function call.
 ///
 /// Distinguishes between synchronous calls (return immediately) and
@@ -347,6 +388,7 @@ fn dispatch_external_call(name: &str, args: Vec<MontyObject>) -> DispatchResult
                 MontyObject::Dataclass {
                     name: "Point".to_string(),
                     type_id: 0, // Test fixture has no real Python type
+                    instance_id: None,
                     field_names: vec!["x".to_string(), "y".to_string()],
                     attrs: vec![
                         (MontyObject::String("x".to_string()), MontyObject::Int(1)),
@@ -366,6 +408,7 @@ fn dispatch_external_call(name: &str, args: Vec<MontyObject>) -> DispatchResult
                 MontyObject::Dataclass {
                     name: "MutablePoint".to_string(),
                     type_id: 0, // Test fixture has no real Python type
+                    instance_id: None,
                     field_names: vec!["x".to_string(), "y".to_string()],
                     attrs: vec![
                         (MontyObject::String("x".to_string()), MontyObject::Int(1)),
@@ -386,6 +429,7 @@ fn dispatch_external_call(name: &str, args: Vec<MontyObject>) -> DispatchResult
                 MontyObject::Dataclass {
                     name: "User".to_string(),
                     type_id: 0, // Test fixture has no real Python type
+                    instance_id: None,
                     field_names: vec!["name".to_string(), "active".to_string()],
                     attrs: vec![
                         (MontyObject::String("name".to_string()), MontyObject::String(name)),
@@ -405,6 +449,7 @@ fn dispatch_external_call(name: &str, args: Vec<MontyObject>) -> DispatchResult
                 MontyObject::Dataclass {
                     name: "Empty".to_string(),
                     type_id: 0, // Test fixture has no real Python type
+                    instance_id: None,
                     field_names: vec![],
                     attrs: vec![].into(),
                     methods: vec![],
@@ -413,6 +458,23 @@ fn dispatch_external_call(name: &str, args: Vec<MontyObject>) -> DispatchResult
                 .into(),
             )
         }
+        "make_transaction" => {
+            assert!(args.len() == 1, "make_transaction requires 1 argument");
+            let name = String::try_from(&args[0]).expect("make_transaction: first arg must be str");
+            // Return an immutable Transaction(name=name) dataclass usable as a context manager
+            DispatchResult::Sync(
+                MontyObject::Dataclass {
+                    name: "Transaction".to_string(),
+                    type_id: 0, // Test fixture has no real Python type
+                    instance_id: None,
+                    field_names: vec!["name".to_string()],
+                    attrs: vec![(MontyObject::String("name".to_string()), MontyObject::String(name))].into(),
+                    methods: vec!["__enter__".to_string(), "__exit__".to_string()],
+                    frozen: true,
+                }
+                .into(),
+            )
+        }
         "async_call" => {
             // async_call(x) -> coroutine that returns x
             // This is an async function - use run_pending() and resolve later
@@ -442,6 +504,12 @@ struct VirtualFile {
 /// Virtual filesystem modification time (arbitrary fixed timestamp).
 const VFS_MTIME: f64 = 1_700_000_000.0;
 
+/// Virtual clock time returned by `time.time()` and read by `datetime.now()`, it never advances.
+const VIRTUAL_TIME: f64 = 1_700_000_000.25;
+
+/// Virtual monotonic clock value returned by `time.monotonic()`, it never advances.
+const VIRTUAL_MONOTONIC: f64 = 1_000.5;
+
 /// Virtual filesystem for testing Path methods.
 ///
 /// Structure:
@@ -676,6 +744,21 @@ fn dispatch_os_call(
         return MontyObject::Dict(env_dict.into()).into();
     }
 
+    // The clock functions take no path argument; tests get a frozen virtual clock
+    match function {
+        OsFunction::Time => return MontyObject::Float(VIRTUAL_TIME).into(),
+        OsFunction::Monotonic => return MontyObject::Float(VIRTUAL_MONOTONIC).into(),
+        OsFunction::Sleep => {
+            assert!(
+                matches!(args[0], MontyObject::Float(_)),
+                "time.sleep: first arg must be a float, got {:?}",
+                args[0]
+            );
+            return MontyObject::None.into();
+        }
+        _ => {}
+    }
+
     // Extract path from MontyObject::Path (or String for backwards compatibility)
     let path = match &args[0] {
         MontyObject::Path(p) => p.clone(),
@@ -684,7 +767,9 @@ fn dispatch_os_call(
     };
 
     match function {
-        OsFunction::GetEnviron => unreachable!("handled above"),
+        OsFunction::GetEnviron | OsFunction::Time | OsFunction::Monotonic | OsFunction::Sleep => {
+            unreachable!("handled above")
+        }
         OsFunction::Exists => {
             let exists = get_virtual_file(&path).is_some() || is_virtual_dir(&path);
             MontyObject::Bool(exists).into()
@@ -1319,10 +1404,20 @@ fn run_iter_loop(exec: MontyRun) -> Result<MontyObject, MontyException> {
 
         match progress {
             RunProgress::Complete(result) => return Ok(result),
+            RunProgress::FunctionCall {
+                function_name,
+                args,
+                method_call: true,
+                state,
+                ..
+            } => {
+                progress = state.run(dispatch_method_call(&function_name, &args), &mut StdPrint)?;
+            }
             RunProgress::FunctionCall {
                 function_name,
                 args,
                 kwargs: _,
+                method_call: false,
                 call_id,
                 state,
             } => {
@@ -302,6 +302,49 @@ fn input_exception_raise() {
     assert_eq!(exc.message(), Some("input error"));
 }
 
+#[test]
+fn input_exception_group() {
+    let ex = MontyRun::new(
+        "(repr(x), x.exceptions[1].exceptions[0], x)".to_owned(),
+        "test.py",
+        vec!["x".to_owned()],
+        vec![],
+    )
+    .unwrap();
+    let group = MontyObject::ExceptionGroup {
+        exc_type: ExcType::ExceptionGroup,
+        message: "errors".to_string(),
+        exceptions: vec![
+            MontyObject::Exception {
+                exc_type: ExcType::ValueError,
+                arg: Some("a".to_string()),
+            },
+            MontyObject::ExceptionGroup {
+                exc_type: ExcType::ExceptionGroup,
+                message: "inner".to_string(),
+                exceptions: vec![MontyObject::Exception {
+                    exc_type: ExcType::KeyError,
+                    arg: Some("b".to_string()),
+                }],
+            },
+        ],
+    };
+    let result = ex.run_no_limits(vec![group.clone()]).unwrap();
+    assert_eq!(
+        result,
+        MontyObject::Tuple(vec![
+            MontyObject::String(
+                "ExceptionGroup('errors', [ValueError('a'), ExceptionGroup('inner', [KeyError('b')])])".to_string()
+            ),
+            MontyObject::Exception {
+                exc_type: ExcType::KeyError,
+                arg: Some("b".to_string()),
+            },
+            group,
+        ])
+    );
+}
+
 // === Invalid Input Tests ===
 
 #[test]
@@ -82,6 +82,22 @@ fn json_output_exception() {
     );
 }
 
+#[test]
+fn json_output_exception_group() {
+    let obj = MontyObject::ExceptionGroup {
+        exc_type: ExcType::ExceptionGroup,
+        message: "errors".to_string(),
+        exceptions: vec![MontyObject::Exception {
+            exc_type: ExcType::ValueError,
+            arg: None,
+        }],
+    };
+    assert_eq!(
+        serde_json::to_string(&obj).unwrap(),
+        r#"{"ExceptionGroup":{"exc_type":"ExceptionGroup","message":"errors","exceptions":[{"Exception":{"exc_type":"ValueError","arg":null}}]}}"#
+    );
+}
+
 #[test]
 fn json_output_repr() {
     let obj = MontyObject::Repr("<function foo>".to_string());
@@ -38,6 +38,8 @@ fn run_to_oscall(code: &str) -> (OsFunction, Vec<MontyObject>) {
                 | OsFunction::Rename => MontyObject::None,
                 OsFunction::Getenv => MontyObject::String("mock_env_value".to_owned()),
                 OsFunction::GetEnviron => MontyObject::Dict(vec![].into()),
+                OsFunction::Time | OsFunction::Monotonic => MontyObject::Float(0.0),
+                OsFunction::Sleep => MontyObject::None,
             };
             let _ = state.run(mock_result, &mut StdPrint);
             (function, args)
@@ -457,3 +459,121 @@ import os
     assert_eq!(func, OsFunction::GetEnviron);
     assert_eq!(result, MontyObject::Bool(true));
 }
+
+// =============================================================================
+// os.path tests
+// =============================================================================
+
+#[test]
+fn os_path_exists_yields_oscall() {
+    let (func, args) = run_to_oscall("import os.path; os.path.exists('/tmp/test.txt')");
+    assert_eq!(func, OsFunction::Exists);
+    assert_eq!(args, vec![MontyObject::Path("/tmp/test.txt".to_owned())]);
+}
+
+#[test]
+fn os_path_isfile_yields_oscall() {
+    let (func, args) = run_to_oscall("from os import path; path.isfile('/tmp/test.txt')");
+    assert_eq!(func, OsFunction::IsFile);
+    assert_eq!(args, vec![MontyObject::Path("/tmp/test.txt".to_owned())]);
+}
+
+#[test]
+fn os_path_isdir_result_used() {
+    let code = r"
+import os
+'dir' if os.path.isdir('/tmp') else 'not dir'
+";
+    let (func, args, result) = run_oscall_with_result(code, MontyObject::Bool(false));
+    assert_eq!(func, OsFunction::IsDir);
+    assert_eq!(args, vec![MontyObject::Path("/tmp".to_owned())]);
+    assert_eq!(result, MontyObject::String("not dir".to_owned()));
+}
+
+// =============================================================================
+// time module and datetime clock tests
+// =============================================================================
+
+#[test]
+fn time_time_yields_oscall() {
+    let (func, args) = run_to_oscall("import time; time.time()");
+    assert_eq!(func, OsFunction::Time);
+    assert_eq!(args, vec![]);
+}
+
+#[test]
+fn time_monotonic_yields_oscall() {
+    let (func, args) = run_to_oscall("import time; time.monotonic()");
+    assert_eq!(func, OsFunction::Monotonic);
+    assert_eq!(args, vec![]);
+}
+
+#[test]
+fn time_sleep_yields_oscall() {
+    // Ints are passed to the host as floats
+    let (func, args) = run_to_oscall("import time; time.sleep(2)");
+    assert_eq!(func, OsFunction::Sleep);
+    assert_eq!(args, vec![MontyObject::Float(2.0)]);
+}
+
+#[test]
+fn time_time_result_used() {
+    let (func, _, result) = run_oscall_with_result("import time; time.time() + 1", MontyObject::Float(41.0));
+    assert_eq!(func, OsFunction::Time);
+    assert_eq!(result, MontyObject::Float(42.0));
+}
+
+#[test]
+fn datetime_now_uses_host_clock() {
+    let code = r"
+from datetime import datetime
+datetime.now().isoformat()
+";
+    let (func, args, result) = run_oscall_with_result(code, MontyObject::Float(0.0));
+    assert_eq!(func, OsFunction::Time);
+    assert_eq!(args, vec![]);
+    assert_eq!(result, MontyObject::String("1970-01-01T00:00:00".to_owned()));
+}
+
+#[test]
+fn datetime_now_with_timezone() {
+    let code = r"
+from datetime import datetime, timezone
+datetime.now(timezone.utc).isoformat()
+";
+    let (func, _, result) = run_oscall_with_result(code, MontyObject::Float(1_700_000_000.5));
+    assert_eq!(func, OsFunction::Time);
+    assert_eq!(
+        result,
+        MontyObject::String("2023-11-14T22:13:20.500000+00:00".to_owned())
+    );
+}
+
+#[test]
+fn date_today_uses_host_clock() {
+    let code = r"
+import datetime
+str(datetime.date.today())
+";
+    let (func, _, result) = run_oscall_with_result(code, MontyObject::Int(86_400 * 365));
+    assert_eq!(func, OsFunction::Time);
+    assert_eq!(result, MontyObject::String("1971-01-01".to_owned()));
+}
+
+#[test]
+fn datetime_now_bad_host_result_is_catchable() {
+    let code = r"
+from datetime import datetime
+try:
+    datetime.now()
+except TypeError as e:
+    result = str(e)
+result
+";
+    let (func, _, result) = run_oscall_with_result(code, MontyObject::String("noon".to_owned()));
+    assert_eq!(func, OsFunction::Time);
+    assert_eq!(
+        result,
+        MontyObject::String("'str' object cannot be interpreted as an integer".to_owned())
+    );
+}
@@ -1,6 +1,6 @@
 use std::fmt::Write;
 
-use monty::{ExcType, MontyException, MontyRun};
+use monty::{ExcType, MontyException, MontyObject, MontyRun};
 
 /// Helper to extract the exception type from a parse error.
 fn get_exc_type(result: Result<MontyRun, MontyException>) -> ExcType {
@@ -9,37 +9,90 @@ fn get_exc_type(result: Result<MontyRun, MontyException>) -> ExcType {
 }
 
 #[test]
-fn complex_numbers_return_not_implemented_error() {
+fn complex_numbers_compile_successfully() {
     let result = MontyRun::new("1 + 2j".to_owned(), "test.py", vec![], vec![]);
-    assert_eq!(get_exc_type(result), ExcType::NotImplementedError);
+    assert!(result.is_ok(), "complex literal should compile successfully");
 }
 
 #[test]
-fn complex_numbers_have_descriptive_message() {
-    let result = MontyRun::new("1 + 2j".to_owned(), "test.py", vec![], vec![]);
+fn complex_numbers_evaluate() {
+    let runner = MontyRun::new("(1 + 2j) * 1j".to_owned(), "test.py", vec![], vec![]).unwrap();
+    let result = runner.run_no_limits(vec![]).unwrap();
+    assert_eq!(result, MontyObject::Complex { real: -2.0, imag: 1.0 });
+}
+
+#[test]
+fn generator_functions_compile_successfully() {
+    let result = MontyRun::new("def foo():\n    yield 1".to_owned(), "test.py", vec![], vec![]);
+    assert!(result.is_ok(), "generator function should compile successfully");
+}
+
+#[test]
+fn yield_outside_function_returns_syntax_error() {
+    let result = MontyRun::new("yield 1".to_owned(), "test.py", vec![], vec![]);
     let exc = result.expect_err("expected parse error");
+    assert_eq!(exc.exc_type(), ExcType::SyntaxError);
+    assert_eq!(exc.message(), Some("'yield' outside function"));
+}
+
+#[test]
+fn starred_expression_outside_display_returns_syntax_error() {
+    let result = MontyRun::new("a = [1]\nx = *a".to_owned(), "test.py", vec![], vec![]);
+    assert_eq!(get_exc_type(result), ExcType::SyntaxError);
+}
+
+#[test]
+fn starred_expressions_in_displays_and_calls_compile_successfully() {
+    let code = "a = [1]\nd = {}\nprint(*a, *a, 2, **d, **d)\n[*a, 0]\n(*a, *a)\n{*a}\n{**d, 'k': 1}";
+    let result = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]);
+    assert!(result.is_ok(), "PEP 448 unpacking should compile successfully");
+}
+
+#[test]
+fn async_generators_compile_successfully() {
+    let code = "async def foo():\n    yield 1\n    async for x in foo():\n        pass\n    [x async for x in foo()]";
+    let result = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]);
     assert!(
-        exc.message().is_some_and(|m| m.contains("complex")),
-        "message should mention 'complex', got: {exc}"
+        result.is_ok(),
+        "async generators and async for should compile successfully"
     );
 }
 
 #[test]
-fn yield_expressions_return_not_implemented_error() {
-    // Yield expressions are not supported and fail at parse time
-    let result = MontyRun::new("def foo():\n    yield 1".to_owned(), "test.py", vec![], vec![]);
-    assert_eq!(get_exc_type(result), ExcType::NotImplementedError);
-    let result = MontyRun::new("def foo():\n    yield 1".to_owned(), "test.py", vec![], vec![]);
+fn yield_from_in_async_function_returns_syntax_error() {
+    let result = MontyRun::new(
+        "async def foo():\n    yield from []".to_owned(),
+        "test.py",
+        vec![],
+        vec![],
+    );
     let exc = result.expect_err("expected parse error");
-    assert!(
-        exc.message().is_some_and(|m| m.contains("yield")),
-        "message should mention 'yield', got: {exc}"
+    assert_eq!(exc.exc_type(), ExcType::SyntaxError);
+    assert_eq!(exc.message(), Some("'yield from' inside async function"));
+}
+
+#[test]
+fn return_value_in_async_generator_returns_syntax_error() {
+    let result = MontyRun::new(
+        "async def foo():\n    yield 1\n    return 2".to_owned(),
+        "test.py",
+        vec![],
+        vec![],
     );
+    let exc = result.expect_err("expected parse error");
+    assert_eq!(exc.exc_type(), ExcType::SyntaxError);
+    assert_eq!(exc.message(), Some("'return' with value in async generator"));
 }
 
 #[test]
-fn classes_return_not_implemented_error() {
+fn classes_compile_successfully() {
     let result = MontyRun::new("class Foo: pass".to_owned(), "test.py", vec![], vec![]);
+    assert!(result.is_ok(), "class definition should compile successfully");
+}
+
+#[test]
+fn multiple_inheritance_returns_not_implemented_error() {
+    let result = MontyRun::new("class A: pass\nclass B: pass\nclass C(A, B): pass".to_owned(), "test.py", vec![], vec![]);
     assert_eq!(get_exc_type(result), ExcType::NotImplementedError);
 }
 
@@ -53,15 +106,72 @@ fn unknown_imports_compile_successfully_error_deferred_to_runtime() {
 }
 
 #[test]
-fn with_statement_returns_not_implemented_error() {
-    let result = MontyRun::new("with open('f') as f: pass".to_owned(), "test.py", vec![], vec![]);
-    assert_eq!(get_exc_type(result), ExcType::NotImplementedError);
+fn multi_module_and_dotted_imports_compile_successfully() {
+    let code = "import sys, os\nimport os.path\nimport os.path as osp\nfrom os import path";
+    let result = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]);
+    assert!(
+        result.is_ok(),
+        "multi-module and dotted imports should compile successfully"
+    );
+}
+
+#[test]
+fn import_allowlist_rejects_unlisted_module() {
+    let allowed = Some(vec!["sys".to_owned()]);
+    let result = MontyRun::new_with_allowed_modules("import sys, os".to_owned(), "test.py", vec![], vec![], allowed);
+    let exc = result.expect_err("os isn't allowed");
+    assert_eq!(exc.exc_type(), ExcType::ModuleNotFoundError);
+    assert_eq!(exc.message(), Some("No module named 'os'"));
+}
+
+#[test]
+fn import_allowlist_rejects_unlisted_from_import() {
+    let allowed = Some(vec![]);
+    let result =
+        MontyRun::new_with_allowed_modules("from os import getenv".to_owned(), "test.py", vec![], vec![], allowed);
+    assert_eq!(get_exc_type(result), ExcType::ModuleNotFoundError);
+}
+
+#[test]
+fn import_allowlist_allows_submodules_of_listed_packages() {
+    let allowed = Some(vec!["os".to_owned()]);
+    let code = "import os.path\nfrom os.path import join";
+    let result = MontyRun::new_with_allowed_modules(code.to_owned(), "test.py", vec![], vec![], allowed);
+    assert!(result.is_ok(), "os should allow os.path");
+}
+
+#[test]
+fn import_allowlist_requires_package_bound_by_dotted_import() {
+    // `import os.path` binds `os`, so allowing only `os.path` isn't enough
+    let allowed = Some(vec!["os.path".to_owned()]);
+    let result =
+        MontyRun::new_with_allowed_modules("import os.path".to_owned(), "test.py", vec![], vec![], allowed.clone());
+    assert_eq!(get_exc_type(result), ExcType::ModuleNotFoundError);
+
+    let code = "import os.path as osp\nfrom os.path import join";
+    let result = MontyRun::new_with_allowed_modules(code.to_owned(), "test.py", vec![], vec![], allowed);
+    assert!(result.is_ok(), "os.path is allowed when os isn't bound");
+}
+
+#[test]
+fn import_allowlist_keeps_unknown_imports_deferred_to_runtime() {
+    let allowed = Some(vec![]);
+    let result = MontyRun::new_with_allowed_modules("import foobar".to_owned(), "test.py", vec![], vec![], allowed);
+    assert!(result.is_ok(), "unknown import should compile successfully");
+}
+
+#[test]
+fn with_statements_compile_successfully() {
+    let code = "with open('f') as f, open('g'):\n    pass\nasync def foo():\n    async with bar() as b:\n        pass";
+    let result = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]);
+    assert!(result.is_ok(), "with and async with should compile successfully");
 }
 
 #[test]
 fn error_display_format() {
     // Verify the Display format matches Python's exception output with traceback
-    let result = MontyRun::new("1 + 2j".to_owned(), "test.py", vec![], vec![]);
+    let code = "class A: pass\nclass B: pass\nclass C(A, B): pass";
+    let result = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]);
     let err = result.expect_err("expected parse error");
     let display = err.to_string();
     // Should start with traceback header
@@ -71,7 +181,7 @@ fn error_display_format() {
     );
     // Should contain the file/line info
     assert!(
-        display.contains("File \"test.py\", line 1"),
+        display.contains("File \"test.py\", line 3"),
         "display should contain file location, got: {display}"
     );
     // Should end with NotImplementedError message
@@ -87,21 +197,9 @@ fn error_display_format() {
 
 /// Tests that syntax errors return `SyntaxError` exceptions.
 
-#[test]
-fn invalid_fstring_format_spec_returns_syntax_error() {
-    let result = MontyRun::new("f'{1:10xyz}'".to_owned(), "test.py", vec![], vec![]);
-    assert_eq!(get_exc_type(result), ExcType::SyntaxError);
-}
-
-#[test]
-fn invalid_fstring_format_spec_str_returns_syntax_error() {
-    let result = MontyRun::new("f'{\"hello\":abc}'".to_owned(), "test.py", vec![], vec![]);
-    assert_eq!(get_exc_type(result), ExcType::SyntaxError);
-}
-
 #[test]
 fn syntax_error_display_format() {
-    let result = MontyRun::new("f'{1:10xyz}'".to_owned(), "test.py", vec![], vec![]);
+    let result = MontyRun::new("1 +".to_owned(), "test.py", vec![], vec![]);
     let err = result.expect_err("expected parse error");
     let display = err.to_string();
     assert!(
@@ -110,6 +208,13 @@ fn syntax_error_display_format() {
     );
 }
 
+/// Non-standard f-string format specs may be handled by `__format__`, so they parse.
+#[test]
+fn nonstandard_fstring_format_spec_parses() {
+    let result = MontyRun::new("f'{1:10xyz}'".to_owned(), "test.py", vec![], vec![]);
+    assert!(result.is_ok(), "format spec should be checked at runtime");
+}
+
 #[test]
 fn deeply_nested_tuples_exceed_limit() {
     // Build nested tuple like ((((x,),),),) with depth > 200
@@ -434,8 +539,35 @@ fn matrix_multiplication_augmented_assignment_has_descriptive_message() {
 }
 
 #[test]
-fn del_statement_returns_not_implemented_error() {
-    // The del statement is not supported at parse time
-    let result = MontyRun::new("x = 1\ndel x".to_owned(), "test.py", vec![], vec![]);
-    assert_eq!(get_exc_type(result), ExcType::NotImplementedError);
+fn del_function_call_returns_syntax_error() {
+    // Only names, subscripts and attributes (or tuples/lists of them) can be deleted
+    let result = MontyRun::new("def f():\n    pass\ndel f()".to_owned(), "test.py", vec![], vec![]);
+    assert_eq!(get_exc_type(result), ExcType::SyntaxError);
+}
+
+#[test]
+fn return_in_except_star_returns_syntax_error() {
+    let code = "def f():\n    try:\n        pass\n    except* ValueError:\n        return 1";
+    let result = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]);
+    let exc = result.expect_err("expected parse error");
+    assert_eq!(exc.exc_type(), ExcType::SyntaxError);
+    assert_eq!(
+        exc.message(),
+        Some("'break', 'continue' and 'return' cannot appear in an except* block")
+    );
+}
+
+#[test]
+fn break_in_loop_inside_except_star_compiles_successfully() {
+    // Only exits that leave the except* block are rejected
+    let code = "try:\n    pass\nexcept* ValueError:\n    for x in []:\n        break";
+    let result = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]);
+    assert!(result.is_ok(), "break inside a loop in except* should compile");
+}
+
+#[test]
+fn break_out_of_except_star_returns_syntax_error() {
+    let code = "for x in []:\n    try:\n        pass\n    except* ValueError:\n        break";
+    let result = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]);
+    assert_eq!(get_exc_type(result), ExcType::SyntaxError);
 }
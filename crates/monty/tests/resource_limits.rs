@@ -681,6 +681,39 @@ fn bigint_builtin_pow_memory_limit() {
     assert_eq!(exc.exc_type(), ExcType::MemoryError);
 }
 
+/// Test that math.factorial() is rejected by memory limits before computing the product.
+#[test]
+fn math_factorial_memory_limit() {
+    // 10_000_000! has ~220M bits, far too many to compute
+    let code = "import math\nmath.factorial(10000000)";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+    let limits = ResourceLimits::new().max_memory(1_000_000);
+    let result = ex.run(vec![], LimitedTracker::new(limits), &mut StdPrint);
+
+    assert!(result.is_err(), "large factorial should exceed memory limit");
+    let exc = result.unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::MemoryError);
+}
+
+/// Test that math.comb() and math.perm() of huge ints respect memory limits.
+#[test]
+fn math_comb_perm_memory_limit() {
+    for code in [
+        "import math\nmath.comb(10 ** 100, 1000000)",
+        "import math\nmath.perm(10 ** 100, 1000000)",
+    ] {
+        let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+        let limits = ResourceLimits::new().max_memory(1_000_000);
+        let result = ex.run(vec![], LimitedTracker::new(limits), &mut StdPrint);
+
+        assert!(result.is_err(), "{code} should exceed memory limit");
+        let exc = result.unwrap_err();
+        assert_eq!(exc.exc_type(), ExcType::MemoryError);
+    }
+}
+
 /// Test that large BigInt operations are rejected BEFORE allocation via check_large_result.
 ///
 /// The pre-allocation size check estimates result size and rejects operations that would
@@ -943,3 +976,93 @@ fn tuple_mult_within_limit() {
     assert!(result.is_ok(), "small tuple mult should succeed");
     assert_eq!(result.unwrap(), MontyObject::Bool(true));
 }
+
+// === Regex memory tests ===
+
+/// Test that compiling a pattern is rejected when the memory limit leaves no room for it.
+#[test]
+fn re_compile_memory_limit() {
+    let code = "import re\nre.search('a+', 'caaat')";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+    let limits = ResourceLimits::new().max_memory(10_000);
+    let result = ex.run(vec![], LimitedTracker::new(limits), &mut StdPrint);
+
+    assert!(result.is_err(), "compiling should be rejected");
+    let exc = result.unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::MemoryError);
+    assert!(
+        exc.message().is_some_and(|m| m.contains("memory limit exceeded")),
+        "expected memory limit error, got: {exc}"
+    );
+}
+
+/// Test that patterns still compile when the memory limit is below the usual size limit.
+#[test]
+fn re_compile_within_limit() {
+    let code = "import re\nre.search('a+', 'caaat').group()";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+    let limits = ResourceLimits::new().max_memory(1_000_000);
+    let result = ex.run(vec![], LimitedTracker::new(limits), &mut StdPrint);
+
+    assert_eq!(result.unwrap(), MontyObject::String("aaa".to_owned()));
+}
+
+/// Test that code compiled by `eval()` counts towards the memory limit, since it's kept for the run.
+#[test]
+fn eval_compiled_code_memory_limit() {
+    let code = "for i in range(5000):\n    eval(f'{i} + 1')";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+    let limits = ResourceLimits::new().max_memory(200_000);
+    let result = ex.run(vec![], LimitedTracker::new(limits), &mut StdPrint);
+
+    assert!(result.is_err(), "compiled code should exceed the memory limit");
+    let exc = result.unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::MemoryError);
+}
+
+/// Test that the number of different pieces of code `eval()` compiles in a run is capped.
+#[test]
+fn eval_compiled_code_count_limit() {
+    let code = "for i in range(20000):\n    eval(str(i))";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+    let exc = ex.run_no_limits(vec![]).unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::RuntimeError);
+    assert_eq!(exc.message(), Some("too much code compiled by eval()/exec()"));
+}
+
+/// Test that huge widths in format specs are rejected before the padding is built.
+#[test]
+fn format_spec_width_memory_limit() {
+    let cases = [
+        "'{:>999999999999}'.format(1)",
+        "format(1, '>999999999999')",
+        "f'{1:>{999999999999}}'",
+        "'{:.999999999999f}'.format(1.5)",
+    ];
+    for code in cases {
+        let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+        let limits = ResourceLimits::new().max_memory(1_000_000);
+        let result = ex.run(vec![], LimitedTracker::new(limits), &mut StdPrint);
+
+        let exc = result.expect_err(code);
+        assert_eq!(exc.exc_type(), ExcType::MemoryError, "{code}");
+    }
+}
+
+/// Test that `%` formatting checks the size of the whole result, not just each conversion.
+#[test]
+fn percent_format_result_memory_limit() {
+    let code = "('%9000000s' * 100) % (('',) * 100)";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+    let limits = ResourceLimits::new().max_memory(100_000_000);
+    let result = ex.run(vec![], LimitedTracker::new(limits), &mut StdPrint);
+
+    let exc = result.expect_err("the formatted result should exceed the memory limit");
+    assert_eq!(exc.exc_type(), ExcType::MemoryError);
+}
@@ -11,8 +11,12 @@ This module is shared between:
 
 from __future__ import annotations
 
+import builtins
+import importlib.util
 import os
 import stat as stat_module
+import time as time_module
+import types
 from dataclasses import dataclass
 from pathlib import Path
 
@@ -82,6 +86,24 @@ def make_empty() -> Empty:
     return Empty()
 
 
+@dataclass(frozen=True)
+class Transaction:
+    """Host context manager, `__enter__` and `__exit__` become method calls to the host."""
+
+    name: str
+
+    def __enter__(self) -> str:
+        return f'tx:{self.name}'
+
+    def __exit__(self, exc_type: object, exc: BaseException | None, tb: object) -> bool:
+        # suppress KeyError, propagate everything else
+        return isinstance(exc, KeyError)
+
+
+def make_transaction(name: str) -> Transaction:
+    return Transaction(name=name)
+
+
 async def async_call(x: object) -> object:
     """Async function that returns its argument.
 
@@ -493,6 +515,54 @@ class VirtualEnviron:
 os.environ = VirtualEnviron()
 
 
+# =============================================================================
+# Virtual Clock for time and datetime Tests
+# =============================================================================
+
+# Frozen clock values (match Rust test constants)
+VIRTUAL_TIME: float = 1700000000.25
+VIRTUAL_MONOTONIC: float = 1000.5
+
+_real_time = time_module
+_real_import = builtins.__import__
+
+
+def _virtual_sleep(secs: float) -> None:
+    """Returns at once for valid lengths, the real `sleep` raises the errors for invalid ones."""
+    if isinstance(secs, (int, float)) and secs >= 0:
+        return
+    _real_time.sleep(secs)
+
+
+# The clock can't be patched globally since asyncio and the test runner use it, so tests get
+# their own `time` module, in which local time is UTC as in Monty
+virtual_time = types.ModuleType('time')
+virtual_time.__dict__.update(_real_time.__dict__)
+virtual_time.time = lambda: VIRTUAL_TIME  # pyright: ignore[reportAttributeAccessIssue]
+virtual_time.monotonic = lambda: VIRTUAL_MONOTONIC  # pyright: ignore[reportAttributeAccessIssue]
+virtual_time.sleep = _virtual_sleep  # pyright: ignore[reportAttributeAccessIssue]
+virtual_time.localtime = _real_time.gmtime  # pyright: ignore[reportAttributeAccessIssue]
+
+# The C `datetime` reads the host clock directly, so tests get a copy of the pure Python one
+# reading the virtual clock
+_pydatetime_spec = importlib.util.find_spec('_pydatetime')
+assert _pydatetime_spec is not None and _pydatetime_spec.loader is not None
+virtual_datetime = importlib.util.module_from_spec(_pydatetime_spec)
+_pydatetime_spec.loader.exec_module(virtual_datetime)
+virtual_datetime._time = virtual_time  # pyright: ignore[reportAttributeAccessIssue]
+
+VIRTUAL_MODULES: dict[str, types.ModuleType] = {'time': virtual_time, 'datetime': virtual_datetime}
+
+
+def _virtual_import(name: str, *args: object, **kwargs: object) -> types.ModuleType:
+    """`__import__` returning the virtual clock modules in place of the real ones."""
+    return VIRTUAL_MODULES.get(name) or _real_import(name, *args, **kwargs)
+
+
+# Builtins for the test's globals, so only the test's imports get the virtual modules
+__builtins__ = {**builtins.__dict__, '__import__': _virtual_import}
+
+
 # All external functions available to iter mode tests
 ITER_MODE_GLOBALS: dict[str, object] = {
     'add_ints': add_ints,
@@ -504,5 +574,7 @@ ITER_MODE_GLOBALS: dict[str, object] = {
     'make_mutable_point': make_mutable_point,
     'make_user': make_user,
     'make_empty': make_empty,
+    'make_transaction': make_transaction,
     'async_call': async_call,
+    '__builtins__': __builtins__,
 }
@@ -88,9 +88,12 @@ def run_file_and_get_traceback(
                 found_user_code = False
 
                 for frame in stack:
-                    # Keep the "Traceback (most recent call last):" header
-                    if frame.startswith('Traceback'):
+                    # Keep the "Traceback (most recent call last):" header, chained exceptions
+                    # each have their own header and runpy frames to skip. Exception groups
+                    # prefix their lines with "|" and use an "Exception Group Traceback" header
+                    if 'Traceback (most recent call last):' in frame:
                         result_frames.append(frame)
+                        found_user_code = False
                         continue
                     elif '__asy.run(__test_main())' in frame:
                         # Skip the asyncio.run(__test_main()) wrapper frame
@@ -106,8 +109,15 @@ def run_file_and_get_traceback(
                         if frame.startswith('  File "<string>"'):
                             continue
 
+                    # An exception with no frames in the test file (like the group raised by except*)
+                    # has no traceback when the file is run directly, so drop the header
+                    if not found_user_code and 'File "' not in frame:
+                        if result_frames and 'Traceback (most recent call last):' in result_frames[-1]:
+                            result_frames.pop()
+                        found_user_code = True
+
                     # Skip until we see our test file
-                    if not found_user_code and frame.startswith(f'  File "{file_path}"'):
+                    if not found_user_code and re.match(rf' *(\| +)?File "{re.escape(file_path)}"', frame):
                         found_user_code = True
 
                     if found_user_code:
@@ -157,7 +167,7 @@ def format_full_traceback(e: Exception):
 
 def normalize_debug_range(line: str) -> str:
     line = line.replace('dataclasses.FrozenInstanceError:', 'FrozenInstanceError:')
-    if re.fullmatch(r' +[\~\^]+', line):
+    if re.fullmatch(r' *(\| +)?[\~\^]+', line):
         return line.replace('^', '~')
     else:
         return line